@@ -0,0 +1,54 @@
+//! Determinism overhead benchmarks: event log recording, trace
+//! collection and hashing against bare execution.
+//!
+//! Criterion times every `Recording` configuration per workload. After
+//! the criterion run (`cargo bench`, not `cargo test --benches`), the
+//! binary prints a per-component overhead table from its own
+//! interleaved samples, so the cost of each component reads off
+//! directly instead of by subtracting criterion medians.
+
+use boruna_benches::determinism::{measure_overhead, run_recorded, Recording};
+use boruna_benches::{compile_or_panic, loop_program, loop_with_kv_program};
+use boruna_bytecode::Module;
+use criterion::{black_box, criterion_group, Criterion};
+
+/// Samples per configuration in the overhead table.
+const REPORT_SAMPLES: usize = 31;
+
+fn workloads() -> Vec<(&'static str, Module)> {
+    vec![
+        (
+            "pure_loop/iters=10000",
+            compile_or_panic("pure_loop", &loop_program(10_000)),
+        ),
+        (
+            "kv_loop/iters=1000",
+            compile_or_panic("kv_loop", &loop_with_kv_program(1_000)),
+        ),
+    ]
+}
+
+fn bench_determinism_overhead(c: &mut Criterion) {
+    for (workload, module) in workloads() {
+        let mut group = c.benchmark_group(format!("determinism_{workload}"));
+        for (name, recording) in Recording::configurations() {
+            group.bench_function(name, |b| {
+                b.iter(|| black_box(run_recorded(black_box(&module), recording)));
+            });
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(determinism_benches, bench_determinism_overhead);
+
+fn main() {
+    determinism_benches();
+    Criterion::default().configure_from_args().final_summary();
+
+    if std::env::args().any(|a| a == "--bench") {
+        for (workload, module) in workloads() {
+            println!("{}", measure_overhead(workload, &module, REPORT_SAMPLES));
+        }
+    }
+}
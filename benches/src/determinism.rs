@@ -0,0 +1,209 @@
+//! Determinism overhead: what recording a run costs over running it.
+//!
+//! A run can pay for three determinism components on top of bare
+//! execution:
+//!
+//! - **event log** — the `boruna run --record` path: copy the VM's event
+//!   log, attach the run outcome, and serialize it to JSON. The VM appends
+//!   capability calls to its in-memory log on every run, so that part is
+//!   in the bare time too.
+//! - **trace** — `Vm::trace_enabled`, one trace line per step.
+//! - **hashing** — SHA-256 over the canonical JSON of the events and the
+//!   result, as replay verification and evidence bundles do. The
+//!   serialization it hashes is included.
+//!
+//! The criterion bench (`benches/determinism.rs`) times each
+//! [`Recording`] configuration; [`measure_overhead`] turns medians into a
+//! per-component report against bare execution.
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use boruna_bytecode::{Module, Value};
+use boruna_hash::HashAlgorithm;
+use boruna_vm::replay::RunOutcome;
+use boruna_vm::{CapabilityGateway, Policy, Vm};
+
+/// Which determinism components a run pays for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Recording {
+    pub event_log: bool,
+    pub trace: bool,
+    pub hashing: bool,
+}
+
+impl Recording {
+    pub const BARE: Recording = Recording {
+        event_log: false,
+        trace: false,
+        hashing: false,
+    };
+    pub const ALL: Recording = Recording {
+        event_log: true,
+        trace: true,
+        hashing: true,
+    };
+
+    /// The configurations the bench compares, by name: bare, each
+    /// component alone, then all of them.
+    pub fn configurations() -> [(&'static str, Recording); 5] {
+        [
+            ("bare", Recording::BARE),
+            (
+                "event_log",
+                Recording {
+                    event_log: true,
+                    ..Recording::BARE
+                },
+            ),
+            (
+                "trace",
+                Recording {
+                    trace: true,
+                    ..Recording::BARE
+                },
+            ),
+            (
+                "hashing",
+                Recording {
+                    hashing: true,
+                    ..Recording::BARE
+                },
+            ),
+            ("all", Recording::ALL),
+        ]
+    }
+}
+
+/// What a recorded run produced. Benches pass it to `black_box` so no
+/// component is optimized away.
+#[derive(Debug, Default)]
+pub struct RecordedRun {
+    pub result: Option<Value>,
+    pub event_log_json: Option<String>,
+    pub trace_lines: usize,
+    pub hashes: Vec<String>,
+}
+
+/// Run `module` under an allow-all gateway, paying for `recording`.
+/// Panics on any VM error — bench fixtures are expected to succeed.
+pub fn run_recorded(module: &Module, recording: Recording) -> RecordedRun {
+    let gateway = CapabilityGateway::new(Policy::allow_all());
+    let mut vm = Vm::new(module.clone(), gateway);
+    vm.trace_enabled = recording.trace;
+    let result = vm.run().expect("vm.run should succeed for bench fixture");
+
+    let mut run = RecordedRun {
+        trace_lines: vm.trace.len(),
+        ..RecordedRun::default()
+    };
+    if recording.event_log {
+        let mut log = vm.event_log().clone();
+        log.set_outcome(RunOutcome {
+            steps: vm.step_count(),
+            result: Some(result.clone()),
+            error: None,
+            denials: vm.gateway().denials().to_vec(),
+            kv_snapshot: None,
+        });
+        run.event_log_json = Some(log.to_json().expect("serialize event log"));
+    }
+    if recording.hashing {
+        let events = serde_json::to_vec(vm.event_log().events()).expect("serialize events");
+        let value = serde_json::to_vec(&result).expect("serialize result");
+        run.hashes = vec![
+            HashAlgorithm::Sha256.hex(events),
+            HashAlgorithm::Sha256.hex(value),
+        ];
+    }
+    run.result = Some(result);
+    run
+}
+
+/// One component's cost relative to bare execution.
+#[derive(Debug, Clone)]
+pub struct ComponentOverhead {
+    pub component: &'static str,
+    pub median: Duration,
+    /// `median` minus the bare median; zero when within noise below it.
+    pub overhead: Duration,
+    /// `overhead` as a percentage of the bare median.
+    pub overhead_pct: f64,
+}
+
+/// Median run times of every [`Recording::configurations`] entry.
+#[derive(Debug, Clone)]
+pub struct OverheadReport {
+    pub workload: String,
+    pub bare: Duration,
+    pub components: Vec<ComponentOverhead>,
+}
+
+/// Time `samples` runs of `module` per configuration, interleaved so
+/// machine drift affects every configuration alike, and report each
+/// median against bare execution.
+pub fn measure_overhead(workload: &str, module: &Module, samples: usize) -> OverheadReport {
+    let configurations = Recording::configurations();
+    let mut times: Vec<Vec<Duration>> = vec![Vec::with_capacity(samples); configurations.len()];
+    for _ in 0..samples.max(1) {
+        for (i, (_, recording)) in configurations.iter().enumerate() {
+            let start = Instant::now();
+            std::hint::black_box(run_recorded(module, *recording));
+            times[i].push(start.elapsed());
+        }
+    }
+    let medians: Vec<Duration> = times.iter_mut().map(|t| median(t)).collect();
+    let bare = medians[0];
+    let components = configurations
+        .iter()
+        .zip(&medians)
+        .skip(1)
+        .map(|((name, _), &median)| {
+            let overhead = median.saturating_sub(bare);
+            ComponentOverhead {
+                component: name,
+                median,
+                overhead,
+                overhead_pct: if bare.is_zero() {
+                    0.0
+                } else {
+                    overhead.as_secs_f64() / bare.as_secs_f64() * 100.0
+                },
+            }
+        })
+        .collect();
+    OverheadReport {
+        workload: workload.to_string(),
+        bare,
+        components,
+    }
+}
+
+fn median(times: &mut [Duration]) -> Duration {
+    times.sort();
+    times.get(times.len() / 2).copied().unwrap_or_default()
+}
+
+impl fmt::Display for OverheadReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "determinism overhead: {}", self.workload)?;
+        writeln!(
+            f,
+            "  {:<10} {:>12} {:>12} {:>9}",
+            "component", "median", "overhead", "% bare"
+        )?;
+        writeln!(
+            f,
+            "  {:<10} {:>12?} {:>12} {:>9}",
+            "bare", self.bare, "-", "-"
+        )?;
+        for c in &self.components {
+            writeln!(
+                f,
+                "  {:<10} {:>12?} {:>12?} {:>8.1}%",
+                c.component, c.median, c.overhead, c.overhead_pct
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -14,6 +14,8 @@ use boruna_orchestrator::audit::{AuditEvent, AuditLog, EvidenceBundleBuilder};
 use boruna_tooling::templates::apply_template;
 use boruna_vm::{CapabilityGateway, Policy, Vm};
 
+pub mod determinism;
+
 /// Minimal program (~20 lines) — exercises the compiler's hot path
 /// without pulling in pattern matching or records.
 pub const SMALL_AX_SOURCE: &str = r#"
@@ -271,6 +273,32 @@ fn main() -> Int {{
     )
 }
 
+/// Loop body that makes a real capability call (`kv_get`) per
+/// iteration, so every iteration adds a call and a result to the event
+/// log. With no store attached the gateway answers `None`.
+pub fn loop_with_kv_program(iterations: i64) -> String {
+    format!(
+        r#"
+fn fetch(acc: Int) -> Int !{{kv.get}} {{
+    match kv_get("counter") {{
+        Some(v) => acc + 1,
+        None => acc,
+    }}
+}}
+
+fn main() -> Int !{{kv.get}} {{
+    let mut acc: Int = 0
+    let mut i: Int = 0
+    while i < {iterations} {{
+        acc = fetch(acc) + i
+        i = i + 1
+    }}
+    acc
+}}
+"#
+    )
+}
+
 /// Run a compiled module under an allow-all gateway and return the
 /// final value. Panics on any VM error — benches expect inputs to
 /// succeed.
@@ -6,10 +6,11 @@
 //! fixture surfaces as a normal `cargo test` failure rather than as a
 //! silent bench-only regression.
 
+use boruna_benches::determinism::{measure_overhead, run_recorded, Recording};
 use boruna_benches::{
     build_evidence_bundle, compile_or_panic, loop_program, loop_with_capability_program,
-    loop_with_record_program, render_crud_admin_template, run_module, MEDIUM_AX_SOURCE,
-    SMALL_AX_SOURCE,
+    loop_with_kv_program, loop_with_record_program, render_crud_admin_template, run_module,
+    MEDIUM_AX_SOURCE, SMALL_AX_SOURCE,
 };
 use boruna_orchestrator::audit::verify_bundle;
 
@@ -38,3 +39,22 @@ fn bench_fixtures_compile_run_and_verify() {
     let result = verify_bundle(&bundle);
     assert!(result.valid, "smoke bundle should verify: {result:?}");
 }
+
+#[test]
+fn determinism_fixtures_record_every_component() {
+    let module = compile_or_panic("kv", &loop_with_kv_program(10));
+
+    let bare = run_recorded(&module, Recording::BARE);
+    assert!(bare.event_log_json.is_none() && bare.hashes.is_empty());
+    assert_eq!(bare.trace_lines, 0);
+
+    let all = run_recorded(&module, Recording::ALL);
+    assert_eq!(all.result, bare.result);
+    assert!(all.event_log_json.unwrap().contains("kv.get"));
+    assert!(all.trace_lines > 0);
+    assert_eq!(all.hashes.len(), 2);
+
+    let report = measure_overhead("kv_loop", &module, 1);
+    let components: Vec<_> = report.components.iter().map(|c| c.component).collect();
+    assert_eq!(components, ["event_log", "trace", "hashing", "all"]);
+}
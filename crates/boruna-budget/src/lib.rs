@@ -0,0 +1,346 @@
+//! Run-level resource budgets.
+//!
+//! A [`BudgetLedger`] tracks what a run has consumed — VM steps,
+//! capability calls, capability payload bytes, LLM tokens and container
+//! time — against a [`Budget`]. Clones share one account, so the workflow runner, every
+//! step's VM and any LLM effect gateway debit the same totals; a run
+//! whose steps execute concurrently still has one allocation.
+//!
+//! [`BudgetLedger::for_step`] returns a handle that debits a per-step
+//! sub-budget as well as the run's, so one runaway step fails on its
+//! own allocation instead of consuming the whole run's. A debit either
+//! fits every account it touches and is recorded in all of them, or is
+//! refused with a [`BudgetExhausted`] naming the account it would
+//! overrun and recorded nowhere.
+
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use serde::{Deserialize, Serialize};
+
+/// A resource a budget limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Resource {
+    /// VM instructions executed.
+    Steps,
+    /// Capability calls made through a gateway.
+    CapabilityCalls,
+    /// Bytes of capability arguments and results.
+    Bytes,
+    /// LLM output tokens requested.
+    LlmTokens,
+    /// Wall-clock milliseconds container-isolated steps ran, as the host
+    /// measured them.
+    ContainerMs,
+}
+
+impl Resource {
+    pub const ALL: [Resource; 5] = [
+        Resource::Steps,
+        Resource::CapabilityCalls,
+        Resource::Bytes,
+        Resource::LlmTokens,
+        Resource::ContainerMs,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Resource::Steps => "steps",
+            Resource::CapabilityCalls => "capability_calls",
+            Resource::Bytes => "bytes",
+            Resource::LlmTokens => "llm_tokens",
+            Resource::ContainerMs => "container_ms",
+        }
+    }
+}
+
+impl fmt::Display for Resource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Limits per resource; `None` is unlimited. Unset limits are omitted
+/// from JSON.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Budget {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_steps: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_calls: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_bytes: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_container_ms: Option<u64>,
+}
+
+impl Budget {
+    pub fn limit(&self, resource: Resource) -> Option<u64> {
+        match resource {
+            Resource::Steps => self.max_steps,
+            Resource::CapabilityCalls => self.max_calls,
+            Resource::Bytes => self.max_bytes,
+            Resource::LlmTokens => self.max_tokens,
+            Resource::ContainerMs => self.max_container_ms,
+        }
+    }
+
+    pub fn is_unlimited(&self) -> bool {
+        Resource::ALL.iter().all(|&r| self.limit(r).is_none())
+    }
+}
+
+/// Amount consumed per resource. `container_ms` is omitted from JSON
+/// when zero.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Usage {
+    pub steps: u64,
+    pub capability_calls: u64,
+    pub bytes: u64,
+    pub llm_tokens: u64,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub container_ms: u64,
+}
+
+fn is_zero(n: &u64) -> bool {
+    *n == 0
+}
+
+impl Usage {
+    pub fn get(&self, resource: Resource) -> u64 {
+        match resource {
+            Resource::Steps => self.steps,
+            Resource::CapabilityCalls => self.capability_calls,
+            Resource::Bytes => self.bytes,
+            Resource::LlmTokens => self.llm_tokens,
+            Resource::ContainerMs => self.container_ms,
+        }
+    }
+
+    fn get_mut(&mut self, resource: Resource) -> &mut u64 {
+        match resource {
+            Resource::Steps => &mut self.steps,
+            Resource::CapabilityCalls => &mut self.capability_calls,
+            Resource::Bytes => &mut self.bytes,
+            Resource::LlmTokens => &mut self.llm_tokens,
+            Resource::ContainerMs => &mut self.container_ms,
+        }
+    }
+}
+
+/// A debit refused because it would overrun a budget.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BudgetExhausted {
+    pub resource: Resource,
+    /// `run`, or `step '<id>'` for a per-step sub-budget.
+    pub scope: String,
+    pub limit: u64,
+    /// Consumed before the refused debit.
+    pub used: u64,
+    pub requested: u64,
+}
+
+impl fmt::Display for BudgetExhausted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} budget exhausted: {} {} used + {} requested > limit {}",
+            self.scope, self.used, self.resource, self.requested, self.limit
+        )
+    }
+}
+
+impl std::error::Error for BudgetExhausted {}
+
+impl boruna_errors::BorunaError for BudgetExhausted {
+    fn error_code(&self) -> &'static boruna_errors::ErrorCode {
+        &boruna_errors::VM_RUN_BUDGET_EXHAUSTED
+    }
+
+    fn error_data(&self) -> serde_json::Value {
+        serde_json::json!({
+            "resource": self.resource,
+            "scope": self.scope,
+            "limit": self.limit,
+            "used": self.used,
+            "requested": self.requested,
+        })
+    }
+}
+
+#[derive(Debug)]
+struct Account {
+    scope: String,
+    limits: Budget,
+    used: Usage,
+}
+
+impl Account {
+    fn new(scope: String, limits: Budget) -> Arc<Mutex<Account>> {
+        Arc::new(Mutex::new(Account {
+            scope,
+            limits,
+            used: Usage::default(),
+        }))
+    }
+
+    fn check(&self, resource: Resource, amount: u64) -> Result<(), BudgetExhausted> {
+        let used = self.used.get(resource);
+        match self.limits.limit(resource) {
+            Some(limit) if used.saturating_add(amount) > limit => Err(BudgetExhausted {
+                resource,
+                scope: self.scope.clone(),
+                limit,
+                used,
+                requested: amount,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    fn remaining(&self, resource: Resource) -> Option<u64> {
+        self.limits
+            .limit(resource)
+            .map(|limit| limit.saturating_sub(self.used.get(resource)))
+    }
+}
+
+/// Shared resource accounting for one run; see the crate docs.
+#[derive(Debug, Clone)]
+pub struct BudgetLedger {
+    run: Arc<Mutex<Account>>,
+    /// The per-step account this handle also debits.
+    step: Option<Arc<Mutex<Account>>>,
+}
+
+impl Default for BudgetLedger {
+    fn default() -> Self {
+        BudgetLedger::new(Budget::default())
+    }
+}
+
+impl BudgetLedger {
+    /// A ledger for a run limited by `budget`.
+    pub fn new(budget: Budget) -> Self {
+        BudgetLedger {
+            run: Account::new("run".to_string(), budget),
+            step: None,
+        }
+    }
+
+    /// A handle sharing this ledger's run account that also debits a
+    /// fresh account for `step_id`, limited by `budget`.
+    pub fn for_step(&self, step_id: &str, budget: Budget) -> Self {
+        BudgetLedger {
+            run: Arc::clone(&self.run),
+            step: Some(Account::new(format!("step '{step_id}'"), budget)),
+        }
+    }
+
+    /// Consume `amount` of `resource` from every account this handle
+    /// debits, or from none of them.
+    pub fn debit(&self, resource: Resource, amount: u64) -> Result<(), BudgetExhausted> {
+        let mut step = self.step.as_deref().map(lock);
+        let mut run = lock(&self.run);
+        if let Some(step) = &step {
+            step.check(resource, amount)?;
+        }
+        run.check(resource, amount)?;
+        for account in step.iter_mut().map(|s| &mut **s).chain([&mut *run]) {
+            let used = account.used.get_mut(resource);
+            *used = used.saturating_add(amount);
+        }
+        Ok(())
+    }
+
+    /// How much of `resource` can still be debited through this handle;
+    /// `None` when no account limits it.
+    pub fn remaining(&self, resource: Resource) -> Option<u64> {
+        let step = self
+            .step
+            .as_deref()
+            .and_then(|s| lock(s).remaining(resource));
+        let run = lock(&self.run).remaining(resource);
+        match (step, run) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+
+    /// Consumed by the whole run so far.
+    pub fn run_usage(&self) -> Usage {
+        lock(&self.run).used
+    }
+
+    /// Consumed through this step handle so far; `None` for a run-level
+    /// handle.
+    pub fn step_usage(&self) -> Option<Usage> {
+        self.step.as_deref().map(|s| lock(s).used)
+    }
+}
+
+/// Accounts hold plain counters, so a panic elsewhere cannot leave one
+/// half-updated; recover from poisoning.
+fn lock(account: &Mutex<Account>) -> MutexGuard<'_, Account> {
+    account.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits(steps: u64, calls: u64) -> Budget {
+        Budget {
+            max_steps: Some(steps),
+            max_calls: Some(calls),
+            ..Budget::default()
+        }
+    }
+
+    #[test]
+    fn test_clones_share_the_run_account() {
+        let ledger = BudgetLedger::new(limits(100, 2));
+        let other = ledger.clone();
+        ledger.debit(Resource::CapabilityCalls, 1).unwrap();
+        other.debit(Resource::CapabilityCalls, 1).unwrap();
+        let err = ledger.debit(Resource::CapabilityCalls, 1).unwrap_err();
+        assert_eq!(err.scope, "run");
+        assert_eq!((err.used, err.limit), (2, 2));
+        assert_eq!(ledger.run_usage().capability_calls, 2);
+        // Unlimited resources never fail.
+        ledger.debit(Resource::LlmTokens, u64::MAX).unwrap();
+    }
+
+    #[test]
+    fn test_step_budget_fails_before_the_run_budget() {
+        let ledger = BudgetLedger::new(limits(100, 10));
+        let step = ledger.for_step("fetch", limits(30, 10));
+        step.debit(Resource::Steps, 25).unwrap();
+        assert_eq!(step.remaining(Resource::Steps), Some(5));
+
+        let err = step.debit(Resource::Steps, 10).unwrap_err();
+        assert_eq!(err.scope, "step 'fetch'");
+        assert_eq!(err.resource, Resource::Steps);
+        // A refused debit is recorded nowhere.
+        assert_eq!(ledger.run_usage().steps, 25);
+
+        let next = ledger.for_step("summarize", limits(100, 10));
+        assert_eq!(next.remaining(Resource::Steps), Some(75));
+        assert_eq!(next.step_usage(), Some(Usage::default()));
+    }
+
+    #[test]
+    fn test_exhaustion_maps_to_a_stable_code() {
+        use boruna_errors::BorunaError;
+        let err = BudgetLedger::new(limits(1, 1))
+            .debit(Resource::Steps, 2)
+            .unwrap_err();
+        let envelope = err.envelope();
+        assert_eq!(envelope.code, "VM028");
+        assert_eq!(envelope.data["resource"], "steps");
+        assert!(err.to_string().contains("run budget exhausted"), "{err}");
+    }
+}
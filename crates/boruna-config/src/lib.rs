@@ -0,0 +1,613 @@
+//! Layered defaults for the Boruna CLIs.
+//!
+//! A handful of flags (`--policy`, `--max-steps`, `--dir` for templates,
+//! `--registry`, ...) take the same value in every invocation of a
+//! project's scripts. [`Config`] resolves each [`Setting`] from, lowest
+//! precedence first:
+//!
+//! 1. the built-in default,
+//! 2. the user config (`$BORUNA_CONFIG`, else
+//!    `$XDG_CONFIG_HOME/boruna/config.toml`, else
+//!    `~/.config/boruna/config.toml`),
+//! 3. the workspace `boruna.toml`, the nearest one in the current
+//!    directory or an ancestor,
+//! 4. the setting's environment variable,
+//!
+//! and [`Config::apply`] installs the result as the default of the
+//! matching clap args, so a flag on the command line still wins.
+//!
+//! ```toml
+//! policy = "policies/dev.json"
+//! max_steps = 1000000
+//! templates_dir = "templates"
+//!
+//! [telemetry]
+//! otlp_endpoint = "http://localhost:4318"
+//! ```
+//!
+//! Relative paths in a config file are relative to the file's directory;
+//! in an environment variable, to the current directory.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use boruna_errors::{BorunaError, ErrorCode};
+use clap::{Arg, Command};
+use serde_json::{json, Value};
+
+/// File name of the workspace config.
+pub const WORKSPACE_FILE: &str = "boruna.toml";
+/// Overrides the user config path.
+pub const CONFIG_ENV: &str = "BORUNA_CONFIG";
+
+/// How a setting's value is checked and interpreted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Text,
+    Integer,
+    Path,
+    /// `allow-all`, `deny-all` or a path to a JSON policy file.
+    Policy,
+}
+
+/// One configurable default.
+#[derive(Debug)]
+pub struct Setting {
+    /// Key in the config file; `telemetry.*` keys live in a `[telemetry]`
+    /// table.
+    pub key: &'static str,
+    pub env: &'static str,
+    pub kind: Kind,
+    /// Id of the clap args the setting fills in, if any.
+    pub arg: Option<&'static str>,
+    /// The built-in default an arg must have to take the setting; `None`
+    /// for args without a default. Args with another default (`repl`'s
+    /// `--policy deny-all`) keep it.
+    pub arg_default: Option<&'static str>,
+    /// Built-in default, for display.
+    pub default: Option<&'static str>,
+    pub summary: &'static str,
+}
+
+/// Every setting, in display order.
+pub const SETTINGS: &[Setting] = &[
+    Setting {
+        key: "policy",
+        env: "BORUNA_POLICY",
+        kind: Kind::Policy,
+        arg: Some("policy"),
+        arg_default: Some("allow-all"),
+        default: Some("allow-all"),
+        summary: "Capability policy for commands that default to allow-all.",
+    },
+    Setting {
+        key: "max_steps",
+        env: "BORUNA_MAX_STEPS",
+        kind: Kind::Integer,
+        arg: Some("max_steps"),
+        arg_default: Some("10000000"),
+        default: Some("10000000"),
+        summary: "Step limit for `boruna run`.",
+    },
+    Setting {
+        key: "templates_dir",
+        env: "BORUNA_TEMPLATES_DIR",
+        kind: Kind::Path,
+        arg: Some("dir"),
+        arg_default: Some("templates"),
+        default: Some("templates"),
+        summary: "Templates directory for `boruna template` and `boruna new`.",
+    },
+    Setting {
+        key: "registry",
+        env: "BORUNA_REGISTRY",
+        kind: Kind::Path,
+        arg: Some("registry"),
+        arg_default: Some("packages/registry"),
+        default: Some("packages/registry"),
+        summary: "Package registry directory for `boruna-pkg`.",
+    },
+    Setting {
+        key: "providers",
+        env: "BORUNA_PROVIDERS",
+        kind: Kind::Path,
+        arg: Some("providers"),
+        arg_default: None,
+        default: None,
+        summary: "LLM provider registry file (`providers.json`).",
+    },
+    Setting {
+        key: "data_dir",
+        env: "BORUNA_DATA_DIR",
+        kind: Kind::Path,
+        arg: Some("data_dir"),
+        arg_default: None,
+        default: Some("./.boruna/data"),
+        summary: "Persistent data directory.",
+    },
+    Setting {
+        key: "env",
+        env: "BORUNA_ENV",
+        kind: Kind::Text,
+        arg: Some("env"),
+        arg_default: None,
+        default: None,
+        summary: "Environment namespace for the data directory and metrics.",
+    },
+    Setting {
+        key: "telemetry.otlp_endpoint",
+        env: "OTEL_EXPORTER_OTLP_ENDPOINT",
+        kind: Kind::Text,
+        arg: None,
+        arg_default: None,
+        default: None,
+        summary: "OTLP endpoint for spans (builds with the `telemetry` feature).",
+    },
+    Setting {
+        key: "telemetry.service_name",
+        env: "OTEL_SERVICE_NAME",
+        kind: Kind::Text,
+        arg: None,
+        arg_default: None,
+        default: Some("boruna"),
+        summary: "`service.name` of exported spans.",
+    },
+    Setting {
+        key: "telemetry.repair_dir",
+        env: "BORUNA_REPAIR_TELEMETRY",
+        kind: Kind::Path,
+        arg: Some("telemetry"),
+        arg_default: None,
+        default: None,
+        summary: "Repair telemetry directory for `boruna lang repair`.",
+    },
+];
+
+/// Where a resolved value came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    Default,
+    User(PathBuf),
+    Workspace(PathBuf),
+    Env(&'static str),
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Default => f.write_str("default"),
+            Self::User(path) => write!(f, "user config {}", path.display()),
+            Self::Workspace(path) => write!(f, "workspace {}", path.display()),
+            Self::Env(var) => write!(f, "env ${var}"),
+        }
+    }
+}
+
+/// A setting's effective value.
+#[derive(Debug, Clone)]
+pub struct Resolved {
+    pub setting: &'static Setting,
+    pub value: Option<String>,
+    pub source: Source,
+}
+
+/// A config file that could not be loaded, or an invalid value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigError {
+    /// The file path or `$VAR` at fault.
+    pub origin: String,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid config {}: {}", self.origin, self.message)
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl BorunaError for ConfigError {
+    fn error_code(&self) -> &'static ErrorCode {
+        &boruna_errors::CONFIG_INVALID
+    }
+
+    fn error_data(&self) -> Value {
+        json!({ "origin": self.origin })
+    }
+}
+
+/// One config file consulted while loading.
+#[derive(Debug, Clone)]
+pub struct Layer {
+    pub source: Source,
+    pub path: PathBuf,
+    /// Whether the file exists. A missing file is skipped.
+    pub found: bool,
+}
+
+/// The resolved settings.
+#[derive(Debug, Clone)]
+pub struct Config {
+    layers: Vec<Layer>,
+    resolved: Vec<Resolved>,
+}
+
+impl Config {
+    /// Load from the current directory and the process environment.
+    pub fn load() -> Result<Self, ConfigError> {
+        let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+        Self::load_from(&cwd, |var| std::env::var(var).ok())
+    }
+
+    /// Load with `cwd` as the current directory and `env` looking up
+    /// environment variables. Empty variables count as unset.
+    pub fn load_from(
+        cwd: &Path,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> Result<Self, ConfigError> {
+        let env = |var: &str| env(var).filter(|v| !v.is_empty());
+
+        let mut layers = Vec::new();
+        if let Some(path) = env(CONFIG_ENV) {
+            // An explicit path must exist.
+            let path = PathBuf::from(path);
+            if !path.is_file() {
+                return Err(ConfigError {
+                    origin: format!("${CONFIG_ENV}"),
+                    message: format!("{} does not exist", path.display()),
+                });
+            }
+            layers.push(Layer {
+                source: Source::User(path.clone()),
+                path,
+                found: true,
+            });
+        } else if let Some(dir) = env("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env("HOME").map(|home| Path::new(&home).join(".config")))
+        {
+            let path = dir.join("boruna").join("config.toml");
+            layers.push(Layer {
+                source: Source::User(path.clone()),
+                found: path.is_file(),
+                path,
+            });
+        }
+        let workspace = cwd
+            .ancestors()
+            .map(|d| d.join(WORKSPACE_FILE))
+            .find(|p| p.is_file());
+        if let Some(path) = workspace {
+            layers.push(Layer {
+                source: Source::Workspace(path.clone()),
+                path,
+                found: true,
+            });
+        }
+
+        let mut resolved: Vec<Resolved> = SETTINGS
+            .iter()
+            .map(|setting| Resolved {
+                setting,
+                value: setting.default.map(String::from),
+                source: Source::Default,
+            })
+            .collect();
+        for layer in layers.iter().filter(|l| l.found) {
+            let origin = layer.path.display().to_string();
+            let text = std::fs::read_to_string(&layer.path).map_err(|e| ConfigError {
+                origin: origin.clone(),
+                message: e.to_string(),
+            })?;
+            let base = layer.path.parent().unwrap_or(Path::new("."));
+            for (key, value) in parse_file(&text, &origin)? {
+                let r = resolved
+                    .iter_mut()
+                    .find(|r| r.setting.key == key)
+                    .expect("parse_file only returns known keys");
+                r.value = Some(interpret(r.setting, &value, base, &origin)?);
+                r.source = layer.source.clone();
+            }
+        }
+        for r in &mut resolved {
+            if let Some(value) = env(r.setting.env) {
+                let origin = format!("${}", r.setting.env);
+                // Paths from the environment stay relative to the cwd.
+                r.value = Some(interpret(
+                    r.setting,
+                    &toml::Value::String(value),
+                    Path::new(""),
+                    &origin,
+                )?);
+                r.source = Source::Env(r.setting.env);
+            }
+        }
+        Ok(Self { layers, resolved })
+    }
+
+    /// The config files consulted, lowest precedence first.
+    pub fn layers(&self) -> &[Layer] {
+        &self.layers
+    }
+
+    /// Every setting's effective value, in [`SETTINGS`] order.
+    pub fn resolved(&self) -> &[Resolved] {
+        &self.resolved
+    }
+
+    /// The effective value of `key`, if it has one.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.resolved
+            .iter()
+            .find(|r| r.setting.key == key)
+            .and_then(|r| r.value.as_deref())
+    }
+
+    /// Install every value that does not come from the built-in default
+    /// as the default of the matching args of `cmd` and its subcommands.
+    pub fn apply(&self, cmd: Command) -> Command {
+        cmd.mut_args(|arg| self.fill(arg))
+            .mut_subcommands(|sub| self.apply(sub))
+    }
+
+    fn fill(&self, arg: Arg) -> Arg {
+        let defaults = arg.get_default_values();
+        let target = self.resolved.iter().find(|r| {
+            r.source != Source::Default
+                && r.setting.arg == Some(arg.get_id().as_str())
+                && match r.setting.arg_default {
+                    Some(d) => defaults.len() == 1 && defaults[0] == d,
+                    None => defaults.is_empty(),
+                }
+        });
+        match target.and_then(|r| r.value.clone()) {
+            Some(value) => arg.default_value(value),
+            None => arg,
+        }
+    }
+
+    /// `boruna config show` as text: the files consulted, then one
+    /// `key = value  (source)` line per setting a file or variable sets,
+    /// or with `resolved` per setting.
+    pub fn render(&self, resolved: bool) -> String {
+        let mut out = String::from("files:\n");
+        if self.layers.is_empty() {
+            out.push_str("  (none)\n");
+        }
+        for layer in &self.layers {
+            let state = if layer.found { "" } else { "  (not found)" };
+            out.push_str(&format!("  {}{state}\n", layer.source));
+        }
+        out.push_str("settings:\n");
+        let width = SETTINGS.iter().map(|s| s.key.len()).max().unwrap_or(0);
+        for r in self.shown(resolved) {
+            let value = r.value.as_deref().unwrap_or("-");
+            out.push_str(&format!(
+                "  {:<width$} = {value}  ({})\n",
+                r.setting.key, r.source
+            ));
+        }
+        out
+    }
+
+    /// `boruna config show --json`.
+    pub fn to_json(&self, resolved: bool) -> Value {
+        let files: Vec<Value> = self
+            .layers
+            .iter()
+            .map(|l| {
+                let scope = match l.source {
+                    Source::User(_) => "user",
+                    _ => "workspace",
+                };
+                json!({ "scope": scope, "path": l.path.display().to_string(), "found": l.found })
+            })
+            .collect();
+        let settings: Vec<Value> = self
+            .shown(resolved)
+            .map(|r| {
+                let (source, origin) = match &r.source {
+                    Source::Default => ("default", Value::Null),
+                    Source::User(p) => ("user", json!(p.display().to_string())),
+                    Source::Workspace(p) => ("workspace", json!(p.display().to_string())),
+                    Source::Env(var) => ("env", json!(var)),
+                };
+                json!({
+                    "key": r.setting.key,
+                    "value": r.value,
+                    "source": source,
+                    "origin": origin,
+                    "env": r.setting.env,
+                })
+            })
+            .collect();
+        json!({ "version": 1, "files": files, "settings": settings })
+    }
+
+    fn shown(&self, resolved: bool) -> impl Iterator<Item = &Resolved> {
+        self.resolved
+            .iter()
+            .filter(move |r| resolved || r.source != Source::Default)
+    }
+}
+
+/// The `(key, value)` pairs of a config file, with `[telemetry]` keys
+/// flattened to `telemetry.<name>`. Unknown keys are errors so a typo
+/// does not silently fall back to the default.
+fn parse_file(text: &str, origin: &str) -> Result<Vec<(String, toml::Value)>, ConfigError> {
+    let err = |message: String| ConfigError {
+        origin: origin.to_string(),
+        message,
+    };
+    let table: toml::Table = text
+        .parse()
+        .map_err(|e: toml::de::Error| err(e.message().to_string()))?;
+    let mut pairs = Vec::new();
+    for (key, value) in table {
+        match value {
+            toml::Value::Table(inner) if key == "telemetry" => {
+                for (name, value) in inner {
+                    pairs.push((format!("telemetry.{name}"), value));
+                }
+            }
+            value => pairs.push((key, value)),
+        }
+    }
+    for (key, _) in &pairs {
+        if !SETTINGS.iter().any(|s| s.key == key) {
+            let known: Vec<&str> = SETTINGS.iter().map(|s| s.key).collect();
+            return Err(err(format!(
+                "unknown key '{key}' (expected one of: {})",
+                known.join(", ")
+            )));
+        }
+    }
+    Ok(pairs)
+}
+
+/// Check `value` against the setting's kind and render it as the flag
+/// value, resolving relative paths against `base`.
+fn interpret(
+    setting: &Setting,
+    value: &toml::Value,
+    base: &Path,
+    origin: &str,
+) -> Result<String, ConfigError> {
+    let err = |expected: &str| ConfigError {
+        origin: origin.to_string(),
+        message: format!("'{}' must be {expected}", setting.key),
+    };
+    match (setting.kind, value) {
+        (Kind::Integer, toml::Value::Integer(n)) if *n >= 0 => Ok(n.to_string()),
+        (Kind::Integer, toml::Value::String(s)) if s.parse::<u64>().is_ok() => Ok(s.clone()),
+        (Kind::Integer, _) => Err(err("a non-negative integer")),
+        (Kind::Text, toml::Value::String(s)) => Ok(s.clone()),
+        (Kind::Policy, toml::Value::String(s)) if s == "allow-all" || s == "deny-all" => {
+            Ok(s.clone())
+        }
+        (Kind::Path | Kind::Policy, toml::Value::String(s)) => {
+            Ok(base.join(s).display().to_string())
+        }
+        _ => Err(err("a string")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn load(cwd: &Path, vars: &[(&str, &str)]) -> Result<Config, ConfigError> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        Config::load_from(cwd, |var| vars.get(var).cloned())
+    }
+
+    #[test]
+    fn layers_apply_in_precedence_order() {
+        let home = tempfile::tempdir().unwrap();
+        let user = home.path().join(".config/boruna");
+        std::fs::create_dir_all(&user).unwrap();
+        std::fs::write(
+            user.join("config.toml"),
+            "policy = \"deny-all\"\nmax_steps = 5\nenv = \"dev\"\n",
+        )
+        .unwrap();
+        let ws = tempfile::tempdir().unwrap();
+        let sub = ws.path().join("src/nested");
+        std::fs::create_dir_all(&sub).unwrap();
+        std::fs::write(
+            ws.path().join(WORKSPACE_FILE),
+            "max_steps = 50\ntemplates_dir = \"tpl\"\n\n[telemetry]\nservice_name = \"app\"\n",
+        )
+        .unwrap();
+        let home_dir = home.path().to_str().unwrap();
+
+        let config = load(&sub, &[("HOME", home_dir), ("BORUNA_ENV", "prod")]).unwrap();
+        assert_eq!(config.get("policy"), Some("deny-all"));
+        assert_eq!(config.get("max_steps"), Some("50"));
+        assert_eq!(config.get("env"), Some("prod"));
+        assert_eq!(config.get("telemetry.service_name"), Some("app"));
+        assert_eq!(config.get("registry"), Some("packages/registry"));
+        assert_eq!(
+            config.get("templates_dir").map(PathBuf::from),
+            Some(ws.path().join("tpl")),
+            "relative to the file"
+        );
+        let source = |key: &str| {
+            config
+                .resolved()
+                .iter()
+                .find(|r| r.setting.key == key)
+                .unwrap()
+                .source
+                .clone()
+        };
+        assert!(matches!(source("policy"), Source::User(_)));
+        assert!(matches!(source("max_steps"), Source::Workspace(_)));
+        assert_eq!(source("env"), Source::Env("BORUNA_ENV"));
+        assert_eq!(source("registry"), Source::Default);
+    }
+
+    #[test]
+    fn invalid_files_and_values_are_errors() {
+        let ws = tempfile::tempdir().unwrap();
+        let file = ws.path().join(WORKSPACE_FILE);
+        for (text, message) in [
+            ("polcy = \"deny-all\"", "unknown key 'polcy'"),
+            (
+                "max_steps = \"lots\"",
+                "'max_steps' must be a non-negative integer",
+            ),
+            (
+                "max_steps = -1",
+                "'max_steps' must be a non-negative integer",
+            ),
+            ("policy = 3", "'policy' must be a string"),
+            ("policy = ", ""),
+        ] {
+            std::fs::write(&file, text).unwrap();
+            let e = load(ws.path(), &[]).unwrap_err();
+            assert!(e.message.contains(message), "{text}: {e}");
+            assert_eq!(e.envelope().code, "CFG001");
+        }
+        std::fs::remove_file(&file).unwrap();
+        let e = load(ws.path(), &[("BORUNA_MAX_STEPS", "x")]).unwrap_err();
+        assert_eq!(e.origin, "$BORUNA_MAX_STEPS");
+        let e = load(ws.path(), &[(CONFIG_ENV, "/no/such/config.toml")]).unwrap_err();
+        assert_eq!(e.origin, "$BORUNA_CONFIG");
+    }
+
+    #[test]
+    fn apply_fills_only_args_with_the_built_in_default() {
+        let ws = tempfile::tempdir().unwrap();
+        std::fs::write(ws.path().join(WORKSPACE_FILE), "policy = \"dev.json\"\n").unwrap();
+        let config = load(ws.path(), &[]).unwrap();
+        let cmd = config.apply(
+            Command::new("boruna")
+                .subcommand(
+                    Command::new("run")
+                        .arg(Arg::new("policy").long("policy").default_value("allow-all")),
+                )
+                .subcommand(
+                    Command::new("repl")
+                        .arg(Arg::new("policy").long("policy").default_value("deny-all")),
+                ),
+        );
+        let policy = |args: &[&str]| {
+            let m = cmd.clone().get_matches_from(args);
+            let (_, sub) = m.subcommand().unwrap();
+            sub.get_one::<String>("policy").cloned().unwrap()
+        };
+        let dev = ws.path().join("dev.json").display().to_string();
+        assert_eq!(policy(&["boruna", "run"]), dev);
+        assert_eq!(
+            policy(&["boruna", "run", "--policy", "allow-all"]),
+            "allow-all"
+        );
+        assert_eq!(policy(&["boruna", "repl"]), "deny-all");
+    }
+}
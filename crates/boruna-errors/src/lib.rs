@@ -0,0 +1,265 @@
+//! Stable error codes and the JSON error envelope.
+//!
+//! Every typed error a Boruna crate surfaces to integrators maps to one
+//! [`ErrorCode`] in [`REGISTRY`]: a short `code` (`E002`, `VM004`, ...)
+//! and a dotted `kind` (`compile.parse`, `vm.division_by_zero`, ...).
+//! Both are stable across releases: never renamed, never reused. The
+//! English message is not; match on `code` or `kind` instead.
+//!
+//! Errors implement [`BorunaError`], which renders them as an
+//! [`ErrorEnvelope`]:
+//!
+//! ```json
+//! { "code": "VM004", "kind": "vm.division_by_zero", "message": "division by zero", "data": null }
+//! ```
+//!
+//! CLIs print the envelope, one line on stderr, when invoked with
+//! `--json`. An error without a typed source (an I/O failure formatted
+//! into a string, say) is reported as [`UNCLASSIFIED`].
+//!
+//! Compile errors keep the `E0NN` diagnostic codes of
+//! `docs/reference/diagnostic-codes.md`.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// One stable error code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ErrorCode {
+    /// Short code, e.g. `"VM004"`.
+    pub code: &'static str,
+    /// Dotted kind, e.g. `"vm.division_by_zero"`. The first segment is
+    /// the domain: `compile`, `vm`, `framework`, `workflow`, `pkg` or
+    /// `config`.
+    pub kind: &'static str,
+    /// One-line summary.
+    pub summary: &'static str,
+}
+
+/// An error with a stable code.
+pub trait BorunaError: fmt::Display {
+    /// The error's code.
+    fn error_code(&self) -> &'static ErrorCode;
+
+    /// Structured detail, e.g. `{"line": 3}`. `null` when there is none.
+    fn error_data(&self) -> Value {
+        Value::Null
+    }
+
+    /// The error as an envelope, with its `Display` text as the message.
+    fn envelope(&self) -> ErrorEnvelope {
+        ErrorEnvelope::new(self.error_code(), self.to_string(), self.error_data())
+    }
+}
+
+/// The JSON shape of every Boruna error: `{ code, kind, message, data }`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ErrorEnvelope {
+    pub code: String,
+    pub kind: String,
+    pub message: String,
+    /// Structured detail; `null` when there is none.
+    #[serde(default)]
+    pub data: Value,
+}
+
+impl ErrorEnvelope {
+    pub fn new(code: &ErrorCode, message: impl Into<String>, data: Value) -> Self {
+        ErrorEnvelope {
+            code: code.code.to_string(),
+            kind: code.kind.to_string(),
+            message: message.into(),
+            data,
+        }
+    }
+
+    /// An error without a typed source.
+    pub fn unclassified(message: impl Into<String>) -> Self {
+        Self::new(&UNCLASSIFIED, message, Value::Null)
+    }
+
+    /// Single-line JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("envelope serializes")
+    }
+}
+
+/// Whether the process was invoked with `--json` before any `--`.
+///
+/// Subcommands declare their own `--json` flags, so a CLI's top-level
+/// error path cannot read one parsed flag; it asks this instead.
+pub fn json_requested() -> bool {
+    std::env::args_os()
+        .skip(1)
+        .take_while(|a| a != "--")
+        .any(|a| a == "--json")
+}
+
+macro_rules! codes {
+    ($($name:ident = $code:literal, $kind:literal, $summary:literal;)*) => {
+        $(
+            #[doc = $summary]
+            pub const $name: ErrorCode = ErrorCode {
+                code: $code,
+                kind: $kind,
+                summary: $summary,
+            };
+        )*
+
+        /// Every code, in code order within each domain.
+        pub const REGISTRY: &[ErrorCode] = &[$($name),*];
+    };
+}
+
+codes! {
+    UNCLASSIFIED = "X000", "unclassified", "An error without a typed source; only the message is available.";
+
+    COMPILE_LEXER = "E001", "compile.lexer", "The source could not be tokenized.";
+    COMPILE_PARSE = "E002", "compile.parse", "The token stream did not form a valid syntax tree.";
+    COMPILE_CODEGEN = "E008", "compile.codegen", "The typechecked program could not be lowered to bytecode.";
+    COMPILE_TYPE = "E009", "compile.type", "An expression's type does not match the type its context requires.";
+    COMPILE_BUDGET = "E015", "compile.budget", "The module exceeds a size or complexity budget.";
+
+    VM_INVALID_BYTECODE = "VM001", "vm.invalid_bytecode", "The bytecode is malformed or refers to a missing function, constant, local or global.";
+    VM_STACK_OVERFLOW = "VM002", "vm.stack_overflow", "The call stack exceeded its maximum depth.";
+    VM_TYPE_ERROR = "VM003", "vm.type_error", "An instruction received a value of the wrong type.";
+    VM_DIVISION_BY_ZERO = "VM004", "vm.division_by_zero", "Division or remainder by zero.";
+    VM_ARITHMETIC_OVERFLOW = "VM005", "vm.arithmetic_overflow", "Integer arithmetic overflowed.";
+    VM_CAPABILITY_DENIED = "VM006", "vm.capability_denied", "The policy denied a capability call.";
+    VM_CAPABILITY_BUDGET_EXCEEDED = "VM007", "vm.capability_budget_exceeded", "A capability was called more often than the policy's budget allows.";
+    VM_UNKNOWN_CAPABILITY = "VM008", "vm.unknown_capability", "The bytecode named a capability id this VM does not know.";
+    VM_INVALID_CAP_HANDLE = "VM009", "vm.invalid_cap_handle", "`cap.await` on a handle that was never spawned or was already awaited.";
+    VM_INVALID_PATTERN = "VM010", "vm.invalid_pattern", "A string pattern is malformed or unsupported.";
+    VM_TABLE_ERROR = "VM011", "vm.table_error", "A table operation named a missing column or combined incompatible values.";
+    VM_NON_FINITE_FLOAT = "VM012", "vm.non_finite_float", "Under strict determinism, a float operation produced NaN or infinity.";
+    VM_ASSERTION_FAILED = "VM013", "vm.assertion_failed", "An assertion evaluated to false.";
+    VM_CONTRACT_VIOLATION = "VM014", "vm.contract_violation", "A `requires` or `ensures` contract was violated.";
+    VM_INDEX_OUT_OF_BOUNDS = "VM015", "vm.index_out_of_bounds", "A list index was outside the list.";
+    VM_MATCH_EXHAUSTED = "VM016", "vm.match_exhausted", "No match arm matched the value.";
+    VM_ACTOR_NOT_FOUND = "VM017", "vm.actor_not_found", "A message was sent to an actor that does not exist.";
+    VM_MAILBOX_EMPTY = "VM018", "vm.mailbox_empty", "`receive` on an empty mailbox where blocking is not supported.";
+    VM_STEP_LIMIT_EXCEEDED = "VM019", "vm.step_limit_exceeded", "The run exceeded its step limit.";
+    VM_WALL_TIME_EXCEEDED = "VM020", "vm.wall_time_exceeded", "The run exceeded its wall-clock limit.";
+    VM_HALT = "VM021", "vm.halt", "The program halted.";
+    VM_BUDGET_EXHAUSTED = "VM022", "vm.budget_exhausted", "The execution budget was exhausted.";
+    VM_DEADLOCK = "VM023", "vm.deadlock", "Every actor is blocked and no messages are pending.";
+    VM_MAX_ROUNDS_EXCEEDED = "VM024", "vm.max_rounds_exceeded", "The actor scheduler exceeded its round limit.";
+    VM_UNTRUSTED_MODULE = "VM025", "vm.untrusted_module", "The policy requires a module signed by a trusted key.";
+    VM_CAPABILITY_SKEW = "VM026", "vm.capability_skew", "The module was compiled against different capability contract versions.";
+    VM_ACTOR_STATE = "VM027", "vm.actor_state", "A persistent actor's checkpoint could not be written, read or restored.";
+    VM_RUN_BUDGET_EXHAUSTED = "VM028", "vm.run_budget_exhausted", "A run-level or per-step resource budget was exhausted.";
+    VM_USER_ASSERTION = "VM029", "vm.user_assertion", "An `assert(cond, msg)` in the program found its condition false.";
+    VM_USER_FAILURE = "VM030", "vm.user_failure", "The program called `fail(msg)`.";
+
+    FRAMEWORK_VALIDATION = "FW001", "framework.validation", "The app failed framework validation.";
+    FRAMEWORK_MISSING_FUNCTION = "FW002", "framework.missing_function", "The app lacks a required function (`init`, `update` or `view`).";
+    FRAMEWORK_PURITY_VIOLATION = "FW003", "framework.purity_violation", "A function that must be pure declares capabilities.";
+    FRAMEWORK_WRONG_ARITY = "FW004", "framework.wrong_arity", "A required function has the wrong number of parameters.";
+    FRAMEWORK_MISSING_TYPE = "FW005", "framework.missing_type", "The app lacks a required type definition.";
+    FRAMEWORK_EFFECT = "FW006", "framework.effect", "An effect is malformed or failed.";
+    FRAMEWORK_POLICY_VIOLATION = "FW007", "framework.policy_violation", "An effect was rejected by the app's policy.";
+    FRAMEWORK_STATE = "FW008", "framework.state", "A state operation failed.";
+    FRAMEWORK_MAX_CYCLES_EXCEEDED = "FW009", "framework.max_cycles_exceeded", "The app exceeded its cycle limit.";
+    FRAMEWORK_SESSION_LIMIT = "FW010", "framework.session_limit", "The session manager is at its live-session limit.";
+    FRAMEWORK_EFFECT_LOOP = "FW011", "framework.effect_loop", "Effect callbacks kept re-triggering the same effect.";
+    FRAMEWORK_LIMIT_EXCEEDED = "FW012", "framework.limit_exceeded", "A message would take the app past a numeric limit in its policy.";
+    FRAMEWORK_INVALID_MESSAGE = "FW013", "framework.invalid_message", "A message's tag is not a variant of the app's `Msg` enum, or its payload does not fit the variant.";
+
+    WORKFLOW_MISSING_SCHEMA_VERSION = "WF001", "workflow.missing_schema_version", "The workflow definition has no `schema_version`.";
+    WORKFLOW_UNSUPPORTED_SCHEMA_VERSION = "WF002", "workflow.unsupported_schema_version", "The workflow definition's `schema_version` is newer than this build supports.";
+    WORKFLOW_INVALID_JSON = "WF003", "workflow.invalid_json", "The workflow definition is not valid JSON or lacks required fields.";
+    WORKFLOW_VALIDATION = "WF010", "workflow.validation", "The workflow failed validation.";
+    WORKFLOW_STEP_FAILED = "WF011", "workflow.step_failed", "A workflow step failed.";
+    WORKFLOW_IO = "WF012", "workflow.io", "A workflow file could not be read or written.";
+    WORKFLOW_INTERNAL = "WF013", "workflow.internal", "An internal workflow error.";
+    WORKFLOW_SCHEMA_VIOLATION = "WF014", "workflow.schema_violation", "A step input or output did not match its declared schema.";
+    WORKFLOW_DATA_FLOW_VIOLATION = "WF015", "workflow.data_flow_violation", "A step received data classified above the policy's maximum.";
+    WORKFLOW_RUN_NOT_FOUND = "WF016", "workflow.run_not_found", "No persisted run has the given id.";
+    WORKFLOW_HASH_MISMATCH = "WF017", "workflow.hash_mismatch", "The workflow changed since the run was persisted.";
+    WORKFLOW_PERSISTENCE = "WF018", "workflow.persistence", "The run store failed.";
+    WORKFLOW_STEP_NOT_FOUND = "WF019", "workflow.step_not_found", "The run has no step with the given id.";
+    WORKFLOW_STEP_NOT_AT_APPROVAL_GATE = "WF020", "workflow.step_not_at_approval_gate", "The step is not waiting for approval.";
+    WORKFLOW_STEP_ALREADY_DECIDED = "WF021", "workflow.step_already_decided", "The approval gate already has a decision.";
+    WORKFLOW_NOT_AN_APPROVAL_GATE = "WF022", "workflow.not_an_approval_gate", "The step is not an approval gate.";
+    WORKFLOW_RUN_NOT_RESUMABLE = "WF023", "workflow.run_not_resumable", "The run has already finished.";
+    WORKFLOW_NOT_AN_EXTERNAL_TRIGGER = "WF024", "workflow.not_an_external_trigger", "The step is not an external trigger.";
+    WORKFLOW_STEP_NOT_AT_EXTERNAL_TRIGGER = "WF025", "workflow.step_not_at_external_trigger", "The step is not waiting for an external event.";
+    WORKFLOW_INVALID_TRIGGER_TOKEN = "WF026", "workflow.invalid_trigger_token", "The trigger token does not match the paused step's.";
+    WORKFLOW_STEP_ALREADY_TRIGGERED = "WF027", "workflow.step_already_triggered", "The step already received its trigger.";
+
+    PKG_MANIFEST = "PKG001", "pkg.manifest", "A package manifest or lockfile is missing, unreadable or invalid.";
+    PKG_DEPENDENCY_NOT_FOUND = "PKG002", "pkg.dependency_not_found", "The manifest has no dependency with the given name.";
+    PKG_RESOLUTION = "PKG003", "pkg.resolution", "Dependencies could not be resolved: a missing package, a version conflict or a cycle.";
+    PKG_REGISTRY = "PKG004", "pkg.registry", "The package registry could not be read or written.";
+    PKG_INTEGRITY = "PKG005", "pkg.integrity", "A package does not match its integrity hash.";
+    PKG_CAPABILITY_POLICY = "PKG006", "pkg.capability_policy", "Dependencies require capabilities the package policy forbids.";
+
+    CONFIG_INVALID = "CFG001", "config.invalid", "A `boruna.toml` or user config file, or a config environment variable, is unreadable or invalid.";
+}
+
+/// The code with the given `code` or `kind` string.
+pub fn lookup(code_or_kind: &str) -> Option<&'static ErrorCode> {
+    REGISTRY
+        .iter()
+        .find(|c| c.code == code_or_kind || c.kind == code_or_kind)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn codes_and_kinds_are_unique() {
+        let codes: BTreeSet<_> = REGISTRY.iter().map(|c| c.code).collect();
+        let kinds: BTreeSet<_> = REGISTRY.iter().map(|c| c.kind).collect();
+        assert_eq!(codes.len(), REGISTRY.len());
+        assert_eq!(kinds.len(), REGISTRY.len());
+    }
+
+    #[test]
+    fn kinds_are_namespaced_by_domain() {
+        for c in &REGISTRY[1..] {
+            let (domain, rest) = c.kind.split_once('.').unwrap();
+            let prefix = match domain {
+                "compile" => "E0",
+                "vm" => "VM",
+                "framework" => "FW",
+                "workflow" => "WF",
+                "pkg" => "PKG",
+                "config" => "CFG",
+                other => panic!("unknown domain {other}"),
+            };
+            assert!(c.code.starts_with(prefix), "{}", c.code);
+            assert!(
+                rest.chars().all(|ch| ch.is_ascii_lowercase() || ch == '_'),
+                "{}",
+                c.kind
+            );
+        }
+    }
+
+    #[test]
+    fn reference_lists_every_code() {
+        let doc = include_str!("../../../docs/reference/error-codes.md");
+        for c in REGISTRY {
+            let row = format!("| `{}` | `{}` |", c.code, c.kind);
+            assert!(doc.contains(&row), "error-codes.md lacks {row}");
+        }
+    }
+
+    #[test]
+    fn envelope_shape_is_stable() {
+        let env = ErrorEnvelope::new(&VM_DIVISION_BY_ZERO, "division by zero", Value::Null);
+        assert_eq!(
+            env.to_json(),
+            r#"{"code":"VM004","kind":"vm.division_by_zero","message":"division by zero","data":null}"#
+        );
+        let back: ErrorEnvelope = serde_json::from_str(&env.to_json()).unwrap();
+        assert_eq!(back, env);
+        assert_eq!(lookup("VM004"), Some(&VM_DIVISION_BY_ZERO));
+        assert_eq!(lookup("vm.division_by_zero"), Some(&VM_DIVISION_BY_ZERO));
+    }
+}
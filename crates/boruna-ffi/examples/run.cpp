@@ -0,0 +1,37 @@
+// Compile and run a Boruna program through libboruna from C++.
+//
+//   cargo build --release -p boruna-ffi
+//   c++ -std=c++17 -I crates/boruna-ffi/include crates/boruna-ffi/examples/run.cpp -L target/release -lboruna -o run
+//   LD_LIBRARY_PATH=target/release ./run
+
+#include <cstdio>
+#include <memory>
+
+#include "boruna.h"
+
+struct FreeModule {
+    void operator()(BorunaModule *m) const { boruna_module_free(m); }
+};
+struct FreeString {
+    void operator()(char *s) const { boruna_free(s); }
+};
+using Module = std::unique_ptr<BorunaModule, FreeModule>;
+using String = std::unique_ptr<char, FreeString>;
+
+int main() {
+    std::printf("libboruna %s\n", boruna_version());
+
+    BorunaModule *raw = nullptr;
+    char *error = nullptr;
+    if (boruna_compile("demo", "fn main() -> Int { 41 + 1 }\n", &raw, &error) != BORUNA_STATUS_OK) {
+        std::fprintf(stderr, "%s\n", String(error).get());
+        return 1;
+    }
+    Module module(raw);
+
+    char *out = nullptr;
+    BorunaStatus status = boruna_run_json(module.get(), R"({"max_steps": 10000})", &out);
+    String result(out);
+    std::printf("%s\n", result.get());
+    return status == BORUNA_STATUS_OK ? 0 : 1;
+}
@@ -0,0 +1,72 @@
+"""Compile and run a Boruna program through libboruna with ctypes.
+
+    cargo build --release -p boruna-ffi
+    python3 crates/boruna-ffi/examples/run.py target/release/libboruna.so
+"""
+
+import ctypes
+import json
+import sys
+
+OK = 0
+
+lib = ctypes.CDLL(sys.argv[1])
+lib.boruna_version.restype = ctypes.c_char_p
+lib.boruna_compile.argtypes = [
+    ctypes.c_char_p,
+    ctypes.c_char_p,
+    ctypes.POINTER(ctypes.c_void_p),
+    ctypes.POINTER(ctypes.c_void_p),
+]
+lib.boruna_run_json.argtypes = [
+    ctypes.c_void_p,
+    ctypes.c_char_p,
+    ctypes.POINTER(ctypes.c_void_p),
+]
+lib.boruna_module_free.argtypes = [ctypes.c_void_p]
+lib.boruna_free.argtypes = [ctypes.c_void_p]
+
+
+class BorunaError(Exception):
+    def __init__(self, error):
+        super().__init__(error["message"])
+        self.error = error
+
+
+def take_json(ptr):
+    try:
+        return json.loads(ctypes.string_at(ptr.value))
+    finally:
+        lib.boruna_free(ptr)
+
+
+def compile(source, name="module"):
+    module, error = ctypes.c_void_p(), ctypes.c_void_p()
+    status = lib.boruna_compile(
+        name.encode(), source.encode(), ctypes.byref(module), ctypes.byref(error)
+    )
+    if status != OK:
+        raise BorunaError(take_json(error))
+    return module
+
+
+def run(module, **options):
+    out = ctypes.c_void_p()
+    status = lib.boruna_run_json(module, json.dumps(options).encode(), ctypes.byref(out))
+    output = take_json(out)
+    if status != OK:
+        raise BorunaError(output)
+    return output
+
+
+print("libboruna", lib.boruna_version().decode())
+module = compile("fn main() -> Int { 41 + 1 }\n")
+try:
+    print(run(module, max_steps=10_000)["result"])
+finally:
+    lib.boruna_module_free(module)
+
+try:
+    compile("fn main( {")
+except BorunaError as e:
+    print(e.error["code"], e)
@@ -0,0 +1,95 @@
+/* Boruna C API. Generated by cbindgen from crates/boruna-ffi/src/lib.rs; do not edit. */
+
+#ifndef BORUNA_H
+#define BORUNA_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Result of every `boruna_*` call.
+typedef enum BorunaStatus {
+  BORUNA_STATUS_OK = 0,
+  // A null pointer, text that is not UTF-8, or malformed options.
+  BORUNA_STATUS_INVALID_ARGUMENT = 1,
+  BORUNA_STATUS_COMPILE_ERROR = 2,
+  // The program ran and failed, or hit a limit.
+  BORUNA_STATUS_RUNTIME_ERROR = 3,
+  // An internal panic, caught at the boundary.
+  BORUNA_STATUS_PANIC = 4,
+} BorunaStatus;
+
+// A compiled module. Opaque to C.
+typedef struct BorunaModule BorunaModule;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Library version, e.g. `"3.2.0"`. Static; do not free.
+const char *boruna_version(void);
+
+// Compile `.ax` `source` into a module named `name` (`"module"` when
+// `name` is null).
+//
+// On `BORUNA_STATUS_OK`, `*module_out` holds the module. Otherwise
+// `*error_out`, when `error_out` is non-null, holds a JSON error such as
+// `{"error_kind":"compile_error","code":"E002","message":"...","line":3}`.
+//
+// # Safety
+//
+// `source` and a non-null `name` must be NUL-terminated strings;
+// `module_out` must be valid for writes; `error_out` must be null or
+// valid for writes.
+enum BorunaStatus boruna_compile(const char *name,
+                                 const char *source,
+                                 struct BorunaModule **module_out,
+                                 char **error_out);
+
+// Run `module` from its entry point and write the outcome to
+// `*result_out` as JSON:
+//
+// ```json
+// {"success": true, "result": 42, "steps": 17, "ui_output": []}
+// ```
+//
+// `options_json` may be null or a JSON object with any of `policy`
+// (`"allow-all"`, the default; `"deny-all"`; or a policy object as in
+// `boruna run --policy`), `max_steps`, and `record` (attach the event
+// log as `event_log`). A failed run returns `BORUNA_STATUS_RUNTIME_ERROR`
+// with `{"success": false, "error_kind": "runtime_error" | "limit_exceeded",
+// "message": ..., "steps": ...}`.
+//
+// # Safety
+//
+// `module` must come from [`boruna_compile`] and not yet be freed; a
+// non-null `options_json` must be a NUL-terminated string; `result_out`
+// must be valid for writes.
+enum BorunaStatus boruna_run_json(const struct BorunaModule *module,
+                                  const char *options_json,
+                                  char **result_out);
+
+// Free a module from [`boruna_compile`]. Null is ignored.
+//
+// # Safety
+//
+// `module` must be null or come from [`boruna_compile`], and must not be
+// used afterwards.
+void boruna_module_free(struct BorunaModule *module);
+
+// Free a string returned through a `char **` out-parameter. Null is
+// ignored.
+//
+// # Safety
+//
+// `s` must be null or a string from this library, and must not be used
+// afterwards.
+void boruna_free(char *s);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* BORUNA_H */
@@ -0,0 +1,370 @@
+//! C API for embedding the Boruna compiler and VM.
+//!
+//! Built as `libboruna` (`.so`, `.dylib`, `.dll`) with the header at
+//! `include/boruna.h`. The calling convention:
+//!
+//! - Every entry point returns a [`BorunaStatus`]; `BORUNA_STATUS_OK` is 0.
+//! - Text crosses the boundary as NUL-terminated UTF-8.
+//! - Strings written to a `char **` out-parameter are owned by the caller
+//!   and released with [`boruna_free`]. On failure the out-parameter holds
+//!   a JSON error object (`error_kind`, `message`, ...) instead of a result,
+//!   so a host keeps the typed error a subprocess would have lost.
+//! - A [`BorunaModule`] from [`boruna_compile`] is released with
+//!   [`boruna_module_free`]. It is immutable, so one module may be run from
+//!   several threads at once.
+//! - A panic never unwinds into the host; it is reported as
+//!   `BORUNA_STATUS_PANIC`.
+
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr;
+
+use boruna_bytecode::{Module, Value};
+use boruna_compiler::CompileError;
+use boruna_vm::capability_gateway::{CapabilityGateway, Policy};
+use boruna_vm::error::VmError;
+use boruna_vm::replay::RunOutcome;
+use boruna_vm::vm::Vm;
+use serde_json::{json, Value as JsonValue};
+
+/// Result of every `boruna_*` call.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BorunaStatus {
+    Ok = 0,
+    /// A null pointer, text that is not UTF-8, or malformed options.
+    InvalidArgument = 1,
+    CompileError = 2,
+    /// The program ran and failed, or hit a limit.
+    RuntimeError = 3,
+    /// An internal panic, caught at the boundary.
+    Panic = 4,
+}
+
+/// A compiled module. Opaque to C.
+pub struct BorunaModule {
+    module: Module,
+}
+
+/// Library version, e.g. `"3.2.0"`. Static; do not free.
+#[no_mangle]
+pub extern "C" fn boruna_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr().cast()
+}
+
+/// Compile `.ax` `source` into a module named `name` (`"module"` when
+/// `name` is null).
+///
+/// On `BORUNA_STATUS_OK`, `*module_out` holds the module. Otherwise
+/// `*error_out`, when `error_out` is non-null, holds a JSON error such as
+/// `{"error_kind":"compile_error","code":"E002","message":"...","line":3}`.
+///
+/// # Safety
+///
+/// `source` and a non-null `name` must be NUL-terminated strings;
+/// `module_out` must be valid for writes; `error_out` must be null or
+/// valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn boruna_compile(
+    name: *const c_char,
+    source: *const c_char,
+    module_out: *mut *mut BorunaModule,
+    error_out: *mut *mut c_char,
+) -> BorunaStatus {
+    guarded(error_out, || {
+        if module_out.is_null() {
+            return Err(invalid("module_out is null"));
+        }
+        let name = if name.is_null() {
+            "module"
+        } else {
+            read_str(name, "name")?
+        };
+        let source = read_str(source, "source")?;
+        let module = boruna_compiler::compile(name, source)
+            .map_err(|e| (BorunaStatus::CompileError, compile_error_json(&e)))?;
+        *module_out = Box::into_raw(Box::new(BorunaModule { module }));
+        Ok(())
+    })
+}
+
+/// Run `module` from its entry point and write the outcome to
+/// `*result_out` as JSON:
+///
+/// ```json
+/// {"success": true, "result": 42, "steps": 17, "ui_output": []}
+/// ```
+///
+/// `options_json` may be null or a JSON object with any of `policy`
+/// (`"allow-all"`, the default; `"deny-all"`; or a policy object as in
+/// `boruna run --policy`), `max_steps`, and `record` (attach the event
+/// log as `event_log`). A failed run returns `BORUNA_STATUS_RUNTIME_ERROR`
+/// with `{"success": false, "error_kind": "runtime_error" | "limit_exceeded",
+/// "message": ..., "steps": ...}`.
+///
+/// # Safety
+///
+/// `module` must come from [`boruna_compile`] and not yet be freed; a
+/// non-null `options_json` must be a NUL-terminated string; `result_out`
+/// must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn boruna_run_json(
+    module: *const BorunaModule,
+    options_json: *const c_char,
+    result_out: *mut *mut c_char,
+) -> BorunaStatus {
+    if result_out.is_null() {
+        return BorunaStatus::InvalidArgument;
+    }
+    guarded(result_out, || {
+        let Some(module) = module.as_ref() else {
+            return Err(invalid("module is null"));
+        };
+        let options = if options_json.is_null() {
+            JsonValue::Null
+        } else {
+            serde_json::from_str(read_str(options_json, "options_json")?)
+                .map_err(|e| invalid(format!("options_json is not JSON: {e}")))?
+        };
+        let output = run(&module.module, &options)?;
+        *result_out = into_c_string(output.to_string());
+        Ok(())
+    })
+}
+
+/// Free a module from [`boruna_compile`]. Null is ignored.
+///
+/// # Safety
+///
+/// `module` must be null or come from [`boruna_compile`], and must not be
+/// used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn boruna_module_free(module: *mut BorunaModule) {
+    if !module.is_null() {
+        drop(Box::from_raw(module));
+    }
+}
+
+/// Free a string returned through a `char **` out-parameter. Null is
+/// ignored.
+///
+/// # Safety
+///
+/// `s` must be null or a string from this library, and must not be used
+/// afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn boruna_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+type Failure = (BorunaStatus, JsonValue);
+
+/// Run `f`, turning a failure or a panic into a status and, when `out`
+/// is non-null, a JSON error written to it.
+unsafe fn guarded(out: *mut *mut c_char, f: impl FnOnce() -> Result<(), Failure>) -> BorunaStatus {
+    let (status, error) = match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => return BorunaStatus::Ok,
+        Ok(Err(failure)) => failure,
+        Err(_) => (
+            BorunaStatus::Panic,
+            json!({ "success": false, "error_kind": "panic", "message": "internal panic" }),
+        ),
+    };
+    if !out.is_null() {
+        *out = into_c_string(error.to_string());
+    }
+    status
+}
+
+fn invalid(message: impl Into<String>) -> Failure {
+    (
+        BorunaStatus::InvalidArgument,
+        json!({ "success": false, "error_kind": "invalid_argument", "message": message.into() }),
+    )
+}
+
+unsafe fn read_str<'a>(s: *const c_char, what: &str) -> Result<&'a str, Failure> {
+    if s.is_null() {
+        return Err(invalid(format!("{what} is null")));
+    }
+    CStr::from_ptr(s)
+        .to_str()
+        .map_err(|_| invalid(format!("{what} is not UTF-8")))
+}
+
+fn into_c_string(s: String) -> *mut c_char {
+    // serde_json escapes NUL inside strings, so JSON text has none.
+    CString::new(s).map_or(ptr::null_mut(), CString::into_raw)
+}
+
+/// Same codes as `boruna_compile` over MCP.
+fn compile_error_json(err: &CompileError) -> JsonValue {
+    let code = match err {
+        CompileError::Lexer { .. } => "E001",
+        CompileError::Parse { .. } => "E002",
+        CompileError::Type { .. } => "E009",
+        CompileError::Codegen { .. } => "E008",
+        CompileError::Budget(_) => "E015",
+    };
+    let mut error = json!({
+        "success": false,
+        "error_kind": "compile_error",
+        "code": code,
+        "message": err.message(),
+    });
+    if let Some(span) = err.span() {
+        error["line"] = json!(span.line);
+        error["col"] = json!(span.col);
+        error["end_line"] = json!(span.end_line);
+        error["end_col"] = json!(span.end_col);
+    }
+    error
+}
+
+fn parse_policy(policy: &JsonValue) -> Result<Policy, Failure> {
+    match policy {
+        JsonValue::Null => Ok(Policy::allow_all()),
+        JsonValue::String(s) if s == "allow-all" => Ok(Policy::allow_all()),
+        JsonValue::String(s) if s == "deny-all" => Ok(Policy::deny_all()),
+        JsonValue::Object(_) => {
+            boruna_vm::policy_validate::parse(&policy.to_string()).map_err(|e| {
+                (
+                    BorunaStatus::InvalidArgument,
+                    json!({
+                        "success": false,
+                        "error_kind": e.error_kind(),
+                        "message": e.to_string(),
+                    }),
+                )
+            })
+        }
+        _ => Err(invalid(
+            "policy must be \"allow-all\", \"deny-all\" or a policy object",
+        )),
+    }
+}
+
+fn run(module: &Module, options: &JsonValue) -> Result<JsonValue, Failure> {
+    if !matches!(options, JsonValue::Null | JsonValue::Object(_)) {
+        return Err(invalid("options_json must be a JSON object"));
+    }
+    let policy = parse_policy(&options["policy"])?;
+    let max_steps = match &options["max_steps"] {
+        JsonValue::Null => None,
+        v => Some(
+            v.as_u64()
+                .ok_or_else(|| invalid("max_steps must be a non-negative integer"))?,
+        ),
+    };
+    let record = match &options["record"] {
+        JsonValue::Null => false,
+        v => v
+            .as_bool()
+            .ok_or_else(|| invalid("record must be a boolean"))?,
+    };
+
+    let mut vm = Vm::new(module.clone(), CapabilityGateway::new(policy));
+    if let Some(max) = max_steps {
+        vm.set_max_steps(max);
+    }
+    let run = vm.run();
+
+    let event_log = record.then(|| {
+        let mut log = vm.event_log().clone();
+        log.set_outcome(RunOutcome {
+            steps: vm.step_count(),
+            result: run.as_ref().ok().cloned(),
+            error: run.as_ref().err().map(|e| e.to_string()),
+            denials: vm.gateway().denials().to_vec(),
+            kv_snapshot: None,
+        });
+        log.to_json()
+            .ok()
+            .and_then(|j| serde_json::from_str::<JsonValue>(&j).ok())
+    });
+    match run {
+        Ok(value) => {
+            let mut output = json!({
+                "success": true,
+                "result": format_value(&value),
+                "steps": vm.step_count(),
+                "ui_output": vm.ui_output.iter().map(format_value).collect::<Vec<_>>(),
+            });
+            if let Some(log) = event_log {
+                output["event_log"] = json!(log);
+            }
+            Ok(output)
+        }
+        Err(e) => {
+            let error_kind = match e {
+                VmError::ExecutionLimitExceeded(_) => "limit_exceeded",
+                _ => "runtime_error",
+            };
+            let mut output = json!({
+                "success": false,
+                "error_kind": error_kind,
+                "message": e.to_string(),
+                "steps": vm.step_count(),
+            });
+            if let Some(log) = event_log {
+                output["event_log"] = json!(log);
+            }
+            Err((BorunaStatus::RuntimeError, output))
+        }
+    }
+}
+
+/// A value as JSON, in the shape `boruna_run` uses over MCP.
+fn format_value(value: &Value) -> JsonValue {
+    match value {
+        Value::Int(n) => json!(n),
+        Value::Float(f) => json!(f),
+        Value::Decimal(d) => json!(d.to_string()),
+        Value::BigInt(n) => json!(n.to_string()),
+        Value::String(s) => json!(s),
+        Value::Bool(b) => json!(b),
+        Value::Unit => JsonValue::Null,
+        Value::None => json!({"option": "None"}),
+        Value::Some(v) => json!({"option": "Some", "value": format_value(v)}),
+        Value::Ok(v) => json!({"result": "Ok", "value": format_value(v)}),
+        Value::Err(v) => json!({"result": "Err", "value": format_value(v)}),
+        Value::List(items) => json!(items.iter().map(format_value).collect::<Vec<_>>()),
+        Value::Record { type_id, fields } => json!({
+            "type": "record",
+            "type_id": type_id,
+            "fields": fields.iter().map(format_value).collect::<Vec<_>>(),
+        }),
+        Value::Enum {
+            type_id,
+            variant,
+            payload,
+        } => json!({
+            "type": "enum",
+            "type_id": type_id,
+            "variant": variant,
+            "payload": format_value(payload),
+        }),
+        Value::Map(entries) => JsonValue::Object(
+            entries
+                .iter()
+                .map(|(k, v)| (k.clone(), format_value(v)))
+                .collect(),
+        ),
+        Value::ActorId(id) => json!({"actor_id": id}),
+        Value::FnRef(idx) => json!({"fn_ref": idx}),
+        Value::CapHandle(id) => json!({"cap_handle": id}),
+        Value::Table(t) => {
+            let rows: Vec<Vec<JsonValue>> = (0..t.num_rows())
+                .map(|r| {
+                    t.columns()
+                        .iter()
+                        .map(|c| format_value(&c.values[r]))
+                        .collect()
+                })
+                .collect();
+            json!({ "type": "table", "columns": t.column_names(), "rows": rows })
+        }
+    }
+}
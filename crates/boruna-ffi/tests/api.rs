@@ -0,0 +1,126 @@
+//! The C API, called the way a host would call it.
+
+use std::ffi::{c_char, CStr, CString};
+use std::ptr;
+
+use boruna::{
+    boruna_compile, boruna_free, boruna_module_free, boruna_run_json, boruna_version, BorunaModule,
+    BorunaStatus,
+};
+use serde_json::Value as JsonValue;
+
+fn compile(source: &str) -> Result<*mut BorunaModule, (BorunaStatus, JsonValue)> {
+    let source = CString::new(source).unwrap();
+    let mut module = ptr::null_mut();
+    let mut error = ptr::null_mut();
+    let status = unsafe { boruna_compile(ptr::null(), source.as_ptr(), &mut module, &mut error) };
+    if status == BorunaStatus::Ok {
+        assert!(error.is_null());
+        Ok(module)
+    } else {
+        assert!(module.is_null());
+        Err((status, unsafe { take_json(error) }))
+    }
+}
+
+fn run(module: *const BorunaModule, options: Option<&str>) -> (BorunaStatus, JsonValue) {
+    let options = options.map(|o| CString::new(o).unwrap());
+    let mut out = ptr::null_mut();
+    let status = unsafe {
+        boruna_run_json(
+            module,
+            options.as_ref().map_or(ptr::null(), |o| o.as_ptr()),
+            &mut out,
+        )
+    };
+    (status, unsafe { take_json(out) })
+}
+
+unsafe fn take_json(s: *mut c_char) -> JsonValue {
+    assert!(!s.is_null());
+    let json = serde_json::from_str(CStr::from_ptr(s).to_str().unwrap()).unwrap();
+    boruna_free(s);
+    json
+}
+
+#[test]
+fn compile_and_run() {
+    let module = compile("fn main() -> Int { 41 + 1 }\n").unwrap();
+    let (status, output) = run(module, None);
+    assert_eq!(status, BorunaStatus::Ok);
+    assert_eq!(output["success"], true);
+    assert_eq!(output["result"], 42);
+    assert!(output["steps"].as_u64().unwrap() > 0);
+    assert!(output.get("event_log").is_none());
+
+    // A module is reusable.
+    let (status, again) = run(module, Some(r#"{"record": true}"#));
+    assert_eq!(status, BorunaStatus::Ok);
+    assert_eq!(again["result"], 42);
+    assert_eq!(again["event_log"]["outcome"]["steps"], again["steps"]);
+    unsafe { boruna_module_free(module) };
+}
+
+#[test]
+fn compile_error_is_typed() {
+    let (status, error) = compile("fn main( {").unwrap_err();
+    assert_eq!(status, BorunaStatus::CompileError);
+    assert_eq!(error["error_kind"], "compile_error");
+    assert_eq!(error["code"], "E002");
+    assert!(error["line"].is_u64());
+}
+
+#[test]
+fn runtime_failures_are_typed() {
+    let module =
+        compile("fn main() -> Int !{kv.get} {\n    let a = kv_get(\"a\")\n    1\n}\n").unwrap();
+    let (status, output) = run(module, Some(r#"{"policy": "deny-all"}"#));
+    assert_eq!(status, BorunaStatus::RuntimeError);
+    assert_eq!(output["success"], false);
+    assert_eq!(output["error_kind"], "runtime_error");
+    unsafe { boruna_module_free(module) };
+
+    let module = compile("fn main() -> Int { 1 + 2 + 3 }\n").unwrap();
+    let (status, output) = run(module, Some(r#"{"max_steps": 1}"#));
+    assert_eq!(status, BorunaStatus::RuntimeError);
+    assert_eq!(output["error_kind"], "limit_exceeded");
+    unsafe { boruna_module_free(module) };
+}
+
+#[test]
+fn bad_arguments_are_rejected() {
+    let module = compile("fn main() -> Int { 1 }\n").unwrap();
+    for options in [
+        "not json",
+        "[]",
+        r#"{"max_steps": -1}"#,
+        r#"{"policy": "some"}"#,
+    ] {
+        let (status, output) = run(module, Some(options));
+        assert_eq!(status, BorunaStatus::InvalidArgument, "{options}");
+        assert_eq!(output["error_kind"], "invalid_argument", "{options}");
+    }
+    let (status, output) = run(module, Some(r#"{"policy": {"schema_version": 99}}"#));
+    assert_eq!(status, BorunaStatus::InvalidArgument);
+    assert!(output["error_kind"]
+        .as_str()
+        .unwrap()
+        .starts_with("policy."));
+    unsafe { boruna_module_free(module) };
+
+    let (status, _) = run(ptr::null(), None);
+    assert_eq!(status, BorunaStatus::InvalidArgument);
+    let mut module = ptr::null_mut();
+    let status = unsafe { boruna_compile(ptr::null(), ptr::null(), &mut module, ptr::null_mut()) };
+    assert_eq!(status, BorunaStatus::InvalidArgument);
+    unsafe {
+        boruna_free(ptr::null_mut());
+        boruna_module_free(ptr::null_mut());
+    }
+}
+
+#[test]
+fn version_matches_crate() {
+    let version = unsafe { CStr::from_ptr(boruna_version()) };
+    assert_eq!(version.to_str().unwrap(), env!("CARGO_PKG_VERSION"));
+}
@@ -0,0 +1,52 @@
+//! Generate the `Boruna` service's server and client plumbing.
+//!
+//! The messages live in `src/proto.rs`, so no `protoc` is needed; the
+//! methods here must match the `service Boruna` block of
+//! `proto/boruna/v1/boruna.proto`.
+
+use tonic_build::manual::{Builder, Method, Service};
+
+fn method(name: &str, route: &str, input: &str, output: &str, streaming: bool) -> Method {
+    let builder = Method::builder()
+        .name(name)
+        .route_name(route)
+        .input_type(format!("crate::proto::{input}"))
+        .output_type(format!("crate::proto::{output}"))
+        .codec_path("tonic::codec::ProstCodec");
+    if streaming {
+        builder.server_streaming().build()
+    } else {
+        builder.build()
+    }
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    let service = Service::builder()
+        .name("Boruna")
+        .package("boruna.v1")
+        .method(method(
+            "compile",
+            "Compile",
+            "CompileRequest",
+            "CompileResponse",
+            false,
+        ))
+        .method(method("run", "Run", "RunRequest", "RunEvent", true))
+        .method(method(
+            "replay",
+            "Replay",
+            "ReplayRequest",
+            "ReplayResponse",
+            false,
+        ))
+        .method(method(
+            "workflow_run",
+            "WorkflowRun",
+            "WorkflowRunRequest",
+            "WorkflowEvent",
+            true,
+        ))
+        .build();
+    Builder::new().compile(&[service]);
+}
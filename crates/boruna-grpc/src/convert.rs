@@ -0,0 +1,481 @@
+//! Conversions between the protobuf messages and the VM's own types.
+//!
+//! Every `*_to_proto` is total. The `*_from_proto` direction validates
+//! what a remote client sent and reports the first malformed field.
+
+use boruna_bytecode::{BigInt, Decimal, Table, Value};
+use boruna_vm::capability_gateway::{Denial, Policy, PolicyMode};
+use boruna_vm::replay::{Event, EventLog, RunOutcome, EVENT_LOG_VERSION};
+
+use crate::proto::{self, event, value};
+
+pub fn value_to_proto(v: &Value) -> proto::Value {
+    use value::Kind;
+    let kind = match v {
+        Value::Unit => Kind::Unit(proto::Unit {}),
+        Value::Bool(b) => Kind::Bool(*b),
+        Value::Int(n) => Kind::Int(*n),
+        Value::Float(f) => Kind::Float(*f),
+        Value::Decimal(d) => Kind::Decimal(d.to_string()),
+        Value::BigInt(n) => Kind::BigInt(n.to_string()),
+        Value::String(s) => Kind::String(s.clone()),
+        Value::None => Kind::None(proto::Unit {}),
+        Value::Some(inner) => Kind::Some(Box::new(value_to_proto(inner))),
+        Value::Ok(inner) => Kind::Ok(Box::new(value_to_proto(inner))),
+        Value::Err(inner) => Kind::Err(Box::new(value_to_proto(inner))),
+        Value::Record { type_id, fields } => Kind::Record(proto::Record {
+            type_id: *type_id,
+            fields: fields.iter().map(value_to_proto).collect(),
+        }),
+        Value::Enum {
+            type_id,
+            variant,
+            payload,
+        } => Kind::Enum(Box::new(proto::EnumValue {
+            type_id: *type_id,
+            variant: u32::from(*variant),
+            payload: Some(Box::new(value_to_proto(payload))),
+        })),
+        Value::List(items) => Kind::List(proto::ValueList {
+            items: items.iter().map(value_to_proto).collect(),
+        }),
+        Value::Map(entries) => Kind::Map(proto::ValueMap {
+            entries: entries
+                .iter()
+                .map(|(k, v)| (k.clone(), value_to_proto(v)))
+                .collect(),
+        }),
+        Value::ActorId(id) => Kind::ActorId(*id),
+        Value::FnRef(idx) => Kind::FnRef(*idx),
+        Value::CapHandle(handle) => Kind::CapHandle(*handle),
+        // A table always serializes; the fallback is unreachable.
+        Value::Table(t) => Kind::TableJson(serde_json::to_string(t).unwrap_or_default()),
+    };
+    proto::Value { kind: Some(kind) }
+}
+
+pub fn value_from_proto(v: &proto::Value) -> Result<Value, String> {
+    use value::Kind;
+    let Some(kind) = &v.kind else {
+        return Err("value has no kind set".into());
+    };
+    Ok(match kind {
+        Kind::Unit(_) => Value::Unit,
+        Kind::Bool(b) => Value::Bool(*b),
+        Kind::Int(n) => Value::Int(*n),
+        Kind::Float(f) => Value::Float(*f),
+        Kind::Decimal(s) => {
+            Value::Decimal(Decimal::parse(s).ok_or_else(|| format!("invalid decimal '{s}'"))?)
+        }
+        Kind::BigInt(s) => {
+            Value::BigInt(BigInt::parse(s).ok_or_else(|| format!("invalid big_int '{s}'"))?)
+        }
+        Kind::String(s) => Value::String(s.clone()),
+        Kind::None(_) => Value::None,
+        Kind::Some(inner) => Value::Some(Box::new(value_from_proto(inner)?)),
+        Kind::Ok(inner) => Value::Ok(Box::new(value_from_proto(inner)?)),
+        Kind::Err(inner) => Value::Err(Box::new(value_from_proto(inner)?)),
+        Kind::Record(r) => Value::Record {
+            type_id: r.type_id,
+            fields: values_from_proto(&r.fields)?,
+        },
+        Kind::Enum(e) => Value::Enum {
+            type_id: e.type_id,
+            variant: u8::try_from(e.variant)
+                .map_err(|_| format!("enum variant {} out of range", e.variant))?,
+            payload: Box::new(match &e.payload {
+                Some(payload) => value_from_proto(payload)?,
+                None => Value::Unit,
+            }),
+        },
+        Kind::List(l) => Value::List(values_from_proto(&l.items)?),
+        Kind::Map(m) => Value::Map(
+            m.entries
+                .iter()
+                .map(|(k, v)| Ok((k.clone(), value_from_proto(v)?)))
+                .collect::<Result<_, String>>()?,
+        ),
+        Kind::ActorId(id) => Value::ActorId(*id),
+        Kind::FnRef(idx) => Value::FnRef(*idx),
+        Kind::CapHandle(handle) => Value::CapHandle(*handle),
+        Kind::TableJson(json) => Value::Table(
+            serde_json::from_str::<Table>(json).map_err(|e| format!("invalid table_json: {e}"))?,
+        ),
+    })
+}
+
+fn values_from_proto(values: &[proto::Value]) -> Result<Vec<Value>, String> {
+    values.iter().map(value_from_proto).collect()
+}
+
+/// An absent value field reads as `Unit`, proto3's "not set".
+fn optional_value_from_proto(v: Option<&proto::Value>) -> Result<Value, String> {
+    v.map_or(Ok(Value::Unit), value_from_proto)
+}
+
+/// Build a [`Policy`] and validate it with the same strict parser as
+/// `boruna run --policy` and the MCP server.
+pub fn policy_from_proto(p: &proto::Policy) -> Result<Policy, String> {
+    let mut json = if p.sections_json.trim().is_empty() {
+        serde_json::Map::new()
+    } else {
+        match serde_json::from_str(&p.sections_json) {
+            Ok(serde_json::Value::Object(map)) => map,
+            Ok(_) => return Err("sections_json must be a JSON object".into()),
+            Err(e) => return Err(format!("invalid sections_json: {e}")),
+        }
+    };
+    json.insert(
+        "schema_version".into(),
+        serde_json::json!(p.schema_version.max(1)),
+    );
+    let rules: serde_json::Map<String, serde_json::Value> = p
+        .rules
+        .iter()
+        .map(|(cap, rule)| {
+            let mut entry = serde_json::json!({ "allow": rule.allow, "budget": rule.budget });
+            if rule.prompt {
+                entry["prompt"] = serde_json::json!(true);
+            }
+            (cap.clone(), entry)
+        })
+        .collect();
+    json.insert("rules".into(), rules.into());
+    json.insert("default_allow".into(), p.default_allow.into());
+    if !p.mode.is_empty() {
+        json.insert("mode".into(), p.mode.clone().into());
+    }
+    let json = serde_json::Value::Object(json).to_string();
+    boruna_vm::policy_validate::parse(&json).map_err(|e| format!("{}: {e}", e.error_kind()))
+}
+
+pub fn policy_to_proto(p: &Policy) -> proto::Policy {
+    let mut sections = match serde_json::to_value(p) {
+        Ok(serde_json::Value::Object(map)) => map,
+        _ => serde_json::Map::new(),
+    };
+    for typed in ["schema_version", "rules", "default_allow", "mode"] {
+        sections.remove(typed);
+    }
+    proto::Policy {
+        schema_version: p.schema_version,
+        rules: p
+            .rules
+            .iter()
+            .map(|(cap, rule)| {
+                let rule = proto::PolicyRule {
+                    allow: rule.allow,
+                    budget: rule.budget,
+                    prompt: rule.prompt,
+                };
+                (cap.clone(), rule)
+            })
+            .collect(),
+        default_allow: p.default_allow,
+        mode: if p.mode == PolicyMode::Enforce {
+            String::new()
+        } else {
+            "dry_run".into()
+        },
+        sections_json: if sections.is_empty() {
+            String::new()
+        } else {
+            serde_json::Value::Object(sections).to_string()
+        },
+    }
+}
+
+pub fn event_to_proto(e: &Event) -> proto::Event {
+    use event::Kind;
+    let kind = match e {
+        Event::CapCall { capability, args } => Kind::CapCall(proto::CapCall {
+            capability: capability.clone(),
+            args: args.iter().map(value_to_proto).collect(),
+        }),
+        Event::CapResult { capability, result } => Kind::CapResult(proto::CapResult {
+            capability: capability.clone(),
+            result: Some(value_to_proto(result)),
+        }),
+        Event::ActorSpawn { actor_id, function } => Kind::ActorSpawn(proto::ActorSpawn {
+            actor_id: *actor_id,
+            function: function.clone(),
+        }),
+        Event::MessageSend { from, to, payload } => Kind::MessageSend(proto::MessageSend {
+            from: *from,
+            to: *to,
+            payload: Some(value_to_proto(payload)),
+        }),
+        Event::MessageReceive { actor_id, payload } => {
+            Kind::MessageReceive(proto::MessageReceive {
+                actor_id: *actor_id,
+                payload: Some(value_to_proto(payload)),
+            })
+        }
+        Event::UiEmit { tree } => Kind::UiEmit(proto::UiEmit {
+            tree: Some(value_to_proto(tree)),
+        }),
+        Event::SchedulerTick {
+            round,
+            active_actor,
+        } => Kind::SchedulerTick(proto::SchedulerTick {
+            round: *round,
+            active_actor: *active_actor,
+        }),
+        Event::ContractCheck {
+            function,
+            kind,
+            index,
+            passed,
+        } => Kind::ContractCheck(proto::ContractCheck {
+            function: function.clone(),
+            kind: kind.clone(),
+            index: *index as u64,
+            passed: *passed,
+        }),
+        Event::ActorCheckpoint {
+            actor_id,
+            state_hash,
+        } => Kind::ActorCheckpoint(proto::ActorCheckpoint {
+            actor_id: *actor_id,
+            state_hash: state_hash.clone(),
+        }),
+        Event::ActorRestore {
+            actor_id,
+            state_hash,
+        } => Kind::ActorRestore(proto::ActorCheckpoint {
+            actor_id: *actor_id,
+            state_hash: state_hash.clone(),
+        }),
+        Event::CapabilityDelegation {
+            parent,
+            child,
+            policy_hash,
+        } => Kind::CapabilityDelegation(proto::CapabilityDelegation {
+            parent: *parent,
+            child: *child,
+            policy_hash: policy_hash.clone(),
+        }),
+    };
+    proto::Event { kind: Some(kind) }
+}
+
+pub fn event_from_proto(e: &proto::Event) -> Result<Event, String> {
+    use event::Kind;
+    let Some(kind) = &e.kind else {
+        return Err("event has no kind set".into());
+    };
+    Ok(match kind {
+        Kind::CapCall(c) => Event::CapCall {
+            capability: c.capability.clone(),
+            args: values_from_proto(&c.args)?,
+        },
+        Kind::CapResult(c) => Event::CapResult {
+            capability: c.capability.clone(),
+            result: optional_value_from_proto(c.result.as_ref())?,
+        },
+        Kind::ActorSpawn(s) => Event::ActorSpawn {
+            actor_id: s.actor_id,
+            function: s.function.clone(),
+        },
+        Kind::MessageSend(m) => Event::MessageSend {
+            from: m.from,
+            to: m.to,
+            payload: optional_value_from_proto(m.payload.as_ref())?,
+        },
+        Kind::MessageReceive(m) => Event::MessageReceive {
+            actor_id: m.actor_id,
+            payload: optional_value_from_proto(m.payload.as_ref())?,
+        },
+        Kind::UiEmit(u) => Event::UiEmit {
+            tree: optional_value_from_proto(u.tree.as_ref())?,
+        },
+        Kind::SchedulerTick(t) => Event::SchedulerTick {
+            round: t.round,
+            active_actor: t.active_actor,
+        },
+        Kind::ContractCheck(c) => Event::ContractCheck {
+            function: c.function.clone(),
+            kind: c.kind.clone(),
+            index: usize::try_from(c.index)
+                .map_err(|_| format!("contract check index {} out of range", c.index))?,
+            passed: c.passed,
+        },
+        Kind::ActorCheckpoint(a) => Event::ActorCheckpoint {
+            actor_id: a.actor_id,
+            state_hash: a.state_hash.clone(),
+        },
+        Kind::ActorRestore(a) => Event::ActorRestore {
+            actor_id: a.actor_id,
+            state_hash: a.state_hash.clone(),
+        },
+        Kind::CapabilityDelegation(d) => Event::CapabilityDelegation {
+            parent: d.parent,
+            child: d.child,
+            policy_hash: d.policy_hash.clone(),
+        },
+    })
+}
+
+pub fn event_log_to_proto(log: &EventLog) -> proto::EventLog {
+    proto::EventLog {
+        version: log.version(),
+        events: log.events().iter().map(event_to_proto).collect(),
+        outcome: log.outcome().map(|o| proto::RunOutcome {
+            steps: o.steps,
+            result: o.result.as_ref().map(value_to_proto),
+            error: o.error.clone(),
+            denials: o
+                .denials
+                .iter()
+                .map(|d| proto::Denial {
+                    capability: d.capability.clone(),
+                    args: d.args.iter().map(value_to_proto).collect(),
+                    decision: d.decision.as_str().to_string(),
+                })
+                .collect(),
+            kv_snapshot: o.kv_snapshot.clone(),
+        }),
+    }
+}
+
+/// Rebuild an [`EventLog`]. Goes through [`EventLog::from_json`] so a
+/// log from a newer format version is refused the same way a file is.
+pub fn event_log_from_proto(log: &proto::EventLog) -> Result<EventLog, String> {
+    let events = log
+        .events
+        .iter()
+        .map(event_from_proto)
+        .collect::<Result<Vec<_>, _>>()?;
+    let outcome = match &log.outcome {
+        Some(o) => Some(RunOutcome {
+            steps: o.steps,
+            result: o.result.as_ref().map(value_from_proto).transpose()?,
+            error: o.error.clone(),
+            denials: o
+                .denials
+                .iter()
+                .map(|d| {
+                    let decision = serde_json::from_value(serde_json::json!(d.decision))
+                        .map_err(|_| format!("invalid denial decision '{}'", d.decision))?;
+                    Ok(Denial {
+                        capability: d.capability.clone(),
+                        args: values_from_proto(&d.args)?,
+                        decision,
+                    })
+                })
+                .collect::<Result<_, String>>()?,
+            kv_snapshot: o.kv_snapshot.clone(),
+        }),
+        None => None,
+    };
+    let version = if log.version == 0 {
+        EVENT_LOG_VERSION
+    } else {
+        log.version
+    };
+    let json = serde_json::json!({
+        "version": version,
+        "events": events,
+        "outcome": outcome,
+    });
+    EventLog::from_json(&json.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prost::Message;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn values_round_trip_through_the_wire() {
+        let value = Value::Map(BTreeMap::from([
+            (
+                "items".to_string(),
+                Value::List(vec![
+                    Value::Int(-3),
+                    Value::Float(1.5),
+                    Value::Decimal(Decimal::parse("12.50").unwrap()),
+                    Value::BigInt(BigInt::parse("-123456789012345678901234567890").unwrap()),
+                ]),
+            ),
+            (
+                "nested".to_string(),
+                Value::Some(Box::new(Value::Ok(Box::new(Value::Enum {
+                    type_id: 2,
+                    variant: 1,
+                    payload: Box::new(Value::Record {
+                        type_id: 1,
+                        fields: vec![Value::String("x".into()), Value::None, Value::Unit],
+                    }),
+                })))),
+            ),
+            ("handle".to_string(), Value::CapHandle(7)),
+        ]));
+        let bytes = value_to_proto(&value).encode_to_vec();
+        let decoded = proto::Value::decode(bytes.as_slice()).unwrap();
+        assert_eq!(value_from_proto(&decoded).unwrap(), value);
+
+        assert!(value_from_proto(&proto::Value::default()).is_err());
+        let bad = proto::Value {
+            kind: Some(value::Kind::Decimal("1.2.3".into())),
+        };
+        assert!(value_from_proto(&bad)
+            .unwrap_err()
+            .contains("invalid decimal"));
+    }
+
+    #[test]
+    fn policies_keep_their_sections_and_are_validated() {
+        let json = r#"{
+            "schema_version": 1,
+            "rules": {"net.fetch": {"allow": true, "budget": 3}},
+            "default_allow": false,
+            "net_policy": {"allowed_domains": ["api.example.com"]}
+        }"#;
+        let policy = boruna_vm::policy_validate::parse(json).unwrap();
+        let wire = policy_to_proto(&policy);
+        assert_eq!(wire.rules["net.fetch"].budget, 3);
+        assert!(wire.sections_json.contains("api.example.com"));
+
+        let back = policy_from_proto(&wire).unwrap();
+        assert_eq!(
+            serde_json::to_value(&back).unwrap(),
+            serde_json::to_value(&policy).unwrap()
+        );
+
+        let mut unknown = wire;
+        unknown
+            .rules
+            .insert("no.such_cap".into(), proto::PolicyRule::default());
+        assert!(policy_from_proto(&unknown).is_err());
+    }
+
+    #[test]
+    fn event_logs_round_trip_with_their_outcome() {
+        let mut log = EventLog::new();
+        let cap = boruna_bytecode::Capability::TimeNow;
+        log.log_cap_call(&cap, &[Value::Int(1)]);
+        log.log_cap_result(&cap, &Value::Int(1_700_000_000));
+        log.log_output_check("reply", true);
+        log.set_outcome(RunOutcome {
+            steps: 12,
+            result: Some(Value::String("done".into())),
+            error: None,
+            denials: Vec::new(),
+            kv_snapshot: None,
+        });
+
+        let wire = event_log_to_proto(&log);
+        let bytes = wire.encode_to_vec();
+        let back =
+            event_log_from_proto(&proto::EventLog::decode(bytes.as_slice()).unwrap()).unwrap();
+        assert_eq!(back.to_json().unwrap(), log.to_json().unwrap());
+
+        let future = proto::EventLog {
+            version: EVENT_LOG_VERSION + 1,
+            ..wire
+        };
+        assert!(event_log_from_proto(&future).is_err());
+    }
+}
@@ -0,0 +1,19 @@
+//! gRPC front end for Boruna: compile, run, replay and workflow-run for
+//! services that cannot shell out to the `boruna` CLI.
+//!
+//! The wire contract is `proto/boruna/v1/boruna.proto`; [`proto`] holds
+//! the matching Rust messages and [`convert`] maps them to the VM's
+//! types. `boruna-grpc` (the binary) serves [`BorunaService`], with TLS
+//! or mutual TLS when given certificates.
+
+pub mod convert;
+pub mod proto;
+mod service;
+
+pub use service::BorunaService;
+
+/// Generated server and client plumbing for the `boruna.v1.Boruna`
+/// service.
+pub mod rpc {
+    include!(concat!(env!("OUT_DIR"), "/boruna.v1.Boruna.rs"));
+}
@@ -0,0 +1,58 @@
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+use boruna_grpc::rpc::boruna_server::BorunaServer;
+use boruna_grpc::BorunaService;
+use clap::Parser;
+use tonic::transport::{Certificate, Identity, Server, ServerTlsConfig};
+
+#[derive(Parser)]
+#[command(name = "boruna-grpc", about = "Boruna gRPC server")]
+struct Args {
+    /// Address to listen on.
+    #[arg(long, default_value = "127.0.0.1:50051")]
+    listen: SocketAddr,
+
+    /// PEM server certificate chain. Serves TLS when set with `--tls-key`.
+    #[arg(long, requires = "tls_key")]
+    tls_cert: Option<PathBuf>,
+
+    /// PEM private key for `--tls-cert`.
+    #[arg(long, requires = "tls_cert")]
+    tls_key: Option<PathBuf>,
+
+    /// PEM CA bundle. Requires every client to present a certificate
+    /// signed by it (mutual TLS).
+    #[arg(long, requires = "tls_cert")]
+    tls_client_ca: Option<PathBuf>,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::parse();
+
+    let mut server = Server::builder();
+    if let (Some(cert), Some(key)) = (&args.tls_cert, &args.tls_key) {
+        let identity = Identity::from_pem(std::fs::read(cert)?, std::fs::read(key)?);
+        let mut tls = ServerTlsConfig::new().identity(identity);
+        if let Some(ca) = &args.tls_client_ca {
+            tls = tls.client_ca_root(Certificate::from_pem(std::fs::read(ca)?));
+        }
+        server = server.tls_config(tls)?;
+    }
+
+    eprintln!(
+        "boruna-grpc listening on {} ({})",
+        args.listen,
+        match (&args.tls_cert, &args.tls_client_ca) {
+            (None, _) => "plaintext",
+            (Some(_), None) => "tls",
+            (Some(_), Some(_)) => "mutual tls",
+        }
+    );
+    server
+        .add_service(BorunaServer::new(BorunaService))
+        .serve(args.listen)
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,678 @@
+//! Protobuf messages of `proto/boruna/v1/boruna.proto`.
+//!
+//! Written by hand rather than by `prost-build`, so building the crate
+//! needs no `protoc`. Field tags must match the `.proto` file, which is
+//! what Java and Go clients generate from; the tests at the bottom parse
+//! both and compare every field's tag, label, type and name.
+
+use std::collections::BTreeMap;
+
+// ── Values ──────────────────────────────────────────────────────────
+
+#[derive(Clone, Copy, PartialEq, prost::Message)]
+pub struct Unit {}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Value {
+    #[prost(
+        oneof = "value::Kind",
+        tags = "1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19"
+    )]
+    pub kind: Option<value::Kind>,
+}
+
+pub mod value {
+    #[derive(Clone, PartialEq, prost::Oneof)]
+    pub enum Kind {
+        #[prost(message, tag = "1")]
+        Unit(super::Unit),
+        #[prost(bool, tag = "2")]
+        Bool(bool),
+        #[prost(int64, tag = "3")]
+        Int(i64),
+        #[prost(double, tag = "4")]
+        Float(f64),
+        #[prost(string, tag = "5")]
+        Decimal(String),
+        #[prost(string, tag = "6")]
+        BigInt(String),
+        #[prost(string, tag = "7")]
+        String(String),
+        #[prost(message, tag = "8")]
+        None(super::Unit),
+        #[prost(message, tag = "9")]
+        Some(Box<super::Value>),
+        #[prost(message, tag = "10")]
+        Ok(Box<super::Value>),
+        #[prost(message, tag = "11")]
+        Err(Box<super::Value>),
+        #[prost(message, tag = "12")]
+        Record(super::Record),
+        #[prost(message, tag = "13")]
+        Enum(Box<super::EnumValue>),
+        #[prost(message, tag = "14")]
+        List(super::ValueList),
+        #[prost(message, tag = "15")]
+        Map(super::ValueMap),
+        #[prost(uint64, tag = "16")]
+        ActorId(u64),
+        #[prost(uint32, tag = "17")]
+        FnRef(u32),
+        #[prost(uint64, tag = "18")]
+        CapHandle(u64),
+        #[prost(string, tag = "19")]
+        TableJson(String),
+    }
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Record {
+    #[prost(uint32, tag = "1")]
+    pub type_id: u32,
+    #[prost(message, repeated, tag = "2")]
+    pub fields: Vec<Value>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct EnumValue {
+    #[prost(uint32, tag = "1")]
+    pub type_id: u32,
+    #[prost(uint32, tag = "2")]
+    pub variant: u32,
+    #[prost(message, optional, boxed, tag = "3")]
+    pub payload: Option<Box<Value>>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ValueList {
+    #[prost(message, repeated, tag = "1")]
+    pub items: Vec<Value>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ValueMap {
+    #[prost(btree_map = "string, message", tag = "1")]
+    pub entries: BTreeMap<String, Value>,
+}
+
+// ── Policy ──────────────────────────────────────────────────────────
+
+#[derive(Clone, Copy, PartialEq, prost::Message)]
+pub struct PolicyRule {
+    #[prost(bool, tag = "1")]
+    pub allow: bool,
+    #[prost(uint64, tag = "2")]
+    pub budget: u64,
+    #[prost(bool, tag = "3")]
+    pub prompt: bool,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Policy {
+    #[prost(uint32, tag = "1")]
+    pub schema_version: u32,
+    #[prost(btree_map = "string, message", tag = "2")]
+    pub rules: BTreeMap<String, PolicyRule>,
+    #[prost(bool, tag = "3")]
+    pub default_allow: bool,
+    #[prost(string, tag = "4")]
+    pub mode: String,
+    #[prost(string, tag = "5")]
+    pub sections_json: String,
+}
+
+// ── Event log ───────────────────────────────────────────────────────
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct CapCall {
+    #[prost(string, tag = "1")]
+    pub capability: String,
+    #[prost(message, repeated, tag = "2")]
+    pub args: Vec<Value>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct CapResult {
+    #[prost(string, tag = "1")]
+    pub capability: String,
+    #[prost(message, optional, tag = "2")]
+    pub result: Option<Value>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ActorSpawn {
+    #[prost(uint64, tag = "1")]
+    pub actor_id: u64,
+    #[prost(string, tag = "2")]
+    pub function: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MessageSend {
+    #[prost(uint64, tag = "1")]
+    pub from: u64,
+    #[prost(uint64, tag = "2")]
+    pub to: u64,
+    #[prost(message, optional, tag = "3")]
+    pub payload: Option<Value>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MessageReceive {
+    #[prost(uint64, tag = "1")]
+    pub actor_id: u64,
+    #[prost(message, optional, tag = "2")]
+    pub payload: Option<Value>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct UiEmit {
+    #[prost(message, optional, tag = "1")]
+    pub tree: Option<Value>,
+}
+
+#[derive(Clone, Copy, PartialEq, prost::Message)]
+pub struct SchedulerTick {
+    #[prost(uint64, tag = "1")]
+    pub round: u64,
+    #[prost(uint64, tag = "2")]
+    pub active_actor: u64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ContractCheck {
+    #[prost(string, tag = "1")]
+    pub function: String,
+    #[prost(string, tag = "2")]
+    pub kind: String,
+    #[prost(uint64, tag = "3")]
+    pub index: u64,
+    #[prost(bool, tag = "4")]
+    pub passed: bool,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ActorCheckpoint {
+    #[prost(uint64, tag = "1")]
+    pub actor_id: u64,
+    #[prost(string, tag = "2")]
+    pub state_hash: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct CapabilityDelegation {
+    #[prost(uint64, tag = "1")]
+    pub parent: u64,
+    #[prost(uint64, tag = "2")]
+    pub child: u64,
+    #[prost(string, tag = "3")]
+    pub policy_hash: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Event {
+    #[prost(oneof = "event::Kind", tags = "1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11")]
+    pub kind: Option<event::Kind>,
+}
+
+pub mod event {
+    #[derive(Clone, PartialEq, prost::Oneof)]
+    pub enum Kind {
+        #[prost(message, tag = "1")]
+        CapCall(super::CapCall),
+        #[prost(message, tag = "2")]
+        CapResult(super::CapResult),
+        #[prost(message, tag = "3")]
+        ActorSpawn(super::ActorSpawn),
+        #[prost(message, tag = "4")]
+        MessageSend(super::MessageSend),
+        #[prost(message, tag = "5")]
+        MessageReceive(super::MessageReceive),
+        #[prost(message, tag = "6")]
+        UiEmit(super::UiEmit),
+        #[prost(message, tag = "7")]
+        SchedulerTick(super::SchedulerTick),
+        #[prost(message, tag = "8")]
+        ContractCheck(super::ContractCheck),
+        #[prost(message, tag = "9")]
+        ActorCheckpoint(super::ActorCheckpoint),
+        #[prost(message, tag = "10")]
+        ActorRestore(super::ActorCheckpoint),
+        #[prost(message, tag = "11")]
+        CapabilityDelegation(super::CapabilityDelegation),
+    }
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Denial {
+    #[prost(string, tag = "1")]
+    pub capability: String,
+    #[prost(message, repeated, tag = "2")]
+    pub args: Vec<Value>,
+    #[prost(string, tag = "3")]
+    pub decision: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct RunOutcome {
+    #[prost(uint64, tag = "1")]
+    pub steps: u64,
+    #[prost(message, optional, tag = "2")]
+    pub result: Option<Value>,
+    #[prost(string, optional, tag = "3")]
+    pub error: Option<String>,
+    #[prost(message, repeated, tag = "4")]
+    pub denials: Vec<Denial>,
+    #[prost(string, optional, tag = "5")]
+    pub kv_snapshot: Option<String>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct EventLog {
+    #[prost(uint32, tag = "1")]
+    pub version: u32,
+    #[prost(message, repeated, tag = "2")]
+    pub events: Vec<Event>,
+    #[prost(message, optional, tag = "3")]
+    pub outcome: Option<RunOutcome>,
+}
+
+// ── Compile ─────────────────────────────────────────────────────────
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct CompileRequest {
+    #[prost(string, tag = "1")]
+    pub source: String,
+    #[prost(string, tag = "2")]
+    pub name: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Diagnostic {
+    #[prost(string, tag = "1")]
+    pub code: String,
+    #[prost(string, tag = "2")]
+    pub message: String,
+    #[prost(uint32, tag = "3")]
+    pub line: u32,
+    #[prost(uint32, tag = "4")]
+    pub col: u32,
+    #[prost(uint32, tag = "5")]
+    pub end_line: u32,
+    #[prost(uint32, tag = "6")]
+    pub end_col: u32,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct CompileResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(bytes = "vec", tag = "2")]
+    pub bytecode: Vec<u8>,
+    #[prost(string, tag = "3")]
+    pub module_name: String,
+    #[prost(uint32, tag = "4")]
+    pub functions: u32,
+    #[prost(message, repeated, tag = "5")]
+    pub errors: Vec<Diagnostic>,
+}
+
+// ── Run ─────────────────────────────────────────────────────────────
+
+/// The `program` oneof shared by [`RunRequest`] and [`ReplayRequest`].
+#[derive(Clone, PartialEq, prost::Oneof)]
+pub enum Program {
+    #[prost(string, tag = "1")]
+    Source(String),
+    #[prost(bytes, tag = "2")]
+    Bytecode(Vec<u8>),
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct RunRequest {
+    #[prost(oneof = "Program", tags = "1, 2")]
+    pub program: Option<Program>,
+    #[prost(message, optional, tag = "3")]
+    pub policy: Option<Policy>,
+    #[prost(uint64, tag = "4")]
+    pub max_steps: u64,
+    #[prost(uint64, tag = "5")]
+    pub max_wall_ms: u64,
+    #[prost(bool, tag = "6")]
+    pub record: bool,
+    #[prost(uint64, optional, tag = "7")]
+    pub scheduler_seed: Option<u64>,
+}
+
+#[derive(Clone, Copy, PartialEq, prost::Message)]
+pub struct Progress {
+    #[prost(uint64, tag = "1")]
+    pub steps: u64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct RunResult {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(message, optional, tag = "2")]
+    pub result: Option<Value>,
+    #[prost(uint64, tag = "3")]
+    pub steps: u64,
+    #[prost(string, tag = "4")]
+    pub error_kind: String,
+    #[prost(string, tag = "5")]
+    pub message: String,
+    #[prost(message, optional, tag = "6")]
+    pub event_log: Option<EventLog>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct RunEvent {
+    #[prost(oneof = "run_event::Kind", tags = "1, 2, 3")]
+    pub kind: Option<run_event::Kind>,
+}
+
+pub mod run_event {
+    #[derive(Clone, PartialEq, prost::Oneof)]
+    pub enum Kind {
+        #[prost(message, tag = "1")]
+        Progress(super::Progress),
+        #[prost(message, tag = "2")]
+        Event(super::Event),
+        #[prost(message, tag = "3")]
+        Result(super::RunResult),
+    }
+}
+
+// ── Replay ──────────────────────────────────────────────────────────
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ReplayRequest {
+    #[prost(oneof = "Program", tags = "1, 2")]
+    pub program: Option<Program>,
+    #[prost(message, optional, tag = "3")]
+    pub event_log: Option<EventLog>,
+    #[prost(bool, tag = "4")]
+    pub full: bool,
+    #[prost(uint64, optional, tag = "5")]
+    pub scheduler_seed: Option<u64>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ReplayResponse {
+    #[prost(bool, tag = "1")]
+    pub identical: bool,
+    #[prost(string, tag = "2")]
+    pub divergence: String,
+    #[prost(message, optional, tag = "3")]
+    pub result: Option<Value>,
+    #[prost(string, tag = "4")]
+    pub error: String,
+    #[prost(uint64, tag = "5")]
+    pub steps: u64,
+}
+
+// ── Workflow run ────────────────────────────────────────────────────
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct WorkflowRunRequest {
+    #[prost(string, tag = "1")]
+    pub workflow_json: String,
+    #[prost(btree_map = "string, string", tag = "2")]
+    pub sources: BTreeMap<String, String>,
+    #[prost(message, optional, tag = "3")]
+    pub policy: Option<Policy>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct StepResult {
+    #[prost(string, tag = "1")]
+    pub step_id: String,
+    #[prost(string, tag = "2")]
+    pub status: String,
+    #[prost(string, optional, tag = "3")]
+    pub output_hash: Option<String>,
+    #[prost(uint64, tag = "4")]
+    pub duration_ms: u64,
+    #[prost(string, repeated, tag = "5")]
+    pub capabilities_used: Vec<String>,
+    #[prost(string, optional, tag = "6")]
+    pub error: Option<String>,
+    #[prost(uint32, tag = "7")]
+    pub attempt_count: u32,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct WorkflowResult {
+    #[prost(string, tag = "1")]
+    pub run_id: String,
+    #[prost(string, tag = "2")]
+    pub workflow_name: String,
+    #[prost(string, tag = "3")]
+    pub status: String,
+    #[prost(uint64, tag = "4")]
+    pub total_duration_ms: u64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct WorkflowEvent {
+    #[prost(oneof = "workflow_event::Kind", tags = "1, 2")]
+    pub kind: Option<workflow_event::Kind>,
+}
+
+pub mod workflow_event {
+    #[derive(Clone, PartialEq, prost::Oneof)]
+    pub enum Kind {
+        #[prost(message, tag = "1")]
+        Step(super::StepResult),
+        #[prost(message, tag = "2")]
+        Result(super::WorkflowResult),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! The messages above are compared with the `.proto` file field by
+    //! field: both are parsed into `message -> tag -> "label type name"`,
+    //! oneof members labelled with their oneof.
+
+    use std::collections::BTreeMap;
+
+    type Schema = BTreeMap<String, BTreeMap<u32, String>>;
+
+    const PROTO: &str = include_str!("../proto/boruna/v1/boruna.proto");
+    const RUST: &str = include_str!("proto.rs");
+
+    fn parse_proto(src: &str) -> Schema {
+        let mut schema = Schema::new();
+        let mut message = String::new();
+        let mut oneof: Option<String> = None;
+        for line in src.lines() {
+            let line = line.split("//").next().unwrap().trim();
+            if let Some(rest) = line.strip_prefix("message ") {
+                message = rest.trim_end_matches(['{', '}', ' ']).to_string();
+                schema.entry(message.clone()).or_default();
+            } else if let Some(rest) = line.strip_prefix("oneof ") {
+                oneof = Some(rest.trim_end_matches(['{', ' ']).to_string());
+            } else if line == "}" {
+                if oneof.take().is_none() {
+                    message.clear();
+                }
+            } else if let Some(field) = line.strip_suffix(';').filter(|_| !message.is_empty()) {
+                let (decl, tag) = field.split_once('=').unwrap();
+                let decl = decl.replace(", ", ",");
+                let words: Vec<&str> = decl.split_whitespace().collect();
+                let (label, ty, name) = match words[..] {
+                    [ty, name] => (String::new(), ty, name),
+                    [label, ty, name] => (format!("{label} "), ty, name),
+                    _ => panic!("unparsed field: {line}"),
+                };
+                let label = oneof.as_ref().map_or(label, |o| format!("oneof({o}) "));
+                schema
+                    .get_mut(&message)
+                    .unwrap()
+                    .insert(tag.trim().parse().unwrap(), format!("{label}{ty} {name}"));
+            }
+        }
+        schema
+    }
+
+    /// Split a `#[prost(...)]` argument list on the commas outside quotes.
+    fn attr_args(attr: &str) -> Vec<String> {
+        let mut args = vec![String::new()];
+        let mut quoted = false;
+        for c in attr.chars() {
+            match c {
+                '"' => quoted = !quoted,
+                ',' if !quoted => {
+                    args.push(String::new());
+                    continue;
+                }
+                _ => {}
+            }
+            args.last_mut().unwrap().push(c);
+        }
+        args.iter().map(|a| a.trim().to_string()).collect()
+    }
+
+    fn quoted(arg: &str) -> &str {
+        arg.split('"').nth(1).unwrap()
+    }
+
+    /// The message a Rust field type holds: `Option<Box<super::Value>>`
+    /// is `Value`.
+    fn message_type(ty: &str) -> &str {
+        ty.split(['<', '>', ',', ':', ' '])
+            .rfind(|w| !["", "Option", "Box", "Vec", "BTreeMap", "String", "super"].contains(w))
+            .unwrap()
+    }
+
+    fn snake_case(name: &str) -> String {
+        let mut out = String::new();
+        for (i, c) in name.chars().enumerate() {
+            if c.is_ascii_uppercase() && i > 0 {
+                out.push('_');
+            }
+            out.push(c.to_ascii_lowercase());
+        }
+        out
+    }
+
+    fn parse_rust(src: &str) -> Schema {
+        let src = src.split("#[cfg(test)]").next().unwrap();
+        let mut schema = Schema::new();
+        // Oneof enums by path (`value::Kind`, `Program`), and the messages
+        // that embed them: (message, oneof field, path, tags).
+        let mut oneofs = Schema::new();
+        let mut uses = Vec::new();
+        let mut module = String::new();
+        let mut current = String::new();
+        let mut is_enum = false;
+        let mut attr: Option<String> = None;
+        let mut pending: Option<String> = None;
+        for raw in src.lines() {
+            let line = raw.trim();
+            if let Some(open) = attr.as_mut() {
+                open.push_str(line);
+                if line.ends_with(")]") {
+                    pending = attr.take();
+                }
+                continue;
+            }
+            if let Some(rest) = line.strip_prefix("#[prost(") {
+                if line.ends_with(")]") {
+                    pending = Some(rest.to_string());
+                } else {
+                    attr = Some(rest.to_string());
+                }
+            } else if let Some(rest) = raw.strip_prefix("pub mod ") {
+                module = format!("{}::", rest.trim_end_matches(" {"));
+            } else if raw == "}" {
+                module.clear();
+            } else if let Some(rest) = line.strip_prefix("pub struct ") {
+                current = rest
+                    .trim_end_matches(" {}")
+                    .trim_end_matches(" {")
+                    .to_string();
+                is_enum = false;
+                schema.entry(current.clone()).or_default();
+            } else if let Some(rest) = line.strip_prefix("pub enum ") {
+                current = format!("{module}{}", rest.trim_end_matches(" {"));
+                is_enum = true;
+            } else if let Some(attr) = pending.take() {
+                let args = attr_args(attr.trim_end_matches(")]"));
+                let (name, ty) = if is_enum {
+                    let (variant, ty) = line.split_once('(').unwrap();
+                    (snake_case(variant), ty.trim_end_matches("),"))
+                } else {
+                    let (name, ty) = line.strip_prefix("pub ").unwrap().split_once(": ").unwrap();
+                    (name.to_string(), ty.trim_end_matches(','))
+                };
+                if let Some(path) = args[0].strip_prefix("oneof = ") {
+                    let tags = quoted(&args[1])
+                        .split(", ")
+                        .map(|t| t.parse::<u32>().unwrap());
+                    uses.push((
+                        current.clone(),
+                        name,
+                        quoted(path).to_string(),
+                        tags.collect::<Vec<_>>(),
+                    ));
+                    continue;
+                }
+                let kind = match args[0].split_once(" = ") {
+                    Some(("btree_map", kv)) => {
+                        let (k, v) = quoted(kv).split_once(", ").unwrap();
+                        let v = if v == "message" { message_type(ty) } else { v };
+                        format!("map<{k},{v}>")
+                    }
+                    Some((kind, _)) => kind.to_string(),
+                    None if args[0] == "message" => message_type(ty).to_string(),
+                    None => args[0].clone(),
+                };
+                let label = if args.iter().any(|a| a == "repeated") {
+                    "repeated "
+                } else if args[0] != "message" && args.iter().any(|a| a == "optional") {
+                    "optional "
+                } else {
+                    ""
+                };
+                let tag = quoted(args.iter().find(|a| a.starts_with("tag = ")).unwrap());
+                let table = if is_enum {
+                    oneofs.entry(current.clone()).or_default()
+                } else {
+                    schema.get_mut(&current).unwrap()
+                };
+                table.insert(tag.parse().unwrap(), format!("{label}{kind} {name}"));
+            }
+        }
+        for (message, field, path, tags) in uses {
+            let members = &oneofs[&path];
+            assert_eq!(
+                members.keys().copied().collect::<Vec<u32>>(),
+                tags,
+                "{message}.{field}: `tags` differ from {path}'s variants"
+            );
+            for (tag, member) in members {
+                let entry = schema.get_mut(&message).unwrap();
+                entry.insert(*tag, format!("oneof({field}) {member}"));
+            }
+        }
+        schema
+    }
+
+    #[test]
+    fn test_messages_match_the_proto_file() {
+        let proto = parse_proto(PROTO);
+        let rust = parse_rust(RUST);
+        assert_eq!(proto["Value"].len(), 19);
+        assert_eq!(proto["RunRequest"][&7], "optional uint64 scheduler_seed");
+        assert_eq!(
+            proto.keys().collect::<Vec<_>>(),
+            rust.keys().collect::<Vec<_>>()
+        );
+        for (message, fields) in &proto {
+            assert_eq!(&rust[message], fields, "message {message}");
+        }
+    }
+}
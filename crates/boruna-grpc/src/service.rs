@@ -0,0 +1,700 @@
+//! The `Boruna` service: compile, run, replay and workflow-run over gRPC.
+//!
+//! Each call runs on a `spawn_blocking` worker. The streaming calls feed
+//! a bounded channel, so a slow client applies back-pressure to the VM,
+//! and a client that hangs up stops its run at the next slice.
+
+// `tonic::Status` is large, but it is what every handler returns.
+#![allow(clippy::result_large_err)]
+
+use std::collections::BTreeMap;
+use std::path::{Component, Path};
+
+use boruna_bytecode::Module;
+use boruna_compiler::CompileError;
+use boruna_orchestrator::workflow::definition::{StepKind, WorkflowDef};
+use boruna_orchestrator::workflow::validator::WorkflowValidator;
+use boruna_orchestrator::workflow::{RunOptions, WorkflowRunError, WorkflowRunner};
+use boruna_vm::capability_gateway::{CapabilityGateway, Policy, ReplayHandler};
+use boruna_vm::error::VmError;
+use boruna_vm::replay::{ReplayEngine, ReplayResult, RunOutcome};
+use boruna_vm::vm::{StepResult, Vm};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{Request, Response, Status};
+
+use crate::convert::{
+    event_log_from_proto, event_log_to_proto, event_to_proto, policy_from_proto, value_to_proto,
+};
+use crate::proto::{self, run_event, workflow_event, Program};
+use crate::rpc::boruna_server::Boruna;
+
+/// VM steps between two looks at the event log during `Run`. Small
+/// enough that capability events reach the client promptly, large
+/// enough that the check costs nothing measurable.
+const STEP_SLICE: u64 = 10_000;
+
+/// Messages buffered per streaming call before the producer waits.
+const STREAM_BUFFER: usize = 64;
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct BorunaService;
+
+#[tonic::async_trait]
+impl Boruna for BorunaService {
+    async fn compile(
+        &self,
+        request: Request<proto::CompileRequest>,
+    ) -> Result<Response<proto::CompileResponse>, Status> {
+        let req = request.into_inner();
+        let response = blocking(move || Ok(compile(&req))).await?;
+        Ok(Response::new(response))
+    }
+
+    type RunStream = ReceiverStream<Result<proto::RunEvent, Status>>;
+
+    async fn run(
+        &self,
+        request: Request<proto::RunRequest>,
+    ) -> Result<Response<Self::RunStream>, Status> {
+        let req = request.into_inner();
+        if req.record && req.max_wall_ms > 0 {
+            // A wall-clock verdict differs across hosts, so it must not
+            // end up in a log that replay verifies.
+            return Err(Status::invalid_argument(
+                "max_wall_ms cannot be combined with record",
+            ));
+        }
+        let policy = policy_of(req.policy.as_ref())?;
+        let module = blocking({
+            let program = req.program.clone();
+            move || load_program(program)
+        })
+        .await?;
+        let (tx, rx) = mpsc::channel(STREAM_BUFFER);
+        tokio::task::spawn_blocking(move || run_streaming(module, policy, &req, &tx));
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+
+    async fn replay(
+        &self,
+        request: Request<proto::ReplayRequest>,
+    ) -> Result<Response<proto::ReplayResponse>, Status> {
+        let req = request.into_inner();
+        let response = blocking(move || replay(req)).await?;
+        Ok(Response::new(response))
+    }
+
+    type WorkflowRunStream = ReceiverStream<Result<proto::WorkflowEvent, Status>>;
+
+    async fn workflow_run(
+        &self,
+        request: Request<proto::WorkflowRunRequest>,
+    ) -> Result<Response<Self::WorkflowRunStream>, Status> {
+        let req = request.into_inner();
+        let def: WorkflowDef = serde_json::from_str(&req.workflow_json)
+            .map_err(|e| Status::invalid_argument(format!("invalid workflow JSON: {e}")))?;
+        let policy = policy_of(req.policy.as_ref())?;
+        let (tx, rx) = mpsc::channel(STREAM_BUFFER);
+        tokio::task::spawn_blocking(move || {
+            let events = run_workflow(&def, &req.sources, policy);
+            for event in events {
+                if tx.blocking_send(event).is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+}
+
+async fn blocking<T, F>(f: F) -> Result<T, Status>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, Status> + Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| Status::internal(format!("worker failed: {e}")))?
+}
+
+/// An absent policy runs allow-all, as `boruna_run` does.
+fn policy_of(policy: Option<&proto::Policy>) -> Result<Policy, Status> {
+    match policy {
+        None => Ok(Policy::allow_all()),
+        Some(p) => policy_from_proto(p).map_err(Status::invalid_argument),
+    }
+}
+
+fn load_program(program: Option<Program>) -> Result<Module, Status> {
+    match program {
+        Some(Program::Source(source)) => boruna_compiler::compile("module", &source)
+            .map_err(|e| Status::invalid_argument(e.to_string())),
+        Some(Program::Bytecode(bytes)) => Module::from_bytes(&bytes)
+            .map_err(|e| Status::invalid_argument(format!("invalid bytecode: {e}"))),
+        None => Err(Status::invalid_argument("program is required")),
+    }
+}
+
+fn compile(req: &proto::CompileRequest) -> proto::CompileResponse {
+    let name = if req.name.is_empty() {
+        "module"
+    } else {
+        &req.name
+    };
+    let module = match boruna_compiler::compile(name, &req.source) {
+        Ok(m) => m,
+        Err(e) => {
+            return proto::CompileResponse {
+                errors: vec![diagnostic(&e)],
+                ..Default::default()
+            }
+        }
+    };
+    match module.to_bytes() {
+        Ok(bytecode) => proto::CompileResponse {
+            success: true,
+            bytecode,
+            module_name: module.name.clone(),
+            functions: module.functions.len() as u32,
+            errors: Vec::new(),
+        },
+        Err(e) => proto::CompileResponse {
+            errors: vec![proto::Diagnostic {
+                code: "E008".into(),
+                message: format!("cannot encode module: {e}"),
+                ..Default::default()
+            }],
+            ..Default::default()
+        },
+    }
+}
+
+/// Same codes as the MCP server's `boruna_compile`.
+fn diagnostic(err: &CompileError) -> proto::Diagnostic {
+    let code = match err {
+        CompileError::Lexer { .. } => "E001",
+        CompileError::Parse { .. } => "E002",
+        CompileError::Type { .. } => "E009",
+        CompileError::Codegen { .. } => "E008",
+        CompileError::Budget(_) => "E015",
+    };
+    let span = err.span().unwrap_or_default();
+    proto::Diagnostic {
+        code: code.into(),
+        message: err.message().to_string(),
+        line: span.line as u32,
+        col: span.col as u32,
+        end_line: span.end_line as u32,
+        end_col: span.end_col as u32,
+    }
+}
+
+fn run_streaming(
+    module: Module,
+    policy: Policy,
+    req: &proto::RunRequest,
+    tx: &mpsc::Sender<Result<proto::RunEvent, Status>>,
+) {
+    let send = |kind| {
+        tx.blocking_send(Ok(proto::RunEvent { kind: Some(kind) }))
+            .is_ok()
+    };
+    let mut vm = Vm::new(module, CapabilityGateway::new(policy));
+    if req.max_steps > 0 {
+        vm.set_max_steps(req.max_steps);
+    }
+    vm.set_max_wall_ms(Some(req.max_wall_ms).filter(|ms| *ms > 0));
+    vm.set_scheduler_seed(req.scheduler_seed);
+
+    // Forward the events logged since the last slice; false once the
+    // client has gone away.
+    let mut sent = 0;
+    let mut forward = |vm: &Vm| {
+        let events = &vm.event_log().events()[sent..];
+        sent += events.len();
+        events
+            .iter()
+            .all(|e| send(run_event::Kind::Event(event_to_proto(e))))
+    };
+
+    vm.start_timer();
+    let entry = vm.module().entry;
+    let run = match vm.set_entry_function(entry) {
+        Err(e) => Err(e),
+        Ok(()) => loop {
+            match vm.execute_bounded(STEP_SLICE) {
+                StepResult::Completed(value) => break Ok(value),
+                StepResult::Yielded { .. } => {
+                    let progress = proto::Progress {
+                        steps: vm.step_count(),
+                    };
+                    if !forward(&vm) || !send(run_event::Kind::Progress(progress)) {
+                        return;
+                    }
+                }
+                // Standalone runs never block on `receive`; see the MCP
+                // server's `drive_vm`.
+                StepResult::Blocked => break Err(VmError::Deadlock),
+                StepResult::Error(e) => break Err(e),
+            }
+        },
+    };
+    if !forward(&vm) {
+        return;
+    }
+
+    let event_log = req.record.then(|| {
+        let mut log = vm.event_log().clone();
+        log.set_outcome(RunOutcome {
+            steps: vm.step_count(),
+            result: run.as_ref().ok().cloned(),
+            error: run.as_ref().err().map(|e| e.to_string()),
+            denials: vm.gateway().denials().to_vec(),
+            kv_snapshot: None,
+        });
+        event_log_to_proto(&log)
+    });
+    let result = match &run {
+        Ok(value) => proto::RunResult {
+            success: true,
+            result: Some(value_to_proto(value)),
+            steps: vm.step_count(),
+            event_log,
+            ..Default::default()
+        },
+        Err(e) => proto::RunResult {
+            success: false,
+            steps: vm.step_count(),
+            error_kind: match e {
+                VmError::WallTimeExceeded(_) | VmError::ExecutionLimitExceeded(_) => {
+                    "limit_exceeded"
+                }
+                _ => "runtime_error",
+            }
+            .into(),
+            message: e.to_string(),
+            event_log,
+            ..Default::default()
+        },
+    };
+    send(run_event::Kind::Result(result));
+}
+
+fn replay(req: proto::ReplayRequest) -> Result<proto::ReplayResponse, Status> {
+    let Some(log) = &req.event_log else {
+        return Err(Status::invalid_argument("event_log is required"));
+    };
+    let original = event_log_from_proto(log).map_err(Status::invalid_argument)?;
+    let module = load_program(req.program)?;
+
+    let handler = Box::new(ReplayHandler::new(original.capability_results()));
+    let gateway = CapabilityGateway::with_handler(Policy::allow_all(), handler);
+    let mut vm = Vm::new(module, gateway);
+    vm.set_scheduler_seed(req.scheduler_seed);
+    let run = vm.run();
+
+    let verdict = if req.full {
+        ReplayEngine::verify_full(&original, vm.event_log())
+    } else {
+        ReplayEngine::verify(&original, vm.event_log())
+    };
+    let divergence = match verdict {
+        ReplayResult::Identical => String::new(),
+        ReplayResult::Diverged { reason } => reason,
+    };
+    Ok(proto::ReplayResponse {
+        identical: divergence.is_empty(),
+        divergence,
+        result: run.as_ref().ok().map(value_to_proto),
+        error: run.err().map(|e| e.to_string()).unwrap_or_default(),
+        steps: vm.step_count(),
+    })
+}
+
+/// Run a workflow with inline sources. The ephemeral runner reports
+/// every step when the run ends, so the step events follow in
+/// topological order, then the summary.
+fn run_workflow(
+    def: &WorkflowDef,
+    sources: &BTreeMap<String, String>,
+    policy: Policy,
+) -> Vec<Result<proto::WorkflowEvent, Status>> {
+    let work = match tempfile::tempdir() {
+        Ok(d) => d,
+        Err(e) => {
+            return vec![Err(Status::internal(format!(
+                "cannot create workflow dir: {e}"
+            )))]
+        }
+    };
+    if let Err(status) = write_sources(def, sources, work.path()) {
+        return vec![Err(status)];
+    }
+    let options = RunOptions {
+        policy: Some(policy),
+        workflow_dir: work.path().display().to_string(),
+        ..RunOptions::default()
+    };
+    let result = match WorkflowRunner::run(def, &options) {
+        Ok(r) => r,
+        Err(WorkflowRunError::Validation(message)) => {
+            return vec![Err(Status::invalid_argument(message))]
+        }
+        Err(e) => return vec![Err(Status::internal(e.to_string()))],
+    };
+
+    let order = WorkflowValidator::topological_order(def).unwrap_or_default();
+    let step = |kind| Ok(proto::WorkflowEvent { kind: Some(kind) });
+    let mut events: Vec<_> = order
+        .iter()
+        .filter_map(|id| result.step_results.get(id))
+        .map(|sr| {
+            step(workflow_event::Kind::Step(proto::StepResult {
+                step_id: sr.step_id.clone(),
+                status: snake_case(&sr.status),
+                output_hash: sr.output_hash.clone(),
+                duration_ms: sr.duration_ms,
+                capabilities_used: sr.capabilities_used.clone(),
+                error: sr.error.clone(),
+                attempt_count: sr.attempt_count,
+            }))
+        })
+        .collect();
+    events.push(step(workflow_event::Kind::Result(proto::WorkflowResult {
+        run_id: result.run_id.clone(),
+        workflow_name: result.workflow_name.clone(),
+        status: snake_case(&result.status),
+        total_duration_ms: result.total_duration_ms,
+    })));
+    events
+}
+
+/// The serde name of a status enum, e.g. `awaiting_approval`.
+fn snake_case<T: serde::Serialize>(status: &T) -> String {
+    match serde_json::to_value(status) {
+        Ok(serde_json::Value::String(s)) => s,
+        _ => String::new(),
+    }
+}
+
+/// Write each source step's body to its declared path under `dir`.
+fn write_sources(
+    def: &WorkflowDef,
+    sources: &BTreeMap<String, String>,
+    dir: &Path,
+) -> Result<(), Status> {
+    if let Some(id) = sources.keys().find(|id| !def.steps.contains_key(*id)) {
+        return Err(Status::invalid_argument(format!(
+            "source given for unknown step '{id}'"
+        )));
+    }
+    for (id, step) in &def.steps {
+        let StepKind::Source { source: rel } = &step.kind else {
+            continue;
+        };
+        let Some(body) = sources.get(id) else {
+            return Err(Status::invalid_argument(format!(
+                "missing inline source for source step '{id}'"
+            )));
+        };
+        let rel = Path::new(rel);
+        if !rel
+            .components()
+            .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+        {
+            return Err(Status::invalid_argument(format!(
+                "step '{id}' source path '{}' must stay inside the workflow directory",
+                rel.display()
+            )));
+        }
+        let path = dir.join(rel);
+        path.parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|()| std::fs::write(&path, body))
+            .map_err(|e| Status::internal(format!("cannot write source for step '{id}': {e}")))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::convert::value_from_proto;
+    use crate::rpc::boruna_client::BorunaClient;
+    use crate::rpc::boruna_server::BorunaServer;
+    use boruna_bytecode::Value;
+    use tokio_stream::wrappers::TcpListenerStream;
+    use tonic::transport::{Channel, Server};
+
+    const KV_SOURCE: &str =
+        "fn main() -> Int !{kv.get} {\n    let a = kv_get(\"a\")\n    41 + 1\n}\n";
+
+    async fn client() -> BorunaClient<Channel> {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(
+            Server::builder()
+                .add_service(BorunaServer::new(BorunaService))
+                .serve_with_incoming(TcpListenerStream::new(listener)),
+        );
+        BorunaClient::connect(format!("http://{addr}"))
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn serves_mutual_tls() {
+        use tonic::transport::{Certificate, ClientTlsConfig, Identity, ServerTlsConfig};
+
+        let ca_key = rcgen::KeyPair::generate().unwrap();
+        let mut ca_params = rcgen::CertificateParams::new(Vec::new()).unwrap();
+        ca_params.is_ca = rcgen::IsCa::Ca(rcgen::BasicConstraints::Unconstrained);
+        let ca = ca_params.self_signed(&ca_key).unwrap();
+        let issue = |name: &str| {
+            let key = rcgen::KeyPair::generate().unwrap();
+            let params = rcgen::CertificateParams::new(vec![name.to_string()]).unwrap();
+            let cert = params.signed_by(&key, &ca, &ca_key).unwrap();
+            Identity::from_pem(cert.pem(), key.serialize_pem())
+        };
+        let ca_pem = Certificate::from_pem(ca.pem());
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let tls = ServerTlsConfig::new()
+            .identity(issue("localhost"))
+            .client_ca_root(ca_pem.clone());
+        tokio::spawn(
+            Server::builder()
+                .tls_config(tls)
+                .unwrap()
+                .add_service(BorunaServer::new(BorunaService))
+                .serve_with_incoming(TcpListenerStream::new(listener)),
+        );
+
+        let endpoint = format!("https://localhost:{port}");
+        let connect = |tls: ClientTlsConfig| {
+            let endpoint = endpoint.clone();
+            async move {
+                let channel = Channel::from_shared(endpoint)
+                    .unwrap()
+                    .tls_config(tls)
+                    .unwrap()
+                    .connect()
+                    .await?;
+                let mut client = BorunaClient::new(channel);
+                let request = proto::CompileRequest {
+                    source: "fn main() -> Int { 1 }\n".into(),
+                    name: String::new(),
+                };
+                Ok::<_, Box<dyn std::error::Error>>(client.compile(request).await?.into_inner())
+            }
+        };
+
+        let trusted = ClientTlsConfig::new()
+            .ca_certificate(ca_pem.clone())
+            .domain_name("localhost");
+        assert!(
+            connect(trusted.clone().identity(issue("client")))
+                .await
+                .unwrap()
+                .success
+        );
+        // Without a client certificate the handshake is refused.
+        assert!(connect(trusted).await.is_err());
+    }
+
+    async fn run_to_end(
+        client: &mut BorunaClient<Channel>,
+        req: proto::RunRequest,
+    ) -> (Vec<proto::Event>, proto::RunResult) {
+        let mut stream = client.run(req).await.unwrap().into_inner();
+        let mut events = Vec::new();
+        while let Some(message) = stream.message().await.unwrap() {
+            match message.kind.unwrap() {
+                run_event::Kind::Event(e) => events.push(e),
+                run_event::Kind::Progress(_) => {}
+                run_event::Kind::Result(r) => {
+                    assert!(stream.message().await.unwrap().is_none());
+                    return (events, r);
+                }
+            }
+        }
+        panic!("stream ended without a result");
+    }
+
+    #[tokio::test]
+    async fn compiled_bytecode_runs_streams_events_and_replays() {
+        let mut client = client().await;
+        let compiled = client
+            .compile(proto::CompileRequest {
+                source: KV_SOURCE.into(),
+                name: "kv".into(),
+            })
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(compiled.success, "{:?}", compiled.errors);
+        assert_eq!(compiled.module_name, "kv");
+
+        let (events, result) = run_to_end(
+            &mut client,
+            proto::RunRequest {
+                program: Some(Program::Bytecode(compiled.bytecode.clone())),
+                record: true,
+                ..Default::default()
+            },
+        )
+        .await;
+        assert!(result.success, "{}", result.message);
+        assert_eq!(
+            value_from_proto(result.result.as_ref().unwrap()).unwrap(),
+            Value::Int(42)
+        );
+        assert!(matches!(
+            &events[0].kind,
+            Some(proto::event::Kind::CapCall(c)) if c.capability == "kv.get"
+        ));
+        let log = result.event_log.unwrap();
+        assert_eq!(log.events, events);
+        assert_eq!(log.outcome.as_ref().unwrap().steps, result.steps);
+
+        let replayed = client
+            .replay(proto::ReplayRequest {
+                program: Some(Program::Source(KV_SOURCE.into())),
+                event_log: Some(log),
+                full: true,
+                ..Default::default()
+            })
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(replayed.identical, "{}", replayed.divergence);
+        assert_eq!(replayed.steps, result.steps);
+    }
+
+    #[tokio::test]
+    async fn failures_are_results_and_bad_requests_are_statuses() {
+        let mut client = client().await;
+        let compiled = client
+            .compile(proto::CompileRequest {
+                source: "fn main( {".into(),
+                name: String::new(),
+            })
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(!compiled.success);
+        assert_eq!(compiled.errors[0].code, "E002");
+
+        let deny_all = proto::Policy {
+            default_allow: false,
+            ..Default::default()
+        };
+        let (_, result) = run_to_end(
+            &mut client,
+            proto::RunRequest {
+                program: Some(Program::Source(KV_SOURCE.into())),
+                policy: Some(deny_all),
+                ..Default::default()
+            },
+        )
+        .await;
+        assert!(!result.success);
+        assert_eq!(result.error_kind, "runtime_error");
+        assert!(result.message.contains("denied"), "{}", result.message);
+
+        let bad_policy = proto::Policy {
+            sections_json: "[1]".into(),
+            ..Default::default()
+        };
+        let status = client
+            .run(proto::RunRequest {
+                program: Some(Program::Source(KV_SOURCE.into())),
+                policy: Some(bad_policy),
+                ..Default::default()
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+
+        let status = client
+            .run(proto::RunRequest {
+                program: Some(Program::Source(KV_SOURCE.into())),
+                record: true,
+                max_wall_ms: 100,
+                ..Default::default()
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn workflow_run_streams_steps_then_the_summary() {
+        let workflow = r#"{
+          "schema_version": 1,
+          "name": "pipeline",
+          "version": "1.0.0",
+          "steps": {
+            "fetch": { "kind": "source", "source": "steps/fetch.ax", "outputs": { "result": "Int" } },
+            "sum": {
+              "kind": "source",
+              "source": "steps/sum.ax",
+              "depends_on": ["fetch"],
+              "inputs": { "n": "fetch.result" },
+              "outputs": { "result": "Int" }
+            }
+          },
+          "edges": [["fetch", "sum"]]
+        }"#;
+        let sources = BTreeMap::from([
+            ("fetch".to_string(), "fn main() -> Int { 20 }\n".to_string()),
+            (
+                "sum".to_string(),
+                "fn main() -> Int { 1 + 2 }\n".to_string(),
+            ),
+        ]);
+        let mut client = client().await;
+        let mut stream = client
+            .workflow_run(proto::WorkflowRunRequest {
+                workflow_json: workflow.into(),
+                sources: sources.clone(),
+                policy: None,
+            })
+            .await
+            .unwrap()
+            .into_inner();
+        let mut kinds = Vec::new();
+        while let Some(message) = stream.message().await.unwrap() {
+            kinds.push(message.kind.unwrap());
+        }
+        let steps: Vec<_> = kinds
+            .iter()
+            .filter_map(|k| match k {
+                workflow_event::Kind::Step(s) => Some((s.step_id.as_str(), s.status.as_str())),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(steps, [("fetch", "completed"), ("sum", "completed")]);
+        let Some(workflow_event::Kind::Result(summary)) = kinds.last() else {
+            panic!("no summary: {kinds:?}");
+        };
+        assert_eq!(summary.status, "completed");
+        assert_eq!(summary.workflow_name, "pipeline");
+
+        let mut unknown = sources;
+        unknown.insert("ghost".into(), String::new());
+        let mut stream = client
+            .workflow_run(proto::WorkflowRunRequest {
+                workflow_json: workflow.into(),
+                sources: unknown,
+                policy: None,
+            })
+            .await
+            .unwrap()
+            .into_inner();
+        let status = stream.message().await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    }
+}
@@ -0,0 +1,350 @@
+//! Digest algorithms and the algorithm-tagged digest format.
+//!
+//! Every hash Boruna computes goes through this crate. A digest is
+//! written `<algorithm>:<lowercase hex>` — `sha256:9f86…` or
+//! `blake3:4878…`. Formats that predate the tag (evidence manifests,
+//! audit chains, trace fingerprints) store bare SHA-256 hex, and keep
+//! doing so: those hashes are replay-verified, so their bytes must not
+//! change. [`Digest::parse`] reads a bare 64-char hex string as SHA-256,
+//! which keeps every hash already on disk verifiable.
+//!
+//! SHA-256 is the default. BLAKE3 is offered where large inputs are
+//! hashed (evidence bundle files, package sources); it is several times
+//! faster per byte and verifiers pick the algorithm from the tag.
+
+use std::fmt;
+use std::io::Read;
+use std::path::Path;
+use std::str::FromStr;
+
+use sha2::Digest as _;
+
+/// A supported digest algorithm.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum HashAlgorithm {
+    #[default]
+    Sha256,
+    Blake3,
+}
+
+impl HashAlgorithm {
+    pub const ALL: [HashAlgorithm; 2] = [HashAlgorithm::Sha256, HashAlgorithm::Blake3];
+
+    /// The tag written before the hex digest.
+    pub fn name(&self) -> &'static str {
+        match self {
+            HashAlgorithm::Sha256 => "sha256",
+            HashAlgorithm::Blake3 => "blake3",
+        }
+    }
+
+    /// Both algorithms produce 32-byte digests.
+    pub fn hex_len(&self) -> usize {
+        64
+    }
+
+    /// Bare lowercase hex digest of `data`.
+    pub fn hex(&self, data: impl AsRef<[u8]>) -> String {
+        let mut h = Hasher::new(*self);
+        h.update(data);
+        h.finalize_hex()
+    }
+
+    /// Tagged digest of `data`.
+    pub fn digest(&self, data: impl AsRef<[u8]>) -> Digest {
+        Digest {
+            algorithm: *self,
+            hex: self.hex(data),
+        }
+    }
+}
+
+impl fmt::Display for HashAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for HashAlgorithm {
+    type Err = DigestError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        HashAlgorithm::ALL
+            .into_iter()
+            .find(|a| a.name() == s)
+            .ok_or_else(|| DigestError::UnknownAlgorithm(s.to_string()))
+    }
+}
+
+/// Errors from parsing a digest or algorithm name.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum DigestError {
+    #[error("unknown hash algorithm '{0}' (expected sha256 or blake3)")]
+    UnknownAlgorithm(String),
+    #[error("malformed {algorithm} digest '{found}': expected 64 lowercase hex chars")]
+    Malformed {
+        algorithm: HashAlgorithm,
+        found: String,
+    },
+}
+
+/// An algorithm-tagged digest.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Digest {
+    algorithm: HashAlgorithm,
+    hex: String,
+}
+
+impl Digest {
+    /// Parse `sha256:<hex>`, `blake3:<hex>`, or legacy bare SHA-256 hex.
+    pub fn parse(s: &str) -> Result<Digest, DigestError> {
+        let (algorithm, hex) = match s.split_once(':') {
+            Some((tag, hex)) => (tag.parse()?, hex),
+            None => (HashAlgorithm::Sha256, s),
+        };
+        let well_formed = hex.len() == algorithm.hex_len()
+            && hex.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'));
+        if !well_formed {
+            return Err(DigestError::Malformed {
+                algorithm,
+                found: s.to_string(),
+            });
+        }
+        Ok(Digest {
+            algorithm,
+            hex: hex.to_string(),
+        })
+    }
+
+    pub fn algorithm(&self) -> HashAlgorithm {
+        self.algorithm
+    }
+
+    /// The bare hex digest, without the tag.
+    pub fn hex(&self) -> &str {
+        &self.hex
+    }
+
+    /// Whether `data` hashes to this digest under its algorithm.
+    pub fn matches(&self, data: impl AsRef<[u8]>) -> bool {
+        self.algorithm.hex(data) == self.hex
+    }
+}
+
+impl fmt::Display for Digest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.algorithm.name(), self.hex)
+    }
+}
+
+impl FromStr for Digest {
+    type Err = DigestError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Digest::parse(s)
+    }
+}
+
+/// Incremental hasher over either algorithm. Also an [`std::io::Write`]
+/// sink, so readers can be streamed into it with [`std::io::copy`].
+#[derive(Clone)]
+pub struct Hasher(Inner);
+
+#[derive(Clone)]
+enum Inner {
+    Sha256(sha2::Sha256),
+    Blake3(Box<blake3::Hasher>),
+}
+
+impl Hasher {
+    pub fn new(algorithm: HashAlgorithm) -> Self {
+        Hasher(match algorithm {
+            HashAlgorithm::Sha256 => Inner::Sha256(sha2::Sha256::new()),
+            HashAlgorithm::Blake3 => Inner::Blake3(Box::new(blake3::Hasher::new())),
+        })
+    }
+
+    pub fn sha256() -> Self {
+        Hasher::new(HashAlgorithm::Sha256)
+    }
+
+    pub fn algorithm(&self) -> HashAlgorithm {
+        match self.0 {
+            Inner::Sha256(_) => HashAlgorithm::Sha256,
+            Inner::Blake3(_) => HashAlgorithm::Blake3,
+        }
+    }
+
+    pub fn update(&mut self, data: impl AsRef<[u8]>) -> &mut Self {
+        match &mut self.0 {
+            Inner::Sha256(h) => h.update(data.as_ref()),
+            Inner::Blake3(h) => {
+                h.update(data.as_ref());
+            }
+        }
+        self
+    }
+
+    /// The raw 32-byte digest.
+    pub fn finalize_bytes(self) -> [u8; 32] {
+        match self.0 {
+            Inner::Sha256(h) => h.finalize().into(),
+            Inner::Blake3(h) => *h.finalize().as_bytes(),
+        }
+    }
+
+    /// Bare lowercase hex digest.
+    pub fn finalize_hex(self) -> String {
+        to_hex(&self.finalize_bytes())
+    }
+
+    /// Tagged digest.
+    pub fn finalize(self) -> Digest {
+        let algorithm = self.algorithm();
+        Digest {
+            algorithm,
+            hex: self.finalize_hex(),
+        }
+    }
+}
+
+impl std::io::Write for Hasher {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Raw SHA-256 of `data`.
+pub fn sha256(data: impl AsRef<[u8]>) -> [u8; 32] {
+    sha2::Sha256::digest(data.as_ref()).into()
+}
+
+/// Bare lowercase-hex SHA-256 of `data` — the format of every untagged
+/// hash Boruna stores.
+pub fn sha256_hex(data: impl AsRef<[u8]>) -> String {
+    to_hex(&sha256(data))
+}
+
+/// HMAC-SHA256 (RFC 2104) of `data` under `key`, as lowercase hex. For
+/// digests of low-entropy values that must not be reversible by hashing
+/// guesses.
+pub fn hmac_sha256_hex(key: &[u8], data: impl AsRef<[u8]>) -> String {
+    const BLOCK: usize = 64;
+    let mut block = [0u8; BLOCK];
+    if key.len() > BLOCK {
+        block[..32].copy_from_slice(&sha256(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+    let mut inner = sha2::Sha256::new();
+    inner.update(block.map(|b| b ^ 0x36));
+    inner.update(data.as_ref());
+    let mut outer = sha2::Sha256::new();
+    outer.update(block.map(|b| b ^ 0x5c));
+    outer.update(inner.finalize());
+    to_hex(&outer.finalize())
+}
+
+/// Stream the file at `path` through `algorithm` without loading it
+/// into memory.
+pub fn hash_file(path: &Path, algorithm: HashAlgorithm) -> std::io::Result<Digest> {
+    let mut file = std::fs::File::open(path)?;
+    hash_reader(&mut file, algorithm)
+}
+
+/// Stream `reader` to its end through `algorithm`.
+pub fn hash_reader(reader: &mut impl Read, algorithm: HashAlgorithm) -> std::io::Result<Digest> {
+    let mut hasher = Hasher::new(algorithm);
+    std::io::copy(reader, &mut hasher)?;
+    Ok(hasher.finalize())
+}
+
+/// Lowercase hex encoding of `bytes`.
+pub fn to_hex(bytes: &[u8]) -> String {
+    use std::fmt::Write;
+    let mut s = String::with_capacity(bytes.len() * 2);
+    for b in bytes {
+        let _ = write!(s, "{b:02x}");
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Published test vectors for "abc".
+    const SHA256_ABC: &str = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+    const BLAKE3_ABC: &str = "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85";
+
+    #[test]
+    fn algorithms_match_test_vectors() {
+        assert_eq!(sha256_hex(b"abc"), SHA256_ABC);
+        assert_eq!(HashAlgorithm::Sha256.hex(b"abc"), SHA256_ABC);
+        assert_eq!(HashAlgorithm::Blake3.hex(b"abc"), BLAKE3_ABC);
+
+        let mut h = Hasher::new(HashAlgorithm::Blake3);
+        h.update(b"a").update(b"bc");
+        assert_eq!(h.finalize().to_string(), format!("blake3:{BLAKE3_ABC}"));
+    }
+
+    #[test]
+    fn hmac_matches_rfc_4231_vectors() {
+        // Test cases 2 and 6 (a key longer than the block size).
+        assert_eq!(
+            hmac_sha256_hex(b"Jefe", b"what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        assert_eq!(
+            hmac_sha256_hex(
+                &[0xaa; 131],
+                b"Test Using Larger Than Block-Size Key - Hash Key First"
+            ),
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+        );
+    }
+
+    #[test]
+    fn parse_accepts_tagged_and_legacy_bare_hex() {
+        let legacy = Digest::parse(SHA256_ABC).unwrap();
+        assert_eq!(legacy.algorithm(), HashAlgorithm::Sha256);
+        assert!(legacy.matches(b"abc"));
+        assert_eq!(
+            legacy,
+            Digest::parse(&format!("sha256:{SHA256_ABC}")).unwrap()
+        );
+
+        let b3: Digest = format!("blake3:{BLAKE3_ABC}").parse().unwrap();
+        assert!(b3.matches(b"abc"));
+        assert!(!b3.matches(b"abd"));
+    }
+
+    #[test]
+    fn parse_rejects_unknown_tags_and_bad_hex() {
+        assert_eq!(
+            Digest::parse("md5:00"),
+            Err(DigestError::UnknownAlgorithm("md5".into()))
+        );
+        assert!(matches!(
+            Digest::parse("sha256:abc"),
+            Err(DigestError::Malformed { .. })
+        ));
+        assert!(Digest::parse(&SHA256_ABC.to_uppercase()).is_err());
+    }
+
+    #[test]
+    fn hash_file_streams_both_algorithms() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.bin");
+        let data = vec![7u8; 200_000];
+        std::fs::write(&path, &data).unwrap();
+        for alg in HashAlgorithm::ALL {
+            assert_eq!(hash_file(&path, alg).unwrap(), alg.digest(&data));
+        }
+    }
+}
@@ -44,21 +44,24 @@ impl BurunaLanguageServer {
 fn compute_diagnostics(text: &str) -> Vec<Diagnostic> {
     match boruna_compiler::compile("lsp", text) {
         Err(err) => {
-            let (line, col, message) = match err {
-                boruna_compiler::CompileError::Lexer { line, col, msg } => {
-                    (line.saturating_sub(1), col.saturating_sub(1), msg)
-                }
-                boruna_compiler::CompileError::Parse { line, msg } => {
-                    (line.saturating_sub(1), 0, msg)
-                }
-                boruna_compiler::CompileError::Type(msg) => (0, 0, msg),
-                boruna_compiler::CompileError::Codegen(msg) => (0, 0, msg),
+            // LSP positions are 0-based.
+            let range = match err.span() {
+                Some(span) => Range::new(
+                    Position::new(
+                        span.line.saturating_sub(1) as u32,
+                        span.col.saturating_sub(1) as u32,
+                    ),
+                    Position::new(
+                        span.end_line.saturating_sub(1) as u32,
+                        span.end_col.saturating_sub(1) as u32,
+                    ),
+                ),
+                None => Range::default(),
             };
-            let pos = Position::new(line as u32, col as u32);
             vec![Diagnostic {
-                range: Range::new(pos, pos),
+                range,
                 severity: Some(DiagnosticSeverity::ERROR),
-                message,
+                message: err.message().to_string(),
                 source: Some("boruna-lsp".into()),
                 ..Default::default()
             }]
@@ -119,7 +122,8 @@ fn completion_items(text: &str) -> Vec<CompletionItem> {
         "mut", "while", "for", "in", "export", "module",
     ];
     let builtin_types = [
-        "Int", "Float", "String", "Bool", "Unit", "Option", "Result", "List", "Map",
+        "Int", "Float", "Decimal", "BigInt", "Json", "String", "Bool", "Unit", "Option", "Result",
+        "List", "Map",
     ];
     let builtin_fns = [
         "parse_int",
@@ -1,6 +1,8 @@
 use clap::Parser;
 use rmcp::ServiceExt;
 
+mod pool;
+mod quota;
 mod server;
 mod tools;
 
@@ -14,13 +16,44 @@ struct Args {
     /// Path to standard libraries directory.
     #[arg(long, default_value = "libs")]
     libs_dir: String,
+
+    /// Compiled scripts kept warm for repeated `boruna_run` and
+    /// `boruna_framework_test` calls. 0 disables the pool.
+    #[arg(long, default_value_t = 64)]
+    pool_size: usize,
+
+    /// Seconds an unused script stays warm.
+    #[arg(long, default_value_t = 300)]
+    pool_ttl_secs: u64,
+
+    /// Most tool calls per client per minute. Unlimited when omitted.
+    #[arg(long)]
+    quota_requests_per_minute: Option<u32>,
+
+    /// Most VM steps per client per hour, summed over its runs.
+    #[arg(long)]
+    quota_steps_per_hour: Option<u64>,
+
+    /// Most executions (runs, framework tests, workflows) one client may
+    /// have in flight at once.
+    #[arg(long)]
+    quota_max_concurrent: Option<u32>,
 }
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args = Args::parse();
 
-    let server = server::BorunaMcpServer::new(args.templates_dir, args.libs_dir);
+    let pool = pool::PoolConfig {
+        size: args.pool_size,
+        ttl: std::time::Duration::from_secs(args.pool_ttl_secs),
+    };
+    let quotas = quota::QuotaConfig {
+        requests_per_minute: args.quota_requests_per_minute,
+        steps_per_hour: args.quota_steps_per_hour,
+        max_concurrent: args.quota_max_concurrent,
+    };
+    let server = server::BorunaMcpServer::new(args.templates_dir, args.libs_dir, pool, quotas);
     let service = server.serve(rmcp::transport::stdio()).await?;
     service.waiting().await?;
     Ok(())
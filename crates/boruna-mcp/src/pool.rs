@@ -0,0 +1,222 @@
+//! Warm pool of compiled modules for `boruna_run` and
+//! `boruna_framework_test`.
+//!
+//! Agent loops call the same script many times a minute, and a call's
+//! latency is dominated by lexing, parsing, type-checking and codegen. The
+//! pool keeps the compiled [`Module`] of recent scripts, keyed by the
+//! SHA-256 of the module name and source, so a repeated call only builds a
+//! fresh VM (or app runtime) from it. VMs themselves are never reused: each
+//! call gets its own, so no state leaks between runs.
+//!
+//! Entries idle for longer than the TTL are dropped, and the least recently
+//! used entry makes room when the pool is full. Compile errors are not
+//! cached.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use boruna_bytecode::Module;
+use boruna_compiler::CompileError;
+use serde::Serialize;
+
+use crate::tools::TOOL_RESPONSE_PROTOCOL_VERSION;
+
+/// Pool sizing, from `--pool-size` and `--pool-ttl-secs`.
+#[derive(Debug, Clone, Copy)]
+pub struct PoolConfig {
+    /// Most modules kept warm. 0 disables the pool.
+    pub size: usize,
+    /// How long an unused module stays warm.
+    pub ttl: Duration,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        PoolConfig {
+            size: 64,
+            ttl: Duration::from_secs(300),
+        }
+    }
+}
+
+/// Hit-rate counters, reported by the `boruna_pool_stats` tool.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct PoolStats {
+    /// Modules currently warm.
+    pub entries: usize,
+    pub capacity: usize,
+    pub ttl_secs: u64,
+    pub hits: u64,
+    pub misses: u64,
+    /// Entries dropped for being idle past the TTL or to make room.
+    pub evictions: u64,
+    /// `hits / (hits + misses)`, 0 before the first lookup.
+    pub hit_rate: f64,
+}
+
+struct Entry {
+    module: Module,
+    last_used: Instant,
+}
+
+#[derive(Default)]
+struct State {
+    entries: HashMap<String, Entry>,
+    hits: u64,
+    misses: u64,
+    evictions: u64,
+}
+
+pub struct VmPool {
+    config: PoolConfig,
+    state: Mutex<State>,
+}
+
+impl VmPool {
+    pub fn new(config: PoolConfig) -> Self {
+        VmPool {
+            config,
+            state: Mutex::new(State::default()),
+        }
+    }
+
+    /// The compiled module for `source`, from the pool when warm. Compiles
+    /// on a miss, outside the lock, and keeps the result if it compiled.
+    pub fn module(&self, name: &str, source: &str) -> Result<Module, CompileError> {
+        let key = boruna_hash::sha256_hex(format!("{name}\0{source}").as_bytes());
+        let now = Instant::now();
+        {
+            let mut state = self.lock();
+            self.expire(&mut state, now);
+            if let Some(entry) = state.entries.get_mut(&key) {
+                entry.last_used = now;
+                let module = entry.module.clone();
+                state.hits += 1;
+                return Ok(module);
+            }
+            state.misses += 1;
+        }
+
+        let module = boruna_compiler::compile(name, source)?;
+        if self.config.size > 0 {
+            let mut state = self.lock();
+            if !state.entries.contains_key(&key) && state.entries.len() >= self.config.size {
+                let oldest = state
+                    .entries
+                    .iter()
+                    .min_by_key(|(_, e)| e.last_used)
+                    .map(|(k, _)| k.clone());
+                if let Some(oldest) = oldest {
+                    state.entries.remove(&oldest);
+                    state.evictions += 1;
+                }
+            }
+            state.entries.insert(
+                key,
+                Entry {
+                    module: module.clone(),
+                    last_used: now,
+                },
+            );
+        }
+        Ok(module)
+    }
+
+    pub fn stats(&self) -> PoolStats {
+        let mut state = self.lock();
+        self.expire(&mut state, Instant::now());
+        let lookups = state.hits + state.misses;
+        PoolStats {
+            entries: state.entries.len(),
+            capacity: self.config.size,
+            ttl_secs: self.config.ttl.as_secs(),
+            hits: state.hits,
+            misses: state.misses,
+            evictions: state.evictions,
+            hit_rate: if lookups == 0 {
+                0.0
+            } else {
+                state.hits as f64 / lookups as f64
+            },
+        }
+    }
+
+    /// The `boruna_pool_stats` tool response.
+    pub fn stats_json(&self) -> String {
+        let mut json = serde_json::to_value(self.stats()).unwrap_or_default();
+        json["success"] = serde_json::json!(true);
+        json["protocol_version"] = serde_json::json!(TOOL_RESPONSE_PROTOCOL_VERSION);
+        serde_json::to_string_pretty(&json).unwrap_or_else(|_| "{}".into())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        // A panic while holding the lock leaves only counters and cached
+        // modules behind, both still valid.
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn expire(&self, state: &mut State, now: Instant) {
+        let ttl = self.config.ttl;
+        let before = state.entries.len();
+        state
+            .entries
+            .retain(|_, e| now.duration_since(e.last_used) < ttl);
+        state.evictions += (before - state.entries.len()) as u64;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SRC: &str = "fn main() -> Int { 1 + 2 }\n";
+
+    fn pool_of(size: usize, ttl: Duration) -> VmPool {
+        VmPool::new(PoolConfig { size, ttl })
+    }
+
+    #[test]
+    fn repeated_source_hits_the_pool() {
+        let pool = pool_of(4, Duration::from_secs(60));
+        let first = pool.module("module", SRC).unwrap();
+        let second = pool.module("module", SRC).unwrap();
+        assert_eq!(first.functions.len(), second.functions.len());
+        // Same source under another module name is a different entry.
+        pool.module("app", SRC).unwrap();
+        let stats = pool.stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (1, 2, 2));
+        assert!((stats.hit_rate - 1.0 / 3.0).abs() < 1e-9);
+
+        // Compile errors are returned, not cached.
+        assert!(pool.module("module", "not valid").is_err());
+        assert!(pool.module("module", "not valid").is_err());
+        assert_eq!(pool.stats().entries, 2);
+    }
+
+    #[test]
+    fn full_pool_evicts_least_recently_used_and_ttl_expires() {
+        let pool = pool_of(2, Duration::from_secs(60));
+        let a = "fn main() -> Int { 1 }\n";
+        let b = "fn main() -> Int { 2 }\n";
+        let c = "fn main() -> Int { 3 }\n";
+        pool.module("module", a).unwrap();
+        pool.module("module", b).unwrap();
+        pool.module("module", a).unwrap(); // b is now the oldest
+        pool.module("module", c).unwrap();
+        let stats = pool.stats();
+        assert_eq!((stats.entries, stats.evictions), (2, 1));
+        pool.module("module", a).unwrap();
+        assert_eq!(pool.stats().hits, 2);
+
+        let expiring = pool_of(4, Duration::ZERO);
+        expiring.module("module", a).unwrap();
+        expiring.module("module", a).unwrap();
+        let stats = expiring.stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (0, 2, 0));
+
+        let disabled = pool_of(0, Duration::from_secs(60));
+        disabled.module("module", a).unwrap();
+        assert_eq!(disabled.stats().entries, 0);
+    }
+}
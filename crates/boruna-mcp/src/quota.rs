@@ -0,0 +1,316 @@
+//! Per-client quotas for the MCP server.
+//!
+//! Clients are told apart by the `clientInfo.name` they send in the MCP
+//! `initialize` handshake. Each client gets its own
+//!
+//! - request rate (tool calls per minute),
+//! - execution budget (VM steps per hour, summed over the `steps` that
+//!   `boruna_run` and `boruna_run_sealed` report), and
+//! - concurrency (executing tools running at once).
+//!
+//! A call over a quota is answered with `error_kind: "quota_exceeded"`
+//! before it runs, so one busy agent cannot starve the others sharing the
+//! server. A run admitted with budget left may overshoot the hourly budget
+//! by at most its own `max_steps`.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::tools::TOOL_RESPONSE_PROTOCOL_VERSION;
+
+const MINUTE: Duration = Duration::from_secs(60);
+const HOUR: Duration = Duration::from_secs(3600);
+
+/// Client identity used before (or without) an `initialize` handshake.
+pub const ANONYMOUS_CLIENT: &str = "anonymous";
+
+/// Limits from `--quota-requests-per-minute`, `--quota-steps-per-hour` and
+/// `--quota-max-concurrent`. `None` = unlimited.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct QuotaConfig {
+    pub requests_per_minute: Option<u32>,
+    pub steps_per_hour: Option<u64>,
+    pub max_concurrent: Option<u32>,
+}
+
+/// A call refused by [`Quotas::admit`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuotaExceeded {
+    pub client: String,
+    /// `requests_per_minute`, `steps_per_hour` or `concurrent_executions`.
+    pub quota: &'static str,
+    pub limit: u64,
+    /// Seconds until the window frees room; `None` for concurrency, which
+    /// frees up when a running call finishes.
+    pub retry_after_secs: Option<u64>,
+}
+
+impl QuotaExceeded {
+    pub fn to_response(&self) -> String {
+        serde_json::json!({
+            "success": false,
+            "protocol_version": TOOL_RESPONSE_PROTOCOL_VERSION,
+            "error_kind": "quota_exceeded",
+            "quota": self.quota,
+            "limit": self.limit,
+            "client": self.client,
+            "retry_after_secs": self.retry_after_secs,
+            "message": format!(
+                "client '{}' exceeded its {} quota of {}",
+                self.client, self.quota, self.limit
+            ),
+        })
+        .to_string()
+    }
+}
+
+/// One client's usage, as reported by `boruna_quota_usage`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ClientUsage {
+    pub requests_last_minute: u64,
+    pub steps_last_hour: u64,
+    pub running: u32,
+    pub rejected: u64,
+}
+
+#[derive(Default)]
+struct ClientState {
+    requests: VecDeque<Instant>,
+    steps: VecDeque<(Instant, u64)>,
+    running: u32,
+    rejected: u64,
+}
+
+impl ClientState {
+    fn prune(&mut self, now: Instant) {
+        while self
+            .requests
+            .front()
+            .is_some_and(|t| now.duration_since(*t) >= MINUTE)
+        {
+            self.requests.pop_front();
+        }
+        while self
+            .steps
+            .front()
+            .is_some_and(|(t, _)| now.duration_since(*t) >= HOUR)
+        {
+            self.steps.pop_front();
+        }
+    }
+
+    fn steps_in_window(&self) -> u64 {
+        self.steps.iter().map(|(_, n)| n).sum()
+    }
+}
+
+pub struct Quotas {
+    config: QuotaConfig,
+    clients: Mutex<BTreeMap<String, ClientState>>,
+}
+
+/// A slot in a client's concurrency quota, released on drop.
+pub struct ExecutionPermit {
+    quotas: Arc<Quotas>,
+    client: String,
+}
+
+impl ExecutionPermit {
+    /// Charge `steps` VM steps to the client's hourly budget.
+    pub fn record_steps(&self, steps: u64) {
+        self.quotas
+            .record_steps_at(&self.client, steps, Instant::now());
+    }
+}
+
+impl Drop for ExecutionPermit {
+    fn drop(&mut self) {
+        let mut clients = self.quotas.lock();
+        if let Some(state) = clients.get_mut(&self.client) {
+            state.running = state.running.saturating_sub(1);
+        }
+    }
+}
+
+impl Quotas {
+    pub fn new(config: QuotaConfig) -> Arc<Self> {
+        Arc::new(Quotas {
+            config,
+            clients: Mutex::new(BTreeMap::new()),
+        })
+    }
+
+    /// Count one tool call from `client`. An `executes` call (one that runs
+    /// a VM) also needs step budget left and a free concurrency slot, and
+    /// holds the slot for the life of the returned permit.
+    pub fn admit(
+        self: &Arc<Self>,
+        client: &str,
+        executes: bool,
+    ) -> Result<Option<ExecutionPermit>, QuotaExceeded> {
+        self.admit_at(client, executes, Instant::now())
+    }
+
+    fn admit_at(
+        self: &Arc<Self>,
+        client: &str,
+        executes: bool,
+        now: Instant,
+    ) -> Result<Option<ExecutionPermit>, QuotaExceeded> {
+        let mut clients = self.lock();
+        let state = clients.entry(client.to_string()).or_default();
+        state.prune(now);
+        let refuse = |quota, limit: u64, retry_after: Option<Duration>| QuotaExceeded {
+            client: client.to_string(),
+            quota,
+            limit,
+            retry_after_secs: retry_after.map(|d| d.as_secs().max(1)),
+        };
+
+        let mut refusal = None;
+        if let Some(limit) = self.config.requests_per_minute {
+            if state.requests.len() as u64 >= u64::from(limit) {
+                let oldest = state.requests.front().copied().unwrap_or(now);
+                refusal = Some(refuse(
+                    "requests_per_minute",
+                    u64::from(limit),
+                    Some(MINUTE.saturating_sub(now.duration_since(oldest))),
+                ));
+            }
+        }
+        if executes && refusal.is_none() {
+            if let Some(limit) = self.config.steps_per_hour {
+                if state.steps_in_window() >= limit {
+                    let oldest = state.steps.front().map_or(now, |(t, _)| *t);
+                    refusal = Some(refuse(
+                        "steps_per_hour",
+                        limit,
+                        Some(HOUR.saturating_sub(now.duration_since(oldest))),
+                    ));
+                }
+            }
+            if let Some(limit) = self.config.max_concurrent {
+                if refusal.is_none() && state.running >= limit {
+                    refusal = Some(refuse("concurrent_executions", u64::from(limit), None));
+                }
+            }
+        }
+        if let Some(refusal) = refusal {
+            state.rejected += 1;
+            return Err(refusal);
+        }
+
+        state.requests.push_back(now);
+        if !executes {
+            return Ok(None);
+        }
+        state.running += 1;
+        Ok(Some(ExecutionPermit {
+            quotas: Arc::clone(self),
+            client: client.to_string(),
+        }))
+    }
+
+    fn record_steps_at(&self, client: &str, steps: u64, now: Instant) {
+        if steps == 0 {
+            return;
+        }
+        let mut clients = self.lock();
+        let state = clients.entry(client.to_string()).or_default();
+        state.prune(now);
+        state.steps.push_back((now, steps));
+    }
+
+    pub fn usage(&self) -> BTreeMap<String, ClientUsage> {
+        let now = Instant::now();
+        let mut clients = self.lock();
+        clients
+            .iter_mut()
+            .map(|(client, state)| {
+                state.prune(now);
+                let usage = ClientUsage {
+                    requests_last_minute: state.requests.len() as u64,
+                    steps_last_hour: state.steps_in_window(),
+                    running: state.running,
+                    rejected: state.rejected,
+                };
+                (client.clone(), usage)
+            })
+            .collect()
+    }
+
+    /// The `boruna_quota_usage` tool response.
+    pub fn usage_json(&self) -> String {
+        let json = serde_json::json!({
+            "success": true,
+            "protocol_version": TOOL_RESPONSE_PROTOCOL_VERSION,
+            "limits": {
+                "requests_per_minute": self.config.requests_per_minute,
+                "steps_per_hour": self.config.steps_per_hour,
+                "max_concurrent": self.config.max_concurrent,
+            },
+            "clients": self.usage(),
+        });
+        serde_json::to_string_pretty(&json).unwrap_or_else(|_| "{}".into())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<String, ClientState>> {
+        self.clients.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_rate_is_per_client_and_windowed() {
+        let quotas = Quotas::new(QuotaConfig {
+            requests_per_minute: Some(2),
+            ..QuotaConfig::default()
+        });
+        let t0 = Instant::now();
+        assert!(quotas.admit_at("a", false, t0).is_ok());
+        assert!(quotas.admit_at("a", false, t0).is_ok());
+        let err = quotas.admit_at("a", false, t0).err().unwrap();
+        assert_eq!(err.quota, "requests_per_minute");
+        assert_eq!(err.retry_after_secs, Some(60));
+        // Another client is unaffected, and the window slides.
+        assert!(quotas.admit_at("b", false, t0).is_ok());
+        assert!(quotas.admit_at("a", false, t0 + MINUTE).is_ok());
+
+        let usage = quotas.usage();
+        assert_eq!(usage["a"].rejected, 1);
+        assert_eq!(usage["b"].requests_last_minute, 1);
+    }
+
+    #[test]
+    fn executions_are_bounded_by_steps_and_concurrency() {
+        let quotas = Quotas::new(QuotaConfig {
+            steps_per_hour: Some(100),
+            max_concurrent: Some(1),
+            ..QuotaConfig::default()
+        });
+        let t0 = Instant::now();
+        let permit = quotas.admit_at("a", true, t0).unwrap().unwrap();
+        let err = quotas.admit_at("a", true, t0).err().unwrap();
+        assert_eq!(err.quota, "concurrent_executions");
+        assert_eq!(err.retry_after_secs, None);
+        // Non-executing tools do not need a slot.
+        assert!(quotas.admit_at("a", false, t0).unwrap().is_none());
+
+        quotas.record_steps_at("a", 150, t0);
+        drop(permit);
+        assert_eq!(quotas.usage()["a"].running, 0);
+        let err = quotas.admit_at("a", true, t0).err().unwrap();
+        assert_eq!((err.quota, err.limit), ("steps_per_hour", 100));
+        assert!(quotas.admit_at("a", true, t0 + HOUR).is_ok());
+
+        let response: serde_json::Value = serde_json::from_str(&err.to_response()).unwrap();
+        assert_eq!(response["error_kind"], "quota_exceeded");
+        assert_eq!(response["client"], "a");
+    }
+}
@@ -1,15 +1,30 @@
-use rmcp::handler::server::tool::ToolRouter;
+use rmcp::handler::server::tool::{ToolCallContext, ToolRouter};
 use rmcp::handler::server::wrapper::Parameters;
 use rmcp::model::*;
 use rmcp::service::RequestContext;
-use rmcp::{tool, tool_handler, tool_router, ErrorData as McpError, RoleServer, ServerHandler};
+use rmcp::{tool, tool_router, ErrorData as McpError, RoleServer, ServerHandler};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
+use crate::pool::{PoolConfig, VmPool};
+use crate::quota::{QuotaConfig, Quotas, ANONYMOUS_CLIENT};
 use crate::tools;
 
 const MAX_SOURCE_SIZE: usize = 1_048_576; // 1 MB
 
+/// Tools that run a VM, and so count against the steps-per-hour and
+/// concurrency quotas.
+const EXECUTING_TOOLS: &[&str] = &[
+    "boruna_run",
+    "boruna_run_sealed",
+    "boruna_framework_test",
+    "boruna_workflow_run",
+];
+
+/// Tools exempt from quotas, so usage stays visible to a throttled client.
+const QUOTA_EXEMPT_TOOLS: &[&str] = &["boruna_quota_usage"];
+
 /// Validate source input size and return an McpError if too large.
 fn validate_source(source: &str) -> Result<(), McpError> {
     if source.len() > MAX_SOURCE_SIZE {
@@ -140,6 +155,10 @@ struct FrameworkTestParams {
     source: String,
     /// Messages to send as 'tag:payload' strings (e.g. ['increment:1', 'reset:0'])
     messages: Vec<String>,
+    /// Also return the session as a trace2tests `trace` (default: false),
+    /// ready for `boruna trace2tests generate`.
+    #[serde(default)]
+    trace: bool,
 }
 
 #[derive(Serialize, Deserialize, JsonSchema)]
@@ -148,6 +167,36 @@ struct WorkflowValidateParams {
     workflow_json: String,
 }
 
+#[derive(Serialize, Deserialize, JsonSchema)]
+struct WorkflowRunParams {
+    /// The workflow.json content as a string
+    workflow_json: String,
+    /// `.ax` body of every `source` step, keyed by step id. Each body is
+    /// written at the path the step declares, relative to a scratch
+    /// workflow directory.
+    #[serde(default)]
+    sources: std::collections::BTreeMap<String, String>,
+    /// Capability policy — SAME shape as `boruna_run`. Either the string
+    /// shorthand "allow-all" / "deny-all" (default: "allow-all") or a Policy
+    /// object (see docs/reference/policy-schema.md).
+    #[serde(default)]
+    policy: Option<serde_json::Value>,
+    /// Optional limits. Hitting any returns success=false,
+    /// error_kind="limit_exceeded", limit_kind="<steps|source_bytes|bundle_bytes>".
+    #[serde(default)]
+    limits: Option<WorkflowRunLimitsParams>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Default)]
+struct WorkflowRunLimitsParams {
+    /// Maximum steps the workflow may declare. Checked before any step runs.
+    #[serde(default)]
+    max_steps: Option<usize>,
+    /// Maximum size in bytes of the base64 evidence archive in the response.
+    #[serde(default)]
+    max_bundle_bytes: Option<u64>,
+}
+
 #[derive(Serialize, Deserialize, JsonSchema)]
 struct TemplateApplyParams {
     /// Template name (e.g. 'crud-admin', 'form-basic')
@@ -165,22 +214,49 @@ struct PolicyValidateParams {
     policy_json: String,
 }
 
+#[derive(Serialize, Deserialize, JsonSchema)]
+struct PolicyExplainParams {
+    /// Capability policy — SAME shape as `boruna_run`: "allow-all",
+    /// "deny-all" or a Policy object (default: "allow-all").
+    #[serde(default)]
+    policy: Option<serde_json::Value>,
+    /// Capability name, e.g. 'net.fetch' or 'kv.set'
+    capability: String,
+    /// Call arguments as the script would pass them, e.g.
+    /// ['https://api.example.com/v1', 'POST'] for net.fetch
+    #[serde(default)]
+    args: Vec<serde_json::Value>,
+    /// Earlier calls to the same capability in the run, for budget checks
+    /// (default: 0)
+    #[serde(default)]
+    prior_calls: u64,
+}
+
 // ── Server ──
 
 #[derive(Clone)]
 pub struct BorunaMcpServer {
     tool_router: ToolRouter<Self>,
     templates_dir: String,
+    pool: Arc<VmPool>,
+    quotas: Arc<Quotas>,
     #[allow(dead_code)]
     libs_dir: String,
 }
 
 #[tool_router]
 impl BorunaMcpServer {
-    pub fn new(templates_dir: String, libs_dir: String) -> Self {
+    pub fn new(
+        templates_dir: String,
+        libs_dir: String,
+        pool: PoolConfig,
+        quotas: QuotaConfig,
+    ) -> Self {
         Self {
             tool_router: Self::tool_router(),
             templates_dir,
+            pool: Arc::new(VmPool::new(pool)),
+            quotas: Quotas::new(quotas),
             libs_dir,
         }
     }
@@ -305,8 +381,10 @@ impl BorunaMcpServer {
                 }
             });
 
+            let pool = self.pool.clone();
             let result = tokio::task::spawn_blocking(move || {
-                tools::run::run_source_with_progress(
+                tools::run::run_source_pooled(
+                    Some(&pool),
                     &source,
                     policy.as_ref(),
                     max_steps,
@@ -333,14 +411,17 @@ impl BorunaMcpServer {
             let _ = forwarder.await;
             result
         } else {
+            let pool = self.pool.clone();
             tokio::task::spawn_blocking(move || {
-                tools::run::run_source(
+                tools::run::run_source_pooled(
+                    Some(&pool),
                     &source,
                     policy.as_ref(),
                     max_steps,
                     trace,
                     limits.as_ref(),
                     output_schema.as_ref(),
+                    None::<fn(u64, Option<String>)>,
                 )
             })
             .await
@@ -427,7 +508,7 @@ impl BorunaMcpServer {
     }
 
     #[tool(
-        description = "Run a Boruna framework app by sending a sequence of messages. Returns init state, each cycle's state transition and effects, and the final view. Partial results are returned if a cycle fails."
+        description = "Run a Boruna framework app by sending a sequence of messages. Returns init state, each cycle's state transition and effects, and the final view. Partial results are returned if a cycle fails. With trace=true the response also carries `trace`, the cycles that completed as a standard trace2tests TraceFile, which `boruna trace2tests generate` turns into a regression test."
     )]
     async fn boruna_framework_test(
         &self,
@@ -436,10 +517,13 @@ impl BorunaMcpServer {
         validate_source(&params.source)?;
         let source = params.source;
         let messages = params.messages;
-        let result =
-            tokio::task::spawn_blocking(move || tools::framework::test_app(&source, &messages))
-                .await
-                .map_err(|e| McpError::internal_error(format!("task join error: {e}"), None))?;
+        let trace = params.trace;
+        let pool = self.pool.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            tools::framework::test_app(Some(&pool), &source, &messages, trace)
+        })
+        .await
+        .map_err(|e| McpError::internal_error(format!("task join error: {e}"), None))?;
         Ok(CallToolResult::success(vec![Content::text(result)]))
     }
 
@@ -460,6 +544,34 @@ impl BorunaMcpServer {
         Ok(CallToolResult::success(vec![Content::text(result)]))
     }
 
+    #[tool(
+        description = "Run an inline workflow definition and return its step results plus the evidence bundle as a base64 tar+gzip archive. `sources` maps each source step id to its .ax body; `policy` uses the SAME shape as boruna_run. The run is ephemeral and recorded: the archive holds workflow.json, policy.json, the audit log, per-step event logs and manifest.json, and verifies with `boruna evidence verify` once unpacked. Optional `limits` cap the declared step count and the archive size. Domain errors (parse_error, validation_error, invalid_sources, workflow_error, limit_exceeded, invalid_policy) are returned as success=false JSON."
+    )]
+    async fn boruna_workflow_run(
+        &self,
+        Parameters(params): Parameters<WorkflowRunParams>,
+    ) -> Result<CallToolResult, McpError> {
+        for source in params.sources.values() {
+            validate_source(source)?;
+        }
+        let limits = params.limits.unwrap_or_default();
+        let limits = tools::workflow::WorkflowRunLimits {
+            max_steps: limits.max_steps,
+            max_bundle_bytes: limits.max_bundle_bytes,
+        };
+        let result = tokio::task::spawn_blocking(move || {
+            tools::workflow::run_workflow(
+                &params.workflow_json,
+                &params.sources,
+                params.policy.as_ref(),
+                &limits,
+            )
+        })
+        .await
+        .map_err(|e| McpError::internal_error(format!("task join error: {e}"), None))?;
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
     // ── Template Tools ──
 
     #[tool(
@@ -509,10 +621,30 @@ impl BorunaMcpServer {
         Ok(CallToolResult::success(vec![Content::text(result)]))
     }
 
+    // ── Warm Pool Tool ──
+
+    #[tool(
+        description = "Report the warm pool that lets repeated boruna_run and boruna_framework_test calls on the same source skip compilation: entries, capacity, ttl_secs, hits, misses, evictions and hit_rate. Size and TTL are set with the server's --pool-size and --pool-ttl-secs flags."
+    )]
+    async fn boruna_pool_stats(&self) -> Result<CallToolResult, McpError> {
+        let result = self.pool.stats_json();
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
+    // ── Quota Tool ──
+
+    #[tool(
+        description = "Report per-client quota usage: the configured limits (requests_per_minute, steps_per_hour, max_concurrent; null = unlimited) and, for each client identified by the clientInfo.name of its MCP handshake, requests in the last minute, VM steps in the last hour, running executions and rejected calls. Calls over a quota return success=false, error_kind='quota_exceeded'. This tool is exempt from quotas."
+    )]
+    async fn boruna_quota_usage(&self) -> Result<CallToolResult, McpError> {
+        let result = self.quotas.usage_json();
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
     // ── Policy Tool (sprint 0.4-S15) ──
 
     #[tool(
-        description = "Strict-validate a policy JSON body. Returns ok or a list of typed errors with stable error_kind strings (policy.unknown_field, policy.invalid_capability, policy.unknown_schema_version, policy.invalid_net_policy, policy.parse_error). Use as a CI gate or pre-flight check before passing the same body to boruna_run. See docs/design-policy-as-code.md and docs/reference/policy-schema.md."
+        description = "Strict-validate a policy JSON body. Returns ok or a list of typed errors with stable error_kind strings (policy.unknown_field, policy.invalid_capability, policy.unknown_schema_version, policy.invalid_net_policy, policy.invalid_trusted_key, policy.parse_error). Use as a CI gate or pre-flight check before passing the same body to boruna_run. See docs/design-policy-as-code.md and docs/reference/policy-schema.md."
     )]
     async fn boruna_policy_validate(
         &self,
@@ -524,10 +656,78 @@ impl BorunaMcpServer {
             .map_err(|e| McpError::internal_error(format!("task join error: {e}"), None))?;
         Ok(CallToolResult::success(vec![Content::text(result)]))
     }
+
+    #[tool(
+        description = "Explain how a policy decides one capability call. Takes a policy (SAME shape as boruna_run), a capability name, the call's args and the number of prior calls to it, and returns the decision (allow/deny/budget_exceeded/prompt), the deciding rule as a JSON Pointer into the policy with a reason, and suggestions: minimal RFC 6902 JSON Patch edits that each flip the outcome. Evaluates the rule or default_allow, the budget, then the handler-scoped limits (kv_policy, queue_policy, notify_policy destinations, net_policy domains and methods). Use it to debug a capability_denied error instead of guessing at policy changes."
+    )]
+    async fn boruna_policy_explain(
+        &self,
+        Parameters(params): Parameters<PolicyExplainParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let result = tokio::task::spawn_blocking(move || {
+            tools::policy::explain_call(
+                params.policy.as_ref(),
+                &params.capability,
+                &params.args,
+                params.prior_calls,
+            )
+        })
+        .await
+        .map_err(|e| McpError::internal_error(format!("task join error: {e}"), None))?;
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
 }
 
-#[tool_handler]
 impl ServerHandler for BorunaMcpServer {
+    /// Route a tool call, enforcing the caller's quotas first. A refused
+    /// call is a `quota_exceeded` domain error, not an MCP error.
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParams,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let tool = request.name.as_ref();
+        if QUOTA_EXEMPT_TOOLS.contains(&tool) {
+            let tcc = ToolCallContext::new(self, request, context);
+            return self.tool_router.call(tcc).await;
+        }
+        let client = context
+            .peer
+            .peer_info()
+            .map(|info| info.client_info.name.clone())
+            .unwrap_or_else(|| ANONYMOUS_CLIENT.to_string());
+        let permit = match self.quotas.admit(&client, EXECUTING_TOOLS.contains(&tool)) {
+            Ok(permit) => permit,
+            Err(exceeded) => {
+                return Ok(CallToolResult::success(vec![Content::text(
+                    exceeded.to_response(),
+                )]))
+            }
+        };
+        let tcc = ToolCallContext::new(self, request, context);
+        let result = self.tool_router.call(tcc).await;
+        if let (Some(permit), Ok(result)) = (&permit, &result) {
+            permit.record_steps(reported_steps(result));
+        }
+        result
+    }
+
+    async fn list_tools(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, McpError> {
+        Ok(ListToolsResult {
+            tools: self.tool_router.list_all(),
+            meta: None,
+            next_cursor: None,
+        })
+    }
+
+    fn get_tool(&self, name: &str) -> Option<Tool> {
+        self.tool_router.get(name).cloned()
+    }
+
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
             capabilities: ServerCapabilities::builder().enable_tools().build(),
@@ -547,3 +747,14 @@ impl ServerHandler for BorunaMcpServer {
         }
     }
 }
+
+/// The `steps` field of a tool's JSON response, 0 when it has none.
+fn reported_steps(result: &CallToolResult) -> u64 {
+    result
+        .content
+        .first()
+        .and_then(|c| c.as_text())
+        .and_then(|t| serde_json::from_str::<serde_json::Value>(&t.text).ok())
+        .and_then(|json| json["steps"].as_u64())
+        .unwrap_or(0)
+}
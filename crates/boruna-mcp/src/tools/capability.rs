@@ -33,8 +33,10 @@ mod tests {
             boruna_bytecode::CAPABILITY_REPORT_PROTOCOL_VERSION
         );
         assert_eq!(json["name"], "boruna");
-        // 0.3-S14: bumped to 11 with the new step.input capability.
-        assert_eq!(json["capabilities"].as_array().unwrap().len(), 11);
+        assert_eq!(
+            json["capabilities"].as_array().unwrap().len(),
+            boruna_bytecode::Capability::ALL.len()
+        );
         assert!(json["capability_set_hash"]
             .as_str()
             .unwrap()
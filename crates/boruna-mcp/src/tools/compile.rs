@@ -77,39 +77,24 @@ pub fn parse_ast(source: &str) -> String {
 }
 
 pub fn compile_error_json(err: &CompileError) -> String {
-    let error = match err {
-        CompileError::Lexer { line, col, msg } => {
-            serde_json::json!({
-                "severity": "error",
-                "code": "E001",
-                "message": msg,
-                "line": line,
-                "col": col,
-            })
-        }
-        CompileError::Parse { line, msg } => {
-            serde_json::json!({
-                "severity": "error",
-                "code": "E002",
-                "message": msg,
-                "line": line,
-            })
-        }
-        CompileError::Type(msg) => {
-            serde_json::json!({
-                "severity": "error",
-                "code": "E009",
-                "message": msg,
-            })
-        }
-        CompileError::Codegen(msg) => {
-            serde_json::json!({
-                "severity": "error",
-                "code": "E008",
-                "message": msg,
-            })
-        }
+    let code = match err {
+        CompileError::Lexer { .. } => "E001",
+        CompileError::Parse { .. } => "E002",
+        CompileError::Type { .. } => "E009",
+        CompileError::Codegen { .. } => "E008",
+        CompileError::Budget(_) => "E015",
     };
+    let mut error = serde_json::json!({
+        "severity": "error",
+        "code": code,
+        "message": err.message(),
+    });
+    if let Some(span) = err.span() {
+        error["line"] = serde_json::json!(span.line);
+        error["col"] = serde_json::json!(span.col);
+        error["end_line"] = serde_json::json!(span.end_line);
+        error["end_col"] = serde_json::json!(span.end_col);
+    }
 
     serde_json::json!({
         "success": false,
@@ -2,6 +2,7 @@ use boruna_bytecode::Value;
 use boruna_framework::runtime::{AppMessage, AppRuntime};
 
 use super::TOOL_RESPONSE_PROTOCOL_VERSION;
+use crate::pool::VmPool;
 
 /// Validate that source conforms to the App protocol.
 pub fn validate_app(source: &str) -> String {
@@ -48,10 +49,18 @@ pub fn validate_app(source: &str) -> String {
     }
 }
 
-/// Run a framework app with a sequence of messages.
-pub fn test_app(source: &str, messages: &[String]) -> String {
-    // Compile
-    let module = match boruna_compiler::compile("app", source) {
+/// Run a framework app with a sequence of messages, taking the compiled
+/// module from `pool` when the same source ran recently.
+///
+/// With `trace`, the response also carries the cycles that completed as a
+/// trace2tests [`TraceFile`](boruna_tooling::trace2tests::TraceFile).
+pub fn test_app(pool: Option<&VmPool>, source: &str, messages: &[String], trace: bool) -> String {
+    // Compile, or reuse a warm module
+    let compiled = match pool {
+        Some(pool) => pool.module("app", source),
+        None => boruna_compiler::compile("app", source),
+    };
+    let module = match compiled {
         Ok(m) => m,
         Err(e) => {
             return crate::tools::compile::compile_error_json(&e);
@@ -94,28 +103,46 @@ pub fn test_app(source: &str, messages: &[String]) -> String {
                     "error": format!("{e}"),
                 }));
                 // Return partial results
-                return serde_json::json!({
+                let mut response = serde_json::json!({
                     "success": false,
                     "protocol_version": TOOL_RESPONSE_PROTOCOL_VERSION,
                     "error_kind": "framework_error",
                     "message": format!("cycle {} failed: {e}", cycles.len()),
                     "init_state": init_state,
                     "cycles": cycles,
-                })
-                .to_string();
+                });
+                if trace {
+                    response["trace"] = session_trace(source, &runtime);
+                }
+                return response.to_string();
             }
         }
     }
 
-    serde_json::json!({
+    let mut response = serde_json::json!({
         "success": true,
         "protocol_version": TOOL_RESPONSE_PROTOCOL_VERSION,
         "init_state": init_state,
         "cycles": cycles,
         "final_state": format_value_brief(runtime.state()),
         "total_cycles": runtime.cycle(),
-    })
-    .to_string()
+    });
+    if trace {
+        response["trace"] = session_trace(source, &runtime);
+    }
+    response.to_string()
+}
+
+/// The runtime's cycle log as a trace of the source file `app.ax`.
+fn session_trace(source: &str, runtime: &AppRuntime) -> serde_json::Value {
+    boruna_tooling::trace2tests::trace_from_cycle_log(
+        source,
+        "app.ax",
+        runtime.cycle_log(),
+        runtime.state(),
+    )
+    .and_then(|t| serde_json::to_value(t).map_err(|e| e.to_string()))
+    .unwrap_or(serde_json::Value::Null)
 }
 
 /// Parse "tag:payload" message format.
@@ -140,6 +167,8 @@ fn format_value_brief(value: &Value) -> serde_json::Value {
     match value {
         Value::Int(n) => serde_json::json!(n),
         Value::Float(f) => serde_json::json!(f),
+        Value::Decimal(d) => serde_json::json!(d.to_string()),
+        Value::BigInt(n) => serde_json::json!(n.to_string()),
         Value::String(s) => serde_json::json!(s),
         Value::Bool(b) => serde_json::json!(b),
         Value::Unit => serde_json::json!(null),
@@ -173,5 +202,43 @@ fn format_value_brief(value: &Value) -> serde_json::Value {
         }
         Value::ActorId(id) => serde_json::json!({"actor_id": id}),
         Value::FnRef(idx) => serde_json::json!({"fn_ref": idx}),
+        Value::CapHandle(id) => serde_json::json!({"cap_handle": id}),
+        Value::Table(t) => {
+            let rows: Vec<Vec<serde_json::Value>> = (0..t.num_rows())
+                .map(|r| {
+                    t.columns()
+                        .iter()
+                        .map(|c| format_value_brief(&c.values[r]))
+                        .collect()
+                })
+                .collect();
+            serde_json::json!({
+                "columns": t.column_names(),
+                "rows": rows,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COUNTER: &str = include_str!("../../../../examples/framework/counter_app.ax");
+
+    #[test]
+    fn trace_is_a_replayable_trace_file() {
+        let messages = vec!["increment:0".to_string(), "increment:0".to_string()];
+        let plain: serde_json::Value =
+            serde_json::from_str(&test_app(None, COUNTER, &messages, false)).unwrap();
+        assert!(plain.get("trace").is_none());
+
+        let response: serde_json::Value =
+            serde_json::from_str(&test_app(None, COUNTER, &messages, true)).unwrap();
+        let trace: boruna_tooling::trace2tests::TraceFile =
+            serde_json::from_value(response["trace"].clone()).unwrap();
+        assert_eq!(trace.cycles.len(), 2);
+        let spec = boruna_tooling::trace2tests::generate_test(&trace, "session");
+        assert!(boruna_tooling::trace2tests::run_test(&spec, COUNTER).passed);
     }
 }
@@ -185,6 +185,17 @@ mod protocol_version_tests {
         assert_protocol_version(&out, "workflow validation_error");
     }
 
+    #[test]
+    fn workflow_run_failure_carries_protocol_version() {
+        let out = workflow::run_workflow(
+            "not json",
+            &Default::default(),
+            None,
+            &workflow::WorkflowRunLimits::default(),
+        );
+        assert_protocol_version(&out, "workflow_run parse_error");
+    }
+
     // ── template ──
 
     #[test]
@@ -236,6 +247,22 @@ mod protocol_version_tests {
         assert_protocol_version(&out, "policy_validate parse_error");
     }
 
+    #[test]
+    fn policy_explain_carries_protocol_version() {
+        let out = policy::explain_call(None, "net.fetch", &[], 0);
+        assert_protocol_version(&out, "policy_explain success");
+        let out = policy::explain_call(None, "nope", &[], 0);
+        assert_protocol_version(&out, "policy_explain invalid_capability");
+    }
+
+    // ── pool stats ──
+
+    #[test]
+    fn pool_stats_carries_protocol_version() {
+        let pool = crate::pool::VmPool::new(crate::pool::PoolConfig::default());
+        assert_protocol_version(&pool.stats_json(), "pool_stats");
+    }
+
     // ── meta ──
 
     #[test]
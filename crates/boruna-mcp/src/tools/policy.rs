@@ -1,7 +1,13 @@
 //! `boruna_policy_validate` MCP tool — strict-validate a policy JSON
 //! body and return ok / error_kind. See `docs/design-policy-as-code.md`.
+//!
+//! `boruna_policy_explain` — report which rule decides one capability
+//! call and the policy edits that would flip it.
 
+use super::run::parse_policy;
 use super::TOOL_RESPONSE_PROTOCOL_VERSION;
+use boruna_bytecode::Capability;
+use boruna_vm::policy_explain;
 use boruna_vm::policy_validate::{self, PolicyParseError, POLICY_SCHEMA_VERSION};
 
 /// Validate a policy JSON body. Returns a successful tool response
@@ -60,6 +66,53 @@ fn error_to_json(e: &PolicyParseError) -> serde_json::Value {
     serde_json::Value::Object(obj)
 }
 
+/// Explain how `policy` (same shapes as `boruna_run`) decides a call to
+/// `capability` with `args`, after `prior_calls` earlier calls to it in
+/// the run. Arguments are JSON and convert as `json_parse` does in `.ax`
+/// (objects become maps, `null` becomes `None`).
+pub fn explain_call(
+    policy: Option<&serde_json::Value>,
+    capability: &str,
+    args: &[serde_json::Value],
+    prior_calls: u64,
+) -> String {
+    let fail = |error_kind: &str, message: String| {
+        serde_json::json!({
+            "success": false,
+            "protocol_version": TOOL_RESPONSE_PROTOCOL_VERSION,
+            "error_kind": error_kind,
+            "message": message,
+        })
+        .to_string()
+    };
+    let policy = match parse_policy(policy) {
+        Ok(p) => p,
+        Err(e) => return fail(&e.error_kind, e.message),
+    };
+    let Some(cap) = Capability::from_name(capability) else {
+        return fail(
+            "invalid_capability",
+            format!("unknown capability '{capability}' (see boruna_capability_list)"),
+        );
+    };
+    // `json::parse` of a serialized JSON value cannot fail.
+    let args: Vec<_> = args
+        .iter()
+        .filter_map(|a| boruna_vm::json::parse(&a.to_string()).ok())
+        .collect();
+    let explanation = policy_explain::explain(&policy, &cap, &args, prior_calls);
+    let mut response = serde_json::json!({
+        "success": true,
+        "protocol_version": TOOL_RESPONSE_PROTOCOL_VERSION,
+    });
+    if let (Some(obj), Ok(serde_json::Value::Object(fields))) =
+        (response.as_object_mut(), serde_json::to_value(&explanation))
+    {
+        obj.extend(fields);
+    }
+    response.to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -129,4 +182,40 @@ mod tests {
         assert_eq!(v["errors"][0]["error_kind"], "policy.invalid_net_policy");
         assert_eq!(v["errors"][0]["field"], "timeout_ms");
     }
+
+    #[test]
+    fn explain_names_the_deciding_rule() {
+        let policy = serde_json::json!({
+            "default_allow": true,
+            "rules": {"net.fetch": {"allow": true, "budget": 0}},
+            "net_policy": {"allowed_domains": ["api.example.com"]}
+        });
+        let v = parse(&explain_call(
+            Some(&policy),
+            "net.fetch",
+            &[serde_json::json!("https://other.example.com/x")],
+            0,
+        ));
+        assert_eq!(v["success"], true);
+        assert_eq!(v["decision"], "deny");
+        assert_eq!(v["rule"]["path"], "/net_policy/allowed_domains");
+        assert_eq!(v["suggestions"][0]["op"], "add");
+        assert_eq!(v["suggestions"][0]["value"], "other.example.com");
+
+        let v = parse(&explain_call(
+            Some(&serde_json::json!("deny-all")),
+            "db",
+            &[],
+            0,
+        ));
+        assert_eq!(v["capability"], "db.query");
+        assert_eq!(v["rule"]["path"], "/default_allow");
+    }
+
+    #[test]
+    fn explain_rejects_unknown_capabilities() {
+        let v = parse(&explain_call(None, "net.fetcher", &[], 0));
+        assert_eq!(v["success"], false);
+        assert_eq!(v["error_kind"], "invalid_capability");
+    }
 }
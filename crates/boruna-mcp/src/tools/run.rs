@@ -5,6 +5,7 @@ use boruna_vm::vm::{StepResult, Vm};
 use serde_json::Value as JsonValue;
 
 use super::TOOL_RESPONSE_PROTOCOL_VERSION;
+use crate::pool::VmPool;
 
 const TRACE_LIMIT: usize = 500;
 
@@ -72,6 +73,7 @@ pub struct RunLimits {
 /// the script's `result` is validated post-execution; mismatches return
 /// `error_kind: "validation_failed"` with per-path JSON Pointer errors. See
 /// [`validate_output_against_schema`] and `docs/design-output-schema.md`.
+#[cfg(test)]
 pub fn run_source(
     source: &str,
     policy: Option<&JsonValue>,
@@ -110,6 +112,7 @@ pub fn run_source(
 /// Keep callback work cheap — a heavy callback adds latency to every slice.
 /// The MCP wiring forwards through a non-blocking `mpsc::unbounded_channel`
 /// so notification dispatch happens on a separate task.
+#[cfg(test)]
 pub fn run_source_with_progress<F>(
     source: &str,
     policy: Option<&JsonValue>,
@@ -119,6 +122,34 @@ pub fn run_source_with_progress<F>(
     output_schema: Option<&JsonValue>,
     progress_callback: Option<F>,
 ) -> String
+where
+    F: FnMut(u64, Option<String>),
+{
+    run_source_pooled(
+        None,
+        source,
+        policy,
+        max_steps,
+        trace,
+        limits,
+        output_schema,
+        progress_callback,
+    )
+}
+
+/// [`run_source_with_progress`] taking the compiled module from `pool`
+/// when the same source ran recently. The server's `boruna_run` entry.
+#[allow(clippy::too_many_arguments)]
+pub fn run_source_pooled<F>(
+    pool: Option<&VmPool>,
+    source: &str,
+    policy: Option<&JsonValue>,
+    max_steps: u64,
+    trace: bool,
+    limits: Option<&RunLimits>,
+    output_schema: Option<&JsonValue>,
+    progress_callback: Option<F>,
+) -> String
 where
     F: FnMut(u64, Option<String>),
 {
@@ -142,8 +173,12 @@ where
         }
     }
 
-    // Compile
-    let module = match boruna_compiler::compile("module", source) {
+    // Compile, or reuse a warm module
+    let compiled = match pool {
+        Some(pool) => pool.module("module", source),
+        None => boruna_compiler::compile("module", source),
+    };
+    let module = match compiled {
         Ok(m) => m,
         Err(e) => {
             return crate::tools::compile::compile_error_json(&e);
@@ -222,11 +257,12 @@ where
             });
 
             if trace {
-                let trace_entries: Vec<&str> = if vm.trace.len() > TRACE_LIMIT {
-                    vm.trace[..TRACE_LIMIT].iter().map(|s| s.as_str()).collect()
-                } else {
-                    vm.trace.iter().map(|s| s.as_str()).collect()
-                };
+                let trace_entries: Vec<&str> = vm
+                    .trace
+                    .iter()
+                    .take(TRACE_LIMIT)
+                    .map(|s| s.as_str())
+                    .collect();
                 json["trace"] = serde_json::json!(trace_entries);
                 json["trace_truncated"] = serde_json::json!(vm.trace.len() > TRACE_LIMIT);
             }
@@ -604,6 +640,8 @@ pub(crate) fn format_value(value: &Value) -> serde_json::Value {
     match value {
         Value::Int(n) => serde_json::json!(n),
         Value::Float(f) => serde_json::json!(f),
+        Value::Decimal(d) => serde_json::json!(d.to_string()),
+        Value::BigInt(n) => serde_json::json!(n.to_string()),
         Value::String(s) => serde_json::json!(s),
         Value::Bool(b) => serde_json::json!(b),
         Value::Unit => serde_json::json!(null),
@@ -642,6 +680,22 @@ pub(crate) fn format_value(value: &Value) -> serde_json::Value {
         }
         Value::ActorId(id) => serde_json::json!({"actor_id": id}),
         Value::FnRef(idx) => serde_json::json!({"fn_ref": idx}),
+        Value::CapHandle(id) => serde_json::json!({"cap_handle": id}),
+        Value::Table(t) => {
+            let rows: Vec<Vec<serde_json::Value>> = (0..t.num_rows())
+                .map(|r| {
+                    t.columns()
+                        .iter()
+                        .map(|c| format_value(&c.values[r]))
+                        .collect()
+                })
+                .collect();
+            serde_json::json!({
+                "type": "table",
+                "columns": t.column_names(),
+                "rows": rows,
+            })
+        }
     }
 }
 
@@ -702,6 +756,29 @@ mod tests {
         assert_eq!(err.error_kind, "policy.parse_error");
     }
 
+    #[test]
+    fn pooled_run_matches_a_cold_run() {
+        let pool = VmPool::new(crate::pool::PoolConfig::default());
+        let src = "fn main() -> Int { 40 + 2 }\n";
+        let run = |pool| {
+            run_source_pooled(
+                pool,
+                src,
+                None,
+                10_000,
+                false,
+                None,
+                None,
+                None::<fn(u64, Option<String>)>,
+            )
+        };
+        let cold = run(None);
+        assert_eq!(run(Some(&pool)), cold);
+        assert_eq!(run(Some(&pool)), cold);
+        let stats = pool.stats();
+        assert_eq!((stats.hits, stats.misses), (1, 1));
+    }
+
     // ── 0.4-S15: strict-validator paths ──
 
     #[test]
@@ -31,12 +31,12 @@
 //! never fabricate a bundle.
 
 use boruna_bytecode::Value;
+use boruna_hash::sha256_hex;
 use boruna_vm::capability_gateway::{CapabilityGateway, ReplayHandler};
 use boruna_vm::error::VmError;
 use boruna_vm::replay::{Event, ReplayEngine, ReplayResult};
 use boruna_vm::vm::Vm;
 use serde_json::Value as JsonValue;
-use sha2::{Digest, Sha256};
 
 use super::TOOL_RESPONSE_PROTOCOL_VERSION;
 use crate::tools::run::{format_value, parse_policy};
@@ -178,22 +178,22 @@ pub fn run_sealed(source: &str, policy: Option<&JsonValue>, max_steps: u64) -> S
 /// type/codegen failures that occur before any execution.
 fn compile_error_json(err: &boruna_compiler::CompileError) -> String {
     use boruna_compiler::CompileError;
-    let (kind, message, line, col) = match err {
-        CompileError::Lexer { line, col, msg } => {
-            ("parse_error", msg.clone(), Some(*line), Some(*col))
-        }
-        CompileError::Parse { line, msg } => ("parse_error", msg.clone(), Some(*line), None),
-        CompileError::Type(msg) | CompileError::Codegen(msg) => {
-            ("compile_error", msg.clone(), None, None)
+    let kind = match err {
+        CompileError::Lexer { .. } | CompileError::Parse { .. } => "parse_error",
+        CompileError::Type { .. } | CompileError::Codegen { .. } | CompileError::Budget(_) => {
+            "compile_error"
         }
     };
+    let span = err.span();
     serde_json::json!({
         "success": false,
         "protocol_version": TOOL_RESPONSE_PROTOCOL_VERSION,
         "error_kind": kind,
-        "error": message,
-        "line": line,
-        "col": col,
+        "error": err.message(),
+        "line": span.map(|s| s.line),
+        "col": span.map(|s| s.col),
+        "end_line": span.map(|s| s.end_line),
+        "end_col": span.map(|s| s.end_col),
     })
     .to_string()
 }
@@ -283,6 +283,32 @@ fn event_json(event: &Event) -> serde_json::Value {
             "index": index,
             "passed": passed,
         }),
+        Event::ActorCheckpoint {
+            actor_id,
+            state_hash,
+        } => serde_json::json!({
+            "event": "actor_checkpoint",
+            "actor_id": actor_id,
+            "state_hash": state_hash,
+        }),
+        Event::ActorRestore {
+            actor_id,
+            state_hash,
+        } => serde_json::json!({
+            "event": "actor_restore",
+            "actor_id": actor_id,
+            "state_hash": state_hash,
+        }),
+        Event::CapabilityDelegation {
+            parent,
+            child,
+            policy_hash,
+        } => serde_json::json!({
+            "event": "capability_delegation",
+            "parent": parent,
+            "child": child,
+            "policy_hash": policy_hash,
+        }),
     }
 }
 
@@ -297,18 +323,6 @@ fn cap_call_json(event: &Event) -> Option<serde_json::Value> {
     }
 }
 
-fn sha256_hex(bytes: &[u8]) -> String {
-    let mut hasher = Sha256::new();
-    hasher.update(bytes);
-    let digest = hasher.finalize();
-    let mut out = String::with_capacity(digest.len() * 2);
-    for b in digest {
-        use std::fmt::Write;
-        let _ = write!(out, "{b:02x}");
-    }
-    out
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
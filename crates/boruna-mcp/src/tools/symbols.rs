@@ -160,20 +160,18 @@ fn render_type(ty: &TypeExpr) -> String {
 }
 
 fn parse_error_json(err: &CompileError) -> String {
-    let (message, line, col) = match err {
-        CompileError::Lexer { line, col, msg } => (msg.clone(), Some(*line), Some(*col)),
-        CompileError::Parse { line, msg } => (msg.clone(), Some(*line), None),
-        // extract_symbols only lexes + parses, so Type/Codegen cannot occur.
-        CompileError::Type(msg) | CompileError::Codegen(msg) => (msg.clone(), None, None),
-    };
+    // extract_symbols only lexes + parses, so every error has a span.
+    let span = err.span();
 
     serde_json::json!({
         "success": false,
         "protocol_version": TOOL_RESPONSE_PROTOCOL_VERSION,
         "error_kind": "parse_error",
-        "error": message,
-        "line": line,
-        "col": col,
+        "error": err.message(),
+        "line": span.map(|s| s.line),
+        "col": span.map(|s| s.col),
+        "end_line": span.map(|s| s.end_line),
+        "end_col": span.map(|s| s.end_col),
     })
     .to_string()
 }
@@ -274,5 +272,10 @@ fn withdraw(amount: Int) -> Int
         assert_eq!(v["success"], false);
         assert_eq!(v["error_kind"], "parse_error");
         assert!(v["error"].is_string());
+        // The span covers just the offending `@`.
+        assert_eq!(
+            (&v["line"], &v["col"], &v["end_line"], &v["end_col"]),
+            (&1.into(), &1.into(), &1.into(), &2.into())
+        );
     }
 }
@@ -1,16 +1,21 @@
 use std::collections::BTreeMap;
 use std::path::Path;
 
+use boruna_tooling::templates::remote;
+
 use super::TOOL_RESPONSE_PROTOCOL_VERSION;
 
-/// List available templates in directory.
+/// List available templates in directory, merged with the remote template
+/// cache.
 pub fn list_templates(dir: &str) -> String {
     let path = Path::new(dir);
-    match boruna_tooling::templates::list_templates(path) {
+    let cache = Path::new(remote::DEFAULT_CACHE_DIR);
+    match boruna_tooling::templates::list_templates_with_cache(path, cache) {
         Ok(templates) => {
             let list: Vec<serde_json::Value> = templates
                 .iter()
-                .map(|t| {
+                .map(|listed| {
+                    let t = &listed.manifest;
                     serde_json::json!({
                         "name": t.name,
                         "version": t.version,
@@ -18,6 +23,7 @@ pub fn list_templates(dir: &str) -> String {
                         "dependencies": t.dependencies,
                         "capabilities": t.capabilities,
                         "args": t.args.keys().collect::<Vec<_>>(),
+                        "provenance": listed.provenance,
                     })
                 })
                 .collect();
@@ -59,7 +65,10 @@ pub fn apply_template(dir: &str, name: &str, args: &[String], validate: bool) ->
         }
     }
 
-    match boruna_tooling::templates::apply_template(path, name, &arg_map) {
+    let cache = Path::new(remote::DEFAULT_CACHE_DIR);
+    let applied = remote::resolve_template_dir(path, cache, name)
+        .and_then(|dir| boruna_tooling::templates::apply_template(&dir, name, &arg_map));
+    match applied {
         Ok(result) => {
             let mut json = serde_json::json!({
                 "success": true,
@@ -69,6 +78,7 @@ pub fn apply_template(dir: &str, name: &str, args: &[String], validate: bool) ->
                 "source": result.source,
                 "dependencies": result.dependencies,
                 "capabilities": result.capabilities,
+                "tests": result.tests,
             });
 
             if validate {
@@ -1,6 +1,22 @@
-use boruna_orchestrator::workflow::definition::WorkflowDef;
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+
+use base64::Engine as _;
+use boruna_bytecode::Value;
+use boruna_orchestrator::audit::{AuditEvent, AuditLog, BundleManifest, EvidenceBundleBuilder};
+use boruna_orchestrator::workflow::definition::{
+    StepKind, StepStatus, WorkflowDef, WorkflowRunResult,
+};
 use boruna_orchestrator::workflow::validator::WorkflowValidator;
+use boruna_orchestrator::workflow::{
+    propagate_classifications, DataStore, RunOptions, WorkflowRunError, WorkflowRunner,
+};
+use boruna_vm::capability_gateway::Policy;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde_json::Value as JsonValue;
 
+use super::run::parse_policy;
 use super::TOOL_RESPONSE_PROTOCOL_VERSION;
 
 /// Validate a workflow definition (JSON string).
@@ -55,3 +71,449 @@ pub fn validate_workflow(workflow_json: &str) -> String {
         }
     }
 }
+
+/// Cap on one inline step source, matching the coordinator's
+/// inline-source submission.
+const MAX_STEP_SOURCE_BYTES: usize = 256 * 1024;
+
+/// Cap on all inline step sources of one run.
+const MAX_AGGREGATE_SOURCE_BYTES: usize = 4 * 1024 * 1024;
+
+/// Limits applied to a single `boruna_workflow_run` invocation.
+///
+/// `None` on any field = no limit beyond the fixed inline-source caps
+/// (256 KiB per step, 4 MiB in total). Hitting a limit returns
+/// `success: false, error_kind: "limit_exceeded"` with a `limit_kind`
+/// of `"steps"`, `"source_bytes"` or `"bundle_bytes"`.
+#[derive(Debug, Default, Clone)]
+pub struct WorkflowRunLimits {
+    /// Steps the workflow may declare. Checked before anything runs.
+    pub max_steps: Option<usize>,
+    /// Size of the base64-encoded evidence archive. Checked after the
+    /// run, so the step results are still returned when it is exceeded.
+    pub max_bundle_bytes: Option<u64>,
+}
+
+fn failure(error_kind: &str, message: impl Into<String>) -> String {
+    serde_json::json!({
+        "success": false,
+        "protocol_version": TOOL_RESPONSE_PROTOCOL_VERSION,
+        "error_kind": error_kind,
+        "message": message.into(),
+    })
+    .to_string()
+}
+
+fn limit_exceeded(limit_kind: &str, message: String) -> String {
+    serde_json::json!({
+        "success": false,
+        "protocol_version": TOOL_RESPONSE_PROTOCOL_VERSION,
+        "error_kind": "limit_exceeded",
+        "limit_kind": limit_kind,
+        "message": message,
+    })
+    .to_string()
+}
+
+/// Run an inline workflow definition and return its step results with
+/// the evidence bundle as a base64 `tar.gz` archive.
+///
+/// `sources` maps each `source` step's id to its `.ax` body. The bodies
+/// are written under a scratch workflow directory at the paths the
+/// definition declares, so the recorded `workflow.json` is the one the
+/// caller sent. `policy` takes the same shapes as `boruna_run`.
+pub fn run_workflow(
+    workflow_json: &str,
+    sources: &BTreeMap<String, String>,
+    policy: Option<&JsonValue>,
+    limits: &WorkflowRunLimits,
+) -> String {
+    let def: WorkflowDef = match serde_json::from_str(workflow_json) {
+        Ok(d) => d,
+        Err(e) => return failure("parse_error", format!("invalid workflow JSON: {e}")),
+    };
+    let policy = match parse_policy(policy) {
+        Ok(p) => p,
+        Err(e) => return failure(&e.error_kind, e.message),
+    };
+    if let Some(max) = limits.max_steps.filter(|max| def.steps.len() > *max) {
+        return limit_exceeded(
+            "steps",
+            format!(
+                "workflow declares {} steps, over max_steps ({max})",
+                def.steps.len()
+            ),
+        );
+    }
+
+    let total: usize = sources.values().map(String::len).sum();
+    if let Some((id, _)) = sources
+        .iter()
+        .find(|(_, body)| body.len() > MAX_STEP_SOURCE_BYTES)
+    {
+        return limit_exceeded(
+            "source_bytes",
+            format!("source for step '{id}' exceeds {MAX_STEP_SOURCE_BYTES} bytes"),
+        );
+    }
+    if total > MAX_AGGREGATE_SOURCE_BYTES {
+        return limit_exceeded(
+            "source_bytes",
+            format!("step sources total {total} bytes, over {MAX_AGGREGATE_SOURCE_BYTES}"),
+        );
+    }
+
+    let work = match tempfile::tempdir() {
+        Ok(d) => d,
+        Err(e) => return failure("io_error", format!("cannot create workflow dir: {e}")),
+    };
+    if let Err(e) = write_sources(&def, sources, work.path()) {
+        return e;
+    }
+
+    let options = RunOptions {
+        policy: Some(policy.clone()),
+        record: true,
+        workflow_dir: work.path().display().to_string(),
+        live: false,
+        concurrency: 1,
+        submit_only: false,
+        params: Default::default(),
+        profile: None,
+        container: None,
+    };
+    let result = match WorkflowRunner::run(&def, &options) {
+        Ok(r) => r,
+        Err(WorkflowRunError::Validation(message)) => {
+            return failure("validation_error", message);
+        }
+        Err(e) => return failure("workflow_error", e.to_string()),
+    };
+
+    let step_results: serde_json::Map<String, JsonValue> = result
+        .step_results
+        .iter()
+        .map(|(id, sr)| {
+            let mut entry = serde_json::json!({
+                "status": sr.status,
+                "duration_ms": sr.duration_ms,
+                "attempt_count": sr.attempt_count,
+                "capabilities_used": sr.capabilities_used,
+                "output_hash": sr.output_hash,
+            });
+            if let Some(err) = &sr.error {
+                entry["error"] = serde_json::json!(err);
+            }
+            (id.clone(), entry)
+        })
+        .collect();
+    let mut response = serde_json::json!({
+        "success": true,
+        "protocol_version": TOOL_RESPONSE_PROTOCOL_VERSION,
+        "run_id": result.run_id,
+        "workflow_name": result.workflow_name,
+        "status": result.status,
+        "total_duration_ms": result.total_duration_ms,
+        "step_results": step_results,
+    });
+
+    let evidence_dir = work.path().join(".evidence");
+    let manifest = match record_evidence(&evidence_dir, workflow_json, &def, &policy, &result) {
+        Ok(m) => m,
+        Err(e) => return failure("io_error", format!("cannot write evidence bundle: {e}")),
+    };
+    let archive = match archive_dir(&evidence_dir.join(&result.run_id)) {
+        Ok(a) => base64::engine::general_purpose::STANDARD.encode(a),
+        Err(e) => return failure("io_error", format!("cannot archive evidence bundle: {e}")),
+    };
+    if let Some(max) = limits
+        .max_bundle_bytes
+        .filter(|max| archive.len() as u64 > *max)
+    {
+        response["success"] = serde_json::json!(false);
+        response["error_kind"] = serde_json::json!("limit_exceeded");
+        response["limit_kind"] = serde_json::json!("bundle_bytes");
+        response["message"] = serde_json::json!(format!(
+            "evidence archive is {} bytes, over max_bundle_bytes ({max})",
+            archive.len()
+        ));
+        return response.to_string();
+    }
+    response["evidence"] = serde_json::json!({
+        "format": "tar+gzip",
+        "encoding": "base64",
+        "bundle_hash": manifest.bundle_hash,
+        "audit_log_hash": manifest.audit_log_hash,
+        "files": manifest.file_checksums.len(),
+        "archive": archive,
+    });
+    response.to_string()
+}
+
+/// Write each source step's body to its declared path under `dir`.
+/// Returns a finished failure response on a missing, unknown or
+/// escaping source.
+fn write_sources(
+    def: &WorkflowDef,
+    sources: &BTreeMap<String, String>,
+    dir: &Path,
+) -> Result<(), String> {
+    if let Some(id) = sources.keys().find(|id| !def.steps.contains_key(*id)) {
+        return Err(failure(
+            "invalid_sources",
+            format!("source given for unknown step '{id}'"),
+        ));
+    }
+    for (id, step) in &def.steps {
+        let StepKind::Source { source: rel } = &step.kind else {
+            continue;
+        };
+        let Some(body) = sources.get(id) else {
+            return Err(failure(
+                "invalid_sources",
+                format!("missing inline source for source step '{id}'"),
+            ));
+        };
+        let rel = Path::new(rel);
+        if !rel
+            .components()
+            .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+        {
+            return Err(failure(
+                "invalid_sources",
+                format!(
+                    "step '{id}' source path '{}' must stay inside the workflow directory",
+                    rel.display()
+                ),
+            ));
+        }
+        let path = dir.join(rel);
+        let written = path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|()| std::fs::write(&path, body));
+        if let Err(e) = written {
+            return Err(failure(
+                "io_error",
+                format!("cannot write source for step '{id}': {e}"),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Build the evidence bundle for `result` the way `boruna workflow run
+/// --record` does: definition, policy, per-step event logs and the
+/// audit log.
+fn record_evidence(
+    evidence_dir: &Path,
+    workflow_json: &str,
+    def: &WorkflowDef,
+    policy: &Policy,
+    result: &WorkflowRunResult,
+) -> std::io::Result<BundleManifest> {
+    let mut builder = EvidenceBundleBuilder::new(evidence_dir, &result.run_id, &def.name)?;
+    builder.add_workflow_def(workflow_json)?;
+    let policy_json = serde_json::to_string_pretty(policy)?;
+    builder.add_policy(&policy_json)?;
+    // Event logs of steps whose output the policy redacts carry the same
+    // data, so they stay out of the bundle.
+    let labels = propagate_classifications(def);
+    for (id, sr) in &result.step_results {
+        let label = labels.get(id).copied().unwrap_or_default();
+        if policy
+            .data_flow
+            .as_ref()
+            .is_some_and(|df| df.redacts(label))
+        {
+            continue;
+        }
+        if let Some(log) = &sr.event_log {
+            builder.add_event_log(id, &log.to_json().map_err(std::io::Error::other)?)?;
+        }
+    }
+
+    let mut audit = AuditLog::new();
+    audit.append(AuditEvent::WorkflowStarted {
+        workflow_hash: DataStore::hash_value(&Value::String(workflow_json.to_string())),
+        policy_hash: DataStore::hash_value(&Value::String(policy_json)),
+    });
+    for (id, sr) in &result.step_results {
+        let event = match sr.status {
+            StepStatus::Completed => AuditEvent::StepCompleted {
+                step_id: id.clone(),
+                output_hash: sr.output_hash.clone().unwrap_or_default(),
+                duration_ms: sr.duration_ms,
+            },
+            StepStatus::Failed => AuditEvent::StepFailed {
+                step_id: id.clone(),
+                error: sr.error.clone().unwrap_or_default(),
+            },
+            _ => continue,
+        };
+        audit.append(event);
+    }
+    audit.append(AuditEvent::WorkflowCompleted {
+        result_hash: format!("{:?}", result.status),
+        total_duration_ms: result.total_duration_ms,
+    });
+    builder.finalize(&audit)
+}
+
+/// Pack `dir` into a gzip-compressed tar archive. Entries are added in
+/// sorted path order with normalized headers, so equal bundles pack to
+/// equal archives.
+fn archive_dir(dir: &Path) -> std::io::Result<Vec<u8>> {
+    fn collect(root: &Path, dir: &Path, out: &mut Vec<PathBuf>) -> std::io::Result<()> {
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                collect(root, &path, out)?;
+            } else {
+                out.push(path.strip_prefix(root).unwrap_or(&path).to_path_buf());
+            }
+        }
+        Ok(())
+    }
+    let mut files = Vec::new();
+    collect(dir, dir, &mut files)?;
+    files.sort();
+
+    let gz = GzEncoder::new(Vec::new(), Compression::default());
+    let mut tar = tar::Builder::new(gz);
+    tar.mode(tar::HeaderMode::Deterministic);
+    for rel in &files {
+        tar.append_path_with_name(dir.join(rel), rel)?;
+    }
+    tar.into_inner()?.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+
+    const WORKFLOW: &str = r#"{
+      "schema_version": 1,
+      "name": "pipeline",
+      "version": "1.0.0",
+      "steps": {
+        "fetch": { "kind": "source", "source": "steps/fetch.ax", "outputs": { "result": "Int" } },
+        "sum": {
+          "kind": "source",
+          "source": "steps/sum.ax",
+          "depends_on": ["fetch"],
+          "inputs": { "n": "fetch.result" },
+          "outputs": { "result": "Int" }
+        }
+      },
+      "edges": [["fetch", "sum"]]
+    }"#;
+
+    fn sources() -> BTreeMap<String, String> {
+        BTreeMap::from([
+            ("fetch".to_string(), "fn main() -> Int { 20 }\n".to_string()),
+            (
+                "sum".to_string(),
+                "fn main() -> Int { 1 + 2 }\n".to_string(),
+            ),
+        ])
+    }
+
+    fn parse(json: &str) -> JsonValue {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn run_returns_step_results_and_a_verifiable_archive() {
+        let out = parse(&run_workflow(
+            WORKFLOW,
+            &sources(),
+            None,
+            &WorkflowRunLimits::default(),
+        ));
+        assert_eq!(out["success"], true, "{out}");
+        assert_eq!(out["status"], "completed");
+        assert_eq!(out["step_results"]["fetch"]["status"], "completed");
+        assert_eq!(out["step_results"]["sum"]["status"], "completed");
+        assert_eq!(out["evidence"]["format"], "tar+gzip");
+
+        let archive = base64::engine::general_purpose::STANDARD
+            .decode(out["evidence"]["archive"].as_str().unwrap())
+            .unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        tar::Archive::new(GzDecoder::new(archive.as_slice()))
+            .unpack(dir.path())
+            .unwrap();
+        let recorded = std::fs::read_to_string(dir.path().join("workflow.json")).unwrap();
+        assert_eq!(recorded, WORKFLOW);
+        let manifest: JsonValue =
+            parse(&std::fs::read_to_string(dir.path().join("manifest.json")).unwrap());
+        assert_eq!(manifest["bundle_hash"], out["evidence"]["bundle_hash"]);
+        let verified = boruna_orchestrator::audit::verify_bundle(dir.path());
+        assert!(verified.valid, "{:?}", verified.errors);
+    }
+
+    #[test]
+    fn sources_must_cover_every_source_step_and_stay_inside() {
+        let mut partial = sources();
+        partial.remove("sum");
+        let out = parse(&run_workflow(
+            WORKFLOW,
+            &partial,
+            None,
+            &WorkflowRunLimits::default(),
+        ));
+        assert_eq!(out["error_kind"], "invalid_sources");
+        assert!(out["message"].as_str().unwrap().contains("'sum'"));
+
+        let escaping = WORKFLOW.replace("steps/fetch.ax", "../fetch.ax");
+        let out = parse(&run_workflow(
+            &escaping,
+            &sources(),
+            None,
+            &WorkflowRunLimits::default(),
+        ));
+        assert_eq!(out["error_kind"], "invalid_sources");
+    }
+
+    #[test]
+    fn limits_are_reported_with_their_kind() {
+        let out = parse(&run_workflow(
+            WORKFLOW,
+            &sources(),
+            None,
+            &WorkflowRunLimits {
+                max_steps: Some(1),
+                ..Default::default()
+            },
+        ));
+        assert_eq!(out["error_kind"], "limit_exceeded");
+        assert_eq!(out["limit_kind"], "steps");
+
+        let out = parse(&run_workflow(
+            WORKFLOW,
+            &sources(),
+            None,
+            &WorkflowRunLimits {
+                max_bundle_bytes: Some(16),
+                ..Default::default()
+            },
+        ));
+        assert_eq!(out["error_kind"], "limit_exceeded");
+        assert_eq!(out["limit_kind"], "bundle_bytes");
+        assert_eq!(out["step_results"]["sum"]["status"], "completed");
+        assert!(out.get("evidence").is_none());
+    }
+
+    #[test]
+    fn policy_errors_surface_before_the_run() {
+        let out = parse(&run_workflow(
+            WORKFLOW,
+            &sources(),
+            Some(&serde_json::json!("allow-some")),
+            &WorkflowRunLimits::default(),
+        ));
+        assert_eq!(out["error_kind"], "invalid_policy");
+    }
+}
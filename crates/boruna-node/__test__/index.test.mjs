@@ -0,0 +1,87 @@
+import assert from 'node:assert/strict'
+import { createRequire } from 'node:module'
+import test from 'node:test'
+
+const { AppRuntime, TestHarness } = createRequire(import.meta.url)('../index.js')
+
+const COUNTER_APP = `
+type State { count: Int, label: String }
+type Msg { tag: String, payload: Int }
+type Effect { kind: String, payload: String, callback_tag: String }
+type UpdateResult { state: State, effects: List<Effect> }
+type UINode { tag: String, text: String }
+
+fn init() -> State {
+    State { count: 0, label: "clicks" }
+}
+
+fn update(state: State, msg: Msg) -> UpdateResult {
+    let new_count: Int = if msg.tag == "increment" {
+        state.count + msg.payload
+    } else {
+        state.count
+    }
+    UpdateResult {
+        state: State { count: new_count, label: state.label },
+        effects: [],
+    }
+}
+
+fn view(state: State) -> UINode {
+    UINode { tag: "counter", text: state.label }
+}
+`
+
+test('send returns named state and the UI tree', () => {
+  const app = new AppRuntime(COUNTER_APP)
+  assert.deepEqual(app.state, { count: 0, label: 'clicks' })
+  assert.deepEqual(app.view(), { tag: 'counter', text: 'clicks' })
+
+  const { state, effects, ui } = app.send('increment', 2)
+  assert.deepEqual(state, { count: 2, label: 'clicks' })
+  assert.deepEqual(effects, [])
+  assert.deepEqual(ui, { tag: 'counter', text: 'clicks' })
+  assert.equal(app.cycle, 1)
+})
+
+test('cycle log and rewind', () => {
+  const app = new AppRuntime(COUNTER_APP)
+  app.send('increment', 2)
+  app.send('increment', 3)
+  const log = app.cycleLog()
+  assert.deepEqual(log.map((c) => c.cycle), [1, 2])
+  assert.deepEqual(log[1].stateBefore, { count: 2, label: 'clicks' })
+  assert.deepEqual(log[1].stateAfter, { count: 5, label: 'clicks' })
+  assert.equal(log[1].payload, 3)
+
+  app.rewind(1)
+  assert.equal(app.state.count, 2)
+  assert.equal(typeof app.snapshot(), 'object')
+})
+
+test('harness is deterministic', () => {
+  const run = () => {
+    const h = new TestHarness(COUNTER_APP)
+    for (let n = 0; n < 10; n++) {
+      h.advanceClock(100)
+      h.send('increment', n)
+    }
+    return h.cycleLog()
+  }
+  assert.deepEqual(run(), run())
+  assert.equal(run().at(-1).timestampMs, 1000)
+})
+
+test('simulate returns the final state', () => {
+  const h = new TestHarness(COUNTER_APP)
+  const state = h.simulate([{ tag: 'increment', payload: 4 }, { tag: 'noop' }, { tag: 'increment', payload: 1 }])
+  assert.deepEqual(state, { count: 5, label: 'clicks' })
+  assert.equal(h.cycle, 3)
+})
+
+test('errors are thrown', () => {
+  assert.throws(() => new AppRuntime('fn main( {'), /parse|expected/)
+  assert.throws(() => new TestHarness('fn main() -> Int { 1 }\n'), /update|init|missing/)
+  const h = new TestHarness(COUNTER_APP)
+  assert.throws(() => h.advanceClock(-1), /negative/)
+})
@@ -0,0 +1,66 @@
+/* Type definitions for the `boruna-node` bindings. Keep in step with src/lib.rs. */
+
+/** An effect returned by `update`. The host runs it and replies with a `callbackTag` message. */
+export interface AppEffect {
+  kind: string
+  payload: any
+  callbackTag: string
+}
+/** The outcome of one `send`. */
+export interface CycleResult {
+  state: any
+  effects: Array<AppEffect>
+  /** The tree `view` rendered for the new state. */
+  ui: any
+}
+/** One entry of `cycleLog()`. */
+export interface Cycle {
+  cycle: number
+  timestampMs: number
+  tag: string
+  payload: any
+  stateBefore: any
+  stateAfter: any
+  effects: Array<AppEffect>
+  ui: any
+}
+/** A message for `TestHarness.simulate`. */
+export interface Message {
+  tag: string
+  payload?: any
+}
+/**
+ * A framework app (`init`/`update`/`view`). State changes only through
+ * `send`, and the same messages always produce the same states, effects
+ * and UI trees.
+ */
+export declare class AppRuntime {
+  /** Compile `source` and run `init`. */
+  constructor(source: string)
+  get state(): any
+  get cycle(): number
+  /** Deliver one message: `update`, then `view`. Effects are returned, not run. */
+  send(tag: string, payload?: any): CycleResult
+  /** Render the current state without sending a message. */
+  view(): any
+  /** The state snapshot, as `boruna framework` writes it. */
+  snapshot(): any
+  cycleLog(): Array<Cycle>
+  /** Restore the state as of `cycle`. */
+  rewind(cycle: number): void
+}
+/** An `AppRuntime` for tests, with a virtual clock that only moves through `advanceClock`. */
+export declare class TestHarness {
+  constructor(source: string)
+  get state(): any
+  get cycle(): number
+  send(tag: string, payload?: any): CycleResult
+  /** Send each message in turn and return the final state. */
+  simulate(messages: Array<Message>): any
+  /** Move the virtual clock forward by `ms`. */
+  advanceClock(ms: number): void
+  view(): any
+  snapshot(): any
+  cycleLog(): Array<Cycle>
+  rewind(cycle: number): void
+}
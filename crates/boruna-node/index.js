@@ -0,0 +1,4 @@
+'use strict'
+
+// `boruna.node` is the `boruna-node` cdylib; `npm run build` places it here.
+module.exports = require('./boruna.node')
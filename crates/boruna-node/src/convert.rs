@@ -0,0 +1,165 @@
+//! Boruna values to and from the JSON handed to JavaScript.
+
+use boruna_bytecode::module::{TypeDef, TypeKind};
+use boruna_bytecode::Value;
+use serde_json::{json, Value as JsonValue};
+
+/// A value as JSON a React component can consume directly.
+///
+/// Records become objects keyed by their declared field names and enums
+/// become `{ type, variant, payload }`, resolved through the module's type
+/// table. `Decimal` and `BigInt` become strings, since a JS number cannot
+/// hold them exactly. Anything else takes the shape `boruna_run` uses over
+/// MCP.
+pub(crate) fn to_json(types: &[TypeDef], value: &Value) -> JsonValue {
+    let all =
+        |items: &[Value]| -> Vec<JsonValue> { items.iter().map(|v| to_json(types, v)).collect() };
+    match value {
+        Value::Int(n) => json!(n),
+        Value::Float(f) => json!(f),
+        Value::Decimal(d) => json!(d.to_string()),
+        Value::BigInt(n) => json!(n.to_string()),
+        Value::String(s) => json!(s),
+        Value::Bool(b) => json!(b),
+        Value::Unit => JsonValue::Null,
+        Value::None => json!({ "option": "None" }),
+        Value::Some(v) => json!({ "option": "Some", "value": to_json(types, v) }),
+        Value::Ok(v) => json!({ "result": "Ok", "value": to_json(types, v) }),
+        Value::Err(v) => json!({ "result": "Err", "value": to_json(types, v) }),
+        Value::List(items) => json!(all(items)),
+        Value::Record { type_id, fields } => match types.get(*type_id as usize) {
+            Some(TypeDef {
+                kind: TypeKind::Record { fields: names },
+                ..
+            }) if names.len() == fields.len() => JsonValue::Object(
+                names
+                    .iter()
+                    .zip(fields)
+                    .map(|((name, _), v)| (name.clone(), to_json(types, v)))
+                    .collect(),
+            ),
+            _ => json!({ "type": "record", "type_id": type_id, "fields": all(fields) }),
+        },
+        Value::Enum {
+            type_id,
+            variant,
+            payload,
+        } => match types.get(*type_id as usize) {
+            Some(TypeDef {
+                name,
+                kind: TypeKind::Enum { variants },
+            }) if (*variant as usize) < variants.len() => json!({
+                "type": name,
+                "variant": variants[*variant as usize].0,
+                "payload": to_json(types, payload),
+            }),
+            _ => json!({
+                "type": "enum",
+                "type_id": type_id,
+                "variant": variant,
+                "payload": to_json(types, payload),
+            }),
+        },
+        Value::Map(entries) => JsonValue::Object(
+            entries
+                .iter()
+                .map(|(k, v)| (k.clone(), to_json(types, v)))
+                .collect(),
+        ),
+        Value::ActorId(id) => json!({ "actor_id": id }),
+        Value::FnRef(idx) => json!({ "fn_ref": idx }),
+        Value::CapHandle(id) => json!({ "cap_handle": id }),
+        Value::Table(t) => {
+            let rows: Vec<Vec<JsonValue>> = (0..t.num_rows())
+                .map(|r| {
+                    t.columns()
+                        .iter()
+                        .map(|c| to_json(types, &c.values[r]))
+                        .collect()
+                })
+                .collect();
+            json!({ "type": "table", "columns": t.column_names(), "rows": rows })
+        }
+    }
+}
+
+/// A JS message payload as a value. Integers that fit `i64` become
+/// `Int`, other numbers `Float`; `null` is `Unit`; objects become `Map`.
+pub(crate) fn from_json(json: &JsonValue) -> Value {
+    match json {
+        JsonValue::Null => Value::Unit,
+        JsonValue::Bool(b) => Value::Bool(*b),
+        JsonValue::Number(n) => match n.as_i64() {
+            Some(i) => Value::Int(i),
+            None => Value::Float(n.as_f64().unwrap_or(f64::NAN)),
+        },
+        JsonValue::String(s) => Value::String(s.clone()),
+        JsonValue::Array(items) => Value::List(items.iter().map(from_json).collect()),
+        JsonValue::Object(entries) => Value::Map(
+            entries
+                .iter()
+                .map(|(k, v)| (k.clone(), from_json(v)))
+                .collect(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn types() -> Vec<TypeDef> {
+        let source = "type Point { x: Int, y: Int }\n\
+                      enum Shape { Dot, Circle(Int) }\n\
+                      fn main() -> Int { 0 }\n";
+        boruna_compiler::compile("t", source).unwrap().types
+    }
+
+    #[test]
+    fn records_and_enums_use_declared_names() {
+        let types = types();
+        let point = types.iter().position(|t| t.name == "Point").unwrap() as u32;
+        let shape = types.iter().position(|t| t.name == "Shape").unwrap() as u32;
+
+        let record = Value::Record {
+            type_id: point,
+            fields: vec![Value::Int(1), Value::Int(2)],
+        };
+        assert_eq!(to_json(&types, &record), json!({ "x": 1, "y": 2 }));
+
+        let circle = Value::Enum {
+            type_id: shape,
+            variant: 1,
+            payload: Box::new(Value::Int(3)),
+        };
+        assert_eq!(
+            to_json(&types, &circle),
+            json!({ "type": "Shape", "variant": "Circle", "payload": 3 })
+        );
+    }
+
+    #[test]
+    fn unknown_types_fall_back_to_positional_fields() {
+        let record = Value::Record {
+            type_id: 99,
+            fields: vec![Value::Bool(true)],
+        };
+        assert_eq!(
+            to_json(&[], &record),
+            json!({ "type": "record", "type_id": 99, "fields": [true] })
+        );
+    }
+
+    #[test]
+    fn payloads_map_numbers_by_kind() {
+        let payload = json!({ "n": 3, "f": 2.5, "xs": [null, "a"] });
+        let mut expected = std::collections::BTreeMap::new();
+        expected.insert("n".to_string(), Value::Int(3));
+        expected.insert("f".to_string(), Value::Float(2.5));
+        expected.insert(
+            "xs".to_string(),
+            Value::List(vec![Value::Unit, Value::String("a".into())]),
+        );
+        assert_eq!(from_json(&payload), Value::Map(expected));
+    }
+}
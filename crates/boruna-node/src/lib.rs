@@ -0,0 +1,269 @@
+//! Node.js bindings for the framework runtime: `require("boruna")`.
+//!
+//! ```js
+//! const { AppRuntime } = require("boruna");
+//!
+//! const app = new AppRuntime(source);
+//! const { state, effects, ui } = app.send("increment", 1);
+//! // render `ui` with React; `state` only changes through `send`
+//! ```
+//!
+//! Values reach JavaScript as plain JSON (see [`convert::to_json`]), so a
+//! view's UI tree can be handed to a component as props.
+
+mod convert;
+
+use boruna_bytecode::module::TypeDef;
+use boruna_bytecode::Value;
+use boruna_framework::effect::Effect;
+use boruna_framework::runtime::AppMessage;
+use boruna_framework::{AppRuntime as FwRuntime, FrameworkError, TestHarness as FwHarness};
+use napi::{Error, Result};
+use napi_derive::napi;
+use serde_json::Value as JsonValue;
+
+use crate::convert::{from_json, to_json};
+
+/// An effect returned by `update`. The host runs it and replies with a
+/// `callbackTag` message.
+#[napi(object)]
+pub struct AppEffect {
+    pub kind: String,
+    pub payload: JsonValue,
+    pub callback_tag: String,
+}
+
+/// The outcome of one `send`.
+#[napi(object)]
+pub struct CycleResult {
+    pub state: JsonValue,
+    pub effects: Vec<AppEffect>,
+    /// The tree `view` rendered for the new state.
+    pub ui: JsonValue,
+}
+
+/// One entry of `cycleLog()`.
+#[napi(object)]
+pub struct Cycle {
+    pub cycle: i64,
+    pub timestamp_ms: i64,
+    pub tag: String,
+    pub payload: JsonValue,
+    pub state_before: JsonValue,
+    pub state_after: JsonValue,
+    pub effects: Vec<AppEffect>,
+    pub ui: JsonValue,
+}
+
+/// A message for `TestHarness.simulate`.
+#[napi(object)]
+pub struct Message {
+    pub tag: String,
+    pub payload: Option<JsonValue>,
+}
+
+/// A framework app (`init`/`update`/`view`). State changes only through
+/// `send`, and the same messages always produce the same states, effects
+/// and UI trees.
+#[napi]
+pub struct AppRuntime {
+    inner: FwRuntime,
+}
+
+#[napi]
+impl AppRuntime {
+    /// Compile `source` and run `init`.
+    #[napi(constructor)]
+    pub fn new(source: String) -> Result<Self> {
+        let module = boruna_compiler::compile("app", &source).map_err(|e| to_error(e.into()))?;
+        FwRuntime::new(module)
+            .map(|inner| AppRuntime { inner })
+            .map_err(to_error)
+    }
+
+    #[napi(getter)]
+    pub fn state(&self) -> JsonValue {
+        state(&self.inner)
+    }
+
+    #[napi(getter)]
+    pub fn cycle(&self) -> i64 {
+        self.inner.cycle() as i64
+    }
+
+    /// Deliver one message: `update`, then `view`. Effects are returned,
+    /// not run.
+    #[napi]
+    pub fn send(&mut self, tag: String, payload: Option<JsonValue>) -> Result<CycleResult> {
+        let (state, effects, ui) = self.inner.send(message(tag, payload)).map_err(to_error)?;
+        let types = &self.inner.module().types;
+        Ok(CycleResult {
+            state: to_json(types, &state),
+            effects: effects_to_js(types, &effects),
+            ui: ui.map_or(JsonValue::Null, |ui| to_json(types, &ui)),
+        })
+    }
+
+    /// Render the current state without sending a message.
+    #[napi]
+    pub fn view(&self) -> Result<JsonValue> {
+        view(&self.inner)
+    }
+
+    /// The state snapshot, as `boruna framework` writes it.
+    #[napi]
+    pub fn snapshot(&self) -> Result<JsonValue> {
+        snapshot(&self.inner)
+    }
+
+    #[napi]
+    pub fn cycle_log(&self) -> Vec<Cycle> {
+        cycle_log(&self.inner)
+    }
+
+    /// Restore the state as of `cycle`.
+    #[napi]
+    pub fn rewind(&mut self, cycle: i64) -> Result<()> {
+        self.inner.rewind(to_cycle(cycle)?).map_err(to_error)
+    }
+}
+
+/// An `AppRuntime` for tests, with a virtual clock that only moves
+/// through `advanceClock`.
+#[napi]
+pub struct TestHarness {
+    inner: FwHarness,
+}
+
+#[napi]
+impl TestHarness {
+    #[napi(constructor)]
+    pub fn new(source: String) -> Result<Self> {
+        FwHarness::from_source(&source)
+            .map(|inner| TestHarness { inner })
+            .map_err(to_error)
+    }
+
+    #[napi(getter)]
+    pub fn state(&self) -> JsonValue {
+        state(self.inner.runtime())
+    }
+
+    #[napi(getter)]
+    pub fn cycle(&self) -> i64 {
+        self.inner.cycle() as i64
+    }
+
+    #[napi]
+    pub fn send(&mut self, tag: String, payload: Option<JsonValue>) -> Result<CycleResult> {
+        self.inner.send(message(tag, payload)).map_err(to_error)?;
+        let runtime = self.inner.runtime();
+        let types = &runtime.module().types;
+        let last = runtime.cycle_log().last().expect("send logs a cycle");
+        Ok(CycleResult {
+            state: to_json(types, &last.state_after),
+            effects: effects_to_js(types, &last.effects),
+            ui: last
+                .ui_tree
+                .as_ref()
+                .map_or(JsonValue::Null, |ui| to_json(types, ui)),
+        })
+    }
+
+    /// Send each message in turn and return the final state.
+    #[napi]
+    pub fn simulate(&mut self, messages: Vec<Message>) -> Result<JsonValue> {
+        let messages = messages
+            .into_iter()
+            .map(|m| message(m.tag, m.payload))
+            .collect();
+        let state = self.inner.simulate(messages).map_err(to_error)?;
+        Ok(to_json(&self.inner.runtime().module().types, &state))
+    }
+
+    /// Move the virtual clock forward by `ms`.
+    #[napi]
+    pub fn advance_clock(&mut self, ms: i64) -> Result<()> {
+        let ms = u64::try_from(ms).map_err(|_| Error::from_reason("ms must not be negative"))?;
+        self.inner.advance_clock(ms);
+        Ok(())
+    }
+
+    #[napi]
+    pub fn view(&self) -> Result<JsonValue> {
+        view(self.inner.runtime())
+    }
+
+    #[napi]
+    pub fn snapshot(&self) -> Result<JsonValue> {
+        snapshot(self.inner.runtime())
+    }
+
+    #[napi]
+    pub fn cycle_log(&self) -> Vec<Cycle> {
+        cycle_log(self.inner.runtime())
+    }
+
+    #[napi]
+    pub fn rewind(&mut self, cycle: i64) -> Result<()> {
+        self.inner.rewind(to_cycle(cycle)?).map_err(to_error)
+    }
+}
+
+fn state(runtime: &FwRuntime) -> JsonValue {
+    to_json(&runtime.module().types, runtime.state())
+}
+
+fn view(runtime: &FwRuntime) -> Result<JsonValue> {
+    let ui = runtime.view().map_err(to_error)?;
+    Ok(to_json(&runtime.module().types, &ui))
+}
+
+fn snapshot(runtime: &FwRuntime) -> Result<JsonValue> {
+    serde_json::from_str(&runtime.snapshot()).map_err(|e| Error::from_reason(e.to_string()))
+}
+
+fn cycle_log(runtime: &FwRuntime) -> Vec<Cycle> {
+    let types = &runtime.module().types;
+    runtime
+        .cycle_log()
+        .iter()
+        .map(|r| Cycle {
+            cycle: r.cycle as i64,
+            timestamp_ms: r.timestamp_ms as i64,
+            tag: r.message.tag.clone(),
+            payload: to_json(types, &r.message.payload),
+            state_before: to_json(types, &r.state_before),
+            state_after: to_json(types, &r.state_after),
+            effects: effects_to_js(types, &r.effects),
+            ui: r
+                .ui_tree
+                .as_ref()
+                .map_or(JsonValue::Null, |ui| to_json(types, ui)),
+        })
+        .collect()
+}
+
+fn effects_to_js(types: &[TypeDef], effects: &[Effect]) -> Vec<AppEffect> {
+    effects
+        .iter()
+        .map(|e| AppEffect {
+            kind: e.kind.as_str().to_string(),
+            payload: to_json(types, &e.payload),
+            callback_tag: e.callback_tag.clone(),
+        })
+        .collect()
+}
+
+fn message(tag: String, payload: Option<JsonValue>) -> AppMessage {
+    AppMessage::new(tag, payload.as_ref().map_or(Value::Unit, from_json))
+}
+
+fn to_cycle(cycle: i64) -> Result<u64> {
+    u64::try_from(cycle).map_err(|_| Error::from_reason("cycle must not be negative"))
+}
+
+/// Thrown errors carry the framework's message.
+fn to_error(e: FrameworkError) -> Error {
+    Error::from_reason(e.to_string())
+}
@@ -0,0 +1,46 @@
+from typing import Any, Literal, Optional, Union
+
+__version__: str
+
+class BorunaError(Exception): ...
+
+class CompileError(BorunaError):
+    code: str
+    line: Optional[int]
+    col: Optional[int]
+
+class ExecutionError(BorunaError): ...
+class LimitExceeded(ExecutionError): ...
+
+class PolicyError(BorunaError):
+    error_kind: str
+
+class FrameworkError(BorunaError): ...
+
+class Module:
+    @property
+    def name(self) -> str: ...
+    def to_bytes(self) -> bytes: ...
+    @staticmethod
+    def from_bytes(data: bytes) -> Module: ...
+
+def compile(source: str, name: str = "module") -> Module: ...
+def run(
+    module: Module,
+    policy: Union[Literal["allow-all", "deny-all"], dict[str, Any], None] = None,
+    max_steps: Optional[int] = None,
+    record: bool = False,
+) -> dict[str, Any]: ...
+
+class TestHarness:
+    def __init__(self, source: str) -> None: ...
+    @property
+    def state(self) -> Any: ...
+    @property
+    def cycle(self) -> int: ...
+    def send(self, tag: str, payload: Any = None) -> tuple[Any, list[dict[str, Any]]]: ...
+    def advance_clock(self, ms: int) -> None: ...
+    def snapshot(self) -> Any: ...
+    def cycle_log(self) -> list[dict[str, Any]]: ...
+    def view(self) -> Any: ...
+    def rewind(self, cycle: int) -> None: ...
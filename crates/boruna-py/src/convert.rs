@@ -0,0 +1,160 @@
+//! Boruna values to and from Python objects.
+
+use boruna_bytecode::{BigInt, Decimal, Value};
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyDict, PyFloat, PyInt, PyList, PyString, PyTuple};
+use pyo3::IntoPyObjectExt;
+
+/// A value as a Python object: native where Python has the type,
+/// otherwise the tagged dict `format_value` produces for MCP.
+pub(crate) fn to_py<'py>(py: Python<'py>, value: &Value) -> PyResult<Bound<'py, PyAny>> {
+    let tagged = |pairs: &[(&str, Bound<'py, PyAny>)]| -> PyResult<Bound<'py, PyAny>> {
+        let dict = PyDict::new(py);
+        for (k, v) in pairs {
+            dict.set_item(k, v)?;
+        }
+        Ok(dict.into_any())
+    };
+    let list = |items: &[Value]| -> PyResult<Bound<'py, PyAny>> {
+        let items = items
+            .iter()
+            .map(|v| to_py(py, v))
+            .collect::<PyResult<Vec<_>>>()?;
+        Ok(PyList::new(py, items)?.into_any())
+    };
+    Ok(match value {
+        Value::Int(n) => n.into_bound_py_any(py)?,
+        Value::Float(f) => f.into_bound_py_any(py)?,
+        Value::String(s) => s.into_bound_py_any(py)?,
+        Value::Bool(b) => b.into_bound_py_any(py)?,
+        Value::Unit => py.None().into_bound(py),
+        Value::Decimal(d) => py
+            .import("decimal")?
+            .getattr("Decimal")?
+            .call1((d.to_string(),))?,
+        Value::BigInt(n) => py.get_type::<PyInt>().call1((n.to_string(),))?,
+        Value::None => tagged(&[("option", "None".into_bound_py_any(py)?)])?,
+        Value::Some(v) => tagged(&[
+            ("option", "Some".into_bound_py_any(py)?),
+            ("value", to_py(py, v)?),
+        ])?,
+        Value::Ok(v) => tagged(&[
+            ("result", "Ok".into_bound_py_any(py)?),
+            ("value", to_py(py, v)?),
+        ])?,
+        Value::Err(v) => tagged(&[
+            ("result", "Err".into_bound_py_any(py)?),
+            ("value", to_py(py, v)?),
+        ])?,
+        Value::List(items) => list(items)?,
+        Value::Record { type_id, fields } => tagged(&[
+            ("type", "record".into_bound_py_any(py)?),
+            ("type_id", type_id.into_bound_py_any(py)?),
+            ("fields", list(fields)?),
+        ])?,
+        Value::Enum {
+            type_id,
+            variant,
+            payload,
+        } => tagged(&[
+            ("type", "enum".into_bound_py_any(py)?),
+            ("type_id", type_id.into_bound_py_any(py)?),
+            ("variant", variant.into_bound_py_any(py)?),
+            ("payload", to_py(py, payload)?),
+        ])?,
+        Value::Map(entries) => {
+            let dict = PyDict::new(py);
+            for (k, v) in entries {
+                dict.set_item(k, to_py(py, v)?)?;
+            }
+            dict.into_any()
+        }
+        Value::ActorId(id) => tagged(&[("actor_id", id.into_bound_py_any(py)?)])?,
+        Value::FnRef(idx) => tagged(&[("fn_ref", idx.into_bound_py_any(py)?)])?,
+        Value::CapHandle(id) => tagged(&[("cap_handle", id.into_bound_py_any(py)?)])?,
+        Value::Table(t) => {
+            let rows = (0..t.num_rows())
+                .map(|r| {
+                    let row = t
+                        .columns()
+                        .iter()
+                        .map(|c| to_py(py, &c.values[r]))
+                        .collect::<PyResult<Vec<_>>>()?;
+                    Ok(PyList::new(py, row)?.into_any())
+                })
+                .collect::<PyResult<Vec<_>>>()?;
+            tagged(&[
+                ("type", "table".into_bound_py_any(py)?),
+                ("columns", t.column_names().into_bound_py_any(py)?),
+                ("rows", rows.into_bound_py_any(py)?),
+            ])?
+        }
+    })
+}
+
+/// A Python message payload as a value. Takes the plain-data subset:
+/// `None`, `bool`, `int`, `float`, `str`, `decimal.Decimal`, lists and
+/// tuples, and dicts with `str` keys.
+pub(crate) fn from_py(obj: &Bound<'_, PyAny>) -> PyResult<Value> {
+    if obj.is_none() {
+        return Ok(Value::Unit);
+    }
+    // `bool` before `int`: Python's bool is an int subclass.
+    if obj.is_instance_of::<PyBool>() {
+        return Ok(Value::Bool(obj.extract()?));
+    }
+    if obj.is_instance_of::<PyInt>() {
+        if let Ok(n) = obj.extract::<i64>() {
+            return Ok(Value::Int(n));
+        }
+        let digits: String = obj.str()?.extract()?;
+        return BigInt::parse(&digits)
+            .map(Value::BigInt)
+            .ok_or_else(|| PyValueError::new_err("integer out of range for BigInt"));
+    }
+    if obj.is_instance_of::<PyFloat>() {
+        return Ok(Value::Float(obj.extract()?));
+    }
+    if obj.is_instance_of::<PyString>() {
+        return Ok(Value::String(obj.extract()?));
+    }
+    if obj.is_instance_of::<PyList>() || obj.is_instance_of::<PyTuple>() {
+        return obj
+            .try_iter()?
+            .map(|item| from_py(&item?))
+            .collect::<PyResult<_>>()
+            .map(Value::List);
+    }
+    if let Ok(dict) = obj.downcast::<PyDict>() {
+        return dict
+            .iter()
+            .map(|(k, v)| {
+                let key = k
+                    .extract::<String>()
+                    .map_err(|_| PyTypeError::new_err("dict keys must be str"))?;
+                Ok((key, from_py(&v)?))
+            })
+            .collect::<PyResult<_>>()
+            .map(Value::Map);
+    }
+    let decimal = obj.py().import("decimal")?.getattr("Decimal")?;
+    if obj.is_instance(&decimal)? {
+        let text: String = obj.str()?.extract()?;
+        return Decimal::parse(&text)
+            .map(Value::Decimal)
+            .ok_or_else(|| PyValueError::new_err(format!("decimal {text} out of range")));
+    }
+    Err(PyTypeError::new_err(format!(
+        "cannot pass {} to Boruna",
+        obj.get_type().name()?
+    )))
+}
+
+pub(crate) fn json_loads<'py>(py: Python<'py>, json: &str) -> PyResult<Bound<'py, PyAny>> {
+    py.import("json")?.call_method1("loads", (json,))
+}
+
+pub(crate) fn json_dumps(py: Python<'_>, obj: &Bound<'_, PyAny>) -> PyResult<String> {
+    py.import("json")?.call_method1("dumps", (obj,))?.extract()
+}
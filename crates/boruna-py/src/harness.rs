@@ -0,0 +1,128 @@
+//! `boruna.TestHarness`: a framework app driven message by message.
+
+use boruna_framework::effect::Effect;
+use boruna_framework::runtime::AppMessage;
+use boruna_framework::TestHarness as FwHarness;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+
+use crate::convert::{from_py, json_loads, to_py};
+use crate::FrameworkError;
+
+/// A framework app (`init`/`update`/`view`) under test, with a virtual
+/// clock that only moves through `advance_clock`. Every run with the same
+/// messages produces the same states, effects and cycle log.
+#[pyclass(module = "boruna", unsendable)]
+pub struct TestHarness {
+    inner: FwHarness,
+}
+
+#[pymethods]
+impl TestHarness {
+    /// Compile and initialise the app. Raises `FrameworkError`.
+    #[new]
+    fn new(source: &str) -> PyResult<Self> {
+        FwHarness::from_source(source)
+            .map(|inner| TestHarness { inner })
+            .map_err(framework_error)
+    }
+
+    /// The current state.
+    #[getter]
+    fn state<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_py(py, self.inner.state())
+    }
+
+    /// Cycles run so far.
+    #[getter]
+    fn cycle(&self) -> u64 {
+        self.inner.cycle()
+    }
+
+    /// Deliver one message and return `(state, effects)`. Effects are
+    /// dicts with `kind`, `payload` and `callback_tag`; they are not
+    /// executed.
+    #[pyo3(signature = (tag, payload = None))]
+    fn send<'py>(
+        &mut self,
+        py: Python<'py>,
+        tag: &str,
+        payload: Option<&Bound<'py, PyAny>>,
+    ) -> PyResult<(Bound<'py, PyAny>, Bound<'py, PyAny>)> {
+        let payload = match payload {
+            Some(p) => from_py(p)?,
+            None => boruna_bytecode::Value::Unit,
+        };
+        let (state, effects) = self
+            .inner
+            .send(AppMessage::new(tag, payload))
+            .map_err(framework_error)?;
+        Ok((to_py(py, &state)?, effects_to_py(py, &effects)?))
+    }
+
+    /// Move the virtual clock forward by `ms`.
+    fn advance_clock(&mut self, ms: u64) {
+        self.inner.advance_clock(ms);
+    }
+
+    /// The state as the JSON document `boruna framework` snapshots write,
+    /// parsed.
+    fn snapshot<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        json_loads(py, &self.inner.snapshot())
+    }
+
+    /// One dict per cycle: `cycle`, `timestamp_ms`, `tag`, `payload`,
+    /// `state_before`, `state_after`, `effects` and `ui_tree`.
+    fn cycle_log<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyList>> {
+        let records = self
+            .inner
+            .cycle_log()
+            .iter()
+            .map(|r| {
+                let dict = PyDict::new(py);
+                dict.set_item("cycle", r.cycle)?;
+                dict.set_item("timestamp_ms", r.timestamp_ms)?;
+                dict.set_item("tag", &r.message.tag)?;
+                dict.set_item("payload", to_py(py, &r.message.payload)?)?;
+                dict.set_item("state_before", to_py(py, &r.state_before)?)?;
+                dict.set_item("state_after", to_py(py, &r.state_after)?)?;
+                dict.set_item("effects", effects_to_py(py, &r.effects)?)?;
+                let ui_tree = match &r.ui_tree {
+                    Some(tree) => to_py(py, tree)?,
+                    None => py.None().into_bound(py),
+                };
+                dict.set_item("ui_tree", ui_tree)?;
+                Ok(dict)
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        PyList::new(py, records)
+    }
+
+    /// The UI tree `view` renders for the current state.
+    fn view<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_py(py, &self.inner.view().map_err(framework_error)?)
+    }
+
+    /// Restore the state as of `cycle`, dropping later cycles.
+    fn rewind(&mut self, cycle: u64) -> PyResult<()> {
+        self.inner.rewind(cycle).map_err(framework_error)
+    }
+}
+
+fn effects_to_py<'py>(py: Python<'py>, effects: &[Effect]) -> PyResult<Bound<'py, PyAny>> {
+    let effects = effects
+        .iter()
+        .map(|e| {
+            let dict = PyDict::new(py);
+            dict.set_item("kind", e.kind.as_str())?;
+            dict.set_item("payload", to_py(py, &e.payload)?)?;
+            dict.set_item("callback_tag", &e.callback_tag)?;
+            Ok(dict)
+        })
+        .collect::<PyResult<Vec<_>>>()?;
+    Ok(PyList::new(py, effects)?.into_any())
+}
+
+fn framework_error(e: boruna_framework::FrameworkError) -> PyErr {
+    FrameworkError::new_err(e.to_string())
+}
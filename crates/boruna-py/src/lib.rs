@@ -0,0 +1,246 @@
+//! Python bindings: `import boruna`.
+//!
+//! ```python
+//! import boruna
+//!
+//! module = boruna.compile("fn main() -> Int { 41 + 1 }")
+//! boruna.run(module, max_steps=10_000)["result"]  # 42
+//!
+//! h = boruna.TestHarness(open("counter.ax").read())
+//! state, effects = h.send("increment", 1)
+//! h.cycle_log()
+//! ```
+//!
+//! Values cross into Python as native types where one exists (`int`,
+//! `float`, `str`, `bool`, `None`, `decimal.Decimal`, `list`, `dict`) and
+//! otherwise as the tagged dicts `boruna_run` returns over MCP.
+
+mod convert;
+mod harness;
+
+use boruna_bytecode::Module as BcModule;
+use boruna_compiler::CompileError as BcCompileError;
+use boruna_vm::capability_gateway::{CapabilityGateway, Policy};
+use boruna_vm::error::VmError;
+use boruna_vm::replay::RunOutcome;
+use boruna_vm::vm::Vm;
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict};
+
+pub use harness::TestHarness;
+
+create_exception!(
+    boruna,
+    BorunaError,
+    PyException,
+    "Base class of every Boruna error."
+);
+create_exception!(
+    boruna,
+    CompileError,
+    BorunaError,
+    "The source did not compile. Carries `code` and, where known, `line`, `col`, `end_line` and `end_col`."
+);
+create_exception!(
+    boruna,
+    ExecutionError,
+    BorunaError,
+    "The program failed at run time."
+);
+create_exception!(
+    boruna,
+    LimitExceeded,
+    ExecutionError,
+    "The program ran past `max_steps`."
+);
+create_exception!(
+    boruna,
+    PolicyError,
+    BorunaError,
+    "The policy was rejected. Carries `error_kind`."
+);
+create_exception!(
+    boruna,
+    FrameworkError,
+    BorunaError,
+    "A framework app failed validation or a cycle."
+);
+
+/// A compiled module. Immutable, picklable through `to_bytes`/`from_bytes`.
+#[pyclass(module = "boruna", frozen)]
+#[derive(Clone)]
+pub struct Module {
+    inner: BcModule,
+}
+
+#[pymethods]
+impl Module {
+    #[getter]
+    fn name(&self) -> &str {
+        &self.inner.name
+    }
+
+    /// The module as `.axbc` bytecode.
+    fn to_bytes<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
+        let bytes = self
+            .inner
+            .to_bytes()
+            .map_err(|e| BorunaError::new_err(e.to_string()))?;
+        Ok(PyBytes::new(py, &bytes))
+    }
+
+    /// Load `.axbc` bytecode, as written by `to_bytes` or `boruna compile`.
+    #[staticmethod]
+    fn from_bytes(data: &[u8]) -> PyResult<Self> {
+        BcModule::from_bytes(data)
+            .map(|inner| Module { inner })
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    fn __repr__(&self) -> String {
+        format!("<boruna.Module {:?}>", self.inner.name)
+    }
+}
+
+/// Compile `.ax` source into a `Module`. Raises `CompileError`.
+#[pyfunction]
+#[pyo3(signature = (source, name = "module"))]
+fn compile(py: Python<'_>, source: &str, name: &str) -> PyResult<Module> {
+    boruna_compiler::compile(name, source)
+        .map(|inner| Module { inner })
+        .map_err(|e| compile_error(py, &e))
+}
+
+/// Run `module` from its entry point.
+///
+/// `policy` is `"allow-all"` (the default), `"deny-all"`, or a policy dict
+/// as in a `boruna run --policy` file. Returns a dict with `result`,
+/// `steps` and `ui_output`, plus `event_log` when `record` is true.
+/// Raises `ExecutionError`, or `LimitExceeded` past `max_steps`.
+#[pyfunction]
+#[pyo3(signature = (module, policy = None, max_steps = None, record = false))]
+fn run<'py>(
+    py: Python<'py>,
+    module: &Module,
+    policy: Option<&Bound<'py, PyAny>>,
+    max_steps: Option<u64>,
+    record: bool,
+) -> PyResult<Bound<'py, PyDict>> {
+    let policy = parse_policy(py, policy)?;
+    let module = module.inner.clone();
+    // The run holds no Python objects, so other threads may proceed.
+    let (run, steps, ui_output, event_log) = py.allow_threads(move || {
+        let mut vm = Vm::new(module, CapabilityGateway::new(policy));
+        if let Some(max) = max_steps {
+            vm.set_max_steps(max);
+        }
+        let run = vm.run();
+        let event_log = record.then(|| {
+            let mut log = vm.event_log().clone();
+            log.set_outcome(RunOutcome {
+                steps: vm.step_count(),
+                result: run.as_ref().ok().cloned(),
+                error: run.as_ref().err().map(|e| e.to_string()),
+                denials: vm.gateway().denials().to_vec(),
+                kv_snapshot: None,
+            });
+            log.to_json()
+        });
+        (
+            run,
+            vm.step_count(),
+            std::mem::take(&mut vm.ui_output),
+            event_log,
+        )
+    });
+
+    let value = run.map_err(|e| match e {
+        VmError::ExecutionLimitExceeded(_) => LimitExceeded::new_err(e.to_string()),
+        _ => ExecutionError::new_err(e.to_string()),
+    })?;
+    let output = PyDict::new(py);
+    output.set_item("result", convert::to_py(py, &value)?)?;
+    output.set_item("steps", steps)?;
+    output.set_item(
+        "ui_output",
+        ui_output
+            .iter()
+            .map(|v| convert::to_py(py, v))
+            .collect::<PyResult<Vec<_>>>()?,
+    )?;
+    if let Some(log) = event_log {
+        let log = log.map_err(BorunaError::new_err)?;
+        output.set_item("event_log", convert::json_loads(py, &log)?)?;
+    }
+    Ok(output)
+}
+
+fn parse_policy(py: Python<'_>, policy: Option<&Bound<'_, PyAny>>) -> PyResult<Policy> {
+    let Some(policy) = policy.filter(|p| !p.is_none()) else {
+        return Ok(Policy::allow_all());
+    };
+    if let Ok(name) = policy.extract::<String>() {
+        return match name.as_str() {
+            "allow-all" => Ok(Policy::allow_all()),
+            "deny-all" => Ok(Policy::deny_all()),
+            _ => Err(PyValueError::new_err(format!(
+                "unknown policy {name:?}; expected \"allow-all\", \"deny-all\" or a dict"
+            ))),
+        };
+    }
+    let json = convert::json_dumps(py, policy)?;
+    boruna_vm::policy_validate::parse(&json).map_err(|e| {
+        let err = PolicyError::new_err(e.to_string());
+        let _ = err.value(py).setattr("error_kind", e.error_kind());
+        err
+    })
+}
+
+fn compile_error(py: Python<'_>, err: &BcCompileError) -> PyErr {
+    // Same codes as `boruna_compile` over MCP.
+    let code = match err {
+        BcCompileError::Lexer { .. } => "E001",
+        BcCompileError::Parse { .. } => "E002",
+        BcCompileError::Type { .. } => "E009",
+        BcCompileError::Codegen { .. } => "E008",
+        BcCompileError::Budget(_) => "E015",
+    };
+    let span = err.span();
+    let py_err = CompileError::new_err(err.to_string());
+    let value = py_err.value(py);
+    let _ = value.setattr("code", code);
+    let _ = value.setattr("line", span.map(|s| s.line));
+    let _ = value.setattr("col", span.map(|s| s.col));
+    let _ = value.setattr("end_line", span.map(|s| s.end_line));
+    let _ = value.setattr("end_col", span.map(|s| s.end_col));
+    py_err
+}
+
+#[pymodule]
+#[pyo3(name = "boruna")]
+fn boruna_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = m.py();
+    m.add("__version__", env!("CARGO_PKG_VERSION"))?;
+    m.add_class::<Module>()?;
+    m.add_class::<TestHarness>()?;
+    m.add_function(wrap_pyfunction!(compile, m)?)?;
+    m.add_function(wrap_pyfunction!(run, m)?)?;
+    m.add("BorunaError", py.get_type::<BorunaError>())?;
+    m.add("CompileError", py.get_type::<CompileError>())?;
+    m.add("ExecutionError", py.get_type::<ExecutionError>())?;
+    m.add("LimitExceeded", py.get_type::<LimitExceeded>())?;
+    m.add("PolicyError", py.get_type::<PolicyError>())?;
+    m.add("FrameworkError", py.get_type::<FrameworkError>())?;
+    Ok(())
+}
+
+/// Register `boruna` in `sys.modules` of an embedded interpreter, as a
+/// wheel install would. For Rust hosts and tests.
+pub fn register(py: Python<'_>) -> PyResult<()> {
+    let module = pyo3::wrap_pymodule!(boruna_module)(py);
+    py.import("sys")?
+        .getattr("modules")?
+        .set_item("boruna", module)
+}
@@ -0,0 +1,31 @@
+//! Runs `test_boruna.py` in an embedded interpreter with the module
+//! registered as `boruna`.
+
+use std::ffi::CString;
+
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+#[test]
+fn python_suite() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        boruna_py::register(py).unwrap();
+        let globals = PyDict::new(py);
+        let source = CString::new(include_str!("test_boruna.py")).unwrap();
+        py.run(&source, Some(&globals), None).unwrap();
+
+        let mut ran = 0;
+        for (name, f) in globals.iter() {
+            let name: String = name.extract().unwrap();
+            if name.starts_with("test_") {
+                if let Err(e) = f.call0() {
+                    e.display(py);
+                    panic!("{name} failed: {e}");
+                }
+                ran += 1;
+            }
+        }
+        assert!(ran >= 10, "only {ran} tests ran");
+    });
+}
@@ -0,0 +1,141 @@
+"""Tests for the `boruna` module.
+
+Run under pytest against an installed wheel, or through `cargo test -p
+boruna-py`, which embeds an interpreter and calls every `test_*` here.
+"""
+
+import decimal
+
+import boruna
+
+COUNTER_APP = """
+type State { count: Int }
+type Msg { tag: String, payload: Int }
+type Effect { kind: String, payload: String, callback_tag: String }
+type UpdateResult { state: State, effects: List<Effect> }
+type UINode { tag: String, text: String }
+
+fn init() -> State {
+    State { count: 0 }
+}
+
+fn update(state: State, msg: Msg) -> UpdateResult {
+    let new_count: Int = if msg.tag == "increment" {
+        state.count + msg.payload
+    } else {
+        state.count
+    }
+    UpdateResult {
+        state: State { count: new_count },
+        effects: [],
+    }
+}
+
+fn view(state: State) -> UINode {
+    UINode { tag: "text", text: "count" }
+}
+"""
+
+
+def raises(exc_type, f, *args, **kwargs):
+    try:
+        f(*args, **kwargs)
+    except exc_type as e:
+        return e
+    raise AssertionError(f"{exc_type.__name__} not raised")
+
+
+def test_compile_and_run():
+    module = boruna.compile("fn main() -> Int { 41 + 1 }\n", name="answer")
+    assert module.name == "answer"
+    output = boruna.run(module)
+    assert output["result"] == 42
+    assert output["steps"] > 0
+    assert output["ui_output"] == []
+    assert "event_log" not in output
+
+
+def test_module_bytes_round_trip():
+    module = boruna.compile("fn main() -> String { \"hi\" }\n")
+    again = boruna.Module.from_bytes(module.to_bytes())
+    assert boruna.run(again)["result"] == "hi"
+    raises(ValueError, boruna.Module.from_bytes, b"not bytecode")
+
+
+def test_values_are_native():
+    module = boruna.compile("fn main() -> List<Int> { [1, 2, 3] }\n")
+    assert boruna.run(module)["result"] == [1, 2, 3]
+    module = boruna.compile("type P { x: Int }\nfn main() -> P { P { x: 5 } }\n")
+    result = boruna.run(module)["result"]
+    assert result["type"] == "record"
+    assert result["fields"] == [5]
+
+
+def test_compile_error_is_typed():
+    e = raises(boruna.CompileError, boruna.compile, "fn main( {")
+    assert isinstance(e, boruna.BorunaError)
+    assert e.code == "E002"
+    assert e.line == 1
+
+
+def test_run_errors_are_typed():
+    source = 'fn main() -> Int !{kv.get} {\n    let a = kv_get("a")\n    1\n}\n'
+    module = boruna.compile(source)
+    raises(boruna.ExecutionError, boruna.run, module, policy="deny-all")
+    record = boruna.run(module, policy="allow-all", record=True)
+    assert record["event_log"]["outcome"]["steps"] == record["steps"]
+
+    module = boruna.compile("fn main() -> Int { 1 + 2 + 3 }\n")
+    e = raises(boruna.LimitExceeded, boruna.run, module, max_steps=1)
+    assert isinstance(e, boruna.ExecutionError)
+
+
+def test_policy_is_validated():
+    module = boruna.compile("fn main() -> Int { 1 }\n")
+    raises(ValueError, boruna.run, module, policy="some")
+    e = raises(boruna.PolicyError, boruna.run, module, policy={"schema_version": 99})
+    assert e.error_kind.startswith("policy.")
+
+
+def test_harness_send_snapshot_and_cycle_log():
+    h = boruna.TestHarness(COUNTER_APP)
+    assert h.cycle == 0
+    state, effects = h.send("increment", 2)
+    assert effects == []
+    state, _ = h.send("increment", 3)
+    assert state["fields"] == [5]
+    assert h.state == state
+    assert h.cycle == 2
+    assert isinstance(h.snapshot(), dict)
+
+    log = h.cycle_log()
+    assert [r["cycle"] for r in log] == [1, 2]
+    assert [r["payload"] for r in log] == [2, 3]
+    assert log[1]["state_before"]["fields"] == [2]
+    assert log[1]["state_after"]["fields"] == [5]
+
+    h.rewind(1)
+    assert h.state["fields"] == [2]
+
+
+def test_harness_is_deterministic():
+    def cycles():
+        h = boruna.TestHarness(COUNTER_APP)
+        for n in range(10):
+            h.advance_clock(100)
+            h.send("increment", n)
+        return h.cycle_log()
+
+    assert cycles() == cycles()
+    assert cycles()[-1]["timestamp_ms"] == 1000
+
+
+def test_payloads_must_be_plain_data():
+    h = boruna.TestHarness(COUNTER_APP)
+    raises(TypeError, h.send, "increment", object())
+    raises(TypeError, h.send, "increment", {1: 2})
+    h.send("noop", {"a": [1, 2.5, True, None, decimal.Decimal("1.25")]})
+
+
+def test_bad_app_is_a_framework_error():
+    raises(boruna.FrameworkError, boruna.TestHarness, "fn main() -> Int { 1 }\n")
@@ -1,7 +1,7 @@
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use sha2::{Digest, Sha256};
+use boruna_hash::sha256_hex;
 
 /// Content-addressed blob store for LLM context.
 pub struct ContextStore {
@@ -98,10 +98,6 @@ impl ContextStore {
     }
 }
 
-fn sha256_hex(content: &str) -> String {
-    format!("{:x}", Sha256::digest(content.as_bytes()))
-}
-
 #[cfg(test)]
 mod context_tests {
     use super::*;
@@ -1,6 +1,7 @@
 use std::collections::BTreeMap;
 use std::path::Path;
 
+use boruna_budget::{BudgetLedger, Resource};
 use boruna_bytecode::Value;
 use serde::{Deserialize, Serialize};
 
@@ -48,6 +49,8 @@ pub struct LlmGateway {
     usage: LlmUsage,
     mode: ExecutionMode,
     log: Vec<LlmLogEntry>,
+    /// Run budget debited for the tokens each call requests.
+    ledger: Option<BudgetLedger>,
 }
 
 impl LlmGateway {
@@ -67,9 +70,17 @@ impl LlmGateway {
             usage: LlmUsage::default(),
             mode,
             log: Vec::new(),
+            ledger: None,
         })
     }
 
+    /// Debit `ledger` for the `max_output_tokens` of every call that
+    /// passes policy, cached or not, the way [`LlmUsage`] counts them.
+    pub fn with_budget_ledger(mut self, ledger: BudgetLedger) -> Self {
+        self.ledger = Some(ledger);
+        self
+    }
+
     /// Get the prompt registry (for registration/verification).
     pub fn prompt_registry(&self) -> &PromptRegistry {
         &self.prompt_registry
@@ -107,6 +118,11 @@ impl LlmGateway {
 
         // 2. Check policy
         policy::check_policy(req, &self.policy, &self.usage, context_bytes)?;
+        if let Some(ledger) = &self.ledger {
+            ledger
+                .debit(Resource::LlmTokens, req.max_output_tokens)
+                .map_err(|e| e.to_string())?;
+        }
 
         // 3. Get prompt and schema hashes for cache key
         let prompt_hash = self
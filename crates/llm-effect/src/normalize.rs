@@ -1,7 +1,7 @@
 use std::collections::BTreeMap;
 
+use boruna_hash::Hasher;
 use serde_json;
-use sha2::{Digest, Sha256};
 
 use boruna_bytecode::Value;
 
@@ -129,6 +129,10 @@ pub fn canonical_json(value: &Value) -> String {
         }
         Value::Int(n) => n.to_string(),
         Value::Float(f) => format!("{f}"),
+        // Quoted: the exact digits must survive JSON readers that parse
+        // numbers as f64.
+        Value::Decimal(d) => serde_json::to_string(&d.to_string()).unwrap_or_default(),
+        Value::BigInt(n) => serde_json::to_string(&n.to_string()).unwrap_or_default(),
         Value::String(s) => serde_json::to_string(s).unwrap_or_default(),
         Value::None => "null".into(),
         Value::Some(v) => canonical_json(v),
@@ -165,6 +169,29 @@ pub fn canonical_json(value: &Value) -> String {
         }
         Value::ActorId(id) => id.to_string(),
         Value::FnRef(id) => id.to_string(),
+        Value::CapHandle(id) => id.to_string(),
+        Value::Table(t) => {
+            let names: Vec<String> = t
+                .column_names()
+                .iter()
+                .map(|n| serde_json::to_string(n).unwrap_or_default())
+                .collect();
+            let rows: Vec<String> = (0..t.num_rows())
+                .map(|r| {
+                    let cells: Vec<String> = t
+                        .columns()
+                        .iter()
+                        .map(|c| canonical_json(&c.values[r]))
+                        .collect();
+                    format!("[{}]", cells.join(","))
+                })
+                .collect();
+            format!(
+                "{{\"columns\":[{}],\"rows\":[{}]}}",
+                names.join(","),
+                rows.join(",")
+            )
+        }
     }
 }
 
@@ -176,7 +203,7 @@ pub fn compute_cache_key(
     prompt_content_hash: &str,
     schema_content_hash: &str,
 ) -> String {
-    let mut hasher = Sha256::new();
+    let mut hasher = Hasher::sha256();
 
     // prompt_id
     hasher.update(canonical_value_json(&Value::String(req.prompt_id.clone())).as_bytes());
@@ -219,7 +246,7 @@ pub fn compute_cache_key(
     // schema content hash
     hasher.update(schema_content_hash.as_bytes());
 
-    format!("sha256:{:x}", hasher.finalize())
+    hasher.finalize().to_string()
 }
 
 fn canonical_value_json(v: &Value) -> String {
@@ -2,8 +2,8 @@ use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+use boruna_hash::HashAlgorithm;
 use serde::{Deserialize, Serialize};
-use sha2::{Digest, Sha256};
 
 /// A prompt template stored in the registry.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -252,8 +252,7 @@ impl PromptRegistry {
 
 /// Compute SHA-256 hash of content.
 pub fn content_hash(content: &str) -> String {
-    let hash = Sha256::digest(content.as_bytes());
-    format!("sha256:{:x}", hash)
+    HashAlgorithm::Sha256.digest(content).to_string()
 }
 
 #[cfg(test)]
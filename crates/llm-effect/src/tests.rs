@@ -1,5 +1,6 @@
 use std::collections::BTreeMap;
 
+use boruna_budget::{Budget, BudgetLedger};
 use boruna_bytecode::Value;
 
 use crate::gateway::{ExecutionMode, LlmGateway};
@@ -220,6 +221,38 @@ fn test_policy_blocks_over_budget_integration() {
     assert!(result.unwrap_err().contains("token budget exceeded"));
 }
 
+#[test]
+fn test_shared_run_budget_limits_tokens_across_gateways() {
+    let dir = tempfile::tempdir().unwrap();
+    let ledger = BudgetLedger::new(Budget {
+        max_tokens: Some(400),
+        ..Budget::default()
+    });
+    let gateway = || {
+        let mut gw = LlmGateway::new(
+            &dir.path().join("prompts"),
+            &dir.path().join("context"),
+            &dir.path().join("cache"),
+            LlmPolicy::allow_all(),
+            ExecutionMode::Mock,
+        )
+        .unwrap()
+        .with_budget_ledger(ledger.clone());
+        gw.prompt_registry_mut()
+            .register_prompt(&make_template("test.shared"))
+            .unwrap();
+        gw
+    };
+    let mut req = make_request("test.shared");
+    req.cache_mode = CacheMode::Off;
+
+    gateway().execute(&req).unwrap();
+    // A second gateway on the same run has only 144 tokens left.
+    let err = gateway().execute(&req).unwrap_err();
+    assert!(err.contains("run budget exhausted"), "{err}");
+    assert_eq!(ledger.run_usage().llm_tokens, 256);
+}
+
 // --- Prompt registry verify ---
 
 #[test]
@@ -0,0 +1,119 @@
+//! Arbitrary-precision integer backing `Value::BigInt` (bytecode 1.2).
+//!
+//! `Int` arithmetic traps on `i64` overflow; code that needs wider results
+//! (hashing, modular arithmetic) converts explicitly with `IntToBigInt` or
+//! writes `123n` literals. Values are capped at [`MAX_BIGINT_BITS`] so a
+//! runaway loop fails with a typed overflow instead of exhausting memory.
+//! The serialized form is the decimal string (`"-12345"`), matching
+//! [`Decimal`](crate::Decimal).
+
+use std::fmt;
+
+use num_bigint::Sign;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Largest magnitude, in bits, a [`BigInt`] may reach. Operations whose
+/// result would exceed it return `None`.
+pub const MAX_BIGINT_BITS: u64 = 65_536;
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct BigInt(num_bigint::BigInt);
+
+impl BigInt {
+    /// Exact conversion; every `i64` fits.
+    pub fn from_int(n: i64) -> Self {
+        BigInt(n.into())
+    }
+
+    /// `Some` iff the value fits in an `i64`.
+    pub fn to_int(&self) -> Option<i64> {
+        i64::try_from(&self.0).ok()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0.sign() == Sign::NoSign
+    }
+
+    pub fn bits(&self) -> u64 {
+        self.0.bits()
+    }
+
+    pub fn checked_add(&self, rhs: &BigInt) -> Option<BigInt> {
+        bounded(&self.0 + &rhs.0)
+    }
+
+    pub fn checked_sub(&self, rhs: &BigInt) -> Option<BigInt> {
+        bounded(&self.0 - &rhs.0)
+    }
+
+    pub fn checked_mul(&self, rhs: &BigInt) -> Option<BigInt> {
+        if self.bits() + rhs.bits() > MAX_BIGINT_BITS + 1 {
+            return None;
+        }
+        bounded(&self.0 * &rhs.0)
+    }
+
+    /// Truncating division, like `Int`. `None` on division by zero.
+    pub fn checked_div(&self, rhs: &BigInt) -> Option<BigInt> {
+        if rhs.is_zero() {
+            return None;
+        }
+        Some(BigInt(&self.0 / &rhs.0))
+    }
+
+    /// Remainder with the sign of the dividend, like `Int`. `None` on
+    /// division by zero.
+    pub fn checked_rem(&self, rhs: &BigInt) -> Option<BigInt> {
+        if rhs.is_zero() {
+            return None;
+        }
+        Some(BigInt(&self.0 % &rhs.0))
+    }
+
+    pub fn neg(&self) -> BigInt {
+        BigInt(-&self.0)
+    }
+
+    /// `self^exp mod modulus`, result in `[0, |modulus|)`. `None` when
+    /// `exp` is negative or `modulus` is zero.
+    pub fn pow_mod(&self, exp: &BigInt, modulus: &BigInt) -> Option<BigInt> {
+        if exp.0.sign() == Sign::Minus || modulus.is_zero() {
+            return None;
+        }
+        let m = num_bigint::BigInt::from(modulus.0.magnitude().clone());
+        let base = ((&self.0 % &m) + &m) % &m;
+        Some(BigInt(base.modpow(&exp.0, &m)))
+    }
+
+    /// Parse `[-]digits`. No sign other than a leading `-`, no separators.
+    pub fn parse(s: &str) -> Option<BigInt> {
+        let digits = s.strip_prefix('-').unwrap_or(s);
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        bounded(s.parse().ok()?)
+    }
+}
+
+fn bounded(n: num_bigint::BigInt) -> Option<BigInt> {
+    (n.bits() <= MAX_BIGINT_BITS).then_some(BigInt(n))
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Serialize for BigInt {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for BigInt {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        BigInt::parse(&s).ok_or_else(|| serde::de::Error::custom(format!("invalid bigint: {s:?}")))
+    }
+}
@@ -1,5 +1,5 @@
+use boruna_hash::Hasher;
 use serde::{Deserialize, Serialize};
-use sha2::{Digest, Sha256};
 use std::fmt;
 
 /// Capabilities that bytecode can request.
@@ -23,6 +23,28 @@ pub enum Capability {
     /// need typed access parse the JSON; the platform stays
     /// String-to-String at the language layer.
     StepInput,
+    /// Read one key from the embedded key-value store. Dispatched from
+    /// the built-in `kv_get(key)`; returns `Some(value)` or `None`.
+    KvGet,
+    /// Write one key to the embedded key-value store. Dispatched from
+    /// the built-in `kv_set(key, value)`; returns `Unit`.
+    KvSet,
+    /// List the entries whose key starts with a prefix. Dispatched from
+    /// the built-in `kv_scan(prefix)`; returns a `Map` of key to value.
+    KvScan,
+    /// Append a value to a named queue. Dispatched from the built-in
+    /// `queue_push(name, value)`; returns the item's sequence number.
+    QueuePush,
+    /// Take the oldest pending item from a named queue. Dispatched from
+    /// the built-in `queue_poll(name)`; returns `Some(value)` or `None`.
+    QueuePoll,
+    /// Send a templated notification to a webhook or email address.
+    /// Dispatched from the built-in `notify_send(destination, template,
+    /// vars)`; returns a `Map` receipt with the rendered body and status.
+    NotifySend,
+    /// Load a CSV file as a `Value::Table`. Dispatched from the built-in
+    /// `read_table(path)`; row and byte limits come from the policy.
+    DataReadTable,
 }
 
 impl Capability {
@@ -39,6 +61,13 @@ impl Capability {
             8 => Some(Capability::ActorSpawn),
             9 => Some(Capability::ActorSend),
             10 => Some(Capability::StepInput),
+            11 => Some(Capability::KvGet),
+            12 => Some(Capability::KvSet),
+            13 => Some(Capability::KvScan),
+            14 => Some(Capability::QueuePush),
+            15 => Some(Capability::QueuePoll),
+            16 => Some(Capability::NotifySend),
+            17 => Some(Capability::DataReadTable),
             _ => None,
         }
     }
@@ -56,6 +85,13 @@ impl Capability {
             Capability::ActorSpawn => 8,
             Capability::ActorSend => 9,
             Capability::StepInput => 10,
+            Capability::KvGet => 11,
+            Capability::KvSet => 12,
+            Capability::KvScan => 13,
+            Capability::QueuePush => 14,
+            Capability::QueuePoll => 15,
+            Capability::NotifySend => 16,
+            Capability::DataReadTable => 17,
         }
     }
 
@@ -72,6 +108,13 @@ impl Capability {
             Capability::ActorSpawn => "actor.spawn",
             Capability::ActorSend => "actor.send",
             Capability::StepInput => "step.input",
+            Capability::KvGet => "kv.get",
+            Capability::KvSet => "kv.set",
+            Capability::KvScan => "kv.scan",
+            Capability::QueuePush => "queue.push",
+            Capability::QueuePoll => "queue.poll",
+            Capability::NotifySend => "notify.send",
+            Capability::DataReadTable => "data.read_table",
         }
     }
 
@@ -88,6 +131,13 @@ impl Capability {
             "actor.spawn" | "actor_spawn" => Some(Capability::ActorSpawn),
             "actor.send" | "actor_send" => Some(Capability::ActorSend),
             "step.input" | "step_input" => Some(Capability::StepInput),
+            "kv.get" | "kv_get" => Some(Capability::KvGet),
+            "kv.set" | "kv_set" => Some(Capability::KvSet),
+            "kv.scan" | "kv_scan" => Some(Capability::KvScan),
+            "queue.push" | "queue_push" => Some(Capability::QueuePush),
+            "queue.poll" | "queue_poll" => Some(Capability::QueuePoll),
+            "notify.send" | "notify_send" => Some(Capability::NotifySend),
+            "data.read_table" | "data_read_table" => Some(Capability::DataReadTable),
             _ => None,
         }
     }
@@ -116,7 +166,14 @@ impl Capability {
             | Capability::LlmCall
             | Capability::ActorSpawn
             | Capability::ActorSend
-            | Capability::StepInput => "1",
+            | Capability::StepInput
+            | Capability::KvGet
+            | Capability::KvSet
+            | Capability::KvScan
+            | Capability::QueuePush
+            | Capability::QueuePoll
+            | Capability::NotifySend
+            | Capability::DataReadTable => "1",
         }
     }
 
@@ -125,14 +182,21 @@ impl Capability {
     /// **Note:** adding a capability bumps `capability_set_hash` (additive
     /// change in surface area); FleetQ-blessed and integrators are
     /// expected to invalidate cache keys on the new hash.
-    pub const ALL: [Capability; 11] = [
+    pub const ALL: [Capability; 18] = [
         Capability::ActorSend,
         Capability::ActorSpawn,
+        Capability::DataReadTable,
         Capability::DbQuery,
         Capability::FsRead,
         Capability::FsWrite,
+        Capability::KvGet,
+        Capability::KvScan,
+        Capability::KvSet,
         Capability::LlmCall,
         Capability::NetFetch,
+        Capability::NotifySend,
+        Capability::QueuePoll,
+        Capability::QueuePush,
         Capability::Random,
         Capability::StepInput,
         Capability::TimeNow,
@@ -196,20 +260,15 @@ where
     S1: AsRef<str>,
     S2: AsRef<str>,
 {
-    let mut hasher = Sha256::new();
+    let mut hasher = Hasher::sha256();
     for (name, version) in entries {
-        hasher.update(name.as_ref().as_bytes());
-        hasher.update(b"\t");
-        hasher.update(version.as_ref().as_bytes());
-        hasher.update(b"\n");
+        hasher
+            .update(name.as_ref())
+            .update(b"\t")
+            .update(version.as_ref())
+            .update(b"\n");
     }
-    let digest = hasher.finalize();
-    let mut out = String::with_capacity(7 + 64);
-    out.push_str("sha256:");
-    for byte in digest {
-        out.push_str(&format!("{byte:02x}"));
-    }
-    out
+    hasher.finalize().to_string()
 }
 
 /// Build a `CapabilitySetReport` for the running binary.
@@ -0,0 +1,185 @@
+//! Fixed-point decimal backing `Value::Decimal` (bytecode 1.2).
+//!
+//! A [`Decimal`] is an `i128` count of 10⁻⁹ units, so every operation is
+//! exact integer arithmetic with identical results on every platform —
+//! unlike `Float`, whose formatting and NaN/Inf edge cases leak into trace
+//! hashes. Multiplication and division round half-to-even at the 9th
+//! fractional digit, computing the intermediate product in 256 bits so
+//! only a result that does not fit is an overflow — which is reported,
+//! never wrapped. The serialized
+//! form is the canonical decimal string (`"12.5"`), so evidence JSON never
+//! depends on how a reader handles 128-bit numbers.
+
+use std::fmt;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Number of fractional digits carried by every [`Decimal`].
+pub const DECIMAL_SCALE: u32 = 9;
+
+const ONE: i128 = 10i128.pow(DECIMAL_SCALE);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Decimal(i128);
+
+impl Decimal {
+    pub const ZERO: Decimal = Decimal(0);
+
+    /// Wrap a raw count of 10⁻⁹ units.
+    pub fn from_raw(raw: i128) -> Self {
+        Decimal(raw)
+    }
+
+    /// The raw count of 10⁻⁹ units.
+    pub fn raw(self) -> i128 {
+        self.0
+    }
+
+    /// Exact conversion; every `i64` fits.
+    pub fn from_int(n: i64) -> Self {
+        Decimal(i128::from(n) * ONE)
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn checked_add(self, rhs: Decimal) -> Option<Decimal> {
+        self.0.checked_add(rhs.0).map(Decimal)
+    }
+
+    pub fn checked_sub(self, rhs: Decimal) -> Option<Decimal> {
+        self.0.checked_sub(rhs.0).map(Decimal)
+    }
+
+    pub fn checked_neg(self) -> Option<Decimal> {
+        self.0.checked_neg().map(Decimal)
+    }
+
+    pub fn checked_mul(self, rhs: Decimal) -> Option<Decimal> {
+        let negative = (self.0 < 0) != (rhs.0 < 0);
+        let product = mul_wide(self.0.unsigned_abs(), rhs.0.unsigned_abs());
+        div_round_half_even(negative, product, ONE as u128).map(Decimal)
+    }
+
+    /// `None` on overflow or division by zero.
+    pub fn checked_div(self, rhs: Decimal) -> Option<Decimal> {
+        if rhs.0 == 0 {
+            return None;
+        }
+        let negative = (self.0 < 0) != (rhs.0 < 0);
+        let scaled = mul_wide(self.0.unsigned_abs(), ONE as u128);
+        div_round_half_even(negative, scaled, rhs.0.unsigned_abs()).map(Decimal)
+    }
+
+    /// Parse `[-]digits[.digits]` with at most [`DECIMAL_SCALE`] fractional
+    /// digits. Extra precision is rejected rather than silently rounded.
+    pub fn parse(s: &str) -> Option<Decimal> {
+        let (negative, body) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s),
+        };
+        let (int_part, frac_part) = match body.split_once('.') {
+            Some((i, f)) if !f.is_empty() => (i, f),
+            Some(_) => return None,
+            None => (body, ""),
+        };
+        if int_part.is_empty()
+            || frac_part.len() > DECIMAL_SCALE as usize
+            || !int_part
+                .bytes()
+                .chain(frac_part.bytes())
+                .all(|b| b.is_ascii_digit())
+        {
+            return None;
+        }
+        let int: i128 = int_part.parse().ok()?;
+        let frac: i128 = if frac_part.is_empty() {
+            0
+        } else {
+            frac_part.parse::<i128>().ok()? * 10i128.pow(DECIMAL_SCALE - frac_part.len() as u32)
+        };
+        let raw = int.checked_mul(ONE)?.checked_add(frac)?;
+        Some(Decimal(if negative { -raw } else { raw }))
+    }
+}
+
+/// Full 256-bit product of `a` and `b` as `(high, low)` halves.
+fn mul_wide(a: u128, b: u128) -> (u128, u128) {
+    const MASK: u128 = u64::MAX as u128;
+    let (a_hi, a_lo) = (a >> 64, a & MASK);
+    let (b_hi, b_lo) = (b >> 64, b & MASK);
+    let lo_lo = a_lo * b_lo;
+    let hi_lo = a_hi * b_lo;
+    let lo_hi = a_lo * b_hi;
+    let hi_hi = a_hi * b_hi;
+    // Sum of three values below 2^64 each: fits.
+    let mid = (lo_lo >> 64) + (hi_lo & MASK) + (lo_hi & MASK);
+    let low = (mid << 64) | (lo_lo & MASK);
+    let high = hi_hi + (hi_lo >> 64) + (lo_hi >> 64) + (mid >> 64);
+    (high, low)
+}
+
+/// The 256-bit magnitude `n` divided by `d`, rounded half-to-even and
+/// negated when `negative`; `None` when the result does not fit an `i128`.
+fn div_round_half_even(negative: bool, n: (u128, u128), d: u128) -> Option<i128> {
+    let (high, low) = n;
+    if high >= d {
+        return None;
+    }
+    // Shift-subtract long division; the remainder stays below `d`.
+    let mut r = high;
+    let mut q: u128 = 0;
+    for bit in (0..128).rev() {
+        let carry = r >> 127;
+        r = (r << 1) | ((low >> bit) & 1);
+        q <<= 1;
+        if carry == 1 || r >= d {
+            r = r.wrapping_sub(d);
+            q |= 1;
+        }
+    }
+    // r < d ≤ 2^127, so doubling fits in u128.
+    let twice = r * 2;
+    if twice > d || (twice == d && !q.is_multiple_of(2)) {
+        q = q.checked_add(1)?;
+    }
+    if negative {
+        0i128.checked_sub_unsigned(q)
+    } else {
+        i128::try_from(q).ok()
+    }
+}
+
+/// Canonical form: no trailing fractional zeros, no trailing dot
+/// (`12.5`, `3`, `-0.000000001`).
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let abs = self.0.unsigned_abs();
+        let one = ONE as u128;
+        if self.0 < 0 {
+            write!(f, "-")?;
+        }
+        write!(f, "{}", abs / one)?;
+        let frac = abs % one;
+        if frac != 0 {
+            let digits = format!("{frac:0width$}", width = DECIMAL_SCALE as usize);
+            write!(f, ".{}", digits.trim_end_matches('0'))?;
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for Decimal {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Decimal {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Decimal::parse(&s)
+            .ok_or_else(|| serde::de::Error::custom(format!("invalid decimal: {s:?}")))
+    }
+}
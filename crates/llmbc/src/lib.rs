@@ -1,16 +1,26 @@
+pub mod bigint;
 pub mod capability;
+pub mod decimal;
 pub mod module;
 pub mod opcode;
+pub mod provenance;
+pub mod signing;
+pub mod table;
 #[cfg(test)]
 mod tests;
 pub mod value;
 
+pub use bigint::{BigInt, MAX_BIGINT_BITS};
 pub use capability::{
     capability_set_report, compute_capability_set_hash, Capability, CapabilityIdentity,
     CapabilitySetReport, CAPABILITY_REPORT_PROTOCOL_VERSION,
 };
-pub use module::{BytecodeError, Function, Module};
+pub use decimal::{Decimal, DECIMAL_SCALE};
+pub use module::{BytecodeError, CapabilitySkew, Function, Module};
 pub use opcode::{ContractKind, Op};
+pub use provenance::Provenance;
+pub use signing::ModuleSignature;
+pub use table::{Aggregate, Column, CompareOp, Table};
 pub use value::Value;
 
 /// Frozen bytecode specification version.
@@ -29,4 +39,15 @@ pub use value::Value;
 /// `Op::DebugMsg` (0xA8) per §1.2(6) of the spec doc. A 1.0 reader presented
 /// with a 1.1 module containing either opcode MUST reject with an unknown-
 /// opcode typed error.
-pub const BYTECODE_VERSION: &str = "1.1";
+///
+/// **1.2** — additive: introduces `Value::Decimal` (fixed-point, see
+/// [`decimal`]) and `Op::DecimalParse` (0xA9), `Op::DecimalToString` (0xAA),
+/// `Op::IntToDecimal` (0xAB); `Value::BigInt` (arbitrary precision, see
+/// [`bigint`]) and `Op::BigIntParse` (0xAC) through `Op::BigIntPowMod`
+/// (0xB0); `Op::JsonParse` (0xB1), `Op::JsonStringify` (0xB2);
+/// `Op::StrMatch` (0xB3) and `Op::StrFind` (0xB4); `Value::Table` (columnar,
+/// see [`table`]) and `Op::TableSelect` (0xB5), `Op::TableFilter` (0xB6),
+/// `Op::TableAggregate` (0xB7), `Op::TableFromColumns` (0xB8) through
+/// `Op::TableHash` (0xBE); `Op::AssertMsg` (0xBF) and `Op::Fail` (0xC0). A
+/// 1.1 reader MUST reject modules using them.
+pub const BYTECODE_VERSION: &str = "1.2";
@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
@@ -9,7 +11,30 @@ use crate::value::Value;
 pub const MAGIC: [u8; 4] = [0x4C, 0x4C, 0x4D, 0x42];
 pub const VERSION: u16 = 1;
 
-#[derive(Debug, Error)]
+/// A capability whose recorded contract version differs from the running
+/// binary's. `current` is `None` when this binary does not know the
+/// capability at all.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CapabilitySkew {
+    pub name: String,
+    pub recorded: String,
+    pub current: Option<String>,
+}
+
+impl std::fmt::Display for CapabilitySkew {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.current {
+            Some(v) => write!(f, "{} v{} (binary has v{v})", self.name, self.recorded),
+            None => write!(
+                f,
+                "{} v{} (unknown to this binary)",
+                self.name, self.recorded
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Error)]
 pub enum BytecodeError {
     #[error("invalid magic bytes")]
     InvalidMagic,
@@ -19,6 +44,8 @@ pub enum BytecodeError {
     InvalidBytecode(String),
     #[error("serialization error: {0}")]
     Serialization(String),
+    #[error("module signature error: {0}")]
+    Signature(String),
 }
 
 /// A pattern arm for match expressions.
@@ -76,6 +103,23 @@ pub struct Module {
     pub types: Vec<TypeDef>,
     pub functions: Vec<Function>,
     pub entry: u32,
+    /// Contract version (`Capability::version`) of every capability the
+    /// module declares or invokes, recorded at codegen time. The VM
+    /// refuses to run a module whose versions differ from the running
+    /// binary's unless capability skew is explicitly allowed. Empty for
+    /// capability-free modules and for modules compiled before this field
+    /// existed.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub capability_versions: BTreeMap<String, String>,
+    /// Compiler, source digest and package the module was built from;
+    /// see [`crate::provenance`]. Written by `boruna compile`; skipped
+    /// when absent so modules built without it encode unchanged.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<crate::provenance::Provenance>,
+    /// Present iff the module was signed (`boruna compile --sign-key`).
+    /// Skipped when absent so unsigned modules encode byte-identically.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<crate::signing::ModuleSignature>,
 }
 
 impl Module {
@@ -88,6 +132,9 @@ impl Module {
             types: Vec::new(),
             functions: Vec::new(),
             entry: 0,
+            capability_versions: BTreeMap::new(),
+            provenance: None,
+            signature: None,
         }
     }
 
@@ -185,7 +232,7 @@ impl Module {
     }
 
     /// The *inferred minimal* capability set `func_idx` actually needs: the
-    /// capabilities it invokes directly (via `CapCall`) plus everything its
+    /// capabilities it invokes directly (via `CapCall`, `CapBatch` or `CapSpawn`) plus everything its
     /// transitive callees need. Sorted by capability id, deterministic,
     /// cycle-safe. Compare against the function's declared `capabilities` to
     /// find over-grants (see [`Self::over_declared_capabilities`]).
@@ -213,7 +260,7 @@ impl Module {
         };
         for op in &f.code {
             match op {
-                Op::CapCall(cap_id, _) => {
+                Op::CapCall(cap_id, _) | Op::CapBatch(cap_id) | Op::CapSpawn(cap_id, _) => {
                     out.insert(*cap_id);
                 }
                 Op::Call(callee, _) | Op::SpawnActor(callee) => {
@@ -224,6 +271,43 @@ impl Module {
         }
     }
 
+    /// Record the running binary's contract version for every capability
+    /// any function declares or invokes via `CapCall`, `CapBatch` or `CapSpawn`. Called by codegen.
+    pub fn record_capability_versions(&mut self) {
+        let mut versions = BTreeMap::new();
+        for f in &self.functions {
+            let invoked = f.code.iter().filter_map(|op| match op {
+                Op::CapCall(id, _) | Op::CapBatch(id) | Op::CapSpawn(id, _) => {
+                    Capability::from_id(*id)
+                }
+                _ => None,
+            });
+            for cap in f.capabilities.iter().copied().chain(invoked) {
+                versions.insert(cap.name().to_string(), cap.version().to_string());
+            }
+        }
+        self.capability_versions = versions;
+    }
+
+    /// Recorded capability versions that differ from the running binary's,
+    /// sorted by capability name. Empty means the module runs with the
+    /// contracts it was compiled against.
+    pub fn capability_skew(&self) -> Vec<CapabilitySkew> {
+        self.capability_versions
+            .iter()
+            .filter_map(|(name, recorded)| {
+                let current = Capability::from_name(name)
+                    .filter(|c| c.name() == name)
+                    .map(|c| c.version().to_string());
+                (current.as_deref() != Some(recorded.as_str())).then(|| CapabilitySkew {
+                    name: name.clone(),
+                    recorded: recorded.clone(),
+                    current,
+                })
+            })
+            .collect()
+    }
+
     /// Capabilities `func_idx` declares but does not (transitively) need — an
     /// over-grant of authority (a least-privilege smell, not a correctness
     /// bug: the VM still gates at runtime). Sorted by capability id,
@@ -17,6 +17,15 @@ impl ContractKind {
             ContractKind::Ensures => "ensures",
         }
     }
+
+    /// Stable diagnostic code a violation of this clause kind is reported
+    /// under (see `docs/reference/diagnostic-codes.md`).
+    pub fn diagnostic_code(&self) -> &'static str {
+        match self {
+            ContractKind::Requires => "E013",
+            ContractKind::Ensures => "E014",
+        }
+    }
 }
 
 /// Bytecode instructions for the Boruna VM.
@@ -112,6 +121,21 @@ pub enum Op {
     /// Capability call: cap_id, arg_count. Args on stack.
     CapCall(u32, u8),
 
+    /// Batched capability call: cap_id. Pops a List whose items are the
+    /// argument Lists of each call, invokes the capability once per item
+    /// in one gateway round-trip, and pushes a List of per-item results
+    /// (`Ok(value)` or `Err(message)`), in item order. Bytecode 1.2.
+    CapBatch(u32),
+
+    /// Start a capability call without waiting for it: cap_id, arg_count.
+    /// Args on stack. Policy is checked now; pushes a `CapHandle`.
+    /// Bytecode 1.2.
+    CapSpawn(u32, u8),
+
+    /// Pop a `CapHandle` and push its call's result, first running every
+    /// spawned call still pending. Bytecode 1.2.
+    CapAwait,
+
     // Arithmetic
     Add,
     Sub,
@@ -277,6 +301,109 @@ pub enum Op {
     /// builtin. Same operational-only semantics as [`Op::Debug`].
     DebugMsg,
 
+    /// Pop a String; push `Some(Decimal)` if it parses as
+    /// `[-]digits[.digits]` with at most 9 fractional digits, else `None`.
+    /// Bytecode 1.2.
+    DecimalParse,
+
+    /// Pop a Decimal; push its canonical String form. Bytecode 1.2.
+    DecimalToString,
+
+    /// Pop an Int; push the exactly equal Decimal. Bytecode 1.2.
+    IntToDecimal,
+
+    /// Pop a String; push `Some(BigInt)` if it parses as `[-]digits`, else
+    /// `None`. Bytecode 1.2.
+    BigIntParse,
+
+    /// Pop a BigInt; push its decimal String form. Bytecode 1.2.
+    BigIntToString,
+
+    /// Pop an Int; push the equal BigInt. The explicit promotion path for
+    /// values that would overflow `i64`. Bytecode 1.2.
+    IntToBigInt,
+
+    /// Pop a BigInt; push `Some(Int)` if it fits in `i64`, else `None`.
+    /// Bytecode 1.2.
+    BigIntToInt,
+
+    /// Pop modulus, exponent, base (BigInt); push `base^exp mod |modulus|`.
+    /// Negative exponent is a type error; zero modulus is division by zero.
+    /// Bytecode 1.2.
+    BigIntPowMod,
+
+    /// Pop a String; push `Ok(value)` with the parsed JSON document (objects
+    /// as Map, arrays as List, null as None) or `Err(String)` with the parse
+    /// error. Bytecode 1.2.
+    JsonParse,
+
+    /// Pop a value; push its compact JSON String with sorted object keys.
+    /// Bytecode 1.2.
+    JsonStringify,
+
+    /// Pop text, then pattern (String); push Bool: whether the pattern
+    /// matches anywhere in the text. Matching steps count toward the VM
+    /// step limit. Bytecode 1.2.
+    StrMatch,
+
+    /// Pop text, then pattern (String); push `Some(String)` with the
+    /// leftmost-longest match, or `None`. Bytecode 1.2.
+    StrFind,
+
+    /// Pop a List of column names, then a Table; push a Table with just
+    /// those columns, in the listed order. Bytecode 1.2.
+    TableSelect,
+
+    /// Pop a value, an operator String (`==`, `!=`, `<`, `<=`, `>`, `>=`),
+    /// a column name and a Table; push the rows whose cell compares true.
+    /// Each input row counts as one VM step. Bytecode 1.2.
+    TableFilter,
+
+    /// Pop an aggregate name (`count`, `sum`, `min`, `max`, `mean`), a
+    /// column name, a List of group-by column names and a Table; push one
+    /// row per group with the result in column `<aggregate>_<column>`.
+    /// Each input row counts as one VM step. Bytecode 1.2.
+    TableAggregate,
+
+    /// Pop a List of columns (each a List of cells), then a List of
+    /// column names; push the Table. Backs the `Table { ... }` literal.
+    /// Bytecode 1.2.
+    TableFromColumns,
+
+    /// Pop a Bool (descending), a List of column names and a Table; push
+    /// the Table stably sorted by those columns, `None` cells first.
+    /// Each input row counts as one VM step. Bytecode 1.2.
+    TableSort,
+
+    /// Pop a column name, then a Table; push that column's cells as a
+    /// List. Bytecode 1.2.
+    TableColumn,
+
+    /// Pop a Table; push its column names as a List, in column order.
+    /// Bytecode 1.2.
+    TableColumnNames,
+
+    /// Pop a Table; push its row count as an Int. Bytecode 1.2.
+    TableLen,
+
+    /// Pop an Int index, then a Table; push `Some(Map)` of column name to
+    /// cell for that row, or `None` when out of range. Bytecode 1.2.
+    TableRow,
+
+    /// Pop a Table; push its canonical `sha256:<hex>` hash as a String.
+    /// Bytecode 1.2.
+    TableHash,
+
+    /// Pop a message, then a condition; trap with
+    /// `VmError::UserAssertion` carrying the message if the condition is
+    /// falsy, else push `Unit`. Used by the `assert(cond, msg)` builtin.
+    /// Unlike [`Op::Assert`] it records no contract event. Bytecode 1.2.
+    AssertMsg,
+
+    /// Pop a message; trap with `VmError::UserFailure` carrying it. Used
+    /// by the `fail(msg)` builtin. Bytecode 1.2.
+    Fail,
+
     /// No operation.
     Nop,
 
@@ -309,6 +436,9 @@ impl Op {
             Op::Assert { .. } => 0x12,
             Op::CapCall(_, _) => 0x13,
             Op::GuardSeal => 0x15,
+            Op::CapBatch(_) => 0x16,
+            Op::CapSpawn(_, _) => 0x17,
+            Op::CapAwait => 0x18,
             Op::Add => 0x20,
             Op::Sub => 0x21,
             Op::Mul => 0x22,
@@ -369,6 +499,30 @@ impl Op {
             Op::MapLen => 0xA6,
             Op::Debug => 0xA7,
             Op::DebugMsg => 0xA8,
+            Op::DecimalParse => 0xA9,
+            Op::DecimalToString => 0xAA,
+            Op::IntToDecimal => 0xAB,
+            Op::BigIntParse => 0xAC,
+            Op::BigIntToString => 0xAD,
+            Op::IntToBigInt => 0xAE,
+            Op::BigIntToInt => 0xAF,
+            Op::BigIntPowMod => 0xB0,
+            Op::JsonParse => 0xB1,
+            Op::JsonStringify => 0xB2,
+            Op::StrMatch => 0xB3,
+            Op::StrFind => 0xB4,
+            Op::TableSelect => 0xB5,
+            Op::TableFilter => 0xB6,
+            Op::TableAggregate => 0xB7,
+            Op::TableFromColumns => 0xB8,
+            Op::TableSort => 0xB9,
+            Op::TableColumn => 0xBA,
+            Op::TableColumnNames => 0xBB,
+            Op::TableLen => 0xBC,
+            Op::TableRow => 0xBD,
+            Op::TableHash => 0xBE,
+            Op::AssertMsg => 0xBF,
+            Op::Fail => 0xC0,
             Op::Nop => 0xFE,
             Op::Halt => 0xFF,
         }
@@ -0,0 +1,69 @@
+//! Build provenance embedded in a compiled [`Module`](crate::Module).
+//!
+//! `boruna compile` records which compiler built the module, a digest of
+//! the source it was built from, and the package it belongs to, so an
+//! `.axbc` file (and every event log and evidence bundle that references
+//! it) can be traced back to the exact source. Every field is a
+//! deterministic function of the inputs: the build time is taken from
+//! `SOURCE_DATE_EPOCH` when the builder sets it and is otherwise absent,
+//! never read from the clock, so two builds of the same source still
+//! produce identical bytes.
+//!
+//! The section is skipped when absent, so modules compiled without it
+//! (and modules from older compilers) encode unchanged. It is part of
+//! the signing payload: a signed module's provenance cannot be altered
+//! without breaking the signature.
+
+use boruna_hash::HashAlgorithm;
+use serde::{Deserialize, Serialize};
+
+/// Environment variable holding the normalized build time, in seconds
+/// since the Unix epoch (the reproducible-builds convention).
+pub const SOURCE_DATE_EPOCH_ENV: &str = "SOURCE_DATE_EPOCH";
+
+/// Where a module came from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Provenance {
+    /// Compiler that produced the module, e.g. `boruna-compiler 3.2.0`.
+    pub compiler: String,
+    /// Tagged digest of the source file as read, before import
+    /// resolution: `sha256:<hex>`.
+    pub source_hash: String,
+    /// `SOURCE_DATE_EPOCH` at build time, when set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub built_at: Option<u64>,
+    /// `name@version` of the package the source belongs to, when it
+    /// belongs to one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub package: Option<String>,
+}
+
+impl Provenance {
+    /// Provenance for `source` compiled by `compiler`, without a build
+    /// time or package.
+    pub fn new(compiler: impl Into<String>, source: &str) -> Self {
+        Provenance {
+            compiler: compiler.into(),
+            source_hash: HashAlgorithm::Sha256.digest(source).to_string(),
+            built_at: None,
+            package: None,
+        }
+    }
+
+    /// Set `built_at`; callers pass `SOURCE_DATE_EPOCH`, never the
+    /// current time.
+    pub fn with_built_at(mut self, built_at: Option<u64>) -> Self {
+        self.built_at = built_at;
+        self
+    }
+
+    pub fn with_package(mut self, package: Option<String>) -> Self {
+        self.package = package;
+        self
+    }
+
+    /// Whether `source` is the source this module was built from.
+    pub fn matches_source(&self, source: &str) -> bool {
+        boruna_hash::Digest::parse(&self.source_hash).is_ok_and(|d| d.matches(source))
+    }
+}
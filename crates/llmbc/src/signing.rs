@@ -0,0 +1,97 @@
+//! Optional ed25519 signatures over compiled modules.
+//!
+//! The signature covers the module's canonical JSON encoding with the
+//! `signature` field absent — the same bytes an unsigned module serializes
+//! to — so signing never changes what is signed and an unsigned module's
+//! encoding is unchanged. `boruna compile --sign-key` produces signed
+//! `.axbc` files; the VM enforces `Policy.require_signed_modules` against a
+//! trusted key list.
+
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+
+use crate::module::{BytecodeError, Module};
+
+/// The only supported signature algorithm.
+pub const SIGNATURE_ALGORITHM: &str = "ed25519";
+
+/// Signature section embedded in a [`Module`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModuleSignature {
+    pub algorithm: String,
+    /// ed25519 public key, 64 hex chars.
+    pub public_key: String,
+    /// ed25519 signature over [`Module::signing_payload`], 128 hex chars.
+    pub signature: String,
+}
+
+impl Module {
+    /// Canonical bytes covered by the signature: the JSON encoding with
+    /// `signature` cleared.
+    pub fn signing_payload(&self) -> Result<Vec<u8>, BytecodeError> {
+        let mut unsigned = self.clone();
+        unsigned.signature = None;
+        serde_json::to_vec(&unsigned).map_err(|e| BytecodeError::Serialization(e.to_string()))
+    }
+
+    /// Sign the module in place with the ed25519 key derived from `seed`,
+    /// replacing any existing signature.
+    pub fn sign(&mut self, seed: &[u8; 32]) -> Result<(), BytecodeError> {
+        let key = SigningKey::from_bytes(seed);
+        let payload = self.signing_payload()?;
+        let sig = key.sign(&payload);
+        self.signature = Some(ModuleSignature {
+            algorithm: SIGNATURE_ALGORITHM.to_string(),
+            public_key: to_hex(key.verifying_key().as_bytes()),
+            signature: to_hex(&sig.to_bytes()),
+        });
+        Ok(())
+    }
+
+    /// Check that the module carries a signature valid for its embedded
+    /// public key. Returns the signature section; whether that key is
+    /// trusted is the caller's decision.
+    pub fn verify_signature(&self) -> Result<&ModuleSignature, BytecodeError> {
+        let sig = self
+            .signature
+            .as_ref()
+            .ok_or_else(|| BytecodeError::Signature("module is unsigned".into()))?;
+        if sig.algorithm != SIGNATURE_ALGORITHM {
+            return Err(BytecodeError::Signature(format!(
+                "unsupported algorithm: {}",
+                sig.algorithm
+            )));
+        }
+        let key = VerifyingKey::from_bytes(&parse_hex::<32>(&sig.public_key)?)
+            .map_err(|e| BytecodeError::Signature(format!("invalid public key: {e}")))?;
+        let signature = Signature::from_bytes(&parse_hex::<64>(&sig.signature)?);
+        key.verify(&self.signing_payload()?, &signature)
+            .map_err(|_| BytecodeError::Signature("signature does not match module".into()))?;
+        Ok(sig)
+    }
+}
+
+/// Parse a 32-byte key or seed from 64 hex chars.
+pub fn parse_key_hex(s: &str) -> Result<[u8; 32], BytecodeError> {
+    parse_hex::<32>(s.trim())
+}
+
+fn parse_hex<const N: usize>(s: &str) -> Result<[u8; N], BytecodeError> {
+    if s.len() != N * 2 || !s.is_ascii() {
+        return Err(BytecodeError::Signature(format!(
+            "expected {} hex chars, got {}",
+            N * 2,
+            s.len()
+        )));
+    }
+    let mut out = [0u8; N];
+    for (i, byte) in out.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&s[i * 2..i * 2 + 2], 16)
+            .map_err(|_| BytecodeError::Signature(format!("invalid hex: {s}")))?;
+    }
+    Ok(out)
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
@@ -0,0 +1,518 @@
+//! Columnar table backing `Value::Table` (bytecode 1.2).
+//!
+//! A [`Table`] is a list of named columns of equal length. Columns keep
+//! the order they were declared or loaded in, and every operation visits
+//! rows in index order, so the same input always produces the same table.
+//! The operations here are the pure part of the table opcodes; the VM
+//! only pops arguments and charges steps.
+//!
+//! **Schema.** Every non-`None` cell of a column has the same type, so a
+//! column has one type name ([`Table::schema`]); `None` marks a missing
+//! cell in any column. Construction and deserialization both reject a
+//! column that mixes types.
+//!
+//! **Ordering.** Sorting is stable and puts `None` cells first; group-by
+//! emits groups in that same key order, so the result of an aggregate
+//! does not depend on the order of the input rows.
+//!
+//! **Hashing.** [`Table::canonical_hash`] is the SHA-256 of the table's
+//! serialized form, which lists columns in order with their cells. Equal
+//! tables hash equal on every host.
+
+use std::cmp::Ordering;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use std::collections::BTreeMap;
+
+use crate::decimal::Decimal;
+use crate::value::Value;
+
+/// One named column.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Column {
+    pub name: String,
+    pub values: Vec<Value>,
+}
+
+/// Named columns of equal length, in a fixed order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "TableRepr", into = "TableRepr")]
+pub struct Table {
+    columns: Vec<Column>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TableRepr {
+    columns: Vec<Column>,
+}
+
+impl TryFrom<TableRepr> for Table {
+    type Error = String;
+
+    fn try_from(repr: TableRepr) -> Result<Self, String> {
+        Table::new(repr.columns)
+    }
+}
+
+impl From<Table> for TableRepr {
+    fn from(table: Table) -> Self {
+        TableRepr {
+            columns: table.columns,
+        }
+    }
+}
+
+/// Comparison used by [`Table::filter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl CompareOp {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "==" => Some(CompareOp::Eq),
+            "!=" => Some(CompareOp::Ne),
+            "<" => Some(CompareOp::Lt),
+            "<=" => Some(CompareOp::Le),
+            ">" => Some(CompareOp::Gt),
+            ">=" => Some(CompareOp::Ge),
+            _ => None,
+        }
+    }
+
+    fn holds(self, ord: Ordering) -> bool {
+        match self {
+            CompareOp::Eq => ord == Ordering::Equal,
+            CompareOp::Ne => ord != Ordering::Equal,
+            CompareOp::Lt => ord == Ordering::Less,
+            CompareOp::Le => ord != Ordering::Greater,
+            CompareOp::Gt => ord == Ordering::Greater,
+            CompareOp::Ge => ord != Ordering::Less,
+        }
+    }
+}
+
+/// Aggregate used by [`Table::aggregate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregate {
+    Count,
+    Sum,
+    Min,
+    Max,
+    Mean,
+}
+
+impl Aggregate {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "count" => Some(Aggregate::Count),
+            "sum" => Some(Aggregate::Sum),
+            "min" => Some(Aggregate::Min),
+            "max" => Some(Aggregate::Max),
+            "mean" => Some(Aggregate::Mean),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Aggregate::Count => "count",
+            Aggregate::Sum => "sum",
+            Aggregate::Min => "min",
+            Aggregate::Max => "max",
+            Aggregate::Mean => "mean",
+        }
+    }
+}
+
+/// Order two cell values of compatible type. `Int`, `Float` and
+/// `Decimal` compare numerically with each other; `String` and `Bool`
+/// compare with their own kind. Anything else is incomparable.
+pub fn compare_cells(a: &Value, b: &Value) -> Option<Ordering> {
+    match (a, b) {
+        (Value::Int(x), Value::Int(y)) => Some(x.cmp(y)),
+        (Value::Decimal(x), Value::Decimal(y)) => Some(x.cmp(y)),
+        (Value::Int(x), Value::Decimal(y)) => Some(Decimal::from_int(*x).cmp(y)),
+        (Value::Decimal(x), Value::Int(y)) => Some(x.cmp(&Decimal::from_int(*y))),
+        (Value::Float(_) | Value::Int(_), Value::Float(_) | Value::Int(_)) => {
+            as_f64(a)?.partial_cmp(&as_f64(b)?)
+        }
+        (Value::String(x), Value::String(y)) => Some(x.cmp(y)),
+        (Value::Bool(x), Value::Bool(y)) => Some(x.cmp(y)),
+        (Value::None, Value::None) => Some(Ordering::Equal),
+        _ => None,
+    }
+}
+
+/// Total order used for sorting and grouping: `None` first, then cells
+/// by [`compare_cells`]. Floats fall back to IEEE total order so NaN
+/// still has a place.
+fn order_cells(a: &Value, b: &Value) -> Ordering {
+    match (a, b) {
+        (Value::None, Value::None) => Ordering::Equal,
+        (Value::None, _) => Ordering::Less,
+        (_, Value::None) => Ordering::Greater,
+        (Value::Float(x), Value::Float(y)) => x.total_cmp(y),
+        _ => compare_cells(a, b).unwrap_or(Ordering::Equal),
+    }
+}
+
+fn order_keys(a: &[Value], b: &[Value]) -> Ordering {
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| order_cells(x, y))
+        .find(|o| o.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+fn as_f64(v: &Value) -> Option<f64> {
+    match v {
+        Value::Int(n) => Some(*n as f64),
+        Value::Float(f) => Some(*f),
+        _ => None,
+    }
+}
+
+impl Table {
+    /// Build a table, checking that column names are unique, every
+    /// column has the same length and each column holds one type.
+    pub fn new(columns: Vec<Column>) -> Result<Self, String> {
+        for (i, col) in columns.iter().enumerate() {
+            if columns[..i].iter().any(|c| c.name == col.name) {
+                return Err(format!("duplicate column '{}'", col.name));
+            }
+            if col.values.len() != columns[0].values.len() {
+                return Err(format!(
+                    "column '{}' has {} rows, expected {}",
+                    col.name,
+                    col.values.len(),
+                    columns[0].values.len()
+                ));
+            }
+            let ty = column_type(&col.values);
+            if let Some(other) = col
+                .values
+                .iter()
+                .find(|v| !matches!(v, Value::None) && v.type_name() != ty)
+            {
+                return Err(format!(
+                    "column '{}' mixes {ty} and {}",
+                    col.name,
+                    other.type_name()
+                ));
+            }
+        }
+        Ok(Table { columns })
+    }
+
+    /// Build a table from parallel lists of names and column values.
+    pub fn from_columns(names: Vec<String>, values: Vec<Vec<Value>>) -> Result<Self, String> {
+        if names.len() != values.len() {
+            return Err(format!(
+                "{} column names for {} columns",
+                names.len(),
+                values.len()
+            ));
+        }
+        Table::new(
+            names
+                .into_iter()
+                .zip(values)
+                .map(|(name, values)| Column { name, values })
+                .collect(),
+        )
+    }
+
+    /// Column names with their cell type, in column order. A column with
+    /// no cells, or only `None` cells, has type `"None"`.
+    pub fn schema(&self) -> Vec<(&str, &'static str)> {
+        self.columns
+            .iter()
+            .map(|c| (c.name.as_str(), column_type(&c.values)))
+            .collect()
+    }
+
+    pub fn columns(&self) -> &[Column] {
+        &self.columns
+    }
+
+    pub fn column_names(&self) -> Vec<&str> {
+        self.columns.iter().map(|c| c.name.as_str()).collect()
+    }
+
+    pub fn num_rows(&self) -> usize {
+        self.columns.first().map_or(0, |c| c.values.len())
+    }
+
+    pub fn column(&self, name: &str) -> Option<&Column> {
+        self.columns.iter().find(|c| c.name == name)
+    }
+
+    /// Row `index` as a map from column name to cell.
+    pub fn row(&self, index: usize) -> Option<BTreeMap<String, Value>> {
+        (index < self.num_rows()).then(|| {
+            self.columns
+                .iter()
+                .map(|c| (c.name.clone(), c.values[index].clone()))
+                .collect()
+        })
+    }
+
+    /// `sha256:<hex>` over the serialized table.
+    pub fn canonical_hash(&self) -> String {
+        let bytes = serde_json::to_vec(self).expect("tables always serialize");
+        format!("sha256:{}", boruna_hash::sha256_hex(bytes))
+    }
+
+    fn require(&self, name: &str) -> Result<&Column, String> {
+        self.column(name).ok_or_else(|| {
+            format!(
+                "no column '{name}' (columns: {})",
+                self.column_names().join(", ")
+            )
+        })
+    }
+
+    /// The named columns, in the order given.
+    pub fn select(&self, names: &[String]) -> Result<Table, String> {
+        let columns = names
+            .iter()
+            .map(|n| self.require(n).cloned())
+            .collect::<Result<Vec<_>, _>>()?;
+        Table::new(columns)
+    }
+
+    /// The rows whose `column` compares to `value` as `op` says. Rows
+    /// whose cell is incomparable with `value` (a `None` cell, or a
+    /// different type) are dropped.
+    pub fn filter(&self, column: &str, op: CompareOp, value: &Value) -> Result<Table, String> {
+        let keep: Vec<usize> = self
+            .require(column)?
+            .values
+            .iter()
+            .enumerate()
+            .filter(|(_, cell)| compare_cells(cell, value).is_some_and(|ord| op.holds(ord)))
+            .map(|(i, _)| i)
+            .collect();
+        Ok(self.take_rows(&keep))
+    }
+
+    /// The rows reordered by the `by` columns, compared left to right.
+    /// The sort is stable, so rows with equal keys keep their order.
+    pub fn sort_by(&self, by: &[String], descending: bool) -> Result<Table, String> {
+        let keys: Vec<&Column> = by
+            .iter()
+            .map(|n| self.require(n))
+            .collect::<Result<_, _>>()?;
+        let mut order: Vec<usize> = (0..self.num_rows()).collect();
+        order.sort_by(|&a, &b| {
+            let ord = keys
+                .iter()
+                .map(|c| order_cells(&c.values[a], &c.values[b]))
+                .find(|o| o.is_ne())
+                .unwrap_or(Ordering::Equal);
+            if descending {
+                ord.reverse()
+            } else {
+                ord
+            }
+        });
+        Ok(self.take_rows(&order))
+    }
+
+    fn take_rows(&self, rows: &[usize]) -> Table {
+        Table {
+            columns: self
+                .columns
+                .iter()
+                .map(|c| Column {
+                    name: c.name.clone(),
+                    values: rows.iter().map(|r| c.values[*r].clone()).collect(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Group rows by the `group_by` columns and reduce `column` in each
+    /// group with `agg`. The result has the group columns followed by one
+    /// column named `<agg>_<column>`; groups appear in ascending key
+    /// order. `None` cells are skipped by every aggregate but
+    /// `count`, which counts rows.
+    pub fn aggregate(
+        &self,
+        group_by: &[String],
+        column: &str,
+        agg: Aggregate,
+    ) -> Result<Table, String> {
+        let keys: Vec<&Column> = group_by
+            .iter()
+            .map(|n| self.require(n))
+            .collect::<Result<_, _>>()?;
+        let target = self.require(column)?;
+
+        // Visit rows in key order (stable, so rows within a group keep
+        // their input order) and start a group at each new key.
+        let mut order: Vec<usize> = (0..self.num_rows()).collect();
+        let key =
+            |row: usize| -> Vec<Value> { keys.iter().map(|c| c.values[row].clone()).collect() };
+        order.sort_by(|&a, &b| order_keys(&key(a), &key(b)));
+        let mut group_keys: Vec<Vec<Value>> = Vec::new();
+        let mut members: Vec<Vec<usize>> = Vec::new();
+        for row in order {
+            let k = key(row);
+            match group_keys.last() {
+                Some(last) if order_keys(last, &k).is_eq() => {
+                    members.last_mut().expect("group exists").push(row)
+                }
+                _ => {
+                    group_keys.push(k);
+                    members.push(vec![row]);
+                }
+            }
+        }
+
+        let mut results = Vec::with_capacity(members.len());
+        for rows in &members {
+            let cells: Vec<&Value> = rows.iter().map(|r| &target.values[*r]).collect();
+            results.push(reduce(agg, column, &cells)?);
+        }
+
+        let mut columns: Vec<Column> = keys
+            .iter()
+            .enumerate()
+            .map(|(i, c)| Column {
+                name: c.name.clone(),
+                values: group_keys.iter().map(|k| k[i].clone()).collect(),
+            })
+            .collect();
+        columns.push(Column {
+            name: format!("{}_{column}", agg.name()),
+            values: results,
+        });
+        Table::new(columns)
+    }
+}
+
+/// The type name shared by a column's non-`None` cells.
+fn column_type(values: &[Value]) -> &'static str {
+    values
+        .iter()
+        .find(|v| !matches!(v, Value::None))
+        .map_or("None", Value::type_name)
+}
+
+fn reduce(agg: Aggregate, column: &str, cells: &[&Value]) -> Result<Value, String> {
+    if agg == Aggregate::Count {
+        return Ok(Value::Int(cells.len() as i64));
+    }
+    let present: Vec<&Value> = cells
+        .iter()
+        .copied()
+        .filter(|v| !matches!(v, Value::None))
+        .collect();
+    match agg {
+        Aggregate::Min | Aggregate::Max => {
+            let mut best: Option<&Value> = None;
+            for v in present {
+                best = match best {
+                    None => Some(v),
+                    Some(b) => {
+                        let ord = compare_cells(v, b).ok_or_else(|| {
+                            format!(
+                                "{}({column}): cannot compare {} with {}",
+                                agg.name(),
+                                v.type_name(),
+                                b.type_name()
+                            )
+                        })?;
+                        let better = if agg == Aggregate::Min {
+                            ord == Ordering::Less
+                        } else {
+                            ord == Ordering::Greater
+                        };
+                        Some(if better { v } else { b })
+                    }
+                };
+            }
+            Ok(best.cloned().unwrap_or(Value::None))
+        }
+        Aggregate::Sum | Aggregate::Mean => {
+            let not_numeric = |v: &Value| {
+                format!(
+                    "{}({column}): expected a numeric column, got {}",
+                    agg.name(),
+                    v.type_name()
+                )
+            };
+            let sum = if present.iter().all(|v| matches!(v, Value::Int(_))) {
+                let mut total: i64 = 0;
+                for v in &present {
+                    if let Value::Int(n) = v {
+                        total = total
+                            .checked_add(*n)
+                            .ok_or_else(|| format!("sum({column}): Int overflow"))?;
+                    }
+                }
+                Value::Int(total)
+            } else if present.iter().all(|v| matches!(v, Value::Decimal(_))) {
+                let mut total = Decimal::ZERO;
+                for v in &present {
+                    if let Value::Decimal(d) = v {
+                        total = total
+                            .checked_add(*d)
+                            .ok_or_else(|| format!("sum({column}): Decimal overflow"))?;
+                    }
+                }
+                Value::Decimal(total)
+            } else {
+                let mut total = 0.0;
+                for v in &present {
+                    total += as_f64(v).ok_or_else(|| not_numeric(v))?;
+                }
+                Value::Float(total)
+            };
+            if agg == Aggregate::Sum {
+                return Ok(sum);
+            }
+            if present.is_empty() {
+                return Ok(Value::None);
+            }
+            let total = match sum {
+                Value::Int(n) => n as f64,
+                Value::Decimal(d) => d.to_string().parse().unwrap_or(f64::NAN),
+                Value::Float(f) => f,
+                _ => unreachable!("sum is numeric"),
+            };
+            Ok(Value::Float(total / present.len() as f64))
+        }
+        Aggregate::Count => unreachable!("handled above"),
+    }
+}
+
+impl fmt::Display for Table {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Table({})[", self.column_names().join(", "))?;
+        for row in 0..self.num_rows() {
+            if row > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "(")?;
+            for (i, col) in self.columns.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{}", col.values[row])?;
+            }
+            write!(f, ")")?;
+        }
+        write!(f, "]")
+    }
+}
@@ -9,10 +9,11 @@ mod tests {
     /// is a coordinated spec freeze, not a routine release operation.
     ///
     /// **1.1** added `Op::Debug` (0xA7) and `Op::DebugMsg` (0xA8) per
-    /// §1.2(6) of the spec — additive opcode minor bump.
+    /// §1.2(6) of the spec — additive opcode minor bump. **1.2** added
+    /// `Value::Decimal` and the decimal opcodes (0xA9–0xAB).
     #[test]
-    fn test_bytecode_version_is_1_1() {
-        assert_eq!(BYTECODE_VERSION, "1.1");
+    fn test_bytecode_version_is_1_2() {
+        assert_eq!(BYTECODE_VERSION, "1.2");
     }
 
     /// The new 1.1 opcodes must have stable byte tags that do not collide
@@ -36,6 +37,30 @@ mod tests {
             Op::MapLen.to_byte_tag(),
             Op::Debug.to_byte_tag(),
             Op::DebugMsg.to_byte_tag(),
+            Op::DecimalParse.to_byte_tag(),
+            Op::DecimalToString.to_byte_tag(),
+            Op::IntToDecimal.to_byte_tag(),
+            Op::BigIntParse.to_byte_tag(),
+            Op::BigIntToString.to_byte_tag(),
+            Op::IntToBigInt.to_byte_tag(),
+            Op::BigIntToInt.to_byte_tag(),
+            Op::BigIntPowMod.to_byte_tag(),
+            Op::JsonParse.to_byte_tag(),
+            Op::JsonStringify.to_byte_tag(),
+            Op::StrMatch.to_byte_tag(),
+            Op::StrFind.to_byte_tag(),
+            Op::TableSelect.to_byte_tag(),
+            Op::TableFilter.to_byte_tag(),
+            Op::TableAggregate.to_byte_tag(),
+            Op::TableFromColumns.to_byte_tag(),
+            Op::TableSort.to_byte_tag(),
+            Op::TableColumn.to_byte_tag(),
+            Op::TableColumnNames.to_byte_tag(),
+            Op::TableLen.to_byte_tag(),
+            Op::TableRow.to_byte_tag(),
+            Op::TableHash.to_byte_tag(),
+            Op::AssertMsg.to_byte_tag(),
+            Op::Fail.to_byte_tag(),
             Op::Nop.to_byte_tag(),
             Op::Halt.to_byte_tag(),
         ];
@@ -92,6 +117,353 @@ mod tests {
         assert_eq!(module, restored);
     }
 
+    #[test]
+    fn test_module_signature_roundtrip_and_tamper() {
+        let mut module = Module::new("test");
+        module.add_const(Value::Int(100));
+        let unsigned_json = module.to_json().unwrap();
+        assert!(!unsigned_json.contains("signature"));
+        assert!(module.verify_signature().is_err());
+
+        module.sign(&[1u8; 32]).unwrap();
+        assert_eq!(module.signing_payload().unwrap(), {
+            let mut m = module.clone();
+            m.signature = None;
+            serde_json::to_vec(&m).unwrap()
+        });
+        let restored = Module::from_bytes(&module.to_bytes().unwrap()).unwrap();
+        assert_eq!(module, restored);
+        let sig = restored.verify_signature().unwrap();
+        assert_eq!(sig.algorithm, "ed25519");
+        assert_eq!(sig.public_key.len(), 64);
+
+        let mut tampered = restored.clone();
+        tampered.constants[0] = Value::Int(101);
+        assert!(matches!(
+            tampered.verify_signature(),
+            Err(BytecodeError::Signature(_))
+        ));
+    }
+
+    #[test]
+    fn test_module_provenance_roundtrip_and_signed() {
+        let mut module = Module::new("test");
+        module.add_const(Value::Int(100));
+        let without = module.to_json().unwrap();
+        assert!(!without.contains("provenance"));
+
+        let source = "fn main() -> Int { 100 }";
+        module.provenance = Some(
+            Provenance::new("boruna-compiler 0.0.0", source)
+                .with_built_at(Some(1_700_000_000))
+                .with_package(Some("demo@1.0.0".into())),
+        );
+        let p = module.provenance.as_ref().unwrap();
+        assert!(p.source_hash.starts_with("sha256:"));
+        assert!(p.matches_source(source));
+        assert!(!p.matches_source("fn main() -> Int { 101 }"));
+
+        module.sign(&[1u8; 32]).unwrap();
+        let restored = Module::from_bytes(&module.to_bytes().unwrap()).unwrap();
+        assert_eq!(module, restored);
+        assert!(restored.verify_signature().is_ok());
+
+        let mut tampered = restored.clone();
+        tampered.provenance.as_mut().unwrap().package = Some("demo@2.0.0".into());
+        assert!(matches!(
+            tampered.verify_signature(),
+            Err(BytecodeError::Signature(_))
+        ));
+    }
+
+    #[test]
+    fn test_capability_versions_recorded_and_skew_detected() {
+        let mut module = Module::new("test");
+        module.add_function(Function {
+            name: "main".into(),
+            arity: 0,
+            locals: 0,
+            code: vec![Op::CapCall(Capability::TimeNow.id(), 0), Op::Ret],
+            capabilities: vec![Capability::FsRead],
+            intent: None,
+            match_tables: vec![],
+        });
+        module.record_capability_versions();
+        let names: Vec<&str> = module
+            .capability_versions
+            .keys()
+            .map(String::as_str)
+            .collect();
+        assert_eq!(names, vec!["fs.read", "time.now"]);
+        assert!(module.capability_skew().is_empty());
+
+        module
+            .capability_versions
+            .insert("fs.read".into(), "0".into());
+        module
+            .capability_versions
+            .insert("fs.chmod".into(), "1".into());
+        let skew = module.capability_skew();
+        assert_eq!(skew.len(), 2);
+        assert_eq!(skew[0].name, "fs.chmod");
+        assert_eq!(skew[0].current, None);
+        assert_eq!(skew[1].name, "fs.read");
+        assert_eq!(skew[1].recorded, "0");
+        assert_eq!(skew[1].current.as_deref(), Some("1"));
+
+        // Capability-free modules encode without the field.
+        assert!(!Module::new("empty")
+            .to_json()
+            .unwrap()
+            .contains("capability_versions"));
+    }
+
+    #[test]
+    fn test_decimal_parse_display_roundtrip() {
+        for (src, canonical) in [
+            ("12.50", "12.5"),
+            ("3", "3"),
+            ("-0.000000001", "-0.000000001"),
+            ("0.0", "0"),
+            ("-7.25", "-7.25"),
+        ] {
+            let d = Decimal::parse(src).unwrap();
+            assert_eq!(d.to_string(), canonical);
+            assert_eq!(Decimal::parse(canonical), Some(d));
+        }
+        for bad in [
+            "",
+            "-",
+            "1.",
+            ".5",
+            "1.0000000001",
+            "1e3",
+            "+1",
+            "1.2.3",
+            " 1",
+        ] {
+            assert_eq!(Decimal::parse(bad), None, "{bad:?}");
+        }
+    }
+
+    #[test]
+    fn test_decimal_arithmetic_rounds_half_even() {
+        let d = |s| Decimal::parse(s).unwrap();
+        assert_eq!(d("0.1").checked_add(d("0.2")), Some(d("0.3")));
+        assert_eq!(d("19.99").checked_mul(d("3")), Some(d("59.97")));
+        assert_eq!(d("1").checked_div(d("3")), Some(d("0.333333333")));
+        assert_eq!(d("2").checked_div(d("3")), Some(d("0.666666667")));
+        // 0.000000001 * 0.5 is exactly half a unit: ties go to even (0).
+        assert_eq!(d("0.000000001").checked_mul(d("0.5")), Some(Decimal::ZERO));
+        assert_eq!(
+            d("0.000000003").checked_mul(d("0.5")),
+            Some(d("0.000000002"))
+        );
+        assert_eq!(d("-1").checked_div(d("3")), Some(d("-0.333333333")));
+        assert_eq!(d("1").checked_div(Decimal::ZERO), None);
+        assert_eq!(
+            Decimal::from_raw(i128::MAX).checked_add(d("0.000000001")),
+            None
+        );
+        assert_eq!(Decimal::from_int(-4), d("-4"));
+    }
+
+    #[test]
+    fn test_decimal_mul_past_i128_intermediate() {
+        let d = |s| Decimal::parse(s).unwrap();
+        // The raw product (10^40) overflows i128; the result does not.
+        let big = d("100000000000");
+        assert_eq!(big.checked_mul(big), Some(d("10000000000000000000000")));
+        assert_eq!(
+            big.checked_mul(d("-100000000000.5")),
+            Some(d("-10000000000050000000000"))
+        );
+        assert_eq!(
+            d("1000000000000000000000").checked_div(d("0.001")),
+            Some(d("1000000000000000000000000"))
+        );
+        let max = Decimal::from_raw(i128::MAX);
+        assert_eq!(max.checked_mul(d("1")), Some(max));
+        assert_eq!(max.checked_mul(d("1.000000001")), None);
+        let min = Decimal::from_raw(i128::MIN);
+        assert_eq!(min.checked_mul(d("1")), Some(min));
+        assert_eq!(min.checked_mul(d("-1")), None);
+    }
+
+    #[test]
+    fn test_decimal_value_serializes_as_string() {
+        let v = Value::Decimal(Decimal::parse("1234567890123456789.5").unwrap());
+        let json = serde_json::to_string(&v).unwrap();
+        assert_eq!(json, r#"{"Decimal":"1234567890123456789.5"}"#);
+        assert_eq!(serde_json::from_str::<Value>(&json).unwrap(), v);
+        assert_eq!(v.type_name(), "Decimal");
+        assert_eq!(v.to_string(), "1234567890123456789.5d");
+        assert!(!Value::Decimal(Decimal::ZERO).is_truthy());
+    }
+
+    #[test]
+    fn test_bigint_arithmetic_past_i64() {
+        let b = |s| BigInt::parse(s).unwrap();
+        let max = BigInt::from_int(i64::MAX);
+        let sum = max.checked_add(&BigInt::from_int(1)).unwrap();
+        assert_eq!(sum.to_string(), "9223372036854775808");
+        assert_eq!(sum.to_int(), None);
+        assert_eq!(max.to_int(), Some(i64::MAX));
+        assert_eq!(
+            max.checked_mul(&max).unwrap(),
+            b("85070591730234615847396907784232501249")
+        );
+        // Truncating division and dividend-signed remainder, like Int.
+        assert_eq!(b("-7").checked_div(&b("2")), Some(b("-3")));
+        assert_eq!(b("-7").checked_rem(&b("2")), Some(b("-1")));
+        assert_eq!(b("1").checked_div(&BigInt::default()), None);
+        assert_eq!(b("4").pow_mod(&b("13"), &b("497")), Some(b("445")));
+        assert_eq!(b("-2").pow_mod(&b("3"), &b("5")), Some(b("2")));
+        assert_eq!(b("2").pow_mod(&b("-1"), &b("5")), None);
+        for bad in ["", "-", "+1", "1_000", "1.0", " 1", "0x10"] {
+            assert_eq!(BigInt::parse(bad), None, "{bad:?}");
+        }
+    }
+
+    #[test]
+    fn test_bigint_size_is_capped() {
+        let near_cap = BigInt::parse(&format!("1{}", "0".repeat(19_000))).unwrap();
+        assert!(near_cap.bits() <= MAX_BIGINT_BITS);
+        assert_eq!(near_cap.checked_mul(&near_cap), None);
+        assert!(BigInt::parse(&"9".repeat(20_000)).is_none());
+    }
+
+    #[test]
+    fn test_bigint_value_serializes_as_string() {
+        let v = Value::BigInt(BigInt::parse("-123456789012345678901234567890").unwrap());
+        let json = serde_json::to_string(&v).unwrap();
+        assert_eq!(json, r#"{"BigInt":"-123456789012345678901234567890"}"#);
+        assert_eq!(serde_json::from_str::<Value>(&json).unwrap(), v);
+        assert_eq!(v.type_name(), "BigInt");
+        assert_eq!(v.to_string(), "-123456789012345678901234567890n");
+        assert!(!Value::BigInt(BigInt::default()).is_truthy());
+    }
+
+    fn sales_table() -> Table {
+        let strs = |v: &[&str]| v.iter().map(|s| Value::String(s.to_string())).collect();
+        Table::new(vec![
+            Column {
+                name: "region".into(),
+                values: strs(&["eu", "us", "eu", "us"]),
+            },
+            Column {
+                name: "units".into(),
+                values: vec![Value::Int(3), Value::Int(5), Value::None, Value::Int(2)],
+            },
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_table_filter_select_aggregate() {
+        let t = sales_table();
+        let us = t
+            .filter("region", CompareOp::Eq, &Value::String("us".into()))
+            .unwrap();
+        assert_eq!(us.num_rows(), 2);
+        let big = t.filter("units", CompareOp::Gt, &Value::Int(2)).unwrap();
+        assert_eq!(
+            big.column("units").unwrap().values,
+            vec![Value::Int(3), Value::Int(5)]
+        );
+        assert_eq!(
+            t.select(&["units".into()]).unwrap().column_names(),
+            vec!["units"]
+        );
+        assert!(t
+            .select(&["price".into()])
+            .unwrap_err()
+            .contains("no column"));
+
+        // Groups in key order; None cells skipped by sum, counted by
+        // count.
+        let sum = t
+            .aggregate(&["region".into()], "units", Aggregate::Sum)
+            .unwrap();
+        assert_eq!(sum.column_names(), vec!["region", "sum_units"]);
+        assert_eq!(
+            sum.to_string(),
+            r#"Table(region, sum_units)[("eu", 3), ("us", 7)]"#
+        );
+        let count = t.aggregate(&[], "units", Aggregate::Count).unwrap();
+        assert_eq!(
+            count.column("count_units").unwrap().values,
+            vec![Value::Int(4)]
+        );
+        let mean = t.aggregate(&[], "units", Aggregate::Mean).unwrap();
+        assert_eq!(
+            mean.column("mean_units").unwrap().values,
+            vec![Value::Float(10.0 / 3.0)]
+        );
+        assert!(t
+            .aggregate(&[], "region", Aggregate::Sum)
+            .unwrap_err()
+            .contains("numeric"));
+    }
+
+    #[test]
+    fn test_table_sort_group_and_hash_ignore_row_order() {
+        let t = sales_table();
+        let by_region = |desc| {
+            t.sort_by(&["region".into(), "units".into()], desc)
+                .unwrap()
+                .to_string()
+        };
+        // None sorts first; the sort is stable and multi-key.
+        assert_eq!(
+            by_region(false),
+            r#"Table(region, units)[("eu", None), ("eu", 3), ("us", 2), ("us", 5)]"#
+        );
+        assert_eq!(
+            by_region(true),
+            r#"Table(region, units)[("us", 5), ("us", 2), ("eu", 3), ("eu", None)]"#
+        );
+
+        // Same rows in another order: same groups, same aggregate hash.
+        let shuffled = t.sort_by(&["units".into()], true).unwrap();
+        let agg = |t: &Table| {
+            t.aggregate(&["region".into()], "units", Aggregate::Max)
+                .unwrap()
+        };
+        assert_eq!(agg(&t).canonical_hash(), agg(&shuffled).canonical_hash());
+        assert_ne!(t.canonical_hash(), shuffled.canonical_hash());
+        assert!(t.canonical_hash().starts_with("sha256:"));
+
+        assert_eq!(t.schema(), vec![("region", "String"), ("units", "Int")]);
+        assert_eq!(t.row(1).unwrap()["units"], Value::Int(5));
+        assert!(t.row(4).is_none());
+    }
+
+    #[test]
+    fn test_table_value_rejects_ragged_columns() {
+        let v = Value::Table(sales_table());
+        let json = serde_json::to_string(&v).unwrap();
+        assert_eq!(serde_json::from_str::<Value>(&json).unwrap(), v);
+        assert_eq!(v.type_name(), "Table");
+
+        let ragged =
+            r#"{"Table":{"columns":[{"name":"a","values":[]},{"name":"b","values":[{"Int":1}]}]}}"#;
+        assert!(serde_json::from_str::<Value>(ragged).is_err());
+        let dup = vec![
+            Column {
+                name: "a".into(),
+                values: vec![],
+            };
+            2
+        ];
+        assert!(Table::new(dup).unwrap_err().contains("duplicate"));
+        let mixed = Table::from_columns(
+            vec!["n".into()],
+            vec![vec![Value::Int(1), Value::None, Value::Float(2.0)]],
+        );
+        assert!(mixed.unwrap_err().contains("mixes Int and Float"));
+    }
+
     #[test]
     fn test_module_intent_json_roundtrip() {
         let mut module = Module::new("test");
@@ -406,13 +778,13 @@ mod tests {
         let report = capability_set_report("boruna", "0.2.0");
         assert_eq!(
             report.capability_set_hash,
-            // Bumped in 0.3-S14 for the new step.input capability.
+            // Bumped for the new data.read_table capability.
             // Computed externally:
-            //   printf 'actor.send\t1\nactor.spawn\t1\ndb.query\t1\nfs.read\t1\nfs.write\t1\nllm.call\t1\nnet.fetch\t1\nrandom\t1\nstep.input\t1\ntime.now\t1\nui.render\t1\n' | shasum -a 256
+            //   printf 'actor.send\t1\nactor.spawn\t1\ndata.read_table\t1\ndb.query\t1\nfs.read\t1\nfs.write\t1\nkv.get\t1\nkv.scan\t1\nkv.set\t1\nllm.call\t1\nnet.fetch\t1\nnotify.send\t1\nqueue.poll\t1\nqueue.push\t1\nrandom\t1\nstep.input\t1\ntime.now\t1\nui.render\t1\n' | shasum -a 256
             // Integrators using the prior hash for cache keys MUST
             // invalidate — additive surface change per the documented
             // contract.
-            "sha256:980d017dc54e30c39c329484b501fbe46914d9ad344bfcb4610b0280f4300a67"
+            "sha256:97ede372d85fff6750dd4d95dbe546b4e38b8d51873731078264f2163ebc76fe"
         );
     }
 
@@ -458,7 +830,7 @@ mod tests {
         );
         assert_eq!(report.name, "boruna");
         assert_eq!(report.version, "0.2.0");
-        assert_eq!(report.capabilities.len(), 11);
+        assert_eq!(report.capabilities.len(), 18);
         for ident in &report.capabilities {
             assert!(!ident.name.is_empty());
             assert!(!ident.version.is_empty());
@@ -1,4 +1,8 @@
 use serde::{Deserialize, Serialize};
+
+use crate::bigint::BigInt;
+use crate::decimal::Decimal;
+use crate::table::Table;
 use std::collections::BTreeMap;
 use std::fmt;
 
@@ -14,6 +18,10 @@ pub enum Value {
     Int(i64),
     /// 64-bit float
     Float(f64),
+    /// Fixed-point decimal (bytecode 1.2); see [`Decimal`].
+    Decimal(Decimal),
+    /// Arbitrary-precision integer (bytecode 1.2); see [`BigInt`].
+    BigInt(BigInt),
     /// UTF-8 string
     String(String),
     /// Option<T> — None
@@ -40,6 +48,11 @@ pub enum Value {
     ActorId(u64),
     /// Function reference (for higher-order functions)
     FnRef(u32),
+    /// Handle to a capability call started with `cap.spawn`, redeemed
+    /// with `cap.await` (bytecode 1.2).
+    CapHandle(u64),
+    /// Columnar table (bytecode 1.2); see [`Table`].
+    Table(Table),
 }
 
 impl Value {
@@ -49,6 +62,8 @@ impl Value {
             Value::Bool(b) => *b,
             Value::Int(n) => *n != 0,
             Value::Float(f) => *f != 0.0,
+            Value::Decimal(d) => !d.is_zero(),
+            Value::BigInt(n) => !n.is_zero(),
             Value::String(s) => !s.is_empty(),
             Value::None => false,
             Value::Some(_) => true,
@@ -60,6 +75,8 @@ impl Value {
             Value::Map(m) => !m.is_empty(),
             Value::ActorId(_) => true,
             Value::FnRef(_) => true,
+            Value::CapHandle(_) => true,
+            Value::Table(t) => t.num_rows() > 0,
         }
     }
 
@@ -69,6 +86,8 @@ impl Value {
             Value::Bool(_) => "Bool",
             Value::Int(_) => "Int",
             Value::Float(_) => "Float",
+            Value::Decimal(_) => "Decimal",
+            Value::BigInt(_) => "BigInt",
             Value::String(_) => "String",
             Value::None => "None",
             Value::Some(_) => "Some",
@@ -80,6 +99,8 @@ impl Value {
             Value::Map(_) => "Map",
             Value::ActorId(_) => "ActorId",
             Value::FnRef(_) => "FnRef",
+            Value::CapHandle(_) => "CapHandle",
+            Value::Table(_) => "Table",
         }
     }
 }
@@ -91,6 +112,8 @@ impl fmt::Display for Value {
             Value::Bool(b) => write!(f, "{b}"),
             Value::Int(n) => write!(f, "{n}"),
             Value::Float(n) => write!(f, "{n}"),
+            Value::Decimal(d) => write!(f, "{d}d"),
+            Value::BigInt(n) => write!(f, "{n}n"),
             Value::String(s) => write!(f, "\"{s}\""),
             Value::None => write!(f, "None"),
             Value::Some(v) => write!(f, "Some({v})"),
@@ -135,6 +158,8 @@ impl fmt::Display for Value {
             }
             Value::ActorId(id) => write!(f, "Actor#{id}"),
             Value::FnRef(id) => write!(f, "Fn#{id}"),
+            Value::CapHandle(id) => write!(f, "CapHandle#{id}"),
+            Value::Table(t) => write!(f, "{t}"),
         }
     }
 }
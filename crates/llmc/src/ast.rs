@@ -1,10 +1,21 @@
+use std::fmt;
+
 use serde::{Deserialize, Serialize};
 
+use crate::span::SourceMap;
+
 /// A complete program / module.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Program {
     pub module_name: Option<String>,
+    /// Module-level `uses` declaration: every capability any function of
+    /// the module may declare. Empty when the module declares none.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub uses: Vec<String>,
     pub items: Vec<Item>,
+    /// Locates type and codegen errors; empty unless parsed from source.
+    #[serde(skip)]
+    pub source_map: SourceMap,
 }
 
 /// Import definition.
@@ -38,6 +49,10 @@ pub struct FnDef {
     pub ensures: Vec<Expr>,
     pub body: Block,
     pub exported: bool,
+    /// Diagnostic codes suppressed for the whole function by a
+    /// `// boruna:allow(...)` pragma directly above it.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -63,6 +78,10 @@ pub struct TypeDef {
     pub name: String,
     pub kind: TypeDefKind,
     pub exported: bool,
+    /// Diagnostic codes suppressed for the definition by a
+    /// `// boruna:allow(...)` pragma directly above it.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -108,6 +127,9 @@ pub enum Stmt {
 pub enum Expr {
     IntLit(i64),
     FloatLit(f64),
+    /// Value and source text without the `d` suffix.
+    DecimalLit(boruna_bytecode::Decimal, String),
+    BigIntLit(boruna_bytecode::BigInt),
     StringLit(String),
     BoolLit(bool),
     NoneLit,
@@ -138,6 +160,8 @@ pub enum Expr {
         value: Box<Expr>,
         arms: Vec<MatchArm>,
     },
+    /// Record literal, or with `spread`, a record update. In an update a
+    /// field name may be a dotted path (`user.name`) into nested records.
     Record {
         type_name: String,
         fields: Vec<(String, Expr)>,
@@ -208,3 +232,161 @@ pub enum UnaryOp {
     Neg,
     Not,
 }
+
+impl BinOp {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BinOp::Add => "+",
+            BinOp::Sub => "-",
+            BinOp::Mul => "*",
+            BinOp::Div => "/",
+            BinOp::Mod => "%",
+            BinOp::Eq => "==",
+            BinOp::Neq => "!=",
+            BinOp::Lt => "<",
+            BinOp::Lte => "<=",
+            BinOp::Gt => ">",
+            BinOp::Gte => ">=",
+            BinOp::And => "&&",
+            BinOp::Or => "||",
+            BinOp::Concat => "++",
+        }
+    }
+
+    /// Binding strength, matching the parser. Higher binds tighter.
+    pub fn precedence(&self) -> u8 {
+        match self {
+            BinOp::Or => 1,
+            BinOp::And => 2,
+            BinOp::Eq | BinOp::Neq => 3,
+            BinOp::Lt | BinOp::Lte | BinOp::Gt | BinOp::Gte => 4,
+            BinOp::Concat => 5,
+            BinOp::Add | BinOp::Sub => 6,
+            BinOp::Mul | BinOp::Div | BinOp::Mod => 7,
+        }
+    }
+}
+
+/// Single-line source rendering, used to quote contract clauses in
+/// violation messages. Block bodies (`if`, `match`, block expressions)
+/// are elided to `{ … }`; everything else round-trips through the parser.
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_expr(f, self, 0)
+    }
+}
+
+fn write_expr(f: &mut fmt::Formatter<'_>, e: &Expr, parent_prec: u8) -> fmt::Result {
+    match e {
+        Expr::IntLit(n) => write!(f, "{n}"),
+        Expr::FloatLit(n) => {
+            let s = n.to_string();
+            if s.contains(['.', 'e', 'E']) {
+                f.write_str(&s)
+            } else {
+                write!(f, "{s}.0")
+            }
+        }
+        Expr::DecimalLit(_, text) => write!(f, "{text}d"),
+        Expr::BigIntLit(n) => write!(f, "{n}n"),
+        Expr::StringLit(s) => write!(f, "{s:?}"),
+        Expr::BoolLit(b) => write!(f, "{b}"),
+        Expr::NoneLit => f.write_str("None"),
+        Expr::Ident(name) => f.write_str(name),
+        Expr::Binary { op, left, right } => {
+            let prec = op.precedence();
+            let parens = prec < parent_prec;
+            if parens {
+                f.write_str("(")?;
+            }
+            write_expr(f, left, prec)?;
+            write!(f, " {} ", op.as_str())?;
+            // Left-associative: an equal-precedence right operand needs parens.
+            write_expr(f, right, prec + 1)?;
+            if parens {
+                f.write_str(")")?;
+            }
+            Ok(())
+        }
+        Expr::Unary { op, expr } => {
+            f.write_str(match op {
+                UnaryOp::Neg => "-",
+                UnaryOp::Not => "!",
+            })?;
+            write_expr(f, expr, u8::MAX)
+        }
+        Expr::Call { func, args } => {
+            write_expr(f, func, u8::MAX)?;
+            write_list(f, "(", args, ")")
+        }
+        Expr::FieldAccess { object, field } => {
+            write_expr(f, object, u8::MAX)?;
+            write!(f, ".{field}")
+        }
+        Expr::If { condition, .. } => write!(f, "if {condition} {{ … }}"),
+        Expr::Match { value, .. } => write!(f, "match {value} {{ … }}"),
+        Expr::Block(_) => f.write_str("{ … }"),
+        Expr::Record {
+            type_name,
+            fields,
+            spread,
+        } => {
+            write!(f, "{type_name} {{")?;
+            let mut sep = " ";
+            if let Some(sp) = spread {
+                write!(f, " ..{sp}")?;
+                sep = ", ";
+            }
+            for (name, val) in fields {
+                write!(f, "{sep}{name}: {val}")?;
+                sep = ", ";
+            }
+            f.write_str(if fields.is_empty() && spread.is_none() {
+                "}"
+            } else {
+                " }"
+            })
+        }
+        Expr::EnumVariant {
+            enum_name,
+            variant,
+            payload,
+        } => {
+            write!(f, "{enum_name}::{variant}")?;
+            match payload {
+                Some(p) => write!(f, "({p})"),
+                None => Ok(()),
+            }
+        }
+        Expr::List(items) => write_list(f, "[", items, "]"),
+        Expr::SomeExpr(inner) => write!(f, "Some({inner})"),
+        Expr::OkExpr(inner) => write!(f, "Ok({inner})"),
+        Expr::ErrExpr(inner) => write!(f, "Err({inner})"),
+        Expr::Spawn(inner) => {
+            f.write_str("spawn ")?;
+            write_expr(f, inner, u8::MAX)
+        }
+        Expr::Send { target, message } => {
+            f.write_str("send ")?;
+            write_expr(f, target, u8::MAX)?;
+            f.write_str(" ")?;
+            write_expr(f, message, u8::MAX)
+        }
+        Expr::Receive => f.write_str("receive"),
+        Expr::Emit(inner) => {
+            f.write_str("emit ")?;
+            write_expr(f, inner, u8::MAX)
+        }
+    }
+}
+
+fn write_list(f: &mut fmt::Formatter<'_>, open: &str, items: &[Expr], close: &str) -> fmt::Result {
+    f.write_str(open)?;
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            f.write_str(", ")?;
+        }
+        write!(f, "{item}")?;
+    }
+    f.write_str(close)
+}
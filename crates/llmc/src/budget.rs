@@ -0,0 +1,203 @@
+//! Size and complexity budgets for compiled modules.
+//!
+//! A [`Budget`] caps the number of functions, the opcodes per function,
+//! the constant pool and the control-flow nesting depth of a program.
+//! Every limit is optional; an empty budget accepts anything. Budgets are
+//! read from JSON:
+//!
+//! ```json
+//! { "max_functions": 200, "max_ops_per_function": 4000,
+//!   "max_constants": 10000, "max_nesting_depth": 8 }
+//! ```
+//!
+//! [`check`] reports every exceeded limit rather than stopping at the
+//! first, so a diagnostic run shows the whole picture.
+
+use std::fmt;
+
+use boruna_bytecode::Module;
+use serde::{Deserialize, Serialize};
+
+use crate::ast::{Block, Expr, FnDef, Item, Program, Stmt};
+
+/// Limits a compiled module must stay within.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Budget {
+    /// Functions in the module, including compiler-generated ones.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_functions: Option<usize>,
+    /// Opcodes in any one function.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_ops_per_function: Option<usize>,
+    /// Entries in the constant pool.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_constants: Option<usize>,
+    /// Nested `if` / `match` / `while` / `for` / block expressions in any
+    /// one function body.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_nesting_depth: Option<usize>,
+}
+
+/// One exceeded limit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BudgetViolation {
+    /// The [`Budget`] field that was exceeded, e.g. `max_ops_per_function`.
+    pub limit: &'static str,
+    /// The offending function, for per-function limits.
+    pub function: Option<String>,
+    pub actual: usize,
+    pub max: usize,
+}
+
+impl fmt::Display for BudgetViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let what = match self.limit {
+            "max_functions" => "functions",
+            "max_ops_per_function" => "ops",
+            "max_constants" => "constants",
+            _ => "levels of nesting",
+        };
+        match &self.function {
+            Some(func) => write!(
+                f,
+                "function '{func}' has {} {what}, over {} ({})",
+                self.actual, self.limit, self.max
+            ),
+            None => write!(
+                f,
+                "module has {} {what}, over {} ({})",
+                self.actual, self.limit, self.max
+            ),
+        }
+    }
+}
+
+/// Check `module`, compiled from `program`, against `budget`.
+pub fn check(program: &Program, module: &Module, budget: &Budget) -> Vec<BudgetViolation> {
+    let mut violations = Vec::new();
+    let mut over = |limit, function, actual, max: Option<usize>| {
+        if let Some(max) = max.filter(|max| actual > *max) {
+            violations.push(BudgetViolation {
+                limit,
+                function,
+                actual,
+                max,
+            });
+        }
+    };
+    over(
+        "max_functions",
+        None,
+        module.functions.len(),
+        budget.max_functions,
+    );
+    over(
+        "max_constants",
+        None,
+        module.constants.len(),
+        budget.max_constants,
+    );
+    for f in &module.functions {
+        over(
+            "max_ops_per_function",
+            Some(f.name.clone()),
+            f.code.len(),
+            budget.max_ops_per_function,
+        );
+    }
+    for item in &program.items {
+        if let Item::Function(f) = item {
+            over(
+                "max_nesting_depth",
+                Some(f.name.clone()),
+                nesting_depth(f),
+                budget.max_nesting_depth,
+            );
+        }
+    }
+    violations
+}
+
+/// Deepest nesting of control-flow constructs in `f`'s body. A body with
+/// no `if`, `match`, loop or block expression has depth 0.
+pub fn nesting_depth(f: &FnDef) -> usize {
+    block_depth(&f.body)
+}
+
+fn block_depth(block: &Block) -> usize {
+    block.stmts.iter().map(stmt_depth).max().unwrap_or(0)
+}
+
+fn stmt_depth(stmt: &Stmt) -> usize {
+    match stmt {
+        Stmt::Let { value, .. } | Stmt::Assign { value, .. } | Stmt::Expr(value) => {
+            expr_depth(value)
+        }
+        Stmt::Return(value) => value.as_ref().map_or(0, expr_depth),
+        Stmt::While { condition, body } => expr_depth(condition).max(1 + block_depth(body)),
+        Stmt::For { iter, body, .. } => expr_depth(iter).max(1 + block_depth(body)),
+    }
+}
+
+fn expr_depth(expr: &Expr) -> usize {
+    match expr {
+        Expr::IntLit(_)
+        | Expr::FloatLit(_)
+        | Expr::DecimalLit(..)
+        | Expr::BigIntLit(_)
+        | Expr::StringLit(_)
+        | Expr::BoolLit(_)
+        | Expr::NoneLit
+        | Expr::Ident(_)
+        | Expr::Receive => 0,
+        Expr::Binary { left, right, .. } => expr_depth(left).max(expr_depth(right)),
+        Expr::Unary { expr, .. }
+        | Expr::SomeExpr(expr)
+        | Expr::OkExpr(expr)
+        | Expr::ErrExpr(expr)
+        | Expr::Spawn(expr)
+        | Expr::Emit(expr)
+        | Expr::FieldAccess { object: expr, .. } => expr_depth(expr),
+        Expr::Call { func, args } => args
+            .iter()
+            .map(expr_depth)
+            .fold(expr_depth(func), usize::max),
+        Expr::If {
+            condition,
+            then_block,
+            else_block,
+        } => {
+            // An `else` holding nothing but another `if` continues the
+            // chain rather than nesting inside it.
+            let else_depth = match else_block.as_ref().map(|b| b.stmts.as_slice()) {
+                Some([Stmt::Expr(chained @ Expr::If { .. })]) => expr_depth(chained) - 1,
+                Some(_) => else_block.as_ref().map_or(0, block_depth),
+                None => 0,
+            };
+            expr_depth(condition).max(1 + block_depth(then_block).max(else_depth))
+        }
+        Expr::Match { value, arms } => {
+            // A braced arm body is the arm's own block, not a further level.
+            let arms = arms
+                .iter()
+                .map(|a| match &a.body {
+                    Expr::Block(block) => block_depth(block),
+                    body => expr_depth(body),
+                })
+                .max()
+                .unwrap_or(0);
+            expr_depth(value).max(1 + arms)
+        }
+        Expr::Record { fields, spread, .. } => fields
+            .iter()
+            .map(|(_, e)| expr_depth(e))
+            .chain(spread.as_deref().map(expr_depth))
+            .max()
+            .unwrap_or(0),
+        Expr::EnumVariant { payload, .. } => payload.as_deref().map_or(0, expr_depth),
+        Expr::List(items) => items.iter().map(expr_depth).max().unwrap_or(0),
+        Expr::Send { target, message } => expr_depth(target).max(expr_depth(message)),
+        Expr::Block(block) => 1 + block_depth(block),
+    }
+}
@@ -9,19 +9,181 @@ use boruna_bytecode::value::Value;
 
 use crate::ast::*;
 use crate::error::CompileError;
+use crate::span::{SourceMap, Span};
+
+/// Code generation options.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EmitOptions {
+    /// Leave `requires`/`ensures` checks out of the bytecode entirely
+    /// (release builds). Stripped functions carry no `Op::Assert`, so
+    /// their runs record no `ContractCheck` events.
+    pub strip_contracts: bool,
+}
+
+/// Built-ins that compile to a `CapCall`. `step_input` is not listed: the
+/// compiler grants `step.input` to any function that calls it.
+pub const CAPABILITY_BUILTINS: &[&str] = &[
+    "kv_get",
+    "kv_set",
+    "kv_scan",
+    "queue_push",
+    "queue_poll",
+    "notify_send",
+    "read_table",
+];
+
+/// The capability a [`CAPABILITY_BUILTINS`] entry calls, and its argument
+/// count.
+pub fn builtin_capability(name: &str) -> Option<(Capability, usize)> {
+    Some(match name {
+        "kv_get" => (Capability::KvGet, 1),
+        "kv_set" => (Capability::KvSet, 2),
+        "kv_scan" => (Capability::KvScan, 1),
+        "queue_push" => (Capability::QueuePush, 2),
+        "queue_poll" => (Capability::QueuePoll, 1),
+        "notify_send" => (Capability::NotifySend, 3),
+        "read_table" => (Capability::DataReadTable, 1),
+        _ => return None,
+    })
+}
+
+/// A `cap.<op>(...)` call: a capability call form that is not a plain
+/// built-in call.
+pub enum CapabilityOp<'a> {
+    /// `cap.batch([kv_get("a"), kv_get("b")])`: the capability and the
+    /// arguments of each of its calls.
+    Batch(Capability, Vec<&'a [Expr]>),
+    /// `cap.spawn(kv_get("a"))`: the capability and the call's arguments.
+    Spawn(Capability, &'a [Expr]),
+    /// `cap.await(handle)`: the handle expression.
+    Await(&'a Expr),
+}
+
+/// Recognise a `cap.<op>(...)` call:
+///
+/// ```text
+/// let values = cap.batch([kv_get("a"), kv_get("b"), kv_get("c")])
+/// let h = cap.spawn(kv_get("a"))
+/// let value = cap.await(h)
+/// ```
+///
+/// `cap.batch` takes a non-empty list literal of calls to one
+/// [`CAPABILITY_BUILTINS`] entry, `cap.spawn` exactly one such call, and
+/// `cap.await` one handle. `None` when the call is not `cap.<op>(...)`;
+/// `Some(Err)` when it is, but is malformed.
+pub fn capability_op<'a>(
+    func: &Expr,
+    args: &'a [Expr],
+) -> Option<Result<CapabilityOp<'a>, CompileError>> {
+    let Expr::FieldAccess { object, field } = func else {
+        return None;
+    };
+    if !matches!(object.as_ref(), Expr::Ident(name) if name == "cap") {
+        return None;
+    }
+    let err = |msg: String| Some(Err(CompileError::type_error(msg)));
+    match field.as_str() {
+        "batch" => {}
+        "spawn" => {
+            let [call] = args else {
+                return err("cap.spawn expects one capability call".into());
+            };
+            return Some(builtin_call("cap.spawn", call).and_then(|(name, args)| {
+                let (cap, argc) = builtin_capability(name).expect("checked by builtin_call");
+                if args.len() != argc {
+                    return Err(CompileError::type_error(format!(
+                        "{name} expects {argc} argument{}, got {}",
+                        if argc == 1 { "" } else { "s" },
+                        args.len()
+                    )));
+                }
+                Ok(CapabilityOp::Spawn(cap, args))
+            }));
+        }
+        "await" => {
+            let [handle] = args else {
+                return err("cap.await expects one handle".into());
+            };
+            return Some(Ok(CapabilityOp::Await(handle)));
+        }
+        other => {
+            return err(format!(
+                "unknown cap operation `cap.{other}` (expected batch, spawn or await)"
+            ))
+        }
+    }
+    let [Expr::List(items)] = args else {
+        return err("cap.batch expects one list literal of capability calls".into());
+    };
+    if items.is_empty() {
+        return err("cap.batch needs at least one call".into());
+    }
+    let mut batch_builtin: Option<&str> = None;
+    let mut calls = Vec::with_capacity(items.len());
+    for item in items {
+        let (name, args) = match builtin_call("cap.batch", item) {
+            Ok(call) => call,
+            Err(e) => return Some(Err(e)),
+        };
+        match batch_builtin {
+            Some(first) if first != name => {
+                return err(format!(
+                    "cap.batch calls must all use one capability: `{first}` and `{name}`"
+                ))
+            }
+            _ => batch_builtin = Some(name),
+        }
+        calls.push(args);
+    }
+    let (cap, _) = builtin_capability(batch_builtin.expect("non-empty batch"))?;
+    Some(Ok(CapabilityOp::Batch(cap, calls)))
+}
+
+/// The built-in name and arguments of `expr`, which `op` requires to be a
+/// call to a [`CAPABILITY_BUILTINS`] entry.
+fn builtin_call<'a>(op: &str, expr: &'a Expr) -> Result<(&'a str, &'a [Expr]), CompileError> {
+    let Expr::Call { func, args } = expr else {
+        return Err(CompileError::type_error(format!(
+            "{op} takes capability calls, got `{expr}`"
+        )));
+    };
+    match func.as_ref() {
+        Expr::Ident(name) if builtin_capability(name).is_some() => {
+            Ok((name.as_str(), args.as_slice()))
+        }
+        other => Err(CompileError::type_error(format!(
+            "{op} calls must use one of {}, got `{other}`",
+            CAPABILITY_BUILTINS.join(", ")
+        ))),
+    }
+}
 
 pub fn emit(name: &str, program: &Program) -> Result<Module, CompileError> {
-    let mut emitter = Emitter::new(name);
+    emit_with_options(name, program, EmitOptions::default())
+}
+
+pub fn emit_with_options(
+    name: &str,
+    program: &Program,
+    options: EmitOptions,
+) -> Result<Module, CompileError> {
+    let mut emitter = Emitter::new(name, options, &program.source_map);
     emitter.emit_program(program)?;
+    emitter.module.record_capability_versions();
     Ok(emitter.module)
 }
 
-struct Emitter {
+struct Emitter<'a> {
     module: Module,
     /// Map from function name to function index.
     fn_map: HashMap<String, u32>,
     /// Map from type name to type index.
     type_map: HashMap<String, u32>,
+    options: EmitOptions,
+    /// Locates errors in the source.
+    source_map: &'a SourceMap,
+    /// The function being emitted, if any.
+    current_fn: Option<String>,
 }
 
 struct FnEmitter {
@@ -32,12 +194,48 @@ struct FnEmitter {
     capabilities: Vec<Capability>,
 }
 
-impl Emitter {
-    fn new(name: &str) -> Self {
+impl<'a> Emitter<'a> {
+    fn new(name: &str, options: EmitOptions, source_map: &'a SourceMap) -> Self {
         Emitter {
             module: Module::new(name),
             fn_map: HashMap::new(),
             type_map: HashMap::new(),
+            options,
+            source_map,
+            current_fn: None,
+        }
+    }
+
+    /// Where `subject` first occurs in the function being emitted, or
+    /// that function's name when it does not occur.
+    fn span_of(&self, subject: &str) -> Option<Span> {
+        let f = self.current_fn.as_deref()?;
+        self.source_map
+            .find(Some(f), subject)
+            .or_else(|| self.source_map.function(f))
+    }
+
+    /// A codegen error about `subject`.
+    fn error(&self, subject: &str, msg: String) -> CompileError {
+        CompileError::Codegen {
+            msg,
+            span: self.span_of(subject),
+        }
+    }
+
+    /// `e`, located at the current function's name unless it already
+    /// has a location.
+    fn locate(&self, e: CompileError) -> CompileError {
+        match e {
+            CompileError::Type { msg, span: None } => CompileError::Type {
+                msg,
+                span: self.span_of(""),
+            },
+            CompileError::Codegen { msg, span: None } => CompileError::Codegen {
+                msg,
+                span: self.span_of(""),
+            },
+            other => other,
         }
     }
 
@@ -78,7 +276,9 @@ impl Emitter {
         // Second pass: emit functions
         for item in &program.items {
             if let Item::Function(f) = item {
-                self.emit_function(f)?;
+                self.current_fn = Some(f.name.clone());
+                self.emit_function(f).map_err(|e| self.locate(e))?;
+                self.current_fn = None;
             }
         }
 
@@ -117,9 +317,19 @@ impl Emitter {
         // Each is evaluated against the arguments at entry; a violation
         // traps with a ContractViolation carrying the offending args as a
         // replayable counterexample. `Op::Assert` is emitted only here.
-        for (i, req) in f.requires.iter().enumerate() {
+        // The message quotes the clause so the violation reads on its own.
+        let (requires, ensures): (&[Expr], &[Expr]) = if self.options.strip_contracts {
+            (&[], &[])
+        } else {
+            (&f.requires, &f.ensures)
+        };
+        for (i, req) in requires.iter().enumerate() {
             self.emit_expr(req, &mut fe)?;
-            let msg = format!("precondition {} failed in `{}`", i + 1, f.name);
+            let msg = format!(
+                "precondition {} failed in `{}`: requires {req}",
+                i + 1,
+                f.name
+            );
             let msg_idx = self.module.add_const(Value::String(msg));
             fe.code.push(Op::Assert {
                 msg: msg_idx,
@@ -131,7 +341,7 @@ impl Emitter {
         // Emit body
         self.emit_block(&f.body, &mut fe)?;
 
-        if f.ensures.is_empty() {
+        if ensures.is_empty() {
             // Implicit return
             if fe.code.last() != Some(&Op::Ret) {
                 fe.code.push(Op::Ret);
@@ -150,9 +360,13 @@ impl Emitter {
             fe.locals.insert("result".to_string(), result_local);
             fe.code.push(Op::StoreLocal(result_local));
 
-            for (i, ens) in f.ensures.iter().enumerate() {
+            for (i, ens) in ensures.iter().enumerate() {
                 self.emit_expr(ens, &mut fe)?;
-                let msg = format!("postcondition {} failed in `{}`", i + 1, f.name);
+                let msg = format!(
+                    "postcondition {} failed in `{}`: ensures {ens}",
+                    i + 1,
+                    f.name
+                );
                 let msg_idx = self.module.add_const(Value::String(msg));
                 fe.code.push(Op::Assert {
                     msg: msg_idx,
@@ -210,9 +424,7 @@ impl Emitter {
                 if let Some(&idx) = fe.locals.get(target) {
                     fe.code.push(Op::StoreLocal(idx));
                 } else {
-                    return Err(CompileError::Codegen(format!(
-                        "undefined variable: {target}"
-                    )));
+                    return Err(self.error(target, format!("undefined variable: {target}")));
                 }
             }
             Stmt::Expr(e) => {
@@ -307,6 +519,14 @@ impl Emitter {
                 let idx = self.module.add_const(Value::Float(*n));
                 fe.code.push(Op::PushConst(idx));
             }
+            Expr::DecimalLit(d, _) => {
+                let idx = self.module.add_const(Value::Decimal(*d));
+                fe.code.push(Op::PushConst(idx));
+            }
+            Expr::BigIntLit(n) => {
+                let idx = self.module.add_const(Value::BigInt(n.clone()));
+                fe.code.push(Op::PushConst(idx));
+            }
             Expr::StringLit(s) => {
                 let idx = self.module.add_const(Value::String(s.clone()));
                 fe.code.push(Op::PushConst(idx));
@@ -326,7 +546,7 @@ impl Emitter {
                     let idx = self.module.add_const(Value::FnRef(func_idx));
                     fe.code.push(Op::PushConst(idx));
                 } else {
-                    return Err(CompileError::Codegen(format!("undefined: {name}")));
+                    return Err(self.error(name, format!("undefined: {name}")));
                 }
             }
             Expr::Binary { op, left, right } => {
@@ -357,6 +577,44 @@ impl Emitter {
                 });
             }
             Expr::Call { func, args } => {
+                // `cap.batch([kv_get(a), kv_get(b)])`: one `CapBatch` over a
+                // list of per-call argument lists. `cap.spawn(kv_get(a))`
+                // and `cap.await(h)`: `CapSpawn` / `CapAwait`. Typeck has
+                // already checked the shape and each call's arity.
+                if !fe.locals.contains_key("cap") {
+                    if let Some(op) = capability_op(func, args) {
+                        match op? {
+                            CapabilityOp::Batch(cap, calls) => {
+                                for call_args in &calls {
+                                    for arg in *call_args {
+                                        self.emit_expr(arg, fe)?;
+                                    }
+                                    let argc = count_as_u8(
+                                        call_args.len(),
+                                        "cap.batch call",
+                                        "arguments",
+                                    )?;
+                                    fe.code.push(Op::MakeList(argc));
+                                }
+                                let count = count_as_u8(calls.len(), "cap.batch", "calls")?;
+                                fe.code.push(Op::MakeList(count));
+                                fe.code.push(Op::CapBatch(cap.id()));
+                            }
+                            CapabilityOp::Spawn(cap, call_args) => {
+                                for arg in call_args {
+                                    self.emit_expr(arg, fe)?;
+                                }
+                                let argc = count_as_u8(call_args.len(), "cap.spawn", "arguments")?;
+                                fe.code.push(Op::CapSpawn(cap.id(), argc));
+                            }
+                            CapabilityOp::Await(handle) => {
+                                self.emit_expr(handle, fe)?;
+                                fe.code.push(Op::CapAwait);
+                            }
+                        }
+                        return Ok(());
+                    }
+                }
                 // If func is a direct function name, check builtins first
                 if let Expr::Ident(name) = func.as_ref() {
                     // Builtin functions → emit opcodes directly
@@ -526,6 +784,92 @@ impl Emitter {
                             fe.code.push(Op::FloatParse);
                             return Ok(());
                         }
+                        "__builtin_decimal_parse" if args.len() == 1 => {
+                            self.emit_expr(&args[0], fe)?;
+                            fe.code.push(Op::DecimalParse);
+                            return Ok(());
+                        }
+                        "__builtin_decimal_to_string" if args.len() == 1 => {
+                            self.emit_expr(&args[0], fe)?;
+                            fe.code.push(Op::DecimalToString);
+                            return Ok(());
+                        }
+                        "__builtin_int_to_decimal" if args.len() == 1 => {
+                            self.emit_expr(&args[0], fe)?;
+                            fe.code.push(Op::IntToDecimal);
+                            return Ok(());
+                        }
+                        "str_match" if args.len() == 2 => {
+                            self.emit_expr(&args[0], fe)?;
+                            self.emit_expr(&args[1], fe)?;
+                            fe.code.push(Op::StrMatch);
+                            return Ok(());
+                        }
+                        "str_find" if args.len() == 2 => {
+                            self.emit_expr(&args[0], fe)?;
+                            self.emit_expr(&args[1], fe)?;
+                            fe.code.push(Op::StrFind);
+                            return Ok(());
+                        }
+                        "table_select" | "table_filter" | "table_aggregate" | "table_sort"
+                        | "table_column" | "table_columns" | "table_len" | "table_row"
+                        | "table_hash" => {
+                            let (op, argc) = match name.as_str() {
+                                "table_select" => (Op::TableSelect, 2),
+                                "table_filter" => (Op::TableFilter, 4),
+                                "table_aggregate" => (Op::TableAggregate, 4),
+                                "table_sort" => (Op::TableSort, 3),
+                                "table_column" => (Op::TableColumn, 2),
+                                "table_columns" => (Op::TableColumnNames, 1),
+                                "table_len" => (Op::TableLen, 1),
+                                "table_row" => (Op::TableRow, 2),
+                                _ => (Op::TableHash, 1),
+                            };
+                            if args.len() == argc {
+                                for arg in args {
+                                    self.emit_expr(arg, fe)?;
+                                }
+                                fe.code.push(op);
+                                return Ok(());
+                            }
+                        }
+                        "json_parse" if args.len() == 1 => {
+                            self.emit_expr(&args[0], fe)?;
+                            fe.code.push(Op::JsonParse);
+                            return Ok(());
+                        }
+                        "json_stringify" if args.len() == 1 => {
+                            self.emit_expr(&args[0], fe)?;
+                            fe.code.push(Op::JsonStringify);
+                            return Ok(());
+                        }
+                        "__builtin_bigint_parse" if args.len() == 1 => {
+                            self.emit_expr(&args[0], fe)?;
+                            fe.code.push(Op::BigIntParse);
+                            return Ok(());
+                        }
+                        "__builtin_bigint_to_string" if args.len() == 1 => {
+                            self.emit_expr(&args[0], fe)?;
+                            fe.code.push(Op::BigIntToString);
+                            return Ok(());
+                        }
+                        "__builtin_int_to_bigint" if args.len() == 1 => {
+                            self.emit_expr(&args[0], fe)?;
+                            fe.code.push(Op::IntToBigInt);
+                            return Ok(());
+                        }
+                        "__builtin_bigint_to_int" if args.len() == 1 => {
+                            self.emit_expr(&args[0], fe)?;
+                            fe.code.push(Op::BigIntToInt);
+                            return Ok(());
+                        }
+                        "__builtin_bigint_pow_mod" if args.len() == 3 => {
+                            self.emit_expr(&args[0], fe)?;
+                            self.emit_expr(&args[1], fe)?;
+                            self.emit_expr(&args[2], fe)?;
+                            fe.code.push(Op::BigIntPowMod);
+                            return Ok(());
+                        }
                         "__builtin_bool_to_string" if args.len() == 1 => {
                             self.emit_expr(&args[0], fe)?;
                             fe.code.push(Op::BoolToString);
@@ -586,6 +930,18 @@ impl Emitter {
                             fe.code.push(Op::DebugMsg);
                             return Ok(());
                         }
+                        // Stack the VM expects (top → bottom): [msg, cond].
+                        "assert" if args.len() == 2 => {
+                            self.emit_expr(&args[0], fe)?;
+                            self.emit_expr(&args[1], fe)?;
+                            fe.code.push(Op::AssertMsg);
+                            return Ok(());
+                        }
+                        "fail" if args.len() == 1 => {
+                            self.emit_expr(&args[0], fe)?;
+                            fe.code.push(Op::Fail);
+                            return Ok(());
+                        }
                         // guard-and-seal: run a deterministic boolean
                         // check on a value, fail closed on false, and seal
                         // the verdict into the evidence trail as an
@@ -621,6 +977,22 @@ impl Emitter {
                             }
                             return Ok(());
                         }
+                        // Capability built-ins (kv, queue, notify,
+                        // tables). Unlike `step_input`, these are real side
+                        // effects: the calling function must declare the
+                        // capability (`!{kv.get}` or `uses kv.get`).
+                        // Typeck rejects an undeclared call, and the VM's
+                        // function-capability check refuses it at runtime.
+                        name if builtin_capability(name).is_some() => {
+                            let (cap, argc) = builtin_capability(name).expect("matched above");
+                            if args.len() == argc {
+                                for arg in args {
+                                    self.emit_expr(arg, fe)?;
+                                }
+                                fe.code.push(Op::CapCall(cap.id(), argc as u8));
+                                return Ok(());
+                            }
+                        }
                         _ => {}
                     }
                     // User-defined function call
@@ -806,6 +1178,26 @@ impl Emitter {
                     fe.match_tables.push(bc_arms);
                 }
             }
+            // `Table { name: [cells], ... }` builds a table unless the
+            // program declares its own `Table` type.
+            Expr::Record {
+                type_name,
+                fields,
+                spread: None,
+            } if type_name == "Table" && !self.type_map.contains_key("Table") => {
+                let names = fields
+                    .iter()
+                    .map(|(name, _)| Value::String(name.clone()))
+                    .collect();
+                let idx = self.module.add_const(Value::List(names));
+                fe.code.push(Op::PushConst(idx));
+                let column_count = count_as_u8(fields.len(), "table literal", "columns")?;
+                for (_, column) in fields {
+                    self.emit_expr(column, fe)?;
+                }
+                fe.code.push(Op::MakeList(column_count));
+                fe.code.push(Op::TableFromColumns);
+            }
             Expr::Record {
                 type_name,
                 fields,
@@ -814,32 +1206,14 @@ impl Emitter {
                 let type_id = self.type_map.get(type_name).copied().unwrap_or(0);
                 if let Some(base_expr) = spread {
                     // Record spread: State { ..base, field_a: val }
-                    // 1) Evaluate base into a temp local
+                    // Evaluate base into a temp local, then rebuild it.
                     self.emit_expr(base_expr, fe)?;
                     let base_local = fe.next_local;
                     fe.next_local += 1;
                     fe.code.push(Op::StoreLocal(base_local));
-
-                    // 2) Get the full field list for this type
-                    let type_fields = self.get_type_fields(type_name);
-                    let total_fields = type_fields.len();
-                    let field_count = count_as_u8(total_fields, "record literal", "fields")?;
-
-                    // 3) Build override set
-                    let overrides: std::collections::HashMap<&str, &Expr> =
+                    let overrides: Vec<(&str, &Expr)> =
                         fields.iter().map(|(n, e)| (n.as_str(), e)).collect();
-
-                    // 4) For each field in type order: emit override or copy from base
-                    for (i, field_name) in type_fields.iter().enumerate() {
-                        if let Some(val_expr) = overrides.get(field_name.as_str()) {
-                            self.emit_expr(val_expr, fe)?;
-                        } else {
-                            fe.code.push(Op::LoadLocal(base_local));
-                            fe.code.push(Op::GetField(i as u8));
-                        }
-                    }
-
-                    fe.code.push(Op::MakeRecord(type_id, field_count));
+                    self.emit_record_update(type_name, base_local, &overrides, fe)?;
                 } else {
                     // Standard record literal (no spread)
                     let field_count = count_as_u8(fields.len(), "record literal", "fields")?;
@@ -896,12 +1270,10 @@ impl Emitter {
                     if let Some(&func_idx) = self.fn_map.get(name) {
                         fe.code.push(Op::SpawnActor(func_idx));
                     } else {
-                        return Err(CompileError::Codegen(format!("unknown function: {name}")));
+                        return Err(self.error(name, format!("unknown function: {name}")));
                     }
                 } else {
-                    return Err(CompileError::Codegen(
-                        "spawn requires a function name".into(),
-                    ));
+                    return Err(CompileError::codegen("spawn requires a function name"));
                 }
             }
             Expr::Send { target, message } => {
@@ -924,11 +1296,61 @@ impl Emitter {
         Ok(())
     }
 
-    fn get_type_fields(&self, type_name: &str) -> Vec<String> {
+    /// Rebuild the `type_name` record in `base_local`, in declared field
+    /// order: a field named in `overrides` takes its expression, a field
+    /// with dotted paths below it (`user.name`) is itself rebuilt from the
+    /// base's value, and every other field is copied from the base.
+    fn emit_record_update(
+        &mut self,
+        type_name: &str,
+        base_local: u32,
+        overrides: &[(&str, &Expr)],
+        fe: &mut FnEmitter,
+    ) -> Result<(), CompileError> {
+        let type_id = self.type_map.get(type_name).copied().unwrap_or(0);
+        let type_fields = self.get_type_fields(type_name);
+        let field_count = count_as_u8(type_fields.len(), "record literal", "fields")?;
+        for (i, (field_name, field_type)) in type_fields.iter().enumerate() {
+            let nested: Vec<(&str, &Expr)> = overrides
+                .iter()
+                .filter_map(|(path, e)| {
+                    let rest = path.strip_prefix(field_name.as_str())?.strip_prefix('.')?;
+                    Some((rest, *e))
+                })
+                .collect();
+            if let Some((_, val_expr)) = overrides.iter().find(|(n, _)| n == field_name) {
+                self.emit_expr(val_expr, fe)?;
+            } else if !nested.is_empty() {
+                if self.get_type_fields(field_type).is_empty() {
+                    return Err(self.error(
+                        field_name,
+                        format!(
+                            "cannot update inside '{field_name}': '{field_type}' is not a record type"
+                        ),
+                    ));
+                }
+                fe.code.push(Op::LoadLocal(base_local));
+                fe.code.push(Op::GetField(i as u8));
+                let field_local = fe.next_local;
+                fe.next_local += 1;
+                fe.code.push(Op::StoreLocal(field_local));
+                self.emit_record_update(field_type, field_local, &nested, fe)?;
+            } else {
+                fe.code.push(Op::LoadLocal(base_local));
+                fe.code.push(Op::GetField(i as u8));
+            }
+        }
+        fe.code.push(Op::MakeRecord(type_id, field_count));
+        Ok(())
+    }
+
+    /// Fields of the record type `type_name` with their type names, in
+    /// declared order; empty when it is not a record type.
+    fn get_type_fields(&self, type_name: &str) -> Vec<(String, String)> {
         for typedef in &self.module.types {
             if typedef.name == type_name {
                 if let BcTypeKind::Record { fields } = &typedef.kind {
-                    return fields.iter().map(|(name, _)| name.clone()).collect();
+                    return fields.clone();
                 }
             }
         }
@@ -971,20 +1393,19 @@ impl Emitter {
                 continue;
             }
             let BcTypeKind::Enum { variants } = &typedef.kind else {
-                return Err(CompileError::Codegen(format!(
-                    "'{enum_name}' is not an enum"
-                )));
+                return Err(self.error(enum_name, format!("'{enum_name}' is not an enum")));
             };
             for (vi, (vname, _)) in variants.iter().enumerate() {
                 if vname == variant {
                     return Ok((ti as u32, count_as_u8(vi, "enum", "variants")?));
                 }
             }
-            return Err(CompileError::Codegen(format!(
-                "enum '{enum_name}' has no variant '{variant}'"
-            )));
+            return Err(self.error(
+                variant,
+                format!("enum '{enum_name}' has no variant '{variant}'"),
+            ));
         }
-        Err(CompileError::Codegen(format!("unknown enum: {enum_name}")))
+        Err(self.error(enum_name, format!("unknown enum: {enum_name}")))
     }
 
     /// Match-arm tag for a pattern. Enum-variant patterns resolve to the
@@ -1023,7 +1444,7 @@ impl Emitter {
 /// count exceeds `u8::MAX`. Wrapping would corrupt the operand stack at runtime.
 fn count_as_u8(n: usize, subject: &str, unit: &str) -> Result<u8, CompileError> {
     if n > u8::MAX as usize {
-        Err(CompileError::Codegen(format!(
+        Err(CompileError::codegen(format!(
             "{subject} has {n} {unit}; max 255"
         )))
     } else {
@@ -0,0 +1,165 @@
+//! Inline expected-output tests (doctests) in `.ax` source.
+//!
+//! A line of the form
+//!
+//! ```text
+//! //! test: <expr> == <expected>
+//! ```
+//!
+//! declares that `<expr>` evaluates to the same value as `<expected>`, e.g.
+//! `//! test: main() == 3` or `//! test: clamp(15, 0, 10) == 10`. To the
+//! rest of the compiler the line is an ordinary comment. [`extract`] finds
+//! the annotations and [`compile_test`] builds one module per test whose
+//! two synthetic functions, [`ACTUAL_FN`] and [`EXPECTED_FN`], evaluate
+//! each side against the file's own declarations. `boruna lang test` runs
+//! them.
+
+use boruna_bytecode::Module;
+
+use crate::error::CompileError;
+use crate::span::Span;
+
+/// Marks a doctest line.
+pub const DOCTEST_PREFIX: &str = "//! test:";
+
+/// Synthetic function evaluating a test's expression.
+pub const ACTUAL_FN: &str = "__doctest_actual";
+
+/// Synthetic function evaluating a test's expected value.
+pub const EXPECTED_FN: &str = "__doctest_expected";
+
+/// One `//! test:` annotation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocTest {
+    /// 1-based line of the annotation.
+    pub line: usize,
+    /// Left of `==`.
+    pub expr: String,
+    /// Right of `==`.
+    pub expected: String,
+}
+
+impl DocTest {
+    /// The annotation as written, without the prefix.
+    pub fn name(&self) -> String {
+        format!("{} == {}", self.expr, self.expected)
+    }
+}
+
+/// Every doctest in `source`, in line order. An annotation without exactly
+/// one top-level `==`, or with an empty side, is a parse error.
+pub fn extract(source: &str) -> Result<Vec<DocTest>, CompileError> {
+    let mut tests = Vec::new();
+    for (i, text) in source.lines().enumerate() {
+        let Some(body) = text.trim_start().strip_prefix(DOCTEST_PREFIX) else {
+            continue;
+        };
+        let line = i + 1;
+        let error = |msg: &str| CompileError::Parse {
+            span: line_span(line, text),
+            msg: format!("doctest: {msg}"),
+        };
+        let (expr, expected) = match top_level_eq(body).as_slice() {
+            [at] => (body[..*at].trim(), body[at + 2..].trim()),
+            [] => return Err(error("expected `<expr> == <expected>`")),
+            _ => return Err(error("more than one `==`; parenthesize the comparison")),
+        };
+        if expr.is_empty() || expected.is_empty() {
+            return Err(error("expected `<expr> == <expected>`"));
+        }
+        tests.push(DocTest {
+            line,
+            expr: expr.to_string(),
+            expected: expected.to_string(),
+        });
+    }
+    Ok(tests)
+}
+
+/// All of line `line`, whose text is `text`.
+fn line_span(line: usize, text: &str) -> Span {
+    Span {
+        line,
+        col: 1,
+        end_line: line,
+        end_col: text.len() + 1,
+    }
+}
+
+/// Byte offsets of each `==` outside brackets and string literals.
+fn top_level_eq(body: &str) -> Vec<usize> {
+    let bytes = body.as_bytes();
+    let mut found = Vec::new();
+    let mut depth = 0i32;
+    let mut in_string = false;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' if in_string => i += 1,
+            b'"' => in_string = !in_string,
+            b'(' | b'[' | b'{' if !in_string => depth += 1,
+            b')' | b']' | b'}' if !in_string => depth -= 1,
+            b'=' if !in_string && depth == 0 && bytes.get(i + 1) == Some(&b'=') => {
+                found.push(i);
+                i += 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    found
+}
+
+/// Compile `source` with `test`'s two sides appended as [`ACTUAL_FN`] and
+/// [`EXPECTED_FN`]. The module's entry is [`ACTUAL_FN`]; errors in the
+/// appended code are reported at the annotation's line.
+pub fn compile_test(name: &str, source: &str, test: &DocTest) -> Result<Module, CompileError> {
+    let source_lines = source.lines().count();
+    // As in the REPL, `Int` stands for any type: return types are not
+    // unified with the body, and the values are compared after the run.
+    let synthetic = format!(
+        "{source}\n\nfn {ACTUAL_FN}() -> Int {{ {} }}\nfn {EXPECTED_FN}() -> Int {{ {} }}\n",
+        test.expr, test.expected
+    );
+    let annotation = line_span(
+        test.line,
+        source.lines().nth(test.line - 1).unwrap_or_default(),
+    );
+    let mut module = crate::compile(name, &synthetic).map_err(|e| match e {
+        CompileError::Lexer { span, msg } if span.line > source_lines => CompileError::Lexer {
+            span: annotation,
+            msg,
+        },
+        CompileError::Parse { span, msg } if span.line > source_lines => CompileError::Parse {
+            span: annotation,
+            msg,
+        },
+        CompileError::Type {
+            msg,
+            span: Some(span),
+        } if span.line > source_lines => CompileError::Type {
+            msg,
+            span: Some(annotation),
+        },
+        CompileError::Codegen {
+            msg,
+            span: Some(span),
+        } if span.line > source_lines => CompileError::Codegen {
+            msg,
+            span: Some(annotation),
+        },
+        other => other,
+    })?;
+    module.entry = function_index(&module, ACTUAL_FN)
+        .ok_or_else(|| CompileError::codegen(format!("{ACTUAL_FN} missing after compile")))?;
+    Ok(module)
+}
+
+/// Index of the function named `name` in `module`.
+pub fn function_index(module: &Module, name: &str) -> Option<u32> {
+    module
+        .functions
+        .iter()
+        .position(|f| f.name == name)
+        .map(|i| i as u32)
+}
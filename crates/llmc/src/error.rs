@@ -1,20 +1,86 @@
 use thiserror::Error;
 
+use crate::span::Span;
+
 #[derive(Debug, Error)]
 pub enum CompileError {
-    #[error("lexer error at line {line}, col {col}: {msg}")]
-    Lexer {
-        line: usize,
-        col: usize,
-        msg: String,
-    },
+    #[error("lexer error at line {}, col {}: {msg}", span.line, span.col)]
+    Lexer { span: Span, msg: String },
+
+    #[error("parse error at line {}: {msg}", span.line)]
+    Parse { span: Span, msg: String },
+
+    /// `span` is the identifier the error is about, when the program was
+    /// parsed from source and the identifier could be found.
+    #[error("type error: {msg}")]
+    Type { msg: String, span: Option<Span> },
+
+    #[error("codegen error: {msg}")]
+    Codegen { msg: String, span: Option<Span> },
+
+    #[error("budget exceeded: {0}")]
+    Budget(String),
+}
+
+impl CompileError {
+    /// A type error without a location.
+    pub fn type_error(msg: impl Into<String>) -> Self {
+        CompileError::Type {
+            msg: msg.into(),
+            span: None,
+        }
+    }
+
+    /// A codegen error without a location.
+    pub fn codegen(msg: impl Into<String>) -> Self {
+        CompileError::Codegen {
+            msg: msg.into(),
+            span: None,
+        }
+    }
 
-    #[error("parse error at line {line}: {msg}")]
-    Parse { line: usize, msg: String },
+    /// The message, without the `type error: ` style prefix.
+    pub fn message(&self) -> &str {
+        match self {
+            CompileError::Lexer { msg, .. }
+            | CompileError::Parse { msg, .. }
+            | CompileError::Type { msg, .. }
+            | CompileError::Codegen { msg, .. }
+            | CompileError::Budget(msg) => msg,
+        }
+    }
+
+    /// Where in the source the error is, when known.
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            CompileError::Lexer { span, .. } | CompileError::Parse { span, .. } => Some(*span),
+            CompileError::Type { span, .. } | CompileError::Codegen { span, .. } => *span,
+            CompileError::Budget(_) => None,
+        }
+    }
+}
 
-    #[error("type error: {0}")]
-    Type(String),
+impl boruna_errors::BorunaError for CompileError {
+    fn error_code(&self) -> &'static boruna_errors::ErrorCode {
+        use boruna_errors as codes;
+        match self {
+            CompileError::Lexer { .. } => &codes::COMPILE_LEXER,
+            CompileError::Parse { .. } => &codes::COMPILE_PARSE,
+            CompileError::Type { .. } => &codes::COMPILE_TYPE,
+            CompileError::Codegen { .. } => &codes::COMPILE_CODEGEN,
+            CompileError::Budget(_) => &codes::COMPILE_BUDGET,
+        }
+    }
 
-    #[error("codegen error: {0}")]
-    Codegen(String),
+    fn error_data(&self) -> serde_json::Value {
+        match self.span() {
+            Some(span) => serde_json::json!({
+                "line": span.line,
+                "col": span.col,
+                "end_line": span.end_line,
+                "end_col": span.end_col,
+            }),
+            None => serde_json::Value::Null,
+        }
+    }
 }
@@ -1,4 +1,5 @@
 use crate::error::CompileError;
+use crate::span::Span;
 use logos::Logos;
 
 /// A piece of source text that is not semantically meaningful to the compiler
@@ -9,6 +10,28 @@ pub enum Trivia {
     LineComment(String),
 }
 
+/// Diagnostic codes named by `// boruna:allow(E005, W001)` suppression
+/// pragmas in `trivia`, in order of appearance.
+pub fn allow_pragma_codes(trivia: &[Trivia]) -> Vec<String> {
+    let mut codes = Vec::new();
+    for Trivia::LineComment(text) in trivia {
+        let body = text.trim_start_matches('/').trim();
+        let Some(list) = body
+            .strip_prefix("boruna:allow(")
+            .and_then(|rest| rest.split_once(')'))
+            .map(|(list, _)| list)
+        else {
+            continue;
+        };
+        for code in list.split(',').map(str::trim).filter(|c| !c.is_empty()) {
+            if !codes.iter().any(|c| c == code) {
+                codes.push(code.to_string());
+            }
+        }
+    }
+    codes
+}
+
 /// The full output of lexing: a token stream plus any trivia that appears
 /// after the last real token (trailing comments at end of file).
 pub struct LexOutput {
@@ -86,6 +109,19 @@ pub enum TokenKind {
     IntLit(i64),
     #[regex(r"[0-9]+\.[0-9]+", |lex| lex.slice().parse::<f64>().ok())]
     FloatLit(f64),
+    /// `12.50d` — fixed-point Decimal; at most 9 fractional digits. Keeps
+    /// the source text (`12.50`), whose trailing zeros are the declared scale.
+    #[regex(r"[0-9]+(\.[0-9]+)?d", |lex| {
+        let s = &lex.slice()[..lex.slice().len() - 1];
+        boruna_bytecode::Decimal::parse(s).map(|d| (d, s.to_string()))
+    })]
+    DecimalLit((boruna_bytecode::Decimal, String)),
+    /// `123n` — arbitrary-precision BigInt.
+    #[regex(r"[0-9]+n", |lex| {
+        let s = lex.slice();
+        boruna_bytecode::BigInt::parse(&s[..s.len() - 1])
+    })]
+    BigIntLit(boruna_bytecode::BigInt),
     #[regex(r#""([^"\\]|\\.)*""#, |lex| {
         let s = lex.slice();
         Some(s[1..s.len()-1].to_string())
@@ -174,11 +210,34 @@ pub struct Token {
     pub kind: TokenKind,
     pub line: usize,
     pub col: usize,
+    /// Where the token's text ends (exclusive); past `line` only for a
+    /// string literal spanning lines.
+    pub end_line: usize,
+    pub end_col: usize,
     /// Comments that appeared immediately before this token (on preceding lines
     /// or on the same line before other content). Empty for most tokens.
     pub leading_trivia: Vec<Trivia>,
 }
 
+impl Token {
+    pub fn span(&self) -> Span {
+        Span {
+            line: self.line,
+            col: self.col,
+            end_line: self.end_line,
+            end_col: self.end_col,
+        }
+    }
+}
+
+/// Where `text`, starting at `line`/`col`, ends (exclusive).
+fn end_of(text: &str, line: usize, col: usize) -> (usize, usize) {
+    match text.rfind('\n') {
+        Some(at) => (line + text.matches('\n').count(), text.len() - at),
+        None => (line, col + text.len()),
+    }
+}
+
 /// Lex `source` and return the full output including trailing trivia.
 pub fn lex_full(source: &str) -> Result<LexOutput, CompileError> {
     let mut tokens = Vec::new();
@@ -200,6 +259,7 @@ pub fn lex_full(source: &str) -> Result<LexOutput, CompileError> {
             }
         }
         let col = span.start - line_start + 1;
+        let (end_line, end_col) = end_of(&source[span.start..span.end], line, col);
 
         match result {
             Ok(kind) => match kind {
@@ -221,6 +281,8 @@ pub fn lex_full(source: &str) -> Result<LexOutput, CompileError> {
                             kind: TokenKind::Newline,
                             line,
                             col,
+                            end_line: line,
+                            end_col: col,
                             leading_trivia: Vec::new(),
                         });
                     }
@@ -230,14 +292,20 @@ pub fn lex_full(source: &str) -> Result<LexOutput, CompileError> {
                         kind: other,
                         line,
                         col,
+                        end_line,
+                        end_col,
                         leading_trivia: std::mem::take(&mut trivia_buf),
                     });
                 }
             },
             Err(_) => {
                 return Err(CompileError::Lexer {
-                    line,
-                    col,
+                    span: Span {
+                        line,
+                        col,
+                        end_line,
+                        end_col,
+                    },
                     msg: format!("unexpected character: {:?}", &source[span.start..span.end]),
                 });
             }
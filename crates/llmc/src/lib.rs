@@ -1,16 +1,24 @@
 pub mod ast;
+pub mod budget;
 pub mod codegen;
+pub mod doctest;
 pub mod error;
 pub mod lexer;
 pub mod parser;
+pub mod reproducible;
+pub mod span;
 pub mod suggest;
 #[cfg(test)]
 mod tests;
 pub mod typeck;
 
+pub use budget::Budget;
+pub use codegen::EmitOptions;
 pub use error::CompileError;
+pub use reproducible::{verify_deterministic, DeterminismReport};
+pub use span::Span;
 
-use boruna_bytecode::Module;
+use boruna_bytecode::{Module, Provenance};
 
 /// Version of the `.ax` language this compiler implements.
 ///
@@ -27,6 +35,16 @@ pub fn language_version() -> &'static str {
     LANGUAGE_VERSION
 }
 
+/// Identifies this compiler in [`Provenance::compiler`].
+pub const COMPILER_ID: &str = concat!("boruna-compiler ", env!("CARGO_PKG_VERSION"));
+
+/// Provenance for a module this compiler builds from `source`: the
+/// compiler id and the source digest. Callers add the package and build
+/// time they know about.
+pub fn provenance(source: &str) -> Provenance {
+    Provenance::new(COMPILER_ID, source)
+}
+
 /// Compile source code to a bytecode module.
 pub fn compile(name: &str, source: &str) -> Result<Module, CompileError> {
     let tokens = lexer::lex(source)?;
@@ -35,6 +53,40 @@ pub fn compile(name: &str, source: &str) -> Result<Module, CompileError> {
     codegen::emit(name, &program)
 }
 
+/// Compile with explicit code generation options, e.g. a release build
+/// that strips contracts.
+pub fn compile_with_options(
+    name: &str,
+    source: &str,
+    options: EmitOptions,
+) -> Result<Module, CompileError> {
+    let tokens = lexer::lex(source)?;
+    let program = parser::parse(tokens)?;
+    typeck::check(&program)?;
+    codegen::emit_with_options(name, &program, options)
+}
+
+/// Compile with `options`, then reject the module if it exceeds any limit
+/// of `budget`. Every exceeded limit is listed in the error.
+pub fn compile_with_budget(
+    name: &str,
+    source: &str,
+    options: EmitOptions,
+    budget: &Budget,
+) -> Result<Module, CompileError> {
+    let tokens = lexer::lex(source)?;
+    let program = parser::parse(tokens)?;
+    typeck::check(&program)?;
+    let module = codegen::emit_with_options(name, &program, options)?;
+    let violations = budget::check(&program, &module, budget);
+    if violations.is_empty() {
+        Ok(module)
+    } else {
+        let msgs: Vec<String> = violations.iter().map(|v| v.to_string()).collect();
+        Err(CompileError::Budget(msgs.join("; ")))
+    }
+}
+
 #[cfg(test)]
 mod version_tests {
     use super::{language_version, LANGUAGE_VERSION};
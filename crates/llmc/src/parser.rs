@@ -1,6 +1,7 @@
 use crate::ast::*;
 use crate::error::CompileError;
-use crate::lexer::{Token, TokenKind};
+use crate::lexer::{allow_pragma_codes, Token, TokenKind};
+use crate::span::{SourceMap, Span};
 use crate::suggest;
 
 /// Render a `TokenKind` for parser-error messages.
@@ -42,6 +43,8 @@ fn display_token(kind: &TokenKind) -> String {
         TokenKind::Ident(s) => format!("'{s}'"),
         TokenKind::IntLit(_) => "<integer literal>".into(),
         TokenKind::FloatLit(_) => "<float literal>".into(),
+        TokenKind::DecimalLit(_) => "<decimal literal>".into(),
+        TokenKind::BigIntLit(_) => "<bigint literal>".into(),
         TokenKind::StringLit(_) => "<string literal>".into(),
         // For all remaining operator/punctuation tokens, fall back
         // to Debug. They appear infrequently in keyword-typo paths
@@ -147,11 +150,23 @@ impl Parser {
         None
     }
 
-    fn current_line(&self) -> usize {
-        if self.pos < self.tokens.len() {
-            self.tokens[self.pos].line
-        } else {
-            self.tokens.last().map(|t| t.line).unwrap_or(1)
+    /// The current token, or the end of the last one at end of input.
+    fn current_span(&self) -> Span {
+        match self.tokens.get(self.pos) {
+            Some(t) => t.span(),
+            None => self.tokens.last().map_or(
+                Span {
+                    line: 1,
+                    col: 1,
+                    end_line: 1,
+                    end_col: 1,
+                },
+                |t| Span {
+                    line: t.end_line,
+                    col: t.end_col,
+                    ..t.span()
+                },
+            ),
         }
     }
 
@@ -240,7 +255,7 @@ impl Parser {
 
     fn error(&self, msg: String) -> CompileError {
         CompileError::Parse {
-            line: self.current_line(),
+            span: self.current_span(),
             msg,
         }
     }
@@ -255,6 +270,8 @@ impl Parser {
             None
         };
 
+        let uses = self.parse_uses()?;
+
         let mut items = Vec::new();
         while self.peek().is_some() {
             self.skip_newlines();
@@ -264,10 +281,48 @@ impl Parser {
             items.push(self.parse_item()?);
         }
 
-        Ok(Program { module_name, items })
+        Ok(Program {
+            module_name,
+            uses,
+            items,
+            source_map: SourceMap::new(&self.tokens),
+        })
+    }
+
+    /// A capability name, possibly dotted like `fs.read`.
+    fn parse_capability_name(&mut self) -> Result<String, CompileError> {
+        let cap = self.expect_ident()?;
+        if self.check(&TokenKind::Dot) {
+            self.advance();
+            let sub = self.expect_ident()?;
+            Ok(format!("{cap}.{sub}"))
+        } else {
+            Ok(cap)
+        }
+    }
+
+    /// An optional `uses cap1, cap2` declaration. `uses` is contextual:
+    /// it is only a keyword in declaration position.
+    fn parse_uses(&mut self) -> Result<Vec<String>, CompileError> {
+        if !matches!(self.peek(), Some(TokenKind::Ident(s)) if s == "uses") {
+            return Ok(Vec::new());
+        }
+        self.advance();
+        let mut caps = vec![self.parse_capability_name()?];
+        while self.check(&TokenKind::Comma) {
+            self.advance();
+            caps.push(self.parse_capability_name()?);
+        }
+        Ok(caps)
     }
 
     fn parse_item(&mut self) -> Result<Item, CompileError> {
+        self.skip_newlines();
+        let allow = self
+            .tokens
+            .get(self.pos)
+            .map(|t| allow_pragma_codes(&t.leading_trivia))
+            .unwrap_or_default();
         let exported = if self.check(&TokenKind::Export) {
             self.advance();
             true
@@ -279,11 +334,13 @@ impl Parser {
             Some(TokenKind::Fn) => {
                 let mut fndef = self.parse_fn_def()?;
                 fndef.exported = exported;
+                fndef.allow = allow;
                 Ok(Item::Function(fndef))
             }
             Some(TokenKind::Type) | Some(TokenKind::Enum) => {
                 let mut typedef = self.parse_type_def()?;
                 typedef.exported = exported;
+                typedef.allow = allow;
                 Ok(Item::TypeDef(typedef))
             }
             Some(TokenKind::Import) => {
@@ -331,8 +388,9 @@ impl Parser {
             None
         };
 
-        // Parse capability annotations: !{cap1, cap2}
-        let capabilities = if self.check(&TokenKind::Bang) {
+        // Parse capability annotations: !{cap1, cap2} and/or
+        // `uses cap1, cap2`
+        let mut capabilities = if self.check(&TokenKind::Bang) {
             self.advance();
             self.expect(&TokenKind::LBrace)?;
             let mut caps = Vec::new();
@@ -340,21 +398,18 @@ impl Parser {
                 if !caps.is_empty() {
                     self.expect(&TokenKind::Comma)?;
                 }
-                let cap = self.expect_ident()?;
-                // Allow dotted names like "fs.read"
-                if self.check(&TokenKind::Dot) {
-                    self.advance();
-                    let sub = self.expect_ident()?;
-                    caps.push(format!("{cap}.{sub}"));
-                } else {
-                    caps.push(cap);
-                }
+                caps.push(self.parse_capability_name()?);
             }
             self.expect(&TokenKind::RBrace)?;
             caps
         } else {
             Vec::new()
         };
+        for cap in self.parse_uses()? {
+            if !capabilities.contains(&cap) {
+                capabilities.push(cap);
+            }
+        }
 
         // Parse intent / requires / ensures clauses (order-independent).
         let mut intent: Option<String> = None;
@@ -393,6 +448,7 @@ impl Parser {
             ensures,
             body,
             exported: false,
+            allow: Vec::new(),
         })
     }
 
@@ -421,6 +477,7 @@ impl Parser {
                     name,
                     kind: TypeDefKind::Record(fields),
                     exported: false,
+                    allow: Vec::new(),
                 })
             }
             Some(TokenKind::Enum) => {
@@ -451,6 +508,7 @@ impl Parser {
                     name,
                     kind: TypeDefKind::Enum(variants),
                     exported: false,
+                    allow: Vec::new(),
                 })
             }
             _ => Err(self.error("expected type or enum".into())),
@@ -759,7 +817,13 @@ impl Parser {
                 };
             } else if self.check(&TokenKind::Dot) {
                 self.advance();
-                let field = self.expect_ident()?;
+                // `spawn` is a keyword but also names `cap.spawn`.
+                let field = if self.check(&TokenKind::Spawn) {
+                    self.advance();
+                    "spawn".to_string()
+                } else {
+                    self.expect_ident()?
+                };
                 expr = Expr::FieldAccess {
                     object: Box::new(expr),
                     field,
@@ -787,6 +851,20 @@ impl Parser {
                     unreachable!()
                 }
             }
+            Some(TokenKind::DecimalLit(_)) => {
+                if let Some(TokenKind::DecimalLit((d, text))) = self.advance() {
+                    Ok(Expr::DecimalLit(d, text))
+                } else {
+                    unreachable!()
+                }
+            }
+            Some(TokenKind::BigIntLit(_)) => {
+                if let Some(TokenKind::BigIntLit(n)) = self.advance() {
+                    Ok(Expr::BigIntLit(n))
+                } else {
+                    unreachable!()
+                }
+            }
             Some(TokenKind::StringLit(_)) => {
                 if let Some(TokenKind::StringLit(s)) = self.advance() {
                     Ok(Expr::StringLit(s))
@@ -920,7 +998,13 @@ impl Parser {
                                 break;
                             }
                         }
-                        let fname = self.expect_ident()?;
+                        let mut fname = self.expect_ident()?;
+                        // Deep update: `user.name: value`
+                        while self.check(&TokenKind::Dot) {
+                            self.advance();
+                            fname.push('.');
+                            fname.push_str(&self.expect_ident()?);
+                        }
                         self.expect(&TokenKind::Colon)?;
                         let fval = self.parse_expr()?;
                         fields.push((fname, fval));
@@ -0,0 +1,96 @@
+//! Deterministic build verification.
+//!
+//! The same source and options must always compile to the same `.axbc`
+//! bytes: content hashes, module signatures and evidence bundles all
+//! depend on it. [`verify_deterministic`] builds a source twice, each
+//! build on a fresh thread so that nothing thread-local — hasher seeds,
+//! caches, interned state — carries over from one build to the other,
+//! and compares the encoded modules byte for byte.
+
+use boruna_hash::HashAlgorithm;
+use serde::Serialize;
+
+use crate::{compile_with_options, CompileError, EmitOptions};
+
+/// Outcome of building one source twice.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DeterminismReport {
+    /// `sha256:<hex>` of each build's `.axbc` bytes.
+    pub digests: [String; 2],
+    /// Encoded size of each build, in bytes.
+    pub sizes: [usize; 2],
+    /// Offset of the first byte at which the builds differ; `None` when
+    /// they are identical.
+    pub first_difference: Option<usize>,
+}
+
+impl DeterminismReport {
+    pub fn is_deterministic(&self) -> bool {
+        self.first_difference.is_none()
+    }
+}
+
+/// Compile `source` twice with `options`, each time on its own thread,
+/// and compare the encoded modules. A compile error fails the check
+/// before anything is compared.
+pub fn verify_deterministic(
+    name: &str,
+    source: &str,
+    options: EmitOptions,
+) -> Result<DeterminismReport, CompileError> {
+    let first = build_isolated(name, source, options)?;
+    let second = build_isolated(name, source, options)?;
+    let first_difference = if first == second {
+        None
+    } else {
+        Some(
+            first
+                .iter()
+                .zip(&second)
+                .position(|(a, b)| a != b)
+                .unwrap_or(first.len().min(second.len())),
+        )
+    };
+    Ok(DeterminismReport {
+        digests: [&first, &second].map(|b| HashAlgorithm::Sha256.digest(b).to_string()),
+        sizes: [first.len(), second.len()],
+        first_difference,
+    })
+}
+
+fn build_isolated(name: &str, source: &str, options: EmitOptions) -> Result<Vec<u8>, CompileError> {
+    std::thread::scope(|scope| {
+        scope
+            .spawn(|| {
+                compile_with_options(name, source, options)?
+                    .to_bytes()
+                    .map_err(|e| CompileError::codegen(e.to_string()))
+            })
+            .join()
+            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_source_builds_identically() {
+        let source = "type P { x: Int, y: Int }\n\
+                      fn main() -> Int {\n\
+                        let p = P { x: 1, y: 2 }\n\
+                        match p.x { 1 => p.y, _ => 0 }\n\
+                      }\n";
+        let report = verify_deterministic("app", source, EmitOptions::default()).unwrap();
+        assert!(report.is_deterministic(), "{report:?}");
+        assert_eq!(report.digests[0], report.digests[1]);
+        assert!(report.digests[0].starts_with("sha256:"));
+        assert_eq!(report.sizes[0], report.sizes[1]);
+    }
+
+    #[test]
+    fn test_compile_error_fails_the_check() {
+        assert!(verify_deterministic("app", "fn main( {", EmitOptions::default()).is_err());
+    }
+}
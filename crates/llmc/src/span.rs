@@ -0,0 +1,134 @@
+//! Source ranges for compile errors.
+//!
+//! Lexer and parser errors take their [`Span`] from the token at fault.
+//! The AST carries no positions, so type and codegen errors are located
+//! through the [`SourceMap`] the parser attaches to the [`Program`]: each
+//! error names the identifier it is about (an undefined name, a field, a
+//! call), and the map finds that identifier's first token inside the
+//! function being checked.
+//!
+//! [`Program`]: crate::ast::Program
+
+use serde::{Deserialize, Serialize};
+
+use crate::lexer::{Token, TokenKind};
+
+/// A range of source text. Lines and columns are 1-based, columns count
+/// bytes, and the end is exclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Span {
+    pub line: usize,
+    pub col: usize,
+    pub end_line: usize,
+    pub end_col: usize,
+}
+
+impl Span {
+    /// The range from the start of `self` to the end of `other`.
+    pub fn to(self, other: Span) -> Span {
+        Span {
+            end_line: other.end_line,
+            end_col: other.end_col,
+            ..self
+        }
+    }
+}
+
+/// Identifier tokens of a parsed file, for locating type and codegen
+/// errors. Empty for a program that was not parsed from source (e.g.
+/// deserialized), in which case nothing is located.
+#[derive(Debug, Clone, Default)]
+pub struct SourceMap {
+    /// Identifiers, `.` separators and `match` keywords in source order.
+    tokens: Vec<(MapToken, Span)>,
+    /// Each top-level `fn`: its name and the range of `tokens` from its
+    /// name up to the next top-level `fn`.
+    functions: Vec<(String, std::ops::Range<usize>)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum MapToken {
+    Ident(String),
+    Dot,
+    Match,
+    /// Any other token; it breaks a dotted path.
+    Other,
+}
+
+impl SourceMap {
+    pub fn new(tokens: &[Token]) -> Self {
+        let mut map = SourceMap::default();
+        let mut depth = 0usize;
+        let mut after_fn = false;
+        for token in tokens {
+            let entry = match &token.kind {
+                TokenKind::Ident(name) => MapToken::Ident(name.clone()),
+                TokenKind::Dot => MapToken::Dot,
+                TokenKind::Match => MapToken::Match,
+                TokenKind::Newline => continue,
+                _ => MapToken::Other,
+            };
+            match (&token.kind, &entry) {
+                (TokenKind::LBrace, _) => depth += 1,
+                (TokenKind::RBrace, _) => depth = depth.saturating_sub(1),
+                (_, MapToken::Ident(name)) if after_fn => {
+                    let at = map.tokens.len();
+                    if let Some((_, range)) = map.functions.last_mut() {
+                        range.end = at;
+                    }
+                    map.functions.push((name.clone(), at..usize::MAX));
+                }
+                _ => {}
+            }
+            after_fn = depth == 0 && token.kind == TokenKind::Fn;
+            map.tokens.push((entry, token.span()));
+        }
+        if let Some((_, range)) = map.functions.last_mut() {
+            range.end = map.tokens.len();
+        }
+        map
+    }
+
+    /// The name in the definition of function `name`.
+    pub fn function(&self, name: &str) -> Option<Span> {
+        let (_, range) = self.functions.iter().find(|(f, _)| f == name)?;
+        Some(self.tokens[range.start].1)
+    }
+
+    /// `match <scrutinee>` inside function `in_fn`.
+    pub fn find_match(&self, in_fn: &str, scrutinee: &str) -> Option<Span> {
+        let (_, range) = self.functions.iter().find(|(name, _)| name == in_fn)?;
+        self.tokens[range.clone()]
+            .windows(2)
+            .find_map(|pair| match pair {
+                [(MapToken::Match, start), (MapToken::Ident(name), end)] if name == scrutinee => {
+                    Some(start.to(*end))
+                }
+                _ => None,
+            })
+    }
+
+    /// The first occurrence of `subject` — an identifier, or a dotted path
+    /// such as `user.name` or `net.fetch` — inside function `in_fn`, or
+    /// anywhere in the file when `in_fn` is `None`. `None` when it does
+    /// not occur, or `in_fn` is not defined in this file.
+    pub fn find(&self, in_fn: Option<&str>, subject: &str) -> Option<Span> {
+        let range = match in_fn {
+            Some(f) => self.functions.iter().find(|(name, _)| name == f)?.1.clone(),
+            None => 0..self.tokens.len(),
+        };
+        let parts: Vec<&str> = subject.split('.').collect();
+        let width = parts.len() * 2 - 1;
+        let tokens = &self.tokens[range];
+        tokens.windows(width).find_map(|window| {
+            let matches = window.iter().enumerate().all(|(i, (token, _))| {
+                if i % 2 == 1 {
+                    *token == MapToken::Dot
+                } else {
+                    matches!(token, MapToken::Ident(name) if name == parts[i / 2])
+                }
+            });
+            matches.then(|| window[0].1.to(window[width - 1].1))
+        })
+    }
+}
@@ -94,6 +94,30 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_allow_pragma_attaches_to_items() {
+        let src = "// helper\n// boruna:allow(W003, E005)\nfn helper() -> Int { 1 }\n\n\
+                   // boruna:allow(E011)\nexport type T { x: Int }\nfn main() -> Int { 0 }\n";
+        let program = parser::parse(lexer::lex(src).unwrap()).unwrap();
+        let allows: Vec<Vec<String>> = program
+            .items
+            .iter()
+            .map(|item| match item {
+                Item::Function(f) => f.allow.clone(),
+                Item::TypeDef(t) => t.allow.clone(),
+                _ => Vec::new(),
+            })
+            .collect();
+        assert_eq!(
+            allows,
+            vec![
+                vec!["W003".to_string(), "E005".to_string()],
+                vec!["E011".to_string()],
+                vec![],
+            ]
+        );
+    }
+
     #[test]
     fn test_compiler_still_compiles_source_with_comments() {
         let value = run_source("// hello\nfn main() -> Int { 42 }");
@@ -200,6 +224,137 @@ mod tests {
         assert_eq!(main.intent.as_deref(), Some("declared purpose"));
     }
 
+    #[test]
+    fn test_codegen_records_capability_versions() {
+        let module = compile("m", "fn main() -> Int !{time.now} { 0 }").unwrap();
+        assert_eq!(
+            module
+                .capability_versions
+                .get("time.now")
+                .map(String::as_str),
+            Some(boruna_bytecode::Capability::TimeNow.version())
+        );
+        let pure = compile("m", "fn main() -> Int { 0 }").unwrap();
+        assert!(pure.capability_versions.is_empty());
+    }
+
+    #[test]
+    fn test_decimal_literal_compiles_to_decimal_constant() {
+        let src =
+            "fn main() -> Decimal { let price = 19.99d\n price * __builtin_int_to_decimal(3) }";
+        let module = compile("m", src).unwrap();
+        assert!(module
+            .constants
+            .iter()
+            .any(|c| matches!(c, Value::Decimal(d) if d.to_string() == "19.99")));
+        let mut vm = Vm::new(module, CapabilityGateway::new(Policy::allow_all()));
+        assert_eq!(vm.run().unwrap().to_string(), "59.97d");
+        // More than 9 fractional digits is not a decimal literal.
+        assert!(compile("m", "fn main() -> Decimal { 0.0000000001d }").is_err());
+    }
+
+    #[test]
+    fn test_bigint_literal_and_explicit_promotion() {
+        let src = "fn main() -> BigInt {\n\
+                   let x = __builtin_int_to_bigint(9223372036854775807)\n\
+                   x * x + 1n\n\
+                   }";
+        let module = compile("m", src).unwrap();
+        let mut vm = Vm::new(module, CapabilityGateway::new(Policy::allow_all()));
+        assert_eq!(
+            vm.run().unwrap().to_string(),
+            "85070591730234615847396907784232501250n"
+        );
+
+        let src = "fn main() -> Bool { __builtin_bigint_pow_mod(4n, 13n, 497n) == 445n }";
+        let mut vm = Vm::new(
+            compile("m", src).unwrap(),
+            CapabilityGateway::new(Policy::allow_all()),
+        );
+        assert_eq!(vm.run().unwrap(), Value::Bool(true));
+    }
+
+    #[test]
+    fn test_json_parse_feeds_list_builtins() {
+        let src = r#"fn main() -> String {
+    match json_parse("[10, [20, 30.5, null, true]]") {
+        Ok(doc) => json_stringify(list_get(doc, 1)),
+        Err(e) => e,
+    }
+}"#;
+        let module = compile("m", src).unwrap();
+        let mut vm = Vm::new(module, CapabilityGateway::new(Policy::allow_all()));
+        assert_eq!(
+            vm.run().unwrap(),
+            Value::String("[20,30.5,null,true]".into())
+        );
+
+        let src = r#"fn main() -> String {
+    match json_parse("[1,") {
+        Ok(doc) => json_stringify(doc),
+        Err(e) => e,
+    }
+}"#;
+        let module = compile("m", src).unwrap();
+        let mut vm = Vm::new(module, CapabilityGateway::new(Policy::allow_all()));
+        assert!(matches!(vm.run().unwrap(), Value::String(e) if e.contains("EOF")));
+    }
+
+    #[test]
+    fn test_e2e_str_match_and_find() {
+        assert_eq!(
+            run_source(r#"fn main() -> Bool { str_match("^[a-z0-9_]{3,16}$", "user_42") }"#),
+            Value::Bool(true),
+        );
+        let result = run_source(
+            r#"
+            fn main() -> String {
+                match str_find("[0-9]+", "invoice 2024-118") {
+                    Some(n) => n,
+                    None => "",
+                }
+            }
+        "#,
+        );
+        assert_eq!(result, Value::String("2024".into()));
+    }
+
+    #[test]
+    fn test_e2e_assert_and_fail_trap_with_message() {
+        let src = r#"
+            fn withdraw(balance: Int, amount: Int) -> Int {
+                assert(amount <= balance, "insufficient funds")
+                balance - amount
+            }
+
+            fn checked(code: Int) -> Int {
+                if code == 0 { fail("unknown code") } else { code }
+            }
+
+            fn main() -> Int {
+                withdraw(10, 3) + checked(2)
+            }
+        "#;
+        assert_eq!(run_source(src), Value::Int(9));
+
+        let run_err = |main: &str| {
+            let module = compile("m", &src.replace("withdraw(10, 3) + checked(2)", main)).unwrap();
+            Vm::new(module, CapabilityGateway::new(Policy::allow_all()))
+                .run()
+                .unwrap_err()
+        };
+        assert!(matches!(
+            run_err("withdraw(1, 3)"),
+            boruna_vm::VmError::UserAssertion { message, function, .. }
+                if message == "insufficient funds" && function == "withdraw"
+        ));
+        assert!(matches!(
+            run_err("checked(0)"),
+            boruna_vm::VmError::UserFailure { message, function, .. }
+                if message == "unknown code" && function == "checked"
+        ));
+    }
+
     #[test]
     fn test_requires_emits_assert_opcode() {
         use boruna_bytecode::Op;
@@ -263,6 +418,96 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_contract_violation_quotes_clause_and_code() {
+        use boruna_vm::VmError;
+        let src = "fn clamp(lo: Int, hi: Int) -> Int requires lo <= hi && (hi - lo) * 2 < 100 ensures result >= lo { lo }\n\
+                   fn main() -> Int { clamp(5, 1) }";
+        let module = compile("test", src).expect("compile");
+        let mut vm = Vm::new(module, CapabilityGateway::new(Policy::allow_all()));
+        match vm.run() {
+            Err(VmError::ContractViolation {
+                message,
+                counterexample,
+            }) => {
+                assert_eq!(
+                    message,
+                    "[E013] precondition 1 failed in `clamp`: requires lo <= hi && (hi - lo) * 2 < 100"
+                );
+                assert_eq!(counterexample, vec!["5".to_string(), "1".to_string()]);
+            }
+            other => panic!("expected ContractViolation, got {other:?}"),
+        }
+
+        let src = "fn neg(n: Int) -> Int ensures result < 0 { n }\nfn main() -> Int { neg(3) }";
+        let err = Vm::new(
+            compile("test", src).unwrap(),
+            CapabilityGateway::new(Policy::allow_all()),
+        )
+        .run()
+        .unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("[E014] postcondition 1 failed in `neg`: ensures result < 0"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_release_build_strips_contracts() {
+        use crate::EmitOptions;
+        use boruna_bytecode::Op;
+        let src = "fn check(x: Int) -> Int requires x > 0 ensures result > 0 { x }\n\
+                   fn main() -> Int { check(0) }";
+        let release = EmitOptions {
+            strip_contracts: true,
+        };
+        let module = crate::compile_with_options("test", src, release).unwrap();
+        assert!(!module
+            .functions
+            .iter()
+            .any(|f| f.code.iter().any(|op| matches!(op, Op::Assert { .. }))));
+        let mut vm = Vm::new(module, CapabilityGateway::new(Policy::allow_all()));
+        assert_eq!(vm.run().unwrap(), Value::Int(0));
+    }
+
+    #[test]
+    fn test_contracts_off_skips_checks() {
+        use boruna_vm::replay::Event;
+        let src = "fn check(x: Int) -> Int requires x > 0 { x }\nfn main() -> Int { check(0) }";
+        let module = compile("test", src).unwrap();
+        let mut vm = Vm::new(module, CapabilityGateway::new(Policy::allow_all()));
+        vm.set_enforce_contracts(false);
+        assert_eq!(vm.run().unwrap(), Value::Int(0));
+        assert!(!vm
+            .event_log()
+            .events()
+            .iter()
+            .any(|e| matches!(e, Event::ContractCheck { .. })));
+    }
+
+    #[test]
+    fn test_expr_display_round_trips_precedence() {
+        use crate::ast::{FnDef, Item};
+        let src = "fn f(a: Int, b: Int, s: String) -> Bool requires (a + b) * 2 > a - (b - 1) || !(s == \"x\") && len(s) > 0 { true }";
+        let program = crate::parser::parse(crate::lexer::lex(src).unwrap()).unwrap();
+        let Item::Function(FnDef { requires, .. }) = &program.items[0] else {
+            panic!("expected a function");
+        };
+        let text = requires[0].to_string();
+        assert_eq!(
+            text,
+            "(a + b) * 2 > a - (b - 1) || !(s == \"x\") && len(s) > 0"
+        );
+        // Re-parsing the rendering yields the same rendering.
+        let again = format!("fn g(a: Int, b: Int, s: String) -> Bool requires {text} {{ true }}");
+        let program = crate::parser::parse(crate::lexer::lex(&again).unwrap()).unwrap();
+        let Item::Function(FnDef { requires, .. }) = &program.items[0] else {
+            panic!("expected a function");
+        };
+        assert_eq!(requires[0].to_string(), text);
+    }
+
     #[test]
     fn test_ensures_satisfied_runs_normally() {
         let src =
@@ -590,6 +835,37 @@ fn main() -> Int { area(Shape::Square(5)) }
         );
     }
 
+    #[test]
+    fn test_non_exhaustive_enum_match_is_rejected() {
+        let program = |arms: &str| {
+            format!(
+                "enum Shape {{ Circle(Int), Square(Int), Point }}\n\
+                 fn area(s: Shape) -> Int {{ match s {{ {arms} }} }}\n\
+                 fn main() -> Int {{ area(Shape::Point) }}"
+            )
+        };
+        let err = compile("test", &program("Circle(r) => r, Square(x) => x"))
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("non-exhaustive match on 's' of type 'Shape': missing variants: Point"),
+            "{err}"
+        );
+        assert!(compile("test", &program("Circle(r) => r, _ => 0")).is_ok());
+        assert!(compile(
+            "test",
+            &program("Circle(r) => r, Square(x) => x, Point => 0")
+        )
+        .is_ok());
+        let err = compile("test", &program("Circle(r) => r, Triangle => 0"))
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("unknown enum variant in match pattern: Triangle"),
+            "{err}"
+        );
+    }
+
     #[test]
     fn test_arity_mismatch_is_rejected() {
         // A direct call with the wrong number of arguments is a static error.
@@ -886,6 +1162,103 @@ fn main() -> Int {
         assert_eq!(run_source(src), Value::Int(1 + 2 + 99 + 5));
     }
 
+    #[test]
+    fn test_e2e_record_deep_update() {
+        let src = r#"
+type Address { city: String, zip: Int }
+type User { name: String, address: Address }
+type State { user: User, count: Int }
+fn main() -> String {
+    let s: State = State { user: User { name: "ada", address: Address { city: "paris", zip: 75 } }, count: 1 }
+    let s2: State = State { ..s, user.address.city: "lyon", count: s.count + 1 }
+    s2.user.name ++ " " ++ s2.user.address.city ++ " " ++ __builtin_int_to_string(s2.user.address.zip + s2.count)
+}
+"#;
+        assert_eq!(run_source(src), Value::String("ada lyon 77".into()));
+    }
+
+    #[test]
+    fn test_record_update_fields_are_checked() {
+        let program = |literal: &str| {
+            format!(
+                "type User {{ name: String, age: Int }}\n\
+                 type State {{ user: User, count: Int }}\n\
+                 fn main() -> Int {{ let s: State = State {{ user: User {{ name: \"a\", age: 1 }}, count: 0 }}\n\
+                 let t: State = {literal}\n t.count }}"
+            )
+        };
+        let err = |literal: &str| compile("test", &program(literal)).unwrap_err().to_string();
+        assert!(compile("test", &program("State { ..s, user.age: 2 }")).is_ok());
+        let e = err("State { ..s, countt: 1 }");
+        assert!(
+            e.contains("unknown field 'countt' in record type 'State'") && e.contains("'count'"),
+            "{e}"
+        );
+        let e = err("State { ..s, user.nmae: \"b\" }");
+        assert!(
+            e.contains("unknown field 'nmae' in record type 'User'"),
+            "{e}"
+        );
+        let e = err("State { ..s, count.x: 1 }");
+        assert!(
+            e.contains("field 'count' of record type 'State' is not a record"),
+            "{e}"
+        );
+        let e = err("State { user.age: 2, count: 1 }");
+        assert!(e.contains("needs a record update"), "{e}");
+        let e = err("State { ..s, user: s.user, user.age: 2 }");
+        assert!(
+            e.contains("conflicting updates to 'user' and 'user.age'"),
+            "{e}"
+        );
+    }
+
+    #[test]
+    fn test_compile_errors_carry_spans() {
+        let span = |src: &str| {
+            compile("test", src)
+                .unwrap_err()
+                .span()
+                .map(|s| (s.line, s.col, s.end_line, s.end_col))
+        };
+        // Lexer: the offending character.
+        assert_eq!(span("fn main() -> Int {\n    1 @ 2\n}"), Some((2, 7, 2, 8)));
+        // Parser: the token it stopped at.
+        assert_eq!(
+            span("fn main() -> Int {\n    let = 1\n}"),
+            Some((2, 9, 2, 10))
+        );
+        // Type: the identifier the error is about, in its function.
+        assert_eq!(
+            span("fn yy() -> Int { 1 }\nfn main() -> Int {\n    let x: Int = 1\n    x + yyy\n}"),
+            Some((4, 9, 4, 12))
+        );
+        assert_eq!(
+            span("fn main() -> Int {\n    let s: String = \"a\"\n    str_contains(s)\n}"),
+            Some((3, 5, 3, 17))
+        );
+        // A deep update path and a non-exhaustive match.
+        let records = "type U { n: Int }\ntype S { u: U }\nfn main() -> Int {\n    \
+                       let s: S = S { u: U { n: 1 } }\n    let t: S = S { ..s, u.m: 2 }\n    1\n}";
+        assert_eq!(span(records), Some((5, 27, 5, 28)));
+        let shapes = "enum Shape { A, B }\nfn f(s: Shape) -> Int {\n    match s { A => 1 }\n}";
+        assert_eq!(span(shapes), Some((3, 5, 3, 12)));
+        // Codegen errors are located the same way.
+        let program = parser::parse(lexer::lex("fn main() -> Int {\n    zz\n}").unwrap()).unwrap();
+        let err = codegen::emit("test", &program).unwrap_err();
+        assert_eq!(
+            err.span().map(|s| (s.line, s.col, s.end_col)),
+            Some((2, 5, 7))
+        );
+        // Programs not parsed from source have nothing to locate against.
+        let json = serde_json::to_string(&program).unwrap();
+        let detached: Program = serde_json::from_str(&json).unwrap();
+        assert!(codegen::emit("test", &detached)
+            .unwrap_err()
+            .span()
+            .is_none());
+    }
+
     // ── String match E2E tests ──
 
     #[test]
@@ -964,4 +1337,257 @@ fn main() -> Int {
 "#;
         assert_eq!(run_source(src), Value::Int(42));
     }
+
+    // --- Budget Tests ---
+
+    #[test]
+    fn test_budget_reports_every_exceeded_limit() {
+        use crate::budget::Budget;
+        let src = r#"
+fn classify(n: Int) -> Int {
+    if n > 10 {
+        if n > 100 { 3 } else { 2 }
+    } else {
+        if n > 0 { 1 } else { 0 }
+    }
+}
+fn main() -> Int { classify(42) }
+"#;
+        let program = parser::parse(lexer::lex(src).unwrap()).unwrap();
+        let depths: Vec<usize> = program
+            .items
+            .iter()
+            .filter_map(|i| match i {
+                Item::Function(f) => Some(crate::budget::nesting_depth(f)),
+                _ => None,
+            })
+            .collect();
+        // The `else { if .. }` continues the outer chain; only the `if`
+        // inside the first branch nests.
+        assert_eq!(depths, vec![2, 0]);
+
+        let lenient = Budget {
+            max_functions: Some(2),
+            max_nesting_depth: Some(2),
+            ..Budget::default()
+        };
+        let options = crate::EmitOptions::default();
+        assert!(crate::compile_with_budget("b", src, options, &lenient).is_ok());
+
+        let strict = Budget {
+            max_functions: Some(1),
+            max_ops_per_function: Some(5),
+            max_nesting_depth: Some(1),
+            ..Budget::default()
+        };
+        let err = crate::compile_with_budget("b", src, options, &strict)
+            .unwrap_err()
+            .to_string();
+        assert!(err.starts_with("budget exceeded: "), "got: {err}");
+        assert!(
+            err.contains("module has 2 functions, over max_functions (1)"),
+            "got: {err}"
+        );
+        assert!(
+            err.contains("function 'classify' has 2 levels of nesting, over max_nesting_depth (1)"),
+            "got: {err}"
+        );
+        assert!(
+            err.contains("function 'classify' has")
+                && err.contains("ops, over max_ops_per_function (5)"),
+            "got: {err}"
+        );
+
+        let unknown = serde_json::from_str::<Budget>(r#"{"max_funcs": 3}"#);
+        assert!(unknown.is_err());
+    }
+
+    // --- Capability Declaration Tests ---
+
+    #[test]
+    fn test_parse_uses_declarations() {
+        let src = "module store\nuses kv.get, kv.set\n\
+                   fn load(key: String) -> Option<String> uses kv.get { kv_get(key) }\n\
+                   fn both() -> Int !{kv.set} uses kv.get { 0 }\n";
+        let program = parser::parse(lexer::lex(src).unwrap()).unwrap();
+        assert_eq!(program.uses, vec!["kv.get", "kv.set"]);
+        let caps: Vec<Vec<String>> = program
+            .items
+            .iter()
+            .filter_map(|i| match i {
+                Item::Function(f) => Some(f.capabilities.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(caps, vec![vec!["kv.get"], vec!["kv.set", "kv.get"]]);
+        // `uses` stays an ordinary identifier outside declarations.
+        assert!(compile("t", "fn main() -> Int { let uses: Int = 1\n uses }").is_ok());
+    }
+
+    #[test]
+    fn test_undeclared_capability_call_is_a_compile_error() {
+        let err = compile("t", "fn main() -> Int { queue_push(\"jobs\", 1) }")
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains(
+                "capability violation: function 'main' calls queue_push, which needs queue.push"
+            ),
+            "got: {err}"
+        );
+        assert!(compile(
+            "t",
+            "fn main() -> Int uses queue.push { queue_push(\"jobs\", 1) }"
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_module_uses_bounds_function_declarations() {
+        let src = "uses kv.get\nfn main() -> Int uses kv.set { kv_set(\"k\", 1)\n 0 }\n";
+        let err = compile("t", src).unwrap_err().to_string();
+        assert!(
+            err.contains(
+                "function 'main' declares kv.set, which the module's `uses` does not list"
+            ),
+            "got: {err}"
+        );
+        let err = compile("t", "uses kv.nope\nfn main() -> Int { 0 }\n")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("unknown capability 'kv.nope'"), "got: {err}");
+        assert!(compile(
+            "t",
+            "uses kv.get, kv.set\nfn main() -> Int uses kv.set { kv_set(\"k\", 1)\n 0 }\n"
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_cap_spawn_and_await_compile_to_async_ops() {
+        use boruna_bytecode::{Capability, Op};
+        let src = "fn main() -> Option<String> uses kv.get {\n\
+                   let a = cap.spawn(kv_get(\"a\"))\n\
+                   let b = cap.spawn(kv_get(\"b\"))\n\
+                   cap.await(b)\n\
+                   cap.await(a)\n}\n";
+        let module = compile("t", src).unwrap();
+        let code = &module.functions[0].code;
+        let spawns = code
+            .iter()
+            .filter(|op| **op == Op::CapSpawn(Capability::KvGet.id(), 1))
+            .count();
+        assert_eq!(spawns, 2);
+        assert_eq!(code.iter().filter(|op| **op == Op::CapAwait).count(), 2);
+
+        let err = |src: &str| compile("t", src).unwrap_err().to_string();
+        let not_a_call = err("fn main() -> Int uses kv.get { cap.spawn(1)\n 0 }");
+        assert!(
+            not_a_call.contains("cap.spawn takes capability calls"),
+            "got: {not_a_call}"
+        );
+        let undeclared = err("fn main() -> Int { cap.spawn(kv_get(\"a\"))\n 0 }");
+        assert!(
+            undeclared.contains("which needs kv.get"),
+            "got: {undeclared}"
+        );
+        let arity = err("fn main() -> Int uses kv.get { cap.spawn(kv_get())\n 0 }");
+        assert!(arity.contains("expects 1 argument"), "got: {arity}");
+    }
+
+    #[test]
+    fn test_cap_batch_compiles_to_one_cap_batch() {
+        let src = "fn main() -> List<Result<Option<String>, String>> uses kv.get {\n\
+                   let k: String = \"b\"\n\
+                   cap.batch([kv_get(\"a\"), kv_get(k)])\n}\n";
+        let module = compile("t", src).unwrap();
+        let code = &module.functions[0].code;
+        assert!(code.contains(&boruna_bytecode::Op::CapBatch(
+            boruna_bytecode::Capability::KvGet.id()
+        )));
+        assert!(!code
+            .iter()
+            .any(|op| matches!(op, boruna_bytecode::Op::CapCall(..))));
+
+        let err = |src: &str| compile("t", src).unwrap_err().to_string();
+        let mixed = err(
+            "fn main() -> Int uses kv.get, kv.scan { cap.batch([kv_get(\"a\"), kv_scan(\"b\")])\n 0 }",
+        );
+        assert!(
+            mixed.contains("must all use one capability"),
+            "got: {mixed}"
+        );
+        let undeclared = err("fn main() -> Int { cap.batch([kv_get(\"a\")])\n 0 }");
+        assert!(
+            undeclared.contains("which needs kv.get"),
+            "got: {undeclared}"
+        );
+        let arity = err("fn main() -> Int uses kv.get { cap.batch([kv_get()])\n 0 }");
+        assert!(arity.contains("expects 1 argument"), "got: {arity}");
+        assert!(err("fn main() -> Int { cap.batch([])\n 0 }").contains("at least one"));
+    }
+
+    #[test]
+    fn compile_errors_carry_stable_codes() {
+        use boruna_errors::BorunaError;
+
+        let parse = compile("t", "fn main() -> Int { 1 + }").unwrap_err();
+        let env = parse.envelope();
+        assert_eq!(
+            (env.code.as_str(), env.kind.as_str()),
+            ("E002", "compile.parse")
+        );
+        assert_eq!(env.data["line"], 1);
+
+        let ty = compile("t", "fn main() -> Int { cap.batch([kv_get(\"a\")])\n 0 }").unwrap_err();
+        assert_eq!(ty.error_code().code, "E009", "{ty}");
+    }
+
+    #[test]
+    fn doctests_are_extracted_and_compiled_against_the_file() {
+        use crate::doctest::{compile_test, extract, function_index, ACTUAL_FN, EXPECTED_FN};
+
+        let src = "//! test: add(1, 2) == 3\n  //! test: f(\"a==b\", [x == y]) == (1 == 1)\nfn add(a: Int, b: Int) -> Int { a + b }\n";
+        let tests = extract(src).unwrap();
+        assert_eq!(tests.len(), 2);
+        assert_eq!((tests[0].line, tests[0].expr.as_str()), (1, "add(1, 2)"));
+        assert_eq!(tests[0].expected, "3");
+        assert_eq!(tests[1].expr, "f(\"a==b\", [x == y])");
+        assert_eq!(tests[1].expected, "(1 == 1)");
+
+        let module = compile_test("t", src, &tests[0]).unwrap();
+        assert_eq!(Some(module.entry), function_index(&module, ACTUAL_FN));
+        assert!(function_index(&module, EXPECTED_FN).is_some());
+
+        for bad in [
+            "//! test: add(1, 2)",
+            "//! test: a == b == c",
+            "//! test:  == 3",
+        ] {
+            let err = extract(bad).unwrap_err();
+            assert!(
+                matches!(
+                    err,
+                    crate::CompileError::Parse {
+                        span: crate::Span { line: 1, .. },
+                        ..
+                    }
+                ),
+                "{bad}: {err}"
+            );
+        }
+
+        let broken = extract("//! test: add(1 +) == 3\nfn add(a: Int) -> Int { a }\n").unwrap();
+        let err = compile_test("t", "fn add(a: Int) -> Int { a }\n", &broken[0]).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                crate::CompileError::Parse {
+                    span: crate::Span { line: 1, .. },
+                    ..
+                }
+            ),
+            "{err}"
+        );
+    }
 }
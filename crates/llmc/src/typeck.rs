@@ -1,7 +1,11 @@
 use std::collections::{HashMap, HashSet};
 
+use boruna_bytecode::Capability;
+
 use crate::ast::*;
+use crate::codegen::{builtin_capability, capability_op};
 use crate::error::CompileError;
+use crate::span::SourceMap;
 use crate::suggest;
 
 /// Build a `did you mean: '...'?` suffix from in-scope locals and
@@ -24,23 +28,180 @@ fn ident_suggestion_suffix(
 
 /// Type checking pass.
 /// For MVP, this does basic validation: name resolution, basic type consistency.
+/// Errors are located through the program's [`SourceMap`].
 pub fn check(program: &Program) -> Result<(), CompileError> {
-    let mut checker = TypeChecker::new();
+    let mut checker = TypeChecker::new(&program.source_map);
     checker.check_program(program)
 }
 
-struct TypeChecker {
+/// Every capability built-in `f` calls, as `(builtin, capability)` in
+/// source order.
+pub fn capability_calls(f: &FnDef) -> Vec<(String, Capability)> {
+    let mut calls = Vec::new();
+    for e in f.requires.iter().chain(&f.ensures) {
+        calls_in_expr(e, &mut calls);
+    }
+    calls_in_block(&f.body, &mut calls);
+    calls
+}
+
+fn calls_in_block(block: &Block, calls: &mut Vec<(String, Capability)>) {
+    for stmt in &block.stmts {
+        match stmt {
+            Stmt::Let { value, .. } | Stmt::Assign { value, .. } | Stmt::Expr(value) => {
+                calls_in_expr(value, calls)
+            }
+            Stmt::Return(value) => {
+                if let Some(e) = value {
+                    calls_in_expr(e, calls);
+                }
+            }
+            Stmt::While { condition, body } => {
+                calls_in_expr(condition, calls);
+                calls_in_block(body, calls);
+            }
+            Stmt::For { iter, body, .. } => {
+                calls_in_expr(iter, calls);
+                calls_in_block(body, calls);
+            }
+        }
+    }
+}
+
+fn calls_in_expr(expr: &Expr, calls: &mut Vec<(String, Capability)>) {
+    match expr {
+        Expr::Call { func, args } => {
+            if let Expr::Ident(name) = func.as_ref() {
+                if let Some((cap, _)) = builtin_capability(name) {
+                    calls.push((name.clone(), cap));
+                }
+            }
+            calls_in_expr(func, calls);
+            for arg in args {
+                calls_in_expr(arg, calls);
+            }
+        }
+        Expr::Binary { left, right, .. } => {
+            calls_in_expr(left, calls);
+            calls_in_expr(right, calls);
+        }
+        Expr::Send { target, message } => {
+            calls_in_expr(target, calls);
+            calls_in_expr(message, calls);
+        }
+        Expr::Unary { expr: e, .. }
+        | Expr::SomeExpr(e)
+        | Expr::OkExpr(e)
+        | Expr::ErrExpr(e)
+        | Expr::Spawn(e)
+        | Expr::Emit(e)
+        | Expr::FieldAccess { object: e, .. } => calls_in_expr(e, calls),
+        Expr::If {
+            condition,
+            then_block,
+            else_block,
+        } => {
+            calls_in_expr(condition, calls);
+            calls_in_block(then_block, calls);
+            if let Some(b) = else_block {
+                calls_in_block(b, calls);
+            }
+        }
+        Expr::Match { value, arms } => {
+            calls_in_expr(value, calls);
+            for arm in arms {
+                calls_in_expr(&arm.body, calls);
+            }
+        }
+        Expr::Record { fields, spread, .. } => {
+            for (_, e) in fields {
+                calls_in_expr(e, calls);
+            }
+            if let Some(e) = spread {
+                calls_in_expr(e, calls);
+            }
+        }
+        Expr::EnumVariant {
+            payload: Some(e), ..
+        } => calls_in_expr(e, calls),
+        Expr::List(items) => {
+            for e in items {
+                calls_in_expr(e, calls);
+            }
+        }
+        Expr::Block(b) => calls_in_block(b, calls),
+        _ => {}
+    }
+}
+
+/// A capability built-in may only be called from a function that declares
+/// its capability, and when the module has a `uses` declaration, every
+/// capability a function declares must appear in it.
+fn check_capabilities(
+    f: &FnDef,
+    module_uses: &[String],
+    source_map: &SourceMap,
+) -> Result<(), CompileError> {
+    let declared: Vec<Capability> = f
+        .capabilities
+        .iter()
+        .filter_map(|c| Capability::from_name(c))
+        .collect();
+    for (builtin, cap) in capability_calls(f) {
+        if !declared.contains(&cap) {
+            return Err(CompileError::Type {
+                msg: format!(
+                    "capability violation: function '{}' calls {builtin}, which needs {cap}, \
+                     but does not declare it — add `uses {cap}`",
+                    f.name
+                ),
+                span: source_map.find(Some(&f.name), &builtin),
+            });
+        }
+    }
+    if !module_uses.is_empty() {
+        for name in &f.capabilities {
+            let cap = Capability::from_name(name);
+            let listed = module_uses
+                .iter()
+                .any(|u| u == name || (cap.is_some() && Capability::from_name(u) == cap));
+            if !listed {
+                return Err(CompileError::Type {
+                    msg: format!(
+                        "capability violation: function '{}' declares {name}, which the module's \
+                         `uses` does not list",
+                        f.name
+                    ),
+                    span: source_map.find(Some(&f.name), name),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+struct TypeChecker<'a> {
     /// Known type names.
     types: HashSet<String>,
     /// Known function names and their arities.
     functions: HashMap<String, usize>,
+    /// Declared enums and their variant names, in declaration order.
+    enums: Vec<(String, Vec<String>)>,
+    /// Declared record types and their fields.
+    records: HashMap<String, Vec<(String, TypeExpr)>>,
+    /// Locates errors in the source.
+    source_map: &'a SourceMap,
+    /// The function being checked, if any.
+    current_fn: Option<String>,
 }
 
-impl TypeChecker {
-    fn new() -> Self {
+impl<'a> TypeChecker<'a> {
+    fn new(source_map: &'a SourceMap) -> Self {
         let mut types = HashSet::new();
         // Built-in types
-        for t in &["Int", "Float", "String", "Bool", "Unit"] {
+        for t in &[
+            "Int", "Float", "Decimal", "BigInt", "Json", "String", "Bool", "Unit", "Table",
+        ] {
             types.insert(t.to_string());
         }
 
@@ -77,6 +238,27 @@ impl TypeChecker {
         functions.insert("__builtin_int_parse".to_string(), 1);
         functions.insert("__builtin_float_parse".to_string(), 1);
         functions.insert("__builtin_bool_to_string".to_string(), 1);
+        functions.insert("__builtin_decimal_parse".to_string(), 1);
+        functions.insert("__builtin_decimal_to_string".to_string(), 1);
+        functions.insert("__builtin_int_to_decimal".to_string(), 1);
+        functions.insert("str_match".to_string(), 2);
+        functions.insert("str_find".to_string(), 2);
+        functions.insert("json_parse".to_string(), 1);
+        functions.insert("json_stringify".to_string(), 1);
+        functions.insert("table_select".to_string(), 2);
+        functions.insert("table_filter".to_string(), 4);
+        functions.insert("table_aggregate".to_string(), 4);
+        functions.insert("table_sort".to_string(), 3);
+        functions.insert("table_column".to_string(), 2);
+        functions.insert("table_columns".to_string(), 1);
+        functions.insert("table_len".to_string(), 1);
+        functions.insert("table_row".to_string(), 2);
+        functions.insert("table_hash".to_string(), 1);
+        functions.insert("__builtin_bigint_parse".to_string(), 1);
+        functions.insert("__builtin_bigint_to_string".to_string(), 1);
+        functions.insert("__builtin_int_to_bigint".to_string(), 1);
+        functions.insert("__builtin_bigint_to_int".to_string(), 1);
+        functions.insert("__builtin_bigint_pow_mod".to_string(), 3);
         functions.insert("__builtin_map_get".to_string(), 2);
         functions.insert("__builtin_map_set".to_string(), 3);
         functions.insert("__builtin_map_remove".to_string(), 2);
@@ -88,6 +270,11 @@ impl TypeChecker {
         // See docs/spec/bytecode-1.0.md §4 (Debug, DebugMsg).
         functions.insert("__builtin_debug".to_string(), 1);
         functions.insert("__builtin_debug_msg".to_string(), 2);
+        // `assert(cond, msg)` traps with the message when `cond` is false
+        // and is `Unit` otherwise; `fail(msg)` always traps. Compile to
+        // `Op::AssertMsg` and `Op::Fail`.
+        functions.insert("assert".to_string(), 2);
+        functions.insert("fail".to_string(), 1);
         // guard-and-seal: `__builtin_guard(value, passed, label)` runs a
         // deterministic boolean check on a value, fails closed on false,
         // and seals the verdict into the evidence trail. Returns `value`
@@ -99,8 +286,32 @@ impl TypeChecker {
         // the JSON-encoded upstream output as a String. Steps that
         // need typed access parse the JSON.
         functions.insert("step_input".to_string(), 1);
+        // Embedded key-value store. Compile to `Op::CapCall(KvGet, 1)`,
+        // `(KvSet, 2)` and `(KvScan, 1)`. `kv_get` returns `Some(value)`
+        // or `None`; `kv_scan` returns a `Map` of key to value.
+        functions.insert("kv_get".to_string(), 1);
+        functions.insert("kv_set".to_string(), 2);
+        functions.insert("kv_scan".to_string(), 1);
+        // File-backed queues. Compile to `Op::CapCall(QueuePush, 2)` and
+        // `(QueuePoll, 1)`. `queue_push` returns the item's sequence
+        // number; `queue_poll` returns `Some(value)` or `None`.
+        functions.insert("queue_push".to_string(), 2);
+        functions.insert("queue_poll".to_string(), 1);
+        // Notifications. Compile to `Op::CapCall(NotifySend, 3)`;
+        // returns a `Map` receipt.
+        functions.insert("notify_send".to_string(), 3);
+        // Table loading. Compiles to `Op::CapCall(DataReadTable, 1)`;
+        // returns a `Table`.
+        functions.insert("read_table".to_string(), 1);
 
-        TypeChecker { types, functions }
+        TypeChecker {
+            types,
+            functions,
+            enums: Vec::new(),
+            records: HashMap::new(),
+            source_map,
+            current_fn: None,
+        }
     }
 
     fn check_program(&mut self, program: &Program) -> Result<(), CompileError> {
@@ -112,6 +323,15 @@ impl TypeChecker {
                 }
                 Item::TypeDef(t) => {
                     self.types.insert(t.name.clone());
+                    match &t.kind {
+                        TypeDefKind::Enum(variants) => {
+                            let names = variants.iter().map(|(v, _)| v.clone()).collect();
+                            self.enums.push((t.name.clone(), names));
+                        }
+                        TypeDefKind::Record(fields) => {
+                            self.records.insert(t.name.clone(), fields.clone());
+                        }
+                    }
                 }
                 _ => {}
             }
@@ -120,12 +340,31 @@ impl TypeChecker {
         // Second pass: validate
         for item in &program.items {
             match item {
-                Item::Function(f) => self.check_fn(f)?,
+                Item::Function(f) => {
+                    self.current_fn = Some(f.name.clone());
+                    self.check_fn(f)?;
+                    self.current_fn = None;
+                }
                 Item::TypeDef(t) => self.check_type_def(t)?,
                 _ => {}
             }
         }
 
+        // Third pass: capability declarations
+        for name in &program.uses {
+            if Capability::from_name(name).is_none() {
+                return Err(self.error(
+                    name,
+                    format!("unknown capability '{name}' in module `uses`"),
+                ));
+            }
+        }
+        for item in &program.items {
+            if let Item::Function(f) = item {
+                check_capabilities(f, &program.uses, self.source_map)?;
+            }
+        }
+
         Ok(())
     }
 
@@ -158,9 +397,7 @@ impl TypeChecker {
             Stmt::Assign { target, value } => {
                 if !locals.contains(target) && !self.functions.contains_key(target) {
                     let suffix = ident_suggestion_suffix(target, locals, &self.functions);
-                    return Err(CompileError::Type(format!(
-                        "undefined variable: {target}{suffix}"
-                    )));
+                    return Err(self.error(target, format!("undefined variable: {target}{suffix}")));
                 }
                 self.check_expr(value, locals)?;
             }
@@ -186,9 +423,7 @@ impl TypeChecker {
         match expr {
             Expr::Ident(name) if !locals.contains(name) && !self.functions.contains_key(name) => {
                 let suffix = ident_suggestion_suffix(name, locals, &self.functions);
-                return Err(CompileError::Type(format!(
-                    "undefined variable: {name}{suffix}"
-                )));
+                return Err(self.error(name, format!("undefined variable: {name}{suffix}")));
             }
             Expr::Ident(_) => {}
             Expr::Binary { left, right, .. } => {
@@ -197,6 +432,18 @@ impl TypeChecker {
             }
             Expr::Unary { expr, .. } => self.check_expr(expr, locals)?,
             Expr::Call { func, args } => {
+                if !locals.contains("cap") {
+                    if let Some(op) = capability_op(func, args) {
+                        if let Err(e) = op {
+                            return Err(self.locate(e, &func.to_string()));
+                        }
+                        // Each call is checked as an ordinary built-in call.
+                        for arg in args {
+                            self.check_expr(arg, locals)?;
+                        }
+                        return Ok(());
+                    }
+                }
                 self.check_expr(func, locals)?;
                 for arg in args {
                     self.check_expr(arg, locals)?;
@@ -208,11 +455,14 @@ impl TypeChecker {
                     if !locals.contains(name) {
                         if let Some(&arity) = self.functions.get(name) {
                             if args.len() != arity {
-                                return Err(CompileError::Type(format!(
-                                    "function '{name}' expects {arity} argument{}, got {}",
-                                    if arity == 1 { "" } else { "s" },
-                                    args.len()
-                                )));
+                                return Err(self.error(
+                                    name,
+                                    format!(
+                                        "function '{name}' expects {arity} argument{}, got {}",
+                                        if arity == 1 { "" } else { "s" },
+                                        args.len()
+                                    ),
+                                ));
                             }
                         }
                     }
@@ -239,14 +489,20 @@ impl TypeChecker {
                     self.collect_pattern_bindings(&arm.pattern, &mut inner);
                     self.check_expr(&arm.body, &inner)?;
                 }
+                self.check_exhaustive(value, arms)?;
             }
-            Expr::Record { fields, spread, .. } => {
+            Expr::Record {
+                type_name,
+                fields,
+                spread,
+            } => {
                 if let Some(base) = spread {
                     self.check_expr(base, locals)?;
                 }
                 for (_, val) in fields {
                     self.check_expr(val, locals)?;
                 }
+                self.check_record_fields(type_name, fields, spread.is_some())?;
             }
             Expr::List(items) => {
                 for item in items {
@@ -276,6 +532,184 @@ impl TypeChecker {
         Ok(())
     }
 
+    /// A match whose arms name enum variants, with no `_` or binding arm,
+    /// must cover every variant of the enum those variants belong to.
+    /// A type error about `subject`, located at its first occurrence in
+    /// the function being checked, or at that function's name when it
+    /// does not occur.
+    fn error(&self, subject: &str, msg: String) -> CompileError {
+        let in_fn = self.current_fn.as_deref();
+        let span = self
+            .source_map
+            .find(in_fn, subject)
+            .or_else(|| self.source_map.function(in_fn?));
+        CompileError::Type { msg, span }
+    }
+
+    /// `e`, located at `subject` unless it already has a location.
+    fn locate(&self, e: CompileError, subject: &str) -> CompileError {
+        match e {
+            CompileError::Type { msg, span: None } => self.error(subject, msg),
+            other => other,
+        }
+    }
+
+    /// A type error about a match: at `match <scrutinee>` when the
+    /// scrutinee is a name, otherwise at the first arm's variant.
+    fn match_error(&self, value: &Expr, covered: &[&str], msg: String) -> CompileError {
+        let span = match (value, self.current_fn.as_deref()) {
+            (Expr::Ident(name), Some(f)) => self.source_map.find_match(f, name),
+            _ => None,
+        };
+        match span {
+            Some(span) => CompileError::Type {
+                msg,
+                span: Some(span),
+            },
+            None => self.error(covered.first().copied().unwrap_or_default(), msg),
+        }
+    }
+
+    fn check_exhaustive(&self, value: &Expr, arms: &[MatchArm]) -> Result<(), CompileError> {
+        if arms
+            .iter()
+            .any(|a| matches!(a.pattern, Pattern::Wildcard | Pattern::Ident(_)))
+        {
+            return Ok(());
+        }
+        let covered: Vec<&str> = arms
+            .iter()
+            .filter_map(|a| match &a.pattern {
+                Pattern::EnumVariant(name, _) => Some(name.as_str()),
+                _ => None,
+            })
+            .collect();
+        if covered.is_empty() {
+            return Ok(());
+        }
+        if let Some(unknown) = covered
+            .iter()
+            .find(|c| !self.enums.iter().any(|(_, vs)| vs.iter().any(|v| v == *c)))
+        {
+            return Err(self.error(
+                unknown,
+                format!("unknown enum variant in match pattern: {unknown}"),
+            ));
+        }
+        let candidates: Vec<&(String, Vec<String>)> = self
+            .enums
+            .iter()
+            .filter(|(_, vs)| covered.iter().all(|c| vs.iter().any(|v| v == c)))
+            .collect();
+        let Some((enum_name, variants)) = candidates.first() else {
+            return Err(self.match_error(
+                value,
+                &covered,
+                format!(
+                    "match arms mix variants of different enums: {}",
+                    covered.join(", ")
+                ),
+            ));
+        };
+        if candidates
+            .iter()
+            .any(|(_, vs)| vs.iter().all(|v| covered.contains(&v.as_str())))
+        {
+            return Ok(());
+        }
+        let missing: Vec<&str> = variants
+            .iter()
+            .map(String::as_str)
+            .filter(|v| !covered.contains(v))
+            .collect();
+        let on = match value {
+            Expr::Ident(name) => format!("'{name}' of type '{enum_name}'"),
+            _ => format!("enum '{enum_name}'"),
+        };
+        Err(self.match_error(
+            value,
+            &covered,
+            format!(
+                "non-exhaustive match on {on}: missing variants: {}\n  add an arm for each, or a `_ =>` arm",
+                missing.join(", ")
+            ),
+        ))
+    }
+
+    /// Every field a literal of a declared record type sets must exist.
+    /// In an update (`has_spread`) a field may be a dotted path through
+    /// nested record fields; no field may be set twice, directly or
+    /// through a path.
+    fn check_record_fields(
+        &self,
+        type_name: &str,
+        fields: &[(String, Expr)],
+        has_spread: bool,
+    ) -> Result<(), CompileError> {
+        if !self.records.contains_key(type_name) {
+            return Ok(());
+        }
+        for (path, _) in fields {
+            if path.contains('.') && !has_spread {
+                return Err(self.error(
+                    path,
+                    format!(
+                        "field path '{path}' needs a record update: `{type_name} {{ ..base, {path}: ... }}`"
+                    ),
+                ));
+            }
+            let mut record = type_name;
+            let mut segments = path.split('.').peekable();
+            while let Some(segment) = segments.next() {
+                let record_fields = &self.records[record];
+                let Some((_, ty)) = record_fields.iter().find(|(n, _)| n == segment) else {
+                    let names = record_fields.iter().map(|(n, _)| n.as_str());
+                    let hint = match suggest::suggestion_from(segment, names) {
+                        Some(name) => format!("\n  did you mean: '{name}'?"),
+                        Option::None => String::new(),
+                    };
+                    return Err(self.error(
+                        segment,
+                        format!("unknown field '{segment}' in record type '{record}'{hint}"),
+                    ));
+                };
+                if segments.peek().is_some() {
+                    record = match ty {
+                        TypeExpr::Named(n) if self.records.contains_key(n) => n,
+                        _ => {
+                            return Err(self.error(
+                                path,
+                                format!(
+                                    "cannot update '{path}': field '{segment}' of record type '{record}' is not a record"
+                                ),
+                            ))
+                        }
+                    };
+                }
+            }
+        }
+        for (i, (a, _)) in fields.iter().enumerate() {
+            for (b, _) in &fields[i + 1..] {
+                if a == b {
+                    return Err(self.error(
+                        a,
+                        format!("field '{a}' is set more than once in '{type_name}' literal"),
+                    ));
+                }
+                let (outer, inner) = if a.len() < b.len() { (a, b) } else { (b, a) };
+                if inner.starts_with(&format!("{outer}.")) {
+                    return Err(self.error(
+                        inner,
+                        format!(
+                            "conflicting updates to '{outer}' and '{inner}' in '{type_name}' literal"
+                        ),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
     #[allow(clippy::only_used_in_recursion)]
     fn collect_pattern_bindings(&self, pattern: &Pattern, locals: &mut HashSet<String>) {
         match pattern {
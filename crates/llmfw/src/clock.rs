@@ -0,0 +1,105 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use boruna_bytecode::{Capability, Value};
+use boruna_vm::capability_gateway::{CapabilityGateway, CapabilityHandler, Policy};
+use boruna_vm::replay::EventLog;
+
+use crate::error::FrameworkError;
+
+/// Source of time for the framework runtime.
+///
+/// The runtime never reads the wall clock itself: it asks its `Clock` once
+/// per cycle and stamps the `CycleRecord` with the answer. Times are in
+/// milliseconds; what they are measured from is up to the clock.
+pub trait Clock: Send {
+    fn now_ms(&mut self) -> Result<u64, FrameworkError>;
+}
+
+/// Deterministic logical clock — the runtime default.
+///
+/// Time only moves when `advance` or `set` is called, so cycle timestamps
+/// are identical on every run. Clones share the same time, which lets a
+/// test keep a handle to a clock it has given to a runtime or executor.
+#[derive(Debug, Clone, Default)]
+pub struct VirtualClock {
+    now_ms: Arc<AtomicU64>,
+}
+
+impl VirtualClock {
+    pub fn new(start_ms: u64) -> Self {
+        VirtualClock {
+            now_ms: Arc::new(AtomicU64::new(start_ms)),
+        }
+    }
+
+    pub fn now(&self) -> u64 {
+        self.now_ms.load(Ordering::SeqCst)
+    }
+
+    /// Move time forward by `ms`.
+    pub fn advance(&self, ms: u64) {
+        self.now_ms.fetch_add(ms, Ordering::SeqCst);
+    }
+
+    /// Jump to `ms`. Time may not go backwards.
+    pub fn set(&self, ms: u64) -> Result<(), FrameworkError> {
+        let now = self.now();
+        if ms < now {
+            return Err(FrameworkError::State(format!(
+                "virtual clock cannot go back from {now}ms to {ms}ms"
+            )));
+        }
+        self.now_ms.store(ms, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+impl Clock for VirtualClock {
+    fn now_ms(&mut self) -> Result<u64, FrameworkError> {
+        Ok(self.now())
+    }
+}
+
+/// Host clock — reads `time.now` through a capability gateway.
+///
+/// Real time is only available where the policy grants `time.now`; a
+/// denied read fails the cycle. Every reading is recorded in the event log
+/// like any other capability call, so a recorded run replays with the same
+/// timestamps. `time.now` yields Unix seconds.
+pub struct GatewayClock {
+    gateway: CapabilityGateway,
+    event_log: EventLog,
+}
+
+impl GatewayClock {
+    pub fn new(policy: Policy, handler: Box<dyn CapabilityHandler>) -> Self {
+        GatewayClock {
+            gateway: CapabilityGateway::with_handler(policy, handler),
+            event_log: EventLog::new(),
+        }
+    }
+
+    /// Get the event log of `time.now` readings.
+    pub fn event_log(&self) -> &EventLog {
+        &self.event_log
+    }
+}
+
+impl Clock for GatewayClock {
+    fn now_ms(&mut self) -> Result<u64, FrameworkError> {
+        match self
+            .gateway
+            .call(&Capability::TimeNow, &[], &mut self.event_log)?
+        {
+            Value::Int(secs) if secs >= 0 => (secs as u64).checked_mul(1000).ok_or_else(|| {
+                FrameworkError::Effect(format!(
+                    "time.now returned {secs} seconds, past the u64 millisecond range"
+                ))
+            }),
+            other => Err(FrameworkError::Effect(format!(
+                "time.now returned {other}, expected non-negative Int seconds"
+            ))),
+        }
+    }
+}
@@ -24,6 +24,19 @@ pub enum EffectKind {
     EmitUi,
     LlmCall,
     SendToActor,
+    /// Payload: the key. Result: `Some(value)` or `None`.
+    KvGet,
+    /// Payload: a `[key, value]` list. Result: `Unit`.
+    KvSet,
+    /// Payload: the key prefix. Result: `Map` of key to value.
+    KvScan,
+    /// Payload: a `[queue, value]` list. Result: the sequence number.
+    QueuePush,
+    /// Payload: the queue name. Result: `Some(value)` or `None`.
+    QueuePoll,
+    /// Payload: a `[destination, template]` or `[destination, template,
+    /// vars]` list. Result: the `Map` receipt.
+    NotifySend,
 }
 
 impl EffectKind {
@@ -39,6 +52,12 @@ impl EffectKind {
             "emit_ui" => Some(EffectKind::EmitUi),
             "llm_call" => Some(EffectKind::LlmCall),
             "send_to_actor" => Some(EffectKind::SendToActor),
+            "kv_get" => Some(EffectKind::KvGet),
+            "kv_set" => Some(EffectKind::KvSet),
+            "kv_scan" => Some(EffectKind::KvScan),
+            "queue_push" => Some(EffectKind::QueuePush),
+            "queue_poll" => Some(EffectKind::QueuePoll),
+            "notify_send" => Some(EffectKind::NotifySend),
             _ => None,
         }
     }
@@ -55,6 +74,12 @@ impl EffectKind {
             EffectKind::EmitUi => "ui.render",
             EffectKind::LlmCall => "llm.call",
             EffectKind::SendToActor => "actor.send",
+            EffectKind::KvGet => "kv.get",
+            EffectKind::KvSet => "kv.set",
+            EffectKind::KvScan => "kv.scan",
+            EffectKind::QueuePush => "queue.push",
+            EffectKind::QueuePoll => "queue.poll",
+            EffectKind::NotifySend => "notify.send",
         }
     }
 
@@ -70,12 +95,18 @@ impl EffectKind {
             EffectKind::EmitUi => "emit_ui",
             EffectKind::LlmCall => "llm_call",
             EffectKind::SendToActor => "send_to_actor",
+            EffectKind::KvGet => "kv_get",
+            EffectKind::KvSet => "kv_set",
+            EffectKind::KvScan => "kv_scan",
+            EffectKind::QueuePush => "queue_push",
+            EffectKind::QueuePoll => "queue_poll",
+            EffectKind::NotifySend => "notify_send",
         }
     }
 }
 
 /// Extract items from a value that may be a List or a Record{type_id:0xFFFF} (list literal).
-fn as_list(value: &Value) -> Option<&[Value]> {
+pub(crate) fn as_list(value: &Value) -> Option<&[Value]> {
     match value {
         Value::List(items) => Some(items),
         // List literals compile to Record with type_id 0xFFFF
@@ -38,4 +38,72 @@ pub enum FrameworkError {
 
     #[error("max cycles exceeded: {0}")]
     MaxCyclesExceeded(u64),
+
+    #[error("session limit reached: {0} live sessions")]
+    SessionLimit(usize),
+
+    /// Callbacks kept emitting the same effect: `tags` is the message
+    /// cycle, ending with the callback that would have been delivered
+    /// again.
+    #[error("effect loop: {effect} emitted {count} times via {}", tags.join(" -> "))]
+    EffectLoop {
+        effect: String,
+        tags: Vec<String>,
+        count: u32,
+    },
+
+    #[error("limit exceeded: {} is {}, got {}", .0.limit.field(), .0.max, .0.actual)]
+    LimitExceeded(crate::policy::LimitViolation),
+
+    /// A message whose tag is not a variant of the app's `Msg` enum, or
+    /// whose payload does not fit that variant.
+    #[error("invalid message: {0}")]
+    InvalidMessage(String),
+}
+
+impl boruna_errors::BorunaError for FrameworkError {
+    /// `Compile` and `Runtime` report the wrapped error's code.
+    fn error_code(&self) -> &'static boruna_errors::ErrorCode {
+        use boruna_errors as codes;
+        match self {
+            FrameworkError::Validation(_) => &codes::FRAMEWORK_VALIDATION,
+            FrameworkError::MissingFunction(_) => &codes::FRAMEWORK_MISSING_FUNCTION,
+            FrameworkError::PurityViolation { .. } => &codes::FRAMEWORK_PURITY_VIOLATION,
+            FrameworkError::WrongArity { .. } => &codes::FRAMEWORK_WRONG_ARITY,
+            FrameworkError::MissingType(_) => &codes::FRAMEWORK_MISSING_TYPE,
+            FrameworkError::Effect(_) => &codes::FRAMEWORK_EFFECT,
+            FrameworkError::PolicyViolation(_) => &codes::FRAMEWORK_POLICY_VIOLATION,
+            FrameworkError::State(_) => &codes::FRAMEWORK_STATE,
+            FrameworkError::Compile(e) => e.error_code(),
+            FrameworkError::Runtime(e) => e.error_code(),
+            FrameworkError::MaxCyclesExceeded(_) => &codes::FRAMEWORK_MAX_CYCLES_EXCEEDED,
+            FrameworkError::SessionLimit(_) => &codes::FRAMEWORK_SESSION_LIMIT,
+            FrameworkError::EffectLoop { .. } => &codes::FRAMEWORK_EFFECT_LOOP,
+            FrameworkError::LimitExceeded(_) => &codes::FRAMEWORK_LIMIT_EXCEEDED,
+            FrameworkError::InvalidMessage(_) => &codes::FRAMEWORK_INVALID_MESSAGE,
+        }
+    }
+
+    fn error_data(&self) -> serde_json::Value {
+        use serde_json::json;
+        match self {
+            FrameworkError::PurityViolation { name } => json!({ "function": name }),
+            FrameworkError::WrongArity {
+                name,
+                expected,
+                got,
+            } => json!({ "function": name, "expected": expected, "got": got }),
+            FrameworkError::Compile(e) => e.error_data(),
+            FrameworkError::Runtime(e) => e.error_data(),
+            FrameworkError::MaxCyclesExceeded(max) => json!({ "max_cycles": max }),
+            FrameworkError::SessionLimit(max) => json!({ "max_sessions": max }),
+            FrameworkError::EffectLoop {
+                effect,
+                tags,
+                count,
+            } => json!({ "effect": effect, "tags": tags, "count": count }),
+            FrameworkError::LimitExceeded(v) => json!(v),
+            _ => serde_json::Value::Null,
+        }
+    }
 }
@@ -4,7 +4,8 @@ use boruna_bytecode::{Capability, Value};
 use boruna_vm::capability_gateway::{CapabilityGateway, CapabilityHandler, Policy};
 use boruna_vm::replay::EventLog;
 
-use crate::effect::{Effect, EffectKind};
+use crate::clock::Clock;
+use crate::effect::{as_list, Effect, EffectKind};
 use crate::error::FrameworkError;
 use crate::runtime::AppMessage;
 
@@ -23,6 +24,8 @@ pub struct MockEffectExecutor {
     default_response: Value,
     /// Next actor ID for mock SpawnActor effects.
     next_mock_actor_id: u64,
+    /// When set, Timer effects answer with this clock's time in ms.
+    clock: Option<Box<dyn Clock>>,
 }
 
 impl Default for MockEffectExecutor {
@@ -37,9 +40,18 @@ impl MockEffectExecutor {
             responses: BTreeMap::new(),
             default_response: Value::String("mock_result".into()),
             next_mock_actor_id: 1,
+            clock: None,
         }
     }
 
+    /// Answer Timer effects with `clock`'s time instead of the default
+    /// response. Pass a clone of the runtime's `VirtualClock` to test
+    /// timer-driven apps.
+    pub fn with_clock(mut self, clock: Box<dyn Clock>) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+
     /// Set a specific response for a callback tag.
     pub fn set_response(&mut self, callback_tag: impl Into<String>, value: Value) {
         self.responses.insert(callback_tag.into(), value);
@@ -74,6 +86,10 @@ impl EffectExecutor for MockEffectExecutor {
                     Value::ActorId(id)
                 }
                 EffectKind::SendToActor => Value::String("delivered".into()),
+                EffectKind::Timer => match &mut self.clock {
+                    Some(clock) => Value::Int(clock.now_ms()? as i64),
+                    None => self.default_response.clone(),
+                },
                 _ => self.default_response.clone(),
             };
             messages.push(AppMessage::new(&effect.callback_tag, response));
@@ -133,6 +149,12 @@ fn effect_to_capability(kind: &EffectKind) -> Option<Capability> {
         EffectKind::LlmCall => Some(Capability::LlmCall),
         EffectKind::SpawnActor => Some(Capability::ActorSpawn),
         EffectKind::SendToActor => Some(Capability::ActorSend),
+        EffectKind::KvGet => Some(Capability::KvGet),
+        EffectKind::KvSet => Some(Capability::KvSet),
+        EffectKind::KvScan => Some(Capability::KvScan),
+        EffectKind::QueuePush => Some(Capability::QueuePush),
+        EffectKind::QueuePoll => Some(Capability::QueuePoll),
+        EffectKind::NotifySend => Some(Capability::NotifySend),
     }
 }
 
@@ -141,6 +163,15 @@ fn effect_args(effect: &Effect) -> Vec<Value> {
     match effect.kind {
         // Timer and Random take no args
         EffectKind::Timer | EffectKind::Random => vec![],
+        // kv_set's `[key, value]`, queue_push's `[queue, value]` and
+        // notify_send's `[destination, template, vars]` payloads spread
+        // into separate args
+        EffectKind::KvSet | EffectKind::QueuePush | EffectKind::NotifySend => {
+            match as_list(&effect.payload) {
+                Some(items) => items.to_vec(),
+                None => vec![effect.payload.clone()],
+            }
+        }
         // All others pass the payload
         _ => vec![effect.payload.clone()],
     }
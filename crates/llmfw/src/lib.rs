@@ -1,8 +1,11 @@
+pub mod clock;
 pub mod effect;
 pub mod error;
 pub mod executor;
 pub mod policy;
+pub mod query;
 pub mod runtime;
+pub mod session;
 pub mod state;
 pub mod testing;
 #[cfg(test)]
@@ -10,9 +13,12 @@ mod tests;
 pub mod ui;
 pub mod validate;
 
+pub use clock::{Clock, GatewayClock, VirtualClock};
 pub use error::FrameworkError;
 pub use executor::{EffectExecutor, HostEffectExecutor, MockEffectExecutor};
-pub use policy::PolicySet;
-pub use runtime::AppRuntime;
+pub use policy::{LimitViolation, PolicyLimit, PolicySet};
+pub use query::{Query, QueryError};
+pub use runtime::{AppRuntime, Simulation, ViolationRecord};
+pub use session::{SessionConfig, SessionManager};
 pub use testing::TestHarness;
 pub use validate::AppValidator;
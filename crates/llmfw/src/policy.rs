@@ -1,4 +1,5 @@
 use boruna_bytecode::Value;
+use serde::Serialize;
 use serde_json;
 
 use crate::effect::Effect;
@@ -36,6 +37,11 @@ pub struct PolicySet {
     pub max_effects_per_cycle: u64,
     /// Max VM steps per function call (0 = unlimited).
     pub max_steps: u64,
+    /// Max size of the JSON-serialized state after a cycle (0 = unlimited).
+    pub max_state_bytes: u64,
+    /// Max messages accepted in any 1000 ms window of the runtime's clock
+    /// (0 = unlimited).
+    pub max_messages_per_second: u64,
 }
 
 impl Default for PolicySet {
@@ -45,6 +51,8 @@ impl Default for PolicySet {
             capabilities: Vec::new(),
             max_effects_per_cycle: 0,
             max_steps: 10_000_000,
+            max_state_bytes: 0,
+            max_messages_per_second: 0,
         }
     }
 }
@@ -65,15 +73,24 @@ impl PolicySet {
                 "llm.call".into(),
                 "actor.spawn".into(),
                 "actor.send".into(),
+                "kv.get".into(),
+                "kv.set".into(),
+                "kv.scan".into(),
+                "queue.push".into(),
+                "queue.poll".into(),
+                "notify.send".into(),
             ],
             max_effects_per_cycle: 0,
             max_steps: 10_000_000,
+            max_state_bytes: 0,
+            max_messages_per_second: 0,
         }
     }
 
     /// Parse a PolicySet from a VM Value returned by the policies() function.
     ///
     /// Expected: Record { capabilities: List<String>, max_effects: Int, max_steps: Int }
+    /// optionally followed by `max_state_bytes: Int, max_messages_per_second: Int`.
     pub fn from_value(value: &Value) -> Self {
         match value {
             Value::Record { fields, .. } => {
@@ -89,11 +106,17 @@ impl PolicySet {
                     Some(Value::Int(n)) => *n as u64,
                     _ => 10_000_000,
                 };
+                let limit = |i: usize| match fields.get(i) {
+                    Some(Value::Int(n)) if *n > 0 => *n as u64,
+                    _ => 0,
+                };
                 PolicySet {
                     schema_version: 1,
                     capabilities,
                     max_effects_per_cycle: max_effects,
                     max_steps,
+                    max_state_bytes: limit(3),
+                    max_messages_per_second: limit(4),
                 }
             }
             _ => PolicySet::default(),
@@ -119,12 +142,13 @@ impl PolicySet {
 
     /// Check if a batch of effects is within limits.
     pub fn check_batch(&self, effects: &[Effect]) -> Result<(), FrameworkError> {
-        if self.max_effects_per_cycle > 0 && effects.len() as u64 > self.max_effects_per_cycle {
-            return Err(FrameworkError::PolicyViolation(format!(
-                "too many effects: {} exceeds limit of {}",
-                effects.len(),
-                self.max_effects_per_cycle
-            )));
+        let count = effects.len() as u64;
+        if self.max_effects_per_cycle > 0 && count > self.max_effects_per_cycle {
+            return Err(FrameworkError::LimitExceeded(LimitViolation {
+                limit: PolicyLimit::EffectsPerCycle,
+                max: self.max_effects_per_cycle,
+                actual: count,
+            }));
         }
         for effect in effects {
             self.check_effect(effect)?;
@@ -138,11 +162,50 @@ impl PolicySet {
             "capabilities": self.capabilities,
             "max_effects_per_cycle": self.max_effects_per_cycle,
             "max_steps": self.max_steps,
+            "max_state_bytes": self.max_state_bytes,
+            "max_messages_per_second": self.max_messages_per_second,
         }))
         .unwrap_or_default()
     }
 }
 
+/// A numeric [`PolicySet`] limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PolicyLimit {
+    /// `max_effects_per_cycle`
+    EffectsPerCycle,
+    /// `max_steps`, for one update() or view() call
+    Steps,
+    /// `max_state_bytes`
+    StateBytes,
+    /// `max_messages_per_second`
+    MessagesPerSecond,
+}
+
+impl PolicyLimit {
+    /// The `PolicySet` field that sets this limit.
+    pub fn field(self) -> &'static str {
+        match self {
+            PolicyLimit::EffectsPerCycle => "max_effects_per_cycle",
+            PolicyLimit::Steps => "max_steps",
+            PolicyLimit::StateBytes => "max_state_bytes",
+            PolicyLimit::MessagesPerSecond => "max_messages_per_second",
+        }
+    }
+}
+
+/// A message that would have taken the app past one of its limits.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LimitViolation {
+    pub limit: PolicyLimit,
+    /// The declared limit.
+    pub max: u64,
+    /// What the message would have produced: effects, steps (the step at
+    /// which the VM stopped), state bytes or messages in the window.
+    pub actual: u64,
+}
+
 /// Produce a structured JSON diagnostic from a FrameworkError.
 pub fn error_to_json(err: &FrameworkError) -> String {
     let (kind, detail) = match err {
@@ -155,6 +218,10 @@ pub fn error_to_json(err: &FrameworkError) -> String {
         FrameworkError::Effect(msg) => ("effect_error", msg.clone()),
         FrameworkError::State(msg) => ("state_error", msg.clone()),
         FrameworkError::MaxCyclesExceeded(n) => ("max_cycles_exceeded", format!("{n}")),
+        FrameworkError::SessionLimit(n) => ("session_limit", format!("{n}")),
+        FrameworkError::EffectLoop { .. } => ("effect_loop", err.to_string()),
+        FrameworkError::LimitExceeded(_) => ("limit_exceeded", err.to_string()),
+        FrameworkError::InvalidMessage(msg) => ("invalid_message", msg.clone()),
         FrameworkError::WrongArity {
             name,
             expected,
@@ -0,0 +1,719 @@
+//! A small path and query language over JSON views of state.
+//!
+//! ```text
+//! state.items[3].name
+//! state.items[-1]
+//! state.users[?role == "admin"] | count
+//! state.users[?age >= 18 && active].name
+//! severity >= 3
+//! ```
+//!
+//! A query is evaluated against a *scope*, a JSON object: the first name
+//! of a path is a key of the scope (`state` for snapshots, the input names
+//! for workflow gate conditions). Inside a `[?filter]`, names refer to
+//! fields of the element being tested, and `@` to the element itself.
+//!
+//! | syntax | meaning |
+//! |--------|---------|
+//! | `a.b` | field `b`; on a list, `b` of every element (missing ones dropped) |
+//! | `a[n]` | list element `n`; negative counts from the end |
+//! | `a[*]` | the values of an object; a list unchanged |
+//! | `a[?expr]` | the list elements for which `expr` is truthy |
+//! | `a \| f` | apply `f`: `count`, `first`, `last`, `keys`, `sum`, `min`, `max` |
+//! | `==` `!=` `<` `<=` `>` `>=` | compare; ordering applies to numbers and strings only |
+//! | `&&` `\|\|` `!` | logic over truthiness |
+//!
+//! Evaluation never fails: a missing field, an out-of-range index or an
+//! operator applied to the wrong kind of value yields `null`. `null`,
+//! `false`, and empty strings, lists and objects are falsy; everything
+//! else, including `0`, is truthy. Only parsing reports errors, so a query
+//! can be validated once and then evaluated against any input.
+
+use std::fmt;
+
+use boruna_bytecode::module::{TypeDef, TypeKind};
+use boruna_bytecode::Value;
+use serde_json::Value as Json;
+
+/// A query that failed to parse.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("query error at offset {offset}: {message}")]
+pub struct QueryError {
+    /// Byte offset into the query text.
+    pub offset: usize,
+    pub message: String,
+}
+
+/// Functions usable after `|`.
+const FUNCTIONS: &[&str] = &["count", "first", "last", "keys", "sum", "min", "max"];
+
+/// A parsed query.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    source: String,
+    expr: Expr,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Literal(Json),
+    /// A name resolved against the current context.
+    Name(String),
+    /// The current context (`@`).
+    Current,
+    Field(Box<Expr>, String),
+    Index(Box<Expr>, i64),
+    Wildcard(Box<Expr>),
+    Filter(Box<Expr>, Box<Expr>),
+    Pipe(Box<Expr>, String),
+    Compare(Box<Expr>, CmpOp, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CmpOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Query {
+    pub fn parse(source: &str) -> Result<Query, QueryError> {
+        let tokens = lex(source)?;
+        let mut parser = Parser {
+            tokens,
+            pos: 0,
+            end: source.len(),
+        };
+        let expr = parser.expr()?;
+        if let Some((offset, tok)) = parser.tokens.get(parser.pos) {
+            return Err(QueryError {
+                offset: *offset,
+                message: format!("unexpected {tok}"),
+            });
+        }
+        Ok(Query {
+            source: source.to_string(),
+            expr,
+        })
+    }
+
+    /// The query text as written.
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Evaluate against `scope`.
+    pub fn eval(&self, scope: &Json) -> Json {
+        eval(&self.expr, scope)
+    }
+
+    /// Whether the query's result is truthy in `scope`.
+    pub fn matches(&self, scope: &Json) -> bool {
+        truthy(&self.eval(scope))
+    }
+}
+
+impl fmt::Display for Query {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl std::str::FromStr for Query {
+    type Err = QueryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Query::parse(s)
+    }
+}
+
+/// `null`, `false`, `""`, `[]` and `{}` are falsy.
+pub fn truthy(value: &Json) -> bool {
+    match value {
+        Json::Null => false,
+        Json::Bool(b) => *b,
+        Json::String(s) => !s.is_empty(),
+        Json::Array(a) => !a.is_empty(),
+        Json::Object(o) => !o.is_empty(),
+        Json::Number(_) => true,
+    }
+}
+
+/// The JSON view of a value that queries run against.
+///
+/// Records become objects keyed by field name when `types` describes their
+/// type, and lists of fields otherwise. `Some(v)` is `v` and `None` is
+/// `null`; `Ok(v)`/`Err(v)` are `{"ok": v}`/`{"err": v}`; an enum is its
+/// variant name, or `{"Variant": payload}` when it carries one. Decimals
+/// and big integers become strings, tables lists of row objects.
+pub fn value_to_json(value: &Value, types: &[TypeDef]) -> Json {
+    let json = |v: &Value| value_to_json(v, types);
+    match value {
+        Value::Unit | Value::None => Json::Null,
+        Value::Bool(b) => Json::Bool(*b),
+        Value::Int(n) => Json::from(*n),
+        Value::Float(f) => serde_json::Number::from_f64(*f).map_or(Json::Null, Json::Number),
+        Value::Decimal(d) => Json::String(d.to_string()),
+        Value::BigInt(n) => Json::String(n.to_string()),
+        Value::String(s) => Json::String(s.clone()),
+        Value::Some(v) => json(v),
+        Value::Ok(v) => serde_json::json!({ "ok": json(v) }),
+        Value::Err(v) => serde_json::json!({ "err": json(v) }),
+        Value::Record { type_id, fields } => match types.get(*type_id as usize).map(|t| &t.kind) {
+            Some(TypeKind::Record { fields: names }) if names.len() == fields.len() => {
+                Json::Object(
+                    names
+                        .iter()
+                        .zip(fields)
+                        .map(|((name, _), v)| (name.clone(), json(v)))
+                        .collect(),
+                )
+            }
+            _ => Json::Array(fields.iter().map(json).collect()),
+        },
+        Value::Enum {
+            type_id,
+            variant,
+            payload,
+        } => {
+            let name = match types.get(*type_id as usize).map(|t| &t.kind) {
+                Some(TypeKind::Enum { variants }) => variants.get(*variant as usize),
+                _ => None,
+            }
+            .map_or_else(|| variant.to_string(), |(name, _)| name.clone());
+            match payload.as_ref() {
+                Value::Unit => Json::String(name),
+                payload => serde_json::json!({ name: json(payload) }),
+            }
+        }
+        Value::List(items) => Json::Array(items.iter().map(json).collect()),
+        Value::Map(entries) => {
+            Json::Object(entries.iter().map(|(k, v)| (k.clone(), json(v))).collect())
+        }
+        Value::ActorId(n) | Value::CapHandle(n) => Json::from(*n),
+        Value::FnRef(n) => Json::from(*n),
+        Value::Table(t) => Json::Array(
+            (0..t.num_rows())
+                .map(|r| {
+                    Json::Object(
+                        t.columns()
+                            .iter()
+                            .map(|c| (c.name.clone(), json(&c.values[r])))
+                            .collect(),
+                    )
+                })
+                .collect(),
+        ),
+    }
+}
+
+// ─── Lexer ────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq)]
+enum Tok {
+    Ident(String),
+    Number(Json),
+    Str(String),
+    Punct(&'static str),
+}
+
+impl fmt::Display for Tok {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Tok::Ident(name) => write!(f, "`{name}`"),
+            Tok::Number(n) => write!(f, "`{n}`"),
+            Tok::Str(s) => write!(f, "{s:?}"),
+            Tok::Punct(p) => write!(f, "`{p}`"),
+        }
+    }
+}
+
+/// Longest first, so `<=` wins over `<`.
+const PUNCT: &[&str] = &[
+    "==", "!=", "<=", ">=", "&&", "||", "<", ">", "!", ".", "[", "]", "(", ")", "?", "*", "|", "@",
+];
+
+fn lex(src: &str) -> Result<Vec<(usize, Tok)>, QueryError> {
+    let mut tokens = Vec::new();
+    let mut chars = src.char_indices().peekable();
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_alphabetic() || c == '_' {
+            let mut end = start;
+            while let Some(&(i, c)) = chars.peek() {
+                if !(c.is_alphanumeric() || c == '_') {
+                    break;
+                }
+                end = i + c.len_utf8();
+                chars.next();
+            }
+            tokens.push((start, Tok::Ident(src[start..end].to_string())));
+        } else if c.is_ascii_digit() || (c == '-' && next_is_digit(&src[start + 1..])) {
+            chars.next();
+            let mut end = start + 1;
+            while let Some(&(i, c)) = chars.peek() {
+                if !(c.is_ascii_digit() || c == '.') {
+                    break;
+                }
+                end = i + 1;
+                chars.next();
+            }
+            let text = &src[start..end];
+            let number = text
+                .parse::<i64>()
+                .map(Json::from)
+                .ok()
+                .or_else(|| {
+                    text.parse::<f64>()
+                        .ok()
+                        .and_then(serde_json::Number::from_f64)
+                        .map(Json::Number)
+                })
+                .ok_or_else(|| QueryError {
+                    offset: start,
+                    message: format!("invalid number `{text}`"),
+                })?;
+            tokens.push((start, Tok::Number(number)));
+        } else if c == '"' {
+            chars.next();
+            let mut s = String::new();
+            loop {
+                match chars.next() {
+                    Some((_, '"')) => break,
+                    Some((_, '\\')) => match chars.next() {
+                        Some((_, 'n')) => s.push('\n'),
+                        Some((_, 't')) => s.push('\t'),
+                        Some((_, c)) => s.push(c),
+                        None => break,
+                    },
+                    Some((_, c)) => s.push(c),
+                    None => {
+                        return Err(QueryError {
+                            offset: start,
+                            message: "unterminated string".into(),
+                        })
+                    }
+                }
+            }
+            tokens.push((start, Tok::Str(s)));
+        } else if let Some(p) = PUNCT.iter().find(|p| src[start..].starts_with(**p)) {
+            for _ in 0..p.len() {
+                chars.next();
+            }
+            tokens.push((start, Tok::Punct(p)));
+        } else {
+            return Err(QueryError {
+                offset: start,
+                message: format!("unexpected character `{c}`"),
+            });
+        }
+    }
+    Ok(tokens)
+}
+
+fn next_is_digit(rest: &str) -> bool {
+    rest.starts_with(|c: char| c.is_ascii_digit())
+}
+
+// ─── Parser ───────────────────────────────────────────────────
+
+struct Parser {
+    tokens: Vec<(usize, Tok)>,
+    pos: usize,
+    /// Offset reported for errors at end of input.
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Tok> {
+        self.tokens.get(self.pos).map(|(_, t)| t)
+    }
+
+    fn offset(&self) -> usize {
+        self.tokens.get(self.pos).map_or(self.end, |(o, _)| *o)
+    }
+
+    fn eat(&mut self, punct: &str) -> bool {
+        if matches!(self.peek(), Some(Tok::Punct(p)) if *p == punct) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, punct: &str) -> Result<(), QueryError> {
+        if self.eat(punct) {
+            Ok(())
+        } else {
+            Err(self.error(format!("expected `{punct}`")))
+        }
+    }
+
+    fn error(&self, message: String) -> QueryError {
+        let found = match self.peek() {
+            Some(tok) => format!(", found {tok}"),
+            None => ", found end of query".to_string(),
+        };
+        QueryError {
+            offset: self.offset(),
+            message: message + &found,
+        }
+    }
+
+    fn expr(&mut self) -> Result<Expr, QueryError> {
+        let mut lhs = self.and()?;
+        while self.eat("||") {
+            lhs = Expr::Or(Box::new(lhs), Box::new(self.and()?));
+        }
+        Ok(lhs)
+    }
+
+    fn and(&mut self) -> Result<Expr, QueryError> {
+        let mut lhs = self.comparison()?;
+        while self.eat("&&") {
+            lhs = Expr::And(Box::new(lhs), Box::new(self.comparison()?));
+        }
+        Ok(lhs)
+    }
+
+    fn comparison(&mut self) -> Result<Expr, QueryError> {
+        let lhs = self.unary()?;
+        let op = match self.peek() {
+            Some(Tok::Punct("==")) => CmpOp::Eq,
+            Some(Tok::Punct("!=")) => CmpOp::Ne,
+            Some(Tok::Punct("<")) => CmpOp::Lt,
+            Some(Tok::Punct("<=")) => CmpOp::Le,
+            Some(Tok::Punct(">")) => CmpOp::Gt,
+            Some(Tok::Punct(">=")) => CmpOp::Ge,
+            _ => return Ok(lhs),
+        };
+        self.pos += 1;
+        let rhs = self.unary()?;
+        Ok(Expr::Compare(Box::new(lhs), op, Box::new(rhs)))
+    }
+
+    fn unary(&mut self) -> Result<Expr, QueryError> {
+        if self.eat("!") {
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+        let mut expr = self.postfix()?;
+        while self.eat("|") {
+            let offset = self.offset();
+            match self.peek() {
+                Some(Tok::Ident(name)) if FUNCTIONS.contains(&name.as_str()) => {
+                    expr = Expr::Pipe(Box::new(expr), name.clone());
+                    self.pos += 1;
+                }
+                Some(Tok::Ident(name)) => {
+                    return Err(QueryError {
+                        offset,
+                        message: format!(
+                            "unknown function `{name}` (expected one of: {})",
+                            FUNCTIONS.join(", ")
+                        ),
+                    })
+                }
+                _ => return Err(self.error("expected a function name after `|`".into())),
+            }
+        }
+        Ok(expr)
+    }
+
+    fn postfix(&mut self) -> Result<Expr, QueryError> {
+        let mut expr = self.primary()?;
+        loop {
+            if self.eat(".") {
+                match self.peek().cloned() {
+                    Some(Tok::Ident(name)) => {
+                        self.pos += 1;
+                        expr = Expr::Field(Box::new(expr), name);
+                    }
+                    _ => return Err(self.error("expected a field name after `.`".into())),
+                }
+            } else if self.eat("[") {
+                expr = if self.eat("*") {
+                    Expr::Wildcard(Box::new(expr))
+                } else if self.eat("?") {
+                    Expr::Filter(Box::new(expr), Box::new(self.expr()?))
+                } else {
+                    match self.peek().cloned() {
+                        Some(Tok::Number(n)) if n.is_i64() => {
+                            self.pos += 1;
+                            Expr::Index(Box::new(expr), n.as_i64().unwrap_or_default())
+                        }
+                        _ => {
+                            return Err(
+                                self.error("expected an index, `*` or `?filter` in `[]`".into())
+                            )
+                        }
+                    }
+                };
+                self.expect("]")?;
+            } else {
+                return Ok(expr);
+            }
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expr, QueryError> {
+        let expr = match self.peek().cloned() {
+            Some(Tok::Ident(name)) => match name.as_str() {
+                "true" => Expr::Literal(Json::Bool(true)),
+                "false" => Expr::Literal(Json::Bool(false)),
+                "null" => Expr::Literal(Json::Null),
+                _ => Expr::Name(name),
+            },
+            Some(Tok::Number(n)) => Expr::Literal(n),
+            Some(Tok::Str(s)) => Expr::Literal(Json::String(s)),
+            Some(Tok::Punct("@")) => Expr::Current,
+            Some(Tok::Punct("(")) => {
+                self.pos += 1;
+                let inner = self.expr()?;
+                self.expect(")")?;
+                return Ok(inner);
+            }
+            _ => return Err(self.error("expected a name, literal or `(`".into())),
+        };
+        self.pos += 1;
+        Ok(expr)
+    }
+}
+
+// ─── Evaluation ───────────────────────────────────────────────
+
+fn eval(expr: &Expr, ctx: &Json) -> Json {
+    match expr {
+        Expr::Literal(v) => v.clone(),
+        Expr::Current => ctx.clone(),
+        Expr::Name(name) => ctx.get(name).cloned().unwrap_or(Json::Null),
+        Expr::Field(base, name) => match eval(base, ctx) {
+            Json::Object(mut map) => map.remove(name).unwrap_or(Json::Null),
+            Json::Array(items) => Json::Array(
+                items
+                    .into_iter()
+                    .filter_map(|item| item.get(name).cloned())
+                    .filter(|v| !v.is_null())
+                    .collect(),
+            ),
+            _ => Json::Null,
+        },
+        Expr::Index(base, i) => match eval(base, ctx) {
+            Json::Array(mut items) => {
+                let len = items.len() as i64;
+                let i = if *i < 0 { len + i } else { *i };
+                if (0..len).contains(&i) {
+                    items.swap_remove(i as usize)
+                } else {
+                    Json::Null
+                }
+            }
+            _ => Json::Null,
+        },
+        Expr::Wildcard(base) => match eval(base, ctx) {
+            Json::Object(map) => Json::Array(map.into_iter().map(|(_, v)| v).collect()),
+            items @ Json::Array(_) => items,
+            _ => Json::Null,
+        },
+        Expr::Filter(base, predicate) => match eval(base, ctx) {
+            Json::Array(items) => Json::Array(
+                items
+                    .into_iter()
+                    .filter(|item| truthy(&eval(predicate, item)))
+                    .collect(),
+            ),
+            _ => Json::Null,
+        },
+        Expr::Pipe(base, function) => apply(function, eval(base, ctx)),
+        Expr::Compare(lhs, op, rhs) => Json::Bool(compare(&eval(lhs, ctx), *op, &eval(rhs, ctx))),
+        Expr::And(lhs, rhs) => Json::Bool(truthy(&eval(lhs, ctx)) && truthy(&eval(rhs, ctx))),
+        Expr::Or(lhs, rhs) => Json::Bool(truthy(&eval(lhs, ctx)) || truthy(&eval(rhs, ctx))),
+        Expr::Not(inner) => Json::Bool(!truthy(&eval(inner, ctx))),
+    }
+}
+
+fn apply(function: &str, value: Json) -> Json {
+    match (function, value) {
+        ("count", Json::Array(a)) => Json::from(a.len()),
+        ("count", Json::Object(o)) => Json::from(o.len()),
+        ("count", Json::String(s)) => Json::from(s.chars().count()),
+        ("count", Json::Null) => Json::from(0),
+        ("first", Json::Array(a)) => a.into_iter().next().unwrap_or(Json::Null),
+        ("last", Json::Array(a)) => a.into_iter().next_back().unwrap_or(Json::Null),
+        ("keys", Json::Object(o)) => {
+            Json::Array(o.into_iter().map(|(k, _)| Json::String(k)).collect())
+        }
+        ("sum", Json::Array(a)) => {
+            let numbers: Vec<&serde_json::Number> = a
+                .iter()
+                .filter_map(|v| match v {
+                    Json::Number(n) => Some(n),
+                    _ => None,
+                })
+                .collect();
+            match numbers
+                .iter()
+                .try_fold(0i64, |acc, n| acc.checked_add(n.as_i64()?))
+            {
+                Some(total) => Json::from(total),
+                None => serde_json::Number::from_f64(
+                    numbers.iter().filter_map(|n| n.as_f64()).sum::<f64>(),
+                )
+                .map_or(Json::Null, Json::Number),
+            }
+        }
+        ("min" | "max", Json::Array(a)) => {
+            let want = if function == "min" {
+                std::cmp::Ordering::Less
+            } else {
+                std::cmp::Ordering::Greater
+            };
+            a.into_iter().fold(Json::Null, |best, v| {
+                if best.is_null() || order(&v, &best) == Some(want) {
+                    v
+                } else {
+                    best
+                }
+            })
+        }
+        _ => Json::Null,
+    }
+}
+
+fn compare(lhs: &Json, op: CmpOp, rhs: &Json) -> bool {
+    use std::cmp::Ordering::*;
+    match op {
+        CmpOp::Eq => equal(lhs, rhs),
+        CmpOp::Ne => !equal(lhs, rhs),
+        CmpOp::Lt => order(lhs, rhs) == Some(Less),
+        CmpOp::Le => matches!(order(lhs, rhs), Some(Less | Equal)),
+        CmpOp::Gt => order(lhs, rhs) == Some(Greater),
+        CmpOp::Ge => matches!(order(lhs, rhs), Some(Greater | Equal)),
+    }
+}
+
+/// JSON equality, except that `1` equals `1.0`.
+fn equal(lhs: &Json, rhs: &Json) -> bool {
+    match (lhs, rhs) {
+        (Json::Number(_), Json::Number(_)) => order(lhs, rhs) == Some(std::cmp::Ordering::Equal),
+        _ => lhs == rhs,
+    }
+}
+
+/// Ordering between two numbers or two strings; `None` otherwise.
+fn order(lhs: &Json, rhs: &Json) -> Option<std::cmp::Ordering> {
+    match (lhs, rhs) {
+        (Json::Number(a), Json::Number(b)) => match (a.as_i64(), b.as_i64()) {
+            (Some(a), Some(b)) => Some(a.cmp(&b)),
+            _ => a.as_f64()?.partial_cmp(&b.as_f64()?),
+        },
+        (Json::String(a), Json::String(b)) => Some(a.cmp(b)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn run(query: &str, scope: &Json) -> Json {
+        Query::parse(query).unwrap().eval(scope)
+    }
+
+    fn scope() -> Json {
+        json!({ "state": {
+            "items": [{ "name": "a" }, { "name": "b" }, { "name": "c" }, { "name": "d" }],
+            "users": [
+                { "name": "ann", "role": "admin", "age": 40 },
+                { "name": "bob", "role": "user", "age": 17 },
+                { "name": "cy", "role": "admin", "age": 22.5 },
+            ],
+        }})
+    }
+
+    #[test]
+    fn test_paths_indices_and_projection() {
+        let s = scope();
+        assert_eq!(run("state.items[3].name", &s), json!("d"));
+        assert_eq!(run("state.items[-1].name", &s), json!("d"));
+        assert_eq!(run("state.items[9].name", &s), Json::Null);
+        assert_eq!(run("state.users.name", &s), json!(["ann", "bob", "cy"]));
+        assert_eq!(run("state.missing.deeper", &s), Json::Null);
+        assert_eq!(run("state[*] | count", &s), json!(2));
+    }
+
+    #[test]
+    fn test_filters_and_functions() {
+        let s = scope();
+        assert_eq!(run(r#"state.users[?role=="admin"] | count"#, &s), json!(2));
+        assert_eq!(
+            run(r#"state.users[?role == "admin" && age > 30].name"#, &s),
+            json!(["ann"])
+        );
+        assert_eq!(
+            run("state.users[?!(age >= 18)].name | first", &s),
+            json!("bob")
+        );
+        assert_eq!(run("state.users.age | max", &s), json!(40));
+        assert_eq!(run("state.users.age | min", &s), json!(17));
+        assert_eq!(run("state.users.age | sum", &s), json!(79.5));
+        assert_eq!(run("state | keys", &s), json!(["items", "users"]));
+        assert_eq!(run("state.items.name[?@ != \"b\"] | last", &s), json!("d"));
+    }
+
+    #[test]
+    fn test_conditions() {
+        let inputs = json!({ "severity": 3, "tags": [] });
+        assert!(Query::parse("severity >= 3").unwrap().matches(&inputs));
+        assert!(!Query::parse("severity > 3 || tags")
+            .unwrap()
+            .matches(&inputs));
+        assert!(Query::parse("severity == 3.0").unwrap().matches(&inputs));
+        assert!(!Query::parse("severity < \"9\"").unwrap().matches(&inputs));
+    }
+
+    #[test]
+    fn test_parse_errors_carry_offsets() {
+        let err = Query::parse("state.items[").unwrap_err();
+        assert_eq!(err.offset, 12);
+        let err = Query::parse("state.users | average").unwrap_err();
+        assert_eq!(err.offset, 14);
+        assert!(err.message.contains("unknown function `average`"));
+        assert!(Query::parse("state.x ==").is_err());
+        assert!(Query::parse("state.x )").is_err());
+        assert!(Query::parse("\"open").is_err());
+    }
+
+    #[test]
+    fn test_value_to_json_names_record_fields() {
+        let types = vec![TypeDef {
+            name: "State".into(),
+            kind: TypeKind::Record {
+                fields: vec![
+                    ("count".into(), "Int".into()),
+                    ("tag".into(), "Option".into()),
+                ],
+            },
+        }];
+        let state = Value::Record {
+            type_id: 0,
+            fields: vec![
+                Value::Int(2),
+                Value::Some(Box::new(Value::String("x".into()))),
+            ],
+        };
+        assert_eq!(
+            value_to_json(&state, &types),
+            json!({ "count": 2, "tag": "x" })
+        );
+        assert_eq!(value_to_json(&state, &[]), json!([2, "x"]));
+    }
+}
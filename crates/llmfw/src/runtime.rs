@@ -1,13 +1,21 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, VecDeque};
 
+use boruna_bytecode::module::{TypeDef, TypeKind};
 use boruna_bytecode::{Module, Value};
 use boruna_vm::capability_gateway::{CapabilityGateway, Policy};
 use boruna_vm::vm::Vm;
 
-use crate::effect::{parse_update_result, Effect};
+use crate::clock::{Clock, VirtualClock};
+use crate::effect::{parse_update_result, Effect, EffectKind};
 use crate::error::FrameworkError;
-use crate::policy::PolicySet;
-use crate::state::StateMachine;
+use crate::policy::{LimitViolation, PolicyLimit, PolicySet};
+use crate::query::{value_to_json, Query};
+use crate::state::{StateDiff, StateMachine};
+use crate::ui::{ui_diff, UiChange};
+
+/// Name of the app's message type. When the app declares it as an enum,
+/// message tags are its variant names; see [`AppMessage::to_value_in`].
+pub const MESSAGE_TYPE: &str = "Msg";
 
 /// Message delivered to the update() function.
 #[derive(Debug, Clone)]
@@ -31,12 +39,95 @@ impl AppMessage {
             fields: vec![Value::String(self.tag.clone()), self.payload.clone()],
         }
     }
+
+    /// The message as `update`'s argument. When `types` declares
+    /// `enum Msg`, the tag must name one of its variants: a unit variant
+    /// drops the payload, and a variant declared with a scalar type
+    /// (`Int`, `Float`, `String`, `Bool`, `Decimal`, `BigInt`) requires a
+    /// payload of that type. Otherwise this is [`to_value`](Self::to_value).
+    pub fn to_value_in(&self, types: &[TypeDef]) -> Result<Value, FrameworkError> {
+        let Some((type_id, variants)) = message_enum(types) else {
+            return Ok(self.to_value());
+        };
+        let Some(index) = variants.iter().position(|(name, _)| *name == self.tag) else {
+            let names: Vec<&str> = variants.iter().map(|(name, _)| name.as_str()).collect();
+            return Err(FrameworkError::InvalidMessage(format!(
+                "`{}` is not a variant of enum {MESSAGE_TYPE} (expected one of: {})",
+                self.tag,
+                names.join(", ")
+            )));
+        };
+        let payload = match &variants[index].1 {
+            None => Value::Unit,
+            Some(ty) if payload_fits(ty, &self.payload) => self.payload.clone(),
+            Some(ty) => {
+                return Err(FrameworkError::InvalidMessage(format!(
+                    "`{}` carries a {ty} payload, got {}",
+                    self.tag,
+                    self.payload.type_name()
+                )))
+            }
+        };
+        Ok(Value::Enum {
+            type_id,
+            variant: index as u8,
+            payload: Box::new(payload),
+        })
+    }
+
+    /// Decode a message from the JSON form [`value_to_json`] gives an enum
+    /// value: `"Variant"`, or `{"Variant": payload}` with the payload
+    /// mapped as `json_parse` maps documents.
+    pub fn from_json(json: &serde_json::Value) -> Result<Self, FrameworkError> {
+        match json {
+            serde_json::Value::String(tag) => Ok(AppMessage::new(tag.clone(), Value::Unit)),
+            serde_json::Value::Object(fields) if fields.len() == 1 => {
+                let (tag, payload) = fields.iter().next().expect("one field");
+                Ok(AppMessage::new(
+                    tag.clone(),
+                    boruna_vm::json::from_json(payload.clone()),
+                ))
+            }
+            other => Err(FrameworkError::InvalidMessage(format!(
+                "expected \"Variant\" or {{\"Variant\": payload}}, got {other}"
+            ))),
+        }
+    }
+}
+
+/// An enum's variants: name and payload type, if any.
+type Variants = [(String, Option<String>)];
+
+/// The type id and variants of the app's `enum Msg`, if it declares one.
+fn message_enum(types: &[TypeDef]) -> Option<(u32, &Variants)> {
+    types.iter().enumerate().find_map(|(i, t)| match &t.kind {
+        TypeKind::Enum { variants } if t.name == MESSAGE_TYPE => {
+            Some((i as u32, variants.as_slice()))
+        }
+        _ => None,
+    })
+}
+
+/// Whether `payload` fits a variant declared with type `ty`. Only scalar
+/// types are checked; any payload other than `Unit` fits the rest.
+fn payload_fits(ty: &str, payload: &Value) -> bool {
+    match ty {
+        "Int" => matches!(payload, Value::Int(_)),
+        "Float" => matches!(payload, Value::Float(_)),
+        "String" => matches!(payload, Value::String(_)),
+        "Bool" => matches!(payload, Value::Bool(_)),
+        "Decimal" => matches!(payload, Value::Decimal(_)),
+        "BigInt" => matches!(payload, Value::BigInt(_)),
+        _ => !matches!(payload, Value::Unit),
+    }
 }
 
 /// Snapshot of one cycle for replay/inspection.
 #[derive(Debug, Clone)]
 pub struct CycleRecord {
     pub cycle: u64,
+    /// Clock reading when the message was delivered, in milliseconds.
+    pub timestamp_ms: u64,
     pub message: AppMessage,
     pub state_before: Value,
     pub state_after: Value,
@@ -44,6 +135,88 @@ pub struct CycleRecord {
     pub ui_tree: Option<Value>,
 }
 
+/// A message rejected for taking the app past one of its policy limits.
+/// Nothing was committed: `cycle` is the cycle the message would have
+/// produced.
+#[derive(Debug, Clone)]
+pub struct ViolationRecord {
+    pub cycle: u64,
+    /// Clock reading when the message was delivered, in milliseconds.
+    pub timestamp_ms: u64,
+    pub message: AppMessage,
+    pub violation: LimitViolation,
+}
+
+impl ViolationRecord {
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "cycle": self.cycle,
+            "timestamp_ms": self.timestamp_ms,
+            "tag": self.message.tag,
+            "violation": self.violation,
+        })
+    }
+}
+
+/// What a message would do, computed by [`AppRuntime::simulate`] without
+/// committing the cycle.
+#[derive(Debug, Clone)]
+pub struct Simulation {
+    /// The cycle number the message would produce.
+    pub cycle: u64,
+    /// The would-be next state.
+    pub state: Value,
+    /// The effects update() would return, already checked against policy.
+    pub effects: Vec<Effect>,
+    /// State fields the message would change.
+    pub state_diff: Vec<StateDiff>,
+    /// The would-be view(state).
+    pub ui_tree: Value,
+    /// How `ui_tree` differs from the current view.
+    pub ui_diff: Vec<UiChange>,
+}
+
+impl Simulation {
+    /// JSON body for the serve `/simulate` endpoint. Values use their
+    /// serde form, as in state snapshots.
+    pub fn to_json(&self) -> serde_json::Value {
+        let value = |v: &Value| serde_json::to_value(v).unwrap_or_default();
+        serde_json::json!({
+            "cycle": self.cycle,
+            "state": value(&self.state),
+            "effects": self.effects.iter().map(|e| serde_json::json!({
+                "kind": e.kind.as_str(),
+                "capability": e.kind.capability_name(),
+                "payload": value(&e.payload),
+                "callback": e.callback_tag,
+            })).collect::<Vec<_>>(),
+            "state_diff": self.state_diff.iter().map(|d| serde_json::json!({
+                "field": d.field_name,
+                "old": value(&d.old_value),
+                "new": value(&d.new_value),
+            })).collect::<Vec<_>>(),
+            "ui_tree": value(&self.ui_tree),
+            "ui_diff": self.ui_diff,
+        })
+    }
+}
+
+/// Default for [`AppRuntime::with_effect_loop_limit`].
+pub const DEFAULT_EFFECT_LOOP_LIMIT: u32 = 100;
+
+/// The callback chain [`AppRuntime::send_with_executor`] is in, for
+/// effect-loop detection. A message that is not an outstanding callback
+/// starts a new chain.
+#[derive(Debug, Default)]
+struct EffectChain {
+    /// Callback messages produced by executed effects, not yet delivered.
+    pending: Vec<String>,
+    /// Tags of the messages delivered in this chain, in order.
+    tags: Vec<String>,
+    /// Effects emitted while handling callbacks, by kind and callback tag.
+    counts: BTreeMap<(&'static str, String), u32>,
+}
+
 /// The application runtime — drives the init → update → effects → view cycle.
 pub struct AppRuntime {
     module: Module,
@@ -51,7 +224,13 @@ pub struct AppRuntime {
     policy: PolicySet,
     fn_map: HashMap<String, u32>,
     cycle_log: Vec<CycleRecord>,
+    violation_log: Vec<ViolationRecord>,
+    /// Timestamps of accepted messages inside the current rate window.
+    recent: VecDeque<u64>,
     max_cycles: u64,
+    clock: Box<dyn Clock>,
+    effect_loop_limit: u32,
+    chain: EffectChain,
 }
 
 impl AppRuntime {
@@ -75,11 +254,11 @@ impl AppRuntime {
         }
 
         // Run init() to get initial state (init may use capabilities)
-        let init_state = Self::call_function(&module, &fn_map, "init", vec![], false)?;
+        let init_state = Self::call_function(&module, &fn_map, "init", vec![], false, None)?;
 
         // Run policies() if it exists
         let policy = if fn_map.contains_key("policies") {
-            let policy_val = Self::call_function(&module, &fn_map, "policies", vec![], true)?;
+            let policy_val = Self::call_function(&module, &fn_map, "policies", vec![], true, None)?;
             PolicySet::from_value(&policy_val)
         } else {
             PolicySet::allow_all()
@@ -93,15 +272,52 @@ impl AppRuntime {
             policy,
             fn_map,
             cycle_log: Vec::new(),
+            violation_log: Vec::new(),
+            recent: VecDeque::new(),
             max_cycles: 100_000,
+            clock: Box::new(VirtualClock::default()),
+            effect_loop_limit: DEFAULT_EFFECT_LOOP_LIMIT,
+            chain: EffectChain::default(),
         })
     }
 
+    /// Replace the clock that stamps cycle records. The default is a
+    /// `VirtualClock` fixed at 0 until advanced.
+    pub fn with_clock(mut self, clock: Box<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// How many times, within one callback chain, a callback may emit the
+    /// same effect kind with the same callback tag before
+    /// [`send_with_executor`](Self::send_with_executor) aborts the chain
+    /// with [`FrameworkError::EffectLoop`]. `0` disables the check.
+    pub fn with_effect_loop_limit(mut self, limit: u32) -> Self {
+        self.effect_loop_limit = limit;
+        self
+    }
+
+    /// Read the runtime's clock.
+    pub fn now_ms(&mut self) -> Result<u64, FrameworkError> {
+        self.clock.now_ms()
+    }
+
     /// Get the current application state.
     pub fn state(&self) -> &Value {
         self.state_machine.current()
     }
 
+    /// The current state as the JSON view queries run against, with
+    /// record fields named (see [`value_to_json`]).
+    pub fn state_json(&self) -> serde_json::Value {
+        value_to_json(self.state_machine.current(), &self.module.types)
+    }
+
+    /// Evaluate `query` against the current state, bound to `state`.
+    pub fn query(&self, query: &Query) -> serde_json::Value {
+        query.eval(&serde_json::json!({ "state": self.state_json() }))
+    }
+
     /// Get the current cycle number.
     pub fn cycle(&self) -> u64 {
         self.state_machine.cycle()
@@ -112,6 +328,16 @@ impl AppRuntime {
         &self.cycle_log
     }
 
+    /// Messages rejected by the policy's numeric limits, in delivery order.
+    pub fn violation_log(&self) -> &[ViolationRecord] {
+        &self.violation_log
+    }
+
+    /// Get the compiled module (type definitions, functions).
+    pub fn module(&self) -> &Module {
+        &self.module
+    }
+
     /// Get the policy.
     pub fn policy(&self) -> &PolicySet {
         &self.policy
@@ -128,21 +354,127 @@ impl AppRuntime {
     pub fn send(
         &mut self,
         msg: AppMessage,
+    ) -> Result<(Value, Vec<Effect>, Option<Value>), FrameworkError> {
+        self.deliver(msg, false)
+    }
+
+    /// [`send`](Self::send), checking the effects for a feedback loop
+    /// before committing when `msg` continues a callback chain.
+    ///
+    /// A message that breaks one of the policy's numeric limits fails with
+    /// [`FrameworkError::LimitExceeded`] and is recorded in the
+    /// [violation log](Self::violation_log) instead of the cycle log.
+    fn deliver(
+        &mut self,
+        msg: AppMessage,
+        in_chain: bool,
     ) -> Result<(Value, Vec<Effect>, Option<Value>), FrameworkError> {
         if self.state_machine.cycle() >= self.max_cycles {
             return Err(FrameworkError::MaxCyclesExceeded(self.max_cycles));
         }
 
+        let timestamp_ms = self.clock.now_ms()?;
         let state_before = self.state_machine.current().clone();
+        let evaluated = self
+            .check_rate(timestamp_ms)
+            .and_then(|()| self.evaluate(&msg));
+        let (new_state, effects, ui_tree) = match evaluated {
+            Err(FrameworkError::LimitExceeded(violation)) => {
+                self.violation_log.push(ViolationRecord {
+                    cycle: self.state_machine.cycle() + 1,
+                    timestamp_ms,
+                    message: msg,
+                    violation: violation.clone(),
+                });
+                return Err(FrameworkError::LimitExceeded(violation));
+            }
+            result => result?,
+        };
+        if in_chain {
+            self.check_effect_loop(&effects)?;
+        }
+
+        // Transition state
+        self.state_machine.transition(new_state.clone());
+        if self.policy.max_messages_per_second > 0 {
+            self.recent.push_back(timestamp_ms);
+        }
+
+        let ui_value = Some(ui_tree.clone());
 
+        // Log the cycle
+        self.cycle_log.push(CycleRecord {
+            cycle: self.state_machine.cycle(),
+            timestamp_ms,
+            message: msg,
+            state_before,
+            state_after: new_state.clone(),
+            effects: effects.clone(),
+            ui_tree: Some(ui_tree),
+        });
+
+        Ok((new_state, effects, ui_value))
+    }
+
+    /// What `msg` would do, without committing the cycle: the next state,
+    /// effects and view, and how state and view would change. update()
+    /// and view() are pure and run in throwaway VMs, so the runtime is
+    /// untouched — no cycle, clock read or log entry. A message `send`
+    /// would reject (policy, malformed update result) fails the same way.
+    pub fn simulate(&self, msg: &AppMessage) -> Result<Simulation, FrameworkError> {
+        let current = self.state_machine.current();
+        let (state, effects, ui_tree) = self.evaluate(msg)?;
+        let current_ui = self.view()?;
+        Ok(Simulation {
+            cycle: self.state_machine.cycle() + 1,
+            state_diff: StateMachine::diff_values(current, &state),
+            ui_diff: ui_diff(&current_ui, &ui_tree),
+            state,
+            effects,
+            ui_tree,
+        })
+    }
+
+    /// Fail when accepting a message at `now_ms` would put more than
+    /// `max_messages_per_second` messages in the trailing 1000 ms window.
+    fn check_rate(&mut self, now_ms: u64) -> Result<(), FrameworkError> {
+        let max = self.policy.max_messages_per_second;
+        if max == 0 {
+            return Ok(());
+        }
+        while self
+            .recent
+            .front()
+            .is_some_and(|&t| now_ms.saturating_sub(t) >= 1000)
+        {
+            self.recent.pop_front();
+        }
+        let actual = self.recent.len() as u64 + 1;
+        if actual > max {
+            return Err(FrameworkError::LimitExceeded(LimitViolation {
+                limit: PolicyLimit::MessagesPerSecond,
+                max,
+                actual,
+            }));
+        }
+        Ok(())
+    }
+
+    /// Run update(state, msg), check its effects and the new state against
+    /// policy, and run view() on the result. Returns
+    /// `(new_state, effects, ui_tree)`.
+    fn evaluate(&self, msg: &AppMessage) -> Result<(Value, Vec<Effect>, Value), FrameworkError> {
         // Call update(state, msg) — PURE: no capabilities allowed
-        let msg_value = msg.to_value();
         let update_result = Self::call_function(
             &self.module,
             &self.fn_map,
             "update",
-            vec![state_before.clone(), msg_value],
+            vec![
+                self.state_machine.current().clone(),
+                msg.to_value_in(&self.module.types)?,
+            ],
             true,
+            Some(self.policy.max_steps),
         )?;
 
         // Parse the UpdateResult
@@ -152,11 +484,21 @@ impl AppRuntime {
             )
         })?;
 
-        // Validate effects against policy
+        // Validate effects and state size against policy
         self.policy.check_batch(&effects)?;
-
-        // Transition state
-        self.state_machine.transition(new_state.clone());
+        let max_bytes = self.policy.max_state_bytes;
+        if max_bytes > 0 {
+            let bytes = serde_json::to_vec(&new_state)
+                .map_err(|e| FrameworkError::State(format!("serialize state: {e}")))?
+                .len() as u64;
+            if bytes > max_bytes {
+                return Err(FrameworkError::LimitExceeded(LimitViolation {
+                    limit: PolicyLimit::StateBytes,
+                    max: max_bytes,
+                    actual: bytes,
+                }));
+            }
+        }
 
         // Call view(state) — PURE: no capabilities allowed
         let ui_tree = Self::call_function(
@@ -165,21 +507,9 @@ impl AppRuntime {
             "view",
             vec![new_state.clone()],
             true,
+            Some(self.policy.max_steps),
         )?;
-
-        let ui_value = Some(ui_tree.clone());
-
-        // Log the cycle
-        self.cycle_log.push(CycleRecord {
-            cycle: self.state_machine.cycle(),
-            message: msg,
-            state_before,
-            state_after: new_state.clone(),
-            effects: effects.clone(),
-            ui_tree: Some(ui_tree),
-        });
-
-        Ok((new_state, effects, ui_value))
+        Ok((new_state, effects, ui_tree))
     }
 
     /// Send a message and execute effects, returning callback messages.
@@ -187,20 +517,81 @@ impl AppRuntime {
     /// This extends `send()` by passing the returned effects through an
     /// `EffectExecutor`, producing callback messages for the next cycle.
     /// Returns: (new_state, callback_messages, ui_tree)
+    ///
+    /// Delivering one of the returned callbacks continues the chain; any
+    /// other message starts a new one. A chain in which callbacks keep
+    /// emitting the same effect past the
+    /// [effect loop limit](Self::with_effect_loop_limit) fails with
+    /// [`FrameworkError::EffectLoop`] before that cycle commits.
     pub fn send_with_executor(
         &mut self,
         msg: AppMessage,
         executor: &mut dyn crate::executor::EffectExecutor,
     ) -> Result<(Value, Vec<AppMessage>, Option<Value>), FrameworkError> {
-        let (state, effects, ui) = self.send(msg)?;
+        let outstanding = self.chain.pending.iter().position(|t| *t == msg.tag);
+        let in_chain = match outstanding {
+            Some(i) => {
+                self.chain.pending.remove(i);
+                true
+            }
+            None => {
+                self.chain = EffectChain::default();
+                false
+            }
+        };
+        self.chain.tags.push(msg.tag.clone());
+        let (state, effects, ui) = self.deliver(msg, in_chain)?;
         let callbacks = executor.execute(effects)?;
+        self.chain
+            .pending
+            .extend(callbacks.iter().map(|m| m.tag.clone()));
         Ok((state, callbacks, ui))
     }
 
+    /// Count the effects a callback emitted, failing once one kind and
+    /// callback tag pass the loop limit.
+    fn check_effect_loop(&mut self, effects: &[Effect]) -> Result<(), FrameworkError> {
+        if self.effect_loop_limit == 0 {
+            return Ok(());
+        }
+        for effect in effects {
+            if effect.kind == EffectKind::EmitUi {
+                continue;
+            }
+            let key = (effect.kind.as_str(), effect.callback_tag.clone());
+            let count = self.chain.counts.entry(key).or_default();
+            *count += 1;
+            if *count > self.effect_loop_limit {
+                // Name the loop: from the callback's last delivery round
+                // to the message that just asked for it again.
+                let tags = &self.chain.tags;
+                let start = tags
+                    .iter()
+                    .rposition(|t| *t == effect.callback_tag)
+                    .unwrap_or(tags.len().saturating_sub(1));
+                let mut cycle = tags[start..].to_vec();
+                cycle.push(effect.callback_tag.clone());
+                return Err(FrameworkError::EffectLoop {
+                    effect: effect.kind.as_str().to_string(),
+                    tags: cycle,
+                    count: *count,
+                });
+            }
+        }
+        Ok(())
+    }
+
     /// Call view() on the current state (without updating). PURE.
     pub fn view(&self) -> Result<Value, FrameworkError> {
         let state = self.state_machine.current().clone();
-        Self::call_function(&self.module, &self.fn_map, "view", vec![state], true)
+        Self::call_function(
+            &self.module,
+            &self.fn_map,
+            "view",
+            vec![state],
+            true,
+            Some(self.policy.max_steps),
+        )
     }
 
     /// Get the state snapshot as JSON.
@@ -220,12 +611,15 @@ impl AppRuntime {
 
     /// Helper: call a named function in the module with given args.
     /// `pure` = true uses deny-all capability policy (for update/view).
+    /// `max_steps` is the policy's step limit (0 = unlimited); `None` keeps
+    /// the VM default, for functions that run before the policy is known.
     fn call_function(
         module: &Module,
         fn_map: &HashMap<String, u32>,
         name: &str,
         args: Vec<Value>,
         pure: bool,
+        max_steps: Option<u64>,
     ) -> Result<Value, FrameworkError> {
         let &func_idx = fn_map
             .get(name)
@@ -242,13 +636,7 @@ impl AppRuntime {
             let mut module_copy = module.clone();
             module_copy.entry = func_idx;
             let mut vm = Vm::new(module_copy, gateway);
-            vm.run().map_err(|e| {
-                if pure {
-                    Self::wrap_purity_error(name, e)
-                } else {
-                    FrameworkError::Runtime(e)
-                }
-            })
+            Self::run_limited(&mut vm, name, pure, max_steps)
         } else {
             let mut wrapper = module.clone();
             let mut wrapper_code = Vec::new();
@@ -275,16 +663,34 @@ impl AppRuntime {
 
             let gateway = CapabilityGateway::new(policy);
             let mut vm = Vm::new(wrapper, gateway);
-            vm.run().map_err(|e| {
-                if pure {
-                    Self::wrap_purity_error(name, e)
-                } else {
-                    FrameworkError::Runtime(e)
-                }
-            })
+            Self::run_limited(&mut vm, name, pure, max_steps)
         }
     }
 
+    /// Run `vm` under the policy's step limit, reporting a breach of it as
+    /// [`FrameworkError::LimitExceeded`].
+    fn run_limited(
+        vm: &mut Vm,
+        name: &str,
+        pure: bool,
+        max_steps: Option<u64>,
+    ) -> Result<Value, FrameworkError> {
+        if let Some(max) = max_steps {
+            vm.set_max_steps(if max == 0 { u64::MAX } else { max });
+        }
+        vm.run().map_err(|e| match (e, max_steps) {
+            (boruna_vm::VmError::ExecutionLimitExceeded(_), Some(max)) if max > 0 => {
+                FrameworkError::LimitExceeded(LimitViolation {
+                    limit: PolicyLimit::Steps,
+                    max,
+                    actual: vm.step_count(),
+                })
+            }
+            (e, _) if pure => Self::wrap_purity_error(name, e),
+            (e, _) => FrameworkError::Runtime(e),
+        })
+    }
+
     /// Convert a VM capability-denied error into a PurityViolation.
     fn wrap_purity_error(name: &str, err: boruna_vm::VmError) -> FrameworkError {
         match &err {
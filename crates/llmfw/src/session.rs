@@ -0,0 +1,204 @@
+use std::collections::BTreeMap;
+
+use boruna_bytecode::{Module, Value};
+use serde::Serialize;
+
+use crate::clock::VirtualClock;
+use crate::effect::Effect;
+use crate::error::FrameworkError;
+use crate::runtime::{AppMessage, AppRuntime, Simulation};
+
+/// Limits for a [`SessionManager`].
+#[derive(Debug, Clone)]
+pub struct SessionConfig {
+    /// Maximum number of live sessions. Creating a session beyond this
+    /// limit first evicts idle sessions, then fails with
+    /// `FrameworkError::SessionLimit`.
+    pub max_sessions: usize,
+    /// A session untouched for this many milliseconds is evicted by
+    /// `evict_idle`. `0` disables idle eviction.
+    pub idle_timeout_ms: u64,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        SessionConfig {
+            max_sessions: 64,
+            idle_timeout_ms: 30 * 60 * 1000,
+        }
+    }
+}
+
+/// One isolated visitor session: its own `AppRuntime` plus bookkeeping.
+struct Session {
+    runtime: AppRuntime,
+    /// The runtime's clock, moved to each request's `now_ms` so cycle
+    /// timestamps and the policy's message rate follow request time.
+    clock: VirtualClock,
+    created_ms: u64,
+    last_active_ms: u64,
+}
+
+/// Per-session summary, as exposed by the serve `/sessions` admin endpoint.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct SessionInfo {
+    pub id: String,
+    pub cycle: u64,
+    /// SHA-256 hex of the JSON-serialized current state.
+    pub state_hash: String,
+    pub created_ms: u64,
+    pub last_active_ms: u64,
+}
+
+/// Pool of isolated `AppRuntime` instances keyed by session id.
+///
+/// Every session starts from a fresh `init()` of the same compiled module,
+/// so messages from one visitor never touch another visitor's state. The
+/// manager takes the current time as an explicit `now_ms` argument instead
+/// of reading a clock, which keeps eviction deterministic under test.
+pub struct SessionManager {
+    module: Module,
+    config: SessionConfig,
+    sessions: BTreeMap<String, Session>,
+}
+
+impl SessionManager {
+    pub fn new(module: Module, config: SessionConfig) -> Self {
+        SessionManager {
+            module,
+            config,
+            sessions: BTreeMap::new(),
+        }
+    }
+
+    pub fn config(&self) -> &SessionConfig {
+        &self.config
+    }
+
+    /// Number of live sessions.
+    pub fn len(&self) -> usize {
+        self.sessions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sessions.is_empty()
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.sessions.contains_key(id)
+    }
+
+    /// Get the runtime for `id`, creating (and running `init()` for) a new
+    /// session if none exists. Marks the session active at `now_ms`.
+    pub fn get_or_create(
+        &mut self,
+        id: &str,
+        now_ms: u64,
+    ) -> Result<&mut AppRuntime, FrameworkError> {
+        if !self.sessions.contains_key(id) {
+            if self.sessions.len() >= self.config.max_sessions {
+                self.evict_idle(now_ms);
+            }
+            if self.sessions.len() >= self.config.max_sessions {
+                return Err(FrameworkError::SessionLimit(self.config.max_sessions));
+            }
+            let clock = VirtualClock::new(now_ms);
+            let runtime = AppRuntime::new(self.module.clone())?.with_clock(Box::new(clock.clone()));
+            self.sessions.insert(
+                id.to_string(),
+                Session {
+                    runtime,
+                    clock,
+                    created_ms: now_ms,
+                    last_active_ms: now_ms,
+                },
+            );
+        }
+        let session = self.sessions.get_mut(id).expect("session inserted above");
+        session.last_active_ms = now_ms;
+        // A request stamped earlier than the last one keeps the later time.
+        let _ = session.clock.set(now_ms);
+        Ok(&mut session.runtime)
+    }
+
+    /// Get an existing session's runtime without touching its activity time.
+    pub fn get(&self, id: &str) -> Option<&AppRuntime> {
+        self.sessions.get(id).map(|s| &s.runtime)
+    }
+
+    /// Deliver a message to one session, creating it on first use.
+    pub fn send(
+        &mut self,
+        id: &str,
+        msg: AppMessage,
+        now_ms: u64,
+    ) -> Result<(Value, Vec<Effect>, Option<Value>), FrameworkError> {
+        self.get_or_create(id, now_ms)?.send(msg)
+    }
+
+    /// What `msg` would do to one session, without committing it; backs
+    /// the serve `/simulate` endpoint (see [`Simulation::to_json`]).
+    /// Creates the session on first use, like [`send`](Self::send).
+    pub fn simulate(
+        &mut self,
+        id: &str,
+        msg: &AppMessage,
+        now_ms: u64,
+    ) -> Result<Simulation, FrameworkError> {
+        self.get_or_create(id, now_ms)?.simulate(msg)
+    }
+
+    /// Drop a session. Returns whether it existed.
+    pub fn remove(&mut self, id: &str) -> bool {
+        self.sessions.remove(id).is_some()
+    }
+
+    /// Evict every session idle for at least `idle_timeout_ms`.
+    /// Returns the evicted ids in sorted order.
+    pub fn evict_idle(&mut self, now_ms: u64) -> Vec<String> {
+        if self.config.idle_timeout_ms == 0 {
+            return Vec::new();
+        }
+        let timeout = self.config.idle_timeout_ms;
+        let expired: Vec<String> = self
+            .sessions
+            .iter()
+            .filter(|(_, s)| now_ms.saturating_sub(s.last_active_ms) >= timeout)
+            .map(|(id, _)| id.clone())
+            .collect();
+        for id in &expired {
+            self.sessions.remove(id);
+        }
+        expired
+    }
+
+    /// Summaries of all live sessions, sorted by id.
+    pub fn sessions(&self) -> Vec<SessionInfo> {
+        self.sessions
+            .iter()
+            .map(|(id, s)| SessionInfo {
+                id: id.clone(),
+                cycle: s.runtime.cycle(),
+                state_hash: state_hash(s.runtime.state()),
+                created_ms: s.created_ms,
+                last_active_ms: s.last_active_ms,
+            })
+            .collect()
+    }
+
+    /// JSON body for the `/sessions` admin endpoint.
+    pub fn sessions_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "max_sessions": self.config.max_sessions,
+            "idle_timeout_ms": self.config.idle_timeout_ms,
+            "count": self.sessions.len(),
+            "sessions": self.sessions(),
+        })
+    }
+}
+
+/// SHA-256 hex digest of a state value's JSON serialization.
+pub fn state_hash(state: &Value) -> String {
+    let json = serde_json::to_string(state).unwrap_or_default();
+    boruna_hash::sha256_hex(json)
+}
@@ -1,23 +1,36 @@
 use boruna_bytecode::Value;
 
+use crate::clock::VirtualClock;
 use crate::effect::Effect;
 use crate::error::FrameworkError;
 use crate::executor::EffectExecutor;
-use crate::runtime::{AppMessage, AppRuntime, CycleRecord};
+use crate::runtime::{AppMessage, AppRuntime, CycleRecord, Simulation};
 
 /// Test harness for framework applications.
 ///
 /// Provides utilities for testing apps without a host UI.
 pub struct TestHarness {
     runtime: AppRuntime,
+    clock: VirtualClock,
 }
 
 impl TestHarness {
     /// Create a test harness from source code.
     pub fn from_source(source: &str) -> Result<Self, FrameworkError> {
         let module = boruna_compiler::compile("test", source)?;
-        let runtime = AppRuntime::new(module)?;
-        Ok(TestHarness { runtime })
+        let clock = VirtualClock::default();
+        let runtime = AppRuntime::new(module)?.with_clock(Box::new(clock.clone()));
+        Ok(TestHarness { runtime, clock })
+    }
+
+    /// The harness's virtual clock. Starts at 0 and only moves when advanced.
+    pub fn clock(&self) -> &VirtualClock {
+        &self.clock
+    }
+
+    /// Move the virtual clock forward by `ms`.
+    pub fn advance_clock(&mut self, ms: u64) {
+        self.clock.advance(ms);
     }
 
     /// Get the current state.
@@ -49,6 +62,13 @@ impl TestHarness {
         Ok((state, callbacks))
     }
 
+    /// What `msg` would do — next state, effects, view and their diffs —
+    /// without sending it. Unlike [`simulate`](Self::simulate), the
+    /// harness's state and cycle are unchanged.
+    pub fn what_if(&self, msg: &AppMessage) -> Result<Simulation, FrameworkError> {
+        self.runtime.simulate(msg)
+    }
+
     /// Simulate a sequence of messages. Returns the final state.
     pub fn simulate(&mut self, messages: Vec<AppMessage>) -> Result<Value, FrameworkError> {
         let mut state = self.runtime.state().clone();
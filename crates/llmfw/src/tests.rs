@@ -2,8 +2,9 @@
 #[allow(clippy::module_inception)]
 mod tests {
     use crate::effect::EffectKind;
-    use crate::policy::PolicySet;
+    use crate::policy::{PolicyLimit, PolicySet};
     use crate::runtime::{AppMessage, AppRuntime};
+    use crate::session::{SessionConfig, SessionManager};
     use crate::state::StateMachine;
     use crate::testing::TestHarness;
     use crate::validate::AppValidator;
@@ -203,6 +204,97 @@ fn policies() -> PolicySet {
         assert!(ui.is_some());
     }
 
+    /// An `enum Msg` app: tags are variant names, checked on delivery.
+    const ENUM_MSG_APP: &str = r#"
+enum Msg { Increment, SetLabel(String) }
+type State { count: Int, label: String }
+type Effect { kind: String, payload: String, callback_tag: String }
+type UpdateResult { state: State, effects: List<Effect> }
+type UINode { tag: String, text: String }
+
+fn init() -> State {
+    State { count: 0, label: "counter" }
+}
+
+fn update(state: State, msg: Msg) -> UpdateResult {
+    match msg {
+        Increment => UpdateResult { state: State { ..state, count: state.count + 1 }, effects: [] }
+        SetLabel(label) => UpdateResult { state: State { ..state, label: label }, effects: [] }
+    }
+}
+
+fn view(state: State) -> UINode {
+    UINode { tag: "text", text: state.label }
+}
+"#;
+
+    #[test]
+    fn test_runtime_enum_msg_tags_are_checked_variants() {
+        use boruna_errors::BorunaError;
+        let module = boruna_compiler::compile("test", ENUM_MSG_APP).unwrap();
+        let mut runtime = AppRuntime::new(module).unwrap();
+
+        // A unit variant drops whatever payload the sender attached.
+        runtime
+            .send(AppMessage::new("Increment", Value::Int(0)))
+            .unwrap();
+        let (state, _, _) = runtime
+            .send(AppMessage::new("SetLabel", Value::String("hi".into())))
+            .unwrap();
+        assert_eq!(
+            state,
+            Value::Record {
+                type_id: 1,
+                fields: vec![Value::Int(1), Value::String("hi".into())],
+            }
+        );
+
+        let unknown = runtime
+            .send(AppMessage::new("increment", Value::Unit))
+            .unwrap_err();
+        assert_eq!(unknown.error_code().code, "FW013");
+        assert!(
+            unknown.to_string().contains("Increment, SetLabel"),
+            "{unknown}"
+        );
+        let wrong_payload = runtime
+            .send(AppMessage::new("SetLabel", Value::Int(5)))
+            .unwrap_err();
+        assert!(
+            wrong_payload
+                .to_string()
+                .contains("carries a String payload"),
+            "{wrong_payload}"
+        );
+        // Rejected messages commit nothing.
+        assert_eq!(runtime.cycle(), 2);
+    }
+
+    #[test]
+    fn test_app_message_json_round_trips_enum_encoding() {
+        let module = boruna_compiler::compile("test", ENUM_MSG_APP).unwrap();
+        let types = &module.types;
+        for msg in [
+            AppMessage::new("Increment", Value::Unit),
+            AppMessage::new("SetLabel", Value::String("hi".into())),
+        ] {
+            let value = msg.to_value_in(types).unwrap();
+            let json = crate::query::value_to_json(&value, types);
+            let back = AppMessage::from_json(&json).unwrap();
+            assert_eq!(back.to_value_in(types).unwrap(), value, "{json}");
+        }
+        assert_eq!(
+            crate::query::value_to_json(
+                &AppMessage::new("SetLabel", Value::String("hi".into()))
+                    .to_value_in(types)
+                    .unwrap(),
+                types
+            ),
+            serde_json::json!({ "SetLabel": "hi" })
+        );
+        assert!(AppMessage::from_json(&serde_json::json!({ "a": 1, "b": 2 })).is_err());
+    }
+
     #[test]
     fn test_runtime_multiple_messages() {
         let module = boruna_compiler::compile("test", COUNTER_APP).unwrap();
@@ -348,6 +440,7 @@ fn policies() -> PolicySet {
             capabilities: vec!["net.fetch".into()],
             max_effects_per_cycle: 1,
             max_steps: 0,
+            ..PolicySet::default()
         };
         let effects = vec![
             crate::effect::Effect {
@@ -374,6 +467,7 @@ fn policies() -> PolicySet {
             capabilities: vec!["net.fetch".into()],
             max_effects_per_cycle: 0,
             max_steps: 0,
+            ..PolicySet::default()
         };
         let allowed = crate::effect::Effect {
             kind: EffectKind::HttpRequest,
@@ -403,6 +497,7 @@ fn policies() -> PolicySet {
             capabilities: Vec::new(),
             max_effects_per_cycle: 0,
             max_steps: 0,
+            ..PolicySet::default()
         };
         let effect = crate::effect::Effect {
             kind: EffectKind::FsWrite,
@@ -421,6 +516,7 @@ fn policies() -> PolicySet {
             capabilities: vec!["net.fetch".into()],
             max_effects_per_cycle: 2,
             max_steps: 0,
+            ..PolicySet::default()
         };
         let make_effect = || crate::effect::Effect {
             kind: EffectKind::HttpRequest,
@@ -443,6 +539,7 @@ fn policies() -> PolicySet {
             capabilities: vec!["net.fetch".into(), "time.now".into()],
             max_effects_per_cycle: 5,
             max_steps: 100000,
+            ..PolicySet::default()
         };
         let json = policy.to_json();
         let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
@@ -543,6 +640,263 @@ fn policies() -> PolicySet {
         }
     }
 
+    #[test]
+    fn test_harness_what_if_does_not_commit() {
+        let harness = TestHarness::from_source(EFFECT_APP).unwrap();
+        let sim = harness
+            .what_if(&AppMessage::new("set", Value::String("hello".into())))
+            .unwrap();
+        assert_eq!(sim.cycle, 1);
+        assert_eq!(sim.state_diff.len(), 1);
+        assert_eq!(sim.state_diff[0].new_value, Value::String("hello".into()));
+        assert_eq!(sim.ui_diff.len(), 1);
+        assert_eq!(sim.ui_diff[0].path, "/1");
+        assert_eq!(sim.ui_diff[0].new, Some(Value::String("hello".into())));
+        assert_eq!(harness.cycle(), 0, "nothing committed");
+
+        let sim = harness
+            .what_if(&AppMessage::new("fetch", Value::String(String::new())))
+            .unwrap();
+        assert_eq!(sim.effects[0].kind, EffectKind::HttpRequest);
+        assert!(sim.state_diff.is_empty() && sim.ui_diff.is_empty());
+    }
+
+    #[test]
+    fn test_ui_diff_reports_each_removed_item() {
+        let list = |n: i64| Value::List((0..n).map(Value::Int).collect());
+        let node = |items| Value::Record {
+            type_id: 1,
+            fields: vec![Value::String("list".into()), items],
+        };
+        let changes = crate::ui::ui_diff(&node(list(5)), &node(list(2)));
+        let paths: Vec<&str> = changes.iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(paths, ["/1/2", "/1/3", "/1/4"]);
+        assert!(changes.iter().all(|c| c.new.is_none()));
+    }
+
+    #[test]
+    fn test_session_simulate_json() {
+        let module = boruna_compiler::compile("counter", COUNTER_APP).unwrap();
+        let mut mgr = SessionManager::new(module, SessionConfig::default());
+        let sim = mgr
+            .simulate("a", &AppMessage::new("increment", Value::Int(0)), 0)
+            .unwrap();
+        let json = sim.to_json();
+        assert_eq!(json["cycle"], 1);
+        assert_eq!(json["state_diff"][0]["new"], serde_json::json!({"Int": 1}));
+        assert_eq!(mgr.get("a").unwrap().cycle(), 0);
+    }
+
+    /// Every message, callbacks included, fetches again.
+    const LOOP_APP: &str = r#"
+type State { n: Int }
+type Msg { tag: String, payload: String }
+type Effect { kind: String, payload: String, callback_tag: String }
+type UpdateResult { state: State, effects: List<Effect> }
+type UINode { tag: String, text: String }
+
+fn init() -> State {
+    State { n: 0 }
+}
+
+fn update(state: State, msg: Msg) -> UpdateResult {
+    UpdateResult {
+        state: State { n: state.n + 1 },
+        effects: [Effect { kind: "http_request", payload: "https://example.com", callback_tag: "fetched" }],
+    }
+}
+
+fn view(state: State) -> UINode {
+    UINode { tag: "text", text: "n" }
+}
+"#;
+
+    #[test]
+    fn test_effect_loop_aborts_the_callback_chain() {
+        let module = boruna_compiler::compile("loop", LOOP_APP).unwrap();
+        let mut rt = AppRuntime::new(module).unwrap().with_effect_loop_limit(3);
+        let mut executor = crate::executor::MockEffectExecutor::new();
+
+        let mut msg = AppMessage::new("refresh", Value::String(String::new()));
+        let err = loop {
+            match rt.send_with_executor(msg, &mut executor) {
+                Ok((_, callbacks, _)) => msg = callbacks[0].clone(),
+                Err(e) => break e,
+            }
+        };
+        match &err {
+            crate::FrameworkError::EffectLoop {
+                effect,
+                tags,
+                count,
+            } => {
+                assert_eq!(effect, "http_request");
+                assert_eq!(tags, &["fetched", "fetched"]);
+                assert_eq!(*count, 4);
+            }
+            other => panic!("expected EffectLoop, got {other}"),
+        }
+        assert_eq!(
+            err.to_string(),
+            "effect loop: http_request emitted 4 times via fetched -> fetched"
+        );
+        assert_eq!(rt.cycle(), 4, "the looping cycle is not committed");
+
+        // An outside message starts a new chain with a fresh count.
+        let (_, callbacks, _) = rt
+            .send_with_executor(
+                AppMessage::new("refresh", Value::String(String::new())),
+                &mut executor,
+            )
+            .unwrap();
+        assert_eq!(callbacks[0].tag, "fetched");
+    }
+
+    #[test]
+    fn test_effect_loop_limit_zero_disables_the_check() {
+        let module = boruna_compiler::compile("loop", LOOP_APP).unwrap();
+        let mut rt = AppRuntime::new(module).unwrap().with_effect_loop_limit(0);
+        let mut executor = crate::executor::MockEffectExecutor::new();
+        let mut msg = AppMessage::new("refresh", Value::String(String::new()));
+        for _ in 0..20 {
+            let (_, callbacks, _) = rt.send_with_executor(msg, &mut executor).unwrap();
+            msg = callbacks[0].clone();
+        }
+        assert_eq!(rt.cycle(), 20);
+    }
+
+    /// App whose state grows with each message, under numeric limits.
+    const LIMITS_APP: &str = r#"
+type State { text: String }
+type Msg { tag: String, payload: String }
+type Effect { kind: String, payload: String, callback_tag: String }
+type UpdateResult { state: State, effects: List<Effect> }
+type UINode { tag: String, text: String }
+type PolicySet { capabilities: List<String>, max_effects: Int, max_steps: Int, max_state_bytes: Int, max_messages_per_second: Int }
+
+fn init() -> State {
+    State { text: "" }
+}
+
+fn update(state: State, msg: Msg) -> UpdateResult {
+    UpdateResult {
+        state: State { text: state.text ++ msg.payload },
+        effects: [],
+    }
+}
+
+fn view(state: State) -> UINode {
+    UINode { tag: "text", text: state.text }
+}
+
+fn policies() -> PolicySet {
+    PolicySet { capabilities: [], max_effects: 0, max_steps: 1000000, max_state_bytes: 200, max_messages_per_second: 3 }
+}
+"#;
+
+    fn text(s: &str) -> AppMessage {
+        AppMessage::new("append", Value::String(s.into()))
+    }
+
+    #[test]
+    fn test_policy_parses_optional_limits() {
+        let module = boruna_compiler::compile("limits", LIMITS_APP).unwrap();
+        let rt = AppRuntime::new(module).unwrap();
+        assert_eq!(rt.policy().max_state_bytes, 200);
+        assert_eq!(rt.policy().max_messages_per_second, 3);
+
+        // Older three-field policies leave the new limits off.
+        let module = boruna_compiler::compile("policy", POLICY_APP).unwrap();
+        let rt = AppRuntime::new(module).unwrap();
+        assert_eq!(rt.policy().max_state_bytes, 0);
+        assert_eq!(rt.policy().max_messages_per_second, 0);
+    }
+
+    #[test]
+    fn test_state_size_limit_rejects_the_cycle() {
+        use boruna_errors::BorunaError;
+        let module = boruna_compiler::compile("limits", LIMITS_APP).unwrap();
+        let mut rt = AppRuntime::new(module).unwrap();
+        rt.send(text("ok")).unwrap();
+
+        let err = rt.send(text(&"x".repeat(300))).unwrap_err();
+        let crate::FrameworkError::LimitExceeded(violation) = &err else {
+            panic!("expected LimitExceeded, got {err}");
+        };
+        assert_eq!(violation.limit, PolicyLimit::StateBytes);
+        assert_eq!(violation.max, 200);
+        assert!(violation.actual > 300);
+        assert_eq!(err.error_code().code, "FW012");
+        assert_eq!(err.error_data()["limit"], "state_bytes");
+
+        assert_eq!(rt.cycle(), 1, "the violating cycle is not committed");
+        assert_eq!(rt.cycle_log().len(), 1);
+        let record = &rt.violation_log()[0];
+        assert_eq!(record.cycle, 2);
+        assert_eq!(record.message.tag, "append");
+        assert_eq!(record.to_json()["violation"]["limit"], "state_bytes");
+
+        // simulate() rejects the same message without logging it.
+        assert!(rt.simulate(&text(&"x".repeat(300))).is_err());
+        assert_eq!(rt.violation_log().len(), 1);
+    }
+
+    #[test]
+    fn test_message_rate_limit_uses_the_runtime_clock() {
+        let clock = crate::clock::VirtualClock::new(0);
+        let module = boruna_compiler::compile("limits", LIMITS_APP).unwrap();
+        let mut rt = AppRuntime::new(module)
+            .unwrap()
+            .with_clock(Box::new(clock.clone()));
+        for _ in 0..3 {
+            rt.send(text("a")).unwrap();
+        }
+        match rt.send(text("a")) {
+            Err(crate::FrameworkError::LimitExceeded(v)) => {
+                assert_eq!(v.limit, PolicyLimit::MessagesPerSecond);
+                assert_eq!((v.max, v.actual), (3, 4));
+            }
+            other => panic!("expected LimitExceeded, got {other:?}"),
+        }
+        clock.advance(999);
+        assert!(rt.send(text("a")).is_err());
+        clock.advance(1);
+        rt.send(text("a")).unwrap();
+        assert_eq!(rt.cycle(), 4);
+        assert_eq!(rt.violation_log().len(), 2);
+    }
+
+    #[test]
+    fn test_step_limit_applies_to_update() {
+        let source = LIMITS_APP.replace("max_steps: 1000000", "max_steps: 3");
+        let module = boruna_compiler::compile("limits", &source).unwrap();
+        let mut rt = AppRuntime::new(module).unwrap();
+        match rt.send(text("a")) {
+            Err(crate::FrameworkError::LimitExceeded(v)) => {
+                assert_eq!(v.limit, PolicyLimit::Steps);
+                assert_eq!(v.max, 3);
+                assert!(v.actual > 3);
+            }
+            other => panic!("expected LimitExceeded, got {other:?}"),
+        }
+        assert_eq!(rt.cycle(), 0);
+    }
+
+    #[test]
+    fn test_session_rate_limit_follows_request_time() {
+        let module = boruna_compiler::compile("limits", LIMITS_APP).unwrap();
+        let mut mgr = SessionManager::new(module, SessionConfig::default());
+        for now in [0, 10, 20] {
+            mgr.send("a", text("x"), now).unwrap();
+        }
+        assert!(mgr.send("a", text("x"), 30).is_err());
+        // Another visitor has its own window.
+        mgr.send("b", text("x"), 30).unwrap();
+        mgr.send("a", text("x"), 1000).unwrap();
+        let rt = mgr.get("a").unwrap();
+        assert_eq!(rt.cycle_log().last().unwrap().timestamp_ms, 1000);
+    }
+
     #[test]
     fn test_harness_assert_state_field() {
         let mut harness = TestHarness::from_source(COUNTER_APP).unwrap();
@@ -1315,6 +1669,7 @@ fn view(state: State) -> UINode {
         let record = &harness.cycle_log()[0];
         // All public fields must be accessible
         let _cycle: u64 = record.cycle;
+        let _timestamp: u64 = record.timestamp_ms;
         let _msg: &AppMessage = &record.message;
         let _before: &Value = &record.state_before;
         let _after: &Value = &record.state_after;
@@ -2072,6 +2427,57 @@ fn view(state: State) -> UINode {
         assert_eq!(messages[0].payload, Value::Bool(true));
     }
 
+    #[test]
+    fn test_host_executor_kv_effects_use_the_store() {
+        use boruna_vm::capability_gateway::{KvPolicy, MockHandler, Policy};
+        use boruna_vm::kv_store::{KvHandler, KvStore};
+        let store = KvStore::in_memory().shared();
+        let handler = KvHandler::new(store.clone(), KvPolicy::default(), Box::new(MockHandler));
+        let mut executor = HostEffectExecutor::with_handler(Policy::allow_all(), Box::new(handler));
+        let effects = vec![
+            Effect {
+                kind: EffectKind::KvSet,
+                payload: Value::List(vec![Value::String("cart/1".into()), Value::Int(2)]),
+                callback_tag: "saved".into(),
+            },
+            Effect {
+                kind: EffectKind::KvGet,
+                payload: Value::String("cart/1".into()),
+                callback_tag: "loaded".into(),
+            },
+        ];
+        let messages = executor.execute(effects).unwrap();
+        assert_eq!(messages[0].payload, Value::Unit);
+        assert_eq!(messages[1].payload, Value::Some(Box::new(Value::Int(2))));
+        assert_eq!(store.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_host_executor_notify_effect_renders_and_records() {
+        use boruna_vm::capability_gateway::{MockHandler, NotifyPolicy, Policy};
+        use boruna_vm::notify::{DryRunNotifySender, NotifyHandler};
+        let handler = NotifyHandler::new(
+            NotifyPolicy::default(),
+            Box::new(DryRunNotifySender),
+            Box::new(MockHandler),
+        );
+        let mut executor = HostEffectExecutor::with_handler(Policy::allow_all(), Box::new(handler));
+        let effects = vec![Effect {
+            kind: EffectKind::NotifySend,
+            payload: Value::List(vec![
+                Value::String("mailto:ops@example.com".into()),
+                Value::String("disk full".into()),
+            ]),
+            callback_tag: "notified".into(),
+        }];
+        let messages = executor.execute(effects).unwrap();
+        let Value::Map(receipt) = &messages[0].payload else {
+            panic!("expected a Map receipt, got {:?}", messages[0].payload);
+        };
+        assert_eq!(receipt["body"], Value::String("disk full".into()));
+        assert_eq!(receipt["status"], Value::Int(0));
+    }
+
     #[test]
     fn test_executor_emit_ui_no_callback() {
         let mut executor = MockEffectExecutor::new();
@@ -2403,4 +2809,205 @@ fn view(state: State) -> UINode {
         let identical = harness.replay_verify(EFFECT_APP, all_messages).unwrap();
         assert!(identical, "replay must produce identical states");
     }
+
+    // === Session Isolation Tests ===
+
+    fn session_manager(max_sessions: usize, idle_timeout_ms: u64) -> SessionManager {
+        let module = boruna_compiler::compile("counter", COUNTER_APP).unwrap();
+        SessionManager::new(
+            module,
+            SessionConfig {
+                max_sessions,
+                idle_timeout_ms,
+            },
+        )
+    }
+
+    #[test]
+    fn test_sessions_have_isolated_state() {
+        let mut mgr = session_manager(8, 0);
+        mgr.send("alice", AppMessage::new("increment", Value::Int(0)), 0)
+            .unwrap();
+        mgr.send("alice", AppMessage::new("increment", Value::Int(0)), 1)
+            .unwrap();
+        mgr.send("bob", AppMessage::new("decrement", Value::Int(0)), 2)
+            .unwrap();
+
+        assert_eq!(mgr.get("alice").unwrap().cycle(), 2);
+        assert_eq!(mgr.get("bob").unwrap().cycle(), 1);
+        assert_ne!(
+            mgr.get("alice").unwrap().state(),
+            mgr.get("bob").unwrap().state()
+        );
+    }
+
+    #[test]
+    fn test_session_limit_rejects_new_sessions() {
+        let mut mgr = session_manager(2, 0);
+        mgr.get_or_create("a", 0).unwrap();
+        mgr.get_or_create("b", 0).unwrap();
+        // Existing sessions stay reachable at the limit.
+        assert!(mgr.get_or_create("a", 1).is_ok());
+        let err = mgr.get_or_create("c", 1).err().unwrap();
+        assert!(matches!(err, crate::FrameworkError::SessionLimit(2)));
+    }
+
+    #[test]
+    fn test_session_idle_eviction() {
+        let mut mgr = session_manager(2, 100);
+        mgr.get_or_create("a", 0).unwrap();
+        mgr.get_or_create("b", 50).unwrap();
+        assert_eq!(mgr.evict_idle(99), Vec::<String>::new());
+        assert_eq!(mgr.evict_idle(100), vec!["a".to_string()]);
+        assert!(!mgr.contains("a"));
+        assert!(mgr.contains("b"));
+
+        // A full pool makes room by evicting idle sessions first.
+        mgr.get_or_create("c", 120).unwrap();
+        mgr.get_or_create("d", 150).unwrap();
+        assert_eq!(mgr.len(), 2);
+        assert!(!mgr.contains("b"));
+    }
+
+    #[test]
+    fn test_sessions_listing_reports_cycles_and_hashes() {
+        let mut mgr = session_manager(8, 0);
+        mgr.get_or_create("x", 0).unwrap();
+        mgr.get_or_create("y", 0).unwrap();
+        let before = mgr.sessions();
+        assert_eq!(before[0].state_hash, before[1].state_hash);
+
+        mgr.send("y", AppMessage::new("increment", Value::Int(0)), 5)
+            .unwrap();
+        let after = mgr.sessions();
+        assert_eq!(after[0].id, "x");
+        assert_eq!(after[1].cycle, 1);
+        assert_eq!(after[1].last_active_ms, 5);
+        assert_ne!(after[0].state_hash, after[1].state_hash);
+
+        let json = mgr.sessions_json();
+        assert_eq!(json["count"], 2);
+        assert_eq!(json["sessions"][1]["id"], "y");
+    }
+
+    // === Clock Tests ===
+
+    use crate::clock::{Clock, GatewayClock, VirtualClock};
+
+    #[test]
+    fn test_cycle_records_carry_virtual_timestamps() {
+        let run = || {
+            let mut harness = TestHarness::from_source(COUNTER_APP).unwrap();
+            harness
+                .send(AppMessage::new("increment", Value::Int(0)))
+                .unwrap();
+            harness.advance_clock(250);
+            harness
+                .send(AppMessage::new("increment", Value::Int(0)))
+                .unwrap();
+            harness.advance_clock(1000);
+            harness
+                .send(AppMessage::new("decrement", Value::Int(0)))
+                .unwrap();
+            harness
+                .cycle_log()
+                .iter()
+                .map(|r| r.timestamp_ms)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(run(), vec![0, 250, 1250]);
+        assert_eq!(run(), run());
+    }
+
+    #[test]
+    fn test_virtual_clock_is_shared_and_monotonic() {
+        let clock = VirtualClock::new(10);
+        let mut handle = clock.clone();
+        clock.advance(5);
+        assert_eq!(handle.now_ms().unwrap(), 15);
+        clock.set(100).unwrap();
+        assert_eq!(handle.now_ms().unwrap(), 100);
+        assert!(clock.set(99).is_err());
+    }
+
+    #[test]
+    fn test_mock_executor_timer_reads_shared_clock() {
+        let mut harness = TestHarness::from_source(MULTI_EFFECT_APP).unwrap();
+        let mut executor = MockEffectExecutor::new().with_clock(Box::new(harness.clock().clone()));
+        harness.advance_clock(3000);
+        let (_, callbacks) = harness
+            .send_with_effects(
+                AppMessage::new("do_stuff", Value::String(String::new())),
+                &mut executor,
+            )
+            .unwrap();
+        let timer = callbacks.iter().find(|m| m.tag == "time_done").unwrap();
+        assert_eq!(timer.payload, Value::Int(3000));
+        // Other effects keep the default mock response.
+        let http = callbacks.iter().find(|m| m.tag == "http_done").unwrap();
+        assert_eq!(http.payload, Value::String("mock_result".into()));
+    }
+
+    #[test]
+    fn test_gateway_clock_is_policy_gated() {
+        use boruna_bytecode::Capability;
+        use boruna_vm::capability_gateway::{MockHandler, Policy};
+
+        let mut policy = Policy::deny_all();
+        policy.allow(&Capability::TimeNow, 10);
+        let mut clock = GatewayClock::new(policy, Box::new(MockHandler));
+        assert_eq!(clock.now_ms().unwrap(), 1_700_000_000_000);
+        assert_eq!(clock.event_log().events().len(), 2);
+
+        let module = boruna_compiler::compile("test", COUNTER_APP).unwrap();
+        let denied = GatewayClock::new(Policy::deny_all(), Box::new(MockHandler));
+        let mut runtime = AppRuntime::new(module)
+            .unwrap()
+            .with_clock(Box::new(denied));
+        let err = runtime
+            .send(AppMessage::new("increment", Value::Int(0)))
+            .unwrap_err();
+        assert!(matches!(err, crate::FrameworkError::Runtime(_)), "{err}");
+        assert_eq!(runtime.cycle(), 0);
+    }
+
+    #[test]
+    fn test_gateway_clock_rejects_seconds_past_the_millisecond_range() {
+        use boruna_bytecode::Capability;
+        use boruna_vm::capability_gateway::{CapabilityHandler, Policy};
+
+        struct FarFuture;
+        impl CapabilityHandler for FarFuture {
+            fn handle(&mut self, _: &Capability, _: &[Value]) -> Result<Value, String> {
+                Ok(Value::Int(i64::MAX))
+            }
+        }
+        let mut policy = Policy::deny_all();
+        policy.allow(&Capability::TimeNow, 10);
+        let mut clock = GatewayClock::new(policy, Box::new(FarFuture));
+        let err = clock.now_ms().unwrap_err();
+        assert!(matches!(err, crate::FrameworkError::Effect(_)), "{err}");
+    }
+
+    #[test]
+    fn framework_errors_carry_stable_codes() {
+        use boruna_errors::BorunaError;
+
+        let missing =
+            AppRuntime::new(boruna_compiler::compile("t", "fn main() -> Int { 0 }").unwrap())
+                .err()
+                .unwrap();
+        assert_eq!(
+            missing.error_code().kind.split('.').next(),
+            Some("framework")
+        );
+
+        // Wrapped errors report the inner code.
+        let runtime = crate::FrameworkError::Runtime(boruna_vm::VmError::DivisionByZero);
+        assert_eq!(runtime.envelope().code, "VM004");
+        assert_eq!(
+            runtime.envelope().message,
+            "runtime error: division by zero"
+        );
+    }
 }
@@ -1,4 +1,7 @@
 use boruna_bytecode::Value;
+use serde::Serialize;
+
+use crate::effect::as_list;
 
 /// A node in the declarative UI tree.
 ///
@@ -78,3 +81,56 @@ pub fn ui_tree_to_value(node: &UINode) -> Value {
 
     Value::Record { type_id: 0, fields }
 }
+
+/// One difference between two view() trees.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UiChange {
+    /// Field and item indices from the root, e.g. `/2/0`; empty for the
+    /// root itself.
+    pub path: String,
+    /// `None` when the node was added.
+    pub old: Option<Value>,
+    /// `None` when the node was removed.
+    pub new: Option<Value>,
+}
+
+/// Differences between two view() trees. Records of the same type and
+/// lists are compared item by item, so removing three list items reports
+/// three removals; anything else that differs is reported whole.
+pub fn ui_diff(old: &Value, new: &Value) -> Vec<UiChange> {
+    let mut changes = Vec::new();
+    diff_node(old, new, String::new(), &mut changes);
+    changes
+}
+
+fn diff_node(old: &Value, new: &Value, path: String, changes: &mut Vec<UiChange>) {
+    if old == new {
+        return;
+    }
+    let children = match (old, new) {
+        (Value::Record { type_id: a, .. }, Value::Record { type_id: b, .. }) if a != b => None,
+        (Value::Record { fields: a, .. }, Value::Record { fields: b, .. }) => {
+            Some((&a[..], &b[..]))
+        }
+        _ => as_list(old).zip(as_list(new)),
+    };
+    let Some((a, b)) = children else {
+        changes.push(UiChange {
+            path,
+            old: Some(old.clone()),
+            new: Some(new.clone()),
+        });
+        return;
+    };
+    for i in 0..a.len().max(b.len()) {
+        let child = format!("{path}/{i}");
+        match (a.get(i), b.get(i)) {
+            (Some(x), Some(y)) => diff_node(x, y, child, changes),
+            (x, y) => changes.push(UiChange {
+                path: child,
+                old: x.cloned(),
+                new: y.cloned(),
+            }),
+        }
+    }
+}
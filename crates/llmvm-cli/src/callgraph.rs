@@ -0,0 +1,295 @@
+//! Call graph rendering for `boruna inspect --callgraph`.
+//!
+//! Builds the function call graph of a compiled [`Module`] from its
+//! bytecode: `Call` is a direct edge, `SpawnActor` a spawn edge, and a
+//! `PushConst` of a `FnRef` constant a reference edge (the function may be
+//! called indirectly from there). Each node carries the capabilities the
+//! function uses itself (declared or via `CapCall`) and a static step
+//! estimate; each edge is labelled with the capabilities reachable through
+//! it, so a reviewer can follow a capability from `main` down to the call
+//! site before approving a policy.
+//!
+//! **Step estimates** count one step per instruction on the straight-line
+//! path through a function plus, per direct call site, the callee's own
+//! estimate. A function containing a backward jump, a recursive call or an
+//! indirect call has no static bound; its estimate is a lower bound and is
+//! rendered with a trailing `+`.
+//!
+//! Output is deterministic: nodes are emitted by function index and edges
+//! in `BTreeSet` order.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use boruna_bytecode::{Capability, Module, Op, Value};
+
+/// Output format for `inspect --callgraph`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum CallgraphFormat {
+    Dot,
+}
+
+/// How one function reaches another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EdgeKind {
+    Call,
+    Spawn,
+    Ref,
+}
+
+/// Static step estimate for a function and its direct callees.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StepEstimate {
+    pub steps: u64,
+    /// False when loops, recursion or indirect calls make `steps` a lower
+    /// bound.
+    pub bounded: bool,
+}
+
+impl std::fmt::Display for StepEstimate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.bounded {
+            write!(f, "{}", self.steps)
+        } else {
+            write!(f, "{}+", self.steps)
+        }
+    }
+}
+
+/// Call graph of a module, indexed by function index.
+#[derive(Debug)]
+pub struct CallGraph {
+    /// `(caller, callee, kind)`.
+    pub edges: BTreeSet<(u32, u32, EdgeKind)>,
+    /// Names of the capabilities each function uses itself.
+    pub direct: Vec<BTreeSet<&'static str>>,
+    /// Capabilities each function can reach through any edge.
+    pub reachable: Vec<BTreeSet<&'static str>>,
+    pub steps: Vec<StepEstimate>,
+}
+
+impl CallGraph {
+    pub fn build(module: &Module) -> Self {
+        let n = module.functions.len();
+        let mut edges = BTreeSet::new();
+        let mut direct = vec![BTreeSet::new(); n];
+        for (idx, f) in module.functions.iter().enumerate() {
+            let caller = idx as u32;
+            direct[idx].extend(f.capabilities.iter().map(|c| c.name()));
+            for op in &f.code {
+                match op {
+                    Op::Call(callee, _) => {
+                        edges.insert((caller, *callee, EdgeKind::Call));
+                    }
+                    Op::SpawnActor(callee) => {
+                        edges.insert((caller, *callee, EdgeKind::Spawn));
+                    }
+                    Op::PushConst(c) => {
+                        if let Some(Value::FnRef(callee)) = module.constants.get(*c as usize) {
+                            edges.insert((caller, *callee, EdgeKind::Ref));
+                        }
+                    }
+                    Op::CapCall(cap_id, _) | Op::CapBatch(cap_id) | Op::CapSpawn(cap_id, _) => {
+                        if let Some(cap) = Capability::from_id(*cap_id) {
+                            direct[idx].insert(cap.name());
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        edges.retain(|(_, callee, _)| (*callee as usize) < n);
+
+        let mut graph = CallGraph {
+            edges,
+            reachable: Vec::new(),
+            steps: Vec::new(),
+            direct,
+        };
+        graph.reachable = (0..n as u32).map(|f| graph.reach(f)).collect();
+        let mut memo = BTreeMap::new();
+        graph.steps = (0..n as u32)
+            .map(|f| graph.estimate(module, f, &mut Vec::new(), &mut memo))
+            .collect();
+        graph
+    }
+
+    fn callees(&self, func: u32) -> impl Iterator<Item = (u32, EdgeKind)> + '_ {
+        self.edges
+            .range((func, 0, EdgeKind::Call)..=(func, u32::MAX, EdgeKind::Ref))
+            .map(|(_, callee, kind)| (*callee, *kind))
+    }
+
+    /// Capabilities used by `func` or anything reachable from it.
+    fn reach(&self, func: u32) -> BTreeSet<&'static str> {
+        let mut caps = BTreeSet::new();
+        let mut visited = BTreeSet::new();
+        let mut pending = vec![func];
+        while let Some(f) = pending.pop() {
+            if !visited.insert(f) {
+                continue;
+            }
+            caps.extend(self.direct[f as usize].iter().copied());
+            pending.extend(self.callees(f).map(|(callee, _)| callee));
+        }
+        caps
+    }
+
+    fn estimate(
+        &self,
+        module: &Module,
+        func: u32,
+        stack: &mut Vec<u32>,
+        memo: &mut BTreeMap<u32, StepEstimate>,
+    ) -> StepEstimate {
+        if let Some(est) = memo.get(&func) {
+            return *est;
+        }
+        let code = &module.functions[func as usize].code;
+        let mut est = StepEstimate {
+            steps: code.len() as u64,
+            bounded: true,
+        };
+        stack.push(func);
+        for (ip, op) in code.iter().enumerate() {
+            match op {
+                Op::Jmp(target) | Op::JmpIf(target) | Op::JmpIfNot(target)
+                    if *target as usize <= ip =>
+                {
+                    est.bounded = false;
+                }
+                Op::CallIndirect(_) => est.bounded = false,
+                Op::Call(callee, _) if (*callee as usize) < module.functions.len() => {
+                    if stack.contains(callee) {
+                        est.bounded = false;
+                        continue;
+                    }
+                    let sub = self.estimate(module, *callee, stack, memo);
+                    est.steps = est.steps.saturating_add(sub.steps);
+                    est.bounded &= sub.bounded;
+                }
+                _ => {}
+            }
+        }
+        stack.pop();
+        // Inside a cycle the lower bound depends on the path taken into
+        // it; any such bound will do.
+        memo.insert(func, est);
+        est
+    }
+}
+
+fn cap_list(caps: &BTreeSet<&str>) -> String {
+    caps.iter().copied().collect::<Vec<_>>().join(", ")
+}
+
+/// Render the call graph of `module` as a Graphviz DOT digraph.
+///
+/// The entry function is drawn bold; functions that use a capability
+/// themselves are filled. Spawn edges are dotted and reference edges
+/// dashed.
+pub fn render_dot(module: &Module) -> String {
+    let graph = CallGraph::build(module);
+    let mut out = String::new();
+    out.push_str(&format!("digraph \"{}\" {{\n", dot_escape(&module.name)));
+    out.push_str("  rankdir=LR;\n");
+    out.push_str("  node [shape=box, style=\"rounded,filled\", fillcolor=\"#ffffff\"];\n");
+    for (idx, f) in module.functions.iter().enumerate() {
+        let mut label = vec![format!("{}/{}", f.name, f.arity)];
+        let direct = &graph.direct[idx];
+        if !direct.is_empty() {
+            label.push(format!("caps: {}", cap_list(direct)));
+        }
+        label.push(format!("steps: {}", graph.steps[idx]));
+        let label: Vec<String> = label.iter().map(|l| dot_escape(l)).collect();
+        let mut attrs = format!("label=\"{}\"", label.join("\\n"));
+        if !direct.is_empty() {
+            attrs.push_str(", fillcolor=\"#f9e2af\"");
+        }
+        if idx as u32 == module.entry {
+            attrs.push_str(", penwidth=2");
+        }
+        out.push_str(&format!("  f{idx} [{attrs}];\n"));
+    }
+    for (caller, callee, kind) in &graph.edges {
+        let mut attrs = Vec::new();
+        match kind {
+            EdgeKind::Call => {}
+            EdgeKind::Spawn => attrs.push("style=dotted".to_string()),
+            EdgeKind::Ref => attrs.push("style=dashed".to_string()),
+        }
+        let reachable = &graph.reachable[*callee as usize];
+        if !reachable.is_empty() {
+            attrs.push(format!("label=\"{}\"", dot_escape(&cap_list(reachable))));
+        }
+        if attrs.is_empty() {
+            out.push_str(&format!("  f{caller} -> f{callee};\n"));
+        } else {
+            out.push_str(&format!(
+                "  f{caller} -> f{callee} [{}];\n",
+                attrs.join(", ")
+            ));
+        }
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn dot_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compile(src: &str) -> Module {
+        boruna_compiler::compile("graph", src).unwrap()
+    }
+
+    #[test]
+    fn edges_carry_the_capabilities_they_reach() {
+        let module = compile(
+            "fn enqueue() -> Int !{queue.push} { queue_push(\"jobs\", 1) }\n\
+             fn helper() -> Int { enqueue() }\n\
+             fn pure() -> Int { 1 + 2 }\n\
+             fn main() -> Int { let n: Int = pure()\n helper() }\n",
+        );
+        let idx = |name: &str| {
+            module
+                .functions
+                .iter()
+                .position(|f| f.name == name)
+                .unwrap()
+        };
+        let (enqueue, helper, pure, main) =
+            (idx("enqueue"), idx("helper"), idx("pure"), idx("main"));
+        let dot = render_dot(&module);
+
+        assert!(dot.contains(&format!("f{main} -> f{helper} [label=\"queue.push\"];")));
+        assert!(dot.contains(&format!("f{helper} -> f{enqueue} [label=\"queue.push\"];")));
+        assert!(dot.contains(&format!("f{main} -> f{pure};")));
+        assert!(dot.contains("caps: queue.push"));
+
+        let graph = CallGraph::build(&module);
+        let steps = |f: usize| module.functions[f].code.len() as u64;
+        assert_eq!(
+            graph.steps[main],
+            StepEstimate {
+                steps: steps(main) + steps(pure) + steps(helper) + steps(enqueue),
+                bounded: true
+            }
+        );
+        assert_eq!(render_dot(&module), dot);
+    }
+
+    #[test]
+    fn recursion_makes_the_estimate_a_lower_bound() {
+        let module = compile(
+            "fn count(n: Int) -> Int { if n == 0 { 0 } else { count(n - 1) } }\n\
+             fn main() -> Int { count(3) }\n",
+        );
+        let graph = CallGraph::build(&module);
+        assert!(graph.steps.iter().all(|s| !s.bounded));
+        assert!(render_dot(&module).contains("+\""));
+    }
+}
@@ -0,0 +1,166 @@
+//! `boruna run` answers for `prompt` policy rules.
+//!
+//! A rule with `"prompt": true` pauses the run on the first call of that
+//! capability and asks the operator on stderr, showing the call's
+//! arguments. Answers are appended to the `--session-policy` file (one
+//! JSON object per line) and reused without asking on later runs, like a
+//! mobile OS remembering a permission. Without a terminal the session file
+//! is still consulted; unanswered rules fall back to their `allow`.
+
+use std::collections::BTreeMap;
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use boruna_bytecode::{Capability, Value};
+use boruna_vm::capability_gateway::CapabilityPrompter;
+
+/// One remembered answer in a session policy file.
+#[derive(Debug, Serialize, Deserialize)]
+struct SessionDecision {
+    capability: String,
+    allow: bool,
+}
+
+/// Answers `prompt` rules from a session file, then from the operator.
+pub struct SessionPrompter<R, W> {
+    decisions: BTreeMap<String, bool>,
+    session: Option<PathBuf>,
+    /// `None` when nobody can be asked (stdin is not a terminal).
+    operator: Option<(R, W)>,
+}
+
+/// The prompter for a `run`: asks on the terminal when stdin is one, and
+/// remembers answers in `session`. `None` when there is neither.
+pub fn for_run(session: Option<&Path>) -> Result<Option<Box<dyn CapabilityPrompter>>, String> {
+    use std::io::IsTerminal;
+    let interactive = std::io::stdin().is_terminal();
+    if !interactive && session.is_none() {
+        return Ok(None);
+    }
+    let operator =
+        interactive.then(|| (std::io::BufReader::new(std::io::stdin()), std::io::stderr()));
+    Ok(Some(Box::new(SessionPrompter::new(session, operator)?)))
+}
+
+impl<R: BufRead, W: Write> SessionPrompter<R, W> {
+    /// Load the decisions already in `session` (the last answer per
+    /// capability wins). A missing file starts an empty session.
+    pub fn new(session: Option<&Path>, operator: Option<(R, W)>) -> Result<Self, String> {
+        let mut decisions = BTreeMap::new();
+        if let Some(path) = session.filter(|p| p.exists()) {
+            let text = std::fs::read_to_string(path)
+                .map_err(|e| format!("session policy {}: {e}", path.display()))?;
+            for (i, line) in text.lines().enumerate() {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let d: SessionDecision = serde_json::from_str(line)
+                    .map_err(|e| format!("session policy {}:{}: {e}", path.display(), i + 1))?;
+                decisions.insert(d.capability, d.allow);
+            }
+        }
+        Ok(SessionPrompter {
+            decisions,
+            session: session.map(Path::to_path_buf),
+            operator,
+        })
+    }
+
+    /// Ask the operator; `None` on end of input or an unreadable answer.
+    fn ask_operator(&mut self, cap: &Capability, args: &[Value]) -> Option<bool> {
+        let (input, output) = self.operator.as_mut()?;
+        let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        let _ = writeln!(
+            output,
+            "capability request: {}({})",
+            cap.name(),
+            args.join(", ")
+        );
+        loop {
+            let _ = write!(output, "  allow {}? [y/n] ", cap.name());
+            let _ = output.flush();
+            let mut line = String::new();
+            if input.read_line(&mut line).ok()? == 0 {
+                return None;
+            }
+            match line.trim().to_ascii_lowercase().as_str() {
+                "y" | "yes" => return Some(true),
+                "n" | "no" => return Some(false),
+                _ => {}
+            }
+        }
+    }
+
+    fn remember(&mut self, cap: &Capability, allow: bool) {
+        self.decisions.insert(cap.name().to_string(), allow);
+        let Some(path) = &self.session else {
+            return;
+        };
+        let line = serde_json::to_string(&SessionDecision {
+            capability: cap.name().to_string(),
+            allow,
+        })
+        .expect("session decision serializes");
+        let written = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .and_then(|mut f| writeln!(f, "{line}"));
+        if let Err(e) = written {
+            eprintln!("warning: session policy {}: {e}", path.display());
+        }
+    }
+}
+
+impl<R: BufRead + Send, W: Write + Send> CapabilityPrompter for SessionPrompter<R, W> {
+    fn ask(&mut self, cap: &Capability, args: &[Value]) -> Option<bool> {
+        if let Some(&allow) = self.decisions.get(cap.name()) {
+            return Some(allow);
+        }
+        let allow = self.ask_operator(cap, args)?;
+        self.remember(cap, allow);
+        Some(allow)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    type Operator = (Cursor<Vec<u8>>, Vec<u8>);
+
+    fn operator(input: &str) -> Option<Operator> {
+        Some((Cursor::new(input.as_bytes().to_vec()), Vec::new()))
+    }
+
+    #[test]
+    fn answer_is_shown_args_and_remembered_in_session() {
+        let dir = tempfile::tempdir().unwrap();
+        let session = dir.path().join("session.jsonl");
+        let url = [Value::String("https://api.example.com".into())];
+
+        let mut p = SessionPrompter::new(Some(&session), operator("maybe\ny\n")).unwrap();
+        assert_eq!(p.ask(&Capability::NetFetch, &url), Some(true));
+        let (_, shown) = p.operator.take().unwrap();
+        let shown = String::from_utf8(shown).unwrap();
+        assert!(
+            shown.contains("net.fetch(\"https://api.example.com\")"),
+            "{shown}"
+        );
+
+        // A later run reuses the answer without asking.
+        let mut again =
+            SessionPrompter::<Cursor<Vec<u8>>, Vec<u8>>::new(Some(&session), None).unwrap();
+        assert_eq!(again.ask(&Capability::NetFetch, &url), Some(true));
+        assert_eq!(again.ask(&Capability::FsWrite, &[]), None);
+    }
+
+    #[test]
+    fn end_of_input_gives_no_answer() {
+        let mut p = SessionPrompter::new(None, operator("")).unwrap();
+        assert_eq!(p.ask(&Capability::FsRead, &[]), None);
+    }
+}
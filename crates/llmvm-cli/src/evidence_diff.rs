@@ -25,6 +25,33 @@ pub struct StepDiff {
     a: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     b: Option<String>,
+    /// Manifest checksum of the step's output file in each bundle.
+    hash_a: Option<String>,
+    hash_b: Option<String>,
+}
+
+/// Policy comparison: manifest `policy_hash` plus the top-level
+/// `policy.json` keys whose values differ.
+#[derive(Debug, Serialize)]
+pub struct PolicyDiff {
+    hash: FieldDiff<String>,
+    changed_keys: Vec<String>,
+}
+
+/// One environment fingerprint field that differs between the bundles.
+#[derive(Debug, Serialize)]
+pub struct EnvDrift {
+    field: String,
+    a: String,
+    b: String,
+}
+
+/// Per-kind audit event counts that differ between the bundles.
+#[derive(Debug, Serialize)]
+pub struct EventDelta {
+    kind: String,
+    a: usize,
+    b: usize,
 }
 
 #[derive(Debug, Serialize)]
@@ -33,7 +60,12 @@ pub struct DiffReport {
     workflow_name: FieldDiff<String>,
     step_count: FieldDiff<usize>,
     step_outputs: Vec<StepDiff>,
+    policy: PolicyDiff,
+    /// Environment drift is informational and does not affect `same`:
+    /// two runs of one workflow on different hosts are still equivalent.
+    env_drift: Vec<EnvDrift>,
     audit_event_count: FieldDiff<usize>,
+    audit_event_deltas: Vec<EventDelta>,
     verification: VerificationStatus,
 }
 
@@ -60,8 +92,8 @@ fn load_audit_log(dir: &Path) -> Result<AuditLog, String> {
 }
 
 /// Read the step output files from `dir/outputs/` into a BTreeMap keyed by
-/// step_id. Each value is the raw JSON string of the first output file found
-/// under `outputs/<step_id>/`.
+/// step_id. A step with one output file maps to its raw JSON string; with
+/// several, to one `<file>: <json>` line per file in name order.
 fn load_step_outputs(dir: &Path) -> BTreeMap<String, String> {
     let mut map = BTreeMap::new();
     let outputs_dir = dir.join("outputs");
@@ -71,19 +103,115 @@ fn load_step_outputs(dir: &Path) -> BTreeMap<String, String> {
     };
     for step_entry in read_dir.flatten() {
         let step_id = step_entry.file_name().to_string_lossy().to_string();
-        let step_path = step_entry.path();
-        if let Ok(files) = std::fs::read_dir(&step_path) {
-            for file_entry in files.flatten() {
-                if let Ok(content) = std::fs::read_to_string(file_entry.path()) {
-                    map.insert(step_id.clone(), content);
-                    break; // first output file per step
-                }
-            }
-        }
+        let Ok(files) = std::fs::read_dir(step_entry.path()) else {
+            continue;
+        };
+        let contents: BTreeMap<String, String> = files
+            .flatten()
+            .filter_map(|f| {
+                let content = std::fs::read_to_string(f.path()).ok()?;
+                Some((f.file_name().to_string_lossy().to_string(), content))
+            })
+            .collect();
+        let value = match contents.len() {
+            0 => continue,
+            1 => contents.into_values().next().unwrap_or_default(),
+            _ => contents
+                .iter()
+                .map(|(name, content)| format!("{name}: {content}"))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        };
+        map.insert(step_id, value);
     }
     map
 }
 
+/// Hash of every output file under `outputs/<step_id>/`: the manifest
+/// checksum itself for a single file, else SHA-256 over each file's path
+/// and checksum in sorted order.
+fn step_output_hash(manifest: &BundleManifest, step_id: &str) -> Option<String> {
+    let prefix = format!("outputs/{step_id}/");
+    let files: Vec<(&String, &String)> = manifest
+        .file_checksums
+        .iter()
+        .filter(|(k, _)| k.starts_with(&prefix))
+        .collect();
+    match files.as_slice() {
+        [] => None,
+        [(_, checksum)] => Some((*checksum).clone()),
+        _ => {
+            let listing: String = files
+                .iter()
+                .map(|(path, checksum)| format!("{path} {checksum}\n"))
+                .collect();
+            Some(boruna_hash::sha256_hex(listing))
+        }
+    }
+}
+
+/// Top-level keys of the two `policy.json` files whose values differ.
+/// Unreadable or non-object policies (e.g. encrypted bundles) yield no
+/// keys; the hash comparison still reports the change.
+fn changed_policy_keys(dir_a: &Path, dir_b: &Path) -> Vec<String> {
+    let load = |dir: &Path| -> BTreeMap<String, serde_json::Value> {
+        std::fs::read_to_string(dir.join("policy.json"))
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    };
+    let a = load(dir_a);
+    let b = load(dir_b);
+    let keys: std::collections::BTreeSet<&String> = a.keys().chain(b.keys()).collect();
+    keys.into_iter()
+        .filter(|k| a.get(*k) != b.get(*k))
+        .cloned()
+        .collect()
+}
+
+fn env_drift(a: &BundleManifest, b: &BundleManifest) -> Vec<EnvDrift> {
+    a.env_fingerprint
+        .drift(&b.env_fingerprint)
+        .into_iter()
+        .map(|(field, a, b)| EnvDrift { field, a, b })
+        .collect()
+}
+
+/// Count audit events by variant name (`StepCompleted`, ...).
+fn event_kind_counts(log: &AuditLog) -> BTreeMap<String, usize> {
+    let mut counts = BTreeMap::new();
+    for entry in log.entries() {
+        let kind = match serde_json::to_value(&entry.event) {
+            Ok(serde_json::Value::Object(m)) => m.keys().next().cloned(),
+            Ok(serde_json::Value::String(s)) => Some(s),
+            _ => None,
+        }
+        .unwrap_or_else(|| "unknown".to_string());
+        *counts.entry(kind).or_insert(0) += 1;
+    }
+    counts
+}
+
+fn event_deltas(a: &AuditLog, b: &AuditLog) -> Vec<EventDelta> {
+    let ca = event_kind_counts(a);
+    let cb = event_kind_counts(b);
+    let kinds: std::collections::BTreeSet<&String> = ca.keys().chain(cb.keys()).collect();
+    kinds
+        .into_iter()
+        .filter_map(|k| {
+            let (na, nb) = (
+                ca.get(k).copied().unwrap_or(0),
+                cb.get(k).copied().unwrap_or(0),
+            );
+            (na != nb).then(|| EventDelta {
+                kind: k.clone(),
+                a: na,
+                b: nb,
+            })
+        })
+        .collect()
+}
+
 /// Build a diff report from two bundle directories, comparing both manifests
 /// and on-disk step outputs.
 pub fn build_diff(dir_a: &Path, dir_b: &Path) -> Result<DiffReport, Box<dyn std::error::Error>> {
@@ -138,7 +266,14 @@ pub fn build_diff(dir_a: &Path, dir_b: &Path) -> Result<DiffReport, Box<dyn std:
     for step_id in &step_ids {
         let val_a = outputs_a.get(step_id);
         let val_b = outputs_b.get(step_id);
-        let same = val_a == val_b;
+        let hash_a = step_output_hash(&manifest_a, step_id);
+        let hash_b = step_output_hash(&manifest_b, step_id);
+        // Encrypted bundles have ciphertext on disk, so the manifest
+        // checksums are the authoritative comparison when both exist.
+        let same = match (&hash_a, &hash_b) {
+            (Some(ha), Some(hb)) => ha == hb && val_a == val_b,
+            _ => val_a == val_b,
+        };
         step_diffs.push(StepDiff {
             step: step_id.clone(),
             same,
@@ -152,6 +287,8 @@ pub fn build_diff(dir_a: &Path, dir_b: &Path) -> Result<DiffReport, Box<dyn std:
             } else {
                 Some(val_b.cloned().unwrap_or_default())
             },
+            hash_a,
+            hash_b,
         });
     }
 
@@ -177,18 +314,31 @@ pub fn build_diff(dir_a: &Path, dir_b: &Path) -> Result<DiffReport, Box<dyn std:
     );
     let step_count_diff = FieldDiff::new(step_count_a, step_count_b);
     let event_count_diff = FieldDiff::new(audit_a.entries().len(), audit_b.entries().len());
+    let audit_event_deltas = event_deltas(&audit_a, &audit_b);
+    let policy = PolicyDiff {
+        hash: FieldDiff::new(
+            manifest_a.policy_hash.clone(),
+            manifest_b.policy_hash.clone(),
+        ),
+        changed_keys: changed_policy_keys(dir_a, dir_b),
+    };
 
     let all_same = workflow_diff.same
         && step_count_diff.same
         && step_diffs.iter().all(|s| s.same)
-        && event_count_diff.same;
+        && policy.hash.same
+        && event_count_diff.same
+        && audit_event_deltas.is_empty();
 
     Ok(DiffReport {
         same: all_same,
         workflow_name: workflow_diff,
         step_count: step_count_diff,
         step_outputs: step_diffs,
+        policy,
+        env_drift: env_drift(&manifest_a, &manifest_b),
         audit_event_count: event_count_diff,
+        audit_event_deltas,
         verification,
     })
 }
@@ -227,6 +377,13 @@ pub fn print_diff(dir_a: &Path, dir_b: &Path, report: &DiffReport) {
                 println!("  {} : SAME", s.step);
             } else {
                 println!("  {} : CHANGED", s.step);
+                if s.hash_a != s.hash_b {
+                    println!(
+                        "    hash: {} → {}",
+                        s.hash_a.as_deref().unwrap_or("(absent)"),
+                        s.hash_b.as_deref().unwrap_or("(absent)")
+                    );
+                }
                 if let Some(a) = &s.a {
                     for line in a.lines() {
                         println!("    - {line}");
@@ -257,8 +414,35 @@ pub fn print_diff(dir_a: &Path, dir_b: &Path, report: &DiffReport) {
             ea - eb
         )
     };
+    println!("Policy:");
+    if report.policy.hash.same {
+        println!("  {} (same)", report.policy.hash.a);
+    } else {
+        println!(
+            "  CHANGED {} → {}",
+            report.policy.hash.a, report.policy.hash.b
+        );
+        for key in &report.policy.changed_keys {
+            println!("    key: {key}");
+        }
+    }
+    println!();
+
+    if report.env_drift.is_empty() {
+        println!("Environment: same");
+    } else {
+        println!("Environment drift:");
+        for d in &report.env_drift {
+            println!("  {}: {} → {}", d.field, d.a, d.b);
+        }
+    }
+    println!();
+
     println!("Audit log:");
     println!("  Events: {event_label}");
+    for d in &report.audit_event_deltas {
+        println!("    {}: {} vs {}", d.kind, d.a, d.b);
+    }
     println!();
 
     println!("Verification:");
@@ -295,12 +479,30 @@ mod tests {
         workflow_name: &str,
         step_output: &str,
         extra_event: bool,
+    ) -> PathBuf {
+        make_bundle_with_policy(
+            base,
+            run_id,
+            workflow_name,
+            step_output,
+            extra_event,
+            r#"{"default_allow":true}"#,
+        )
+    }
+
+    fn make_bundle_with_policy(
+        base: &Path,
+        run_id: &str,
+        workflow_name: &str,
+        step_output: &str,
+        extra_event: bool,
+        policy: &str,
     ) -> PathBuf {
         let mut builder = EvidenceBundleBuilder::new(base, run_id, workflow_name).unwrap();
         builder
             .add_workflow_def(&format!(r#"{{"name":"{workflow_name}"}}"#))
             .unwrap();
-        builder.add_policy(r#"{"default_allow":true}"#).unwrap();
+        builder.add_policy(policy).unwrap();
         builder
             .add_step_output("step1", "result", step_output)
             .unwrap();
@@ -391,4 +593,108 @@ mod tests {
         assert!(parsed.get("audit_event_count").is_some());
         assert!(parsed.get("verification").is_some());
     }
+
+    #[test]
+    fn diff_reports_step_output_hashes() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = make_bundle(dir.path(), "run-a5", "wf", r#"{"v":1}"#, false);
+        let b = make_bundle(dir.path(), "run-b5", "wf", r#"{"v":2}"#, false);
+
+        let report = build_diff(&a, &b).unwrap();
+        let step1 = &report.step_outputs[0];
+        assert!(step1.hash_a.is_some() && step1.hash_b.is_some());
+        assert_ne!(step1.hash_a, step1.hash_b);
+    }
+
+    #[test]
+    fn diff_compares_every_output_file_of_a_step() {
+        let dir = tempfile::tempdir().unwrap();
+        let bundle = |run_id: &str, extra: &str| {
+            let mut builder = EvidenceBundleBuilder::new(dir.path(), run_id, "wf").unwrap();
+            builder.add_workflow_def(r#"{"name":"wf"}"#).unwrap();
+            builder.add_policy(r#"{"default_allow":true}"#).unwrap();
+            builder
+                .add_step_output("step1", "result", r#"{"v":1}"#)
+                .unwrap();
+            builder.add_step_output("step1", "extra", extra).unwrap();
+            builder.finalize(&AuditLog::new()).unwrap();
+            dir.path().join(run_id)
+        };
+        let a = bundle("run-a7", r#"{"w":1}"#);
+        let b = bundle("run-b7", r#"{"w":2}"#);
+        let same = bundle("run-c7", r#"{"w":1}"#);
+
+        let report = build_diff(&a, &b).unwrap();
+        let step1 = &report.step_outputs[0];
+        assert!(!step1.same, "a changed second output must be reported");
+        assert_ne!(step1.hash_a, step1.hash_b);
+        assert!(step1
+            .b
+            .as_deref()
+            .unwrap()
+            .contains(r#"extra.json: {"w":2}"#));
+
+        let report = build_diff(&a, &same).unwrap();
+        assert!(report.step_outputs[0].same);
+        assert_eq!(report.step_outputs[0].hash_a, report.step_outputs[0].hash_b);
+    }
+
+    #[test]
+    fn diff_reports_changed_policy_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = make_bundle_with_policy(
+            dir.path(),
+            "run-a6",
+            "wf",
+            "{}",
+            false,
+            r#"{"default_allow":true,"rules":[]}"#,
+        );
+        let b = make_bundle_with_policy(
+            dir.path(),
+            "run-b6",
+            "wf",
+            "{}",
+            false,
+            r#"{"default_allow":false,"rules":[]}"#,
+        );
+
+        let report = build_diff(&a, &b).unwrap();
+        assert!(!report.same);
+        assert!(!report.policy.hash.same);
+        assert_eq!(report.policy.changed_keys, vec!["default_allow"]);
+    }
+
+    #[test]
+    fn diff_reports_audit_event_deltas_by_kind() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = make_bundle(dir.path(), "run-a7", "wf", "{}", false);
+        let b = make_bundle(dir.path(), "run-b7", "wf", "{}", true);
+
+        let report = build_diff(&a, &b).unwrap();
+        assert_eq!(report.audit_event_deltas.len(), 1);
+        assert_eq!(report.audit_event_deltas[0].kind, "PolicyEvaluated");
+        assert_eq!(
+            (
+                report.audit_event_deltas[0].a,
+                report.audit_event_deltas[0].b
+            ),
+            (0, 1)
+        );
+    }
+
+    #[test]
+    fn env_drift_lists_differing_fields_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = make_bundle(dir.path(), "run-a8", "wf", "{}", false);
+        let ma = load_manifest(&a).unwrap();
+        let mut mb = ma.clone();
+        assert!(env_drift(&ma, &mb).is_empty());
+
+        mb.env_fingerprint.boruna_version = "9.9.9".into();
+        let drift = env_drift(&ma, &mb);
+        assert_eq!(drift.len(), 1);
+        assert_eq!(drift[0].field, "boruna_version");
+        assert_eq!(drift[0].b, "9.9.9");
+    }
 }
@@ -0,0 +1,446 @@
+//! `boruna evidence gc` — retention policy for an evidence root.
+//!
+//! An evidence root is a directory whose immediate subdirectories are
+//! bundles (each with a `manifest.json`). A bundle is pruned only when it
+//! falls outside BOTH retention rules (`--keep-last N` newest by
+//! `completed_at`, and `--keep-days D`), and additionally:
+//!
+//! - its `completed_at` parses — a bundle of unknown age is never pruned
+//!   and does not take a `--keep-last` slot;
+//! - it verified cleanly before the sweep — a bundle that fails
+//!   `verify_bundle` is kept for forensics, never deleted;
+//! - no retained bundle references it — a bundle whose `bundle_hash`
+//!   appears in any file of a retained bundle (e.g. a replay that
+//!   records its source bundle) is kept, transitively.
+//!
+//! Before deleting anything a deletion manifest
+//! (`gc-<unix_ms>.json`) is written to the root, and after the sweep
+//! every retained bundle that verified before is re-verified. Dry-run
+//! mode computes and prints the plan without touching disk.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use boruna_orchestrator::audit::{evidence::BundleManifest, verify::verify_bundle};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// Retention rules. At least one must be set.
+#[derive(Debug, Clone, Serialize)]
+pub struct GcPolicy {
+    pub keep_last: Option<usize>,
+    pub keep_days: Option<u64>,
+}
+
+/// One bundle in the plan.
+#[derive(Debug, Clone, Serialize)]
+pub struct GcBundle {
+    pub dir: String,
+    pub run_id: String,
+    pub workflow_name: String,
+    pub bundle_hash: String,
+    pub completed_at: String,
+    /// Why the bundle is kept (`keep_last`, `keep_days`, `unknown_age`,
+    /// `verification_failed`, `referenced_by:<dir>`); `None` when pruned.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kept_because: Option<String>,
+}
+
+/// Output of [`plan_gc`] — also the body of the deletion manifest.
+#[derive(Debug, Serialize)]
+pub struct GcPlan {
+    pub policy: GcPolicy,
+    pub evaluated_at: String,
+    pub dry_run: bool,
+    pub deleted: Vec<GcBundle>,
+    pub kept: Vec<GcBundle>,
+    /// Subdirectories without a readable `manifest.json`; left untouched.
+    pub ignored: Vec<String>,
+}
+
+struct Candidate {
+    path: PathBuf,
+    manifest: BundleManifest,
+    completed: Option<DateTime<Utc>>,
+    valid: bool,
+}
+
+/// Compute which bundles under `root` to prune at time `now`.
+pub fn plan_gc(
+    root: &Path,
+    policy: &GcPolicy,
+    now: DateTime<Utc>,
+    dry_run: bool,
+) -> Result<GcPlan, String> {
+    if policy.keep_last.is_none() && policy.keep_days.is_none() {
+        return Err(
+            "refusing to gc without a retention rule; pass --keep-last and/or --keep-days".into(),
+        );
+    }
+    let cutoff = match policy.keep_days {
+        Some(d) => Some(
+            i64::try_from(d)
+                .ok()
+                .and_then(chrono::TimeDelta::try_days)
+                .and_then(|age| now.checked_sub_signed(age))
+                .ok_or_else(|| format!("--keep-days {d} is out of range"))?,
+        ),
+        None => None,
+    };
+    let read_dir =
+        fs::read_dir(root).map_err(|e| format!("cannot read {}: {e}", root.display()))?;
+
+    let mut candidates = Vec::new();
+    let mut ignored = Vec::new();
+    for entry in read_dir.flatten() {
+        let path = entry.path();
+        if !path.is_dir() {
+            continue;
+        }
+        let manifest = fs::read_to_string(path.join("manifest.json"))
+            .ok()
+            .and_then(|s| serde_json::from_str::<BundleManifest>(&s).ok());
+        match manifest {
+            Some(manifest) => {
+                let completed = DateTime::parse_from_rfc3339(&manifest.completed_at)
+                    .ok()
+                    .map(|t| t.with_timezone(&Utc));
+                let valid = verify_bundle(&path).valid;
+                candidates.push(Candidate {
+                    path,
+                    manifest,
+                    completed,
+                    valid,
+                });
+            }
+            None => ignored.push(dir_name(&path)),
+        }
+    }
+    ignored.sort();
+
+    // Newest first; bundles with an unparseable timestamp sort last and are
+    // kept without counting toward `keep_last`.
+    candidates.sort_by(|a, b| {
+        b.completed
+            .cmp(&a.completed)
+            .then_with(|| dir_name(&a.path).cmp(&dir_name(&b.path)))
+    });
+
+    let mut reasons: Vec<Option<String>> = candidates
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            let Some(completed) = c.completed else {
+                return Some("unknown_age".to_string());
+            };
+            if policy.keep_last.is_some_and(|n| i < n) {
+                Some("keep_last".to_string())
+            } else if cutoff.is_some_and(|cutoff| completed >= cutoff) {
+                Some("keep_days".to_string())
+            } else {
+                None
+            }
+            .or_else(|| (!c.valid).then(|| "verification_failed".to_string()))
+        })
+        .collect();
+
+    // Keep anything a retained bundle references, to a fixed point.
+    loop {
+        let mut changed = false;
+        for i in 0..candidates.len() {
+            if reasons[i].is_some() {
+                continue;
+            }
+            let hash = &candidates[i].manifest.bundle_hash;
+            let referrer = candidates
+                .iter()
+                .zip(&reasons)
+                .find(|(c, r)| r.is_some() && references(&c.path, hash));
+            if let Some((c, _)) = referrer {
+                reasons[i] = Some(format!("referenced_by:{}", dir_name(&c.path)));
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    let mut deleted = Vec::new();
+    let mut kept = Vec::new();
+    for (c, reason) in candidates.iter().zip(reasons) {
+        let bundle = GcBundle {
+            dir: dir_name(&c.path),
+            run_id: c.manifest.run_id.clone(),
+            workflow_name: c.manifest.workflow_name.clone(),
+            bundle_hash: c.manifest.bundle_hash.clone(),
+            completed_at: c.manifest.completed_at.clone(),
+            kept_because: reason,
+        };
+        if bundle.kept_because.is_some() {
+            kept.push(bundle);
+        } else {
+            deleted.push(bundle);
+        }
+    }
+
+    Ok(GcPlan {
+        policy: policy.clone(),
+        evaluated_at: now.to_rfc3339(),
+        dry_run,
+        deleted,
+        kept,
+        ignored,
+    })
+}
+
+/// Write the deletion manifest, delete the pruned bundles, then re-verify
+/// the retained ones. Returns the manifest path.
+pub fn apply_gc(root: &Path, plan: &GcPlan, now: DateTime<Utc>) -> Result<PathBuf, String> {
+    let manifest_path = root.join(format!("gc-{}.json", now.timestamp_millis()));
+    let json = serde_json::to_string_pretty(plan).map_err(|e| e.to_string())?;
+    fs::write(&manifest_path, json)
+        .map_err(|e| format!("cannot write {}: {e}", manifest_path.display()))?;
+
+    for b in &plan.deleted {
+        let path = root.join(&b.dir);
+        fs::remove_dir_all(&path).map_err(|e| format!("cannot delete {}: {e}", path.display()))?;
+    }
+
+    let broken: Vec<&str> = plan
+        .kept
+        .iter()
+        .filter(|b| b.kept_because.as_deref() != Some("verification_failed"))
+        .filter(|b| !verify_bundle(&root.join(&b.dir)).valid)
+        .map(|b| b.dir.as_str())
+        .collect();
+    if !broken.is_empty() {
+        return Err(format!(
+            "retained bundle(s) failed verification after gc: {}",
+            broken.join(", ")
+        ));
+    }
+    Ok(manifest_path)
+}
+
+/// CLI entry for `boruna evidence gc`.
+pub fn run(
+    root: &Path,
+    policy: GcPolicy,
+    dry_run: bool,
+    json: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let now = Utc::now();
+    let plan = plan_gc(root, &policy, now, dry_run)?;
+    let manifest_path = if dry_run || plan.deleted.is_empty() {
+        None
+    } else {
+        Some(apply_gc(root, &plan, now)?)
+    };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&plan)?);
+        return Ok(());
+    }
+    let verb = if dry_run { "would delete" } else { "deleted" };
+    for b in &plan.deleted {
+        println!(
+            "{verb}: {} ({}, completed {})",
+            b.dir, b.run_id, b.completed_at
+        );
+    }
+    for b in &plan.kept {
+        if let Some(reason) = &b.kept_because {
+            if reason != "keep_last" && reason != "keep_days" {
+                println!("kept: {} ({reason})", b.dir);
+            }
+        }
+    }
+    println!(
+        "summary: {verb}={} kept={} ignored={}",
+        plan.deleted.len(),
+        plan.kept.len(),
+        plan.ignored.len()
+    );
+    if let Some(path) = manifest_path {
+        println!("deletion manifest: {}", path.display());
+    }
+    Ok(())
+}
+
+/// Does any plaintext file in `bundle` mention `hash`?
+fn references(bundle: &Path, hash: &str) -> bool {
+    if hash.is_empty() {
+        return false;
+    }
+    let mut stack = vec![bundle.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                stack.push(path);
+            } else if path.file_name().is_some_and(|n| n != "manifest.json") {
+                if let Ok(text) = fs::read_to_string(&path) {
+                    if text.contains(hash) {
+                        return true;
+                    }
+                }
+            }
+        }
+    }
+    false
+}
+
+fn dir_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use boruna_orchestrator::audit::{
+        evidence::EvidenceBundleBuilder,
+        log::{AuditEvent, AuditLog},
+    };
+
+    fn make_bundle(root: &Path, run_id: &str, output: &str) -> String {
+        let mut builder = EvidenceBundleBuilder::new(root, run_id, "wf").unwrap();
+        builder.add_workflow_def(r#"{"name":"wf"}"#).unwrap();
+        builder.add_policy(r#"{"default_allow":true}"#).unwrap();
+        builder.add_step_output("s1", "result", output).unwrap();
+        let mut log = AuditLog::new();
+        log.append(AuditEvent::WorkflowCompleted {
+            result_hash: "r".into(),
+            total_duration_ms: 1,
+        });
+        builder.finalize(&log).unwrap().bundle_hash
+    }
+
+    fn policy(keep_last: Option<usize>, keep_days: Option<u64>) -> GcPolicy {
+        GcPolicy {
+            keep_last,
+            keep_days,
+        }
+    }
+
+    fn names(bundles: &[GcBundle]) -> Vec<&str> {
+        let mut v: Vec<&str> = bundles.iter().map(|b| b.dir.as_str()).collect();
+        v.sort();
+        v
+    }
+
+    #[test]
+    fn requires_a_retention_rule() {
+        let root = tempfile::tempdir().unwrap();
+        assert!(plan_gc(root.path(), &policy(None, None), Utc::now(), true).is_err());
+    }
+
+    #[test]
+    fn keep_last_prunes_oldest_and_writes_manifest() {
+        let root = tempfile::tempdir().unwrap();
+        for id in ["r1", "r2", "r3"] {
+            make_bundle(root.path(), id, r#"{"v":1}"#);
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+        let now = Utc::now();
+        let plan = plan_gc(root.path(), &policy(Some(2), None), now, false).unwrap();
+        assert_eq!(names(&plan.deleted), vec!["r1"]);
+        assert_eq!(names(&plan.kept), vec!["r2", "r3"]);
+
+        let manifest = apply_gc(root.path(), &plan, now).unwrap();
+        assert!(!root.path().join("r1").exists());
+        let recorded: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(manifest).unwrap()).unwrap();
+        assert_eq!(recorded["deleted"][0]["run_id"], "r1");
+    }
+
+    #[test]
+    fn keep_days_retains_recent_bundles() {
+        let root = tempfile::tempdir().unwrap();
+        make_bundle(root.path(), "r1", "{}");
+        let plan = plan_gc(root.path(), &policy(None, Some(7)), Utc::now(), true).unwrap();
+        assert!(plan.deleted.is_empty());
+
+        let later = Utc::now() + chrono::Duration::days(8);
+        let plan = plan_gc(root.path(), &policy(None, Some(7)), later, true).unwrap();
+        assert_eq!(names(&plan.deleted), vec!["r1"]);
+    }
+
+    #[test]
+    fn out_of_range_keep_days_is_rejected() {
+        let root = tempfile::tempdir().unwrap();
+        let err =
+            plan_gc(root.path(), &policy(None, Some(u64::MAX)), Utc::now(), true).unwrap_err();
+        assert!(err.contains("out of range"), "got: {err}");
+        let err = plan_gc(root.path(), &policy(None, Some(1 << 40)), Utc::now(), true).unwrap_err();
+        assert!(err.contains("out of range"), "got: {err}");
+    }
+
+    #[test]
+    fn bundles_of_unknown_age_survive_keep_last() {
+        let root = tempfile::tempdir().unwrap();
+        for id in ["r1", "r2"] {
+            make_bundle(root.path(), id, "{}");
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+        let manifest_path = root.path().join("r1/manifest.json");
+        let manifest = fs::read_to_string(&manifest_path).unwrap();
+        let mut manifest: serde_json::Value = serde_json::from_str(&manifest).unwrap();
+        manifest["completed_at"] = "not a timestamp".into();
+        fs::write(&manifest_path, manifest.to_string()).unwrap();
+
+        let plan = plan_gc(root.path(), &policy(Some(1), None), Utc::now(), true).unwrap();
+        assert!(plan.deleted.is_empty());
+        let reason = |dir: &str| {
+            plan.kept
+                .iter()
+                .find(|b| b.dir == dir)
+                .and_then(|b| b.kept_because.clone())
+                .unwrap()
+        };
+        // Kept for its age before verification is even considered, and
+        // without taking the single keep_last slot from r2.
+        assert_eq!(reason("r1"), "unknown_age");
+        assert_eq!(reason("r2"), "keep_last");
+    }
+
+    #[test]
+    fn referenced_and_invalid_bundles_are_kept() {
+        let root = tempfile::tempdir().unwrap();
+        let parent_hash = make_bundle(root.path(), "parent", "{}");
+        make_bundle(root.path(), "tampered", "{}");
+        fs::write(
+            root.path().join("tampered/outputs/s1/result.json"),
+            r#"{"x":1}"#,
+        )
+        .unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        make_bundle(
+            root.path(),
+            "child",
+            &format!(r#"{{"source_bundle":"{parent_hash}"}}"#),
+        );
+
+        let plan = plan_gc(root.path(), &policy(Some(1), None), Utc::now(), true).unwrap();
+        assert!(
+            plan.deleted.is_empty(),
+            "deleted: {:?}",
+            names(&plan.deleted)
+        );
+        let reason = |dir: &str| {
+            plan.kept
+                .iter()
+                .find(|b| b.dir == dir)
+                .and_then(|b| b.kept_because.clone())
+                .unwrap()
+        };
+        assert_eq!(reason("child"), "keep_last");
+        assert_eq!(reason("parent"), "referenced_by:child");
+        assert_eq!(reason("tampered"), "verification_failed");
+    }
+}
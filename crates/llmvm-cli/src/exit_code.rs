@@ -0,0 +1,105 @@
+//! `boruna`'s exit codes, so scripts can branch on the class of failure
+//! without parsing stderr. See `docs/reference/exit-codes.md`.
+//!
+//! Failures with a typed error are classified by its stable kind (see
+//! `boruna-errors`); commands that check something (`fmt --check`,
+//! `evidence verify`, test suites, ...) exit [`CHECK_FAILED`] when the
+//! check ran and did not pass.
+
+/// Any failure without a more specific class (I/O, unclassified errors).
+pub const FAILURE: i32 = 1;
+/// The input is invalid: a compile error, a policy or workflow definition
+/// that fails validation, or a usage error reported by the argument
+/// parser (which also exits 2).
+pub const INVALID_INPUT: i32 = 2;
+/// The policy denied a capability, module or data flow.
+pub const POLICY_DENIED: i32 = 3;
+/// A step, time, cycle, capability-budget or stack limit was exceeded.
+pub const LIMIT_EXCEEDED: i32 = 4;
+/// A replay or repeated run produced different results.
+pub const REPLAY_DIVERGED: i32 = 5;
+/// A check or gate ran to completion and failed.
+pub const CHECK_FAILED: i32 = 6;
+/// The program failed at run time for any other reason.
+pub const RUNTIME_ERROR: i32 = 7;
+
+/// The exit code for an error of the given `boruna-errors` kind.
+pub fn for_kind(kind: &str) -> i32 {
+    match kind {
+        "vm.capability_denied"
+        | "vm.untrusted_module"
+        | "vm.capability_skew"
+        | "framework.policy_violation"
+        | "workflow.data_flow_violation" => POLICY_DENIED,
+        "vm.stack_overflow"
+        | "vm.step_limit_exceeded"
+        | "vm.wall_time_exceeded"
+        | "vm.capability_budget_exceeded"
+        | "vm.budget_exhausted"
+        | "vm.max_rounds_exceeded"
+        | "framework.max_cycles_exceeded"
+        | "framework.session_limit" => LIMIT_EXCEEDED,
+        "framework.validation"
+        | "framework.missing_function"
+        | "framework.purity_violation"
+        | "framework.wrong_arity"
+        | "framework.missing_type"
+        | "workflow.missing_schema_version"
+        | "workflow.unsupported_schema_version"
+        | "workflow.invalid_json"
+        | "workflow.validation"
+        | "config.invalid" => INVALID_INPUT,
+        "framework.effect"
+        | "framework.state"
+        | "workflow.step_failed"
+        | "workflow.schema_violation" => RUNTIME_ERROR,
+        k if k.starts_with("compile.") => INVALID_INPUT,
+        k if k.starts_with("vm.") => RUNTIME_ERROR,
+        _ => FAILURE,
+    }
+}
+
+/// The exit code for an error returned by a command.
+pub fn for_error(e: &(dyn std::error::Error + 'static)) -> i32 {
+    for_kind(&crate::error_envelope(e).kind)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_registered_kind_has_a_deliberate_class() {
+        for code in boruna_errors::REGISTRY {
+            let class = for_kind(code.kind);
+            let domain = code.kind.split('.').next().unwrap();
+            match domain {
+                "compile" => assert_eq!(class, INVALID_INPUT, "{}", code.kind),
+                "vm" => assert_ne!(class, FAILURE, "{}", code.kind),
+                _ => {}
+            }
+        }
+        assert_eq!(for_kind("vm.division_by_zero"), RUNTIME_ERROR);
+        assert_eq!(for_kind("vm.capability_denied"), POLICY_DENIED);
+        assert_eq!(for_kind("vm.step_limit_exceeded"), LIMIT_EXCEEDED);
+        assert_eq!(for_kind("unclassified"), FAILURE);
+    }
+
+    #[test]
+    fn every_exit_code_is_explained() {
+        use boruna_tooling::diagnostics::explain::explain;
+        for code in [
+            0,
+            FAILURE,
+            INVALID_INPUT,
+            POLICY_DENIED,
+            LIMIT_EXCEEDED,
+            REPLAY_DIVERGED,
+            CHECK_FAILED,
+            RUNTIME_ERROR,
+        ] {
+            assert!(explain(&format!("exit:{code}")).is_some(), "exit:{code}");
+        }
+        assert!(explain(&format!("exit:{}", RUNTIME_ERROR + 1)).is_none());
+    }
+}
@@ -0,0 +1,504 @@
+//! `boruna framework export` — self-contained static bundle for an app.
+//!
+//! Compiles a framework app, checks it boots (`init()` runs and the App
+//! protocol functions exist) and that the shim implements every opcode it
+//! uses, and writes a directory that runs entirely client-side:
+//!
+//! - `index.html` — message form, rendered view, and state panel
+//! - `boruna-shim.js` — JS bytecode interpreter + mock capability gateway
+//! - `app.js` — the compiled module, embedded as a JS constant so the
+//!   bundle also works from `file://` (no fetch)
+//! - `manifest.json` — module name plus SHA-256 of the source and bytecode
+//!
+//! The shim mirrors the VM's opcode semantics for a subset of the
+//! instruction set. Modules that use anything else (actors, Decimal,
+//! BigInt, JSON, regex, Table) are refused here rather than failing in the
+//! browser.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use boruna_hash::sha256_hex;
+use serde::Serialize;
+
+use boruna_framework::AppRuntime;
+
+const SHIM_JS: &str = include_str!("export_shim.js");
+
+/// Opcodes the shim's dispatch switch interprets. `check_shim_support`
+/// refuses modules using anything else, and the emitted shim gets the same
+/// list so it refuses them too; a test keeps it in step with the switch.
+const SHIM_OPCODES: &[&str] = &[
+    "PushConst",
+    "LoadLocal",
+    "StoreLocal",
+    "LoadGlobal",
+    "StoreGlobal",
+    "Call",
+    "CallIndirect",
+    "Ret",
+    "Jmp",
+    "JmpIf",
+    "JmpIfNot",
+    "Match",
+    "MakeRecord",
+    "MakeEnum",
+    "GetField",
+    "Assert",
+    "GuardSeal",
+    "CapCall",
+    "Add",
+    "Sub",
+    "Mul",
+    "Div",
+    "Mod",
+    "Neg",
+    "Eq",
+    "Neq",
+    "Lt",
+    "Lte",
+    "Gt",
+    "Gte",
+    "Not",
+    "And",
+    "Or",
+    "Concat",
+    "Pop",
+    "Dup",
+    "EmitUi",
+    "MakeList",
+    "ListLen",
+    "ListLenBuiltin",
+    "ListGet",
+    "ListPush",
+    "ListAppend",
+    "ListIsEmpty",
+    "ListHead",
+    "ListTail",
+    "ListConcat",
+    "ListReverse",
+    "ParseInt",
+    "TryParseInt",
+    "IntParse",
+    "FloatParse",
+    "IntToString",
+    "FloatToString",
+    "BoolToString",
+    "StringLen",
+    "StringChars",
+    "StrContains",
+    "StringContains",
+    "StrStartsWith",
+    "StringStartsWith",
+    "StringEndsWith",
+    "StringToUpper",
+    "StringToLower",
+    "StringTrim",
+    "StringJoin",
+    "StringSplit",
+    "StringReplace",
+    "StringSlice",
+    "MapGet",
+    "MapSet",
+    "MapRemove",
+    "MapContainsKey",
+    "MapKeys",
+    "MapValues",
+    "MapLen",
+    "Debug",
+    "DebugMsg",
+    "Nop",
+    "Halt",
+];
+
+#[derive(Debug, Serialize)]
+pub struct ExportManifest {
+    pub module: String,
+    pub source_sha256: String,
+    pub bytecode_sha256: String,
+    pub shim: &'static str,
+    pub files: Vec<String>,
+}
+
+/// Compile `source` and write the static bundle into `out_dir`.
+pub fn export_app(
+    name: &str,
+    source: &str,
+    out_dir: &Path,
+) -> Result<ExportManifest, Box<dyn std::error::Error>> {
+    let module = boruna_compiler::compile(name, source)?;
+    // Boot once natively so a bundle is never emitted for an app whose
+    // init() traps or that is missing init/update/view.
+    AppRuntime::new(module.clone())?;
+    check_shim_support(&module)?;
+
+    let module_json = serde_json::to_string(&module)?;
+    fs::create_dir_all(out_dir)?;
+
+    let files = [
+        ("index.html", index_html(name)),
+        ("boruna-shim.js", shim_js()),
+        (
+            "app.js",
+            format!("\"use strict\";\nconst BORUNA_MODULE = {module_json};\n"),
+        ),
+    ];
+    for (file, contents) in &files {
+        fs::write(out_dir.join(file), contents)?;
+    }
+
+    let manifest = ExportManifest {
+        module: module.name.clone(),
+        source_sha256: sha256_hex(source.as_bytes()),
+        bytecode_sha256: sha256_hex(module_json.as_bytes()),
+        shim: "js",
+        files: files.iter().map(|(f, _)| f.to_string()).collect(),
+    };
+    fs::write(
+        out_dir.join("manifest.json"),
+        serde_json::to_string_pretty(&manifest)?,
+    )?;
+    Ok(manifest)
+}
+
+/// The shim source with `SHIM_OPCODES` filled in.
+fn shim_js() -> String {
+    let opcodes = serde_json::to_string(SHIM_OPCODES).expect("a string list serializes");
+    SHIM_JS.replacen("/* SHIM_OPCODES */ []", &opcodes, 1)
+}
+
+/// Refuse `module` if any function uses an opcode the shim lacks.
+fn check_shim_support(module: &boruna_bytecode::Module) -> Result<(), String> {
+    let supported: BTreeSet<&str> = SHIM_OPCODES.iter().copied().collect();
+    let mut missing: BTreeMap<String, BTreeSet<&str>> = BTreeMap::new();
+    for func in &module.functions {
+        for op in &func.code {
+            let name = match serde_json::to_value(op) {
+                Ok(serde_json::Value::String(name)) => name,
+                Ok(serde_json::Value::Object(map)) => {
+                    map.keys().next().cloned().unwrap_or_default()
+                }
+                _ => continue,
+            };
+            if !supported.contains(name.as_str()) {
+                missing.entry(name).or_default().insert(&func.name);
+            }
+        }
+    }
+    if missing.is_empty() {
+        return Ok(());
+    }
+    let detail: Vec<String> = missing
+        .iter()
+        .map(|(op, funcs)| {
+            let funcs: Vec<&str> = funcs.iter().copied().collect();
+            format!("{op} (in {})", funcs.join(", "))
+        })
+        .collect();
+    Err(format!(
+        "cannot export: the static bundle shim does not support {}",
+        detail.join("; ")
+    ))
+}
+
+/// CLI entry: export `file` into `out`, printing a short summary.
+pub fn run(file: &PathBuf, out: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let source = fs::read_to_string(file)?;
+    let name = file
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("app")
+        .to_string();
+    let manifest = export_app(&name, &source, out)?;
+    println!("exported '{}' to {}", manifest.module, out.display());
+    for f in &manifest.files {
+        println!("  {f}");
+    }
+    println!("  manifest.json");
+    println!("  bytecode sha256: {}", manifest.bytecode_sha256);
+    Ok(())
+}
+
+fn index_html(name: &str) -> String {
+    let title = html_escape(name);
+    format!(
+        r#"<!doctype html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>{title} — Boruna</title>
+<style>
+body {{ font-family: system-ui, sans-serif; margin: 2rem; }}
+.boruna-node {{ margin-left: 1rem; }}
+.boruna-node[data-tag]::before {{ content: attr(data-tag); color: #888; font-size: 0.8em; }}
+#state {{ background: #f4f4f4; padding: 1rem; }}
+#error {{ color: #b00; }}
+</style>
+</head>
+<body>
+<main id="app">
+<h1>{title}</h1>
+<form id="send">
+<input id="msg-tag" placeholder="message tag" required>
+<input id="msg-payload" placeholder="payload" value="0">
+<button type="submit">send</button>
+</form>
+<p id="error"></p>
+<section id="view"></section>
+<pre id="state"></pre>
+</main>
+<script src="boruna-shim.js"></script>
+<script src="app.js"></script>
+<script>BorunaShim.mount(document.getElementById("app"), BORUNA_MODULE);</script>
+</body>
+</html>
+"#
+    )
+}
+
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COUNTER: &str = r#"
+type State { count: Int }
+type Msg { tag: String, payload: Int }
+type Effect { kind: String, payload: String, callback_tag: String }
+type UpdateResult { state: State, effects: List<Effect> }
+type UINode { tag: String, text: String }
+
+fn init() -> State { State { count: 0 } }
+
+fn update(state: State, msg: Msg) -> UpdateResult {
+    UpdateResult { state: State { count: state.count + msg.payload }, effects: [] }
+}
+
+fn view(state: State) -> UINode { UINode { tag: "text", text: "count" } }
+"#;
+
+    #[test]
+    fn export_writes_self_contained_bundle() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest = export_app("counter", COUNTER, dir.path()).unwrap();
+        assert_eq!(manifest.module, "counter");
+        for f in ["index.html", "boruna-shim.js", "app.js", "manifest.json"] {
+            assert!(dir.path().join(f).exists(), "missing {f}");
+        }
+        let app_js = fs::read_to_string(dir.path().join("app.js")).unwrap();
+        assert!(app_js.contains("const BORUNA_MODULE = {"));
+        let html = fs::read_to_string(dir.path().join("index.html")).unwrap();
+        assert!(html.contains("BorunaShim.mount"));
+        assert!(!html.contains("http://") && !html.contains("https://"));
+    }
+
+    #[test]
+    fn export_is_deterministic() {
+        let a = tempfile::tempdir().unwrap();
+        let b = tempfile::tempdir().unwrap();
+        let ma = export_app("counter", COUNTER, a.path()).unwrap();
+        let mb = export_app("counter", COUNTER, b.path()).unwrap();
+        assert_eq!(ma.bytecode_sha256, mb.bytecode_sha256);
+        assert_eq!(
+            fs::read(a.path().join("app.js")).unwrap(),
+            fs::read(b.path().join("app.js")).unwrap()
+        );
+    }
+
+    #[test]
+    fn export_rejects_non_app() {
+        let dir = tempfile::tempdir().unwrap();
+        let err = export_app("plain", "fn main() -> Int { 1 }", dir.path()).unwrap_err();
+        assert!(err.to_string().contains("init"), "got: {err}");
+        assert!(!dir.path().join("index.html").exists());
+    }
+
+    #[test]
+    fn export_refuses_opcodes_the_shim_lacks() {
+        let source = COUNTER.replace("text: \"count\"", "text: json_stringify(state.count)");
+        let dir = tempfile::tempdir().unwrap();
+        let err = export_app("json_view", &source, dir.path()).unwrap_err();
+        assert!(
+            err.to_string().contains("JsonStringify (in view)"),
+            "got: {err}"
+        );
+        assert!(!dir.path().join("index.html").exists());
+    }
+
+    #[test]
+    fn shim_opcodes_match_its_dispatch_switch() {
+        let dispatch = SHIM_JS.split_once("switch (name) {").unwrap().1;
+        let cases: BTreeSet<&str> = dispatch
+            .split("case \"")
+            .skip(1)
+            .filter_map(|s| s.split_once('"').map(|(op, _)| op))
+            .collect();
+        let listed: BTreeSet<&str> = SHIM_OPCODES.iter().copied().collect();
+        assert_eq!(listed.len(), SHIM_OPCODES.len(), "duplicate opcode");
+        assert_eq!(listed, cases);
+        // Every listed name is a real opcode.
+        for op in SHIM_OPCODES {
+            if let Err(e) = serde_json::from_value::<boruna_bytecode::Op>(serde_json::json!(op)) {
+                assert!(!e.to_string().contains("unknown variant"), "{e}");
+            }
+        }
+        assert!(shim_js().contains(r#"new Set(["PushConst","LoadLocal","#));
+    }
+
+    /// Run `script` with Node.js against the bundle in `dir`, where the
+    /// shim is `Shim` and the module `MODULE`. `None` when Node.js is not
+    /// installed.
+    fn run_shim(dir: &Path, script: &str) -> Option<Result<String, String>> {
+        let prelude = format!(
+            "const fs = require('fs');\n\
+             const Shim = require({shim:?});\n\
+             const MODULE = new Function(fs.readFileSync({app:?}, 'utf8') + '; return BORUNA_MODULE;')();\n",
+            shim = dir.join("boruna-shim.js").display().to_string(),
+            app = dir.join("app.js").display().to_string(),
+        );
+        let out = std::process::Command::new("node")
+            .arg("-e")
+            .arg(prelude + script)
+            .output()
+            .ok()?;
+        let stdout = String::from_utf8_lossy(&out.stdout).trim().to_string();
+        Some(if out.status.success() {
+            Ok(stdout)
+        } else {
+            Err(String::from_utf8_lossy(&out.stderr).to_string())
+        })
+    }
+
+    const NOTES_APP: &str = r#"
+type State { notes: List<String>, count: Int, flagged: Int }
+type Msg { tag: String, payload: String }
+type Effect { kind: String, payload: String, callback_tag: String }
+type UpdateResult { state: State, effects: List<Effect> }
+type UINode { tag: String, text: String }
+
+fn init() -> State { State { notes: [], count: 0, flagged: 0 } }
+
+fn update(state: State, msg: Msg) -> UpdateResult {
+    if msg.tag == "add" {
+        let notes: List<String> = list_push(state.notes, msg.payload)
+        let flag: Int = if str_contains(msg.payload, "!") { 1 } else { 0 }
+        UpdateResult {
+            state: State { notes: notes, count: list_len(notes), flagged: state.flagged + flag },
+            effects: [],
+        }
+    } else {
+        UpdateResult { state: state, effects: [] }
+    }
+}
+
+fn view(state: State) -> UINode { UINode { tag: "notes", text: list_get(state.notes, 0) } }
+"#;
+
+    #[test]
+    fn export_shim_runs_the_app_like_the_runtime() {
+        use boruna_bytecode::Value;
+        use boruna_framework::runtime::AppMessage;
+
+        let dir = tempfile::tempdir().unwrap();
+        export_app("notes", NOTES_APP, dir.path()).unwrap();
+        let script = r#"
+const app = new Shim.App(MODULE);
+for (const note of ["first", "urgent!", "later"]) app.send("add", { String: note });
+app.send("ignored", { String: "" });
+console.log(JSON.stringify({ state: app.state, view: app.view(), cycle: app.cycle }));
+"#;
+        let Some(result) = run_shim(dir.path(), script) else {
+            eprintln!("skipping: node is not installed");
+            return;
+        };
+        let shim: serde_json::Value = serde_json::from_str(&result.unwrap()).unwrap();
+
+        let module = boruna_compiler::compile("notes", NOTES_APP).unwrap();
+        let mut runtime = AppRuntime::new(module).unwrap();
+        for note in ["first", "urgent!", "later"] {
+            runtime
+                .send(AppMessage::new("add", Value::String(note.into())))
+                .unwrap();
+        }
+        runtime
+            .send(AppMessage::new("ignored", Value::String(String::new())))
+            .unwrap();
+        assert_eq!(
+            shim["state"],
+            serde_json::to_value(runtime.state()).unwrap()
+        );
+        assert_eq!(
+            shim["view"],
+            serde_json::to_value(runtime.view().unwrap()).unwrap()
+        );
+        assert_eq!(shim["cycle"], 4);
+    }
+
+    const ENUM_MSG_APP: &str = r#"
+enum Msg { Increment, SetLabel(String) }
+type State { count: Int, label: String }
+type Effect { kind: String, payload: String, callback_tag: String }
+type UpdateResult { state: State, effects: List<Effect> }
+type UINode { tag: String, text: String }
+
+fn init() -> State { State { count: 0, label: "counter" } }
+
+fn update(state: State, msg: Msg) -> UpdateResult {
+    match msg {
+        Increment => UpdateResult { state: State { ..state, count: state.count + 1 }, effects: [] }
+        SetLabel(label) => UpdateResult { state: State { ..state, label: label }, effects: [] }
+    }
+}
+
+fn view(state: State) -> UINode { UINode { tag: "text", text: state.label } }
+"#;
+
+    #[test]
+    fn export_shim_sends_enum_messages_like_the_runtime() {
+        use boruna_bytecode::Value;
+        use boruna_framework::runtime::AppMessage;
+
+        let dir = tempfile::tempdir().unwrap();
+        export_app("enum_msg", ENUM_MSG_APP, dir.path()).unwrap();
+        let script = r#"
+const app = new Shim.App(MODULE);
+app.send("Increment", { Int: 0 });
+app.send("SetLabel", { String: "clicks" });
+let rejected = "";
+try { app.send("Reset", "Unit"); } catch (e) { rejected = e.message; }
+console.log(JSON.stringify({ state: app.state, rejected }));
+"#;
+        let Some(result) = run_shim(dir.path(), script) else {
+            eprintln!("skipping: node is not installed");
+            return;
+        };
+        let shim: serde_json::Value = serde_json::from_str(&result.unwrap()).unwrap();
+
+        let module = boruna_compiler::compile("enum_msg", ENUM_MSG_APP).unwrap();
+        let mut runtime = AppRuntime::new(module).unwrap();
+        runtime
+            .send(AppMessage::new("Increment", Value::Int(0)))
+            .unwrap();
+        runtime
+            .send(AppMessage::new("SetLabel", Value::String("clicks".into())))
+            .unwrap();
+        assert_eq!(
+            shim["state"],
+            serde_json::to_value(runtime.state()).unwrap()
+        );
+        assert!(
+            shim["rejected"]
+                .as_str()
+                .unwrap()
+                .contains("not a variant of enum Msg"),
+            "{shim}"
+        );
+    }
+}
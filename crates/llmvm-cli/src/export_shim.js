@@ -0,0 +1,405 @@
+// Boruna static-bundle interpreter shim.
+//
+// Emitted by `boruna framework export`. Interprets the bundled bytecode
+// module (serde JSON form of `boruna_bytecode::Module`) entirely in the
+// browser and drives the App protocol: init() -> update(state, msg) ->
+// view(state). Capability calls go to a mock gateway that returns the same
+// deterministic stubs as the Rust `MockEffectExecutor`; nothing leaves the
+// page.
+//
+// Values keep their serde JSON shape ({"Int": 1}, "Unit", {"Record": ...})
+// so a state snapshot here is byte-comparable with `framework diag` output.
+// Ints are JS numbers: values beyond 2^53 lose precision in this shim.
+"use strict";
+
+const BorunaShim = (() => {
+  const MAX_STEPS = 10000000;
+  const MAX_CALL_DEPTH = 1024;
+  const MOCK_RESULT = { String: "mock_result" };
+  // Filled in by `boruna framework export` from the same list it checks
+  // modules against: exactly the opcodes the dispatch switch below handles.
+  const OPCODES = new Set(/* SHIM_OPCODES */ []);
+
+  class ShimError extends Error {}
+
+  function tag(v) {
+    return typeof v === "string" ? v : Object.keys(v)[0];
+  }
+  function inner(v) {
+    return typeof v === "string" ? undefined : v[tag(v)];
+  }
+  function truthy(v) {
+    switch (tag(v)) {
+      case "Unit": case "None": case "Err": return false;
+      case "Bool": return v.Bool;
+      case "Int": case "Float": return inner(v) !== 0;
+      case "String": return v.String.length > 0;
+      case "List": return v.List.length > 0;
+      case "Map": return Object.keys(v.Map).length > 0;
+      default: return true;
+    }
+  }
+  function eq(a, b) {
+    return JSON.stringify(a) === JSON.stringify(b);
+  }
+  function expect(v, t) {
+    if (tag(v) !== t) throw new ShimError(`type error: expected ${t}, got ${tag(v)}`);
+    return inner(v);
+  }
+  // List literals compile to Record{type_id: 0xFFFF}; treat both as lists.
+  function listItems(v) {
+    if (tag(v) === "List") return v.List;
+    if (tag(v) === "Record" && v.Record.type_id === 0xffff) return v.Record.fields;
+    throw new ShimError(`type error: expected List, got ${tag(v)}`);
+  }
+  function num(v) {
+    const t = tag(v);
+    if (t !== "Int" && t !== "Float") throw new ShimError(`type error: expected numeric, got ${t}`);
+    return inner(v);
+  }
+  function arith(a, b, f, name) {
+    if (tag(a) === "Int" && tag(b) === "Int") {
+      const r = f(a.Int, b.Int);
+      if (!Number.isSafeInteger(r)) throw new ShimError(`arithmetic overflow: ${name}`);
+      return { Int: r };
+    }
+    return { Float: f(num(a), num(b)) };
+  }
+  function compare(a, b) {
+    const ta = tag(a);
+    if (ta !== tag(b) || !["Int", "Float", "String"].includes(ta)) {
+      throw new ShimError(`type error: expected comparable, got ${ta}`);
+    }
+    const x = inner(a), y = inner(b);
+    return x < y ? -1 : x > y ? 1 : 0;
+  }
+  function str(s) { return { String: s }; }
+  function bool(b) { return { Bool: b }; }
+  function option(v) { return v === undefined ? "None" : { Some: v }; }
+
+  class Vm {
+    constructor(module) {
+      this.module = module;
+      for (const f of module.functions) {
+        const op = f.code.map(tag).find((name) => !OPCODES.has(name));
+        if (op) throw new ShimError(`opcode ${op} (in ${f.name}) is not supported by the static bundle shim`);
+      }
+      this.globals = module.globals.map(() => "Unit");
+      this.gatewayLog = [];
+    }
+
+    callByName(name, args) {
+      const idx = this.module.functions.findIndex((f) => f.name === name);
+      if (idx < 0) throw new ShimError(`missing required function: ${name}`);
+      return this.run(idx, args);
+    }
+
+    run(entry, args) {
+      const stack = [];
+      const frames = [];
+      const mod = this.module;
+      const pop = () => {
+        if (stack.length === 0) throw new ShimError("stack underflow");
+        return stack.pop();
+      };
+      const call = (fi, callArgs) => {
+        const f = mod.functions[fi];
+        if (!f) throw new ShimError(`invalid function: ${fi}`);
+        if (frames.length >= MAX_CALL_DEPTH) throw new ShimError("stack overflow");
+        const locals = new Array(f.locals).fill("Unit");
+        callArgs.forEach((a, i) => { if (i < locals.length) locals[i] = a; });
+        frames.push({ fi, ip: 0, base: stack.length, locals });
+      };
+      const popN = (n) => {
+        const out = [];
+        for (let i = 0; i < n; i++) out.push(pop());
+        return out.reverse();
+      };
+
+      call(entry, args);
+      let steps = 0;
+      for (;;) {
+        if (++steps > MAX_STEPS) throw new ShimError(`execution limit exceeded: ${MAX_STEPS}`);
+        const frame = frames[frames.length - 1];
+        if (!frame) return stack.length ? stack.pop() : "Unit";
+        const fn = mod.functions[frame.fi];
+        if (frame.ip >= fn.code.length) {
+          frames.pop();
+          stack.length = frame.base;
+          stack.push("Unit");
+          continue;
+        }
+        const op = fn.code[frame.ip++];
+        const name = tag(op);
+        const arg = inner(op);
+        switch (name) {
+          case "PushConst": stack.push(mod.constants[arg]); break;
+          case "LoadLocal": stack.push(frame.locals[arg]); break;
+          case "StoreLocal": frame.locals[arg] = pop(); break;
+          case "LoadGlobal": stack.push(this.globals[arg]); break;
+          case "StoreGlobal": this.globals[arg] = pop(); break;
+          case "Call": call(arg[0], popN(arg[1])); break;
+          case "CallIndirect": {
+            const callee = expect(pop(), "FnRef");
+            call(callee, popN(arg));
+            break;
+          }
+          case "Ret": {
+            const result = stack.length > frame.base ? pop() : "Unit";
+            frames.pop();
+            stack.length = frame.base;
+            stack.push(result);
+            break;
+          }
+          case "Jmp": frame.ip = arg; break;
+          case "JmpIf": if (truthy(pop())) frame.ip = arg; break;
+          case "JmpIfNot": if (!truthy(pop())) frame.ip = arg; break;
+          case "Match": {
+            const v = pop();
+            const t = tag(v);
+            const key = t === "Enum" ? v.Enum.variant
+              : t === "Bool" ? (v.Bool ? 1 : 0)
+              : t === "None" ? -2 : t === "Some" ? -3
+              : t === "Ok" ? -4 : t === "Err" ? -5 : -1;
+            const arm = fn.match_tables[arg].find((a) => a.tag === key || a.tag === -1);
+            if (!arm) throw new ShimError("match exhausted");
+            if (t === "Enum") stack.push(v.Enum.payload);
+            else if (t === "Some" || t === "Ok" || t === "Err") stack.push(inner(v));
+            else stack.push(v);
+            frame.ip = arm.target;
+            break;
+          }
+          case "MakeRecord": stack.push({ Record: { type_id: arg[0], fields: popN(arg[1]) } }); break;
+          case "MakeEnum": stack.push({ Enum: { type_id: arg[0], variant: arg[1], payload: pop() } }); break;
+          case "GetField": {
+            const fields = expect(pop(), "Record").fields;
+            if (arg >= fields.length) throw new ShimError("type error: field index out of bounds");
+            stack.push(fields[arg]);
+            break;
+          }
+          case "Assert": {
+            if (!truthy(pop())) throw new ShimError(`contract violation: ${JSON.stringify(mod.constants[arg.msg])}`);
+            break;
+          }
+          case "GuardSeal": {
+            const label = pop();
+            if (!truthy(pop())) throw new ShimError(`output guard \`${inner(label)}\` failed`);
+            break;
+          }
+          case "CapCall": {
+            const callArgs = popN(arg[1]);
+            this.gatewayLog.push({ cap_id: arg[0], args: callArgs });
+            stack.push(MOCK_RESULT);
+            break;
+          }
+          case "Add": { const b = pop(), a = pop(); stack.push(arith(a, b, (x, y) => x + y, "addition")); break; }
+          case "Sub": { const b = pop(), a = pop(); stack.push(arith(a, b, (x, y) => x - y, "subtraction")); break; }
+          case "Mul": { const b = pop(), a = pop(); stack.push(arith(a, b, (x, y) => x * y, "multiplication")); break; }
+          case "Div": {
+            const b = pop(), a = pop();
+            if (num(b) === 0) throw new ShimError("division by zero");
+            stack.push(tag(a) === "Int" && tag(b) === "Int"
+              ? { Int: Math.trunc(a.Int / b.Int) }
+              : { Float: num(a) / num(b) });
+            break;
+          }
+          case "Mod": {
+            const b = expect(pop(), "Int"), a = expect(pop(), "Int");
+            if (b === 0) throw new ShimError("division by zero");
+            stack.push({ Int: a % b });
+            break;
+          }
+          case "Neg": { const v = pop(); stack.push({ [tag(v)]: -num(v) }); break; }
+          case "Eq": { const b = pop(), a = pop(); stack.push(bool(eq(a, b))); break; }
+          case "Neq": { const b = pop(), a = pop(); stack.push(bool(!eq(a, b))); break; }
+          case "Lt": { const b = pop(), a = pop(); stack.push(bool(compare(a, b) < 0)); break; }
+          case "Lte": { const b = pop(), a = pop(); stack.push(bool(compare(a, b) <= 0)); break; }
+          case "Gt": { const b = pop(), a = pop(); stack.push(bool(compare(a, b) > 0)); break; }
+          case "Gte": { const b = pop(), a = pop(); stack.push(bool(compare(a, b) >= 0)); break; }
+          case "Not": stack.push(bool(!truthy(pop()))); break;
+          case "And": { const b = pop(), a = pop(); stack.push(bool(truthy(a) && truthy(b))); break; }
+          case "Or": { const b = pop(), a = pop(); stack.push(bool(truthy(a) || truthy(b))); break; }
+          case "Concat": { const b = expect(pop(), "String"), a = expect(pop(), "String"); stack.push(str(a + b)); break; }
+          case "Pop": pop(); break;
+          case "Dup": { const v = pop(); stack.push(v, v); break; }
+          case "EmitUi": pop(); break;
+          case "MakeList": stack.push({ List: popN(arg) }); break;
+          case "ListLen": case "ListLenBuiltin": stack.push({ Int: listItems(pop()).length }); break;
+          case "ListGet": {
+            const i = expect(pop(), "Int"), items = listItems(pop());
+            if (i < 0 || i >= items.length) throw new ShimError(`index out of bounds: ${i} (length ${items.length})`);
+            stack.push(items[i]);
+            break;
+          }
+          case "ListPush": case "ListAppend": { const v = pop(); stack.push({ List: [...listItems(pop()), v] }); break; }
+          case "ListIsEmpty": stack.push(bool(listItems(pop()).length === 0)); break;
+          case "ListHead": stack.push(option(listItems(pop())[0])); break;
+          case "ListTail": stack.push({ List: listItems(pop()).slice(1) }); break;
+          case "ListConcat": { const b = listItems(pop()), a = listItems(pop()); stack.push({ List: [...a, ...b] }); break; }
+          case "ListReverse": stack.push({ List: [...listItems(pop())].reverse() }); break;
+          case "ParseInt": { const n = Number.parseInt(expect(pop(), "String").trim(), 10); stack.push({ Int: Number.isNaN(n) ? 0 : n }); break; }
+          case "TryParseInt": {
+            const s = expect(pop(), "String");
+            stack.push(/^[+-]?\d+$/.test(s.trim()) ? { Ok: { Int: Number(s.trim()) } } : { Err: str(`invalid integer: ${s}`) });
+            break;
+          }
+          case "IntParse": { const s = expect(pop(), "String"); stack.push(/^[+-]?\d+$/.test(s) ? { Some: { Int: Number(s) } } : "None"); break; }
+          case "FloatParse": { const n = Number(expect(pop(), "String")); stack.push(Number.isNaN(n) ? "None" : { Some: { Float: n } }); break; }
+          case "IntToString": stack.push(str(String(expect(pop(), "Int")))); break;
+          case "FloatToString": stack.push(str(String(expect(pop(), "Float")))); break;
+          case "BoolToString": stack.push(str(String(expect(pop(), "Bool")))); break;
+          case "StringLen": stack.push({ Int: new TextEncoder().encode(expect(pop(), "String")).length }); break;
+          case "StringChars": stack.push({ List: [...expect(pop(), "String")].map(str) }); break;
+          case "StrContains": case "StringContains": { const n = expect(pop(), "String"), h = expect(pop(), "String"); stack.push(bool(h.includes(n))); break; }
+          case "StrStartsWith": case "StringStartsWith": { const p = expect(pop(), "String"), s = expect(pop(), "String"); stack.push(bool(s.startsWith(p))); break; }
+          case "StringEndsWith": { const p = expect(pop(), "String"), s = expect(pop(), "String"); stack.push(bool(s.endsWith(p))); break; }
+          case "StringToUpper": stack.push(str(expect(pop(), "String").toUpperCase())); break;
+          case "StringToLower": stack.push(str(expect(pop(), "String").toLowerCase())); break;
+          case "StringTrim": stack.push(str(expect(pop(), "String").trim())); break;
+          case "StringJoin": { const sep = expect(pop(), "String"); stack.push(str(listItems(pop()).map((v) => expect(v, "String")).join(sep))); break; }
+          case "StringSplit": {
+            const sep = expect(pop(), "String"), s = expect(pop(), "String");
+            stack.push({ List: (sep === "" ? [...s] : s.split(sep)).map(str) });
+            break;
+          }
+          case "StringReplace": { const to = expect(pop(), "String"), from = expect(pop(), "String"), s = expect(pop(), "String"); stack.push(str(s.split(from).join(to))); break; }
+          case "StringSlice": { const end = expect(pop(), "Int"), start = expect(pop(), "Int"), s = expect(pop(), "String"); stack.push(str(s.slice(start, end))); break; }
+          case "MapGet": { const k = expect(pop(), "String"), m = expect(pop(), "Map"); stack.push(option(m[k])); break; }
+          case "MapSet": { const v = pop(), k = expect(pop(), "String"), m = expect(pop(), "Map"); stack.push({ Map: sortKeys({ ...m, [k]: v }) }); break; }
+          case "MapRemove": { const k = expect(pop(), "String"), m = { ...expect(pop(), "Map") }; delete m[k]; stack.push({ Map: m }); break; }
+          case "MapContainsKey": { const k = expect(pop(), "String"), m = expect(pop(), "Map"); stack.push(bool(k in m)); break; }
+          case "MapKeys": stack.push({ List: Object.keys(expect(pop(), "Map")).sort().map(str) }); break;
+          case "MapValues": { const m = expect(pop(), "Map"); stack.push({ List: Object.keys(m).sort().map((k) => m[k]) }); break; }
+          case "MapLen": stack.push({ Int: Object.keys(expect(pop(), "Map")).length }); break;
+          case "Debug": case "DebugMsg": {
+            const v = pop();
+            if (name === "DebugMsg") console.debug(inner(pop()), v); else console.debug(v);
+            stack.push(v);
+            break;
+          }
+          case "Nop": break;
+          case "Halt": return stack.length ? stack.pop() : "Unit";
+          default:
+            throw new ShimError(`opcode ${name} is not supported by the static bundle shim`);
+        }
+      }
+    }
+  }
+
+  // BTreeMap ordering: keep map keys sorted so JSON snapshots match Rust.
+  function sortKeys(m) {
+    const out = {};
+    for (const k of Object.keys(m).sort()) out[k] = m[k];
+    return out;
+  }
+
+  // Drives init/update/view and routes effects through the mock gateway.
+  class App {
+    constructor(module) {
+      this.vm = new Vm(module);
+      this.state = this.vm.callByName("init", []);
+      this.cycle = 0;
+      this.log = [];
+    }
+
+    send(msgTag, payload) {
+      const before = this.state;
+      const msg = messageValue(this.vm.module.types, msgTag, payload);
+      const result = this.vm.callByName("update", [before, msg]);
+      const fields = expect(result, "Record").fields;
+      if (fields.length < 2) throw new ShimError("update() must return a Record with [state, effects] fields");
+      this.state = fields[0];
+      this.cycle += 1;
+      const effects = parseEffects(fields[1]);
+      this.log.push({ cycle: this.cycle, message: msgTag, state_before: before, state_after: this.state, effects });
+      // Mock gateway: every non-UI effect calls back with a stub result.
+      for (const e of effects) {
+        if (e.kind !== "emit_ui" && e.callback_tag) this.send(e.callback_tag, MOCK_RESULT);
+      }
+      return this.state;
+    }
+
+    view() {
+      return this.vm.callByName("view", [this.state]);
+    }
+  }
+
+  // update()'s argument, built as AppMessage::to_value_in builds it: with
+  // an `enum Msg`, the tag must name a variant, a unit variant drops the
+  // payload and a scalar-typed variant requires a payload of that type;
+  // otherwise a Record of [tag, payload].
+  function messageValue(types, msgTag, payload) {
+    const typeId = (types || []).findIndex((t) => t.name === "Msg" && tag(t.kind) === "Enum");
+    if (typeId < 0) return { Record: { type_id: 0, fields: [str(msgTag), payload] } };
+    const variants = types[typeId].kind.Enum.variants;
+    const variant = variants.findIndex(([name]) => name === msgTag);
+    if (variant < 0) {
+      const names = variants.map(([name]) => name).join(", ");
+      throw new ShimError(`\`${msgTag}\` is not a variant of enum Msg (expected one of: ${names})`);
+    }
+    const ty = variants[variant][1];
+    if (ty !== null && !payloadFits(ty, payload)) {
+      throw new ShimError(`\`${msgTag}\` carries a ${ty} payload, got ${tag(payload)}`);
+    }
+    return { Enum: { type_id: typeId, variant, payload: ty === null ? "Unit" : payload } };
+  }
+  function payloadFits(ty, payload) {
+    const scalars = ["Int", "Float", "String", "Bool", "Decimal", "BigInt"];
+    return scalars.includes(ty) ? tag(payload) === ty : tag(payload) !== "Unit";
+  }
+
+  function parseEffects(v) {
+    let items;
+    try { items = listItems(v); } catch (_) { return []; }
+    return items
+      .filter((i) => tag(i) === "Record" && i.Record.fields.length >= 3)
+      .map((i) => ({
+        kind: inner(i.Record.fields[0]),
+        payload: i.Record.fields[1],
+        callback_tag: tag(i.Record.fields[2]) === "String" ? i.Record.fields[2].String : "",
+      }));
+  }
+
+  // Render a view() value as plain DOM: records become <div>s labelled by
+  // their first String field, scalars become text.
+  function render(v) {
+    const el = document.createElement("div");
+    el.className = "boruna-node";
+    const t = tag(v);
+    if (t === "Record") {
+      const [head, ...rest] = v.Record.fields;
+      if (head && tag(head) === "String") el.dataset.tag = head.String;
+      for (const f of rest) el.appendChild(render(f));
+    } else if (t === "List") {
+      for (const f of v.List) el.appendChild(render(f));
+    } else {
+      el.textContent = t === "Unit" || t === "None" ? "" : String(inner(v));
+    }
+    return el;
+  }
+
+  function mount(root, module) {
+    const app = new App(module);
+    const out = root.querySelector("#view");
+    const state = root.querySelector("#state");
+    const err = root.querySelector("#error");
+    const refresh = () => {
+      out.replaceChildren(render(app.view()));
+      state.textContent = `cycle ${app.cycle}\n${JSON.stringify(app.state, null, 2)}`;
+    };
+    root.querySelector("#send").addEventListener("submit", (ev) => {
+      ev.preventDefault();
+      const msgTag = root.querySelector("#msg-tag").value.trim();
+      const raw = root.querySelector("#msg-payload").value.trim();
+      const payload = /^[+-]?\d+$/.test(raw) ? { Int: Number(raw) } : str(raw);
+      err.textContent = "";
+      try { app.send(msgTag, payload); refresh(); } catch (e) { err.textContent = String(e.message || e); }
+    });
+    refresh();
+    return app;
+  }
+
+  return { App, Vm, ShimError, mount };
+})();
+
+if (typeof module !== "undefined") module.exports = BorunaShim;
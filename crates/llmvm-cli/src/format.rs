@@ -5,11 +5,11 @@
 //! - `boruna fmt <file>` — rewrite `<file>` in place with the canonical
 //!   formatting. Exits 0 on success.
 //! - `boruna fmt --check <file>` — exit 0 if the file is already
-//!   canonically formatted, exit 1 otherwise (prints a short diff
+//!   canonically formatted, exit 6 otherwise (prints a short diff
 //!   summary to stderr). Designed as a CI gate.
 //!
 //! Parse errors are surfaced with exit code 2 so CI can distinguish
-//! "needs formatting" (1) from "broken file" (2).
+//! "needs formatting" (6) from "broken file" (2).
 
 use std::fs;
 use std::path::Path;
@@ -33,7 +33,7 @@ pub fn run_fmt(file: &Path, check: bool) -> Result<(), Box<dyn std::error::Error
                     file.display()
                 ),
             }
-            process::exit(2);
+            process::exit(crate::exit_code::INVALID_INPUT);
         }
     };
 
@@ -52,7 +52,7 @@ pub fn run_fmt(file: &Path, check: bool) -> Result<(), Box<dyn std::error::Error
             eprintln!("    -{src_line}");
             eprintln!("    +{fmt_line}");
         }
-        process::exit(1);
+        process::exit(crate::exit_code::CHECK_FAILED);
     }
 
     if formatted != source {
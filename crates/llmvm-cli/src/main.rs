@@ -2,28 +2,47 @@ use std::fs;
 use std::path::PathBuf;
 use std::process;
 
-use clap::{Parser, Subcommand};
+use boruna_errors::{BorunaError, ErrorEnvelope};
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
 
 use boruna_bytecode::Module;
 use boruna_framework::runtime::AppMessage;
 use boruna_framework::testing::TestHarness;
 use boruna_framework::validate::AppValidator;
 use boruna_tooling::diagnostics::collector::DiagnosticCollector;
+use boruna_tooling::diagnostics::config::DiagnosticsConfig;
+use boruna_tooling::diagnostics::project::ProjectCollector;
+use boruna_tooling::diagnostics::{suggest, DiagnosticSet};
+use boruna_tooling::history;
+use boruna_tooling::repair::calibration::TelemetryStore;
 use boruna_tooling::repair::{RepairStrategy, RepairTool};
 use boruna_tooling::trace2tests;
-use boruna_vm::capability_gateway::{CapabilityGateway, Policy, ReplayHandler};
-use boruna_vm::replay::EventLog;
+use boruna_vm::capability_gateway::{CapabilityGateway, Policy, PolicyMode, ReplayHandler};
+use boruna_vm::kv_store::{KvHandler, KvStore};
+use boruna_vm::notify::{DryRunNotifySender, LiveNotifySender, NotifyHandler, NotifySender};
+use boruna_vm::queue_store::{QueueHandler, QueueStore};
+use boruna_vm::replay::{EventLog, RunOutcome};
+use boruna_vm::table_loader::TableHandler;
+use boruna_vm::trace::TraceSampling;
 use boruna_vm::vm::Vm;
 
+mod callgraph;
+mod capability_prompt;
 mod doctor;
 mod evidence_diff;
+mod evidence_gc;
+mod exit_code;
+mod export;
 mod format;
+mod policy_infer;
 mod provider_registry;
 mod repl;
+mod run_report;
 mod scaffold;
 mod size;
 mod skills;
 mod workflow_eval;
+mod workflow_graph;
 
 #[derive(Parser)]
 #[command(
@@ -56,6 +75,28 @@ enum Command {
         /// Output file path (.axbc). Defaults to same name with .axbc extension.
         #[arg(short, long)]
         output: Option<PathBuf>,
+        /// Sign the module with this ed25519 seed (32 bytes as 64 hex
+        /// chars). Policies with `require_signed_modules` only run
+        /// modules signed by a key in `trusted_module_keys`. Falls back
+        /// to `BORUNA_MODULE_SIGNING_KEY`.
+        #[arg(long, value_name = "HEX")]
+        sign_key: Option<String>,
+        /// Release build: strip `requires`/`ensures` checks from the
+        /// bytecode.
+        #[arg(long)]
+        release: bool,
+        /// Fail if the module exceeds the size and complexity limits in
+        /// this JSON file (max_functions, max_ops_per_function,
+        /// max_constants, max_nesting_depth).
+        #[arg(long, value_name = "FILE")]
+        budget: Option<PathBuf>,
+        /// Compile the source twice in isolation and fail (exit 5) unless
+        /// both builds are byte-identical. Writes no output file.
+        #[arg(long, conflicts_with_all = ["output", "sign_key", "budget"])]
+        verify_deterministic: bool,
+        /// Report a failure as a JSON error envelope on stderr.
+        #[arg(long)]
+        json: bool,
     },
     /// Run a .ax source file or bytecode file.
     Run {
@@ -73,6 +114,13 @@ enum Command {
         /// Use real HTTP handler for net.fetch (requires `http` feature).
         #[arg(long)]
         live: bool,
+        /// Confine the run with an OS sandbox derived from the policy
+        /// (Linux: Landlock and seccomp): files only when `fs.*` or
+        /// `data.read_table` is allowed, network only when `net.fetch`,
+        /// `llm.call` or `notify.send` is. Fails if the kernel cannot
+        /// enforce it.
+        #[arg(long, requires = "live", conflicts_with = "watch")]
+        sandbox: bool,
         /// Record net.fetch transactions to a tape file (requires --live).
         /// Mutually exclusive with --replay-net-from.
         /// See docs/design-net-record-replay.md.
@@ -96,23 +144,96 @@ enum Command {
         /// `docs/guides/llm-integration.md`.
         #[arg(long)]
         providers: Option<PathBuf>,
+        /// Run a `.axbc` compiled against capability contract versions
+        /// that differ from this binary's. By default such modules are
+        /// refused, since the capability may now behave differently.
+        #[arg(long)]
+        allow_capability_skew: bool,
+        /// Fail the run with a typed error when a Float operation yields
+        /// NaN or ±Inf, whose formatting varies across hosts and breaks
+        /// trace-hash stability. Use `Decimal` for money-like values.
+        #[arg(long)]
+        strict_determinism: bool,
+        /// Complete calls started with `cap.spawn` in the order fixed by
+        /// this seed instead of spawn order. The event log records the
+        /// order, so the run replays under the same seed.
+        #[arg(long)]
+        scheduler_seed: Option<u64>,
+        /// Write the capability transcript of a `"mode": "dry_run"`
+        /// policy to this file as JSON.
+        #[arg(long)]
+        transcript: Option<PathBuf>,
+        /// Remember answers to `"prompt": true` policy rules in this file
+        /// and reuse them on later runs. Rules prompt on stderr only when
+        /// stdin is a terminal.
+        #[arg(long)]
+        session_policy: Option<PathBuf>,
+        /// `enforce` traps on a false `requires`/`ensures` clause with
+        /// E013/E014; `off` skips the checks. Bytecode compiled with
+        /// `--release` carries no checks.
+        #[arg(long, value_enum, default_value_t = ContractsMode::Enforce)]
+        contracts: ContractsMode,
+        /// Serve `kv.get`/`kv.set`/`kv.scan` from the store file at this
+        /// path, creating it on first write. Without it the store is
+        /// in-memory and discarded after the run.
+        #[arg(long, value_name = "PATH")]
+        kv: Option<PathBuf>,
+        /// Serve `queue.push`/`queue.poll` from one file per queue in
+        /// this directory, so later runs can poll what this run pushed.
+        /// Without it queues are in-memory and discarded after the run.
+        #[arg(long, value_name = "DIR")]
+        queue_dir: Option<PathBuf>,
+        /// Report a failure as a JSON error envelope on stderr.
+        #[arg(long)]
+        json: bool,
     },
     /// Run with execution tracing enabled.
     Trace {
         /// File path (.ax or .axbc)
         file: PathBuf,
+        /// Record only every Nth step.
+        #[arg(long, value_name = "N", conflicts_with = "boundaries")]
+        every: Option<u64>,
+        /// Record only function calls and returns, and capability calls.
+        #[arg(long)]
+        boundaries: bool,
+        /// Keep only the last N recorded entries.
+        #[arg(long, value_name = "N")]
+        max_entries: Option<usize>,
+        /// Print the summary without the trace entries.
+        #[arg(long)]
+        summary: bool,
+        /// Functions listed in the summary.
+        #[arg(long, value_name = "N", default_value = "10")]
+        top: usize,
     },
     /// Replay execution from a recorded event log.
+    #[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
     Replay {
+        #[command(subcommand)]
+        command: Option<ReplayCommand>,
         /// Bytecode file (.axbc)
-        file: PathBuf,
+        #[arg(required = true)]
+        file: Option<PathBuf>,
         /// Event log file (JSON)
-        log: PathBuf,
+        #[arg(required = true)]
+        log: Option<PathBuf>,
+        /// Scheduler seed the run was recorded with (`run --scheduler-seed`).
+        #[arg(long)]
+        scheduler_seed: Option<u64>,
+        /// Print the result, verification and divergence analysis as one
+        /// JSON document.
+        #[arg(long)]
+        json: bool,
     },
     /// Inspect a bytecode file.
     Inspect {
         /// Bytecode file (.axbc)
         file: PathBuf,
+        /// Print the function call graph, annotated with capabilities and
+        /// step estimates, instead of the disassembly
+        #[arg(long, value_name = "FORMAT")]
+        callgraph: Option<callgraph::CallgraphFormat>,
     },
     /// Dump the AST of a .ax source file.
     Ast {
@@ -122,7 +243,7 @@ enum Command {
     /// Format a .ax source file (canonical pretty-print).
     ///
     /// Default: rewrite the file in place. With `--check`, exit 0 if the
-    /// file is already formatted, exit 1 otherwise (CI gate). Exits 2 on
+    /// file is already formatted, exit 6 otherwise (CI gate). Exits 2 on
     /// parse errors so CI can distinguish "needs formatting" from
     /// "broken file". v1 strips comments — see `docs/design-boruna-fmt.md`.
     Fmt {
@@ -144,6 +265,25 @@ enum Command {
         #[arg(long)]
         json: bool,
     },
+    /// Explain a diagnostic code (`E005`, `W001`) or an exit code
+    /// (`exit:4`): what it means, common causes and how to fix it.
+    Explain {
+        /// Diagnostic code or `exit:<N>`.
+        id: String,
+        /// Output the explanation as JSON.
+        #[arg(long)]
+        json: bool,
+    },
+    /// Layered CLI defaults (`boruna.toml`, user config, environment).
+    #[command(subcommand)]
+    Config(ConfigCommand),
+    /// Print a shell completion script to stdout, e.g.
+    /// `boruna completions bash > /etc/bash_completion.d/boruna`.
+    Completions {
+        /// Target shell.
+        #[arg(value_enum)]
+        shell: clap_complete::Shell,
+    },
     /// Report the bytecode artifact size of a .ax source file.
     Size {
         /// Source file (.ax).
@@ -177,6 +317,10 @@ enum Command {
         /// are non-deterministic by definition.
         #[arg(short, long, default_value = "deny-all")]
         policy: String,
+        /// Record the `:app` session to this trace file, rewritten after
+        /// every `:send` (see `boruna trace2tests generate`).
+        #[arg(long)]
+        record: Option<PathBuf>,
     },
     /// Random property-based simulation of a workflow. Runs the
     /// workflow N times under a user-supplied invariant (and optional
@@ -236,6 +380,9 @@ enum Command {
         /// Allow writing into a non-empty target directory.
         #[arg(long)]
         force: bool,
+        /// Run the template's generated test specs after scaffolding.
+        #[arg(long)]
+        run_tests: bool,
     },
     /// Workflow execution and validation.
     #[command(subcommand)]
@@ -304,6 +451,28 @@ enum PolicyCommand {
         /// Policy file path (.json).
         file: PathBuf,
     },
+    /// Print the JSON Schema (draft 2020-12) that `validate` and every
+    /// `--policy <path>` enforce. Editors and CI linters can validate
+    /// policy files against it before they reach a run.
+    Schema,
+    /// Infer the tightest policy covering a set of recorded runs: the
+    /// capabilities called, the per-run maximum call counts as budgets,
+    /// and the `net.fetch` hosts and methods seen. Reports what the
+    /// baseline policy allowed that the inferred one would deny.
+    Infer {
+        /// Event log recorded with `boruna run --record` (repeatable).
+        #[arg(long = "from-log", required = true)]
+        from_log: Vec<PathBuf>,
+        /// Where to write the inferred policy (.json).
+        #[arg(long)]
+        out: PathBuf,
+        /// Policy to compare against (default: allow-all).
+        #[arg(long)]
+        baseline: Option<PathBuf>,
+        /// Emit the report as JSON instead of text.
+        #[arg(long)]
+        json: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -337,19 +506,44 @@ enum CapabilityCommand {
 
 #[derive(Subcommand)]
 enum TemplateCommand {
-    /// List available templates.
+    /// List local and cached remote templates with their provenance.
     List {
         /// Templates directory (defaults to ./templates).
         #[arg(long, default_value = "templates")]
         dir: PathBuf,
+        /// Remote template cache.
+        #[arg(long, default_value = boruna_tooling::templates::remote::DEFAULT_CACHE_DIR)]
+        cache: PathBuf,
+    },
+    /// Fetch a remote template pack into the local cache.
+    ///
+    /// The source is a git repository URL or a pack index (`.json`, over
+    /// `http(s)://` with the `http` feature, or a local path). Each
+    /// template's integrity hash is checked against the index and
+    /// recorded in the cache.
+    Add {
+        /// Git URL or pack index URL.
+        source: String,
+        /// Remote template cache.
+        #[arg(long, default_value = boruna_tooling::templates::remote::DEFAULT_CACHE_DIR)]
+        cache: PathBuf,
+        /// Trust the pack without asking.
+        #[arg(long)]
+        yes: bool,
     },
     /// Apply a template with arguments.
+    ///
+    /// A template missing from `--dir` is taken from the remote template
+    /// cache after its integrity hash is re-checked.
     Apply {
         /// Template name.
         name: String,
         /// Templates directory (defaults to ./templates).
         #[arg(long, default_value = "templates")]
         dir: PathBuf,
+        /// Remote template cache.
+        #[arg(long, default_value = boruna_tooling::templates::remote::DEFAULT_CACHE_DIR)]
+        cache: PathBuf,
         /// Template arguments as key=value pairs (comma-separated).
         #[arg(long)]
         args: String,
@@ -359,14 +553,70 @@ enum TemplateCommand {
         /// Validate that output compiles.
         #[arg(long)]
         validate: bool,
+        /// Run the test specs generated from the template's `tests/`
+        /// directory and fail unless they all pass.
+        #[arg(long)]
+        run_tests: bool,
+        /// Where pristine output is kept for `template upgrade`.
+        #[arg(long, default_value = boruna_tooling::templates::upgrade::DEFAULT_BASE_DIR)]
+        base_dir: PathBuf,
+    },
+    /// Re-apply a newer template version to a generated file, keeping
+    /// your edits.
+    ///
+    /// The file's stamp comment records the template and args that
+    /// produced it. The new version is three-way merged with your copy;
+    /// hunks both sides changed are written with conflict markers and
+    /// reported as E016 diagnostics.
+    Upgrade {
+        /// Generated source file (.ax) to upgrade in place.
+        file: PathBuf,
+        /// Target template as `<name>@<version>`.
+        #[arg(long, value_name = "NAME@VERSION")]
+        template: String,
+        /// Templates directory (defaults to ./templates).
+        #[arg(long, default_value = "templates")]
+        dir: PathBuf,
+        /// Remote template cache.
+        #[arg(long, default_value = boruna_tooling::templates::remote::DEFAULT_CACHE_DIR)]
+        cache: PathBuf,
+        /// Where pristine output is kept.
+        #[arg(long, default_value = boruna_tooling::templates::upgrade::DEFAULT_BASE_DIR)]
+        base_dir: PathBuf,
+        /// Output conflict diagnostics as JSON.
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigCommand {
+    /// Show the config files consulted and the settings each one sets.
+    /// With `--resolved`, every setting's effective value and where it
+    /// came from. Flags on the command line override all of them.
+    Show {
+        /// Print every setting's effective value and its source.
+        #[arg(long)]
+        resolved: bool,
+        /// Output as JSON.
+        #[arg(long)]
+        json: bool,
     },
 }
 
 #[derive(Subcommand)]
 enum LangCommand {
     /// Check a source file and report diagnostics.
+    ///
+    /// Given a directory or a `package.ax.json`, checks every `.ax` file
+    /// together: calls resolve across files through `export`, and duplicate
+    /// type names and unused exports are reported.
+    ///
+    /// Severity promotion and per-path ignores come from the nearest
+    /// `boruna.diagnostics.json` at or above the checked path, unless
+    /// `--config` names one.
     Check {
-        /// Source file (.ax)
+        /// Source file (.ax), project directory, or package manifest.
         file: PathBuf,
         /// Output diagnostics as JSON.
         #[arg(long)]
@@ -374,6 +624,9 @@ enum LangCommand {
         /// Write JSON diagnostics to this file.
         #[arg(short, long)]
         output: Option<PathBuf>,
+        /// Diagnostics config to use instead of the discovered one.
+        #[arg(long)]
+        config: Option<PathBuf>,
     },
     /// Repair a source file using diagnostic suggestions.
     Repair {
@@ -385,7 +638,27 @@ enum LangCommand {
         /// Strategy: "best" (default), "all", or a specific patch ID.
         #[arg(long, default_value = "best")]
         apply: String,
+        /// Re-check and repair again after each round, up to N rounds,
+        /// until no applicable patch remains.
+        #[arg(long, default_value_t = 1)]
+        max_rounds: usize,
+        /// Record repair outcomes in this telemetry directory and rank
+        /// patches by them. Falls back to $BORUNA_REPAIR_TELEMETRY; off
+        /// when neither is set.
+        #[arg(long)]
+        telemetry: Option<PathBuf>,
+        /// Instead of repairing, record whether the last recorded repair
+        /// of the file was kept.
+        #[arg(long, value_enum)]
+        feedback: Option<RepairFeedback>,
     },
+    /// Revert the last repair or template application recorded in
+    /// `.boruna/history/` of the current directory.
+    ///
+    /// Refuses, changing nothing, when a file was edited since.
+    Undo,
+    /// Re-apply the last change reverted by `lang undo`.
+    Redo,
     /// List the registry of stable diagnostic codes.
     Codes {
         /// Output the registry as JSON.
@@ -393,13 +666,74 @@ enum LangCommand {
         json: bool,
     },
     /// Report each function's declared vs. inferred-needed capabilities and
-    /// flag over-declarations (capabilities granted but never used).
+    /// flag over-declarations (capabilities granted but never used). For
+    /// framework apps, also report which capabilities each message tag can
+    /// reach.
     Caps {
         /// Source file (.ax)
         file: PathBuf,
         /// Output the report as JSON.
         #[arg(long)]
         json: bool,
+        /// Write the tightest policy allowing every capability some
+        /// message can reach (framework apps only).
+        #[arg(long)]
+        emit_policy: Option<PathBuf>,
+    },
+    /// Run the inline tests of a source file: each `//! test: <expr> ==
+    /// <expected>` line is compiled against the file and both sides are
+    /// evaluated and compared.
+    ///
+    /// Exits non-zero unless every test passes. Example:
+    ///   boruna lang test libs/std-testing/src/core.ax --format json
+    Test {
+        /// Source file (.ax).
+        file: PathBuf,
+        /// Report format, as for `trace2tests run-suite`.
+        #[arg(long, value_enum, default_value_t = SuiteFormat::Console)]
+        format: SuiteFormat,
+        /// Write the report here instead of stdout.
+        #[arg(short, long)]
+        out: Option<PathBuf>,
+    },
+    /// Run every `.ax` file in a corpus through each bytecode pipeline
+    /// (direct, binary and JSON round-trip) under the same policy and
+    /// assert identical results and EventLogs. Exits non-zero on any
+    /// divergence; files that do not compile are skipped.
+    Difftest {
+        /// Directory of `.ax` files (searched recursively).
+        corpus_dir: PathBuf,
+        /// Step limit per run.
+        #[arg(long, default_value_t = boruna_tooling::difftest::DEFAULT_MAX_STEPS)]
+        max_steps: u64,
+        /// Output the report as JSON.
+        #[arg(long)]
+        json: bool,
+    },
+    /// Fuzz the lexer and parser with mutated templates, std libs, and
+    /// examples. Each panic is written to `--out` as the crashing input
+    /// plus an E010 DiagnosticSet; exits non-zero if any were found.
+    Fuzz {
+        /// Time budget in minutes.
+        #[arg(long, default_value_t = 1)]
+        minutes: u64,
+        /// Stop after this many inputs (overrides the time budget).
+        #[arg(long)]
+        iterations: Option<u64>,
+        /// PRNG seed; a seed plus `--iterations` reproduces a run.
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+        /// Directory for crash artifacts, created only when something
+        /// crashes. Defaults to `fuzz-crashes/` in the workspace root.
+        #[arg(long)]
+        out: Option<PathBuf>,
+        /// Also write the seed corpus here (e.g. `fuzz/corpus/parse`) for
+        /// the cargo-fuzz targets.
+        #[arg(long, value_name = "DIR")]
+        corpus_out: Option<PathBuf>,
+        /// Output the report as JSON.
+        #[arg(long)]
+        json: bool,
     },
 }
 
@@ -454,6 +788,9 @@ enum Trace2TestsCommand {
         /// Output trace file (JSON).
         #[arg(short, long)]
         out: PathBuf,
+        /// Also store each cycle's view in the trace.
+        #[arg(long)]
+        ui_snapshots: bool,
     },
     /// Generate a test spec from a recorded trace.
     Generate {
@@ -466,6 +803,14 @@ enum Trace2TestsCommand {
         /// Output test spec file (JSON).
         #[arg(short, long)]
         out: PathBuf,
+        /// Also assert per-cycle effect sequences, changed state fields,
+        /// and final UI text, so failures point at the cycle that broke.
+        #[arg(long)]
+        granular: bool,
+        /// Source file (.ax) replayed for `--granular`. If not provided,
+        /// uses source_file from the trace.
+        #[arg(long)]
+        source: Option<PathBuf>,
     },
     /// Run a test spec against source code.
     Run {
@@ -475,6 +820,56 @@ enum Trace2TestsCommand {
         /// Source file (.ax). If not provided, uses source_file from spec.
         #[arg(long)]
         source: Option<PathBuf>,
+        /// Run the spec N times and fail if the trace differs between runs.
+        #[arg(long, default_value_t = 1)]
+        repeat: usize,
+    },
+    /// Run every test spec under a directory, in parallel.
+    ///
+    /// Exits non-zero unless every spec passes. Example:
+    ///   boruna trace2tests run-suite tests/traces --format junit --out junit.xml
+    RunSuite {
+        /// Directory searched recursively for test spec files (JSON).
+        dir: PathBuf,
+        /// Specs run concurrently. Defaults to the number of CPUs.
+        #[arg(long)]
+        jobs: Option<usize>,
+        /// Per-run wall-clock timeout, in seconds.
+        #[arg(long, default_value_t = 60)]
+        timeout_secs: u64,
+        /// Run each spec N times and report specs whose trace differs
+        /// between runs as flaky.
+        #[arg(long, default_value_t = 1)]
+        repeat: usize,
+        /// Report format.
+        #[arg(long, value_enum, default_value_t = SuiteFormat::Console)]
+        format: SuiteFormat,
+        /// Write the report here instead of stdout.
+        #[arg(short, long)]
+        out: Option<PathBuf>,
+    },
+    /// List the test specs a patch bundle could affect.
+    ///
+    /// A spec is affected when the bundle touches a function reachable from
+    /// its app's entry points, through its source or imported libraries.
+    /// Example:
+    ///   boruna trace2tests affected --bundle fix.patchbundle.json --specs tests/traces
+    Affected {
+        /// Patch bundle (JSON).
+        #[arg(long)]
+        bundle: PathBuf,
+        /// Directory searched recursively for test spec files (JSON).
+        #[arg(long)]
+        specs: PathBuf,
+        /// Directory the bundle's file paths are relative to.
+        #[arg(long, default_value = ".")]
+        root: PathBuf,
+        /// Directory holding imported libraries.
+        #[arg(long, default_value = "libs")]
+        libs_dir: PathBuf,
+        /// Output format.
+        #[arg(long, value_enum, default_value_t = AffectedFormat::Paths)]
+        format: AffectedFormat,
     },
     /// Minimize a failing trace using delta debugging.
     Minimize {
@@ -484,15 +879,73 @@ enum Trace2TestsCommand {
         /// Source file (.ax).
         #[arg(long)]
         source: PathBuf,
-        /// Predicate: "panic" (default) or external command.
+        /// Predicate: "panic" (default; any runtime error), "assertion"
+        /// (an `assert`/`fail` trap), or an external command.
         #[arg(long, default_value = "panic")]
         predicate: String,
+        /// Timeout for one external predicate invocation, in seconds. A
+        /// predicate that overruns is killed and counts as unresolved.
+        #[arg(long, default_value_t = 30)]
+        predicate_timeout_secs: u64,
         /// Output minimized trace file.
         #[arg(short, long)]
         out: Option<PathBuf>,
     },
 }
 
+#[derive(Subcommand)]
+enum ReplayCommand {
+    /// Summarize a recorded event log as a one-page report.
+    ///
+    /// Lists capability calls per type with byte counts, network domains,
+    /// policy denials, step count, and the result hash. Logs from `boruna run --record` carry the run's
+    /// outcome; other logs report their calls only.
+    Report {
+        /// Event log file (JSON)
+        log: PathBuf,
+        /// Output format.
+        #[arg(long, value_enum, default_value = "md")]
+        format: ReportFormat,
+    },
+}
+
+/// Contract checking for `run --contracts`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum ContractsMode {
+    Enforce,
+    Off,
+}
+
+/// Output format for `replay report`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum ReportFormat {
+    Md,
+    Json,
+}
+
+/// Verdict for `lang repair --feedback`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum RepairFeedback {
+    Accept,
+    Reject,
+}
+
+/// Report format for `trace2tests run-suite`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum SuiteFormat {
+    Console,
+    Json,
+    Junit,
+}
+
+/// Output format for `trace2tests affected`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum AffectedFormat {
+    /// Affected spec paths, one per line.
+    Paths,
+    Json,
+}
+
 #[derive(Subcommand)]
 enum WorkflowCommand {
     /// Validate a workflow definition directory.
@@ -514,6 +967,30 @@ enum WorkflowCommand {
         /// Capability policy: "allow-all", "deny-all", or a JSON policy file.
         #[arg(short, long, default_value = "allow-all")]
         policy: String,
+        /// Bind a workflow param: `--param NAME=VALUE`, repeatable.
+        /// String params take VALUE verbatim; other types parse it as
+        /// JSON. Overrides the same name in `--params-file`.
+        #[arg(long = "param", value_name = "NAME=VALUE")]
+        params: Vec<String>,
+        /// JSON object of workflow param values, by name.
+        #[arg(long, value_name = "PATH")]
+        params_file: Option<PathBuf>,
+        /// Environment profile: apply `<dir>/profiles/NAME.json` (policy,
+        /// live handlers, params, budget, concurrency). A profile's
+        /// `policy` and `live` take precedence over `--policy`/`--live`;
+        /// `--param` still overrides its params.
+        #[arg(long, value_name = "NAME")]
+        profile: Option<String>,
+        /// Environment variables to fingerprint in the evidence bundle,
+        /// comma-separated or repeated. Only a SHA-256 of each value is
+        /// recorded, never the value.
+        #[arg(
+            long,
+            value_name = "NAME",
+            value_delimiter = ',',
+            env = "BORUNA_FINGERPRINT_ENV"
+        )]
+        fingerprint_env: Vec<String>,
         /// Record evidence bundle for this run.
         #[arg(long)]
         record: bool,
@@ -536,6 +1013,11 @@ enum WorkflowCommand {
         /// `"default"` when omitted.
         #[arg(long, value_name = "ID")]
         bundle_kek_id: Option<String>,
+        /// Algorithm for the evidence bundle's file checksums:
+        /// `sha256` (default) or `blake3`, which is several times faster
+        /// on large step outputs. `evidence verify` reads either.
+        #[arg(long, value_name = "ALG", default_value = "sha256")]
+        checksum_algorithm: boruna_hash::HashAlgorithm,
         /// Use real HTTP handler for net.fetch (requires `http` feature).
         #[arg(long)]
         live: bool,
@@ -622,6 +1104,20 @@ enum WorkflowCommand {
         /// `docs/guides/llm-integration.md`.
         #[arg(long)]
         providers: Option<PathBuf>,
+        /// Image for steps with `"isolation": "container"`; it must
+        /// have `boruna` on its `PATH`. Without it such steps fail
+        /// rather than run in-process.
+        #[arg(long, value_name = "IMAGE", env = "BORUNA_CONTAINER_IMAGE")]
+        container_image: Option<String>,
+        /// Docker-compatible runtime that runs `--container-image`:
+        /// `podman` or `docker`.
+        #[arg(
+            long,
+            value_name = "PROGRAM",
+            default_value = "podman",
+            env = "BORUNA_CONTAINER_RUNTIME"
+        )]
+        container_runtime: String,
     },
     /// Approve a paused approval-gate step. Records an approval sentinel
     /// in the run's metadata; the operator must run `boruna workflow
@@ -732,6 +1228,29 @@ enum WorkflowCommand {
         /// against an operator-supplied expected value).
         #[arg(long, value_name = "HEX")]
         expect_workflow_hash: Option<String>,
+        /// Image for steps with `"isolation": "container"`; it must
+        /// have `boruna` on its `PATH`. Without it such steps fail
+        /// rather than run in-process.
+        #[arg(long, value_name = "IMAGE", env = "BORUNA_CONTAINER_IMAGE")]
+        container_image: Option<String>,
+        /// Docker-compatible runtime that runs `--container-image`:
+        /// `podman` or `docker`.
+        #[arg(
+            long,
+            value_name = "PROGRAM",
+            default_value = "podman",
+            env = "BORUNA_CONTAINER_RUNTIME"
+        )]
+        container_runtime: String,
+    },
+    /// Run one container-isolated step from its job file and write the
+    /// result next to it. Run by the runner inside the step's container.
+    #[command(hide = true)]
+    ExecStep {
+        /// Step job written by the runner.
+        job: PathBuf,
+        /// Where to write the step result.
+        result: PathBuf,
     },
     /// Run a workflow on a cron schedule in a long-running daemon
     /// process. Validates the cron expression on startup (fail fast),
@@ -795,11 +1314,42 @@ enum WorkflowCommand {
         #[arg(long)]
         json: bool,
     },
-    /// Emit the workflow DAG as structured graph facts.
+    /// Emit the workflow DAG as structured graph facts, or render it as
+    /// a Graphviz DOT / Mermaid diagram for docs and dashboards.
+    ///
+    ///   boruna workflow graph ./wf --format mermaid
+    ///   boruna workflow graph ./wf --format dot --run-id <id> | dot -Tsvg
     Graph {
         /// Workflow directory containing workflow.json.
         dir: PathBuf,
-        /// Output graph facts as JSON.
+        /// Output graph facts as JSON. Shorthand for `--format json`.
+        #[arg(long, conflicts_with = "format")]
+        json: bool,
+        /// Output format.
+        #[arg(long, value_enum, default_value = "text")]
+        format: workflow_graph::GraphFormat,
+        /// Color nodes by the step statuses and durations of a recorded
+        /// run (read from `--data-dir`). Applies to `dot` and `mermaid`.
+        #[arg(long)]
+        run_id: Option<String>,
+        /// Persistent data directory holding `runs.db`. Same fallback
+        /// chain as `boruna workflow run`.
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+    },
+    /// Regression gate: re-execute a recorded run against the current
+    /// step sources, replaying the capability results captured in its
+    /// evidence bundle, and check every step's output hash against the
+    /// recorded one. Exits 1 on any mismatch, divergence, or failure.
+    ///
+    ///   boruna workflow verify ./wf --evidence ./wf/evidence/<run-id>
+    Verify {
+        /// Workflow directory containing workflow.json.
+        dir: PathBuf,
+        /// Evidence bundle directory written by `workflow run --record`.
+        #[arg(long)]
+        evidence: PathBuf,
+        /// Output the report as JSON.
         #[arg(long)]
         json: bool,
     },
@@ -908,6 +1458,27 @@ enum EvidenceCommand {
         #[arg(long)]
         json: bool,
     },
+    /// Prune old bundles under an evidence root by retention policy. A
+    /// bundle is deleted only when it is outside both `--keep-last` and
+    /// `--keep-days`, verified cleanly, and is not referenced by any
+    /// retained bundle. Writes a `gc-<unix_ms>.json` deletion manifest
+    /// to the root before deleting and re-verifies retained bundles after.
+    Gc {
+        /// Directory whose immediate subdirectories are bundles.
+        evidence_root: PathBuf,
+        /// Always keep the N most recently completed bundles.
+        #[arg(long, value_name = "N")]
+        keep_last: Option<usize>,
+        /// Always keep bundles completed within the last D days.
+        #[arg(long, value_name = "D")]
+        keep_days: Option<u64>,
+        /// Report what would be deleted without deleting anything.
+        #[arg(long)]
+        dry_run: bool,
+        /// Emit the plan as JSON.
+        #[arg(long)]
+        json: bool,
+    },
     /// Rotate the KEK on one or more encrypted evidence bundles
     /// (post1-T-2.4). Unwraps the DEK with the old KEK, re-wraps it
     /// under the new KEK, and atomically rewrites each bundle's
@@ -966,8 +1537,9 @@ enum EvidenceCommand {
         reason: Option<String>,
     },
     /// Compare two evidence bundles side-by-side (post1-evidence-diff).
-    /// Reports differences in workflow metadata, step outputs, audit event
-    /// counts, and verification status.
+    /// Reports differences in workflow metadata, step output hashes,
+    /// policy, environment fingerprint, audit events (per kind), and
+    /// verification status.
     Diff {
         /// First evidence bundle directory.
         bundle_a: PathBuf,
@@ -1110,6 +1682,10 @@ enum FrameworkCommand {
         /// Messages to send before inspecting.
         #[arg(short, long)]
         messages: Option<String>,
+        /// Print only the result of this query over the state, as JSON
+        /// (e.g. `state.items[0].name`, `state.users[?role=="admin"] | count`).
+        #[arg(short, long)]
+        query: Option<String>,
     },
     /// Simulate a sequence of messages and display state transitions.
     Simulate {
@@ -1149,14 +1725,24 @@ enum FrameworkCommand {
         /// Cycle log file (JSON).
         log: PathBuf,
     },
+    /// Export a self-contained static HTML/JS bundle that runs the app
+    /// client-side against the mock capability gateway.
+    Export {
+        /// Source file (.ax)
+        file: PathBuf,
+        /// Output directory for the bundle.
+        #[arg(long, default_value = "dist")]
+        out: PathBuf,
+    },
 }
 
 /// CLI entry point.
 ///
 /// **Telemetry feature:** when built with `--features telemetry`, `main`
 /// starts a tokio runtime (required by the OTel batch exporter) and calls
-/// `boruna_vm::init_telemetry()` BEFORE parsing CLI args. The init function
-/// reads `OTEL_EXPORTER_OTLP_ENDPOINT`; when unset the telemetry handle is
+/// `boruna_vm::init_telemetry_with()` BEFORE parsing CLI args, with
+/// `telemetry.otlp_endpoint` from the layered config (which
+/// `OTEL_EXPORTER_OTLP_ENDPOINT` overrides); when unset the telemetry handle is
 /// `Disabled` and behaves identically to a non-telemetry build (zero
 /// allocations, zero spans exported). When set, capability spans
 /// (`boruna.cap` with `cap.name`, `bytes_in`, `bytes_out`,
@@ -1178,7 +1764,11 @@ fn main() {
     // Init may return Err on a hard config problem (malformed endpoint URL,
     // global subscriber already installed). Treat as warning, not fatal —
     // the rest of the CLI works fine without telemetry.
-    let _telemetry_handle = match boruna_vm::init_telemetry() {
+    let config = load_config();
+    let _telemetry_handle = match boruna_vm::init_telemetry_with(
+        config.get("telemetry.otlp_endpoint"),
+        config.get("telemetry.service_name"),
+    ) {
         Ok(h) => Some(h),
         Err(e) => {
             eprintln!("warning: telemetry init failed: {e}");
@@ -1186,8 +1776,8 @@ fn main() {
         }
     };
 
-    let cli = Cli::parse();
-    let result = run(cli);
+    let cli = parse_cli(&config);
+    let result = run(cli, &config);
 
     // Drop the telemetry handle BEFORE shutting down the runtime so
     // force_flush has somewhere to enqueue. The handle's Drop calls
@@ -1201,22 +1791,69 @@ fn main() {
     runtime.shutdown_timeout(std::time::Duration::from_secs(5));
 
     if let Err(e) = result {
-        eprintln!("error: {e}");
-        process::exit(1);
+        report_error(&*e);
+        process::exit(exit_code::for_error(&*e));
     }
 }
 
 #[cfg(not(feature = "telemetry"))]
 fn main() {
-    let cli = Cli::parse();
+    let config = load_config();
+    let cli = parse_cli(&config);
+
+    if let Err(e) = run(cli, &config) {
+        report_error(&*e);
+        process::exit(exit_code::for_error(&*e));
+    }
+}
+
+/// The layered defaults from `boruna.toml`, the user config and the
+/// environment. An invalid config file is fatal: running with a default
+/// the user meant to override could grant a looser policy.
+fn load_config() -> boruna_config::Config {
+    boruna_config::Config::load().unwrap_or_else(|e| {
+        report_error(&e);
+        process::exit(exit_code::for_error(&e));
+    })
+}
 
-    if let Err(e) = run(cli) {
+/// Parse the command line with `config`'s values as flag defaults.
+fn parse_cli(config: &boruna_config::Config) -> Cli {
+    let matches = config.apply(Cli::command()).get_matches();
+    Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit())
+}
+
+/// Print a failed command's error: `error: <message>`, or with `--json`
+/// the error envelope (see `docs/reference/error-codes.md`).
+fn report_error(e: &(dyn std::error::Error + 'static)) {
+    if boruna_errors::json_requested() {
+        eprintln!("{}", error_envelope(e).to_json());
+    } else {
         eprintln!("error: {e}");
-        process::exit(1);
     }
 }
 
-fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
+/// The envelope for `e`: typed errors report their stable code,
+/// anything else is `unclassified`.
+fn error_envelope(e: &(dyn std::error::Error + 'static)) -> ErrorEnvelope {
+    if let Some(e) = e.downcast_ref::<boruna_compiler::CompileError>() {
+        e.envelope()
+    } else if let Some(e) = e.downcast_ref::<boruna_config::ConfigError>() {
+        e.envelope()
+    } else if let Some(e) = e.downcast_ref::<boruna_vm::VmError>() {
+        e.envelope()
+    } else if let Some(e) = e.downcast_ref::<boruna_framework::FrameworkError>() {
+        e.envelope()
+    } else if let Some(e) = e.downcast_ref::<boruna_orchestrator::workflow::WorkflowParseError>() {
+        e.envelope()
+    } else if let Some(e) = e.downcast_ref::<boruna_orchestrator::workflow::WorkflowRunError>() {
+        e.envelope()
+    } else {
+        ErrorEnvelope::unclassified(e.to_string())
+    }
+}
+
+fn run(cli: Cli, config: &boruna_config::Config) -> Result<(), Box<dyn std::error::Error>> {
     // 0.4-S14: install the env namespace once at the top of run() so
     // every downstream call to `resolve_data_dir` and metrics export
     // sees the same value. The env flag wins over BORUNA_ENV; both
@@ -1236,18 +1873,76 @@ fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
     let env_arg = env_name.as_deref();
 
     match cli.command {
-        Command::Compile { file, output } => {
+        Command::Compile {
+            file,
+            output,
+            sign_key,
+            release,
+            budget,
+            verify_deterministic,
+            json,
+        } => {
             let source = fs::read_to_string(&file)?;
             let name = file
                 .file_stem()
                 .map(|s| s.to_string_lossy().to_string())
                 .unwrap_or_else(|| "module".into());
             let resolved = maybe_resolve_imports(&source)?;
-            let module = boruna_compiler::compile(&name, &resolved)?;
+            let options = boruna_compiler::EmitOptions {
+                strip_contracts: release,
+            };
+            if verify_deterministic {
+                let report = boruna_compiler::verify_deterministic(&name, &resolved, options)?;
+                if json {
+                    println!("{}", serde_json::to_string(&report)?);
+                } else if let Some(offset) = report.first_difference {
+                    println!(
+                        "{}: builds differ at byte {offset}\n  first:  {} ({} bytes)\n  second: {} ({} bytes)",
+                        file.display(),
+                        report.digests[0],
+                        report.sizes[0],
+                        report.digests[1],
+                        report.sizes[1]
+                    );
+                } else {
+                    println!(
+                        "{}: deterministic, 2 builds identical ({}, {} bytes)",
+                        file.display(),
+                        report.digests[0],
+                        report.sizes[0]
+                    );
+                }
+                if !report.is_deterministic() {
+                    process::exit(exit_code::REPLAY_DIVERGED);
+                }
+                return Ok(());
+            }
+            let budget = match budget {
+                Some(path) => {
+                    let json = fs::read_to_string(&path)
+                        .map_err(|e| format!("cannot read budget {}: {e}", path.display()))?;
+                    serde_json::from_str(&json)
+                        .map_err(|e| format!("invalid budget {}: {e}", path.display()))?
+                }
+                None => boruna_compiler::Budget::default(),
+            };
+            let mut module =
+                boruna_compiler::compile_with_budget(&name, &resolved, options, &budget)?;
+            // Before signing, so the signature covers it.
+            module.provenance = Some(source_provenance(&file, &source));
+            if let Some(seed_hex) =
+                sign_key.or_else(|| std::env::var("BORUNA_MODULE_SIGNING_KEY").ok())
+            {
+                let seed = boruna_bytecode::signing::parse_key_hex(&seed_hex)?;
+                module.sign(&seed)?;
+            }
             let out_path = output.unwrap_or_else(|| file.with_extension("axbc"));
             let bytes = module.to_bytes()?;
             fs::write(&out_path, bytes)?;
             println!("compiled {} -> {}", file.display(), out_path.display());
+            if let Some(sig) = &module.signature {
+                println!("  signed with ed25519 key {}", sig.public_key);
+            }
             println!(
                 "  {} functions, {} constants, {} types",
                 module.functions.len(),
@@ -1261,11 +1956,22 @@ fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
             max_steps,
             record,
             live,
+            sandbox,
             record_net_to,
             replay_net_from,
             watch,
             providers,
+            allow_capability_skew,
+            strict_determinism,
+            scheduler_seed,
+            transcript,
+            session_policy,
+            contracts,
+            kv,
+            queue_dir,
+            json,
         } => {
+            let enforce_contracts = contracts == ContractsMode::Enforce;
             if let Some(p) = providers {
                 let reg = provider_registry::ProviderRegistry::from_file(&p)?;
                 eprintln!("providers: {}", reg.describe());
@@ -1279,6 +1985,14 @@ fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
                     live,
                     record_net_to.as_deref(),
                     replay_net_from.as_deref(),
+                    allow_capability_skew,
+                    strict_determinism,
+                    scheduler_seed,
+                    enforce_contracts,
+                    transcript.as_deref(),
+                    session_policy.as_deref(),
+                    kv.as_deref(),
+                    queue_dir.as_deref(),
                 )?;
             } else if let Err(e) = run_once(
                 &file,
@@ -1286,30 +2000,96 @@ fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
                 max_steps,
                 record.as_deref(),
                 live,
+                sandbox,
                 record_net_to.as_deref(),
                 replay_net_from.as_deref(),
+                allow_capability_skew,
+                strict_determinism,
+                scheduler_seed,
+                enforce_contracts,
+                transcript.as_deref(),
+                session_policy.as_deref(),
+                kv.as_deref(),
+                queue_dir.as_deref(),
             ) {
-                eprintln!("{e}");
-                process::exit(1);
+                if json {
+                    eprintln!("{}", error_envelope(&*e).to_json());
+                } else {
+                    print_run_error(&*e);
+                }
+                process::exit(exit_code::for_error(&*e));
             }
         }
-        Command::Trace { file } => {
+        Command::Trace {
+            file,
+            every,
+            boundaries,
+            max_entries,
+            summary,
+            top,
+        } => {
             let module = load_module(&file)?;
             let gateway = CapabilityGateway::new(Policy::allow_all());
             let mut vm = Vm::new(module, gateway);
             vm.trace_enabled = true;
+            vm.set_trace_sampling(match (every, boundaries) {
+                (Some(n), _) => TraceSampling::EveryNth(n),
+                (None, true) => TraceSampling::Boundaries,
+                (None, false) => TraceSampling::All,
+            });
+            // Summary-only runs keep no entries at all.
+            vm.set_trace_capacity(if summary { Some(0) } else { max_entries });
 
             match vm.run() {
                 Ok(result) => println!("result: {result}"),
                 Err(e) => eprintln!("runtime error: {e}"),
             }
 
-            println!("\n--- Trace ({} steps) ---", vm.step_count());
-            for entry in &vm.trace {
-                println!("  {entry}");
+            let report = vm.trace_summary(top);
+            if !summary {
+                println!("\n--- Trace ({} steps) ---", vm.step_count());
+                if report.dropped > 0 {
+                    println!("  ... {} earlier entries dropped", report.dropped);
+                }
+                for entry in &vm.trace {
+                    println!("  {entry}");
+                }
+            }
+            println!(
+                "\n--- Summary ({} steps, {} sampled) ---",
+                report.total_steps, report.recorded
+            );
+            for f in &report.top_functions {
+                println!("  {:>12}  {}  ({} calls)", f.steps, f.name, f.calls);
+            }
+        }
+        Command::Replay {
+            command: Some(ReplayCommand::Report { log, format }),
+            ..
+        } => {
+            let log_json = fs::read_to_string(&log)?;
+            let event_log =
+                EventLog::from_json(&log_json).map_err(|e| format!("invalid event log: {e}"))?;
+            let report = run_report::build_report(&event_log);
+            match format {
+                ReportFormat::Md => {
+                    let name = log.file_name().unwrap_or_default().to_string_lossy();
+                    print!("{}", run_report::render_markdown(&name, &report));
+                }
+                ReportFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
             }
         }
-        Command::Replay { file, log } => {
+        Command::Replay {
+            file,
+            log,
+            scheduler_seed,
+            json,
+            ..
+        } => {
+            // clap requires both positionals when no subcommand is given.
+            let (Some(file), Some(log)) = (file, log) else {
+                return Err("usage: boruna replay <FILE> <LOG>".into());
+            };
             let module = load_module(&file)?;
             let log_json = fs::read_to_string(&log)?;
             let original_log =
@@ -1319,21 +2099,75 @@ fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
             let handler = Box::new(ReplayHandler::new(results));
             let gateway = CapabilityGateway::with_handler(Policy::allow_all(), handler);
             let mut vm = Vm::new(module, gateway);
+            vm.set_scheduler_seed(scheduler_seed);
 
-            match vm.run() {
-                Ok(result) => println!("replay result: {result}"),
-                Err(e) => eprintln!("replay error: {e}"),
+            let run = vm.run();
+            if !json {
+                match &run {
+                    Ok(result) => println!("replay result: {result}"),
+                    Err(e) => eprintln!("replay error: {e}"),
+                }
             }
 
-            let replay_result =
-                boruna_vm::replay::ReplayEngine::verify(&original_log, vm.event_log());
-            println!("replay verification: {replay_result:?}");
+            let mut replay_log = vm.event_log().clone();
+            replay_log.set_outcome(boruna_vm::replay::RunOutcome {
+                steps: vm.step_count(),
+                result: run.as_ref().ok().cloned(),
+                error: run.as_ref().err().map(|e| e.to_string()),
+                denials: Vec::new(),
+                kv_snapshot: None,
+            });
+            let replay_result = boruna_vm::replay::ReplayEngine::verify(&original_log, &replay_log);
+            let identical = matches!(replay_result, boruna_vm::replay::ReplayResult::Identical);
+            // `verify` only compares capability calls; the analyzer walks
+            // every event to find where (and how) the runs parted.
+            let divergence = if identical {
+                None
+            } else {
+                boruna_vm::divergence::analyze(&original_log, &replay_log)
+            };
+            if json {
+                let report = serde_json::json!({
+                    "result": run.as_ref().ok().map(|v| v.to_string()),
+                    "error": run.as_ref().err().map(|e| e.to_string()),
+                    "verification": if identical { "identical" } else { "diverged" },
+                    "reason": match &replay_result {
+                        boruna_vm::replay::ReplayResult::Diverged { reason } => Some(reason),
+                        boruna_vm::replay::ReplayResult::Identical => None,
+                    },
+                    "divergence": divergence,
+                });
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                println!("replay verification: {replay_result:?}");
+                if let Some(divergence) = &divergence {
+                    println!("divergence:\n{}", serde_json::to_string_pretty(divergence)?);
+                }
+            }
+            if !identical {
+                process::exit(exit_code::REPLAY_DIVERGED);
+            }
         }
-        Command::Inspect { file } => {
+        Command::Inspect { file, callgraph } => {
             let module = load_module(&file)?;
+            if let Some(callgraph::CallgraphFormat::Dot) = callgraph {
+                print!("{}", callgraph::render_dot(&module));
+                return Ok(());
+            }
             println!("Module: {}", module.name);
             println!("Version: {}", module.version);
             println!("Entry: function #{}", module.entry);
+            if let Some(p) = &module.provenance {
+                println!("\nProvenance:");
+                println!("  compiler: {}", p.compiler);
+                println!("  source: {}", p.source_hash);
+                if let Some(package) = &p.package {
+                    println!("  package: {package}");
+                }
+                if let Some(t) = p.built_at {
+                    println!("  built_at: {t} (SOURCE_DATE_EPOCH)");
+                }
+            }
             println!("\nTypes ({}):", module.types.len());
             for (i, t) in module.types.iter().enumerate() {
                 println!("  #{i}: {} {:?}", t.name, t.kind);
@@ -1373,9 +2207,36 @@ fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
         Command::Doctor { json } => {
             let data_dir = resolve_data_dir(None, env_arg);
             if !doctor::run(&data_dir, json) {
-                process::exit(1);
+                process::exit(exit_code::CHECK_FAILED);
+            }
+        }
+        Command::Explain { id, json } => {
+            let Some(explanation) = boruna_tooling::diagnostics::explain::explain(&id) else {
+                eprintln!(
+                    "error: unknown code '{id}'; expected a diagnostic code (see `boruna lang codes`) or exit:0 to exit:{}",
+                    exit_code::RUNTIME_ERROR
+                );
+                process::exit(exit_code::FAILURE);
+            };
+            if json {
+                println!("{}", serde_json::to_string_pretty(explanation)?);
+            } else {
+                print!("{}", explanation.render());
+            }
+        }
+        Command::Config(ConfigCommand::Show { resolved, json }) => {
+            if json {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&config.to_json(resolved))?
+                );
+            } else {
+                print!("{}", config.render(resolved));
             }
         }
+        Command::Completions { shell } => {
+            clap_complete::generate(shell, &mut Cli::command(), "boruna", &mut std::io::stdout());
+        }
         Command::Size { file, json } => {
             let source = fs::read_to_string(&file)?;
             let name = file
@@ -1389,20 +2250,24 @@ fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
             SkillsCommand::List { json } => skills::run_list(json),
             SkillsCommand::Get { name, json } => {
                 if !skills::run_get(&name, json) {
-                    process::exit(1);
+                    process::exit(exit_code::FAILURE);
                 }
             }
         },
         Command::Trace2tests(t2t) => run_trace2tests(t2t)?,
         Command::Template(tmpl) => run_template(tmpl)?,
         Command::Literate(lit) => run_literate(lit)?,
-        Command::Repl { file, policy } => {
+        Command::Repl {
+            file,
+            policy,
+            record,
+        } => {
             let policy_obj = match policy.as_str() {
                 "allow-all" => Policy::allow_all(),
                 "deny-all" => Policy::deny_all(),
                 path => boruna_vm::policy_validate::parse_file(std::path::Path::new(path))?,
             };
-            repl::run(file, policy_obj)?;
+            repl::run(file, policy_obj, record)?;
         }
         Command::Simulate {
             dir,
@@ -1422,6 +2287,7 @@ fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
             vars,
             no_input,
             force,
+            run_tests,
         } => {
             let args = scaffold::NewArgs {
                 template,
@@ -1430,6 +2296,7 @@ fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
                 vars,
                 no_input,
                 force,
+                run_tests,
             };
             let stdin = std::io::stdin();
             let stdout = std::io::stdout();
@@ -1517,12 +2384,64 @@ fn run_policy(cmd: PolicyCommand) -> i32 {
                     eprintln!("error: {e}");
                 }
                 if matches!(e, policy_validate::PolicyParseError::Io { .. }) {
-                    1
+                    exit_code::FAILURE
                 } else {
-                    2
+                    exit_code::INVALID_INPUT
                 }
             }
         },
+        PolicyCommand::Schema => {
+            print!("{}", policy_validate::POLICY_JSON_SCHEMA);
+            0
+        }
+        PolicyCommand::Infer {
+            from_log,
+            out,
+            baseline,
+            json,
+        } => {
+            let baseline = match baseline {
+                Some(path) => match policy_validate::parse_file(&path) {
+                    Ok(p) => p,
+                    Err(e) => {
+                        eprintln!("error: baseline: {e}");
+                        return exit_code::INVALID_INPUT;
+                    }
+                },
+                None => Policy::allow_all(),
+            };
+            let mut logs = Vec::with_capacity(from_log.len());
+            for path in &from_log {
+                let parsed = fs::read_to_string(path)
+                    .map_err(|e| e.to_string())
+                    .and_then(|json| EventLog::from_json(&json));
+                match parsed {
+                    Ok(log) => logs.push(log),
+                    Err(e) => {
+                        eprintln!("error: invalid event log {}: {e}", path.display());
+                        return exit_code::FAILURE;
+                    }
+                }
+            }
+            let report = policy_infer::build_report(&logs, &baseline);
+            let written = serde_json::to_string_pretty(&report.policy)
+                .map_err(|e| e.to_string())
+                .and_then(|text| fs::write(&out, text + "\n").map_err(|e| e.to_string()));
+            if let Err(e) = written {
+                eprintln!("error: cannot write {}: {e}", out.display());
+                return exit_code::FAILURE;
+            }
+            if json {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&report).expect("report serializes")
+                );
+            } else {
+                print!("{}", policy_infer::render_text(&report));
+                println!("wrote {}", out.display());
+            }
+            0
+        }
         PolicyCommand::Show { file } => match policy_validate::parse_file(&file) {
             Ok(p) => {
                 print_policy_show(&p);
@@ -1531,9 +2450,9 @@ fn run_policy(cmd: PolicyCommand) -> i32 {
             Err(e) => {
                 eprintln!("error: {e}");
                 if matches!(e, policy_validate::PolicyParseError::Io { .. }) {
-                    1
+                    exit_code::FAILURE
                 } else {
-                    2
+                    exit_code::INVALID_INPUT
                 }
             }
         },
@@ -1661,12 +2580,49 @@ fn run_capability(cmd: CapabilityCommand) -> Result<(), Box<dyn std::error::Erro
     Ok(())
 }
 
+/// Record `edits`, already written, in the current directory's edit
+/// journal. A journal failure does not fail the command that made them.
+fn journal_edits(intent: &str, author: &str, edits: Vec<history::FileEdit>) {
+    match history::EditJournal::new(".").record(intent, author, &edits) {
+        Ok(Some(entry)) => eprintln!("recorded {} (revert with `boruna lang undo`)", entry.id),
+        Ok(None) => {}
+        Err(e) => eprintln!("warning: edit not journaled: {e}"),
+    }
+}
+
 fn run_lang(cmd: LangCommand) -> Result<(), Box<dyn std::error::Error>> {
     match cmd {
-        LangCommand::Check { file, json, output } => {
-            let source = fs::read_to_string(&file)?;
-            let file_str = file.display().to_string();
-            let ds = DiagnosticCollector::new(&file_str, &source).collect();
+        LangCommand::Check {
+            file,
+            json,
+            output,
+            config,
+        } => {
+            let config = match config {
+                Some(path) => Some(DiagnosticsConfig::load(&path)?),
+                None => DiagnosticsConfig::discover(&file)?,
+            };
+            let project = if file.is_dir() {
+                Some(ProjectCollector::from_dir(&file)?)
+            } else if file.file_name().is_some_and(|n| n == "package.ax.json") {
+                Some(ProjectCollector::from_manifest(&file)?)
+            } else {
+                None
+            };
+            let ds = if let Some(project) = project {
+                match config {
+                    Some(config) => project.with_config(config).collect(),
+                    None => project.collect(),
+                }
+            } else {
+                let source = fs::read_to_string(&file)?;
+                let file_str = file.display().to_string();
+                let collector = DiagnosticCollector::new(&file_str, &source);
+                match &config {
+                    Some(config) => collector.with_config(config).collect(),
+                    None => collector.collect(),
+                }
+            };
 
             if json || output.is_some() {
                 let json_str = ds.to_json();
@@ -1686,19 +2642,62 @@ fn run_lang(cmd: LangCommand) -> Result<(), Box<dyn std::error::Error>> {
             }
 
             if ds.has_errors() {
-                process::exit(1);
+                process::exit(exit_code::INVALID_INPUT);
             }
         }
-        LangCommand::Repair { file, from, apply } => {
-            let source = fs::read_to_string(&file)?;
+        LangCommand::Repair {
+            file,
+            from,
+            apply,
+            max_rounds,
+            telemetry,
+            feedback,
+        } => {
             let file_str = file.display().to_string();
+            let store = telemetry
+                .map(TelemetryStore::new)
+                .or_else(TelemetryStore::from_env);
+            if let Some(feedback) = feedback {
+                let store = store.ok_or(
+                    "--feedback needs a telemetry store (--telemetry or $BORUNA_REPAIR_TELEMETRY)",
+                )?;
+                let accepted = feedback == RepairFeedback::Accept;
+                let n = store.record_feedback(&file_str, accepted)?;
+                let verdict = if accepted { "accepted" } else { "rejected" };
+                println!("recorded: {n} patches {verdict}");
+                return Ok(());
+            }
+
+            let source = fs::read_to_string(&file)?;
+            let config = DiagnosticsConfig::discover(&file)?;
+            let calibration = match &store {
+                Some(store) => Some(store.calibration()?),
+                None => None,
+            };
+            let collect = |src: &str| {
+                let mut collector = DiagnosticCollector::new(&file_str, src);
+                if let Some(config) = &config {
+                    collector = collector.with_config(config);
+                }
+                if let Some(calibration) = &calibration {
+                    collector = collector.with_calibration(calibration);
+                }
+                collector.collect()
+            };
 
             // Get diagnostics: from file or run check
             let ds = if let Some(diag_path) = from {
                 let json = fs::read_to_string(&diag_path)?;
-                serde_json::from_str(&json).map_err(|e| format!("invalid diagnostics JSON: {e}"))?
+                let mut ds: DiagnosticSet = serde_json::from_str(&json)
+                    .map_err(|e| format!("invalid diagnostics JSON: {e}"))?;
+                if let Some(calibration) = &calibration {
+                    for diag in &mut ds.diagnostics {
+                        suggest::rank_patches(diag, calibration);
+                    }
+                }
+                ds
             } else {
-                DiagnosticCollector::new(&file_str, &source).collect()
+                collect(&source)
             };
 
             let (strategy, specific_id) = match apply.as_str() {
@@ -1707,6 +2706,71 @@ fn run_lang(cmd: LangCommand) -> Result<(), Box<dyn std::error::Error>> {
                 id => (RepairStrategy::ById, Some(id.to_string())),
             };
 
+            if max_rounds > 1 {
+                let (repaired, result) = RepairTool::repair_iterative(
+                    &source,
+                    &ds,
+                    strategy,
+                    specific_id.as_deref(),
+                    max_rounds,
+                    &collect,
+                );
+                if result.rounds.iter().all(|r| r.applied.is_empty()) {
+                    println!("no patches applied");
+                    for s in result.rounds.iter().flat_map(|r| &r.skipped) {
+                        println!("  skipped {}: {}", s.diagnostic_id, s.reason);
+                    }
+                    return Ok(());
+                }
+                fs::write(&file, &repaired)?;
+                let applied = result.applied().count();
+                journal_edits(
+                    &format!("repair {file_str}: {applied} patches"),
+                    "lang repair",
+                    vec![history::FileEdit {
+                        path: file.clone(),
+                        before: Some(source.clone()),
+                        after: repaired.clone(),
+                    }],
+                );
+                if let Some(store) = &store {
+                    for r in &result.rounds {
+                        store.record_repair(&file_str, &r.applied)?;
+                    }
+                }
+                for r in &result.rounds {
+                    println!(
+                        "round {}: applied {} patches, diagnostics: {} -> {}",
+                        r.round,
+                        r.applied.len(),
+                        r.diagnostics_before,
+                        r.diagnostics_after
+                    );
+                    for a in &r.applied {
+                        println!("  [{}] {}: {}", a.diagnostic_id, a.patch_id, a.description);
+                    }
+                    if !r.resolved.is_empty() {
+                        println!("  resolved: {}", r.resolved.join(", "));
+                    }
+                    if !r.introduced.is_empty() {
+                        println!("  new: {}", r.introduced.join(", "));
+                    }
+                }
+                println!(
+                    "stopped after {} rounds ({}); diagnostics: {} -> {}",
+                    result.rounds.len(),
+                    result.stop.as_str(),
+                    result.diagnostics_before,
+                    result.diagnostics_after
+                );
+                if result.verify_passed {
+                    println!("verify: PASS");
+                } else {
+                    println!("verify: FAIL (remaining issues)");
+                }
+                return Ok(());
+            }
+
             let (repaired, result) =
                 RepairTool::repair(&file_str, &source, &ds, strategy, specific_id.as_deref());
 
@@ -1720,6 +2784,18 @@ fn run_lang(cmd: LangCommand) -> Result<(), Box<dyn std::error::Error>> {
             } else {
                 // Write repaired source
                 fs::write(&file, &repaired)?;
+                journal_edits(
+                    &format!("repair {file_str}: {} patches", result.applied.len()),
+                    "lang repair",
+                    vec![history::FileEdit {
+                        path: file.clone(),
+                        before: Some(source),
+                        after: repaired,
+                    }],
+                );
+                if let Some(store) = &store {
+                    store.record_repair(&file_str, &result.applied)?;
+                }
                 println!("applied {} patches:", result.applied.len());
                 for a in &result.applied {
                     println!("  [{}] {}: {}", a.diagnostic_id, a.patch_id, a.description);
@@ -1735,6 +2811,14 @@ fn run_lang(cmd: LangCommand) -> Result<(), Box<dyn std::error::Error>> {
                 }
             }
         }
+        LangCommand::Undo => match history::EditJournal::new(".").undo()? {
+            Some(entry) => println!("undid {}: {}", entry.id, entry.intent),
+            None => println!("nothing to undo"),
+        },
+        LangCommand::Redo => match history::EditJournal::new(".").redo()? {
+            Some(entry) => println!("redid {}: {}", entry.id, entry.intent),
+            None => println!("nothing to redo"),
+        },
         LangCommand::Codes { json } => {
             let registry = boruna_tooling::diagnostics::registry::registry();
             if json {
@@ -1753,13 +2837,49 @@ fn run_lang(cmd: LangCommand) -> Result<(), Box<dyn std::error::Error>> {
                 }
             }
         }
-        LangCommand::Caps { file, json } => {
+        LangCommand::Test { file, format, out } => {
+            let source = fs::read_to_string(&file)?;
+            let tests = match boruna_compiler::doctest::extract(&source) {
+                Ok(tests) => tests,
+                Err(e) => {
+                    eprintln!("{}: {e}", file.display());
+                    process::exit(exit_code::INVALID_INPUT);
+                }
+            };
+            let resolved = maybe_resolve_imports(&source)?;
+            let report = boruna_tooling::doctest::run_doctests(
+                &file.display().to_string(),
+                &tests,
+                &resolved,
+            );
+            let rendered = render_suite_report(&report, format)?;
+            match out {
+                Some(path) => {
+                    fs::write(&path, rendered)?;
+                    println!(
+                        "wrote {} ({}/{} passed)",
+                        path.display(),
+                        report.passed,
+                        report.total
+                    );
+                }
+                None => print!("{rendered}"),
+            }
+            if !report.all_passed() {
+                process::exit(exit_code::CHECK_FAILED);
+            }
+        }
+        LangCommand::Caps {
+            file,
+            json,
+            emit_policy,
+        } => {
             let source = fs::read_to_string(&file)?;
             let module = match boruna_compiler::compile(&file.display().to_string(), &source) {
                 Ok(m) => m,
                 Err(e) => {
                     eprintln!("compile error: {e}");
-                    process::exit(1);
+                    process::exit(exit_code::INVALID_INPUT);
                 }
             };
             let mut any_over = false;
@@ -1788,6 +2908,14 @@ fn run_lang(cmd: LangCommand) -> Result<(), Box<dyn std::error::Error>> {
                 }
                 report.push((f.name.clone(), declared, needed, over));
             }
+            let matrix = boruna_tooling::capmatrix::analyze(&module);
+            if let Some(path) = &emit_policy {
+                let Some(matrix) = &matrix else {
+                    eprintln!("--emit-policy needs a framework app (no `update` function)");
+                    process::exit(exit_code::FAILURE);
+                };
+                fs::write(path, serde_json::to_string_pretty(&matrix.to_policy())?)?;
+            }
             if json {
                 let functions: Vec<_> = report
                     .iter()
@@ -1800,7 +2928,10 @@ fn run_lang(cmd: LangCommand) -> Result<(), Box<dyn std::error::Error>> {
                         })
                     })
                     .collect();
-                let payload = serde_json::json!({ "version": 1, "functions": functions });
+                let mut payload = serde_json::json!({ "version": 1, "functions": functions });
+                if let Some(matrix) = &matrix {
+                    payload["messages"] = serde_json::to_value(&matrix.messages)?;
+                }
                 println!("{}", serde_json::to_string_pretty(&payload)?);
             } else {
                 for (name, declared, needed, over) in &report {
@@ -1818,6 +2949,19 @@ fn run_lang(cmd: LangCommand) -> Result<(), Box<dyn std::error::Error>> {
                         println!("  over-declared: {}", over.join(", "));
                     }
                 }
+                if let Some(matrix) = &matrix {
+                    println!();
+                    println!("messages");
+                    for row in &matrix.messages {
+                        let caps = if row.capabilities.is_empty() {
+                            "(none)".to_string()
+                        } else {
+                            row.capabilities.join(", ")
+                        };
+                        let note = if row.exact { "" } else { " [conservative]" };
+                        println!("  {}: {caps}{note}", row.tag);
+                    }
+                }
                 println!();
                 if any_over {
                     println!("over-declared capabilities found (granted but never used)");
@@ -1825,8 +2969,94 @@ fn run_lang(cmd: LangCommand) -> Result<(), Box<dyn std::error::Error>> {
                     println!("all capability declarations are minimal");
                 }
             }
-            if any_over {
-                process::exit(1);
+            if any_over {
+                process::exit(exit_code::CHECK_FAILED);
+            }
+        }
+        LangCommand::Difftest {
+            corpus_dir,
+            max_steps,
+            json,
+        } => {
+            let report = boruna_tooling::difftest::run_corpus(&corpus_dir, max_steps)?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                use boruna_tooling::difftest::CaseStatus;
+                for case in &report.cases {
+                    match case.status {
+                        CaseStatus::Identical => println!("  [SAME] {}", case.file),
+                        CaseStatus::Diverged => println!(
+                            "  [DIFF] {}: {}",
+                            case.file,
+                            case.reason.as_deref().unwrap_or("")
+                        ),
+                        CaseStatus::Skipped => println!("  [SKIP] {}", case.file),
+                    }
+                }
+                println!(
+                    "identical={} diverged={} skipped={}",
+                    report.identical, report.diverged, report.skipped
+                );
+            }
+            if !report.passed() {
+                process::exit(exit_code::CHECK_FAILED);
+            }
+        }
+        LangCommand::Fuzz {
+            minutes,
+            iterations,
+            seed,
+            out,
+            corpus_out,
+            json,
+        } => {
+            use boruna_tooling::fuzz;
+            let root = fuzz::workspace_root(&std::env::current_dir()?);
+            let corpus = fuzz::seed_corpus(&root);
+            let out = out.unwrap_or_else(|| root.join("fuzz-crashes"));
+            if let Some(dir) = &corpus_out {
+                let n = fuzz::write_corpus(&corpus, dir)?;
+                eprintln!("wrote {n} corpus seeds to {}", dir.display());
+            }
+            let config = fuzz::FuzzConfig {
+                duration: std::time::Duration::from_secs(minutes * 60),
+                max_iterations: iterations,
+                seed,
+            };
+            let report = fuzz::run(&corpus, &config);
+            let artifacts = fuzz::write_artifacts(&report, &out)?;
+            if json {
+                let sets: Vec<_> = report
+                    .crashes
+                    .iter()
+                    .map(|c| c.to_diagnostic_set())
+                    .collect();
+                let payload = serde_json::json!({
+                    "seed": report.seed,
+                    "iterations": report.iterations,
+                    "corpus_size": report.corpus_size,
+                    "crashes": sets,
+                });
+                println!("{}", serde_json::to_string_pretty(&payload)?);
+            } else {
+                println!(
+                    "fuzzed {} inputs from {} seeds (seed {})",
+                    report.iterations, report.corpus_size, report.seed
+                );
+                for (crash, path) in report.crashes.iter().zip(&artifacts) {
+                    println!(
+                        "  [{}] {} at {} -> {}",
+                        crash.target,
+                        crash.message,
+                        crash.location,
+                        path.display()
+                    );
+                }
+                println!("{} unique crash(es)", report.crashes.len());
+            }
+            if !report.crashes.is_empty() {
+                process::exit(exit_code::CHECK_FAILED);
             }
         }
     }
@@ -1839,6 +3069,7 @@ fn run_trace2tests(cmd: Trace2TestsCommand) -> Result<(), Box<dyn std::error::Er
             file,
             messages,
             out,
+            ui_snapshots,
         } => {
             let source = fs::read_to_string(&file)?;
             let file_str = file.display().to_string();
@@ -1851,7 +3082,8 @@ fn run_trace2tests(cmd: Trace2TestsCommand) -> Result<(), Box<dyn std::error::Er
                 })
                 .collect();
 
-            let trace = trace2tests::record_trace(&source, &file_str, msgs)
+            let options = trace2tests::RecordOptions { ui_snapshots };
+            let trace = trace2tests::record_trace_with(&source, &file_str, msgs, options)
                 .map_err(|e| format!("record failed: {e}"))?;
             let json = serde_json::to_string_pretty(&trace)?;
             fs::write(&out, json)?;
@@ -1863,11 +3095,24 @@ fn run_trace2tests(cmd: Trace2TestsCommand) -> Result<(), Box<dyn std::error::Er
             );
             println!("trace hash: {}", trace.trace_hash);
         }
-        Trace2TestsCommand::Generate { trace, name, out } => {
+        Trace2TestsCommand::Generate {
+            trace,
+            name,
+            out,
+            granular,
+            source,
+        } => {
             let trace_json = fs::read_to_string(&trace)?;
-            let trace_file: trace2tests::TraceFile = serde_json::from_str(&trace_json)?;
+            let trace_file = trace2tests::load_trace(&trace_json)?;
 
-            let spec = trace2tests::generate_test(&trace_file, &name);
+            let spec = if granular {
+                let source_path = source.unwrap_or_else(|| PathBuf::from(&trace_file.source_file));
+                let source_code = fs::read_to_string(&source_path)?;
+                trace2tests::generate_granular_test(&trace_file, &name, &source_code)
+                    .map_err(|e| format!("generate failed: {e}"))?
+            } else {
+                trace2tests::generate_test(&trace_file, &name)
+            };
             let json = serde_json::to_string_pretty(&spec)?;
             fs::write(&out, json)?;
 
@@ -1878,14 +3123,30 @@ fn run_trace2tests(cmd: Trace2TestsCommand) -> Result<(), Box<dyn std::error::Er
                 spec.assertions.len()
             );
         }
-        Trace2TestsCommand::Run { spec, source } => {
+        Trace2TestsCommand::Run {
+            spec,
+            source,
+            repeat,
+        } => {
             let spec_json = fs::read_to_string(&spec)?;
             let test_spec: trace2tests::TestSpec = serde_json::from_str(&spec_json)?;
 
             let source_path = source.unwrap_or_else(|| PathBuf::from(&test_spec.source_file));
             let source_code = fs::read_to_string(&source_path)?;
 
-            let result = trace2tests::run_test(&test_spec, &source_code);
+            let repeated = trace2tests::run_test_repeated(&test_spec, &source_code, repeat);
+            if repeated.is_flaky() {
+                println!("FLAKY: {}", test_spec.name);
+                for (i, hash) in repeated.trace_hashes.iter().enumerate() {
+                    println!(
+                        "  run {}: trace hash {}",
+                        i + 1,
+                        hash.as_deref().unwrap_or("<did not complete>")
+                    );
+                }
+                process::exit(exit_code::REPLAY_DIVERGED);
+            }
+            let result = repeated.first;
 
             if result.passed {
                 println!("PASS: {}", test_spec.name);
@@ -1897,24 +3158,88 @@ fn run_trace2tests(cmd: Trace2TestsCommand) -> Result<(), Box<dyn std::error::Er
                 if let Some(err) = &result.error {
                     println!("  error: {err}");
                 }
-                for ar in &result.assertion_results {
+                for (ar, spec_assertion) in
+                    result.assertion_results.iter().zip(&test_spec.assertions)
+                {
                     let status = if ar.passed { "ok" } else { "FAIL" };
+                    let at = match (spec_assertion.cycle, &spec_assertion.path) {
+                        (Some(c), Some(p)) => format!(" (cycle {c}, {p})"),
+                        (Some(c), None) => format!(" (cycle {c})"),
+                        (None, Some(p)) => format!(" ({p})"),
+                        (None, None) => String::new(),
+                    };
                     println!(
-                        "  [{status}] {}: expected={}, actual={}",
+                        "  [{status}] {}{at}: expected={}, actual={}",
                         ar.kind, ar.expected, ar.actual
                     );
                 }
-                process::exit(1);
+                process::exit(exit_code::CHECK_FAILED);
+            }
+        }
+        Trace2TestsCommand::RunSuite {
+            dir,
+            jobs,
+            timeout_secs,
+            repeat,
+            format,
+            out,
+        } => {
+            use trace2tests::suite::{self, SuiteOptions};
+
+            let opts = SuiteOptions {
+                jobs: jobs.unwrap_or_else(num_threads_default),
+                timeout: std::time::Duration::from_secs(timeout_secs),
+                repeat,
+            };
+            let report = suite::run_suite(&dir, &opts)
+                .map_err(|e| format!("scan specs in {}: {e}", dir.display()))?;
+
+            let rendered = render_suite_report(&report, format)?;
+            match out {
+                Some(path) => {
+                    fs::write(&path, rendered)?;
+                    println!(
+                        "wrote {} ({}/{} passed)",
+                        path.display(),
+                        report.passed,
+                        report.total
+                    );
+                }
+                None => print!("{rendered}"),
+            }
+            if !report.all_passed() {
+                process::exit(exit_code::CHECK_FAILED);
+            }
+        }
+        Trace2TestsCommand::Affected {
+            bundle,
+            specs,
+            root,
+            libs_dir,
+            format,
+        } => {
+            use trace2tests::impact::{affected_specs, ImpactOptions};
+
+            let bundle = boruna_orchestrator::patch::PatchBundle::load(&bundle)?;
+            let report = affected_specs(&bundle, &specs, &ImpactOptions { root, libs_dir })?;
+            match format {
+                AffectedFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+                AffectedFormat::Paths => {
+                    for spec in &report.affected {
+                        println!("{}", specs.join(&spec.path).display());
+                    }
+                }
             }
         }
         Trace2TestsCommand::Minimize {
             trace,
             source,
             predicate,
+            predicate_timeout_secs,
             out,
         } => {
             let trace_json = fs::read_to_string(&trace)?;
-            let trace_file: trace2tests::TraceFile = serde_json::from_str(&trace_json)?;
+            let trace_file = trace2tests::load_trace(&trace_json)?;
             let source_code = fs::read_to_string(&source)?;
 
             let messages: Vec<trace2tests::TraceMessage> = trace_file
@@ -1931,13 +3256,44 @@ fn run_trace2tests(cmd: Trace2TestsCommand) -> Result<(), Box<dyn std::error::Er
                     &messages,
                     &trace2tests::predicate_runtime_error,
                 ),
+                "assertion" => trace2tests::minimize_trace(
+                    &source_code,
+                    &messages,
+                    &trace2tests::predicate_assertion,
+                ),
                 _ => {
-                    // External command predicate
-                    let cmd_str = predicate.clone();
+                    // External command predicate. Compile once so each
+                    // invocation can load the module instead of the source.
+                    let name = source
+                        .file_stem()
+                        .map(|s| s.to_string_lossy().to_string())
+                        .unwrap_or_else(|| "module".into());
+                    let module = boruna_compiler::compile(&name, &source_code)?;
+                    let module_file = tempfile::Builder::new().suffix(".axbc").tempfile()?;
+                    fs::write(module_file.path(), module.to_bytes()?)?;
+
+                    let ctx = PredicateContext {
+                        command: predicate.clone(),
+                        source_file: trace_file.source_file.clone(),
+                        module_file: module_file.path().to_path_buf(),
+                        timeout: std::time::Duration::from_secs(predicate_timeout_secs),
+                    };
+                    // The predicate must be 'static, so the last failure
+                    // reason is shared through an Rc.
+                    let last_reason = std::rc::Rc::new(std::cell::RefCell::new(None));
+                    let reason_slot = std::rc::Rc::clone(&last_reason);
                     let pred = move |_src: &str, msgs: &[trace2tests::TraceMessage]| {
-                        external_predicate(&cmd_str, &trace_file.source_file, msgs)
+                        let (outcome, reason) = external_predicate(&ctx, msgs);
+                        if outcome == trace2tests::PredicateOutcome::Fail && reason.is_some() {
+                            *reason_slot.borrow_mut() = reason;
+                        }
+                        outcome
                     };
-                    trace2tests::minimize_trace(&source_code, &messages, &pred)
+                    let minimal = trace2tests::minimize_trace(&source_code, &messages, &pred);
+                    if let Some(reason) = last_reason.borrow_mut().take() {
+                        println!("predicate reason: {reason}");
+                    }
+                    minimal
                 }
             };
 
@@ -1963,44 +3319,87 @@ fn run_trace2tests(cmd: Trace2TestsCommand) -> Result<(), Box<dyn std::error::Er
     Ok(())
 }
 
+/// What an external `minimize` predicate is invoked with.
+struct PredicateContext {
+    command: String,
+    source_file: String,
+    /// Compiled module, written once per minimize run.
+    module_file: PathBuf,
+    timeout: std::time::Duration,
+}
+
+/// Run an external predicate on `messages` (protocol v2, see
+/// `trace2tests::PREDICATE_PROTOCOL_VERSION`). The command gets the path
+/// of a JSON input file as its last argument; a spawn failure or timeout
+/// is `Unresolved`.
 fn external_predicate(
-    command: &str,
-    source_file: &str,
+    ctx: &PredicateContext,
     messages: &[trace2tests::TraceMessage],
-) -> trace2tests::PredicateOutcome {
+) -> (trace2tests::PredicateOutcome, Option<String>) {
+    use std::io::Read;
+
+    let unresolved = |reason: String| (trace2tests::PredicateOutcome::Unresolved, Some(reason));
+
     let temp_file = match tempfile::NamedTempFile::new() {
         Ok(f) => f,
-        Err(_) => return trace2tests::PredicateOutcome::Unresolved,
+        Err(e) => return unresolved(format!("cannot create input file: {e}")),
     };
     let temp_path = temp_file.path().to_path_buf();
     let trace_data = serde_json::json!({
-        "source_file": source_file,
+        "protocol": trace2tests::PREDICATE_PROTOCOL_VERSION,
+        "source_file": ctx.source_file,
+        "module_file": ctx.module_file,
         "messages": messages,
     });
-    if fs::write(
+    if let Err(e) = fs::write(
         &temp_path,
         serde_json::to_string(&trace_data).unwrap_or_default(),
-    )
-    .is_err()
-    {
-        return trace2tests::PredicateOutcome::Unresolved;
+    ) {
+        return unresolved(format!("cannot write input file: {e}"));
     }
 
-    let parts: Vec<&str> = command.split_whitespace().collect();
+    let parts: Vec<&str> = ctx.command.split_whitespace().collect();
     if parts.is_empty() {
-        return trace2tests::PredicateOutcome::Unresolved;
+        return unresolved("empty predicate command".to_string());
     }
 
-    let output = std::process::Command::new(parts[0])
+    let mut child = match std::process::Command::new(parts[0])
         .args(&parts[1..])
         .arg(temp_path.to_string_lossy().as_ref())
-        .output();
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::piped())
+        .spawn()
+    {
+        Ok(c) => c,
+        Err(e) => return unresolved(format!("cannot run {}: {e}", parts[0])),
+    };
 
-    match output {
-        Ok(o) if o.status.success() => trace2tests::PredicateOutcome::Pass,
-        Ok(_) => trace2tests::PredicateOutcome::Fail,
-        Err(_) => trace2tests::PredicateOutcome::Unresolved,
-    }
+    // Drain stdout on a thread so a chatty predicate can't block on a
+    // full pipe while we poll for exit.
+    let mut stdout_pipe = child.stdout.take();
+    let reader = std::thread::spawn(move || {
+        let mut out = String::new();
+        if let Some(pipe) = stdout_pipe.as_mut() {
+            let _ = pipe.read_to_string(&mut out);
+        }
+        out
+    });
+
+    let deadline = std::time::Instant::now() + ctx.timeout;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if std::time::Instant::now() >= deadline => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return unresolved(format!("timed out after {}s", ctx.timeout.as_secs_f64()));
+            }
+            Ok(None) => std::thread::sleep(std::time::Duration::from_millis(10)),
+            Err(e) => return unresolved(format!("wait failed: {e}")),
+        }
+    };
+    let stdout = reader.join().unwrap_or_default();
+    trace2tests::parse_predicate_verdict(&stdout, status.success())
 }
 
 fn run_framework(cmd: FrameworkCommand) -> Result<(), Box<dyn std::error::Error>> {
@@ -2084,7 +3483,7 @@ fn main() -> Int {{
                 }
                 Err(e) => {
                     eprintln!("validation failed: {e}");
-                    process::exit(1);
+                    process::exit(exit_code::for_error(&e));
                 }
             }
         }
@@ -2110,7 +3509,7 @@ fn main() -> Int {{
                         }
                         Err(e) => {
                             eprintln!("error at cycle {}: {e}", harness.cycle());
-                            process::exit(1);
+                            process::exit(exit_code::for_error(&e));
                         }
                     }
                 }
@@ -2125,7 +3524,16 @@ fn main() -> Int {{
                 Err(e) => eprintln!("view error: {e}"),
             }
         }
-        FrameworkCommand::InspectState { file, messages } => {
+        FrameworkCommand::InspectState {
+            file,
+            messages,
+            query,
+        } => {
+            // Parse before running anything so a typo fails fast.
+            let query = query
+                .as_deref()
+                .map(boruna_framework::Query::parse)
+                .transpose()?;
             let source = fs::read_to_string(&file)?;
             let mut harness = TestHarness::from_source(&source)?;
 
@@ -2136,6 +3544,12 @@ fn main() -> Int {{
                 }
             }
 
+            if let Some(query) = query {
+                let result = harness.runtime().query(&query);
+                println!("{}", serde_json::to_string_pretty(&result)?);
+                return Ok(());
+            }
+
             println!("--- State Inspection ---");
             println!("cycle: {}", harness.cycle());
             println!("state: {}", harness.state());
@@ -2186,7 +3600,7 @@ fn main() -> Int {{
                     }
                     Err(e) => {
                         eprintln!("step {} failed: {e}", i + 1);
-                        process::exit(1);
+                        process::exit(exit_code::for_error(&e));
                     }
                 }
             }
@@ -2383,9 +3797,12 @@ fn main() -> Int {{
                 for m in &mismatches {
                     println!("  {m}");
                 }
-                process::exit(1);
+                process::exit(exit_code::REPLAY_DIVERGED);
             }
         }
+        FrameworkCommand::Export { file, out } => {
+            export::run(&file, &out)?;
+        }
     }
     Ok(())
 }
@@ -2422,14 +3839,91 @@ fn load_module(path: &PathBuf) -> Result<Module, Box<dyn std::error::Error>> {
                 .map(|s| s.to_string_lossy().to_string())
                 .unwrap_or_else(|| "module".into());
             let resolved = maybe_resolve_imports(&source)?;
-            Ok(boruna_compiler::compile(&name, &resolved)?)
+            let mut module = boruna_compiler::compile(&name, &resolved)?;
+            module.provenance = Some(source_provenance(path, &source));
+            Ok(module)
         }
         _ => Err(format!("unknown file extension: {ext}").into()),
     }
 }
 
+/// Provenance for a module compiled from `source`, read from `path`:
+/// the compiler and source digest, the package whose `package.ax.json`
+/// is nearest above `path`, and `SOURCE_DATE_EPOCH` if set.
+fn source_provenance(path: &std::path::Path, source: &str) -> boruna_bytecode::Provenance {
+    let built_at = std::env::var(boruna_bytecode::provenance::SOURCE_DATE_EPOCH_ENV)
+        .ok()
+        .and_then(|s| s.trim().parse().ok());
+    let dir = path
+        .parent()
+        .map(std::path::Path::to_path_buf)
+        .unwrap_or_default();
+    let dir = fs::canonicalize(&dir).unwrap_or(dir);
+    let package = dir.ancestors().find_map(|d| {
+        let manifest = fs::read_to_string(d.join("package.ax.json")).ok()?;
+        let manifest: serde_json::Value = serde_json::from_str(&manifest).ok()?;
+        Some(format!(
+            "{}@{}",
+            manifest["name"].as_str()?,
+            manifest["version"].as_str()?
+        ))
+    });
+    boruna_compiler::provenance(source)
+        .with_package(package)
+        .with_built_at(built_at)
+}
+
 /// Run import resolution if a `libs/` directory exists relative to cwd.
 /// If no `libs/` dir is found, returns the source unchanged (additive only).
+/// Render a suite report (`trace2tests run-suite`, `lang test`) in `format`.
+fn render_suite_report(
+    report: &trace2tests::suite::SuiteReport,
+    format: SuiteFormat,
+) -> Result<String, serde_json::Error> {
+    use trace2tests::suite::CaseOutcome;
+
+    Ok(match format {
+        SuiteFormat::Json => serde_json::to_string_pretty(&report)? + "\n",
+        SuiteFormat::Junit => report.to_junit(),
+        SuiteFormat::Console => {
+            let mut text = String::new();
+            for case in &report.cases {
+                let status = match case.outcome {
+                    CaseOutcome::Passed => "PASS",
+                    CaseOutcome::Failed => "FAIL",
+                    CaseOutcome::Error => "ERROR",
+                    CaseOutcome::TimedOut => "TIMEOUT",
+                    CaseOutcome::Flaky => "FLAKY",
+                };
+                text.push_str(&format!(
+                    "{status}: {} ({}, {}ms)\n",
+                    case.name, case.path, case.duration_ms
+                ));
+                if let Some(msg) = &case.message {
+                    text.push_str(&format!("  error: {msg}\n"));
+                }
+                for ar in case.assertions.iter().filter(|a| !a.passed) {
+                    text.push_str(&format!(
+                        "  [FAIL] {}: expected={}, actual={}\n",
+                        ar.kind, ar.expected, ar.actual
+                    ));
+                }
+            }
+            text.push_str(&format!(
+                "summary: total={} passed={} failed={} errors={} timed_out={} flaky={} ({}ms)\n",
+                report.total,
+                report.passed,
+                report.failed,
+                report.errors,
+                report.timed_out,
+                report.flaky,
+                report.duration_ms
+            ));
+            text
+        }
+    })
+}
+
 fn maybe_resolve_imports(source: &str) -> Result<String, Box<dyn std::error::Error>> {
     let libs_dir = std::env::current_dir().unwrap_or_default().join("libs");
     if libs_dir.is_dir() {
@@ -2550,47 +4044,182 @@ fn make_gateway(
     Ok(CapabilityGateway::new(policy))
 }
 
+/// SMTP relay for `notify.send` `mailto:` destinations, from
+/// `BORUNA_SMTP_RELAY` (`host[:port]`), `BORUNA_SMTP_FROM` and the
+/// optional `BORUNA_SMTP_USER` / `BORUNA_SMTP_PASSWORD`.
+fn smtp_config_from_env() -> Option<boruna_vm::notify::SmtpConfig> {
+    let relay = std::env::var("BORUNA_SMTP_RELAY").ok()?;
+    let from = std::env::var("BORUNA_SMTP_FROM").unwrap_or_else(|_| "boruna@localhost".into());
+    let credentials = match (
+        std::env::var("BORUNA_SMTP_USER"),
+        std::env::var("BORUNA_SMTP_PASSWORD"),
+    ) {
+        (Ok(user), Ok(password)) => Some((user, password)),
+        _ => None,
+    };
+    Some(boruna_vm::notify::SmtpConfig {
+        relay,
+        from,
+        credentials,
+    })
+}
+
+/// Print a `run_once` failure. VM errors keep their `runtime error:`
+/// prefix; `run_once` returns them typed so `--json` can classify them.
+fn print_run_error(e: &(dyn std::error::Error + 'static)) {
+    match e.downcast_ref::<boruna_vm::VmError>() {
+        Some(e) => eprintln!("runtime error: {e}"),
+        None => eprintln!("{e}"),
+    }
+}
+
 /// Compile and execute the file once. Returns Err on compile or
 /// runtime failure; the caller decides whether to exit (single-run
 /// mode) or print and continue (watch mode).
+#[allow(clippy::too_many_arguments)]
 fn run_once(
     file: &PathBuf,
     policy: &str,
     max_steps: u64,
     record: Option<&std::path::Path>,
     live: bool,
+    sandbox: bool,
     record_net_to: Option<&std::path::Path>,
     replay_net_from: Option<&std::path::Path>,
+    allow_capability_skew: bool,
+    strict_determinism: bool,
+    scheduler_seed: Option<u64>,
+    enforce_contracts: bool,
+    transcript: Option<&std::path::Path>,
+    session_policy: Option<&std::path::Path>,
+    kv: Option<&std::path::Path>,
+    queue_dir: Option<&std::path::Path>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let module = load_module(file)?;
-    let gateway = make_gateway(policy, live, record_net_to, replay_net_from)?;
+    let mut gateway = make_gateway(policy, live, record_net_to, replay_net_from)?;
+    if let Some(prompter) = capability_prompt::for_run(session_policy)? {
+        gateway = gateway.with_prompter(prompter);
+    }
+    let kv_store = match kv {
+        Some(path) => KvStore::open(path)?,
+        None => KvStore::in_memory(),
+    }
+    .shared();
+    let kv_policy = gateway.policy().kv_policy.clone().unwrap_or_default();
+    let gateway =
+        gateway.wrap_handler(|inner| Box::new(KvHandler::new(kv_store.clone(), kv_policy, inner)));
+    let queues = match queue_dir {
+        Some(dir) => QueueStore::open(dir),
+        None => QueueStore::in_memory(),
+    }
+    .shared();
+    let queue_policy = gateway.policy().queue_policy.clone().unwrap_or_default();
+    let gateway =
+        gateway.wrap_handler(|inner| Box::new(QueueHandler::new(queues, queue_policy, inner)));
+    // Only `--live` runs alert anyone; otherwise notifications are
+    // rendered and policy-checked but not delivered.
+    let notify_sender: Box<dyn NotifySender> = if live {
+        Box::new(LiveNotifySender::new(smtp_config_from_env()))
+    } else {
+        Box::new(DryRunNotifySender)
+    };
+    let notify_policy = gateway.policy().notify_policy.clone().unwrap_or_default();
+    let gateway = gateway
+        .wrap_handler(|inner| Box::new(NotifyHandler::new(notify_policy, notify_sender, inner)));
+    let table_policy = gateway.policy().table_policy.clone().unwrap_or_default();
+    let gateway = gateway.wrap_handler(|inner| Box::new(TableHandler::new(table_policy, inner)));
+    let dry_run = gateway.policy().mode == PolicyMode::DryRun;
+    if dry_run && (live || record_net_to.is_some() || replay_net_from.is_some()) {
+        eprintln!("warning: dry_run policy: every capability call is answered by the mock handler");
+    }
+    if sandbox {
+        // Everything is loaded; what the run still writes stays open.
+        let mut profile = boruna_vm::SandboxProfile::from_policy(gateway.policy());
+        let outputs = [
+            record,
+            record_net_to,
+            transcript,
+            session_policy,
+            kv,
+            queue_dir,
+        ];
+        for path in outputs.into_iter().flatten() {
+            profile = profile.allow_path(path, true);
+        }
+        profile.apply()?;
+        eprintln!("sandbox: {}", profile.summary());
+    }
     let mut vm = Vm::new(module, gateway);
     vm.set_max_steps(max_steps);
-
-    match vm.run() {
-        Ok(result) => {
-            println!("{result}");
-            if !vm.ui_output.is_empty() {
-                println!("\n--- UI Output ---");
-                for tree in &vm.ui_output {
-                    let json = serde_json::to_string_pretty(tree)?;
-                    println!("{json}");
-                }
+    vm.set_allow_capability_skew(allow_capability_skew);
+    vm.set_strict_determinism(strict_determinism);
+    vm.set_scheduler_seed(scheduler_seed);
+    vm.set_enforce_contracts(enforce_contracts);
+
+    let run = vm.run();
+    // Only a file-backed store outlives the run, so only it has a
+    // snapshot worth reporting.
+    let kv_snapshot = kv.map(|_| {
+        kv_store
+            .lock()
+            .map(|store| store.snapshot_hash())
+            .unwrap_or_default()
+    });
+    if let Ok(result) = &run {
+        println!("{result}");
+        if !vm.ui_output.is_empty() {
+            println!("\n--- UI Output ---");
+            for tree in &vm.ui_output {
+                let json = serde_json::to_string_pretty(tree)?;
+                println!("{json}");
             }
         }
-        Err(e) => {
-            return Err(format!("runtime error: {e}").into());
-        }
     }
 
+    // Failed runs are recorded too: the outcome (error, denials) is what
+    // `replay report` needs most.
     if let Some(log_path) = record {
-        let json = vm
-            .event_log()
+        let mut log = vm.event_log().clone();
+        log.set_outcome(RunOutcome {
+            steps: vm.step_count(),
+            result: run.as_ref().ok().cloned(),
+            error: run.as_ref().err().map(|e| e.to_string()),
+            denials: vm.gateway().denials().to_vec(),
+            kv_snapshot: kv_snapshot.clone(),
+        });
+        let json = log
             .to_json()
             .map_err(|e| format!("failed to serialize event log: {e}"))?;
         fs::write(log_path, json)?;
         println!("events recorded to {}", log_path.display());
     }
+    if let Err(e) = run {
+        return Err(e.into());
+    }
+
+    if dry_run {
+        let entries = vm.gateway().transcript();
+        println!("\n--- Dry-run transcript ({} calls) ---", entries.len());
+        for e in entries {
+            let args: Vec<String> = e.args.iter().map(|a| a.to_string()).collect();
+            println!(
+                "  #{} {}({}) -> {} [mock: {}]",
+                e.seq,
+                e.capability,
+                args.join(", "),
+                e.decision.as_str(),
+                e.result
+            );
+        }
+        if let Some(path) = transcript {
+            fs::write(path, serde_json::to_string_pretty(entries)?)?;
+            println!("transcript written to {}", path.display());
+        }
+    } else if transcript.is_some() {
+        eprintln!(
+            "warning: --transcript needs a policy with \"mode\": \"dry_run\"; nothing written"
+        );
+    }
 
     println!("steps: {}", vm.step_count());
     Ok(())
@@ -2603,6 +4232,7 @@ fn run_once(
 /// exactly one rerun. Errors in a single run print to stderr but do
 /// NOT exit the loop — the user fixes the file and the next save
 /// re-executes. Ctrl-C exits cleanly via the default SIGINT handler.
+#[allow(clippy::too_many_arguments)]
 fn run_watch_loop(
     file: &PathBuf,
     policy: &str,
@@ -2611,6 +4241,14 @@ fn run_watch_loop(
     live: bool,
     record_net_to: Option<&std::path::Path>,
     replay_net_from: Option<&std::path::Path>,
+    allow_capability_skew: bool,
+    strict_determinism: bool,
+    scheduler_seed: Option<u64>,
+    enforce_contracts: bool,
+    transcript: Option<&std::path::Path>,
+    session_policy: Option<&std::path::Path>,
+    kv: Option<&std::path::Path>,
+    queue_dir: Option<&std::path::Path>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     use std::sync::mpsc::{channel, RecvTimeoutError};
     use std::time::{Duration, Instant};
@@ -2629,10 +4267,19 @@ fn run_watch_loop(
         max_steps,
         record,
         live,
+        false,
         record_net_to,
         replay_net_from,
+        allow_capability_skew,
+        strict_determinism,
+        scheduler_seed,
+        enforce_contracts,
+        transcript,
+        session_policy,
+        kv,
+        queue_dir,
     ) {
-        eprintln!("{e}");
+        print_run_error(&*e);
     }
 
     let (tx, rx) = channel::<notify::Result<Event>>();
@@ -2692,10 +4339,19 @@ fn run_watch_loop(
             max_steps,
             record,
             live,
+            false,
             record_net_to,
             replay_net_from,
+            allow_capability_skew,
+            strict_determinism,
+            scheduler_seed,
+            enforce_contracts,
+            transcript,
+            session_policy,
+            kv,
+            queue_dir,
         ) {
-            eprintln!("{e}");
+            print_run_error(&*e);
         }
     }
 
@@ -2767,18 +4423,23 @@ fn run_workflow(
                     for err in &errors {
                         eprintln!("  {err}");
                     }
-                    process::exit(1);
+                    process::exit(exit_code::INVALID_INPUT);
                 }
             }
         }
         WorkflowCommand::Run {
             dir,
             policy,
+            params,
+            params_file,
+            profile,
+            fingerprint_env,
             record,
             evidence_dir,
             encrypt_bundle,
             bundle_encryption_key,
             bundle_kek_id,
+            checksum_algorithm,
             live,
             data_dir,
             ephemeral,
@@ -2788,6 +4449,8 @@ fn run_workflow(
             expect_workflow_hash,
             bundle_storage,
             providers,
+            container_image,
+            container_runtime,
         } => {
             if let Some(p) = providers {
                 let reg = provider_registry::ProviderRegistry::from_file(&p)?;
@@ -2808,7 +4471,7 @@ fn run_workflow(
             let def_path = dir.join("workflow.json");
             let json = fs::read_to_string(&def_path)
                 .map_err(|e| format!("cannot read {}: {e}", def_path.display()))?;
-            let def: WorkflowDef =
+            let mut def: WorkflowDef =
                 serde_json::from_str(&json).map_err(|e| format!("invalid workflow.json: {e}"))?;
 
             // 0.3-S9: pre-flight hash check. Refuse before any
@@ -2819,6 +4482,16 @@ fn run_workflow(
                 check_workflow_hash_expectation(&def, expected)?;
             }
 
+            let profile = profile
+                .as_deref()
+                .map(|name| boruna_orchestrator::workflow::Profile::load(&dir, name))
+                .transpose()?;
+            // A profile's policy path is relative to the workflow dir.
+            let policy = match profile.as_ref().and_then(|p| p.profile.policy.as_deref()) {
+                Some(p @ ("allow-all" | "deny-all")) => p.to_string(),
+                Some(path) => dir.join(path).display().to_string(),
+                None => policy,
+            };
             let policy_obj = match policy.as_str() {
                 "allow-all" => Policy::allow_all(),
                 "deny-all" => Policy::deny_all(),
@@ -2830,14 +4503,36 @@ fn run_workflow(
                 }
             };
 
-            let options = RunOptions {
+            // `--params-file` first, then each `--param` on top.
+            let mut param_values = match &params_file {
+                Some(path) => {
+                    let text = fs::read_to_string(path)
+                        .map_err(|e| format!("cannot read {}: {e}", path.display()))?;
+                    serde_json::from_str::<boruna_orchestrator::workflow::ParamValues>(&text)
+                        .map_err(|e| format!("invalid params file {}: {e}", path.display()))?
+                }
+                None => Default::default(),
+            };
+            for binding in &params {
+                let (name, value) = boruna_orchestrator::workflow::parse_binding(&def, binding)?;
+                param_values.insert(name, value);
+            }
+
+            let mut options = RunOptions {
                 policy: Some(policy_obj.clone()),
                 record,
                 workflow_dir: dir.display().to_string(),
                 live,
                 concurrency,
                 submit_only,
+                params: param_values,
+                profile: None,
+                container: container_backend(container_image, container_runtime),
             };
+            if let Some(profile) = &profile {
+                profile.apply(&mut def, &mut options);
+                println!("  profile: {} ({})", profile.name, &profile.hash[..16]);
+            }
 
             let result = if ephemeral {
                 WorkflowRunner::run(&def, &options).map_err(|e| format!("{e}"))?
@@ -2929,7 +4624,16 @@ fn run_workflow(
 
             if record {
                 let ev_dir = evidence_dir.unwrap_or_else(|| dir.join("evidence"));
-                let mut builder = EvidenceBundleBuilder::new(&ev_dir, &result.run_id, &def.name)?;
+                let mut builder = EvidenceBundleBuilder::new(&ev_dir, &result.run_id, &def.name)?
+                    .with_checksum_algorithm(checksum_algorithm);
+                let mut fingerprint_env = fingerprint_env;
+                if let Some(profile) = &profile {
+                    builder = builder.with_profile(profile.fingerprint());
+                    fingerprint_env.extend(profile.profile.fingerprint_env.iter().cloned());
+                }
+                fingerprint_env.sort();
+                fingerprint_env.dedup();
+                builder = builder.with_fingerprint_env(fingerprint_env);
 
                 // Sprint W6-B: enable envelope encryption when the
                 // operator passed --encrypt-bundle. KEK comes from
@@ -2953,6 +4657,37 @@ fn run_workflow(
                 builder.add_workflow_def(&json)?;
                 let policy_json = serde_json::to_string_pretty(&policy_obj)?;
                 builder.add_policy(&policy_json)?;
+                // Capability events let `workflow verify` replay the run.
+                // Events of steps whose output is redacted by the policy's
+                // `data_flow` are left out: they carry the same data.
+                let labels = boruna_orchestrator::workflow::propagate_classifications(&def);
+                for (id, sr) in &result.step_results {
+                    let label = labels.get(id).copied().unwrap_or_default();
+                    if policy_obj
+                        .data_flow
+                        .as_ref()
+                        .is_some_and(|df| df.redacts(label))
+                    {
+                        continue;
+                    }
+                    if let Some(log) = &sr.event_log {
+                        builder.add_event_log(id, &log.to_json()?)?;
+                    }
+                }
+                // Every step that ran, redacted or not, names its source.
+                let provenance: std::collections::BTreeMap<_, _> = result
+                    .step_results
+                    .iter()
+                    .filter_map(|(id, sr)| {
+                        let p = sr.event_log.as_ref()?.provenance()?;
+                        Some((id.clone(), p.clone()))
+                    })
+                    .collect();
+                builder.add_provenance(&provenance)?;
+                // Bound exactly as the run bound them.
+                let bound = boruna_orchestrator::workflow::params::bind(&def, &options.params)
+                    .map_err(|e| format!("{e}"))?;
+                builder.add_params(&bound)?;
 
                 // Build audit log from results
                 let mut audit = AuditLog::new();
@@ -3026,6 +4761,8 @@ fn run_workflow(
             live,
             concurrency,
             expect_workflow_hash,
+            container_image,
+            container_runtime,
         } => {
             if concurrency == 0 {
                 return Err("--concurrency must be >= 1 (got 0); use 1 for sequential".into());
@@ -3079,6 +4816,7 @@ fn run_workflow(
                     live,
                     workflow_dir_override: workflow_dir.map(|p| p.display().to_string()),
                     concurrency,
+                    container: container_backend(container_image, container_runtime),
                 };
                 let result = WorkflowRunner::resume(&run_id, &resolved, &options)
                     .map_err(|e| format!("{e}"))?;
@@ -3098,11 +4836,15 @@ fn run_workflow(
             #[cfg(not(feature = "persist-sqlite"))]
             {
                 let _ = (run_id, data_dir, workflow_dir, policy, live);
+                let _ = (container_image, container_runtime);
                 return Err("`workflow resume` requires the `persist-sqlite` feature \
                             (on by default in boruna-orchestrator)"
                     .into());
             }
         }
+        WorkflowCommand::ExecStep { job, result } => {
+            boruna_orchestrator::workflow::container::exec_job_file(&job, &result)?;
+        }
         WorkflowCommand::Approve {
             run_id,
             step_id,
@@ -3452,8 +5194,80 @@ fn run_workflow(
         WorkflowCommand::Find { dir, json } => {
             handle_workflow_find(&dir, json)?;
         }
-        WorkflowCommand::Graph { dir, json } => {
-            handle_workflow_graph(&dir, json)?;
+        WorkflowCommand::Graph {
+            dir,
+            json,
+            format,
+            run_id,
+            data_dir,
+        } => {
+            let format = if json {
+                workflow_graph::GraphFormat::Json
+            } else {
+                format
+            };
+            let overlay = match run_id {
+                Some(id) => Some(load_run_overlay(
+                    &resolve_data_dir(data_dir.as_ref(), env_arg),
+                    &id,
+                )?),
+                None => None,
+            };
+            handle_workflow_graph(&dir, format, overlay.as_ref())?;
+        }
+        WorkflowCommand::Verify {
+            dir,
+            evidence,
+            json,
+        } => {
+            let integrity = boruna_orchestrator::audit::verify_bundle(&evidence);
+            if !integrity.valid {
+                eprintln!("evidence bundle INVALID:");
+                for err in &integrity.errors {
+                    eprintln!("  {err}");
+                }
+                process::exit(exit_code::CHECK_FAILED);
+            }
+            let def_path = dir.join("workflow.json");
+            let def_json = fs::read_to_string(&def_path)
+                .map_err(|e| format!("cannot read {}: {e}", def_path.display()))?;
+            let def: WorkflowDef = serde_json::from_str(&def_json)
+                .map_err(|e| format!("invalid workflow.json: {e}"))?;
+            let report = WorkflowRunner::verify(&def, &dir.display().to_string(), &evidence)
+                .map_err(|e| format!("{e}"))?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                println!("workflow '{}' verify: run {}", def.name, report.run_id);
+                for step in &report.steps {
+                    let status = serde_json::to_value(step.status)?;
+                    print!(
+                        "  step '{}': {}",
+                        step.step_id,
+                        status.as_str().unwrap_or("?")
+                    );
+                    match &step.detail {
+                        Some(detail) => println!(" ({detail})"),
+                        None => println!(),
+                    }
+                    if step.status == boruna_orchestrator::workflow::StepVerifyStatus::Mismatched {
+                        println!(
+                            "    expected: {}\n    actual:   {}",
+                            step.expected_hash.as_deref().unwrap_or("-"),
+                            step.actual_hash.as_deref().unwrap_or("-")
+                        );
+                    }
+                }
+            }
+            if !report.passed() {
+                if !json {
+                    eprintln!("verification FAILED");
+                }
+                process::exit(exit_code::REPLAY_DIVERGED);
+            }
+            if !json {
+                println!("verification passed");
+            }
         }
     }
     Ok(())
@@ -3461,14 +5275,16 @@ fn run_workflow(
 
 /// Emit the workflow DAG under `dir` as structured graph facts: nodes (with
 /// kind, capabilities, dependencies), edges, topological order, roots, and
-/// leaves. Read-only. Exits non-zero if the directory is unreadable or the
-/// graph is not a DAG.
+/// leaves. Read-only. Exits non-zero if the directory is unreadable, the
+/// definition does not validate, or the graph is not a DAG.
 fn handle_workflow_graph(
     dir: &std::path::Path,
-    json: bool,
+    format: workflow_graph::GraphFormat,
+    overlay: Option<&workflow_graph::RunOverlay>,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    use boruna_orchestrator::workflow::{StepKind, WorkflowDef, WorkflowValidator};
+    use boruna_orchestrator::workflow::{ValidationErrorKind, WorkflowDef, WorkflowValidator};
     use std::collections::BTreeSet;
+    use workflow_graph::{kind_label, GraphFormat};
 
     let def_path = dir.join("workflow.json");
     let raw = fs::read_to_string(&def_path)
@@ -3476,34 +5292,37 @@ fn handle_workflow_graph(
     let def: WorkflowDef =
         serde_json::from_str(&raw).map_err(|e| format!("invalid workflow.json: {e}"))?;
 
-    let kind_label = |k: &StepKind| -> &'static str {
-        match k {
-            StepKind::Source { .. } => "source",
-            StepKind::ApprovalGate { .. } => "approval_gate",
-            StepKind::ExternalTrigger { .. } => "external_trigger",
+    // Every format refuses an invalid definition. A cycle on its own is
+    // still reported as graph facts (`is_dag: false`) by text and JSON.
+    if let Err(errors) = WorkflowValidator::validate(&def) {
+        let facts = matches!(format, GraphFormat::Text | GraphFormat::Json);
+        if !(facts
+            && errors
+                .iter()
+                .all(|e| e.kind == ValidationErrorKind::CycleDetected))
+        {
+            eprintln!("validation failed:");
+            for err in &errors {
+                eprintln!("  {err}");
+            }
+            process::exit(exit_code::INVALID_INPUT);
         }
-    };
+    }
 
-    // Dependency relation = union of per-step `depends_on` and global `edges`.
-    let mut deps: std::collections::BTreeMap<&str, BTreeSet<&str>> = def
-        .steps
-        .keys()
-        .map(|id| (id.as_str(), BTreeSet::new()))
-        .collect();
-    for (id, step) in &def.steps {
-        let entry = deps.entry(id.as_str()).or_default();
-        for d in &step.depends_on {
-            entry.insert(d.as_str());
+    match format {
+        GraphFormat::Dot => {
+            print!("{}", workflow_graph::render_dot(&def, overlay));
+            return Ok(());
         }
-    }
-    for (from, to) in &def.edges {
-        // Only record edges between declared steps — an edge to an unknown
-        // step is a malformed def (caught by `workflow validate`) and must
-        // not introduce a phantom node into `deps`/`roots`.
-        if let Some(set) = deps.get_mut(to.as_str()) {
-            set.insert(from.as_str());
+        GraphFormat::Mermaid => {
+            print!("{}", workflow_graph::render_mermaid(&def, overlay));
+            return Ok(());
         }
+        GraphFormat::Text | GraphFormat::Json => {}
     }
+
+    // Dependency relation = union of per-step `depends_on` and global `edges`.
+    let deps = workflow_graph::dependencies(&def);
     let has_dependents: BTreeSet<&str> = deps.values().flatten().copied().collect();
 
     let roots: Vec<&str> = deps
@@ -3534,7 +5353,7 @@ fn handle_workflow_graph(
         })
         .collect();
 
-    if json {
+    if format == GraphFormat::Json {
         let payload = serde_json::json!({
             "workflow": def.name,
             "version": def.version,
@@ -3571,11 +5390,50 @@ fn handle_workflow_graph(
     }
 
     if !is_dag {
-        process::exit(1);
+        process::exit(exit_code::INVALID_INPUT);
     }
     Ok(())
 }
 
+/// Load per-step status + duration for `run_id` as a graph overlay.
+#[cfg(feature = "persist-sqlite")]
+fn load_run_overlay(
+    data_dir: &std::path::Path,
+    run_id: &str,
+) -> Result<workflow_graph::RunOverlay, Box<dyn std::error::Error>> {
+    let detail =
+        boruna_orchestrator::workflow::show_run(data_dir, run_id).map_err(|e| format!("{e}"))?;
+    Ok(detail
+        .checkpoints
+        .iter()
+        .map(|c| {
+            let duration_ms = match (c.started_at_ms, c.ended_at_ms) {
+                (Some(start), Some(end)) => Some(end - start),
+                _ => None,
+            };
+            (
+                c.step_id.clone(),
+                workflow_graph::StepOverlay {
+                    status: c.status.as_str().to_string(),
+                    duration_ms,
+                },
+            )
+        })
+        .collect())
+}
+
+#[cfg(not(feature = "persist-sqlite"))]
+fn load_run_overlay(
+    _data_dir: &std::path::Path,
+    _run_id: &str,
+) -> Result<workflow_graph::RunOverlay, Box<dyn std::error::Error>> {
+    Err(
+        "`workflow graph --run-id` requires the `persist-sqlite` feature \
+         (on by default in boruna-orchestrator)"
+            .into(),
+    )
+}
+
 /// Parse a 5-field cron expression and return the next fire time
 /// from `now_ms` in milliseconds, or an error on invalid input.
 ///
@@ -3747,6 +5605,9 @@ fn run_workflow_schedule(
             live,
             concurrency: 1,
             submit_only: false,
+            params: Default::default(),
+            profile: None,
+            container: None,
         };
 
         #[cfg(feature = "persist-sqlite")]
@@ -3871,6 +5732,16 @@ fn check_workflow_hash_expectation(
     }
 }
 
+/// The backend for container-isolated steps, when `--container-image`
+/// names an image.
+fn container_backend(
+    image: Option<String>,
+    runtime: String,
+) -> Option<std::sync::Arc<dyn boruna_orchestrator::workflow::container::ContainerBackend>> {
+    let backend = boruna_orchestrator::workflow::container::CliBackend::new(runtime, image?);
+    Some(std::sync::Arc::new(backend))
+}
+
 /// Resolve the persistent `--data-dir` argument with the documented
 /// fallback chain: explicit flag → `BORUNA_DATA_DIR` env var → `./.boruna/data`
 /// in the current working directory.
@@ -3998,7 +5869,7 @@ fn run_evidence(
                 for err in &result.errors {
                     eprintln!("  {err}");
                 }
-                process::exit(1);
+                process::exit(exit_code::CHECK_FAILED);
             }
         }
         EvidenceCommand::Inspect {
@@ -4169,11 +6040,21 @@ fn run_evidence(
                     println!("  {name}: {}", &hash[..16]);
                 }
                 println!("\nenv:");
-                println!("  boruna: {}", manifest.env_fingerprint.boruna_version);
-                println!(
-                    "  os: {}/{}",
-                    manifest.env_fingerprint.os, manifest.env_fingerprint.arch
-                );
+                let env = &manifest.env_fingerprint;
+                println!("  boruna: {}", env.boruna_version);
+                println!("  os: {}/{}", env.os, env.arch);
+                if !env.locale.is_empty() {
+                    println!("  locale: {}, timezone: {}", env.locale, env.timezone);
+                }
+                if let Some(profile) = &env.profile {
+                    println!("  profile: {} ({})", profile.name, &profile.hash[..16]);
+                }
+                for (name, hash) in &env.env_vars {
+                    match hash {
+                        Some(hash) => println!("  env {name}: {}", &hash[..16]),
+                        None => println!("  env {name}: unset"),
+                    }
+                }
                 if let Some(outputs) = step_outputs {
                     if decrypt {
                         println!("\n=== Step Outputs (decrypted) ===");
@@ -4211,6 +6092,23 @@ fn run_evidence(
                 return Err("`evidence gc-blobs` requires the `persist-sqlite` feature".into());
             }
         }
+        EvidenceCommand::Gc {
+            evidence_root,
+            keep_last,
+            keep_days,
+            dry_run,
+            json,
+        } => {
+            evidence_gc::run(
+                &evidence_root,
+                evidence_gc::GcPolicy {
+                    keep_last,
+                    keep_days,
+                },
+                dry_run,
+                json,
+            )?;
+        }
         EvidenceCommand::RotateKek {
             target,
             old_kek,
@@ -4265,7 +6163,7 @@ fn run_evidence(
                 for err in &result.errors {
                     eprintln!("  {err}");
                 }
-                process::exit(1);
+                process::exit(exit_code::CHECK_FAILED);
             }
         }
         EvidenceCommand::Diff {
@@ -4357,7 +6255,7 @@ fn run_evidence_attest(
             }
             Err(e) => {
                 eprintln!("attestation INVALID: {e}");
-                process::exit(1);
+                process::exit(exit_code::CHECK_FAILED);
             }
         }
         return Ok(());
@@ -4435,7 +6333,7 @@ fn run_evidence_anchor(
             }
             Err(e) => {
                 eprintln!("anchor INVALID: {e}");
-                process::exit(1);
+                process::exit(exit_code::CHECK_FAILED);
             }
         }
         return Ok(());
@@ -4841,14 +6739,15 @@ fn run_template(cmd: TemplateCommand) -> Result<(), Box<dyn std::error::Error>>
     use boruna_tooling::templates;
 
     match cmd {
-        TemplateCommand::List { dir } => {
-            let templates = templates::list_templates(&dir)
+        TemplateCommand::List { dir, cache } => {
+            let templates = templates::list_templates_with_cache(&dir, &cache)
                 .map_err(|e| -> Box<dyn std::error::Error> { e.into() })?;
             if templates.is_empty() {
                 println!("no templates found in {}", dir.display());
             } else {
                 println!("available templates:");
-                for t in &templates {
+                for listed in &templates {
+                    let t = &listed.manifest;
                     println!("  {} v{} — {}", t.name, t.version, t.description);
                     if !t.dependencies.is_empty() {
                         println!("    deps: {}", t.dependencies.join(", "));
@@ -4856,15 +6755,50 @@ fn run_template(cmd: TemplateCommand) -> Result<(), Box<dyn std::error::Error>>
                     if !t.capabilities.is_empty() {
                         println!("    caps: {}", t.capabilities.join(", "));
                     }
+                    match &listed.provenance {
+                        templates::Provenance::Local => println!("    from: local"),
+                        templates::Provenance::Remote { source, sha256 } => {
+                            println!("    from: {source} ({sha256})")
+                        }
+                    }
+                }
+            }
+        }
+        TemplateCommand::Add { source, cache, yes } => {
+            let pack = templates::remote::fetch(&source)
+                .map_err(|e| -> Box<dyn std::error::Error> { e.into() })?;
+            println!("{} template(s) from {}:", pack.templates.len(), pack.source);
+            for t in &pack.templates {
+                let m = &t.manifest;
+                println!("  {} v{} — {}", m.name, m.version, m.description);
+                if !m.capabilities.is_empty() {
+                    println!("    caps: {}", m.capabilities.join(", "));
+                }
+                println!("    sha256: {}", t.sha256);
+            }
+            if !yes {
+                print!("Trust templates from {}? [y/N] ", pack.source);
+                std::io::Write::flush(&mut std::io::stdout())?;
+                let mut answer = String::new();
+                std::io::stdin().read_line(&mut answer)?;
+                if !matches!(answer.trim(), "y" | "Y" | "yes") {
+                    return Err("not trusted; nothing was installed".into());
                 }
             }
+            fs::create_dir_all(&cache)?;
+            let installed = templates::remote::install(&cache, &pack)
+                .map_err(|e| -> Box<dyn std::error::Error> { e.into() })?;
+            println!("cached {} in {}", installed.join(", "), cache.display());
         }
         TemplateCommand::Apply {
             name,
             dir,
+            cache,
             args,
             out,
             validate,
+            run_tests,
+            base_dir,
         } => {
             // Parse args from "key1=val1,key2=val2"
             let mut arg_map = std::collections::BTreeMap::new();
@@ -4880,6 +6814,8 @@ fn run_template(cmd: TemplateCommand) -> Result<(), Box<dyn std::error::Error>>
                 arg_map.insert(parts[0].to_string(), parts[1].to_string());
             }
 
+            let dir = templates::remote::resolve_template_dir(&dir, &cache, &name)
+                .map_err(|e| -> Box<dyn std::error::Error> { e.into() })?;
             let result = templates::apply_template(&dir, &name, &arg_map)
                 .map_err(|e| -> Box<dyn std::error::Error> { e.into() })?;
 
@@ -4890,7 +6826,14 @@ fn run_template(cmd: TemplateCommand) -> Result<(), Box<dyn std::error::Error>>
             }
 
             let output_path = out.unwrap_or_else(|| PathBuf::from(&result.output_file));
+            let mut edits = vec![history::FileEdit {
+                path: output_path.clone(),
+                before: fs::read_to_string(&output_path).ok(),
+                after: result.source.clone(),
+            }];
             fs::write(&output_path, &result.source)?;
+            templates::upgrade::store_base(&base_dir, &result.source)
+                .map_err(|e| -> Box<dyn std::error::Error> { e.into() })?;
             println!(
                 "generated {} from template '{}'",
                 output_path.display(),
@@ -4898,6 +6841,102 @@ fn run_template(cmd: TemplateCommand) -> Result<(), Box<dyn std::error::Error>>
             );
             println!("  deps: {}", result.dependencies.join(", "));
             println!("  caps: {}", result.capabilities.join(", "));
+
+            let out_dir = output_path.parent().map(PathBuf::from).unwrap_or_default();
+            for test in &result.tests {
+                let mut spec = test.spec.clone();
+                spec.source_file = output_path.display().to_string();
+                let spec_path = out_dir.join(&test.file);
+                if let Some(parent) = spec_path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                let spec_json = serde_json::to_string_pretty(&spec)?;
+                edits.push(history::FileEdit {
+                    path: spec_path.clone(),
+                    before: fs::read_to_string(&spec_path).ok(),
+                    after: spec_json.clone(),
+                });
+                fs::write(&spec_path, spec_json)?;
+                println!("  test: {}", spec_path.display());
+            }
+            journal_edits(
+                &format!("template apply {}", result.template_name),
+                "template apply",
+                edits,
+            );
+            if run_tests {
+                let mut failed = 0;
+                for (test, outcome) in templates::run_template_tests(&result) {
+                    if outcome.passed {
+                        println!("PASS: {}", test.spec.name);
+                        continue;
+                    }
+                    failed += 1;
+                    println!("FAIL: {}", test.spec.name);
+                    if let Some(err) = &outcome.error {
+                        println!("  error: {err}");
+                    }
+                    for a in outcome.assertion_results.iter().filter(|a| !a.passed) {
+                        println!("  {}: expected {}, got {}", a.kind, a.expected, a.actual);
+                    }
+                }
+                if failed > 0 {
+                    return Err(format!(
+                        "{failed} of {} template test(s) failed",
+                        result.tests.len()
+                    )
+                    .into());
+                }
+            }
+        }
+        TemplateCommand::Upgrade {
+            file,
+            template,
+            dir,
+            cache,
+            base_dir,
+            json,
+        } => {
+            let (name, version) = template
+                .split_once('@')
+                .ok_or_else(|| format!("--template must be <name>@<version>, got '{template}'"))?;
+            let source = fs::read_to_string(&file)?;
+            let dirs = templates::upgrade::UpgradeDirs {
+                templates: &dir,
+                cache: &cache,
+                base: &base_dir,
+            };
+            let file_str = file.display().to_string();
+            let outcome = templates::upgrade::upgrade(&file_str, &source, name, version, &dirs)
+                .map_err(|e| -> Box<dyn std::error::Error> { e.into() })?;
+            fs::write(&file, &outcome.source)?;
+            journal_edits(
+                &format!("template upgrade {file_str} to {template}"),
+                "template upgrade",
+                vec![history::FileEdit {
+                    path: file.clone(),
+                    before: Some(source),
+                    after: outcome.source.clone(),
+                }],
+            );
+            templates::upgrade::store_base(&base_dir, &outcome.new_base)
+                .map_err(|e| -> Box<dyn std::error::Error> { e.into() })?;
+            let conflicts = &outcome.conflicts;
+            if json {
+                println!("{}", conflicts.to_json());
+            } else {
+                print!("{}", conflicts.to_human());
+            }
+            if conflicts.has_errors() {
+                return Err(format!(
+                    "upgraded {file_str} to {template} with {} conflict(s); resolve the markers",
+                    conflicts.diagnostics.len()
+                )
+                .into());
+            }
+            if !json {
+                println!("upgraded {file_str} to {template}");
+            }
         }
     }
     Ok(())
@@ -5113,9 +7152,15 @@ mod tests {
                     retry: None,
                     budget: None,
                     required_capability_versions: Default::default(),
+                    input_schema: Default::default(),
+                    output_schema: None,
+                    classification: None,
+                    isolation: Default::default(),
                 },
             )]),
             edges: vec![],
+            budget: None,
+            params: Default::default(),
         }
     }
 
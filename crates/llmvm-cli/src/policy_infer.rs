@@ -0,0 +1,314 @@
+//! `boruna policy infer`: the tightest policy covering a set of recorded runs.
+//!
+//! Every capability call that went through in one of the [`EventLog`]s is
+//! allowed, with a budget equal to the most calls any single run made
+//! (budgets are per run). Everything else is denied by default. When
+//! `net.fetch` was called, `net_policy` is narrowed to the exact hosts and
+//! methods seen. Calls refused at record time are not in the event stream
+//! and stay denied.
+//!
+//! The report compares the inferred policy against a baseline (allow-all
+//! unless one is given) and lists what the baseline allowed that the new
+//! policy would deny.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+
+use boruna_bytecode::{Capability, Value};
+use boruna_vm::capability_gateway::{NetPolicy, Policy, PolicyRule};
+use boruna_vm::replay::{Event, EventLog};
+
+use crate::run_report::url_host;
+
+/// A net-policy allowlist before and after; empty means "any".
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ListChange {
+    pub before: Vec<String>,
+    pub after: Vec<String>,
+}
+
+/// A budget before and after; 0 means unlimited.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct BudgetChange {
+    pub before: u64,
+    pub after: u64,
+}
+
+/// What the baseline allowed that the inferred policy denies.
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct NewlyDenied {
+    pub capabilities: Vec<String>,
+    pub budgets: BTreeMap<String, BudgetChange>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub domains: Option<ListChange>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub methods: Option<ListChange>,
+}
+
+impl NewlyDenied {
+    pub fn is_empty(&self) -> bool {
+        *self == NewlyDenied::default()
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct InferReport {
+    pub runs: usize,
+    pub policy: Policy,
+    pub newly_denied: NewlyDenied,
+}
+
+/// Infer the tightest policy covering `logs`.
+pub fn infer_policy(logs: &[EventLog]) -> Policy {
+    let mut budgets: BTreeMap<String, u64> = BTreeMap::new();
+    let mut domains = BTreeSet::new();
+    let mut methods = BTreeSet::new();
+    for log in logs {
+        let mut calls: BTreeMap<&str, u64> = BTreeMap::new();
+        for event in log.events() {
+            let Event::CapCall { capability, args } = event else {
+                continue;
+            };
+            *calls.entry(capability).or_default() += 1;
+            if capability == Capability::NetFetch.name() {
+                if let Some(host) = args.first().and_then(url_host) {
+                    domains.insert(host);
+                }
+                let method = match args.get(1) {
+                    Some(Value::String(m)) => m.to_uppercase(),
+                    _ => "GET".to_string(),
+                };
+                methods.insert(method);
+            }
+        }
+        for (cap, n) in calls {
+            let budget = budgets.entry(cap.to_string()).or_default();
+            *budget = (*budget).max(n);
+        }
+    }
+
+    let mut policy = Policy::deny_all();
+    for (cap, budget) in budgets {
+        policy.rules.insert(
+            cap,
+            PolicyRule {
+                allow: true,
+                budget,
+                prompt: false,
+            },
+        );
+    }
+    if !domains.is_empty() || !methods.is_empty() {
+        policy.net_policy = Some(NetPolicy {
+            allowed_domains: domains.into_iter().collect(),
+            allowed_methods: methods.into_iter().collect(),
+            ..NetPolicy::default()
+        });
+    }
+    policy
+}
+
+fn allows(policy: &Policy, cap: &str) -> bool {
+    match policy.rules.get(cap) {
+        Some(rule) => rule.allow || rule.prompt,
+        None => policy.default_allow,
+    }
+}
+
+fn budget(policy: &Policy, cap: &str) -> u64 {
+    policy.rules.get(cap).map_or(0, |rule| rule.budget)
+}
+
+/// Whether allowlist `after` is narrower than `before` (empty = any).
+fn narrower(before: &[String], after: &[String]) -> bool {
+    !after.is_empty() && (before.is_empty() || before.iter().any(|b| !after.contains(b)))
+}
+
+/// What `baseline` allows that `inferred` denies.
+pub fn newly_denied(baseline: &Policy, inferred: &Policy) -> NewlyDenied {
+    let mut out = NewlyDenied::default();
+    for cap in Capability::ALL {
+        let name = cap.name();
+        if !allows(baseline, name) {
+            continue;
+        }
+        if !allows(inferred, name) {
+            out.capabilities.push(name.to_string());
+            continue;
+        }
+        let (before, after) = (budget(baseline, name), budget(inferred, name));
+        if after != 0 && (before == 0 || after < before) {
+            out.budgets
+                .insert(name.to_string(), BudgetChange { before, after });
+        }
+    }
+
+    if allows(baseline, Capability::NetFetch.name()) {
+        if let Some(np) = &inferred.net_policy {
+            let base = baseline.net_policy.clone().unwrap_or_default();
+            if narrower(&base.allowed_domains, &np.allowed_domains) {
+                out.domains = Some(ListChange {
+                    before: base.allowed_domains.clone(),
+                    after: np.allowed_domains.clone(),
+                });
+            }
+            if narrower(&base.allowed_methods, &np.allowed_methods) {
+                out.methods = Some(ListChange {
+                    before: base.allowed_methods.clone(),
+                    after: np.allowed_methods.clone(),
+                });
+            }
+        }
+    }
+    out
+}
+
+pub fn build_report(logs: &[EventLog], baseline: &Policy) -> InferReport {
+    let policy = infer_policy(logs);
+    let newly_denied = newly_denied(baseline, &policy);
+    InferReport {
+        runs: logs.len(),
+        policy,
+        newly_denied,
+    }
+}
+
+fn list_or_any(items: &[String]) -> String {
+    if items.is_empty() {
+        "(any)".to_string()
+    } else {
+        items.join(", ")
+    }
+}
+
+/// Render the report as plain text.
+pub fn render_text(report: &InferReport) -> String {
+    let mut out = format!("inferred from {} run(s)\n", report.runs);
+    if report.policy.rules.is_empty() {
+        out += "allowed: (none)\n";
+    } else {
+        out += "allowed:\n";
+        for (cap, rule) in &report.policy.rules {
+            out += &format!("  {cap:<14} budget={}\n", rule.budget);
+        }
+    }
+    if let Some(np) = &report.policy.net_policy {
+        out += &format!(
+            "  net.fetch domains: {}\n",
+            list_or_any(&np.allowed_domains)
+        );
+        out += &format!(
+            "  net.fetch methods: {}\n",
+            list_or_any(&np.allowed_methods)
+        );
+    }
+
+    let nd = &report.newly_denied;
+    if nd.is_empty() {
+        out += "newly denied: (nothing)\n";
+        return out;
+    }
+    out += "newly denied:\n";
+    if !nd.capabilities.is_empty() {
+        out += &format!("  capabilities: {}\n", nd.capabilities.join(", "));
+    }
+    for (cap, change) in &nd.budgets {
+        let before = if change.before == 0 {
+            "unlimited".to_string()
+        } else {
+            change.before.to_string()
+        };
+        out += &format!("  {cap} budget: {before} -> {}\n", change.after);
+    }
+    if let Some(change) = &nd.domains {
+        out += &format!(
+            "  net.fetch domains: {} -> {}\n",
+            list_or_any(&change.before),
+            change.after.join(", ")
+        );
+    }
+    if let Some(change) = &nd.methods {
+        out += &format!(
+            "  net.fetch methods: {} -> {}\n",
+            list_or_any(&change.before),
+            change.after.join(", ")
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(urls: &[(&str, &str)], time_calls: usize) -> EventLog {
+        let mut log = EventLog::new();
+        for (url, method) in urls {
+            let args = [
+                Value::String((*url).into()),
+                Value::String((*method).into()),
+            ];
+            log.log_cap_call(&Capability::NetFetch, &args);
+            log.log_cap_result(&Capability::NetFetch, &Value::String("ok".into()));
+        }
+        for _ in 0..time_calls {
+            log.log_cap_call(&Capability::TimeNow, &[]);
+            log.log_cap_result(&Capability::TimeNow, &Value::Int(1));
+        }
+        log
+    }
+
+    #[test]
+    fn budgets_are_the_per_run_maximum() {
+        let logs = [
+            run(&[("https://api.example.com/a", "get")], 3),
+            run(
+                &[
+                    ("https://api.example.com/b", "GET"),
+                    ("https://cdn.example.com/x", "POST"),
+                ],
+                1,
+            ),
+        ];
+        let policy = infer_policy(&logs);
+        assert!(!policy.default_allow);
+        assert_eq!(policy.rules["net.fetch"].budget, 2);
+        assert_eq!(policy.rules["time.now"].budget, 3);
+        assert_eq!(policy.rules.len(), 2);
+        let np = policy.net_policy.unwrap();
+        assert_eq!(np.allowed_domains, ["api.example.com", "cdn.example.com"]);
+        assert_eq!(np.allowed_methods, ["GET", "POST"]);
+    }
+
+    #[test]
+    fn reports_what_allow_all_loses() {
+        let logs = [run(&[("https://api.example.com/a", "GET")], 0)];
+        let report = build_report(&logs, &Policy::allow_all());
+        let nd = &report.newly_denied;
+        assert!(nd.capabilities.contains(&"fs.write".to_string()));
+        assert!(!nd.capabilities.contains(&"net.fetch".to_string()));
+        assert_eq!(
+            nd.budgets["net.fetch"],
+            BudgetChange {
+                before: 0,
+                after: 1
+            }
+        );
+        assert_eq!(nd.domains.as_ref().unwrap().after, ["api.example.com"]);
+
+        let text = render_text(&report);
+        assert!(text.contains("net.fetch budget: unlimited -> 1"), "{text}");
+        assert!(
+            text.contains("net.fetch domains: (any) -> api.example.com"),
+            "{text}"
+        );
+    }
+
+    #[test]
+    fn inferred_policy_loses_nothing_against_itself() {
+        let logs = [run(&[("https://api.example.com/a", "GET")], 2)];
+        let policy = infer_policy(&logs);
+        assert!(newly_denied(&policy, &policy).is_empty());
+    }
+}
@@ -20,6 +20,13 @@
 //! Bytecode 1.1 surface is exercised through the REPL: `__builtin_debug(v)`
 //! prints to stderr and returns its argument unchanged, so users can sprinkle
 //! traces in expressions without restructuring.
+//!
+//! `:app <file>` also starts a framework app session that `:send` drives one
+//! message at a time. With `:record <trace.json>` (or `boruna repl --record`)
+//! the session is rewritten to that file as a standard trace2tests
+//! [`TraceFile`](boruna_tooling::trace2tests::TraceFile) after every message,
+//! so an exploratory session survives a crash and turns into a regression
+//! test with `boruna trace2tests generate`.
 
 use std::fs;
 use std::io::{self, BufRead, Write};
@@ -27,6 +34,9 @@ use std::path::{Path, PathBuf};
 
 use boruna_bytecode::{Module, Value};
 use boruna_compiler::compile;
+use boruna_framework::runtime::AppMessage;
+use boruna_framework::testing::TestHarness;
+use boruna_tooling::trace2tests;
 use boruna_vm::{capability_gateway::Policy, vm::Vm, CapabilityGateway};
 
 /// Return type declared on the synthetic wrapper function. Boruna's
@@ -59,6 +69,17 @@ pub struct ReplSession {
     loaded_from: Option<PathBuf>,
     /// Capability policy applied to every input.
     policy: Policy,
+    /// Framework app started with `:app`, driven by `:send`.
+    app: Option<AppSession>,
+    /// Trace file the app session is recorded to.
+    record_to: Option<PathBuf>,
+}
+
+/// A framework app under `:send`, with the source it was started from.
+struct AppSession {
+    path: PathBuf,
+    source: String,
+    harness: TestHarness,
 }
 
 impl ReplSession {
@@ -68,6 +89,8 @@ impl ReplSession {
             module_source: String::new(),
             loaded_from: None,
             policy,
+            app: None,
+            record_to: None,
         }
     }
 
@@ -132,6 +155,75 @@ impl ReplSession {
         }
     }
 
+    /// Start a framework app session from a `.ax` file, replacing any
+    /// previous one. Rewrites the recording, if any, to the empty session.
+    pub fn start_app(&mut self, path: &Path) -> Result<(), String> {
+        let source =
+            fs::read_to_string(path).map_err(|e| format!("cannot read {}: {e}", path.display()))?;
+        let harness = TestHarness::from_source(&source).map_err(|e| e.to_string())?;
+        self.app = Some(AppSession {
+            path: path.to_path_buf(),
+            source,
+            harness,
+        });
+        self.write_trace()
+    }
+
+    /// Deliver one `tag:payload` message to the app session. Returns the
+    /// new state, with record fields named, and the kinds of the effects it
+    /// emitted.
+    pub fn send(&mut self, message: &str) -> Result<(serde_json::Value, Vec<String>), String> {
+        let app = self
+            .app
+            .as_mut()
+            .ok_or("no app session; use `:app <file>` first")?;
+        let (tag, payload) = crate::parse_message(message);
+        let (_, effects) = app
+            .harness
+            .send(AppMessage::new(tag, payload))
+            .map_err(|e| e.to_string())?;
+        let state = app.harness.runtime().state_json();
+        let kinds = effects
+            .iter()
+            .map(|e| e.kind.as_str().to_string())
+            .collect();
+        self.write_trace()?;
+        Ok((state, kinds))
+    }
+
+    /// State of the app session, with record fields named.
+    pub fn app_state(&self) -> Option<serde_json::Value> {
+        self.app
+            .as_ref()
+            .map(|app| app.harness.runtime().state_json())
+    }
+
+    /// Record the app session to `path` from now on, starting with the
+    /// messages already sent.
+    pub fn record(&mut self, path: &Path) -> Result<(), String> {
+        self.record_to = Some(path.to_path_buf());
+        self.write_trace()
+    }
+
+    /// The app session as a trace, or `None` without one.
+    pub fn trace(&self) -> Option<Result<trace2tests::TraceFile, String>> {
+        let app = self.app.as_ref()?;
+        Some(trace2tests::trace_from_cycle_log(
+            &app.source,
+            &app.path.display().to_string(),
+            app.harness.cycle_log(),
+            app.harness.state(),
+        ))
+    }
+
+    fn write_trace(&self) -> Result<(), String> {
+        let (Some(path), Some(trace)) = (&self.record_to, self.trace()) else {
+            return Ok(());
+        };
+        let json = serde_json::to_string_pretty(&trace?).map_err(|e| e.to_string())?;
+        fs::write(path, json).map_err(|e| format!("cannot write {}: {e}", path.display()))
+    }
+
     fn compile_with(&self, expr: &str, ret_ty: &str) -> Result<Module, String> {
         let synthetic = format!(
             "{}\n\nfn {REPL_FN_NAME}() -> {ret_ty} {{ {expr} }}\n",
@@ -238,6 +330,46 @@ fn dispatch_meta(cmd: &str, session: &mut ReplSession) -> MetaResult {
                 MetaResult::Ok(names.join("\n"))
             }
         }
+        "app" => {
+            if arg.is_empty() {
+                return MetaResult::Err(":app requires a path".into());
+            }
+            let path = PathBuf::from(arg);
+            match session.start_app(&path) {
+                Ok(()) => MetaResult::Ok(format!(
+                    "app {} started: {}",
+                    path.display(),
+                    session.app_state().unwrap_or_default()
+                )),
+                Err(e) => MetaResult::Err(e),
+            }
+        }
+        "send" | "s" => {
+            if arg.is_empty() {
+                return MetaResult::Err(":send requires a `tag:payload` message".into());
+            }
+            match session.send(arg) {
+                Ok((state, effects)) if effects.is_empty() => MetaResult::Ok(state.to_string()),
+                Ok((state, effects)) => {
+                    MetaResult::Ok(format!("{state}\neffects: {}", effects.join(", ")))
+                }
+                Err(e) => MetaResult::Err(e),
+            }
+        }
+        "state" => match session.app_state() {
+            Some(state) => MetaResult::Ok(state.to_string()),
+            None => MetaResult::Err("no app session; use `:app <file>` first".into()),
+        },
+        "record" => {
+            if arg.is_empty() {
+                return MetaResult::Err(":record requires a path".into());
+            }
+            let path = PathBuf::from(arg);
+            match session.record(&path) {
+                Ok(()) => MetaResult::Ok(format!("recording app session to {}", path.display())),
+                Err(e) => MetaResult::Err(e),
+            }
+        }
         other => MetaResult::Err(format!("unknown meta-command `:{other}` (try `:help`)")),
     }
 }
@@ -258,6 +390,10 @@ fn help_text() -> String {
         "  :reset         Drop the loaded module",
         "  :type <expr>   Show the inferred return-type label for <expr>",
         "  :env           List functions defined in the loaded module",
+        "  :app <file>    Start a framework app session",
+        "  :send <msg>    Send a `tag:payload` message to the app",
+        "  :state         Show the app's state",
+        "  :record <file> Record the app session as a trace2tests trace",
         "  :help          Show this help",
         "  :quit          Exit the REPL",
         "",
@@ -267,16 +403,26 @@ fn help_text() -> String {
         "type is reported post-hoc via `:type` (which runs the expression).",
         "",
         "Side-effect-free debugging: __builtin_debug(v) prints to stderr and returns v.",
+        "",
+        "A recorded trace is rewritten after every message; turn it into a test",
+        "with `boruna trace2tests generate --trace <file> --out <spec>`.",
     ]
     .join("\n")
 }
 
 /// CLI entry point. Reads from stdin, writes to stdout.
-pub fn run(initial_file: Option<PathBuf>, policy: Policy) -> Result<(), String> {
+pub fn run(
+    initial_file: Option<PathBuf>,
+    policy: Policy,
+    record: Option<PathBuf>,
+) -> Result<(), String> {
     let mut session = ReplSession::new(policy);
     if let Some(path) = initial_file {
         session.load(&path)?;
     }
+    if let Some(path) = record {
+        session.record(&path)?;
+    }
     let stdin = io::stdin();
     let mut reader = stdin.lock();
     let stdout = io::stdout();
@@ -486,6 +632,45 @@ mod tests {
         assert!(out.contains("14"), "double(7) should be 14, got: {out:?}");
     }
 
+    const COUNTER_APP: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../../examples/framework/counter_app.ax"
+    );
+
+    #[test]
+    fn send_without_app_reports_error() {
+        let out = run_with_input(":send increment:0\n:quit\n", Policy::allow_all());
+        assert!(out.contains("no app session"), "got: {out:?}");
+    }
+
+    #[test]
+    fn recorded_app_session_replays_as_a_passing_test() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let trace_path = dir.path().join("session.json");
+        let cmd = format!(
+            ":app {COUNTER_APP}\n:record {}\n:send increment:0\n:send increment:0\n:send decrement:0\n:state\n:quit\n",
+            trace_path.display()
+        );
+        let out = run_with_input(&cmd, Policy::allow_all());
+        assert!(out.contains("recording app session"), "got: {out:?}");
+        assert!(out.ends_with("{\"count\":1}\n"), "got: {out:?}");
+
+        let trace: trace2tests::TraceFile =
+            serde_json::from_str(&fs::read_to_string(&trace_path).unwrap()).unwrap();
+        assert_eq!(trace.cycles.len(), 3);
+        let tags: Vec<&str> = trace
+            .cycles
+            .iter()
+            .map(|c| c.message.tag.as_str())
+            .collect();
+        assert_eq!(tags, ["increment", "increment", "decrement"]);
+
+        let spec = trace2tests::generate_test(&trace, "exploratory");
+        let source = fs::read_to_string(COUNTER_APP).unwrap();
+        let result = trace2tests::run_test(&spec, &source);
+        assert!(result.passed, "{:?}", result.assertion_results);
+    }
+
     #[test]
     fn banner_is_skipped_in_non_interactive_mode() {
         let out = run_with_input(":quit\n", Policy::allow_all());
@@ -0,0 +1,282 @@
+//! `boruna replay report`: a one-page summary of a recorded run.
+//!
+//! Reads an [`EventLog`] written by `boruna run --record` and condenses it
+//! into what a reviewer asks of a change ticket: which capabilities were
+//! called and how much data moved, which domains were contacted, what
+//! policy refused, how long the run was, and a hash of its result. Logs
+//! without a stored outcome (other producers, older `run`s) still report
+//! their capability calls; step count and result are shown as unknown.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+
+use boruna_tooling::trace2tests::hash_value;
+use boruna_vm::capability_gateway::{approx_value_bytes, Denial};
+use boruna_vm::replay::{Event, EventLog};
+
+/// Calls of one capability. Bytes are the string payload sizes of the
+/// arguments sent and results received.
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct CapabilitySummary {
+    pub calls: u64,
+    pub denied: u64,
+    pub bytes_in: u64,
+    pub bytes_out: u64,
+}
+
+/// Counts of the non-capability events.
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct EventCounts {
+    pub total: usize,
+    pub actors_spawned: u64,
+    pub messages_sent: u64,
+    pub ui_emits: u64,
+    pub contract_checks_passed: u64,
+    pub contract_checks_failed: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RunReport {
+    pub log_version: u32,
+    /// `ok`, `error`, or `unknown` when the log has no outcome.
+    pub status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub steps: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// SHA-256 of the result value's canonical JSON.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result_hash: Option<String>,
+    pub capabilities: BTreeMap<String, CapabilitySummary>,
+    /// Hosts of `net.fetch` calls that went through.
+    pub domains: BTreeSet<String>,
+    pub denials: Vec<Denial>,
+    pub events: EventCounts,
+}
+
+pub fn build_report(log: &EventLog) -> RunReport {
+    let mut capabilities: BTreeMap<String, CapabilitySummary> = BTreeMap::new();
+    let mut domains = BTreeSet::new();
+    let mut events = EventCounts {
+        total: log.events().len(),
+        ..EventCounts::default()
+    };
+    for event in log.events() {
+        match event {
+            Event::CapCall { capability, args } => {
+                let cap = capabilities.entry(capability.clone()).or_default();
+                cap.calls += 1;
+                cap.bytes_in += args.iter().map(approx_value_bytes).sum::<u64>();
+                if capability == "net.fetch" {
+                    if let Some(host) = args.first().and_then(url_host) {
+                        domains.insert(host);
+                    }
+                }
+            }
+            Event::CapResult { capability, result } => {
+                capabilities
+                    .entry(capability.clone())
+                    .or_default()
+                    .bytes_out += approx_value_bytes(result);
+            }
+            Event::ActorSpawn { .. } => events.actors_spawned += 1,
+            Event::MessageSend { .. } => events.messages_sent += 1,
+            Event::UiEmit { .. } => events.ui_emits += 1,
+            Event::ContractCheck { passed: true, .. } => events.contract_checks_passed += 1,
+            Event::ContractCheck { passed: false, .. } => events.contract_checks_failed += 1,
+            Event::MessageReceive { .. }
+            | Event::SchedulerTick { .. }
+            | Event::ActorCheckpoint { .. }
+            | Event::ActorRestore { .. }
+            | Event::CapabilityDelegation { .. } => {}
+        }
+    }
+
+    let outcome = log.outcome();
+    let denials = outcome.map(|o| o.denials.clone()).unwrap_or_default();
+    for d in &denials {
+        capabilities.entry(d.capability.clone()).or_default().denied += 1;
+    }
+    let status = match outcome {
+        None => "unknown",
+        Some(o) if o.error.is_some() => "error",
+        Some(_) => "ok",
+    };
+    RunReport {
+        log_version: log.version(),
+        status,
+        steps: outcome.map(|o| o.steps),
+        error: outcome.and_then(|o| o.error.clone()),
+        result_hash: outcome.and_then(|o| o.result.as_ref()).map(hash_value),
+        capabilities,
+        domains,
+        denials,
+        events,
+    }
+}
+
+/// The host of a URL argument, lowercased, without userinfo or port.
+pub(crate) fn url_host(arg: &boruna_bytecode::Value) -> Option<String> {
+    let boruna_bytecode::Value::String(url) = arg else {
+        return None;
+    };
+    let rest = url.split_once("://").map_or(url.as_str(), |(_, r)| r);
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host = authority.rsplit_once('@').map_or(authority, |(_, h)| h);
+    let host = match host.strip_prefix('[') {
+        // IPv6 literal: keep the brackets, drop the port.
+        Some(v6) => format!("[{}]", v6.split(']').next()?),
+        None => host.split(':').next()?.to_string(),
+    };
+    (!host.is_empty()).then(|| host.to_ascii_lowercase())
+}
+
+/// Render the report as Markdown.
+pub fn render_markdown(log_name: &str, report: &RunReport) -> String {
+    let mut out = format!("# Run report: {log_name}\n\n");
+    out += "| | |\n|---|---|\n";
+    out += &format!("| Status | {} |\n", report.status);
+    let steps = report
+        .steps
+        .map_or("unknown".to_string(), |s| s.to_string());
+    out += &format!("| Steps | {steps} |\n");
+    if let Some(e) = &report.error {
+        out += &format!("| Error | {} |\n", e.replace('|', "\\|"));
+    }
+    let hash = report.result_hash.as_deref().unwrap_or("none");
+    out += &format!("| Result hash | `{hash}` |\n");
+    out += &format!(
+        "| Events | {} (log format v{}) |\n",
+        report.events.total, report.log_version
+    );
+
+    out += "\n## Capability calls\n\n";
+    if report.capabilities.is_empty() {
+        out += "None.\n";
+    } else {
+        out += "| Capability | Calls | Denied | Bytes in | Bytes out |\n";
+        out += "|---|---:|---:|---:|---:|\n";
+        for (name, c) in &report.capabilities {
+            out += &format!(
+                "| {name} | {} | {} | {} | {} |\n",
+                c.calls, c.denied, c.bytes_in, c.bytes_out
+            );
+        }
+    }
+
+    out += "\n## Domains\n\n";
+    if report.domains.is_empty() {
+        out += "None.\n";
+    }
+    for d in &report.domains {
+        out += &format!("- {d}\n");
+    }
+
+    out += "\n## Denials\n\n";
+    if report.denials.is_empty() {
+        out += "None.\n";
+    }
+    for d in &report.denials {
+        let args: Vec<String> = d.args.iter().map(|a| a.to_string()).collect();
+        out += &format!(
+            "- `{}({})`: {}\n",
+            d.capability,
+            args.join(", "),
+            d.decision.as_str()
+        );
+    }
+
+    let e = &report.events;
+    out += "\n## Other events\n\n";
+    out += &format!("- actors spawned: {}\n", e.actors_spawned);
+    out += &format!("- messages sent: {}\n", e.messages_sent);
+    out += &format!("- UI emits: {}\n", e.ui_emits);
+    out += &format!(
+        "- contract checks: {} passed, {} failed\n",
+        e.contract_checks_passed, e.contract_checks_failed
+    );
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use boruna_bytecode::{Capability, Value};
+    use boruna_vm::capability_gateway::PolicyDecision;
+    use boruna_vm::replay::RunOutcome;
+
+    fn fetch_log() -> EventLog {
+        let mut log = EventLog::new();
+        let url = [Value::String(
+            "https://user@API.example.com:443/v1?q=1".into(),
+        )];
+        log.log_cap_call(&Capability::NetFetch, &url);
+        log.log_cap_result(&Capability::NetFetch, &Value::String("hello".into()));
+        log.log_cap_call(&Capability::TimeNow, &[]);
+        log.log_cap_result(&Capability::TimeNow, &Value::Int(1));
+        log
+    }
+
+    #[test]
+    fn groups_calls_and_collects_domains() {
+        let mut log = fetch_log();
+        log.set_outcome(RunOutcome {
+            steps: 42,
+            result: None,
+            error: Some("capability denied: fs.write".into()),
+            denials: vec![Denial {
+                capability: "fs.write".into(),
+                args: vec![Value::String("/tmp/x".into())],
+                decision: PolicyDecision::Deny,
+            }],
+            kv_snapshot: None,
+        });
+        let report = build_report(&log);
+        assert_eq!(report.status, "error");
+        assert_eq!(report.steps, Some(42));
+        assert_eq!(
+            report.capabilities["net.fetch"],
+            CapabilitySummary {
+                calls: 1,
+                denied: 0,
+                bytes_in: 39,
+                bytes_out: 5,
+            }
+        );
+        assert_eq!(report.capabilities["time.now"].calls, 1);
+        assert_eq!(report.capabilities["fs.write"].denied, 1);
+        assert_eq!(
+            report.domains.iter().collect::<Vec<_>>(),
+            ["api.example.com"]
+        );
+
+        let md = render_markdown("run.json", &report);
+        assert!(md.contains("| net.fetch | 1 | 0 | 39 | 5 |"), "{md}");
+        assert!(md.contains("- `fs.write(\"/tmp/x\")`: deny"), "{md}");
+    }
+
+    #[test]
+    fn result_hash_is_stable_and_missing_outcome_is_unknown() {
+        let log = fetch_log();
+        let report = build_report(&log);
+        assert_eq!(report.status, "unknown");
+        assert_eq!(report.result_hash, None);
+
+        let mut a = fetch_log();
+        let mut b = fetch_log();
+        for log in [&mut a, &mut b] {
+            log.set_outcome(RunOutcome {
+                steps: 3,
+                result: Some(Value::Int(7)),
+                error: None,
+                denials: Vec::new(),
+                kv_snapshot: None,
+            });
+        }
+        let (ra, rb) = (build_report(&a), build_report(&b));
+        assert_eq!(ra.status, "ok");
+        assert!(ra.result_hash.is_some());
+        assert_eq!(ra.result_hash, rb.result_hash);
+    }
+}
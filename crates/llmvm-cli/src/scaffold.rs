@@ -31,6 +31,8 @@ pub struct NewArgs {
     pub no_input: bool,
     /// Allow writing into a non-empty target directory.
     pub force: bool,
+    /// Run the template's generated test specs and fail unless they pass.
+    pub run_tests: bool,
 }
 
 /// Result returned by `run_new` — the binary path discards it; tests
@@ -179,9 +181,43 @@ pub fn run_new<R: BufRead, W: Write>(
     let out_path = target_dir.join(&result.output_file);
     fs::write(&out_path, &result.source).map_err(|e| format!("write output: {e}"))?;
 
-    let written_files = vec![out_path.clone()];
+    templates::upgrade::store_base(
+        &target_dir.join(templates::upgrade::DEFAULT_BASE_DIR),
+        &result.source,
+    )?;
 
-    // 8. Print next-step hints.
+    let mut written_files = vec![out_path.clone()];
+    for test in &result.tests {
+        let spec_path = target_dir.join(&test.file);
+        if let Some(parent) = spec_path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("create tests dir: {e}"))?;
+        }
+        let json = serde_json::to_string_pretty(&test.spec).map_err(|e| e.to_string())?;
+        fs::write(&spec_path, json).map_err(|e| format!("write {}: {e}", test.file))?;
+        written_files.push(spec_path);
+    }
+
+    // 8. Run the generated tests (opt-in).
+    if args.run_tests {
+        for (test, outcome) in templates::run_template_tests(&result) {
+            if outcome.passed {
+                writeln!(writer, "PASS: {}", test.spec.name).map_err(io_err)?;
+            } else {
+                let detail = outcome.error.unwrap_or_else(|| {
+                    outcome
+                        .assertion_results
+                        .iter()
+                        .filter(|a| !a.passed)
+                        .map(|a| format!("{}: expected {}, got {}", a.kind, a.expected, a.actual))
+                        .collect::<Vec<_>>()
+                        .join("; ")
+                });
+                return Err(format!("template test {} failed: {detail}", test.file));
+            }
+        }
+    }
+
+    // 9. Print next-step hints.
     writeln!(
         writer,
         "\nOK: scaffolded {} at {}",
@@ -197,6 +233,9 @@ pub fn run_new<R: BufRead, W: Write>(
         result.output_file
     )
     .map_err(io_err)?;
+    for test in &result.tests {
+        writeln!(writer, "  boruna trace2tests run --spec {}", test.file).map_err(io_err)?;
+    }
     if !result.dependencies.is_empty() {
         writeln!(writer, "  deps: {}", result.dependencies.join(", ")).map_err(io_err)?;
     }
@@ -384,6 +423,7 @@ mod tests {
             vars: vec!["entity_name=widgets".into(), "fields=a|b".into()],
             no_input: true,
             force: false,
+            run_tests: false,
         };
         let mut out = Vec::new();
         let outcome =
@@ -409,6 +449,7 @@ mod tests {
             vars: vec![],
             no_input: false,
             force: false,
+            run_tests: false,
         };
         let mut out = Vec::new();
         let outcome = run_new(Cursor::new(stdin), &mut out, args).expect("scaffold succeeds");
@@ -435,6 +476,7 @@ mod tests {
             vars: vec!["entity_name=x".into(), "fields=y".into()],
             no_input: true,
             force: false,
+            run_tests: false,
         };
         let mut out = Vec::new();
         let err = run_new(Cursor::new(Vec::<u8>::new()), &mut out, args).unwrap_err();
@@ -456,6 +498,7 @@ mod tests {
             vars: vec!["entity_name=x".into(), "fields=y".into()],
             no_input: true,
             force: true,
+            run_tests: false,
         };
         let mut out = Vec::new();
         let outcome =
@@ -478,6 +521,7 @@ mod tests {
             vars: vec![],
             no_input: true,
             force: false,
+            run_tests: false,
         };
         let mut out = Vec::new();
         let err = run_new(Cursor::new(Vec::<u8>::new()), &mut out, args).unwrap_err();
@@ -500,6 +544,7 @@ mod tests {
             vars: vec![],
             no_input: true,
             force: false,
+            run_tests: false,
         };
         let mut out = Vec::new();
         let err = run_new(Cursor::new(Vec::<u8>::new()), &mut out, args).unwrap_err();
@@ -525,10 +570,38 @@ mod tests {
             vars: vec![],
             no_input: false,
             force: false,
+            run_tests: false,
         };
         let mut out = Vec::new();
         let outcome = run_new(Cursor::new(stdin), &mut out, args).expect("scaffold succeeds");
         let body = fs::read_to_string(&outcome.written_files[0]).unwrap();
         assert!(body.contains("things fields: name|price"), "body: {body}");
     }
+
+    #[test]
+    fn scaffold_writes_and_runs_template_tests() {
+        let templates_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../templates");
+        let target = tempfile::tempdir().unwrap();
+        let target_path = target.path().join("contact");
+        let args = NewArgs {
+            template: Some("form-basic".into()),
+            templates_dir,
+            target: Some(target_path.clone()),
+            vars: vec!["form_name=contact".into(), "fields=name,email".into()],
+            no_input: true,
+            force: false,
+            run_tests: true,
+        };
+        let mut out = Vec::new();
+        let outcome =
+            run_new(Cursor::new(Vec::<u8>::new()), &mut out, args).expect("scaffold succeeds");
+        let spec = target_path.join("tests/submit.testspec.json");
+        assert!(outcome.written_files.contains(&spec));
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("PASS: contact_submit"), "out: {out}");
+        assert!(
+            out.contains("boruna trace2tests run --spec tests/submit.testspec.json"),
+            "out: {out}"
+        );
+    }
 }
@@ -62,6 +62,9 @@ fn run_one(
         live: false,
         concurrency: 1,
         submit_only: false,
+        params: Default::default(),
+        profile: None,
+        container: None,
     };
 
     let t0 = Instant::now();
@@ -0,0 +1,299 @@
+//! DOT / Mermaid rendering for `boruna workflow graph --format`.
+//!
+//! Renders the step DAG of a `WorkflowDef` — the dependency relation is the
+//! union of per-step `depends_on` and the global `edges` list — optionally
+//! colored by a recorded run's per-step status and duration. Output is
+//! deterministic: nodes and edges are emitted in `BTreeMap`/`BTreeSet`
+//! order, so a diagram checked into docs only changes when the DAG or the
+//! overlaid run changes.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use boruna_orchestrator::workflow::{StepKind, WorkflowDef};
+
+/// Output format for `workflow graph`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum GraphFormat {
+    Text,
+    Json,
+    Dot,
+    Mermaid,
+}
+
+/// Status + duration of one step in a recorded run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StepOverlay {
+    pub status: String,
+    pub duration_ms: Option<i64>,
+}
+
+/// Per-step overlay for a recorded run, keyed by step id.
+pub type RunOverlay = BTreeMap<String, StepOverlay>;
+
+pub fn kind_label(k: &StepKind) -> &'static str {
+    match k {
+        StepKind::Source { .. } => "source",
+        StepKind::ApprovalGate { .. } => "approval_gate",
+        StepKind::ExternalTrigger { .. } => "external_trigger",
+    }
+}
+
+/// Dependency relation: step id -> the steps it depends on. Edges that
+/// reference an undeclared step are dropped (caught by `workflow validate`).
+pub fn dependencies(def: &WorkflowDef) -> BTreeMap<&str, BTreeSet<&str>> {
+    let mut deps: BTreeMap<&str, BTreeSet<&str>> = def
+        .steps
+        .keys()
+        .map(|id| (id.as_str(), BTreeSet::new()))
+        .collect();
+    for (id, step) in &def.steps {
+        let entry = deps.entry(id.as_str()).or_default();
+        for d in &step.depends_on {
+            if def.steps.contains_key(d) {
+                entry.insert(d.as_str());
+            }
+        }
+    }
+    for (from, to) in &def.edges {
+        if !def.steps.contains_key(from) {
+            continue;
+        }
+        if let Some(set) = deps.get_mut(to.as_str()) {
+            set.insert(from.as_str());
+        }
+    }
+    deps
+}
+
+/// Fill color for a step status. Steps absent from the run stay white.
+fn status_color(status: Option<&str>) -> &'static str {
+    match status {
+        Some("completed") => "#a6e3a1",
+        Some("failed") => "#f38ba8",
+        Some("running") => "#89b4fa",
+        Some("awaiting_approval") | Some("awaiting_external_event") => "#f9e2af",
+        Some("pending") => "#e6e6e6",
+        _ => "#ffffff",
+    }
+}
+
+/// Label lines for a node: id, kind, and (with an overlay) status/duration.
+fn node_label(id: &str, kind: &str, overlay: Option<&StepOverlay>) -> Vec<String> {
+    let mut lines = vec![id.to_string(), kind.to_string()];
+    if let Some(o) = overlay {
+        lines.push(match o.duration_ms {
+            Some(ms) => format!("{} ({ms}ms)", o.status),
+            None => o.status.clone(),
+        });
+    }
+    lines
+}
+
+/// Render the DAG as a Graphviz DOT digraph.
+pub fn render_dot(def: &WorkflowDef, overlay: Option<&RunOverlay>) -> String {
+    let deps = dependencies(def);
+    let mut out = String::new();
+    out.push_str(&format!("digraph \"{}\" {{\n", dot_escape(&def.name)));
+    out.push_str("  rankdir=LR;\n");
+    out.push_str("  node [shape=box, style=\"rounded,filled\", fillcolor=\"#ffffff\"];\n");
+    for (id, step) in &def.steps {
+        let o = overlay.and_then(|m| m.get(id));
+        let shape = match step.kind {
+            StepKind::Source { .. } => "box",
+            StepKind::ApprovalGate { .. } | StepKind::ExternalTrigger { .. } => "diamond",
+        };
+        let label: Vec<String> = node_label(id, kind_label(&step.kind), o)
+            .iter()
+            .map(|l| dot_escape(l))
+            .collect();
+        let mut attrs = format!("label=\"{}\", shape={shape}", label.join("\\n"));
+        if overlay.is_some() {
+            attrs.push_str(&format!(
+                ", fillcolor=\"{}\"",
+                status_color(o.map(|o| o.status.as_str()))
+            ));
+        }
+        out.push_str(&format!("  \"{}\" [{attrs}];\n", dot_escape(id)));
+    }
+    for (to, froms) in &deps {
+        for from in froms {
+            out.push_str(&format!(
+                "  \"{}\" -> \"{}\";\n",
+                dot_escape(from),
+                dot_escape(to)
+            ));
+        }
+    }
+    out.push_str("}\n");
+    out
+}
+
+/// Render the DAG as a Mermaid `flowchart`.
+///
+/// Mermaid node ids must be identifier-like, so steps are addressed as
+/// `s0, s1, ...` in sorted step-id order with the real id in the label.
+pub fn render_mermaid(def: &WorkflowDef, overlay: Option<&RunOverlay>) -> String {
+    let deps = dependencies(def);
+    let ids: BTreeMap<&str, String> = def
+        .steps
+        .keys()
+        .enumerate()
+        .map(|(i, id)| (id.as_str(), format!("s{i}")))
+        .collect();
+    let mut out = String::from("flowchart LR\n");
+    for (id, step) in &def.steps {
+        let o = overlay.and_then(|m| m.get(id));
+        let label = node_label(id, kind_label(&step.kind), o)
+            .join("<br/>")
+            .replace('"', "#quot;");
+        let node = &ids[id.as_str()];
+        match step.kind {
+            StepKind::Source { .. } => out.push_str(&format!("  {node}[\"{label}\"]\n")),
+            _ => out.push_str(&format!("  {node}{{\"{label}\"}}\n")),
+        }
+    }
+    for (to, froms) in &deps {
+        for from in froms {
+            out.push_str(&format!("  {} --> {}\n", ids[from], ids[to]));
+        }
+    }
+    if let Some(overlay) = overlay {
+        for id in def.steps.keys() {
+            let status = overlay.get(id).map(|o| o.status.as_str());
+            out.push_str(&format!(
+                "  style {} fill:{}\n",
+                ids[id.as_str()],
+                status_color(status)
+            ));
+        }
+    }
+    out
+}
+
+fn dot_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use boruna_orchestrator::workflow::StepDef;
+
+    fn step(kind: StepKind, depends_on: &[&str]) -> StepDef {
+        StepDef {
+            kind,
+            capabilities: vec![],
+            inputs: BTreeMap::new(),
+            outputs: BTreeMap::new(),
+            depends_on: depends_on.iter().map(|s| s.to_string()).collect(),
+            timeout_ms: None,
+            retry: None,
+            budget: None,
+            required_capability_versions: Default::default(),
+            input_schema: Default::default(),
+            output_schema: None,
+            classification: None,
+            isolation: Default::default(),
+        }
+    }
+
+    fn source(path: &str) -> StepKind {
+        StepKind::Source {
+            source: path.into(),
+        }
+    }
+
+    fn def() -> WorkflowDef {
+        WorkflowDef {
+            schema_version: 1,
+            name: "pipeline".into(),
+            version: "1.0.0".into(),
+            description: String::new(),
+            steps: BTreeMap::from([
+                ("fetch".into(), step(source("fetch.ax"), &[])),
+                ("review".into(), step(source("review.ax"), &["fetch"])),
+                ("report".into(), step(source("report.ax"), &[])),
+            ]),
+            edges: vec![("review".into(), "report".into())],
+            budget: None,
+            params: Default::default(),
+        }
+    }
+
+    #[test]
+    fn dot_contains_nodes_and_edges() {
+        let dot = render_dot(&def(), None);
+        assert!(dot.starts_with("digraph \"pipeline\" {"));
+        assert!(dot.contains("\"fetch\" [label=\"fetch\\nsource\", shape=box];"));
+        assert!(dot.contains("\"fetch\" -> \"review\";"));
+        assert!(dot.contains("\"review\" -> \"report\";"));
+        assert!(!dot.contains("fillcolor=\"#a6e3a1\""));
+    }
+
+    #[test]
+    fn dot_overlay_colors_by_status() {
+        let overlay = RunOverlay::from([(
+            "fetch".to_string(),
+            StepOverlay {
+                status: "completed".into(),
+                duration_ms: Some(12),
+            },
+        )]);
+        let dot = render_dot(&def(), Some(&overlay));
+        assert!(dot.contains("fetch\\nsource\\ncompleted (12ms)"));
+        assert!(dot.contains("fillcolor=\"#a6e3a1\""));
+        // Steps missing from the run render as not-run.
+        assert!(dot
+            .contains("\"report\" [label=\"report\\nsource\", shape=box, fillcolor=\"#ffffff\"]"));
+    }
+
+    #[test]
+    fn mermaid_uses_stable_ids() {
+        let m = render_mermaid(&def(), None);
+        assert!(m.starts_with("flowchart LR\n"));
+        // Sorted ids: fetch=s0, report=s1, review=s2.
+        assert!(m.contains("  s0[\"fetch<br/>source\"]\n"));
+        assert!(m.contains("  s0 --> s2\n"));
+        assert!(m.contains("  s2 --> s1\n"));
+        assert!(!m.contains("style"));
+        assert_eq!(m, render_mermaid(&def(), None));
+    }
+
+    #[test]
+    fn mermaid_overlay_styles_every_node() {
+        let overlay = RunOverlay::from([(
+            "review".to_string(),
+            StepOverlay {
+                status: "failed".into(),
+                duration_ms: None,
+            },
+        )]);
+        let m = render_mermaid(&def(), Some(&overlay));
+        assert!(m.contains("review<br/>source<br/>failed"));
+        assert!(m.contains("  style s2 fill:#f38ba8\n"));
+        assert!(m.contains("  style s0 fill:#ffffff\n"));
+    }
+
+    #[test]
+    fn undeclared_dependencies_are_dropped() {
+        let mut def = def();
+        def.steps
+            .get_mut("review")
+            .unwrap()
+            .depends_on
+            .push("missing".into());
+        def.edges.push(("ghost".into(), "report".into()));
+        def.edges.push(("fetch".into(), "phantom".into()));
+
+        let deps = dependencies(&def);
+        assert_eq!(deps["review"], BTreeSet::from(["fetch"]));
+        assert_eq!(deps["report"], BTreeSet::from(["review"]));
+        assert!(!deps.contains_key("phantom"));
+
+        let m = render_mermaid(&def, None);
+        assert!(m.contains("  s0 --> s2\n"));
+        assert!(!m.contains("missing") && !m.contains("ghost"));
+        let dot = render_dot(&def, None);
+        assert!(!dot.contains("missing") && !dot.contains("ghost"));
+    }
+}
@@ -36,19 +36,20 @@ fn lang_codes_human_lists_all_codes() {
     assert!(out.status.success());
     let s = stdout(&out);
     for code in [
-        "E001", "E002", "E003", "E004", "E005", "E006", "E007", "E008", "E009",
+        "E001", "E002", "E003", "E004", "E005", "E006", "E007", "E008", "E009", "E010", "E011",
+        "E012", "E013", "E014", "E015", "E016", "W001", "W002", "W003", "W004", "W005",
     ] {
         assert!(s.contains(code), "missing {code} in:\n{s}");
     }
 }
 
 #[test]
-fn lang_codes_json_has_nine_entries() {
+fn lang_codes_json_has_twenty_one_entries() {
     let out = run(&["lang", "codes", "--json"]);
     assert!(out.status.success());
     let v: Value = serde_json::from_str(&stdout(&out)).expect("valid JSON");
     let codes = v["codes"].as_array().expect("codes array");
-    assert_eq!(codes.len(), 9);
+    assert_eq!(codes.len(), 21);
     for c in codes {
         assert!(c["code"].is_string());
         assert!(c["name"].is_string());
@@ -144,7 +145,38 @@ fn workflow_graph_detects_a_cycle() {
         .arg(dir.path())
         .output()
         .expect("invoke boruna");
-    assert_eq!(out.status.code(), Some(1), "cyclic graph must exit 1");
+    assert_eq!(out.status.code(), Some(2), "cyclic graph must exit 2");
+}
+
+#[test]
+fn workflow_graph_refuses_invalid_definitions_in_every_format() {
+    let src = fs::read_to_string(llm_code_review_dir().join("workflow.json")).unwrap();
+    let valid: Value = serde_json::from_str(&src).unwrap();
+    let mut undeclared = valid.clone();
+    undeclared["steps"]["fetch_diff"]["depends_on"] = serde_json::json!(["no_such_step"]);
+    let mut cyclic = valid;
+    cyclic["steps"]["fetch_diff"]["depends_on"] = serde_json::json!(["report"]);
+
+    for (def, formats) in [
+        (undeclared, &["dot", "mermaid", "text", "json"][..]),
+        (cyclic, &["dot", "mermaid"][..]),
+    ] {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("workflow.json"), def.to_string()).unwrap();
+        for format in formats {
+            let out = Command::new(boruna_bin())
+                .args(["workflow", "graph", "--format", format])
+                .arg(dir.path())
+                .output()
+                .expect("invoke boruna");
+            assert_eq!(out.status.code(), Some(2), "{format}");
+            assert!(stdout(&out).is_empty(), "{format}");
+            assert!(
+                String::from_utf8_lossy(&out.stderr).contains("validation failed"),
+                "{format}"
+            );
+        }
+    }
 }
 
 #[test]
@@ -0,0 +1,43 @@
+//! CLI integration tests for `compile --budget`.
+
+use std::process::{Command, Output};
+
+use tempfile::tempdir;
+
+const SOURCE: &str = "fn helper() -> Int { 1 }\nfn main() -> Int { helper() }\n";
+
+fn compile(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_boruna"))
+        .arg("compile")
+        .args(args)
+        .output()
+        .expect("invoke boruna")
+}
+
+#[test]
+fn compile_fails_when_the_module_exceeds_its_budget() {
+    let dir = tempdir().unwrap();
+    let src = dir.path().join("app.ax");
+    std::fs::write(&src, SOURCE).unwrap();
+    let out_path = dir.path().join("app.axbc");
+    let budget = dir.path().join("budget.json");
+
+    std::fs::write(&budget, r#"{"max_functions": 1}"#).unwrap();
+    let out = compile(&[src.to_str().unwrap(), "--budget", budget.to_str().unwrap()]);
+    assert!(!out.status.success());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("module has 2 functions, over max_functions (1)"),
+        "stderr: {stderr}"
+    );
+    assert!(!out_path.exists());
+
+    std::fs::write(&budget, r#"{"max_functions": 2, "max_nesting_depth": 0}"#).unwrap();
+    let out = compile(&[src.to_str().unwrap(), "--budget", budget.to_str().unwrap()]);
+    assert!(
+        out.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert!(out_path.exists());
+}
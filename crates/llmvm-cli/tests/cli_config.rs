@@ -0,0 +1,109 @@
+//! CLI integration tests for layered defaults (`boruna.toml`, user
+//! config, environment, flags) and `boruna config show`.
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+use tempfile::tempdir;
+
+const KV_APP: &str = "fn main() -> Int !{kv.get} {\n kv_get(\"a\")\n 0\n}\n";
+
+/// Run `boruna` in `cwd` with `home` as the home directory and no
+/// config variables from the calling environment.
+fn boruna(cwd: &Path, home: &Path, env: &[(&str, &str)], args: &[&str]) -> Output {
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_boruna"));
+    cmd.current_dir(cwd).env("HOME", home);
+    for var in [
+        "XDG_CONFIG_HOME",
+        "BORUNA_CONFIG",
+        "BORUNA_POLICY",
+        "BORUNA_MAX_STEPS",
+        "BORUNA_ENV",
+        "BORUNA_DATA_DIR",
+    ] {
+        cmd.env_remove(var);
+    }
+    cmd.envs(env.iter().copied())
+        .args(args)
+        .output()
+        .expect("invoke boruna")
+}
+
+#[test]
+fn flags_override_env_override_workspace_override_user() {
+    let home = tempdir().unwrap();
+    let ws = tempdir().unwrap();
+    let user = home.path().join(".config/boruna");
+    std::fs::create_dir_all(&user).unwrap();
+    std::fs::write(user.join("config.toml"), "policy = \"allow-all\"\n").unwrap();
+    let app_dir = ws.path().join("app");
+    std::fs::create_dir_all(&app_dir).unwrap();
+    std::fs::write(app_dir.join("app.ax"), KV_APP).unwrap();
+    let run = |env: &[(&str, &str)], args: &[&str]| {
+        let args: Vec<&str> = ["run", "app.ax"].iter().chain(args).copied().collect();
+        boruna(&app_dir, home.path(), env, &args).status.code()
+    };
+
+    assert_eq!(run(&[], &[]), Some(0), "user config allows");
+    std::fs::write(ws.path().join("boruna.toml"), "policy = \"deny-all\"\n").unwrap();
+    assert_eq!(run(&[], &[]), Some(3), "workspace denies");
+    assert_eq!(
+        run(&[("BORUNA_POLICY", "allow-all")], &[]),
+        Some(0),
+        "env allows"
+    );
+    assert_eq!(
+        run(&[("BORUNA_POLICY", "deny-all")], &["--policy", "allow-all"]),
+        Some(0),
+        "flag allows"
+    );
+}
+
+#[test]
+fn config_show_resolved_reports_sources() {
+    let home = tempdir().unwrap();
+    let ws = tempdir().unwrap();
+    std::fs::write(
+        ws.path().join("boruna.toml"),
+        "max_steps = 1000\n\n[telemetry]\nservice_name = \"orders\"\n",
+    )
+    .unwrap();
+    let out = boruna(
+        ws.path(),
+        home.path(),
+        &[("BORUNA_ENV", "staging")],
+        &["config", "show", "--resolved", "--json"],
+    );
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    let setting = |key: &str| {
+        v["settings"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|s| s["key"] == key)
+            .unwrap()
+            .clone()
+    };
+    assert_eq!(setting("max_steps")["value"], "1000");
+    assert_eq!(setting("max_steps")["source"], "workspace");
+    assert_eq!(setting("telemetry.service_name")["value"], "orders");
+    assert_eq!(setting("env")["source"], "env");
+    assert_eq!(setting("policy")["source"], "default");
+    assert_eq!(v["files"].as_array().unwrap().len(), 2);
+}
+
+#[test]
+fn invalid_config_exits_2() {
+    let home = tempdir().unwrap();
+    let ws = tempdir().unwrap();
+    std::fs::write(ws.path().join("boruna.toml"), "max_step = 10\n").unwrap();
+    let out = boruna(ws.path(), home.path(), &[], &["config", "show", "--json"]);
+    assert_eq!(out.status.code(), Some(2));
+    let envelope: serde_json::Value = serde_json::from_slice(&out.stderr).unwrap();
+    assert_eq!(envelope["code"], "CFG001");
+}
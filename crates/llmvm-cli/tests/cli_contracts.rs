@@ -0,0 +1,69 @@
+//! CLI integration tests for contract modes: `run --contracts` and
+//! `compile --release`.
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+use tempfile::tempdir;
+
+const SRC: &str = "fn check(x: Int) -> Int requires x > 0 { x }\nfn main() -> Int { check(0) }\n";
+
+fn boruna(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_boruna"))
+        .args(args)
+        .output()
+        .expect("invoke boruna")
+}
+
+fn write_source(dir: &Path) -> String {
+    let path = dir.join("check.ax");
+    std::fs::write(&path, SRC).unwrap();
+    path.to_string_lossy().into_owned()
+}
+
+#[test]
+fn run_enforces_contracts_by_default() {
+    let dir = tempdir().unwrap();
+    let out = boruna(&["run", &write_source(dir.path())]);
+    assert!(!out.status.success());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("[E013] precondition 1 failed in `check`: requires x > 0"),
+        "stderr: {stderr}"
+    );
+    assert!(stderr.contains("[counterexample: (0)]"), "stderr: {stderr}");
+}
+
+#[test]
+fn run_contracts_off_skips_checks() {
+    let dir = tempdir().unwrap();
+    let out = boruna(&["run", &write_source(dir.path()), "--contracts", "off"]);
+    assert!(
+        out.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert_eq!(stdout.lines().next(), Some("0"), "stdout: {stdout}");
+}
+
+#[test]
+fn release_build_strips_contracts() {
+    let dir = tempdir().unwrap();
+    let src = write_source(dir.path());
+    let axbc = dir.path().join("check.axbc");
+    let axbc = axbc.to_string_lossy();
+    let out = boruna(&["compile", &src, "--release", "-o", &axbc]);
+    assert!(
+        out.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    // Even with enforcement on, there is nothing left to check.
+    let out = boruna(&["run", &axbc, "--contracts", "enforce"]);
+    assert!(
+        out.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+}
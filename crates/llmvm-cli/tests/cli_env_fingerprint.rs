@@ -0,0 +1,113 @@
+//! CLI integration tests for `boruna workflow run --fingerprint-env`.
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+use tempfile::tempdir;
+
+const WORKFLOW: &str = r#"{
+  "schema_version": 1,
+  "name": "fingerprint-demo",
+  "version": "1.0.0",
+  "steps": { "greet": { "kind": "source", "source": "greet.ax" } },
+  "edges": []
+}"#;
+
+const SECRET: &str = "hunter2-do-not-leak";
+
+fn boruna(args: &[&str], envs: &[(&str, &str)]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_boruna"))
+        .args(args)
+        .envs(envs.iter().copied())
+        .output()
+        .expect("invoke boruna")
+}
+
+fn record(dir: &Path, envs: &[(&str, &str)]) -> PathBuf {
+    std::fs::write(dir.join("workflow.json"), WORKFLOW).unwrap();
+    std::fs::write(dir.join("greet.ax"), "fn main() -> String { \"hi\" }").unwrap();
+    let evidence = dir.join("evidence");
+    let out = boruna(
+        &[
+            "workflow",
+            "run",
+            &dir.to_string_lossy(),
+            "--ephemeral",
+            "--record",
+            "--evidence-dir",
+            &evidence.to_string_lossy(),
+            "--fingerprint-env",
+            "API_TOKEN,BORUNA_TEST_UNSET_VAR",
+        ],
+        envs,
+    );
+    assert!(
+        out.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    std::fs::read_dir(&evidence)
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap()
+        .path()
+}
+
+#[test]
+fn fingerprinted_vars_are_hashed_and_the_bundle_verifies() {
+    let dir = tempdir().unwrap();
+    let config = dir.path().join("config");
+    let bundle = record(
+        dir.path(),
+        &[
+            ("API_TOKEN", SECRET),
+            ("XDG_CONFIG_HOME", &config.to_string_lossy()),
+        ],
+    );
+    assert!(config.join("boruna/fingerprint.key").is_file());
+
+    let text = std::fs::read_to_string(bundle.join("env_fingerprint.json")).unwrap();
+    assert!(!text.contains(SECRET), "raw value leaked: {text}");
+    let fingerprint: serde_json::Value = serde_json::from_str(&text).unwrap();
+    let digest = fingerprint["env_vars"]["API_TOKEN"].as_str().unwrap();
+    assert_eq!(digest.len(), 64);
+    // Keyed, so not the plain SHA-256 a dictionary attack would try.
+    assert_ne!(digest, boruna_hash::sha256_hex(SECRET));
+    assert!(fingerprint["env_vars"]["BORUNA_TEST_UNSET_VAR"].is_null());
+    assert!(fingerprint["locale"].is_string());
+    assert!(fingerprint["timezone"].is_string());
+    let manifest = std::fs::read_to_string(bundle.join("manifest.json")).unwrap();
+    assert!(!manifest.contains(SECRET));
+
+    let out = boruna(&["evidence", "verify", &bundle.to_string_lossy()], &[]);
+    assert!(
+        out.status.success(),
+        "stdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&out.stdout),
+        String::from_utf8_lossy(&out.stderr)
+    );
+}
+
+#[test]
+fn evidence_diff_reports_env_var_drift() {
+    let a = tempdir().unwrap();
+    let b = tempdir().unwrap();
+    let key = ("BORUNA_FINGERPRINT_KEY", "shared-ci-key");
+    let bundle_a = record(a.path(), &[key, ("API_TOKEN", "one")]);
+    let bundle_b = record(b.path(), &[key, ("API_TOKEN", "two")]);
+
+    let out = boruna(
+        &[
+            "evidence",
+            "diff",
+            &bundle_a.to_string_lossy(),
+            &bundle_b.to_string_lossy(),
+        ],
+        &[],
+    );
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("env.API_TOKEN"), "stdout: {stdout}");
+    // Both sides used the same key, so only the value differs.
+    assert!(!stdout.contains("env_key_id"), "stdout: {stdout}");
+}
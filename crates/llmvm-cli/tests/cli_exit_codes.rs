@@ -0,0 +1,69 @@
+//! CLI integration tests for the exit-code contract
+//! (`docs/reference/exit-codes.md`).
+
+use std::path::Path;
+use std::process::Command;
+
+use tempfile::tempdir;
+
+fn exit_code(dir: &Path, source: &str, args: &[&str]) -> Option<i32> {
+    let file = dir.join("app.ax");
+    std::fs::write(&file, source).unwrap();
+    Command::new(env!("CARGO_BIN_EXE_boruna"))
+        .args(args)
+        .arg(&file)
+        .output()
+        .expect("invoke boruna")
+        .status
+        .code()
+}
+
+#[test]
+fn failures_exit_with_their_class() {
+    let dir = tempdir().unwrap();
+    let dir = dir.path();
+
+    assert_eq!(exit_code(dir, "fn main() -> Int { 1 }", &["run"]), Some(0));
+    assert_eq!(
+        exit_code(dir, "fn main() -> Int { 1 + }", &["compile"]),
+        Some(2),
+        "compile error"
+    );
+    assert_eq!(
+        exit_code(dir, "fn main() -> Int { 1 + }", &["run"]),
+        Some(2),
+        "compile error under run"
+    );
+    assert_eq!(
+        exit_code(
+            dir,
+            "fn main() -> Int !{kv.get} {\n kv_get(\"a\")\n 0\n}",
+            &["run", "--policy", "deny-all"],
+        ),
+        Some(3),
+        "policy denial"
+    );
+    assert_eq!(
+        exit_code(
+            dir,
+            "fn loop_(n: Int) -> Int { loop_(n + 1) }\nfn main() -> Int { loop_(0) }",
+            &["run", "--max-steps", "1000"],
+        ),
+        Some(4),
+        "limit exceeded"
+    );
+    assert_eq!(
+        exit_code(dir, "fn main() -> Int { 1 / 0 }", &["run"]),
+        Some(7),
+        "runtime error"
+    );
+}
+
+#[test]
+fn usage_errors_exit_2() {
+    let out = Command::new(env!("CARGO_BIN_EXE_boruna"))
+        .arg("--no-such-flag")
+        .output()
+        .expect("invoke boruna");
+    assert_eq!(out.status.code(), Some(2));
+}
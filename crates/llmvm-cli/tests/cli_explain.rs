@@ -0,0 +1,47 @@
+//! CLI integration tests for `boruna explain` and `boruna completions`.
+
+use std::process::{Command, Output};
+
+fn boruna(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_boruna"))
+        .args(args)
+        .output()
+        .expect("invoke boruna")
+}
+
+#[test]
+fn explain_prints_causes_and_fixes() {
+    let out = boruna(&["explain", "E005"]);
+    assert!(out.status.success());
+    let text = String::from_utf8(out.stdout).unwrap();
+    assert!(text.starts_with("E005: non-exhaustive-match\n"), "{text}");
+    assert!(text.contains("Common causes:"), "{text}");
+    assert!(text.contains("How to fix:"), "{text}");
+}
+
+#[test]
+fn explain_accepts_exit_codes_and_json() {
+    let out = boruna(&["explain", "exit:4", "--json"]);
+    assert!(out.status.success());
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(v["id"], "exit:4");
+    assert_eq!(v["title"], "limit exceeded");
+    assert!(!v["fixes"].as_array().unwrap().is_empty());
+}
+
+#[test]
+fn explain_rejects_unknown_codes() {
+    let out = boruna(&["explain", "E999"]);
+    assert_eq!(out.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("unknown code 'E999'"));
+}
+
+#[test]
+fn completions_cover_every_shell() {
+    for shell in ["bash", "zsh", "fish"] {
+        let out = boruna(&["completions", shell]);
+        assert!(out.status.success(), "{shell}");
+        let script = String::from_utf8(out.stdout).unwrap();
+        assert!(script.contains("explain"), "{shell}");
+    }
+}
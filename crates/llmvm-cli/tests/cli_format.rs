@@ -2,7 +2,7 @@
 //!
 //! Validates the three behaviors that matter for CI integration:
 //!
-//! 1. `boruna fmt --check <unformatted>` exits 1.
+//! 1. `boruna fmt --check <unformatted>` exits 6 (check failed).
 //! 2. `boruna fmt <unformatted>` rewrites the file in place.
 //! 3. After in-place rewrite, `boruna fmt --check <file>` exits 0.
 
@@ -18,7 +18,7 @@ fn boruna_bin() -> &'static str {
 const UNFORMATTED: &str = "fn main() -> Int {\nlet x: Int = 1\nx\n}\n";
 
 #[test]
-fn fmt_check_exits_1_on_unformatted() {
+fn fmt_check_exits_6_on_unformatted() {
     let dir = tempdir().unwrap();
     let path = dir.path().join("u.ax");
     fs::write(&path, UNFORMATTED).unwrap();
@@ -30,7 +30,7 @@ fn fmt_check_exits_1_on_unformatted() {
         .expect("invoke boruna");
     assert_eq!(
         out.status.code(),
-        Some(1),
+        Some(6),
         "stdout: {}\nstderr: {}",
         String::from_utf8_lossy(&out.stdout),
         String::from_utf8_lossy(&out.stderr)
@@ -0,0 +1,36 @@
+//! CLI integration tests for `inspect --callgraph dot`.
+
+use std::process::Command;
+
+use tempfile::tempdir;
+
+const SOURCE: &str = "fn enqueue() -> Int !{queue.push} { queue_push(\"jobs\", 1) }\n\
+    fn submit() -> Int { enqueue() }\n\
+    fn main() -> Int { submit() }\n";
+
+#[test]
+fn callgraph_dot_traces_capabilities_to_main() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("jobs.ax");
+    std::fs::write(&path, SOURCE).unwrap();
+    let out = Command::new(env!("CARGO_BIN_EXE_boruna"))
+        .args(["inspect", path.to_str().unwrap(), "--callgraph", "dot"])
+        .output()
+        .expect("invoke boruna");
+    assert!(
+        out.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let dot = String::from_utf8_lossy(&out.stdout);
+    assert!(dot.starts_with("digraph \"jobs\" {"), "got: {dot}");
+    assert!(dot.contains("main/0"), "got: {dot}");
+    assert!(dot.contains("caps: queue.push"), "got: {dot}");
+    // Both edges on the path from main carry the capability.
+    assert_eq!(
+        dot.matches("[label=\"queue.push\"]").count(),
+        2,
+        "got: {dot}"
+    );
+    assert!(!dot.contains("Functions ("), "got: {dot}");
+}
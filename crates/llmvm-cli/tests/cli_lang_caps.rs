@@ -0,0 +1,83 @@
+//! CLI integration tests for the per-message matrix of `lang caps`.
+
+use std::process::{Command, Output};
+
+use tempfile::tempdir;
+
+const APP: &str = r#"type Msg { tag: String, payload: String }
+type Effect { kind: String, payload: String, callback_tag: String }
+type UpdateResult { state: Int, effects: List<Effect> }
+
+fn init() -> Int { 0 }
+
+fn update(state: Int, msg: Msg) -> UpdateResult {
+    if msg.tag == "load" {
+        UpdateResult { state: state, effects: [Effect { kind: "http_request", payload: "", callback_tag: "loaded" }] }
+    } else {
+        UpdateResult { state: state + 1, effects: [] }
+    }
+}
+
+fn view(state: Int) -> Int { state }
+"#;
+
+fn boruna(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_boruna"))
+        .args(args)
+        .output()
+        .expect("invoke boruna")
+}
+
+#[test]
+fn caps_json_reports_messages_and_emits_policy() {
+    let dir = tempdir().unwrap();
+    let src = dir.path().join("app.ax");
+    std::fs::write(&src, APP).unwrap();
+    let policy = dir.path().join("policy.json");
+    let out = boruna(&[
+        "lang",
+        "caps",
+        &src.to_string_lossy(),
+        "--json",
+        "--emit-policy",
+        &policy.to_string_lossy(),
+    ]);
+    assert!(
+        out.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let report: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    let messages = report["messages"].as_array().unwrap();
+    assert_eq!(messages.len(), 2);
+    assert_eq!(messages[0]["tag"], "load");
+    assert_eq!(
+        messages[0]["capabilities"],
+        serde_json::json!(["net.fetch"])
+    );
+    assert_eq!(messages[1]["tag"], "*");
+    assert_eq!(messages[1]["capabilities"], serde_json::json!([]));
+
+    let policy: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&policy).unwrap()).unwrap();
+    assert_eq!(policy["default_allow"], false);
+    assert_eq!(policy["rules"]["net.fetch"]["allow"], true);
+    assert_eq!(policy["rules"].as_object().unwrap().len(), 1);
+}
+
+#[test]
+fn emit_policy_rejects_non_app() {
+    let dir = tempdir().unwrap();
+    let src = dir.path().join("main.ax");
+    std::fs::write(&src, "fn main() -> Int { 1 }\n").unwrap();
+    let policy = dir.path().join("policy.json");
+    let out = boruna(&[
+        "lang",
+        "caps",
+        &src.to_string_lossy(),
+        "--emit-policy",
+        &policy.to_string_lossy(),
+    ]);
+    assert!(!out.status.success());
+    assert!(!policy.exists());
+}
@@ -1,4 +1,4 @@
-//! CLI integration tests for `boruna policy {validate, show}`
+//! CLI integration tests for `boruna policy {validate, show, schema, infer}`
 //! (sprint 0.4-S15). Uses `env!("CARGO_BIN_EXE_boruna")` to invoke
 //! the freshly compiled binary.
 
@@ -266,3 +266,79 @@ fn run_with_invalid_policy_propagates_kind() {
         "should propagate stable error_kind from validator. stderr: {stderr}"
     );
 }
+
+#[test]
+fn policy_schema_prints_the_versioned_schema() {
+    let out = Command::new(boruna_bin())
+        .args(["policy", "schema"])
+        .output()
+        .expect("invoke boruna");
+    assert!(out.status.success());
+    let schema: serde_json::Value = serde_json::from_slice(&out.stdout).expect("schema is JSON");
+    assert_eq!(
+        schema["x-schema-version"].as_u64(),
+        Some(u64::from(boruna_vm::POLICY_SCHEMA_VERSION))
+    );
+    assert_eq!(schema["additionalProperties"], false);
+}
+
+#[test]
+fn policy_infer_writes_a_policy_that_validates() {
+    use boruna_bytecode::{Capability, Value};
+    use boruna_vm::replay::EventLog;
+
+    let dir = tempfile::tempdir().unwrap();
+    let mut paths = Vec::new();
+    for (i, fetches) in [1, 2].into_iter().enumerate() {
+        let mut log = EventLog::new();
+        for _ in 0..fetches {
+            let args = [Value::String("https://api.example.com/v1".into())];
+            log.log_cap_call(&Capability::NetFetch, &args);
+            log.log_cap_result(&Capability::NetFetch, &Value::String("ok".into()));
+        }
+        let path = dir.path().join(format!("run{i}.json"));
+        std::fs::write(&path, log.to_json().unwrap()).unwrap();
+        paths.push(path);
+    }
+    let policy_path = dir.path().join("policy.json");
+    let out = Command::new(boruna_bin())
+        .args([
+            "policy",
+            "infer",
+            "--from-log",
+            paths[0].to_str().unwrap(),
+            "--from-log",
+            paths[1].to_str().unwrap(),
+            "--out",
+            policy_path.to_str().unwrap(),
+            "--json",
+        ])
+        .output()
+        .expect("invoke boruna");
+    assert!(
+        out.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let report: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(report["runs"], 2);
+    assert_eq!(report["policy"]["rules"]["net.fetch"]["budget"], 2);
+    assert_eq!(
+        report["newly_denied"]["domains"]["after"],
+        serde_json::json!(["api.example.com"])
+    );
+    assert!(report["newly_denied"]["capabilities"]
+        .as_array()
+        .unwrap()
+        .contains(&serde_json::json!("fs.write")));
+
+    let out = Command::new(boruna_bin())
+        .args(["policy", "validate", policy_path.to_str().unwrap()])
+        .output()
+        .expect("invoke boruna");
+    assert!(
+        out.status.success(),
+        "inferred policy should validate: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+}
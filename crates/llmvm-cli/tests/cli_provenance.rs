@@ -0,0 +1,79 @@
+//! CLI integration tests for the module provenance section written by
+//! `boruna compile` and shown by `boruna inspect`.
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+use tempfile::tempdir;
+
+const SOURCE: &str = "fn main() -> Int { 42 }\n";
+
+fn boruna(args: &[&str], epoch: Option<&str>) -> Output {
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_boruna"));
+    match epoch {
+        Some(epoch) => cmd.env("SOURCE_DATE_EPOCH", epoch),
+        None => cmd.env_remove("SOURCE_DATE_EPOCH"),
+    };
+    cmd.args(args).output().expect("invoke boruna")
+}
+
+fn compile(src: &Path, epoch: Option<&str>) -> Vec<u8> {
+    let out = boruna(&["compile", src.to_str().unwrap()], epoch);
+    assert!(
+        out.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    std::fs::read(src.with_extension("axbc")).unwrap()
+}
+
+#[test]
+fn compile_records_provenance_and_inspect_shows_it() {
+    let dir = tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("package.ax.json"),
+        r#"{"name": "demo.app", "version": "0.3.0", "dependencies": {}}"#,
+    )
+    .unwrap();
+    let src_dir = dir.path().join("src");
+    std::fs::create_dir_all(&src_dir).unwrap();
+    let src = src_dir.join("app.ax");
+    std::fs::write(&src, SOURCE).unwrap();
+
+    compile(&src, Some("1700000000"));
+    let out = boruna(
+        &["inspect", src.with_extension("axbc").to_str().unwrap()],
+        None,
+    );
+    assert!(out.status.success());
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("Provenance:"), "stdout: {stdout}");
+    assert!(
+        stdout.contains("compiler: boruna-compiler "),
+        "stdout: {stdout}"
+    );
+    assert!(stdout.contains("source: sha256:"), "stdout: {stdout}");
+    assert!(
+        stdout.contains("package: demo.app@0.3.0"),
+        "stdout: {stdout}"
+    );
+    assert!(stdout.contains("built_at: 1700000000"), "stdout: {stdout}");
+}
+
+#[test]
+fn provenance_keeps_builds_reproducible() {
+    let dir = tempdir().unwrap();
+    let src = dir.path().join("app.ax");
+    std::fs::write(&src, SOURCE).unwrap();
+
+    assert_eq!(
+        compile(&src, Some("1700000000")),
+        compile(&src, Some("1700000000"))
+    );
+    // Without SOURCE_DATE_EPOCH the build time is left out, not read
+    // from the clock.
+    let first = compile(&src, None);
+    std::thread::sleep(std::time::Duration::from_millis(1100));
+    assert_eq!(first, compile(&src, None));
+    assert!(!String::from_utf8_lossy(&first).contains("built_at"));
+}
@@ -0,0 +1,72 @@
+//! CLI integration tests for `run --queue-dir`: one run pushes, later
+//! runs poll in push order.
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+use tempfile::tempdir;
+
+const PRODUCER: &str = "fn enqueue() -> Int !{queue.push} {\n    \
+    let a: Int = queue_push(\"jobs\", \"first\")\n    \
+    queue_push(\"jobs\", \"second\")\n}\n\
+    fn main() -> Int { enqueue() }\n";
+
+const CONSUMER: &str = "fn take() -> String !{queue.poll} {\n    \
+    match queue_poll(\"jobs\") {\n        \
+    Some(job) => job,\n        \
+    None => \"empty\",\n    \
+    }\n}\n\
+    fn main() -> String { take() }\n";
+
+fn boruna(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_boruna"))
+        .args(args)
+        .output()
+        .expect("invoke boruna")
+}
+
+fn write(dir: &Path, name: &str, src: &str) -> String {
+    let path = dir.join(name);
+    std::fs::write(&path, src).unwrap();
+    path.to_string_lossy().into_owned()
+}
+
+fn first_line(out: &Output) -> String {
+    assert!(
+        out.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    stdout.lines().next().unwrap_or_default().to_string()
+}
+
+#[test]
+fn queue_dir_carries_items_between_runs() {
+    let dir = tempdir().unwrap();
+    let queues = dir.path().join("queues");
+    let queues = queues.to_string_lossy();
+    let producer = write(dir.path(), "producer.ax", PRODUCER);
+    let consumer = write(dir.path(), "consumer.ax", CONSUMER);
+
+    // The second push is item 2.
+    let out = boruna(&["run", &producer, "--queue-dir", &queues]);
+    assert_eq!(first_line(&out), "2");
+
+    let out = boruna(&["run", &consumer, "--queue-dir", &queues]);
+    assert_eq!(first_line(&out), "\"first\"");
+    let out = boruna(&["run", &consumer, "--queue-dir", &queues]);
+    assert_eq!(first_line(&out), "\"second\"");
+    let out = boruna(&["run", &consumer, "--queue-dir", &queues]);
+    assert_eq!(first_line(&out), "\"empty\"");
+}
+
+#[test]
+fn queue_without_dir_is_discarded_after_the_run() {
+    let dir = tempdir().unwrap();
+    let producer = write(dir.path(), "producer.ax", PRODUCER);
+    let consumer = write(dir.path(), "consumer.ax", CONSUMER);
+    first_line(&boruna(&["run", &producer]));
+    let out = boruna(&["run", &consumer]);
+    assert_eq!(first_line(&out), "\"empty\"");
+}
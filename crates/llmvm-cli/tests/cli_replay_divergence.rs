@@ -0,0 +1,81 @@
+//! CLI integration tests for the divergence analysis `boruna replay`
+//! prints when a replay does not match its recording.
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+use tempfile::tempdir;
+
+const PROGRAM: &str = "fn main() -> Option<String> uses kv.get {\n    kv_get(\"greeting\")\n}\n";
+
+fn boruna(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_boruna"))
+        .args(args)
+        .output()
+        .expect("invoke boruna")
+}
+
+/// Record a run of `PROGRAM`, returning `(program, log)` paths.
+fn record(dir: &Path) -> (String, String) {
+    let program = dir.join("greet.ax");
+    let log = dir.join("log.json");
+    std::fs::write(&program, PROGRAM).unwrap();
+    let (program, log) = (
+        program.to_string_lossy().into_owned(),
+        log.to_string_lossy().into_owned(),
+    );
+    let out = boruna(&["run", &program, "--record", &log]);
+    assert!(
+        out.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    (program, log)
+}
+
+#[test]
+fn identical_replay_reports_no_divergence() {
+    let dir = tempdir().unwrap();
+    let (program, log) = record(dir.path());
+
+    let out = boruna(&["replay", &program, &log, "--json"]);
+    assert!(out.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(report["verification"], "identical");
+    assert!(report["divergence"].is_null());
+}
+
+#[test]
+fn diverged_replay_points_at_the_first_differing_event() {
+    let dir = tempdir().unwrap();
+    let (program, log) = record(dir.path());
+    let tampered = std::fs::read_to_string(&log)
+        .unwrap()
+        .replace("\"greeting\"", "\"farewell\"");
+    std::fs::write(&log, tampered).unwrap();
+
+    let out = boruna(&["replay", &program, &log, "--json"]);
+    assert_eq!(out.status.code(), Some(5), "divergence exits 5");
+    let report: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(report["verification"], "diverged");
+    let divergence = &report["divergence"];
+    assert_eq!(divergence["kind"], "call_arguments");
+    assert_eq!(divergence["event_index"], 0);
+    assert_eq!(divergence["path"], "/CapCall/args/0/String");
+    assert_eq!(
+        divergence["expected"]["CapCall"]["args"][0]["String"],
+        "farewell"
+    );
+    assert_eq!(
+        divergence["actual"]["CapCall"]["args"][0]["String"],
+        "greeting"
+    );
+
+    let out = boruna(&["replay", &program, &log]);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("divergence:"), "stdout: {stdout}");
+    assert!(
+        stdout.contains("\"kind\": \"call_arguments\""),
+        "stdout: {stdout}"
+    );
+}
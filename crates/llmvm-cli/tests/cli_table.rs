@@ -0,0 +1,99 @@
+//! CLI integration tests for tables: load a CSV with `data.read_table`
+//! and work on it with the table built-ins, or build one from a literal.
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+use tempfile::tempdir;
+
+const SALES: &str = "region,units\neu,3\nus,5\neu,4\nus,1\n";
+
+fn program(csv: &Path) -> String {
+    format!(
+        "fn load() -> Table !{{data.read_table}} {{\n    \
+         read_table(\"{}\")\n}}\n\
+         fn main() -> Table {{\n    \
+         let big: Table = table_filter(load(), \"units\", \">=\", 3)\n    \
+         table_aggregate(big, [\"region\"], \"units\", \"sum\")\n}}\n",
+        csv.display()
+    )
+}
+
+fn boruna(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_boruna"))
+        .args(args)
+        .output()
+        .expect("invoke boruna")
+}
+
+#[test]
+fn read_table_then_filter_and_aggregate() {
+    let dir = tempdir().unwrap();
+    let csv = dir.path().join("sales.csv");
+    std::fs::write(&csv, SALES).unwrap();
+    let src = dir.path().join("report.ax");
+    std::fs::write(&src, program(&csv)).unwrap();
+
+    let out = boruna(&["run", &src.to_string_lossy()]);
+    assert!(
+        out.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert_eq!(
+        stdout.lines().next().unwrap_or_default(),
+        r#"Table(region, sum_units)[("eu", 7), ("us", 5)]"#
+    );
+}
+
+#[test]
+fn read_table_respects_policy_row_limit() {
+    let dir = tempdir().unwrap();
+    let csv = dir.path().join("sales.csv");
+    std::fs::write(&csv, SALES).unwrap();
+    let src = dir.path().join("report.ax");
+    std::fs::write(&src, program(&csv)).unwrap();
+    let policy = dir.path().join("policy.json");
+    std::fs::write(
+        &policy,
+        r#"{"default_allow": true, "table_policy": {"max_rows": 2}}"#,
+    )
+    .unwrap();
+
+    let out = boruna(&[
+        "run",
+        &src.to_string_lossy(),
+        "--policy",
+        &policy.to_string_lossy(),
+    ]);
+    assert!(!out.status.success());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("max_rows"), "stderr: {stderr}");
+}
+
+#[test]
+fn table_literal_sorts_and_exposes_rows() {
+    let dir = tempdir().unwrap();
+    let src = dir.path().join("literal.ax");
+    std::fs::write(
+        &src,
+        "fn main() -> Option<Map<String, Int>> {\n    \
+         let t: Table = Table { region: [\"us\", \"eu\", \"us\"], units: [5, 3, 1] }\n    \
+         let sorted: Table = table_sort(t, [\"region\", \"units\"], false)\n    \
+         table_row(sorted, table_len(sorted) - 2)\n}\n",
+    )
+    .unwrap();
+
+    let out = boruna(&["run", &src.to_string_lossy()]);
+    assert!(
+        out.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert_eq!(
+        stdout.lines().next().unwrap_or_default(),
+        r#"Some({region: "us", units: 1})"#
+    );
+}
@@ -0,0 +1,173 @@
+//! CLI integration tests for `template add` and the remote template cache.
+
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+
+use tempfile::tempdir;
+
+const MANIFEST: &str = r#"{
+  "name": "billing",
+  "version": "1.2.0",
+  "description": "Invoice pipeline",
+  "dependencies": [],
+  "capabilities": ["db.query"],
+  "args": {
+    "entity": { "type": "string", "required": true, "description": "Entity name" }
+  }
+}"#;
+
+fn git(dir: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .current_dir(dir)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .expect("invoke git");
+    assert!(status.success(), "git {args:?} failed");
+}
+
+/// A git repository holding one template under `templates/`.
+fn template_repo(dir: &Path) {
+    let t = dir.join("templates/billing");
+    std::fs::create_dir_all(&t).unwrap();
+    std::fs::write(t.join("template.json"), MANIFEST).unwrap();
+    std::fs::write(
+        t.join("app.ax.template"),
+        "// {{entity}}\nfn main() -> Int { 0 }\n",
+    )
+    .unwrap();
+    git(dir, &["init", "--quiet"]);
+    git(dir, &["add", "."]);
+    git(dir, &["commit", "--quiet", "-m", "billing template"]);
+}
+
+fn boruna(args: &[&str], stdin: &str, cwd: &Path) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_boruna"))
+        .args(args)
+        .current_dir(cwd)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("invoke boruna");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn declining_the_trust_prompt_installs_nothing() {
+    let repo = tempdir().unwrap();
+    template_repo(repo.path());
+    let work = tempdir().unwrap();
+    let out = boruna(
+        &["template", "add", repo.path().to_str().unwrap()],
+        "n\n",
+        work.path(),
+    );
+    assert!(!out.status.success());
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("billing v1.2.0"), "got: {stdout}");
+    assert!(stdout.contains("caps: db.query"), "got: {stdout}");
+    assert!(stdout.contains("Trust templates from"), "got: {stdout}");
+    assert!(!work.path().join(".boruna/template-cache").exists());
+}
+
+#[test]
+fn added_templates_list_with_provenance_and_apply() {
+    let repo = tempdir().unwrap();
+    template_repo(repo.path());
+    let source = repo.path().to_str().unwrap();
+    let work = tempdir().unwrap();
+
+    let out = boruna(&["template", "add", source], "y\n", work.path());
+    assert!(
+        out.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert!(work
+        .path()
+        .join(".boruna/template-cache/billing/template.json")
+        .exists());
+
+    let out = boruna(&["template", "list"], "", work.path());
+    assert!(out.status.success());
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(
+        stdout.contains("billing v1.2.0 — Invoice pipeline"),
+        "got: {stdout}"
+    );
+    assert!(
+        stdout.contains(&format!("from: {source} (sha256:")),
+        "got: {stdout}"
+    );
+
+    let out = boruna(
+        &[
+            "template",
+            "apply",
+            "billing",
+            "--args",
+            "entity=invoices",
+            "--out",
+            "app.ax",
+        ],
+        "",
+        work.path(),
+    );
+    assert!(
+        out.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let app = std::fs::read_to_string(work.path().join("app.ax")).unwrap();
+    assert!(app.contains("\n// invoices\n"), "got: {app}");
+}
+
+#[test]
+fn yes_skips_the_prompt_and_tampering_is_caught() {
+    let repo = tempdir().unwrap();
+    template_repo(repo.path());
+    let work = tempdir().unwrap();
+    let cache = work.path().join("cache");
+    let out = boruna(
+        &[
+            "template",
+            "add",
+            repo.path().to_str().unwrap(),
+            "--cache",
+            cache.to_str().unwrap(),
+            "--yes",
+        ],
+        "",
+        work.path(),
+    );
+    assert!(out.status.success());
+    assert!(!String::from_utf8_lossy(&out.stdout).contains("Trust templates"));
+
+    std::fs::write(cache.join("billing/app.ax.template"), "fn main() { }").unwrap();
+    let out = boruna(
+        &[
+            "template",
+            "apply",
+            "billing",
+            "--cache",
+            cache.to_str().unwrap(),
+            "--args",
+            "entity=x",
+        ],
+        "",
+        work.path(),
+    );
+    assert!(!out.status.success());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("integrity check"), "got: {stderr}");
+}
@@ -0,0 +1,50 @@
+//! CLI integration tests for test specs scaffolded by `template apply`.
+
+use std::path::PathBuf;
+use std::process::Command;
+
+use tempfile::tempdir;
+
+fn templates_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../templates")
+}
+
+#[test]
+fn apply_writes_template_tests_that_pass() {
+    let dir = tempdir().unwrap();
+    let out_path = dir.path().join("contact.ax");
+    let out = Command::new(env!("CARGO_BIN_EXE_boruna"))
+        .args(["template", "apply", "form-basic", "--dir"])
+        .arg(templates_dir())
+        .args([
+            "--args",
+            "form_name=contact,fields=name|email",
+            "--run-tests",
+        ])
+        .arg("--out")
+        .arg(&out_path)
+        .current_dir(dir.path())
+        .output()
+        .expect("invoke boruna");
+    assert!(
+        out.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("PASS: contact_submit"), "got: {stdout}");
+
+    // The written spec points at the generated source and replays green.
+    let spec = dir.path().join("tests/submit.testspec.json");
+    let out = Command::new(env!("CARGO_BIN_EXE_boruna"))
+        .args(["trace2tests", "run", "--spec"])
+        .arg(&spec)
+        .output()
+        .expect("invoke boruna");
+    assert!(
+        out.status.success(),
+        "stdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&out.stdout),
+        String::from_utf8_lossy(&out.stderr)
+    );
+}
@@ -0,0 +1,118 @@
+//! CLI integration tests for `template upgrade`.
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+use tempfile::tempdir;
+
+fn write_template(dir: &Path, version: &str, body: &str) {
+    let t = dir.join("templates/limits");
+    std::fs::create_dir_all(&t).unwrap();
+    std::fs::write(
+        t.join("template.json"),
+        format!(
+            r#"{{ "name": "limits", "version": "{version}", "description": "Limits",
+                 "dependencies": [], "capabilities": [],
+                 "args": {{ "n": {{ "type": "string", "required": true, "description": "Limit" }} }} }}"#
+        ),
+    )
+    .unwrap();
+    std::fs::write(t.join("app.ax.template"), body).unwrap();
+}
+
+fn boruna(cwd: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_boruna"))
+        .args(args)
+        .current_dir(cwd)
+        .output()
+        .expect("invoke boruna")
+}
+
+#[test]
+fn upgrade_merges_user_edits_and_flags_conflicts() {
+    let dir = tempdir().unwrap();
+    let work = dir.path();
+    write_template(
+        work,
+        "1.0.0",
+        "// limit {{n}}\nfn limit() -> Int { {{n}} }\nfn main() -> Int { limit() }\n",
+    );
+    let out = boruna(
+        work,
+        &[
+            "template", "apply", "limits", "--args", "n=10", "--out", "app.ax",
+        ],
+    );
+    assert!(
+        out.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let app = work.join("app.ax");
+    let generated = std::fs::read_to_string(&app).unwrap();
+    assert!(
+        generated.starts_with("// boruna-template: {"),
+        "got: {generated}"
+    );
+
+    // A user edit and a template change in different places merge.
+    std::fs::write(
+        &app,
+        generated.replace("fn limit()", "fn extra() -> Int { 1 }\nfn limit()"),
+    )
+    .unwrap();
+    write_template(
+        work,
+        "1.1.0",
+        "// limit {{n}}\nfn limit() -> Int { {{n}} }\nfn main() -> Int { limit() + 0 }\n",
+    );
+    let out = boruna(
+        work,
+        &[
+            "template",
+            "upgrade",
+            "app.ax",
+            "--template",
+            "limits@1.1.0",
+        ],
+    );
+    assert!(
+        out.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let merged = std::fs::read_to_string(&app).unwrap();
+    assert!(merged.contains("\"version\":\"1.1.0\""), "got: {merged}");
+    assert!(merged.contains("fn extra() -> Int { 1 }"), "got: {merged}");
+    assert!(merged.contains("limit() + 0"), "got: {merged}");
+
+    // Both sides rewrite `main`: conflict markers and an E016 diagnostic.
+    std::fs::write(&app, merged.replace("limit() + 0", "limit() * 2")).unwrap();
+    write_template(
+        work,
+        "2.0.0",
+        "// limit {{n}}\nfn limit() -> Int { {{n}} }\nfn main() -> Int { limit() - 1 }\n",
+    );
+    let out = boruna(
+        work,
+        &[
+            "template",
+            "upgrade",
+            "app.ax",
+            "--template",
+            "limits@2.0.0",
+            "--json",
+        ],
+    );
+    assert!(!out.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&out.stdout).expect("JSON report");
+    let diags = report["diagnostics"].as_array().unwrap();
+    assert_eq!(diags.len(), 1);
+    assert_eq!(diags[0]["id"], "E016");
+    let conflicted = std::fs::read_to_string(&app).unwrap();
+    assert!(conflicted.contains("<<<<<<< yours\nfn main() -> Int { limit() * 2 }\n======="));
+    assert!(
+        conflicted.contains(">>>>>>> limits@2.0.0"),
+        "got: {conflicted}"
+    );
+}
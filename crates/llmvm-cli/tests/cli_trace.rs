@@ -0,0 +1,41 @@
+//! CLI integration tests for `boruna trace` sampling and summaries.
+
+use std::process::{Command, Output};
+
+fn trace(extra: &[&str]) -> Output {
+    let example = concat!(env!("CARGO_MANIFEST_DIR"), "/../../examples/fibonacci.ax");
+    Command::new(env!("CARGO_BIN_EXE_boruna"))
+        .arg("trace")
+        .arg(example)
+        .args(extra)
+        .output()
+        .expect("invoke boruna")
+}
+
+#[test]
+fn summary_only_prints_top_functions_and_no_entries() {
+    let out = trace(&["--summary", "--top", "1"]);
+    assert!(out.status.success());
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(!stdout.contains("--- Trace"), "stdout: {stdout}");
+    assert!(stdout.contains("--- Summary"), "stdout: {stdout}");
+    assert!(stdout.contains("fib  (177 calls)"), "stdout: {stdout}");
+    assert!(!stdout.contains("main  ("), "--top 1 lists one function");
+}
+
+#[test]
+fn boundaries_with_max_entries_keeps_the_newest() {
+    let out = trace(&["--boundaries", "--max-entries", "3"]);
+    assert!(out.status.success());
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    let entries: Vec<&str> = stdout
+        .lines()
+        .filter(|l| l.trim_start().starts_with('['))
+        .collect();
+    assert_eq!(entries.len(), 3, "stdout: {stdout}");
+    assert!(entries[2].contains("[main:"), "stdout: {stdout}");
+    assert!(
+        stdout.contains("earlier entries dropped"),
+        "stdout: {stdout}"
+    );
+}
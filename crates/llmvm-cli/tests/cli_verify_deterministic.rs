@@ -0,0 +1,44 @@
+//! CLI integration tests for `compile --verify-deterministic`.
+
+use std::process::{Command, Output};
+
+use tempfile::tempdir;
+
+fn compile(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_boruna"))
+        .arg("compile")
+        .args(args)
+        .output()
+        .expect("invoke boruna")
+}
+
+#[test]
+fn verify_deterministic_reports_identical_builds_and_writes_nothing() {
+    let dir = tempdir().unwrap();
+    let src = dir.path().join("app.ax");
+    std::fs::write(&src, "fn main() -> Int { 1 + 2 }\n").unwrap();
+
+    let out = compile(&[src.to_str().unwrap(), "--verify-deterministic"]);
+    assert_eq!(out.status.code(), Some(0));
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(
+        stdout.contains("deterministic, 2 builds identical (sha256:"),
+        "stdout: {stdout}"
+    );
+    assert!(!src.with_extension("axbc").exists());
+
+    let out = compile(&[src.to_str().unwrap(), "--verify-deterministic", "--json"]);
+    assert_eq!(out.status.code(), Some(0));
+    let report: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(report["digests"][0], report["digests"][1]);
+    assert!(report["first_difference"].is_null());
+}
+
+#[test]
+fn verify_deterministic_fails_on_compile_error() {
+    let dir = tempdir().unwrap();
+    let src = dir.path().join("bad.ax");
+    std::fs::write(&src, "fn main( {\n").unwrap();
+    let out = compile(&[src.to_str().unwrap(), "--verify-deterministic"]);
+    assert_eq!(out.status.code(), Some(2));
+}
@@ -0,0 +1,100 @@
+//! CLI integration tests for container-isolated workflow steps
+//! (`"isolation": "container"` with `workflow run --container-image`).
+#![cfg(unix)]
+
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::process::{Command, Output};
+
+use tempfile::tempdir;
+
+const WORKFLOW: &str = r#"{
+  "schema_version": 1,
+  "name": "container-demo",
+  "version": "1.0.0",
+  "steps": {
+    "work": { "kind": "source", "source": "work.ax", "isolation": "container" }
+  },
+  "edges": []
+}"#;
+
+const WORK: &str = "fn main() -> Int { 40 + 2 }\n";
+
+fn boruna(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_boruna"))
+        .args(args)
+        .output()
+        .expect("invoke boruna")
+}
+
+/// A stand-in container runtime: records its arguments, then runs the
+/// job from the mounted job directory with this build of `boruna`.
+fn fake_runtime(dir: &Path) -> String {
+    let script = dir.join("runtime.sh");
+    let body = format!(
+        "#!/bin/sh\n\
+         echo \"$@\" > '{args}'\n\
+         for arg; do\n\
+           case \"$arg\" in --mount=*,dst=/boruna/job) job=\"${{arg#*,src=}}\"; job=\"${{job%,dst=/boruna/job}}\";; esac\n\
+         done\n\
+         exec '{boruna}' workflow exec-step \"$job/job.json\" \"$job/result.json\"\n",
+        args = dir.join("args").display(),
+        boruna = env!("CARGO_BIN_EXE_boruna"),
+    );
+    std::fs::write(&script, body).unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+    script.display().to_string()
+}
+
+fn write_workflow(dir: &Path) {
+    std::fs::write(dir.join("workflow.json"), WORKFLOW).unwrap();
+    std::fs::write(dir.join("work.ax"), WORK).unwrap();
+}
+
+#[test]
+fn container_step_runs_through_the_runtime() {
+    let dir = tempdir().unwrap();
+    write_workflow(dir.path());
+    let runtime = fake_runtime(dir.path());
+
+    let out = boruna(&[
+        "workflow",
+        "run",
+        &dir.path().to_string_lossy(),
+        "--ephemeral",
+        "--policy",
+        "deny-all",
+        "--container-image",
+        "boruna:test",
+        "--container-runtime",
+        &runtime,
+    ]);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(out.status.success(), "{stdout}");
+    assert!(stdout.contains("run: Completed"), "{stdout}");
+
+    let args = std::fs::read_to_string(dir.path().join("args")).unwrap();
+    assert!(args.contains("--network=none"), "{args}");
+    assert!(args.contains("--read-only"), "{args}");
+    assert!(args.contains("--mount=type=bind,src="), "{args}");
+    assert!(
+        args.contains("boruna:test boruna workflow exec-step"),
+        "{args}"
+    );
+}
+
+#[test]
+fn container_step_without_image_fails() {
+    let dir = tempdir().unwrap();
+    write_workflow(dir.path());
+
+    let out = boruna(&[
+        "workflow",
+        "run",
+        &dir.path().to_string_lossy(),
+        "--ephemeral",
+    ]);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("run: Failed"), "{stdout}");
+    assert!(stdout.contains("no container backend"), "{stdout}");
+}
@@ -0,0 +1,135 @@
+//! CLI integration tests for `boruna workflow run --param / --params-file`.
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+use tempfile::tempdir;
+
+const WORKFLOW: &str = r#"{
+  "schema_version": 1,
+  "name": "params-demo",
+  "version": "1.0.0",
+  "params": {
+    "region": { "type": "string", "default": "eu-west-1" },
+    "limit": { "type": "int" }
+  },
+  "steps": {
+    "echo": {
+      "kind": "source",
+      "source": "echo.ax",
+      "inputs": { "limit": "params.limit", "region": "params.region" }
+    }
+  },
+  "edges": []
+}"#;
+
+const ECHO: &str =
+    "fn main() -> String {\n    let limit: String = step_input(\"limit\")\n    limit\n}";
+
+fn boruna(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_boruna"))
+        .args(args)
+        .output()
+        .expect("invoke boruna")
+}
+
+fn write_workflow(dir: &Path) {
+    std::fs::write(dir.join("workflow.json"), WORKFLOW).unwrap();
+    std::fs::write(dir.join("echo.ax"), ECHO).unwrap();
+}
+
+fn run(dir: &Path, extra: &[&str]) -> Output {
+    let evidence = dir.join("evidence");
+    let dir = dir.to_string_lossy();
+    let evidence = evidence.to_string_lossy();
+    let mut args = vec![
+        "workflow",
+        "run",
+        &dir,
+        "--ephemeral",
+        "--record",
+        "--evidence-dir",
+        &evidence,
+    ];
+    args.extend_from_slice(extra);
+    boruna(&args)
+}
+
+fn bundle(dir: &Path) -> PathBuf {
+    std::fs::read_dir(dir.join("evidence"))
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap()
+        .path()
+}
+
+#[test]
+fn params_are_bound_recorded_and_replayed() {
+    let dir = tempdir().unwrap();
+    write_workflow(dir.path());
+    let file = dir.path().join("params.json");
+    std::fs::write(&file, r#"{"limit": 5, "region": "us-east-1"}"#).unwrap();
+
+    let out = run(
+        dir.path(),
+        &[
+            "--params-file",
+            &file.to_string_lossy(),
+            "--param",
+            "limit=50",
+        ],
+    );
+    assert!(
+        out.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+
+    let bundle = bundle(dir.path());
+    let recorded: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(bundle.join("params.json")).unwrap())
+            .unwrap();
+    assert_eq!(
+        recorded,
+        serde_json::json!({"limit": 50, "region": "us-east-1"}),
+        "--param overrides --params-file"
+    );
+    let manifest: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(bundle.join("manifest.json")).unwrap())
+            .unwrap();
+    assert!(manifest["params_hash"].is_string());
+
+    let out = boruna(&[
+        "workflow",
+        "verify",
+        &dir.path().to_string_lossy(),
+        "--evidence",
+        &bundle.to_string_lossy(),
+    ]);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(out.status.success(), "stdout: {stdout}");
+    assert!(stdout.contains("step 'echo': matched"), "stdout: {stdout}");
+}
+
+#[test]
+fn unknown_and_missing_params_are_rejected() {
+    let dir = tempdir().unwrap();
+    write_workflow(dir.path());
+
+    let out = run(dir.path(), &["--param", "colour=red"]);
+    assert!(!out.status.success());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("unknown param 'colour'"),
+        "stderr: {stderr}"
+    );
+
+    let out = run(dir.path(), &[]);
+    assert!(!out.status.success());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("param 'limit' has no default"),
+        "stderr: {stderr}"
+    );
+}
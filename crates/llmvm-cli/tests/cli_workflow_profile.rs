@@ -0,0 +1,105 @@
+//! CLI integration tests for `boruna workflow run --profile`.
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+use tempfile::tempdir;
+
+const WORKFLOW: &str = r#"{
+  "schema_version": 1,
+  "name": "profile-demo",
+  "version": "1.0.0",
+  "params": { "api_base": { "type": "string" } },
+  "steps": {
+    "echo": {
+      "kind": "source",
+      "source": "echo.ax",
+      "inputs": { "api_base": "params.api_base" }
+    }
+  },
+  "edges": []
+}"#;
+
+const ECHO: &str =
+    "fn main() -> String {\n    let base: String = step_input(\"api_base\")\n    base\n}";
+
+fn boruna(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_boruna"))
+        .args(args)
+        .output()
+        .expect("invoke boruna")
+}
+
+fn write_workflow(dir: &Path) {
+    std::fs::write(dir.join("workflow.json"), WORKFLOW).unwrap();
+    std::fs::write(dir.join("echo.ax"), ECHO).unwrap();
+    std::fs::create_dir_all(dir.join("profiles")).unwrap();
+    std::fs::write(
+        dir.join("profiles/dev.json"),
+        r#"{"policy": "deny-all", "params": {"api_base": "http://localhost:8080"}}"#,
+    )
+    .unwrap();
+}
+
+fn run(dir: &Path, extra: &[&str]) -> Output {
+    let evidence = dir.join("evidence");
+    let dir = dir.to_string_lossy();
+    let evidence = evidence.to_string_lossy();
+    let mut args = vec![
+        "workflow",
+        "run",
+        &dir,
+        "--ephemeral",
+        "--record",
+        "--evidence-dir",
+        &evidence,
+    ];
+    args.extend_from_slice(extra);
+    boruna(&args)
+}
+
+#[test]
+fn profile_binds_params_and_is_fingerprinted() {
+    let dir = tempdir().unwrap();
+    write_workflow(dir.path());
+
+    let out = run(dir.path(), &["--profile", "dev"]);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(
+        out.status.success(),
+        "stdout: {stdout}\nstderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert!(stdout.contains("profile: dev"), "stdout: {stdout}");
+
+    let bundle = std::fs::read_dir(dir.path().join("evidence"))
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap()
+        .path();
+    let fingerprint: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(bundle.join("env_fingerprint.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(fingerprint["profile"]["name"], "dev");
+    assert_eq!(fingerprint["profile"]["hash"].as_str().unwrap().len(), 64);
+    let policy: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(bundle.join("policy.json")).unwrap())
+            .unwrap();
+    assert_eq!(policy["default_allow"], false, "profile policy applied");
+}
+
+#[test]
+fn unknown_profile_is_rejected() {
+    let dir = tempdir().unwrap();
+    write_workflow(dir.path());
+
+    let out = run(dir.path(), &["--profile", "prod"]);
+    assert!(!out.status.success());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("profile 'prod' not found"),
+        "stderr: {stderr}"
+    );
+}
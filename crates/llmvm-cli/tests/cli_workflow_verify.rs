@@ -0,0 +1,105 @@
+//! CLI integration tests for `boruna workflow verify`.
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+use tempfile::tempdir;
+
+const WORKFLOW: &str = r#"{
+  "schema_version": 1,
+  "name": "verify-demo",
+  "version": "1.0.0",
+  "steps": {
+    "greet": { "kind": "source", "source": "greet.ax" },
+    "echo": {
+      "kind": "source",
+      "source": "echo.ax",
+      "inputs": { "msg": "greet.result" },
+      "depends_on": ["greet"]
+    }
+  },
+  "edges": [["greet", "echo"]]
+}"#;
+
+const ECHO: &str =
+    "fn main() -> String {\n    let received: String = step_input(\"msg\")\n    received\n}";
+
+fn boruna(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_boruna"))
+        .args(args)
+        .output()
+        .expect("invoke boruna")
+}
+
+/// Write the workflow, run it with `--record`, and return the bundle dir.
+fn record(dir: &Path) -> PathBuf {
+    std::fs::write(dir.join("workflow.json"), WORKFLOW).unwrap();
+    std::fs::write(dir.join("greet.ax"), "fn main() -> String { \"hello\" }").unwrap();
+    std::fs::write(dir.join("echo.ax"), ECHO).unwrap();
+    let evidence = dir.join("evidence");
+    let out = boruna(&[
+        "workflow",
+        "run",
+        &dir.to_string_lossy(),
+        "--ephemeral",
+        "--record",
+        "--evidence-dir",
+        &evidence.to_string_lossy(),
+    ]);
+    assert!(
+        out.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let bundle = std::fs::read_dir(&evidence)
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap()
+        .path();
+    assert!(bundle.join("events/echo.json").exists());
+    bundle
+}
+
+fn verify(dir: &Path, bundle: &Path) -> Output {
+    boruna(&[
+        "workflow",
+        "verify",
+        &dir.to_string_lossy(),
+        "--evidence",
+        &bundle.to_string_lossy(),
+    ])
+}
+
+#[test]
+fn verify_passes_against_its_own_recording() {
+    let dir = tempdir().unwrap();
+    let bundle = record(dir.path());
+    let out = verify(dir.path(), &bundle);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(out.status.success(), "stdout: {stdout}");
+    assert!(stdout.contains("step 'echo': matched"), "stdout: {stdout}");
+    assert!(stdout.contains("verification passed"), "stdout: {stdout}");
+}
+
+#[test]
+fn verify_fails_when_a_step_output_changes() {
+    let dir = tempdir().unwrap();
+    let bundle = record(dir.path());
+    std::fs::write(
+        dir.path().join("greet.ax"),
+        "fn main() -> String { \"bye\" }",
+    )
+    .unwrap();
+    let out = verify(dir.path(), &bundle);
+    assert_eq!(out.status.code(), Some(5), "divergence exits 5");
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(
+        stdout.contains("step 'greet': mismatched"),
+        "stdout: {stdout}"
+    );
+    assert!(
+        stdout.contains("step 'echo': mismatched"),
+        "stdout: {stdout}"
+    );
+}
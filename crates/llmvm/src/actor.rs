@@ -1,6 +1,10 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
 use boruna_bytecode::{Module, Value};
 
+use crate::actor_store::{ActorCheckpoint, ActorStateStore};
 use crate::capability_gateway::{CapabilityGateway, Policy};
+use crate::delegation::policy_hash;
 use crate::error::VmError;
 use crate::replay::EventLog;
 use crate::vm::{StepResult, Vm};
@@ -24,6 +28,8 @@ pub enum ActorStatus {
 /// An actor instance.
 struct Actor {
     id: u64,
+    /// Name of the function the actor runs.
+    function: String,
     vm: Vm,
     status: ActorStatus,
     parent: Option<u64>,
@@ -42,10 +48,14 @@ pub struct ActorSystem {
     max_rounds: u64,
     /// Steps budget per actor per round.
     budget_per_round: u64,
-    /// The policy used for spawning child actor gateways.
-    policy: Option<Policy>,
     /// Event log for the scheduler.
     event_log: EventLog,
+    /// Where persistent actors are checkpointed and restored from.
+    state_store: Option<ActorStateStore>,
+    /// Functions whose actors are persistent.
+    persistent_functions: BTreeSet<String>,
+    /// Grants narrowing the policy of actors spawned to run a function.
+    delegations: BTreeMap<String, Policy>,
 }
 
 impl Default for ActorSystem {
@@ -62,11 +72,43 @@ impl ActorSystem {
             pending_messages: Vec::new(),
             max_rounds: 10_000,
             budget_per_round: 1000,
-            policy: None,
             event_log: EventLog::new(),
+            state_store: None,
+            persistent_functions: BTreeSet::new(),
+            delegations: BTreeMap::new(),
         }
     }
 
+    /// Checkpoint persistent actors to `store`, and resume any actor the
+    /// store already holds a checkpoint for. See [`crate::actor_store`].
+    pub fn set_state_store(&mut self, store: ActorStateStore) {
+        self.state_store = Some(store);
+    }
+
+    pub fn state_store(&self) -> Option<&ActorStateStore> {
+        self.state_store.as_ref()
+    }
+
+    /// Detach the state store, e.g. to hand it to the next system.
+    pub fn take_state_store(&mut self) -> Option<ActorStateStore> {
+        self.state_store.take()
+    }
+
+    /// Make actors running `function` persistent: with a state store
+    /// attached, they are checkpointed after processing their messages
+    /// and restored when a new system starts. Name the entry function
+    /// (usually `main`) to persist the root actor.
+    pub fn persist_function(&mut self, function: &str) {
+        self.persistent_functions.insert(function.to_string());
+    }
+
+    /// Run actors spawned for `function` under their parent's policy
+    /// attenuated by `grant` ([`Policy::attenuate`]) instead of the
+    /// parent's policy itself. See [`crate::delegation`].
+    pub fn delegate(&mut self, function: &str, grant: Policy) {
+        self.delegations.insert(function.to_string(), grant);
+    }
+
     pub fn set_max_rounds(&mut self, max: u64) {
         self.max_rounds = max;
     }
@@ -79,12 +121,17 @@ impl ActorSystem {
     pub fn spawn_root(&mut self, module: Module, gateway: CapabilityGateway) -> u64 {
         let id = self.next_id;
         self.next_id += 1;
-        self.policy = Some(gateway.policy().clone());
+        let function = module
+            .functions
+            .get(module.entry as usize)
+            .map(|f| f.name.clone())
+            .unwrap_or_default();
         let mut vm = Vm::new(module, gateway);
         vm.set_actor_id(id);
         vm.next_spawn_id = self.next_id;
         self.actors.push(Actor {
             id,
+            function,
             vm,
             status: ActorStatus::Runnable,
             parent: None,
@@ -114,6 +161,11 @@ impl ActorSystem {
             return Ok(Value::Unit);
         }
 
+        // Resume the root, and the persistent actors it had spawned, from
+        // their checkpoints. A resumed root has a call stack, so setting
+        // its entry function below is a no-op.
+        self.restore_root()?;
+
         // Set up root actor's entry function
         let entry = self.actors[0].vm.module().entry;
         self.actors[0].vm.set_entry_function(entry)?;
@@ -213,9 +265,13 @@ impl ActorSystem {
                         .get(req.func_idx as usize)
                         .map(|f| f.name.clone())
                         .unwrap_or_else(|| "unknown".to_string());
-                    let policy = self.policy.clone().unwrap_or_default();
-                    let gateway = CapabilityGateway::new(policy);
-                    let mut child_vm = Vm::new(module, gateway);
+                    let (policy, delegated) =
+                        self.child_policy(self.actors[actor_idx].vm.gateway().policy(), &func_name);
+                    let mut child_vm = Vm::new(module, CapabilityGateway::new(policy));
+                    child_vm.set_allow_capability_skew(
+                        self.actors[actor_idx].vm.allow_capability_skew(),
+                    );
+                    child_vm.set_strict_determinism(self.actors[actor_idx].vm.strict_determinism());
                     child_vm.set_actor_id(child_id);
                     child_vm.set_in_actor_context(true);
                     // A crafted `SpawnActor(func_idx)` with an out-of-range index
@@ -242,12 +298,30 @@ impl ActorSystem {
                     }
                     self.next_id += 1;
                     self.event_log.log_actor_spawn(child_id, &func_name);
+                    if let Some(hash) = delegated {
+                        self.event_log
+                            .log_capability_delegation(parent_id, child_id, &hash);
+                    }
+                    // A persistent child spawned again by a fresh parent
+                    // picks up from its checkpoint.
+                    let checkpoint = self
+                        .state_store
+                        .as_ref()
+                        .filter(|_| self.persistent_functions.contains(&func_name))
+                        .and_then(|store| store.get(child_id).cloned());
+                    let mut children = Vec::new();
+                    if let Some(checkpoint) = checkpoint {
+                        children = checkpoint.children.clone();
+                        let resumed = Self::resume(&mut child_vm, &func_name, checkpoint)?;
+                        self.log_restore(resumed);
+                    }
                     self.actors.push(Actor {
                         id: child_id,
+                        function: func_name,
                         vm: child_vm,
                         status: ActorStatus::Runnable,
                         parent: Some(parent_id),
-                        children: Vec::new(),
+                        children,
                         result: None,
                     });
                     // Track child in parent
@@ -260,6 +334,15 @@ impl ActorSystem {
                 for (target_id, payload) in outgoing {
                     self.pending_messages.push((sender_id, target_id, payload));
                 }
+
+                // An actor blocks once it has processed every message it
+                // was sent: checkpoint it there. A completed actor has
+                // nothing left to resume.
+                match self.actors[actor_idx].status {
+                    ActorStatus::Blocked => self.checkpoint(actor_idx)?,
+                    ActorStatus::Completed => self.forget(actor_idx)?,
+                    _ => {}
+                }
             }
 
             // Phase 2: Deliver pending messages in deterministic order
@@ -272,6 +355,154 @@ impl ActorSystem {
         Err(VmError::MaxRoundsExceeded(self.max_rounds))
     }
 
+    /// Resume the root actor from its checkpoint, if it is persistent and
+    /// has one, then every persistent actor it had spawned: those are not
+    /// spawned again. Actors that were not persistent are gone.
+    fn restore_root(&mut self) -> Result<(), VmError> {
+        let Some(store) = &self.state_store else {
+            return Ok(());
+        };
+        if !self.persistent_functions.contains(&self.actors[0].function) {
+            return Ok(());
+        }
+        let Some(checkpoint) = store.get(self.actors[0].id).cloned() else {
+            return Ok(());
+        };
+        let mut pending: VecDeque<u64> = checkpoint.children.iter().copied().collect();
+        self.actors[0].children = checkpoint.children.clone();
+        let root = &mut self.actors[0];
+        let resumed = Self::resume(&mut root.vm, &root.function, checkpoint)?;
+        self.log_restore(resumed);
+
+        while let Some(id) = pending.pop_front() {
+            let Some(checkpoint) = self.state_store.as_ref().and_then(|s| s.get(id).cloned())
+            else {
+                continue;
+            };
+            if !self.persistent_functions.contains(&checkpoint.function) {
+                continue;
+            }
+            let parent = checkpoint.parent;
+            let parent_policy = self
+                .actors
+                .iter()
+                .find(|a| Some(a.id) == parent)
+                .unwrap_or(&self.actors[0])
+                .vm
+                .gateway()
+                .policy();
+            let (policy, delegated) = self.child_policy(parent_policy, &checkpoint.function);
+            let root = &self.actors[0].vm;
+            let module = root.module().clone();
+            let mut vm = Vm::new(module, CapabilityGateway::new(policy));
+            vm.set_allow_capability_skew(root.allow_capability_skew());
+            vm.set_strict_determinism(root.strict_determinism());
+            vm.set_actor_id(id);
+            vm.set_in_actor_context(true);
+            let function = checkpoint.function.clone();
+            let children = checkpoint.children.clone();
+            let resumed = Self::resume(&mut vm, &function, checkpoint)?;
+            self.log_restore(resumed);
+            if let (Some(hash), Some(parent)) = (delegated, parent) {
+                self.event_log.log_capability_delegation(parent, id, &hash);
+            }
+            pending.extend(&children);
+            self.actors.push(Actor {
+                id,
+                function,
+                vm,
+                status: ActorStatus::Runnable,
+                parent,
+                children,
+                result: None,
+            });
+        }
+        Ok(())
+    }
+
+    /// The policy for a child running `function` whose parent runs under
+    /// `parent`: `parent` itself, or `parent` attenuated by the function's
+    /// grant together with the resulting policy's hash.
+    fn child_policy(&self, parent: &Policy, function: &str) -> (Policy, Option<String>) {
+        match self.delegations.get(function) {
+            Some(grant) => {
+                let policy = parent.attenuate(grant);
+                let hash = policy_hash(&policy);
+                (policy, Some(hash))
+            }
+            None => (parent.clone(), None),
+        }
+    }
+
+    /// Load `checkpoint` into `vm`, after checking it belongs to an actor
+    /// running `function` and still matches its hash. Returns what
+    /// [`log_restore`](Self::log_restore) needs.
+    fn resume(
+        vm: &mut Vm,
+        function: &str,
+        checkpoint: ActorCheckpoint,
+    ) -> Result<(u64, u64, String), VmError> {
+        let id = checkpoint.actor_id;
+        if checkpoint.function != function {
+            return Err(VmError::ActorState(format!(
+                "actor {id} checkpoint is for `{}`, not `{function}`",
+                checkpoint.function
+            )));
+        }
+        if !checkpoint.is_intact() {
+            return Err(VmError::ActorState(format!(
+                "actor {id} checkpoint does not match its hash {}",
+                checkpoint.state_hash
+            )));
+        }
+        vm.restore_state(checkpoint.state)?;
+        Ok((id, checkpoint.next_actor_id, checkpoint.state_hash))
+    }
+
+    /// Record a resumed actor: log its state hash and keep fresh actor
+    /// ids clear of the ids it had already handed out.
+    fn log_restore(&mut self, (id, next_actor_id, state_hash): (u64, u64, String)) {
+        self.next_id = self.next_id.max(next_actor_id);
+        self.event_log.log_actor_restore(id, &state_hash);
+    }
+
+    /// Write a persistent actor's checkpoint, unless the store already
+    /// holds an identical one.
+    fn checkpoint(&mut self, actor_idx: usize) -> Result<(), VmError> {
+        let actor = &self.actors[actor_idx];
+        let Some(store) = self.state_store.as_mut() else {
+            return Ok(());
+        };
+        if !self.persistent_functions.contains(&actor.function) {
+            return Ok(());
+        }
+        let checkpoint = ActorCheckpoint::new(
+            actor.id,
+            &actor.function,
+            actor.parent,
+            actor.children.clone(),
+            self.next_id,
+            actor.vm.capture_state(),
+        );
+        if store.get(actor.id) == Some(&checkpoint) {
+            return Ok(());
+        }
+        let (id, hash) = (actor.id, checkpoint.state_hash.clone());
+        store.save(checkpoint).map_err(VmError::ActorState)?;
+        self.event_log.log_actor_checkpoint(id, &hash);
+        Ok(())
+    }
+
+    /// Drop a completed actor's checkpoint.
+    fn forget(&mut self, actor_idx: usize) -> Result<(), VmError> {
+        match self.state_store.as_mut() {
+            Some(store) => store
+                .remove(self.actors[actor_idx].id)
+                .map_err(VmError::ActorState),
+            None => Ok(()),
+        }
+    }
+
     /// Deliver pending messages sorted by (target_id, sender_id) for determinism.
     fn deliver_messages(&mut self) {
         // Sort for deterministic delivery order
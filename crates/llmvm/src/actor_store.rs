@@ -0,0 +1,256 @@
+//! Checkpoint store for persistent actors.
+//!
+//! An [`ActorSystem`](crate::actor::ActorSystem) with a store attached
+//! checkpoints every persistent actor — one whose function was passed to
+//! [`persist_function`](crate::actor::ActorSystem::persist_function) —
+//! each time it has processed its messages and blocks waiting for the
+//! next one, and removes the checkpoint when the actor completes. A new
+//! system given the same store resumes those actors where they stopped
+//! instead of starting them fresh.
+//!
+//! **On disk.** Like [`KvStore`](crate::kv_store::KvStore), a store is a
+//! single append-only file with one JSON line per write,
+//! `{"actor_id": N, "checkpoint": {...}}`, or `"checkpoint": null` for a
+//! removal. Opening replays the lines in order, so the last write for an
+//! actor wins; a final line without a trailing newline is a write torn by
+//! a crash and is dropped.
+//!
+//! **Determinism.** Each checkpoint carries the `sha256:` digest of the
+//! state it holds. The scheduler logs it as `ActorCheckpoint` when
+//! writing and as `ActorRestore` when resuming, after re-hashing the
+//! stored state, so a corrupted store fails the restore and
+//! [`ReplayEngine::verify_restores`](crate::replay::ReplayEngine::verify_restores)
+//! can prove a resumed run picked up exactly where the previous one left
+//! off. Messages still in flight when the process stopped are not part of
+//! any checkpoint.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use boruna_hash::Hasher;
+use serde::{Deserialize, Serialize};
+
+use crate::vm::VmState;
+
+/// A persistent actor's state as of its last checkpoint.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActorCheckpoint {
+    pub actor_id: u64,
+    /// Function the actor runs; a checkpoint only restores an actor
+    /// running the same function.
+    pub function: String,
+    pub parent: Option<u64>,
+    pub children: Vec<u64>,
+    /// The id the scheduler would assign to the next spawned actor, so a
+    /// restored system never reuses an id.
+    pub next_actor_id: u64,
+    /// `sha256:<hex>` of `state`'s compact JSON.
+    pub state_hash: String,
+    pub state: VmState,
+}
+
+impl ActorCheckpoint {
+    pub fn new(
+        actor_id: u64,
+        function: &str,
+        parent: Option<u64>,
+        children: Vec<u64>,
+        next_actor_id: u64,
+        state: VmState,
+    ) -> Self {
+        ActorCheckpoint {
+            actor_id,
+            function: function.to_string(),
+            parent,
+            children,
+            next_actor_id,
+            state_hash: state_hash(&state),
+            state,
+        }
+    }
+
+    /// Whether `state_hash` still matches `state`.
+    pub fn is_intact(&self) -> bool {
+        state_hash(&self.state) == self.state_hash
+    }
+}
+
+/// `sha256:<hex>` over the compact JSON of `state`.
+pub fn state_hash(state: &VmState) -> String {
+    let json = serde_json::to_string(state).unwrap_or_default();
+    let mut hasher = Hasher::sha256();
+    hasher.update(json.as_bytes());
+    hasher.finalize().to_string()
+}
+
+/// One line of the store file.
+#[derive(Serialize, Deserialize)]
+struct CheckpointRecord {
+    actor_id: u64,
+    checkpoint: Option<ActorCheckpoint>,
+}
+
+/// Actor checkpoints backed by an append-only file, or held in memory.
+#[derive(Debug, Default)]
+pub struct ActorStateStore {
+    /// `None` for an in-memory store.
+    path: Option<PathBuf>,
+    checkpoints: BTreeMap<u64, ActorCheckpoint>,
+}
+
+impl ActorStateStore {
+    /// An empty store that is never written to disk.
+    pub fn in_memory() -> Self {
+        ActorStateStore::default()
+    }
+
+    /// Open the store at `path`, creating it on first write if missing.
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let mut checkpoints = BTreeMap::new();
+        let data = match fs::read_to_string(path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => {
+                return Err(format!(
+                    "failed to read actor store '{}': {e}",
+                    path.display()
+                ))
+            }
+        };
+        let complete = match data.rfind('\n') {
+            Some(end) => &data[..end],
+            None => "",
+        };
+        for (i, line) in complete.lines().enumerate() {
+            if line.is_empty() {
+                continue;
+            }
+            let record: CheckpointRecord = serde_json::from_str(line).map_err(|e| {
+                format!(
+                    "actor store '{}' line {}: invalid record: {e}",
+                    path.display(),
+                    i + 1
+                )
+            })?;
+            match record.checkpoint {
+                Some(checkpoint) => checkpoints.insert(record.actor_id, checkpoint),
+                None => checkpoints.remove(&record.actor_id),
+            };
+        }
+        Ok(ActorStateStore {
+            path: Some(path.to_path_buf()),
+            checkpoints,
+        })
+    }
+
+    pub fn get(&self, actor_id: u64) -> Option<&ActorCheckpoint> {
+        self.checkpoints.get(&actor_id)
+    }
+
+    /// Write a checkpoint, replacing the actor's previous one. File-backed
+    /// stores append the record and sync it before returning.
+    pub fn save(&mut self, checkpoint: ActorCheckpoint) -> Result<(), String> {
+        let actor_id = checkpoint.actor_id;
+        let record = CheckpointRecord {
+            actor_id,
+            checkpoint: Some(checkpoint),
+        };
+        self.append(&record)?;
+        if let Some(checkpoint) = record.checkpoint {
+            self.checkpoints.insert(actor_id, checkpoint);
+        }
+        Ok(())
+    }
+
+    /// Drop the actor's checkpoint, if any.
+    pub fn remove(&mut self, actor_id: u64) -> Result<(), String> {
+        if self.checkpoints.contains_key(&actor_id) {
+            self.append(&CheckpointRecord {
+                actor_id,
+                checkpoint: None,
+            })?;
+            self.checkpoints.remove(&actor_id);
+        }
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.checkpoints.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.checkpoints.is_empty()
+    }
+
+    fn append(&self, record: &CheckpointRecord) -> Result<(), String> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let mut line = serde_json::to_string(record)
+            .map_err(|e| format!("actor {}: serialize checkpoint: {e}", record.actor_id))?;
+        line.push('\n');
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| format!("actor store: open '{}': {e}", path.display()))?;
+        file.write_all(line.as_bytes())
+            .and_then(|()| file.sync_data())
+            .map_err(|e| format!("actor store: write '{}': {e}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capability_gateway::{CapabilityGateway, Policy};
+    use crate::vm::Vm;
+    use boruna_bytecode::Module;
+
+    fn checkpoint(actor_id: u64) -> ActorCheckpoint {
+        let vm = Vm::new(
+            Module::new("test"),
+            CapabilityGateway::new(Policy::allow_all()),
+        );
+        ActorCheckpoint::new(actor_id, "worker", Some(0), vec![], 3, vm.capture_state())
+    }
+
+    #[test]
+    fn test_file_store_replays_saves_and_removals() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("actors.jsonl");
+        let mut store = ActorStateStore::open(&path).unwrap();
+        store.save(checkpoint(1)).unwrap();
+        store.save(checkpoint(2)).unwrap();
+        store.remove(1).unwrap();
+
+        let reopened = ActorStateStore::open(&path).unwrap();
+        assert_eq!(reopened.len(), 1);
+        assert_eq!(reopened.get(2), Some(&checkpoint(2)));
+        assert!(reopened.get(1).is_none());
+    }
+
+    #[test]
+    fn test_torn_last_line_is_dropped() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("actors.jsonl");
+        let mut store = ActorStateStore::open(&path).unwrap();
+        store.save(checkpoint(1)).unwrap();
+        let mut data = fs::read_to_string(&path).unwrap();
+        data.push_str("{\"actor_id\": 2, \"checkp");
+        fs::write(&path, data).unwrap();
+        let reopened = ActorStateStore::open(&path).unwrap();
+        assert_eq!(reopened.len(), 1);
+    }
+
+    #[test]
+    fn test_mismatched_hash_is_not_intact() {
+        let mut cp = checkpoint(1);
+        assert!(cp.is_intact());
+        assert!(cp.state_hash.starts_with("sha256:"));
+        cp.state_hash = format!("sha256:{}", "0".repeat(64));
+        assert!(!cp.is_intact());
+    }
+}
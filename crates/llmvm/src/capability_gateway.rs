@@ -1,4 +1,5 @@
-use boruna_bytecode::{Capability, Value};
+use boruna_budget::{BudgetLedger, Resource};
+use boruna_bytecode::{Capability, Module, Value};
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 
@@ -11,6 +12,12 @@ pub struct PolicyRule {
     pub allow: bool,
     /// Maximum invocations allowed (0 = unlimited).
     pub budget: u64,
+    /// Ask the gateway's [`CapabilityPrompter`] on the first call instead
+    /// of applying `allow`, which remains the answer when no prompter is
+    /// installed (non-interactive runs). Omitted when false so existing
+    /// policy hashes are unchanged.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub prompt: bool,
 }
 
 impl Default for PolicyRule {
@@ -18,6 +25,7 @@ impl Default for PolicyRule {
         PolicyRule {
             allow: true,
             budget: 0,
+            prompt: false,
         }
     }
 }
@@ -66,6 +74,233 @@ impl Default for NetPolicy {
     }
 }
 
+/// Whether `host` matches a `net_policy.allowed_domains` list. An entry
+/// `*.example.com` matches the domain and any subdomain. Empty = all.
+pub(crate) fn domain_matches(host: &str, allowed: &[String]) -> bool {
+    allowed.is_empty()
+        || allowed
+            .iter()
+            .any(|pattern| match pattern.strip_prefix('*') {
+                Some(suffix) if suffix.starts_with('.') => {
+                    host.ends_with(suffix) || host == &suffix[1..]
+                }
+                _ => host == pattern,
+            })
+}
+
+/// Whether `method` is in a `net_policy.allowed_methods` list, ignoring
+/// case. Empty = all.
+pub(crate) fn method_matches(method: &str, allowed: &[String]) -> bool {
+    allowed.is_empty() || allowed.iter().any(|m| m.eq_ignore_ascii_case(method))
+}
+
+/// Data classification label, ordered from least to most sensitive.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Classification {
+    #[default]
+    Public,
+    Internal,
+    Secret,
+}
+
+impl Classification {
+    pub fn name(self) -> &'static str {
+        match self {
+            Classification::Public => "public",
+            Classification::Internal => "internal",
+            Classification::Secret => "secret",
+        }
+    }
+}
+
+impl std::fmt::Display for Classification {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Limits on the `kv.*` capabilities. A key's namespace is the text
+/// before its first `/`; enforced by
+/// [`KvHandler`](crate::kv_store::KvHandler).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct KvPolicy {
+    /// Namespaces a run may read and write. Empty = all.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub namespaces: Vec<String>,
+    /// Namespaces that may be read and scanned but not written.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub read_only: Vec<String>,
+}
+
+impl KvPolicy {
+    /// Check a `kv.*` call on `key` (the prefix, for `kv.scan`).
+    pub fn check(&self, cap: &Capability, key: &str) -> Result<(), String> {
+        let namespace = key.split_once('/').map(|(ns, _)| ns);
+        if !self.namespaces.is_empty() {
+            match namespace {
+                None => {
+                    return Err(format!(
+                        "{cap}: '{key}' names no namespace (expected '<namespace>/...'); \
+                         kv_policy allows {:?}",
+                        self.namespaces
+                    ))
+                }
+                Some(ns) if !self.namespaces.iter().any(|n| n == ns) => {
+                    return Err(format!(
+                        "{cap}: namespace '{ns}' is not allowed by kv_policy (allowed: {:?})",
+                        self.namespaces
+                    ))
+                }
+                Some(_) => {}
+            }
+        }
+        if let (Capability::KvSet, Some(ns)) = (cap, namespace) {
+            if self.read_only.iter().any(|n| n == ns) {
+                return Err(format!("{cap}: namespace '{ns}' is read-only"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Limits on the `queue.*` capabilities, by queue name; enforced by
+/// [`QueueHandler`](crate::queue_store::QueueHandler).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct QueuePolicy {
+    /// Queues a run may push to. Empty = all.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub push: Vec<String>,
+    /// Queues a run may poll. Empty = all.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub poll: Vec<String>,
+}
+
+impl QueuePolicy {
+    /// Check a `queue.push` or `queue.poll` call on `queue`.
+    pub fn check(&self, cap: &Capability, queue: &str) -> Result<(), String> {
+        let allowed = if *cap == Capability::QueuePush {
+            &self.push
+        } else {
+            &self.poll
+        };
+        if !allowed.is_empty() && !allowed.iter().any(|q| q == queue) {
+            return Err(format!(
+                "{cap}: queue '{queue}' is not allowed by queue_policy (allowed: {allowed:?})"
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Limits on `notify.send`; enforced by
+/// [`NotifyHandler`](crate::notify::NotifyHandler). Limits count calls
+/// within one run, so replay hits them at the same call.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NotifyPolicy {
+    /// Destinations a run may notify: webhook URLs and `mailto:`
+    /// addresses. An entry ending in `*` matches by prefix. Empty = all.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub destinations: Vec<String>,
+    /// Most notifications per run. 0 = unlimited.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub max_per_run: u32,
+    /// Most notifications to one destination per run. 0 = unlimited.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub max_per_destination: u32,
+}
+
+fn is_zero(n: &u32) -> bool {
+    *n == 0
+}
+
+impl NotifyPolicy {
+    /// Check one more notification to `destination`, given `total`
+    /// already sent this run and `to_destination` of them to it.
+    pub fn check(&self, destination: &str, total: u32, to_destination: u32) -> Result<(), String> {
+        let allowed = self.destinations.is_empty()
+            || self.destinations.iter().any(|d| match d.strip_suffix('*') {
+                Some(prefix) => destination.starts_with(prefix),
+                None => d == destination,
+            });
+        if !allowed {
+            return Err(format!(
+                "notify.send: destination '{destination}' is not allowed by notify_policy \
+                 (allowed: {:?})",
+                self.destinations
+            ));
+        }
+        if self.max_per_run > 0 && total >= self.max_per_run {
+            return Err(format!(
+                "notify.send: max_per_run ({}) reached",
+                self.max_per_run
+            ));
+        }
+        if self.max_per_destination > 0 && to_destination >= self.max_per_destination {
+            return Err(format!(
+                "notify.send: max_per_destination ({}) reached for '{destination}'",
+                self.max_per_destination
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Limits on `data.read_table`; enforced by
+/// [`TableHandler`](crate::table_loader::TableHandler) while the file is
+/// read, so an oversized file fails before it is fully loaded.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TablePolicy {
+    /// Most data rows per table (default 100000).
+    #[serde(default = "default_table_max_rows")]
+    pub max_rows: u64,
+    /// Most bytes read per table file (default 16 MB).
+    #[serde(default = "default_table_max_bytes")]
+    pub max_bytes: u64,
+}
+
+fn default_table_max_rows() -> u64 {
+    100_000
+}
+
+fn default_table_max_bytes() -> u64 {
+    16 * 1024 * 1024
+}
+
+impl Default for TablePolicy {
+    fn default() -> Self {
+        TablePolicy {
+            max_rows: default_table_max_rows(),
+            max_bytes: default_table_max_bytes(),
+        }
+    }
+}
+
+/// Controls on where classified workflow data may flow. Enforced by the
+/// workflow runner, not the gateway: labels belong to step data, which
+/// the gateway never sees.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DataFlowPolicy {
+    /// Highest label a step's inputs may carry when the step can invoke
+    /// the capability (e.g. `{"net.fetch": "internal"}` keeps secret data
+    /// away from the network).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub max_classification: BTreeMap<String, Classification>,
+    /// Step outputs labeled at or above this are replaced by a redaction
+    /// placeholder in evidence bundles.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redact_in_evidence: Option<Classification>,
+}
+
+impl DataFlowPolicy {
+    /// Whether data labeled `label` must be redacted from evidence.
+    pub fn redacts(&self, label: Classification) -> bool {
+        self.redact_in_evidence.is_some_and(|min| label >= min)
+    }
+}
+
 /// Policy configuration for the capability gateway.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Policy {
@@ -77,6 +312,125 @@ pub struct Policy {
     /// Network-specific policy controls (for NetFetch capability).
     #[serde(default)]
     pub net_policy: Option<NetPolicy>,
+    /// Refuse to run modules without a valid signature from a key in
+    /// `trusted_module_keys`. Both fields are omitted when unset so
+    /// existing policy hashes are unchanged.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub require_signed_modules: bool,
+    /// Trusted ed25519 public keys (64 hex chars) for module signatures.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trusted_module_keys: Vec<String>,
+    /// `enforce` (default) or `dry_run`. Omitted when `enforce` so
+    /// existing policy hashes are unchanged.
+    #[serde(default, skip_serializing_if = "PolicyMode::is_enforce")]
+    pub mode: PolicyMode,
+    /// Classification data-flow controls for workflow runs. Omitted when
+    /// unset so existing policy hashes are unchanged.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_flow: Option<DataFlowPolicy>,
+    /// Namespace limits for the `kv.*` capabilities. Omitted when unset
+    /// so existing policy hashes are unchanged.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kv_policy: Option<KvPolicy>,
+    /// Queue-name limits for the `queue.*` capabilities. Omitted when
+    /// unset so existing policy hashes are unchanged.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub queue_policy: Option<QueuePolicy>,
+    /// Destination and rate limits for `notify.send`. Omitted when unset
+    /// so existing policy hashes are unchanged.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notify_policy: Option<NotifyPolicy>,
+    /// Row and byte limits for `data.read_table`. Omitted when unset so
+    /// existing policy hashes are unchanged.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub table_policy: Option<TablePolicy>,
+}
+
+/// How the gateway applies a [`Policy`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PolicyMode {
+    /// Denied or over-budget calls fail the run.
+    #[default]
+    Enforce,
+    /// Every call is evaluated and recorded in the gateway's
+    /// [`transcript`](CapabilityGateway::transcript) with the decision
+    /// enforcement would have made, then answered by [`MockHandler`]
+    /// whatever the decision. Nothing real happens, so operators can
+    /// review what a script would do before granting a live policy.
+    DryRun,
+}
+
+impl PolicyMode {
+    fn is_enforce(&self) -> bool {
+        *self == PolicyMode::Enforce
+    }
+}
+
+/// The decision an enforcing gateway makes for one capability call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PolicyDecision {
+    Allow,
+    Deny,
+    BudgetExceeded,
+    /// A `prompt` rule with no answer yet: an interactive run would ask.
+    Prompt,
+}
+
+impl PolicyDecision {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PolicyDecision::Allow => "allow",
+            PolicyDecision::Deny => "deny",
+            PolicyDecision::BudgetExceeded => "budget_exceeded",
+            PolicyDecision::Prompt => "prompt",
+        }
+    }
+}
+
+/// A call admitted by [`CapabilityGateway::admit`] and not yet run.
+#[derive(Debug, Clone)]
+pub struct PendingCall {
+    pub cap: Capability,
+    pub args: Vec<Value>,
+    decision: PolicyDecision,
+}
+
+/// One capability call recorded in [`PolicyMode::DryRun`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptEntry {
+    /// 1-based position among the run's capability calls.
+    pub seq: u64,
+    pub capability: String,
+    pub args: Vec<Value>,
+    /// What enforcement would have decided. Handler-level checks (e.g.
+    /// `net_policy` domains) are not evaluated, since no real handler runs.
+    pub decision: PolicyDecision,
+    /// The mock answer the script received.
+    pub result: Value,
+}
+
+/// A gateway's usage counters, dry-run transcript and denials, as saved
+/// in a [`VmSnapshot`](crate::VmSnapshot).
+#[derive(Debug, Clone)]
+pub(crate) struct GatewayAccounting {
+    usage: BTreeMap<String, u64>,
+    transcript: Vec<TranscriptEntry>,
+    denials: Vec<Denial>,
+}
+
+/// A call an enforcing gateway refused (the run fails with it).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Denial {
+    pub capability: String,
+    pub args: Vec<Value>,
+    /// [`PolicyDecision::Deny`] or [`PolicyDecision::BudgetExceeded`].
+    pub decision: PolicyDecision,
+}
+
+fn is_false(b: &bool) -> bool {
+    !*b
 }
 
 fn default_schema_version() -> u32 {
@@ -95,6 +449,14 @@ impl Default for Policy {
             rules: BTreeMap::new(),
             default_allow: false,
             net_policy: None,
+            require_signed_modules: false,
+            trusted_module_keys: Vec::new(),
+            mode: PolicyMode::Enforce,
+            data_flow: None,
+            kv_policy: None,
+            queue_policy: None,
+            notify_policy: None,
+            table_policy: None,
         }
     }
 }
@@ -107,6 +469,14 @@ impl Policy {
             rules: BTreeMap::new(),
             default_allow: true,
             net_policy: None,
+            require_signed_modules: false,
+            trusted_module_keys: Vec::new(),
+            mode: PolicyMode::Enforce,
+            data_flow: None,
+            kv_policy: None,
+            queue_policy: None,
+            notify_policy: None,
+            table_policy: None,
         }
     }
 
@@ -115,6 +485,13 @@ impl Policy {
         Policy::default()
     }
 
+    /// JSON Schema (draft 2020-12) for policy files at
+    /// [`POLICY_SCHEMA_VERSION`](crate::policy_validate::POLICY_SCHEMA_VERSION).
+    /// See [`policy_validate::json_schema`](crate::policy_validate::json_schema).
+    pub fn json_schema() -> serde_json::Value {
+        crate::policy_validate::json_schema()
+    }
+
     /// Allow a specific capability with an optional budget.
     pub fn allow(&mut self, cap: &Capability, budget: u64) -> &mut Self {
         self.rules.insert(
@@ -122,6 +499,7 @@ impl Policy {
             PolicyRule {
                 allow: true,
                 budget,
+                prompt: false,
             },
         );
         self
@@ -134,10 +512,35 @@ impl Policy {
             PolicyRule {
                 allow: false,
                 budget: 0,
+                prompt: false,
             },
         );
         self
     }
+
+    /// Enforce `require_signed_modules`: the module must carry a valid
+    /// signature from one of `trusted_module_keys`. No-op when the flag
+    /// is off, so unsigned modules keep running under existing policies.
+    pub fn check_module_trust(&self, module: &Module) -> Result<(), VmError> {
+        if !self.require_signed_modules {
+            return Ok(());
+        }
+        let sig = module
+            .verify_signature()
+            .map_err(|e| VmError::UntrustedModule(format!("{}: {e}", module.name)))?;
+        if self
+            .trusted_module_keys
+            .iter()
+            .any(|k| k.eq_ignore_ascii_case(&sig.public_key))
+        {
+            Ok(())
+        } else {
+            Err(VmError::UntrustedModule(format!(
+                "{}: signing key {} is not in trusted_module_keys",
+                module.name, sig.public_key
+            )))
+        }
+    }
 }
 
 /// Capability gateway — all side effects go through here.
@@ -146,11 +549,84 @@ pub struct CapabilityGateway {
     usage: BTreeMap<String, u64>,
     /// Host-provided handler for capability calls.
     handler: Box<dyn CapabilityHandler>,
+    /// Calls recorded in [`PolicyMode::DryRun`].
+    transcript: Vec<TranscriptEntry>,
+    /// Calls refused in [`PolicyMode::Enforce`].
+    denials: Vec<Denial>,
+    /// Asked about capabilities whose rule has `prompt` set.
+    prompter: Option<Box<dyn CapabilityPrompter>>,
+    /// Answers given by the prompter this run, by capability name.
+    answers: BTreeMap<String, bool>,
+    /// Run budget debited for each call and its payload bytes.
+    ledger: Option<BudgetLedger>,
 }
 
 /// Trait for host-provided capability implementations.
 pub trait CapabilityHandler: Send {
     fn handle(&mut self, cap: &Capability, args: &[Value]) -> Result<Value, String>;
+
+    /// Whether this handler serves `cap` itself. Wrapping handlers answer
+    /// `false` for the capabilities they pass to [`delegate`](Self::delegate),
+    /// so batched and concurrent calls still reach the wrapped handler's
+    /// bulk path.
+    fn handles(&self, _cap: &Capability) -> bool {
+        true
+    }
+
+    /// The handler this one wraps, if any.
+    fn delegate(&mut self) -> Option<&mut dyn CapabilityHandler> {
+        None
+    }
+
+    /// Handle a batch of calls to one capability, returning one result per
+    /// item, in item order. The default handles the items one at a time;
+    /// handlers with a cheaper bulk path (concurrent requests, one query
+    /// for many keys) override it.
+    fn handle_batch(
+        &mut self,
+        cap: &Capability,
+        batch: &[Vec<Value>],
+    ) -> Vec<Result<Value, String>> {
+        if !self.handles(cap) {
+            if let Some(inner) = self.delegate() {
+                return inner.handle_batch(cap, batch);
+            }
+        }
+        batch.iter().map(|args| self.handle(cap, args)).collect()
+    }
+
+    /// Handle independent calls, possibly to different capabilities, that
+    /// a script started without waiting on each other (`cap.spawn`).
+    /// Returns one result per call, in call order. The default serves its
+    /// own calls one at a time and passes the rest to
+    /// [`delegate`](Self::delegate) in one go.
+    fn handle_many(&mut self, calls: &[(Capability, Vec<Value>)]) -> Vec<Result<Value, String>> {
+        let mut results: Vec<Option<Result<Value, String>>> = vec![None; calls.len()];
+        let forwarded: Vec<usize> = (0..calls.len())
+            .filter(|&i| !self.handles(&calls[i].0))
+            .collect();
+        if !forwarded.is_empty() {
+            if let Some(inner) = self.delegate() {
+                let sub: Vec<_> = forwarded.iter().map(|&i| calls[i].clone()).collect();
+                for (&i, result) in forwarded.iter().zip(inner.handle_many(&sub)) {
+                    results[i] = Some(result);
+                }
+            }
+        }
+        results
+            .into_iter()
+            .zip(calls)
+            .map(|(result, (cap, args))| result.unwrap_or_else(|| self.handle(cap, args)))
+            .collect()
+    }
+}
+
+/// Host-provided operator prompt for `prompt` rules. Called at most once
+/// per capability per run, on its first call; the answer (`Some(true)` to
+/// allow) then applies to every later call of that capability. `None`
+/// means no answer could be obtained, and the rule's `allow` applies.
+pub trait CapabilityPrompter: Send {
+    fn ask(&mut self, cap: &Capability, args: &[Value]) -> Option<bool>;
 }
 
 /// Default handler that returns mock values (for testing / sandbox).
@@ -185,6 +661,19 @@ impl CapabilityHandler for MockHandler {
                 // Actor ops are handled at the opcode level, not through the gateway
                 Ok(Value::Unit)
             }
+            Capability::KvGet => Ok(Value::None),
+            Capability::KvSet => Ok(Value::Unit),
+            Capability::KvScan => Ok(Value::Map(BTreeMap::new())),
+            Capability::QueuePush => Ok(Value::Int(1)),
+            Capability::QueuePoll => Ok(Value::None),
+            Capability::NotifySend => {
+                let mut receipt = BTreeMap::new();
+                receipt.insert("status".to_string(), Value::Int(0));
+                Ok(Value::Map(receipt))
+            }
+            Capability::DataReadTable => Ok(Value::Table(
+                boruna_bytecode::Table::new(Vec::new()).expect("empty table is valid"),
+            )),
             Capability::StepInput => {
                 // 0.3-S14: the MockHandler returns an empty string for
                 // step.input — the real implementation lives in the
@@ -273,6 +762,14 @@ impl CapabilityHandler for StepInputHandler {
             self.inner.handle(cap, args)
         }
     }
+
+    fn handles(&self, cap: &Capability) -> bool {
+        matches!(cap, Capability::StepInput)
+    }
+
+    fn delegate(&mut self) -> Option<&mut dyn CapabilityHandler> {
+        Some(self.inner.as_mut())
+    }
 }
 
 /// Multi-provider router for `Capability::LlmCall` (sprint `0.4-S13`).
@@ -404,6 +901,14 @@ impl CapabilityHandler for LlmRouterHandler {
             )),
         }
     }
+
+    fn handles(&self, cap: &Capability) -> bool {
+        matches!(cap, Capability::LlmCall)
+    }
+
+    fn delegate(&mut self) -> Option<&mut dyn CapabilityHandler> {
+        Some(self.fallback.as_mut())
+    }
 }
 
 /// Replay handler that returns values from a recorded log.
@@ -428,6 +933,26 @@ impl CapabilityHandler for ReplayHandler {
             Err("replay log exhausted".into())
         }
     }
+
+    /// Batch items were recorded as the `Ok`/`Err` values the gateway
+    /// wrapped them in; unwrap them so the gateway wraps them again.
+    fn handle_batch(
+        &mut self,
+        cap: &Capability,
+        batch: &[Vec<Value>],
+    ) -> Vec<Result<Value, String>> {
+        batch
+            .iter()
+            .map(|args| match self.handle(cap, args)? {
+                Value::Ok(v) => Ok(*v),
+                Value::Err(e) => Err(match *e {
+                    Value::String(msg) => msg,
+                    other => other.to_string(),
+                }),
+                other => Ok(other),
+            })
+            .collect()
+    }
 }
 
 impl CapabilityGateway {
@@ -436,6 +961,11 @@ impl CapabilityGateway {
             policy,
             usage: BTreeMap::new(),
             handler: Box::new(MockHandler),
+            transcript: Vec::new(),
+            denials: Vec::new(),
+            prompter: None,
+            answers: BTreeMap::new(),
+            ledger: None,
         }
     }
 
@@ -449,9 +979,39 @@ impl CapabilityGateway {
             policy,
             usage: BTreeMap::new(),
             handler,
+            transcript: Vec::new(),
+            denials: Vec::new(),
+            prompter: None,
+            answers: BTreeMap::new(),
+            ledger: None,
         }
     }
 
+    /// Replace the handler with `wrap(handler)`, for composing handlers
+    /// such as [`KvHandler`](crate::kv_store::KvHandler) over whatever
+    /// the gateway was built with.
+    pub fn wrap_handler(
+        mut self,
+        wrap: impl FnOnce(Box<dyn CapabilityHandler>) -> Box<dyn CapabilityHandler>,
+    ) -> Self {
+        let inner = std::mem::replace(&mut self.handler, Box::new(MockHandler));
+        self.handler = wrap(inner);
+        self
+    }
+
+    /// Debit `ledger` for every call that reaches the handler: one
+    /// capability call plus the bytes of its arguments, then the bytes of
+    /// its result. Dry runs debit nothing.
+    pub fn set_budget_ledger(&mut self, ledger: BudgetLedger) {
+        self.ledger = Some(ledger);
+    }
+
+    /// Install an operator prompt for rules with `prompt` set.
+    pub fn with_prompter(mut self, prompter: Box<dyn CapabilityPrompter>) -> Self {
+        self.prompter = Some(prompter);
+        self
+    }
+
     /// Execute a capability call with policy enforcement.
     ///
     /// **Telemetry:** wraps the call body in a `tracing::info_span!` named
@@ -465,6 +1025,10 @@ impl CapabilityGateway {
     /// operational metadata only — never feed an `EventLog`, `AuditLog`, or
     /// `EvidenceBundle`. Capability args are NOT included in attributes
     /// (privacy + size); only their cumulative byte count is.
+    ///
+    /// In [`PolicyMode::DryRun`] no call fails on policy: each is recorded
+    /// in the [`transcript`](Self::transcript) and answered by
+    /// [`MockHandler`] instead of the installed handler.
     pub fn call(
         &mut self,
         cap: &Capability,
@@ -486,13 +1050,18 @@ impl CapabilityGateway {
         let _enter = span.enter();
 
         // Check policy
-        let rule = self.policy.rules.get(name);
-        let allowed = match rule {
-            Some(r) => r.allow,
-            None => self.policy.default_allow,
+        let rule = self.policy.rules.get(name).cloned();
+        let dry_run = self.policy.mode == PolicyMode::DryRun;
+        let Some(allowed) = self.allowed(cap, args, rule.as_ref(), dry_run) else {
+            // A dry run never pauses; it reports that it would ask.
+            return Ok(self.dry_run(cap, args, PolicyDecision::Prompt, log));
         };
         if !allowed {
             span.record("error.kind", "denied");
+            if dry_run {
+                return Ok(self.dry_run(cap, args, PolicyDecision::Deny, log));
+            }
+            self.deny(cap, args, PolicyDecision::Deny);
             return Err(VmError::CapabilityDenied(*cap));
         }
 
@@ -504,16 +1073,30 @@ impl CapabilityGateway {
         // join on (cap.budget_remaining, error.kind) to disambiguate.
         let count = self.usage.entry(name.to_string()).or_insert(0);
         *count += 1;
-        if let Some(r) = rule {
+        if let Some(r) = &rule {
             if r.budget > 0 {
                 if *count > r.budget {
                     span.record("error.kind", "budget_exceeded");
                     span.record("cap.budget_remaining", 0u64);
+                    if dry_run {
+                        return Ok(self.dry_run(cap, args, PolicyDecision::BudgetExceeded, log));
+                    }
+                    self.deny(cap, args, PolicyDecision::BudgetExceeded);
                     return Err(VmError::CapabilityBudgetExceeded(*cap));
                 }
                 span.record("cap.budget_remaining", r.budget.saturating_sub(*count));
             }
         }
+        if dry_run {
+            let result = self.dry_run(cap, args, PolicyDecision::Allow, log);
+            span.record("bytes_out", approx_value_bytes(&result));
+            return Ok(result);
+        }
+
+        if let Err(e) = self.charge(1, bytes_in) {
+            span.record("error.kind", "run_budget_exhausted");
+            return Err(e);
+        }
 
         // Log the call (replay-verified state)
         log.log_cap_call(cap, args);
@@ -531,14 +1114,342 @@ impl CapabilityGateway {
         log.log_cap_result(cap, &result);
 
         // Operational telemetry: record output size on the span.
-        span.record("bytes_out", approx_value_bytes(&result));
+        let bytes_out = approx_value_bytes(&result);
+        span.record("bytes_out", bytes_out);
+        if let Err(e) = self.charge(0, bytes_out) {
+            span.record("error.kind", "run_budget_exhausted");
+            return Err(e);
+        }
 
         Ok(result)
     }
 
+    /// Execute `batch.len()` calls of one capability in a single handler
+    /// round-trip ([`CapabilityHandler::handle_batch`]).
+    ///
+    /// Policy is decided once for the whole batch and the budget is charged
+    /// per item; a batch that would overrun the budget is refused before
+    /// any item runs. Each item is logged as its own `CapCall`/`CapResult`
+    /// pair, in item order, so a batch replays like the same calls made one
+    /// after another. Item results are `Ok(value)`, or `Err(message)` when
+    /// the handler failed that item; a failed item does not fail the batch.
+    pub fn call_batch(
+        &mut self,
+        cap: &Capability,
+        batch: &[Vec<Value>],
+        log: &mut EventLog,
+    ) -> Result<Vec<Value>, VmError> {
+        let name = cap.name();
+        let span = tracing::info_span!(
+            "boruna.cap_batch",
+            cap.name = name,
+            batch.size = batch.len(),
+            bytes_in = batch.iter().map(|args| approx_bytes(args)).sum::<u64>(),
+            bytes_out = tracing::field::Empty,
+            error.kind = tracing::field::Empty,
+        );
+        let _enter = span.enter();
+
+        let rule = self.policy.rules.get(name).cloned();
+        let dry_run = self.policy.mode == PolicyMode::DryRun;
+        let first = batch.first().map(Vec::as_slice).unwrap_or_default();
+        let mut decision = match self.allowed(cap, first, rule.as_ref(), dry_run) {
+            Some(true) => PolicyDecision::Allow,
+            Some(false) => PolicyDecision::Deny,
+            None => PolicyDecision::Prompt,
+        };
+        if decision == PolicyDecision::Allow {
+            let count = self.usage.entry(name.to_string()).or_insert(0);
+            *count += batch.len() as u64;
+            if rule
+                .as_ref()
+                .is_some_and(|r| r.budget > 0 && *count > r.budget)
+            {
+                decision = PolicyDecision::BudgetExceeded;
+            }
+        }
+        let error_kind = match decision {
+            PolicyDecision::Deny => Some("denied"),
+            PolicyDecision::BudgetExceeded => Some("budget_exceeded"),
+            _ => None,
+        };
+        if let Some(kind) = error_kind {
+            span.record("error.kind", kind);
+        }
+        if decision != PolicyDecision::Allow && !dry_run {
+            for args in batch {
+                self.deny(cap, args, decision);
+            }
+            return Err(match decision {
+                PolicyDecision::BudgetExceeded => VmError::CapabilityBudgetExceeded(*cap),
+                _ => VmError::CapabilityDenied(*cap),
+            });
+        }
+
+        let outcomes = if dry_run {
+            batch
+                .iter()
+                .map(|args| MockHandler.handle(cap, args))
+                .collect()
+        } else {
+            let bytes_in = batch.iter().map(|args| approx_bytes(args)).sum();
+            if let Err(e) = self.charge(batch.len() as u64, bytes_in) {
+                span.record("error.kind", "run_budget_exhausted");
+                return Err(e);
+            }
+            self.handler.handle_batch(cap, batch)
+        };
+        if outcomes.len() != batch.len() {
+            span.record("error.kind", "runtime_error");
+            return Err(VmError::AssertionFailed(format!(
+                "capability error: {name} handler returned {} results for a batch of {}",
+                outcomes.len(),
+                batch.len()
+            )));
+        }
+
+        let mut results = Vec::with_capacity(batch.len());
+        for (args, outcome) in batch.iter().zip(outcomes) {
+            let result = match outcome {
+                Ok(v) => Value::Ok(Box::new(v)),
+                Err(e) => Value::Err(Box::new(Value::String(e))),
+            };
+            log.log_cap_call(cap, args);
+            log.log_cap_result(cap, &result);
+            if dry_run {
+                self.transcript.push(TranscriptEntry {
+                    seq: self.transcript.len() as u64 + 1,
+                    capability: name.to_string(),
+                    args: args.clone(),
+                    decision,
+                    result: result.clone(),
+                });
+            }
+            results.push(result);
+        }
+        let bytes_out = results.iter().map(approx_value_bytes).sum::<u64>();
+        span.record("bytes_out", bytes_out);
+        if !dry_run {
+            if let Err(e) = self.charge(0, bytes_out) {
+                span.record("error.kind", "run_budget_exhausted");
+                return Err(e);
+            }
+        }
+        Ok(results)
+    }
+
+    /// Start a call without running it (`cap.spawn`). Policy and budget
+    /// are decided now, exactly as [`call`](Self::call) would decide them,
+    /// so a refused call fails at the spawn site. Nothing is logged until
+    /// the call runs in [`complete`](Self::complete).
+    pub fn admit(&mut self, cap: &Capability, args: Vec<Value>) -> Result<PendingCall, VmError> {
+        let name = cap.name();
+        let span = tracing::info_span!(
+            "boruna.cap_spawn",
+            cap.name = name,
+            bytes_in = approx_bytes(&args),
+            error.kind = tracing::field::Empty,
+        );
+        let _enter = span.enter();
+
+        let rule = self.policy.rules.get(name).cloned();
+        let dry_run = self.policy.mode == PolicyMode::DryRun;
+        let mut decision = match self.allowed(cap, &args, rule.as_ref(), dry_run) {
+            Some(true) => PolicyDecision::Allow,
+            Some(false) => PolicyDecision::Deny,
+            None => PolicyDecision::Prompt,
+        };
+        if decision == PolicyDecision::Allow {
+            let count = self.usage.entry(name.to_string()).or_insert(0);
+            *count += 1;
+            if rule
+                .as_ref()
+                .is_some_and(|r| r.budget > 0 && *count > r.budget)
+            {
+                decision = PolicyDecision::BudgetExceeded;
+            }
+        }
+        let error_kind = match decision {
+            PolicyDecision::Deny => Some("denied"),
+            PolicyDecision::BudgetExceeded => Some("budget_exceeded"),
+            _ => None,
+        };
+        if let Some(kind) = error_kind {
+            span.record("error.kind", kind);
+        }
+        if decision != PolicyDecision::Allow && !dry_run {
+            self.deny(cap, &args, decision);
+            return Err(match decision {
+                PolicyDecision::BudgetExceeded => VmError::CapabilityBudgetExceeded(*cap),
+                _ => VmError::CapabilityDenied(*cap),
+            });
+        }
+        if !dry_run {
+            if let Err(e) = self.charge(1, approx_bytes(&args)) {
+                span.record("error.kind", "run_budget_exhausted");
+                return Err(e);
+            }
+        }
+        Ok(PendingCall {
+            cap: *cap,
+            args,
+            decision,
+        })
+    }
+
+    /// Run admitted calls together in one handler round-trip
+    /// ([`CapabilityHandler::handle_many`]), which may serve them
+    /// concurrently. Each call is logged as a `CapCall`/`CapResult` pair in
+    /// the order of `calls`, whatever order the handler finished them in;
+    /// that order is what replay checks.
+    pub fn complete(
+        &mut self,
+        calls: &[PendingCall],
+        log: &mut EventLog,
+    ) -> Vec<Result<Value, VmError>> {
+        let span = tracing::info_span!(
+            "boruna.cap_complete",
+            calls = calls.len(),
+            error.kind = tracing::field::Empty,
+        );
+        let _enter = span.enter();
+
+        if self.policy.mode == PolicyMode::DryRun {
+            return calls
+                .iter()
+                .map(|c| Ok(self.dry_run(&c.cap, &c.args, c.decision, log)))
+                .collect();
+        }
+        let requests: Vec<(Capability, Vec<Value>)> =
+            calls.iter().map(|c| (c.cap, c.args.clone())).collect();
+        let mut outcomes = self.handler.handle_many(&requests).into_iter();
+        calls
+            .iter()
+            .map(|c| {
+                log.log_cap_call(&c.cap, &c.args);
+                let outcome = outcomes
+                    .next()
+                    .unwrap_or_else(|| Err(format!("{} handler returned no result", c.cap)));
+                match outcome {
+                    Ok(v) => {
+                        log.log_cap_result(&c.cap, &v);
+                        self.charge(0, approx_value_bytes(&v))?;
+                        Ok(v)
+                    }
+                    Err(e) => {
+                        span.record("error.kind", "runtime_error");
+                        Err(VmError::AssertionFailed(format!("capability error: {e}")))
+                    }
+                }
+            })
+            .collect()
+    }
+
     pub fn usage(&self) -> &BTreeMap<String, u64> {
         &self.usage
     }
+
+    /// Calls recorded so far in [`PolicyMode::DryRun`], in call order.
+    /// Empty when enforcing.
+    pub fn transcript(&self) -> &[TranscriptEntry] {
+        &self.transcript
+    }
+
+    /// Calls refused by policy so far. Refused calls never reach the
+    /// [`EventLog`], so this is the only record of them.
+    pub fn denials(&self) -> &[Denial] {
+        &self.denials
+    }
+
+    /// The per-run counters [`Vm::snapshot`](crate::Vm::snapshot) saves.
+    /// Prompter answers are not among them: an operator asked during a
+    /// discarded branch is not asked again.
+    pub(crate) fn accounting(&self) -> GatewayAccounting {
+        GatewayAccounting {
+            usage: self.usage.clone(),
+            transcript: self.transcript.clone(),
+            denials: self.denials.clone(),
+        }
+    }
+
+    pub(crate) fn restore_accounting(&mut self, accounting: GatewayAccounting) {
+        self.usage = accounting.usage;
+        self.transcript = accounting.transcript;
+        self.denials = accounting.denials;
+    }
+
+    /// Whether policy allows a call of `cap`, asking the prompter the
+    /// first time a `prompt` rule is hit. `None` when a dry run reaches an
+    /// unanswered `prompt` rule, which it never asks about.
+    fn allowed(
+        &mut self,
+        cap: &Capability,
+        args: &[Value],
+        rule: Option<&PolicyRule>,
+        dry_run: bool,
+    ) -> Option<bool> {
+        let name = cap.name();
+        Some(match rule {
+            Some(r) if r.prompt => match self.answers.get(name) {
+                Some(&answer) => answer,
+                None if dry_run => return None,
+                None => {
+                    let answer = self
+                        .prompter
+                        .as_mut()
+                        .and_then(|p| p.ask(cap, args))
+                        .unwrap_or(r.allow);
+                    self.answers.insert(name.to_string(), answer);
+                    answer
+                }
+            },
+            Some(r) => r.allow,
+            None => self.policy.default_allow,
+        })
+    }
+
+    /// Debit the run budget, if one is attached, for `calls` capability
+    /// calls moving `bytes` of payload.
+    fn charge(&self, calls: u64, bytes: u64) -> Result<(), VmError> {
+        let Some(ledger) = &self.ledger else {
+            return Ok(());
+        };
+        ledger
+            .debit(Resource::CapabilityCalls, calls)
+            .and_then(|()| ledger.debit(Resource::Bytes, bytes))
+            .map_err(VmError::RunBudgetExhausted)
+    }
+
+    fn deny(&mut self, cap: &Capability, args: &[Value], decision: PolicyDecision) {
+        self.denials.push(Denial {
+            capability: cap.name().to_string(),
+            args: args.to_vec(),
+            decision,
+        });
+    }
+
+    /// Answer a dry-run call from [`MockHandler`], logging it like a real
+    /// call so the run stays replayable.
+    fn dry_run(
+        &mut self,
+        cap: &Capability,
+        args: &[Value],
+        decision: PolicyDecision,
+        log: &mut EventLog,
+    ) -> Value {
+        log.log_cap_call(cap, args);
+        // MockHandler never fails.
+        let result = MockHandler.handle(cap, args).unwrap_or(Value::Unit);
+        log.log_cap_result(cap, &result);
+        self.transcript.push(TranscriptEntry {
+            seq: self.transcript.len() as u64 + 1,
+            capability: cap.name().to_string(),
+            args: args.to_vec(),
+            decision,
+            result: result.clone(),
+        });
+        result
+    }
 }
 
 /// Best-effort byte-count estimate for telemetry attributes only.
@@ -551,7 +1462,7 @@ impl CapabilityGateway {
 /// fixed-size and not the payload story we're trying to surface.
 ///
 /// This is OPERATIONAL metadata only; never feed it into an audit hash.
-fn approx_value_bytes(value: &Value) -> u64 {
+pub fn approx_value_bytes(value: &Value) -> u64 {
     match value {
         Value::String(s) => s.len() as u64,
         Value::List(items) => items.iter().map(approx_value_bytes).sum(),
@@ -0,0 +1,348 @@
+//! Capability delegation between actors.
+//!
+//! A spawned actor runs under its parent's policy unless the scheduler
+//! holds a grant for the function it runs
+//! ([`ActorSystem::delegate`](crate::actor::ActorSystem::delegate)). The
+//! child's gateway then enforces [`Policy::attenuate`] of the parent's
+//! policy by the grant, so a helper can be handed less than its parent
+//! holds — never more, whatever the grant says. Grants compose down the
+//! actor tree: a grandchild is attenuated from its parent's already
+//! attenuated policy.
+//!
+//! Every attenuated spawn is logged as `CapabilityDelegation` with the
+//! parent, the child and the [`policy_hash`] of the child's policy, so
+//! the chain from the root to any actor can be read back from the event
+//! log.
+//!
+//! Budgets are counted per actor gateway, as before: a child's budget of
+//! `n` calls is its own, not a share of its parent's.
+
+use std::collections::BTreeMap;
+
+use boruna_bytecode::Capability;
+use boruna_hash::Hasher;
+
+use crate::capability_gateway::{
+    domain_matches, method_matches, DataFlowPolicy, KvPolicy, NetPolicy, NotifyPolicy, Policy,
+    PolicyMode, PolicyRule, QueuePolicy, TablePolicy,
+};
+
+/// `sha256:<hex>` over the compact JSON of `policy`.
+pub fn policy_hash(policy: &Policy) -> String {
+    let json = serde_json::to_string(policy).unwrap_or_default();
+    let mut hasher = Hasher::sha256();
+    hasher.update(json.as_bytes());
+    hasher.finalize().to_string()
+}
+
+impl Policy {
+    /// The policy allowing only what both `self` and `grant` allow.
+    ///
+    /// A capability is allowed when both policies allow it, its budget is
+    /// the smaller one (0 still meaning unlimited), and every sub-policy
+    /// keeps the stricter limit of each field. Allow-lists are intersected;
+    /// when two non-empty lists share nothing, the capabilities they govern
+    /// are denied outright, since an empty list would mean "all".
+    pub fn attenuate(&self, grant: &Policy) -> Policy {
+        let mut rules = BTreeMap::new();
+        for name in self.rules.keys().chain(grant.rules.keys()) {
+            let rule = narrow_rule(&self.rule_for(name), &grant.rule_for(name));
+            rules.insert(name.clone(), rule);
+        }
+        let mut denied = Vec::new();
+
+        let net_policy = both(&self.net_policy, &grant.net_policy, |a, b| NetPolicy {
+            allowed_domains: narrow_list(&a.allowed_domains, &b.allowed_domains, domain_covered)
+                .unwrap_or_else(|| {
+                    denied.push(Capability::NetFetch);
+                    Vec::new()
+                }),
+            allowed_methods: narrow_list(&a.allowed_methods, &b.allowed_methods, |m, list| {
+                method_matches(m, list)
+            })
+            .unwrap_or_else(|| {
+                denied.push(Capability::NetFetch);
+                Vec::new()
+            }),
+            max_response_bytes: a.max_response_bytes.min(b.max_response_bytes),
+            timeout_ms: a.timeout_ms.min(b.timeout_ms),
+            allow_redirects: a.allow_redirects && b.allow_redirects,
+        });
+        let kv_policy = both(&self.kv_policy, &grant.kv_policy, |a, b| {
+            let mut read_only: Vec<String> =
+                a.read_only.iter().chain(&b.read_only).cloned().collect();
+            read_only.sort();
+            read_only.dedup();
+            KvPolicy {
+                namespaces: narrow_list(&a.namespaces, &b.namespaces, listed).unwrap_or_else(
+                    || {
+                        denied.extend([Capability::KvGet, Capability::KvSet, Capability::KvScan]);
+                        Vec::new()
+                    },
+                ),
+                read_only,
+            }
+        });
+        let queue_policy = both(&self.queue_policy, &grant.queue_policy, |a, b| {
+            QueuePolicy {
+                push: narrow_list(&a.push, &b.push, listed).unwrap_or_else(|| {
+                    denied.push(Capability::QueuePush);
+                    Vec::new()
+                }),
+                poll: narrow_list(&a.poll, &b.poll, listed).unwrap_or_else(|| {
+                    denied.push(Capability::QueuePoll);
+                    Vec::new()
+                }),
+            }
+        });
+        let notify_policy = both(&self.notify_policy, &grant.notify_policy, |a, b| {
+            NotifyPolicy {
+                destinations: narrow_list(&a.destinations, &b.destinations, destination_covered)
+                    .unwrap_or_else(|| {
+                        denied.push(Capability::NotifySend);
+                        Vec::new()
+                    }),
+                max_per_run: min_limit(a.max_per_run, b.max_per_run),
+                max_per_destination: min_limit(a.max_per_destination, b.max_per_destination),
+            }
+        });
+        let table_policy = both(&self.table_policy, &grant.table_policy, |a, b| {
+            TablePolicy {
+                max_rows: a.max_rows.min(b.max_rows),
+                max_bytes: a.max_bytes.min(b.max_bytes),
+            }
+        });
+        let data_flow = both(&self.data_flow, &grant.data_flow, |a, b| {
+            let mut max_classification = a.max_classification.clone();
+            for (cap, label) in &b.max_classification {
+                max_classification
+                    .entry(cap.clone())
+                    .and_modify(|l| *l = (*l).min(*label))
+                    .or_insert(*label);
+            }
+            DataFlowPolicy {
+                max_classification,
+                redact_in_evidence: match (a.redact_in_evidence, b.redact_in_evidence) {
+                    (Some(x), Some(y)) => Some(x.min(y)),
+                    (x, y) => x.or(y),
+                },
+            }
+        });
+
+        for cap in denied {
+            rules.insert(
+                cap.name().to_string(),
+                PolicyRule {
+                    allow: false,
+                    budget: 0,
+                    prompt: false,
+                },
+            );
+        }
+
+        let trusted_module_keys = match (self.require_signed_modules, grant.require_signed_modules)
+        {
+            (true, true) => self
+                .trusted_module_keys
+                .iter()
+                .filter(|k| {
+                    grant
+                        .trusted_module_keys
+                        .iter()
+                        .any(|g| g.eq_ignore_ascii_case(k))
+                })
+                .cloned()
+                .collect(),
+            (false, true) => grant.trusted_module_keys.clone(),
+            _ => self.trusted_module_keys.clone(),
+        };
+
+        Policy {
+            schema_version: self.schema_version,
+            rules,
+            default_allow: self.default_allow && grant.default_allow,
+            net_policy,
+            require_signed_modules: self.require_signed_modules || grant.require_signed_modules,
+            trusted_module_keys,
+            mode: if self.mode == PolicyMode::DryRun || grant.mode == PolicyMode::DryRun {
+                PolicyMode::DryRun
+            } else {
+                PolicyMode::Enforce
+            },
+            data_flow,
+            kv_policy,
+            queue_policy,
+            notify_policy,
+            table_policy,
+        }
+    }
+
+    /// The rule the gateway applies to the capability `name`.
+    fn rule_for(&self, name: &str) -> PolicyRule {
+        self.rules.get(name).cloned().unwrap_or(PolicyRule {
+            allow: self.default_allow,
+            budget: 0,
+            prompt: false,
+        })
+    }
+}
+
+/// Allowed only when both rules allow. A `prompt` survives only if
+/// neither side rules the capability out, so a prompter can never grant
+/// what the parent denies.
+fn narrow_rule(a: &PolicyRule, b: &PolicyRule) -> PolicyRule {
+    let may_allow = |r: &PolicyRule| r.allow || r.prompt;
+    PolicyRule {
+        allow: a.allow && b.allow,
+        budget: match (a.budget, b.budget) {
+            (0, n) | (n, 0) => n,
+            (x, y) => x.min(y),
+        },
+        prompt: (a.prompt || b.prompt) && may_allow(a) && may_allow(b),
+    }
+}
+
+fn min_limit(a: u32, b: u32) -> u32 {
+    match (a, b) {
+        (0, n) | (n, 0) => n,
+        (x, y) => x.min(y),
+    }
+}
+
+/// Combine two optional sub-policies; one set on a single side applies
+/// as is.
+fn both<T: Clone>(a: &Option<T>, b: &Option<T>, mut combine: impl FnMut(&T, &T) -> T) -> Option<T> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(combine(a, b)),
+        (a, b) => a.clone().or_else(|| b.clone()),
+    }
+}
+
+/// Intersect two allow-lists in which empty means "all": the entries of
+/// each list that the other list fully covers. `None` when two non-empty
+/// lists share nothing.
+fn narrow_list(
+    parent: &[String],
+    grant: &[String],
+    covered: impl Fn(&str, &[String]) -> bool,
+) -> Option<Vec<String>> {
+    if parent.is_empty() {
+        return Some(grant.to_vec());
+    }
+    if grant.is_empty() {
+        return Some(parent.to_vec());
+    }
+    let mut kept: Vec<String> = grant
+        .iter()
+        .filter(|e| covered(e, parent))
+        .chain(parent.iter().filter(|e| covered(e, grant)))
+        .cloned()
+        .collect();
+    kept.sort();
+    kept.dedup();
+    (!kept.is_empty()).then_some(kept)
+}
+
+fn listed(entry: &str, list: &[String]) -> bool {
+    list.iter().any(|e| e == entry)
+}
+
+/// Whether every host `entry` matches is matched by `list`.
+fn domain_covered(entry: &str, list: &[String]) -> bool {
+    match entry.strip_prefix('*') {
+        Some(suffix) if suffix.starts_with('.') => list.iter().any(|p| {
+            p.strip_prefix('*')
+                .is_some_and(|s| s.starts_with('.') && suffix.ends_with(s))
+        }),
+        _ => domain_matches(entry, list),
+    }
+}
+
+/// Whether every destination `entry` matches is matched by `list`; a
+/// trailing `*` matches by prefix.
+fn destination_covered(entry: &str, list: &[String]) -> bool {
+    let entry_prefix = entry.strip_suffix('*');
+    list.iter()
+        .any(|d| match (d.strip_suffix('*'), entry_prefix) {
+            (Some(prefix), Some(entry_prefix)) => entry_prefix.starts_with(prefix),
+            (Some(prefix), None) => entry.starts_with(prefix),
+            (None, Some(_)) => false,
+            (None, None) => d == entry,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn net(domains: &[&str]) -> Policy {
+        let mut policy = Policy::allow_all();
+        policy.net_policy = Some(NetPolicy {
+            allowed_domains: domains.iter().map(|d| d.to_string()).collect(),
+            ..NetPolicy::default()
+        });
+        policy
+    }
+
+    #[test]
+    fn test_grant_cannot_amplify() {
+        let mut parent = Policy::deny_all();
+        parent.allow(&Capability::TimeNow, 3);
+        let grant = Policy::allow_all();
+
+        let child = parent.attenuate(&grant);
+        assert!(!child.default_allow);
+        assert!(!child.rule_for("net.fetch").allow);
+        let time = child.rule_for("time.now");
+        assert!(time.allow);
+        assert_eq!(time.budget, 3);
+    }
+
+    #[test]
+    fn test_grant_narrows_rules_and_budgets() {
+        let parent = Policy::allow_all();
+        let mut grant = Policy::allow_all();
+        grant.deny(&Capability::NetFetch);
+        grant.allow(&Capability::LlmCall, 2);
+
+        let child = parent.attenuate(&grant);
+        assert!(!child.rule_for("net.fetch").allow);
+        assert_eq!(child.rule_for("llm.call").budget, 2);
+        assert!(child.rule_for("fs.read").allow);
+        assert_eq!(
+            policy_hash(&child.attenuate(&Policy::allow_all())),
+            policy_hash(&child)
+        );
+    }
+
+    #[test]
+    fn test_disjoint_allow_lists_deny_the_capability() {
+        let child = net(&["*.example.com"]).attenuate(&net(&["api.example.com", "evil.test"]));
+        assert_eq!(
+            child.net_policy.as_ref().unwrap().allowed_domains,
+            vec!["api.example.com".to_string()]
+        );
+        assert!(child.rule_for("net.fetch").allow);
+
+        let child = net(&["api.example.com"]).attenuate(&net(&["*.other.com"]));
+        assert!(!child.rule_for("net.fetch").allow);
+    }
+
+    #[test]
+    fn test_prompt_cannot_override_parent_denial() {
+        let mut parent = Policy::allow_all();
+        parent.deny(&Capability::FsWrite);
+        let mut grant = Policy::allow_all();
+        grant.rules.insert(
+            "fs.write".into(),
+            PolicyRule {
+                allow: false,
+                budget: 0,
+                prompt: true,
+            },
+        );
+        let rule = parent.attenuate(&grant).rule_for("fs.write");
+        assert!(!rule.allow);
+        assert!(!rule.prompt);
+    }
+}
@@ -0,0 +1,324 @@
+//! Root-cause analysis of a replay divergence.
+//!
+//! [`ReplayEngine::verify`](crate::replay::ReplayEngine::verify) answers
+//! whether a replay matched its recording. When it did not, [`analyze`]
+//! says how: it walks both event logs to the first event that differs,
+//! classifies the difference, and reports both versions of the event with
+//! the JSON pointer of the first differing value inside it.
+//!
+//! | kind | meaning |
+//! |------|---------|
+//! | `capability_result` | the same call got a different result |
+//! | `call_order` | the same calls happened in a different order |
+//! | `call_arguments` | a call was made with different arguments |
+//! | `nondeterministic_value` | the first differing value is a float, or a string differing only in its digits (timestamps, float formatting, generated ids) |
+//! | `control_flow` | a different kind of event happened at this point |
+//! | `event_count` | one log is a strict prefix of the other |
+//! | `step_count_drift` | every event matched, but the VM ran a different number of steps |
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::replay::{Event, EventLog};
+
+/// How a replay diverged from its recording.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DivergenceKind {
+    CapabilityResult,
+    CallOrder,
+    CallArguments,
+    NondeterministicValue,
+    ControlFlow,
+    EventCount,
+    StepCountDrift,
+}
+
+/// The first point at which a replay diverged, and why.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Divergence {
+    pub kind: DivergenceKind,
+    /// Index of the first differing event in both logs; absent for
+    /// `step_count_drift`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_index: Option<usize>,
+    /// The recorded event (or step count), `null` when the recording
+    /// ended first.
+    pub expected: serde_json::Value,
+    /// The replayed event (or step count), `null` when the replay ended
+    /// first.
+    pub actual: serde_json::Value,
+    /// JSON pointer into `expected`/`actual` of the first differing value.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    pub detail: String,
+}
+
+/// Find and classify the first divergence between `original` and
+/// `replay`. `None` when every event matches and neither log's recorded
+/// outcome shows a different step count.
+pub fn analyze(original: &EventLog, replay: &EventLog) -> Option<Divergence> {
+    let (orig, repl) = (original.events(), replay.events());
+    let first = orig
+        .iter()
+        .zip(repl)
+        .position(|(o, r)| to_json(o) != to_json(r));
+
+    let Some(i) = first else {
+        if orig.len() != repl.len() {
+            let i = orig.len().min(repl.len());
+            let (expected, actual) = (orig.get(i).map(to_json), repl.get(i).map(to_json));
+            let detail = if orig.len() > repl.len() {
+                format!(
+                    "replay stopped after {} events; the recording has {}",
+                    repl.len(),
+                    orig.len()
+                )
+            } else {
+                format!(
+                    "replay produced {} events; the recording stopped after {}",
+                    repl.len(),
+                    orig.len()
+                )
+            };
+            return Some(Divergence {
+                kind: DivergenceKind::EventCount,
+                event_index: Some(i),
+                expected: expected.unwrap_or_default(),
+                actual: actual.unwrap_or_default(),
+                path: None,
+                detail,
+            });
+        }
+        let steps = |log: &EventLog| log.outcome().map(|o| o.steps);
+        return match (steps(original), steps(replay)) {
+            (Some(a), Some(b)) if a != b => Some(Divergence {
+                kind: DivergenceKind::StepCountDrift,
+                event_index: None,
+                expected: a.into(),
+                actual: b.into(),
+                path: None,
+                detail: format!("every event matched, but the VM ran {b} steps instead of {a}"),
+            }),
+            _ => None,
+        };
+    };
+
+    let (o, r) = (&orig[i], &repl[i]);
+    let (expected, actual) = (to_json(o), to_json(r));
+    let path = first_difference(&expected, &actual, String::new());
+    let nondeterministic = path
+        .as_ref()
+        .is_some_and(|(_, a, b)| looks_nondeterministic(a, b));
+    let (kind, detail) = match (o, r) {
+        (Event::CapCall { capability: oc, .. }, Event::CapCall { capability: rc, .. })
+            if reordered(&orig[i..], &repl[i..]) =>
+        {
+            (
+                DivergenceKind::CallOrder,
+                format!("capability calls happened in a different order: expected {oc}, got {rc}"),
+            )
+        }
+        (Event::CapCall { capability: oc, .. }, Event::CapCall { capability: rc, .. })
+            if oc != rc =>
+        {
+            (
+                DivergenceKind::ControlFlow,
+                format!("expected a call to {oc}, got a call to {rc}"),
+            )
+        }
+        (Event::CapCall { capability, .. }, Event::CapCall { .. }) if nondeterministic => (
+            DivergenceKind::NondeterministicValue,
+            format!("{capability} was called with a value that changed between runs"),
+        ),
+        (Event::CapCall { capability, .. }, Event::CapCall { .. }) => (
+            DivergenceKind::CallArguments,
+            format!("{capability} was called with different arguments"),
+        ),
+        (Event::CapResult { capability, .. }, Event::CapResult { .. }) if nondeterministic => (
+            DivergenceKind::NondeterministicValue,
+            format!("{capability} returned a value that changed between runs"),
+        ),
+        (Event::CapResult { capability, .. }, Event::CapResult { .. }) => (
+            DivergenceKind::CapabilityResult,
+            format!("{capability} returned a different result"),
+        ),
+        _ if std::mem::discriminant(o) == std::mem::discriminant(r) && nondeterministic => (
+            DivergenceKind::NondeterministicValue,
+            format!(
+                "{} carried a value that changed between runs",
+                event_name(o)
+            ),
+        ),
+        _ => (
+            DivergenceKind::ControlFlow,
+            format!("expected {}, got {}", event_name(o), event_name(r)),
+        ),
+    };
+    Some(Divergence {
+        kind,
+        event_index: Some(i),
+        expected,
+        actual,
+        path: path.map(|(p, _, _)| p),
+        detail,
+    })
+}
+
+fn to_json(event: &Event) -> serde_json::Value {
+    serde_json::to_value(event).unwrap_or_default()
+}
+
+fn event_name(event: &Event) -> String {
+    match to_json(event) {
+        serde_json::Value::Object(map) => map.keys().next().cloned().unwrap_or_default(),
+        other => other.as_str().unwrap_or_default().to_string(),
+    }
+}
+
+/// True when both suffixes make the same multiset of capability calls,
+/// just not in the same order.
+fn reordered(orig: &[Event], repl: &[Event]) -> bool {
+    let calls = |events: &[Event]| {
+        let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+        for e in events {
+            if let Event::CapCall { .. } = e {
+                *counts.entry(to_json(e).to_string()).or_default() += 1;
+            }
+        }
+        counts
+    };
+    calls(orig) == calls(repl)
+}
+
+/// Pointer to, and both sides of, the first leaf at which `a` and `b`
+/// differ.
+fn first_difference(
+    a: &serde_json::Value,
+    b: &serde_json::Value,
+    path: String,
+) -> Option<(String, serde_json::Value, serde_json::Value)> {
+    use serde_json::Value as J;
+    match (a, b) {
+        _ if a == b => None,
+        (J::Object(x), J::Object(y)) if x.len() == y.len() && x.keys().eq(y.keys()) => x
+            .iter()
+            .find_map(|(k, v)| first_difference(v, &y[k], format!("{path}/{k}"))),
+        (J::Array(x), J::Array(y)) if x.len() == y.len() => x
+            .iter()
+            .zip(y)
+            .enumerate()
+            .find_map(|(i, (v, w))| first_difference(v, w, format!("{path}/{i}"))),
+        _ => Some((path, a.clone(), b.clone())),
+    }
+}
+
+/// A float, or two strings that both contain digits and differ only in
+/// them.
+fn looks_nondeterministic(a: &serde_json::Value, b: &serde_json::Value) -> bool {
+    use serde_json::Value as J;
+    match (a, b) {
+        (J::Number(x), J::Number(y)) => x.is_f64() || y.is_f64(),
+        (J::String(x), J::String(y)) => {
+            let digits = |s: &str| s.chars().any(|c| c.is_ascii_digit());
+            let strip = |s: &str| {
+                s.chars()
+                    .filter(|c| !c.is_ascii_digit())
+                    .collect::<String>()
+            };
+            digits(x) && digits(y) && strip(x) == strip(y)
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::replay::RunOutcome;
+    use boruna_bytecode::{Capability, Value};
+
+    fn log(build: impl FnOnce(&mut EventLog)) -> EventLog {
+        let mut log = EventLog::new();
+        build(&mut log);
+        log
+    }
+
+    #[test]
+    fn test_identical_logs_have_no_divergence() {
+        let a = log(|l| l.log_cap_call(&Capability::TimeNow, &[]));
+        assert_eq!(analyze(&a, &a.clone()), None);
+    }
+
+    #[test]
+    fn test_capability_result_points_at_the_value() {
+        let a = log(|l| {
+            l.log_cap_call(&Capability::NetFetch, &[Value::String("u".into())]);
+            l.log_cap_result(&Capability::NetFetch, &Value::Int(200));
+        });
+        let b = log(|l| {
+            l.log_cap_call(&Capability::NetFetch, &[Value::String("u".into())]);
+            l.log_cap_result(&Capability::NetFetch, &Value::Int(500));
+        });
+        let d = analyze(&a, &b).unwrap();
+        assert_eq!(d.kind, DivergenceKind::CapabilityResult);
+        assert_eq!(d.event_index, Some(1));
+        assert_eq!(d.path.as_deref(), Some("/CapResult/result/Int"));
+        assert_eq!(d.expected["CapResult"]["result"]["Int"], 200);
+        assert_eq!(d.actual["CapResult"]["result"]["Int"], 500);
+    }
+
+    #[test]
+    fn test_call_order_and_nondeterministic_values() {
+        let fetch = |l: &mut EventLog, url: &str| {
+            l.log_cap_call(&Capability::NetFetch, &[Value::String(url.into())])
+        };
+        let a = log(|l| {
+            fetch(l, "a");
+            fetch(l, "b");
+        });
+        let b = log(|l| {
+            fetch(l, "b");
+            fetch(l, "a");
+        });
+        assert_eq!(analyze(&a, &b).unwrap().kind, DivergenceKind::CallOrder);
+
+        let a = log(|l| fetch(l, "report-2026-01-01T10:00:00"));
+        let b = log(|l| fetch(l, "report-2026-01-01T10:00:07"));
+        assert_eq!(
+            analyze(&a, &b).unwrap().kind,
+            DivergenceKind::NondeterministicValue
+        );
+        let b = log(|l| fetch(l, "report-latest"));
+        assert_eq!(analyze(&a, &b).unwrap().kind, DivergenceKind::CallArguments);
+    }
+
+    #[test]
+    fn test_event_count_and_step_count_drift() {
+        let a = log(|l| {
+            l.log_cap_call(&Capability::TimeNow, &[]);
+            l.log_cap_call(&Capability::TimeNow, &[]);
+        });
+        let b = log(|l| l.log_cap_call(&Capability::TimeNow, &[]));
+        let d = analyze(&a, &b).unwrap();
+        assert_eq!(d.kind, DivergenceKind::EventCount);
+        assert_eq!(d.event_index, Some(1));
+        assert!(d.actual.is_null());
+
+        let outcome = |steps| RunOutcome {
+            steps,
+            result: None,
+            error: None,
+            denials: vec![],
+            kv_snapshot: None,
+        };
+        let mut a = b.clone();
+        let mut c = b.clone();
+        a.set_outcome(outcome(10));
+        c.set_outcome(outcome(12));
+        let d = analyze(&a, &c).unwrap();
+        assert_eq!(d.kind, DivergenceKind::StepCountDrift);
+        assert_eq!((d.expected, d.actual), (10.into(), 12.into()));
+    }
+}
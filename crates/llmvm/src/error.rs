@@ -1,7 +1,7 @@
 use boruna_bytecode::Capability;
 use thiserror::Error;
 
-#[derive(Debug, Error)]
+#[derive(Debug, Clone, Error)]
 pub enum VmError {
     #[error("stack underflow")]
     StackUnderflow,
@@ -45,9 +45,44 @@ pub enum VmError {
     #[error("unknown capability id: {0}")]
     UnknownCapability(u32),
 
+    /// `cap.await` on a handle that was never spawned or was already
+    /// awaited.
+    #[error("invalid capability handle: {0}")]
+    InvalidCapHandle(u64),
+
+    /// `str_match` / `str_find` pattern is malformed or unsupported.
+    #[error("invalid pattern: {0}")]
+    InvalidPattern(String),
+
+    /// A table opcode named a missing column, an unknown comparison or
+    /// aggregate, or aggregated values it cannot combine.
+    #[error("table error: {0}")]
+    TableError(String),
+
+    /// Strict determinism: a `Float` operation produced NaN or ±Inf.
+    #[error("non-finite float result in {0} (strict determinism)")]
+    NonFiniteFloat(&'static str),
+
     #[error("assertion failed: {0}")]
     AssertionFailed(String),
 
+    /// An `assert(cond, msg)` in the program found `cond` false.
+    /// `function` and `ip` locate the failing instruction.
+    #[error("assertion failed in `{function}` at ip {ip}: {message}")]
+    UserAssertion {
+        message: String,
+        function: String,
+        ip: usize,
+    },
+
+    /// The program called `fail(msg)`. Located like [`VmError::UserAssertion`].
+    #[error("failed in `{function}` at ip {ip}: {message}")]
+    UserFailure {
+        message: String,
+        function: String,
+        ip: usize,
+    },
+
     /// A declared `requires`/`ensures` contract was violated at runtime.
     /// `counterexample` is the concrete argument list that triggered the
     /// violation (positional, rendered) — the replayable input an auditor
@@ -88,6 +123,101 @@ pub enum VmError {
     #[error("max scheduler rounds exceeded ({0})")]
     MaxRoundsExceeded(u64),
 
+    /// The policy requires signed modules and this one is unsigned,
+    /// mis-signed, or signed by a key outside `trusted_module_keys`.
+    #[error("untrusted module: {0}")]
+    UntrustedModule(String),
+
+    /// The module was compiled against capability contract versions that
+    /// differ from this binary's. Override with `--allow-capability-skew`.
+    #[error("capability version skew: {0}")]
+    CapabilitySkew(String),
+
+    /// The actor state store failed, or a stored checkpoint does not fit
+    /// the actor it would restore.
+    #[error("actor state: {0}")]
+    ActorState(String),
+
+    /// A run-level or per-step [`BudgetLedger`](boruna_budget::BudgetLedger)
+    /// refused a debit.
+    #[error("{0}")]
+    RunBudgetExhausted(boruna_budget::BudgetExhausted),
+
     #[error("bytecode error: {0}")]
     Bytecode(#[from] boruna_bytecode::BytecodeError),
 }
+
+impl boruna_errors::BorunaError for VmError {
+    fn error_code(&self) -> &'static boruna_errors::ErrorCode {
+        use boruna_errors as codes;
+        match self {
+            VmError::StackUnderflow
+            | VmError::InvalidIp(_)
+            | VmError::InvalidFunction(_)
+            | VmError::InvalidConstant(_)
+            | VmError::InvalidLocal(_)
+            | VmError::InvalidGlobal(_)
+            | VmError::Bytecode(_) => &codes::VM_INVALID_BYTECODE,
+            VmError::StackOverflow(_) => &codes::VM_STACK_OVERFLOW,
+            VmError::TypeError { .. } => &codes::VM_TYPE_ERROR,
+            VmError::DivisionByZero => &codes::VM_DIVISION_BY_ZERO,
+            VmError::ArithmeticOverflow(_) => &codes::VM_ARITHMETIC_OVERFLOW,
+            VmError::CapabilityDenied(_) => &codes::VM_CAPABILITY_DENIED,
+            VmError::CapabilityBudgetExceeded(_) => &codes::VM_CAPABILITY_BUDGET_EXCEEDED,
+            VmError::UnknownCapability(_) => &codes::VM_UNKNOWN_CAPABILITY,
+            VmError::InvalidCapHandle(_) => &codes::VM_INVALID_CAP_HANDLE,
+            VmError::InvalidPattern(_) => &codes::VM_INVALID_PATTERN,
+            VmError::TableError(_) => &codes::VM_TABLE_ERROR,
+            VmError::NonFiniteFloat(_) => &codes::VM_NON_FINITE_FLOAT,
+            VmError::AssertionFailed(_) => &codes::VM_ASSERTION_FAILED,
+            VmError::UserAssertion { .. } => &codes::VM_USER_ASSERTION,
+            VmError::UserFailure { .. } => &codes::VM_USER_FAILURE,
+            VmError::ContractViolation { .. } => &codes::VM_CONTRACT_VIOLATION,
+            VmError::IndexOutOfBounds { .. } => &codes::VM_INDEX_OUT_OF_BOUNDS,
+            VmError::MatchExhausted => &codes::VM_MATCH_EXHAUSTED,
+            VmError::ActorNotFound(_) => &codes::VM_ACTOR_NOT_FOUND,
+            VmError::MailboxEmpty => &codes::VM_MAILBOX_EMPTY,
+            VmError::ExecutionLimitExceeded(_) => &codes::VM_STEP_LIMIT_EXCEEDED,
+            VmError::WallTimeExceeded(_) => &codes::VM_WALL_TIME_EXCEEDED,
+            VmError::Halt => &codes::VM_HALT,
+            VmError::BudgetExhausted => &codes::VM_BUDGET_EXHAUSTED,
+            VmError::Deadlock => &codes::VM_DEADLOCK,
+            VmError::MaxRoundsExceeded(_) => &codes::VM_MAX_ROUNDS_EXCEEDED,
+            VmError::UntrustedModule(_) => &codes::VM_UNTRUSTED_MODULE,
+            VmError::CapabilitySkew(_) => &codes::VM_CAPABILITY_SKEW,
+            VmError::ActorState(_) => &codes::VM_ACTOR_STATE,
+            VmError::RunBudgetExhausted(_) => &codes::VM_RUN_BUDGET_EXHAUSTED,
+        }
+    }
+
+    fn error_data(&self) -> serde_json::Value {
+        use serde_json::json;
+        match self {
+            VmError::StackOverflow(max) => json!({ "max_depth": max }),
+            VmError::TypeError { expected, got } => json!({ "expected": expected, "got": got }),
+            VmError::CapabilityDenied(cap) | VmError::CapabilityBudgetExceeded(cap) => {
+                json!({ "capability": cap.to_string() })
+            }
+            VmError::ContractViolation { counterexample, .. } => {
+                json!({ "counterexample": counterexample })
+            }
+            VmError::UserAssertion {
+                message,
+                function,
+                ip,
+            }
+            | VmError::UserFailure {
+                message,
+                function,
+                ip,
+            } => json!({ "message": message, "function": function, "ip": ip }),
+            VmError::IndexOutOfBounds { index, length } => {
+                json!({ "index": index, "length": length })
+            }
+            VmError::ExecutionLimitExceeded(limit) => json!({ "max_steps": limit }),
+            VmError::WallTimeExceeded(ms) => json!({ "max_wall_ms": ms }),
+            VmError::RunBudgetExhausted(e) => boruna_errors::BorunaError::error_data(e),
+            _ => serde_json::Value::Null,
+        }
+    }
+}
@@ -11,12 +11,17 @@ use std::time::Duration;
 /// Maximum redirect hops followed manually (each re-validated for SSRF).
 const MAX_REDIRECTS: usize = 10;
 
+/// Maximum `net.fetch` requests of one batch in flight at once.
+const MAX_BATCH_CONCURRENCY: usize = 8;
+
 use ureq::{Agent, AgentBuilder};
 use url::Url;
 
 use boruna_bytecode::{Capability, Value};
 
-use crate::capability_gateway::{CapabilityHandler, MockHandler, NetPolicy};
+use crate::capability_gateway::{
+    domain_matches, method_matches, CapabilityHandler, MockHandler, NetPolicy,
+};
 
 /// HTTP handler that makes real network requests for `NetFetch`.
 /// All other capabilities are delegated to `MockHandler`.
@@ -135,6 +140,73 @@ impl CapabilityHandler for HttpHandler {
             other => self.fallback.handle(other, args),
         }
     }
+
+    fn handles(&self, cap: &Capability) -> bool {
+        *cap == Capability::NetFetch
+    }
+
+    fn delegate(&mut self) -> Option<&mut dyn CapabilityHandler> {
+        Some(&mut self.fallback)
+    }
+
+    /// Batched `net.fetch` requests run concurrently, at most
+    /// [`MAX_BATCH_CONCURRENCY`] at a time; results keep item order.
+    fn handle_batch(
+        &mut self,
+        cap: &Capability,
+        batch: &[Vec<Value>],
+    ) -> Vec<Result<Value, String>> {
+        if *cap != Capability::NetFetch {
+            return batch.iter().map(|args| self.handle(cap, args)).collect();
+        }
+        let requests: Vec<&[Value]> = batch.iter().map(Vec::as_slice).collect();
+        self.fetch_concurrently(&requests)
+    }
+
+    /// Spawned `net.fetch` calls run concurrently like a batch; other
+    /// capabilities go to the fallback handler.
+    fn handle_many(&mut self, calls: &[(Capability, Vec<Value>)]) -> Vec<Result<Value, String>> {
+        let fetches: Vec<&[Value]> = calls
+            .iter()
+            .filter(|(cap, _)| *cap == Capability::NetFetch)
+            .map(|(_, args)| args.as_slice())
+            .collect();
+        let mut fetched = self.fetch_concurrently(&fetches).into_iter();
+        calls
+            .iter()
+            .map(|(cap, args)| match cap {
+                Capability::NetFetch => fetched
+                    .next()
+                    .unwrap_or_else(|| Err("net.fetch result missing".into())),
+                other => self.fallback.handle(other, args),
+            })
+            .collect()
+    }
+}
+
+impl HttpHandler {
+    /// Run `net.fetch` requests on scoped threads, at most
+    /// [`MAX_BATCH_CONCURRENCY`] at a time; results keep request order.
+    fn fetch_concurrently(&self, requests: &[&[Value]]) -> Vec<Result<Value, String>> {
+        requests
+            .chunks(MAX_BATCH_CONCURRENCY)
+            .flat_map(|chunk| {
+                std::thread::scope(|scope| {
+                    let handles: Vec<_> = chunk
+                        .iter()
+                        .map(|args| scope.spawn(move || self.handle_net_fetch(args)))
+                        .collect();
+                    handles
+                        .into_iter()
+                        .map(|h| {
+                            h.join()
+                                .unwrap_or_else(|_| Err("net.fetch request panicked".into()))
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect()
+    }
 }
 
 /// Parsed `net.fetch` arguments. Shared between the real handler and the
@@ -201,7 +273,7 @@ pub fn parse_net_fetch_args(args: &[Value]) -> Option<ParsedNetFetchArgs> {
 /// literal private/reserved IP hosts (IPv4 and — via bracket-stripping — IPv6).
 /// A bare hostname passes here and is checked against DNS in
 /// [`resolve_host_safety`] on the live path.
-fn validate_url_safety(url: &Url) -> Result<(), String> {
+pub(crate) fn validate_url_safety(url: &Url) -> Result<(), String> {
     // Only allow http and https
     match url.scheme() {
         "http" | "https" => {}
@@ -251,7 +323,7 @@ fn validate_url_safety(url: &Url) -> Result<(), String> {
 /// rebinds to a private IP *between* this check and `ureq`'s own connect
 /// resolution (classic TOCTOU rebinding) is not fully closed — that requires
 /// pinning the validated IP for the connection, tracked as a follow-up.
-fn resolve_host_safety(url: &Url) -> Result<(), String> {
+pub(crate) fn resolve_host_safety(url: &Url) -> Result<(), String> {
     let host = url
         .host_str()
         .ok_or_else(|| "URL has no host".to_string())?;
@@ -310,21 +382,9 @@ fn is_private_ip(ip: &IpAddr) -> bool {
 /// Supports wildcard patterns like `*.example.com`.
 /// Empty allowlist means all domains are allowed.
 fn check_domain_allowed(url: &Url, allowed: &[String]) -> Result<(), String> {
-    if allowed.is_empty() {
-        return Ok(());
-    }
-
     let host = url.host_str().unwrap_or("");
-
-    for pattern in allowed {
-        if pattern.starts_with("*.") {
-            let suffix = &pattern[1..]; // e.g. ".example.com"
-            if host.ends_with(suffix) || host == &pattern[2..] {
-                return Ok(());
-            }
-        } else if host == pattern {
-            return Ok(());
-        }
+    if domain_matches(host, allowed) {
+        return Ok(());
     }
 
     Err(format!(
@@ -336,12 +396,7 @@ fn check_domain_allowed(url: &Url, allowed: &[String]) -> Result<(), String> {
 /// Check if the HTTP method is in the allowed list.
 /// Empty allowlist means all methods are allowed.
 fn check_method_allowed(method: &str, allowed: &[String]) -> Result<(), String> {
-    if allowed.is_empty() {
-        return Ok(());
-    }
-
-    let method_upper = method.to_uppercase();
-    if allowed.iter().any(|m| m.to_uppercase() == method_upper) {
+    if method_matches(method, allowed) {
         return Ok(());
     }
 
@@ -0,0 +1,132 @@
+//! JSON ↔ `Value` conversion behind `Op::JsonParse` / `Op::JsonStringify`.
+//!
+//! A parsed document is built from ordinary values, so the existing list
+//! and map builtins work on it: objects become `Map` (keys ordered by the
+//! `BTreeMap`), arrays `List`, `null` `None`, integers `Int` (or `BigInt`
+//! past `i64`), other numbers `Float`. Stringify is the inverse and always
+//! emits compact JSON with sorted keys, so the same value produces the same
+//! bytes on every run.
+
+use boruna_bytecode::{BigInt, Value};
+
+use crate::error::VmError;
+
+/// Parse `text` into a `Value`; the error is serde_json's message.
+pub fn parse(text: &str) -> Result<Value, String> {
+    let doc: serde_json::Value = serde_json::from_str(text).map_err(|e| e.to_string())?;
+    Ok(from_json(doc))
+}
+
+/// Convert a parsed JSON document; see the module docs for the mapping.
+pub fn from_json(doc: serde_json::Value) -> Value {
+    match doc {
+        serde_json::Value::Null => Value::None,
+        serde_json::Value::Bool(b) => Value::Bool(b),
+        serde_json::Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                Value::Int(i)
+            } else if let Some(big) = n.as_u64().and_then(|u| BigInt::parse(&u.to_string())) {
+                Value::BigInt(big)
+            } else {
+                Value::Float(n.as_f64().unwrap_or(f64::NAN))
+            }
+        }
+        serde_json::Value::String(s) => Value::String(s),
+        serde_json::Value::Array(items) => Value::List(items.into_iter().map(from_json).collect()),
+        serde_json::Value::Object(fields) => {
+            Value::Map(fields.into_iter().map(|(k, v)| (k, from_json(v))).collect())
+        }
+    }
+}
+
+/// Render `value` as compact JSON with sorted object keys; a table renders
+/// as `{"columns": [names], "rows": [[cells]]}`. Values with no
+/// JSON form (records, enums, actor and function references) and
+/// non-finite floats are errors.
+pub fn stringify(value: &Value) -> Result<String, VmError> {
+    let mut out = String::new();
+    write_value(value, &mut out)?;
+    Ok(out)
+}
+
+fn write_value(value: &Value, out: &mut String) -> Result<(), VmError> {
+    match value {
+        Value::Unit | Value::None => out.push_str("null"),
+        Value::Some(inner) => write_value(inner, out)?,
+        Value::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        Value::Int(n) => out.push_str(&n.to_string()),
+        Value::BigInt(n) => out.push_str(&n.to_string()),
+        Value::Decimal(d) => out.push_str(&d.to_string()),
+        Value::Float(f) => {
+            if !f.is_finite() {
+                return Err(VmError::TypeError {
+                    expected: "finite Float",
+                    got: "Float",
+                });
+            }
+            out.push_str(
+                &serde_json::Number::from_f64(*f).map_or_else(String::new, |n| n.to_string()),
+            );
+        }
+        Value::String(s) => write_string(s, out),
+        Value::List(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_value(item, out)?;
+            }
+            out.push(']');
+        }
+        Value::Map(fields) => {
+            out.push('{');
+            for (i, (k, v)) in fields.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_string(k, out);
+                out.push(':');
+                write_value(v, out)?;
+            }
+            out.push('}');
+        }
+        Value::Table(table) => {
+            out.push_str("{\"columns\":[");
+            for (i, name) in table.column_names().iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_string(name, out);
+            }
+            out.push_str("],\"rows\":[");
+            for row in 0..table.num_rows() {
+                if row > 0 {
+                    out.push(',');
+                }
+                out.push('[');
+                for (i, column) in table.columns().iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    write_value(&column.values[row], out)?;
+                }
+                out.push(']');
+            }
+            out.push_str("]}");
+        }
+        other => {
+            return Err(VmError::TypeError {
+                expected: "JSON-representable value",
+                got: other.type_name(),
+            })
+        }
+    }
+    Ok(())
+}
+
+fn write_string(s: &str, out: &mut String) {
+    // serde_json's escaping is canonical (shortest form, `\uXXXX` for
+    // control characters only).
+    out.push_str(&serde_json::to_string(s).unwrap_or_default());
+}
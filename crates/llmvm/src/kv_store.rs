@@ -0,0 +1,357 @@
+//! Embedded key-value store behind the `kv.get` / `kv.set` / `kv.scan`
+//! capabilities.
+//!
+//! Scripts that need state to outlive a run, without standing up a
+//! database, read and write string keys through the built-ins
+//! `kv_get(key)`, `kv_set(key, value)` and `kv_scan(prefix)`. The
+//! [`KvHandler`] serves those calls from a [`KvStore`] and delegates every
+//! other capability to a wrapped handler, the same way `StepInputHandler`
+//! composes with the mock and HTTP handlers.
+//!
+//! **On disk.** A store is a single append-only file with one JSON line
+//! per write, `{"key": "...", "value": <Value>}`. Opening replays the lines
+//! in order, so the last write to a key wins. A final line without a
+//! trailing newline is a write torn by a crash and is truncated away, so
+//! the next write starts on a fresh line; any other malformed line is an
+//! error. [`KvStore::compact`] rewrites the
+//! file with one line per live key.
+//!
+//! **Determinism.** Every call goes through the gateway, so the
+//! [`EventLog`](crate::replay::EventLog) records each operation with its
+//! arguments (including the value written) and its result. Replaying the
+//! log serves the recorded results without touching the store.
+//! [`KvStore::snapshot_hash`] identifies the store's contents after a run.
+//!
+//! **Namespaces.** A key's namespace is the text before its first `/`
+//! (`cart/42` is in `cart`). [`KvPolicy`] limits which namespaces a run
+//! may touch and which of them are read-only.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use boruna_bytecode::{Capability, Value};
+use boruna_hash::Hasher;
+use serde::{Deserialize, Serialize};
+
+use crate::capability_gateway::{CapabilityHandler, KvPolicy};
+
+/// One line of the store file.
+#[derive(Serialize, Deserialize)]
+struct KvRecord {
+    key: String,
+    value: Value,
+}
+
+/// Key-value store backed by an append-only file, or held in memory.
+#[derive(Debug, Default)]
+pub struct KvStore {
+    /// `None` for an in-memory store.
+    path: Option<PathBuf>,
+    entries: BTreeMap<String, Value>,
+}
+
+/// A store shared between a [`KvHandler`] and the host that reads its
+/// snapshot hash after the run.
+pub type SharedKvStore = Arc<Mutex<KvStore>>;
+
+impl KvStore {
+    /// An empty store that is never written to disk.
+    pub fn in_memory() -> Self {
+        KvStore::default()
+    }
+
+    /// Open the store at `path`, creating it on first write if missing.
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let mut entries = BTreeMap::new();
+        let data = match fs::read(path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(format!("failed to read kv store '{}': {e}", path.display())),
+        };
+        let complete = data
+            .iter()
+            .rposition(|&b| b == b'\n')
+            .map_or(0, |end| end + 1);
+        if complete < data.len() {
+            fs::OpenOptions::new()
+                .write(true)
+                .open(path)
+                .and_then(|file| file.set_len(complete as u64))
+                .map_err(|e| format!("failed to truncate kv store '{}': {e}", path.display()))?;
+        }
+        let complete = std::str::from_utf8(&data[..complete])
+            .map_err(|e| format!("kv store '{}': invalid UTF-8: {e}", path.display()))?;
+        for (i, line) in complete.lines().enumerate() {
+            if line.is_empty() {
+                continue;
+            }
+            let record: KvRecord = serde_json::from_str(line).map_err(|e| {
+                format!(
+                    "kv store '{}' line {}: invalid record: {e}",
+                    path.display(),
+                    i + 1
+                )
+            })?;
+            entries.insert(record.key, record.value);
+        }
+        Ok(KvStore {
+            path: Some(path.to_path_buf()),
+            entries,
+        })
+    }
+
+    /// A store shared with a [`KvHandler`].
+    pub fn shared(self) -> SharedKvStore {
+        Arc::new(Mutex::new(self))
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.entries.get(key)
+    }
+
+    /// Write `key`. File-backed stores append the record and sync it
+    /// before returning.
+    pub fn set(&mut self, key: &str, value: Value) -> Result<(), String> {
+        if let Some(path) = &self.path {
+            let record = KvRecord {
+                key: key.to_string(),
+                value,
+            };
+            let mut line = serde_json::to_string(&record)
+                .map_err(|e| format!("kv.set: serialize '{key}': {e}"))?;
+            line.push('\n');
+            let mut file = fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .map_err(|e| format!("kv.set: open '{}': {e}", path.display()))?;
+            file.write_all(line.as_bytes())
+                .and_then(|()| file.sync_data())
+                .map_err(|e| format!("kv.set: write '{}': {e}", path.display()))?;
+            self.entries.insert(record.key, record.value);
+        } else {
+            self.entries.insert(key.to_string(), value);
+        }
+        Ok(())
+    }
+
+    /// Entries whose key starts with `prefix`, in key order.
+    pub fn scan(&self, prefix: &str) -> BTreeMap<String, Value> {
+        self.entries
+            .range(prefix.to_string()..)
+            .take_while(|(k, _)| k.starts_with(prefix))
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// `sha256:<hex>` over the compact JSON of every entry in key order.
+    /// Equal contents give equal hashes, however many writes produced them.
+    pub fn snapshot_hash(&self) -> String {
+        // BTreeMap serializes with sorted keys → deterministic bytes.
+        let json = serde_json::to_string(&self.entries).unwrap_or_default();
+        let mut hasher = Hasher::sha256();
+        hasher.update(json.as_bytes());
+        hasher.finalize().to_string()
+    }
+
+    /// Rewrite the file with one record per live key, replacing it
+    /// atomically. No-op for an in-memory store.
+    pub fn compact(&mut self) -> Result<(), String> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let mut out = String::new();
+        for (key, value) in &self.entries {
+            let record = KvRecord {
+                key: key.clone(),
+                value: value.clone(),
+            };
+            let line = serde_json::to_string(&record)
+                .map_err(|e| format!("kv compact: serialize '{key}': {e}"))?;
+            out.push_str(&line);
+            out.push('\n');
+        }
+        let tmp = path.with_extension("compact.tmp");
+        fs::write(&tmp, out)
+            .and_then(|()| fs::rename(&tmp, path))
+            .map_err(|e| format!("kv compact '{}': {e}", path.display()))
+    }
+}
+
+/// Handler that serves the `kv.*` capabilities from a [`KvStore`],
+/// enforcing a [`KvPolicy`]. All other capabilities delegate to `inner`.
+pub struct KvHandler {
+    store: SharedKvStore,
+    policy: KvPolicy,
+    inner: Box<dyn CapabilityHandler>,
+}
+
+impl KvHandler {
+    pub fn new(store: SharedKvStore, policy: KvPolicy, inner: Box<dyn CapabilityHandler>) -> Self {
+        KvHandler {
+            store,
+            policy,
+            inner,
+        }
+    }
+}
+
+fn string_arg<'a>(cap: &Capability, args: &'a [Value], what: &str) -> Result<&'a str, String> {
+    match args.first() {
+        Some(Value::String(s)) => Ok(s),
+        Some(other) => Err(format!(
+            "{cap}: {what} must be a String, got {}",
+            other.type_name()
+        )),
+        None => Err(format!("{cap}: missing {what}")),
+    }
+}
+
+impl CapabilityHandler for KvHandler {
+    fn handle(&mut self, cap: &Capability, args: &[Value]) -> Result<Value, String> {
+        let mut store = match cap {
+            Capability::KvGet | Capability::KvSet | Capability::KvScan => self
+                .store
+                .lock()
+                .map_err(|_| format!("{cap}: store poisoned"))?,
+            _ => return self.inner.handle(cap, args),
+        };
+        match cap {
+            Capability::KvGet => {
+                let key = string_arg(cap, args, "key")?;
+                self.policy.check(cap, key)?;
+                Ok(match store.get(key) {
+                    Some(v) => Value::Some(Box::new(v.clone())),
+                    None => Value::None,
+                })
+            }
+            Capability::KvSet => {
+                let key = string_arg(cap, args, "key")?;
+                self.policy.check(cap, key)?;
+                let value = args
+                    .get(1)
+                    .cloned()
+                    .ok_or_else(|| format!("{cap}: missing value"))?;
+                store.set(key, value)?;
+                Ok(Value::Unit)
+            }
+            _ => {
+                let prefix = string_arg(cap, args, "prefix")?;
+                self.policy.check(cap, prefix)?;
+                Ok(Value::Map(store.scan(prefix)))
+            }
+        }
+    }
+
+    fn handles(&self, cap: &Capability) -> bool {
+        matches!(
+            cap,
+            Capability::KvGet | Capability::KvSet | Capability::KvScan
+        )
+    }
+
+    fn delegate(&mut self) -> Option<&mut dyn CapabilityHandler> {
+        Some(self.inner.as_mut())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capability_gateway::MockHandler;
+
+    fn s(v: &str) -> Value {
+        Value::String(v.into())
+    }
+
+    #[test]
+    fn reopen_replays_writes_and_drops_a_torn_tail() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("kv.log");
+        let mut store = KvStore::open(&path).unwrap();
+        store.set("cart/1", Value::Int(1)).unwrap();
+        store.set("cart/1", Value::Int(2)).unwrap();
+        store.set("user/a", s("ada")).unwrap();
+        let hash = store.snapshot_hash();
+
+        // A crash mid-append leaves a partial final line.
+        let mut file = fs::OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(br#"{"key":"cart/2","val"#).unwrap();
+
+        let mut reopened = KvStore::open(&path).unwrap();
+        assert_eq!(reopened.get("cart/1"), Some(&Value::Int(2)));
+        assert_eq!(reopened.len(), 2);
+        assert_eq!(reopened.snapshot_hash(), hash);
+
+        // The next write must not land on the torn line.
+        reopened.set("cart/2", Value::Int(5)).unwrap();
+        let mut reopened = KvStore::open(&path).unwrap();
+        assert_eq!(reopened.get("cart/2"), Some(&Value::Int(5)));
+        assert_eq!(reopened.len(), 3);
+        let hash = reopened.snapshot_hash();
+
+        reopened.compact().unwrap();
+        let compacted = fs::read_to_string(&path).unwrap();
+        assert_eq!(compacted.lines().count(), 3);
+        assert_eq!(KvStore::open(&path).unwrap().snapshot_hash(), hash);
+    }
+
+    #[test]
+    fn scan_returns_prefixed_entries_in_key_order() {
+        let mut store = KvStore::in_memory();
+        for key in ["cart/2", "cart/1", "cartel/x", "user/a"] {
+            store.set(key, s(key)).unwrap();
+        }
+        let keys: Vec<String> = store.scan("cart/").into_keys().collect();
+        assert_eq!(keys, vec!["cart/1", "cart/2"]);
+    }
+
+    #[test]
+    fn handler_enforces_namespaces() {
+        let store = KvStore::in_memory().shared();
+        let policy = KvPolicy {
+            namespaces: vec!["cart".into(), "config".into()],
+            read_only: vec!["config".into()],
+        };
+        let mut h = KvHandler::new(store.clone(), policy, Box::new(MockHandler));
+
+        h.handle(&Capability::KvSet, &[s("cart/1"), Value::Int(3)])
+            .unwrap();
+        assert_eq!(
+            h.handle(&Capability::KvGet, &[s("cart/1")]).unwrap(),
+            Value::Some(Box::new(Value::Int(3)))
+        );
+        assert_eq!(
+            h.handle(&Capability::KvGet, &[s("cart/2")]).unwrap(),
+            Value::None
+        );
+        h.handle(&Capability::KvGet, &[s("config/theme")]).unwrap();
+
+        let err = h
+            .handle(&Capability::KvSet, &[s("config/theme"), s("dark")])
+            .unwrap_err();
+        assert!(err.contains("read-only"), "got: {err}");
+        let err = h.handle(&Capability::KvGet, &[s("user/a")]).unwrap_err();
+        assert!(err.contains("namespace 'user'"), "got: {err}");
+        let err = h.handle(&Capability::KvScan, &[s("ca")]).unwrap_err();
+        assert!(err.contains("namespace"), "got: {err}");
+
+        // Other capabilities fall through to the inner handler.
+        assert_eq!(
+            h.handle(&Capability::TimeNow, &[]).unwrap(),
+            Value::Int(1700000000)
+        );
+        assert_eq!(store.lock().unwrap().len(), 1);
+    }
+}
@@ -1,30 +1,51 @@
 pub mod actor;
+pub mod actor_store;
 pub mod capability_gateway;
+pub mod delegation;
+pub mod divergence;
 pub mod error;
 #[cfg(feature = "http")]
 pub mod http_handler;
+pub mod json;
+pub mod kv_store;
 #[cfg(feature = "http")]
 pub mod net_record_replay;
+pub mod notify;
+pub mod os_sandbox;
+pub mod pattern;
+pub mod policy_explain;
 pub mod policy_validate;
+pub mod queue_store;
 pub mod replay;
+pub mod table_loader;
 #[cfg(feature = "telemetry")]
 pub mod telemetry;
 #[cfg(test)]
 mod tests;
+pub mod trace;
 pub mod vm;
 
 pub use actor::{ActorStatus, ActorSystem, Message};
-pub use capability_gateway::{CapabilityGateway, NetPolicy, Policy, PolicyRule};
+pub use actor_store::{ActorCheckpoint, ActorStateStore};
+pub use capability_gateway::{
+    CapabilityGateway, KvPolicy, NetPolicy, NotifyPolicy, PendingCall, Policy, PolicyRule,
+    QueuePolicy, TablePolicy,
+};
 pub use error::VmError;
+pub use kv_store::{KvHandler, KvStore, SharedKvStore};
 #[cfg(feature = "http")]
 pub use net_record_replay::{
     NetTape, NetTransaction, RecordingHttpHandler, ReplayingHttpHandler, TAPE_FORMAT_VERSION,
 };
+pub use notify::{DryRunNotifySender, LiveNotifySender, NotifyHandler, NotifySender};
+pub use os_sandbox::{SandboxError, SandboxProfile};
 pub use policy_validate::{
     parse as parse_policy_file, parse_file as parse_policy_path, PolicyParseError,
-    POLICY_SCHEMA_VERSION,
+    POLICY_JSON_SCHEMA, POLICY_SCHEMA_VERSION,
 };
+pub use queue_store::{QueueHandler, QueueStore, SharedQueueStore};
 pub use replay::{EventLog, ReplayEngine};
+pub use table_loader::TableHandler;
 #[cfg(feature = "telemetry")]
-pub use telemetry::{init as init_telemetry, TelemetryHandle};
-pub use vm::{SpawnRequest, StepResult, Vm};
+pub use telemetry::{init as init_telemetry, init_with as init_telemetry_with, TelemetryHandle};
+pub use vm::{SpawnRequest, StepResult, Vm, VmSnapshot, VmState};
@@ -0,0 +1,386 @@
+//! Handlers for the `notify.send` capability.
+//!
+//! Scripts alert people with `notify_send(destination, template, vars)`.
+//! The [`NotifyHandler`] renders the template, checks the destination and
+//! rate limits in a [`NotifyPolicy`], and hands the rendered message to a
+//! [`NotifySender`]. Every other capability delegates to a wrapped
+//! handler, like [`KvHandler`](crate::kv_store::KvHandler).
+//!
+//! **Destinations.** An `http://` or `https://` URL is a webhook: the
+//! message is POSTed as `{"text": "<message>"}`, which Slack, Mattermost
+//! and most chat webhooks accept. A `mailto:<address>` destination is
+//! sent by email through an SMTP relay. [`LiveNotifySender`] needs the
+//! `http` feature for webhooks and the `smtp` feature for email;
+//! [`DryRunNotifySender`] delivers nothing.
+//!
+//! **Templates.** `{{name}}` is replaced with `vars["name"]`. String
+//! values are inserted as-is and other values in their display form. A
+//! placeholder with no matching var is an error, so a typo never sends
+//! a half-filled message.
+//!
+//! **Determinism.** The call's arguments and its result (destination,
+//! rendered body and delivery status) are recorded in the
+//! [`EventLog`](crate::replay::EventLog). Replay serves the recorded
+//! result and never sends anything. Rate limits count calls in the run,
+//! not wall-clock time, so a replayed run hits the same limits.
+
+use std::collections::BTreeMap;
+
+use boruna_bytecode::{Capability, Value};
+
+use crate::capability_gateway::{CapabilityHandler, NotifyPolicy};
+
+/// Delivers a rendered notification. Returns a status code: the HTTP
+/// status for webhooks, `250` for accepted email, `0` when nothing was
+/// sent.
+pub trait NotifySender: Send {
+    fn send(&mut self, destination: &str, body: &str) -> Result<i64, String>;
+}
+
+/// Sender that delivers nothing and reports status `0`. Used when the
+/// run is not `--live`, so policy, templates and rate limits can be
+/// exercised without alerting anyone.
+#[derive(Debug, Default)]
+pub struct DryRunNotifySender;
+
+impl NotifySender for DryRunNotifySender {
+    fn send(&mut self, _destination: &str, _body: &str) -> Result<i64, String> {
+        Ok(0)
+    }
+}
+
+/// SMTP relay settings for `mailto:` destinations.
+#[derive(Debug, Clone, Default)]
+pub struct SmtpConfig {
+    /// Relay host, optionally `host:port` (default port 587, STARTTLS).
+    pub relay: String,
+    /// `From:` address.
+    pub from: String,
+    /// Relay credentials, if the relay requires them.
+    pub credentials: Option<(String, String)>,
+}
+
+/// Sender that POSTs webhooks and sends email for real.
+pub struct LiveNotifySender {
+    #[cfg(feature = "http")]
+    agent: ureq::Agent,
+    smtp: Option<SmtpConfig>,
+}
+
+impl LiveNotifySender {
+    /// `smtp` is required only for `mailto:` destinations.
+    pub fn new(smtp: Option<SmtpConfig>) -> Self {
+        LiveNotifySender {
+            #[cfg(feature = "http")]
+            agent: ureq::AgentBuilder::new()
+                .timeout(std::time::Duration::from_secs(30))
+                .redirects(0)
+                .build(),
+            smtp,
+        }
+    }
+
+    #[cfg(feature = "http")]
+    fn post_webhook(&self, destination: &str, body: &str) -> Result<i64, String> {
+        let url = url::Url::parse(destination)
+            .map_err(|e| format!("invalid webhook URL '{destination}': {e}"))?;
+        crate::http_handler::validate_url_safety(&url)?;
+        crate::http_handler::resolve_host_safety(&url)?;
+        let payload = serde_json::json!({ "text": body }).to_string();
+        match self
+            .agent
+            .post(url.as_str())
+            .set("Content-Type", "application/json")
+            .send_string(&payload)
+        {
+            Ok(response) => Ok(i64::from(response.status())),
+            Err(ureq::Error::Status(code, _)) => {
+                Err(format!("webhook '{destination}' returned HTTP {code}"))
+            }
+            Err(e) => Err(format!("webhook '{destination}' failed: {e}")),
+        }
+    }
+
+    #[cfg(not(feature = "http"))]
+    fn post_webhook(&self, _destination: &str, _body: &str) -> Result<i64, String> {
+        Err("webhook notifications require the `http` feature".into())
+    }
+
+    #[cfg(feature = "smtp")]
+    fn send_email(&self, address: &str, body: &str) -> Result<i64, String> {
+        use lettre::transport::smtp::authentication::Credentials;
+        use lettre::{Message, SmtpTransport, Transport};
+
+        let smtp = self
+            .smtp
+            .as_ref()
+            .ok_or("email notifications need an SMTP relay (BORUNA_SMTP_RELAY)")?;
+        let from = smtp
+            .from
+            .parse()
+            .map_err(|e| format!("invalid SMTP from address '{}': {e}", smtp.from))?;
+        let to = address
+            .parse()
+            .map_err(|e| format!("invalid email address '{address}': {e}"))?;
+        let subject = body.lines().next().unwrap_or_default();
+        let message = Message::builder()
+            .from(from)
+            .to(to)
+            .subject(subject)
+            .body(body.to_string())
+            .map_err(|e| format!("failed to build email: {e}"))?;
+        let (host, port) = match smtp.relay.rsplit_once(':') {
+            Some((host, port)) => (
+                host,
+                port.parse()
+                    .map_err(|_| format!("invalid SMTP relay port in '{}'", smtp.relay))?,
+            ),
+            None => (smtp.relay.as_str(), 587),
+        };
+        let mut transport = SmtpTransport::starttls_relay(host)
+            .map_err(|e| format!("SMTP relay '{host}': {e}"))?
+            .port(port);
+        if let Some((user, password)) = &smtp.credentials {
+            transport = transport.credentials(Credentials::new(user.clone(), password.clone()));
+        }
+        let response = transport
+            .build()
+            .send(&message)
+            .map_err(|e| format!("email to '{address}' failed: {e}"))?;
+        Ok(i64::from(u16::from(response.code())))
+    }
+
+    #[cfg(not(feature = "smtp"))]
+    fn send_email(&self, _address: &str, _body: &str) -> Result<i64, String> {
+        let _ = &self.smtp;
+        Err("email notifications require the `smtp` feature".into())
+    }
+}
+
+impl NotifySender for LiveNotifySender {
+    fn send(&mut self, destination: &str, body: &str) -> Result<i64, String> {
+        match destination.strip_prefix("mailto:") {
+            Some(address) => self.send_email(address, body),
+            None => self.post_webhook(destination, body),
+        }
+    }
+}
+
+/// Render `template`, replacing each `{{name}}` with `vars["name"]`.
+pub fn render_template(template: &str, vars: &BTreeMap<String, Value>) -> Result<String, String> {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after.find("}}").ok_or_else(|| {
+            format!(
+                "unclosed '{{{{' in template at byte {}",
+                template.len() - rest.len() + start
+            )
+        })?;
+        let name = after[..end].trim();
+        match vars.get(name) {
+            Some(Value::String(s)) => out.push_str(s),
+            Some(other) => out.push_str(&other.to_string()),
+            None => return Err(format!("template placeholder '{{{{{name}}}}}' has no var")),
+        }
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// Handler that serves `notify.send`, enforcing a [`NotifyPolicy`]. All
+/// other capabilities delegate to `inner`.
+pub struct NotifyHandler {
+    policy: NotifyPolicy,
+    sender: Box<dyn NotifySender>,
+    inner: Box<dyn CapabilityHandler>,
+    /// Notifications sent so far, by destination.
+    sent: BTreeMap<String, u32>,
+}
+
+impl NotifyHandler {
+    pub fn new(
+        policy: NotifyPolicy,
+        sender: Box<dyn NotifySender>,
+        inner: Box<dyn CapabilityHandler>,
+    ) -> Self {
+        NotifyHandler {
+            policy,
+            sender,
+            inner,
+            sent: BTreeMap::new(),
+        }
+    }
+
+    fn notify(&mut self, args: &[Value]) -> Result<Value, String> {
+        let cap = Capability::NotifySend;
+        let destination = match args.first() {
+            Some(Value::String(s)) => s.clone(),
+            Some(other) => {
+                return Err(format!(
+                    "{cap}: destination must be a String, got {}",
+                    other.type_name()
+                ))
+            }
+            None => return Err(format!("{cap}: missing destination")),
+        };
+        let template = match args.get(1) {
+            Some(Value::String(s)) => s.as_str(),
+            Some(other) => {
+                return Err(format!(
+                    "{cap}: template must be a String, got {}",
+                    other.type_name()
+                ))
+            }
+            None => return Err(format!("{cap}: missing template")),
+        };
+        let empty = BTreeMap::new();
+        let vars = match args.get(2) {
+            Some(Value::Map(m)) => m,
+            None | Some(Value::Unit) => &empty,
+            Some(other) => {
+                return Err(format!(
+                    "{cap}: vars must be a Map, got {}",
+                    other.type_name()
+                ))
+            }
+        };
+
+        let total: u32 = self.sent.values().sum();
+        let to_destination = self.sent.get(&destination).copied().unwrap_or(0);
+        self.policy.check(&destination, total, to_destination)?;
+        let body = render_template(template, vars).map_err(|e| format!("{cap}: {e}"))?;
+        let status = self
+            .sender
+            .send(&destination, &body)
+            .map_err(|e| format!("{cap}: {e}"))?;
+        *self.sent.entry(destination.clone()).or_insert(0) += 1;
+
+        let mut receipt = BTreeMap::new();
+        receipt.insert("destination".to_string(), Value::String(destination));
+        receipt.insert("body".to_string(), Value::String(body));
+        receipt.insert("status".to_string(), Value::Int(status));
+        Ok(Value::Map(receipt))
+    }
+}
+
+impl CapabilityHandler for NotifyHandler {
+    fn handle(&mut self, cap: &Capability, args: &[Value]) -> Result<Value, String> {
+        match cap {
+            Capability::NotifySend => self.notify(args),
+            other => self.inner.handle(other, args),
+        }
+    }
+
+    fn handles(&self, cap: &Capability) -> bool {
+        matches!(cap, Capability::NotifySend)
+    }
+
+    fn delegate(&mut self) -> Option<&mut dyn CapabilityHandler> {
+        Some(self.inner.as_mut())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capability_gateway::MockHandler;
+    use std::sync::{Arc, Mutex};
+
+    fn s(v: &str) -> Value {
+        Value::String(v.into())
+    }
+
+    /// Sender that keeps what it was asked to deliver.
+    struct Outbox(Arc<Mutex<Vec<(String, String)>>>);
+
+    impl NotifySender for Outbox {
+        fn send(&mut self, destination: &str, body: &str) -> Result<i64, String> {
+            self.0
+                .lock()
+                .unwrap()
+                .push((destination.to_string(), body.to_string()));
+            Ok(200)
+        }
+    }
+
+    #[test]
+    fn render_template_fills_vars_and_rejects_unknown_ones() {
+        let mut vars = BTreeMap::new();
+        vars.insert("job".to_string(), s("nightly"));
+        vars.insert("failed".to_string(), Value::Int(3));
+        assert_eq!(
+            render_template("{{job}}: {{ failed }} failed", &vars).unwrap(),
+            "nightly: 3 failed"
+        );
+        let err = render_template("{{jbo}} failed", &vars).unwrap_err();
+        assert!(err.contains("'{{jbo}}'"), "got: {err}");
+        let err = render_template("{{job", &vars).unwrap_err();
+        assert!(err.contains("unclosed"), "got: {err}");
+    }
+
+    #[test]
+    fn handler_enforces_destinations_and_rate_limits() {
+        let outbox = Arc::new(Mutex::new(Vec::new()));
+        let policy = NotifyPolicy {
+            destinations: vec![
+                "https://hooks.example.com/*".into(),
+                "mailto:oncall@example.com".into(),
+            ],
+            max_per_run: 3,
+            max_per_destination: 2,
+        };
+        let mut h = NotifyHandler::new(
+            policy,
+            Box::new(Outbox(outbox.clone())),
+            Box::new(MockHandler),
+        );
+        let hook = s("https://hooks.example.com/ops");
+        let mut vars = BTreeMap::new();
+        vars.insert("n".to_string(), Value::Int(1));
+
+        let receipt = h
+            .handle(
+                &Capability::NotifySend,
+                &[hook.clone(), s("run {{n}} failed"), Value::Map(vars)],
+            )
+            .unwrap();
+        let Value::Map(receipt) = receipt else {
+            panic!("expected a Map receipt")
+        };
+        assert_eq!(receipt["body"], s("run 1 failed"));
+        assert_eq!(receipt["status"], Value::Int(200));
+
+        h.handle(&Capability::NotifySend, &[hook.clone(), s("again")])
+            .unwrap();
+        let err = h
+            .handle(&Capability::NotifySend, &[hook, s("third")])
+            .unwrap_err();
+        assert!(err.contains("max_per_destination"), "got: {err}");
+
+        let err = h
+            .handle(
+                &Capability::NotifySend,
+                &[s("https://evil.example.net/x"), s("hi")],
+            )
+            .unwrap_err();
+        assert!(err.contains("not allowed"), "got: {err}");
+
+        h.handle(
+            &Capability::NotifySend,
+            &[s("mailto:oncall@example.com"), s("paging")],
+        )
+        .unwrap();
+        let err = h
+            .handle(
+                &Capability::NotifySend,
+                &[s("mailto:oncall@example.com"), s("paging")],
+            )
+            .unwrap_err();
+        assert!(err.contains("max_per_run"), "got: {err}");
+        assert_eq!(outbox.lock().unwrap().len(), 3);
+    }
+}
@@ -0,0 +1,545 @@
+//! OS-level sandbox for live runs (`boruna run --live --sandbox`).
+//!
+//! The [`CapabilityGateway`](crate::CapabilityGateway) decides which
+//! capability calls a script may make. A [`SandboxProfile`] derived from
+//! the same [`Policy`] backs that decision with the kernel, so a bug in a
+//! handler cannot reach what the policy denies:
+//!
+//! - **Filesystem** (Landlock): files are readable only when `fs.read` or
+//!   `data.read_table` is allowed, and writable only when `fs.write` is.
+//!   The paths the run itself writes (event log, KV store, queues, tapes)
+//!   are added with [`SandboxProfile::allow_path`]. Executing files is
+//!   never allowed.
+//! - **Network** (seccomp): unless a capability with a live network
+//!   handler is allowed ([`NETWORK_CAPABILITIES`]), creating an IPv4 or
+//!   IPv6 socket, or an io_uring instance, fails with `EACCES`.
+//!
+//! A `dry_run` policy answers every call from the mock handler, so its
+//! profile allows neither. The seccomp filter is synchronized onto every
+//! thread of the process, including ones already running (an HTTP
+//! client's runtime, a logger); Landlock covers the calling thread and the
+//! threads it spawns afterwards. Neither can be lifted. Only Linux is
+//! supported; elsewhere [`SandboxProfile::apply`] returns
+//! [`SandboxError::Unsupported`].
+
+use std::path::{Path, PathBuf};
+
+use boruna_bytecode::Capability;
+
+use crate::capability_gateway::{Policy, PolicyMode};
+
+/// Capabilities whose live handlers open network connections.
+pub const NETWORK_CAPABILITIES: [Capability; 3] = [
+    Capability::NetFetch,
+    Capability::LlmCall,
+    Capability::NotifySend,
+];
+
+/// Capabilities whose handlers read files the script names.
+pub const READ_CAPABILITIES: [Capability; 2] = [Capability::FsRead, Capability::DataReadTable];
+
+/// Capabilities whose handlers write files the script names.
+pub const WRITE_CAPABILITIES: [Capability; 1] = [Capability::FsWrite];
+
+/// Read-only system directories a run with network access needs for name
+/// resolution and TLS (resolver config, NSS modules, CA bundles).
+pub const NETWORK_SYSTEM_PATHS: [&str; 4] = ["/etc", "/usr", "/lib", "/lib64"];
+
+/// A path the sandbox leaves open regardless of the policy. A directory
+/// covers everything beneath it; a file covers its directory, so it can be
+/// created and replaced.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SandboxPath {
+    pub path: PathBuf,
+    pub write: bool,
+}
+
+/// What a sandboxed run may touch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SandboxProfile {
+    /// Any file may be read.
+    pub read_anywhere: bool,
+    /// Any file may be written.
+    pub write_anywhere: bool,
+    /// IPv4/IPv6 sockets may be created.
+    pub network: bool,
+    pub paths: Vec<SandboxPath>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SandboxError {
+    #[error("OS sandboxing is only supported on Linux")]
+    Unsupported,
+    #[error("Landlock is not available on this kernel ({0})")]
+    LandlockUnavailable(std::io::Error),
+    #[error("cannot sandbox {path}: {source}")]
+    Path {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("{step} failed: {source}")]
+    Syscall {
+        step: &'static str,
+        source: std::io::Error,
+    },
+    #[error("cannot apply the seccomp filter to thread {0}, which has a conflicting filter")]
+    ThreadSync(i64),
+}
+
+impl SandboxProfile {
+    /// The profile `policy` implies. A capability counts as allowed when
+    /// its rule allows it or may prompt for it.
+    pub fn from_policy(policy: &Policy) -> Self {
+        let live = policy.mode == PolicyMode::Enforce;
+        let allowed = |caps: &[Capability]| {
+            live && caps.iter().any(|cap| match policy.rules.get(cap.name()) {
+                Some(rule) => rule.allow || rule.prompt,
+                None => policy.default_allow,
+            })
+        };
+        let mut profile = SandboxProfile {
+            read_anywhere: allowed(&READ_CAPABILITIES),
+            write_anywhere: allowed(&WRITE_CAPABILITIES),
+            network: allowed(&NETWORK_CAPABILITIES),
+            paths: Vec::new(),
+        };
+        if profile.network {
+            for dir in NETWORK_SYSTEM_PATHS {
+                if Path::new(dir).is_dir() {
+                    profile = profile.allow_path(dir, false);
+                }
+            }
+        }
+        profile
+    }
+
+    /// Leave `path` readable, and writable when `write`.
+    pub fn allow_path(mut self, path: impl Into<PathBuf>, write: bool) -> Self {
+        self.paths.push(SandboxPath {
+            path: path.into(),
+            write,
+        });
+        self
+    }
+
+    /// One line for the run's log, e.g.
+    /// `fs: read none, write none (+2 paths); network: denied`.
+    pub fn summary(&self) -> String {
+        let scope = |anywhere: bool| if anywhere { "any" } else { "none" };
+        let mut fs = format!(
+            "fs: read {}, write {}",
+            scope(self.read_anywhere),
+            scope(self.write_anywhere)
+        );
+        if !self.paths.is_empty() {
+            fs.push_str(&format!(" (+{} paths)", self.paths.len()));
+        }
+        let network = if self.network { "allowed" } else { "denied" };
+        format!("{fs}; network: {network}")
+    }
+
+    /// Restrict the process to this profile: the network layer applies to
+    /// every thread, the filesystem layer to the calling thread and the
+    /// threads it spawns afterwards. Fails rather than run unsandboxed
+    /// when a layer the profile needs is unavailable.
+    pub fn apply(&self) -> Result<(), SandboxError> {
+        #[cfg(target_os = "linux")]
+        {
+            linux::apply(self)
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            Err(SandboxError::Unsupported)
+        }
+    }
+
+    /// The directories to open in Landlock: each directory path, or a
+    /// file's parent. A directory the run has yet to create is covered by
+    /// its nearest existing ancestor.
+    fn rule_dirs(&self) -> Vec<(PathBuf, bool)> {
+        self.paths
+            .iter()
+            .map(|p| {
+                let mut dir = p.path.as_path();
+                if !dir.is_dir() {
+                    dir = dir.parent().unwrap_or(Path::new(""));
+                }
+                while !dir.as_os_str().is_empty() && !dir.is_dir() {
+                    dir = dir.parent().unwrap_or(Path::new(""));
+                }
+                if dir.as_os_str().is_empty() {
+                    dir = Path::new(".");
+                }
+                (dir.to_path_buf(), p.write)
+            })
+            .collect()
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::ffi::CString;
+    use std::io;
+    use std::os::unix::ffi::OsStrExt;
+
+    use super::{SandboxError, SandboxProfile};
+
+    // linux/landlock.h
+    const LANDLOCK_CREATE_RULESET_VERSION: u32 = 1 << 0;
+    const LANDLOCK_RULE_PATH_BENEATH: libc::c_int = 1;
+    const ACCESS_FS_WRITE_FILE: u64 = 1 << 1;
+    const ACCESS_FS_READ_FILE: u64 = 1 << 2;
+    const ACCESS_FS_READ_DIR: u64 = 1 << 3;
+    const ACCESS_FS_REMOVE_DIR: u64 = 1 << 4;
+    const ACCESS_FS_REMOVE_FILE: u64 = 1 << 5;
+    const ACCESS_FS_MAKE_DIR: u64 = 1 << 7;
+    const ACCESS_FS_MAKE_REG: u64 = 1 << 8;
+    /// Every right of ABI 1, `EXECUTE` (bit 0) through `MAKE_SYM` (bit 12).
+    const ACCESS_FS_ABI_1: u64 = (1 << 13) - 1;
+    const ACCESS_FS_REFER: u64 = 1 << 13;
+    const ACCESS_FS_TRUNCATE: u64 = 1 << 14;
+
+    const READ: u64 = ACCESS_FS_READ_FILE | ACCESS_FS_READ_DIR;
+    const WRITE: u64 = ACCESS_FS_WRITE_FILE
+        | ACCESS_FS_REMOVE_DIR
+        | ACCESS_FS_REMOVE_FILE
+        | ACCESS_FS_MAKE_DIR
+        | ACCESS_FS_MAKE_REG
+        | ACCESS_FS_REFER
+        | ACCESS_FS_TRUNCATE;
+
+    #[repr(C)]
+    struct RulesetAttr {
+        handled_access_fs: u64,
+    }
+
+    #[repr(C, packed)]
+    struct PathBeneathAttr {
+        allowed_access: u64,
+        parent_fd: i32,
+    }
+
+    // linux/audit.h
+    #[cfg(target_arch = "x86_64")]
+    const AUDIT_ARCH: u32 = 0xC000_003E;
+    #[cfg(target_arch = "aarch64")]
+    const AUDIT_ARCH: u32 = 0xC000_00B7;
+    /// x32 syscalls on x86_64 carry this bit; none are allowed through.
+    const X32_SYSCALL_BIT: u32 = 0x4000_0000;
+
+    pub(super) fn apply(profile: &SandboxProfile) -> Result<(), SandboxError> {
+        // Required to install either layer without CAP_SYS_ADMIN, and
+        // keeps setuid binaries from regaining privileges.
+        // SAFETY: PR_SET_NO_NEW_PRIVS takes integer arguments only.
+        if unsafe { libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) } != 0 {
+            return Err(syscall_error("PR_SET_NO_NEW_PRIVS"));
+        }
+        if !(profile.read_anywhere && profile.write_anywhere) {
+            landlock(profile)?;
+        }
+        if !profile.network {
+            seccomp()?;
+        }
+        Ok(())
+    }
+
+    fn syscall_error(step: &'static str) -> SandboxError {
+        SandboxError::Syscall {
+            step,
+            source: io::Error::last_os_error(),
+        }
+    }
+
+    fn landlock(profile: &SandboxProfile) -> Result<(), SandboxError> {
+        // SAFETY: a null attribute with size 0 and the VERSION flag only
+        // queries the ABI version.
+        let abi = unsafe {
+            libc::syscall(
+                libc::SYS_landlock_create_ruleset,
+                std::ptr::null::<RulesetAttr>(),
+                0usize,
+                LANDLOCK_CREATE_RULESET_VERSION,
+            )
+        };
+        if abi < 1 {
+            return Err(SandboxError::LandlockUnavailable(io::Error::last_os_error()));
+        }
+        let mut handled = ACCESS_FS_ABI_1;
+        if abi >= 2 {
+            handled |= ACCESS_FS_REFER;
+        }
+        if abi >= 3 {
+            handled |= ACCESS_FS_TRUNCATE;
+        }
+
+        let attr = RulesetAttr {
+            handled_access_fs: handled,
+        };
+        // SAFETY: `attr` is a valid ruleset attribute of the given size.
+        let ruleset = unsafe {
+            libc::syscall(
+                libc::SYS_landlock_create_ruleset,
+                &attr as *const RulesetAttr,
+                std::mem::size_of::<RulesetAttr>(),
+                0u32,
+            )
+        };
+        if ruleset < 0 {
+            return Err(syscall_error("landlock_create_ruleset"));
+        }
+        let ruleset = ruleset as libc::c_int;
+        let result = fill_and_restrict(ruleset, profile, handled);
+        // SAFETY: `ruleset` is the descriptor created above; the
+        // restriction outlives it.
+        unsafe { libc::close(ruleset) };
+        result
+    }
+
+    fn fill_and_restrict(
+        ruleset: libc::c_int,
+        profile: &SandboxProfile,
+        handled: u64,
+    ) -> Result<(), SandboxError> {
+        let mut anywhere = 0;
+        if profile.read_anywhere {
+            anywhere |= READ;
+        }
+        if profile.write_anywhere {
+            anywhere |= WRITE;
+        }
+        if anywhere != 0 {
+            add_rule(ruleset, std::path::Path::new("/"), anywhere & handled)?;
+        }
+        for (dir, write) in profile.rule_dirs() {
+            let access = if write { READ | WRITE } else { READ };
+            add_rule(ruleset, &dir, access & handled)?;
+        }
+        // SAFETY: `ruleset` is a valid Landlock ruleset descriptor.
+        if unsafe { libc::syscall(libc::SYS_landlock_restrict_self, ruleset, 0u32) } != 0 {
+            return Err(syscall_error("landlock_restrict_self"));
+        }
+        Ok(())
+    }
+
+    fn add_rule(
+        ruleset: libc::c_int,
+        dir: &std::path::Path,
+        access: u64,
+    ) -> Result<(), SandboxError> {
+        let path_error = |source| SandboxError::Path {
+            path: dir.to_path_buf(),
+            source,
+        };
+        let c_path = CString::new(dir.as_os_str().as_bytes())
+            .map_err(|e| path_error(io::Error::new(io::ErrorKind::InvalidInput, e)))?;
+        // SAFETY: `c_path` is a NUL-terminated path.
+        let fd = unsafe { libc::open(c_path.as_ptr(), libc::O_PATH | libc::O_CLOEXEC) };
+        if fd < 0 {
+            return Err(path_error(io::Error::last_os_error()));
+        }
+        let attr = PathBeneathAttr {
+            allowed_access: access,
+            parent_fd: fd,
+        };
+        // SAFETY: `attr` is a valid path-beneath attribute for `fd`.
+        let rc = unsafe {
+            libc::syscall(
+                libc::SYS_landlock_add_rule,
+                ruleset,
+                LANDLOCK_RULE_PATH_BENEATH,
+                &attr as *const PathBeneathAttr,
+                0u32,
+            )
+        };
+        let error = io::Error::last_os_error();
+        // SAFETY: `fd` was opened above and is not used afterwards.
+        unsafe { libc::close(fd) };
+        if rc != 0 {
+            return Err(path_error(error));
+        }
+        Ok(())
+    }
+
+    #[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+    fn seccomp() -> Result<(), SandboxError> {
+        use libc::{
+            sock_filter, BPF_ABS, BPF_JEQ, BPF_JGE, BPF_JMP, BPF_K, BPF_LD, BPF_RET, BPF_W,
+        };
+
+        fn stmt(code: u32, k: u32) -> sock_filter {
+            sock_filter {
+                code: code as u16,
+                jt: 0,
+                jf: 0,
+                k,
+            }
+        }
+        fn jump(op: u32, k: u32, jt: u8, jf: u8) -> sock_filter {
+            sock_filter {
+                code: (BPF_JMP | op | BPF_K) as u16,
+                jt,
+                jf,
+                k,
+            }
+        }
+        // Offsets into `struct seccomp_data`; the low half of args[0] on a
+        // little-endian target.
+        const NR: u32 = 0;
+        const ARCH: u32 = 4;
+        const ARG0: u32 = 16;
+        let load = |offset| stmt(BPF_LD | BPF_W | BPF_ABS, offset);
+        let deny = libc::SECCOMP_RET_ERRNO | libc::EACCES as u32;
+
+        let mut filter = [
+            load(ARCH),
+            jump(BPF_JEQ, AUDIT_ARCH, 1, 0),
+            stmt(BPF_RET | BPF_K, libc::SECCOMP_RET_KILL_PROCESS),
+            load(NR),
+            jump(BPF_JGE, X32_SYSCALL_BIT, 6, 0),
+            jump(BPF_JEQ, libc::SYS_io_uring_setup as u32, 5, 0),
+            jump(BPF_JEQ, libc::SYS_socket as u32, 0, 3),
+            load(ARG0),
+            jump(BPF_JEQ, libc::AF_INET as u32, 2, 0),
+            jump(BPF_JEQ, libc::AF_INET6 as u32, 1, 0),
+            stmt(BPF_RET | BPF_K, libc::SECCOMP_RET_ALLOW),
+            stmt(BPF_RET | BPF_K, deny),
+        ];
+        let program = libc::sock_fprog {
+            len: filter.len() as libc::c_ushort,
+            filter: filter.as_mut_ptr(),
+        };
+        // TSYNC installs the filter on every thread of the process, not
+        // just this one, or fails naming a thread it could not reach.
+        // SAFETY: `program` points at `filter`, which outlives the call;
+        // the kernel copies it.
+        let rc = unsafe {
+            libc::syscall(
+                libc::SYS_seccomp,
+                libc::SECCOMP_SET_MODE_FILTER,
+                libc::SECCOMP_FILTER_FLAG_TSYNC,
+                &program as *const libc::sock_fprog,
+            )
+        };
+        match rc {
+            0 => Ok(()),
+            rc if rc > 0 => Err(SandboxError::ThreadSync(rc)),
+            _ => Err(syscall_error("seccomp(SECCOMP_SET_MODE_FILTER)")),
+        }
+    }
+
+    #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+    fn seccomp() -> Result<(), SandboxError> {
+        Err(SandboxError::Unsupported)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profile_follows_the_policy() {
+        let profile = SandboxProfile::from_policy(&Policy::deny_all());
+        assert!(!profile.read_anywhere && !profile.write_anywhere && !profile.network);
+        assert!(profile.paths.is_empty());
+        assert_eq!(
+            profile.summary(),
+            "fs: read none, write none; network: denied"
+        );
+
+        let mut policy = Policy::deny_all();
+        policy.allow(&Capability::DataReadTable, 0);
+        policy.allow(&Capability::NetFetch, 0);
+        let profile = SandboxProfile::from_policy(&policy);
+        assert!(profile.read_anywhere && !profile.write_anywhere && profile.network);
+        assert!(profile.paths.iter().all(|p| !p.write));
+
+        let all = SandboxProfile::from_policy(&Policy::allow_all());
+        assert!(all.read_anywhere && all.write_anywhere && all.network);
+    }
+
+    #[test]
+    fn prompt_rules_and_dry_runs() {
+        let mut policy = Policy::deny_all();
+        policy.rules.insert(
+            "fs.write".into(),
+            crate::PolicyRule {
+                allow: false,
+                budget: 0,
+                prompt: true,
+            },
+        );
+        assert!(SandboxProfile::from_policy(&policy).write_anywhere);
+
+        let mut dry = Policy::allow_all();
+        dry.mode = PolicyMode::DryRun;
+        let profile = SandboxProfile::from_policy(&dry);
+        assert!(!profile.read_anywhere && !profile.write_anywhere && !profile.network);
+    }
+
+    /// Set in the child process [`kernel_enforces_a_deny_all_profile`]
+    /// runs the sandbox in, to the work directory it may use.
+    #[cfg(target_os = "linux")]
+    const SANDBOX_CHILD_ENV: &str = "BORUNA_TEST_SANDBOX_CHILD";
+
+    /// The seccomp filter reaches every thread, so the profile is applied
+    /// in a child copy of this test binary, never in the test process.
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn kernel_enforces_a_deny_all_profile() {
+        if let Some(work) = std::env::var_os(SANDBOX_CHILD_ENV) {
+            return enforce_deny_all(Path::new(&work));
+        }
+        let work = tempfile::tempdir().unwrap();
+        std::fs::create_dir(work.path().join("outside")).unwrap();
+        std::fs::create_dir(work.path().join("inside")).unwrap();
+        std::fs::write(work.path().join("outside/secret.txt"), "x").unwrap();
+        let out = std::process::Command::new(std::env::current_exe().unwrap())
+            .args([
+                "--exact",
+                "os_sandbox::tests::kernel_enforces_a_deny_all_profile",
+                "--nocapture",
+                "--test-threads=1",
+            ])
+            .env(SANDBOX_CHILD_ENV, work.path())
+            .output()
+            .unwrap();
+        let stdout = String::from_utf8_lossy(&out.stdout);
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        assert!(out.status.success(), "{stdout}{stderr}");
+        assert!(stdout.contains("1 passed"), "{stdout}");
+    }
+
+    #[cfg(target_os = "linux")]
+    fn enforce_deny_all(work: &Path) {
+        let secret = work.join("outside/secret.txt");
+        let log = work.join("inside/runs/events.json");
+        let denied = std::io::ErrorKind::PermissionDenied;
+
+        // A thread already running when the sandbox goes up, like an
+        // HTTP client's runtime.
+        let (go, wait) = std::sync::mpsc::channel::<()>();
+        let earlier = std::thread::spawn(move || {
+            wait.recv().unwrap();
+            std::net::UdpSocket::bind("127.0.0.1:0").map(drop)
+        });
+
+        let profile = SandboxProfile::from_policy(&Policy::deny_all()).allow_path(&log, true);
+        match profile.apply() {
+            Err(SandboxError::LandlockUnavailable(e)) => {
+                eprintln!("skipping: {e}");
+                return;
+            }
+            other => other.unwrap(),
+        }
+        assert_eq!(std::fs::read(&secret).unwrap_err().kind(), denied);
+        std::fs::create_dir(log.parent().unwrap()).unwrap();
+        std::fs::write(&log, "{}").unwrap();
+        assert_eq!(std::fs::read_to_string(&log).unwrap(), "{}");
+        let bind = std::net::UdpSocket::bind("127.0.0.1:0");
+        assert_eq!(bind.unwrap_err().kind(), denied);
+
+        go.send(()).unwrap();
+        assert_eq!(earlier.join().unwrap().unwrap_err().kind(), denied);
+    }
+}
@@ -0,0 +1,490 @@
+//! Bounded regular-expression matching behind `Op::StrMatch` / `Op::StrFind`.
+//!
+//! Patterns are an RE2-style subset compiled to a Pike VM: there is no
+//! backtracking, so a match costs at most `program length × text length`
+//! steps, and every step is counted against the budget the caller passes
+//! in. The VM charges those steps to its own step counter, so `max_steps`
+//! bounds pattern work exactly like bytecode work and the count is the same
+//! on every run.
+//!
+//! Supported: literals, `.` (any char but `\n`), classes (`[a-z_]`,
+//! `[^0-9]`), `\d \w \s` and their negations, `\n \t`, escaped
+//! punctuation, `^` / `$` (start/end of text), groups (`(...)`, `(?:...)`),
+//! `|`, and the greedy quantifiers `* + ? {n} {n,} {n,m}`. Backreferences,
+//! lookaround, lazy quantifiers, and flags are rejected. Matching is
+//! unanchored; `find` returns the leftmost-longest match.
+
+use std::fmt;
+
+/// Longest accepted pattern, in bytes.
+pub const MAX_PATTERN_LEN: usize = 1024;
+
+/// Largest compiled program, in instructions (after `{n,m}` expansion).
+pub const MAX_PROGRAM_LEN: usize = 4096;
+
+/// Upper bound for `n` and `m` in `{n,m}`.
+const MAX_REPEAT: u32 = 100;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatternError {
+    /// The pattern is malformed or uses an unsupported feature.
+    Syntax(String),
+    /// Matching needed more steps than the budget allowed.
+    StepLimit,
+}
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PatternError::Syntax(msg) => write!(f, "{msg}"),
+            PatternError::StepLimit => write!(f, "pattern step limit exceeded"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Empty,
+    Char(char),
+    Any,
+    Class(Vec<(char, char)>, bool),
+    Start,
+    End,
+    Concat(Vec<Node>),
+    Alt(Vec<Node>),
+    Repeat(Box<Node>, u32, Option<u32>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Inst {
+    Char(char),
+    Any,
+    Class(Vec<(char, char)>, bool),
+    Start,
+    End,
+    Split(usize, usize),
+    Jmp(usize),
+    Match,
+}
+
+/// A compiled pattern.
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    prog: Vec<Inst>,
+}
+
+impl Pattern {
+    pub fn compile(src: &str) -> Result<Pattern, PatternError> {
+        if src.len() > MAX_PATTERN_LEN {
+            return Err(PatternError::Syntax(format!(
+                "pattern longer than {MAX_PATTERN_LEN} bytes"
+            )));
+        }
+        let mut parser = Parser {
+            chars: src.chars().collect(),
+            pos: 0,
+        };
+        let node = parser.alternation()?;
+        if parser.pos < parser.chars.len() {
+            return Err(parser.error("unmatched ')'"));
+        }
+        let mut prog = Vec::new();
+        emit(&node, &mut prog)?;
+        prog.push(Inst::Match);
+        Ok(Pattern { prog })
+    }
+
+    /// Number of compiled instructions; callers charge this as compile cost.
+    pub fn len(&self) -> usize {
+        self.prog.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.prog.is_empty()
+    }
+
+    /// Whether the pattern matches anywhere in `text`, and the steps used.
+    pub fn is_match(&self, text: &str, budget: u64) -> Result<(bool, u64), PatternError> {
+        self.run(text, budget, true)
+            .map(|(span, steps)| (span.is_some(), steps))
+    }
+
+    /// Byte span of the leftmost-longest match, and the steps used.
+    pub fn find(
+        &self,
+        text: &str,
+        budget: u64,
+    ) -> Result<(Option<(usize, usize)>, u64), PatternError> {
+        self.run(text, budget, false)
+    }
+
+    fn run(
+        &self,
+        text: &str,
+        budget: u64,
+        first_only: bool,
+    ) -> Result<(Option<(usize, usize)>, u64), PatternError> {
+        let mut steps = 0u64;
+        let mut best: Option<(usize, usize)> = None;
+        // Threads are (pc, start byte); list order is priority, so for a
+        // given pc the earliest start always claims it first.
+        let mut clist: Vec<(usize, usize)> = Vec::new();
+        let mut nlist: Vec<(usize, usize)> = Vec::new();
+        let mut seen = vec![usize::MAX; self.prog.len()];
+        let mut stack = Vec::new();
+
+        let positions = text
+            .char_indices()
+            .map(|(i, c)| (i, Some(c)))
+            .chain(std::iter::once((text.len(), None)));
+        for (gen, (at, ch)) in positions.enumerate() {
+            let mut current = std::mem::take(&mut clist);
+            if best.is_none() {
+                current.push((0, at));
+            }
+            // Epsilon closure at `at`, in priority order.
+            let mut ready = Vec::with_capacity(current.len());
+            for (pc, start) in current {
+                stack.push(pc);
+                while let Some(pc) = stack.pop() {
+                    if seen[pc] == gen {
+                        continue;
+                    }
+                    seen[pc] = gen;
+                    steps += 1;
+                    if steps > budget {
+                        return Err(PatternError::StepLimit);
+                    }
+                    match &self.prog[pc] {
+                        Inst::Jmp(to) => stack.push(*to),
+                        Inst::Split(a, b) => {
+                            stack.push(*b);
+                            stack.push(*a);
+                        }
+                        Inst::Start if at == 0 => stack.push(pc + 1),
+                        Inst::End if at == text.len() => stack.push(pc + 1),
+                        Inst::Start | Inst::End => {}
+                        Inst::Match => {
+                            let better = match best {
+                                None => true,
+                                Some((s, e)) => start < s || (start == s && at > e),
+                            };
+                            if better {
+                                best = Some((start, at));
+                            }
+                            if first_only {
+                                return Ok((best, steps));
+                            }
+                        }
+                        _ => ready.push((pc, start)),
+                    }
+                }
+            }
+            let Some(c) = ch else { break };
+            for (pc, start) in ready {
+                if best.is_some_and(|(s, _)| start > s) {
+                    continue;
+                }
+                let hit = match &self.prog[pc] {
+                    Inst::Char(x) => *x == c,
+                    Inst::Any => c != '\n',
+                    Inst::Class(ranges, negated) => class_contains(ranges, c) != *negated,
+                    _ => false,
+                };
+                if hit {
+                    nlist.push((pc + 1, start));
+                }
+            }
+            std::mem::swap(&mut clist, &mut nlist);
+            nlist.clear();
+            if clist.is_empty() && best.is_some() {
+                break;
+            }
+        }
+        Ok((best, steps))
+    }
+}
+
+fn class_contains(ranges: &[(char, char)], c: char) -> bool {
+    ranges.iter().any(|&(lo, hi)| lo <= c && c <= hi)
+}
+
+fn emit(node: &Node, prog: &mut Vec<Inst>) -> Result<(), PatternError> {
+    if prog.len() > MAX_PROGRAM_LEN {
+        return Err(PatternError::Syntax(format!(
+            "pattern compiles to more than {MAX_PROGRAM_LEN} instructions"
+        )));
+    }
+    match node {
+        Node::Empty => {}
+        Node::Char(c) => prog.push(Inst::Char(*c)),
+        Node::Any => prog.push(Inst::Any),
+        Node::Class(ranges, negated) => prog.push(Inst::Class(ranges.clone(), *negated)),
+        Node::Start => prog.push(Inst::Start),
+        Node::End => prog.push(Inst::End),
+        Node::Concat(items) => {
+            for item in items {
+                emit(item, prog)?;
+            }
+        }
+        Node::Alt(branches) => {
+            // split L1, next; L1: a; jmp end; next: split L2, ... ; last
+            let mut jumps = Vec::new();
+            for (i, branch) in branches.iter().enumerate() {
+                if i + 1 < branches.len() {
+                    let split = prog.len();
+                    prog.push(Inst::Split(split + 1, 0));
+                    emit(branch, prog)?;
+                    jumps.push(prog.len());
+                    prog.push(Inst::Jmp(0));
+                    let next = prog.len();
+                    prog[split] = Inst::Split(split + 1, next);
+                } else {
+                    emit(branch, prog)?;
+                }
+            }
+            let end = prog.len();
+            for j in jumps {
+                prog[j] = Inst::Jmp(end);
+            }
+        }
+        Node::Repeat(body, min, max) => {
+            for _ in 0..*min {
+                emit(body, prog)?;
+            }
+            match max {
+                None => {
+                    let split = prog.len();
+                    prog.push(Inst::Split(split + 1, 0));
+                    emit(body, prog)?;
+                    prog.push(Inst::Jmp(split));
+                    let end = prog.len();
+                    prog[split] = Inst::Split(split + 1, end);
+                }
+                Some(max) => {
+                    let mut splits = Vec::new();
+                    for _ in *min..*max {
+                        splits.push(prog.len());
+                        prog.push(Inst::Split(prog.len() + 1, 0));
+                        emit(body, prog)?;
+                    }
+                    let end = prog.len();
+                    for s in splits {
+                        prog[s] = Inst::Split(s + 1, end);
+                    }
+                }
+            }
+        }
+    }
+    if prog.len() > MAX_PROGRAM_LEN {
+        return Err(PatternError::Syntax(format!(
+            "pattern compiles to more than {MAX_PROGRAM_LEN} instructions"
+        )));
+    }
+    Ok(())
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn error(&self, msg: &str) -> PatternError {
+        PatternError::Syntax(format!("{msg} at offset {}", self.pos))
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek();
+        self.pos += 1;
+        c
+    }
+
+    fn alternation(&mut self) -> Result<Node, PatternError> {
+        let mut branches = vec![self.concat()?];
+        while self.peek() == Some('|') {
+            self.pos += 1;
+            branches.push(self.concat()?);
+        }
+        Ok(if branches.len() == 1 {
+            branches.pop().unwrap_or(Node::Empty)
+        } else {
+            Node::Alt(branches)
+        })
+    }
+
+    fn concat(&mut self) -> Result<Node, PatternError> {
+        let mut items = Vec::new();
+        while let Some(c) = self.peek() {
+            if c == '|' || c == ')' {
+                break;
+            }
+            let atom = self.atom()?;
+            items.push(self.quantified(atom)?);
+        }
+        Ok(match items.len() {
+            0 => Node::Empty,
+            1 => items.pop().unwrap_or(Node::Empty),
+            _ => Node::Concat(items),
+        })
+    }
+
+    fn quantified(&mut self, atom: Node) -> Result<Node, PatternError> {
+        let (min, max) = match self.peek() {
+            Some('*') => (0, None),
+            Some('+') => (1, None),
+            Some('?') => (0, Some(1)),
+            Some('{') => return self.braces(atom),
+            _ => return Ok(atom),
+        };
+        self.pos += 1;
+        self.reject_stacked_quantifier()?;
+        Ok(Node::Repeat(Box::new(atom), min, max))
+    }
+
+    fn braces(&mut self, atom: Node) -> Result<Node, PatternError> {
+        self.pos += 1;
+        let min = self.number()?;
+        let max = if self.peek() == Some(',') {
+            self.pos += 1;
+            if self.peek() == Some('}') {
+                None
+            } else {
+                Some(self.number()?)
+            }
+        } else {
+            Some(min)
+        };
+        if self.next() != Some('}') {
+            return Err(self.error("expected '}'"));
+        }
+        if max.is_some_and(|m| m < min) {
+            return Err(self.error("repeat range is inverted"));
+        }
+        self.reject_stacked_quantifier()?;
+        Ok(Node::Repeat(Box::new(atom), min, max))
+    }
+
+    fn reject_stacked_quantifier(&self) -> Result<(), PatternError> {
+        match self.peek() {
+            Some('*' | '+' | '?' | '{') => {
+                Err(self.error("lazy or stacked quantifiers are not supported"))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn number(&mut self) -> Result<u32, PatternError> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        let digits: String = self.chars[start..self.pos].iter().collect();
+        match digits.parse::<u32>() {
+            Ok(n) if n <= MAX_REPEAT => Ok(n),
+            Ok(_) => Err(self.error(&format!("repeat count above {MAX_REPEAT}"))),
+            Err(_) => Err(self.error("expected a repeat count")),
+        }
+    }
+
+    fn atom(&mut self) -> Result<Node, PatternError> {
+        match self.next() {
+            Some('(') => {
+                if self.peek() == Some('?') {
+                    self.pos += 1;
+                    if self.next() != Some(':') {
+                        return Err(self.error("only (?:...) groups are supported"));
+                    }
+                }
+                let inner = self.alternation()?;
+                if self.next() != Some(')') {
+                    return Err(self.error("missing ')'"));
+                }
+                Ok(inner)
+            }
+            Some('[') => self.class(),
+            Some('.') => Ok(Node::Any),
+            Some('^') => Ok(Node::Start),
+            Some('$') => Ok(Node::End),
+            Some('\\') => self.escape(),
+            Some('*' | '+' | '?' | '{') => {
+                self.pos -= 1;
+                Err(self.error("quantifier without a preceding atom"))
+            }
+            Some(c) => Ok(Node::Char(c)),
+            None => Err(self.error("unexpected end of pattern")),
+        }
+    }
+
+    /// After a backslash.
+    fn escape(&mut self) -> Result<Node, PatternError> {
+        let c = self.next().ok_or_else(|| self.error("trailing '\\'"))?;
+        Ok(match c {
+            'd' => Node::Class(DIGIT.to_vec(), false),
+            'D' => Node::Class(DIGIT.to_vec(), true),
+            'w' => Node::Class(WORD.to_vec(), false),
+            'W' => Node::Class(WORD.to_vec(), true),
+            's' => Node::Class(SPACE.to_vec(), false),
+            'S' => Node::Class(SPACE.to_vec(), true),
+            'n' => Node::Char('\n'),
+            't' => Node::Char('\t'),
+            'r' => Node::Char('\r'),
+            c if c.is_ascii_punctuation() => Node::Char(c),
+            _ => return Err(self.error(&format!("unsupported escape '\\{c}'"))),
+        })
+    }
+
+    fn class(&mut self) -> Result<Node, PatternError> {
+        let negated = self.peek() == Some('^');
+        if negated {
+            self.pos += 1;
+        }
+        let mut ranges = Vec::new();
+        let mut first = true;
+        loop {
+            let c = self.next().ok_or_else(|| self.error("missing ']'"))?;
+            if c == ']' && !first {
+                break;
+            }
+            first = false;
+            let lo = if c == '\\' {
+                match self.escape()? {
+                    Node::Char(c) => c,
+                    Node::Class(rs, false) => {
+                        ranges.extend(rs);
+                        continue;
+                    }
+                    _ => return Err(self.error("negated shorthand inside a class")),
+                }
+            } else {
+                c
+            };
+            if self.peek() == Some('-') && self.chars.get(self.pos + 1).is_some_and(|&n| n != ']') {
+                self.pos += 1;
+                let hi = match self.next() {
+                    Some('\\') => match self.escape()? {
+                        Node::Char(c) => c,
+                        _ => return Err(self.error("invalid class range")),
+                    },
+                    Some(c) => c,
+                    None => return Err(self.error("missing ']'")),
+                };
+                if hi < lo {
+                    return Err(self.error("class range is inverted"));
+                }
+                ranges.push((lo, hi));
+            } else {
+                ranges.push((lo, lo));
+            }
+        }
+        Ok(Node::Class(ranges, negated))
+    }
+}
+
+const DIGIT: &[(char, char)] = &[('0', '9')];
+const WORD: &[(char, char)] = &[('0', '9'), ('A', 'Z'), ('_', '_'), ('a', 'z')];
+const SPACE: &[(char, char)] = &[('\t', '\n'), ('\u{b}', '\r'), (' ', ' ')];
@@ -0,0 +1,430 @@
+//! Explain how a [`Policy`] decides one capability call.
+//!
+//! [`explain`] evaluates a call the way an enforcing run would — the
+//! capability's rule (or `default_allow`), its budget, then the scoped
+//! limits its handler applies (`kv_policy`, `queue_policy`,
+//! `notify_policy` destinations, `net_policy` domains and methods) — and
+//! reports the first rule that decides it, with the smallest policy edits
+//! that flip the outcome. Edits are RFC 6902 JSON Patch operations against
+//! the policy's JSON form, so a caller can apply one and re-check.
+//!
+//! Nothing runs: handler checks are evaluated from the arguments alone,
+//! and rate limits that depend on earlier calls (`max_per_run`,
+//! `max_per_destination`) are not evaluated. A `prompt` rule reports
+//! [`PolicyDecision::Prompt`], since the answer is the operator's.
+
+use boruna_bytecode::{Capability, Value};
+use serde::Serialize;
+
+use crate::capability_gateway::{
+    domain_matches, method_matches, Policy, PolicyDecision, PolicyMode, PolicyRule,
+};
+
+/// The policy field that decided a call.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DecidingRule {
+    /// JSON Pointer into the policy, e.g. `/rules/net.fetch/allow` or
+    /// `/default_allow`.
+    pub path: String,
+    pub reason: String,
+}
+
+/// One edit that would change the decision.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PolicyEdit {
+    /// `add`, `replace` or `remove`, as in RFC 6902.
+    pub op: &'static str,
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<serde_json::Value>,
+    /// What the call's decision becomes with this edit applied.
+    pub effect: PolicyDecision,
+    pub description: String,
+}
+
+/// How a policy decides one capability call.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CallExplanation {
+    pub capability: String,
+    pub decision: PolicyDecision,
+    /// False in [`PolicyMode::DryRun`], where the decision is only
+    /// recorded and the call is answered by a mock.
+    pub enforced: bool,
+    pub rule: DecidingRule,
+    /// Alternative edits, each enough on its own.
+    pub suggestions: Vec<PolicyEdit>,
+}
+
+fn rule_path(cap: &str) -> String {
+    format!("/rules/{}", cap.replace('~', "~0").replace('/', "~1"))
+}
+
+fn string_arg(args: &[Value], idx: usize) -> Option<&str> {
+    match args.get(idx) {
+        Some(Value::String(s)) => Some(s),
+        _ => None,
+    }
+}
+
+/// Host part of a URL, without userinfo or port. Good enough for
+/// allowlist matching; the real handler parses the URL fully.
+fn url_host(url: &str) -> &str {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or("");
+    let host = authority.rsplit_once('@').map_or(authority, |(_, h)| h);
+    match host.strip_prefix('[') {
+        Some(v6) => v6.split(']').next().unwrap_or(""),
+        None => host.split(':').next().unwrap_or(""),
+    }
+}
+
+/// Explain how `policy` decides a call to `cap` with `args`, after
+/// `prior_calls` earlier calls to the same capability in the run.
+pub fn explain(
+    policy: &Policy,
+    cap: &Capability,
+    args: &[Value],
+    prior_calls: u64,
+) -> CallExplanation {
+    let name = cap.name();
+    let (decision, rule, suggestions) = decide(policy, cap, args, prior_calls);
+    CallExplanation {
+        capability: name.to_string(),
+        decision,
+        enforced: policy.mode == PolicyMode::Enforce,
+        rule,
+        suggestions,
+    }
+}
+
+fn decide(
+    policy: &Policy,
+    cap: &Capability,
+    args: &[Value],
+    prior_calls: u64,
+) -> (PolicyDecision, DecidingRule, Vec<PolicyEdit>) {
+    let name = cap.name();
+    let path = rule_path(name);
+    let deny_rule = PolicyEdit {
+        op: if policy.rules.contains_key(name) {
+            "replace"
+        } else {
+            "add"
+        },
+        path: path.clone(),
+        value: Some(serde_json::json!({ "allow": false, "budget": 0 })),
+        effect: PolicyDecision::Deny,
+        description: format!("deny {name} with an explicit rule"),
+    };
+
+    let rule = match policy.rules.get(name) {
+        Some(PolicyRule { prompt: true, .. }) => {
+            return (
+                PolicyDecision::Prompt,
+                DecidingRule {
+                    path: format!("{path}/prompt"),
+                    reason: format!(
+                        "{name} asks the operator on first use; a non-interactive run \
+                         answers with the rule's `allow`"
+                    ),
+                },
+                vec![PolicyEdit {
+                    op: "replace",
+                    path: format!("{path}/prompt"),
+                    value: Some(serde_json::json!(false)),
+                    effect: if policy.rules[name].allow {
+                        PolicyDecision::Allow
+                    } else {
+                        PolicyDecision::Deny
+                    },
+                    description: format!("decide {name} by the rule's `allow` without asking"),
+                }],
+            );
+        }
+        Some(rule) if !rule.allow => {
+            return (
+                PolicyDecision::Deny,
+                DecidingRule {
+                    path: format!("{path}/allow"),
+                    reason: format!("the rule for {name} sets allow: false"),
+                },
+                vec![PolicyEdit {
+                    op: "replace",
+                    path: format!("{path}/allow"),
+                    value: Some(serde_json::json!(true)),
+                    effect: PolicyDecision::Allow,
+                    description: format!("allow {name}"),
+                }],
+            );
+        }
+        None if !policy.default_allow => {
+            return (
+                PolicyDecision::Deny,
+                DecidingRule {
+                    path: "/default_allow".into(),
+                    reason: format!("no rule for {name} and default_allow is false"),
+                },
+                vec![PolicyEdit {
+                    op: "add",
+                    path,
+                    value: Some(serde_json::json!({ "allow": true, "budget": 0 })),
+                    effect: PolicyDecision::Allow,
+                    description: format!("add a rule allowing {name}"),
+                }],
+            );
+        }
+        rule => rule,
+    };
+
+    if let Some(rule) = rule.filter(|r| r.budget > 0 && prior_calls >= r.budget) {
+        return (
+            PolicyDecision::BudgetExceeded,
+            DecidingRule {
+                path: format!("{path}/budget"),
+                reason: format!(
+                    "call {} of {name} exceeds its budget of {}",
+                    prior_calls + 1,
+                    rule.budget
+                ),
+            },
+            vec![PolicyEdit {
+                op: "replace",
+                path: format!("{path}/budget"),
+                value: Some(serde_json::json!(prior_calls + 1)),
+                effect: PolicyDecision::Allow,
+                description: format!("raise the {name} budget to {}", prior_calls + 1),
+            }],
+        );
+    }
+
+    if let Some(denied) = scoped_check(policy, cap, args) {
+        return (PolicyDecision::Deny, denied.0, denied.1);
+    }
+
+    let rule = match rule {
+        Some(rule) if rule.budget > 0 => DecidingRule {
+            path: format!("{path}/budget"),
+            reason: format!(
+                "the rule for {name} allows it; call {} is within its budget of {}",
+                prior_calls + 1,
+                rule.budget
+            ),
+        },
+        Some(_) => DecidingRule {
+            path: format!("{path}/allow"),
+            reason: format!("the rule for {name} sets allow: true"),
+        },
+        None => DecidingRule {
+            path: "/default_allow".into(),
+            reason: format!("no rule for {name} and default_allow is true"),
+        },
+    };
+    (PolicyDecision::Allow, rule, vec![deny_rule])
+}
+
+/// Limits the capability's handler applies to its arguments. Returns the
+/// deciding rule and the edits that lift it when the call is refused.
+fn scoped_check(
+    policy: &Policy,
+    cap: &Capability,
+    args: &[Value],
+) -> Option<(DecidingRule, Vec<PolicyEdit>)> {
+    let append = |path: &str, value: &str, description: String| PolicyEdit {
+        op: "add",
+        path: format!("{path}/-"),
+        value: Some(serde_json::json!(value)),
+        effect: PolicyDecision::Allow,
+        description,
+    };
+    let refused = |path: &str, reason: String, edits: Vec<PolicyEdit>| {
+        Some((
+            DecidingRule {
+                path: path.to_string(),
+                reason,
+            },
+            edits,
+        ))
+    };
+
+    match cap {
+        Capability::KvGet | Capability::KvSet | Capability::KvScan => {
+            let kv = policy.kv_policy.as_ref()?;
+            let key = string_arg(args, 0)?;
+            let namespace = key.split_once('/').map(|(ns, _)| ns);
+            if !kv.namespaces.is_empty() {
+                match namespace {
+                    None => {
+                        return refused(
+                            "/kv_policy/namespaces",
+                            format!("'{key}' names no namespace and kv_policy limits namespaces"),
+                            Vec::new(),
+                        )
+                    }
+                    Some(ns) if !kv.namespaces.iter().any(|n| n == ns) => {
+                        return refused(
+                            "/kv_policy/namespaces",
+                            format!("namespace '{ns}' is not in kv_policy.namespaces"),
+                            vec![append(
+                                "/kv_policy/namespaces",
+                                ns,
+                                format!("allow the '{ns}' namespace"),
+                            )],
+                        )
+                    }
+                    Some(_) => {}
+                }
+            }
+            let ns = namespace.filter(|_| *cap == Capability::KvSet)?;
+            let idx = kv.read_only.iter().position(|n| n == ns)?;
+            refused(
+                "/kv_policy/read_only",
+                format!("namespace '{ns}' is read-only"),
+                vec![PolicyEdit {
+                    op: "remove",
+                    path: format!("/kv_policy/read_only/{idx}"),
+                    value: None,
+                    effect: PolicyDecision::Allow,
+                    description: format!("make the '{ns}' namespace writable"),
+                }],
+            )
+        }
+        Capability::QueuePush | Capability::QueuePoll => {
+            let queues = policy.queue_policy.as_ref()?;
+            let queue = string_arg(args, 0)?;
+            let (field, allowed) = if *cap == Capability::QueuePush {
+                ("push", &queues.push)
+            } else {
+                ("poll", &queues.poll)
+            };
+            if allowed.is_empty() || allowed.iter().any(|q| q == queue) {
+                return None;
+            }
+            let path = format!("/queue_policy/{field}");
+            refused(
+                &path,
+                format!("queue '{queue}' is not in queue_policy.{field}"),
+                vec![append(&path, queue, format!("allow {field} on '{queue}'"))],
+            )
+        }
+        Capability::NotifySend => {
+            let notify = policy.notify_policy.as_ref()?;
+            let destination = string_arg(args, 0)?;
+            if notify.check(destination, 0, 0).is_ok() {
+                return None;
+            }
+            refused(
+                "/notify_policy/destinations",
+                format!("destination '{destination}' is not in notify_policy.destinations"),
+                vec![append(
+                    "/notify_policy/destinations",
+                    destination,
+                    format!("allow notifications to '{destination}'"),
+                )],
+            )
+        }
+        Capability::NetFetch => {
+            let net = policy.net_policy.as_ref()?;
+            let url = string_arg(args, 0)?;
+            let host = url_host(url);
+            if !domain_matches(host, &net.allowed_domains) {
+                return refused(
+                    "/net_policy/allowed_domains",
+                    format!("domain '{host}' is not in net_policy.allowed_domains"),
+                    vec![append(
+                        "/net_policy/allowed_domains",
+                        host,
+                        format!("allow requests to '{host}'"),
+                    )],
+                );
+            }
+            let method = string_arg(args, 1).unwrap_or("GET").to_uppercase();
+            if method_matches(&method, &net.allowed_methods) {
+                return None;
+            }
+            refused(
+                "/net_policy/allowed_methods",
+                format!("method '{method}' is not in net_policy.allowed_methods"),
+                vec![append(
+                    "/net_policy/allowed_methods",
+                    &method,
+                    format!("allow {method} requests"),
+                )],
+            )
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capability_gateway::{KvPolicy, NetPolicy};
+
+    fn s(v: &str) -> Value {
+        Value::String(v.into())
+    }
+
+    #[test]
+    fn default_deny_suggests_adding_a_rule() {
+        let e = explain(&Policy::deny_all(), &Capability::DbQuery, &[], 0);
+        assert_eq!(e.decision, PolicyDecision::Deny);
+        assert_eq!(e.rule.path, "/default_allow");
+        assert_eq!(e.suggestions.len(), 1);
+        assert_eq!(e.suggestions[0].op, "add");
+        assert_eq!(e.suggestions[0].path, "/rules/db.query");
+        assert_eq!(e.suggestions[0].effect, PolicyDecision::Allow);
+    }
+
+    #[test]
+    fn explicit_deny_and_budget_name_their_field() {
+        let mut policy = Policy::allow_all();
+        policy.deny(&Capability::FsWrite);
+        let e = explain(&policy, &Capability::FsWrite, &[], 0);
+        assert_eq!(e.rule.path, "/rules/fs.write/allow");
+        assert_eq!(e.suggestions[0].value, Some(serde_json::json!(true)));
+
+        policy.allow(&Capability::LlmCall, 2);
+        let e = explain(&policy, &Capability::LlmCall, &[], 1);
+        assert_eq!(e.decision, PolicyDecision::Allow);
+        assert_eq!(e.rule.path, "/rules/llm.call/budget");
+        let e = explain(&policy, &Capability::LlmCall, &[], 2);
+        assert_eq!(e.decision, PolicyDecision::BudgetExceeded);
+        assert_eq!(e.suggestions[0].value, Some(serde_json::json!(3)));
+    }
+
+    #[test]
+    fn scoped_limits_are_checked_against_the_arguments() {
+        let mut policy = Policy::allow_all();
+        policy.net_policy = Some(NetPolicy {
+            allowed_domains: vec!["*.example.com".into()],
+            allowed_methods: vec!["GET".into()],
+            ..NetPolicy::default()
+        });
+        policy.kv_policy = Some(KvPolicy {
+            namespaces: vec!["app".into(), "config".into()],
+            read_only: vec!["config".into()],
+        });
+
+        let fetch = |url: &str, method: &str| {
+            explain(&policy, &Capability::NetFetch, &[s(url), s(method)], 0)
+        };
+        assert_eq!(
+            fetch("https://api.example.com:8443/v1", "get").decision,
+            PolicyDecision::Allow
+        );
+        let e = fetch("https://user@evil.test/x", "GET");
+        assert_eq!(e.rule.path, "/net_policy/allowed_domains");
+        assert_eq!(e.suggestions[0].path, "/net_policy/allowed_domains/-");
+        assert_eq!(e.suggestions[0].value, Some(serde_json::json!("evil.test")));
+        let e = fetch("https://api.example.com", "POST");
+        assert_eq!(e.rule.path, "/net_policy/allowed_methods");
+
+        let e = explain(&policy, &Capability::KvSet, &[s("config/x"), s("v")], 0);
+        assert_eq!(e.decision, PolicyDecision::Deny);
+        assert_eq!(e.suggestions[0].op, "remove");
+        assert_eq!(e.suggestions[0].path, "/kv_policy/read_only/0");
+        let e = explain(&policy, &Capability::KvGet, &[s("config/x")], 0);
+        assert_eq!(e.decision, PolicyDecision::Allow);
+        assert_eq!(e.suggestions[0].effect, PolicyDecision::Deny);
+    }
+}
@@ -27,6 +27,14 @@
 //! | `policy.unknown_field` | Unknown top-level or `net_policy` field |
 //! | `policy.invalid_capability` | `rules` key not a known capability |
 //! | `policy.invalid_net_policy` | Out-of-range / bad `net_policy` value |
+//! | `policy.invalid_trusted_key` | `trusted_module_keys` entry not 64 hex chars |
+//! | `policy.invalid_kv_policy` | Empty or `/`-containing `kv_policy` namespace |
+//! | `policy.invalid_queue_policy` | `queue_policy` name outside `[A-Za-z0-9_.-]+` |
+//! | `policy.invalid_notify_policy` | `notify_policy` destination not a webhook URL or `mailto:` |
+//! | `policy.invalid_table_policy` | `table_policy` limit of zero |
+//!
+//! A `data_flow.max_classification` key that is not a canonical
+//! capability name reports `policy.invalid_capability`, like `rules`.
 //!
 //! See `docs/design-policy-as-code.md` and
 //! `docs/architecture-policy-as-code.md` for the design rationale.
@@ -39,16 +47,58 @@ use boruna_bytecode::Capability;
 use serde::Deserialize;
 use serde_json::Value;
 
-use crate::capability_gateway::{NetPolicy, Policy, PolicyRule};
+use crate::capability_gateway::{
+    DataFlowPolicy, KvPolicy, NetPolicy, NotifyPolicy, Policy, PolicyMode, PolicyRule, QueuePolicy,
+    TablePolicy,
+};
+use crate::queue_store::is_valid_queue_name;
 
 /// Schema version we accept. The validator rejects any other value.
 /// Bumping this is a breaking change in the policy file contract;
 /// new fields can be added at v1 as long as they are additive.
 pub const POLICY_SCHEMA_VERSION: u32 = 1;
 
+/// The JSON Schema (draft 2020-12) for policy files at
+/// [`POLICY_SCHEMA_VERSION`], as emitted by `boruna policy schema`.
+/// `docs/reference/policy.schema.json` is a verbatim copy; tests below
+/// keep it, and the allow-lists, in sync with this file.
+pub const POLICY_JSON_SCHEMA: &str = include_str!("policy.schema.json");
+
+/// [`POLICY_JSON_SCHEMA`] parsed.
+pub fn json_schema() -> Value {
+    serde_json::from_str(POLICY_JSON_SCHEMA).expect("embedded policy schema is valid JSON")
+}
+
 /// Allow-listed top-level field names on the policy file.
-const POLICY_TOP_LEVEL_FIELDS: &[&str] =
-    &["schema_version", "rules", "default_allow", "net_policy"];
+const POLICY_TOP_LEVEL_FIELDS: &[&str] = &[
+    "schema_version",
+    "rules",
+    "default_allow",
+    "net_policy",
+    "require_signed_modules",
+    "trusted_module_keys",
+    "mode",
+    "data_flow",
+    "kv_policy",
+    "queue_policy",
+    "notify_policy",
+    "table_policy",
+];
+
+/// Allow-listed field names on a `data_flow` object.
+const DATA_FLOW_FIELDS: &[&str] = &["max_classification", "redact_in_evidence"];
+
+/// Allow-listed field names on a `kv_policy` object.
+const KV_POLICY_FIELDS: &[&str] = &["namespaces", "read_only"];
+
+/// Allow-listed field names on a `queue_policy` object.
+const QUEUE_POLICY_FIELDS: &[&str] = &["push", "poll"];
+
+/// Allow-listed field names on a `notify_policy` object.
+const NOTIFY_POLICY_FIELDS: &[&str] = &["destinations", "max_per_run", "max_per_destination"];
+
+/// Allow-listed field names on a `table_policy` object.
+const TABLE_POLICY_FIELDS: &[&str] = &["max_rows", "max_bytes"];
 
 /// Allow-listed field names on a `net_policy` object.
 const NET_POLICY_FIELDS: &[&str] = &[
@@ -60,7 +110,7 @@ const NET_POLICY_FIELDS: &[&str] = &[
 ];
 
 /// Allow-listed field names on a `PolicyRule` object.
-const POLICY_RULE_FIELDS: &[&str] = &["allow", "budget"];
+const POLICY_RULE_FIELDS: &[&str] = &["allow", "budget", "prompt"];
 
 /// Canonical HTTP methods accepted in `net_policy.allowed_methods`.
 const CANONICAL_HTTP_METHODS: &[&str] =
@@ -91,6 +141,18 @@ pub enum PolicyParseError {
     InvalidCapability { found: String, hint: Option<String> },
     /// A `net_policy` value is out of range or otherwise unacceptable.
     InvalidNetPolicy { field: &'static str, reason: String },
+    /// A `trusted_module_keys` entry is not a 64-hex-char ed25519
+    /// public key, or `require_signed_modules` is set with no keys.
+    InvalidTrustedKey { found: String, reason: String },
+    /// A `kv_policy` namespace is empty or contains `/`.
+    InvalidKvPolicy { field: &'static str, reason: String },
+    /// A `queue_policy` name is not a valid queue name.
+    InvalidQueuePolicy { field: &'static str, reason: String },
+    /// A `notify_policy` destination is not an `http(s)://` URL or a
+    /// `mailto:` address.
+    InvalidNotifyPolicy { found: String, reason: String },
+    /// A `table_policy` limit is zero.
+    InvalidTablePolicy { field: &'static str, reason: String },
 }
 
 impl PolicyParseError {
@@ -105,6 +167,11 @@ impl PolicyParseError {
             Self::UnknownField { .. } => "policy.unknown_field",
             Self::InvalidCapability { .. } => "policy.invalid_capability",
             Self::InvalidNetPolicy { .. } => "policy.invalid_net_policy",
+            Self::InvalidTrustedKey { .. } => "policy.invalid_trusted_key",
+            Self::InvalidKvPolicy { .. } => "policy.invalid_kv_policy",
+            Self::InvalidQueuePolicy { .. } => "policy.invalid_queue_policy",
+            Self::InvalidNotifyPolicy { .. } => "policy.invalid_notify_policy",
+            Self::InvalidTablePolicy { .. } => "policy.invalid_table_policy",
         }
     }
 }
@@ -125,10 +192,11 @@ impl fmt::Display for PolicyParseError {
             ),
             Self::UnknownField { path, found } => write!(
                 f,
-                "{}: unknown field {:?} at {}",
+                "{}: unknown field {:?} at {} (not in policy schema_version {}; see `boruna policy schema`)",
                 self.error_kind(),
                 found,
-                path
+                path,
+                POLICY_SCHEMA_VERSION
             ),
             Self::InvalidCapability { found, hint } => match hint {
                 Some(h) => write!(
@@ -143,6 +211,29 @@ impl fmt::Display for PolicyParseError {
             Self::InvalidNetPolicy { field, reason } => {
                 write!(f, "{}: net_policy.{}: {}", self.error_kind(), field, reason)
             }
+            Self::InvalidTrustedKey { found, reason } => write!(
+                f,
+                "{}: trusted_module_keys {:?}: {}",
+                self.error_kind(),
+                found,
+                reason
+            ),
+            Self::InvalidKvPolicy { field, reason } => {
+                write!(f, "{}: kv_policy.{}: {}", self.error_kind(), field, reason)
+            }
+            Self::InvalidQueuePolicy { field, reason } => {
+                write!(f, "{}: queue_policy.{}: {}", self.error_kind(), field, reason)
+            }
+            Self::InvalidNotifyPolicy { found, reason } => write!(
+                f,
+                "{}: notify_policy.destinations: {:?}: {}",
+                self.error_kind(),
+                found,
+                reason
+            ),
+            Self::InvalidTablePolicy { field, reason } => {
+                write!(f, "{}: table_policy.{}: {}", self.error_kind(), field, reason)
+            }
         }
     }
 }
@@ -222,6 +313,66 @@ fn walk_unknown_fields(value: &Value) -> Result<(), PolicyParseError> {
                     }
                 }
             }
+            "data_flow" => {
+                if let Value::Object(df) = v {
+                    for (k2, _) in df {
+                        if !DATA_FLOW_FIELDS.contains(&k2.as_str()) {
+                            return Err(PolicyParseError::UnknownField {
+                                path: format!("data_flow.{k2}"),
+                                found: k2.clone(),
+                            });
+                        }
+                    }
+                }
+            }
+            "kv_policy" => {
+                if let Value::Object(kv) = v {
+                    for (k2, _) in kv {
+                        if !KV_POLICY_FIELDS.contains(&k2.as_str()) {
+                            return Err(PolicyParseError::UnknownField {
+                                path: format!("kv_policy.{k2}"),
+                                found: k2.clone(),
+                            });
+                        }
+                    }
+                }
+            }
+            "queue_policy" => {
+                if let Value::Object(qp) = v {
+                    for (k2, _) in qp {
+                        if !QUEUE_POLICY_FIELDS.contains(&k2.as_str()) {
+                            return Err(PolicyParseError::UnknownField {
+                                path: format!("queue_policy.{k2}"),
+                                found: k2.clone(),
+                            });
+                        }
+                    }
+                }
+            }
+            "notify_policy" => {
+                if let Value::Object(np) = v {
+                    for (k2, _) in np {
+                        if !NOTIFY_POLICY_FIELDS.contains(&k2.as_str()) {
+                            return Err(PolicyParseError::UnknownField {
+                                path: format!("notify_policy.{k2}"),
+                                found: k2.clone(),
+                            });
+                        }
+                    }
+                }
+            }
+            "table_policy" => {
+                if let Value::Object(tp) = v {
+                    for (k2, _) in tp {
+                        if !TABLE_POLICY_FIELDS.contains(&k2.as_str()) {
+                            return Err(PolicyParseError::UnknownField {
+                                path: format!("table_policy.{k2}"),
+                                found: k2.clone(),
+                            });
+                        }
+                    }
+                }
+            }
             "rules" => {
                 if let Value::Object(rules) = v {
                     for (cap_name, rule_val) in rules {
@@ -255,6 +406,69 @@ struct PolicyFileV1 {
     default_allow: bool,
     #[serde(default)]
     net_policy: Option<NetPolicyFileV1>,
+    #[serde(default)]
+    require_signed_modules: bool,
+    #[serde(default)]
+    trusted_module_keys: Vec<String>,
+    #[serde(default)]
+    mode: PolicyMode,
+    #[serde(default)]
+    data_flow: Option<DataFlowFileV1>,
+    #[serde(default)]
+    kv_policy: Option<KvPolicyFileV1>,
+    #[serde(default)]
+    queue_policy: Option<QueuePolicyFileV1>,
+    #[serde(default)]
+    notify_policy: Option<NotifyPolicyFileV1>,
+    #[serde(default)]
+    table_policy: Option<TablePolicyFileV1>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TablePolicyFileV1 {
+    #[serde(default)]
+    max_rows: Option<u64>,
+    #[serde(default)]
+    max_bytes: Option<u64>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NotifyPolicyFileV1 {
+    #[serde(default)]
+    destinations: Vec<String>,
+    #[serde(default)]
+    max_per_run: u32,
+    #[serde(default)]
+    max_per_destination: u32,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct QueuePolicyFileV1 {
+    #[serde(default)]
+    push: Vec<String>,
+    #[serde(default)]
+    poll: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct KvPolicyFileV1 {
+    #[serde(default)]
+    namespaces: Vec<String>,
+    #[serde(default)]
+    read_only: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DataFlowFileV1 {
+    #[serde(default)]
+    max_classification: BTreeMap<String, crate::capability_gateway::Classification>,
+    #[serde(default)]
+    redact_in_evidence: Option<crate::capability_gateway::Classification>,
 }
 
 #[derive(Deserialize)]
@@ -298,24 +512,50 @@ impl PolicyFileV1 {
         // we accept only what `Capability::name()` would emit.
         let mut canonical_rules = BTreeMap::new();
         for (key, rule) in self.rules {
-            match Capability::from_name(&key) {
-                Some(cap) if cap.name() == key => {
-                    canonical_rules.insert(key, rule);
-                }
-                Some(cap) => {
-                    return Err(PolicyParseError::InvalidCapability {
-                        found: key,
-                        hint: Some(cap.name().to_string()),
-                    });
-                }
-                None => {
-                    return Err(PolicyParseError::InvalidCapability {
-                        found: key,
-                        hint: None,
-                    });
+            check_canonical_capability(&key)?;
+            canonical_rules.insert(key, rule);
+        }
+
+        // data_flow: capability keys follow the same rule.
+        let data_flow = match self.data_flow {
+            Some(df) => {
+                for key in df.max_classification.keys() {
+                    check_canonical_capability(key)?;
                 }
+                Some(DataFlowPolicy {
+                    max_classification: df.max_classification,
+                    redact_in_evidence: df.redact_in_evidence,
+                })
             }
-        }
+            None => None,
+        };
+
+        // kv_policy: a namespace is the text before a key's first `/`,
+        // so an empty name or one containing `/` could never match.
+        let kv_policy = match self.kv_policy {
+            Some(kv) => Some(kv.validate()?),
+            None => None,
+        };
+
+        // queue_policy: names map to queue file names, so reject any
+        // the queue store would refuse.
+        let queue_policy = match self.queue_policy {
+            Some(qp) => Some(qp.validate()?),
+            None => None,
+        };
+
+        // notify_policy: every destination must name a channel the
+        // notify handler can deliver to.
+        let notify_policy = match self.notify_policy {
+            Some(np) => Some(np.validate()?),
+            None => None,
+        };
+
+        // table_policy: a zero limit would refuse every table.
+        let table_policy = match self.table_policy {
+            Some(tp) => Some(tp.validate()?),
+            None => None,
+        };
 
         // net_policy: validate bounds
         let net_policy = match self.net_policy {
@@ -323,15 +563,140 @@ impl PolicyFileV1 {
             None => None,
         };
 
+        // trusted_module_keys: ed25519 public keys as 64 hex chars;
+        // requiring signatures with no trusted key would refuse every
+        // module, which is never what the operator meant.
+        for key in &self.trusted_module_keys {
+            if key.len() != 64 || !key.bytes().all(|b| b.is_ascii_hexdigit()) {
+                return Err(PolicyParseError::InvalidTrustedKey {
+                    found: key.clone(),
+                    reason: "expected 64 hex chars (ed25519 public key)".to_string(),
+                });
+            }
+        }
+        if self.require_signed_modules && self.trusted_module_keys.is_empty() {
+            return Err(PolicyParseError::InvalidTrustedKey {
+                found: String::new(),
+                reason: "require_signed_modules is set but no keys are trusted".to_string(),
+            });
+        }
+
         Ok(Policy {
             schema_version: POLICY_SCHEMA_VERSION,
             rules: canonical_rules,
             default_allow: self.default_allow,
             net_policy,
+            require_signed_modules: self.require_signed_modules,
+            trusted_module_keys: self
+                .trusted_module_keys
+                .into_iter()
+                .map(|k| k.to_ascii_lowercase())
+                .collect(),
+            mode: self.mode,
+            data_flow,
+            kv_policy,
+            queue_policy,
+            notify_policy,
+            table_policy,
+        })
+    }
+}
+
+fn check_canonical_capability(key: &str) -> Result<(), PolicyParseError> {
+    match Capability::from_name(key) {
+        Some(cap) if cap.name() == key => Ok(()),
+        Some(cap) => Err(PolicyParseError::InvalidCapability {
+            found: key.to_string(),
+            hint: Some(cap.name().to_string()),
+        }),
+        None => Err(PolicyParseError::InvalidCapability {
+            found: key.to_string(),
+            hint: None,
+        }),
+    }
+}
+
+impl KvPolicyFileV1 {
+    fn validate(self) -> Result<KvPolicy, PolicyParseError> {
+        for (field, names) in [
+            ("namespaces", &self.namespaces),
+            ("read_only", &self.read_only),
+        ] {
+            if let Some(bad) = names.iter().find(|n| n.is_empty() || n.contains('/')) {
+                return Err(PolicyParseError::InvalidKvPolicy {
+                    field,
+                    reason: format!("namespace {bad:?} must be non-empty and contain no '/'"),
+                });
+            }
+        }
+        Ok(KvPolicy {
+            namespaces: self.namespaces,
+            read_only: self.read_only,
         })
     }
 }
 
+impl QueuePolicyFileV1 {
+    fn validate(self) -> Result<QueuePolicy, PolicyParseError> {
+        for (field, names) in [("push", &self.push), ("poll", &self.poll)] {
+            if let Some(bad) = names.iter().find(|n| !is_valid_queue_name(n)) {
+                return Err(PolicyParseError::InvalidQueuePolicy {
+                    field,
+                    reason: format!("queue name {bad:?} must match [A-Za-z0-9_.-]+"),
+                });
+            }
+        }
+        Ok(QueuePolicy {
+            push: self.push,
+            poll: self.poll,
+        })
+    }
+}
+
+impl NotifyPolicyFileV1 {
+    fn validate(self) -> Result<NotifyPolicy, PolicyParseError> {
+        for dest in &self.destinations {
+            let pattern = dest.strip_suffix('*').unwrap_or(dest);
+            let known = ["https://", "http://", "mailto:"]
+                .iter()
+                .any(|scheme| pattern.starts_with(scheme) && pattern.len() > scheme.len());
+            if !known {
+                return Err(PolicyParseError::InvalidNotifyPolicy {
+                    found: dest.clone(),
+                    reason: "expected an http(s):// webhook URL or a mailto: address".to_string(),
+                });
+            }
+        }
+        Ok(NotifyPolicy {
+            destinations: self.destinations,
+            max_per_run: self.max_per_run,
+            max_per_destination: self.max_per_destination,
+        })
+    }
+}
+
+impl TablePolicyFileV1 {
+    fn validate(self) -> Result<TablePolicy, PolicyParseError> {
+        let defaults = TablePolicy::default();
+        let policy = TablePolicy {
+            max_rows: self.max_rows.unwrap_or(defaults.max_rows),
+            max_bytes: self.max_bytes.unwrap_or(defaults.max_bytes),
+        };
+        for (field, value) in [
+            ("max_rows", policy.max_rows),
+            ("max_bytes", policy.max_bytes),
+        ] {
+            if value == 0 {
+                return Err(PolicyParseError::InvalidTablePolicy {
+                    field,
+                    reason: "must be > 0".to_string(),
+                });
+            }
+        }
+        Ok(policy)
+    }
+}
+
 impl NetPolicyFileV1 {
     fn validate(self) -> Result<NetPolicy, PolicyParseError> {
         if self.max_response_bytes == 0 {
@@ -517,6 +882,9 @@ mod tests {
         let err = parse(r#"{"default_alow": true}"#).unwrap_err();
         assert!(matches!(err, PolicyParseError::UnknownField { .. }));
         assert_eq!(err.error_kind(), "policy.unknown_field");
+        let msg = err.to_string();
+        assert!(msg.contains("at default_alow"), "{msg}");
+        assert!(msg.contains("schema_version 1"), "{msg}");
     }
 
     #[test]
@@ -648,6 +1016,66 @@ mod tests {
         assert_eq!(p.net_policy.unwrap().allowed_methods.len(), 7);
     }
 
+    // ─── Module trust ───
+
+    #[test]
+    fn accept_trusted_module_keys_lowercased() {
+        let key = "AB".repeat(32);
+        let p = parse(&format!(
+            r#"{{"default_allow": true, "require_signed_modules": true, "trusted_module_keys": ["{key}"]}}"#
+        ))
+        .unwrap();
+        assert!(p.require_signed_modules);
+        assert_eq!(p.trusted_module_keys, vec!["ab".repeat(32)]);
+    }
+
+    #[test]
+    fn reject_malformed_trusted_key() {
+        let err = parse(r#"{"trusted_module_keys": ["not-a-key"]}"#).unwrap_err();
+        assert_eq!(err.error_kind(), "policy.invalid_trusted_key");
+    }
+
+    #[test]
+    fn reject_require_signed_without_keys() {
+        let err = parse(r#"{"require_signed_modules": true}"#).unwrap_err();
+        assert_eq!(err.error_kind(), "policy.invalid_trusted_key");
+    }
+
+    // ─── Prompt rules ───
+
+    #[test]
+    fn accept_prompt_rule_and_omit_it_when_false() {
+        let p = parse(r#"{"rules": {"net.fetch": {"allow": false, "budget": 0, "prompt": true}}}"#)
+            .unwrap();
+        assert!(p.rules["net.fetch"].prompt);
+
+        let mut policy = Policy::deny_all();
+        policy.allow(&Capability::NetFetch, 0);
+        let json = serde_json::to_string(&policy).unwrap();
+        assert!(!json.contains("prompt"), "{json}");
+    }
+
+    // ─── Mode ───
+
+    #[test]
+    fn accept_dry_run_mode() {
+        let p = parse(r#"{"mode": "dry_run"}"#).unwrap();
+        assert_eq!(p.mode, PolicyMode::DryRun);
+        assert_eq!(parse("{}").unwrap().mode, PolicyMode::Enforce);
+    }
+
+    #[test]
+    fn reject_unknown_mode() {
+        assert_eq!(err_kind(r#"{"mode": "audit"}"#), "policy.parse_error");
+    }
+
+    #[test]
+    fn enforce_mode_is_not_serialized() {
+        // Keeps hashes of policies written before `mode` existed stable.
+        let json = serde_json::to_string(&Policy::allow_all()).unwrap();
+        assert!(!json.contains("mode"), "{json}");
+    }
+
     // ─── Parse / IO errors ───
 
     #[test]
@@ -708,6 +1136,13 @@ mod tests {
                 },
                 "policy.invalid_net_policy",
             ),
+            (
+                PolicyParseError::InvalidTrustedKey {
+                    found: "zz".into(),
+                    reason: "x".into(),
+                },
+                "policy.invalid_trusted_key",
+            ),
         ];
         for (err, kind) in cases {
             assert_eq!(err.error_kind(), *kind);
@@ -716,19 +1151,28 @@ mod tests {
 
     // ─── Schema drift detection ───
     //
-    // `docs/reference/policy.schema.json` is hand-written. These
-    // tests detect drift between the schema and the parser by
-    // comparing capability names, top-level fields, net-policy
-    // fields, rule fields, and schema_version. They run as plain
-    // unit tests (no jsonschema dep) by parsing the schema as
-    // generic JSON and walking specific paths.
+    // `policy.schema.json` is hand-written. These tests detect drift
+    // between the schema and the parser by comparing capability names,
+    // top-level fields, net-policy fields, rule fields, and
+    // schema_version. They run as plain unit tests (no jsonschema dep)
+    // by parsing the schema as generic JSON and walking specific paths.
 
     fn load_schema() -> serde_json::Value {
+        json_schema()
+    }
+
+    #[test]
+    fn docs_schema_is_a_copy_of_the_embedded_schema() {
         let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
             .join("../../docs/reference/policy.schema.json");
-        let json = std::fs::read_to_string(&path)
+        let docs = std::fs::read_to_string(&path)
             .unwrap_or_else(|e| panic!("failed to read {}: {}", path.display(), e));
-        serde_json::from_str(&json).unwrap_or_else(|e| panic!("schema is not valid JSON: {e}"))
+        assert!(
+            docs == POLICY_JSON_SCHEMA,
+            "{} differs from the embedded schema; regenerate it with \
+             `boruna policy schema > docs/reference/policy.schema.json`",
+            path.display()
+        );
     }
 
     #[test]
@@ -742,6 +1186,12 @@ mod tests {
             u64::from(POLICY_SCHEMA_VERSION),
             "schema schema_version drift — schema={v}, parser={POLICY_SCHEMA_VERSION}"
         );
+        assert_eq!(
+            schema["x-schema-version"].as_u64(),
+            Some(u64::from(POLICY_SCHEMA_VERSION)),
+            "x-schema-version drift"
+        );
+        assert_eq!(Policy::json_schema(), schema);
     }
 
     #[test]
@@ -803,6 +1253,213 @@ mod tests {
         );
     }
 
+    #[test]
+    fn schema_data_flow_fields_match_parser_allowlist() {
+        let schema = load_schema();
+        let mut schema_fields: Vec<String> = schema["$defs"]["dataFlow"]["properties"]
+            .as_object()
+            .expect("$defs.dataFlow.properties is an object")
+            .keys()
+            .cloned()
+            .collect();
+        schema_fields.sort();
+        let mut parser_fields: Vec<String> =
+            DATA_FLOW_FIELDS.iter().map(|s| s.to_string()).collect();
+        parser_fields.sort();
+        assert_eq!(
+            schema_fields, parser_fields,
+            "data_flow field drift between policy.schema.json and DATA_FLOW_FIELDS"
+        );
+    }
+
+    #[test]
+    fn accept_data_flow_and_reject_bad_keys() {
+        let p = parse(
+            r#"{"data_flow": {"max_classification": {"net.fetch": "internal"}, "redact_in_evidence": "secret"}}"#,
+        )
+        .unwrap();
+        let df = p.data_flow.unwrap();
+        assert_eq!(
+            df.max_classification["net.fetch"],
+            crate::capability_gateway::Classification::Internal
+        );
+        assert_eq!(
+            df.redact_in_evidence,
+            Some(crate::capability_gateway::Classification::Secret)
+        );
+
+        assert_eq!(
+            err_kind(r#"{"data_flow": {"max_classification": {"net": "public"}}}"#),
+            "policy.invalid_capability"
+        );
+        assert_eq!(
+            err_kind(r#"{"data_flow": {"redact": "secret"}}"#),
+            "policy.unknown_field"
+        );
+        assert_eq!(
+            err_kind(r#"{"data_flow": {"redact_in_evidence": "top_secret"}}"#),
+            "policy.parse_error"
+        );
+    }
+
+    #[test]
+    fn schema_kv_policy_fields_match_parser_allowlist() {
+        let schema = load_schema();
+        let mut schema_fields: Vec<String> = schema["$defs"]["kvPolicy"]["properties"]
+            .as_object()
+            .expect("$defs.kvPolicy.properties is an object")
+            .keys()
+            .cloned()
+            .collect();
+        schema_fields.sort();
+        let mut parser_fields: Vec<String> =
+            KV_POLICY_FIELDS.iter().map(|s| s.to_string()).collect();
+        parser_fields.sort();
+        assert_eq!(
+            schema_fields, parser_fields,
+            "kv_policy field drift between policy.schema.json and KV_POLICY_FIELDS"
+        );
+    }
+
+    #[test]
+    fn accept_kv_policy_and_reject_bad_namespaces() {
+        let p =
+            parse(r#"{"kv_policy": {"namespaces": ["cart", "config"], "read_only": ["config"]}}"#)
+                .unwrap();
+        let kv = p.kv_policy.unwrap();
+        assert_eq!(kv.namespaces, vec!["cart", "config"]);
+        assert_eq!(kv.read_only, vec!["config"]);
+
+        assert_eq!(
+            err_kind(r#"{"kv_policy": {"namespaces": ["cart/items"]}}"#),
+            "policy.invalid_kv_policy"
+        );
+        assert_eq!(
+            err_kind(r#"{"kv_policy": {"read_only": [""]}}"#),
+            "policy.invalid_kv_policy"
+        );
+        assert_eq!(
+            err_kind(r#"{"kv_policy": {"prefixes": ["cart"]}}"#),
+            "policy.unknown_field"
+        );
+    }
+
+    #[test]
+    fn schema_queue_policy_fields_match_parser_allowlist() {
+        let schema = load_schema();
+        let mut schema_fields: Vec<String> = schema["$defs"]["queuePolicy"]["properties"]
+            .as_object()
+            .expect("$defs.queuePolicy.properties is an object")
+            .keys()
+            .cloned()
+            .collect();
+        schema_fields.sort();
+        let mut parser_fields: Vec<String> =
+            QUEUE_POLICY_FIELDS.iter().map(|s| s.to_string()).collect();
+        parser_fields.sort();
+        assert_eq!(
+            schema_fields, parser_fields,
+            "queue_policy field drift between policy.schema.json and QUEUE_POLICY_FIELDS"
+        );
+    }
+
+    #[test]
+    fn accept_queue_policy_and_reject_bad_names() {
+        let p = parse(r#"{"queue_policy": {"push": ["jobs"], "poll": ["results"]}}"#).unwrap();
+        let qp = p.queue_policy.unwrap();
+        assert_eq!(qp.push, vec!["jobs"]);
+        assert_eq!(qp.poll, vec!["results"]);
+
+        assert_eq!(
+            err_kind(r#"{"queue_policy": {"push": ["../jobs"]}}"#),
+            "policy.invalid_queue_policy"
+        );
+        assert_eq!(
+            err_kind(r#"{"queue_policy": {"ack": ["jobs"]}}"#),
+            "policy.unknown_field"
+        );
+    }
+
+    #[test]
+    fn schema_notify_policy_fields_match_parser_allowlist() {
+        let schema = load_schema();
+        let mut schema_fields: Vec<String> = schema["$defs"]["notifyPolicy"]["properties"]
+            .as_object()
+            .expect("$defs.notifyPolicy.properties is an object")
+            .keys()
+            .cloned()
+            .collect();
+        schema_fields.sort();
+        let mut parser_fields: Vec<String> =
+            NOTIFY_POLICY_FIELDS.iter().map(|s| s.to_string()).collect();
+        parser_fields.sort();
+        assert_eq!(
+            schema_fields, parser_fields,
+            "notify_policy field drift between policy.schema.json and NOTIFY_POLICY_FIELDS"
+        );
+    }
+
+    #[test]
+    fn accept_notify_policy_and_reject_unknown_channels() {
+        let p = parse(
+            r#"{"notify_policy": {"destinations": ["https://hooks.example.com/*", "mailto:ops@example.com"], "max_per_run": 5}}"#,
+        )
+        .unwrap();
+        let np = p.notify_policy.unwrap();
+        assert_eq!(np.destinations.len(), 2);
+        assert_eq!(np.max_per_run, 5);
+        assert_eq!(np.max_per_destination, 0);
+
+        assert_eq!(
+            err_kind(r#"{"notify_policy": {"destinations": ["ftp://example.com"]}}"#),
+            "policy.invalid_notify_policy"
+        );
+        assert_eq!(
+            err_kind(r#"{"notify_policy": {"destinations": ["mailto:*"]}}"#),
+            "policy.invalid_notify_policy"
+        );
+        assert_eq!(
+            err_kind(r#"{"notify_policy": {"per_minute": 5}}"#),
+            "policy.unknown_field"
+        );
+    }
+
+    #[test]
+    fn schema_table_policy_fields_match_parser_allowlist() {
+        let schema = load_schema();
+        let mut schema_fields: Vec<String> = schema["$defs"]["tablePolicy"]["properties"]
+            .as_object()
+            .expect("$defs.tablePolicy.properties is an object")
+            .keys()
+            .cloned()
+            .collect();
+        schema_fields.sort();
+        let mut parser_fields: Vec<String> =
+            TABLE_POLICY_FIELDS.iter().map(|s| s.to_string()).collect();
+        parser_fields.sort();
+        assert_eq!(
+            schema_fields, parser_fields,
+            "table_policy field drift between policy.schema.json and TABLE_POLICY_FIELDS"
+        );
+    }
+
+    #[test]
+    fn accept_table_policy_with_defaults_and_reject_zero_limits() {
+        let p = parse(r#"{"table_policy": {"max_rows": 500}}"#).unwrap();
+        let tp = p.table_policy.unwrap();
+        assert_eq!(tp.max_rows, 500);
+        assert_eq!(tp.max_bytes, TablePolicy::default().max_bytes);
+
+        assert_eq!(
+            err_kind(r#"{"table_policy": {"max_bytes": 0}}"#),
+            "policy.invalid_table_policy"
+        );
+        assert_eq!(
+            err_kind(r#"{"table_policy": {"max_columns": 5}}"#),
+            "policy.unknown_field"
+        );
+    }
+
     #[test]
     fn schema_capability_enum_matches_canonical_names() {
         // The schema constrains `rules` keys via a propertyNames.enum.
@@ -835,6 +1492,13 @@ mod tests {
             Capability::ActorSpawn,
             Capability::ActorSend,
             Capability::StepInput,
+            Capability::KvGet,
+            Capability::KvSet,
+            Capability::KvScan,
+            Capability::QueuePush,
+            Capability::QueuePoll,
+            Capability::NotifySend,
+            Capability::DataReadTable,
         ]
         .iter()
         .map(|c| c.name().to_string())
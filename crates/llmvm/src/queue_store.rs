@@ -0,0 +1,385 @@
+//! File-backed queues behind the `queue.push` / `queue.poll`
+//! capabilities.
+//!
+//! One run enqueues work with `queue_push(name, value)`; a later run
+//! takes it with `queue_poll(name)`. The [`QueueHandler`] serves those
+//! calls from a [`QueueStore`] and delegates every other capability to a
+//! wrapped handler, like [`KvHandler`](crate::kv_store::KvHandler).
+//!
+//! **On disk.** A store is a directory with one append-only file per
+//! queue, `<name>.jsonl`. Each line is either `{"push": <seq>, "value":
+//! <Value>}` or `{"poll": <seq>}`. Opening a queue replays its lines, so
+//! the pending items are the pushes without a matching poll. As in the
+//! kv store, a final line without a trailing newline is a write torn by
+//! a crash and is truncated away before the next append; any other
+//! malformed line is an error.
+//!
+//! **Concurrency.** Every push, poll and length check takes an exclusive
+//! lock on the queue file and replays it under that lock, so producers
+//! and consumers in other processes never share a sequence number or an
+//! item.
+//!
+//! **Determinism.** Sequence numbers start at 1 per queue and polls
+//! always take the lowest pending one, so poll order is push order.
+//! Every call goes through the gateway, so the
+//! [`EventLog`](crate::replay::EventLog) records the pushed value, the
+//! assigned sequence number and each polled item; replaying the log
+//! serves the recorded results without touching the queue files.
+//!
+//! **Names.** Queue names are `[A-Za-z0-9_.-]+` so they map to a file
+//! name. [`QueuePolicy`] limits which queues a run may push to or poll.
+
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use boruna_bytecode::{Capability, Value};
+use serde::{Deserialize, Serialize};
+
+use crate::capability_gateway::{CapabilityHandler, QueuePolicy};
+
+/// One line of a queue file.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum QueueRecord {
+    Push { push: u64, value: Value },
+    Poll { poll: u64 },
+}
+
+/// Replayed state of one queue.
+#[derive(Debug, Default)]
+struct Queue {
+    /// Pending items by sequence number.
+    pending: BTreeMap<u64, Value>,
+    /// Highest sequence number ever pushed.
+    last_seq: u64,
+}
+
+/// Queues backed by a directory of append-only files, or held in memory.
+#[derive(Debug, Default)]
+pub struct QueueStore {
+    /// `None` for an in-memory store.
+    dir: Option<PathBuf>,
+    /// Queues of an in-memory store, by name.
+    queues: BTreeMap<String, Queue>,
+}
+
+/// A store shared between a [`QueueHandler`] and the host.
+pub type SharedQueueStore = Arc<Mutex<QueueStore>>;
+
+/// Whether `name` is a valid queue name.
+pub fn is_valid_queue_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'))
+        && name != "."
+        && name != ".."
+}
+
+impl QueueStore {
+    /// An empty store that is never written to disk.
+    pub fn in_memory() -> Self {
+        QueueStore::default()
+    }
+
+    /// Use `dir` for queue files, creating it on first use if missing.
+    pub fn open(dir: &Path) -> Self {
+        QueueStore {
+            dir: Some(dir.to_path_buf()),
+            queues: BTreeMap::new(),
+        }
+    }
+
+    /// A store shared with a [`QueueHandler`].
+    pub fn shared(self) -> SharedQueueStore {
+        Arc::new(Mutex::new(self))
+    }
+
+    /// Run `op` on the current state of `name`. For a file-backed store
+    /// the queue file is locked and replayed first, and the record `op`
+    /// returns is appended before the lock is released.
+    fn with_queue<T>(
+        &mut self,
+        name: &str,
+        op: impl FnOnce(&mut Queue) -> (Option<QueueRecord>, T),
+    ) -> Result<T, String> {
+        if !is_valid_queue_name(name) {
+            return Err(format!(
+                "invalid queue name '{name}' (expected [A-Za-z0-9_.-]+)"
+            ));
+        }
+        let Some(dir) = &self.dir else {
+            let (_, out) = op(self.queues.entry(name.to_string()).or_default());
+            return Ok(out);
+        };
+        let path = dir.join(format!("{name}.jsonl"));
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("queue '{name}': create '{}': {e}", dir.display()))?;
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| format!("queue '{name}': open '{}': {e}", path.display()))?;
+        file.lock()
+            .map_err(|e| format!("queue '{name}': lock '{}': {e}", path.display()))?;
+        let mut queue = load_queue(&mut file, &path)?;
+        let (record, out) = op(&mut queue);
+        if let Some(record) = record {
+            let mut line = serde_json::to_string(&record)
+                .map_err(|e| format!("queue '{name}': serialize: {e}"))?;
+            line.push('\n');
+            file.write_all(line.as_bytes())
+                .and_then(|()| file.sync_data())
+                .map_err(|e| format!("queue '{name}': write '{}': {e}", path.display()))?;
+        }
+        Ok(out)
+    }
+
+    /// Enqueue `value` on `name` and return its sequence number.
+    pub fn push(&mut self, name: &str, value: Value) -> Result<u64, String> {
+        self.with_queue(name, |queue| {
+            let seq = queue.last_seq + 1;
+            queue.last_seq = seq;
+            queue.pending.insert(seq, value.clone());
+            (Some(QueueRecord::Push { push: seq, value }), seq)
+        })
+    }
+
+    /// Take the oldest pending item from `name`, if any.
+    pub fn poll(&mut self, name: &str) -> Result<Option<(u64, Value)>, String> {
+        self.with_queue(name, |queue| match queue.pending.pop_first() {
+            Some((seq, value)) => (Some(QueueRecord::Poll { poll: seq }), Some((seq, value))),
+            None => (None, None),
+        })
+    }
+
+    /// Number of pending items on `name`.
+    pub fn len(&mut self, name: &str) -> Result<usize, String> {
+        self.with_queue(name, |queue| (None, queue.pending.len()))
+    }
+}
+
+/// Replay the locked queue `file`, truncating a torn final line so the
+/// next append starts on a fresh line.
+fn load_queue(file: &mut File, path: &Path) -> Result<Queue, String> {
+    let mut data = Vec::new();
+    file.read_to_end(&mut data)
+        .map_err(|e| format!("failed to read queue '{}': {e}", path.display()))?;
+    let complete = data
+        .iter()
+        .rposition(|&b| b == b'\n')
+        .map_or(0, |end| end + 1);
+    if complete < data.len() {
+        file.set_len(complete as u64)
+            .map_err(|e| format!("failed to truncate queue '{}': {e}", path.display()))?;
+    }
+    let complete = std::str::from_utf8(&data[..complete])
+        .map_err(|e| format!("queue '{}': invalid UTF-8: {e}", path.display()))?;
+    let mut queue = Queue::default();
+    for (i, line) in complete.lines().enumerate() {
+        if line.is_empty() {
+            continue;
+        }
+        let record: QueueRecord = serde_json::from_str(line).map_err(|e| {
+            format!(
+                "queue '{}' line {}: invalid record: {e}",
+                path.display(),
+                i + 1
+            )
+        })?;
+        match record {
+            QueueRecord::Push { push, value } => {
+                queue.last_seq = queue.last_seq.max(push);
+                queue.pending.insert(push, value);
+            }
+            QueueRecord::Poll { poll } => {
+                queue.pending.remove(&poll);
+            }
+        }
+    }
+    Ok(queue)
+}
+
+/// Handler that serves the `queue.*` capabilities from a [`QueueStore`],
+/// enforcing a [`QueuePolicy`]. All other capabilities delegate to
+/// `inner`.
+pub struct QueueHandler {
+    store: SharedQueueStore,
+    policy: QueuePolicy,
+    inner: Box<dyn CapabilityHandler>,
+}
+
+impl QueueHandler {
+    pub fn new(
+        store: SharedQueueStore,
+        policy: QueuePolicy,
+        inner: Box<dyn CapabilityHandler>,
+    ) -> Self {
+        QueueHandler {
+            store,
+            policy,
+            inner,
+        }
+    }
+}
+
+impl CapabilityHandler for QueueHandler {
+    fn handle(&mut self, cap: &Capability, args: &[Value]) -> Result<Value, String> {
+        if !matches!(cap, Capability::QueuePush | Capability::QueuePoll) {
+            return self.inner.handle(cap, args);
+        }
+        let name = match args.first() {
+            Some(Value::String(s)) => s.as_str(),
+            Some(other) => {
+                return Err(format!(
+                    "{cap}: queue name must be a String, got {}",
+                    other.type_name()
+                ))
+            }
+            None => return Err(format!("{cap}: missing queue name")),
+        };
+        self.policy.check(cap, name)?;
+        let mut store = self
+            .store
+            .lock()
+            .map_err(|_| format!("{cap}: store poisoned"))?;
+        if *cap == Capability::QueuePush {
+            let value = args
+                .get(1)
+                .cloned()
+                .ok_or_else(|| format!("{cap}: missing value"))?;
+            let seq = store.push(name, value).map_err(|e| format!("{cap}: {e}"))?;
+            Ok(Value::Int(seq as i64))
+        } else {
+            Ok(match store.poll(name).map_err(|e| format!("{cap}: {e}"))? {
+                Some((_, value)) => Value::Some(Box::new(value)),
+                None => Value::None,
+            })
+        }
+    }
+
+    fn handles(&self, cap: &Capability) -> bool {
+        matches!(cap, Capability::QueuePush | Capability::QueuePoll)
+    }
+
+    fn delegate(&mut self) -> Option<&mut dyn CapabilityHandler> {
+        Some(self.inner.as_mut())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capability_gateway::MockHandler;
+
+    fn s(v: &str) -> Value {
+        Value::String(v.into())
+    }
+
+    #[test]
+    fn poll_order_is_push_order_across_reopens() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut producer = QueueStore::open(dir.path());
+        assert_eq!(producer.push("jobs", s("a")).unwrap(), 1);
+        assert_eq!(producer.push("jobs", s("b")).unwrap(), 2);
+        assert_eq!(producer.push("jobs", s("c")).unwrap(), 3);
+
+        let mut consumer = QueueStore::open(dir.path());
+        assert_eq!(consumer.poll("jobs").unwrap(), Some((1, s("a"))));
+
+        // A crash mid-append leaves a partial final line.
+        let path = dir.path().join("jobs.jsonl");
+        let mut file = fs::OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(br#"{"poll":"#).unwrap();
+
+        let mut next = QueueStore::open(dir.path());
+        assert_eq!(next.len("jobs").unwrap(), 2);
+        assert_eq!(next.poll("jobs").unwrap(), Some((2, s("b"))));
+        assert_eq!(next.push("jobs", s("d")).unwrap(), 4);
+        assert_eq!(next.poll("jobs").unwrap(), Some((3, s("c"))));
+        assert_eq!(next.poll("jobs").unwrap(), Some((4, s("d"))));
+        assert_eq!(next.poll("jobs").unwrap(), None);
+        // The torn tail was cut before the next append, so the file
+        // still replays.
+        assert_eq!(QueueStore::open(dir.path()).len("jobs").unwrap(), 0);
+        let data = fs::read_to_string(&path).unwrap();
+        assert!(data
+            .lines()
+            .all(|l| serde_json::from_str::<QueueRecord>(l).is_ok()));
+    }
+
+    #[test]
+    fn interleaved_stores_share_one_queue() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut a = QueueStore::open(dir.path());
+        let mut b = QueueStore::open(dir.path());
+        assert_eq!(a.push("jobs", s("a")).unwrap(), 1);
+        assert_eq!(b.push("jobs", s("b")).unwrap(), 2);
+        assert_eq!(a.push("jobs", s("c")).unwrap(), 3);
+        assert_eq!(b.poll("jobs").unwrap(), Some((1, s("a"))));
+        assert_eq!(a.poll("jobs").unwrap(), Some((2, s("b"))));
+        assert_eq!(b.poll("jobs").unwrap(), Some((3, s("c"))));
+        assert_eq!(a.poll("jobs").unwrap(), None);
+
+        // Concurrent producers never reuse a sequence number.
+        let producers: Vec<_> = (0..2)
+            .map(|_| {
+                let dir = dir.path().to_path_buf();
+                std::thread::spawn(move || {
+                    let mut store = QueueStore::open(&dir);
+                    (0..25)
+                        .map(|i| store.push("burst", Value::Int(i)).unwrap())
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        let mut seqs: Vec<u64> = producers
+            .into_iter()
+            .flat_map(|p| p.join().unwrap())
+            .collect();
+        seqs.sort_unstable();
+        assert_eq!(seqs, (1..=50).collect::<Vec<_>>());
+        assert_eq!(a.len("burst").unwrap(), 50);
+    }
+
+    #[test]
+    fn handler_enforces_queue_policy() {
+        let store = QueueStore::in_memory().shared();
+        let policy = QueuePolicy {
+            push: vec!["jobs".into()],
+            poll: vec!["results".into()],
+        };
+        let mut h = QueueHandler::new(store.clone(), policy, Box::new(MockHandler));
+
+        assert_eq!(
+            h.handle(&Capability::QueuePush, &[s("jobs"), Value::Int(7)])
+                .unwrap(),
+            Value::Int(1)
+        );
+        assert_eq!(
+            h.handle(&Capability::QueuePoll, &[s("results")]).unwrap(),
+            Value::None
+        );
+        let err = h.handle(&Capability::QueuePoll, &[s("jobs")]).unwrap_err();
+        assert!(err.contains("not allowed"), "got: {err}");
+        let err = h
+            .handle(&Capability::QueuePush, &[s("results"), Value::Int(1)])
+            .unwrap_err();
+        assert!(err.contains("not allowed"), "got: {err}");
+        assert_eq!(store.lock().unwrap().len("jobs").unwrap(), 1);
+
+        let mut open = QueueHandler::new(
+            QueueStore::in_memory().shared(),
+            QueuePolicy::default(),
+            Box::new(MockHandler),
+        );
+        let err = open
+            .handle(&Capability::QueuePush, &[s("../etc"), Value::Unit])
+            .unwrap_err();
+        assert!(err.contains("invalid queue name"), "got: {err}");
+    }
+}
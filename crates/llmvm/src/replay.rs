@@ -1,15 +1,19 @@
-use boruna_bytecode::{Capability, ContractKind, Value};
+use boruna_bytecode::{Capability, ContractKind, Provenance, Value};
 use serde::{Deserialize, Serialize};
 
+use crate::capability_gateway::Denial;
+
 /// Current version of the EventLog format.
 ///
-/// Bumped to 2 when `Event::ContractCheck` was added. Version-1 logs
-/// simply lack the variant; they deserialize unchanged (the new arm is
+/// Bumped to 2 when `Event::ContractCheck` was added, and to 3 when
+/// `Event::ActorCheckpoint`, `Event::ActorRestore` and
+/// `Event::CapabilityDelegation` were. Older logs
+/// simply lack the variants; they deserialize unchanged (the new arms are
 /// additive), so old evidence still verifies.
-pub const EVENT_LOG_VERSION: u32 = 2;
+pub const EVENT_LOG_VERSION: u32 = 3;
 
 /// Maximum supported version (for forward-compat rejection).
-const MAX_SUPPORTED_VERSION: u32 = 2;
+const MAX_SUPPORTED_VERSION: u32 = 3;
 
 /// A single event in the execution log.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -60,6 +64,29 @@ pub enum Event {
         index: usize,
         passed: bool,
     },
+    /// A persistent actor's state was written to the actor state store.
+    /// `state_hash` is the `sha256:` digest of the checkpointed state.
+    ActorCheckpoint {
+        actor_id: u64,
+        state_hash: String,
+    },
+    /// A persistent actor resumed from its stored checkpoint instead of
+    /// starting fresh. [`ReplayEngine::verify_restores`] checks the hash
+    /// against the last checkpoint of the previous run.
+    ActorRestore {
+        actor_id: u64,
+        state_hash: String,
+    },
+    /// Actor `child` was spawned (or restored) under its parent's policy
+    /// narrowed by a grant. `policy_hash` is the
+    /// [`policy_hash`](crate::delegation::policy_hash) of the policy its
+    /// gateway enforces; following `parent` links back to the root gives
+    /// the delegation chain.
+    CapabilityDelegation {
+        parent: u64,
+        child: u64,
+        policy_hash: String,
+    },
 }
 
 /// Event log for recording and replay.
@@ -70,6 +97,37 @@ pub struct EventLog {
     #[serde(default = "default_version")]
     version: u32,
     events: Vec<Event>,
+    /// How the run ended. Set by `boruna run --record`; absent from logs
+    /// written by other producers (and from older logs), and never
+    /// compared by [`ReplayEngine::verify`], so it needs no version bump.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    outcome: Option<RunOutcome>,
+    /// Provenance of the module that ran, when it carries one: the log
+    /// references the exact source it was recorded from. Like `outcome`,
+    /// never compared by [`ReplayEngine::verify`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    provenance: Option<Provenance>,
+}
+
+/// Summary of a finished run stored alongside its events, so a log can be
+/// reported on (`boruna replay report`) without re-running it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunOutcome {
+    /// VM steps executed.
+    pub steps: u64,
+    /// The entry function's result, when the run completed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    /// The runtime error, when it did not.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Capability calls refused by policy; these never appear as events.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub denials: Vec<Denial>,
+    /// [`KvStore::snapshot_hash`](crate::kv_store::KvStore::snapshot_hash)
+    /// of the store file after the run, when one was attached.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kv_snapshot: Option<String>,
 }
 
 fn default_version() -> u32 {
@@ -87,6 +145,8 @@ impl EventLog {
         EventLog {
             version: EVENT_LOG_VERSION,
             events: Vec::new(),
+            outcome: None,
+            provenance: None,
         }
     }
 
@@ -116,6 +176,28 @@ impl EventLog {
         });
     }
 
+    pub fn log_actor_checkpoint(&mut self, actor_id: u64, state_hash: &str) {
+        self.events.push(Event::ActorCheckpoint {
+            actor_id,
+            state_hash: state_hash.to_string(),
+        });
+    }
+
+    pub fn log_actor_restore(&mut self, actor_id: u64, state_hash: &str) {
+        self.events.push(Event::ActorRestore {
+            actor_id,
+            state_hash: state_hash.to_string(),
+        });
+    }
+
+    pub fn log_capability_delegation(&mut self, parent: u64, child: u64, policy_hash: &str) {
+        self.events.push(Event::CapabilityDelegation {
+            parent,
+            child,
+            policy_hash: policy_hash.to_string(),
+        });
+    }
+
     pub fn log_message_send(&mut self, from: u64, to: u64, payload: &Value) {
         self.events.push(Event::MessageSend {
             from,
@@ -179,6 +261,24 @@ impl EventLog {
         &self.events
     }
 
+    /// How the recorded run ended, if the producer stored it.
+    pub fn outcome(&self) -> Option<&RunOutcome> {
+        self.outcome.as_ref()
+    }
+
+    pub fn set_outcome(&mut self, outcome: RunOutcome) {
+        self.outcome = Some(outcome);
+    }
+
+    /// Provenance of the module the log was recorded from, if it had one.
+    pub fn provenance(&self) -> Option<&Provenance> {
+        self.provenance.as_ref()
+    }
+
+    pub fn set_provenance(&mut self, provenance: Option<Provenance>) {
+        self.provenance = provenance;
+    }
+
     /// Serialize the log to JSON.
     pub fn to_json(&self) -> Result<String, String> {
         serde_json::to_string_pretty(self).map_err(|e| e.to_string())
@@ -291,6 +391,48 @@ impl ReplayEngine {
 
         ReplayResult::Identical
     }
+
+    /// Verify that a resumed run restored its persistent actors from the
+    /// state `previous` left behind: every `ActorRestore` in `resumed` must
+    /// carry the hash of that actor's last `ActorCheckpoint` in `previous`.
+    pub fn verify_restores(previous: &EventLog, resumed: &EventLog) -> ReplayResult {
+        let mut last = std::collections::BTreeMap::new();
+        for event in previous.events() {
+            if let Event::ActorCheckpoint {
+                actor_id,
+                state_hash,
+            } = event
+            {
+                last.insert(*actor_id, state_hash.as_str());
+            }
+        }
+        for event in resumed.events() {
+            if let Event::ActorRestore {
+                actor_id,
+                state_hash,
+            } = event
+            {
+                match last.get(actor_id) {
+                    Some(expected) if *expected == state_hash => {}
+                    Some(expected) => {
+                        return ReplayResult::Diverged {
+                            reason: format!(
+                                "actor {actor_id} restored {state_hash}, last checkpoint was {expected}"
+                            ),
+                        }
+                    }
+                    None => {
+                        return ReplayResult::Diverged {
+                            reason: format!(
+                                "actor {actor_id} restored {state_hash} with no checkpoint in the previous run"
+                            ),
+                        }
+                    }
+                }
+            }
+        }
+        ReplayResult::Identical
+    }
 }
 
 #[derive(Debug)]
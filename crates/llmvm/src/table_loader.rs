@@ -0,0 +1,319 @@
+//! Table loading behind the `data.read_table` capability.
+//!
+//! `read_table(path)` loads a CSV file (or, with the `parquet` feature, a
+//! `.parquet` file) into a [`Value::Table`]. The [`TableHandler`] serves
+//! those calls and delegates every other capability to a wrapped handler,
+//! like [`KvHandler`](crate::kv_store::KvHandler).
+//!
+//! **CSV.** The first record is the header and names the columns. Each
+//! column's type is inferred from its non-empty cells: `Int` if every
+//! cell parses as one, else `Float` if every cell is a finite float, else
+//! `String`. Empty cells load as `None`. Ragged rows are an error.
+//!
+//! **Limits.** [`TablePolicy`] caps the bytes read and the data rows
+//! loaded. Both are checked while reading, so an oversized file fails
+//! without being loaded whole.
+//!
+//! **Determinism.** The loaded table is the result of a gateway call, so
+//! the [`EventLog`](crate::replay::EventLog) records it in full and replay
+//! serves it without reading the file again.
+
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+use boruna_bytecode::{Capability, Column, Table, Value};
+
+use crate::capability_gateway::{CapabilityHandler, TablePolicy};
+
+/// Load the table at `path`, enforcing `policy`. Files ending in
+/// `.parquet` are read as Parquet; anything else as CSV.
+pub fn load_table(path: &Path, policy: &TablePolicy) -> Result<Table, String> {
+    let meta = fs::metadata(path).map_err(|e| format!("cannot read '{}': {e}", path.display()))?;
+    if meta.len() > policy.max_bytes {
+        return Err(format!(
+            "'{}' is {} bytes, over table_policy.max_bytes ({})",
+            path.display(),
+            meta.len(),
+            policy.max_bytes
+        ));
+    }
+    if path.extension().is_some_and(|ext| ext == "parquet") {
+        return load_parquet(path, policy);
+    }
+    // The file may grow between the size check and the read; read at
+    // most one byte past the limit to notice.
+    let mut data = Vec::new();
+    fs::File::open(path)
+        .and_then(|f| f.take(policy.max_bytes + 1).read_to_end(&mut data))
+        .map_err(|e| format!("cannot read '{}': {e}", path.display()))?;
+    if data.len() as u64 > policy.max_bytes {
+        return Err(format!(
+            "'{}' is over table_policy.max_bytes ({})",
+            path.display(),
+            policy.max_bytes
+        ));
+    }
+    parse_csv(&data, policy.max_rows).map_err(|e| format!("'{}': {e}", path.display()))
+}
+
+/// Parse CSV `data` with a header row into a table of at most
+/// `max_rows` data rows.
+pub fn parse_csv(data: &[u8], max_rows: u64) -> Result<Table, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(true)
+        .flexible(false)
+        .from_reader(data);
+    let headers = reader
+        .headers()
+        .map_err(|e| format!("invalid CSV header: {e}"))?
+        .clone();
+    let mut cells: Vec<Vec<String>> = vec![Vec::new(); headers.len()];
+    for (i, record) in reader.records().enumerate() {
+        if i as u64 >= max_rows {
+            return Err(format!("more than table_policy.max_rows ({max_rows}) rows"));
+        }
+        let record = record.map_err(|e| format!("invalid CSV: {e}"))?;
+        for (column, cell) in cells.iter_mut().zip(record.iter()) {
+            column.push(cell.to_string());
+        }
+    }
+    let columns = headers
+        .iter()
+        .zip(cells)
+        .map(|(name, raw)| Column {
+            name: name.to_string(),
+            values: infer_column(raw),
+        })
+        .collect();
+    Table::new(columns)
+}
+
+/// Type one column's cells: all-`Int`, else all-finite-`Float`, else
+/// `String`. Empty cells are `None` whatever the column type.
+fn infer_column(raw: Vec<String>) -> Vec<Value> {
+    let present = || raw.iter().filter(|c| !c.is_empty());
+    let all_int = present().all(|c| c.parse::<i64>().is_ok());
+    let all_float = present().all(|c| c.parse::<f64>().is_ok_and(f64::is_finite));
+    raw.into_iter()
+        .map(|cell| {
+            if cell.is_empty() {
+                Value::None
+            } else if all_int {
+                Value::Int(cell.parse().expect("checked above"))
+            } else if all_float {
+                Value::Float(cell.parse().expect("checked above"))
+            } else {
+                Value::String(cell)
+            }
+        })
+        .collect()
+}
+
+#[cfg(feature = "parquet")]
+fn load_parquet(path: &Path, policy: &TablePolicy) -> Result<Table, String> {
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+    use parquet::record::Field;
+
+    let fail = |e: parquet::errors::ParquetError| format!("'{}': {e}", path.display());
+    let file =
+        fs::File::open(path).map_err(|e| format!("cannot read '{}': {e}", path.display()))?;
+    let reader = SerializedFileReader::new(file).map_err(fail)?;
+    let rows = reader.metadata().file_metadata().num_rows();
+    if rows as u64 > policy.max_rows {
+        return Err(format!(
+            "'{}' has {rows} rows, over table_policy.max_rows ({})",
+            path.display(),
+            policy.max_rows
+        ));
+    }
+    let names: Vec<String> = reader
+        .metadata()
+        .file_metadata()
+        .schema_descr()
+        .root_schema()
+        .get_fields()
+        .iter()
+        .map(|f| f.name().to_string())
+        .collect();
+    let mut columns: Vec<Column> = names
+        .into_iter()
+        .map(|name| Column {
+            name,
+            values: Vec::new(),
+        })
+        .collect();
+    for row in reader.get_row_iter(None).map_err(fail)? {
+        let row = row.map_err(fail)?;
+        for (column, (_, field)) in columns.iter_mut().zip(row.get_column_iter()) {
+            let value = match field {
+                Field::Null => Value::None,
+                Field::Bool(b) => Value::Bool(*b),
+                Field::Byte(n) => Value::Int(i64::from(*n)),
+                Field::Short(n) => Value::Int(i64::from(*n)),
+                Field::Int(n) => Value::Int(i64::from(*n)),
+                Field::Long(n) => Value::Int(*n),
+                Field::UByte(n) => Value::Int(i64::from(*n)),
+                Field::UShort(n) => Value::Int(i64::from(*n)),
+                Field::UInt(n) => Value::Int(i64::from(*n)),
+                Field::Float(f) => Value::Float(f64::from(*f)),
+                Field::Double(f) => Value::Float(*f),
+                Field::Str(s) => Value::String(s.clone()),
+                other => {
+                    return Err(format!(
+                        "'{}': column '{}' has unsupported type ({other})",
+                        path.display(),
+                        column.name
+                    ))
+                }
+            };
+            column.values.push(value);
+        }
+    }
+    Table::new(columns)
+}
+
+#[cfg(not(feature = "parquet"))]
+fn load_parquet(path: &Path, _policy: &TablePolicy) -> Result<Table, String> {
+    Err(format!(
+        "'{}': Parquet support requires the `parquet` feature",
+        path.display()
+    ))
+}
+
+/// Handler that serves `data.read_table` from local files, enforcing a
+/// [`TablePolicy`]. All other capabilities delegate to `inner`.
+pub struct TableHandler {
+    policy: TablePolicy,
+    inner: Box<dyn CapabilityHandler>,
+}
+
+impl TableHandler {
+    pub fn new(policy: TablePolicy, inner: Box<dyn CapabilityHandler>) -> Self {
+        TableHandler { policy, inner }
+    }
+}
+
+impl CapabilityHandler for TableHandler {
+    fn handle(&mut self, cap: &Capability, args: &[Value]) -> Result<Value, String> {
+        if *cap != Capability::DataReadTable {
+            return self.inner.handle(cap, args);
+        }
+        let path = match args.first() {
+            Some(Value::String(s)) => s,
+            Some(other) => {
+                return Err(format!(
+                    "{cap}: path must be a String, got {}",
+                    other.type_name()
+                ))
+            }
+            None => return Err(format!("{cap}: missing path")),
+        };
+        load_table(Path::new(path), &self.policy)
+            .map(Value::Table)
+            .map_err(|e| format!("{cap}: {e}"))
+    }
+
+    fn handles(&self, cap: &Capability) -> bool {
+        matches!(cap, Capability::DataReadTable)
+    }
+
+    fn delegate(&mut self) -> Option<&mut dyn CapabilityHandler> {
+        Some(self.inner.as_mut())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capability_gateway::MockHandler;
+
+    #[test]
+    fn csv_columns_are_typed_from_their_cells() {
+        let t = parse_csv(b"region,units,price,note\neu,3,1.5,\nus,,2,rush\n", 10).unwrap();
+        assert_eq!(t.column_names(), vec!["region", "units", "price", "note"]);
+        assert_eq!(t.num_rows(), 2);
+        let col = |n: &str| t.column(n).unwrap().values.clone();
+        assert_eq!(
+            col("region"),
+            vec![Value::String("eu".into()), Value::String("us".into())]
+        );
+        assert_eq!(col("units"), vec![Value::Int(3), Value::None]);
+        assert_eq!(col("price"), vec![Value::Float(1.5), Value::Float(2.0)]);
+        assert_eq!(col("note"), vec![Value::None, Value::String("rush".into())]);
+
+        let err = parse_csv(b"a,b\n1,2\n3\n", 10).unwrap_err();
+        assert!(err.contains("invalid CSV"), "got: {err}");
+    }
+
+    #[test]
+    fn handler_enforces_row_and_byte_limits() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("orders.csv");
+        fs::write(&path, "id\n1\n2\n3\n").unwrap();
+        let path = Value::String(path.to_string_lossy().into_owned());
+
+        let mut h = TableHandler::new(TablePolicy::default(), Box::new(MockHandler));
+        match h.handle(&Capability::DataReadTable, std::slice::from_ref(&path)) {
+            Ok(Value::Table(t)) => assert_eq!(t.num_rows(), 3),
+            other => panic!("expected a table, got {other:?}"),
+        }
+
+        let rows = TablePolicy {
+            max_rows: 2,
+            ..TablePolicy::default()
+        };
+        let err = TableHandler::new(rows, Box::new(MockHandler))
+            .handle(&Capability::DataReadTable, std::slice::from_ref(&path))
+            .unwrap_err();
+        assert!(err.contains("max_rows"), "got: {err}");
+
+        let bytes = TablePolicy {
+            max_bytes: 4,
+            ..TablePolicy::default()
+        };
+        let err = TableHandler::new(bytes, Box::new(MockHandler))
+            .handle(&Capability::DataReadTable, &[path])
+            .unwrap_err();
+        assert!(err.contains("max_bytes"), "got: {err}");
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn parquet_columns_load_by_name() {
+        use std::sync::Arc;
+
+        use parquet::data_type::{ByteArray, ByteArrayType, Int64Type};
+        use parquet::file::writer::SerializedFileWriter;
+        use parquet::schema::parser::parse_message_type;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sales.parquet");
+        let schema = parse_message_type(
+            "message sales { required binary region (UTF8); required int64 units; }",
+        )
+        .unwrap();
+        let file = fs::File::create(&path).unwrap();
+        let mut writer =
+            SerializedFileWriter::new(file, Arc::new(schema), Default::default()).unwrap();
+        let mut group = writer.next_row_group().unwrap();
+        let mut col = group.next_column().unwrap().unwrap();
+        col.typed::<ByteArrayType>()
+            .write_batch(&[ByteArray::from("eu"), ByteArray::from("us")], None, None)
+            .unwrap();
+        col.close().unwrap();
+        let mut col = group.next_column().unwrap().unwrap();
+        col.typed::<Int64Type>()
+            .write_batch(&[3, 5], None, None)
+            .unwrap();
+        col.close().unwrap();
+        group.close().unwrap();
+        writer.close().unwrap();
+
+        let t = load_table(&path, &TablePolicy::default()).unwrap();
+        assert_eq!(
+            t.to_string(),
+            r#"Table(region, units)[("eu", 3), ("us", 5)]"#
+        );
+    }
+}
@@ -73,19 +73,31 @@ enum HandleState {
 /// second subscriber. Document and enforce in the CLI integration that
 /// `init` runs exactly once during startup.
 pub fn init() -> Result<TelemetryHandle, String> {
-    let endpoint = match env::var(OTLP_ENDPOINT_ENV) {
-        Ok(v) if !v.is_empty() => v,
-        _ => {
+    let endpoint = env::var(OTLP_ENDPOINT_ENV).ok();
+    let service_name = env::var(SERVICE_NAME_ENV).ok();
+    init_with(endpoint.as_deref(), service_name.as_deref())
+}
+
+/// [`init`] with the endpoint and service name supplied by the caller
+/// rather than read from the environment — the CLI resolves them from
+/// `boruna.toml` as well. `None` or empty behaves like an unset variable.
+pub fn init_with(
+    endpoint: Option<&str>,
+    service_name: Option<&str>,
+) -> Result<TelemetryHandle, String> {
+    let endpoint = match endpoint.filter(|v| !v.is_empty()) {
+        Some(v) => v.to_string(),
+        None => {
             return Ok(TelemetryHandle {
                 state: HandleState::Disabled,
             })
         }
     };
 
-    let service_name = env::var(SERVICE_NAME_ENV)
-        .ok()
+    let service_name = service_name
         .filter(|s| !s.is_empty())
-        .unwrap_or_else(|| DEFAULT_SERVICE_NAME.to_string());
+        .unwrap_or(DEFAULT_SERVICE_NAME)
+        .to_string();
 
     // Set up the global OTel propagator so trace context can flow if anyone
     // ever wires Boruna into a parent trace. Cheap; no effect when unused.
@@ -4,6 +4,7 @@ mod tests {
     use crate::capability_gateway::*;
     use crate::error::VmError;
     use crate::replay::*;
+    use crate::trace::TraceSampling;
     use crate::vm::Vm;
     use boruna_bytecode::*;
 
@@ -242,6 +243,60 @@ mod tests {
         assert!(vm.run().is_err());
     }
 
+    #[test]
+    fn test_budget_ledger_is_shared_between_vms() {
+        use boruna_budget::{Budget, BudgetLedger, Resource};
+
+        // Two time.now calls per run.
+        let module = || {
+            simple_module(
+                vec![Op::CapCall(5, 0), Op::Pop, Op::CapCall(5, 0), Op::Ret],
+                vec![],
+            )
+        };
+        let ledger = BudgetLedger::new(Budget {
+            max_calls: Some(3),
+            ..Budget::default()
+        });
+        let mut first = Vm::new(module(), CapabilityGateway::new(Policy::allow_all()));
+        first.set_budget_ledger(ledger.clone());
+        first.run().unwrap();
+        assert_eq!(ledger.run_usage().capability_calls, 2);
+        assert!(ledger.run_usage().steps > 0);
+
+        let mut second = Vm::new(module(), CapabilityGateway::new(Policy::allow_all()));
+        second.set_budget_ledger(ledger.clone());
+        match second.run() {
+            Err(VmError::RunBudgetExhausted(e)) => {
+                assert_eq!(e.resource, Resource::CapabilityCalls);
+                assert_eq!((e.used, e.limit), (3, 3));
+            }
+            other => panic!("expected RunBudgetExhausted, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_budget_ledger_steps_stop_the_run() {
+        use boruna_budget::{Budget, BudgetLedger, Resource};
+
+        let module = simple_module(
+            vec![Op::PushConst(0), Op::Pop, Op::Jmp(0)],
+            vec![Value::Int(0)],
+        );
+        let ledger = BudgetLedger::new(Budget {
+            max_steps: Some(50),
+            ..Budget::default()
+        });
+        let mut vm = Vm::new(module, CapabilityGateway::new(Policy::allow_all()));
+        vm.set_budget_ledger(ledger.clone());
+        match vm.run() {
+            Err(VmError::RunBudgetExhausted(e)) => assert_eq!(e.resource, Resource::Steps),
+            other => panic!("expected RunBudgetExhausted, got {other:?}"),
+        }
+        // The refused debit is not recorded.
+        assert_eq!(ledger.run_usage().steps, 0);
+    }
+
     // ── 0.4-S5: capability span emission ──
 
     /// Test helper: a span-capture layer keyed by span Id (proper matching,
@@ -486,6 +541,550 @@ mod tests {
         }
     }
 
+    fn signed_policy(trusted: &[&str]) -> Policy {
+        let mut policy = Policy::allow_all();
+        policy.require_signed_modules = true;
+        policy.trusted_module_keys = trusted.iter().map(|k| k.to_string()).collect();
+        policy
+    }
+
+    #[test]
+    fn test_signed_module_runs_under_trusted_key() {
+        let mut module = simple_module(vec![Op::PushConst(0), Op::Ret], vec![Value::Int(7)]);
+        module.sign(&[7u8; 32]).unwrap();
+        let key = module.signature.as_ref().unwrap().public_key.clone();
+        let mut vm = Vm::new(module, CapabilityGateway::new(signed_policy(&[&key])));
+        assert_eq!(vm.run().unwrap(), Value::Int(7));
+    }
+
+    #[test]
+    fn test_untrusted_modules_are_refused() {
+        let unsigned = simple_module(vec![Op::PushConst(0), Op::Ret], vec![Value::Int(7)]);
+        let mut signed = unsigned.clone();
+        signed.sign(&[7u8; 32]).unwrap();
+        let trusted = signed.signature.as_ref().unwrap().public_key.clone();
+
+        // Tampering after signing invalidates the signature.
+        let mut tampered = signed.clone();
+        tampered.constants[0] = Value::Int(8);
+
+        let mut other = unsigned.clone();
+        other.sign(&[9u8; 32]).unwrap();
+
+        for module in [unsigned.clone(), tampered, other] {
+            let mut vm = Vm::new(module, CapabilityGateway::new(signed_policy(&[&trusted])));
+            assert!(matches!(vm.run(), Err(VmError::UntrustedModule(_))));
+        }
+
+        // Bounded execution enforces the same check.
+        let mut vm = Vm::new(
+            unsigned.clone(),
+            CapabilityGateway::new(signed_policy(&[&trusted])),
+        );
+        assert!(matches!(
+            vm.set_entry_function(0),
+            Err(VmError::UntrustedModule(_))
+        ));
+
+        // Without the flag, unsigned modules run as before.
+        let mut vm = Vm::new(unsigned, CapabilityGateway::new(Policy::allow_all()));
+        assert_eq!(vm.run().unwrap(), Value::Int(7));
+    }
+
+    #[test]
+    fn test_capability_skew_refused_unless_allowed() {
+        let mut module = simple_module(vec![Op::PushConst(0), Op::Ret], vec![Value::Int(1)]);
+        module.record_capability_versions();
+        assert_eq!(run_module(module.clone()).unwrap(), Value::Int(1));
+
+        module
+            .capability_versions
+            .insert("net.fetch".into(), "0".into());
+        let err = run_module(module.clone()).unwrap_err();
+        match err {
+            VmError::CapabilitySkew(msg) => assert!(msg.contains("net.fetch v0"), "{msg}"),
+            other => panic!("expected CapabilitySkew, got {other:?}"),
+        }
+
+        let mut vm = Vm::new(module, CapabilityGateway::new(Policy::allow_all()));
+        vm.set_allow_capability_skew(true);
+        assert_eq!(vm.run().unwrap(), Value::Int(1));
+    }
+
+    fn dec(s: &str) -> Value {
+        Value::Decimal(boruna_bytecode::Decimal::parse(s).unwrap())
+    }
+
+    #[test]
+    fn test_decimal_arithmetic_and_ordering() {
+        let module = simple_module(
+            vec![
+                Op::PushConst(0),
+                Op::PushConst(1),
+                Op::Mul,
+                Op::PushConst(2),
+                Op::Sub,
+                Op::Ret,
+            ],
+            vec![dec("19.99"), dec("3"), dec("0.47")],
+        );
+        assert_eq!(run_module(module).unwrap(), dec("59.5"));
+
+        let module = simple_module(
+            vec![Op::PushConst(0), Op::PushConst(1), Op::Lt, Op::Ret],
+            vec![dec("0.1"), dec("0.25")],
+        );
+        assert_eq!(run_module(module).unwrap(), Value::Bool(true));
+
+        let module = simple_module(
+            vec![Op::PushConst(0), Op::PushConst(1), Op::Div, Op::Ret],
+            vec![dec("1"), dec("0")],
+        );
+        assert!(matches!(run_module(module), Err(VmError::DivisionByZero)));
+
+        // No implicit Decimal/Float mixing.
+        let module = simple_module(
+            vec![Op::PushConst(0), Op::PushConst(1), Op::Add, Op::Ret],
+            vec![dec("1"), Value::Float(1.0)],
+        );
+        assert!(matches!(run_module(module), Err(VmError::TypeError { .. })));
+    }
+
+    #[test]
+    fn test_decimal_conversion_opcodes() {
+        let module = simple_module(
+            vec![
+                Op::PushConst(0),
+                Op::IntToDecimal,
+                Op::PushConst(1),
+                Op::DecimalParse,
+                Op::Ret,
+            ],
+            vec![Value::Int(2), Value::String("1.005".into())],
+        );
+        assert_eq!(
+            run_module(module).unwrap(),
+            Value::Some(Box::new(dec("1.005")))
+        );
+
+        let module = simple_module(
+            vec![
+                Op::PushConst(0),
+                Op::IntToDecimal,
+                Op::DecimalToString,
+                Op::Ret,
+            ],
+            vec![Value::Int(-3)],
+        );
+        assert_eq!(run_module(module).unwrap(), Value::String("-3".into()));
+    }
+
+    fn big(s: &str) -> Value {
+        Value::BigInt(boruna_bytecode::BigInt::parse(s).unwrap())
+    }
+
+    #[test]
+    fn test_bigint_arithmetic_does_not_wrap() {
+        // Int overflow still traps; promotion to BigInt is explicit.
+        let module = simple_module(
+            vec![Op::PushConst(0), Op::PushConst(1), Op::Add, Op::Ret],
+            vec![Value::Int(i64::MAX), Value::Int(1)],
+        );
+        assert!(matches!(
+            run_module(module),
+            Err(VmError::ArithmeticOverflow("addition"))
+        ));
+
+        let module = simple_module(
+            vec![
+                Op::PushConst(0),
+                Op::IntToBigInt,
+                Op::PushConst(0),
+                Op::IntToBigInt,
+                Op::Mul,
+                Op::PushConst(1),
+                Op::Mod,
+                Op::Ret,
+            ],
+            vec![Value::Int(i64::MAX), big("1000000007")],
+        );
+        // (2^63 - 1)^2 mod 1_000_000_007
+        assert_eq!(run_module(module).unwrap(), big("737564071"));
+
+        let module = simple_module(
+            vec![Op::PushConst(0), Op::PushConst(1), Op::Gt, Op::Ret],
+            vec![big("9223372036854775808"), big("-1")],
+        );
+        assert_eq!(run_module(module).unwrap(), Value::Bool(true));
+
+        let module = simple_module(
+            vec![Op::PushConst(0), Op::PushConst(1), Op::Div, Op::Ret],
+            vec![big("1"), big("0")],
+        );
+        assert!(matches!(run_module(module), Err(VmError::DivisionByZero)));
+
+        // No implicit Int/BigInt mixing.
+        let module = simple_module(
+            vec![Op::PushConst(0), Op::PushConst(1), Op::Add, Op::Ret],
+            vec![big("1"), Value::Int(1)],
+        );
+        assert!(matches!(run_module(module), Err(VmError::TypeError { .. })));
+    }
+
+    #[test]
+    fn test_bigint_conversion_opcodes() {
+        let module = simple_module(
+            vec![
+                Op::PushConst(0),
+                Op::BigIntParse,
+                Op::PushConst(1),
+                Op::BigIntParse,
+                Op::MakeList(2),
+                Op::Ret,
+            ],
+            vec![
+                Value::String("-18446744073709551616".into()),
+                Value::String("12abc".into()),
+            ],
+        );
+        assert_eq!(
+            run_module(module).unwrap(),
+            Value::List(vec![
+                Value::Some(Box::new(big("-18446744073709551616"))),
+                Value::None,
+            ])
+        );
+
+        let module = simple_module(
+            vec![
+                Op::PushConst(0),
+                Op::BigIntToInt,
+                Op::PushConst(1),
+                Op::BigIntToInt,
+                Op::PushConst(1),
+                Op::BigIntToString,
+                Op::MakeList(3),
+                Op::Ret,
+            ],
+            vec![big("42"), big("9223372036854775808")],
+        );
+        assert_eq!(
+            run_module(module).unwrap(),
+            Value::List(vec![
+                Value::Some(Box::new(Value::Int(42))),
+                Value::None,
+                Value::String("9223372036854775808".into()),
+            ])
+        );
+
+        let module = simple_module(
+            vec![
+                Op::PushConst(0),
+                Op::PushConst(1),
+                Op::PushConst(2),
+                Op::BigIntPowMod,
+                Op::Ret,
+            ],
+            vec![big("4"), big("13"), big("497")],
+        );
+        assert_eq!(run_module(module).unwrap(), big("445"));
+    }
+
+    #[test]
+    fn test_bigint_pow_mod_charges_by_operand_size() {
+        let pow_mod = |base: &str, exp: &str, modulus: &str, max_steps: u64| {
+            let module = simple_module(
+                vec![
+                    Op::PushConst(0),
+                    Op::PushConst(1),
+                    Op::PushConst(2),
+                    Op::BigIntPowMod,
+                    Op::Ret,
+                ],
+                vec![big(base), big(exp), big(modulus)],
+            );
+            let mut vm = Vm::new(module, CapabilityGateway::new(Policy::allow_all()));
+            vm.set_max_steps(max_steps);
+            vm.run()
+        };
+        // Small operands fit in a handful of steps.
+        assert_eq!(pow_mod("4", "13", "497", 10).unwrap(), big("445"));
+
+        // ~20,000-bit operands: refused before any of the work is done.
+        let huge = "9".repeat(6_000);
+        assert!(matches!(
+            pow_mod(&huge, &huge, &huge, 1_000_000),
+            Err(VmError::ExecutionLimitExceeded(1_000_000))
+        ));
+    }
+
+    #[test]
+    fn test_bigint_arithmetic_charges_by_operand_size() {
+        let arith = |op: Op, a: &str, b: &str, max_steps: u64| {
+            let module = simple_module(
+                vec![Op::PushConst(0), Op::PushConst(1), op, Op::Ret],
+                vec![big(a), big(b)],
+            );
+            let mut vm = Vm::new(module, CapabilityGateway::new(Policy::allow_all()));
+            vm.set_max_steps(max_steps);
+            vm.run()
+        };
+        // Small operands cost no more than Int arithmetic.
+        assert_eq!(arith(Op::Mul, "6", "7", 10).unwrap(), big("42"));
+
+        // ~30,000-bit operands: the product costs thousands of steps, the
+        // sum only a handful.
+        let huge = "9".repeat(9_000);
+        assert!(arith(Op::Add, &huge, &huge, 100).is_ok());
+        for op in [Op::Mul, Op::Div, Op::Mod] {
+            assert!(matches!(
+                arith(op, &huge, &huge, 1_000),
+                Err(VmError::ExecutionLimitExceeded(1_000))
+            ));
+        }
+        assert!(arith(Op::Mul, &huge, &huge, 10_000).is_ok());
+    }
+
+    #[test]
+    fn test_json_parse_and_stringify_are_canonical() {
+        let module = simple_module(
+            vec![Op::PushConst(0), Op::JsonParse, Op::Ret],
+            vec![Value::String(
+                r#"{"z": [1, 2.5, null], "a": {"ok": true}, "big": 18446744073709551615}"#.into(),
+            )],
+        );
+        let doc = match run_module(module).unwrap() {
+            Value::Ok(doc) => *doc,
+            other => panic!("expected Ok, got {other:?}"),
+        };
+        let Value::Map(fields) = &doc else {
+            panic!("expected Map, got {doc:?}");
+        };
+        assert_eq!(
+            fields["z"],
+            Value::List(vec![Value::Int(1), Value::Float(2.5), Value::None])
+        );
+        assert_eq!(fields["big"], big("18446744073709551615"));
+
+        let module = simple_module(
+            vec![Op::PushConst(0), Op::JsonStringify, Op::Ret],
+            vec![doc],
+        );
+        assert_eq!(
+            run_module(module).unwrap(),
+            Value::String(
+                r#"{"a":{"ok":true},"big":18446744073709551615,"z":[1,2.5,null]}"#.into()
+            )
+        );
+
+        let module = simple_module(
+            vec![Op::PushConst(0), Op::JsonParse, Op::Ret],
+            vec![Value::String("{\"a\": ".into())],
+        );
+        assert!(matches!(run_module(module).unwrap(), Value::Err(_)));
+
+        let module = simple_module(
+            vec![Op::PushConst(0), Op::JsonStringify, Op::Ret],
+            vec![Value::FnRef(0)],
+        );
+        assert!(matches!(run_module(module), Err(VmError::TypeError { .. })));
+    }
+
+    #[test]
+    fn test_pattern_match_and_find() {
+        use crate::pattern::Pattern;
+        let matches = |p: &str, t: &str| {
+            Pattern::compile(p)
+                .unwrap()
+                .is_match(t, u64::MAX)
+                .unwrap()
+                .0
+        };
+        let find = |p: &str, t: &str| {
+            let (span, _) = Pattern::compile(p).unwrap().find(t, u64::MAX).unwrap();
+            span.map(|(s, e)| t[s..e].to_string())
+        };
+        assert!(matches(r"^\d{3}-\d{4}$", "555-1234"));
+        assert!(!matches(r"^\d{3}-\d{4}$", "5555-1234"));
+        assert!(matches(
+            r"^[\w.+-]+@[\w-]+(\.[\w-]+)+$",
+            "a.b+c@mail.example.org"
+        ));
+        assert!(!matches(r"^[\w.+-]+@[\w-]+(\.[\w-]+)+$", "a@b"));
+        assert!(matches("colou?r", "my colour"));
+        assert!(!matches("^a.c$", "a\nc"));
+        assert!(matches("", "anything"));
+
+        // Leftmost, then longest.
+        assert_eq!(find("a+", "baaac").as_deref(), Some("aaa"));
+        assert_eq!(
+            find("cat|category", "category").as_deref(),
+            Some("category")
+        );
+        assert_eq!(
+            find("[A-Za-z_][A-Za-z0-9_]*", "  foo_1 bar").as_deref(),
+            Some("foo_1")
+        );
+        assert_eq!(find("é+", "caféé!").as_deref(), Some("éé"));
+        assert_eq!(find("x", "abc"), None);
+        assert_eq!(find("b*", "abc").as_deref(), Some(""));
+    }
+
+    #[test]
+    fn test_pattern_cost_is_bounded_and_deterministic() {
+        use crate::pattern::{Pattern, PatternError};
+        // Catastrophic for a backtracking engine; linear here.
+        let p = Pattern::compile("(a*)*b").unwrap();
+        let text = "a".repeat(2_000);
+        let (hit, steps) = p.is_match(&text, u64::MAX).unwrap();
+        assert!(!hit);
+        assert!(steps < 10 * p.len() as u64 * 2_000, "{steps}");
+        assert_eq!(p.is_match(&text, u64::MAX).unwrap().1, steps);
+        assert_eq!(p.is_match(&text, steps - 1), Err(PatternError::StepLimit));
+
+        for bad in [
+            "(", "a)", "a**", "*a", "a+?", "(?=x)", r"\1", "a{101}", "a{3,2}", "[z-a]", "[abc",
+        ] {
+            assert!(
+                matches!(Pattern::compile(bad), Err(PatternError::Syntax(_))),
+                "{bad:?}"
+            );
+        }
+        assert!(Pattern::compile(&"a{100}".repeat(50)).is_err());
+    }
+
+    #[test]
+    fn test_str_match_opcodes_charge_vm_steps() {
+        let program = |op: Op, pattern: &str, text: &str| {
+            simple_module(
+                vec![Op::PushConst(0), Op::PushConst(1), op, Op::Ret],
+                vec![Value::String(pattern.into()), Value::String(text.into())],
+            )
+        };
+        assert_eq!(
+            run_module(program(Op::StrMatch, r"^\d+$", "12345")).unwrap(),
+            Value::Bool(true)
+        );
+        assert_eq!(
+            run_module(program(Op::StrFind, r"\d+", "order 66 shipped")).unwrap(),
+            Value::Some(Box::new(Value::String("66".into())))
+        );
+        assert_eq!(
+            run_module(program(Op::StrFind, r"\d+", "none")).unwrap(),
+            Value::None
+        );
+        assert!(matches!(
+            run_module(program(Op::StrMatch, "(", "x")),
+            Err(VmError::InvalidPattern(_))
+        ));
+
+        let text = "a".repeat(10_000);
+        let mut vm = Vm::new(
+            program(Op::StrMatch, "(a|aa)*b", &text),
+            CapabilityGateway::new(Policy::allow_all()),
+        );
+        vm.set_max_steps(1_000);
+        assert!(matches!(
+            vm.run(),
+            Err(VmError::ExecutionLimitExceeded(1_000))
+        ));
+
+        let mut vm = Vm::new(
+            program(Op::StrMatch, "a+$", &text),
+            CapabilityGateway::new(Policy::allow_all()),
+        );
+        assert_eq!(vm.run().unwrap(), Value::Bool(true));
+        assert!(vm.step_count() > 10_000, "{}", vm.step_count());
+    }
+
+    #[test]
+    fn test_table_opcodes_filter_then_aggregate() {
+        use boruna_bytecode::{Column, Table};
+        let table = Table::new(vec![
+            Column {
+                name: "region".into(),
+                values: vec![
+                    Value::String("eu".into()),
+                    Value::String("us".into()),
+                    Value::String("eu".into()),
+                ],
+            },
+            Column {
+                name: "units".into(),
+                values: vec![Value::Int(3), Value::Int(5), Value::Int(4)],
+            },
+        ])
+        .unwrap();
+        let s = |v: &str| Value::String(v.into());
+        // table_aggregate(table_filter(t, "units", ">", 3), ["region"], "units", "sum")
+        let module = simple_module(
+            vec![
+                Op::PushConst(0),
+                Op::PushConst(1),
+                Op::PushConst(2),
+                Op::PushConst(3),
+                Op::TableFilter,
+                Op::PushConst(4),
+                Op::PushConst(1),
+                Op::PushConst(5),
+                Op::TableAggregate,
+                Op::Ret,
+            ],
+            vec![
+                Value::Table(table),
+                s("units"),
+                s(">"),
+                Value::Int(3),
+                Value::List(vec![s("region")]),
+                s("sum"),
+            ],
+        );
+        let mut vm = Vm::new(module.clone(), CapabilityGateway::new(Policy::allow_all()));
+        match vm.run().unwrap() {
+            Value::Table(t) => assert_eq!(
+                t.to_string(),
+                r#"Table(region, sum_units)[("eu", 4), ("us", 5)]"#
+            ),
+            other => panic!("expected a table, got {other:?}"),
+        }
+        // The loop's 11 steps, plus 3 rows filtered and 2 aggregated.
+        assert_eq!(vm.step_count(), 11 + 3 + 2);
+
+        let mut bad = module;
+        bad.constants[2] = s("~=");
+        assert!(matches!(
+            run_module(bad),
+            Err(VmError::TableError(msg)) if msg.contains("unknown comparison")
+        ));
+    }
+
+    #[test]
+    fn test_strict_determinism_rejects_non_finite_floats() {
+        let overflow = || {
+            simple_module(
+                vec![Op::PushConst(0), Op::PushConst(0), Op::Mul, Op::Ret],
+                vec![Value::Float(1e200)],
+            )
+        };
+        // Off by default: IEEE semantics.
+        assert_eq!(run_module(overflow()).unwrap(), Value::Float(f64::INFINITY));
+
+        let mut vm = Vm::new(overflow(), CapabilityGateway::new(Policy::allow_all()));
+        vm.set_strict_determinism(true);
+        assert!(matches!(
+            vm.run(),
+            Err(VmError::NonFiniteFloat("multiplication"))
+        ));
+
+        let parse_nan = simple_module(
+            vec![Op::PushConst(0), Op::FloatParse, Op::Ret],
+            vec![Value::String("NaN".into())],
+        );
+        let mut vm = Vm::new(parse_nan, CapabilityGateway::new(Policy::allow_all()));
+        vm.set_strict_determinism(true);
+        assert!(matches!(vm.run(), Err(VmError::NonFiniteFloat(_))));
+    }
+
     #[test]
     fn test_wall_time_limit_unset_does_not_fire() {
         // Sanity: a short program runs fine without any wall-clock limit set.
@@ -543,6 +1142,159 @@ mod tests {
         assert!(vm.run().is_err());
     }
 
+    #[test]
+    fn test_dry_run_records_would_be_decisions() {
+        let module = simple_module(
+            vec![
+                Op::CapCall(0, 0), // net.fetch — denied
+                Op::Pop,
+                Op::CapCall(5, 0), // time.now — allowed
+                Op::Pop,
+                Op::CapCall(5, 0), // time.now again — over budget
+                Op::Ret,
+            ],
+            vec![],
+        );
+        let mut policy = Policy::deny_all();
+        policy.allow(&Capability::TimeNow, 1);
+        policy.mode = PolicyMode::DryRun;
+        let mut vm = Vm::new(module, CapabilityGateway::new(policy));
+        // Nothing fails: every call is answered by the mock handler.
+        assert_eq!(vm.run().unwrap(), Value::Int(1700000000));
+
+        let decisions: Vec<_> = vm
+            .gateway()
+            .transcript()
+            .iter()
+            .map(|e| (e.seq, e.capability.as_str(), e.decision))
+            .collect();
+        assert_eq!(
+            decisions,
+            vec![
+                (1, "net.fetch", PolicyDecision::Deny),
+                (2, "time.now", PolicyDecision::Allow),
+                (3, "time.now", PolicyDecision::BudgetExceeded),
+            ]
+        );
+        assert_eq!(vm.event_log().events().len(), 6);
+    }
+
+    #[test]
+    fn test_event_log_references_module_provenance() {
+        let mut module = simple_module(vec![Op::PushConst(0), Op::Ret], vec![Value::Int(1)]);
+        let provenance = Provenance::new("boruna-compiler 0.0.0", "fn main() -> Int { 1 }");
+        module.provenance = Some(provenance.clone());
+        let mut vm = Vm::new(module, CapabilityGateway::new(Policy::allow_all()));
+        vm.run().unwrap();
+
+        let log = vm.event_log();
+        assert_eq!(log.provenance(), Some(&provenance));
+        let restored = EventLog::from_json(&log.to_json().unwrap()).unwrap();
+        assert_eq!(restored.provenance(), Some(&provenance));
+        // Provenance identifies the build; it does not take part in replay.
+        let mut stripped = restored.clone();
+        stripped.set_provenance(None);
+        assert!(matches!(
+            ReplayEngine::verify_full(log, &stripped),
+            ReplayResult::Identical
+        ));
+    }
+
+    #[test]
+    fn test_enforced_denials_are_kept_off_the_event_log() {
+        let module = simple_module(
+            vec![
+                Op::CapCall(5, 0), // time.now — allowed
+                Op::Pop,
+                Op::CapCall(0, 0), // net.fetch — denied
+                Op::Ret,
+            ],
+            vec![],
+        );
+        let mut policy = Policy::deny_all();
+        policy.allow(&Capability::TimeNow, 0);
+        let mut vm = Vm::new(module, CapabilityGateway::new(policy));
+        assert!(matches!(vm.run(), Err(VmError::CapabilityDenied(_))));
+
+        let denials = vm.gateway().denials();
+        assert_eq!(denials.len(), 1);
+        assert_eq!(denials[0].capability, "net.fetch");
+        assert_eq!(denials[0].decision, PolicyDecision::Deny);
+        // Only the allowed call and its result were logged.
+        assert_eq!(vm.event_log().events().len(), 2);
+    }
+
+    struct CountingPrompter {
+        answer: Option<bool>,
+        asked: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+    }
+
+    impl CapabilityPrompter for CountingPrompter {
+        fn ask(&mut self, _cap: &Capability, _args: &[Value]) -> Option<bool> {
+            self.asked.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            self.answer
+        }
+    }
+
+    fn prompt_policy() -> Policy {
+        let mut policy = Policy::deny_all();
+        policy.rules.insert(
+            "time.now".into(),
+            PolicyRule {
+                allow: false,
+                budget: 0,
+                prompt: true,
+            },
+        );
+        policy
+    }
+
+    fn two_time_calls() -> Module {
+        simple_module(
+            vec![Op::CapCall(5, 0), Op::Pop, Op::CapCall(5, 0), Op::Ret],
+            vec![],
+        )
+    }
+
+    #[test]
+    fn test_prompt_rule_asks_once_per_capability() {
+        let asked = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let prompter = CountingPrompter {
+            answer: Some(true),
+            asked: asked.clone(),
+        };
+        let gateway = CapabilityGateway::new(prompt_policy()).with_prompter(Box::new(prompter));
+        let mut vm = Vm::new(two_time_calls(), gateway);
+        assert!(vm.run().is_ok());
+        assert_eq!(asked.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_prompt_rule_without_answer_applies_allow() {
+        // No prompter installed (non-interactive run): `allow: false` applies.
+        let mut vm = Vm::new(two_time_calls(), CapabilityGateway::new(prompt_policy()));
+        assert!(vm.run().is_err());
+
+        // A prompter that cannot answer falls back the same way.
+        let prompter = CountingPrompter {
+            answer: None,
+            asked: Default::default(),
+        };
+        let gateway = CapabilityGateway::new(prompt_policy()).with_prompter(Box::new(prompter));
+        assert!(Vm::new(two_time_calls(), gateway).run().is_err());
+
+        // A dry run never pauses; it reports the call as `prompt`.
+        let mut policy = prompt_policy();
+        policy.mode = PolicyMode::DryRun;
+        let mut vm = Vm::new(two_time_calls(), CapabilityGateway::new(policy));
+        assert!(vm.run().is_ok());
+        assert!(vm
+            .gateway()
+            .transcript()
+            .iter()
+            .all(|e| e.decision == PolicyDecision::Prompt));
+    }
+
     #[test]
     fn test_emit_ui() {
         let module = simple_module(
@@ -975,25 +1727,96 @@ mod tests {
     }
 
     #[test]
-    fn test_replay_divergence() {
-        let mut log1 = EventLog::new();
-        log1.log_cap_call(&Capability::TimeNow, &[]);
-
-        let mut log2 = EventLog::new();
-        log2.log_cap_call(&Capability::NetFetch, &[Value::String("url".into())]);
-
-        let result = ReplayEngine::verify(&log1, &log2);
-        assert!(matches!(result, ReplayResult::Diverged { .. }));
+    fn test_replay_divergence() {
+        let mut log1 = EventLog::new();
+        log1.log_cap_call(&Capability::TimeNow, &[]);
+
+        let mut log2 = EventLog::new();
+        log2.log_cap_call(&Capability::NetFetch, &[Value::String("url".into())]);
+
+        let result = ReplayEngine::verify(&log1, &log2);
+        assert!(matches!(result, ReplayResult::Diverged { .. }));
+    }
+
+    #[test]
+    fn test_trace_enabled() {
+        let module = simple_module(vec![Op::PushConst(0), Op::Ret], vec![Value::Int(1)]);
+        let gateway = CapabilityGateway::new(Policy::allow_all());
+        let mut vm = Vm::new(module, gateway);
+        vm.trace_enabled = true;
+        vm.run().unwrap();
+        assert!(!vm.trace.is_empty());
+    }
+
+    fn two_calls_module() -> Module {
+        let mut module = Module::new("test");
+        module.constants = vec![Value::Int(10), Value::Int(20)];
+        // main: add(add(10, 20), 20)
+        module.add_function(Function {
+            name: "main".into(),
+            arity: 0,
+            locals: 0,
+            code: vec![
+                Op::PushConst(0),
+                Op::PushConst(1),
+                Op::Call(1, 2),
+                Op::PushConst(1),
+                Op::Call(1, 2),
+                Op::Ret,
+            ],
+            capabilities: vec![],
+            intent: None,
+            match_tables: vec![],
+        });
+        module.add_function(Function {
+            name: "add".into(),
+            arity: 2,
+            locals: 2,
+            code: vec![Op::LoadLocal(0), Op::LoadLocal(1), Op::Add, Op::Ret],
+            capabilities: vec![],
+            intent: None,
+            match_tables: vec![],
+        });
+        module
+    }
+
+    #[test]
+    fn test_trace_boundaries_with_capacity() {
+        let mut vm = Vm::new(
+            two_calls_module(),
+            CapabilityGateway::new(Policy::allow_all()),
+        );
+        vm.trace_enabled = true;
+        vm.set_trace_sampling(TraceSampling::Boundaries);
+        vm.set_trace_capacity(Some(2));
+        assert_eq!(vm.run().unwrap(), Value::Int(50));
+
+        // main Call, add Ret, main Call, add Ret, main Ret — the last two kept.
+        assert_eq!(vm.trace.len(), 2);
+        assert!(vm.trace[0].starts_with("[add:3] Ret"), "{:?}", vm.trace);
+        assert!(vm.trace[1].starts_with("[main:5] Ret"), "{:?}", vm.trace);
+
+        let summary = vm.trace_summary(10);
+        assert_eq!((summary.recorded, summary.dropped), (5, 3));
+        let top: Vec<_> = summary
+            .top_functions
+            .iter()
+            .map(|f| (f.name.as_str(), f.steps, f.calls))
+            .collect();
+        assert_eq!(top, [("add", 8, 2), ("main", 6, 1)]);
     }
 
     #[test]
-    fn test_trace_enabled() {
-        let module = simple_module(vec![Op::PushConst(0), Op::Ret], vec![Value::Int(1)]);
-        let gateway = CapabilityGateway::new(Policy::allow_all());
-        let mut vm = Vm::new(module, gateway);
+    fn test_trace_every_nth_step() {
+        let mut vm = Vm::new(
+            two_calls_module(),
+            CapabilityGateway::new(Policy::allow_all()),
+        );
         vm.trace_enabled = true;
+        vm.set_trace_sampling(TraceSampling::EveryNth(5));
         vm.run().unwrap();
-        assert!(!vm.trace.is_empty());
+        assert_eq!(vm.trace.len(), 2, "{:?}", vm.trace);
+        assert_eq!(vm.trace_summary(1).top_functions.len(), 1);
     }
 
     // ── List operation tests ──
@@ -1301,7 +2124,7 @@ mod tests {
 
     // ── EventLog version and format stability tests ──
 
-    use crate::replay::EVENT_LOG_VERSION;
+    use crate::replay::{RunOutcome, EVENT_LOG_VERSION};
 
     #[test]
     fn test_event_log_includes_version() {
@@ -1349,9 +2172,29 @@ mod tests {
     }
 
     #[test]
-    fn test_event_log_v2_format_stability() {
+    fn test_event_log_outcome_is_optional() {
+        let mut log = EventLog::new();
+        log.log_cap_call(&Capability::TimeNow, &[]);
+        let without = log.to_json().unwrap();
+        assert!(!without.contains("outcome"), "absent outcome is omitted");
+
+        log.set_outcome(RunOutcome {
+            steps: 7,
+            result: Some(Value::Int(1)),
+            error: None,
+            denials: Vec::new(),
+            kv_snapshot: Some("sha256:00".into()),
+        });
+        let restored = EventLog::from_json(&log.to_json().unwrap()).unwrap();
+        assert_eq!(restored.outcome(), log.outcome());
+        assert_eq!(restored.version(), EVENT_LOG_VERSION);
+    }
+
+    #[test]
+    fn test_event_log_v3_format_stability() {
         // Golden test: lock the JSON format of the current EventLog
-        // (v2 — bumped when ContractCheck was added).
+        // (v3 — bumped when the actor checkpoint and delegation events were
+        // added).
         let mut log = EventLog::new();
         log.log_cap_call(
             &Capability::NetFetch,
@@ -1362,7 +2205,7 @@ mod tests {
         let json = log.to_json().unwrap();
 
         // Must contain version
-        assert!(json.contains("\"version\": 2"), "must have version: 2");
+        assert!(json.contains("\"version\": 3"), "must have version: 3");
         // Must contain events array
         assert!(json.contains("\"events\""), "must have events array");
         // Must contain CapCall variant
@@ -1390,10 +2233,13 @@ mod tests {
         log.log_actor_spawn(1, "worker");
         log.log_message_send(0, 1, &Value::String("hello".into()));
         log.log_ui_emit(&Value::String("tree".into()));
+        log.log_actor_checkpoint(1, "sha256:ab");
+        log.log_actor_restore(1, "sha256:ab");
+        log.log_capability_delegation(0, 1, "sha256:cd");
 
         let json = log.to_json().unwrap();
         let restored = EventLog::from_json(&json).unwrap();
-        assert_eq!(restored.events().len(), 5);
+        assert_eq!(restored.events().len(), 8);
 
         // Roundtrip preserves format
         let json2 = restored.to_json().unwrap();
@@ -1465,6 +2311,74 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_snapshot_restore_discards_a_speculative_branch() {
+        // Wait for a message, call time.now, return the message.
+        let module = simple_module(
+            vec![Op::ReceiveMsg, Op::CapCall(5, 0), Op::Pop, Op::Ret],
+            vec![],
+        );
+        let mut vm = Vm::new(module, CapabilityGateway::new(Policy::allow_all()));
+        vm.set_in_actor_context(true);
+        vm.set_entry_function(0).unwrap();
+        assert!(matches!(
+            vm.execute_bounded(100),
+            crate::vm::StepResult::Blocked
+        ));
+        let snapshot = vm.snapshot();
+        let steps = vm.step_count();
+
+        let message = |n| crate::actor::Message {
+            from: 0,
+            payload: Value::Int(n),
+        };
+        let run = |vm: &mut Vm, n| {
+            vm.deliver_message(message(n));
+            match vm.execute_bounded(100) {
+                crate::vm::StepResult::Completed(v) => v,
+                other => panic!("expected Completed, got {other:?}"),
+            }
+        };
+        assert_eq!(run(&mut vm, 1), Value::Int(1));
+        assert_eq!(vm.event_log().events().len(), 2);
+        assert_eq!(vm.gateway().usage()["time.now"], 1);
+
+        vm.restore(&snapshot).unwrap();
+        assert_eq!(vm.step_count(), steps);
+        assert!(vm.event_log().events().is_empty());
+        assert!(vm.gateway().usage().is_empty());
+        assert!(!vm.has_messages());
+
+        // A fork branches off without touching the original.
+        let mut fork = vm.fork(CapabilityGateway::new(Policy::allow_all()));
+        assert_eq!(run(&mut fork, 3), Value::Int(3));
+        assert!(vm.event_log().events().is_empty());
+
+        // The same branch point restores any number of times.
+        assert_eq!(run(&mut vm, 2), Value::Int(2));
+        vm.restore(&snapshot).unwrap();
+        assert_eq!(vm.step_count(), steps);
+    }
+
+    #[test]
+    fn test_restore_rejects_a_snapshot_from_another_module() {
+        let long = simple_module(
+            vec![Op::PushConst(0), Op::PushConst(0), Op::Add, Op::Ret],
+            vec![Value::Int(1)],
+        );
+        let mut vm = Vm::new(long, CapabilityGateway::new(Policy::allow_all()));
+        vm.set_entry_function(0).unwrap();
+        let _ = vm.execute_bounded(3);
+        let snapshot = vm.snapshot();
+
+        let short = simple_module(vec![Op::Ret], vec![]);
+        let mut other = Vm::new(short, CapabilityGateway::new(Policy::allow_all()));
+        assert!(matches!(
+            other.restore(&snapshot),
+            Err(VmError::InvalidIp(_))
+        ));
+    }
+
     #[test]
     fn test_receive_msg_pops_from_mailbox() {
         // ReceiveMsg returns message from mailbox
@@ -1986,6 +2900,255 @@ mod tests {
         assert_eq!(result, Value::Int(123));
     }
 
+    /// `main` sums the messages it receives and returns once the total
+    /// exceeds 20.
+    fn summing_agent() -> Module {
+        simple_module(
+            vec![
+                Op::PushConst(0),
+                Op::StoreLocal(0),
+                Op::ReceiveMsg, // 2: wait for the next message
+                Op::LoadLocal(0),
+                Op::Add,
+                Op::StoreLocal(0),
+                Op::LoadLocal(0),
+                Op::PushConst(1),
+                Op::Gt,
+                Op::JmpIfNot(2),
+                Op::LoadLocal(0),
+                Op::Ret,
+            ],
+            vec![Value::Int(0), Value::Int(20)],
+        )
+    }
+
+    fn run_agent(
+        store: crate::actor_store::ActorStateStore,
+        persistent: bool,
+        messages: &[i64],
+    ) -> (
+        Result<Value, VmError>,
+        EventLog,
+        crate::actor_store::ActorStateStore,
+    ) {
+        let mut system = crate::actor::ActorSystem::new();
+        system.set_state_store(store);
+        if persistent {
+            system.persist_function("main");
+        }
+        system.spawn_root(summing_agent(), CapabilityGateway::new(Policy::allow_all()));
+        for &m in messages {
+            system
+                .send(
+                    0,
+                    crate::actor::Message {
+                        from: 99,
+                        payload: Value::Int(m),
+                    },
+                )
+                .unwrap();
+        }
+        let result = system.run();
+        let log = system.event_log().clone();
+        (result, log, system.take_state_store().unwrap())
+    }
+
+    #[test]
+    fn test_persistent_actor_resumes_from_checkpoint() {
+        let store = crate::actor_store::ActorStateStore::in_memory();
+        let (result, first, store) = run_agent(store, true, &[5, 7]);
+        assert!(matches!(result, Err(VmError::Deadlock)));
+        let checkpoint = store.get(0).expect("root checkpointed");
+        assert!(first.events().iter().any(|e| matches!(
+            e,
+            Event::ActorCheckpoint { actor_id: 0, state_hash } if *state_hash == checkpoint.state_hash
+        )));
+
+        // A new system picks up the running total of 12.
+        let (result, second, store) = run_agent(store, true, &[10]);
+        assert_eq!(result.unwrap(), Value::Int(22));
+        assert!(matches!(
+            ReplayEngine::verify_restores(&first, &second),
+            ReplayResult::Identical
+        ));
+        // The completed actor's checkpoint is gone.
+        assert!(store.is_empty());
+
+        // Restoring against the wrong previous run is caught.
+        assert!(matches!(
+            ReplayEngine::verify_restores(&EventLog::new(), &second),
+            ReplayResult::Diverged { .. }
+        ));
+    }
+
+    #[test]
+    fn test_non_persistent_actor_starts_fresh() {
+        let store = crate::actor_store::ActorStateStore::in_memory();
+        let (_, first, store) = run_agent(store, false, &[5, 7]);
+        assert!(store.is_empty());
+        assert!(!first
+            .events()
+            .iter()
+            .any(|e| matches!(e, Event::ActorCheckpoint { .. })));
+        let (result, _, _) = run_agent(store, false, &[10]);
+        assert!(matches!(result, Err(VmError::Deadlock)));
+    }
+
+    #[test]
+    fn test_persistent_child_resumes_when_spawned_again() {
+        let mut module = Module::new("test");
+        // Function 0: counter — sums messages; past 8, reports to actor 0.
+        module.add_function(Function {
+            name: "counter".into(),
+            arity: 0,
+            locals: 1,
+            code: vec![
+                Op::PushConst(0),
+                Op::StoreLocal(0),
+                Op::ReceiveMsg, // 2
+                Op::LoadLocal(0),
+                Op::Add,
+                Op::StoreLocal(0),
+                Op::LoadLocal(0),
+                Op::PushConst(1),
+                Op::Gt,
+                Op::JmpIfNot(2),
+                Op::PushConst(2),
+                Op::LoadLocal(0),
+                Op::SendMsg,
+                Op::LoadLocal(0),
+                Op::Ret,
+            ],
+            capabilities: vec![],
+            intent: None,
+            match_tables: vec![],
+        });
+        // Function 1: main — spawns counter, sends it 5, returns its report.
+        module.add_function(Function {
+            name: "main".into(),
+            arity: 0,
+            locals: 0,
+            code: vec![
+                Op::SpawnActor(0),
+                Op::PushConst(3),
+                Op::SendMsg,
+                Op::ReceiveMsg,
+                Op::Ret,
+            ],
+            capabilities: vec![],
+            intent: None,
+            match_tables: vec![],
+        });
+        module.constants = vec![
+            Value::Int(0),
+            Value::Int(8),
+            Value::ActorId(0),
+            Value::Int(5),
+        ];
+        module.entry = 1;
+
+        let run = |store| {
+            let mut system = crate::actor::ActorSystem::new();
+            system.set_state_store(store);
+            system.persist_function("counter");
+            system.spawn_root(module.clone(), CapabilityGateway::new(Policy::allow_all()));
+            let result = system.run();
+            (result, system.take_state_store().unwrap())
+        };
+        let (result, store) = run(crate::actor_store::ActorStateStore::in_memory());
+        assert!(matches!(result, Err(VmError::Deadlock)));
+        assert_eq!(store.get(1).map(|c| c.function.as_str()), Some("counter"));
+        // The respawned counter already holds 5: the second 5 takes it past 8.
+        let (result, _) = run(store);
+        assert_eq!(result.unwrap(), Value::Int(10));
+    }
+
+    #[test]
+    fn test_corrupt_checkpoint_fails_restore() {
+        let store = crate::actor_store::ActorStateStore::in_memory();
+        let (_, _, mut store) = run_agent(store, true, &[5]);
+        let mut checkpoint = store.get(0).unwrap().clone();
+        checkpoint.state_hash = format!("sha256:{}", "0".repeat(64));
+        store.save(checkpoint).unwrap();
+        let (result, _, _) = run_agent(store, true, &[10]);
+        assert!(matches!(result, Err(VmError::ActorState(_))), "{result:?}");
+    }
+
+    /// `main` spawns a `fetcher` that calls `net.fetch` and sends the
+    /// response back; `main` returns whatever it receives.
+    fn fetcher_module() -> Module {
+        let mut module = Module::new("test");
+        module.add_function(Function {
+            name: "fetcher".into(),
+            arity: 0,
+            locals: 0,
+            code: vec![
+                Op::PushConst(0),
+                Op::PushConst(1),
+                Op::CapCall(0, 1), // net.fetch
+                Op::SendMsg,
+                Op::PushConst(1),
+                Op::Ret,
+            ],
+            capabilities: vec![Capability::NetFetch],
+            intent: None,
+            match_tables: vec![],
+        });
+        module.add_function(Function {
+            name: "main".into(),
+            arity: 0,
+            locals: 0,
+            code: vec![Op::SpawnActor(0), Op::Pop, Op::ReceiveMsg, Op::Ret],
+            capabilities: vec![],
+            intent: None,
+            match_tables: vec![],
+        });
+        module.constants = vec![
+            Value::ActorId(0),
+            Value::String("https://example.com".into()),
+        ];
+        module.entry = 1;
+        module
+    }
+
+    fn run_fetcher(root: Policy, grant: Option<Policy>) -> (Value, EventLog) {
+        let mut system = crate::actor::ActorSystem::new();
+        if let Some(grant) = grant {
+            system.delegate("fetcher", grant);
+        }
+        system.spawn_root(fetcher_module(), CapabilityGateway::new(root));
+        let result = system.run().unwrap();
+        (result, system.event_log().clone())
+    }
+
+    #[test]
+    fn test_delegated_child_is_denied_what_the_grant_withholds() {
+        let (result, log) = run_fetcher(Policy::allow_all(), None);
+        assert!(matches!(result, Value::String(_)), "{result:?}");
+        assert!(!log
+            .events()
+            .iter()
+            .any(|e| matches!(e, Event::CapabilityDelegation { .. })));
+
+        let mut grant = Policy::allow_all();
+        grant.deny(&Capability::NetFetch);
+        let expected = crate::delegation::policy_hash(&Policy::allow_all().attenuate(&grant));
+        let (result, log) = run_fetcher(Policy::allow_all(), Some(grant));
+        assert!(matches!(result, Value::Err(_)), "{result:?}");
+        assert!(log.events().iter().any(|e| matches!(
+            e,
+            Event::CapabilityDelegation { parent: 0, child: 1, policy_hash } if *policy_hash == expected
+        )));
+    }
+
+    #[test]
+    fn test_delegation_cannot_amplify_the_parent_policy() {
+        let mut root = Policy::allow_all();
+        root.deny(&Capability::NetFetch);
+        let (result, _) = run_fetcher(root, Some(Policy::allow_all()));
+        assert!(matches!(result, Value::Err(_)), "{result:?}");
+    }
+
     #[test]
     fn test_message_delivery_order_deterministic() {
         // Multiple senders to the same target — delivery order is deterministic
@@ -3120,6 +4283,50 @@ mod tests {
         assert_eq!(run_module(module).unwrap(), Value::Bool(true));
     }
 
+    /// `Op::AssertMsg` takes `[cond, msg]` (msg on top): `Unit` when the
+    /// condition holds, else a located `UserAssertion` with the message.
+    #[test]
+    fn test_op_assert_msg_traps_with_message_and_location() {
+        let program = |cond: bool| {
+            simple_module(
+                vec![Op::PushConst(0), Op::PushConst(1), Op::AssertMsg, Op::Ret],
+                vec![Value::Bool(cond), Value::String("balance >= 0".into())],
+            )
+        };
+        assert_eq!(run_module(program(true)).unwrap(), Value::Unit);
+        match run_module(program(false)).unwrap_err() {
+            VmError::UserAssertion {
+                message,
+                function,
+                ip,
+            } => assert_eq!(
+                (message.as_str(), function.as_str(), ip),
+                ("balance >= 0", "main", 2)
+            ),
+            other => panic!("expected UserAssertion, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_op_fail_always_traps_with_distinct_code() {
+        use boruna_errors::BorunaError;
+        let module = simple_module(
+            vec![Op::PushConst(0), Op::Fail, Op::PushConst(1), Op::Ret],
+            vec![Value::String("unreachable state".into()), Value::Int(1)],
+        );
+        let err = run_module(module).unwrap_err();
+        assert!(matches!(
+            &err,
+            VmError::UserFailure { message, ip: 1, .. } if message == "unreachable state"
+        ));
+        let envelope = err.envelope();
+        assert_eq!(
+            (envelope.code.as_str(), envelope.kind.as_str()),
+            ("VM030", "vm.user_failure")
+        );
+        assert_eq!(envelope.data["function"], "main");
+    }
+
     /// Chain of debug calls preserves the value end-to-end.
     #[test]
     fn test_op_debug_chain_preserves_value() {
@@ -3129,4 +4336,227 @@ mod tests {
         );
         assert_eq!(run_module(module).unwrap(), Value::Int(123));
     }
+
+    // --- Capability batching ---
+
+    /// Fails every `fs.read` of a path starting with "bad".
+    struct PickyHandler;
+
+    impl CapabilityHandler for PickyHandler {
+        fn handle(&mut self, _cap: &Capability, args: &[Value]) -> Result<Value, String> {
+            match args.first() {
+                Some(Value::String(p)) if p.starts_with("bad") => Err(format!("no such file: {p}")),
+                Some(Value::String(p)) => Ok(Value::String(format!("contents of {p}"))),
+                _ => Err("missing path".into()),
+            }
+        }
+    }
+
+    fn batch_module(paths: &[&str]) -> Module {
+        let mut code = Vec::new();
+        for i in 0..paths.len() {
+            code.push(Op::PushConst(i as u32));
+            code.push(Op::MakeList(1));
+        }
+        code.push(Op::MakeList(paths.len() as u8));
+        code.push(Op::CapBatch(Capability::FsRead.id()));
+        code.push(Op::Ret);
+        let constants = paths.iter().map(|p| Value::String(p.to_string())).collect();
+        simple_module(code, constants)
+    }
+
+    #[test]
+    fn test_cap_batch_returns_per_item_results_in_order() {
+        let gateway = CapabilityGateway::with_handler(Policy::allow_all(), Box::new(PickyHandler));
+        let mut vm = Vm::new(batch_module(&["a", "bad", "c"]), gateway);
+        let result = vm.run().unwrap();
+        let ok = |s: &str| Value::Ok(Box::new(Value::String(s.into())));
+        assert_eq!(
+            result,
+            Value::List(vec![
+                ok("contents of a"),
+                Value::Err(Box::new(Value::String("no such file: bad".into()))),
+                ok("contents of c"),
+            ])
+        );
+        assert_eq!(vm.gateway().usage()["fs.read"], 3);
+
+        // One CapCall/CapResult pair per item, in item order.
+        let calls: Vec<_> = vm
+            .event_log()
+            .events()
+            .iter()
+            .filter_map(|e| match e {
+                Event::CapCall { args, .. } => Some(args[0].clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(calls, ["a", "bad", "c"].map(|p| Value::String(p.into())));
+
+        // Replaying the recorded results reproduces the batch exactly.
+        let original = vm.event_log().clone();
+        let replay = ReplayHandler::new(original.capability_results());
+        let gateway = CapabilityGateway::with_handler(Policy::allow_all(), Box::new(replay));
+        let mut vm = Vm::new(batch_module(&["a", "bad", "c"]), gateway);
+        assert_eq!(vm.run().unwrap(), result);
+        assert!(matches!(
+            ReplayEngine::verify(&original, vm.event_log()),
+            ReplayResult::Identical
+        ));
+    }
+
+    #[test]
+    fn test_cap_batch_over_budget_is_refused_whole() {
+        let mut policy = Policy::deny_all();
+        policy.allow(&Capability::FsRead, 2);
+        let mut vm = Vm::new(
+            batch_module(&["a", "b", "c"]),
+            CapabilityGateway::new(policy),
+        );
+        assert!(matches!(
+            vm.run(),
+            Err(VmError::CapabilityBudgetExceeded(Capability::FsRead))
+        ));
+        assert!(vm.event_log().events().is_empty());
+        assert_eq!(vm.gateway().denials().len(), 3);
+
+        let mut vm = Vm::new(
+            batch_module(&["a"]),
+            CapabilityGateway::new(Policy::deny_all()),
+        );
+        assert!(matches!(
+            vm.run(),
+            Err(VmError::CapabilityDenied(Capability::FsRead))
+        ));
+    }
+
+    // --- Async capability calls ---
+
+    /// Spawn an `fs.read` of each path, then await them in reverse order.
+    fn spawn_module(paths: &[&str]) -> Module {
+        let mut code = Vec::new();
+        for i in 0..paths.len() as u32 {
+            code.push(Op::PushConst(i));
+            code.push(Op::CapSpawn(Capability::FsRead.id(), 1));
+            code.push(Op::StoreLocal(i));
+        }
+        for i in (0..paths.len() as u32).rev() {
+            code.push(Op::LoadLocal(i));
+            code.push(Op::CapAwait);
+        }
+        code.push(Op::MakeList(paths.len() as u8));
+        code.push(Op::Ret);
+        let constants = paths.iter().map(|p| Value::String(p.to_string())).collect();
+        simple_module(code, constants)
+    }
+
+    fn cap_call_args(log: &EventLog) -> Vec<Value> {
+        log.events()
+            .iter()
+            .filter_map(|e| match e {
+                Event::CapCall { args, .. } => Some(args[0].clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn run_spawned(paths: &[&str], seed: Option<u64>) -> (Value, EventLog) {
+        let gateway = CapabilityGateway::with_handler(Policy::allow_all(), Box::new(PickyHandler));
+        let mut vm = Vm::new(spawn_module(paths), gateway);
+        vm.set_scheduler_seed(seed);
+        let result = vm.run().unwrap();
+        (result, vm.event_log().clone())
+    }
+
+    #[test]
+    fn test_cap_spawn_await_returns_each_calls_result() {
+        let (result, log) = run_spawned(&["a", "b", "c"], None);
+        let contents = |s: &str| Value::String(format!("contents of {s}"));
+        assert_eq!(
+            result,
+            Value::List(vec![contents("c"), contents("b"), contents("a")])
+        );
+        // Without a seed, spawned calls complete in spawn order.
+        assert_eq!(
+            cap_call_args(&log),
+            ["a", "b", "c"].map(|p| Value::String(p.into()))
+        );
+    }
+
+    #[test]
+    fn test_cap_spawn_completion_order_follows_the_seed() {
+        let paths = ["a", "b", "c", "d"];
+        let spawn_order = cap_call_args(&run_spawned(&paths, None).1);
+        let mut reordered = false;
+        for seed in 0..8 {
+            let (result, log) = run_spawned(&paths, Some(seed));
+            let (again, log_again) = run_spawned(&paths, Some(seed));
+            assert_eq!(result, again);
+            assert_eq!(cap_call_args(&log), cap_call_args(&log_again));
+            reordered |= cap_call_args(&log) != spawn_order;
+
+            // Replaying under the recorded seed reproduces the run.
+            let replay = ReplayHandler::new(log.capability_results());
+            let gateway = CapabilityGateway::with_handler(Policy::allow_all(), Box::new(replay));
+            let mut vm = Vm::new(spawn_module(&paths), gateway);
+            vm.set_scheduler_seed(Some(seed));
+            assert_eq!(vm.run().unwrap(), result);
+            assert!(matches!(
+                ReplayEngine::verify(&log, vm.event_log()),
+                ReplayResult::Identical
+            ));
+        }
+        assert!(reordered, "no seed changed the completion order");
+    }
+
+    #[test]
+    fn test_cap_spawn_is_refused_at_spawn_and_handles_await_once() {
+        let mut vm = Vm::new(
+            spawn_module(&["a"]),
+            CapabilityGateway::new(Policy::deny_all()),
+        );
+        assert!(matches!(
+            vm.run(),
+            Err(VmError::CapabilityDenied(Capability::FsRead))
+        ));
+        assert!(vm.event_log().events().is_empty());
+        assert_eq!(vm.gateway().denials().len(), 1);
+
+        let code = vec![
+            Op::PushConst(0),
+            Op::CapSpawn(Capability::FsRead.id(), 1),
+            Op::Dup,
+            Op::CapAwait,
+            Op::Pop,
+            Op::CapAwait,
+            Op::Ret,
+        ];
+        let module = simple_module(code, vec![Value::String("a".into())]);
+        assert!(matches!(
+            run_module(module),
+            Err(VmError::InvalidCapHandle(0))
+        ));
+    }
+
+    #[test]
+    fn vm_errors_carry_stable_codes() {
+        use boruna_errors::BorunaError;
+
+        let module = simple_module(
+            vec![Op::PushConst(0), Op::PushConst(1), Op::Div, Op::Ret],
+            vec![Value::Int(1), Value::Int(0)],
+        );
+        let env = run_module(module).unwrap_err().envelope();
+        assert_eq!(env.code, "VM004");
+        assert_eq!(env.kind, "vm.division_by_zero");
+        assert_eq!(env.message, "division by zero");
+
+        let denied = VmError::CapabilityDenied(Capability::FsRead).envelope();
+        assert_eq!(denied.code, "VM006");
+        assert_eq!(denied.data["capability"], Capability::FsRead.to_string());
+        assert_eq!(
+            VmError::StackUnderflow.error_code().kind,
+            "vm.invalid_bytecode"
+        );
+    }
 }
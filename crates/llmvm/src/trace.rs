@@ -0,0 +1,148 @@
+//! Execution trace sampling and summarization.
+//!
+//! With [`Vm::trace_enabled`](crate::Vm) set, the VM records one line per
+//! executed instruction. That is fine for a hundred-step example and
+//! useless for a ten-million-step run, so the trace can be thinned:
+//!
+//! - [`TraceSampling`] picks which steps are recorded: all of them, every
+//!   Nth, or only the boundaries (calls, returns and capability calls).
+//! - [`Vm::set_trace_capacity`](crate::Vm::set_trace_capacity) keeps only
+//!   the most recent entries, evicting the oldest.
+//!
+//! Whatever is recorded, every step is counted against the function that
+//! executed it, so [`Vm::trace_summary`](crate::Vm::trace_summary) reports
+//! exact per-function step counts for the whole run.
+
+use std::collections::VecDeque;
+
+use boruna_bytecode::{Module, Op};
+use serde::Serialize;
+
+/// Which executed instructions the trace records.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TraceSampling {
+    /// Every instruction.
+    #[default]
+    All,
+    /// Every Nth step (by the VM's step counter).
+    EveryNth(u64),
+    /// Function calls and returns, and capability calls.
+    Boundaries,
+}
+
+impl TraceSampling {
+    fn records(self, step: u64, op: &Op) -> bool {
+        match self {
+            TraceSampling::All => true,
+            TraceSampling::EveryNth(n) => step.is_multiple_of(n.max(1)),
+            TraceSampling::Boundaries => matches!(
+                op,
+                Op::Call(..)
+                    | Op::CallIndirect(_)
+                    | Op::Ret
+                    | Op::CapCall(..)
+                    | Op::CapBatch(_)
+                    | Op::CapSpawn(..)
+                    | Op::CapAwait
+            ),
+        }
+    }
+}
+
+/// Sampling state and per-function counters behind a VM's trace.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Tracer {
+    pub(crate) sampling: TraceSampling,
+    pub(crate) capacity: Option<usize>,
+    /// Steps and calls, indexed by function.
+    counts: Vec<(u64, u64)>,
+    recorded: u64,
+    dropped: u64,
+}
+
+impl Tracer {
+    pub(crate) fn enter(&mut self, func_idx: u32) {
+        self.slot(func_idx).1 += 1;
+    }
+
+    /// Count a step of `func_idx`; `entry` builds the trace line when the
+    /// sampling keeps this step.
+    pub(crate) fn step(
+        &mut self,
+        trace: &mut VecDeque<String>,
+        func_idx: u32,
+        step: u64,
+        op: &Op,
+        entry: impl FnOnce() -> String,
+    ) {
+        self.slot(func_idx).0 += 1;
+        if !self.sampling.records(step, op) {
+            return;
+        }
+        self.recorded += 1;
+        if self.capacity == Some(0) {
+            self.dropped += 1;
+            return;
+        }
+        if self.capacity.is_some_and(|cap| trace.len() >= cap) {
+            trace.pop_front();
+            self.dropped += 1;
+        }
+        trace.push_back(entry());
+    }
+
+    pub(crate) fn summary(&self, module: &Module, total_steps: u64, top: usize) -> TraceSummary {
+        let mut functions: Vec<FunctionSteps> = self
+            .counts
+            .iter()
+            .enumerate()
+            .filter(|(_, (steps, _))| *steps > 0)
+            .map(|(i, &(steps, calls))| FunctionSteps {
+                name: module
+                    .functions
+                    .get(i)
+                    .map_or_else(|| format!("#{i}"), |f| f.name.clone()),
+                steps,
+                calls,
+            })
+            .collect();
+        functions.sort_by(|a, b| b.steps.cmp(&a.steps).then_with(|| a.name.cmp(&b.name)));
+        functions.truncate(top);
+        TraceSummary {
+            total_steps,
+            recorded: self.recorded,
+            dropped: self.dropped,
+            top_functions: functions,
+        }
+    }
+
+    fn slot(&mut self, func_idx: u32) -> &mut (u64, u64) {
+        let i = func_idx as usize;
+        if i >= self.counts.len() {
+            self.counts.resize(i + 1, (0, 0));
+        }
+        &mut self.counts[i]
+    }
+}
+
+/// What a traced run did, independent of how much of it was recorded.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TraceSummary {
+    /// Steps the VM executed.
+    pub total_steps: u64,
+    /// Steps the sampling selected for the trace.
+    pub recorded: u64,
+    /// Selected entries evicted by the trace capacity.
+    pub dropped: u64,
+    /// Functions by steps executed, most first.
+    pub top_functions: Vec<FunctionSteps>,
+}
+
+/// Steps executed in one function's body (callees excluded).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FunctionSteps {
+    pub name: String,
+    pub steps: u64,
+    /// Times the function was entered.
+    pub calls: u64,
+}
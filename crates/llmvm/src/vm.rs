@@ -1,12 +1,19 @@
-use std::collections::VecDeque;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Arc;
 use std::time::Instant;
 
-use boruna_bytecode::{Capability, Module, Op, Value};
+use boruna_budget::{BudgetLedger, Resource};
+use boruna_bytecode::{
+    Aggregate, BigInt, Capability, CompareOp, Decimal, Module, Op, Table, Value,
+};
+use serde::{Deserialize, Serialize};
 
 use crate::actor::Message;
-use crate::capability_gateway::CapabilityGateway;
+use crate::capability_gateway::{CapabilityGateway, GatewayAccounting, PendingCall};
 use crate::error::VmError;
+use crate::pattern::{Pattern, PatternError};
 use crate::replay::EventLog;
+use crate::trace::{TraceSampling, TraceSummary, Tracer};
 
 const MAX_STACK: usize = 4096;
 const MAX_CALL_DEPTH: usize = 256;
@@ -36,7 +43,7 @@ pub struct SpawnRequest {
 }
 
 /// A call frame on the call stack.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 struct CallFrame {
     func_idx: u32,
     ip: usize,
@@ -44,6 +51,54 @@ struct CallFrame {
     locals: Vec<Value>,
 }
 
+/// A VM's execution state between instructions — operand stack, call
+/// frames and globals — as captured by [`Vm::capture_state`]. This is
+/// what a persistent actor's checkpoint stores; the module, gateway and
+/// mailbox are not part of it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VmState {
+    stack: Vec<Value>,
+    frames: Vec<CallFrame>,
+    globals: Vec<Value>,
+}
+
+/// Everything a run can change, as taken by [`Vm::snapshot`]: the
+/// [`VmState`] plus the event log, step count, UI output, mailbox,
+/// outgoing messages and spawns, in-flight capability calls, and the
+/// gateway's usage counters, dry-run transcript and denials. Restoring
+/// one discards whatever the VM did since, so a caller can try a branch —
+/// deliver a candidate message, run it, inspect the outcome — and roll
+/// back.
+///
+/// Outside the snapshot: the capability handler's own effects (a live
+/// `kv.set` stays written, so speculate against a mock or replay
+/// handler), the run [`BudgetLedger`] (steps spent on a discarded branch
+/// stay spent), and the debug trace.
+///
+/// Values are deep-copied into the snapshot and again on each restore;
+/// the snapshot itself is shared, so cloning it to keep one per branch
+/// point costs nothing. Once `Value`'s collections are persistent these
+/// copies become structural sharing with no API change.
+#[derive(Debug, Clone)]
+pub struct VmSnapshot(Arc<SnapshotData>);
+
+#[derive(Debug)]
+struct SnapshotData {
+    state: VmState,
+    event_log: EventLog,
+    step_count: u64,
+    budget_start: u64,
+    ui_output: Vec<Value>,
+    mailbox: VecDeque<Message>,
+    outgoing_messages: Vec<(u64, Value)>,
+    spawn_requests: Vec<SpawnRequest>,
+    next_spawn_id: u64,
+    pending_caps: BTreeMap<u64, PendingCall>,
+    finished_caps: BTreeMap<u64, Result<Value, VmError>>,
+    next_cap_handle: u64,
+    gateway: GatewayAccounting,
+}
+
 /// The Boruna virtual machine.
 pub struct Vm {
     module: Module,
@@ -62,9 +117,11 @@ pub struct Vm {
     start_time: Option<Instant>,
     /// UI tree emitted by EmitUi instructions.
     pub ui_output: Vec<Value>,
-    /// Trace log for debugging.
-    pub trace: Vec<String>,
+    /// Trace log for debugging. Holds the sampled entries, newest last;
+    /// see [`crate::trace`].
+    pub trace: VecDeque<String>,
     pub trace_enabled: bool,
+    tracer: Tracer,
     /// Capability calls made during the current execute_bounded slice.
     /// Drained by `take_last_cap_events` between slices (T-2.2).
     last_cap_events: Vec<&'static str>,
@@ -99,25 +156,49 @@ pub struct Vm {
     /// `boruna_run`'s streaming and non-streaming paths diverge for
     /// any program emitting `Op::ReceiveMsg` outside an actor system.
     in_actor_context: bool,
+    /// Run modules whose recorded capability contract versions differ
+    /// from this binary's (`--allow-capability-skew`). Defaults to
+    /// `false`: skewed bytecode is refused before any code runs.
+    allow_capability_skew: bool,
+    /// Reject `Float` operations that produce NaN or ±Inf with
+    /// [`VmError::NonFiniteFloat`] (`--strict-determinism`).
+    strict_determinism: bool,
+    /// Evaluate `requires`/`ensures` checks (`--contracts enforce`).
+    /// Defaults to `true`. When `false` an `Op::Assert` discards its
+    /// verdict: nothing is recorded and nothing traps.
+    enforce_contracts: bool,
+    /// Seed that orders the completion of spawned capability calls
+    /// (`--scheduler-seed`). `None` completes them in spawn order.
+    scheduler_seed: Option<u64>,
+    /// Calls started with `cap.spawn` and not yet run, by handle.
+    pending_caps: BTreeMap<u64, PendingCall>,
+    /// Results of run calls whose handles have not been awaited yet.
+    finished_caps: BTreeMap<u64, Result<Value, VmError>>,
+    next_cap_handle: u64,
+    /// Run budget debited for the steps [`run`](Self::run) executes.
+    ledger: Option<BudgetLedger>,
 }
 
 impl Vm {
     pub fn new(module: Module, gateway: CapabilityGateway) -> Self {
         let global_count = module.globals.len();
+        let mut event_log = EventLog::new();
+        event_log.set_provenance(module.provenance.clone());
         Vm {
             module,
             stack: Vec::with_capacity(256),
             call_stack: Vec::new(),
             globals: vec![Value::Unit; global_count],
             gateway,
-            event_log: EventLog::new(),
+            event_log,
             step_count: 0,
             max_steps: 10_000_000,
             max_wall_ms: None,
             start_time: None,
             ui_output: Vec::new(),
-            trace: Vec::new(),
+            trace: VecDeque::new(),
             trace_enabled: false,
+            tracer: Tracer::default(),
             last_cap_events: Vec::new(),
             actor_id: 0,
             mailbox: VecDeque::new(),
@@ -127,6 +208,14 @@ impl Vm {
             budget: None,
             budget_start: 0,
             in_actor_context: false,
+            allow_capability_skew: false,
+            strict_determinism: false,
+            enforce_contracts: true,
+            scheduler_seed: None,
+            pending_caps: BTreeMap::new(),
+            finished_caps: BTreeMap::new(),
+            next_cap_handle: 0,
+            ledger: None,
         }
     }
 
@@ -165,6 +254,16 @@ impl Vm {
         self.max_steps = max;
     }
 
+    /// Debit `ledger` for this VM's work: [`run`](Self::run) stops once
+    /// the steps the ledger has left are used up and then charges the
+    /// steps it executed, and the gateway charges each capability call
+    /// and its payload bytes. Exhaustion fails the run with
+    /// [`VmError::RunBudgetExhausted`].
+    pub fn set_budget_ledger(&mut self, ledger: BudgetLedger) {
+        self.gateway.set_budget_ledger(ledger.clone());
+        self.ledger = Some(ledger);
+    }
+
     /// Set a wall-clock execution limit in milliseconds.
     /// Pass `None` to disable. Checked every `WALL_TIME_CHECK_EVERY` steps.
     ///
@@ -197,23 +296,49 @@ impl Vm {
         &self.event_log
     }
 
+    /// The capability gateway, e.g. to read a dry-run transcript.
+    pub fn gateway(&self) -> &CapabilityGateway {
+        &self.gateway
+    }
+
     pub fn step_count(&self) -> u64 {
         self.step_count
     }
 
     /// Run from the module entry point.
     pub fn run(&mut self) -> Result<Value, VmError> {
+        self.check_module()?;
         let entry = self.module.entry;
+        // The ledger's remaining steps lower the ceiling for this run only.
+        let steps_before = self.step_count;
+        let max_steps = self.max_steps;
+        if let Some(left) = self
+            .ledger
+            .as_ref()
+            .and_then(|l| l.remaining(Resource::Steps))
+        {
+            self.max_steps = max_steps.min(steps_before.saturating_add(left));
+        }
         // Start the wall-clock timer before any user code executes — gives the
         // tightest accounting and ensures the limit covers the entry call too.
         self.start_time = Some(Instant::now());
         let result = (|| {
             self.call_function(entry, vec![])?;
-            self.execute()
+            let value = self.execute()?;
+            self.flush_cap_calls();
+            Ok(value)
         })();
         // Clear the timer so a subsequent reuse of the VM doesn't accidentally
         // measure against a stale start.
         self.start_time = None;
+        self.max_steps = max_steps;
+        // Charging more steps than the ledger had left is what stopped the
+        // run, so that error takes the place of the step-limit one.
+        if let Some(ledger) = &self.ledger {
+            ledger
+                .debit(Resource::Steps, self.step_count - steps_before)
+                .map_err(VmError::RunBudgetExhausted)?;
+        }
         result
     }
 
@@ -238,6 +363,7 @@ impl Vm {
             Ok(val) => {
                 // Completed — clear the timer for any future reuse.
                 self.start_time = None;
+                self.flush_cap_calls();
                 StepResult::Completed(val)
             }
             Err(VmError::BudgetExhausted) => StepResult::Yielded {
@@ -252,14 +378,212 @@ impl Vm {
         }
     }
 
+    /// Run every spawned call that has not run yet, together, keeping the
+    /// results for `cap.await`. Completion order is spawn order, or a
+    /// permutation fixed by the scheduler seed.
+    fn flush_cap_calls(&mut self) {
+        let mut pending: Vec<(u64, PendingCall)> =
+            std::mem::take(&mut self.pending_caps).into_iter().collect();
+        if pending.is_empty() {
+            return;
+        }
+        if let Some(seed) = self.scheduler_seed {
+            pending.sort_by_key(|(handle, _)| (splitmix64(seed ^ handle), *handle));
+        }
+        let calls: Vec<PendingCall> = pending.iter().map(|(_, call)| call.clone()).collect();
+        let results = self.gateway.complete(&calls, &mut self.event_log);
+        for ((handle, call), result) in pending.into_iter().zip(results) {
+            self.last_cap_events.push(call.cap.name());
+            self.finished_caps.insert(handle, result);
+        }
+    }
+
     /// Set up the entry function for bounded execution.
     pub fn set_entry_function(&mut self, func_idx: u32) -> Result<(), VmError> {
         if self.call_stack.is_empty() {
+            self.check_module()?;
             self.call_function(func_idx, vec![])?;
         }
         Ok(())
     }
 
+    /// Allow running a module compiled against different capability
+    /// contract versions (see [`Module::capability_skew`]).
+    pub fn set_allow_capability_skew(&mut self, allow: bool) {
+        self.allow_capability_skew = allow;
+    }
+
+    pub fn allow_capability_skew(&self) -> bool {
+        self.allow_capability_skew
+    }
+
+    /// Fail the run when a `Float` operation yields NaN or ±Inf.
+    pub fn set_strict_determinism(&mut self, strict: bool) {
+        self.strict_determinism = strict;
+    }
+
+    pub fn strict_determinism(&self) -> bool {
+        self.strict_determinism
+    }
+
+    /// Which steps the trace records when `trace_enabled` is set.
+    pub fn set_trace_sampling(&mut self, sampling: TraceSampling) {
+        self.tracer.sampling = sampling;
+    }
+
+    /// Keep at most `capacity` trace entries, evicting the oldest;
+    /// `None` (the default) keeps every sampled entry.
+    pub fn set_trace_capacity(&mut self, capacity: Option<usize>) {
+        self.tracer.capacity = capacity;
+    }
+
+    /// Step counts of the `top` busiest functions, plus how many entries
+    /// the trace sampled and evicted. Counts cover every step executed
+    /// while `trace_enabled` was set, whatever the sampling.
+    pub fn trace_summary(&self, top: usize) -> TraceSummary {
+        self.tracer.summary(&self.module, self.step_count, top)
+    }
+
+    /// Order in which spawned capability calls complete. Each seed gives
+    /// one fixed order, recorded in the event log, so a run is replayable
+    /// under the seed it ran with; `None` (the default) is spawn order.
+    pub fn set_scheduler_seed(&mut self, seed: Option<u64>) {
+        self.scheduler_seed = seed;
+    }
+
+    pub fn scheduler_seed(&self) -> Option<u64> {
+        self.scheduler_seed
+    }
+
+    /// Enforce contract checks compiled into the module. Bytecode built
+    /// with contracts stripped has no checks to enforce either way.
+    pub fn set_enforce_contracts(&mut self, enforce: bool) {
+        self.enforce_contracts = enforce;
+    }
+
+    pub fn enforce_contracts(&self) -> bool {
+        self.enforce_contracts
+    }
+
+    /// Checks that must pass before any module code runs: the policy's
+    /// signature requirement and capability contract version skew.
+    fn check_module(&self) -> Result<(), VmError> {
+        self.gateway.policy().check_module_trust(&self.module)?;
+        if !self.allow_capability_skew {
+            let skew = self.module.capability_skew();
+            if !skew.is_empty() {
+                let list: Vec<String> = skew.iter().map(ToString::to_string).collect();
+                return Err(VmError::CapabilitySkew(list.join(", ")));
+            }
+        }
+        Ok(())
+    }
+
+    /// Snapshot the execution state, to be resumed later by
+    /// [`restore_state`](Self::restore_state) on a VM for the same module.
+    pub fn capture_state(&self) -> VmState {
+        VmState {
+            stack: self.stack.clone(),
+            frames: self.call_stack.clone(),
+            globals: self.globals.clone(),
+        }
+    }
+
+    /// Resume from a captured state instead of calling an entry function.
+    /// Runs the same module checks as starting fresh, and rejects a state
+    /// whose frames do not fit this module.
+    pub fn restore_state(&mut self, state: VmState) -> Result<(), VmError> {
+        self.check_module()?;
+        self.check_state(&state)?;
+        self.stack = state.stack;
+        self.call_stack = state.frames;
+        self.globals = state.globals;
+        Ok(())
+    }
+
+    /// Save everything a run can change, to roll back to with
+    /// [`restore`](Self::restore). See [`VmSnapshot`] for what is covered.
+    pub fn snapshot(&self) -> VmSnapshot {
+        VmSnapshot(Arc::new(SnapshotData {
+            state: self.capture_state(),
+            event_log: self.event_log.clone(),
+            step_count: self.step_count,
+            budget_start: self.budget_start,
+            ui_output: self.ui_output.clone(),
+            mailbox: self.mailbox.clone(),
+            outgoing_messages: self.outgoing_messages.clone(),
+            spawn_requests: self.spawn_requests.clone(),
+            next_spawn_id: self.next_spawn_id,
+            pending_caps: self.pending_caps.clone(),
+            finished_caps: self.finished_caps.clone(),
+            next_cap_handle: self.next_cap_handle,
+            gateway: self.gateway.accounting(),
+        }))
+    }
+
+    /// Roll back to `snapshot`, discarding everything since. The snapshot
+    /// stays valid, so one branch point can be restored any number of
+    /// times. Rejects a snapshot whose frames do not fit this module.
+    pub fn restore(&mut self, snapshot: &VmSnapshot) -> Result<(), VmError> {
+        let data = &*snapshot.0;
+        self.check_state(&data.state)?;
+        self.stack = data.state.stack.clone();
+        self.call_stack = data.state.frames.clone();
+        self.globals = data.state.globals.clone();
+        self.event_log = data.event_log.clone();
+        self.step_count = data.step_count;
+        self.budget_start = data.budget_start;
+        self.ui_output = data.ui_output.clone();
+        self.mailbox = data.mailbox.clone();
+        self.outgoing_messages = data.outgoing_messages.clone();
+        self.spawn_requests = data.spawn_requests.clone();
+        self.next_spawn_id = data.next_spawn_id;
+        self.pending_caps = data.pending_caps.clone();
+        self.finished_caps = data.finished_caps.clone();
+        self.next_cap_handle = data.next_cap_handle;
+        self.gateway.restore_accounting(data.gateway.clone());
+        Ok(())
+    }
+
+    /// A copy of this VM, in its current state, that calls capabilities
+    /// through `gateway`. Limits and execution settings carry over; the
+    /// run budget ledger does not, so a fork is unmetered unless given
+    /// one.
+    pub fn fork(&self, gateway: CapabilityGateway) -> Vm {
+        let mut vm = Vm::new(self.module.clone(), gateway);
+        vm.max_steps = self.max_steps;
+        vm.max_wall_ms = self.max_wall_ms;
+        vm.actor_id = self.actor_id;
+        vm.in_actor_context = self.in_actor_context;
+        vm.allow_capability_skew = self.allow_capability_skew;
+        vm.strict_determinism = self.strict_determinism;
+        vm.enforce_contracts = self.enforce_contracts;
+        vm.scheduler_seed = self.scheduler_seed;
+        vm.restore(&self.snapshot())
+            .expect("a VM's own snapshot fits its module");
+        vm
+    }
+
+    fn check_state(&self, state: &VmState) -> Result<(), VmError> {
+        for frame in &state.frames {
+            let func = self
+                .module
+                .functions
+                .get(frame.func_idx as usize)
+                .ok_or(VmError::InvalidFunction(frame.func_idx))?;
+            if frame.ip > func.code.len() {
+                return Err(VmError::InvalidIp(frame.ip));
+            }
+            if frame.stack_base > state.stack.len() {
+                return Err(VmError::StackUnderflow);
+            }
+        }
+        if state.globals.len() != self.globals.len() {
+            return Err(VmError::InvalidGlobal(state.globals.len() as u32));
+        }
+        Ok(())
+    }
+
     /// Get the module (for cloning into child actors).
     pub fn module(&self) -> &Module {
         &self.module
@@ -319,6 +643,9 @@ impl Vm {
             locals,
         };
         self.call_stack.push(frame);
+        if self.trace_enabled {
+            self.tracer.enter(func_idx);
+        }
         Ok(())
     }
 
@@ -374,11 +701,11 @@ impl Vm {
 
             if self.trace_enabled {
                 let fname = &self.module.functions[func_idx as usize].name;
-                self.trace.push(format!(
-                    "[{fname}:{ip}] {:?}  stack_depth={}",
-                    op,
-                    self.stack.len()
-                ));
+                let depth = self.stack.len();
+                self.tracer
+                    .step(&mut self.trace, func_idx, self.step_count, &op, || {
+                        format!("[{fname}:{ip}] {op:?}  stack_depth={depth}")
+                    });
             }
 
             // Advance IP before executing (jumps will override)
@@ -589,6 +916,9 @@ impl Vm {
                     index,
                 } => {
                     let val = self.pop()?;
+                    if !self.enforce_contracts {
+                        continue;
+                    }
                     let passed = val.is_truthy();
                     // Seal the contract check into the evidence trail for
                     // BOTH outcomes: a passing run proves the clause held;
@@ -604,7 +934,10 @@ impl Vm {
                             .module
                             .constants
                             .get(err_const as usize)
-                            .map(|v| format!("{v}"))
+                            .map(|v| match v {
+                                Value::String(s) => format!("[{}] {s}", kind.diagnostic_code()),
+                                other => format!("[{}] {other}", kind.diagnostic_code()),
+                            })
                             .unwrap_or_else(|| "contract violation".into());
                         // Capture the offending arguments (locals[0..arity])
                         // as a concrete, replayable counterexample. `Op::Assert`
@@ -673,100 +1006,263 @@ impl Vm {
                     self.last_cap_events.push(cap.name());
                     self.push(result)?;
                 }
-                Op::Add => self.binary_op(|a, b| match (a, b) {
-                    (Value::Int(x), Value::Int(y)) => x
-                        .checked_add(y)
-                        .map(Value::Int)
-                        .ok_or(VmError::ArithmeticOverflow("addition")),
-                    (Value::Float(x), Value::Float(y)) => Ok(Value::Float(x + y)),
-                    (Value::Int(x), Value::Float(y)) => Ok(Value::Float(x as f64 + y)),
-                    (Value::Float(x), Value::Int(y)) => Ok(Value::Float(x + y as f64)),
-                    (a, b) => Err(VmError::TypeError {
-                        expected: "numeric",
-                        got: if matches!(a, Value::Int(_) | Value::Float(_)) {
-                            b.type_name()
-                        } else {
-                            a.type_name()
-                        },
-                    }),
-                })?,
-                Op::Sub => self.binary_op(|a, b| match (a, b) {
-                    (Value::Int(x), Value::Int(y)) => x
-                        .checked_sub(y)
-                        .map(Value::Int)
-                        .ok_or(VmError::ArithmeticOverflow("subtraction")),
-                    (Value::Float(x), Value::Float(y)) => Ok(Value::Float(x - y)),
-                    (Value::Int(x), Value::Float(y)) => Ok(Value::Float(x as f64 - y)),
-                    (Value::Float(x), Value::Int(y)) => Ok(Value::Float(x - y as f64)),
-                    (a, b) => Err(VmError::TypeError {
-                        expected: "numeric",
-                        got: if matches!(a, Value::Int(_) | Value::Float(_)) {
-                            b.type_name()
-                        } else {
-                            a.type_name()
-                        },
-                    }),
-                })?,
-                Op::Mul => self.binary_op(|a, b| match (a, b) {
-                    (Value::Int(x), Value::Int(y)) => x
-                        .checked_mul(y)
-                        .map(Value::Int)
-                        .ok_or(VmError::ArithmeticOverflow("multiplication")),
-                    (Value::Float(x), Value::Float(y)) => Ok(Value::Float(x * y)),
-                    (Value::Int(x), Value::Float(y)) => Ok(Value::Float(x as f64 * y)),
-                    (Value::Float(x), Value::Int(y)) => Ok(Value::Float(x * y as f64)),
-                    (a, b) => Err(VmError::TypeError {
-                        expected: "numeric",
-                        got: if matches!(a, Value::Int(_) | Value::Float(_)) {
-                            b.type_name()
-                        } else {
-                            a.type_name()
-                        },
-                    }),
-                })?,
-                Op::Div => self.binary_op(|a, b| {
-                    match (&a, &b) {
-                        (_, Value::Int(0)) => return Err(VmError::DivisionByZero),
-                        (_, Value::Float(f)) if *f == 0.0 => return Err(VmError::DivisionByZero),
-                        _ => {}
+                Op::CapBatch(cap_id) => {
+                    let cap =
+                        Capability::from_id(cap_id).ok_or(VmError::UnknownCapability(cap_id))?;
+                    if !self.module.functions[func_idx as usize]
+                        .capabilities
+                        .contains(&cap)
+                    {
+                        return Err(VmError::CapabilityDenied(cap));
                     }
-                    match (a, b) {
+
+                    let items = match self.pop()? {
+                        Value::List(items) => items,
+                        other => {
+                            return Err(VmError::TypeError {
+                                expected: "List",
+                                got: other.type_name(),
+                            })
+                        }
+                    };
+                    let batch = items
+                        .into_iter()
+                        .map(|item| match item {
+                            Value::List(args) => Ok(args),
+                            other => Err(VmError::TypeError {
+                                expected: "List of argument Lists",
+                                got: other.type_name(),
+                            }),
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+
+                    let results = self.gateway.call_batch(&cap, &batch, &mut self.event_log)?;
+                    self.last_cap_events
+                        .extend(std::iter::repeat_n(cap.name(), batch.len()));
+                    self.push(Value::List(results))?;
+                }
+                Op::CapSpawn(cap_id, arg_count) => {
+                    let cap =
+                        Capability::from_id(cap_id).ok_or(VmError::UnknownCapability(cap_id))?;
+                    if !self.module.functions[func_idx as usize]
+                        .capabilities
+                        .contains(&cap)
+                    {
+                        return Err(VmError::CapabilityDenied(cap));
+                    }
+
+                    let mut args = Vec::with_capacity(arg_count as usize);
+                    for _ in 0..arg_count {
+                        args.push(self.pop()?);
+                    }
+                    args.reverse();
+
+                    let pending = self.gateway.admit(&cap, args)?;
+                    let handle = self.next_cap_handle;
+                    self.next_cap_handle += 1;
+                    self.pending_caps.insert(handle, pending);
+                    self.push(Value::CapHandle(handle))?;
+                }
+                Op::CapAwait => {
+                    let handle = match self.pop()? {
+                        Value::CapHandle(h) => h,
+                        other => {
+                            return Err(VmError::TypeError {
+                                expected: "CapHandle",
+                                got: other.type_name(),
+                            })
+                        }
+                    };
+                    if self.pending_caps.contains_key(&handle) {
+                        self.flush_cap_calls();
+                    }
+                    let result = self
+                        .finished_caps
+                        .remove(&handle)
+                        .ok_or(VmError::InvalidCapHandle(handle))??;
+                    self.push(result)?;
+                }
+                Op::Add => {
+                    self.charge_bigint_operands(false)?;
+                    self.binary_op(|a, b| match (a, b) {
                         (Value::Int(x), Value::Int(y)) => x
-                            .checked_div(y)
+                            .checked_add(y)
                             .map(Value::Int)
-                            .ok_or(VmError::ArithmeticOverflow("division")),
-                        (Value::Float(x), Value::Float(y)) => Ok(Value::Float(x / y)),
-                        (Value::Int(x), Value::Float(y)) => Ok(Value::Float(x as f64 / y)),
-                        (Value::Float(x), Value::Int(y)) => Ok(Value::Float(x / y as f64)),
+                            .ok_or(VmError::ArithmeticOverflow("addition")),
+                        (Value::Float(x), Value::Float(y)) => Ok(Value::Float(x + y)),
+                        (Value::Int(x), Value::Float(y)) => Ok(Value::Float(x as f64 + y)),
+                        (Value::Float(x), Value::Int(y)) => Ok(Value::Float(x + y as f64)),
+                        (Value::Decimal(x), Value::Decimal(y)) => x
+                            .checked_add(y)
+                            .map(Value::Decimal)
+                            .ok_or(VmError::ArithmeticOverflow("addition")),
+                        (Value::BigInt(x), Value::BigInt(y)) => x
+                            .checked_add(&y)
+                            .map(Value::BigInt)
+                            .ok_or(VmError::ArithmeticOverflow("addition")),
                         (a, b) => Err(VmError::TypeError {
                             expected: "numeric",
-                            got: if matches!(a, Value::Int(_) | Value::Float(_)) {
+                            got: if matches!(
+                                a,
+                                Value::Int(_)
+                                    | Value::Float(_)
+                                    | Value::Decimal(_)
+                                    | Value::BigInt(_)
+                            ) {
                                 b.type_name()
                             } else {
                                 a.type_name()
                             },
                         }),
-                    }
-                })?,
-                Op::Mod => self.binary_op(|a, b| match (a, b) {
-                    (Value::Int(x), Value::Int(y)) => {
-                        if y == 0 {
-                            return Err(VmError::DivisionByZero);
-                        }
-                        // checked_rem also guards i64::MIN % -1 (overflow panic).
-                        x.checked_rem(y)
+                    })?;
+                    self.check_finite("addition")?;
+                }
+                Op::Sub => {
+                    self.charge_bigint_operands(false)?;
+                    self.binary_op(|a, b| match (a, b) {
+                        (Value::Int(x), Value::Int(y)) => x
+                            .checked_sub(y)
                             .map(Value::Int)
-                            .ok_or(VmError::ArithmeticOverflow("remainder"))
-                    }
-                    (a, b) => Err(VmError::TypeError {
-                        expected: "Int",
-                        got: if matches!(a, Value::Int(_)) {
-                            b.type_name()
-                        } else {
-                            a.type_name()
-                        },
-                    }),
-                })?,
+                            .ok_or(VmError::ArithmeticOverflow("subtraction")),
+                        (Value::Float(x), Value::Float(y)) => Ok(Value::Float(x - y)),
+                        (Value::Int(x), Value::Float(y)) => Ok(Value::Float(x as f64 - y)),
+                        (Value::Float(x), Value::Int(y)) => Ok(Value::Float(x - y as f64)),
+                        (Value::Decimal(x), Value::Decimal(y)) => x
+                            .checked_sub(y)
+                            .map(Value::Decimal)
+                            .ok_or(VmError::ArithmeticOverflow("subtraction")),
+                        (Value::BigInt(x), Value::BigInt(y)) => x
+                            .checked_sub(&y)
+                            .map(Value::BigInt)
+                            .ok_or(VmError::ArithmeticOverflow("subtraction")),
+                        (a, b) => Err(VmError::TypeError {
+                            expected: "numeric",
+                            got: if matches!(
+                                a,
+                                Value::Int(_)
+                                    | Value::Float(_)
+                                    | Value::Decimal(_)
+                                    | Value::BigInt(_)
+                            ) {
+                                b.type_name()
+                            } else {
+                                a.type_name()
+                            },
+                        }),
+                    })?;
+                    self.check_finite("subtraction")?;
+                }
+                Op::Mul => {
+                    self.charge_bigint_operands(true)?;
+                    self.binary_op(|a, b| match (a, b) {
+                        (Value::Int(x), Value::Int(y)) => x
+                            .checked_mul(y)
+                            .map(Value::Int)
+                            .ok_or(VmError::ArithmeticOverflow("multiplication")),
+                        (Value::Float(x), Value::Float(y)) => Ok(Value::Float(x * y)),
+                        (Value::Int(x), Value::Float(y)) => Ok(Value::Float(x as f64 * y)),
+                        (Value::Float(x), Value::Int(y)) => Ok(Value::Float(x * y as f64)),
+                        (Value::Decimal(x), Value::Decimal(y)) => x
+                            .checked_mul(y)
+                            .map(Value::Decimal)
+                            .ok_or(VmError::ArithmeticOverflow("multiplication")),
+                        (Value::BigInt(x), Value::BigInt(y)) => x
+                            .checked_mul(&y)
+                            .map(Value::BigInt)
+                            .ok_or(VmError::ArithmeticOverflow("multiplication")),
+                        (a, b) => Err(VmError::TypeError {
+                            expected: "numeric",
+                            got: if matches!(
+                                a,
+                                Value::Int(_)
+                                    | Value::Float(_)
+                                    | Value::Decimal(_)
+                                    | Value::BigInt(_)
+                            ) {
+                                b.type_name()
+                            } else {
+                                a.type_name()
+                            },
+                        }),
+                    })?;
+                    self.check_finite("multiplication")?;
+                }
+                Op::Div => {
+                    self.charge_bigint_operands(true)?;
+                    self.binary_op(|a, b| {
+                        match (&a, &b) {
+                            (_, Value::Int(0)) => return Err(VmError::DivisionByZero),
+                            (_, Value::Float(f)) if *f == 0.0 => {
+                                return Err(VmError::DivisionByZero)
+                            }
+                            (_, Value::Decimal(d)) if d.is_zero() => {
+                                return Err(VmError::DivisionByZero)
+                            }
+                            (_, Value::BigInt(n)) if n.is_zero() => {
+                                return Err(VmError::DivisionByZero)
+                            }
+                            _ => {}
+                        }
+                        match (a, b) {
+                            (Value::Int(x), Value::Int(y)) => x
+                                .checked_div(y)
+                                .map(Value::Int)
+                                .ok_or(VmError::ArithmeticOverflow("division")),
+                            (Value::Float(x), Value::Float(y)) => Ok(Value::Float(x / y)),
+                            (Value::Int(x), Value::Float(y)) => Ok(Value::Float(x as f64 / y)),
+                            (Value::Float(x), Value::Int(y)) => Ok(Value::Float(x / y as f64)),
+                            (Value::Decimal(x), Value::Decimal(y)) => x
+                                .checked_div(y)
+                                .map(Value::Decimal)
+                                .ok_or(VmError::ArithmeticOverflow("division")),
+                            (Value::BigInt(x), Value::BigInt(y)) => x
+                                .checked_div(&y)
+                                .map(Value::BigInt)
+                                .ok_or(VmError::ArithmeticOverflow("division")),
+                            (a, b) => Err(VmError::TypeError {
+                                expected: "numeric",
+                                got: if matches!(
+                                    a,
+                                    Value::Int(_)
+                                        | Value::Float(_)
+                                        | Value::Decimal(_)
+                                        | Value::BigInt(_)
+                                ) {
+                                    b.type_name()
+                                } else {
+                                    a.type_name()
+                                },
+                            }),
+                        }
+                    })?;
+                    self.check_finite("division")?;
+                }
+                Op::Mod => {
+                    self.charge_bigint_operands(true)?;
+                    self.binary_op(|a, b| match (a, b) {
+                        (Value::Int(x), Value::Int(y)) => {
+                            if y == 0 {
+                                return Err(VmError::DivisionByZero);
+                            }
+                            // checked_rem also guards i64::MIN % -1 (overflow panic).
+                            x.checked_rem(y)
+                                .map(Value::Int)
+                                .ok_or(VmError::ArithmeticOverflow("remainder"))
+                        }
+                        (Value::BigInt(x), Value::BigInt(y)) => x
+                            .checked_rem(&y)
+                            .map(Value::BigInt)
+                            .ok_or(VmError::DivisionByZero),
+                        (a, b) => Err(VmError::TypeError {
+                            expected: "Int",
+                            got: if matches!(a, Value::Int(_) | Value::BigInt(_)) {
+                                b.type_name()
+                            } else {
+                                a.type_name()
+                            },
+                        }),
+                    })?;
+                }
                 Op::Neg => {
                     let val = self.pop()?;
                     match val {
@@ -777,6 +1273,13 @@ impl Vm {
                             self.push(Value::Int(neg))?
                         }
                         Value::Float(n) => self.push(Value::Float(-n))?,
+                        Value::Decimal(d) => {
+                            let neg = d
+                                .checked_neg()
+                                .ok_or(VmError::ArithmeticOverflow("negation"))?;
+                            self.push(Value::Decimal(neg))?
+                        }
+                        Value::BigInt(n) => self.push(Value::BigInt(n.neg()))?,
                         _ => {
                             return Err(VmError::TypeError {
                                 expected: "numeric",
@@ -1376,6 +1879,9 @@ impl Vm {
                         Value::String(s) => {
                             let result = match s.parse::<f64>() {
                                 std::result::Result::Ok(f) => {
+                                    if self.strict_determinism && !f.is_finite() {
+                                        return Err(VmError::NonFiniteFloat("float parse"));
+                                    }
                                     Value::Some(Box::new(Value::Float(f)))
                                 }
                                 std::result::Result::Err(_) => Value::None,
@@ -1563,6 +2069,320 @@ impl Vm {
                     eprintln!("{msg_str} {value}");
                     self.push(value)?;
                 }
+                Op::DecimalParse => {
+                    let val = self.pop()?;
+                    match val {
+                        Value::String(s) => {
+                            let result = match Decimal::parse(s.trim()) {
+                                Some(d) => Value::Some(Box::new(Value::Decimal(d))),
+                                None => Value::None,
+                            };
+                            self.push(result)?;
+                        }
+                        _ => {
+                            return Err(VmError::TypeError {
+                                expected: "String",
+                                got: val.type_name(),
+                            })
+                        }
+                    }
+                }
+                Op::DecimalToString => {
+                    let val = self.pop()?;
+                    match val {
+                        Value::Decimal(d) => self.push(Value::String(d.to_string()))?,
+                        _ => {
+                            return Err(VmError::TypeError {
+                                expected: "Decimal",
+                                got: val.type_name(),
+                            })
+                        }
+                    }
+                }
+                Op::IntToDecimal => {
+                    let val = self.pop()?;
+                    match val {
+                        Value::Int(n) => self.push(Value::Decimal(Decimal::from_int(n)))?,
+                        _ => {
+                            return Err(VmError::TypeError {
+                                expected: "Int",
+                                got: val.type_name(),
+                            })
+                        }
+                    }
+                }
+                Op::BigIntParse => {
+                    let val = self.pop()?;
+                    match val {
+                        Value::String(s) => {
+                            let result = match BigInt::parse(s.trim()) {
+                                Some(n) => Value::Some(Box::new(Value::BigInt(n))),
+                                None => Value::None,
+                            };
+                            self.push(result)?;
+                        }
+                        _ => {
+                            return Err(VmError::TypeError {
+                                expected: "String",
+                                got: val.type_name(),
+                            })
+                        }
+                    }
+                }
+                Op::BigIntToString => {
+                    let val = self.pop()?;
+                    match val {
+                        Value::BigInt(n) => self.push(Value::String(n.to_string()))?,
+                        _ => {
+                            return Err(VmError::TypeError {
+                                expected: "BigInt",
+                                got: val.type_name(),
+                            })
+                        }
+                    }
+                }
+                Op::IntToBigInt => {
+                    let val = self.pop()?;
+                    match val {
+                        Value::Int(n) => self.push(Value::BigInt(BigInt::from_int(n)))?,
+                        _ => {
+                            return Err(VmError::TypeError {
+                                expected: "Int",
+                                got: val.type_name(),
+                            })
+                        }
+                    }
+                }
+                Op::BigIntToInt => {
+                    let val = self.pop()?;
+                    match val {
+                        Value::BigInt(n) => {
+                            let result = match n.to_int() {
+                                Some(i) => Value::Some(Box::new(Value::Int(i))),
+                                None => Value::None,
+                            };
+                            self.push(result)?;
+                        }
+                        _ => {
+                            return Err(VmError::TypeError {
+                                expected: "BigInt",
+                                got: val.type_name(),
+                            })
+                        }
+                    }
+                }
+                Op::BigIntPowMod => {
+                    let modulus = self.pop()?;
+                    let exp = self.pop()?;
+                    let base = self.pop()?;
+                    match (&base, &exp, &modulus) {
+                        (Value::BigInt(b), Value::BigInt(e), Value::BigInt(m)) => {
+                            if m.is_zero() {
+                                return Err(VmError::DivisionByZero);
+                            }
+                            self.charge(pow_mod_steps(b, e, m))?;
+                            let result = b.pow_mod(e, m).ok_or(VmError::TypeError {
+                                expected: "non-negative exponent",
+                                got: "BigInt",
+                            })?;
+                            self.push(Value::BigInt(result))?;
+                        }
+                        _ => {
+                            let got = [&base, &exp, &modulus]
+                                .into_iter()
+                                .find(|v| !matches!(v, Value::BigInt(_)))
+                                .map_or("BigInt", |v| v.type_name());
+                            return Err(VmError::TypeError {
+                                expected: "BigInt",
+                                got,
+                            });
+                        }
+                    }
+                }
+                Op::JsonParse => {
+                    let val = self.pop()?;
+                    match val {
+                        Value::String(s) => {
+                            let result = match crate::json::parse(&s) {
+                                Ok(doc) => Value::Ok(Box::new(doc)),
+                                Err(e) => Value::Err(Box::new(Value::String(e))),
+                            };
+                            self.push(result)?;
+                        }
+                        _ => {
+                            return Err(VmError::TypeError {
+                                expected: "String",
+                                got: val.type_name(),
+                            })
+                        }
+                    }
+                }
+                Op::JsonStringify => {
+                    let val = self.pop()?;
+                    let text = crate::json::stringify(&val)?;
+                    self.push(Value::String(text))?;
+                }
+                Op::StrMatch | Op::StrFind => {
+                    let text = self.pop()?;
+                    let pattern = self.pop()?;
+                    let (Value::String(pattern), Value::String(text)) = (&pattern, &text) else {
+                        return Err(VmError::TypeError {
+                            expected: "String",
+                            got: if matches!(pattern, Value::String(_)) {
+                                text.type_name()
+                            } else {
+                                pattern.type_name()
+                            },
+                        });
+                    };
+                    let span = self.find_pattern(pattern, text, op == Op::StrMatch)?;
+                    let result = match (op, span) {
+                        (Op::StrMatch, span) => Value::Bool(span.is_some()),
+                        (_, Some((start, end))) => {
+                            Value::Some(Box::new(Value::String(text[start..end].to_string())))
+                        }
+                        (_, None) => Value::None,
+                    };
+                    self.push(result)?;
+                }
+                Op::TableSelect => {
+                    let names = self.pop_names()?;
+                    let table = self.pop_table()?;
+                    self.charge_rows(&table)?;
+                    let out = table.select(&names).map_err(VmError::TableError)?;
+                    self.push(Value::Table(out))?;
+                }
+                Op::TableFilter => {
+                    let value = self.pop()?;
+                    let op = self.pop_string()?;
+                    let column = self.pop_string()?;
+                    let table = self.pop_table()?;
+                    let op = CompareOp::parse(&op)
+                        .ok_or_else(|| VmError::TableError(format!("unknown comparison '{op}'")))?;
+                    self.charge_rows(&table)?;
+                    let out = table
+                        .filter(&column, op, &value)
+                        .map_err(VmError::TableError)?;
+                    self.push(Value::Table(out))?;
+                }
+                Op::TableAggregate => {
+                    let agg = self.pop_string()?;
+                    let column = self.pop_string()?;
+                    let group_by = self.pop_names()?;
+                    let table = self.pop_table()?;
+                    let agg = Aggregate::parse(&agg)
+                        .ok_or_else(|| VmError::TableError(format!("unknown aggregate '{agg}'")))?;
+                    self.charge_rows(&table)?;
+                    let out = table
+                        .aggregate(&group_by, &column, agg)
+                        .map_err(VmError::TableError)?;
+                    self.push(Value::Table(out))?;
+                }
+                Op::TableFromColumns => {
+                    let Value::List(columns) = self.pop()? else {
+                        return Err(VmError::TypeError {
+                            expected: "List",
+                            got: "non-List",
+                        });
+                    };
+                    let names = self.pop_names()?;
+                    let values = columns
+                        .into_iter()
+                        .map(|c| match c {
+                            Value::List(cells) => Ok(cells),
+                            other => Err(VmError::TypeError {
+                                expected: "List",
+                                got: other.type_name(),
+                            }),
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    let table = Table::from_columns(names, values).map_err(VmError::TableError)?;
+                    self.push(Value::Table(table))?;
+                }
+                Op::TableSort => {
+                    let descending = match self.pop()? {
+                        Value::Bool(b) => b,
+                        other => {
+                            return Err(VmError::TypeError {
+                                expected: "Bool",
+                                got: other.type_name(),
+                            })
+                        }
+                    };
+                    let by = self.pop_names()?;
+                    let table = self.pop_table()?;
+                    self.charge_rows(&table)?;
+                    let out = table
+                        .sort_by(&by, descending)
+                        .map_err(VmError::TableError)?;
+                    self.push(Value::Table(out))?;
+                }
+                Op::TableColumn => {
+                    let name = self.pop_string()?;
+                    let table = self.pop_table()?;
+                    let column = table.column(&name).ok_or_else(|| {
+                        VmError::TableError(format!(
+                            "no column '{name}' (columns: {})",
+                            table.column_names().join(", ")
+                        ))
+                    })?;
+                    self.push(Value::List(column.values.clone()))?;
+                }
+                Op::TableColumnNames => {
+                    let table = self.pop_table()?;
+                    let names = table
+                        .column_names()
+                        .into_iter()
+                        .map(|n| Value::String(n.to_string()))
+                        .collect();
+                    self.push(Value::List(names))?;
+                }
+                Op::TableLen => {
+                    let table = self.pop_table()?;
+                    self.push(Value::Int(table.num_rows() as i64))?;
+                }
+                Op::TableRow => {
+                    let index = match self.pop()? {
+                        Value::Int(n) => n,
+                        other => {
+                            return Err(VmError::TypeError {
+                                expected: "Int",
+                                got: other.type_name(),
+                            })
+                        }
+                    };
+                    let table = self.pop_table()?;
+                    let row = usize::try_from(index).ok().and_then(|i| table.row(i));
+                    self.push(match row {
+                        Some(map) => Value::Some(Box::new(Value::Map(map))),
+                        None => Value::None,
+                    })?;
+                }
+                Op::TableHash => {
+                    let table = self.pop_table()?;
+                    self.push(Value::String(table.canonical_hash()))?;
+                }
+                Op::AssertMsg => {
+                    // Stack (top → bottom): [msg, cond].
+                    let message = self.pop_message()?;
+                    if self.pop()?.is_truthy() {
+                        self.push(Value::Unit)?;
+                    } else {
+                        return Err(VmError::UserAssertion {
+                            message,
+                            function: self.module.functions[func_idx as usize].name.clone(),
+                            ip,
+                        });
+                    }
+                }
+                Op::Fail => {
+                    let message = self.pop_message()?;
+                    return Err(VmError::UserFailure {
+                        message,
+                        function: self.module.functions[func_idx as usize].name.clone(),
+                        ip,
+                    });
+                }
                 Op::Nop => {}
                 Op::Halt => {
                     return Ok(self.stack.pop().unwrap_or(Value::Unit));
@@ -1583,6 +2403,83 @@ impl Vm {
         self.stack.pop().ok_or(VmError::StackUnderflow)
     }
 
+    /// Pop an `assert`/`fail` message; non-String values are rendered.
+    fn pop_message(&mut self) -> Result<String, VmError> {
+        Ok(match self.pop()? {
+            Value::String(s) => s,
+            other => other.to_string(),
+        })
+    }
+
+    fn pop_string(&mut self) -> Result<String, VmError> {
+        match self.pop()? {
+            Value::String(s) => Ok(s),
+            other => Err(VmError::TypeError {
+                expected: "String",
+                got: other.type_name(),
+            }),
+        }
+    }
+
+    fn pop_table(&mut self) -> Result<Table, VmError> {
+        match self.pop()? {
+            Value::Table(t) => Ok(t),
+            other => Err(VmError::TypeError {
+                expected: "Table",
+                got: other.type_name(),
+            }),
+        }
+    }
+
+    /// Pop a List of column names.
+    fn pop_names(&mut self) -> Result<Vec<String>, VmError> {
+        let Value::List(items) = self.pop()? else {
+            return Err(VmError::TypeError {
+                expected: "List",
+                got: "non-List",
+            });
+        };
+        items
+            .into_iter()
+            .map(|v| match v {
+                Value::String(s) => Ok(s),
+                other => Err(VmError::TypeError {
+                    expected: "String",
+                    got: other.type_name(),
+                }),
+            })
+            .collect()
+    }
+
+    /// Table opcodes visit every row, so each row costs one step.
+    fn charge_rows(&mut self, table: &Table) -> Result<(), VmError> {
+        self.charge(table.num_rows() as u64)
+    }
+
+    /// Count `steps` more toward the step limit, for opcodes whose work
+    /// grows with their operands.
+    fn charge(&mut self, steps: u64) -> Result<(), VmError> {
+        self.step_count = self.step_count.saturating_add(steps);
+        if self.step_count > self.max_steps {
+            return Err(VmError::ExecutionLimitExceeded(self.max_steps));
+        }
+        Ok(())
+    }
+
+    /// Charge a BigInt `Add`/`Sub`/`Mul`/`Div`/`Mod` by operand size
+    /// before it runs; other operand types cost nothing extra.
+    fn charge_bigint_operands(&mut self, product: bool) -> Result<(), VmError> {
+        let n = self.stack.len();
+        if n < 2 {
+            return Ok(());
+        }
+        if let (Value::BigInt(a), Value::BigInt(b)) = (&self.stack[n - 2], &self.stack[n - 1]) {
+            let steps = bigint_arith_steps(a, b, product);
+            self.charge(steps)?;
+        }
+        Ok(())
+    }
+
     fn get_local(&self, idx: u32) -> Result<&Value, VmError> {
         let frame = self.call_stack.last().ok_or(VmError::StackUnderflow)?;
         frame
@@ -1600,6 +2497,51 @@ impl Vm {
         Ok(())
     }
 
+    /// Run a pattern against `text`, charging compile size plus matching
+    /// steps to the step counter so `max_steps` bounds pattern work too.
+    fn find_pattern(
+        &mut self,
+        pattern: &str,
+        text: &str,
+        first_only: bool,
+    ) -> Result<Option<(usize, usize)>, VmError> {
+        let compiled =
+            Pattern::compile(pattern).map_err(|e| VmError::InvalidPattern(e.to_string()))?;
+        let budget = self
+            .max_steps
+            .saturating_sub(self.step_count)
+            .saturating_sub(compiled.len() as u64);
+        let outcome = if first_only {
+            compiled
+                .is_match(text, budget)
+                .map(|(hit, steps)| (hit.then_some((0, 0)), steps))
+        } else {
+            compiled.find(text, budget)
+        };
+        match outcome {
+            Ok((span, steps)) => {
+                self.step_count += compiled.len() as u64 + steps;
+                Ok(span)
+            }
+            Err(PatternError::StepLimit) => Err(VmError::ExecutionLimitExceeded(self.max_steps)),
+            Err(e) => Err(VmError::InvalidPattern(e.to_string())),
+        }
+    }
+
+    /// Under strict determinism a `Float` result must be finite: NaN/Inf
+    /// formatting and propagation differ across hosts and poison trace
+    /// hashes, so they fail the run instead of flowing onward.
+    fn check_finite(&self, op: &'static str) -> Result<(), VmError> {
+        if self.strict_determinism {
+            if let Some(Value::Float(f)) = self.stack.last() {
+                if !f.is_finite() {
+                    return Err(VmError::NonFiniteFloat(op));
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn binary_op(
         &mut self,
         f: impl FnOnce(Value, Value) -> Result<Value, VmError>,
@@ -1619,6 +2561,8 @@ impl Vm {
                 x.partial_cmp(y).unwrap_or(std::cmp::Ordering::Equal)
             }
             (Value::String(x), Value::String(y)) => x.cmp(y),
+            (Value::Decimal(x), Value::Decimal(y)) => x.cmp(y),
+            (Value::BigInt(x), Value::BigInt(y)) => x.cmp(y),
             _ => {
                 return Err(VmError::TypeError {
                     expected: "comparable",
@@ -1629,3 +2573,42 @@ impl Vm {
         self.push(Value::Bool(f(ord)))
     }
 }
+
+/// SplitMix64 finalizer: a cheap, well-mixed hash for deriving the
+/// seeded completion order of spawned capability calls.
+fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Extra steps for BigInt arithmetic: one per 64 machine words touched.
+/// Addition and subtraction are linear in the limb counts; schoolbook
+/// multiplication and division are proportional to their product, so
+/// repeatedly squaring a large value cannot outrun the step budget.
+fn bigint_arith_steps(a: &BigInt, b: &BigInt, product: bool) -> u64 {
+    let words = |n: &BigInt| n.bits().div_ceil(64).max(1);
+    let (a, b) = (words(a), words(b));
+    let work = if product {
+        a.saturating_mul(b)
+    } else {
+        a.saturating_add(b)
+    };
+    work / 64
+}
+
+/// Extra steps for `BigIntPowMod`: one per 64 machine-word products.
+/// Square-and-multiply does one modulus-sized product per exponent bit,
+/// and reducing the base takes one pass over it, so a single opcode
+/// cannot buy unbounded work with multi-thousand-bit operands.
+fn pow_mod_steps(base: &BigInt, exp: &BigInt, modulus: &BigInt) -> u64 {
+    let words = |n: &BigInt| n.bits().div_ceil(64).max(1);
+    let m = words(modulus);
+    let products = exp
+        .bits()
+        .saturating_mul(m)
+        .saturating_mul(m)
+        .saturating_add(words(base).saturating_mul(m));
+    products / 64
+}
@@ -0,0 +1,12 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(source) = std::str::from_utf8(data) else {
+        return;
+    };
+    if let Ok(tokens) = boruna_compiler::lexer::lex(source) {
+        let _ = boruna_compiler::parser::parse(tokens);
+    }
+});
@@ -1,8 +1,13 @@
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::Path;
 use std::process::Command;
 use std::time::Instant;
 
+use crate::patch::TraceCase;
+
+pub mod trend;
+
 /// Result of a single gate check.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GateResult {
@@ -25,6 +30,21 @@ pub enum GateStatus {
 pub struct GateContext<'a> {
     pub workspace_root: &'a Path,
     pub example_files: Vec<String>,
+    /// Cargo build directory to reuse when `workspace_root` is a sandbox
+    /// copy without its own `target/`.
+    pub cargo_target_dir: Option<&'a Path>,
+}
+
+impl GateContext<'_> {
+    /// A `cargo` command run in the workspace root.
+    fn cargo(&self) -> Command {
+        let mut cmd = Command::new("cargo");
+        cmd.current_dir(self.workspace_root);
+        if let Some(dir) = self.cargo_target_dir {
+            cmd.env("CARGO_TARGET_DIR", dir);
+        }
+        cmd
+    }
 }
 
 /// Trait for gate adapters that wrap existing tooling.
@@ -43,10 +63,7 @@ impl GateAdapter for CompileAdapter {
 
     fn run(&self, ctx: &GateContext) -> GateResult {
         let start = Instant::now();
-        let output = Command::new("cargo")
-            .args(["build", "--workspace"])
-            .current_dir(ctx.workspace_root)
-            .output();
+        let output = ctx.cargo().args(["build", "--workspace"]).output();
 
         let duration_ms = start.elapsed().as_millis() as u64;
 
@@ -89,10 +106,7 @@ impl GateAdapter for TestAdapter {
 
     fn run(&self, ctx: &GateContext) -> GateResult {
         let start = Instant::now();
-        let output = Command::new("cargo")
-            .args(["test", "--workspace"])
-            .current_dir(ctx.workspace_root)
-            .output();
+        let output = ctx.cargo().args(["test", "--workspace"]).output();
 
         let duration_ms = start.elapsed().as_millis() as u64;
 
@@ -161,7 +175,8 @@ impl GateAdapter for ReplayAdapter {
         let mut results = Vec::new();
 
         for (file, expected) in &self.expected_hashes {
-            let output = Command::new("cargo")
+            let output = ctx
+                .cargo()
                 .args([
                     "run",
                     "-p",
@@ -171,7 +186,6 @@ impl GateAdapter for ReplayAdapter {
                     "trace-hash",
                     file,
                 ])
-                .current_dir(ctx.workspace_root)
                 .output();
 
             match output {
@@ -230,9 +244,9 @@ impl GateAdapter for DiagAdapter {
         let mut outputs = Vec::new();
 
         for file in &self.files {
-            let output = Command::new("cargo")
+            let output = ctx
+                .cargo()
                 .args(["run", "-p", "llmvm-cli", "--", "framework", "diag", file])
-                .current_dir(ctx.workspace_root)
                 .output();
 
             match output {
@@ -264,6 +278,200 @@ impl GateAdapter for DiagAdapter {
     }
 }
 
+/// Adapter: `boruna lang difftest <dir>` — interpreter pipelines must agree.
+pub struct DiffTestAdapter {
+    pub corpus_dirs: Vec<String>,
+}
+
+impl GateAdapter for DiffTestAdapter {
+    fn name(&self) -> &str {
+        "difftest"
+    }
+
+    fn run(&self, ctx: &GateContext) -> GateResult {
+        if self.corpus_dirs.is_empty() {
+            return GateResult {
+                gate: "difftest".into(),
+                status: GateStatus::Skip,
+                duration_ms: 0,
+                output: "no difftest corpus configured".into(),
+                details: serde_json::json!({}),
+            };
+        }
+
+        let start = Instant::now();
+        let mut all_pass = true;
+        let mut results = Vec::new();
+
+        for dir in &self.corpus_dirs {
+            let output = ctx
+                .cargo()
+                .args([
+                    "run",
+                    "-q",
+                    "-p",
+                    "boruna-cli",
+                    "--",
+                    "lang",
+                    "difftest",
+                    dir,
+                    "--json",
+                ])
+                .output();
+
+            match output {
+                Ok(out) => {
+                    let stdout = String::from_utf8_lossy(&out.stdout).to_string();
+                    if !out.status.success() {
+                        all_pass = false;
+                    }
+                    let report: serde_json::Value =
+                        serde_json::from_str(&stdout).unwrap_or(serde_json::Value::Null);
+                    results.push(serde_json::json!({
+                        "corpus": dir,
+                        "exit_code": out.status.code(),
+                        "identical": report["identical"],
+                        "diverged": report["diverged"],
+                        "skipped": report["skipped"],
+                    }));
+                }
+                Err(e) => {
+                    all_pass = false;
+                    results.push(serde_json::json!({
+                        "corpus": dir,
+                        "error": format!("{e}"),
+                    }));
+                }
+            }
+        }
+
+        GateResult {
+            gate: "difftest".into(),
+            status: if all_pass {
+                GateStatus::Pass
+            } else {
+                GateStatus::Fail
+            },
+            duration_ms: start.elapsed().as_millis() as u64,
+            output: format!("{} difftest corpora", self.corpus_dirs.len()),
+            details: serde_json::json!({"corpora": results}),
+        }
+    }
+}
+
+/// Adapter: `boruna compile --verify-deterministic` over every std
+/// library — each must compile to byte-identical `.axbc` twice.
+pub struct DeterministicBuildAdapter;
+
+impl DeterministicBuildAdapter {
+    /// `libs/<package>/src/**/*.ax` under `workspace_root`, sorted and
+    /// relative to it.
+    fn library_sources(workspace_root: &Path) -> Vec<String> {
+        fn walk(dir: &Path, out: &mut Vec<std::path::PathBuf>) {
+            let Ok(entries) = std::fs::read_dir(dir) else {
+                return;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.is_dir() {
+                    walk(&path, out);
+                } else if path.extension().is_some_and(|e| e == "ax") {
+                    out.push(path);
+                }
+            }
+        }
+        let mut files = Vec::new();
+        if let Ok(packages) = std::fs::read_dir(workspace_root.join("libs")) {
+            for package in packages.flatten() {
+                walk(&package.path().join("src"), &mut files);
+            }
+        }
+        let mut files: Vec<String> = files
+            .iter()
+            .filter_map(|f| f.strip_prefix(workspace_root).ok())
+            .map(|f| f.to_string_lossy().into_owned())
+            .collect();
+        files.sort();
+        files
+    }
+}
+
+impl GateAdapter for DeterministicBuildAdapter {
+    fn name(&self) -> &str {
+        "deterministic_build"
+    }
+
+    fn run(&self, ctx: &GateContext) -> GateResult {
+        let files = Self::library_sources(ctx.workspace_root);
+        if files.is_empty() {
+            return GateResult {
+                gate: "deterministic_build".into(),
+                status: GateStatus::Skip,
+                duration_ms: 0,
+                output: "no std library sources under libs/".into(),
+                details: serde_json::json!({}),
+            };
+        }
+
+        let start = Instant::now();
+        let mut all_pass = true;
+        let mut results = Vec::new();
+
+        for file in &files {
+            let output = ctx
+                .cargo()
+                .args([
+                    "run",
+                    "-q",
+                    "-p",
+                    "boruna-cli",
+                    "--",
+                    "compile",
+                    file,
+                    "--verify-deterministic",
+                    "--json",
+                ])
+                .output();
+
+            match output {
+                Ok(out) => {
+                    let stdout = String::from_utf8_lossy(&out.stdout).to_string();
+                    if !out.status.success() {
+                        all_pass = false;
+                    }
+                    let report: serde_json::Value =
+                        serde_json::from_str(&stdout).unwrap_or(serde_json::Value::Null);
+                    results.push(serde_json::json!({
+                        "file": file,
+                        "exit_code": out.status.code(),
+                        "digests": report["digests"],
+                        "first_difference": report["first_difference"],
+                    }));
+                }
+                Err(e) => {
+                    all_pass = false;
+                    results.push(serde_json::json!({
+                        "file": file,
+                        "error": format!("{e}"),
+                    }));
+                }
+            }
+        }
+
+        GateResult {
+            gate: "deterministic_build".into(),
+            status: if all_pass {
+                GateStatus::Pass
+            } else {
+                GateStatus::Fail
+            },
+            duration_ms: start.elapsed().as_millis() as u64,
+            output: format!("{} std library sources built twice", files.len()),
+            details: serde_json::json!({"builds": results}),
+        }
+    }
+}
+
 /// Adapter: `boruna-pkg verify` — verify package integrity after dependency changes.
 pub struct PackageVerifyAdapter;
 
@@ -274,9 +482,9 @@ impl GateAdapter for PackageVerifyAdapter {
 
     fn run(&self, ctx: &GateContext) -> GateResult {
         let start = Instant::now();
-        let output = Command::new("cargo")
+        let output = ctx
+            .cargo()
             .args(["run", "-p", "boruna-pkg", "--", "verify"])
-            .current_dir(ctx.workspace_root)
             .output();
 
         let duration_ms = start.elapsed().as_millis() as u64;
@@ -320,9 +528,9 @@ impl GateAdapter for PackageResolveAdapter {
 
     fn run(&self, ctx: &GateContext) -> GateResult {
         let start = Instant::now();
-        let output = Command::new("cargo")
+        let output = ctx
+            .cargo()
             .args(["run", "-p", "boruna-pkg", "--", "resolve"])
-            .current_dir(ctx.workspace_root)
             .output();
 
         let duration_ms = start.elapsed().as_millis() as u64;
@@ -381,10 +589,7 @@ impl GateAdapter for LlmMockGateAdapter {
         }
 
         // Run boruna-effect tests to verify mock mode works
-        let output = Command::new("cargo")
-            .args(["test", "-p", "boruna-effect"])
-            .current_dir(ctx.workspace_root)
-            .output();
+        let output = ctx.cargo().args(["test", "-p", "boruna-effect"]).output();
 
         let duration_ms = start.elapsed().as_millis() as u64;
 
@@ -420,6 +625,164 @@ impl GateAdapter for LlmMockGateAdapter {
     }
 }
 
+/// Trace hash per case key, or why the case failed to run.
+pub type TraceHashes = BTreeMap<String, Result<String, String>>;
+
+/// Adapter: trace hash stability across a patch.
+///
+/// `record` runs each case through `framework trace-hash` before the patch
+/// is applied; `run` re-records afterwards and fails if any case outside
+/// `affected` produced a different hash. Catches semantic drift from
+/// patches that are meant to be refactor-only.
+pub struct TraceStabilityAdapter {
+    pub cases: Vec<TraceCase>,
+    /// Case files the bundle is allowed to change.
+    pub affected: BTreeSet<String>,
+    /// Case key -> hash recorded before the patch. `None` when no
+    /// baseline was taken (e.g. `orch review`, which does not apply).
+    pub baseline: Option<TraceHashes>,
+}
+
+impl TraceStabilityAdapter {
+    /// Record the trace hash of every case in `ctx`'s workspace. A case
+    /// that fails to run records its error.
+    pub fn record(cases: &[TraceCase], ctx: &GateContext) -> TraceHashes {
+        cases
+            .iter()
+            .map(|c| (c.key(), trace_hash(c, ctx)))
+            .collect()
+    }
+
+    /// Compare `after` against the baseline. A case that failed to run on
+    /// either side proves nothing, so it is unstable.
+    fn compare(
+        &self,
+        baseline: &TraceHashes,
+        after: &TraceHashes,
+    ) -> (bool, Vec<serde_json::Value>) {
+        let missing = || Err("not recorded".to_string());
+        let mut stable = true;
+        let mut checks = Vec::new();
+        for case in &self.cases {
+            let key = case.key();
+            let before = baseline.get(&key).cloned().unwrap_or_else(missing);
+            let now = after.get(&key).cloned().unwrap_or_else(missing);
+            let expected = self.affected.contains(&case.file);
+            match (before, now) {
+                (Ok(before), Ok(now)) => {
+                    let changed = before != now;
+                    if changed && !expected {
+                        stable = false;
+                    }
+                    checks.push(serde_json::json!({
+                        "case": key,
+                        "before": before,
+                        "after": now,
+                        "changed": changed,
+                        "expected_to_change": expected,
+                    }));
+                }
+                (before, now) => {
+                    stable = false;
+                    checks.push(serde_json::json!({
+                        "case": key,
+                        "before": before.unwrap_or_else(|e| format!("error: {e}")),
+                        "after": now.unwrap_or_else(|e| format!("error: {e}")),
+                        "error": true,
+                        "expected_to_change": expected,
+                    }));
+                }
+            }
+        }
+        (stable, checks)
+    }
+}
+
+impl GateAdapter for TraceStabilityAdapter {
+    fn name(&self) -> &str {
+        "trace_stability"
+    }
+
+    fn run(&self, ctx: &GateContext) -> GateResult {
+        let Some(baseline) = &self.baseline else {
+            return GateResult {
+                gate: "trace_stability".into(),
+                status: GateStatus::Skip,
+                duration_ms: 0,
+                output: "no pre-patch baseline recorded".into(),
+                details: serde_json::json!({}),
+            };
+        };
+        if self.cases.is_empty() {
+            return GateResult {
+                gate: "trace_stability".into(),
+                status: GateStatus::Skip,
+                duration_ms: 0,
+                output: "no trace cases configured".into(),
+                details: serde_json::json!({}),
+            };
+        }
+
+        let start = Instant::now();
+        let after = Self::record(&self.cases, ctx);
+        let (stable, checks) = self.compare(baseline, &after);
+        let drifted = checks
+            .iter()
+            .filter(|c| c["changed"] == true && c["expected_to_change"] == false)
+            .count();
+        let errored = checks.iter().filter(|c| c["error"] == true).count();
+
+        GateResult {
+            gate: "trace_stability".into(),
+            status: if stable {
+                GateStatus::Pass
+            } else {
+                GateStatus::Fail
+            },
+            duration_ms: start.elapsed().as_millis() as u64,
+            output: format!(
+                "{} trace cases, {drifted} unexpectedly changed, {errored} failed to run",
+                self.cases.len()
+            ),
+            details: serde_json::json!({"checks": checks}),
+        }
+    }
+}
+
+/// `cargo run -p boruna-cli -- framework trace-hash <file>`; first stdout
+/// line is the hash.
+fn trace_hash(case: &TraceCase, ctx: &GateContext) -> Result<String, String> {
+    let mut args = vec![
+        "run",
+        "-q",
+        "-p",
+        "boruna-cli",
+        "--",
+        "framework",
+        "trace-hash",
+        case.file.as_str(),
+    ];
+    if let Some(m) = &case.messages {
+        args.extend(["--messages", m.as_str()]);
+    }
+    match ctx.cargo().args(&args).output() {
+        Ok(out) if out.status.success() => {
+            let stdout = String::from_utf8_lossy(&out.stdout);
+            match stdout.lines().next().map(str::trim) {
+                Some(hash) if !hash.is_empty() => Ok(hash.to_string()),
+                _ => Err("trace-hash printed no hash".to_string()),
+            }
+        }
+        Ok(out) => Err(String::from_utf8_lossy(&out.stderr)
+            .lines()
+            .last()
+            .unwrap_or("")
+            .trim()
+            .to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
 /// Run all gates in order. Stops on first failure.
 pub fn run_gates(adapters: &[Box<dyn GateAdapter>], ctx: &GateContext) -> Vec<GateResult> {
     let mut results = Vec::new();
@@ -503,6 +866,117 @@ test result: ok. 76 passed; 0 failed; 0 ignored
         assert_eq!(total, 127);
     }
 
+    fn adapter(affected: &[&str]) -> TraceStabilityAdapter {
+        TraceStabilityAdapter {
+            cases: vec![
+                TraceCase {
+                    file: "examples/a.ax".into(),
+                    messages: None,
+                },
+                TraceCase {
+                    file: "examples/b.ax".into(),
+                    messages: Some("inc:1".into()),
+                },
+            ],
+            affected: affected.iter().map(|s| s.to_string()).collect(),
+            baseline: None,
+        }
+    }
+
+    fn hashes(a: &str, b: &str) -> TraceHashes {
+        BTreeMap::from([
+            ("examples/a.ax".to_string(), Ok(a.to_string())),
+            ("examples/b.ax?inc:1".to_string(), Ok(b.to_string())),
+        ])
+    }
+
+    #[test]
+    fn test_trace_stability_unexpected_change_fails() {
+        let (stable, checks) = adapter(&[]).compare(&hashes("h1", "h2"), &hashes("h1", "h3"));
+        assert!(!stable);
+        assert_eq!(checks[1]["case"], "examples/b.ax?inc:1");
+        assert_eq!(checks[1]["changed"], true);
+    }
+
+    #[test]
+    fn test_trace_stability_expected_change_passes() {
+        let (stable, checks) =
+            adapter(&["examples/b.ax"]).compare(&hashes("h1", "h2"), &hashes("h1", "h3"));
+        assert!(stable);
+        assert_eq!(checks[1]["expected_to_change"], true);
+        assert_eq!(checks[0]["changed"], false);
+    }
+
+    #[test]
+    fn test_trace_stability_run_errors_fail() {
+        let mut failing = hashes("h1", "h2");
+        failing.insert("examples/b.ax?inc:1".into(), Err("build failed".into()));
+        // Failing the same way before and after is not stability.
+        let (stable, checks) = adapter(&[]).compare(&failing, &failing);
+        assert!(!stable);
+        assert_eq!(checks[1]["error"], true);
+        assert_eq!(checks[1]["after"], "error: build failed");
+        assert!(checks[0]["error"].is_null());
+
+        // Even when the case is allowed to change.
+        let (stable, _) = adapter(&["examples/b.ax"]).compare(&hashes("h1", "h2"), &failing);
+        assert!(!stable);
+    }
+
+    #[test]
+    fn test_trace_stability_skips_without_baseline() {
+        let ctx = GateContext {
+            workspace_root: Path::new("."),
+            example_files: vec![],
+            cargo_target_dir: None,
+        };
+        assert_eq!(adapter(&[]).run(&ctx).status, GateStatus::Skip);
+    }
+
+    #[test]
+    fn test_difftest_skips_without_corpus() {
+        let ctx = GateContext {
+            workspace_root: Path::new("."),
+            example_files: vec![],
+            cargo_target_dir: None,
+        };
+        let adapter = DiffTestAdapter {
+            corpus_dirs: vec![],
+        };
+        assert_eq!(adapter.run(&ctx).status, GateStatus::Skip);
+    }
+
+    #[test]
+    fn test_deterministic_build_finds_library_sources() {
+        let dir = tempfile::tempdir().unwrap();
+        let ctx = GateContext {
+            workspace_root: dir.path(),
+            example_files: vec![],
+            cargo_target_dir: None,
+        };
+        assert_eq!(DeterministicBuildAdapter.run(&ctx).status, GateStatus::Skip);
+
+        for (path, body) in [
+            ("libs/std-b/src/core.ax", ""),
+            ("libs/std-a/src/nested/util.ax", ""),
+            ("libs/std-a/src/core.ax", ""),
+            ("libs/std-a/package.ax.json", "{}"),
+            ("libs/std-a/tests/t.ax", ""),
+        ] {
+            let path = dir.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, body).unwrap();
+        }
+        assert_eq!(
+            DeterministicBuildAdapter::library_sources(dir.path()),
+            vec![
+                "libs/std-a/src/core.ax",
+                "libs/std-a/src/nested/util.ax",
+                "libs/std-b/src/core.ax",
+            ]
+        );
+    }
+
     #[test]
     fn test_parse_test_counts_with_failures() {
         let output = "test result: FAILED. 10 passed; 2 failed; 0 ignored\n";
@@ -0,0 +1,242 @@
+//! Gate result history and flaky-gate detection for `orch gates history`.
+//!
+//! Every `apply` and `review` appends a [`GateRun`] to the store. Runs are
+//! grouped by subject — the node the bundle belongs to, or the bundle
+//! itself when it has no node. A gate flips when its outcome differs from
+//! the subject's previous decided run of that gate (skips decide nothing);
+//! a flip while the bundle's files are unchanged cannot be a regression in
+//! the change under test, so the gate is flagged as flaky.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::{GateResult, GateStatus};
+
+/// Outcome and timing of one gate within a run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GateSample {
+    pub gate: String,
+    pub status: GateStatus,
+    pub duration_ms: u64,
+}
+
+impl From<&GateResult> for GateSample {
+    fn from(r: &GateResult) -> Self {
+        Self {
+            gate: r.gate.clone(),
+            status: r.status.clone(),
+            duration_ms: r.duration_ms,
+        }
+    }
+}
+
+/// One `apply` or `review` gate run, appended to `gates/history.jsonl`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GateRun {
+    pub timestamp: String,
+    /// `apply` or `review`.
+    pub action: String,
+    pub bundle_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub node_id: Option<String>,
+    /// [`files_hash`] of the bundle's files as the gates saw them.
+    pub files_hash: String,
+    pub gates: Vec<GateSample>,
+}
+
+impl GateRun {
+    /// The node the run belongs to, or its bundle when it has none.
+    pub fn subject(&self) -> &str {
+        self.node_id.as_deref().unwrap_or(&self.bundle_id)
+    }
+
+    /// Total time spent in the run's gates.
+    pub fn duration_ms(&self) -> u64 {
+        self.gates.iter().map(|g| g.duration_ms).sum()
+    }
+}
+
+/// SHA-256 over the paths and contents of `files` under `root`, in sorted
+/// order. A missing file hashes differently from an empty one.
+pub fn files_hash(root: &Path, files: &[String]) -> String {
+    let sorted: BTreeSet<&String> = files.iter().collect();
+    let mut data = Vec::new();
+    for file in sorted {
+        data.extend_from_slice(file.as_bytes());
+        data.push(0);
+        match std::fs::read(root.join(file)) {
+            Ok(contents) => {
+                data.push(1);
+                data.extend_from_slice(&(contents.len() as u64).to_le_bytes());
+                data.extend_from_slice(&contents);
+            }
+            Err(_) => data.push(0),
+        }
+    }
+    boruna_hash::sha256_hex(data)
+}
+
+/// A gate whose outcome differs from the subject's previous decided run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Flip {
+    /// Index of the flipping run in the history.
+    pub run: usize,
+    pub gate: String,
+    /// The bundle's files were identical in both runs.
+    pub flaky: bool,
+}
+
+/// Every flip in `runs`, oldest first.
+pub fn flips(runs: &[GateRun]) -> Vec<Flip> {
+    let mut last: BTreeMap<(&str, &str), (&GateStatus, &str)> = BTreeMap::new();
+    let mut out = Vec::new();
+    for (i, run) in runs.iter().enumerate() {
+        for sample in &run.gates {
+            if sample.status == GateStatus::Skip {
+                continue;
+            }
+            let key = (run.subject(), sample.gate.as_str());
+            if let Some((status, files_hash)) = last.get(&key) {
+                if **status != sample.status {
+                    out.push(Flip {
+                        run: i,
+                        gate: sample.gate.clone(),
+                        flaky: *files_hash == run.files_hash,
+                    });
+                }
+            }
+            last.insert(key, (&sample.status, &run.files_hash));
+        }
+    }
+    out
+}
+
+/// Aggregate outcome of one gate for one subject.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GateTrend {
+    pub subject: String,
+    pub gate: String,
+    pub runs: usize,
+    pub passed: usize,
+    pub failed: usize,
+    pub mean_ms: u64,
+    /// Flips while the bundle's files were unchanged.
+    pub flaky_flips: usize,
+    pub flaky: bool,
+}
+
+/// Per subject and gate, in subject then gate order.
+pub fn trends(runs: &[GateRun]) -> Vec<GateTrend> {
+    let mut by_key: BTreeMap<(&str, &str), GateTrend> = BTreeMap::new();
+    let mut total_ms: BTreeMap<(&str, &str), u64> = BTreeMap::new();
+    for run in runs {
+        for sample in &run.gates {
+            let key = (run.subject(), sample.gate.as_str());
+            let trend = by_key.entry(key).or_insert_with(|| GateTrend {
+                subject: key.0.to_string(),
+                gate: key.1.to_string(),
+                runs: 0,
+                passed: 0,
+                failed: 0,
+                mean_ms: 0,
+                flaky_flips: 0,
+                flaky: false,
+            });
+            trend.runs += 1;
+            match sample.status {
+                GateStatus::Pass => trend.passed += 1,
+                GateStatus::Fail => trend.failed += 1,
+                GateStatus::Skip => {}
+            }
+            *total_ms.entry(key).or_default() += sample.duration_ms;
+        }
+    }
+    let found = flips(runs);
+    for flip in found.iter().filter(|f| f.flaky) {
+        let key = (runs[flip.run].subject(), flip.gate.as_str());
+        if let Some(trend) = by_key.get_mut(&key) {
+            trend.flaky_flips += 1;
+            trend.flaky = true;
+        }
+    }
+    by_key
+        .into_iter()
+        .map(|(key, mut trend)| {
+            trend.mean_ms = total_ms[&key] / trend.runs as u64;
+            trend
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(node: &str, files_hash: &str, gates: &[(&str, GateStatus, u64)]) -> GateRun {
+        GateRun {
+            timestamp: "2026-01-01T00:00:00Z".into(),
+            action: "apply".into(),
+            bundle_id: format!("{node}-bundle"),
+            node_id: Some(node.into()),
+            files_hash: files_hash.into(),
+            gates: gates
+                .iter()
+                .map(|(gate, status, duration_ms)| GateSample {
+                    gate: gate.to_string(),
+                    status: status.clone(),
+                    duration_ms: *duration_ms,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn flip_without_file_change_is_flaky() {
+        let runs = vec![
+            run("WN-1", "h1", &[("test", GateStatus::Pass, 100)]),
+            run("WN-1", "h1", &[("test", GateStatus::Fail, 300)]),
+            run("WN-1", "h2", &[("test", GateStatus::Pass, 200)]),
+        ];
+        let found = flips(&runs);
+        assert_eq!(found.len(), 2);
+        assert_eq!((found[0].run, found[0].flaky), (1, true));
+        // The files changed before the fix: a real repair, not flakiness.
+        assert_eq!((found[1].run, found[1].flaky), (2, false));
+
+        let t = trends(&runs);
+        assert_eq!(t.len(), 1);
+        assert_eq!((t[0].runs, t[0].passed, t[0].failed), (3, 2, 1));
+        assert_eq!(t[0].mean_ms, 200);
+        assert_eq!(t[0].flaky_flips, 1);
+        assert!(t[0].flaky);
+    }
+
+    #[test]
+    fn skips_and_other_subjects_do_not_flip() {
+        let runs = vec![
+            run("WN-1", "h1", &[("replay", GateStatus::Pass, 10)]),
+            run("WN-1", "h1", &[("replay", GateStatus::Skip, 0)]),
+            run("WN-2", "h1", &[("replay", GateStatus::Fail, 10)]),
+            run("WN-1", "h1", &[("replay", GateStatus::Pass, 10)]),
+        ];
+        assert!(flips(&runs).is_empty());
+        assert!(trends(&runs).iter().all(|t| !t.flaky));
+    }
+
+    #[test]
+    fn files_hash_tracks_contents_and_presence() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = vec!["b.rs".to_string(), "a.rs".to_string()];
+        let missing = files_hash(dir.path(), &files);
+        std::fs::write(dir.path().join("a.rs"), "").unwrap();
+        let empty = files_hash(dir.path(), &files);
+        assert_ne!(missing, empty);
+        std::fs::write(dir.path().join("a.rs"), "fn main() {}").unwrap();
+        let written = files_hash(dir.path(), &files);
+        assert_ne!(empty, written);
+        let reordered = vec!["a.rs".to_string(), "b.rs".to_string()];
+        assert_eq!(written, files_hash(dir.path(), &reordered));
+    }
+}
@@ -45,8 +45,8 @@
 //! NOT implemented here.
 
 use base64::Engine as _;
+use boruna_hash::Hasher;
 use serde::{Deserialize, Serialize};
-use sha2::{Digest, Sha256};
 
 use crate::audit::evidence::BundleManifest;
 
@@ -360,19 +360,19 @@ pub fn verify_entry(
 
 /// RFC 6962 leaf hash: `SHA-256(0x00 || leaf_data)`.
 pub fn rfc6962_leaf_hash(leaf_data: &[u8]) -> [u8; 32] {
-    let mut h = Sha256::new();
+    let mut h = Hasher::sha256();
     h.update([0x00u8]);
     h.update(leaf_data);
-    h.finalize().into()
+    h.finalize_bytes()
 }
 
 /// RFC 6962 internal node hash: `SHA-256(0x01 || left || right)`.
 fn rfc6962_node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
-    let mut h = Sha256::new();
+    let mut h = Hasher::sha256();
     h.update([0x01u8]);
     h.update(left);
     h.update(right);
-    h.finalize().into()
+    h.finalize_bytes()
 }
 
 /// Reconstruct the Merkle tree root from an RFC 6962 inclusion proof,
@@ -218,7 +218,7 @@ pub fn pae(payload_type: &str, payload: &[u8]) -> Vec<u8> {
 /// Build the in-toto Statement for a finalized bundle manifest.
 ///
 /// `subject[]` is every component file the manifest checksums (by its
-/// SHA-256), plus a synthetic subject for the bundle itself keyed by
+/// SHA-256, or the algorithm its checksum is tagged with), plus a synthetic subject for the bundle itself keyed by
 /// `bundle_hash`. The predicate maps the manifest's provenance fields
 /// into a SLSA-shaped structure. Fully deterministic: `file_checksums`
 /// is a `BTreeMap` so subjects come out in sorted-name order.
@@ -228,7 +228,10 @@ pub fn statement_from_manifest(manifest: &BundleManifest, boruna_version: &str)
         .iter()
         .map(|(name, sha)| {
             let mut digest = BTreeMap::new();
-            digest.insert("sha256".to_string(), sha.clone());
+            match boruna_hash::Digest::parse(sha) {
+                Ok(d) => digest.insert(d.algorithm().name().to_string(), d.hex().to_string()),
+                Err(_) => digest.insert("sha256".to_string(), sha.clone()),
+            };
             Subject {
                 name: name.clone(),
                 digest,
@@ -27,9 +27,9 @@ use aes_gcm::aead::{Aead, KeyInit, Payload};
 use aes_gcm::{Aes256Gcm, Key, Nonce};
 use base64::engine::general_purpose::STANDARD as B64;
 use base64::Engine;
+use boruna_hash::Hasher;
 use rand_core::{OsRng, RngCore};
 use serde::{Deserialize, Serialize};
-use sha2::{Digest, Sha256};
 use zeroize::Zeroize;
 
 /// AES-256 key length in bytes.
@@ -304,9 +304,9 @@ impl Envelope {
 
 /// Deterministic per-file nonce: first 12 bytes of SHA-256(filename).
 fn derive_nonce(filename: &str) -> [u8; NONCE_LEN] {
-    let mut hasher = Sha256::new();
+    let mut hasher = Hasher::sha256();
     hasher.update(filename.as_bytes());
-    let digest = hasher.finalize();
+    let digest = hasher.finalize_bytes();
     let mut nonce = [0u8; NONCE_LEN];
     nonce.copy_from_slice(&digest[..NONCE_LEN]);
     nonce
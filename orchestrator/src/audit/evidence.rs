@@ -1,12 +1,13 @@
+use boruna_hash::{sha256_hex, Digest, HashAlgorithm};
 use serde::{Deserialize, Serialize};
-use sha2::{Digest, Sha256};
 use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
 use crate::audit::encryption::{EncryptionError, EncryptionInfo, Envelope, KEY_LEN};
-use crate::audit::fingerprint::EnvFingerprint;
+use crate::audit::fingerprint::{EnvFingerprint, ProfileFingerprint};
 use crate::audit::log::AuditLog;
 use crate::audit::BUNDLE_FORMAT_VERSION;
+use crate::workflow::params::{self, ParamValues};
 
 fn default_schema_version() -> u32 {
     1
@@ -92,6 +93,10 @@ pub struct BundleManifest {
     /// (unchanged manifest bytes). Not part of `bundle_hash`.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub signature: Option<ManifestSignature>,
+    /// Hash of the run's bound workflow params (`params.json`); equals
+    /// the run's `inputs_hash`. Absent for runs without params.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub params_hash: Option<String>,
 }
 
 /// Builder for creating evidence bundles on disk.
@@ -116,6 +121,15 @@ pub struct EvidenceBundleBuilder {
     /// (+ public key) in `manifest.signature`. Absent → unsigned
     /// bundle (unchanged behavior).
     signing_key: Option<ed25519_dalek::SigningKey>,
+    /// Algorithm for `file_checksums`. SHA-256 checksums are written as
+    /// bare hex (the pre-existing format); others carry their tag.
+    checksum_algorithm: HashAlgorithm,
+    /// Set by `add_params`; recorded in the manifest.
+    params_hash: Option<String>,
+    /// Recorded in the environment fingerprint.
+    profile: Option<ProfileFingerprint>,
+    /// Environment variables whose value hashes the fingerprint records.
+    fingerprint_env: Vec<String>,
 }
 
 impl EvidenceBundleBuilder {
@@ -135,9 +149,23 @@ impl EvidenceBundleBuilder {
             encryption: None,
             encrypted_files: Vec::new(),
             signing_key: None,
+            checksum_algorithm: HashAlgorithm::Sha256,
+            params_hash: None,
+            profile: None,
+            fingerprint_env: Vec::new(),
         })
     }
 
+    /// Checksum component files with `algorithm` instead of SHA-256.
+    /// BLAKE3 is several times faster on large step outputs; its
+    /// checksums are written tagged (`blake3:<hex>`) so verifiers pick
+    /// the algorithm per file. `workflow_hash`, `policy_hash`, and
+    /// `bundle_hash` stay SHA-256 either way.
+    pub fn with_checksum_algorithm(mut self, algorithm: HashAlgorithm) -> Self {
+        self.checksum_algorithm = algorithm;
+        self
+    }
+
     /// verify-A: sign the finalized manifest's `bundle_hash` with the
     /// ed25519 key derived from `seed` (a 32-byte secret seed). When
     /// set, `finalize` populates `manifest.signature`; when unset the
@@ -147,6 +175,21 @@ impl EvidenceBundleBuilder {
         self
     }
 
+    /// Record the environment profile the run was resolved with in the
+    /// bundle's `env_fingerprint.json`.
+    pub fn with_profile(mut self, profile: ProfileFingerprint) -> Self {
+        self.profile = Some(profile);
+        self
+    }
+
+    /// Record the SHA-256 of each named environment variable's value
+    /// (never the value) in `env_fingerprint.json`, so bundles from two
+    /// runs show which of them drifted.
+    pub fn with_fingerprint_env(mut self, vars: Vec<String>) -> Self {
+        self.fingerprint_env = vars;
+        self
+    }
+
     /// Sprint W6-B: enable AES-256-GCM envelope encryption for all
     /// subsequent file writes. Generates a fresh DEK and wraps it
     /// with the supplied KEK. The DEK lives in-memory only and is
@@ -162,13 +205,13 @@ impl EvidenceBundleBuilder {
 
     /// Store the workflow definition in the bundle.
     pub fn add_workflow_def(&mut self, json: &str) -> std::io::Result<()> {
-        self.workflow_hash = sha256_str(json);
+        self.workflow_hash = sha256_hex(json);
         self.write_file("workflow.json", json)
     }
 
     /// Store the policy snapshot.
     pub fn add_policy(&mut self, json: &str) -> std::io::Result<()> {
-        self.policy_hash = sha256_str(json);
+        self.policy_hash = sha256_hex(json);
         self.write_file("policy.json", json)
     }
 
@@ -186,10 +229,29 @@ impl EvidenceBundleBuilder {
         let bytes = self.encrypt_if_needed(&filename, json.as_bytes());
         std::fs::write(&path, &bytes)?;
         // Checksum is over the plaintext: verify decrypts then hashes.
-        self.file_checksums.insert(filename, sha256_str(json));
+        let checksum = file_checksum(self.checksum_algorithm, json.as_bytes());
+        self.file_checksums.insert(filename, checksum);
         Ok(())
     }
 
+    /// Store a redaction placeholder in place of a classified step output.
+    /// The placeholder keeps the label and the output's hash, so the
+    /// audit log's `StepCompleted` entry can still be matched against it.
+    pub fn add_redacted_step_output(
+        &mut self,
+        step_id: &str,
+        name: &str,
+        classification: &str,
+        output_hash: &str,
+    ) -> std::io::Result<()> {
+        let placeholder = serde_json::json!({
+            "redacted": classification,
+            "output_hash": output_hash,
+        });
+        let json = serde_json::to_string_pretty(&placeholder).map_err(std::io::Error::other)?;
+        self.add_step_output(step_id, name, &json)
+    }
+
     /// Store a raw file in the bundle.
     pub fn add_file(&mut self, name: &str, content: &str) -> std::io::Result<()> {
         self.write_file(name, content)
@@ -230,6 +292,42 @@ impl EvidenceBundleBuilder {
         self.write_file("model_invoking_steps.json", &json)
     }
 
+    /// Store per-step module provenance (compiler and source digest, see
+    /// `boruna_bytecode::provenance`) as `provenance.json`, mapping
+    /// step_id → provenance. Ties every step's evidence to the exact
+    /// source it ran. Checksummed + hash-covered like every other
+    /// component. No-op when the map is empty.
+    pub fn add_provenance(
+        &mut self,
+        provenance: &BTreeMap<String, boruna_bytecode::Provenance>,
+    ) -> std::io::Result<()> {
+        if provenance.is_empty() {
+            return Ok(());
+        }
+        let json = serde_json::to_string_pretty(provenance).map_err(std::io::Error::other)?;
+        self.write_file("provenance.json", &json)
+    }
+
+    /// Store the run's bound workflow params as `params.json` and
+    /// record their hash in the manifest, so `workflow verify` replays
+    /// with the same values. No-op when the workflow declares none.
+    pub fn add_params(&mut self, params: &ParamValues) -> std::io::Result<()> {
+        if params.is_empty() {
+            return Ok(());
+        }
+        self.params_hash = Some(params::params_hash(params));
+        let json = serde_json::to_string_pretty(params).map_err(std::io::Error::other)?;
+        self.write_file("params.json", &json)
+    }
+
+    /// Store a step's capability event log as `events/<step_id>.json`.
+    /// `workflow verify` feeds the recorded capability results back to the
+    /// step on replay, so a bundle with event logs can be re-executed
+    /// without touching the outside world.
+    pub fn add_event_log(&mut self, step_id: &str, json: &str) -> std::io::Result<()> {
+        self.write_file(&format!("events/{step_id}.json"), json)
+    }
+
     /// Finalize the bundle: write audit log, env fingerprint, and manifest.
     pub fn finalize(mut self, audit_log: &AuditLog) -> std::io::Result<BundleManifest> {
         let completed_at = chrono::Utc::now().to_rfc3339();
@@ -238,7 +336,8 @@ impl EvidenceBundleBuilder {
         let audit_json = audit_log.to_json().map_err(std::io::Error::other)?;
         self.write_file("audit_log.json", &audit_json)?;
 
-        let env_fingerprint = EnvFingerprint::capture();
+        let mut env_fingerprint = EnvFingerprint::capture_with_vars(&self.fingerprint_env);
+        env_fingerprint.profile = self.profile.clone();
         let env_json =
             serde_json::to_string_pretty(&env_fingerprint).map_err(std::io::Error::other)?;
         self.write_file("env_fingerprint.json", &env_json)?;
@@ -270,6 +369,7 @@ impl EvidenceBundleBuilder {
             bundle_hash: String::new(), // filled below
             encryption: encryption_info,
             signature: None, // filled below iff a signing key was supplied
+            params_hash: self.params_hash.clone(),
         };
 
         // Compute bundle hash from manifest (excluding bundle_hash and
@@ -277,7 +377,7 @@ impl EvidenceBundleBuilder {
         // here, so the serialized bytes match the verifier's recompute).
         let manifest_json =
             serde_json::to_string_pretty(&manifest).map_err(std::io::Error::other)?;
-        let bundle_hash = sha256_str(&manifest_json);
+        let bundle_hash = sha256_hex(&manifest_json);
 
         // verify-A: sign the bundle_hash bytes iff a signing key was
         // supplied. The signature is added AFTER bundle_hash is
@@ -320,12 +420,21 @@ impl EvidenceBundleBuilder {
         if self.bundle_dir.join("outputs").exists() {
             components.push("outputs/".to_string());
         }
+        if self.bundle_dir.join("events").exists() {
+            components.push("events/".to_string());
+        }
         if self.bundle_dir.join("intents.json").exists() {
             components.push("intents.json".to_string());
         }
         if self.bundle_dir.join("model_invoking_steps.json").exists() {
             components.push("model_invoking_steps.json".to_string());
         }
+        if self.bundle_dir.join("provenance.json").exists() {
+            components.push("provenance.json".to_string());
+        }
+        if self.bundle_dir.join("params.json").exists() {
+            components.push("params.json".to_string());
+        }
         components.sort();
 
         let bundle_json = BundleJson {
@@ -358,8 +467,8 @@ impl EvidenceBundleBuilder {
         }
         let bytes = self.encrypt_if_needed(name, content.as_bytes());
         std::fs::write(&path, &bytes)?;
-        self.file_checksums
-            .insert(name.to_string(), sha256_str(content));
+        let checksum = file_checksum(self.checksum_algorithm, content.as_bytes());
+        self.file_checksums.insert(name.to_string(), checksum);
         Ok(())
     }
 
@@ -378,10 +487,12 @@ impl EvidenceBundleBuilder {
     }
 }
 
-fn sha256_str(s: &str) -> String {
-    let mut hasher = Sha256::new();
-    hasher.update(s.as_bytes());
-    format!("{:x}", hasher.finalize())
+/// A `file_checksums` entry: bare hex for SHA-256, tagged otherwise.
+pub(crate) fn file_checksum(algorithm: HashAlgorithm, bytes: &[u8]) -> String {
+    match algorithm {
+        HashAlgorithm::Sha256 => sha256_hex(bytes),
+        other => other.digest(bytes).to_string(),
+    }
 }
 
 /// Lowercase-hex encode bytes (for ed25519 public key / signature in
@@ -567,9 +678,16 @@ pub fn redact_bundle(
         .to_json()
         .map_err(|e| BundleRedactError::InvalidAuditLog(e.to_string()))?;
     atomic_write_with_dir_fsync(bundle_dir, "audit_log.json", new_audit_json.as_bytes())?;
-    manifest
+    // Keep whichever algorithm the bundle was checksummed with.
+    let algorithm = manifest
         .file_checksums
-        .insert("audit_log.json".to_string(), sha256_str(&new_audit_json));
+        .get("audit_log.json")
+        .and_then(|c| Digest::parse(c).ok())
+        .map_or(HashAlgorithm::Sha256, |d| d.algorithm());
+    manifest.file_checksums.insert(
+        "audit_log.json".to_string(),
+        file_checksum(algorithm, new_audit_json.as_bytes()),
+    );
 
     // 5. audit_log_hash is unchanged; drop the now-stale signature.
     let signature_stripped = manifest.signature.take().is_some();
@@ -603,7 +721,7 @@ fn recompute_bundle_hash(manifest: &BundleManifest) -> Result<String, serde_json
     clone.bundle_hash = String::new();
     clone.signature = None;
     let json = serde_json::to_string_pretty(&clone)?;
-    Ok(sha256_str(&json))
+    Ok(sha256_hex(&json))
 }
 
 #[cfg(test)]
@@ -714,6 +832,28 @@ mod tests {
             .contains_key("model_invoking_steps.json"));
     }
 
+    #[test]
+    fn test_bundle_captures_step_provenance_and_is_covered() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut builder = EvidenceBundleBuilder::new(dir.path(), "run-prov-001", "wf").unwrap();
+        builder.add_provenance(&BTreeMap::new()).unwrap();
+        let provenance = BTreeMap::from([(
+            "step1".to_string(),
+            boruna_bytecode::Provenance::new("boruna-compiler 0.0.0", "fn main() -> Int { 1 }"),
+        )]);
+        builder.add_provenance(&provenance).unwrap();
+        let manifest = builder.finalize(&AuditLog::new()).unwrap();
+        let bundle_path = dir.path().join("run-prov-001");
+        assert!(manifest.file_checksums.contains_key("provenance.json"));
+        let written: BTreeMap<String, boruna_bytecode::Provenance> = serde_json::from_str(
+            &std::fs::read_to_string(bundle_path.join("provenance.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(written, provenance);
+        let bundle_json = std::fs::read_to_string(bundle_path.join("bundle.json")).unwrap();
+        assert!(bundle_json.contains("\"provenance.json\""));
+    }
+
     #[test]
     fn test_bundle_no_model_invocations_writes_no_file() {
         let dir = tempfile::tempdir().unwrap();
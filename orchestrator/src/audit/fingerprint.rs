@@ -1,6 +1,13 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
 use serde::{Deserialize, Serialize};
 
 /// Environment fingerprint captured at runtime (no secrets).
+///
+/// Fields added after the first release are omitted when empty, so
+/// manifests of older bundles re-serialize to the same bytes and their
+/// `bundle_hash` still verifies.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct EnvFingerprint {
     pub boruna_version: String,
@@ -8,23 +15,210 @@ pub struct EnvFingerprint {
     pub os: String,
     pub arch: String,
     pub hostname: String,
+    /// The workflow profile the run used, if any. Set by
+    /// `EvidenceBundleBuilder::with_profile`; absent otherwise.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<ProfileFingerprint>,
+    /// Target triple the binary was built for.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub target: String,
+    /// Effective `LC_ALL` / `LC_CTYPE` / `LANG` locale (`C` when unset).
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub locale: String,
+    /// `TZ`, else the zone `/etc/localtime` links to, else `unknown`.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub timezone: String,
+    /// Allowlisted environment variables: name → HMAC-SHA256 of the value
+    /// under the install's [`fingerprint_key`], `null` when unset. Raw
+    /// values are never recorded, and a keyed digest cannot be reversed
+    /// by hashing guesses.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env_vars: BTreeMap<String, Option<String>>,
+    /// Identifies the key `env_vars` were digested with (a hash of it).
+    /// Digests made under different keys are not comparable.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub env_key_id: String,
+}
+
+/// Identifies the environment profile (`profiles/<name>.json`) a run
+/// was resolved with.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ProfileFingerprint {
+    pub name: String,
+    /// SHA-256 of the profile file's bytes.
+    pub hash: String,
 }
 
 impl EnvFingerprint {
     /// Capture the current environment.
     pub fn capture() -> Self {
+        Self::capture_with_vars(&[])
+    }
+
+    /// Capture the current environment plus the keyed digests of the
+    /// named environment variables.
+    pub fn capture_with_vars(vars: &[String]) -> Self {
+        if vars.is_empty() {
+            return Self::capture_with_key(vars, &[]);
+        }
+        Self::capture_with_key(vars, &fingerprint_key())
+    }
+
+    /// [`capture_with_vars`](Self::capture_with_vars) with an explicit
+    /// digest key.
+    pub fn capture_with_key(vars: &[String], key: &[u8]) -> Self {
         EnvFingerprint {
             boruna_version: env!("CARGO_PKG_VERSION").to_string(),
             rust_version: rustc_version(),
             os: std::env::consts::OS.to_string(),
             arch: std::env::consts::ARCH.to_string(),
             hostname: hostname(),
+            profile: None,
+            target: env!("BORUNA_BUILD_TARGET").to_string(),
+            locale: locale(),
+            timezone: timezone(),
+            env_vars: vars
+                .iter()
+                .map(|name| {
+                    let hash = std::env::var_os(name)
+                        .map(|v| boruna_hash::hmac_sha256_hex(key, v.as_encoded_bytes()));
+                    (name.clone(), hash)
+                })
+                .collect(),
+            env_key_id: if vars.is_empty() {
+                String::new()
+            } else {
+                boruna_hash::hmac_sha256_hex(key, "env_key_id")[..16].to_string()
+            },
         }
     }
+
+    /// Fields that differ from `other`, as `(field, self, other)`.
+    /// Allowlisted variables are reported as `env.<NAME>` with their
+    /// hashes (`unset` when absent on one side).
+    pub fn drift(&self, other: &EnvFingerprint) -> Vec<(String, String, String)> {
+        let profile = |p: &Option<ProfileFingerprint>| {
+            p.as_ref()
+                .map_or_else(String::new, |p| format!("{} ({})", p.name, p.hash))
+        };
+        let mut drift: Vec<(String, String, String)> = [
+            (
+                "boruna_version",
+                &self.boruna_version,
+                &other.boruna_version,
+            ),
+            ("rust_version", &self.rust_version, &other.rust_version),
+            ("os", &self.os, &other.os),
+            ("arch", &self.arch, &other.arch),
+            ("hostname", &self.hostname, &other.hostname),
+            ("target", &self.target, &other.target),
+            ("locale", &self.locale, &other.locale),
+            ("timezone", &self.timezone, &other.timezone),
+            ("profile", &profile(&self.profile), &profile(&other.profile)),
+            ("env_key_id", &self.env_key_id, &other.env_key_id),
+        ]
+        .into_iter()
+        .filter(|(_, a, b)| a != b)
+        .map(|(field, a, b)| (field.to_string(), a.clone(), b.clone()))
+        .collect();
+
+        let names: std::collections::BTreeSet<&String> =
+            self.env_vars.keys().chain(other.env_vars.keys()).collect();
+        let value = |vars: &BTreeMap<String, Option<String>>, name: &str| match vars.get(name) {
+            Some(Some(hash)) => hash.clone(),
+            Some(None) => "unset".to_string(),
+            None => "not captured".to_string(),
+        };
+        for name in names {
+            let (a, b) = (value(&self.env_vars, name), value(&other.env_vars, name));
+            if a != b {
+                drift.push((format!("env.{name}"), a, b));
+            }
+        }
+        drift
+    }
+}
+
+/// The key for `env_vars` digests: `$BORUNA_FINGERPRINT_KEY`, else
+/// `boruna/fingerprint.key` under the user config directory, created with
+/// a random key on first use. Machines whose bundles are compared for
+/// drift share a key through the variable. Without either, or if the file
+/// does not hold a valid key, a one-off random key is used: the digests then match nothing, but still reveal
+/// nothing.
+pub fn fingerprint_key() -> Vec<u8> {
+    if let Some(key) = std::env::var_os("BORUNA_FINGERPRINT_KEY").filter(|k| !k.is_empty()) {
+        return key.as_encoded_bytes().to_vec();
+    }
+    key_file()
+        .and_then(|path| load_or_create_key(&path).ok())
+        .unwrap_or_else(random_key)
+}
+
+fn key_file() -> Option<PathBuf> {
+    let dir = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|d| !d.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(dir.join("boruna").join("fingerprint.key"))
+}
+
+/// Read the key at `path`, or create it. The new key is written to a
+/// temp file and linked into place, so a concurrent reader never sees a
+/// partial key and a losing writer picks up the winner's.
+fn load_or_create_key(path: &Path) -> std::io::Result<Vec<u8>> {
+    use std::io::Write;
+
+    match read_key(path) {
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        found => return found,
+    }
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let key = boruna_hash::to_hex(&random_key());
+    let tmp = path.with_extension(format!("tmp.{}", &boruna_hash::to_hex(&random_key())[..16]));
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let linked = options
+        .open(&tmp)
+        .and_then(|mut file| {
+            file.write_all(key.as_bytes())?;
+            file.sync_all()
+        })
+        .and_then(|()| std::fs::hard_link(&tmp, path));
+    let _ = std::fs::remove_file(&tmp);
+    match linked {
+        Ok(()) => Ok(key.into_bytes()),
+        // Another process created it first; use theirs.
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => read_key(path),
+        Err(e) => Err(e),
+    }
+}
+
+/// A key file holds the 64 hex digits of a 32-byte key.
+fn read_key(path: &Path) -> std::io::Result<Vec<u8>> {
+    let key = std::fs::read_to_string(path)?;
+    let key = key.trim();
+    if key.len() != 64 || !key.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("{} is not a 64-digit hex key", path.display()),
+        ));
+    }
+    Ok(key.as_bytes().to_vec())
+}
+
+fn random_key() -> Vec<u8> {
+    use rand_core::{OsRng, RngCore};
+    let mut key = vec![0u8; 32];
+    OsRng.fill_bytes(&mut key);
+    key
 }
 
 fn rustc_version() -> String {
-    // Use the version baked at compile time
+    // Baked at compile time by build.rs
     option_env!("RUSTC_VERSION")
         .unwrap_or("unknown")
         .to_string()
@@ -36,6 +230,31 @@ fn hostname() -> String {
         .unwrap_or_else(|_| "unknown".into())
 }
 
+fn locale() -> String {
+    ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|v| !v.is_empty())
+        .unwrap_or_else(|| "C".into())
+}
+
+fn timezone() -> String {
+    if let Ok(tz) = std::env::var("TZ") {
+        if !tz.is_empty() {
+            return tz;
+        }
+    }
+    std::fs::read_link("/etc/localtime")
+        .ok()
+        .and_then(|target| {
+            let target = target.to_string_lossy().into_owned();
+            target
+                .split_once("zoneinfo/")
+                .map(|(_, zone)| zone.to_string())
+        })
+        .unwrap_or_else(|| "unknown".into())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -46,6 +265,9 @@ mod tests {
         assert!(!fp.boruna_version.is_empty());
         assert!(!fp.os.is_empty());
         assert!(!fp.arch.is_empty());
+        assert!(!fp.target.is_empty());
+        assert!(!fp.locale.is_empty());
+        assert!(fp.env_vars.is_empty());
     }
 
     #[test]
@@ -57,4 +279,91 @@ mod tests {
         assert_eq!(fp.os, restored.os);
         assert_eq!(fp.arch, restored.arch);
     }
+
+    #[test]
+    fn test_legacy_fingerprint_reserializes_unchanged() {
+        let legacy = r#"{"boruna_version":"1.0.0","rust_version":"unknown","os":"linux","arch":"x86_64","hostname":"h"}"#;
+        let fp: EnvFingerprint = serde_json::from_str(legacy).unwrap();
+        assert_eq!(serde_json::to_string(&fp).unwrap(), legacy);
+    }
+
+    #[test]
+    fn test_captured_vars_are_hashed_never_raw() {
+        let path = std::env::var("PATH").unwrap();
+        let vars = ["PATH".to_string(), "BORUNA_TEST_SURELY_UNSET".to_string()];
+        let fp = EnvFingerprint::capture_with_key(&vars, b"install-key");
+        let json = serde_json::to_string(&fp).unwrap();
+        assert!(!json.contains(&path));
+        assert_eq!(
+            fp.env_vars["PATH"].as_deref(),
+            Some(boruna_hash::hmac_sha256_hex(b"install-key", &path).as_str())
+        );
+        // Not the plain hash a dictionary attack would precompute.
+        assert_ne!(
+            fp.env_vars["PATH"].as_deref(),
+            Some(boruna_hash::sha256_hex(&path).as_str())
+        );
+        assert_eq!(fp.env_vars["BORUNA_TEST_SURELY_UNSET"], None);
+
+        // Same key, same digests: no drift.
+        let again = EnvFingerprint::capture_with_key(&vars, b"install-key");
+        assert!(fp.drift(&again).is_empty());
+        let rekeyed = EnvFingerprint::capture_with_key(&vars, b"other-key");
+        let fields: Vec<String> = fp.drift(&rekeyed).into_iter().map(|d| d.0).collect();
+        assert_eq!(fields, ["env_key_id", "env.PATH"]);
+
+        let mut other = fp.clone();
+        other.env_vars.insert("PATH".into(), Some("0".repeat(64)));
+        other.timezone = "Mars/Olympus_Mons".into();
+        let fields: Vec<String> = fp.drift(&other).into_iter().map(|d| d.0).collect();
+        assert_eq!(fields, ["timezone", "env.PATH"]);
+    }
+
+    #[test]
+    fn test_key_file_is_created_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("boruna/fingerprint.key");
+        let key = load_or_create_key(&path).unwrap();
+        assert_eq!(key.len(), 64);
+        assert_eq!(load_or_create_key(&path).unwrap(), key);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        // The temp file the key was staged in is gone.
+        let entries = std::fs::read_dir(path.parent().unwrap()).unwrap().count();
+        assert_eq!(entries, 1);
+    }
+
+    #[test]
+    fn test_racing_key_creators_agree() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("fingerprint.key");
+        let keys: Vec<Vec<u8>> = std::thread::scope(|s| {
+            let handles: Vec<_> = (0..8)
+                .map(|_| s.spawn(|| load_or_create_key(&path).unwrap()))
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+        assert!(keys.iter().all(|k| *k == keys[0]));
+        assert_eq!(std::fs::read(&path).unwrap(), keys[0]);
+    }
+
+    #[test]
+    fn test_malformed_key_file_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("fingerprint.key");
+        for bad in ["", "abc123", &"z".repeat(64), &"a".repeat(65)] {
+            std::fs::write(&path, bad).unwrap();
+            let err = load_or_create_key(&path).unwrap_err();
+            assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        }
+        std::fs::write(&path, format!("{}\n", "a".repeat(64))).unwrap();
+        assert_eq!(
+            load_or_create_key(&path).unwrap(),
+            "a".repeat(64).into_bytes()
+        );
+    }
 }
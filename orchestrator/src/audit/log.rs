@@ -1,5 +1,5 @@
+use boruna_hash::Hasher;
 use serde::{Deserialize, Serialize};
-use sha2::{Digest, Sha256};
 
 /// Sentinel value substituted for a redacted string leaf. Verification
 /// never inspects this text — the removed content is proven by the
@@ -405,19 +405,17 @@ impl AuditLog {
     /// be redacted later without disturbing the chain.
     fn content_hash(event: &AuditEvent) -> String {
         let event_json = serde_json::to_string(event).unwrap_or_default();
-        let mut hasher = Sha256::new();
-        hasher.update(event_json.as_bytes());
-        format!("{:x}", hasher.finalize())
+        boruna_hash::sha256_hex(event_json)
     }
 
     /// Commitment-chain (format 1.1) entry hash:
     /// `SHA-256(sequence_le || prev_hash || content_sha256)`.
     fn compute_entry_hash(sequence: u64, prev_hash: &str, content_sha256: &str) -> String {
-        let mut hasher = Sha256::new();
+        let mut hasher = Hasher::sha256();
         hasher.update(sequence.to_le_bytes());
         hasher.update(prev_hash.as_bytes());
         hasher.update(content_sha256.as_bytes());
-        format!("{:x}", hasher.finalize())
+        hasher.finalize_hex()
     }
 
     /// Legacy (format 1.0) entry hash used to verify pre-1.1 logs:
@@ -425,11 +423,11 @@ impl AuditLog {
     /// back-compat verification only; `append` never produces this form.
     fn compute_hash_legacy(sequence: u64, prev_hash: &str, event: &AuditEvent) -> String {
         let event_json = serde_json::to_string(event).unwrap_or_default();
-        let mut hasher = Sha256::new();
+        let mut hasher = Hasher::sha256();
         hasher.update(sequence.to_le_bytes());
         hasher.update(prev_hash.as_bytes());
         hasher.update(event_json.as_bytes());
-        format!("{:x}", hasher.finalize())
+        hasher.finalize_hex()
     }
 }
 
@@ -36,8 +36,8 @@
 //!     from the manifest's `started_at`, or 0 if unparseable) plus the
 //!     event index. They encode ordering, not measured latency.
 
+use boruna_hash::Hasher;
 use serde::Serialize;
-use sha2::{Digest, Sha256};
 use std::path::Path;
 
 use crate::audit::evidence::BundleManifest;
@@ -370,6 +370,53 @@ fn build_traces(manifest: &BundleManifest, event_log: Option<&EventLog>) -> Trac
                     true,
                 ));
             }
+            Event::ActorCheckpoint {
+                actor_id,
+                state_hash,
+            }
+            | Event::ActorRestore {
+                actor_id,
+                state_hash,
+            } => {
+                let name = if matches!(ev, Event::ActorCheckpoint { .. }) {
+                    "boruna.actor_checkpoint"
+                } else {
+                    "boruna.actor_restore"
+                };
+                child_spans.push(generic_span(
+                    &trace_id,
+                    &root_span_id,
+                    &manifest.run_id,
+                    i,
+                    base,
+                    name,
+                    vec![
+                        kv("boruna.actor.id", int_val(*actor_id)),
+                        kv("boruna.actor.state_hash", str_val(state_hash.clone())),
+                    ],
+                    true,
+                ));
+            }
+            Event::CapabilityDelegation {
+                parent,
+                child,
+                policy_hash,
+            } => {
+                child_spans.push(generic_span(
+                    &trace_id,
+                    &root_span_id,
+                    &manifest.run_id,
+                    i,
+                    base,
+                    "boruna.capability_delegation",
+                    vec![
+                        kv("boruna.actor.parent", int_val(*parent)),
+                        kv("boruna.actor.id", int_val(*child)),
+                        kv("boruna.policy.hash", str_val(policy_hash.clone())),
+                    ],
+                    true,
+                ));
+            }
         }
     }
 
@@ -540,18 +587,18 @@ fn gen_ai_operation(capability: &str) -> Option<String> {
 /// 16-byte (32 hex) trace id derived from the run id. Deterministic, never
 /// random — see module docs.
 fn trace_id_from(run_id: &str) -> String {
-    let digest = Sha256::digest(run_id.as_bytes());
+    let digest = boruna_hash::sha256(run_id);
     to_hex(&digest[..16])
 }
 
 /// 8-byte (16 hex) span id derived from `run_id` + a per-span tag (the
 /// event index, or "root"). Deterministic.
 fn span_id_from(run_id: &str, tag: &str) -> String {
-    let mut hasher = Sha256::new();
+    let mut hasher = Hasher::sha256();
     hasher.update(run_id.as_bytes());
     hasher.update(b":");
     hasher.update(tag.as_bytes());
-    let digest = hasher.finalize();
+    let digest = hasher.finalize_bytes();
     to_hex(&digest[..8])
 }
 
@@ -219,10 +219,7 @@ fn compute_bundle_hash(manifest: &BundleManifest) -> Result<String, RotationErro
     clone.signature = None;
     let json = serde_json::to_string_pretty(&clone)
         .map_err(|e| RotationError::InvalidManifest(format!("hash-prep: {e}")))?;
-    use sha2::{Digest, Sha256};
-    let mut hasher = Sha256::new();
-    hasher.update(json.as_bytes());
-    Ok(format!("{:x}", hasher.finalize()))
+    Ok(boruna_hash::sha256_hex(json))
 }
 
 #[cfg(test)]
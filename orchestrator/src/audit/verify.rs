@@ -1,4 +1,4 @@
-use sha2::{Digest, Sha256};
+use boruna_hash::{sha256_hex, Digest};
 use std::path::Path;
 
 use crate::audit::encryption::{EncryptionError, Envelope, KEY_LEN};
@@ -316,8 +316,17 @@ pub fn verify_bundle_with_opts(bundle_dir: &Path, opts: &VerifyOptions) -> Verif
             },
             None => raw,
         };
-        let actual_hash = sha256_bytes(&plaintext_bytes);
-        if actual_hash != *expected_hash {
+        // Untagged checksums are SHA-256; tagged ones name their
+        // algorithm (see `EvidenceBundleBuilder::with_checksum_algorithm`).
+        let expected = match Digest::parse(expected_hash) {
+            Ok(d) => d,
+            Err(e) => {
+                errors.push(format!("invalid checksum for {filename}: {e}"));
+                continue;
+            }
+        };
+        if !expected.matches(&plaintext_bytes) {
+            let actual_hash = expected.algorithm().hex(&plaintext_bytes);
             errors.push(format!(
                 "checksum mismatch for {filename}: expected {expected_hash}, got {actual_hash}"
             ));
@@ -391,12 +400,6 @@ pub fn verify_bundle_with_opts(bundle_dir: &Path, opts: &VerifyOptions) -> Verif
     }
 }
 
-fn sha256_bytes(b: &[u8]) -> String {
-    let mut hasher = Sha256::new();
-    hasher.update(b);
-    format!("{:x}", hasher.finalize())
-}
-
 /// verify-A: check the manifest's ed25519 signature over `bundle_hash`.
 ///
 /// The chain of trust is: signature (over `bundle_hash`, under the
@@ -502,7 +505,7 @@ fn recompute_bundle_hash(manifest: &BundleManifest) -> Result<String, serde_json
     clone.bundle_hash = String::new();
     clone.signature = None;
     let json = serde_json::to_string_pretty(&clone)?;
-    Ok(sha256_bytes(json.as_bytes()))
+    Ok(sha256_hex(json.as_bytes()))
 }
 
 /// Decode a fixed-length lowercase/uppercase hex string into `[u8; N]`.
@@ -742,7 +745,7 @@ mod tests {
         let mut manifest = good.clone();
         manifest
             .file_checksums
-            .insert(out_rel.to_string(), sha256_bytes(forged_output.as_bytes()));
+            .insert(out_rel.to_string(), sha256_hex(forged_output.as_bytes()));
         manifest.bundle_hash = recompute_bundle_hash(&manifest).unwrap();
         std::fs::write(
             bundle_dir.join("manifest.json"),
@@ -803,6 +806,33 @@ mod tests {
             .any(|e| e.contains("checksum mismatch")));
     }
 
+    #[test]
+    fn test_verify_blake3_checksums() {
+        use boruna_hash::HashAlgorithm;
+        let dir = tempfile::tempdir().unwrap();
+        let mut builder = EvidenceBundleBuilder::new(dir.path(), "run-b3", "b3-test")
+            .unwrap()
+            .with_checksum_algorithm(HashAlgorithm::Blake3);
+        builder.add_workflow_def(r#"{"name":"test"}"#).unwrap();
+        builder.add_policy(r#"{"default_allow":true}"#).unwrap();
+        let manifest = builder.finalize(&AuditLog::new()).unwrap();
+        assert!(manifest
+            .file_checksums
+            .values()
+            .all(|c| c.starts_with("blake3:")));
+
+        let bundle_dir = dir.path().join("run-b3");
+        let result = verify_bundle(&bundle_dir);
+        assert!(result.valid, "errors: {:?}", result.errors);
+
+        std::fs::write(bundle_dir.join("workflow.json"), r#"{"name":"TAMPERED"}"#).unwrap();
+        let result = verify_bundle(&bundle_dir);
+        assert!(result
+            .errors
+            .iter()
+            .any(|e| e.contains("checksum mismatch")));
+    }
+
     #[test]
     fn test_verify_detects_missing_file() {
         let dir = tempfile::tempdir().unwrap();
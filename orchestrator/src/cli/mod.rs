@@ -1,9 +1,24 @@
 use std::path::Path;
+use std::time::Duration;
 
-use crate::adapters::{self, CompileAdapter, GateAdapter, GateContext, ReplayAdapter, TestAdapter};
-use crate::engine::{NodeStatus, Role, Scheduler, WorkGraph};
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::adapters::trend::{self, GateRun, GateSample};
+use crate::adapters::{
+    self, CompileAdapter, DeterministicBuildAdapter, DiffTestAdapter, GateAdapter, GateContext,
+    ReplayAdapter, TestAdapter, TraceStabilityAdapter,
+};
+use crate::engine::analyze;
+use crate::engine::render::{self, GraphFormat};
+use crate::engine::{
+    EditEvent, GraphEdit, NodeStatus, Role, ScheduleStrategy, Scheduler, WorkGraph,
+};
+use crate::git::{self, GitRepo};
+use crate::identity;
 use crate::patch::PatchBundle;
-use crate::storage::Store;
+use crate::planner::{self, GoalSpec};
+use crate::sandbox::Sandbox;
+use crate::storage::{ActivityEvent, Store};
 
 /// Default storage directory relative to workspace root.
 const STORAGE_DIR: &str = "orchestrator/storage";
@@ -19,30 +34,192 @@ fn load_active_graph(store: &Store) -> Result<WorkGraph, String> {
     store.load_graph(&graph_id)
 }
 
+/// Fail unless the store's roster, if any, lets `actor` act in one of `roles`.
+fn authorize(store: &Store, actor: &str, roles: &[Role]) -> Result<(), String> {
+    let Some(roster) = store.load_roster()? else {
+        return Ok(());
+    };
+    let mut denied = Vec::new();
+    for role in roles {
+        match roster.authorize(actor, role) {
+            Ok(()) => return Ok(()),
+            Err(e) => denied.push(e),
+        }
+    }
+    Err(format!("permission denied: {}", denied.join("; ")))
+}
+
+/// Append an actor-attributed entry to the store's activity log.
+fn record_activity(
+    store: &Store,
+    actor: &str,
+    action: &str,
+    graph_id: Option<&str>,
+    node_id: Option<&str>,
+    detail: serde_json::Value,
+) -> Result<(), String> {
+    store.record_activity(&ActivityEvent {
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        actor: actor.to_string(),
+        action: action.to_string(),
+        graph_id: graph_id.map(str::to_string),
+        node_id: node_id.map(str::to_string),
+        detail,
+    })
+}
+
+/// Append a gate run to the store's gate history and flag every gate that
+/// changed outcome while the bundle's files stayed the same. The caller
+/// holds the store's writer lock, so the last run in the history is this
+/// one.
+fn record_gate_run(
+    store: &Store,
+    action: &str,
+    bundle: &PatchBundle,
+    node_id: Option<&str>,
+    files_hash: String,
+    results: &[adapters::GateResult],
+) -> Result<(), String> {
+    store.append_gate_run(&GateRun {
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        action: action.to_string(),
+        bundle_id: bundle.metadata.id.clone(),
+        node_id: node_id.map(str::to_string),
+        files_hash,
+        gates: results.iter().map(GateSample::from).collect(),
+    })?;
+    let history = store.load_gate_history()?;
+    let latest = history.len() - 1;
+    for flip in trend::flips(&history)
+        .iter()
+        .filter(|f| f.run == latest && f.flaky)
+    {
+        println!(
+            "  flaky: {} changed outcome with the bundle's files unchanged",
+            flip.gate
+        );
+    }
+    Ok(())
+}
+
 /// `orch plan <spec.json>` — Create a DAG from a plan specification file.
-pub fn cmd_plan(workspace: &Path, spec_path: &Path) -> Result<(), String> {
+///
+/// Runs as `actor`, who must hold the planner role. Waits up to
+/// `wait_timeout` for another writer to release the store.
+pub fn cmd_plan(
+    workspace: &Path,
+    spec_path: &Path,
+    strategy: Option<ScheduleStrategy>,
+    actor: &str,
+    wait_timeout: Duration,
+) -> Result<(), String> {
     let data = std::fs::read_to_string(spec_path).map_err(|e| format!("cannot read spec: {e}"))?;
-    let graph: WorkGraph =
+    let mut graph: WorkGraph =
         serde_json::from_str(&data).map_err(|e| format!("invalid spec JSON: {e}"))?;
+    if let Some(strategy) = strategy {
+        graph.strategy = strategy;
+    }
 
     // Validate DAG
     let sched = Scheduler::new(graph.clone(), 4);
     sched.validate()?;
 
-    let store = store_for(workspace)?;
+    let store = store_for(workspace)?.with_wait_timeout(wait_timeout);
+    authorize(&store, actor, &[Role::Planner])?;
+    let _lock = store.lock()?;
     store.save_graph(&graph)?;
+    record_activity(
+        &store,
+        actor,
+        "plan",
+        Some(&graph.id),
+        None,
+        serde_json::Value::Null,
+    )?;
 
     let order = sched.topological_order()?;
     println!("created work graph: {}", graph.id);
     println!("  {} nodes", graph.nodes.len());
     println!("  execution order: {}", order.join(" → "));
+    println!("  strategy: {}", graph.strategy);
+
+    Ok(())
+}
+
+/// `orch plan synth <goal.json>` — Synthesize a candidate work graph from a
+/// goal spec for human review. Nothing is written to the store; accept the
+/// candidate with `orch plan <candidate.json>`.
+///
+/// With `out`, the candidate is written there and the rationale printed;
+/// otherwise the candidate goes to stdout and the rationale to stderr.
+/// `llm` asks the mock LLM gateway for extra dependency edges.
+pub fn cmd_plan_synth(goal_path: &Path, out: Option<&Path>, llm: bool) -> Result<(), String> {
+    let data = std::fs::read_to_string(goal_path).map_err(|e| format!("cannot read goal: {e}"))?;
+    let goal: GoalSpec =
+        serde_json::from_str(&data).map_err(|e| format!("invalid goal JSON: {e}"))?;
+
+    let mut synthesis = planner::synthesize(&goal)?;
+    if llm {
+        let scratch = tempfile::tempdir().map_err(|e| format!("cannot create temp dir: {e}"))?;
+        let request_id = planner::suggest_with_llm(&goal, &mut synthesis, scratch.path())?;
+        synthesis.rationale.push(planner::Rationale {
+            node_id: goal.id.clone(),
+            reason: format!("llm request {request_id} (mock backend)"),
+        });
+    }
+    let json = serde_json::to_string_pretty(&synthesis.graph)
+        .map_err(|e| format!("serialize error: {e}"))?;
+    let order = Scheduler::new(synthesis.graph.clone(), 4).topological_order()?;
 
+    let mut notes = vec![
+        format!("candidate work graph: {}", synthesis.graph.id),
+        format!("  {} nodes", synthesis.graph.nodes.len()),
+        format!("  execution order: {}", order.join(" → ")),
+        "rationale:".to_string(),
+    ];
+    notes.extend(
+        synthesis
+            .rationale
+            .iter()
+            .map(|r| format!("  {}: {}", r.node_id, r.reason)),
+    );
+    match out {
+        Some(path) => {
+            std::fs::write(path, json + "\n").map_err(|e| format!("write error: {e}"))?;
+            for line in &notes {
+                println!("{line}");
+            }
+            println!(
+                "review {}, then accept it with: boruna-orch plan {}",
+                path.display(),
+                path.display()
+            );
+        }
+        None => {
+            println!("{json}");
+            for line in &notes {
+                eprintln!("{line}");
+            }
+        }
+    }
     Ok(())
 }
 
 /// `orch next --role <role>` — Assign the next ready node for a role.
-pub fn cmd_next(workspace: &Path, role: Role) -> Result<(), String> {
-    let store = store_for(workspace)?;
+///
+/// The graph and lock table are read, updated and saved under the store's
+/// writer lock, so concurrent invocations never assign the same node.
+/// The node is assigned to `actor`, who must hold `role`. Waits up to
+/// `wait_timeout` for another writer.
+pub fn cmd_next(
+    workspace: &Path,
+    role: Role,
+    actor: &str,
+    wait_timeout: Duration,
+) -> Result<(), String> {
+    let store = store_for(workspace)?.with_wait_timeout(wait_timeout);
+    authorize(&store, actor, std::slice::from_ref(&role))?;
+    let _lock = store.lock()?;
     let graph = load_active_graph(&store)?;
     let mut sched = Scheduler::new(graph, 4);
 
@@ -56,11 +233,24 @@ pub fn cmd_next(workspace: &Path, role: Role) -> Result<(), String> {
 
             match locks.acquire(&node_id, &outputs, &timestamp) {
                 Ok(()) => {
+                    sched.graph.node_mut(&node_id).unwrap().assigned_to = Some(actor.to_string());
                     store.save_locks(&locks)?;
                     store.save_graph(&sched.graph)?;
+                    record_activity(
+                        &store,
+                        actor,
+                        "assign",
+                        Some(&sched.graph.id),
+                        Some(&node_id),
+                        serde_json::json!({ "role": role }),
+                    )?;
                     let node = sched.graph.node(&node_id).unwrap();
                     println!("assigned: {} ({})", node_id, node.description);
                     println!("  role: {role}");
+                    if !node.priority.is_normal() {
+                        println!("  priority: {}", node.priority);
+                    }
+                    println!("  actor: {actor}");
                     if !outputs.is_empty() {
                         println!("  locked: {}", outputs.join(", "));
                     }
@@ -69,6 +259,14 @@ pub fn cmd_next(workspace: &Path, role: Role) -> Result<(), String> {
                     // Mark blocked instead
                     sched.mark_blocked(&node_id)?;
                     store.save_graph(&sched.graph)?;
+                    record_activity(
+                        &store,
+                        actor,
+                        "block",
+                        Some(&sched.graph.id),
+                        Some(&node_id),
+                        serde_json::json!({ "conflict": conflict.to_string() }),
+                    )?;
                     println!("node {node_id} blocked: {conflict}");
                 }
             }
@@ -81,8 +279,82 @@ pub fn cmd_next(workspace: &Path, role: Role) -> Result<(), String> {
     Ok(())
 }
 
+/// `orch node add|skip|retry|reassign` — Edit the active work graph.
+///
+/// The edit is applied under the store's writer lock and rejected if the
+/// resulting graph is not a valid DAG. Skipping, retrying or reassigning a
+/// node releases its module locks. Each accepted edit is appended to the
+/// graph's history, attributed to `actor`, who must hold the planner
+/// role. Waits up to `wait_timeout` for another writer.
+pub fn cmd_node_edit(
+    workspace: &Path,
+    edit: GraphEdit,
+    reason: Option<String>,
+    actor: &str,
+    wait_timeout: Duration,
+) -> Result<(), String> {
+    let store = store_for(workspace)?.with_wait_timeout(wait_timeout);
+    authorize(&store, actor, &[Role::Planner])?;
+    let _lock = store.lock()?;
+    let mut graph = load_active_graph(&store)?;
+    let previous_status = graph.apply_edit(&edit)?;
+    let node_id = edit.node_id().to_string();
+
+    let mut released = Vec::new();
+    if edit.releases_locks() {
+        let mut locks = store.load_locks()?;
+        released = locks
+            .active_locks()
+            .iter()
+            .filter(|l| l.held_by == node_id)
+            .map(|l| l.module.clone())
+            .collect();
+        if !released.is_empty() {
+            locks.release(&node_id);
+            store.save_locks(&locks)?;
+        }
+    }
+    store.save_graph(&graph)?;
+    store.append_edit(&EditEvent {
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        graph_id: graph.id.clone(),
+        edit,
+        previous_status: previous_status.clone(),
+        reason,
+        actor: Some(actor.to_string()),
+    })?;
+
+    let node = graph.node(&node_id).unwrap();
+    match previous_status {
+        Some(prev) => println!("{node_id}: {prev} → {} ({})", node.status, node.owner_role),
+        None => println!("added: {node_id} ({})", node.description),
+    }
+    if !released.is_empty() {
+        println!("  released: {}", released.join(", "));
+    }
+    Ok(())
+}
+
 /// `orch apply <bundle.patchbundle.json>` — Apply a patch bundle and run gates.
-pub fn cmd_apply(workspace: &Path, bundle_path: &Path) -> Result<(), String> {
+///
+/// The bundle belongs to `node`, or else to the active graph's node whose
+/// ID or `patch_bundle` matches the bundle. For such a node, the gate
+/// results, the applied bundle, the rollback bundle and (when the gates
+/// pass) the patched files are stored under `artifacts/<node_id>/` and
+/// referenced from the node. With `git`, the bundle's files must be clean
+/// beforehand and a passing bundle is committed with `Boruna-*` trailers.
+/// Runs as `actor`, who must hold the implementer role. The store's writer
+/// lock is held from the lock-conflict check until the graph is updated,
+/// so two applies never both pass the check; waits up to `wait_timeout`
+/// for another writer to release it.
+pub fn cmd_apply(
+    workspace: &Path,
+    bundle_path: &Path,
+    node: Option<&str>,
+    git: bool,
+    actor: &str,
+    wait_timeout: Duration,
+) -> Result<(), String> {
     let bundle = PatchBundle::load(bundle_path)?;
 
     // Validate bundle format
@@ -94,11 +366,15 @@ pub fn cmd_apply(workspace: &Path, bundle_path: &Path) -> Result<(), String> {
         return Err("invalid bundle".into());
     }
 
-    let store = store_for(workspace)?;
+    let store = store_for(workspace)?.with_wait_timeout(wait_timeout);
+    authorize(&store, actor, &[Role::Implementer])?;
+    let _lock = store.lock()?;
+    let node_id = bundle_node(&store, &bundle, bundle_path, node)?;
     let locks = store.load_locks()?;
 
     // Check lock conflicts
-    let conflicts = locks.check_conflicts(&bundle.metadata.id, &bundle.metadata.touched_modules);
+    let holder = node_id.as_deref().unwrap_or(&bundle.metadata.id);
+    let conflicts = locks.check_conflicts(holder, &bundle.metadata.touched_modules);
     if !conflicts.is_empty() {
         println!("lock conflicts:");
         for c in &conflicts {
@@ -107,19 +383,52 @@ pub fn cmd_apply(workspace: &Path, bundle_path: &Path) -> Result<(), String> {
         return Err("cannot apply: lock conflict".into());
     }
 
-    // Apply the patch bundle
+    let files: Vec<String> = bundle.patches.iter().map(|p| p.file.clone()).collect();
+    let repo = if git {
+        let repo = GitRepo::open(workspace)?;
+        repo.ensure_clean(&files)?;
+        Some(repo)
+    } else {
+        None
+    };
+
+    // Record pre-patch trace hashes for the stability gate
+    let trace_baseline = bundle.expected_checks.trace_stability.as_ref().map(|cfg| {
+        println!(
+            "recording baseline trace hashes ({} cases)",
+            cfg.cases.len()
+        );
+        let ctx = GateContext {
+            workspace_root: workspace,
+            example_files: vec![],
+            cargo_target_dir: None,
+        };
+        TraceStabilityAdapter::record(&cfg.cases, &ctx)
+    });
+
+    // Apply the patch bundle to a sandboxed copy of the workspace
+    let sandbox = Sandbox::create(workspace, &[".git", "target", STORAGE_DIR], &files)?;
     println!(
         "applying bundle: {} ({})",
         bundle.metadata.id, bundle.metadata.intent
     );
-    let rollback = bundle.apply(workspace)?;
-    println!("  patches applied to {} files", bundle.patches.len());
+    let rollback = bundle.apply(sandbox.root())?;
+    let files_hash = trend::files_hash(sandbox.root(), &files);
+    println!(
+        "  patches applied to {} files in sandbox {}",
+        bundle.patches.len(),
+        sandbox.root().display()
+    );
 
-    // Run gates
-    let adapters: Vec<Box<dyn GateAdapter>> = build_gate_adapters(&bundle);
+    // Run gates in the sandbox, sharing the workspace's build cache
+    let adapters: Vec<Box<dyn GateAdapter>> = build_gate_adapters(&bundle, trace_baseline);
+    let target_dir = workspace.join("target");
     let ctx = GateContext {
-        workspace_root: workspace,
+        workspace_root: sandbox.root(),
         example_files: vec![],
+        cargo_target_dir: std::env::var_os("CARGO_TARGET_DIR")
+            .is_none()
+            .then_some(target_dir.as_path()),
     };
 
     println!("running gates...");
@@ -143,11 +452,22 @@ pub fn cmd_apply(workspace: &Path, bundle_path: &Path) -> Result<(), String> {
         "bundle_id": bundle.metadata.id,
         "results": results,
         "all_pass": all_pass,
+        "content_hash": bundle.content_hash(),
     });
     store.save_gate_result(&bundle.metadata.id, &gate_json)?;
+    record_gate_run(
+        &store,
+        "apply",
+        &bundle,
+        node_id.as_deref(),
+        files_hash,
+        &results,
+    )?;
 
     if all_pass {
         println!("all gates passed");
+        sandbox.sync_back(&files)?;
+        println!("  {} files synced to workspace", files.len());
         // Save rollback bundle for future use
         let rollback_path = store
             .bundles_dir()
@@ -155,18 +475,167 @@ pub fn cmd_apply(workspace: &Path, bundle_path: &Path) -> Result<(), String> {
         rollback.save(&rollback_path)?;
         println!("  rollback saved to {}", rollback_path.display());
     } else {
-        println!("gate failure — rolling back patches");
-        rollback.apply(workspace)?;
-        println!("  rollback complete");
-        return Err("gates failed".into());
+        println!("gate failure — workspace left untouched");
     }
+    drop(sandbox);
 
-    Ok(())
+    let commit = match &repo {
+        Some(repo) if all_pass => {
+            let sha = repo.commit_bundle(&bundle, node_id.as_deref(), actor)?;
+            println!("  committed: {sha}");
+            Some(sha)
+        }
+        _ => None,
+    };
+
+    record_activity(
+        &store,
+        actor,
+        "apply",
+        store.latest_graph()?.as_deref(),
+        node_id.as_deref(),
+        serde_json::json!({
+            "bundle_id": bundle.metadata.id,
+            "content_hash": bundle.content_hash(),
+            "all_pass": all_pass,
+            "commit": commit,
+        }),
+    )?;
+    if let Some(node_id) = &node_id {
+        store.clear_artifacts(node_id)?;
+        let mut artifacts = vec![
+            store.save_artifact(node_id, "gate_results", "gates.json", &to_json(&gate_json)?)?,
+            store.save_artifact(
+                node_id,
+                "bundle",
+                "bundle.patchbundle.json",
+                &to_json(&bundle)?,
+            )?,
+            store.save_artifact(
+                node_id,
+                "rollback_bundle",
+                "rollback.patchbundle.json",
+                &to_json(&rollback)?,
+            )?,
+        ];
+        if all_pass {
+            for patch in &bundle.patches {
+                let contents = std::fs::read(workspace.join(&patch.file))
+                    .map_err(|e| format!("cannot read {}: {e}", patch.file))?;
+                let name = format!("files/{}", patch.file);
+                artifacts.push(store.save_artifact(node_id, "file", &name, &contents)?);
+            }
+        }
+
+        let mut graph = load_active_graph(&store)?;
+        let node = graph
+            .node_mut(node_id)
+            .ok_or_else(|| format!("node not found: {node_id}"))?;
+        node.patch_bundle = Some(bundle_path.display().to_string());
+        node.artifacts = artifacts;
+        let count = node.artifacts.len();
+        store.save_graph(&graph)?;
+        println!(
+            "  {count} artifacts recorded in {}",
+            store.artifacts_dir(node_id).display()
+        );
+    }
+
+    if all_pass {
+        Ok(())
+    } else {
+        Err("gates failed".into())
+    }
+}
+
+/// The work node a bundle is applied for: the explicit `node`, or the
+/// active graph's node whose ID equals the bundle ID or whose
+/// `patch_bundle` names the bundle. `None` when there is no match.
+fn bundle_node(
+    store: &Store,
+    bundle: &PatchBundle,
+    bundle_path: &Path,
+    node: Option<&str>,
+) -> Result<Option<String>, String> {
+    if let Some(node) = node {
+        let graph = load_active_graph(store)?;
+        graph
+            .node(node)
+            .ok_or_else(|| format!("node not found: {node}"))?;
+        return Ok(Some(node.to_string()));
+    }
+    if store.latest_graph()?.is_none() {
+        return Ok(None);
+    }
+    let graph = load_active_graph(store)?;
+    let path = bundle_path.display().to_string();
+    Ok(graph
+        .nodes
+        .iter()
+        .find(|n| {
+            n.id == bundle.metadata.id
+                || n.patch_bundle.as_deref() == Some(bundle.metadata.id.as_str())
+                || n.patch_bundle.as_deref() == Some(path.as_str())
+        })
+        .map(|n| n.id.clone()))
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> Result<Vec<u8>, String> {
+    serde_json::to_vec_pretty(value).map_err(|e| format!("serialize error: {e}"))
 }
 
 /// `orch review <bundle.patchbundle.json>` — Review a bundle: validate + gates + checklist.
-pub fn cmd_review(workspace: &Path, bundle_path: &Path) -> Result<(), String> {
+///
+/// `actor` must hold the reviewer or red-team role and, by the two-person
+/// rule, be neither the bundle's author nor the actor its node is assigned
+/// to, nor any actor the activity log records applying it. With
+/// `against_commit`, the bundle has already landed: the commit's
+/// `Boruna-*` trailers and changed files must match the bundle, and the
+/// actor named in them may not review it either. The decision is recorded in the
+/// activity log.
+pub fn cmd_review(
+    workspace: &Path,
+    bundle_path: &Path,
+    against_commit: Option<&str>,
+    actor: &str,
+) -> Result<(), String> {
     let bundle = PatchBundle::load(bundle_path)?;
+    let store = store_for(workspace)?;
+    authorize(&store, actor, &[Role::Reviewer, Role::RedTeam])?;
+    let node_id = bundle_node(&store, &bundle, bundle_path, None)?;
+    let assignee = match &node_id {
+        Some(id) => load_active_graph(&store)?
+            .node(id)
+            .and_then(|n| n.assigned_to.clone()),
+        None => None,
+    };
+    let appliers: Vec<String> = store
+        .load_activity()?
+        .into_iter()
+        .filter(|e| e.action == "apply" && e.detail["bundle_id"] == bundle.metadata.id)
+        .map(|e| e.actor)
+        .collect();
+    identity::check_two_person_rule(
+        actor,
+        &bundle.metadata.author,
+        assignee.as_deref(),
+        &appliers,
+    )?;
+    let finish = |decision: &str, reason: &str| -> Result<(), String> {
+        output_review_result(decision, reason);
+        record_activity(
+            &store,
+            actor,
+            "review",
+            store.latest_graph()?.as_deref(),
+            node_id.as_deref(),
+            serde_json::json!({
+                "bundle_id": bundle.metadata.id,
+                "decision": decision,
+                "reason": reason,
+            }),
+        )
+    };
 
     println!("=== Review: {} ===", bundle.metadata.id);
     println!("intent: {}", bundle.metadata.intent);
@@ -175,6 +644,29 @@ pub fn cmd_review(workspace: &Path, bundle_path: &Path) -> Result<(), String> {
     println!("modules: {}", bundle.metadata.touched_modules.join(", "));
     println!();
 
+    if let Some(rev) = against_commit {
+        let repo = GitRepo::open(workspace)?;
+        print!("commit {rev}: ");
+        match repo.verify_bundle_commit(rev, &bundle) {
+            Ok(trailers) => {
+                println!("PASS");
+                if let Some(applier) = trailers.get(git::TRAILER_ACTOR) {
+                    identity::check_two_person_rule(
+                        actor,
+                        &bundle.metadata.author,
+                        None,
+                        std::slice::from_ref(applier),
+                    )?;
+                }
+            }
+            Err(e) => {
+                println!("FAIL");
+                println!("  - {e}");
+                return finish("reject", "commit does not match bundle");
+            }
+        }
+    }
+
     // 1. Validate format
     print!("format validation: ");
     match bundle.validate() {
@@ -184,10 +676,7 @@ pub fn cmd_review(workspace: &Path, bundle_path: &Path) -> Result<(), String> {
             for e in &errors {
                 println!("  - {e}");
             }
-            return {
-                output_review_result("reject", "format validation failed");
-                Ok(())
-            };
+            return finish("reject", "format validation failed");
         }
     }
 
@@ -195,11 +684,13 @@ pub fn cmd_review(workspace: &Path, bundle_path: &Path) -> Result<(), String> {
     let hash = bundle.content_hash();
     println!("content hash: {hash}");
 
-    // 3. Run gates (compile + test)
-    let adapters: Vec<Box<dyn GateAdapter>> = build_gate_adapters(&bundle);
+    // 3. Run gates (compile + test). Review does not apply the bundle, so
+    //    there is no baseline and trace_stability reports SKIP.
+    let adapters: Vec<Box<dyn GateAdapter>> = build_gate_adapters(&bundle, None);
     let ctx = GateContext {
         workspace_root: workspace,
         example_files: vec![],
+        cargo_target_dir: None,
     };
 
     println!("\nrunning gates...");
@@ -219,10 +710,7 @@ pub fn cmd_review(workspace: &Path, bundle_path: &Path) -> Result<(), String> {
     }
 
     if !all_pass {
-        return {
-            output_review_result("reject", "gate check failed");
-            Ok(())
-        };
+        return finish("reject", "gate check failed");
     }
 
     // 4. Reviewer checklist
@@ -231,14 +719,13 @@ pub fn cmd_review(workspace: &Path, bundle_path: &Path) -> Result<(), String> {
         println!("  [ ] {item}");
     }
 
-    // 5. Two-person rule check
+    // 5. Two-person rule, enforced above
     println!(
-        "\ntwo-person rule: reviewer must differ from author ({})",
+        "\ntwo-person rule: reviewer {actor} differs from author {}",
         bundle.metadata.author
     );
 
     // Store gate results
-    let store = store_for(workspace)?;
     let gate_json = serde_json::json!({
         "bundle_id": bundle.metadata.id,
         "review": true,
@@ -247,9 +734,18 @@ pub fn cmd_review(workspace: &Path, bundle_path: &Path) -> Result<(), String> {
         "content_hash": hash,
     });
     store.save_gate_result(&format!("{}-review", bundle.metadata.id), &gate_json)?;
+    let files: Vec<String> = bundle.patches.iter().map(|p| p.file.clone()).collect();
+    let _lock = store.lock()?;
+    record_gate_run(
+        &store,
+        "review",
+        &bundle,
+        node_id.as_deref(),
+        trend::files_hash(workspace, &files),
+        &results,
+    )?;
 
-    output_review_result("approve", "all gates passed, checklist presented");
-    Ok(())
+    finish("approve", "all gates passed, checklist presented")
 }
 
 /// `orch status` — Show current graph state.
@@ -262,6 +758,7 @@ pub fn cmd_status(workspace: &Path) -> Result<(), String> {
     println!("=== Work Graph: {} ===", graph.id);
     println!("{}", graph.description);
     println!();
+    println!("strategy: {}", graph.strategy);
     println!("nodes: {} total", summary.total);
     println!("  passed:  {}", summary.passed);
     println!("  running: {}", summary.running);
@@ -269,6 +766,7 @@ pub fn cmd_status(workspace: &Path) -> Result<(), String> {
     println!("  blocked: {}", summary.blocked);
     println!("  failed:  {}", summary.failed);
     println!("  pending: {}", summary.pending);
+    println!("  skipped: {}", summary.skipped);
     println!();
 
     for node in &graph.nodes {
@@ -279,14 +777,27 @@ pub fn cmd_status(workspace: &Path) -> Result<(), String> {
             NodeStatus::Blocked => "[!!]",
             NodeStatus::Failed => "[XX]",
             NodeStatus::Pending => "[  ]",
+            NodeStatus::Skipped => "[--]",
+        };
+        let priority = if node.priority.is_normal() {
+            String::new()
+        } else {
+            format!(", {}", node.priority)
         };
         println!(
-            "  {status_icon} {} — {} ({})",
+            "  {status_icon} {} — {} ({}{priority})",
             node.id, node.description, node.owner_role
         );
         if !node.dependencies.is_empty() {
             println!("        deps: {}", node.dependencies.join(", "));
         }
+        if !node.artifacts.is_empty() {
+            println!(
+                "        artifacts: {} in {}",
+                node.artifacts.len(),
+                store.artifacts_dir(&node.id).display()
+            );
+        }
     }
 
     // Show locks
@@ -322,9 +833,12 @@ pub fn cmd_report(workspace: &Path) -> Result<(), String> {
                 "description": n.description,
                 "status": n.status,
                 "role": n.owner_role,
+                "priority": n.priority,
                 "dependencies": n.dependencies,
                 "outputs": n.outputs,
                 "tags": n.tags,
+                "patch_bundle": n.patch_bundle,
+                "artifacts": n.artifacts,
             })
         })
         .collect();
@@ -344,6 +858,7 @@ pub fn cmd_report(workspace: &Path) -> Result<(), String> {
     let report = serde_json::json!({
         "graph_id": graph.id,
         "description": graph.description,
+        "strategy": graph.strategy,
         "total_nodes": summary.total,
         "passed": summary.passed,
         "running": summary.running,
@@ -351,6 +866,7 @@ pub fn cmd_report(workspace: &Path) -> Result<(), String> {
         "blocked": summary.blocked,
         "failed": summary.failed,
         "pending": summary.pending,
+        "skipped": summary.skipped,
         "nodes": nodes_json,
         "locks": locks_json,
     });
@@ -359,12 +875,270 @@ pub fn cmd_report(workspace: &Path) -> Result<(), String> {
     Ok(())
 }
 
+/// `orch analyze [--format text|json]` — Critical path, expected node
+/// durations from the store's gate timings, projected completion, and the
+/// blocked nodes whose unblocking frees the most work.
+pub fn cmd_analyze(workspace: &Path, format: &str) -> Result<(), String> {
+    let json = match format {
+        "text" => false,
+        "json" => true,
+        _ => {
+            return Err(format!(
+                "unknown analyze format: {format} (expected text|json)"
+            ))
+        }
+    };
+    let store = store_for(workspace)?;
+    let graph = load_active_graph(&store)?;
+    let timings = gate_timings(&store, &graph)?;
+    let analysis = analyze::analyze(&graph, &timings, 4)?;
+    let completion = chrono::Utc::now()
+        + chrono::Duration::milliseconds(i64::try_from(analysis.projected_ms).unwrap_or(i64::MAX));
+
+    if json {
+        let mut value =
+            serde_json::to_value(&analysis).map_err(|e| format!("serialize error: {e}"))?;
+        value["projected_completion"] = serde_json::json!(completion.to_rfc3339());
+        println!("{}", serde_json::to_string_pretty(&value).unwrap());
+        return Ok(());
+    }
+
+    println!("=== Analysis: {} ===", analysis.graph_id);
+    if analysis.critical_path.is_empty() {
+        println!("critical path: none (all nodes finished)");
+    } else {
+        println!(
+            "critical path: {} ({}ms)",
+            analysis.critical_path.join(" → "),
+            analysis.critical_path_ms
+        );
+    }
+    println!(
+        "remaining: {} nodes, {}ms of work",
+        analysis.remaining_nodes, analysis.remaining_ms
+    );
+    println!(
+        "projected completion: {}ms ({}, max_parallel {})",
+        analysis.projected_ms,
+        completion.to_rfc3339(),
+        analysis.max_parallel
+    );
+    println!();
+    println!("expected durations:");
+    for e in &analysis.estimates {
+        let source = match e.samples {
+            0 => "default".to_string(),
+            1 => "1 sample".to_string(),
+            n => format!("{n} samples"),
+        };
+        println!(
+            "  {} [{}] {}ms ({source})",
+            e.node_id, e.status, e.expected_ms
+        );
+    }
+    if !analysis.blockers.is_empty() {
+        println!();
+        println!("unblock next:");
+        for b in &analysis.blockers {
+            print!(
+                "  {} [{}] unlocks {} nodes, {}ms",
+                b.node_id,
+                b.status,
+                b.unlocks.len(),
+                b.unlocks_ms
+            );
+            if b.unlocks.is_empty() {
+                println!();
+            } else {
+                println!(": {}", b.unlocks.join(", "));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Gate timings per node, in milliseconds: one sample per gate run (apply
+/// or review), the sum of its gates' durations. Runs come from the gate
+/// history; a stored gate result whose bundle never appears there (written
+/// before the history existed) counts as one more run. A run without a node
+/// belongs to the node whose ID or `patch_bundle` equals its bundle ID, or
+/// whose recorded gate results name that bundle.
+fn gate_timings(store: &Store, graph: &WorkGraph) -> Result<BTreeMap<String, Vec<u64>>, String> {
+    let mut owners: BTreeMap<String, String> = BTreeMap::new();
+    for node in graph.nodes.iter().rev() {
+        owners.insert(node.id.clone(), node.id.clone());
+        if let Some(bundle) = &node.patch_bundle {
+            owners.insert(bundle.clone(), node.id.clone());
+        }
+        let recorded = store.artifacts_dir(&node.id).join("gates.json");
+        if let Ok(data) = std::fs::read_to_string(recorded) {
+            let gates: serde_json::Value =
+                serde_json::from_str(&data).map_err(|e| format!("parse error: {e}"))?;
+            if let Some(bundle) = gates["bundle_id"].as_str() {
+                owners.insert(bundle.to_string(), node.id.clone());
+            }
+        }
+    }
+
+    let mut timings: BTreeMap<String, Vec<u64>> = BTreeMap::new();
+    let history = store.load_gate_history()?;
+    for run in &history {
+        let node_id = run.node_id.as_ref().or_else(|| owners.get(&run.bundle_id));
+        if let Some(node_id) = node_id {
+            timings
+                .entry(node_id.clone())
+                .or_default()
+                .push(run.duration_ms());
+        }
+    }
+    let tracked: BTreeSet<&str> = history.iter().map(|r| r.bundle_id.as_str()).collect();
+    for run in store.list_gate_results()? {
+        let Some(bundle) = run["bundle_id"].as_str() else {
+            continue;
+        };
+        let Some(node_id) = owners.get(bundle).filter(|_| !tracked.contains(bundle)) else {
+            continue;
+        };
+        let total: u64 = run["results"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|r| r["duration_ms"].as_u64())
+            .sum();
+        timings.entry(node_id.clone()).or_default().push(total);
+    }
+    Ok(timings)
+}
+
+/// `orch gates history` — List recorded gate runs, oldest first, with
+/// per-gate trends. Runs can be narrowed to a node, a bundle and a gate.
+/// A gate that changed outcome while the bundle's files were unchanged is
+/// flagged flaky.
+pub fn cmd_gates_history(
+    workspace: &Path,
+    node: Option<&str>,
+    bundle: Option<&str>,
+    gate: Option<&str>,
+    format: &str,
+) -> Result<(), String> {
+    let json = match format {
+        "text" => false,
+        "json" => true,
+        _ => {
+            return Err(format!(
+                "unknown history format: {format} (expected text|json)"
+            ))
+        }
+    };
+    let store = store_for(workspace)?;
+    let mut runs: Vec<GateRun> = store
+        .load_gate_history()?
+        .into_iter()
+        .filter(|r| node.is_none_or(|n| r.node_id.as_deref() == Some(n)))
+        .filter(|r| bundle.is_none_or(|b| r.bundle_id == b))
+        .collect();
+    if let Some(gate) = gate {
+        for run in &mut runs {
+            run.gates.retain(|g| g.gate == gate);
+        }
+        runs.retain(|r| !r.gates.is_empty());
+    }
+    let flips = trend::flips(&runs);
+    let trends = trend::trends(&runs);
+    let flaky_in = |i: usize, gate: &str| {
+        flips
+            .iter()
+            .any(|f| f.run == i && f.gate == gate && f.flaky)
+    };
+
+    if json {
+        let runs_json: Vec<serde_json::Value> = runs
+            .iter()
+            .enumerate()
+            .map(|(i, run)| {
+                let mut value = serde_json::to_value(run).unwrap_or_default();
+                let flaky: Vec<&str> = run
+                    .gates
+                    .iter()
+                    .filter(|g| flaky_in(i, &g.gate))
+                    .map(|g| g.gate.as_str())
+                    .collect();
+                value["flaky"] = serde_json::json!(flaky);
+                value
+            })
+            .collect();
+        let report = serde_json::json!({ "runs": runs_json, "trends": trends });
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+        return Ok(());
+    }
+
+    if runs.is_empty() {
+        println!("no gate runs recorded");
+        return Ok(());
+    }
+    println!("=== Gate history ===");
+    for (i, run) in runs.iter().enumerate() {
+        println!(
+            "{} {} {} (bundle {}, files {})",
+            run.timestamp,
+            run.subject(),
+            run.action,
+            run.bundle_id,
+            &run.files_hash[..run.files_hash.len().min(12)]
+        );
+        for g in &run.gates {
+            let icon = match g.status {
+                adapters::GateStatus::Pass => "PASS",
+                adapters::GateStatus::Fail => "FAIL",
+                adapters::GateStatus::Skip => "SKIP",
+            };
+            let flag = if flaky_in(i, &g.gate) { " flaky" } else { "" };
+            println!("  [{icon}] {} ({}ms){flag}", g.gate, g.duration_ms);
+        }
+    }
+    println!();
+    println!("trends:");
+    for t in &trends {
+        print!(
+            "  {} {}: {} runs, {} passed, {} failed, mean {}ms",
+            t.subject, t.gate, t.runs, t.passed, t.failed, t.mean_ms
+        );
+        if t.flaky {
+            let flips = match t.flaky_flips {
+                1 => "1 flip".to_string(),
+                n => format!("{n} flips"),
+            };
+            println!(" — FLAKY ({flips} with unchanged files)");
+        } else {
+            println!();
+        }
+    }
+    Ok(())
+}
+
+/// `orch graph --format dot|mermaid|json` — Export the work graph with node
+/// status, role, lock ownership, and dependency edges.
+pub fn cmd_graph(workspace: &Path, format: &str) -> Result<(), String> {
+    let format: GraphFormat = format.parse()?;
+    let store = store_for(workspace)?;
+    let graph = load_active_graph(&store)?;
+    let locks = store.load_locks()?;
+    print!("{}", render::render(&graph, &locks, format));
+    if format == GraphFormat::Json {
+        println!();
+    }
+    Ok(())
+}
+
 fn output_review_result(decision: &str, reason: &str) {
     println!("\n=== Review Result: {} ===", decision.to_uppercase());
     println!("reason: {reason}");
 }
 
-fn build_gate_adapters(bundle: &PatchBundle) -> Vec<Box<dyn GateAdapter>> {
+fn build_gate_adapters(
+    bundle: &PatchBundle,
+    trace_baseline: Option<adapters::TraceHashes>,
+) -> Vec<Box<dyn GateAdapter>> {
     let mut adapters: Vec<Box<dyn GateAdapter>> = Vec::new();
 
     if bundle.expected_checks.compile {
@@ -378,6 +1152,24 @@ fn build_gate_adapters(bundle: &PatchBundle) -> Vec<Box<dyn GateAdapter>> {
             expected_hashes: vec![],
         }));
     }
+    if !bundle.expected_checks.difftest_corpus.is_empty() {
+        adapters.push(Box::new(DiffTestAdapter {
+            corpus_dirs: bundle.expected_checks.difftest_corpus.clone(),
+        }));
+    }
+    if bundle.expected_checks.deterministic_build {
+        adapters.push(Box::new(DeterministicBuildAdapter));
+    }
+    if let Some(cfg) = &bundle.expected_checks.trace_stability {
+        let mut affected: std::collections::BTreeSet<String> =
+            cfg.affects.iter().cloned().collect();
+        affected.extend(bundle.patches.iter().map(|p| p.file.clone()));
+        adapters.push(Box::new(TraceStabilityAdapter {
+            cases: cfg.cases.clone(),
+            affected,
+            baseline: trace_baseline,
+        }));
+    }
 
     adapters
 }
@@ -0,0 +1,317 @@
+//! Critical-path and completion analysis for `orch analyze`.
+//!
+//! Expected node durations come from gate timings recorded in the store:
+//! a node's estimate is the mean of its samples, and a node without samples
+//! takes the mean of the estimates that have them. Only unfinished nodes
+//! (neither passed nor skipped) count toward remaining work, and a running
+//! node counts in full. Ties resolve in graph order so the same inputs
+//! always give the same analysis.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+
+use super::graph::{NodeStatus, WorkGraph};
+use super::Scheduler;
+
+/// Expected duration of one node.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NodeEstimate {
+    pub node_id: String,
+    pub status: NodeStatus,
+    pub expected_ms: u64,
+    /// Gate timings the estimate is based on; 0 means it is the default.
+    pub samples: usize,
+}
+
+/// A blocked or failed node and the work that waits only on it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Blocker {
+    pub node_id: String,
+    pub status: NodeStatus,
+    /// Unfinished nodes downstream with no other blocked or failed
+    /// ancestor, in graph order.
+    pub unlocks: Vec<String>,
+    pub unlocks_ms: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Analysis {
+    pub graph_id: String,
+    /// Longest chain of unfinished nodes by expected duration, first node
+    /// first.
+    pub critical_path: Vec<String>,
+    pub critical_path_ms: u64,
+    pub remaining_nodes: usize,
+    pub remaining_ms: u64,
+    pub max_parallel: usize,
+    /// The longer of the critical path and the remaining work spread over
+    /// `max_parallel` slots.
+    pub projected_ms: u64,
+    /// Every node, in graph order.
+    pub estimates: Vec<NodeEstimate>,
+    /// Most downstream work unlocked first.
+    pub blockers: Vec<Blocker>,
+}
+
+/// Analyze `graph` given each node's recorded gate timings in milliseconds.
+pub fn analyze(
+    graph: &WorkGraph,
+    timings: &BTreeMap<String, Vec<u64>>,
+    max_parallel: usize,
+) -> Result<Analysis, String> {
+    let sched = Scheduler::new(graph.clone(), max_parallel);
+    sched.validate()?;
+    let order = sched.topological_order()?;
+    let position: BTreeMap<&str, usize> = graph
+        .nodes
+        .iter()
+        .enumerate()
+        .map(|(i, n)| (n.id.as_str(), i))
+        .collect();
+
+    let means: BTreeMap<&str, u64> = timings
+        .iter()
+        .filter(|(_, samples)| !samples.is_empty())
+        .map(|(id, samples)| (id.as_str(), mean(samples)))
+        .collect();
+    let default_ms = mean(&means.values().copied().collect::<Vec<_>>());
+    let estimates: Vec<NodeEstimate> = graph
+        .nodes
+        .iter()
+        .map(|n| NodeEstimate {
+            node_id: n.id.clone(),
+            status: n.status.clone(),
+            expected_ms: means.get(n.id.as_str()).copied().unwrap_or(default_ms),
+            samples: timings.get(&n.id).map_or(0, Vec::len),
+        })
+        .collect();
+    let expected: BTreeMap<&str, u64> = estimates
+        .iter()
+        .map(|e| (e.node_id.as_str(), e.expected_ms))
+        .collect();
+    let unfinished: BTreeSet<&str> = graph
+        .nodes
+        .iter()
+        .filter(|n| !matches!(n.status, NodeStatus::Passed | NodeStatus::Skipped))
+        .map(|n| n.id.as_str())
+        .collect();
+
+    // Longest path starting at each unfinished node, weighed by expected
+    // duration and then node count: (ms, nodes, next node).
+    let mut longest: BTreeMap<&str, (u64, usize, Option<&str>)> = BTreeMap::new();
+    for id in order.iter().rev() {
+        let id = id.as_str();
+        if !unfinished.contains(id) {
+            continue;
+        }
+        let mut best: (u64, usize, Option<&str>) = (0, 0, None);
+        for dependent in graph
+            .nodes
+            .iter()
+            .filter(|n| n.dependencies.iter().any(|d| d == id))
+        {
+            if let Some(&(ms, nodes, _)) = longest.get(dependent.id.as_str()) {
+                if (ms, nodes) > (best.0, best.1) {
+                    best = (ms, nodes, Some(dependent.id.as_str()));
+                }
+            }
+        }
+        longest.insert(id, (expected[id] + best.0, best.1 + 1, best.2));
+    }
+    let mut head: Option<(&str, (u64, usize))> = None;
+    for node in &graph.nodes {
+        if let Some(&(ms, nodes, _)) = longest.get(node.id.as_str()) {
+            if head.is_none_or(|(_, best)| (ms, nodes) > best) {
+                head = Some((node.id.as_str(), (ms, nodes)));
+            }
+        }
+    }
+    let mut critical_path = Vec::new();
+    let mut next = head.map(|(id, _)| id);
+    while let Some(id) = next {
+        critical_path.push(id.to_string());
+        next = longest[id].2;
+    }
+    let critical_path_ms = head.map_or(0, |(_, (ms, _))| ms);
+
+    let remaining_ms: u64 = unfinished.iter().map(|id| expected[id]).sum();
+    let spread_ms = remaining_ms.div_ceil(max_parallel.max(1) as u64);
+
+    // The blocked or failed nodes upstream of each node.
+    let mut stuck_above: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    for id in &order {
+        let node = graph.node(id).expect("ordered node exists");
+        let mut above = BTreeSet::new();
+        for dep in &node.dependencies {
+            let dep = dep.as_str();
+            above.extend(stuck_above.get(dep).into_iter().flatten().copied());
+            if graph
+                .node(dep)
+                .is_some_and(|d| matches!(d.status, NodeStatus::Blocked | NodeStatus::Failed))
+            {
+                above.insert(dep);
+            }
+        }
+        stuck_above.insert(id.as_str(), above);
+    }
+    let mut blockers: Vec<Blocker> = graph
+        .nodes
+        .iter()
+        .filter(|n| matches!(n.status, NodeStatus::Blocked | NodeStatus::Failed))
+        .map(|b| {
+            let unlocks: Vec<String> = graph
+                .nodes
+                .iter()
+                .filter(|n| unfinished.contains(n.id.as_str()))
+                .filter(|n| {
+                    let above = &stuck_above[n.id.as_str()];
+                    above.len() == 1 && above.contains(b.id.as_str())
+                })
+                .map(|n| n.id.clone())
+                .collect();
+            Blocker {
+                node_id: b.id.clone(),
+                status: b.status.clone(),
+                unlocks_ms: unlocks.iter().map(|id| expected[id.as_str()]).sum(),
+                unlocks,
+            }
+        })
+        .collect();
+    blockers.sort_by_key(|b| {
+        (
+            std::cmp::Reverse(b.unlocks_ms),
+            std::cmp::Reverse(b.unlocks.len()),
+            position[b.node_id.as_str()],
+        )
+    });
+
+    Ok(Analysis {
+        graph_id: graph.id.clone(),
+        critical_path,
+        critical_path_ms,
+        remaining_nodes: unfinished.len(),
+        remaining_ms,
+        max_parallel,
+        projected_ms: critical_path_ms.max(spread_ms),
+        estimates,
+        blockers,
+    })
+}
+
+fn mean(samples: &[u64]) -> u64 {
+    if samples.is_empty() {
+        return 0;
+    }
+    samples.iter().sum::<u64>() / samples.len() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{Role, WorkNode};
+
+    fn node(id: &str, deps: &[&str], status: NodeStatus) -> WorkNode {
+        WorkNode {
+            id: id.to_string(),
+            description: format!("node {id}"),
+            inputs: vec![],
+            outputs: vec![],
+            dependencies: deps.iter().map(|s| s.to_string()).collect(),
+            owner_role: Role::Implementer,
+            tags: vec![],
+            status,
+            assigned_to: None,
+            patch_bundle: None,
+            review_result: None,
+            artifacts: vec![],
+            priority: Default::default(),
+        }
+    }
+
+    fn graph(nodes: Vec<WorkNode>) -> WorkGraph {
+        WorkGraph {
+            schema_version: 1,
+            id: "g1".into(),
+            description: "test".into(),
+            nodes,
+            strategy: Default::default(),
+        }
+    }
+
+    fn timings(entries: &[(&str, &[u64])]) -> BTreeMap<String, Vec<u64>> {
+        entries
+            .iter()
+            .map(|(id, samples)| (id.to_string(), samples.to_vec()))
+            .collect()
+    }
+
+    #[test]
+    fn critical_path_follows_expected_durations() {
+        // A → B → D and A → C → D; C is slow.
+        let g = graph(vec![
+            node("A", &[], NodeStatus::Passed),
+            node("B", &["A"], NodeStatus::Pending),
+            node("C", &["A"], NodeStatus::Pending),
+            node("D", &["B", "C"], NodeStatus::Pending),
+        ]);
+        let t = timings(&[("B", &[100]), ("C", &[500, 700]), ("D", &[200])]);
+        let a = analyze(&g, &t, 4).unwrap();
+        assert_eq!(a.critical_path, vec!["C", "D"]);
+        assert_eq!(a.critical_path_ms, 800);
+        assert_eq!(a.remaining_nodes, 3);
+        assert_eq!(a.remaining_ms, 900);
+        assert_eq!(a.projected_ms, 800);
+        assert_eq!(a.estimates[2].expected_ms, 600);
+        assert_eq!(a.estimates[2].samples, 2);
+    }
+
+    #[test]
+    fn nodes_without_history_take_the_default() {
+        let g = graph(vec![
+            node("A", &[], NodeStatus::Pending),
+            node("B", &[], NodeStatus::Pending),
+            node("C", &[], NodeStatus::Pending),
+        ]);
+        let t = timings(&[("A", &[100]), ("B", &[300])]);
+        let a = analyze(&g, &t, 1).unwrap();
+        assert_eq!(a.estimates[2].expected_ms, 200);
+        assert_eq!(a.estimates[2].samples, 0);
+        // One slot: the work runs back to back.
+        assert_eq!(a.projected_ms, 600);
+        // No dependencies: the slowest node is the whole critical path.
+        assert_eq!(a.critical_path, vec!["B"]);
+    }
+
+    #[test]
+    fn no_history_falls_back_to_node_count() {
+        let g = graph(vec![
+            node("A", &[], NodeStatus::Pending),
+            node("B", &[], NodeStatus::Pending),
+            node("C", &["B"], NodeStatus::Pending),
+        ]);
+        let a = analyze(&g, &BTreeMap::new(), 4).unwrap();
+        assert_eq!(a.critical_path, vec!["B", "C"]);
+        assert_eq!(a.projected_ms, 0);
+    }
+
+    #[test]
+    fn blockers_ranked_by_work_they_alone_unlock() {
+        let g = graph(vec![
+            node("X", &[], NodeStatus::Blocked),
+            node("Y", &[], NodeStatus::Failed),
+            node("X1", &["X"], NodeStatus::Pending),
+            node("X2", &["X1"], NodeStatus::Pending),
+            node("Y1", &["Y"], NodeStatus::Pending),
+            node("XY", &["X", "Y"], NodeStatus::Pending),
+        ]);
+        let t = timings(&[("X1", &[100]), ("X2", &[100]), ("Y1", &[500])]);
+        let a = analyze(&g, &t, 4).unwrap();
+        assert_eq!(a.blockers.len(), 2);
+        assert_eq!(a.blockers[0].node_id, "Y");
+        assert_eq!(a.blockers[0].unlocks, vec!["Y1"]);
+        assert_eq!(a.blockers[0].unlocks_ms, 500);
+        // XY waits on both, so neither unlocks it alone.
+        assert_eq!(a.blockers[1].unlocks, vec!["X1", "X2"]);
+    }
+}
@@ -0,0 +1,211 @@
+use serde::{Deserialize, Serialize};
+
+use super::{NodeStatus, Role, Scheduler, WorkGraph, WorkNode};
+
+/// A change to a live work graph, applied with [`WorkGraph::apply_edit`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum GraphEdit {
+    /// Insert a new pending node.
+    Add { node: Box<WorkNode> },
+    /// Give up on a node; dependents treat it as satisfied.
+    Skip { node_id: String },
+    /// Put a failed, blocked or skipped node back to pending.
+    Retry { node_id: String },
+    /// Hand a node to a different role.
+    Reassign { node_id: String, role: Role },
+}
+
+impl GraphEdit {
+    /// The node the edit targets.
+    pub fn node_id(&self) -> &str {
+        match self {
+            GraphEdit::Add { node } => &node.id,
+            GraphEdit::Skip { node_id }
+            | GraphEdit::Retry { node_id }
+            | GraphEdit::Reassign { node_id, .. } => node_id,
+        }
+    }
+
+    /// Whether applying the edit frees the node's module locks.
+    pub fn releases_locks(&self) -> bool {
+        !matches!(self, GraphEdit::Add { .. })
+    }
+}
+
+/// One line of a graph's edit history.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EditEvent {
+    pub timestamp: String,
+    pub graph_id: String,
+    #[serde(flatten)]
+    pub edit: GraphEdit,
+    /// Status of the node before the edit; absent for `add`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_status: Option<NodeStatus>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// Who made the edit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actor: Option<String>,
+}
+
+impl WorkGraph {
+    /// Apply `edit`, re-validating the DAG. On error the graph is left
+    /// unchanged. Returns the node's status before the edit (`None` for
+    /// an added node).
+    pub fn apply_edit(&mut self, edit: &GraphEdit) -> Result<Option<NodeStatus>, String> {
+        let mut next = self.clone();
+        let previous = next.edit_in_place(edit)?;
+        Scheduler::new(next.clone(), 1).validate()?;
+        *self = next;
+        Ok(previous)
+    }
+
+    fn edit_in_place(&mut self, edit: &GraphEdit) -> Result<Option<NodeStatus>, String> {
+        if let GraphEdit::Add { node } = edit {
+            if self.node(&node.id).is_some() {
+                return Err(format!("node already exists: {}", node.id));
+            }
+            if let Some(dep) = node.dependencies.iter().find(|d| self.node(d).is_none()) {
+                return Err(format!("unknown dependency: {dep}"));
+            }
+            let mut node = (**node).clone();
+            node.status = NodeStatus::Pending;
+            node.assigned_to = None;
+            self.nodes.push(node);
+            return Ok(None);
+        }
+
+        let node_id = edit.node_id();
+        let node = self
+            .node_mut(node_id)
+            .ok_or_else(|| format!("node not found: {node_id}"))?;
+        let previous = node.status.clone();
+        match edit {
+            GraphEdit::Add { .. } => unreachable!("handled above"),
+            GraphEdit::Skip { .. } => {
+                if matches!(previous, NodeStatus::Passed | NodeStatus::Skipped) {
+                    return Err(format!("cannot skip {node_id}: already {previous}"));
+                }
+                node.status = NodeStatus::Skipped;
+                node.assigned_to = None;
+            }
+            GraphEdit::Retry { .. } => {
+                if !matches!(
+                    previous,
+                    NodeStatus::Failed | NodeStatus::Blocked | NodeStatus::Skipped
+                ) {
+                    return Err(format!(
+                        "cannot retry {node_id}: it is {previous}, not failed, blocked or skipped"
+                    ));
+                }
+                node.status = NodeStatus::Pending;
+                node.assigned_to = None;
+                node.review_result = None;
+            }
+            GraphEdit::Reassign { role, .. } => {
+                if matches!(previous, NodeStatus::Passed | NodeStatus::Skipped) {
+                    return Err(format!("cannot reassign {node_id}: already {previous}"));
+                }
+                node.owner_role = role.clone();
+                node.assigned_to = None;
+                // Work in progress under the old role is abandoned.
+                if previous == NodeStatus::Running {
+                    node.status = NodeStatus::Pending;
+                }
+            }
+        }
+        Ok(Some(previous))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(id: &str, deps: &[&str]) -> WorkNode {
+        WorkNode {
+            id: id.into(),
+            description: format!("node {id}"),
+            inputs: vec![],
+            outputs: vec![],
+            dependencies: deps.iter().map(|d| d.to_string()).collect(),
+            owner_role: Role::Implementer,
+            tags: vec![],
+            status: NodeStatus::Pending,
+            assigned_to: None,
+            patch_bundle: None,
+            review_result: None,
+            artifacts: vec![],
+            priority: Default::default(),
+        }
+    }
+
+    fn graph(nodes: Vec<WorkNode>) -> WorkGraph {
+        WorkGraph {
+            schema_version: 1,
+            id: "G-edit".into(),
+            description: "edit".into(),
+            nodes,
+            strategy: Default::default(),
+        }
+    }
+
+    #[test]
+    fn add_rejects_duplicates_and_unknown_dependencies() {
+        let mut g = graph(vec![node("A", &[])]);
+        let dup = GraphEdit::Add {
+            node: Box::new(node("A", &[])),
+        };
+        assert!(g.apply_edit(&dup).unwrap_err().contains("already exists"));
+        let dangling = GraphEdit::Add {
+            node: Box::new(node("B", &["Z"])),
+        };
+        assert!(g.apply_edit(&dangling).unwrap_err().contains("unknown"));
+
+        let ok = GraphEdit::Add {
+            node: Box::new(node("B", &["A"])),
+        };
+        assert_eq!(g.apply_edit(&ok).unwrap(), None);
+        assert_eq!(g.nodes.len(), 2);
+    }
+
+    #[test]
+    fn skipped_node_unblocks_dependents() {
+        let mut g = graph(vec![node("A", &[]), node("B", &["A"])]);
+        g.node_mut("A").unwrap().status = NodeStatus::Failed;
+        let skip = GraphEdit::Skip {
+            node_id: "A".into(),
+        };
+        assert_eq!(g.apply_edit(&skip).unwrap(), Some(NodeStatus::Failed));
+        assert_eq!(Scheduler::new(g.clone(), 4).ready_nodes(), ["B"]);
+        assert!(g.apply_edit(&skip).is_err());
+    }
+
+    #[test]
+    fn retry_and_reassign_reset_the_node() {
+        let mut g = graph(vec![node("A", &[])]);
+        let retry = GraphEdit::Retry {
+            node_id: "A".into(),
+        };
+        assert!(g.apply_edit(&retry).unwrap_err().contains("pending"));
+
+        let a = g.node_mut("A").unwrap();
+        a.status = NodeStatus::Running;
+        a.assigned_to = Some("agent-1".into());
+        let reassign = GraphEdit::Reassign {
+            node_id: "A".into(),
+            role: Role::Reviewer,
+        };
+        assert_eq!(g.apply_edit(&reassign).unwrap(), Some(NodeStatus::Running));
+        let a = g.node("A").unwrap();
+        assert_eq!(a.owner_role, Role::Reviewer);
+        assert_eq!(a.status, NodeStatus::Pending);
+        assert_eq!(a.assigned_to, None);
+
+        g.node_mut("A").unwrap().status = NodeStatus::Blocked;
+        g.apply_edit(&retry).unwrap();
+        assert_eq!(g.node("A").unwrap().status, NodeStatus::Pending);
+    }
+}
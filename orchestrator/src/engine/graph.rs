@@ -11,6 +11,8 @@ pub enum NodeStatus {
     Blocked,
     Failed,
     Passed,
+    /// Dropped from the plan by an edit; satisfies dependents like `passed`.
+    Skipped,
 }
 
 impl std::fmt::Display for NodeStatus {
@@ -22,6 +24,7 @@ impl std::fmt::Display for NodeStatus {
             Self::Blocked => write!(f, "blocked"),
             Self::Failed => write!(f, "failed"),
             Self::Passed => write!(f, "passed"),
+            Self::Skipped => write!(f, "skipped"),
         }
     }
 }
@@ -59,6 +62,94 @@ impl std::str::FromStr for Role {
     }
 }
 
+/// How urgently a node should run relative to others ready at the same
+/// time. Orders nodes under [`ScheduleStrategy::Priority`] and breaks ties
+/// under [`ScheduleStrategy::CriticalPath`].
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Priority {
+    Low,
+    #[default]
+    Normal,
+    High,
+    Critical,
+}
+
+impl Priority {
+    pub fn is_normal(&self) -> bool {
+        *self == Priority::Normal
+    }
+}
+
+impl std::fmt::Display for Priority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Low => write!(f, "low"),
+            Self::Normal => write!(f, "normal"),
+            Self::High => write!(f, "high"),
+            Self::Critical => write!(f, "critical"),
+        }
+    }
+}
+
+impl std::str::FromStr for Priority {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "low" => Ok(Self::Low),
+            "normal" => Ok(Self::Normal),
+            "high" => Ok(Self::High),
+            "critical" => Ok(Self::Critical),
+            _ => Err(format!("unknown priority: {s}")),
+        }
+    }
+}
+
+/// The order in which the scheduler hands out nodes that are ready at the
+/// same time. Remaining ties go to the node listed first in the graph.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduleStrategy {
+    /// Graph order.
+    #[default]
+    Fifo,
+    /// Highest [`Priority`] first.
+    Priority,
+    /// Longest chain of unfinished work behind the node first, then
+    /// highest priority.
+    CriticalPath,
+}
+
+impl ScheduleStrategy {
+    pub fn is_fifo(&self) -> bool {
+        *self == ScheduleStrategy::Fifo
+    }
+}
+
+impl std::fmt::Display for ScheduleStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Fifo => write!(f, "fifo"),
+            Self::Priority => write!(f, "priority"),
+            Self::CriticalPath => write!(f, "critical-path"),
+        }
+    }
+}
+
+impl std::str::FromStr for ScheduleStrategy {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "fifo" => Ok(Self::Fifo),
+            "priority" => Ok(Self::Priority),
+            "critical-path" | "critical_path" => Ok(Self::CriticalPath),
+            _ => Err(format!("unknown strategy: {s}")),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum ReviewResult {
@@ -80,6 +171,23 @@ pub struct WorkNode {
     pub assigned_to: Option<String>,
     pub patch_bundle: Option<String>,
     pub review_result: Option<ReviewResult>,
+    /// What `orch apply` recorded for this node, stored under
+    /// `artifacts/<node_id>/` in the orchestrator storage directory.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub artifacts: Vec<ArtifactRef>,
+    #[serde(default, skip_serializing_if = "Priority::is_normal")]
+    pub priority: Priority,
+}
+
+/// A file recorded for a node by `orch apply`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArtifactRef {
+    /// `gate_results`, `bundle`, `rollback_bundle` or `file`.
+    pub kind: String,
+    /// Path relative to the storage directory.
+    pub path: String,
+    /// SHA-256 of the stored file's contents.
+    pub sha256: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -89,6 +197,9 @@ pub struct WorkGraph {
     pub id: String,
     pub description: String,
     pub nodes: Vec<WorkNode>,
+    /// How the scheduler orders ready nodes.
+    #[serde(default, skip_serializing_if = "ScheduleStrategy::is_fifo")]
+    pub strategy: ScheduleStrategy,
 }
 
 fn default_schema_version() -> u32 {
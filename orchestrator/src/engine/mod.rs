@@ -1,10 +1,21 @@
+pub mod analyze;
+mod edit;
 mod graph;
+pub mod render;
 
+pub use edit::*;
 pub use graph::*;
 
 use std::collections::{BTreeMap, BTreeSet, VecDeque};
 
 /// The DAG scheduler. Manages node lifecycle, topological ordering, and concurrency.
+///
+/// Nodes ready at the same time are ordered by the graph's
+/// [`ScheduleStrategy`]. When free slots are fewer than ready nodes, they
+/// are shared between roles: each slot goes to the role with the fewest
+/// ready or running nodes, so a stream of implementer work cannot starve
+/// reviewers. Every ordering ends in graph order, so the same graph always
+/// schedules the same way.
 pub struct Scheduler {
     pub graph: WorkGraph,
     pub max_parallel: usize,
@@ -70,13 +81,14 @@ impl Scheduler {
         }
     }
 
-    /// Compute the set of node IDs that are ready (all deps passed).
+    /// Compute the set of node IDs that are ready (all deps passed or skipped),
+    /// up to the free slots, in the order they should be taken.
     pub fn ready_nodes(&self) -> Vec<String> {
         let passed: BTreeSet<&str> = self
             .graph
             .nodes
             .iter()
-            .filter(|n| n.status == NodeStatus::Passed)
+            .filter(|n| matches!(n.status, NodeStatus::Passed | NodeStatus::Skipped))
             .map(|n| n.id.as_str())
             .collect();
 
@@ -89,14 +101,109 @@ impl Scheduler {
 
         let available_slots = self.max_parallel.saturating_sub(running_count);
 
-        self.graph
+        let eligible = self.ordered(
+            self.graph
+                .nodes
+                .iter()
+                .filter(|n| n.status == NodeStatus::Pending)
+                .filter(|n| n.dependencies.iter().all(|d| passed.contains(d.as_str()))),
+        );
+
+        // One queue per role, in the order its first node was taken.
+        let mut queues: Vec<(&Role, VecDeque<&WorkNode>)> = Vec::new();
+        for node in eligible {
+            match queues
+                .iter_mut()
+                .find(|(role, _)| **role == node.owner_role)
+            {
+                Some((_, queue)) => queue.push_back(node),
+                None => queues.push((&node.owner_role, VecDeque::from([node]))),
+            }
+        }
+        let mut load: Vec<usize> = queues
+            .iter()
+            .map(|(role, _)| {
+                self.graph
+                    .nodes
+                    .iter()
+                    .filter(|n| n.owner_role == **role)
+                    .filter(|n| matches!(n.status, NodeStatus::Ready | NodeStatus::Running))
+                    .count()
+            })
+            .collect();
+
+        let mut ready = Vec::new();
+        while ready.len() < available_slots {
+            // Least-loaded role first; equal loads keep the queue order,
+            // which is the order of each role's best node.
+            let Some(i) = (0..queues.len())
+                .filter(|&i| !queues[i].1.is_empty())
+                .min_by_key(|&i| load[i])
+            else {
+                break;
+            };
+            let node = queues[i].1.pop_front().expect("queue is non-empty");
+            load[i] += 1;
+            ready.push(node.id.clone());
+        }
+        ready
+    }
+
+    /// `nodes` in the order the graph's strategy takes them.
+    pub fn ordered<'a>(&'a self, nodes: impl Iterator<Item = &'a WorkNode>) -> Vec<&'a WorkNode> {
+        let position: BTreeMap<&str, usize> = self
+            .graph
             .nodes
             .iter()
-            .filter(|n| n.status == NodeStatus::Pending)
-            .filter(|n| n.dependencies.iter().all(|d| passed.contains(d.as_str())))
-            .take(available_slots)
-            .map(|n| n.id.clone())
-            .collect()
+            .enumerate()
+            .map(|(i, n)| (n.id.as_str(), i))
+            .collect();
+        let chains = match self.graph.strategy {
+            ScheduleStrategy::CriticalPath => self.critical_path_lengths(),
+            _ => BTreeMap::new(),
+        };
+        let mut nodes: Vec<&WorkNode> = nodes.collect();
+        nodes.sort_by_key(|n| {
+            let chain = chains.get(&n.id).copied().unwrap_or(0);
+            let (chain, priority) = match self.graph.strategy {
+                ScheduleStrategy::Fifo => (0, Priority::Normal),
+                ScheduleStrategy::Priority => (0, n.priority),
+                ScheduleStrategy::CriticalPath => (chain, n.priority),
+            };
+            (
+                std::cmp::Reverse(chain),
+                std::cmp::Reverse(priority),
+                position.get(n.id.as_str()).copied().unwrap_or(usize::MAX),
+            )
+        });
+        nodes
+    }
+
+    /// For each unfinished node, the number of unfinished nodes on the
+    /// longest dependency chain starting at it (itself included). Passed
+    /// and skipped nodes count 0.
+    pub fn critical_path_lengths(&self) -> BTreeMap<String, usize> {
+        let mut lengths: BTreeMap<String, usize> = BTreeMap::new();
+        let order = self.topological_order().unwrap_or_default();
+        for id in order.iter().rev() {
+            let Some(node) = self.graph.node(id) else {
+                continue;
+            };
+            let own = usize::from(!matches!(
+                node.status,
+                NodeStatus::Passed | NodeStatus::Skipped
+            ));
+            let longest_after = self
+                .graph
+                .nodes
+                .iter()
+                .filter(|n| n.dependencies.iter().any(|d| d == id))
+                .map(|n| lengths.get(&n.id).copied().unwrap_or(0))
+                .max()
+                .unwrap_or(0);
+            lengths.insert(id.clone(), own + longest_after);
+        }
+        lengths
     }
 
     /// Transition pending nodes whose dependencies are met to ready state.
@@ -116,14 +223,20 @@ impl Scheduler {
         // First advance pending -> ready
         self.advance();
 
-        let node = self
-            .graph
-            .nodes
-            .iter_mut()
-            .find(|n| n.status == NodeStatus::Ready && n.owner_role == role)?;
-
+        let id = self
+            .ordered(
+                self.graph
+                    .nodes
+                    .iter()
+                    .filter(|n| n.status == NodeStatus::Ready && n.owner_role == role),
+            )
+            .first()?
+            .id
+            .clone();
+
+        let node = self.graph.node_mut(&id)?;
         node.status = NodeStatus::Running;
-        Some(node.id.clone())
+        Some(id)
     }
 
     /// Mark a node as passed.
@@ -218,6 +331,7 @@ impl Scheduler {
                 NodeStatus::Blocked => s.blocked += 1,
                 NodeStatus::Failed => s.failed += 1,
                 NodeStatus::Passed => s.passed += 1,
+                NodeStatus::Skipped => s.skipped += 1,
             }
         }
         s
@@ -233,6 +347,7 @@ pub struct GraphSummary {
     pub blocked: usize,
     pub failed: usize,
     pub passed: usize,
+    pub skipped: usize,
 }
 
 #[cfg(test)]
@@ -252,6 +367,8 @@ mod tests {
             assigned_to: None,
             patch_bundle: None,
             review_result: None,
+            artifacts: vec![],
+            priority: Default::default(),
         }
     }
 
@@ -266,6 +383,7 @@ mod tests {
                 make_node("B", &["A"], Role::Implementer),
                 make_node("C", &["A"], Role::Reviewer),
             ],
+            strategy: Default::default(),
         };
         let sched = Scheduler::new(graph, 4);
         assert!(sched.validate().is_ok());
@@ -282,6 +400,7 @@ mod tests {
                 make_node("B", &["A"], Role::Implementer),
                 make_node("C", &["B"], Role::Implementer),
             ],
+            strategy: Default::default(),
         };
         let sched = Scheduler::new(graph, 4);
         assert!(sched.validate().is_err());
@@ -298,6 +417,7 @@ mod tests {
                 make_node("B", &["A"], Role::Implementer),
                 make_node("C", &[], Role::Reviewer),
             ],
+            strategy: Default::default(),
         };
         let sched = Scheduler::new(graph, 4);
         let ready = sched.ready_nodes();
@@ -314,6 +434,7 @@ mod tests {
                 make_node("A", &[], Role::Implementer),
                 make_node("B", &["A"], Role::Implementer),
             ],
+            strategy: Default::default(),
         };
         let mut sched = Scheduler::new(graph, 4);
         // B not ready yet
@@ -334,6 +455,7 @@ mod tests {
                 make_node("B", &[], Role::Implementer),
                 make_node("C", &[], Role::Implementer),
             ],
+            strategy: Default::default(),
         };
         let mut sched = Scheduler::new(graph, 2);
         // Only 2 should be ready due to max_parallel
@@ -355,6 +477,7 @@ mod tests {
                 make_node("B", &["A"], Role::Implementer),
                 make_node("C", &["A", "B"], Role::Reviewer),
             ],
+            strategy: Default::default(),
         };
         let sched = Scheduler::new(graph, 4);
         let order = sched.topological_order().unwrap();
@@ -375,6 +498,7 @@ mod tests {
                 make_node("A", &[], Role::Implementer),
                 make_node("B", &[], Role::Reviewer),
             ],
+            strategy: Default::default(),
         };
         let mut sched = Scheduler::new(graph, 4);
         assert_eq!(sched.assign_next(Role::Implementer), Some("A".into()));
@@ -394,6 +518,7 @@ mod tests {
                 make_node("B", &["A"], Role::Implementer),
                 make_node("C", &[], Role::Reviewer),
             ],
+            strategy: Default::default(),
         };
         let mut sched = Scheduler::new(graph, 4);
         sched.graph.nodes[0].status = NodeStatus::Passed;
@@ -404,4 +529,99 @@ mod tests {
         assert_eq!(s.running, 1);
         assert_eq!(s.pending, 1);
     }
+
+    fn graph_with(nodes: Vec<WorkNode>, strategy: ScheduleStrategy) -> WorkGraph {
+        WorkGraph {
+            schema_version: 1,
+            id: "G-test".into(),
+            description: "test".into(),
+            nodes,
+            strategy,
+        }
+    }
+
+    #[test]
+    fn test_reviewer_not_starved() {
+        let graph = graph_with(
+            vec![
+                make_node("I1", &[], Role::Implementer),
+                make_node("I2", &[], Role::Implementer),
+                make_node("I3", &[], Role::Implementer),
+                make_node("R1", &[], Role::Reviewer),
+            ],
+            ScheduleStrategy::Fifo,
+        );
+        let mut sched = Scheduler::new(graph, 2);
+        assert_eq!(sched.ready_nodes(), vec!["I1", "R1"]);
+
+        // A running implementer counts against its role's share.
+        sched.graph.nodes[0].status = NodeStatus::Running;
+        assert_eq!(sched.ready_nodes(), vec!["R1"]);
+    }
+
+    #[test]
+    fn test_priority_strategy() {
+        let mut low = make_node("A", &[], Role::Implementer);
+        low.priority = Priority::Low;
+        let mut critical = make_node("C", &[], Role::Implementer);
+        critical.priority = Priority::Critical;
+        let nodes = vec![low, make_node("B", &[], Role::Implementer), critical];
+
+        let sched = Scheduler::new(graph_with(nodes.clone(), ScheduleStrategy::Fifo), 4);
+        assert_eq!(sched.ready_nodes(), vec!["A", "B", "C"]);
+
+        let mut sched = Scheduler::new(graph_with(nodes, ScheduleStrategy::Priority), 4);
+        assert_eq!(sched.ready_nodes(), vec!["C", "B", "A"]);
+        sched.advance();
+        assert_eq!(sched.assign_next(Role::Implementer), Some("C".into()));
+    }
+
+    #[test]
+    fn test_critical_path_strategy() {
+        let graph = graph_with(
+            vec![
+                make_node("short", &[], Role::Implementer),
+                make_node("long", &[], Role::Implementer),
+                make_node("long2", &["long"], Role::Implementer),
+                make_node("long3", &["long2"], Role::Implementer),
+            ],
+            ScheduleStrategy::CriticalPath,
+        );
+        let mut sched = Scheduler::new(graph, 1);
+        let lengths = sched.critical_path_lengths();
+        assert_eq!(lengths["long"], 3);
+        assert_eq!(lengths["short"], 1);
+        assert_eq!(sched.ready_nodes(), vec!["long"]);
+
+        sched.graph.nodes[1].status = NodeStatus::Passed;
+        assert_eq!(sched.critical_path_lengths()["long"], 2);
+        assert_eq!(sched.ready_nodes(), vec!["long2"]);
+    }
+
+    #[test]
+    fn test_ties_break_in_graph_order() {
+        let nodes = vec![
+            make_node("Z", &[], Role::Implementer),
+            make_node("A", &[], Role::Implementer),
+            make_node("M", &[], Role::Implementer),
+        ];
+        for strategy in [
+            ScheduleStrategy::Fifo,
+            ScheduleStrategy::Priority,
+            ScheduleStrategy::CriticalPath,
+        ] {
+            let sched = Scheduler::new(graph_with(nodes.clone(), strategy), 4);
+            assert_eq!(sched.ready_nodes(), vec!["Z", "A", "M"], "{strategy}");
+        }
+    }
+
+    #[test]
+    fn test_strategy_parse() {
+        assert_eq!(
+            "critical-path".parse::<ScheduleStrategy>(),
+            Ok(ScheduleStrategy::CriticalPath)
+        );
+        assert_eq!("high".parse::<Priority>(), Ok(Priority::High));
+        assert!("urgent".parse::<Priority>().is_err());
+    }
 }
@@ -0,0 +1,269 @@
+//! Export a `WorkGraph` as DOT, Mermaid, or JSON for `orch graph`.
+//!
+//! Each node carries its status, owner role, and the modules it currently
+//! holds locks on; edges run dependency → dependent. Nodes are emitted in
+//! graph order and locks in `BTreeMap` order so output is stable.
+//!
+//! A dangling dependency is rejected by `Scheduler::validate`; every format
+//! skips it rather than invent a node the graph does not have.
+
+use std::collections::BTreeMap;
+
+use crate::conflict::LockTable;
+
+use super::graph::{NodeStatus, WorkGraph};
+
+/// Output format for `orch graph`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    Dot,
+    Mermaid,
+    Json,
+}
+
+impl std::str::FromStr for GraphFormat {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dot" => Ok(Self::Dot),
+            "mermaid" => Ok(Self::Mermaid),
+            "json" => Ok(Self::Json),
+            _ => Err(format!(
+                "unknown graph format: {s} (expected dot|mermaid|json)"
+            )),
+        }
+    }
+}
+
+/// Render `graph` (with lock ownership from `locks`) in `format`.
+pub fn render(graph: &WorkGraph, locks: &LockTable, format: GraphFormat) -> String {
+    match format {
+        GraphFormat::Dot => render_dot(graph, locks),
+        GraphFormat::Mermaid => render_mermaid(graph, locks),
+        GraphFormat::Json => {
+            serde_json::to_string_pretty(&graph_json(graph, locks)).unwrap_or_default()
+        }
+    }
+}
+
+/// Modules locked by each node id.
+fn locks_by_node(locks: &LockTable) -> BTreeMap<&str, Vec<&str>> {
+    let mut out: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for entry in locks.locks.values() {
+        out.entry(entry.held_by.as_str())
+            .or_default()
+            .push(entry.module.as_str());
+    }
+    out
+}
+
+/// `(dependency, dependent)` pairs, in graph order, for dependencies that
+/// name a node in the graph.
+fn edges(graph: &WorkGraph) -> impl Iterator<Item = (&str, &str)> {
+    graph.nodes.iter().flat_map(move |n| {
+        n.dependencies
+            .iter()
+            .filter(|d| graph.nodes.iter().any(|m| m.id == **d))
+            .map(move |d| (d.as_str(), n.id.as_str()))
+    })
+}
+
+fn status_color(status: &NodeStatus) -> &'static str {
+    match status {
+        NodeStatus::Passed => "#a6e3a1",
+        NodeStatus::Failed => "#f38ba8",
+        NodeStatus::Running => "#89b4fa",
+        NodeStatus::Ready => "#94e2d5",
+        NodeStatus::Blocked => "#f9e2af",
+        NodeStatus::Pending => "#e6e6e6",
+        NodeStatus::Skipped => "#bac2de",
+    }
+}
+
+pub fn render_dot(graph: &WorkGraph, locks: &LockTable) -> String {
+    let held = locks_by_node(locks);
+    let mut out = format!("digraph \"{}\" {{\n", dot_escape(&graph.id));
+    out.push_str("  rankdir=LR;\n");
+    out.push_str("  node [shape=box, style=\"rounded,filled\"];\n");
+    for node in &graph.nodes {
+        let mut label = format!(
+            "{}\\n{} [{}]",
+            dot_escape(&node.id),
+            node.status,
+            node.owner_role
+        );
+        if let Some(modules) = held.get(node.id.as_str()) {
+            label.push_str(&format!("\\nlocks: {}", dot_escape(&modules.join(", "))));
+        }
+        out.push_str(&format!(
+            "  \"{}\" [label=\"{label}\", fillcolor=\"{}\"];\n",
+            dot_escape(&node.id),
+            status_color(&node.status)
+        ));
+    }
+    for (from, to) in edges(graph) {
+        out.push_str(&format!(
+            "  \"{}\" -> \"{}\";\n",
+            dot_escape(from),
+            dot_escape(to)
+        ));
+    }
+    out.push_str("}\n");
+    out
+}
+
+/// Mermaid ids must be identifier-like, so nodes are addressed as `n0, n1,
+/// ...` in graph order with the real id in the label.
+pub fn render_mermaid(graph: &WorkGraph, locks: &LockTable) -> String {
+    let held = locks_by_node(locks);
+    let ids: BTreeMap<&str, String> = graph
+        .nodes
+        .iter()
+        .enumerate()
+        .map(|(i, n)| (n.id.as_str(), format!("n{i}")))
+        .collect();
+    let mut out = String::from("flowchart LR\n");
+    for node in &graph.nodes {
+        let mut label = format!("{}<br/>{} [{}]", node.id, node.status, node.owner_role);
+        if let Some(modules) = held.get(node.id.as_str()) {
+            label.push_str(&format!("<br/>locks: {}", modules.join(", ")));
+        }
+        out.push_str(&format!(
+            "  {}[\"{}\"]\n",
+            ids[node.id.as_str()],
+            label.replace('"', "#quot;")
+        ));
+    }
+    for (from, to) in edges(graph) {
+        out.push_str(&format!("  {} --> {}\n", ids[from], ids[to]));
+    }
+    for node in &graph.nodes {
+        out.push_str(&format!(
+            "  style {} fill:{}\n",
+            ids[node.id.as_str()],
+            status_color(&node.status)
+        ));
+    }
+    out
+}
+
+pub fn graph_json(graph: &WorkGraph, locks: &LockTable) -> serde_json::Value {
+    let held = locks_by_node(locks);
+    let nodes: Vec<serde_json::Value> = graph
+        .nodes
+        .iter()
+        .map(|n| {
+            serde_json::json!({
+                "id": n.id,
+                "description": n.description,
+                "status": n.status,
+                "role": n.owner_role,
+                "assigned_to": n.assigned_to,
+                "locks": held.get(n.id.as_str()).cloned().unwrap_or_default(),
+            })
+        })
+        .collect();
+    let edges: Vec<serde_json::Value> = edges(graph)
+        .map(|(from, to)| serde_json::json!({ "from": from, "to": to }))
+        .collect();
+    serde_json::json!({
+        "graph_id": graph.id,
+        "description": graph.description,
+        "nodes": nodes,
+        "edges": edges,
+    })
+}
+
+fn dot_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{Role, WorkNode};
+
+    fn node(id: &str, deps: &[&str], status: NodeStatus) -> WorkNode {
+        WorkNode {
+            id: id.to_string(),
+            description: format!("node {id}"),
+            inputs: vec![],
+            outputs: vec![],
+            dependencies: deps.iter().map(|s| s.to_string()).collect(),
+            owner_role: Role::Implementer,
+            tags: vec![],
+            status,
+            assigned_to: None,
+            patch_bundle: None,
+            review_result: None,
+            artifacts: vec![],
+            priority: Default::default(),
+        }
+    }
+
+    fn fixture() -> (WorkGraph, LockTable) {
+        let graph = WorkGraph {
+            schema_version: 1,
+            id: "g1".into(),
+            description: "test".into(),
+            nodes: vec![
+                node("A", &[], NodeStatus::Passed),
+                node("B", &["A"], NodeStatus::Running),
+            ],
+            strategy: Default::default(),
+        };
+        let mut locks = LockTable::new();
+        locks
+            .acquire("B", &["crates/llmc".to_string()], "2026-01-01T00:00:00Z")
+            .unwrap();
+        (graph, locks)
+    }
+
+    #[test]
+    fn test_format_parse() {
+        assert_eq!("dot".parse::<GraphFormat>().unwrap(), GraphFormat::Dot);
+        assert!("svg".parse::<GraphFormat>().is_err());
+    }
+
+    #[test]
+    fn test_dot_shows_status_role_and_locks() {
+        let (graph, locks) = fixture();
+        let dot = render_dot(&graph, &locks);
+        assert!(dot.contains("\"A\" [label=\"A\\npassed [implementer]\", fillcolor=\"#a6e3a1\"];"));
+        assert!(dot.contains("B\\nrunning [implementer]\\nlocks: crates/llmc"));
+        assert!(dot.contains("\"A\" -> \"B\";"));
+    }
+
+    #[test]
+    fn test_mermaid_edges_and_styles() {
+        let (graph, locks) = fixture();
+        let m = render_mermaid(&graph, &locks);
+        assert!(m.starts_with("flowchart LR\n"));
+        assert!(m.contains("  n0 --> n1\n"));
+        assert!(m.contains("  style n1 fill:#89b4fa\n"));
+        assert!(m.contains("locks: crates/llmc"));
+    }
+
+    #[test]
+    fn test_json_nodes_and_edges() {
+        let (graph, locks) = fixture();
+        let v = graph_json(&graph, &locks);
+        assert_eq!(v["nodes"][1]["status"], "running");
+        assert_eq!(v["nodes"][1]["locks"][0], "crates/llmc");
+        assert_eq!(v["edges"][0]["from"], "A");
+        assert_eq!(v["edges"][0]["to"], "B");
+    }
+
+    #[test]
+    fn test_dangling_dependencies_are_skipped_in_every_format() {
+        let (mut graph, locks) = fixture();
+        graph.nodes[1].dependencies.push("ghost".into());
+        let dot = render_dot(&graph, &locks);
+        assert!(!dot.contains("ghost"), "{dot}");
+        assert!(dot.contains("\"A\" -> \"B\";"));
+        let m = render_mermaid(&graph, &locks);
+        assert_eq!(m.matches(" --> ").count(), 1, "{m}");
+        let v = graph_json(&graph, &locks);
+        assert_eq!(v["edges"].as_array().unwrap().len(), 1);
+    }
+}
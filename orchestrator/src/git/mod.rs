@@ -0,0 +1,298 @@
+//! Git backend for `orch apply` and `orch review`.
+//!
+//! With `apply --git`, a bundle whose gates pass is committed with trailers
+//! linking the commit to the orchestrator's decision:
+//!
+//! ```text
+//! Add list_set opcode for indexed mutation
+//!
+//! Boruna-Bundle-Id: PB-20260220-001
+//! Boruna-Content-Hash: 3f2a…
+//! Boruna-Gates: pass
+//! Boruna-Node: WN-001
+//! Boruna-Actor: agent-1
+//! ```
+//!
+//! `review --against-commit <rev>` reads those trailers back to check that
+//! a landed commit is the bundle under review. Everything shells out to the
+//! `git` binary.
+
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::patch::PatchBundle;
+
+pub const TRAILER_BUNDLE_ID: &str = "Boruna-Bundle-Id";
+pub const TRAILER_CONTENT_HASH: &str = "Boruna-Content-Hash";
+pub const TRAILER_GATES: &str = "Boruna-Gates";
+pub const TRAILER_NODE: &str = "Boruna-Node";
+pub const TRAILER_ACTOR: &str = "Boruna-Actor";
+
+/// A git working tree containing the orchestrator workspace.
+pub struct GitRepo {
+    workdir: PathBuf,
+}
+
+impl GitRepo {
+    /// Open the repository containing `workspace`.
+    pub fn open(workspace: &Path) -> Result<Self, String> {
+        let repo = Self {
+            workdir: workspace.to_path_buf(),
+        };
+        repo.git(&["rev-parse", "--show-toplevel"]).map_err(|e| {
+            format!(
+                "{} is not inside a git repository: {e}",
+                workspace.display()
+            )
+        })?;
+        Ok(repo)
+    }
+
+    /// Fail if any of `files` has staged or unstaged changes, so a commit
+    /// holds exactly the patch and a rollback leaves the tree clean.
+    pub fn ensure_clean(&self, files: &[String]) -> Result<(), String> {
+        let mut args = vec!["status", "--porcelain", "--"];
+        args.extend(files.iter().map(String::as_str));
+        let dirty = self.git(&args)?;
+        if dirty.trim().is_empty() {
+            Ok(())
+        } else {
+            Err(format!(
+                "uncommitted changes in bundle files; commit or stash them first:\n{}",
+                dirty.trim_end()
+            ))
+        }
+    }
+
+    /// Commit the patched files of `bundle` with orchestrator trailers.
+    /// Returns the new commit's hash.
+    pub fn commit_bundle(
+        &self,
+        bundle: &PatchBundle,
+        node_id: Option<&str>,
+        actor: &str,
+    ) -> Result<String, String> {
+        let files: Vec<&str> = bundle.patches.iter().map(|p| p.file.as_str()).collect();
+        let mut add = vec!["add", "--"];
+        add.extend(&files);
+        self.git(&add)?;
+
+        let mut trailers = vec![
+            (TRAILER_BUNDLE_ID, bundle.metadata.id.clone()),
+            (TRAILER_CONTENT_HASH, bundle.content_hash()),
+            (TRAILER_GATES, "pass".to_string()),
+        ];
+        if let Some(node) = node_id {
+            trailers.push((TRAILER_NODE, node.to_string()));
+        }
+        trailers.push((TRAILER_ACTOR, actor.to_string()));
+        let mut message = format!("{}\n\n", bundle.metadata.intent.trim());
+        for (key, value) in trailers {
+            message.push_str(&format!("{key}: {value}\n"));
+        }
+
+        let mut commit = vec!["commit", "--quiet", "--file", "-", "--"];
+        commit.extend(&files);
+        self.git_with_stdin(&commit, &message)?;
+        Ok(self.git(&["rev-parse", "HEAD"])?.trim().to_string())
+    }
+
+    /// The trailers of `rev`'s commit message.
+    pub fn trailers(&self, rev: &str) -> Result<BTreeMap<String, String>, String> {
+        let out = self.git(&["log", "-1", "--format=%(trailers:only,unfold)", rev, "--"])?;
+        Ok(out
+            .lines()
+            .filter_map(|l| l.split_once(": "))
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect())
+    }
+
+    /// Paths changed by `rev` relative to its first parent, sorted.
+    pub fn changed_files(&self, rev: &str) -> Result<Vec<String>, String> {
+        let out = self.git(&[
+            "diff-tree",
+            "--no-commit-id",
+            "--name-only",
+            "-r",
+            "--root",
+            "--relative",
+            rev,
+        ])?;
+        let mut files: Vec<String> = out.lines().map(str::to_string).collect();
+        files.sort();
+        Ok(files)
+    }
+
+    /// Check that `rev` is the commit `apply --git` made for `bundle`.
+    /// Returns the commit's trailers.
+    pub fn verify_bundle_commit(
+        &self,
+        rev: &str,
+        bundle: &PatchBundle,
+    ) -> Result<BTreeMap<String, String>, String> {
+        let trailers = self.trailers(rev)?;
+        let expect = [
+            (TRAILER_BUNDLE_ID, bundle.metadata.id.clone()),
+            (TRAILER_CONTENT_HASH, bundle.content_hash()),
+            (TRAILER_GATES, "pass".to_string()),
+        ];
+        for (key, want) in expect {
+            match trailers.get(key) {
+                Some(got) if *got == want => {}
+                Some(got) => return Err(format!("commit {rev}: {key} is {got}, expected {want}")),
+                None => return Err(format!("commit {rev} has no {key} trailer")),
+            }
+        }
+        let mut patched: Vec<String> = bundle.patches.iter().map(|p| p.file.clone()).collect();
+        patched.sort();
+        patched.dedup();
+        let changed = self.changed_files(rev)?;
+        if changed != patched {
+            return Err(format!(
+                "commit {rev} changes [{}], but the bundle patches [{}]",
+                changed.join(", "),
+                patched.join(", ")
+            ));
+        }
+        Ok(trailers)
+    }
+
+    fn git(&self, args: &[&str]) -> Result<String, String> {
+        let out = Command::new("git")
+            .args(args)
+            .current_dir(&self.workdir)
+            .stdin(Stdio::null())
+            .output()
+            .map_err(|e| format!("run git: {e}"))?;
+        if out.status.success() {
+            Ok(String::from_utf8_lossy(&out.stdout).into_owned())
+        } else {
+            Err(format!(
+                "git {} failed: {}",
+                args.first().unwrap_or(&""),
+                String::from_utf8_lossy(&out.stderr).trim()
+            ))
+        }
+    }
+
+    fn git_with_stdin(&self, args: &[&str], input: &str) -> Result<(), String> {
+        let mut child = Command::new("git")
+            .args(args)
+            .current_dir(&self.workdir)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("run git: {e}"))?;
+        child
+            .stdin
+            .take()
+            .expect("piped stdin")
+            .write_all(input.as_bytes())
+            .map_err(|e| format!("write to git: {e}"))?;
+        let out = child
+            .wait_with_output()
+            .map_err(|e| format!("run git: {e}"))?;
+        if out.status.success() {
+            Ok(())
+        } else {
+            Err(format!(
+                "git {} failed: {}",
+                args.first().unwrap_or(&""),
+                String::from_utf8_lossy(&out.stderr).trim()
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patch::{ExpectedChecks, FilePatch, Hunk, PatchMetadata, RiskLevel};
+
+    fn repo_with(files: &[(&str, &str)]) -> (tempfile::TempDir, GitRepo) {
+        let dir = tempfile::tempdir().unwrap();
+        for (path, contents) in files {
+            let path = dir.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, contents).unwrap();
+        }
+        let run = |args: &[&str]| {
+            let ok = Command::new("git")
+                .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+                .args(args)
+                .current_dir(dir.path())
+                .output()
+                .unwrap()
+                .status
+                .success();
+            assert!(ok, "git {args:?}");
+        };
+        run(&["init", "--quiet"]);
+        run(&["config", "user.name", "test"]);
+        run(&["config", "user.email", "test@example.com"]);
+        run(&["add", "."]);
+        run(&["commit", "--quiet", "-m", "init"]);
+        let repo = GitRepo::open(dir.path()).unwrap();
+        (dir, repo)
+    }
+
+    fn bundle(file: &str) -> PatchBundle {
+        PatchBundle {
+            version: 1,
+            metadata: PatchMetadata {
+                id: "PB-1".into(),
+                intent: "Rename a line".into(),
+                author: "agent-1".into(),
+                timestamp: "2026-01-01T00:00:00Z".into(),
+                touched_modules: vec!["m".into()],
+                risk_level: RiskLevel::Low,
+            },
+            patches: vec![FilePatch {
+                file: file.into(),
+                hunks: vec![Hunk {
+                    start_line: 1,
+                    old_text: "one".into(),
+                    new_text: "1".into(),
+                }],
+            }],
+            expected_checks: ExpectedChecks {
+                compile: false,
+                test: false,
+                replay: false,
+                diagnostics_count: None,
+                trace_stability: None,
+                difftest_corpus: vec![],
+                deterministic_build: false,
+            },
+            reviewer_checklist: vec!["ok".into()],
+        }
+    }
+
+    #[test]
+    fn committed_bundle_carries_verifiable_trailers() {
+        let (dir, repo) = repo_with(&[("src/a.txt", "one\n"), ("src/b.txt", "b\n")]);
+        let bundle = bundle("src/a.txt");
+        repo.ensure_clean(&["src/a.txt".into()]).unwrap();
+        bundle.apply(dir.path()).unwrap();
+        // An unrelated dirty file stays out of the commit.
+        std::fs::write(dir.path().join("src/b.txt"), "dirty\n").unwrap();
+
+        let sha = repo
+            .commit_bundle(&bundle, Some("WN-001"), "agent-2")
+            .unwrap();
+        let trailers = repo.verify_bundle_commit(&sha, &bundle).unwrap();
+        assert_eq!(trailers[TRAILER_NODE], "WN-001");
+        assert_eq!(trailers[TRAILER_ACTOR], "agent-2");
+        assert_eq!(repo.changed_files("HEAD").unwrap(), ["src/a.txt"]);
+        assert!(repo.ensure_clean(&["src/b.txt".into()]).is_err());
+
+        let mut other = bundle.clone();
+        other.patches[0].hunks[0].new_text = "uno".into();
+        let err = repo.verify_bundle_commit(&sha, &other).unwrap_err();
+        assert!(err.contains(TRAILER_CONTENT_HASH), "{err}");
+        assert!(repo.verify_bundle_commit("HEAD~1", &bundle).is_err());
+    }
+}
@@ -0,0 +1,133 @@
+//! Actor identity and role-based access control for orchestrator commands.
+//!
+//! Every command that changes the store runs as a named actor, resolved by
+//! [`resolve_actor`] from `--actor`, then `BORUNA_ACTOR`, then the
+//! workspace's `.boruna/identity.json`. When the store holds a roster
+//! (`actors.json`), the actor must hold the role a command needs; without
+//! one every actor may act in every role.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::engine::Role;
+
+/// Environment variable naming the acting identity.
+pub const ACTOR_ENV: &str = "BORUNA_ACTOR";
+
+/// Identity file, relative to the workspace root.
+pub const IDENTITY_FILE: &str = ".boruna/identity.json";
+
+/// Contents of the identity file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Identity {
+    pub actor: String,
+}
+
+/// Which roles each actor may act in.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Roster {
+    pub actors: BTreeMap<String, Vec<Role>>,
+}
+
+impl Roster {
+    /// Fail unless `actor` may act as `role`.
+    pub fn authorize(&self, actor: &str, role: &Role) -> Result<(), String> {
+        match self.actors.get(actor) {
+            Some(roles) if roles.contains(role) => Ok(()),
+            Some(_) => Err(format!("actor '{actor}' does not hold the {role} role")),
+            None => Err(format!("actor '{actor}' is not in the roster")),
+        }
+    }
+}
+
+/// The acting identity: `flag`, else `$BORUNA_ACTOR`, else the workspace's
+/// identity file.
+pub fn resolve_actor(flag: Option<&str>, workspace: &Path) -> Result<String, String> {
+    let env = std::env::var(ACTOR_ENV).ok();
+    if let Some(actor) = flag.or(env.as_deref()) {
+        return validate(actor);
+    }
+    let path = workspace.join(IDENTITY_FILE);
+    if path.exists() {
+        let data = std::fs::read_to_string(&path)
+            .map_err(|e| format!("cannot read {}: {e}", path.display()))?;
+        let identity: Identity = serde_json::from_str(&data)
+            .map_err(|e| format!("invalid identity file {}: {e}", path.display()))?;
+        return validate(&identity.actor);
+    }
+    Err(format!(
+        "no actor identity: pass --actor, set {ACTOR_ENV}, or write {IDENTITY_FILE}"
+    ))
+}
+
+fn validate(actor: &str) -> Result<String, String> {
+    let actor = actor.trim();
+    if actor.is_empty() {
+        return Err("actor name is empty".into());
+    }
+    Ok(actor.to_string())
+}
+
+/// Enforce the two-person rule: a bundle's reviewer must be neither its
+/// author, nor the actor the node was assigned to, nor an actor who
+/// applied it.
+pub fn check_two_person_rule(
+    reviewer: &str,
+    author: &str,
+    assignee: Option<&str>,
+    appliers: &[String],
+) -> Result<(), String> {
+    if reviewer == author {
+        return Err(format!(
+            "two-person rule: '{reviewer}' authored this bundle and cannot review it"
+        ));
+    }
+    if assignee == Some(reviewer) {
+        return Err(format!(
+            "two-person rule: '{reviewer}' implemented this node and cannot review it"
+        ));
+    }
+    if appliers.iter().any(|a| a == reviewer) {
+        return Err(format!(
+            "two-person rule: '{reviewer}' applied this bundle and cannot review it"
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identity_file_is_the_last_fallback() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(resolve_actor(Some(" alice "), dir.path()).unwrap(), "alice");
+        std::fs::create_dir_all(dir.path().join(".boruna")).unwrap();
+        std::fs::write(dir.path().join(IDENTITY_FILE), r#"{ "actor": "bob" }"#).unwrap();
+        if std::env::var(ACTOR_ENV).is_err() {
+            assert_eq!(resolve_actor(None, dir.path()).unwrap(), "bob");
+        }
+        assert!(resolve_actor(Some(""), dir.path()).is_err());
+    }
+
+    #[test]
+    fn roster_and_two_person_rule() {
+        let roster: Roster = serde_json::from_str(
+            r#"{ "actors": { "alice": ["implementer"], "bob": ["reviewer", "red_team"] } }"#,
+        )
+        .unwrap();
+        assert!(roster.authorize("alice", &Role::Implementer).is_ok());
+        assert!(roster.authorize("alice", &Role::Reviewer).is_err());
+        assert!(roster.authorize("bob", &Role::RedTeam).is_ok());
+        assert!(roster.authorize("carol", &Role::Planner).is_err());
+
+        let appliers = ["dave".to_string()];
+        assert!(check_two_person_rule("bob", "alice", Some("alice"), &appliers).is_ok());
+        assert!(check_two_person_rule("alice", "alice", None, &[]).is_err());
+        assert!(check_two_person_rule("bob", "carol", Some("bob"), &[]).is_err());
+        assert!(check_two_person_rule("dave", "alice", None, &appliers).is_err());
+    }
+}
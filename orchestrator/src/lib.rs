@@ -3,11 +3,15 @@ pub mod audit;
 pub mod cli;
 pub mod conflict;
 pub mod engine;
+pub mod git;
+pub mod identity;
 #[cfg(feature = "persist-sqlite")]
 pub mod metrics;
 pub mod patch;
 #[cfg(feature = "persist-sqlite")]
 pub mod persistence;
+pub mod planner;
+pub mod sandbox;
 pub mod simulate;
 pub mod storage;
 pub mod workflow;
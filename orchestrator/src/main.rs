@@ -1,10 +1,12 @@
 use std::path::PathBuf;
 use std::process;
+use std::time::Duration;
 
+use boruna_errors::ErrorEnvelope;
 use clap::{Parser, Subcommand};
 
-use boruna_orchestrator::cli;
-use boruna_orchestrator::engine::Role;
+use boruna_orchestrator::engine::{GraphEdit, NodeStatus, Role, WorkNode};
+use boruna_orchestrator::{cli, identity};
 
 #[derive(Parser)]
 #[command(name = "boruna-orch", about = "Boruna Multi-Agent Orchestrator")]
@@ -13,6 +15,20 @@ struct Cli {
     #[arg(long, default_value = ".")]
     workspace: PathBuf,
 
+    /// Milliseconds to wait for another orchestrator process holding the
+    /// store's writer lock before failing with a contention error.
+    #[arg(long, global = true, default_value_t = 10_000)]
+    wait_timeout: u64,
+
+    /// Acting identity for commands that change the store (default:
+    /// $BORUNA_ACTOR, then .boruna/identity.json in the workspace).
+    #[arg(long, global = true)]
+    actor: Option<String>,
+
+    /// Report a failure as a JSON error envelope on stderr.
+    #[arg(long, global = true)]
+    json: bool,
+
     #[command(subcommand)]
     command: Command,
 }
@@ -20,9 +36,17 @@ struct Cli {
 #[derive(Subcommand)]
 enum Command {
     /// Create a DAG from a plan specification file.
+    #[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
     Plan {
         /// Path to the plan spec JSON file.
-        spec: PathBuf,
+        #[arg(required = true)]
+        spec: Option<PathBuf>,
+        /// Ordering for ready nodes: fifo, priority, critical-path.
+        /// Overrides the spec's `strategy`.
+        #[arg(long)]
+        strategy: Option<String>,
+        #[command(subcommand)]
+        mode: Option<PlanMode>,
     },
     /// Assign the next ready node for a given role.
     Next {
@@ -34,43 +58,274 @@ enum Command {
     Apply {
         /// Path to the .patchbundle.json file.
         bundle: PathBuf,
+        /// Work node the bundle implements (default: the node whose ID or
+        /// patch_bundle matches the bundle).
+        #[arg(long)]
+        node: Option<String>,
+        /// Commit the patched files with Boruna-* trailers once gates pass.
+        #[arg(long)]
+        git: bool,
     },
     /// Review a patch bundle: validate + gates + checklist.
     Review {
         /// Path to the .patchbundle.json file.
         bundle: PathBuf,
+        /// Review a bundle already committed by `apply --git`: verify the
+        /// commit's trailers and files match the bundle.
+        #[arg(long)]
+        against_commit: Option<String>,
     },
     /// Show current work graph state.
     Status,
+    /// Export the work graph (status, role, locks, edges) for Graphviz,
+    /// Mermaid, or a dashboard.
+    Graph {
+        /// Output format: dot, mermaid, or json.
+        #[arg(long, default_value = "dot")]
+        format: String,
+    },
+    /// Critical path, expected durations from gate timings, projected
+    /// completion, and which blocked node unlocks the most work.
+    Analyze {
+        /// Output format: text or json.
+        #[arg(long, default_value = "text")]
+        format: String,
+    },
+    /// Inspect recorded gate results.
+    Gates {
+        #[command(subcommand)]
+        command: GatesCommand,
+    },
+    /// Edit nodes of the active work graph.
+    Node {
+        #[command(subcommand)]
+        command: NodeCommand,
+    },
     /// Machine-readable JSON summary of graph + gates.
-    Report {
-        /// Output as JSON.
-        #[arg(long, default_value_t = true)]
-        json: bool,
+    Report,
+}
+
+#[derive(Subcommand)]
+enum PlanMode {
+    /// Synthesize a candidate work graph from a goal spec for review.
+    Synth {
+        /// Path to the goal spec JSON file.
+        goal: PathBuf,
+        /// Write the candidate graph here instead of stdout.
+        #[arg(long)]
+        out: Option<PathBuf>,
+        /// Ask the (mock) LLM gateway for extra dependency edges.
+        #[arg(long)]
+        llm: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum GatesCommand {
+    /// List gate runs with per-gate trends and flaky-gate flags.
+    History {
+        /// Only runs for this node.
+        #[arg(long)]
+        node: Option<String>,
+        /// Only runs of this bundle.
+        #[arg(long)]
+        bundle: Option<String>,
+        /// Only this gate (e.g. compile, test, replay).
+        #[arg(long)]
+        gate: Option<String>,
+        /// Output format: text or json.
+        #[arg(long, default_value = "text")]
+        format: String,
     },
 }
 
+#[derive(Subcommand)]
+enum NodeCommand {
+    /// Add a pending node to the graph.
+    Add {
+        /// New node ID.
+        id: String,
+        /// What the node does.
+        #[arg(long)]
+        description: String,
+        /// Role: planner, implementer, reviewer, red-team
+        #[arg(long, default_value = "implementer")]
+        role: String,
+        /// Node IDs this node depends on (comma-separated).
+        #[arg(long, value_delimiter = ',')]
+        deps: Vec<String>,
+        /// Modules the node reads (comma-separated).
+        #[arg(long, value_delimiter = ',')]
+        inputs: Vec<String>,
+        /// Modules the node writes and locks (comma-separated).
+        #[arg(long, value_delimiter = ',')]
+        outputs: Vec<String>,
+        /// Tags (comma-separated).
+        #[arg(long, value_delimiter = ',')]
+        tags: Vec<String>,
+        /// Priority: low, normal, high, critical
+        #[arg(long, default_value = "normal")]
+        priority: String,
+        /// Why the node was added, recorded in the edit history.
+        #[arg(long)]
+        reason: Option<String>,
+    },
+    /// Skip a node; its dependents proceed as if it had passed.
+    Skip {
+        id: String,
+        /// Why the node was skipped, recorded in the edit history.
+        #[arg(long)]
+        reason: Option<String>,
+    },
+    /// Return a failed, blocked or skipped node to pending.
+    Retry {
+        id: String,
+        /// Why the node is retried, recorded in the edit history.
+        #[arg(long)]
+        reason: Option<String>,
+    },
+    /// Hand a node to a different role.
+    Reassign {
+        id: String,
+        /// Role: planner, implementer, reviewer, red-team
+        #[arg(long)]
+        role: String,
+        /// Why the node was reassigned, recorded in the edit history.
+        #[arg(long)]
+        reason: Option<String>,
+    },
+}
+
+fn parse_role(role: &str) -> Role {
+    role.parse().unwrap_or_else(|e| {
+        eprintln!("error: {e}");
+        process::exit(1);
+    })
+}
+
+fn parse_arg<T: std::str::FromStr<Err = String>>(value: &str) -> T {
+    value.parse().unwrap_or_else(|e| {
+        eprintln!("error: {e}");
+        process::exit(1);
+    })
+}
+
 fn main() {
     let cli = Cli::parse();
     let workspace = &cli.workspace;
+    let wait_timeout = Duration::from_millis(cli.wait_timeout);
+    let json = cli.json;
+    let actor = || {
+        identity::resolve_actor(cli.actor.as_deref(), workspace).unwrap_or_else(|e| fail(json, e))
+    };
 
     let result = match cli.command {
-        Command::Plan { spec } => cli::cmd_plan(workspace, &spec),
+        Command::Plan {
+            mode: Some(PlanMode::Synth { goal, out, llm }),
+            ..
+        } => cli::cmd_plan_synth(&goal, out.as_deref(), llm),
+        Command::Plan {
+            spec,
+            strategy,
+            mode: None,
+        } => {
+            let spec = spec.expect("clap requires a spec without a subcommand");
+            let strategy = strategy.as_deref().map(parse_arg);
+            cli::cmd_plan(workspace, &spec, strategy, &actor(), wait_timeout)
+        }
         Command::Next { role } => {
-            let role: Role = role.parse().unwrap_or_else(|e| {
-                eprintln!("error: {e}");
-                process::exit(1);
-            });
-            cli::cmd_next(workspace, role)
+            cli::cmd_next(workspace, parse_role(&role), &actor(), wait_timeout)
         }
-        Command::Apply { bundle } => cli::cmd_apply(workspace, &bundle),
-        Command::Review { bundle } => cli::cmd_review(workspace, &bundle),
+        Command::Apply { bundle, node, git } => cli::cmd_apply(
+            workspace,
+            &bundle,
+            node.as_deref(),
+            git,
+            &actor(),
+            wait_timeout,
+        ),
+        Command::Review {
+            bundle,
+            against_commit,
+        } => cli::cmd_review(workspace, &bundle, against_commit.as_deref(), &actor()),
         Command::Status => cli::cmd_status(workspace),
-        Command::Report { .. } => cli::cmd_report(workspace),
+        Command::Graph { format } => cli::cmd_graph(workspace, &format),
+        Command::Analyze { format } => cli::cmd_analyze(workspace, &format),
+        Command::Gates {
+            command:
+                GatesCommand::History {
+                    node,
+                    bundle,
+                    gate,
+                    format,
+                },
+        } => cli::cmd_gates_history(
+            workspace,
+            node.as_deref(),
+            bundle.as_deref(),
+            gate.as_deref(),
+            &format,
+        ),
+        Command::Node { command } => {
+            let (edit, reason) = match command {
+                NodeCommand::Add {
+                    id,
+                    description,
+                    role,
+                    deps,
+                    inputs,
+                    outputs,
+                    tags,
+                    priority,
+                    reason,
+                } => {
+                    let node = WorkNode {
+                        id,
+                        description,
+                        inputs,
+                        outputs,
+                        dependencies: deps,
+                        owner_role: parse_role(&role),
+                        tags,
+                        status: NodeStatus::Pending,
+                        assigned_to: None,
+                        patch_bundle: None,
+                        review_result: None,
+                        artifacts: vec![],
+                        priority: parse_arg(&priority),
+                    };
+                    let edit = GraphEdit::Add {
+                        node: Box::new(node),
+                    };
+                    (edit, reason)
+                }
+                NodeCommand::Skip { id, reason } => (GraphEdit::Skip { node_id: id }, reason),
+                NodeCommand::Retry { id, reason } => (GraphEdit::Retry { node_id: id }, reason),
+                NodeCommand::Reassign { id, role, reason } => {
+                    let edit = GraphEdit::Reassign {
+                        node_id: id,
+                        role: parse_role(&role),
+                    };
+                    (edit, reason)
+                }
+            };
+            cli::cmd_node_edit(workspace, edit, reason, &actor(), wait_timeout)
+        }
+        Command::Report => cli::cmd_report(workspace),
     };
 
     if let Err(e) = result {
+        fail(json, e);
+    }
+}
+
+/// Print `e` and exit 1. Orchestrator errors are plain strings, so under
+/// `--json` the envelope is `unclassified`.
+fn fail(json: bool, e: String) -> ! {
+    if json {
+        eprintln!("{}", ErrorEnvelope::unclassified(e).to_json());
+    } else {
         eprintln!("error: {e}");
-        process::exit(1);
     }
+    process::exit(1);
 }
@@ -1,5 +1,5 @@
+use boruna_hash::Hasher;
 use serde::{Deserialize, Serialize};
-use sha2::{Digest, Sha256};
 use std::fs;
 use std::path::Path;
 
@@ -49,6 +49,50 @@ pub struct ExpectedChecks {
     pub test: bool,
     pub replay: bool,
     pub diagnostics_count: Option<usize>,
+    /// Apps whose trace hashes must not change across the patch unless
+    /// listed in `affects`. Absent in older bundles.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace_stability: Option<TraceStabilityConfig>,
+    /// Corpus directories for the `difftest` gate (`lang difftest`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub difftest_corpus: Vec<String>,
+    /// Run the `deterministic_build` gate: every std library must compile
+    /// to identical bytes twice. Absent in older bundles.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub deterministic_build: bool,
+}
+
+fn is_false(b: &bool) -> bool {
+    !*b
+}
+
+/// Configuration for the `trace_stability` gate.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TraceStabilityConfig {
+    pub cases: Vec<TraceCase>,
+    /// Case files the bundle is expected to change. Cases whose file is
+    /// itself patched are treated as affected too.
+    #[serde(default)]
+    pub affects: Vec<String>,
+}
+
+/// An example app plus the message script to drive it, in the
+/// `framework trace-hash --messages` syntax (`tag:payload,...`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceCase {
+    pub file: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub messages: Option<String>,
+}
+
+impl TraceCase {
+    /// Stable key identifying the case in gate details.
+    pub fn key(&self) -> String {
+        match &self.messages {
+            Some(m) => format!("{}?{m}", self.file),
+            None => self.file.clone(),
+        }
+    }
 }
 
 impl PatchBundle {
@@ -116,7 +160,7 @@ impl PatchBundle {
 
     /// Compute a stable SHA-256 hash of the bundle content (patches only, not metadata).
     pub fn content_hash(&self) -> String {
-        let mut hasher = Sha256::new();
+        let mut hasher = Hasher::sha256();
         for patch in &self.patches {
             hasher.update(patch.file.as_bytes());
             for hunk in &patch.hunks {
@@ -125,7 +169,7 @@ impl PatchBundle {
                 hasher.update(hunk.new_text.as_bytes());
             }
         }
-        format!("{:x}", hasher.finalize())
+        hasher.finalize_hex()
     }
 
     /// Apply the bundle to the filesystem rooted at `base_dir`.
@@ -253,6 +297,9 @@ mod tests {
                 test: true,
                 replay: false,
                 diagnostics_count: None,
+                trace_stability: None,
+                difftest_corpus: vec![],
+                deterministic_build: false,
             },
             reviewer_checklist: vec!["looks good".into()],
         }
@@ -2225,14 +2225,14 @@ where
 /// triple yield the same `run_id` — the determinism property the platform
 /// relies on for cross-machine replay.
 pub fn derive_run_id(workflow_hash: &str, inputs_hash: &str, counter: i64) -> String {
-    use sha2::{Digest, Sha256};
-    let mut hasher = Sha256::new();
+    use boruna_hash::Hasher;
+    let mut hasher = Hasher::sha256();
     hasher.update(workflow_hash.as_bytes());
     hasher.update(b":");
     hasher.update(inputs_hash.as_bytes());
     hasher.update(b":");
     hasher.update(counter.to_le_bytes());
-    let digest = hasher.finalize();
+    let digest = hasher.finalize_bytes();
     // First 8 bytes → 16 hex chars. Plenty of entropy for collision
     // avoidance in a single-tenant store while staying short enough to
     // be human-pasteable on a CLI line.
@@ -4177,17 +4177,6 @@ mod tests {
         (store, dir)
     }
 
-    fn sha256_hex(s: &str) -> String {
-        // Sprint 0.5-S7: tests use the SHA-256 over the JSON bytes,
-        // matching `complete_step_cas`'s contract. We avoid pulling
-        // sha2 directly here; instead we use the existing
-        // `DataStore::hash_value`-equivalent via a light dependency.
-        use sha2::Digest;
-        let mut h = sha2::Sha256::new();
-        h.update(s.as_bytes());
-        format!("{:x}", h.finalize())
-    }
-
     fn schema_version_value(store: &RunCheckpointStore) -> i64 {
         store
             .conn
@@ -4214,7 +4203,7 @@ mod tests {
             ClaimOutcome::Claimed { claim_id } => claim_id,
             other => panic!("expected Claimed, got {other:?}"),
         };
-        let hash = sha256_hex(output_json);
+        let hash = boruna_hash::sha256_hex(output_json);
         store
             .complete_step_cas(run_id, step_id, claim, output_json, &hash, 1, 2_000)
             .unwrap()
@@ -4255,7 +4244,7 @@ mod tests {
         // 100 KiB string content (greater than 64 KiB threshold).
         let big_payload = "a".repeat(100 * 1024);
         let big_quoted = format!("\"{big_payload}\"");
-        let expected_hash = sha256_hex(&big_quoted);
+        let expected_hash = boruna_hash::sha256_hex(&big_quoted);
         let outcome = complete_running_step(&store, "R1", "s1", &big_quoted);
         assert!(matches!(outcome, TerminalOutcome::Committed));
         let cps = store.list_step_checkpoints("R1").unwrap();
@@ -0,0 +1,498 @@
+//! Work graph synthesis from a high-level goal spec.
+//!
+//! [`synthesize`] turns a [`GoalSpec`] — deliverables, constraints and a
+//! module map — into a candidate [`WorkGraph`] using deterministic
+//! heuristics. The candidate is written out for a human to review and
+//! edit; it only becomes the active graph once passed to `orch plan`.
+//!
+//! Dependencies are inferred from the modules each deliverable touches:
+//!
+//! - a deliverable that reads a module another one writes runs after it;
+//! - a deliverable whose module depends (per the module map, transitively)
+//!   on a module another one writes runs after it;
+//! - two deliverables writing the same module run in spec order, so they
+//!   never contend for the same module lock.
+//!
+//! Every inferred edge carries a [`Rationale`] line explaining it.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use boruna_bytecode::Value;
+use boruna_effect::gateway::{ExecutionMode, LlmGateway};
+use boruna_effect::normalize::{CacheMode, LlmRequest};
+use boruna_effect::policy::LlmPolicy;
+use serde::{Deserialize, Serialize};
+
+use crate::engine::{NodeStatus, Role, Scheduler, WorkGraph, WorkNode};
+
+/// Prompt ID sent to the LLM gateway by [`suggest_with_llm`].
+pub const SYNTH_PROMPT_ID: &str = "orch.plan_synth";
+
+/// A high-level description of what a plan must deliver.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GoalSpec {
+    /// ID of the synthesized graph.
+    pub id: String,
+    pub description: String,
+    pub deliverables: Vec<Deliverable>,
+    #[serde(default)]
+    pub constraints: Constraints,
+    /// Module name → modules it depends on.
+    #[serde(default)]
+    pub modules: BTreeMap<String, ModuleInfo>,
+}
+
+/// One unit of work the goal requires.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Deliverable {
+    /// Node ID; defaults to `WN-<nnn>` by position.
+    #[serde(default)]
+    pub id: Option<String>,
+    pub description: String,
+    /// Modules the deliverable changes.
+    #[serde(default)]
+    pub touches: Vec<String>,
+    /// Modules the deliverable only reads.
+    #[serde(default)]
+    pub reads: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// Plan-wide rules applied on top of the inferred graph.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Constraints {
+    /// Add a reviewer node after every implementation node.
+    #[serde(default)]
+    pub require_review: bool,
+    /// Deliverables carrying any of these tags also get a red-team node.
+    #[serde(default)]
+    pub red_team_tags: Vec<String>,
+    /// Fail instead of emitting a graph with more nodes than this.
+    #[serde(default)]
+    pub max_nodes: Option<usize>,
+}
+
+/// Entry in the goal's module map.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModuleInfo {
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+}
+
+/// Why an edge or node is in the candidate graph.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rationale {
+    pub node_id: String,
+    pub reason: String,
+}
+
+/// A candidate graph plus the reasoning behind it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Synthesis {
+    pub graph: WorkGraph,
+    pub rationale: Vec<Rationale>,
+}
+
+/// Build a candidate work graph for `goal`.
+pub fn synthesize(goal: &GoalSpec) -> Result<Synthesis, String> {
+    if goal.deliverables.is_empty() {
+        return Err("goal spec has no deliverables".into());
+    }
+    let ids: Vec<String> = goal
+        .deliverables
+        .iter()
+        .enumerate()
+        .map(|(i, d)| d.id.clone().unwrap_or_else(|| format!("WN-{:03}", i + 1)))
+        .collect();
+    let mut seen = BTreeSet::new();
+    if let Some(dup) = ids.iter().find(|id| !seen.insert(id.as_str())) {
+        return Err(format!("duplicate deliverable id: {dup}"));
+    }
+    if let Some(unknown) = goal
+        .modules
+        .values()
+        .flat_map(|m| &m.depends_on)
+        .find(|m| !goal.modules.contains_key(*m))
+    {
+        return Err(format!("module map references unknown module: {unknown}"));
+    }
+
+    let mut nodes = Vec::new();
+    let mut rationale = Vec::new();
+    for (i, d) in goal.deliverables.iter().enumerate() {
+        let mut deps = Vec::new();
+        for (j, earlier) in goal.deliverables.iter().enumerate() {
+            if i == j {
+                continue;
+            }
+            if let Some(reason) = ordering_reason(goal, earlier, d, j < i) {
+                deps.push(ids[j].clone());
+                rationale.push(Rationale {
+                    node_id: ids[i].clone(),
+                    reason: format!("after {}: {reason}", ids[j]),
+                });
+            }
+        }
+        nodes.push(node(
+            &ids[i],
+            &d.description,
+            Role::Implementer,
+            d.reads.clone(),
+            d.touches.clone(),
+            deps,
+            d.tags.clone(),
+        ));
+    }
+
+    let mut checks = Vec::new();
+    for (i, d) in goal.deliverables.iter().enumerate() {
+        if goal.constraints.require_review {
+            checks.push((ids[i].clone(), "review", Role::Reviewer, d));
+        }
+        if d.tags
+            .iter()
+            .any(|t| goal.constraints.red_team_tags.contains(t))
+        {
+            checks.push((ids[i].clone(), "redteam", Role::RedTeam, d));
+        }
+    }
+    for (target, suffix, role, d) in checks {
+        let id = format!("{target}-{suffix}");
+        rationale.push(Rationale {
+            node_id: id.clone(),
+            reason: match role {
+                Role::Reviewer => "constraints.require_review".into(),
+                _ => format!("tags {:?} match constraints.red_team_tags", d.tags),
+            },
+        });
+        nodes.push(node(
+            &id,
+            &format!("{role} {target}: {}", d.description),
+            role,
+            d.touches.clone(),
+            vec![],
+            vec![target],
+            d.tags.clone(),
+        ));
+    }
+
+    if let Some(max) = goal.constraints.max_nodes {
+        if nodes.len() > max {
+            return Err(format!(
+                "synthesized {} nodes, over constraints.max_nodes ({max})",
+                nodes.len()
+            ));
+        }
+    }
+
+    let graph = WorkGraph {
+        schema_version: 1,
+        id: goal.id.clone(),
+        description: goal.description.clone(),
+        nodes,
+        strategy: Default::default(),
+    };
+    Scheduler::new(graph.clone(), 4).validate().map_err(|e| {
+        format!("{e}; the deliverables' module dependencies are circular — split or reorder them")
+    })?;
+    Ok(Synthesis { graph, rationale })
+}
+
+/// Why `later` must run after `earlier`, if it must. `in_order` is true
+/// when `earlier` precedes `later` in the spec.
+fn ordering_reason(
+    goal: &GoalSpec,
+    earlier: &Deliverable,
+    later: &Deliverable,
+    in_order: bool,
+) -> Option<String> {
+    for m in &earlier.touches {
+        if later.reads.contains(m) {
+            return Some(format!("reads {m}"));
+        }
+        if in_order && later.touches.contains(m) {
+            return Some(format!("both write {m}"));
+        }
+    }
+    for m in &later.touches {
+        let upstream = upstream_modules(goal, m);
+        if let Some(dep) = earlier
+            .touches
+            .iter()
+            .find(|e| upstream.contains(e.as_str()))
+        {
+            return Some(format!("{m} depends on {dep}"));
+        }
+    }
+    None
+}
+
+/// Modules `module` depends on, directly or transitively.
+fn upstream_modules<'a>(goal: &'a GoalSpec, module: &str) -> BTreeSet<&'a str> {
+    let mut out = BTreeSet::new();
+    let mut stack: Vec<&str> = goal
+        .modules
+        .get(module)
+        .map(|m| m.depends_on.iter().map(String::as_str).collect())
+        .unwrap_or_default();
+    while let Some(m) = stack.pop() {
+        if m != module && out.insert(m) {
+            if let Some(info) = goal.modules.get(m) {
+                stack.extend(info.depends_on.iter().map(String::as_str));
+            }
+        }
+    }
+    out
+}
+
+fn node(
+    id: &str,
+    description: &str,
+    role: Role,
+    inputs: Vec<String>,
+    outputs: Vec<String>,
+    dependencies: Vec<String>,
+    tags: Vec<String>,
+) -> WorkNode {
+    WorkNode {
+        id: id.into(),
+        description: description.into(),
+        inputs,
+        outputs,
+        dependencies,
+        owner_role: role,
+        tags,
+        status: NodeStatus::Pending,
+        assigned_to: None,
+        patch_bundle: None,
+        review_result: None,
+        artifacts: vec![],
+        priority: Default::default(),
+    }
+}
+
+/// Ask the LLM gateway for extra dependency edges and merge the ones that
+/// keep the graph a valid DAG.
+///
+/// The gateway runs in [`ExecutionMode::Mock`] with caching off, so the
+/// call is deterministic and never leaves the machine. A response may
+/// carry `dependencies: [[before, after], ...]`; each accepted or
+/// rejected suggestion is added to the rationale. Returns the gateway's
+/// request ID.
+pub fn suggest_with_llm(
+    goal: &GoalSpec,
+    synthesis: &mut Synthesis,
+    scratch_dir: &Path,
+) -> Result<String, String> {
+    let mut gateway = LlmGateway::new(
+        &scratch_dir.join("prompts"),
+        &scratch_dir.join("context"),
+        &scratch_dir.join("cache"),
+        LlmPolicy::allow_all(),
+        ExecutionMode::Mock,
+    )?;
+    let mut args = BTreeMap::new();
+    args.insert("goal".to_string(), json_arg(goal));
+    args.insert("candidate".to_string(), json_arg(&synthesis.graph));
+    let result = gateway.execute(&LlmRequest {
+        prompt_id: SYNTH_PROMPT_ID.into(),
+        args,
+        context_refs: vec![],
+        model: "mock".into(),
+        max_output_tokens: 1024,
+        temperature: 0,
+        output_schema_id: "json_object".into(),
+        cache_mode: CacheMode::Off,
+    })?;
+
+    for (before, after) in suggested_edges(&result.result) {
+        let mut graph = synthesis.graph.clone();
+        let known = graph.node(&before).is_some();
+        let reason = match graph.node_mut(&after) {
+            Some(n) if known => {
+                if !n.dependencies.contains(&before) {
+                    n.dependencies.push(before.clone());
+                }
+                match Scheduler::new(graph.clone(), 4).validate() {
+                    Ok(()) => {
+                        synthesis.graph = graph;
+                        format!("after {before}: suggested by {SYNTH_PROMPT_ID}")
+                    }
+                    Err(e) => format!("rejected suggestion {before} → {after}: {e}"),
+                }
+            }
+            _ => format!("rejected suggestion {before} → {after}: unknown node"),
+        };
+        synthesis.rationale.push(Rationale {
+            node_id: after,
+            reason,
+        });
+    }
+    Ok(result.request_id)
+}
+
+fn json_arg<T: Serialize>(value: &T) -> Value {
+    Value::String(serde_json::to_string(value).unwrap_or_default())
+}
+
+/// `[[before, after], ...]` pairs from an LLM response's `dependencies`.
+fn suggested_edges(result: &Value) -> Vec<(String, String)> {
+    let Value::Map(fields) = result else {
+        return Vec::new();
+    };
+    let Some(Value::List(edges)) = fields.get("dependencies") else {
+        return Vec::new();
+    };
+    edges
+        .iter()
+        .filter_map(|e| match e {
+            Value::List(pair) => match pair.as_slice() {
+                [Value::String(a), Value::String(b)] => Some((a.clone(), b.clone())),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deliverable(desc: &str, touches: &[&str], reads: &[&str]) -> Deliverable {
+        Deliverable {
+            id: None,
+            description: desc.into(),
+            touches: touches.iter().map(|s| s.to_string()).collect(),
+            reads: reads.iter().map(|s| s.to_string()).collect(),
+            tags: vec![],
+        }
+    }
+
+    fn goal(deliverables: Vec<Deliverable>) -> GoalSpec {
+        GoalSpec {
+            id: "G-synth".into(),
+            description: "synth".into(),
+            deliverables,
+            constraints: Constraints::default(),
+            modules: BTreeMap::new(),
+        }
+    }
+
+    fn deps<'a>(s: &'a Synthesis, id: &str) -> &'a [String] {
+        &s.graph.node(id).unwrap().dependencies
+    }
+
+    #[test]
+    fn infers_edges_from_touched_modules() {
+        let mut g = goal(vec![
+            deliverable("vm opcode", &["vm"], &[]),
+            deliverable("compiler lowering", &["compiler"], &[]),
+            deliverable("docs", &["docs"], &["vm"]),
+            deliverable("vm tests", &["vm"], &[]),
+        ]);
+        g.modules.insert(
+            "compiler".into(),
+            ModuleInfo {
+                depends_on: vec!["bytecode".into()],
+            },
+        );
+        g.modules.insert(
+            "bytecode".into(),
+            ModuleInfo {
+                depends_on: vec!["vm".into()],
+            },
+        );
+        g.modules.insert("vm".into(), ModuleInfo::default());
+
+        let s = synthesize(&g).unwrap();
+        assert!(deps(&s, "WN-001").is_empty());
+        assert_eq!(deps(&s, "WN-002"), ["WN-001", "WN-004"]);
+        assert_eq!(deps(&s, "WN-003"), ["WN-001", "WN-004"]);
+        assert_eq!(deps(&s, "WN-004"), ["WN-001"]);
+        assert!(s.rationale.contains(&Rationale {
+            node_id: "WN-002".into(),
+            reason: "after WN-001: compiler depends on vm".into(),
+        }));
+        // Deterministic: same goal, same graph.
+        let again = synthesize(&g).unwrap();
+        assert_eq!(
+            serde_json::to_string(&s.graph).unwrap(),
+            serde_json::to_string(&again.graph).unwrap()
+        );
+    }
+
+    #[test]
+    fn constraints_add_check_nodes_and_cap_size() {
+        let mut g = goal(vec![
+            deliverable("a", &["m1"], &[]),
+            deliverable("b", &["m2"], &[]),
+        ]);
+        g.deliverables[1].tags = vec!["security".into()];
+        g.constraints.require_review = true;
+        g.constraints.red_team_tags = vec!["security".into()];
+
+        let s = synthesize(&g).unwrap();
+        let roles: Vec<(&str, Role)> = s
+            .graph
+            .nodes
+            .iter()
+            .map(|n| (n.id.as_str(), n.owner_role.clone()))
+            .collect();
+        assert_eq!(
+            roles,
+            [
+                ("WN-001", Role::Implementer),
+                ("WN-002", Role::Implementer),
+                ("WN-001-review", Role::Reviewer),
+                ("WN-002-review", Role::Reviewer),
+                ("WN-002-redteam", Role::RedTeam),
+            ]
+        );
+        assert_eq!(deps(&s, "WN-002-redteam"), ["WN-002"]);
+
+        g.constraints.max_nodes = Some(4);
+        assert!(synthesize(&g).unwrap_err().contains("max_nodes"));
+    }
+
+    #[test]
+    fn circular_module_dependencies_are_rejected() {
+        let mut g = goal(vec![
+            deliverable("a", &["m1"], &["m2"]),
+            deliverable("b", &["m2"], &["m1"]),
+        ]);
+        assert!(synthesize(&g).unwrap_err().contains("circular"));
+        g.deliverables[1].id = Some("WN-001".into());
+        assert!(synthesize(&g).unwrap_err().contains("duplicate"));
+    }
+
+    #[test]
+    fn mock_llm_suggestions_leave_the_graph_valid() {
+        let g = goal(vec![
+            deliverable("a", &["m1"], &[]),
+            deliverable("b", &["m2"], &[]),
+        ]);
+        let mut s = synthesize(&g).unwrap();
+        let before = serde_json::to_string(&s.graph).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let request_id = suggest_with_llm(&g, &mut s, dir.path()).unwrap();
+        assert!(!request_id.is_empty());
+        // The mock backend suggests nothing, so the candidate is unchanged.
+        assert_eq!(serde_json::to_string(&s.graph).unwrap(), before);
+
+        let mut map = BTreeMap::new();
+        map.insert(
+            "dependencies".to_string(),
+            Value::List(vec![Value::List(vec![
+                Value::String("WN-001".into()),
+                Value::String("WN-002".into()),
+            ])]),
+        );
+        assert_eq!(
+            suggested_edges(&Value::Map(map)),
+            [("WN-001".to_string(), "WN-002".to_string())]
+        );
+    }
+}
@@ -0,0 +1,317 @@
+//! Isolated workspace copies for `orch apply`.
+//!
+//! A bundle is applied and gated inside a [`Sandbox`] — a temporary copy of
+//! the workspace — and only the patched files are copied back once every
+//! gate passes. A hunk that fails to apply, a crashed gate or a failing
+//! check therefore never touches the real workspace.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// A temporary copy of a workspace, removed on drop.
+pub struct Sandbox {
+    dir: tempfile::TempDir,
+    workspace: PathBuf,
+    /// SHA-256 of each guarded file in the workspace when the copy was
+    /// made, or `None` if it did not exist.
+    originals: BTreeMap<String, Option<String>>,
+}
+
+/// A file [`Sandbox::sync_back`] has staged but not yet put in place.
+enum Staged {
+    /// Rename the staged copy `tmp` over `target`.
+    Replace { tmp: PathBuf, target: PathBuf },
+    /// The file was deleted in the sandbox; remove `target`.
+    Delete { target: PathBuf },
+}
+
+impl Staged {
+    fn discard(&self) {
+        if let Staged::Replace { tmp, .. } = self {
+            let _ = fs::remove_file(tmp);
+        }
+    }
+}
+
+/// SHA-256 of the file at `path`, or `None` if it does not exist.
+fn hash_if_exists(path: &Path, file: &str) -> Result<Option<String>, String> {
+    match fs::read(path) {
+        Ok(data) => Ok(Some(boruna_hash::sha256_hex(&data))),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(format!("cannot read {file}: {e}")),
+    }
+}
+
+impl Sandbox {
+    /// Copy `workspace` into a fresh temporary directory, skipping the
+    /// workspace-relative paths in `exclude`. `guarded` files are hashed
+    /// so [`Sandbox::sync_back`] can detect edits made in the meantime.
+    pub fn create(workspace: &Path, exclude: &[&str], guarded: &[String]) -> Result<Self, String> {
+        let dir = tempfile::Builder::new()
+            .prefix("boruna-apply-")
+            .tempdir()
+            .map_err(|e| format!("cannot create sandbox: {e}"))?;
+        let exclude: Vec<PathBuf> = exclude.iter().map(PathBuf::from).collect();
+        copy_tree(workspace, dir.path(), Path::new(""), &exclude)?;
+
+        let mut originals = BTreeMap::new();
+        for file in guarded {
+            let path = workspace.join(checked(file)?);
+            originals.insert(file.clone(), hash_if_exists(&path, file)?);
+        }
+        Ok(Self {
+            dir,
+            workspace: workspace.to_path_buf(),
+            originals,
+        })
+    }
+
+    /// Root of the copy.
+    pub fn root(&self) -> &Path {
+        self.dir.path()
+    }
+
+    /// Copy `files` from the sandbox back into the workspace, creating
+    /// missing parent directories, and delete those removed in the
+    /// sandbox. Fails without writing anything if a guarded file changed
+    /// in the workspace since the copy was made. All contents are staged
+    /// before the first file is replaced by a rename; staged copies left
+    /// over by a failure are removed.
+    pub fn sync_back(&self, files: &[String]) -> Result<(), String> {
+        for file in files {
+            let Some(expected) = self.originals.get(file) else {
+                continue;
+            };
+            let current = hash_if_exists(&self.workspace.join(checked(file)?), file)?;
+            if current != *expected {
+                return Err(format!(
+                    "{file} changed in the workspace while gates ran; not syncing"
+                ));
+            }
+        }
+
+        let mut staged = Vec::new();
+        if let Err(e) = self.stage(files, &mut staged) {
+            staged.iter().for_each(Staged::discard);
+            return Err(e);
+        }
+        for (i, step) in staged.iter().enumerate() {
+            let done = match step {
+                Staged::Replace { tmp, target } => fs::rename(tmp, target)
+                    .map_err(|e| format!("cannot replace {}: {e}", target.display())),
+                Staged::Delete { target } => match fs::remove_file(target) {
+                    Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                        Err(format!("cannot delete {}: {e}", target.display()))
+                    }
+                    _ => Ok(()),
+                },
+            };
+            if let Err(e) = done {
+                staged[i..].iter().for_each(Staged::discard);
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Stage each of `files` next to its workspace target, pushing onto
+    /// `staged` as it goes so the caller can clean up after a failure.
+    fn stage(&self, files: &[String], staged: &mut Vec<Staged>) -> Result<(), String> {
+        for file in files {
+            let rel = checked(file)?;
+            let target = self.workspace.join(rel);
+            let data = match fs::read(self.root().join(rel)) {
+                Ok(data) => data,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                    staged.push(Staged::Delete { target });
+                    continue;
+                }
+                Err(e) => return Err(format!("cannot read sandbox {file}: {e}")),
+            };
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|e| format!("cannot create {}: {e}", parent.display()))?;
+            }
+            let tmp = target.with_file_name(format!(
+                ".{}.boruna-sync",
+                target
+                    .file_name()
+                    .map(|n| n.to_string_lossy().into_owned())
+                    .unwrap_or_default()
+            ));
+            fs::write(&tmp, data).map_err(|e| {
+                let _ = fs::remove_file(&tmp);
+                format!("cannot stage {file}: {e}")
+            })?;
+            staged.push(Staged::Replace { tmp, target });
+        }
+        Ok(())
+    }
+}
+
+/// `file` as a relative path with no `..`, root or prefix components.
+fn checked(file: &str) -> Result<&Path, String> {
+    let path = Path::new(file);
+    if path.components().all(|c| matches!(c, Component::Normal(_))) {
+        Ok(path)
+    } else {
+        Err(format!("path escapes the workspace: {file}"))
+    }
+}
+
+fn copy_tree(src: &Path, dst: &Path, rel: &Path, exclude: &[PathBuf]) -> Result<(), String> {
+    let from = src.join(rel);
+    for entry in fs::read_dir(&from).map_err(|e| format!("read {}: {e}", from.display()))? {
+        let entry = entry.map_err(|e| format!("read entry: {e}"))?;
+        let rel = rel.join(entry.file_name());
+        if exclude.contains(&rel) {
+            continue;
+        }
+        let kind = entry
+            .file_type()
+            .map_err(|e| format!("stat {}: {e}", rel.display()))?;
+        let to = dst.join(&rel);
+        if kind.is_dir() {
+            fs::create_dir_all(&to).map_err(|e| format!("mkdir {}: {e}", to.display()))?;
+            copy_tree(src, dst, &rel, exclude)?;
+        } else if kind.is_symlink() {
+            copy_symlink(&entry.path(), &to)?;
+        } else {
+            fs::copy(entry.path(), &to).map_err(|e| format!("copy {}: {e}", rel.display()))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn copy_symlink(link: &Path, to: &Path) -> Result<(), String> {
+    let target = fs::read_link(link).map_err(|e| format!("readlink {}: {e}", link.display()))?;
+    std::os::unix::fs::symlink(target, to).map_err(|e| format!("symlink {}: {e}", to.display()))
+}
+
+#[cfg(not(unix))]
+fn copy_symlink(link: &Path, to: &Path) -> Result<(), String> {
+    if link.is_file() {
+        fs::copy(link, to).map_err(|e| format!("copy {}: {e}", link.display()))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workspace() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        for (path, contents) in [
+            ("src/a.txt", "a\n"),
+            ("src/b.txt", "b\n"),
+            ("target/debug/big", "x"),
+            ("orchestrator/storage/graphs/G.json", "{}"),
+        ] {
+            let path = dir.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, contents).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn copies_workspace_and_syncs_only_named_files() {
+        let ws = workspace();
+        let files = vec!["src/a.txt".to_string()];
+        let sandbox =
+            Sandbox::create(ws.path(), &["target", "orchestrator/storage"], &files).unwrap();
+        assert!(sandbox.root().join("src/b.txt").exists());
+        assert!(!sandbox.root().join("target").exists());
+        assert!(!sandbox.root().join("orchestrator/storage").exists());
+        assert!(sandbox.root().join("orchestrator").exists());
+
+        fs::write(sandbox.root().join("src/a.txt"), "patched\n").unwrap();
+        fs::write(sandbox.root().join("src/b.txt"), "stray\n").unwrap();
+        sandbox.sync_back(&files).unwrap();
+        assert_eq!(
+            fs::read_to_string(ws.path().join("src/a.txt")).unwrap(),
+            "patched\n"
+        );
+        assert_eq!(
+            fs::read_to_string(ws.path().join("src/b.txt")).unwrap(),
+            "b\n"
+        );
+
+        let root = sandbox.root().to_path_buf();
+        drop(sandbox);
+        assert!(!root.exists());
+    }
+
+    #[test]
+    fn concurrent_workspace_edits_block_the_sync() {
+        let ws = workspace();
+        let files = vec!["src/a.txt".to_string()];
+        let sandbox = Sandbox::create(ws.path(), &[], &files).unwrap();
+        fs::write(sandbox.root().join("src/a.txt"), "patched\n").unwrap();
+        fs::write(ws.path().join("src/a.txt"), "edited meanwhile\n").unwrap();
+
+        let err = sandbox.sync_back(&files).unwrap_err();
+        assert!(err.contains("changed in the workspace"), "{err}");
+        assert_eq!(
+            fs::read_to_string(ws.path().join("src/a.txt")).unwrap(),
+            "edited meanwhile\n"
+        );
+        assert!(Sandbox::create(ws.path(), &[], &["../x".to_string()]).is_err());
+    }
+
+    #[test]
+    fn new_files_in_new_directories_are_synced() {
+        let ws = workspace();
+        let files = vec!["src/new/deep/c.txt".to_string()];
+        let sandbox = Sandbox::create(ws.path(), &[], &files).unwrap();
+        let created = sandbox.root().join("src/new/deep/c.txt");
+        fs::create_dir_all(created.parent().unwrap()).unwrap();
+        fs::write(&created, "c\n").unwrap();
+
+        sandbox.sync_back(&files).unwrap();
+        assert_eq!(
+            fs::read_to_string(ws.path().join("src/new/deep/c.txt")).unwrap(),
+            "c\n"
+        );
+    }
+
+    #[test]
+    fn deletions_in_the_sandbox_are_synced() {
+        let ws = workspace();
+        let files = vec!["src/a.txt".to_string(), "src/b.txt".to_string()];
+        let sandbox = Sandbox::create(ws.path(), &[], &files).unwrap();
+        fs::remove_file(sandbox.root().join("src/a.txt")).unwrap();
+        fs::write(sandbox.root().join("src/b.txt"), "patched\n").unwrap();
+
+        sandbox.sync_back(&files).unwrap();
+        assert!(!ws.path().join("src/a.txt").exists());
+        assert_eq!(
+            fs::read_to_string(ws.path().join("src/b.txt")).unwrap(),
+            "patched\n"
+        );
+    }
+
+    #[test]
+    fn a_failed_sync_leaves_no_staged_files() {
+        let ws = workspace();
+        let sandbox = Sandbox::create(ws.path(), &[], &["src/a.txt".to_string()]).unwrap();
+        fs::write(sandbox.root().join("src/a.txt"), "patched\n").unwrap();
+        fs::create_dir_all(sandbox.root().join("blocked")).unwrap();
+        fs::write(sandbox.root().join("blocked/c.txt"), "c\n").unwrap();
+        // The second file's directory is a regular file in the workspace,
+        // so staging it fails after the first file was staged.
+        fs::write(ws.path().join("blocked"), "not a directory").unwrap();
+
+        let files = vec!["src/a.txt".to_string(), "blocked/c.txt".to_string()];
+        let err = sandbox.sync_back(&files).unwrap_err();
+        assert!(err.contains("cannot create"), "{err}");
+        assert!(!ws.path().join("src/.a.txt.boruna-sync").exists());
+        assert_eq!(
+            fs::read_to_string(ws.path().join("src/a.txt")).unwrap(),
+            "a\n"
+        );
+    }
+}
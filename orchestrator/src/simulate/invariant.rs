@@ -508,6 +508,7 @@ mod tests {
             capabilities_used: vec![],
             error: None,
             attempt_count: 1,
+            event_log: None,
         }
     }
 
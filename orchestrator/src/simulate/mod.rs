@@ -296,6 +296,8 @@ mod tests {
             description: String::new(),
             steps: BTreeMap::new(),
             edges: Vec::new(),
+            budget: None,
+            params: Default::default(),
         }
     }
 
@@ -1,12 +1,93 @@
-use std::fs;
+use std::fmt;
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
+use crate::adapters::trend::GateRun;
 use crate::conflict::LockTable;
-use crate::engine::WorkGraph;
+use serde::{Deserialize, Serialize};
+
+use crate::engine::{ArtifactRef, EditEvent, WorkGraph};
+use crate::identity::Roster;
+
+/// How long [`Store::lock`] waits for another writer by default.
+pub const DEFAULT_WAIT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Interval between attempts while [`Store::lock`] waits.
+const LOCK_POLL: Duration = Duration::from_millis(10);
+
+/// Failure to take the store's writer lock.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StoreError {
+    /// Another process held the lock for the whole wait.
+    Contention { lock: PathBuf, waited_ms: u64 },
+    /// The lock file could not be opened or locked.
+    Io(String),
+}
+
+impl StoreError {
+    /// Stable machine-readable kind (`store.contention`, `store.io`).
+    pub fn error_kind(&self) -> &'static str {
+        match self {
+            StoreError::Contention { .. } => "store.contention",
+            StoreError::Io(_) => "store.io",
+        }
+    }
+}
+
+impl fmt::Display for StoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StoreError::Contention { lock, waited_ms } => write!(
+                f,
+                "store is locked by another writer ({}); gave up after {waited_ms}ms \
+                 (raise --wait-timeout to wait longer)",
+                lock.display()
+            ),
+            StoreError::Io(e) => write!(f, "store lock error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for StoreError {}
+
+impl From<StoreError> for String {
+    fn from(e: StoreError) -> Self {
+        e.to_string()
+    }
+}
+
+/// One actor-attributed command, appended to `activity.jsonl`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActivityEvent {
+    pub timestamp: String,
+    pub actor: String,
+    /// `plan`, `assign`, `block`, `apply` or `review`.
+    pub action: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub graph_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub node_id: Option<String>,
+    #[serde(default, skip_serializing_if = "serde_json::Value::is_null")]
+    pub detail: serde_json::Value,
+}
+
+/// Exclusive hold on the store's writer lock, released on drop.
+#[derive(Debug)]
+pub struct StoreLock {
+    _file: File,
+}
 
 /// Local file-based JSON storage for orchestrator state.
+///
+/// Files are replaced atomically (write to a temporary sibling, then
+/// rename), so a reader never sees a half-written graph or lock table.
+/// Read-modify-write sequences must hold [`Store::lock`] so two
+/// processes cannot both act on the same state.
 pub struct Store {
     base_dir: PathBuf,
+    wait_timeout: Duration,
 }
 
 impl Store {
@@ -15,17 +96,64 @@ impl Store {
         let bundles_dir = base_dir.join("bundles");
         let locks_dir = base_dir.join("locks");
         let gates_dir = base_dir.join("gates");
+        let history_dir = base_dir.join("history");
+        let artifacts_dir = base_dir.join("artifacts");
 
-        for dir in [&graphs_dir, &bundles_dir, &locks_dir, &gates_dir] {
+        for dir in [
+            &graphs_dir,
+            &bundles_dir,
+            &locks_dir,
+            &gates_dir,
+            &history_dir,
+            &artifacts_dir,
+        ] {
             fs::create_dir_all(dir)
                 .map_err(|e| format!("failed to create {}: {e}", dir.display()))?;
         }
 
         Ok(Self {
             base_dir: base_dir.to_path_buf(),
+            wait_timeout: DEFAULT_WAIT_TIMEOUT,
         })
     }
 
+    /// Set how long [`Store::lock`] waits for another writer.
+    pub fn with_wait_timeout(mut self, timeout: Duration) -> Self {
+        self.wait_timeout = timeout;
+        self
+    }
+
+    /// Take the advisory writer lock on the store, waiting up to the
+    /// store's wait timeout. Hold the returned guard across every load
+    /// and save of a read-modify-write sequence.
+    pub fn lock(&self) -> Result<StoreLock, StoreError> {
+        let path = self.base_dir.join("store.lock");
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)
+            .map_err(|e| StoreError::Io(format!("{}: {e}", path.display())))?;
+        let start = Instant::now();
+        loop {
+            match file.try_lock() {
+                Ok(()) => return Ok(StoreLock { _file: file }),
+                Err(TryLockError::WouldBlock) if start.elapsed() < self.wait_timeout => {
+                    std::thread::sleep(LOCK_POLL);
+                }
+                Err(TryLockError::WouldBlock) => {
+                    return Err(StoreError::Contention {
+                        lock: path,
+                        waited_ms: start.elapsed().as_millis() as u64,
+                    })
+                }
+                Err(TryLockError::Error(e)) => {
+                    return Err(StoreError::Io(format!("{}: {e}", path.display())))
+                }
+            }
+        }
+    }
+
     /// Save a work graph.
     pub fn save_graph(&self, graph: &WorkGraph) -> Result<(), String> {
         let path = self
@@ -34,7 +162,7 @@ impl Store {
             .join(format!("{}.json", graph.id));
         let json =
             serde_json::to_string_pretty(graph).map_err(|e| format!("serialize error: {e}"))?;
-        fs::write(&path, json).map_err(|e| format!("write error: {e}"))
+        write_atomic(&path, &json)
     }
 
     /// Load a work graph by ID.
@@ -53,6 +181,9 @@ impl Store {
         let mut ids = Vec::new();
         for entry in fs::read_dir(&dir).map_err(|e| format!("read dir error: {e}"))? {
             let entry = entry.map_err(|e| format!("entry error: {e}"))?;
+            if !is_json(&entry.path()) {
+                continue;
+            }
             if let Some(name) = entry.path().file_stem() {
                 ids.push(name.to_string_lossy().to_string());
             }
@@ -67,6 +198,9 @@ impl Store {
 
         for entry in fs::read_dir(&dir).map_err(|e| format!("read dir error: {e}"))? {
             let entry = entry.map_err(|e| format!("entry error: {e}"))?;
+            if !is_json(&entry.path()) {
+                continue;
+            }
             let meta = entry
                 .metadata()
                 .map_err(|e| format!("metadata error: {e}"))?;
@@ -93,7 +227,7 @@ impl Store {
         let path = self.base_dir.join("locks").join("locks.json");
         let json =
             serde_json::to_string_pretty(locks).map_err(|e| format!("serialize error: {e}"))?;
-        fs::write(&path, json).map_err(|e| format!("write error: {e}"))
+        write_atomic(&path, &json)
     }
 
     /// Load lock table.
@@ -118,7 +252,7 @@ impl Store {
             .join(format!("{node_id}.gate.json"));
         let json =
             serde_json::to_string_pretty(result).map_err(|e| format!("serialize error: {e}"))?;
-        fs::write(&path, json).map_err(|e| format!("write error: {e}"))
+        write_atomic(&path, &json)
     }
 
     /// Load gate results for a node.
@@ -131,12 +265,161 @@ impl Store {
         serde_json::from_str(&data).map_err(|e| format!("parse error: {e}"))
     }
 
+    /// Load every stored gate result, ordered by file name.
+    pub fn list_gate_results(&self) -> Result<Vec<serde_json::Value>, String> {
+        let dir = self.base_dir.join("gates");
+        let mut paths = Vec::new();
+        for entry in fs::read_dir(&dir).map_err(|e| format!("read dir error: {e}"))? {
+            let entry = entry.map_err(|e| format!("entry error: {e}"))?;
+            if is_json(&entry.path()) {
+                paths.push(entry.path());
+            }
+        }
+        paths.sort();
+        paths
+            .iter()
+            .map(|path| {
+                let data = fs::read_to_string(path).map_err(|e| format!("read error: {e}"))?;
+                serde_json::from_str(&data).map_err(|e| format!("parse error: {e}"))
+            })
+            .collect()
+    }
+
+    /// Append a gate run to the gate history.
+    pub fn append_gate_run(&self, run: &GateRun) -> Result<(), String> {
+        append_jsonl(&self.gate_history_path(), run)
+    }
+
+    /// Load the gate history, oldest first.
+    pub fn load_gate_history(&self) -> Result<Vec<GateRun>, String> {
+        load_jsonl(&self.gate_history_path())
+    }
+
+    fn gate_history_path(&self) -> PathBuf {
+        self.base_dir.join("gates").join("history.jsonl")
+    }
+
+    /// Append an edit to the graph's history (one JSON object per line).
+    pub fn append_edit(&self, event: &EditEvent) -> Result<(), String> {
+        append_jsonl(&self.history_path(&event.graph_id), event)
+    }
+
+    /// Load a graph's edit history, oldest first.
+    pub fn load_history(&self, graph_id: &str) -> Result<Vec<EditEvent>, String> {
+        load_jsonl(&self.history_path(graph_id))
+    }
+
+    /// Append a command to the activity log.
+    pub fn record_activity(&self, event: &ActivityEvent) -> Result<(), String> {
+        append_jsonl(&self.base_dir.join("activity.jsonl"), event)
+    }
+
+    /// Load the activity log, oldest first.
+    pub fn load_activity(&self) -> Result<Vec<ActivityEvent>, String> {
+        load_jsonl(&self.base_dir.join("activity.jsonl"))
+    }
+
+    /// Load the actor roster (`actors.json`), if the store has one.
+    pub fn load_roster(&self) -> Result<Option<Roster>, String> {
+        let path = self.base_dir.join("actors.json");
+        if !path.exists() {
+            return Ok(None);
+        }
+        let data = fs::read_to_string(&path).map_err(|e| format!("read error: {e}"))?;
+        serde_json::from_str(&data)
+            .map(Some)
+            .map_err(|e| format!("invalid roster {}: {e}", path.display()))
+    }
+
+    fn history_path(&self, graph_id: &str) -> PathBuf {
+        self.base_dir
+            .join("history")
+            .join(format!("{graph_id}.jsonl"))
+    }
+
+    /// Store `contents` as `artifacts/<node_id>/<name>`; `name` may contain
+    /// `/`-separated subdirectories but no `..` or absolute components.
+    pub fn save_artifact(
+        &self,
+        node_id: &str,
+        kind: &str,
+        name: &str,
+        contents: &[u8],
+    ) -> Result<ArtifactRef, String> {
+        let rel = Path::new(name);
+        if !rel
+            .components()
+            .all(|c| matches!(c, std::path::Component::Normal(_)))
+        {
+            return Err(format!("invalid artifact name: {name}"));
+        }
+        let path = self.artifacts_dir(node_id).join(rel);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("write error: {e}"))?;
+        }
+        fs::write(&path, contents).map_err(|e| format!("write error: {e}"))?;
+        Ok(ArtifactRef {
+            kind: kind.to_string(),
+            path: format!("artifacts/{node_id}/{name}"),
+            sha256: boruna_hash::sha256_hex(contents),
+        })
+    }
+
+    /// Remove everything recorded for a node, ahead of a fresh attempt.
+    pub fn clear_artifacts(&self, node_id: &str) -> Result<(), String> {
+        let dir = self.artifacts_dir(node_id);
+        if dir.exists() {
+            fs::remove_dir_all(&dir).map_err(|e| format!("remove error: {e}"))?;
+        }
+        Ok(())
+    }
+
+    /// Directory holding a node's artifacts.
+    pub fn artifacts_dir(&self, node_id: &str) -> PathBuf {
+        self.base_dir.join("artifacts").join(node_id)
+    }
+
     /// Path to the bundles directory.
     pub fn bundles_dir(&self) -> PathBuf {
         self.base_dir.join("bundles")
     }
 }
 
+fn append_jsonl<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    let mut line = serde_json::to_string(value).map_err(|e| format!("serialize error: {e}"))?;
+    line.push('\n');
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("write error: {e}"))?;
+    file.write_all(line.as_bytes())
+        .map_err(|e| format!("write error: {e}"))
+}
+
+fn load_jsonl<T: for<'de> Deserialize<'de>>(path: &Path) -> Result<Vec<T>, String> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let data = fs::read_to_string(path).map_err(|e| format!("read error: {e}"))?;
+    data.lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| serde_json::from_str(l).map_err(|e| format!("parse error: {e}")))
+        .collect()
+}
+
+/// Graph files, as opposed to an in-flight [`write_atomic`] temporary.
+fn is_json(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "json")
+}
+
+/// Replace `path` with `contents` via a temporary sibling and a rename.
+fn write_atomic(path: &Path, contents: &str) -> Result<(), String> {
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, contents).map_err(|e| format!("write error: {e}"))?;
+    fs::rename(&tmp, path).map_err(|e| format!("write error: {e}"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -163,7 +446,10 @@ mod tests {
                 assigned_to: None,
                 patch_bundle: None,
                 review_result: None,
+                artifacts: vec![],
+                priority: Default::default(),
             }],
+            strategy: Default::default(),
         };
 
         store.save_graph(&graph).unwrap();
@@ -183,12 +469,14 @@ mod tests {
             id: "G-001".into(),
             description: "a".into(),
             nodes: vec![],
+            strategy: Default::default(),
         };
         let g2 = WorkGraph {
             schema_version: 1,
             id: "G-002".into(),
             description: "b".into(),
             nodes: vec![],
+            strategy: Default::default(),
         };
         store.save_graph(&g1).unwrap();
         store.save_graph(&g2).unwrap();
@@ -232,5 +520,175 @@ mod tests {
         store.save_gate_result("WN-001", &result).unwrap();
         let loaded = store.load_gate_result("WN-001").unwrap();
         assert_eq!(loaded["test"]["total"], 179);
+
+        store
+            .save_gate_result("WN-000", &serde_json::json!({"bundle_id": "WN-000"}))
+            .unwrap();
+        let all = store.list_gate_results().unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0]["bundle_id"], "WN-000");
+
+        assert!(store.load_gate_history().unwrap().is_empty());
+        let run = GateRun {
+            timestamp: "2026-01-01T00:00:00Z".into(),
+            action: "apply".into(),
+            bundle_id: "WN-001".into(),
+            node_id: None,
+            files_hash: "h".into(),
+            gates: vec![],
+        };
+        store.append_gate_run(&run).unwrap();
+        store.append_gate_run(&run).unwrap();
+        assert_eq!(store.load_gate_history().unwrap(), vec![run.clone(), run]);
+        // The history is not a gate result file.
+        assert_eq!(store.list_gate_results().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_edit_history_appends_in_order() {
+        use crate::engine::GraphEdit;
+
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::new(dir.path()).unwrap();
+        assert!(store.load_history("G-001").unwrap().is_empty());
+
+        for (i, id) in ["WN-001", "WN-002"].iter().enumerate() {
+            store
+                .append_edit(&EditEvent {
+                    timestamp: format!("t{i}"),
+                    graph_id: "G-001".into(),
+                    edit: GraphEdit::Skip {
+                        node_id: id.to_string(),
+                    },
+                    previous_status: Some(NodeStatus::Failed),
+                    reason: None,
+                    actor: Some("alice".into()),
+                })
+                .unwrap();
+        }
+        let history = store.load_history("G-001").unwrap();
+        let ids: Vec<&str> = history.iter().map(|e| e.edit.node_id()).collect();
+        assert_eq!(ids, ["WN-001", "WN-002"]);
+        assert_eq!(history[1].previous_status, Some(NodeStatus::Failed));
+    }
+
+    #[test]
+    fn test_artifacts_are_hashed_and_confined() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::new(dir.path()).unwrap();
+
+        let art = store
+            .save_artifact("WN-001", "file", "files/src/lib.rs", b"fn x() {}")
+            .unwrap();
+        assert_eq!(art.path, "artifacts/WN-001/files/src/lib.rs");
+        assert_eq!(art.sha256, boruna_hash::sha256_hex(b"fn x() {}"));
+        assert!(dir.path().join(&art.path).exists());
+
+        assert!(store
+            .save_artifact("WN-001", "file", "../escape", b"")
+            .is_err());
+        store.clear_artifacts("WN-001").unwrap();
+        assert!(!store.artifacts_dir("WN-001").exists());
+    }
+
+    #[test]
+    fn test_activity_log_and_roster() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::new(dir.path()).unwrap();
+        assert!(store.load_roster().unwrap().is_none());
+
+        let event = ActivityEvent {
+            timestamp: "t".into(),
+            actor: "bob".into(),
+            action: "review".into(),
+            graph_id: Some("G-001".into()),
+            node_id: None,
+            detail: serde_json::json!({ "decision": "approve" }),
+        };
+        store.record_activity(&event).unwrap();
+        assert_eq!(store.load_activity().unwrap(), [event]);
+
+        fs::write(
+            dir.path().join("actors.json"),
+            r#"{ "actors": { "bob": ["reviewer"] } }"#,
+        )
+        .unwrap();
+        let roster = store.load_roster().unwrap().unwrap();
+        assert!(roster.authorize("bob", &Role::Reviewer).is_ok());
+    }
+
+    #[test]
+    fn lock_contention_is_a_typed_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::new(dir.path()).unwrap();
+        let held = store.lock().unwrap();
+
+        let waiting = Store::new(dir.path())
+            .unwrap()
+            .with_wait_timeout(Duration::from_millis(30));
+        let err = waiting.lock().unwrap_err();
+        assert_eq!(err.error_kind(), "store.contention");
+        match &err {
+            StoreError::Contention { lock, waited_ms } => {
+                assert_eq!(lock, &dir.path().join("store.lock"));
+                assert!(*waited_ms >= 30);
+            }
+            other => panic!("expected contention, got {other:?}"),
+        }
+
+        drop(held);
+        assert!(waiting.lock().is_ok());
+    }
+
+    #[test]
+    fn concurrent_writers_assign_distinct_nodes() {
+        use crate::engine::Scheduler;
+
+        let dir = tempfile::tempdir().unwrap();
+        let node = |id: &str| WorkNode {
+            id: id.into(),
+            description: id.into(),
+            inputs: vec![],
+            outputs: vec![format!("mod-{id}")],
+            dependencies: vec![],
+            owner_role: Role::Implementer,
+            tags: vec![],
+            status: NodeStatus::Ready,
+            assigned_to: None,
+            patch_bundle: None,
+            review_result: None,
+            artifacts: vec![],
+            priority: Default::default(),
+        };
+        let graph = WorkGraph {
+            schema_version: 1,
+            id: "G-race".into(),
+            description: "race".into(),
+            nodes: (0..8).map(|i| node(&format!("WN-{i}"))).collect(),
+            strategy: Default::default(),
+        };
+        Store::new(dir.path()).unwrap().save_graph(&graph).unwrap();
+
+        let workers: Vec<_> = (0..8)
+            .map(|_| {
+                let base = dir.path().to_path_buf();
+                std::thread::spawn(move || {
+                    let store = Store::new(&base).unwrap();
+                    let _lock = store.lock().unwrap();
+                    let mut sched = Scheduler::new(store.load_graph("G-race").unwrap(), 8);
+                    let id = sched.assign_next(Role::Implementer).unwrap();
+                    store.save_graph(&sched.graph).unwrap();
+                    id
+                })
+            })
+            .collect();
+        let mut assigned: Vec<String> = workers.into_iter().map(|w| w.join().unwrap()).collect();
+        assigned.sort();
+        assigned.dedup();
+        assert_eq!(assigned.len(), 8);
+        assert_eq!(
+            Store::new(dir.path()).unwrap().list_graphs().unwrap(),
+            ["G-race"]
+        );
     }
 }
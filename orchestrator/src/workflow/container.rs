@@ -0,0 +1,640 @@
+//! Container isolation for workflow steps.
+//!
+//! A source step declaring `"isolation": "container"` is read, checked
+//! against its schemas and the data-flow policy, and compiled on the host
+//! exactly like an in-process step; only its VM runs in a container. The
+//! runner writes a [`StepJob`] — bytecode, step policy, resolved inputs and
+//! the budget left to the step — to a scratch directory, a
+//! [`ContainerBackend`] runs `boruna workflow exec-step` on it inside a
+//! container, and the [`StepJobResult`] written back carries the step's
+//! value, event log and resource usage. The runner then stores and hashes
+//! the output as usual, so output hashes and evidence match an in-process
+//! run of the same step.
+//!
+//! The container is not trusted to report what it consumed: it enforces
+//! the budget written into its job, and the host debits only the
+//! wall-clock time it measured ([`Resource::ContainerMs`]).
+//!
+//! [`ContainerSpec::for_step`] translates the step policy into container
+//! settings through the same reading of the policy as the OS sandbox
+//! ([`SandboxProfile::from_policy`]): no network unless a network
+//! capability is allowed, and the workflow directory mounted read-only
+//! when the policy allows reads, read-write when it allows writes, and not
+//! at all otherwise. [`CliBackend`] runs the spec with podman or docker,
+//! and kills the container when the step's `timeout_ms` expires.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use boruna_budget::{Budget, BudgetLedger, Resource, Usage};
+use boruna_bytecode::{Module, Value};
+use boruna_vm::capability_gateway::Policy;
+use boruna_vm::replay::EventLog;
+use boruna_vm::SandboxProfile;
+use serde::{Deserialize, Serialize};
+
+use crate::workflow::runner::{error_class, run_step_vm};
+
+/// Job file the runner writes into the scratch directory.
+pub const JOB_FILE: &str = "job.json";
+
+/// Result file `exec-step` writes next to the job.
+pub const RESULT_FILE: &str = "result.json";
+
+/// Where the scratch directory is mounted in the container.
+pub const CONTAINER_JOB_DIR: &str = "/boruna/job";
+
+/// Where the workflow directory is mounted when the policy allows it.
+pub const CONTAINER_WORKFLOW_DIR: &str = "/boruna/workflow";
+
+/// Everything a container needs to run one step's VM.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepJob {
+    pub step_id: String,
+    pub module: Module,
+    /// The step policy, as built for an in-process run.
+    pub policy: Policy,
+    /// Resolved `step_input` values, by input name.
+    pub inputs: BTreeMap<String, Value>,
+    pub live: bool,
+    /// What the run's and the step's budgets have left.
+    pub budget: Budget,
+}
+
+/// How a step's VM run ended.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepJobOutcome {
+    Completed {
+        value: Value,
+        event_log: Box<EventLog>,
+    },
+    /// `class` is one of the [`error_class`] strings.
+    Failed { error: String, class: String },
+}
+
+/// What `exec-step` writes to [`RESULT_FILE`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepJobResult {
+    pub outcome: StepJobOutcome,
+    /// Resources the run reports it consumed. The host cannot check this,
+    /// so it is not debited from the host ledger.
+    pub usage: Usage,
+}
+
+/// A bind mount into the container.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mount {
+    pub host: PathBuf,
+    pub container: String,
+    pub read_only: bool,
+}
+
+/// How to run one step's container, independent of the runtime.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContainerSpec {
+    pub step_id: String,
+    /// Container name, taken from the job directory's name so it is
+    /// unique per job and a runaway container can be killed by name.
+    pub name: String,
+    pub mounts: Vec<Mount>,
+    /// Whether the container gets a network. Without one it has only
+    /// loopback.
+    pub network: bool,
+    pub workdir: String,
+    pub command: Vec<String>,
+    /// Wall-clock limit for the run, from the step's `timeout_ms`.
+    pub timeout: Option<Duration>,
+}
+
+impl ContainerSpec {
+    /// The spec for running the job in `job_dir` under `policy`. The root
+    /// filesystem is read-only in every case; `job_dir` is the only
+    /// mount that is always writable.
+    pub fn for_step(step_id: &str, policy: &Policy, workflow_dir: &Path, job_dir: &Path) -> Self {
+        let profile = SandboxProfile::from_policy(policy);
+        let mut mounts = vec![Mount {
+            host: job_dir.to_path_buf(),
+            container: CONTAINER_JOB_DIR.to_string(),
+            read_only: false,
+        }];
+        let mut workdir = CONTAINER_JOB_DIR.to_string();
+        if profile.read_anywhere || profile.write_anywhere {
+            mounts.push(Mount {
+                host: workflow_dir.to_path_buf(),
+                container: CONTAINER_WORKFLOW_DIR.to_string(),
+                read_only: !profile.write_anywhere,
+            });
+            workdir = CONTAINER_WORKFLOW_DIR.to_string();
+        }
+        let name = job_dir
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default()
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-') {
+                    c
+                } else {
+                    '-'
+                }
+            })
+            .collect();
+        ContainerSpec {
+            step_id: step_id.to_string(),
+            name,
+            mounts,
+            network: profile.network,
+            workdir,
+            command: vec![
+                "boruna".into(),
+                "workflow".into(),
+                "exec-step".into(),
+                format!("{CONTAINER_JOB_DIR}/{JOB_FILE}"),
+                format!("{CONTAINER_JOB_DIR}/{RESULT_FILE}"),
+            ],
+            timeout: None,
+        }
+    }
+}
+
+/// Why a [`ContainerBackend`] run did not complete.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ContainerRunError {
+    /// The container could not run or exited unsuccessfully.
+    Failed(String),
+    /// The container outlived the spec's timeout and was killed.
+    TimedOut(Duration),
+}
+
+impl fmt::Display for ContainerRunError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ContainerRunError::Failed(msg) => f.write_str(msg),
+            ContainerRunError::TimedOut(limit) => write!(
+                f,
+                "step exceeded its {}ms wall-time limit and was killed",
+                limit.as_millis()
+            ),
+        }
+    }
+}
+
+/// Runs a [`ContainerSpec`] to completion, or until its timeout. A step
+/// that fails inside the container still exits cleanly and reports the
+/// failure in its result.
+pub trait ContainerBackend: fmt::Debug + Send + Sync {
+    fn run(&self, spec: &ContainerSpec) -> Result<(), ContainerRunError>;
+}
+
+/// A [`ContainerBackend`] driving a docker-compatible CLI (`podman`,
+/// `docker`) with an image that has `boruna` on its `PATH`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CliBackend {
+    pub program: String,
+    pub image: String,
+}
+
+impl CliBackend {
+    pub fn new(program: impl Into<String>, image: impl Into<String>) -> Self {
+        CliBackend {
+            program: program.into(),
+            image: image.into(),
+        }
+    }
+
+    /// Arguments to `program` for running `spec`.
+    pub fn args(&self, spec: &ContainerSpec) -> Vec<String> {
+        let mut args: Vec<String> = [
+            "run",
+            "--rm",
+            "--read-only",
+            "--cap-drop=ALL",
+            "--security-opt=no-new-privileges",
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect();
+        args.push(format!("--name={}", spec.name));
+        if !spec.network {
+            args.push("--network=none".into());
+        }
+        for mount in &spec.mounts {
+            let mut fields = vec![
+                "type=bind".to_string(),
+                mount_field("src", &mount.host.display().to_string()),
+                mount_field("dst", &mount.container),
+            ];
+            if mount.read_only {
+                fields.push("readonly".into());
+            }
+            args.push(format!("--mount={}", fields.join(",")));
+        }
+        args.push(format!("--workdir={}", spec.workdir));
+        args.push(self.image.clone());
+        args.extend(spec.command.iter().cloned());
+        args
+    }
+}
+
+/// One `key=value` field of a `--mount` option. The option is parsed as
+/// CSV, so a value with `,` or `"` is quoted and its quotes doubled.
+fn mount_field(key: &str, value: &str) -> String {
+    let field = format!("{key}={value}");
+    if field.contains([',', '"']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field
+    }
+}
+
+/// How often [`CliBackend`] checks a running container against its
+/// deadline.
+const WAIT_POLL: Duration = Duration::from_millis(10);
+
+impl CliBackend {
+    /// Kill and remove the container `name`. Best effort: the container
+    /// may already have exited.
+    fn kill(&self, name: &str) {
+        for args in [&["kill", name][..], &["rm", "-f", name][..]] {
+            let _ = Command::new(&self.program)
+                .args(args)
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status();
+        }
+    }
+}
+
+impl ContainerBackend for CliBackend {
+    fn run(&self, spec: &ContainerSpec) -> Result<(), ContainerRunError> {
+        let mut child = Command::new(&self.program)
+            .args(self.args(spec))
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| {
+                ContainerRunError::Failed(format!("cannot start {}: {e}", self.program))
+            })?;
+        // Drain stderr on the side so a chatty container cannot block on
+        // a full pipe while we wait.
+        let mut pipe = child.stderr.take().expect("stderr is piped");
+        let stderr = std::thread::spawn(move || {
+            let mut buf = Vec::new();
+            let _ = pipe.read_to_end(&mut buf);
+            buf
+        });
+        let deadline = spec.timeout.map(|t| Instant::now() + t);
+        let status = loop {
+            match child.try_wait() {
+                Ok(Some(status)) => break status,
+                Ok(None) => {}
+                Err(e) => {
+                    return Err(ContainerRunError::Failed(format!(
+                        "cannot wait for {}: {e}",
+                        self.program
+                    )))
+                }
+            }
+            if deadline.is_some_and(|d| Instant::now() >= d) {
+                self.kill(&spec.name);
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(ContainerRunError::TimedOut(
+                    spec.timeout.expect("deadline implies timeout"),
+                ));
+            }
+            std::thread::sleep(WAIT_POLL);
+        };
+        if status.success() {
+            return Ok(());
+        }
+        let stderr = stderr.join().unwrap_or_default();
+        Err(ContainerRunError::Failed(format!(
+            "{} exited with {}: {}",
+            self.program,
+            status,
+            String::from_utf8_lossy(&stderr).trim()
+        )))
+    }
+}
+
+/// Run `job`'s VM in this process. What `exec-step` does inside the
+/// container.
+pub fn run_job(job: StepJob) -> StepJobResult {
+    let ledger = BudgetLedger::new(job.budget);
+    let outcome = match run_step_vm(job.module, job.policy, job.inputs, job.live, &ledger) {
+        Ok((value, event_log)) => StepJobOutcome::Completed {
+            value,
+            event_log: Box::new(event_log),
+        },
+        Err((error, class)) => StepJobOutcome::Failed {
+            error,
+            class: class.to_string(),
+        },
+    };
+    StepJobResult {
+        outcome,
+        usage: ledger.run_usage(),
+    }
+}
+
+/// Read a [`StepJob`] from `job_path`, run it and write the
+/// [`StepJobResult`] to `result_path`.
+pub fn exec_job_file(job_path: &Path, result_path: &Path) -> Result<(), String> {
+    let text = std::fs::read_to_string(job_path)
+        .map_err(|e| format!("cannot read {}: {e}", job_path.display()))?;
+    let job: StepJob = serde_json::from_str(&text)
+        .map_err(|e| format!("invalid step job {}: {e}", job_path.display()))?;
+    let result = run_job(job);
+    let json = serde_json::to_string(&result).map_err(|e| format!("result serialize: {e}"))?;
+    std::fs::write(result_path, json)
+        .map_err(|e| format!("cannot write {}: {e}", result_path.display()))
+}
+
+/// Run a compiled step through `backend` and debit the wall-clock time
+/// it took from `ledger`. A container still running after `timeout_ms`
+/// is killed and the step fails with `wall_time_exceeded`. Errors carry
+/// the message and [`error_class`] of the failure, as for an in-process
+/// run.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn run_in_container(
+    backend: &dyn ContainerBackend,
+    step_id: &str,
+    module: Module,
+    policy: Policy,
+    inputs: BTreeMap<String, Value>,
+    live: bool,
+    timeout_ms: Option<u64>,
+    workflow_dir: &Path,
+    ledger: &BudgetLedger,
+) -> Result<(Value, EventLog), (String, &'static str)> {
+    let container_error = |msg: String| (format!("container: {msg}"), error_class::CONTAINER_ERROR);
+    let scratch = tempfile::Builder::new()
+        .prefix("boruna-step-")
+        .tempdir()
+        .map_err(|e| container_error(format!("cannot create job directory: {e}")))?;
+    let mut spec = ContainerSpec::for_step(step_id, &policy, workflow_dir, scratch.path());
+    spec.timeout = timeout_ms.map(Duration::from_millis);
+    let job = StepJob {
+        step_id: step_id.to_string(),
+        module,
+        policy,
+        inputs,
+        live,
+        budget: Budget {
+            max_steps: ledger.remaining(Resource::Steps),
+            max_calls: ledger.remaining(Resource::CapabilityCalls),
+            max_bytes: ledger.remaining(Resource::Bytes),
+            max_tokens: ledger.remaining(Resource::LlmTokens),
+            max_container_ms: None,
+        },
+    };
+    let json =
+        serde_json::to_string(&job).map_err(|e| container_error(format!("job serialize: {e}")))?;
+    std::fs::write(scratch.path().join(JOB_FILE), json)
+        .map_err(|e| container_error(format!("cannot write job: {e}")))?;
+
+    let started = Instant::now();
+    let ran = backend.run(&spec);
+    let elapsed_ms = u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX);
+    ledger
+        .debit(Resource::ContainerMs, elapsed_ms)
+        .map_err(|e| {
+            (
+                format!("runtime error: {e}"),
+                error_class::RUN_BUDGET_EXHAUSTED,
+            )
+        })?;
+    ran.map_err(|e| match e {
+        ContainerRunError::TimedOut(_) => {
+            (format!("container: {e}"), error_class::WALL_TIME_EXCEEDED)
+        }
+        ContainerRunError::Failed(msg) => container_error(msg),
+    })?;
+
+    let text = std::fs::read_to_string(scratch.path().join(RESULT_FILE))
+        .map_err(|e| container_error(format!("no step result: {e}")))?;
+    let result: StepJobResult = serde_json::from_str(&text)
+        .map_err(|e| container_error(format!("invalid step result: {e}")))?;
+    match result.outcome {
+        StepJobOutcome::Completed { value, event_log } => Ok((value, *event_log)),
+        StepJobOutcome::Failed { error, class } => Err((error, known_class(&class))),
+    }
+}
+
+/// The [`error_class`] constant named `class`; classes a VM run can end
+/// with, falling back to `runtime_error` for anything else.
+fn known_class(class: &str) -> &'static str {
+    [
+        error_class::WALL_TIME_EXCEEDED,
+        error_class::STEP_LIMIT_EXCEEDED,
+        error_class::CAPABILITY_DENIED,
+        error_class::CAPABILITY_BUDGET_EXCEEDED,
+        error_class::CONTRACT_VIOLATION,
+        error_class::TRANSIENT_NETWORK,
+        error_class::RUN_BUDGET_EXHAUSTED,
+    ]
+    .into_iter()
+    .find(|known| *known == class)
+    .unwrap_or(error_class::RUNTIME_ERROR)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use boruna_vm::capability_gateway::PolicyRule;
+
+    fn spec(policy: &Policy) -> ContainerSpec {
+        ContainerSpec::for_step("s", policy, Path::new("/wf"), Path::new("/tmp/job"))
+    }
+
+    #[test]
+    fn test_deny_all_gets_no_network_and_only_the_job_dir() {
+        let spec = spec(&Policy::deny_all());
+        assert!(!spec.network);
+        assert_eq!(spec.mounts.len(), 1);
+        assert_eq!(spec.mounts[0].container, CONTAINER_JOB_DIR);
+        assert_eq!(spec.workdir, CONTAINER_JOB_DIR);
+
+        let args = CliBackend::new("podman", "boruna:latest").args(&spec);
+        assert!(args.contains(&"--network=none".to_string()));
+        assert!(args.contains(&"--read-only".to_string()));
+        assert!(args.contains(&"--mount=type=bind,src=/tmp/job,dst=/boruna/job".to_string()));
+        let image = args.iter().position(|a| a == "boruna:latest").unwrap();
+        assert_eq!(args[image + 1..], spec.command[..]);
+    }
+
+    #[test]
+    fn test_policy_maps_to_mounts_and_network() {
+        let mut policy = Policy::deny_all();
+        policy.rules.insert(
+            "fs.read".into(),
+            PolicyRule {
+                allow: true,
+                budget: 0,
+                prompt: false,
+            },
+        );
+        let read = spec(&policy);
+        assert!(!read.network);
+        assert_eq!(
+            read.mounts[1],
+            Mount {
+                host: PathBuf::from("/wf"),
+                container: CONTAINER_WORKFLOW_DIR.into(),
+                read_only: true,
+            }
+        );
+        assert_eq!(read.workdir, CONTAINER_WORKFLOW_DIR);
+
+        let all = spec(&Policy::allow_all());
+        assert!(all.network);
+        assert!(!all.mounts[1].read_only);
+        let args = CliBackend::new("docker", "img").args(&all);
+        assert!(!args.iter().any(|a| a.starts_with("--network")));
+        assert!(args.contains(&"--mount=type=bind,src=/wf,dst=/boruna/workflow".to_string()));
+        let read_args = CliBackend::new("docker", "img").args(&read);
+        assert!(read_args
+            .contains(&"--mount=type=bind,src=/wf,dst=/boruna/workflow,readonly".to_string()));
+    }
+
+    #[test]
+    fn test_mount_paths_with_separators_are_quoted() {
+        let spec = ContainerSpec::for_step(
+            "s",
+            &Policy::allow_all(),
+            Path::new("/data/a:b,c\"d"),
+            Path::new("/tmp/job"),
+        );
+        let args = CliBackend::new("docker", "img").args(&spec);
+        assert!(
+            args.contains(
+                &"--mount=type=bind,\"src=/data/a:b,c\"\"d\",dst=/boruna/workflow".to_string()
+            ),
+            "{args:?}"
+        );
+    }
+
+    /// Writes a result claiming no usage at all, after `delay`.
+    #[derive(Debug)]
+    struct UnderReporting {
+        delay: std::time::Duration,
+    }
+
+    impl ContainerBackend for UnderReporting {
+        fn run(&self, spec: &ContainerSpec) -> Result<(), ContainerRunError> {
+            std::thread::sleep(self.delay);
+            let result = StepJobResult {
+                outcome: StepJobOutcome::Completed {
+                    value: Value::Int(1),
+                    event_log: Box::default(),
+                },
+                usage: Usage::default(),
+            };
+            let job_dir = &spec.mounts[0].host;
+            std::fs::write(
+                job_dir.join(RESULT_FILE),
+                serde_json::to_string(&result).unwrap(),
+            )
+            .map_err(|e| ContainerRunError::Failed(e.to_string()))
+        }
+    }
+
+    fn run_under(ledger: &BudgetLedger) -> Result<(Value, EventLog), (String, &'static str)> {
+        let backend = UnderReporting {
+            delay: std::time::Duration::from_millis(20),
+        };
+        run_in_container(
+            &backend,
+            "s",
+            Module::new("s"),
+            Policy::deny_all(),
+            BTreeMap::new(),
+            false,
+            None,
+            Path::new("/wf"),
+            ledger,
+        )
+    }
+
+    #[test]
+    fn test_host_measured_time_is_debited_not_the_reported_usage() {
+        let ledger = BudgetLedger::default();
+        let (value, _) = run_under(&ledger).unwrap();
+        assert_eq!(value, Value::Int(1));
+        assert!(ledger.run_usage().container_ms >= 20);
+
+        let tight = BudgetLedger::new(Budget {
+            max_container_ms: Some(5),
+            ..Budget::default()
+        });
+        let (error, class) = run_under(&tight).unwrap_err();
+        assert_eq!(class, error_class::RUN_BUDGET_EXHAUSTED);
+        assert!(error.contains("container_ms"), "{error}");
+    }
+
+    /// A runtime whose `run` hangs and whose `kill` and `rm` are logged.
+    #[cfg(unix)]
+    fn hanging_runtime(dir: &Path) -> CliBackend {
+        use std::os::unix::fs::PermissionsExt;
+        let script = dir.join("runtime.sh");
+        let body = format!(
+            "#!/bin/sh\n\
+             case \"$1\" in\n\
+               run) exec sleep 30;;\n\
+               *) echo \"$@\" >> '{log}';;\n\
+             esac\n",
+            log = dir.join("log").display(),
+        );
+        std::fs::write(&script, body).unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        CliBackend::new(script.display().to_string(), "img")
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_hung_container_is_killed_at_the_step_timeout() {
+        let dir = tempfile::tempdir().unwrap();
+        let backend = hanging_runtime(dir.path());
+        let ledger = BudgetLedger::default();
+        let started = Instant::now();
+        let (error, class) = run_in_container(
+            &backend,
+            "s",
+            Module::new("s"),
+            Policy::deny_all(),
+            BTreeMap::new(),
+            false,
+            Some(100),
+            Path::new("/wf"),
+            &ledger,
+        )
+        .unwrap_err();
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert_eq!(class, error_class::WALL_TIME_EXCEEDED);
+        assert!(error.contains("100ms wall-time limit"), "{error}");
+        assert!(ledger.run_usage().container_ms >= 100);
+
+        let log = std::fs::read_to_string(dir.path().join("log")).unwrap();
+        let mut lines = log.lines();
+        let kill = lines.next().unwrap();
+        assert!(kill.starts_with("kill boruna-step-"), "{log}");
+        assert_eq!(lines.next(), Some(format!("rm -f {}", &kill[5..]).as_str()));
+    }
+
+    #[test]
+    fn test_unknown_class_is_a_runtime_error() {
+        assert_eq!(
+            known_class(error_class::CAPABILITY_DENIED),
+            error_class::CAPABILITY_DENIED
+        );
+        assert_eq!(known_class("bogus"), error_class::RUNTIME_ERROR);
+    }
+}
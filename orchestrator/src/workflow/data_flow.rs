@@ -1,8 +1,12 @@
 use boruna_bytecode::Value;
-use sha2::{Digest, Sha256};
+use boruna_vm::capability_gateway::Classification;
 use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
+use crate::workflow::definition::WorkflowDef;
+use crate::workflow::params::PARAMS_PREFIX;
+use crate::workflow::validator::WorkflowValidator;
+
 /// Flush a file's data blocks to stable storage with the strongest
 /// guarantee the platform offers.
 ///
@@ -54,6 +58,37 @@ pub struct DataStore {
     base_dir: PathBuf,
     /// In-memory cache of step outputs (step_id -> output_name -> value).
     outputs: BTreeMap<String, BTreeMap<String, Value>>,
+    /// Effective classification of each step's outputs. Steps not listed
+    /// are public.
+    classifications: BTreeMap<String, Classification>,
+    /// The run's bound workflow params, read by `params.<name>` inputs.
+    params: BTreeMap<String, Value>,
+}
+
+/// Effective classification of every step's output: the step's declared
+/// `classification` raised to the highest label among the steps its
+/// inputs read from. Labels only move up, so secret data stays secret
+/// however many steps it passes through. Declared labels alone are used
+/// if the graph has a cycle (the validator reports that separately).
+pub fn propagate_classifications(def: &WorkflowDef) -> BTreeMap<String, Classification> {
+    let order = WorkflowValidator::topological_order(def)
+        .unwrap_or_else(|_| def.steps.keys().cloned().collect());
+    let mut labels: BTreeMap<String, Classification> = BTreeMap::new();
+    for step_id in order {
+        let Some(step) = def.steps.get(&step_id) else {
+            continue;
+        };
+        let inherited = step
+            .inputs
+            .values()
+            .filter_map(|r| r.split_once('.'))
+            .filter_map(|(upstream, _)| labels.get(upstream).copied())
+            .max()
+            .unwrap_or_default();
+        let label = step.classification.unwrap_or_default().max(inherited);
+        labels.insert(step_id, label);
+    }
+    labels
 }
 
 impl DataStore {
@@ -62,9 +97,42 @@ impl DataStore {
         Ok(DataStore {
             base_dir: base_dir.to_path_buf(),
             outputs: BTreeMap::new(),
+            classifications: BTreeMap::new(),
+            params: BTreeMap::new(),
         })
     }
 
+    /// Attach the classification labels of `def`'s steps (see
+    /// [`propagate_classifications`]).
+    pub fn with_classifications(mut self, def: &WorkflowDef) -> Self {
+        self.classifications = propagate_classifications(def);
+        self
+    }
+
+    /// Attach the run's bound params (see [`crate::workflow::params::to_values`]).
+    pub fn with_params(mut self, params: BTreeMap<String, Value>) -> Self {
+        self.params = params;
+        self
+    }
+
+    /// Classification of a step's outputs.
+    pub fn classification(&self, step_id: &str) -> Classification {
+        self.classifications
+            .get(step_id)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Highest classification among the outputs `inputs` refer to.
+    pub fn input_classification(&self, inputs: &BTreeMap<String, String>) -> Classification {
+        inputs
+            .values()
+            .filter_map(|r| r.split_once('.'))
+            .map(|(step_id, _)| self.classification(step_id))
+            .max()
+            .unwrap_or_default()
+    }
+
     /// Store a step's output value.
     ///
     /// **Atomicity guarantee.** The JSON is serialized into a temp
@@ -160,8 +228,14 @@ impl DataStore {
         Ok(())
     }
 
-    /// Resolve an input reference ("step_id.output_name") to a value.
+    /// Resolve an input reference ("step_id.output_name", or
+    /// "params.name" for a workflow param) to a value.
     pub fn resolve_input(&self, input_ref: &str) -> Result<Value, String> {
+        if let Some(name) = input_ref.strip_prefix(PARAMS_PREFIX) {
+            if let Some(value) = self.params.get(name) {
+                return Ok(value.clone());
+            }
+        }
         let (step_id, output_name) = input_ref
             .split_once('.')
             .ok_or_else(|| format!("invalid input ref: {input_ref}"))?;
@@ -189,9 +263,7 @@ impl DataStore {
     /// Compute SHA-256 hash of a value's JSON representation.
     pub fn hash_value(value: &Value) -> String {
         let json = serde_json::to_string(value).unwrap_or_default();
-        let mut hasher = Sha256::new();
-        hasher.update(json.as_bytes());
-        format!("{:x}", hasher.finalize())
+        boruna_hash::sha256_hex(json)
     }
 
     /// Get the output directory path.
@@ -247,6 +319,36 @@ mod tests {
         assert_eq!(resolved["second"], Value::String("test".into()));
     }
 
+    #[test]
+    fn test_classifications_propagate_downstream() {
+        let def = WorkflowDef::from_json(
+            r#"{
+                "schema_version": 1, "name": "labels", "version": "1.0.0",
+                "steps": {
+                    "creds": {"kind": "source", "source": "a.ax", "classification": "secret"},
+                    "notes": {"kind": "source", "source": "b.ax", "classification": "internal"},
+                    "plain": {"kind": "source", "source": "c.ax"},
+                    "merge": {"kind": "source", "source": "d.ax",
+                              "inputs": {"c": "creds.result", "n": "notes.result"}}
+                },
+                "edges": [["creds", "merge"], ["notes", "merge"]]
+            }"#,
+        )
+        .unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let store = DataStore::new(dir.path())
+            .unwrap()
+            .with_classifications(&def);
+        assert_eq!(store.classification("merge"), Classification::Secret);
+        assert_eq!(store.classification("notes"), Classification::Internal);
+        assert_eq!(store.classification("plain"), Classification::Public);
+        let inputs = BTreeMap::from([("n".to_string(), "notes.result".to_string())]);
+        assert_eq!(
+            store.input_classification(&inputs),
+            Classification::Internal
+        );
+    }
+
     #[test]
     fn test_hash_deterministic() {
         let v1 = Value::String("test".into());
@@ -277,9 +379,7 @@ mod tests {
 
         let on_disk_bytes = std::fs::read(dir.path().join("outputs/step/result.json")).unwrap();
         // Hash the on-disk bytes directly.
-        let mut hasher = Sha256::new();
-        hasher.update(&on_disk_bytes);
-        let on_disk_hash = format!("{:x}", hasher.finalize());
+        let on_disk_hash = boruna_hash::sha256_hex(&on_disk_bytes);
 
         // Compare to what hash_value computes.
         let api_hash = DataStore::hash_value(&value);
@@ -1,6 +1,12 @@
+use boruna_budget::Budget;
+use boruna_vm::capability_gateway::Classification;
+use boruna_vm::replay::EventLog;
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 
+use crate::workflow::params::ParamDef;
+use crate::workflow::schema::ValueSchema;
+
 /// Highest workflow-DAG schema major version this build understands.
 ///
 /// See `docs/spec/workflow-dag-1.0.md` for the formal contract.
@@ -65,6 +71,28 @@ impl std::fmt::Display for WorkflowParseError {
 
 impl std::error::Error for WorkflowParseError {}
 
+impl boruna_errors::BorunaError for WorkflowParseError {
+    fn error_code(&self) -> &'static boruna_errors::ErrorCode {
+        match self {
+            Self::MissingSchemaVersion => &boruna_errors::WORKFLOW_MISSING_SCHEMA_VERSION,
+            Self::UnsupportedSchemaVersion { .. } => {
+                &boruna_errors::WORKFLOW_UNSUPPORTED_SCHEMA_VERSION
+            }
+            Self::InvalidJson(_) => &boruna_errors::WORKFLOW_INVALID_JSON,
+        }
+    }
+
+    fn error_data(&self) -> serde_json::Value {
+        match self {
+            Self::UnsupportedSchemaVersion {
+                found,
+                supported_max,
+            } => serde_json::json!({ "found": found, "supported_max": supported_max }),
+            _ => serde_json::Value::Null,
+        }
+    }
+}
+
 /// A workflow definition — a DAG of steps with typed data flow.
 ///
 /// Persistent shape: `workflow.json` on disk. Spec frozen at sprint
@@ -85,6 +113,17 @@ pub struct WorkflowDef {
     pub description: String,
     pub steps: BTreeMap<String, StepDef>,
     pub edges: Vec<(String, String)>,
+    /// Resources the whole run may consume, shared by every step; a
+    /// step's own [`StepBudget`] further limits what it alone may use.
+    /// Omitted from JSON when absent, so `workflow_hash` of
+    /// budget-less definitions is unchanged.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget: Option<Budget>,
+    /// Typed run parameters, read by steps as `params.<name>` inputs
+    /// and bound per run (see [`crate::workflow::params`]). Omitted
+    /// from JSON when empty.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub params: BTreeMap<String, ParamDef>,
 }
 
 impl WorkflowDef {
@@ -153,6 +192,10 @@ impl<'de> Deserialize<'de> for WorkflowDef {
             description: String,
             steps: BTreeMap<String, StepDef>,
             edges: Vec<(String, String)>,
+            #[serde(default)]
+            budget: Option<Budget>,
+            #[serde(default)]
+            params: BTreeMap<String, ParamDef>,
         }
 
         let raw = Raw::deserialize(deserializer)?;
@@ -175,6 +218,8 @@ impl<'de> Deserialize<'de> for WorkflowDef {
             description: raw.description,
             steps: raw.steps,
             edges: raw.edges,
+            budget: raw.budget,
+            params: raw.params,
         })
     }
 }
@@ -205,6 +250,51 @@ pub struct StepDef {
     /// version declaration for a capability default to "1.0".
     #[serde(default)]
     pub required_capability_versions: BTreeMap<String, String>,
+    /// Declared shape of each input, keyed by input name. Checked against
+    /// the upstream step's `output_schema` at validation time and against
+    /// the resolved value before the step runs. Omitted from JSON when
+    /// empty, so `workflow_hash` of schema-less definitions is unchanged.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub input_schema: BTreeMap<String, ValueSchema>,
+    /// Declared shape of the step's `result`, checked after a source step
+    /// runs. Omitted from JSON when absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_schema: Option<ValueSchema>,
+    /// Data classification of the step's output (for an external trigger,
+    /// of the payload it receives). The effective label is the highest of
+    /// this and the labels of the step's inputs; see
+    /// [`propagate_classifications`](crate::workflow::propagate_classifications).
+    /// Omitted from JSON when absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub classification: Option<Classification>,
+    /// Where a source step's VM runs; see [`StepIsolation`]. Omitted from
+    /// JSON when `process`, so `workflow_hash` of existing definitions is
+    /// unchanged.
+    #[serde(default, skip_serializing_if = "StepIsolation::is_process")]
+    pub isolation: StepIsolation,
+}
+
+/// Where a source step executes.
+///
+/// - `Process` — in the runner's own process (the default).
+/// - `Container` — in a container started through the run's
+///   [`ContainerBackend`](crate::workflow::container::ContainerBackend),
+///   with the step's policy translated to mounts and network settings.
+///   The step is still compiled and checked on the host, and its output
+///   and event log come back to the runner, so output hashes and evidence
+///   match an in-process run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepIsolation {
+    #[default]
+    Process,
+    Container,
+}
+
+impl StepIsolation {
+    pub fn is_process(&self) -> bool {
+        *self == StepIsolation::Process
+    }
 }
 
 /// The kind of step.
@@ -272,12 +362,36 @@ pub struct RetryPolicy {
 }
 
 /// Budget limits for a step.
+///
+/// `max_calls` caps each capability the step declares, as a policy
+/// rule budget. The other limits are the step's share of the run's
+/// [`WorkflowDef::budget`], debited alongside it.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StepBudget {
     #[serde(default)]
     pub max_tokens: Option<u64>,
     #[serde(default)]
     pub max_calls: Option<u64>,
+    /// VM instructions the step may execute. Omitted from JSON when absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_steps: Option<u64>,
+    /// Capability payload bytes the step may move. Omitted from JSON
+    /// when absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_bytes: Option<u64>,
+}
+
+impl StepBudget {
+    /// The step's sub-budget of the run ledger.
+    pub fn ledger_budget(&self) -> Budget {
+        Budget {
+            max_steps: self.max_steps,
+            max_calls: None,
+            max_bytes: self.max_bytes,
+            max_tokens: self.max_tokens,
+            max_container_ms: None,
+        }
+    }
 }
 
 /// Result of running a single step.
@@ -298,6 +412,14 @@ pub struct StepResult {
     /// `step_checkpoints.attempt_count` in the persistent store.
     #[serde(default = "default_attempt_count")]
     pub attempt_count: u32,
+    /// Capability events of the attempt that produced the output, so an
+    /// evidence bundle can carry them for [`WorkflowRunner::verify`].
+    /// `None` for gates, triggers, failures, and steps restored from a
+    /// checkpoint. Operational only; omitted from JSON when absent.
+    ///
+    /// [`WorkflowRunner::verify`]: crate::workflow::WorkflowRunner::verify
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event_log: Option<EventLog>,
 }
 
 fn default_attempt_count() -> u32 {
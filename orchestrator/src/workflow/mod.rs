@@ -1,9 +1,18 @@
+pub mod container;
 pub mod data_flow;
 pub mod definition;
+pub mod params;
+pub mod profile;
+pub mod replay_verify;
 pub mod runner;
+pub mod schema;
 pub mod validator;
 
 pub use data_flow::*;
 pub use definition::*;
+pub use params::*;
+pub use profile::*;
+pub use replay_verify::*;
 pub use runner::*;
+pub use schema::*;
 pub use validator::*;
@@ -0,0 +1,304 @@
+//! Workflow parameters.
+//!
+//! A workflow declares typed `params`, optionally with defaults, instead
+//! of hard-coding per-environment values in `workflow.json`:
+//!
+//! ```json
+//! "params": {
+//!   "region": {"type": "string", "default": "eu-west-1"},
+//!   "limit":  {"type": "int", "description": "Max records per batch"}
+//! }
+//! ```
+//!
+//! Steps read them like any upstream output, as `params.<name>` inputs.
+//! Values are bound per run (`boruna workflow run --param limit=50` or
+//! `--params-file`); [`bind`] rejects unknown names, fills in defaults,
+//! requires every param without one, and checks each value against its
+//! declared type. The bound set is canonical JSON, so its
+//! [`params_hash`] is the run's `inputs_hash` — two runs of the same
+//! definition with different params get different `run_id`s — and the
+//! evidence bundle records it as `params.json`.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use boruna_bytecode::Value;
+use serde::{Deserialize, Serialize};
+
+use crate::workflow::definition::WorkflowDef;
+use crate::workflow::schema::ValueSchema;
+
+/// Prefix of a step input that reads a param.
+pub const PARAMS_PREFIX: &str = "params.";
+
+/// One declared workflow parameter.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParamDef {
+    /// Declared type; the `type` tag and its fields sit inline, as in a
+    /// step's `input_schema`.
+    #[serde(flatten)]
+    pub schema: ValueSchema,
+    /// Value used when the run binds none. A param without a default is
+    /// required.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+}
+
+/// Param values bound for one run, by name, as canonical JSON.
+pub type ParamValues = BTreeMap<String, serde_json::Value>;
+
+/// Why a set of param values cannot be bound.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParamError {
+    /// A value was given for a param the workflow does not declare.
+    Unknown(String),
+    /// A param without a default was given no value.
+    Missing(String),
+    /// A value, or a field inside it, does not match the declared type.
+    TypeMismatch {
+        field: String,
+        expected: String,
+        actual: String,
+    },
+    /// A `--param` argument that is not `name=value`, or a value that is
+    /// not valid JSON for a non-string param.
+    Malformed(String),
+}
+
+impl fmt::Display for ParamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParamError::Unknown(name) => write!(f, "unknown param '{name}'"),
+            ParamError::Missing(name) => {
+                write!(f, "param '{name}' has no default and was not bound")
+            }
+            ParamError::TypeMismatch {
+                field,
+                expected,
+                actual,
+            } => write!(f, "param {field}: expected {expected}, got {actual}"),
+            ParamError::Malformed(msg) => write!(f, "{msg}"),
+        }
+    }
+}
+
+impl std::error::Error for ParamError {}
+
+/// Parse a `name=value` command-line binding. A `string` param takes
+/// the text verbatim; any other type parses it as JSON (`50`, `true`,
+/// `["a","b"]`).
+pub fn parse_binding(
+    def: &WorkflowDef,
+    binding: &str,
+) -> Result<(String, serde_json::Value), ParamError> {
+    let (name, text) = binding
+        .split_once('=')
+        .ok_or_else(|| ParamError::Malformed(format!("expected name=value, got '{binding}'")))?;
+    let param = def
+        .params
+        .get(name)
+        .ok_or_else(|| ParamError::Unknown(name.to_string()))?;
+    let value = match param.schema {
+        ValueSchema::String => serde_json::Value::String(text.to_string()),
+        _ => serde_json::from_str(text).map_err(|e| {
+            ParamError::Malformed(format!("param '{name}': '{text}' is not valid JSON: {e}"))
+        })?,
+    };
+    Ok((name.to_string(), value))
+}
+
+/// Bind `provided` to `def`'s declared params: every declared param gets
+/// its provided value or its default, type-checked. Integers given for
+/// `float` params are widened, so `--param ratio=1` binds `1.0`.
+pub fn bind(def: &WorkflowDef, provided: &ParamValues) -> Result<ParamValues, ParamError> {
+    if let Some(name) = provided.keys().find(|n| !def.params.contains_key(*n)) {
+        return Err(ParamError::Unknown(name.clone()));
+    }
+    let mut bound = ParamValues::new();
+    for (name, param) in &def.params {
+        let value = provided
+            .get(name)
+            .or(param.default.as_ref())
+            .ok_or_else(|| ParamError::Missing(name.clone()))?;
+        let value = widen(&param.schema, value.clone());
+        check(name, &param.schema, &value)?;
+        bound.insert(name.clone(), value);
+    }
+    Ok(bound)
+}
+
+/// Check `value` against a param's declared type.
+pub fn check(
+    name: &str,
+    schema: &ValueSchema,
+    value: &serde_json::Value,
+) -> Result<(), ParamError> {
+    schema
+        .check(&to_value(schema, value), &format!("'{name}'"))
+        .map_err(|(field, expected, actual)| ParamError::TypeMismatch {
+            field,
+            expected,
+            actual,
+        })
+}
+
+/// Hex SHA-256 of the bound set's compact JSON. An empty
+/// set hashes `{}`, the inputs hash of a run without params.
+pub fn params_hash(bound: &ParamValues) -> String {
+    boruna_hash::sha256_hex(serde_json::to_string(bound).unwrap_or_default())
+}
+
+/// The VM values steps receive for `bound`.
+pub fn to_values(def: &WorkflowDef, bound: &ParamValues) -> BTreeMap<String, Value> {
+    bound
+        .iter()
+        .map(|(name, json)| {
+            let value = match def.params.get(name) {
+                Some(param) => to_value(&param.schema, json),
+                None => boruna_vm::json::from_json(json.clone()),
+            };
+            (name.clone(), value)
+        })
+        .collect()
+}
+
+/// Convert JSON to the value a step sees, guided by the declared type:
+/// a non-null value for an `option` param is `Some`, and nested shapes
+/// are converted the same way.
+fn to_value(schema: &ValueSchema, json: &serde_json::Value) -> Value {
+    match (schema, json) {
+        (ValueSchema::Option { some }, v) if !v.is_null() => Value::Some(Box::new(match some {
+            Some(s) => to_value(s, v),
+            None => boruna_vm::json::from_json(v.clone()),
+        })),
+        (ValueSchema::List { items: Some(items) }, serde_json::Value::Array(elems)) => {
+            Value::List(elems.iter().map(|e| to_value(items, e)).collect())
+        }
+        (ValueSchema::Map { properties, values }, serde_json::Value::Object(fields)) => Value::Map(
+            fields
+                .iter()
+                .map(|(k, v)| {
+                    let value = match properties.get(k).or(values.as_deref()) {
+                        Some(s) => to_value(s, v),
+                        None => boruna_vm::json::from_json(v.clone()),
+                    };
+                    (k.clone(), value)
+                })
+                .collect(),
+        ),
+        _ => boruna_vm::json::from_json(json.clone()),
+    }
+}
+
+/// Widen integers given where a float is declared.
+fn widen(schema: &ValueSchema, json: serde_json::Value) -> serde_json::Value {
+    match (schema, json) {
+        (ValueSchema::Float, serde_json::Value::Number(n)) if !n.is_f64() => n
+            .as_f64()
+            .and_then(serde_json::Number::from_f64)
+            .map_or(serde_json::Value::Number(n), serde_json::Value::Number),
+        (ValueSchema::Option { some: Some(s) }, json) => widen(s, json),
+        (ValueSchema::List { items: Some(items) }, serde_json::Value::Array(elems)) => {
+            serde_json::Value::Array(elems.into_iter().map(|e| widen(items, e)).collect())
+        }
+        (ValueSchema::Map { properties, values }, serde_json::Value::Object(fields)) => {
+            serde_json::Value::Object(
+                fields
+                    .into_iter()
+                    .map(|(k, v)| {
+                        let v = match properties.get(&k).or(values.as_deref()) {
+                            Some(s) => widen(s, v),
+                            None => v,
+                        };
+                        (k, v)
+                    })
+                    .collect(),
+            )
+        }
+        (_, json) => json,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn def() -> WorkflowDef {
+        WorkflowDef::from_json(
+            r#"{
+                "schema_version": 1, "name": "wf", "version": "1", "steps": {}, "edges": [],
+                "params": {
+                    "region": {"type": "string", "default": "eu"},
+                    "limit": {"type": "int"},
+                    "ratio": {"type": "float", "default": 0.5},
+                    "tags": {"type": "list", "items": {"type": "string"}, "default": []}
+                }
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_bind_fills_defaults_and_requires_the_rest() {
+        let def = def();
+        assert_eq!(
+            bind(&def, &ParamValues::new()),
+            Err(ParamError::Missing("limit".into()))
+        );
+
+        let (name, value) = parse_binding(&def, "limit=50").unwrap();
+        let bound = bind(&def, &ParamValues::from([(name, value)])).unwrap();
+        assert_eq!(bound["limit"], 50);
+        assert_eq!(bound["region"], "eu");
+        assert_eq!(to_values(&def, &bound)["limit"], Value::Int(50));
+        assert_ne!(params_hash(&bound), params_hash(&ParamValues::new()));
+    }
+
+    #[test]
+    fn test_bind_rejects_unknown_names_and_wrong_types() {
+        let def = def();
+        let mut provided = ParamValues::from([("limit".to_string(), serde_json::json!(1))]);
+        provided.insert("colour".into(), serde_json::json!("red"));
+        assert_eq!(
+            bind(&def, &provided),
+            Err(ParamError::Unknown("colour".into()))
+        );
+
+        let provided = ParamValues::from([
+            ("limit".to_string(), serde_json::json!("many")),
+            ("tags".to_string(), serde_json::json!(["a", 2])),
+        ]);
+        let err = bind(&def, &provided).unwrap_err();
+        assert_eq!(err.to_string(), "param 'limit': expected Int, got String");
+    }
+
+    #[test]
+    fn test_string_bindings_are_verbatim_and_floats_widen() {
+        let def = def();
+        assert_eq!(
+            parse_binding(&def, "region=us-east-1").unwrap().1,
+            serde_json::json!("us-east-1")
+        );
+        assert!(matches!(
+            parse_binding(&def, "limit=lots"),
+            Err(ParamError::Malformed(_))
+        ));
+
+        let provided = ParamValues::from([
+            ("limit".to_string(), serde_json::json!(1)),
+            ("ratio".to_string(), serde_json::json!(2)),
+        ]);
+        let bound = bind(&def, &provided).unwrap();
+        assert_eq!(to_values(&def, &bound)["ratio"], Value::Float(2.0));
+    }
+
+    #[test]
+    fn test_empty_params_hash_matches_paramless_inputs_hash() {
+        assert_eq!(
+            params_hash(&ParamValues::new()),
+            boruna_hash::sha256_hex(b"{}")
+        );
+    }
+}
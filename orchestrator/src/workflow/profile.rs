@@ -0,0 +1,235 @@
+//! Environment profiles.
+//!
+//! One `workflow.json` serves every environment; what differs between
+//! dev, staging and prod lives in overlays next to it:
+//!
+//! ```text
+//! my-workflow/
+//!   workflow.json
+//!   profiles/
+//!     dev.json
+//!     prod.json
+//! ```
+//!
+//! ```json
+//! {
+//!   "policy": "policies/prod.json",
+//!   "live": true,
+//!   "params": {"api_base": "https://api.example.com"},
+//!   "budget": {"max_calls": 500},
+//!   "concurrency": 4
+//! }
+//! ```
+//!
+//! Every field is optional. `policy` is `allow-all`, `deny-all` or a
+//! policy file relative to the workflow directory; `live` selects real
+//! capability handlers over mocks; `params` binds the workflow's declared
+//! params (endpoints and other per-environment values), under any bound
+//! on the command line; `budget` replaces the workflow's run budget;
+//! `concurrency` sets the steps run per wave; `fingerprint_env` names
+//! environment variables whose value hashes the evidence bundle's
+//! environment fingerprint records. Unknown fields are
+//! rejected, so a typo cannot silently fall back to the defaults.
+//!
+//! `boruna workflow run --profile prod` selects `profiles/prod.json`. The
+//! profile's name and content hash are recorded in the run metadata, so
+//! `resume` re-applies it, and in the evidence bundle's environment
+//! fingerprint.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use boruna_budget::Budget;
+use serde::{Deserialize, Serialize};
+
+use crate::audit::ProfileFingerprint;
+use crate::workflow::definition::WorkflowDef;
+use crate::workflow::params::ParamValues;
+use crate::workflow::runner::RunOptions;
+
+/// Directory, relative to the workflow directory, holding the profiles.
+pub const PROFILES_DIR: &str = "profiles";
+
+/// An environment overlay for a workflow.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub policy: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub live: Option<bool>,
+    #[serde(default, skip_serializing_if = "ParamValues::is_empty")]
+    pub params: ParamValues,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget: Option<Budget>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub concurrency: Option<usize>,
+    /// Environment variables whose value hashes the evidence bundle's
+    /// fingerprint records, on top of any named on the command line.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fingerprint_env: Vec<String>,
+}
+
+/// A profile read from disk, with what identifies it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadedProfile {
+    pub name: String,
+    /// SHA-256 of the profile file's bytes.
+    pub hash: String,
+    pub profile: Profile,
+}
+
+/// Why a profile cannot be loaded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProfileError {
+    /// The name is not a plain file stem (`[A-Za-z0-9_-]+`).
+    InvalidName(String),
+    /// `profiles/<name>.json` does not exist or cannot be read.
+    NotFound { name: String, path: PathBuf },
+    /// The file is not a valid profile.
+    Invalid { name: String, message: String },
+}
+
+impl fmt::Display for ProfileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProfileError::InvalidName(name) => write!(
+                f,
+                "invalid profile name '{name}' (letters, digits, '-' and '_' only)"
+            ),
+            ProfileError::NotFound { name, path } => {
+                write!(f, "profile '{name}' not found at {}", path.display())
+            }
+            ProfileError::Invalid { name, message } => {
+                write!(f, "invalid profile '{name}': {message}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ProfileError {}
+
+impl Profile {
+    /// Read `profiles/<name>.json` under `workflow_dir`.
+    pub fn load(workflow_dir: &Path, name: &str) -> Result<LoadedProfile, ProfileError> {
+        let valid = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            return Err(ProfileError::InvalidName(name.to_string()));
+        }
+        let path = workflow_dir.join(PROFILES_DIR).join(format!("{name}.json"));
+        let bytes = std::fs::read(&path).map_err(|_| ProfileError::NotFound {
+            name: name.to_string(),
+            path: path.clone(),
+        })?;
+        let profile = serde_json::from_slice(&bytes).map_err(|e| ProfileError::Invalid {
+            name: name.to_string(),
+            message: e.to_string(),
+        })?;
+        Ok(LoadedProfile {
+            name: name.to_string(),
+            hash: boruna_hash::sha256_hex(&bytes),
+            profile,
+        })
+    }
+
+    /// Apply the parts of the profile that change the definition. The
+    /// overlaid budget is part of `workflow_hash`, so `resume` calls this
+    /// again before checking the hash.
+    pub fn apply_to_def(&self, def: &mut WorkflowDef) {
+        if let Some(budget) = self.budget {
+            def.budget = Some(budget);
+        }
+    }
+
+    /// Apply the run options the profile sets. Params already in
+    /// `options` were bound on the command line and win. The policy is
+    /// left to the caller, which resolves its path.
+    pub fn apply_to_options(&self, options: &mut RunOptions) {
+        if let Some(live) = self.live {
+            options.live = live;
+        }
+        if let Some(concurrency) = self.concurrency {
+            options.concurrency = concurrency;
+        }
+        for (name, value) in &self.params {
+            options
+                .params
+                .entry(name.clone())
+                .or_insert_with(|| value.clone());
+        }
+    }
+}
+
+impl LoadedProfile {
+    /// Apply the profile to a run and record it in `options`.
+    pub fn apply(&self, def: &mut WorkflowDef, options: &mut RunOptions) {
+        self.profile.apply_to_def(def);
+        self.profile.apply_to_options(options);
+        options.profile = Some(self.fingerprint());
+    }
+
+    /// What the evidence bundle and run metadata record.
+    pub fn fingerprint(&self) -> ProfileFingerprint {
+        ProfileFingerprint {
+            name: self.name.clone(),
+            hash: self.hash.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, name: &str, json: &str) {
+        std::fs::create_dir_all(dir.join(PROFILES_DIR)).unwrap();
+        std::fs::write(dir.join(PROFILES_DIR).join(format!("{name}.json")), json).unwrap();
+    }
+
+    #[test]
+    fn test_load_and_apply() {
+        let dir = tempfile::tempdir().unwrap();
+        write(
+            dir.path(),
+            "prod",
+            r#"{"live": true, "concurrency": 4, "budget": {"max_calls": 9},
+                "params": {"region": "us", "limit": 5}}"#,
+        );
+        let loaded = Profile::load(dir.path(), "prod").unwrap();
+        let mut def = WorkflowDef::from_json(
+            r#"{"schema_version": 1, "name": "wf", "version": "1", "steps": {}, "edges": []}"#,
+        )
+        .unwrap();
+        let mut options = RunOptions {
+            params: ParamValues::from([("limit".to_string(), serde_json::json!(50))]),
+            ..RunOptions::default()
+        };
+        loaded.apply(&mut def, &mut options);
+
+        assert_eq!(def.budget.unwrap().max_calls, Some(9));
+        assert!(options.live);
+        assert_eq!(options.concurrency, 4);
+        assert_eq!(options.params["region"], "us");
+        assert_eq!(options.params["limit"], 50, "command-line params win");
+        assert_eq!(options.profile.unwrap().name, "prod");
+    }
+
+    #[test]
+    fn test_load_rejects_bad_names_missing_files_and_typos() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "dev", r#"{"polcy": "deny-all"}"#);
+        assert_eq!(
+            Profile::load(dir.path(), "../dev"),
+            Err(ProfileError::InvalidName("../dev".into()))
+        );
+        assert!(matches!(
+            Profile::load(dir.path(), "staging"),
+            Err(ProfileError::NotFound { .. })
+        ));
+        let err = Profile::load(dir.path(), "dev").unwrap_err();
+        assert!(err.to_string().contains("unknown field `polcy`"), "{err}");
+    }
+}
@@ -0,0 +1,270 @@
+//! Replay verification of a recorded run against its evidence bundle.
+//!
+//! [`WorkflowRunner::verify`] re-executes every step the bundle's audit log
+//! records as completed, with the capability results captured at record
+//! time (`events/<step_id>.json`) served back through a `ReplayHandler`
+//! instead of the live or mock handler. The step's output hash must match
+//! the recorded `StepCompleted.output_hash`. A step that now makes a
+//! different sequence of capability calls is reported as diverged even when
+//! its output still matches. Nothing touches the outside world: the bundle
+//! is the only source of capability results.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::Serialize;
+
+use boruna_bytecode::{Capability, Value};
+use boruna_vm::capability_gateway::{CapabilityGateway, Policy, ReplayHandler, StepInputHandler};
+use boruna_vm::replay::{Event, EventLog, ReplayEngine, ReplayResult};
+use boruna_vm::Vm;
+
+use crate::audit::{AuditEvent, AuditLog, BundleManifest};
+use crate::workflow::data_flow::DataStore;
+use crate::workflow::definition::*;
+use crate::workflow::params::{self, ParamValues};
+use crate::workflow::runner::{WorkflowRunError, WorkflowRunner};
+use crate::workflow::validator::WorkflowValidator;
+
+/// Outcome of replaying one step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepVerifyStatus {
+    /// Output hash matches and the capability calls are the recorded ones.
+    Matched,
+    /// Output hash differs from the recorded one.
+    Mismatched,
+    /// Output hash matches but the step's capability calls changed.
+    Diverged,
+    /// The step failed to compile, resolve its inputs, or run.
+    Failed,
+    /// Not replayed: not a source step, or not completed in the recording.
+    Skipped,
+}
+
+/// Verification result for a single step.
+#[derive(Debug, Clone, Serialize)]
+pub struct StepVerification {
+    pub step_id: String,
+    pub status: StepVerifyStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actual_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+/// Result of [`WorkflowRunner::verify`], with steps in topological order.
+#[derive(Debug, Clone, Serialize)]
+pub struct VerifyReport {
+    pub run_id: String,
+    pub steps: Vec<StepVerification>,
+}
+
+impl VerifyReport {
+    /// True when no replayed step mismatched, diverged, or failed.
+    pub fn passed(&self) -> bool {
+        self.steps.iter().all(|s| {
+            matches!(
+                s.status,
+                StepVerifyStatus::Matched | StepVerifyStatus::Skipped
+            )
+        })
+    }
+}
+
+fn read_bundle_file(bundle_dir: &Path, name: &str) -> Result<String, WorkflowRunError> {
+    let path = bundle_dir.join(name);
+    std::fs::read_to_string(&path)
+        .map_err(|e| WorkflowRunError::Io(format!("cannot read {}: {e}", path.display())))
+}
+
+impl WorkflowRunner {
+    /// Re-execute the run recorded in the evidence bundle at `bundle_dir`
+    /// against the workflow in `workflow_dir`, replaying the recorded
+    /// capability results, and compare each step's output hash with the
+    /// one in the bundle's audit log.
+    ///
+    /// Only plaintext bundles are supported. Bundle integrity is not
+    /// checked here; callers run [`crate::audit::verify_bundle`] first.
+    pub fn verify(
+        def: &WorkflowDef,
+        workflow_dir: &str,
+        bundle_dir: &Path,
+    ) -> Result<VerifyReport, WorkflowRunError> {
+        let manifest: BundleManifest =
+            serde_json::from_str(&read_bundle_file(bundle_dir, "manifest.json")?)
+                .map_err(|e| WorkflowRunError::Validation(format!("invalid manifest.json: {e}")))?;
+        if manifest.encryption.is_some() {
+            return Err(WorkflowRunError::Validation(
+                "cannot replay an encrypted evidence bundle".to_string(),
+            ));
+        }
+        let policy: Policy = serde_json::from_str(&read_bundle_file(bundle_dir, "policy.json")?)
+            .map_err(|e| WorkflowRunError::Validation(format!("invalid policy.json: {e}")))?;
+        let audit = AuditLog::from_json(&read_bundle_file(bundle_dir, "audit_log.json")?)
+            .map_err(|e| WorkflowRunError::Validation(format!("invalid audit_log.json: {e}")))?;
+
+        let mut recorded_hashes = BTreeMap::new();
+        for entry in audit.entries() {
+            if let AuditEvent::StepCompleted {
+                step_id,
+                output_hash,
+                ..
+            } = &entry.event
+            {
+                recorded_hashes.insert(step_id.clone(), output_hash.clone());
+            }
+        }
+
+        let order =
+            WorkflowValidator::topological_order(def).map_err(WorkflowRunError::Validation)?;
+        let run_dir = tempfile::tempdir().map_err(|e| WorkflowRunError::Io(e.to_string()))?;
+        let bound: ParamValues = if manifest.params_hash.is_some() {
+            serde_json::from_str(&read_bundle_file(bundle_dir, "params.json")?)
+                .map_err(|e| WorkflowRunError::Validation(format!("invalid params.json: {e}")))?
+        } else {
+            ParamValues::new()
+        };
+        let mut data_store = DataStore::new(run_dir.path())
+            .map_err(|e| WorkflowRunError::Io(e.to_string()))?
+            .with_params(params::to_values(def, &bound));
+        let policy = Some(policy);
+
+        let mut steps = Vec::with_capacity(order.len());
+        for step_id in order {
+            let step_def = def
+                .steps
+                .get(&step_id)
+                .ok_or_else(|| WorkflowRunError::Internal(format!("step not found: {step_id}")))?;
+            let expected_hash = recorded_hashes.get(&step_id).cloned();
+            let skipped = |detail: &str| StepVerification {
+                step_id: step_id.clone(),
+                status: StepVerifyStatus::Skipped,
+                expected_hash: expected_hash.clone(),
+                actual_hash: None,
+                detail: Some(detail.to_string()),
+            };
+            let StepKind::Source { source } = &step_def.kind else {
+                steps.push(skipped("not a source step"));
+                continue;
+            };
+            let Some(expected) = expected_hash.clone() else {
+                steps.push(skipped("not completed in the recorded run"));
+                continue;
+            };
+
+            let events_name = format!("events/{step_id}.json");
+            let recorded = if bundle_dir.join(&events_name).exists() {
+                let json = read_bundle_file(bundle_dir, &events_name)?;
+                Some(EventLog::from_json(&json).map_err(|e| {
+                    WorkflowRunError::Validation(format!("invalid {events_name}: {e}"))
+                })?)
+            } else {
+                None
+            };
+
+            let replayed = data_store
+                .resolve_step_inputs(&step_def.inputs)
+                .map_err(|e| format!("input resolution: {e}"))
+                .and_then(|inputs| {
+                    replay_step(
+                        &step_id,
+                        source,
+                        step_def,
+                        workflow_dir,
+                        &policy,
+                        inputs,
+                        recorded.as_ref(),
+                    )
+                });
+            let (value, replay_log) = match replayed {
+                Ok(done) => done,
+                Err(detail) => {
+                    steps.push(StepVerification {
+                        step_id: step_id.clone(),
+                        status: StepVerifyStatus::Failed,
+                        expected_hash: Some(expected),
+                        actual_hash: None,
+                        detail: Some(detail),
+                    });
+                    continue;
+                }
+            };
+
+            let actual = DataStore::hash_value(&value);
+            data_store
+                .store_output(&step_id, "result", &value)
+                .map_err(|e| WorkflowRunError::Io(e.to_string()))?;
+            let divergence =
+                recorded.and_then(|log| match ReplayEngine::verify(&log, &replay_log) {
+                    ReplayResult::Identical => None,
+                    ReplayResult::Diverged { reason } => Some(reason),
+                });
+            let (status, detail) = if actual != expected {
+                (StepVerifyStatus::Mismatched, divergence)
+            } else if divergence.is_some() {
+                (StepVerifyStatus::Diverged, divergence)
+            } else {
+                (StepVerifyStatus::Matched, None)
+            };
+            steps.push(StepVerification {
+                step_id: step_id.clone(),
+                status,
+                expected_hash: Some(expected),
+                actual_hash: Some(actual),
+                detail,
+            });
+        }
+
+        Ok(VerifyReport {
+            run_id: manifest.run_id,
+            steps,
+        })
+    }
+}
+
+/// Compile and run one step with `recorded`'s capability results served
+/// back in order. `step.input` is answered from the resolved inputs, so
+/// its results are left out of the replay queue.
+fn replay_step(
+    step_id: &str,
+    source: &str,
+    step_def: &StepDef,
+    workflow_dir: &str,
+    policy: &Option<Policy>,
+    inputs: BTreeMap<String, Value>,
+    recorded: Option<&EventLog>,
+) -> Result<(Value, EventLog), String> {
+    let source_path = Path::new(workflow_dir).join(source);
+    let source_code = std::fs::read_to_string(&source_path)
+        .map_err(|e| format!("cannot read {}: {e}", source_path.display()))?;
+    let module = boruna_compiler::compile(step_id, &source_code)
+        .map_err(|e| format!("compile error: {e}"))?;
+
+    let results = recorded.map_or_else(Vec::new, |log| {
+        log.events()
+            .iter()
+            .filter_map(|e| match e {
+                Event::CapResult { capability, result }
+                    if capability != Capability::StepInput.name() =>
+                {
+                    Some(result.clone())
+                }
+                _ => None,
+            })
+            .collect()
+    });
+    let handler = Box::new(StepInputHandler::new(
+        inputs,
+        Box::new(ReplayHandler::new(results)),
+    ));
+    let gateway = CapabilityGateway::with_handler(
+        WorkflowRunner::build_step_policy(policy, step_def),
+        handler,
+    );
+    let mut vm = Vm::new(module, gateway);
+    let value = vm.run().map_err(|e| format!("runtime error: {e}"))?;
+    Ok((value, vm.event_log().clone()))
+}
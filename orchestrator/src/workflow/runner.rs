@@ -1,13 +1,23 @@
 use std::collections::{BTreeMap, BTreeSet};
 use std::path::Path;
+use std::sync::Arc;
 use std::time::Instant;
 
-use boruna_vm::capability_gateway::{CapabilityGateway, Policy, PolicyRule};
+use boruna_budget::BudgetLedger;
+use boruna_vm::capability_gateway::{
+    CapabilityGateway, Classification, DataFlowPolicy, Policy, PolicyRule,
+};
 use boruna_vm::error::VmError;
+use boruna_vm::replay::EventLog;
 use boruna_vm::Vm;
 
+use crate::audit::ProfileFingerprint;
+use crate::workflow::container::{run_in_container, ContainerBackend};
 use crate::workflow::data_flow::DataStore;
 use crate::workflow::definition::*;
+use crate::workflow::params::{self, ParamValues};
+#[cfg(feature = "persist-sqlite")]
+use crate::workflow::profile::Profile;
 use crate::workflow::validator::WorkflowValidator;
 
 #[cfg(feature = "persist-sqlite")]
@@ -60,6 +70,18 @@ pub struct RunOptions {
     /// `attempt_count += 1`); the next worker re-runs.
     #[cfg_attr(not(feature = "persist-sqlite"), allow(dead_code))]
     pub submit_only: bool,
+    /// Values for the workflow's declared `params`, by name. Bound
+    /// against the definition at run start: unknown names and type
+    /// mismatches fail the run, absent params take their defaults.
+    pub params: ParamValues,
+    /// The environment profile already applied to this run's definition
+    /// and options (see [`crate::workflow::profile`]). Recorded in the
+    /// run metadata so `resume` re-applies it.
+    pub profile: Option<ProfileFingerprint>,
+    /// Backend that runs steps declaring `"isolation": "container"`. A
+    /// run reaching such a step without one fails the step rather than
+    /// running it in-process.
+    pub container: Option<Arc<dyn ContainerBackend>>,
 }
 
 impl Default for RunOptions {
@@ -71,6 +93,9 @@ impl Default for RunOptions {
             live: false,
             concurrency: 1,
             submit_only: false,
+            params: ParamValues::new(),
+            profile: None,
+            container: None,
         }
     }
 }
@@ -92,6 +117,8 @@ pub struct ResumeOptions {
     /// Maximum steps to run concurrently per wave. Default `1` =
     /// sequential. See [`RunOptions::concurrency`].
     pub concurrency: usize,
+    /// See [`RunOptions::container`].
+    pub container: Option<Arc<dyn ContainerBackend>>,
 }
 
 #[cfg(feature = "persist-sqlite")]
@@ -103,6 +130,7 @@ impl Default for ResumeOptions {
             live: false,
             workflow_dir_override: None,
             concurrency: 1,
+            container: None,
         }
     }
 }
@@ -174,6 +202,17 @@ struct PersistedRunMetadata {
     /// not a replay-verified record.
     #[serde(default)]
     step_sources: BTreeMap<String, String>,
+    /// Params bound for this run (see [`crate::workflow::params`]);
+    /// `resume` restores them so later steps read the same values.
+    /// Their hash is the run's `inputs_hash`. Defaulted so databases
+    /// from before workflow params parse cleanly.
+    #[serde(default)]
+    params: ParamValues,
+    /// Environment profile the run was started with. `resume`
+    /// reloads it and refuses if the file changed. Defaulted so
+    /// databases from before profiles parse cleanly.
+    #[serde(default)]
+    profile: Option<ProfileFingerprint>,
     /// Full workflow DAG embedded for client-side multi-wave
     /// advancement (sprint `0.5-S2f`). Populated only when
     /// `RunOptions::submit_only` is true; in-process
@@ -351,7 +390,8 @@ impl WorkflowRunner {
         // run_id, which is fine for an ephemeral path that never inserts
         // into a store.
         let workflow_hash = Self::workflow_hash_from_def(def);
-        let inputs_hash = Self::ephemeral_inputs_hash();
+        let bound = Self::bind_run_params(def, options)?;
+        let inputs_hash = params::params_hash(&bound);
 
         #[cfg(feature = "persist-sqlite")]
         let run_id = derive_run_id(&workflow_hash, &inputs_hash, 0);
@@ -360,14 +400,14 @@ impl WorkflowRunner {
             // Fallback when the persistence feature is off: use a hash-only
             // derivation that doesn't require the helper from the
             // persistence module.
-            use sha2::{Digest, Sha256};
-            let mut h = Sha256::new();
+            use boruna_hash::Hasher;
+            let mut h = Hasher::sha256();
             h.update(workflow_hash.as_bytes());
             h.update(b":");
             h.update(inputs_hash.as_bytes());
             h.update(b":");
             h.update(0i64.to_le_bytes());
-            let digest = h.finalize();
+            let digest = h.finalize_bytes();
             let mut s = String::with_capacity(16);
             for b in &digest[..8] {
                 use std::fmt::Write;
@@ -377,8 +417,10 @@ impl WorkflowRunner {
         };
 
         let run_dir = tempfile::tempdir().map_err(|e| WorkflowRunError::Io(e.to_string()))?;
-        let mut data_store =
-            DataStore::new(run_dir.path()).map_err(|e| WorkflowRunError::Io(e.to_string()))?;
+        let mut data_store = DataStore::new(run_dir.path())
+            .map_err(|e| WorkflowRunError::Io(e.to_string()))?
+            .with_classifications(def)
+            .with_params(params::to_values(def, &bound));
 
         let result = Self::execute_steps(
             def,
@@ -444,7 +486,7 @@ impl WorkflowRunner {
                 &run_id,
                 crate::audit::AuditEvent::WorkflowStarted {
                     workflow_hash: Self::workflow_hash_from_def(def),
-                    policy_hash: sha256_hex(&policy_hash_seed),
+                    policy_hash: boruna_hash::sha256_hex(&policy_hash_seed),
                 },
             ) {
                 eprintln!(
@@ -499,7 +541,8 @@ impl WorkflowRunner {
 
         let store = open_store(data_dir)?;
         let workflow_hash = Self::workflow_hash_from_def(def);
-        let inputs_hash = Self::ephemeral_inputs_hash();
+        let bound = Self::bind_run_params(def, options)?;
+        let inputs_hash = params::params_hash(&bound);
         let policy_json = serde_json::to_string(&options.policy)
             .map_err(|e| WorkflowRunError::Internal(format!("policy serialize: {e}")))?;
         let metadata = PersistedRunMetadata {
@@ -510,6 +553,8 @@ impl WorkflowRunner {
             triggers: BTreeMap::new(),
             audit_log: Vec::new(),
             step_sources: Self::collect_step_sources(def, &options.workflow_dir)?,
+            params: bound,
+            profile: options.profile.clone(),
             workflow_def: Self::embed_workflow_def_for_metadata(def, options)?,
         };
         let metadata_json = serde_json::to_string(&metadata)
@@ -668,6 +713,16 @@ impl WorkflowRunner {
             )
         })?;
 
+        // Workers resolve step inputs from upstream outputs only;
+        // params are not shipped to the cluster.
+        if !def.params.is_empty() {
+            return Err(WorkflowRunError::Validation(
+                "submit-with-inline-sources: workflows that declare params \
+                 cannot run in distributed mode"
+                    .into(),
+            ));
+        }
+
         // Every Source-kind step in the def must be covered by an
         // entry in `step_sources`; missing entries would mean the
         // remote cluster has no .ax to compile when a worker claims
@@ -717,7 +772,7 @@ impl WorkflowRunner {
         }
 
         let workflow_hash = Self::workflow_hash_from_def(def);
-        let inputs_hash = Self::ephemeral_inputs_hash();
+        let inputs_hash = params::params_hash(&ParamValues::new());
         let policy_json = serde_json::to_string(policy)
             .map_err(|e| WorkflowRunError::Internal(format!("policy serialize: {e}")))?;
         let metadata = PersistedRunMetadata {
@@ -730,6 +785,8 @@ impl WorkflowRunner {
             triggers: BTreeMap::new(),
             audit_log: Vec::new(),
             step_sources,
+            params: ParamValues::new(),
+            profile: None,
             workflow_def: Some(def.clone()),
         };
         let metadata_json = serde_json::to_string(&metadata)
@@ -757,7 +814,7 @@ impl WorkflowRunner {
             &run_id,
             crate::audit::AuditEvent::WorkflowStarted {
                 workflow_hash: Self::workflow_hash_from_def(def),
-                policy_hash: sha256_hex(&policy_hash_seed),
+                policy_hash: boruna_hash::sha256_hex(&policy_hash_seed),
             },
         ) {
             eprintln!(
@@ -1400,7 +1457,8 @@ impl WorkflowRunner {
 
         let store = open_store(data_dir)?;
         let workflow_hash = Self::workflow_hash_from_def(def);
-        let inputs_hash = Self::ephemeral_inputs_hash();
+        let bound = Self::bind_run_params(def, options)?;
+        let inputs_hash = params::params_hash(&bound);
         let policy_json = serde_json::to_string(&options.policy)
             .map_err(|e| WorkflowRunError::Internal(format!("policy serialize: {e}")))?;
         let metadata = PersistedRunMetadata {
@@ -1411,6 +1469,8 @@ impl WorkflowRunner {
             triggers: BTreeMap::new(),
             audit_log: Vec::new(),
             step_sources: Self::collect_step_sources(def, &options.workflow_dir)?,
+            params: bound,
+            profile: options.profile.clone(),
             workflow_def: Self::embed_workflow_def_for_metadata(def, options)?,
         };
         let metadata_json = serde_json::to_string(&metadata)
@@ -1447,8 +1507,11 @@ impl WorkflowRunner {
         // the data store survives a crash. Caller controls the parent;
         // each run gets its own folder keyed by run_id.
         let run_data_dir = data_dir.join("runs").join(&run_id);
-        let mut data_store =
-            DataStore::new(&run_data_dir).map_err(|e| WorkflowRunError::Io(e.to_string()))?;
+        let bound = Self::bind_run_params(def, options)?;
+        let mut data_store = DataStore::new(&run_data_dir)
+            .map_err(|e| WorkflowRunError::Io(e.to_string()))?
+            .with_classifications(def)
+            .with_params(params::to_values(def, &bound));
 
         // 0.4-S11: append WorkflowStarted to the audit chain. The
         // run row was just inserted with an empty audit_log, so
@@ -1459,7 +1522,7 @@ impl WorkflowRunner {
             &run_id,
             crate::audit::AuditEvent::WorkflowStarted {
                 workflow_hash: Self::workflow_hash_from_def(def),
-                policy_hash: sha256_hex(&policy_hash_seed),
+                policy_hash: boruna_hash::sha256_hex(&policy_hash_seed),
             },
         ) {
             // Best-effort logging; don't shadow the workflow's result.
@@ -1604,8 +1667,19 @@ impl WorkflowRunner {
         let def_json = std::fs::read_to_string(&def_path).map_err(|e| {
             WorkflowRunError::Io(format!("cannot read {}: {e}", def_path.display()))
         })?;
-        let def: WorkflowDef = serde_json::from_str(&def_json)
+        let mut def: WorkflowDef = serde_json::from_str(&def_json)
             .map_err(|e| WorkflowRunError::Internal(format!("invalid workflow.json: {e}")))?;
+        if let Some(recorded) = &metadata.profile {
+            let loaded = Profile::load(Path::new(&workflow_dir), &recorded.name)
+                .map_err(|e| WorkflowRunError::Validation(e.to_string()))?;
+            if loaded.hash != recorded.hash {
+                return Err(WorkflowRunError::Validation(format!(
+                    "profile '{}' changed since run '{run_id}' started",
+                    recorded.name
+                )));
+            }
+            loaded.profile.apply_to_def(&mut def);
+        }
 
         let actual_hash = Self::workflow_hash_from_def(&def);
         if actual_hash != record.workflow_hash {
@@ -1631,8 +1705,10 @@ impl WorkflowRunner {
         // Restore data store. Persistent runs keep their step output JSONs
         // alongside the runs.db file under data_dir/runs/<run_id>/outputs.
         let run_data_dir = data_dir.join("runs").join(run_id);
-        let mut data_store =
-            DataStore::new(&run_data_dir).map_err(|e| WorkflowRunError::Io(e.to_string()))?;
+        let mut data_store = DataStore::new(&run_data_dir)
+            .map_err(|e| WorkflowRunError::Io(e.to_string()))?
+            .with_classifications(&def)
+            .with_params(params::to_values(&def, &metadata.params));
 
         // Walk persisted checkpoints. Build:
         //   - already_completed: step ids whose output should be restored
@@ -1680,6 +1756,7 @@ impl WorkflowRunner {
                             capabilities_used: vec![],
                             error: None,
                             attempt_count: 1,
+                            event_log: None,
                         },
                     );
                 }
@@ -1704,6 +1781,7 @@ impl WorkflowRunner {
                             capabilities_used: vec![],
                             error: cp.error_msg.clone(),
                             attempt_count: 1,
+                            event_log: None,
                         },
                     );
                     halt_with_failed_step = Some(cp.step_id.clone());
@@ -1835,6 +1913,7 @@ impl WorkflowRunner {
                             capabilities_used: vec![],
                             error: None,
                             attempt_count: 1,
+                            event_log: None,
                         },
                     );
                 }
@@ -1870,6 +1949,7 @@ impl WorkflowRunner {
                             capabilities_used: vec![],
                             error: Some(err_msg),
                             attempt_count: 1,
+                            event_log: None,
                         },
                     );
                     // get_or_insert: preserve the FIRST failure as the
@@ -1985,6 +2065,7 @@ impl WorkflowRunner {
                     capabilities_used: vec![],
                     error: None,
                     attempt_count: 1,
+                    event_log: None,
                 },
             );
         }
@@ -2034,6 +2115,9 @@ impl WorkflowRunner {
             // Resume always executes in-process; submit-only is
             // a fresh-run-only mode (sprint 0.5-S2e).
             submit_only: false,
+            params: Default::default(),
+            profile: None,
+            container: options.container.clone(),
         };
 
         // Reset run status to Running for the resume window.
@@ -2121,20 +2205,24 @@ impl WorkflowRunner {
     /// hash.
     pub fn workflow_hash_from_def(def: &WorkflowDef) -> String {
         let canonical = serde_json::to_string(def).unwrap_or_default();
-        use sha2::{Digest, Sha256};
-        let mut hasher = Sha256::new();
-        hasher.update(canonical.as_bytes());
-        format!("{:x}", hasher.finalize())
+        boruna_hash::sha256_hex(canonical)
     }
 
-    /// Inputs hash for the runner's current "no external workflow inputs"
-    /// shape. A future sprint adding workflow-level params will replace
-    /// this with a real serialization.
-    fn ephemeral_inputs_hash() -> String {
-        use sha2::{Digest, Sha256};
-        let mut hasher = Sha256::new();
-        hasher.update(b"{}");
-        format!("{:x}", hasher.finalize())
+    /// Bind `options.params` to `def`'s declared params. The bound set
+    /// feeds the run's `inputs_hash`. Submit-only runs are refused when
+    /// the workflow declares params: cluster workers never see them.
+    fn bind_run_params(
+        def: &WorkflowDef,
+        options: &RunOptions,
+    ) -> Result<ParamValues, WorkflowRunError> {
+        let bound = params::bind(def, &options.params)
+            .map_err(|e| WorkflowRunError::Validation(e.to_string()))?;
+        if options.submit_only && !bound.is_empty() {
+            return Err(WorkflowRunError::Validation(
+                "workflows that declare params cannot run in submit-only (distributed) mode".into(),
+            ));
+        }
+        Ok(bound)
     }
 
     /// Reconstruct a `WorkflowRunResult` from persisted state for a run
@@ -2170,6 +2258,7 @@ impl WorkflowRunner {
                     capabilities_used: vec![],
                     error: cp.error_msg,
                     attempt_count: 1,
+                    event_log: None,
                 },
             );
         }
@@ -2237,6 +2326,7 @@ impl WorkflowRunner {
         let mut step_results: BTreeMap<String, StepResult> = prior_results.clone();
         let mut workflow_status = WorkflowStatus::Running;
         let max_concurrency = options.concurrency.max(1);
+        let ledger = BudgetLedger::new(def.budget.unwrap_or_default());
 
         'outer: for level in levels {
             // Filter out skip-on-resume steps and partition into
@@ -2254,6 +2344,13 @@ impl WorkflowRunner {
                     .get(id)
                     .ok_or_else(|| WorkflowRunError::Internal(format!("step not found: {id}")))?;
                 match &step_def.kind {
+                    StepKind::ApprovalGate { .. }
+                        if !gate_condition_holds(id, step_def, data_store)? =>
+                    {
+                        let sr = pass_gate(id, data_store)?;
+                        persist_passed_gate(store, run_id, &sr, data_store)?;
+                        step_results.insert(id.clone(), sr);
+                    }
                     StepKind::ApprovalGate { .. } | StepKind::ExternalTrigger { .. } => {
                         pauses.push(id.as_str())
                     }
@@ -2309,6 +2406,7 @@ impl WorkflowRunner {
                                     capabilities_used: vec![],
                                     error: None,
                                     attempt_count: 1,
+                                    event_log: None,
                                 },
                             );
                         }
@@ -2386,6 +2484,7 @@ impl WorkflowRunner {
                                 capabilities_used: vec![],
                                 error: Some(err_msg.clone()),
                                 attempt_count: 1,
+                                event_log: None,
                             },
                         );
                     }
@@ -2413,6 +2512,8 @@ impl WorkflowRunner {
                     StepDef,
                     String,
                     BTreeMap<String, boruna_bytecode::Value>,
+                    Classification,
+                    BudgetLedger,
                 )> = Vec::new();
                 for &step_id in chunk {
                     let step_def = def.steps[step_id].clone();
@@ -2432,7 +2533,16 @@ impl WorkflowRunner {
                                  for step '{step_id}': {e}"
                             ))
                         })?;
-                    dispatches.push((step_id.to_string(), step_def, source_path, resolved_inputs));
+                    let input_class = data_store.input_classification(&step_def.inputs);
+                    let step_ledger = step_ledger(&ledger, step_id, &step_def);
+                    dispatches.push((
+                        step_id.to_string(),
+                        step_def,
+                        source_path,
+                        resolved_inputs,
+                        input_class,
+                        step_ledger,
+                    ));
                 }
 
                 // Spawn workers, tracking step_id alongside each
@@ -2441,35 +2551,42 @@ impl WorkflowRunner {
                 let workflow_dir = options.workflow_dir.clone();
                 let policy = options.policy.clone();
                 let live = options.live;
+                let container = options.container.clone();
                 let handles: Vec<(String, StepDef, std::thread::JoinHandle<_>)> = dispatches
                     .into_iter()
-                    .map(|(step_id, step_def, source, resolved_inputs)| {
-                        let workflow_dir = workflow_dir.clone();
-                        let policy = policy.clone();
-                        let id_for_thread = step_id.clone();
-                        let def_for_thread = step_def.clone();
-                        let start = Instant::now();
-                        let h = std::thread::spawn(move || {
-                            // Workers honor the same RetryPolicy as
-                            // sequential execution. The retry happens
-                            // INSIDE the worker thread; the chunk
-                            // wave waits for ALL workers (including
-                            // ones still retrying) before moving on.
-                            // Wall-clock backoff is bounded by the
-                            // policy's max_attempts.
-                            let result = Self::compile_and_run_step_with_retry(
-                                &id_for_thread,
-                                &source,
-                                &def_for_thread,
-                                &workflow_dir,
-                                &policy,
-                                live,
-                                resolved_inputs,
-                            );
-                            (result, start.elapsed().as_millis() as u64)
-                        });
-                        (step_id, step_def, h)
-                    })
+                    .map(
+                        |(step_id, step_def, source, resolved_inputs, input_class, ledger)| {
+                            let workflow_dir = workflow_dir.clone();
+                            let policy = policy.clone();
+                            let container = container.clone();
+                            let id_for_thread = step_id.clone();
+                            let def_for_thread = step_def.clone();
+                            let start = Instant::now();
+                            let h = std::thread::spawn(move || {
+                                // Workers honor the same RetryPolicy as
+                                // sequential execution. The retry happens
+                                // INSIDE the worker thread; the chunk
+                                // wave waits for ALL workers (including
+                                // ones still retrying) before moving on.
+                                // Wall-clock backoff is bounded by the
+                                // policy's max_attempts.
+                                let result = Self::compile_and_run_step_with_retry(
+                                    &id_for_thread,
+                                    &source,
+                                    &def_for_thread,
+                                    &workflow_dir,
+                                    &policy,
+                                    live,
+                                    container.as_deref(),
+                                    resolved_inputs,
+                                    input_class,
+                                    &ledger,
+                                );
+                                (result, start.elapsed().as_millis() as u64)
+                            });
+                            (step_id, step_def, h)
+                        },
+                    )
                     .collect();
 
                 // Join EVERY handle into a results Vec before
@@ -2487,7 +2604,7 @@ impl WorkflowRunner {
                     String,
                     StepDef,
                     std::thread::Result<(
-                        Result<(boruna_bytecode::Value, u32), (WorkflowRunError, u32)>,
+                        Result<((boruna_bytecode::Value, EventLog), u32), (WorkflowRunError, u32)>,
                         u64,
                     )>,
                 )> = handles
@@ -2507,7 +2624,7 @@ impl WorkflowRunner {
                 let mut chunk_failed = false;
                 for (step_id, step_def, join_res) in joined {
                     match join_res {
-                        Ok((Ok((value, attempt_count)), duration_ms)) => {
+                        Ok((Ok(((value, event_log), attempt_count)), duration_ms)) => {
                             let output_hash = DataStore::hash_value(&value);
                             data_store
                                 .store_output(&step_id, "result", &value)
@@ -2555,6 +2672,7 @@ impl WorkflowRunner {
                                     capabilities_used: step_def.capabilities.clone(),
                                     error: None,
                                     attempt_count,
+                                    event_log: Some(event_log),
                                 },
                             );
                         }
@@ -2596,6 +2714,7 @@ impl WorkflowRunner {
                                     capabilities_used: vec![],
                                     error: Some(err_msg),
                                     attempt_count,
+                                    event_log: None,
                                 },
                             );
                             chunk_failed = true;
@@ -2652,6 +2771,7 @@ impl WorkflowRunner {
                                     capabilities_used: vec![],
                                     error: Some(err_msg),
                                     attempt_count: 1,
+                                    event_log: None,
                                 },
                             );
                             chunk_failed = true;
@@ -2692,6 +2812,7 @@ impl WorkflowRunner {
         let run_start = Instant::now();
         let mut step_results: BTreeMap<String, StepResult> = prior_results.clone();
         let mut workflow_status = WorkflowStatus::Running;
+        let ledger = BudgetLedger::new(def.budget.unwrap_or_default());
 
         for step_id in order {
             // Skip already-completed steps on resume.
@@ -2721,6 +2842,16 @@ impl WorkflowRunner {
             }
 
             match &step_def.kind {
+                StepKind::ApprovalGate { .. }
+                    if !gate_condition_holds(step_id, step_def, data_store)? =>
+                {
+                    let sr = pass_gate(step_id, data_store)?;
+                    #[cfg(feature = "persist-sqlite")]
+                    if let Some(s) = store {
+                        persist_passed_gate(s, run_id, &sr, data_store)?;
+                    }
+                    step_results.insert(step_id.clone(), sr);
+                }
                 StepKind::ApprovalGate { required_role, .. } => {
                     let cp = StepResult {
                         step_id: step_id.clone(),
@@ -2730,6 +2861,7 @@ impl WorkflowRunner {
                         capabilities_used: vec![],
                         error: None,
                         attempt_count: 1,
+                        event_log: None,
                     };
                     step_results.insert(step_id.clone(), cp);
                     workflow_status = WorkflowStatus::Paused;
@@ -2800,6 +2932,7 @@ impl WorkflowRunner {
                             capabilities_used: vec![],
                             error: None,
                             attempt_count: 1,
+                            event_log: None,
                         };
                         step_results.insert(step_id.clone(), cp);
                         workflow_status = WorkflowStatus::Paused;
@@ -2837,6 +2970,8 @@ impl WorkflowRunner {
                         &options.policy,
                         data_store,
                         options.live,
+                        options.container.as_deref(),
+                        &step_ledger(&ledger, step_id, step_def),
                     );
                     let duration_ms = step_start.elapsed().as_millis() as u64;
 
@@ -2907,6 +3042,7 @@ impl WorkflowRunner {
                                     capabilities_used: vec![],
                                     error: Some(err_msg.clone()),
                                     attempt_count,
+                                    event_log: None,
                                 },
                             );
                             workflow_status = WorkflowStatus::Failed;
@@ -2978,6 +3114,7 @@ impl WorkflowRunner {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn execute_source_step(
         step_id: &str,
         source: &str,
@@ -2986,6 +3123,8 @@ impl WorkflowRunner {
         policy: &Option<Policy>,
         data_store: &mut DataStore,
         live: bool,
+        container: Option<&dyn ContainerBackend>,
+        ledger: &BudgetLedger,
     ) -> Result<StepResult, (WorkflowRunError, u32)> {
         // 0.3-S14: resolve inputs ONCE up front, then pass the
         // resolved map to the compute path. The .ax step's
@@ -3005,14 +3144,18 @@ impl WorkflowRunner {
         // 0.3-S13: surface the attempt count on the failure path too
         // so the sequential terminal-failure upsert can persist the
         // accurate count instead of defaulting to 1.
-        let (value, attempt_count) = Self::compile_and_run_step_with_retry(
+        let input_class = data_store.input_classification(&step_def.inputs);
+        let ((value, event_log), attempt_count) = Self::compile_and_run_step_with_retry(
             step_id,
             source,
             step_def,
             workflow_dir,
             policy,
             live,
+            container,
             resolved_inputs,
+            input_class,
+            ledger,
         )?;
 
         let output_hash = DataStore::hash_value(&value);
@@ -3033,6 +3176,7 @@ impl WorkflowRunner {
             capabilities_used: step_def.capabilities.clone(),
             error: None,
             attempt_count,
+            event_log: Some(event_log),
         })
     }
 
@@ -3052,6 +3196,7 @@ impl WorkflowRunner {
     /// concurrent worker closure inside [`Self::execute_steps_concurrent`].
     /// Introduced in `0.3-S5` (closes the prior "retry once
     /// regardless of max_attempts" primitive).
+    #[allow(clippy::too_many_arguments)]
     fn compile_and_run_step_with_retry(
         step_id: &str,
         source: &str,
@@ -3059,8 +3204,11 @@ impl WorkflowRunner {
         workflow_dir: &str,
         policy: &Option<Policy>,
         live: bool,
+        container: Option<&dyn ContainerBackend>,
         resolved_inputs: BTreeMap<String, boruna_bytecode::Value>,
-    ) -> Result<(boruna_bytecode::Value, u32), (WorkflowRunError, u32)> {
+        input_class: Classification,
+        ledger: &BudgetLedger,
+    ) -> Result<((boruna_bytecode::Value, EventLog), u32), (WorkflowRunError, u32)> {
         retry_with_backoff(step_def.retry.as_ref(), step_id, |_attempt| {
             // Each retry attempt gets its own clone of the inputs
             // (the underlying compile+run path takes ownership).
@@ -3073,7 +3221,10 @@ impl WorkflowRunner {
                 workflow_dir,
                 policy,
                 live,
+                container,
                 resolved_inputs.clone(),
+                input_class,
+                ledger,
             )
         })
     }
@@ -3092,6 +3243,7 @@ impl WorkflowRunner {
     /// the [`error_class`] string. The retry loop consults the class
     /// to decide whether to retry per the policy's `retry_on`
     /// allowlist.
+    #[allow(clippy::too_many_arguments)]
     fn compile_and_run_step(
         step_id: &str,
         source: &str,
@@ -3099,8 +3251,11 @@ impl WorkflowRunner {
         workflow_dir: &str,
         policy: &Option<Policy>,
         live: bool,
+        container: Option<&dyn ContainerBackend>,
         resolved_inputs: BTreeMap<String, boruna_bytecode::Value>,
-    ) -> Result<boruna_bytecode::Value, (WorkflowRunError, &'static str)> {
+        input_class: Classification,
+        ledger: &BudgetLedger,
+    ) -> Result<(boruna_bytecode::Value, EventLog), (WorkflowRunError, &'static str)> {
         let source_path = Path::new(workflow_dir).join(source);
         let source_code = std::fs::read_to_string(&source_path).map_err(|e| {
             (
@@ -3112,57 +3267,73 @@ impl WorkflowRunner {
             )
         })?;
 
-        let module = boruna_compiler::compile(step_id, &source_code).map_err(|e| {
+        crate::workflow::schema::check_inputs(step_id, &step_def.input_schema, &resolved_inputs)
+            .map_err(|v| {
+                (
+                    WorkflowRunError::SchemaViolation(v),
+                    error_class::SCHEMA_VIOLATION,
+                )
+            })?;
+
+        let mut module = boruna_compiler::compile(step_id, &source_code).map_err(|e| {
             (
                 WorkflowRunError::StepFailed(step_id.to_string(), format!("compile error: {e}")),
                 error_class::COMPILE_ERROR,
             )
         })?;
+        // The step's event log references the source it ran.
+        module.provenance = Some(boruna_compiler::provenance(&source_code));
+
+        if let Some(data_flow) = policy.as_ref().and_then(|p| p.data_flow.as_ref()) {
+            check_data_flow(step_id, step_def, &module, data_flow, input_class)
+                .map_err(|e| (e, error_class::DATA_FLOW_VIOLATION))?;
+        }
 
         let step_policy = Self::build_step_policy(policy, step_def);
 
-        // Each call builds its own gateway. In the concurrent path,
-        // workers each construct their own gateway/VM — no shared
-        // gateway state.
-        //
-        // 0.3-S14: wrap the chosen handler in `StepInputHandler` so
-        // `step_input("name")` calls in the .ax source dispatch to
-        // the runner-resolved upstream outputs. The wrapper
-        // delegates non-StepInput calls to the inner handler, so
-        // this composes with both the mock handler and (under
-        // `--live`) the real HTTP handler.
-        let inner_handler: Box<dyn boruna_vm::capability_gateway::CapabilityHandler> = if live {
-            #[cfg(feature = "http")]
-            {
-                let net_policy = step_policy.net_policy.clone().unwrap_or_default();
-                Box::new(boruna_vm::http_handler::HttpHandler::new(net_policy))
+        let (value, event_log) = match step_def.isolation {
+            StepIsolation::Process => {
+                run_step_vm(module, step_policy, resolved_inputs, live, ledger)
             }
-            #[cfg(not(feature = "http"))]
-            {
-                eprintln!(
-                    "warning: --live requires the `http` feature; falling back to mock handler"
-                );
-                Box::new(boruna_vm::capability_gateway::MockHandler)
-            }
-        } else {
-            Box::new(boruna_vm::capability_gateway::MockHandler)
-        };
-        let handler = Box::new(boruna_vm::capability_gateway::StepInputHandler::new(
-            resolved_inputs,
-            inner_handler,
-        ));
-        let gateway = CapabilityGateway::with_handler(step_policy, handler);
-        let mut vm = Vm::new(module, gateway);
-        vm.run().map_err(|e| {
-            let class = classify_vm_error(&e);
+            StepIsolation::Container => match container {
+                Some(backend) => run_in_container(
+                    backend,
+                    step_id,
+                    module,
+                    step_policy,
+                    resolved_inputs,
+                    live,
+                    step_def.timeout_ms,
+                    Path::new(workflow_dir),
+                    ledger,
+                ),
+                // Never fall back to in-process: the step asked not to
+                // share the runner's process.
+                None => Err((
+                    "container: step requires container isolation but no \
+                     container backend is configured"
+                        .to_string(),
+                    error_class::CONTAINER_ERROR,
+                )),
+            },
+        }
+        .map_err(|(msg, class)| {
             (
-                WorkflowRunError::StepFailed(step_id.to_string(), format!("runtime error: {e}")),
+                WorkflowRunError::StepFailed(step_id.to_string(), msg),
                 class,
             )
-        })
+        })?;
+        crate::workflow::schema::check_output(step_id, step_def.output_schema.as_ref(), &value)
+            .map_err(|v| {
+                (
+                    WorkflowRunError::SchemaViolation(v),
+                    error_class::SCHEMA_VIOLATION,
+                )
+            })?;
+        Ok((value, event_log))
     }
 
-    fn build_step_policy(base_policy: &Option<Policy>, step_def: &StepDef) -> Policy {
+    pub(crate) fn build_step_policy(base_policy: &Option<Policy>, step_def: &StepDef) -> Policy {
         match base_policy {
             Some(p) => {
                 let mut policy = p.clone();
@@ -3172,6 +3343,7 @@ impl WorkflowRunner {
                             policy.rules.entry(cap.clone()).or_insert(PolicyRule {
                                 allow: true,
                                 budget: max_calls,
+                                prompt: false,
                             });
                         }
                     }
@@ -3197,6 +3369,7 @@ impl WorkflowRunner {
                     .or_insert(PolicyRule {
                         allow: true,
                         budget: u64::MAX,
+                        prompt: false,
                     });
                 policy
             }
@@ -3249,6 +3422,15 @@ pub mod error_class {
     /// Step input resolution failed (e.g., upstream output missing).
     /// Recommended for retry: no — DAG-level concern, not transient.
     pub const INPUT_RESOLUTION: &str = "input_resolution";
+    /// A step input or output did not match the step's declared
+    /// `input_schema` / `output_schema`.
+    /// Recommended for retry: no — a wiring mistake, not transient.
+    pub const SCHEMA_VIOLATION: &str = "schema_violation";
+    /// A step whose inputs carry a classification label above the
+    /// policy's `data_flow.max_classification` for a capability it
+    /// declares or can reach.
+    /// Recommended for retry: no — labels and policy are static.
+    pub const DATA_FLOW_VIOLATION: &str = "data_flow_violation";
     /// Network-level transient failure from a `net.fetch` capability
     /// call: timeout, connection refused, DNS resolution failure,
     /// connection reset mid-stream, etc. Detected by string-matching
@@ -3258,6 +3440,103 @@ pub mod error_class {
     /// `RUNTIME_ERROR` (not retry-eligible by default).
     /// Recommended for retry: yes — typically transient.
     pub const TRANSIENT_NETWORK: &str = "transient_network";
+    /// The run's `budget` or the step's share of it ran out
+    /// (`VmError::RunBudgetExhausted`).
+    /// Recommended for retry: no — a retry draws on the same budget.
+    pub const RUN_BUDGET_EXHAUSTED: &str = "run_budget_exhausted";
+    /// A step with `"isolation": "container"` could not run in its
+    /// container: no backend configured, the runtime or image missing,
+    /// or the container exiting without a result.
+    /// Recommended for retry: maybe — runtime hiccups do happen.
+    pub const CONTAINER_ERROR: &str = "container_error";
+}
+
+/// The handle a step debits: the run's `ledger` plus the step's own
+/// [`StepBudget`] allocation, if it declares one.
+fn step_ledger(ledger: &BudgetLedger, step_id: &str, step_def: &StepDef) -> BudgetLedger {
+    let budget = step_def
+        .budget
+        .as_ref()
+        .map(StepBudget::ledger_budget)
+        .unwrap_or_default();
+    ledger.for_step(step_id, budget)
+}
+
+/// Run a compiled step's VM under `step_policy`, serving `step_input`
+/// from `resolved_inputs`. Shared by the in-process path and the
+/// container's `exec-step`. Errors carry the message and [`error_class`].
+pub(crate) fn run_step_vm(
+    module: boruna_bytecode::Module,
+    step_policy: Policy,
+    resolved_inputs: BTreeMap<String, boruna_bytecode::Value>,
+    live: bool,
+    ledger: &BudgetLedger,
+) -> Result<(boruna_bytecode::Value, EventLog), (String, &'static str)> {
+    // Each call builds its own gateway. In the concurrent path,
+    // workers each construct their own gateway/VM — no shared
+    // gateway state.
+    //
+    // 0.3-S14: wrap the chosen handler in `StepInputHandler` so
+    // `step_input("name")` calls in the .ax source dispatch to
+    // the runner-resolved upstream outputs. The wrapper
+    // delegates non-StepInput calls to the inner handler, so
+    // this composes with both the mock handler and (under
+    // `--live`) the real HTTP handler.
+    let inner_handler: Box<dyn boruna_vm::capability_gateway::CapabilityHandler> = if live {
+        #[cfg(feature = "http")]
+        {
+            let net_policy = step_policy.net_policy.clone().unwrap_or_default();
+            Box::new(boruna_vm::http_handler::HttpHandler::new(net_policy))
+        }
+        #[cfg(not(feature = "http"))]
+        {
+            eprintln!("warning: --live requires the `http` feature; falling back to mock handler");
+            Box::new(boruna_vm::capability_gateway::MockHandler)
+        }
+    } else {
+        Box::new(boruna_vm::capability_gateway::MockHandler)
+    };
+    let handler = Box::new(boruna_vm::capability_gateway::StepInputHandler::new(
+        resolved_inputs,
+        inner_handler,
+    ));
+    let gateway = CapabilityGateway::with_handler(step_policy, handler);
+    let mut vm = Vm::new(module, gateway);
+    vm.set_budget_ledger(ledger.clone());
+    let value = vm
+        .run()
+        .map_err(|e| (format!("runtime error: {e}"), classify_vm_error(&e)))?;
+    Ok((value, vm.event_log().clone()))
+}
+
+/// Refuse a step whose inputs are labelled above the policy's
+/// `data_flow.max_classification` for a capability the step declares or
+/// whose compiled module can reach from its entry point. Checked before
+/// the VM runs, so the data never reaches the capability.
+fn check_data_flow(
+    step_id: &str,
+    step_def: &StepDef,
+    module: &boruna_bytecode::Module,
+    data_flow: &DataFlowPolicy,
+    input_class: Classification,
+) -> Result<(), WorkflowRunError> {
+    for (capability, &max) in &data_flow.max_classification {
+        if input_class <= max {
+            continue;
+        }
+        let declared = step_def.capabilities.iter().any(|c| c == capability);
+        let reachable = boruna_bytecode::Capability::from_name(capability)
+            .is_some_and(|cap| module.transitively_invokes(module.entry, cap));
+        if declared || reachable {
+            return Err(WorkflowRunError::DataFlowViolation {
+                step_id: step_id.to_string(),
+                capability: capability.clone(),
+                classification: input_class,
+                max,
+            });
+        }
+    }
+    Ok(())
 }
 
 /// Classify a [`VmError`] into one of the strings in [`error_class`]
@@ -3269,6 +3548,7 @@ fn classify_vm_error(e: &VmError) -> &'static str {
         VmError::ExecutionLimitExceeded(_) => error_class::STEP_LIMIT_EXCEEDED,
         VmError::CapabilityDenied(_) => error_class::CAPABILITY_DENIED,
         VmError::CapabilityBudgetExceeded(_) => error_class::CAPABILITY_BUDGET_EXCEEDED,
+        VmError::RunBudgetExhausted(_) => error_class::RUN_BUDGET_EXHAUSTED,
         // Capability errors surface as AssertionFailed wrapping the
         // handler's `Err(String)` (see capability_gateway::invoke).
         // Distinguish transient network failures (retry-eligible) from
@@ -3354,6 +3634,8 @@ pub(crate) fn classify_failure_message(error_msg: &str) -> &'static str {
         // sees the original class for all variants.
         if is_transient_network_error(trimmed) {
             error_class::TRANSIENT_NETWORK
+        } else if trimmed.contains(" budget exhausted: ") {
+            error_class::RUN_BUDGET_EXHAUSTED
         } else {
             error_class::RUNTIME_ERROR
         }
@@ -3736,16 +4018,6 @@ fn append_audit_event(
     )))
 }
 
-/// Compute a SHA-256 hash of a string for audit-event fields like
-/// `policy_hash` (sprint `0.4-S11`). Hex-encoded, lowercase.
-#[cfg(feature = "persist-sqlite")]
-fn sha256_hex(s: &str) -> String {
-    use sha2::{Digest, Sha256};
-    let mut hasher = Sha256::new();
-    hasher.update(s.as_bytes());
-    format!("{:x}", hasher.finalize())
-}
-
 /// Route a step output to inline storage or the blob store based on size.
 ///
 /// Sprint 0.5-S7. Returns `(output_json, output_blob_ref)`. Exactly one of
@@ -3764,7 +4036,7 @@ fn route_output(
     use crate::persistence::BLOB_THRESHOLD;
     match blob_store {
         Some(bs) if output_json.len() > BLOB_THRESHOLD => {
-            let hash = sha256_hex(&output_json);
+            let hash = boruna_hash::sha256_hex(&output_json);
             match bs.write(&hash, output_json.as_bytes()) {
                 Ok(()) => (None, Some(hash)),
                 Err(_) => (Some(output_json), None),
@@ -3825,6 +4097,94 @@ fn emit_step_terminal_audit(
     }
 }
 
+/// Whether an approval gate pauses the run. A gate with a `condition`
+/// pauses only when the condition — a [state query](boruna_framework::query)
+/// over the gate's resolved inputs, e.g. `severity >= 3` — is truthy. A
+/// gate without `inputs` has nothing to evaluate it against and always
+/// pauses. The in-process runners evaluate it; the coordinator's
+/// [`advance_run_one_tick`](WorkflowRunner::advance_run_one_tick) always
+/// opens the gate.
+fn gate_condition_holds(
+    step_id: &str,
+    step_def: &StepDef,
+    data_store: &DataStore,
+) -> Result<bool, WorkflowRunError> {
+    let StepKind::ApprovalGate {
+        condition: Some(condition),
+        ..
+    } = &step_def.kind
+    else {
+        return Ok(true);
+    };
+    if step_def.inputs.is_empty() {
+        return Ok(true);
+    }
+    let query = boruna_framework::Query::parse(condition)
+        .map_err(|e| WorkflowRunError::Validation(format!("step '{step_id}' condition: {e}")))?;
+    let inputs = data_store
+        .resolve_step_inputs(&step_def.inputs)
+        .map_err(|e| WorkflowRunError::StepFailed(step_id.to_string(), e))?;
+    let scope = serde_json::Value::Object(
+        inputs
+            .iter()
+            .map(|(name, v)| (name.clone(), boruna_framework::query::value_to_json(v, &[])))
+            .collect(),
+    );
+    Ok(query.matches(&scope))
+}
+
+/// Complete an approval gate whose condition is false, with the same
+/// empty-record output an approval gives it.
+fn pass_gate(step_id: &str, data_store: &mut DataStore) -> Result<StepResult, WorkflowRunError> {
+    let synthetic = boruna_bytecode::Value::Map(BTreeMap::new());
+    data_store
+        .store_output(step_id, "result", &synthetic)
+        .map_err(|e| WorkflowRunError::Io(e.to_string()))?;
+    eprintln!("Approval gate '{step_id}' passed: its condition is false");
+    Ok(StepResult {
+        step_id: step_id.to_string(),
+        status: StepStatus::Completed,
+        output_hash: Some(DataStore::hash_value(&synthetic)),
+        duration_ms: 0,
+        capabilities_used: vec![],
+        error: None,
+        attempt_count: 1,
+        event_log: None,
+    })
+}
+
+#[cfg(feature = "persist-sqlite")]
+fn persist_passed_gate(
+    store: &RunCheckpointStore,
+    run_id: &str,
+    sr: &StepResult,
+    data_store: &DataStore,
+) -> Result<(), WorkflowRunError> {
+    let output_json = WorkflowRunner::lookup_output_json(data_store, &sr.step_id, "result")?;
+    let (output_json, output_blob_ref) = match output_json {
+        Some(json) => route_output(json, store.blob_store()),
+        None => (None, None),
+    };
+    let now = now_unix_ms();
+    store
+        .upsert_step_checkpoint(&StepCheckpoint {
+            run_id: run_id.to_string(),
+            step_id: sr.step_id.clone(),
+            status: PersistStepStatus::Completed,
+            output_json,
+            output_hash: sr.output_hash.clone(),
+            started_at_ms: Some(now),
+            ended_at_ms: Some(now),
+            error_msg: None,
+            attempt_count: 1,
+            worker_id: None,
+            lease_expires_at_ms: None,
+            claim_id: 0,
+            output_blob_ref,
+        })
+        .map_err(WorkflowRunError::from)
+}
+
 /// Persist a single pause-step's checkpoint and (for triggers) its
 /// token, printing the operator-facing pause message (sprint `0.4-S7`).
 /// Returns the corresponding `StepStatus` on success so the caller can
@@ -4615,19 +4975,31 @@ pub fn create_bundle(
     // directly would silently omit large outputs from the evidence
     // bundle, which is a compliance regression (the bundle would verify
     // but be incomplete).
+    //
+    // Outputs whose classification label reaches the policy's
+    // `data_flow.redact_in_evidence` are replaced by a placeholder that
+    // keeps only the label and the output hash.
+    let policy: Option<Policy> = serde_json::from_str(&run.policy_json).ok();
+    let data_flow = policy.and_then(|p| p.data_flow);
+    let labels = match (&data_flow, WorkflowDef::from_json(&workflow_json)) {
+        (Some(_), Ok(def)) => crate::workflow::data_flow::propagate_classifications(&def),
+        _ => BTreeMap::new(),
+    };
     for cp in &checkpoints {
         if let Some(output_json) = store
             .read_step_output(run_id, &cp.step_id)
             .map_err(WorkflowRunError::from)?
         {
-            builder
-                .add_step_output(&cp.step_id, "result", &output_json)
-                .map_err(|e| {
-                    WorkflowRunError::Io(format!(
-                        "bundle add_step_output for '{}': {e}",
-                        cp.step_id
-                    ))
-                })?;
+            let label = labels.get(&cp.step_id).copied().unwrap_or_default();
+            let added = if data_flow.as_ref().is_some_and(|df| df.redacts(label)) {
+                let output_hash = cp.output_hash.clone().unwrap_or_default();
+                builder.add_redacted_step_output(&cp.step_id, "result", label.name(), &output_hash)
+            } else {
+                builder.add_step_output(&cp.step_id, "result", &output_json)
+            };
+            added.map_err(|e| {
+                WorkflowRunError::Io(format!("bundle add_step_output for '{}': {e}", cp.step_id))
+            })?;
         }
     }
 
@@ -4645,7 +5017,11 @@ pub fn create_bundle(
     // propagated up the call graph). `step_sources` is a BTreeMap, so this
     // list is built in sorted order → deterministic bytes.
     let mut model_invoking_steps: Vec<String> = Vec::new();
+    // Compiler and source digest per step, so the bundle names the exact
+    // source each output came from without shipping the source itself.
+    let mut provenance = BTreeMap::new();
     for (step_id, source) in &metadata.step_sources {
+        provenance.insert(step_id.clone(), boruna_compiler::provenance(source));
         if let Ok(module) = boruna_compiler::compile(step_id, source) {
             if let Some(intent) = module
                 .functions
@@ -4666,6 +5042,9 @@ pub fn create_bundle(
     builder
         .add_model_invocations(&model_invoking_steps)
         .map_err(|e| WorkflowRunError::Io(format!("bundle add_model_invocations: {e}")))?;
+    builder
+        .add_provenance(&provenance)
+        .map_err(|e| WorkflowRunError::Io(format!("bundle add_provenance: {e}")))?;
 
     // Hash-chained audit log from metadata. We verify the chain at
     // bundle-creation time so that direct sqlite3 tamper of
@@ -4737,6 +5116,16 @@ pub enum WorkflowRunError {
     StepFailed(String, String),
     Io(String),
     Internal(String),
+    /// A step input or output did not match its declared schema.
+    SchemaViolation(crate::workflow::schema::SchemaViolation),
+    /// A step received data labelled above what the policy's
+    /// `data_flow.max_classification` allows for one of its capabilities.
+    DataFlowViolation {
+        step_id: String,
+        capability: String,
+        classification: Classification,
+        max: Classification,
+    },
     /// Resume target run_id does not exist in the store. Surfaced from
     /// `WorkflowRunner::resume`. Aligned with project-conventions §1: a
     /// typo'd run_id MUST surface as a typed error rather than silent no-op.
@@ -4852,6 +5241,17 @@ impl std::fmt::Display for WorkflowRunError {
             Self::StepFailed(step, msg) => write!(f, "step '{step}' failed: {msg}"),
             Self::Io(msg) => write!(f, "IO error: {msg}"),
             Self::Internal(msg) => write!(f, "internal error: {msg}"),
+            Self::SchemaViolation(v) => write!(f, "schema violation: {v}"),
+            Self::DataFlowViolation {
+                step_id,
+                capability,
+                classification,
+                max,
+            } => write!(
+                f,
+                "data flow violation: step '{step_id}' receives {classification} data \
+                 but may reach '{capability}', which the policy limits to {max}"
+            ),
             #[cfg(feature = "persist-sqlite")]
             Self::RunNotFound(run_id) => write!(f, "run not found: '{run_id}'"),
             #[cfg(feature = "persist-sqlite")]
@@ -4935,6 +5335,114 @@ impl std::fmt::Display for WorkflowRunError {
     }
 }
 
+impl std::error::Error for WorkflowRunError {}
+
+impl boruna_errors::BorunaError for WorkflowRunError {
+    fn error_code(&self) -> &'static boruna_errors::ErrorCode {
+        use boruna_errors as codes;
+        match self {
+            Self::Validation(_) => &codes::WORKFLOW_VALIDATION,
+            Self::StepFailed(..) => &codes::WORKFLOW_STEP_FAILED,
+            Self::Io(_) => &codes::WORKFLOW_IO,
+            Self::Internal(_) => &codes::WORKFLOW_INTERNAL,
+            Self::SchemaViolation(_) => &codes::WORKFLOW_SCHEMA_VIOLATION,
+            Self::DataFlowViolation { .. } => &codes::WORKFLOW_DATA_FLOW_VIOLATION,
+            #[cfg(feature = "persist-sqlite")]
+            Self::RunNotFound(_) => &codes::WORKFLOW_RUN_NOT_FOUND,
+            #[cfg(feature = "persist-sqlite")]
+            Self::WorkflowHashMismatch { .. } => &codes::WORKFLOW_HASH_MISMATCH,
+            #[cfg(feature = "persist-sqlite")]
+            Self::Persistence(_) => &codes::WORKFLOW_PERSISTENCE,
+            #[cfg(feature = "persist-sqlite")]
+            Self::StepNotFound { .. } => &codes::WORKFLOW_STEP_NOT_FOUND,
+            #[cfg(feature = "persist-sqlite")]
+            Self::StepNotAtApprovalGate { .. } => &codes::WORKFLOW_STEP_NOT_AT_APPROVAL_GATE,
+            #[cfg(feature = "persist-sqlite")]
+            Self::StepAlreadyDecided { .. } => &codes::WORKFLOW_STEP_ALREADY_DECIDED,
+            #[cfg(feature = "persist-sqlite")]
+            Self::NotAnApprovalGateStep { .. } => &codes::WORKFLOW_NOT_AN_APPROVAL_GATE,
+            #[cfg(feature = "persist-sqlite")]
+            Self::RunNotResumable { .. } => &codes::WORKFLOW_RUN_NOT_RESUMABLE,
+            #[cfg(feature = "persist-sqlite")]
+            Self::NotAnExternalTriggerStep { .. } => &codes::WORKFLOW_NOT_AN_EXTERNAL_TRIGGER,
+            #[cfg(feature = "persist-sqlite")]
+            Self::StepNotAtExternalTriggerGate { .. } => {
+                &codes::WORKFLOW_STEP_NOT_AT_EXTERNAL_TRIGGER
+            }
+            #[cfg(feature = "persist-sqlite")]
+            Self::InvalidTriggerToken { .. } => &codes::WORKFLOW_INVALID_TRIGGER_TOKEN,
+            #[cfg(feature = "persist-sqlite")]
+            Self::StepAlreadyTriggered { .. } => &codes::WORKFLOW_STEP_ALREADY_TRIGGERED,
+        }
+    }
+
+    fn error_data(&self) -> serde_json::Value {
+        use serde_json::json;
+        match self {
+            Self::StepFailed(step_id, _) => json!({ "step_id": step_id }),
+            Self::DataFlowViolation {
+                step_id,
+                capability,
+                classification,
+                max,
+            } => json!({
+                "step_id": step_id,
+                "capability": capability,
+                "classification": classification.name(),
+                "max": max.name(),
+            }),
+            #[cfg(feature = "persist-sqlite")]
+            Self::RunNotFound(run_id) => json!({ "run_id": run_id }),
+            #[cfg(feature = "persist-sqlite")]
+            Self::WorkflowHashMismatch {
+                run_id,
+                expected,
+                actual,
+            } => json!({ "run_id": run_id, "expected": expected, "actual": actual }),
+            #[cfg(feature = "persist-sqlite")]
+            Self::StepNotFound { run_id, step_id }
+            | Self::NotAnApprovalGateStep { run_id, step_id }
+            | Self::NotAnExternalTriggerStep { run_id, step_id }
+            | Self::InvalidTriggerToken { run_id, step_id } => {
+                json!({ "run_id": run_id, "step_id": step_id })
+            }
+            #[cfg(feature = "persist-sqlite")]
+            Self::StepNotAtApprovalGate {
+                run_id,
+                step_id,
+                current_status,
+            }
+            | Self::StepNotAtExternalTriggerGate {
+                run_id,
+                step_id,
+                current_status,
+            } => json!({ "run_id": run_id, "step_id": step_id, "current_status": current_status }),
+            #[cfg(feature = "persist-sqlite")]
+            Self::StepAlreadyDecided {
+                run_id,
+                step_id,
+                prior_decision,
+            } => json!({ "run_id": run_id, "step_id": step_id, "prior_decision": prior_decision }),
+            #[cfg(feature = "persist-sqlite")]
+            Self::RunNotResumable {
+                run_id,
+                terminal_status,
+            } => json!({ "run_id": run_id, "terminal_status": terminal_status }),
+            #[cfg(feature = "persist-sqlite")]
+            Self::StepAlreadyTriggered {
+                run_id,
+                step_id,
+                prior_triggered_at_ms,
+            } => json!({
+                "run_id": run_id,
+                "step_id": step_id,
+                "prior_triggered_at_ms": prior_triggered_at_ms,
+            }),
+            _ => serde_json::Value::Null,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -4964,6 +5472,10 @@ mod tests {
                     retry: None,
                     budget: None,
                     required_capability_versions: Default::default(),
+                    input_schema: Default::default(),
+                    output_schema: None,
+                    classification: None,
+                    isolation: Default::default(),
                 },
             );
 
@@ -4980,6 +5492,8 @@ mod tests {
             description: "test".into(),
             steps,
             edges,
+            budget: None,
+            params: Default::default(),
         };
 
         (def, dir)
@@ -5001,6 +5515,9 @@ mod tests {
             live: false,
             concurrency: 1,
             submit_only: true,
+            params: Default::default(),
+            profile: None,
+            container: None,
         };
         let result = WorkflowRunner::run_persistent(&def, &options, data_dir.path()).unwrap();
         // Submit-only returns an in-flight result.
@@ -5030,6 +5547,9 @@ mod tests {
             live: false,
             concurrency: 1,
             submit_only: true,
+            params: Default::default(),
+            profile: None,
+            container: None,
         };
         let result = WorkflowRunner::run_persistent(&def, &options, data_dir.path()).unwrap();
         let store =
@@ -5088,6 +5608,10 @@ mod tests {
                     retry: None,
                     budget: None,
                     required_capability_versions: Default::default(),
+                    input_schema: Default::default(),
+                    output_schema: None,
+                    classification: None,
+                    isolation: Default::default(),
                 },
             );
         }
@@ -5104,6 +5628,8 @@ mod tests {
             description: "fan-in test".into(),
             steps,
             edges,
+            budget: None,
+            params: Default::default(),
         };
         (def, dir)
     }
@@ -5183,6 +5709,10 @@ mod tests {
                     retry: None,
                     budget: None,
                     required_capability_versions: Default::default(),
+                    input_schema: Default::default(),
+                    output_schema: None,
+                    classification: None,
+                    isolation: Default::default(),
                 },
             );
         }
@@ -5193,6 +5723,8 @@ mod tests {
             description: String::new(),
             steps,
             edges: vec![],
+            budget: None,
+            params: Default::default(),
         };
         let status_map = BTreeMap::new();
         let ready = WorkflowRunner::compute_ready_steps(&def, &status_map);
@@ -5210,6 +5742,9 @@ mod tests {
             live: false,
             concurrency: 1,
             submit_only: true,
+            params: Default::default(),
+            profile: None,
+            container: None,
         };
         let result = WorkflowRunner::run_persistent(&def, &options, data_dir.path()).unwrap();
         let store =
@@ -5252,6 +5787,9 @@ mod tests {
             live: false,
             concurrency: 1,
             submit_only: true,
+            params: Default::default(),
+            profile: None,
+            container: None,
         };
         let result = WorkflowRunner::run_persistent(&def, &options, data_dir.path()).unwrap();
         let store =
@@ -5278,6 +5816,9 @@ mod tests {
             live: false,
             concurrency: 1,
             submit_only: true,
+            params: Default::default(),
+            profile: None,
+            container: None,
         };
         let result = WorkflowRunner::run_persistent(&def, &options, data_dir.path()).unwrap();
         let store =
@@ -5311,6 +5852,9 @@ mod tests {
             live: false,
             concurrency: 1,
             submit_only: true,
+            params: Default::default(),
+            profile: None,
+            container: None,
         };
         let result = WorkflowRunner::run_persistent(&def, &options, data_dir.path()).unwrap();
         let store =
@@ -5356,6 +5900,9 @@ mod tests {
                 live: false,
                 concurrency: 1,
                 submit_only: true,
+                params: Default::default(),
+                profile: None,
+                container: None,
             },
             data_dir.path(),
         )
@@ -5401,6 +5948,9 @@ mod tests {
                 live: false,
                 concurrency: 1,
                 submit_only: true,
+                params: Default::default(),
+                profile: None,
+                container: None,
             },
             data_dir.path(),
         )
@@ -5455,6 +6005,9 @@ mod tests {
                 live: false,
                 concurrency: 1,
                 submit_only: true,
+                params: Default::default(),
+                profile: None,
+                container: None,
             },
             data_dir.path(),
         )
@@ -5503,6 +6056,9 @@ mod tests {
             live: false,
             concurrency: 1,
             submit_only: true,
+            params: Default::default(),
+            profile: None,
+            container: None,
         };
         let result = WorkflowRunner::run_persistent(&def, &options, data_dir.path()).unwrap();
         let store =
@@ -5546,6 +6102,10 @@ mod tests {
                 retry,
                 budget: None,
                 required_capability_versions: Default::default(),
+                input_schema: Default::default(),
+                output_schema: None,
+                classification: None,
+                isolation: Default::default(),
             },
         );
         let def = WorkflowDef {
@@ -5555,6 +6115,8 @@ mod tests {
             description: "retry-test".into(),
             steps,
             edges: vec![],
+            budget: None,
+            params: Default::default(),
         };
         (def, dir)
     }
@@ -5574,6 +6136,9 @@ mod tests {
             live: false,
             concurrency: 1,
             submit_only: true,
+            params: Default::default(),
+            profile: None,
+            container: None,
         };
         let result = WorkflowRunner::run_persistent(def, &options, data_dir.path()).unwrap();
         // Reopen the store rather than let the tempdir drop. We
@@ -5718,6 +6283,12 @@ mod tests {
             classify_failure_message("runtime: assertion failed"),
             error_class::RUNTIME_ERROR,
         );
+        assert_eq!(
+            classify_failure_message(
+                "runtime: step 'fetch' budget exhausted: 9 steps used + 5 requested > limit 10"
+            ),
+            error_class::RUN_BUDGET_EXHAUSTED,
+        );
         assert_eq!(
             classify_failure_message("policy parse: invalid JSON"),
             error_class::RUNTIME_ERROR,
@@ -5805,6 +6376,9 @@ mod tests {
             live: false,
             concurrency: 1,
             submit_only: true,
+            params: Default::default(),
+            profile: None,
+            container: None,
         };
         let result = WorkflowRunner::run_persistent(&def, &options, data_dir.path()).unwrap();
         let store =
@@ -5832,6 +6406,9 @@ mod tests {
             live: false,
             concurrency: 1,
             submit_only: false,
+            params: Default::default(),
+            profile: None,
+            container: None,
         };
         let result = WorkflowRunner::run_persistent(&def, &options, data_dir.path()).unwrap();
         let store =
@@ -5870,6 +6447,10 @@ mod tests {
                 retry: None,
                 budget: None,
                 required_capability_versions: Default::default(),
+                input_schema: Default::default(),
+                output_schema: None,
+                classification: None,
+                isolation: Default::default(),
             },
         );
         let def = WorkflowDef {
@@ -5879,6 +6460,8 @@ mod tests {
             description: "x".repeat(2 * 1024 * 1024), // 2 MiB description
             steps,
             edges: vec![],
+            budget: None,
+            params: Default::default(),
         };
         let data_dir = tempfile::tempdir().unwrap();
         let options = RunOptions {
@@ -5888,6 +6471,9 @@ mod tests {
             live: false,
             concurrency: 1,
             submit_only: true,
+            params: Default::default(),
+            profile: None,
+            container: None,
         };
         let err = WorkflowRunner::run_persistent(&def, &options, data_dir.path())
             .expect_err("expected oversize rejection");
@@ -5909,6 +6495,9 @@ mod tests {
             live: false,
             concurrency: 1,
             submit_only: false, // does NOT embed workflow_def
+            params: Default::default(),
+            profile: None,
+            container: None,
         };
         let result = WorkflowRunner::run_persistent(&def, &options, data_dir.path()).unwrap();
         let store =
@@ -5935,6 +6524,9 @@ mod tests {
             live: false,
             concurrency: 4, // triggers the warning
             submit_only: true,
+            params: Default::default(),
+            profile: None,
+            container: None,
         };
         let result =
             WorkflowRunner::run_persistent(&def, &options, data_dir.path()).expect("submit ok");
@@ -5955,6 +6547,9 @@ mod tests {
             live: false,
             concurrency: 1,
             submit_only: true,
+            params: Default::default(),
+            profile: None,
+            container: None,
         };
         let result = WorkflowRunner::run_persistent(&def, &options, data_dir.path()).unwrap();
         let store =
@@ -6004,6 +6599,9 @@ mod tests {
             live: false,
             concurrency: 1,
             submit_only: true,
+            params: Default::default(),
+            profile: None,
+            container: None,
         };
         let result = WorkflowRunner::run_persistent(&def, &options, data_dir.path()).unwrap();
         let store =
@@ -6041,6 +6639,9 @@ mod tests {
             live: false,
             concurrency: 1,
             submit_only: true,
+            params: Default::default(),
+            profile: None,
+            container: None,
         };
         let result = WorkflowRunner::run_persistent(&def, &options, data_dir.path()).unwrap();
         let store =
@@ -6089,6 +6690,9 @@ mod tests {
             live: false,
             concurrency: 1,
             submit_only: true,
+            params: Default::default(),
+            profile: None,
+            container: None,
         };
         let err = WorkflowRunner::run_persistent(&mutated, &options, data_dir.path()).unwrap_err();
         let msg = format!("{err}");
@@ -6111,6 +6715,9 @@ mod tests {
             live: false,
             concurrency: 1,
             submit_only: false,
+            params: Default::default(),
+            profile: None,
+            container: None,
         };
 
         let result = WorkflowRunner::run(&def, &options).unwrap();
@@ -6135,6 +6742,9 @@ mod tests {
             live: false,
             concurrency: 1,
             submit_only: false,
+            params: Default::default(),
+            profile: None,
+            container: None,
         };
 
         let result = WorkflowRunner::run(&def, &options).unwrap();
@@ -6143,6 +6753,147 @@ mod tests {
         assert_eq!(result.step_results["bad"].status, StepStatus::Failed);
     }
 
+    const COUNT_DOWN: &str = "fn count(n: Int) -> Int {\n\
+                                if n == 0 { 0 } else { count(n - 1) }\n\
+                              }\n\
+                              fn main() -> Int { count(100) }\n";
+
+    fn budget_options(dir: &tempfile::TempDir) -> RunOptions {
+        RunOptions {
+            policy: Some(Policy::allow_all()),
+            record: false,
+            workflow_dir: dir.path().to_string_lossy().to_string(),
+            live: false,
+            concurrency: 1,
+            submit_only: false,
+            params: Default::default(),
+            profile: None,
+            container: None,
+        }
+    }
+
+    #[test]
+    fn test_run_budget_is_shared_across_steps() {
+        let (mut def, dir) = make_workflow_with_steps(&[
+            ("first", "fn main() -> Int { 1 }"),
+            ("second", COUNT_DOWN),
+        ]);
+        def.budget = Some(boruna_budget::Budget {
+            max_steps: Some(50),
+            ..Default::default()
+        });
+
+        let result = WorkflowRunner::run(&def, &budget_options(&dir)).unwrap();
+        assert_eq!(result.status, WorkflowStatus::Failed);
+        assert_eq!(result.step_results["first"].status, StepStatus::Completed);
+        let error = result.step_results["second"].error.clone().unwrap();
+        assert!(error.contains("run budget exhausted"), "{error}");
+    }
+
+    #[test]
+    fn test_step_budget_fails_only_that_step() {
+        let (mut def, dir) = make_workflow_with_steps(&[("work", COUNT_DOWN)]);
+        def.steps.get_mut("work").unwrap().budget = Some(StepBudget {
+            max_tokens: None,
+            max_calls: None,
+            max_steps: Some(20),
+            max_bytes: None,
+        });
+        def.budget = Some(boruna_budget::Budget {
+            max_steps: Some(1_000_000),
+            ..Default::default()
+        });
+
+        let result = WorkflowRunner::run(&def, &budget_options(&dir)).unwrap();
+        assert_eq!(result.status, WorkflowStatus::Failed);
+        let error = result.step_results["work"].error.clone().unwrap();
+        assert!(error.contains("step 'work' budget exhausted"), "{error}");
+        assert!(error.contains("steps"), "{error}");
+    }
+
+    /// Runs each job the way `exec-step` does inside the container, but
+    /// in this process, and records the specs it was given.
+    #[derive(Debug, Default)]
+    struct InProcessBackend {
+        specs: std::sync::Mutex<Vec<crate::workflow::container::ContainerSpec>>,
+    }
+
+    impl ContainerBackend for InProcessBackend {
+        fn run(
+            &self,
+            spec: &crate::workflow::container::ContainerSpec,
+        ) -> Result<(), crate::workflow::container::ContainerRunError> {
+            use crate::workflow::container::{
+                exec_job_file, ContainerRunError, JOB_FILE, RESULT_FILE,
+            };
+            self.specs.lock().unwrap().push(spec.clone());
+            let job_dir = &spec.mounts[0].host;
+            exec_job_file(&job_dir.join(JOB_FILE), &job_dir.join(RESULT_FILE))
+                .map_err(ContainerRunError::Failed)
+        }
+    }
+
+    #[test]
+    fn test_container_step_matches_in_process_run() {
+        let (mut def, dir) = make_workflow_with_steps(&[
+            ("first", "fn main() -> Int { 1 }"),
+            ("second", COUNT_DOWN),
+        ]);
+        let in_process = WorkflowRunner::run(&def, &budget_options(&dir)).unwrap();
+        assert_eq!(in_process.status, WorkflowStatus::Completed);
+
+        def.steps.get_mut("second").unwrap().isolation = StepIsolation::Container;
+        let backend = Arc::new(InProcessBackend::default());
+        let options = RunOptions {
+            container: Some(backend.clone()),
+            ..budget_options(&dir)
+        };
+        let isolated = WorkflowRunner::run(&def, &options).unwrap();
+        assert_eq!(isolated.status, WorkflowStatus::Completed);
+        for id in ["first", "second"] {
+            assert_eq!(
+                isolated.step_results[id].output_hash,
+                in_process.step_results[id].output_hash
+            );
+        }
+        let specs = backend.specs.lock().unwrap();
+        assert_eq!(specs.len(), 1);
+        assert_eq!(specs[0].step_id, "second");
+    }
+
+    #[test]
+    fn test_container_step_draws_on_the_run_budget() {
+        let (mut def, dir) = make_workflow_with_steps(&[
+            ("first", "fn main() -> Int { 1 }"),
+            ("second", COUNT_DOWN),
+        ]);
+        def.steps.get_mut("second").unwrap().isolation = StepIsolation::Container;
+        def.budget = Some(boruna_budget::Budget {
+            max_steps: Some(50),
+            ..Default::default()
+        });
+        let options = RunOptions {
+            container: Some(Arc::new(InProcessBackend::default())),
+            ..budget_options(&dir)
+        };
+
+        let result = WorkflowRunner::run(&def, &options).unwrap();
+        assert_eq!(result.status, WorkflowStatus::Failed);
+        let error = result.step_results["second"].error.clone().unwrap();
+        assert!(error.contains("run budget exhausted"), "{error}");
+    }
+
+    #[test]
+    fn test_container_step_without_backend_fails() {
+        let (mut def, dir) = make_workflow_with_steps(&[("work", "fn main() -> Int { 1 }")]);
+        def.steps.get_mut("work").unwrap().isolation = StepIsolation::Container;
+
+        let result = WorkflowRunner::run(&def, &budget_options(&dir)).unwrap();
+        assert_eq!(result.status, WorkflowStatus::Failed);
+        let error = result.step_results["work"].error.clone().unwrap();
+        assert!(error.contains("no container backend"), "{error}");
+    }
+
     #[test]
     fn test_run_with_policy_deny() {
         let dir = tempfile::tempdir().unwrap();
@@ -6173,9 +6924,15 @@ mod tests {
                     retry: None,
                     budget: None,
                     required_capability_versions: Default::default(),
+                    input_schema: Default::default(),
+                    output_schema: None,
+                    classification: None,
+                    isolation: Default::default(),
                 },
             )]),
             edges: vec![],
+            budget: None,
+            params: Default::default(),
         };
 
         let options = RunOptions {
@@ -6185,6 +6942,9 @@ mod tests {
             live: false,
             concurrency: 1,
             submit_only: false,
+            params: Default::default(),
+            profile: None,
+            container: None,
         };
 
         // With allow_all, should succeed
@@ -6219,6 +6979,10 @@ mod tests {
                         retry: None,
                         budget: None,
                         required_capability_versions: Default::default(),
+                        input_schema: Default::default(),
+                        output_schema: None,
+                        classification: None,
+                        isolation: Default::default(),
                     },
                 ),
                 (
@@ -6236,6 +7000,10 @@ mod tests {
                         retry: None,
                         budget: None,
                         required_capability_versions: Default::default(),
+                        input_schema: Default::default(),
+                        output_schema: None,
+                        classification: None,
+                        isolation: Default::default(),
                     },
                 ),
                 (
@@ -6252,10 +7020,16 @@ mod tests {
                         retry: None,
                         budget: None,
                         required_capability_versions: Default::default(),
+                        input_schema: Default::default(),
+                        output_schema: None,
+                        classification: None,
+                        isolation: Default::default(),
                     },
                 ),
             ]),
             edges: vec![],
+            budget: None,
+            params: Default::default(),
         };
 
         let options = RunOptions {
@@ -6265,6 +7039,9 @@ mod tests {
             live: false,
             concurrency: 1,
             submit_only: false,
+            params: Default::default(),
+            profile: None,
+            container: None,
         };
 
         let result = WorkflowRunner::run(&def, &options).unwrap();
@@ -6287,6 +7064,8 @@ mod tests {
             description: String::new(),
             steps: BTreeMap::new(),
             edges: vec![],
+            budget: None,
+            params: Default::default(),
         };
         let options = RunOptions {
             policy: Some(Policy::allow_all()),
@@ -6295,6 +7074,9 @@ mod tests {
             live: false,
             concurrency: 1,
             submit_only: false,
+            params: Default::default(),
+            profile: None,
+            container: None,
         };
         assert!(WorkflowRunner::run(&def, &options).is_err());
     }
@@ -6774,6 +7556,10 @@ mod tests {
                 }),
                 budget: None,
                 required_capability_versions: Default::default(),
+                input_schema: Default::default(),
+                output_schema: None,
+                classification: None,
+                isolation: Default::default(),
             };
             bad.inputs.clear();
             let def = WorkflowDef {
@@ -6783,6 +7569,8 @@ mod tests {
                 description: String::new(),
                 steps: BTreeMap::from([("bad".into(), bad)]),
                 edges: vec![],
+                budget: None,
+                params: Default::default(),
             };
             let options = RunOptions {
                 policy: Some(Policy::allow_all()),
@@ -6791,6 +7579,9 @@ mod tests {
                 live: false,
                 concurrency: 1,
                 submit_only: false,
+                params: Default::default(),
+                profile: None,
+                container: None,
             };
             let result = WorkflowRunner::run(&def, &options).unwrap();
             assert_eq!(result.status, WorkflowStatus::Failed);
@@ -6842,6 +7633,9 @@ mod tests {
                 live: false,
                 concurrency: 1,
                 submit_only: false,
+                params: Default::default(),
+                profile: None,
+                container: None,
             };
 
             let result = WorkflowRunner::run_persistent(&def, &options, data_dir.path()).unwrap();
@@ -6870,17 +7664,15 @@ mod tests {
                 live: false,
                 concurrency: 1,
                 submit_only: false,
+                params: Default::default(),
+                profile: None,
+                container: None,
             };
             let r1 = WorkflowRunner::run_persistent(&def, &options, data_dir.path()).unwrap();
             let r2 = WorkflowRunner::run_persistent(&def, &options, data_dir.path()).unwrap();
             assert_ne!(r1.run_id, r2.run_id);
             let workflow_hash = WorkflowRunner::workflow_hash_from_def(&def);
-            let inputs_hash = {
-                use sha2::{Digest, Sha256};
-                let mut h = Sha256::new();
-                h.update(b"{}");
-                format!("{:x}", h.finalize())
-            };
+            let inputs_hash = { boruna_hash::sha256_hex(b"{}") };
             assert_eq!(r1.run_id, derive_run_id(&workflow_hash, &inputs_hash, 0));
             assert_eq!(r2.run_id, derive_run_id(&workflow_hash, &inputs_hash, 1));
         }
@@ -6920,6 +7712,9 @@ mod tests {
                 live: false,
                 concurrency: 1,
                 submit_only: false,
+                params: Default::default(),
+                profile: None,
+                container: None,
             };
             let result = WorkflowRunner::run_persistent(&def, &options, data_dir.path()).unwrap();
             assert_eq!(result.status, WorkflowStatus::Completed);
@@ -6951,6 +7746,9 @@ mod tests {
                 live: false,
                 concurrency: 1,
                 submit_only: false,
+                params: Default::default(),
+                profile: None,
+                container: None,
             };
 
             // Insert a run row with a deliberately-altered workflow_hash
@@ -7023,12 +7821,7 @@ mod tests {
             // (it'd match the real value 100). If resume restores from
             // the store, the sentinel persists.
             let workflow_hash = WorkflowRunner::workflow_hash_from_def(&def);
-            let inputs_hash = {
-                use sha2::{Digest, Sha256};
-                let mut h = Sha256::new();
-                h.update(b"{}");
-                format!("{:x}", h.finalize())
-            };
+            let inputs_hash = { boruna_hash::sha256_hex(b"{}") };
             let run_id = derive_run_id(&workflow_hash, &inputs_hash, 0);
 
             let metadata = serde_json::json!({
@@ -7118,12 +7911,7 @@ mod tests {
             ]);
             let data_dir = tempfile::tempdir().unwrap();
             let workflow_hash = WorkflowRunner::workflow_hash_from_def(&def);
-            let inputs_hash = {
-                use sha2::{Digest, Sha256};
-                let mut h = Sha256::new();
-                h.update(b"{}");
-                format!("{:x}", h.finalize())
-            };
+            let inputs_hash = { boruna_hash::sha256_hex(b"{}") };
             let run_id = derive_run_id(&workflow_hash, &inputs_hash, 0);
             let metadata = serde_json::json!({
                 "workflow_dir": wf_dir.path().to_string_lossy(),
@@ -7206,12 +7994,7 @@ mod tests {
             ]);
             let data_dir = tempfile::tempdir().unwrap();
             let workflow_hash = WorkflowRunner::workflow_hash_from_def(&def);
-            let inputs_hash = {
-                use sha2::{Digest, Sha256};
-                let mut h = Sha256::new();
-                h.update(b"{}");
-                format!("{:x}", h.finalize())
-            };
+            let inputs_hash = { boruna_hash::sha256_hex(b"{}") };
             let run_id = derive_run_id(&workflow_hash, &inputs_hash, 0);
             let metadata = serde_json::json!({
                 "workflow_dir": wf_dir.path().to_string_lossy(),
@@ -7319,9 +8102,15 @@ mod tests {
                         retry: None,
                         budget: None,
                         required_capability_versions: Default::default(),
+                        input_schema: Default::default(),
+                        output_schema: None,
+                        classification: None,
+                        isolation: Default::default(),
                     },
                 )]),
                 edges: vec![],
+                budget: None,
+                params: Default::default(),
             };
             let json = serde_json::to_string_pretty(&def).unwrap();
             std::fs::write(dir.path().join("workflow.json"), &json).unwrap();
@@ -7337,6 +8126,9 @@ mod tests {
                     live: false,
                     concurrency: 1,
                     submit_only: false,
+                    params: Default::default(),
+                    profile: None,
+                    container: None,
                 },
                 data_dir.path(),
             )
@@ -7432,6 +8224,9 @@ mod tests {
                 live: false,
                 concurrency: 1,
                 submit_only: false,
+                params: Default::default(),
+                profile: None,
+                container: None,
             };
             let err = WorkflowRunner::run_persistent(&def, &options, Path::new("/"))
                 .expect_err("must reject /");
@@ -7469,6 +8264,10 @@ mod tests {
                 retry: None,
                 budget: None,
                 required_capability_versions: Default::default(),
+                input_schema: Default::default(),
+                output_schema: None,
+                classification: None,
+                isolation: Default::default(),
             };
             let def = WorkflowDef {
                 schema_version: 1,
@@ -7489,6 +8288,8 @@ mod tests {
                     ),
                 ]),
                 edges: vec![],
+                budget: None,
+                params: Default::default(),
             };
             let json = serde_json::to_string_pretty(&def).unwrap();
             std::fs::write(dir.path().join("workflow.json"), &json).unwrap();
@@ -7510,6 +8311,9 @@ mod tests {
                 live: false,
                 concurrency: c,
                 submit_only: false,
+                params: Default::default(),
+                profile: None,
+                container: None,
             };
             let dir1 = tempfile::tempdir().unwrap();
             let r1 = WorkflowRunner::run_persistent(&def, &make_options(1), dir1.path()).unwrap();
@@ -7543,6 +8347,9 @@ mod tests {
                 live: false,
                 concurrency: 4,
                 submit_only: false,
+                params: Default::default(),
+                profile: None,
+                container: None,
             };
             let result = WorkflowRunner::run_persistent(&def, &options, data_dir.path()).unwrap();
             assert_eq!(result.status, WorkflowStatus::Completed);
@@ -7578,6 +8385,10 @@ mod tests {
                 retry: None,
                 budget: None,
                 required_capability_versions: Default::default(),
+                input_schema: Default::default(),
+                output_schema: None,
+                classification: None,
+                isolation: Default::default(),
             };
             let def = WorkflowDef {
                 schema_version: 1,
@@ -7590,6 +8401,8 @@ mod tests {
                     ("bad".into(), mk("bad")),
                 ]),
                 edges: vec![],
+                budget: None,
+                params: Default::default(),
             };
             let json = serde_json::to_string_pretty(&def).unwrap();
             std::fs::write(dir.path().join("workflow.json"), &json).unwrap();
@@ -7602,6 +8415,9 @@ mod tests {
                 live: false,
                 concurrency: 4,
                 submit_only: false,
+                params: Default::default(),
+                profile: None,
+                container: None,
             };
             let result = WorkflowRunner::run_persistent(&def, &options, data_dir.path()).unwrap();
             assert_eq!(result.status, WorkflowStatus::Failed);
@@ -7625,6 +8441,9 @@ mod tests {
                 live: false,
                 concurrency: 1,
                 submit_only: false,
+                params: Default::default(),
+                profile: None,
+                container: None,
             };
             let r1 = WorkflowRunner::run_persistent(&def, &options, data_dir.path()).unwrap();
             assert_eq!(r1.status, WorkflowStatus::Completed);
@@ -7681,6 +8500,10 @@ mod tests {
                 retry: None,
                 budget: None,
                 required_capability_versions: Default::default(),
+                input_schema: Default::default(),
+                output_schema: None,
+                classification: None,
+                isolation: Default::default(),
             };
             bad.inputs.insert("missing".into(), "ghost.result".into());
             // We need to bypass workflow validation (which would reject
@@ -7714,6 +8537,10 @@ mod tests {
                 retry: None,
                 budget: None,
                 required_capability_versions: Default::default(),
+                input_schema: Default::default(),
+                output_schema: None,
+                classification: None,
+                isolation: Default::default(),
             };
             // Add a third step at level 1, sibling of bad_input, that
             // shares the same input-failure pattern OR depends on
@@ -7732,6 +8559,10 @@ mod tests {
                 retry: None,
                 budget: None,
                 required_capability_versions: Default::default(),
+                input_schema: Default::default(),
+                output_schema: None,
+                classification: None,
+                isolation: Default::default(),
             };
             sibling.inputs.clear();
 
@@ -7746,6 +8577,8 @@ mod tests {
                     ("sibling".into(), sibling),
                 ]),
                 edges: vec![],
+                budget: None,
+                params: Default::default(),
             };
             let json = serde_json::to_string_pretty(&def).unwrap();
             std::fs::write(dir.path().join("workflow.json"), &json).unwrap();
@@ -7760,6 +8593,9 @@ mod tests {
                     live: false,
                     concurrency: 4,
                     submit_only: false,
+                    params: Default::default(),
+                    profile: None,
+                    container: None,
                 },
                 data_dir.path(),
             )
@@ -7818,6 +8654,10 @@ mod tests {
                 }),
                 budget: None,
                 required_capability_versions: Default::default(),
+                input_schema: Default::default(),
+                output_schema: None,
+                classification: None,
+                isolation: Default::default(),
             };
             let def = WorkflowDef {
                 schema_version: 1,
@@ -7826,6 +8666,8 @@ mod tests {
                 description: String::new(),
                 steps: BTreeMap::from([("bad".into(), bad)]),
                 edges: vec![],
+                budget: None,
+                params: Default::default(),
             };
             let json = serde_json::to_string_pretty(&def).unwrap();
             std::fs::write(dir.path().join("workflow.json"), &json).unwrap();
@@ -7839,6 +8681,9 @@ mod tests {
                     live: false,
                     concurrency: 1,
                     submit_only: false,
+                    params: Default::default(),
+                    profile: None,
+                    container: None,
                 },
                 data_dir.path(),
             )
@@ -7890,6 +8735,10 @@ mod tests {
                             retry: None,
                             budget: None,
                             required_capability_versions: Default::default(),
+                            input_schema: Default::default(),
+                            output_schema: None,
+                            classification: None,
+                            isolation: Default::default(),
                         },
                     ),
                     (
@@ -7907,6 +8756,10 @@ mod tests {
                             retry: None,
                             budget: None,
                             required_capability_versions: Default::default(),
+                            input_schema: Default::default(),
+                            output_schema: None,
+                            classification: None,
+                            isolation: Default::default(),
                         },
                     ),
                     (
@@ -7923,10 +8776,16 @@ mod tests {
                             retry: None,
                             budget: None,
                             required_capability_versions: Default::default(),
+                            input_schema: Default::default(),
+                            output_schema: None,
+                            classification: None,
+                            isolation: Default::default(),
                         },
                     ),
                 ]),
                 edges: vec![],
+                budget: None,
+                params: Default::default(),
             };
             let json = serde_json::to_string_pretty(&def).unwrap();
             std::fs::write(dir.path().join("workflow.json"), &json).unwrap();
@@ -7941,12 +8800,78 @@ mod tests {
                 live: false,
                 concurrency: 1,
                 submit_only: false,
+                params: Default::default(),
+                profile: None,
+                container: None,
             };
             let r = WorkflowRunner::run_persistent(def, &options, data_dir).unwrap();
             assert_eq!(r.status, WorkflowStatus::Paused);
             r.run_id
         }
 
+        /// Run the approval workflow with `condition` on the gate, which
+        /// sees `analyze`'s result (42) as `score`.
+        fn run_with_condition(condition: &str, concurrency: usize) -> WorkflowRunResult {
+            let (mut def, wf_dir) = workflow_with_approval_gate();
+            let gate = def.steps.get_mut("human_review").unwrap();
+            gate.kind = StepKind::ApprovalGate {
+                required_role: "reviewer".into(),
+                condition: Some(condition.into()),
+            };
+            gate.inputs = BTreeMap::from([("score".into(), "analyze.result".into())]);
+            let data_dir = tempfile::tempdir().unwrap();
+            let options = RunOptions {
+                policy: Some(Policy::allow_all()),
+                record: false,
+                workflow_dir: wf_dir.path().to_string_lossy().to_string(),
+                live: false,
+                concurrency,
+                submit_only: false,
+                params: Default::default(),
+                profile: None,
+                container: None,
+            };
+            WorkflowRunner::run_persistent(&def, &options, data_dir.path()).unwrap()
+        }
+
+        #[test]
+        fn gate_condition_decides_whether_the_run_pauses() {
+            for concurrency in [1, 2] {
+                let r = run_with_condition("score > 100", concurrency);
+                assert_eq!(
+                    r.status,
+                    WorkflowStatus::Completed,
+                    "concurrency {concurrency}"
+                );
+                assert_eq!(r.step_results["human_review"].status, StepStatus::Completed);
+                assert_eq!(r.step_results["publish"].status, StepStatus::Completed);
+
+                let r = run_with_condition("score >= 42", concurrency);
+                assert_eq!(
+                    r.status,
+                    WorkflowStatus::Paused,
+                    "concurrency {concurrency}"
+                );
+                assert_eq!(
+                    r.step_results["human_review"].status,
+                    StepStatus::AwaitingApproval
+                );
+            }
+        }
+
+        #[test]
+        fn gate_condition_must_parse() {
+            let (mut def, _wf_dir) = workflow_with_approval_gate();
+            def.steps.get_mut("human_review").unwrap().kind = StepKind::ApprovalGate {
+                required_role: "reviewer".into(),
+                condition: Some("score >=".into()),
+            };
+            let errors = WorkflowValidator::validate(&def).unwrap_err();
+            assert!(errors.iter().any(|e| e.kind
+                == crate::workflow::validator::ValidationErrorKind::InvalidCondition
+                && e.message.contains("human_review")));
+        }
+
         // ── record_approval_decision validation ──
 
         #[test]
@@ -8395,12 +9320,7 @@ mod tests {
             let data_dir = tempfile::tempdir().unwrap();
             // Plant the run row + failed step1 checkpoint manually.
             let workflow_hash = WorkflowRunner::workflow_hash_from_def(&def);
-            let inputs_hash = {
-                use sha2::{Digest, Sha256};
-                let mut h = Sha256::new();
-                h.update(b"{}");
-                format!("{:x}", h.finalize())
-            };
+            let inputs_hash = { boruna_hash::sha256_hex(b"{}") };
             let run_id = derive_run_id(&workflow_hash, &inputs_hash, 0);
             let metadata = serde_json::json!({
                 "workflow_dir": wf_dir.path().to_string_lossy(),
@@ -8515,11 +9435,8 @@ mod tests {
             // captured at sprint-merge time so a serialization change
             // is impossible to miss in code review.
             let expected_inline = {
-                use sha2::{Digest, Sha256};
                 let json = serde_json::to_string(&synthetic).unwrap();
-                let mut h = Sha256::new();
-                h.update(json.as_bytes());
-                format!("{:x}", h.finalize())
+                boruna_hash::sha256_hex(json)
             };
             assert_eq!(actual_hash, expected_inline, "self-consistency");
             // Hard-coded golden — bumping this requires a deliberate
@@ -8557,12 +9474,7 @@ mod tests {
             let (def, wf_dir) = workflow_with_approval_gate();
             let data_dir = tempfile::tempdir().unwrap();
             let workflow_hash = WorkflowRunner::workflow_hash_from_def(&def);
-            let inputs_hash = {
-                use sha2::{Digest, Sha256};
-                let mut h = Sha256::new();
-                h.update(b"{}");
-                format!("{:x}", h.finalize())
-            };
+            let inputs_hash = { boruna_hash::sha256_hex(b"{}") };
             let run_id = derive_run_id(&workflow_hash, &inputs_hash, 0);
             // Sentinel for human_review even though the workflow hasn't
             // reached the gate yet (no checkpoint at all).
@@ -8661,6 +9573,9 @@ mod tests {
                     live: false,
                     concurrency: 1,
                     submit_only: false,
+                    params: Default::default(),
+                    profile: None,
+                    container: None,
                 },
                 data_dir.path(),
             )
@@ -8703,6 +9618,9 @@ mod tests {
                     live: false,
                     concurrency: 1,
                     submit_only: false,
+                    params: Default::default(),
+                    profile: None,
+                    container: None,
                 },
                 data_dir.path(),
             )
@@ -8749,6 +9667,9 @@ mod tests {
                     live: false,
                     concurrency: 1,
                     submit_only: false,
+                    params: Default::default(),
+                    profile: None,
+                    container: None,
                 },
                 data_dir.path(),
             )
@@ -8798,6 +9719,9 @@ mod tests {
                     live: false,
                     concurrency: 1,
                     submit_only: false,
+                    params: Default::default(),
+                    profile: None,
+                    container: None,
                 },
                 data_dir.path(),
             )
@@ -8814,6 +9738,7 @@ mod tests {
 
     mod step_input {
         use super::*;
+        use crate::workflow::schema::ValueSchema;
 
         fn upstream_downstream_workflow() -> (WorkflowDef, tempfile::TempDir) {
             let dir = tempfile::tempdir().unwrap();
@@ -8843,6 +9768,10 @@ mod tests {
                 retry: None,
                 budget: None,
                 required_capability_versions: Default::default(),
+                input_schema: Default::default(),
+                output_schema: None,
+                classification: None,
+                isolation: Default::default(),
             };
             let mut downstream = StepDef {
                 kind: StepKind::Source {
@@ -8856,6 +9785,10 @@ mod tests {
                 retry: None,
                 budget: None,
                 required_capability_versions: Default::default(),
+                input_schema: Default::default(),
+                output_schema: None,
+                classification: None,
+                isolation: Default::default(),
             };
             downstream
                 .inputs
@@ -8870,6 +9803,8 @@ mod tests {
                     ("downstream".into(), downstream),
                 ]),
                 edges: vec![],
+                budget: None,
+                params: Default::default(),
             };
             let json = serde_json::to_string_pretty(&def).unwrap();
             std::fs::write(dir.path().join("workflow.json"), &json).unwrap();
@@ -8889,6 +9824,9 @@ mod tests {
                 live: false,
                 concurrency: 1,
                 submit_only: false,
+                params: Default::default(),
+                profile: None,
+                container: None,
             };
             let result = WorkflowRunner::run(&def, &options).unwrap();
             assert_eq!(result.status, WorkflowStatus::Completed);
@@ -8954,6 +9892,10 @@ mod tests {
                 retry: None,
                 budget: None,
                 required_capability_versions: Default::default(),
+                input_schema: Default::default(),
+                output_schema: None,
+                classification: None,
+                isolation: Default::default(),
             };
             let mut downstream = StepDef {
                 kind: StepKind::Source {
@@ -8967,6 +9909,10 @@ mod tests {
                 retry: None,
                 budget: None,
                 required_capability_versions: Default::default(),
+                input_schema: Default::default(),
+                output_schema: None,
+                classification: None,
+                isolation: Default::default(),
             };
             // Declare "msg" but the .ax step asks for "missing" —
             // pre-validation passes, gateway catches the mismatch.
@@ -8983,6 +9929,8 @@ mod tests {
                     ("downstream".into(), downstream),
                 ]),
                 edges: vec![],
+                budget: None,
+                params: Default::default(),
             };
             let json = serde_json::to_string_pretty(&def).unwrap();
             std::fs::write(dir.path().join("workflow.json"), &json).unwrap();
@@ -8993,6 +9941,9 @@ mod tests {
                 live: false,
                 concurrency: 1,
                 submit_only: false,
+                params: Default::default(),
+                profile: None,
+                container: None,
             };
             let result = WorkflowRunner::run(&def, &options).unwrap();
             assert_eq!(result.status, WorkflowStatus::Failed);
@@ -9009,6 +9960,177 @@ mod tests {
             );
         }
 
+        #[test]
+        fn schema_violations_fail_the_step_with_the_field() {
+            let dir = tempfile::tempdir().unwrap();
+            let steps_dir = dir.path().join("steps");
+            std::fs::create_dir_all(&steps_dir).unwrap();
+            std::fs::write(
+                steps_dir.join("upstream.ax"),
+                "fn main() -> String { \"hi\" }",
+            )
+            .unwrap();
+            std::fs::write(
+                steps_dir.join("downstream.ax"),
+                "fn main() -> String {\n    let x: String = step_input(\"msg\")\n    x\n}",
+            )
+            .unwrap();
+            let step = |source: &str| StepDef {
+                kind: StepKind::Source {
+                    source: source.into(),
+                },
+                capabilities: vec![],
+                inputs: BTreeMap::new(),
+                outputs: BTreeMap::new(),
+                depends_on: vec![],
+                timeout_ms: None,
+                retry: None,
+                budget: None,
+                required_capability_versions: Default::default(),
+                input_schema: Default::default(),
+                output_schema: None,
+                classification: None,
+                isolation: Default::default(),
+            };
+            let mut downstream = step("steps/downstream.ax");
+            downstream
+                .inputs
+                .insert("msg".into(), "upstream.result".into());
+            downstream
+                .input_schema
+                .insert("msg".into(), ValueSchema::Int);
+            let mut def = WorkflowDef {
+                schema_version: 1,
+                name: "schema-violation".into(),
+                version: "1.0.0".into(),
+                description: String::new(),
+                steps: BTreeMap::from([
+                    ("upstream".into(), step("steps/upstream.ax")),
+                    ("downstream".into(), downstream),
+                ]),
+                edges: vec![("upstream".into(), "downstream".into())],
+                budget: None,
+                params: Default::default(),
+            };
+            let options = RunOptions {
+                policy: Some(Policy::allow_all()),
+                workflow_dir: dir.path().to_string_lossy().to_string(),
+                ..RunOptions::default()
+            };
+
+            // Undeclared upstream output: the runtime check catches it.
+            let result = WorkflowRunner::run(&def, &options).unwrap();
+            assert_eq!(result.status, WorkflowStatus::Failed);
+            let err = result.step_results["downstream"].error.as_deref().unwrap();
+            assert!(
+                err.contains("step 'downstream' input 'msg': expected Int, got String"),
+                "got: {err}"
+            );
+
+            // Wrong output shape fails the producing step.
+            def.steps
+                .get_mut("downstream")
+                .unwrap()
+                .input_schema
+                .clear();
+            def.steps.get_mut("upstream").unwrap().output_schema =
+                Some(ValueSchema::List { items: None });
+            let result = WorkflowRunner::run(&def, &options).unwrap();
+            let err = result.step_results["upstream"].error.as_deref().unwrap();
+            assert!(
+                err.contains("step 'upstream' output: expected List, got String"),
+                "got: {err}"
+            );
+            assert!(!result.step_results.contains_key("downstream"));
+        }
+
+        #[test]
+        fn secret_inputs_cannot_reach_a_limited_capability() {
+            use boruna_vm::capability_gateway::{Classification, DataFlowPolicy};
+            let dir = tempfile::tempdir().unwrap();
+            let steps_dir = dir.path().join("steps");
+            std::fs::create_dir_all(&steps_dir).unwrap();
+            std::fs::write(
+                steps_dir.join("upstream.ax"),
+                "fn main() -> String { \"token\" }",
+            )
+            .unwrap();
+            // `net.fetch` is reachable from main but not declared in
+            // workflow.json: the compiled call graph still counts.
+            std::fs::write(
+                steps_dir.join("downstream.ax"),
+                "fn fetch(url: String) -> String !{net.fetch} { url }\n\
+                 fn main() -> String !{net.fetch} {\n    \
+                 let x: String = step_input(\"msg\")\n    fetch(x)\n}",
+            )
+            .unwrap();
+            let step = |source: &str| StepDef {
+                kind: StepKind::Source {
+                    source: source.into(),
+                },
+                capabilities: vec![],
+                inputs: BTreeMap::new(),
+                outputs: BTreeMap::new(),
+                depends_on: vec![],
+                timeout_ms: None,
+                retry: None,
+                budget: None,
+                required_capability_versions: Default::default(),
+                input_schema: Default::default(),
+                output_schema: None,
+                classification: None,
+                isolation: Default::default(),
+            };
+            let mut upstream = step("steps/upstream.ax");
+            upstream.classification = Some(Classification::Secret);
+            let mut downstream = step("steps/downstream.ax");
+            downstream
+                .inputs
+                .insert("msg".into(), "upstream.result".into());
+            let mut def = WorkflowDef {
+                schema_version: 1,
+                name: "data-flow".into(),
+                version: "1.0.0".into(),
+                description: String::new(),
+                steps: BTreeMap::from([
+                    ("upstream".into(), upstream),
+                    ("downstream".into(), downstream),
+                ]),
+                edges: vec![("upstream".into(), "downstream".into())],
+                budget: None,
+                params: Default::default(),
+            };
+            let mut policy = Policy::allow_all();
+            policy.data_flow = Some(DataFlowPolicy {
+                max_classification: BTreeMap::from([(
+                    "net.fetch".to_string(),
+                    Classification::Internal,
+                )]),
+                redact_in_evidence: None,
+            });
+            let options = RunOptions {
+                policy: Some(policy),
+                workflow_dir: dir.path().to_string_lossy().to_string(),
+                ..RunOptions::default()
+            };
+
+            let result = WorkflowRunner::run(&def, &options).unwrap();
+            assert_eq!(result.status, WorkflowStatus::Failed);
+            let err = result.step_results["downstream"].error.as_deref().unwrap();
+            assert!(
+                err.contains(
+                    "step 'downstream' receives secret data but may reach 'net.fetch', \
+                     which the policy limits to internal"
+                ),
+                "got: {err}"
+            );
+
+            // Internal data is within the limit.
+            def.steps.get_mut("upstream").unwrap().classification = Some(Classification::Internal);
+            let result = WorkflowRunner::run(&def, &options).unwrap();
+            assert_eq!(result.status, WorkflowStatus::Completed);
+        }
+
         #[test]
         fn operator_can_deny_step_input_via_policy() {
             // 0.3-S14 review-driven regression: build_step_policy's
@@ -9041,6 +10163,10 @@ mod tests {
                 retry: None,
                 budget: None,
                 required_capability_versions: Default::default(),
+                input_schema: Default::default(),
+                output_schema: None,
+                classification: None,
+                isolation: Default::default(),
             };
             let mut downstream = StepDef {
                 kind: StepKind::Source {
@@ -9054,6 +10180,10 @@ mod tests {
                 retry: None,
                 budget: None,
                 required_capability_versions: Default::default(),
+                input_schema: Default::default(),
+                output_schema: None,
+                classification: None,
+                isolation: Default::default(),
             };
             downstream
                 .inputs
@@ -9068,6 +10198,8 @@ mod tests {
                     ("downstream".into(), downstream),
                 ]),
                 edges: vec![],
+                budget: None,
+                params: Default::default(),
             };
             let json = serde_json::to_string_pretty(&def).unwrap();
             std::fs::write(dir.path().join("workflow.json"), &json).unwrap();
@@ -9081,6 +10213,7 @@ mod tests {
                 PolicyRule {
                     allow: false,
                     budget: 0,
+                    prompt: false,
                 },
             );
             let options = RunOptions {
@@ -9090,6 +10223,9 @@ mod tests {
                 live: false,
                 concurrency: 1,
                 submit_only: false,
+                params: Default::default(),
+                profile: None,
+                container: None,
             };
             let result = WorkflowRunner::run(&def, &options).unwrap();
             assert_eq!(
@@ -9114,6 +10250,9 @@ mod tests {
                 live: false,
                 concurrency: c,
                 submit_only: false,
+                params: Default::default(),
+                profile: None,
+                container: None,
             };
             let dir1 = tempfile::tempdir().unwrap();
             let r1 = WorkflowRunner::run_persistent(&def, &make_options(1), dir1.path()).unwrap();
@@ -9128,6 +10267,84 @@ mod tests {
                 );
             }
         }
+
+        fn param_workflow() -> (WorkflowDef, tempfile::TempDir) {
+            let (mut def, dir) = make_workflow_with_steps(&[(
+                "read",
+                "fn main() -> String {\n    let limit: String = step_input(\"limit\")\n    limit\n}",
+            )]);
+            def.params =
+                serde_json::from_str(r#"{"limit": {"type": "int", "default": 10}}"#).unwrap();
+            def.steps
+                .get_mut("read")
+                .unwrap()
+                .inputs
+                .insert("limit".into(), "params.limit".into());
+            (def, dir)
+        }
+
+        #[test]
+        fn step_input_reads_bound_params() {
+            let (def, wf_dir) = param_workflow();
+            let run_with = |params: ParamValues| {
+                let options = RunOptions {
+                    policy: Some(Policy::allow_all()),
+                    workflow_dir: wf_dir.path().to_string_lossy().to_string(),
+                    params,
+                    ..RunOptions::default()
+                };
+                WorkflowRunner::run(&def, &options)
+            };
+            let defaulted = run_with(ParamValues::new()).unwrap();
+            let bound =
+                run_with(ParamValues::from([("limit".into(), serde_json::json!(50))])).unwrap();
+            assert_eq!(defaulted.status, WorkflowStatus::Completed);
+            assert_eq!(bound.status, WorkflowStatus::Completed);
+            // Different params: different inputs_hash, so different
+            // run_id, and the step saw a different value.
+            assert_ne!(defaulted.run_id, bound.run_id);
+            assert_ne!(
+                defaulted.step_results["read"].output_hash,
+                bound.step_results["read"].output_hash
+            );
+
+            let err = run_with(ParamValues::from([(
+                "limit".into(),
+                serde_json::json!("x"),
+            )]))
+            .unwrap_err();
+            assert!(
+                matches!(&err, WorkflowRunError::Validation(m) if m.contains("expected Int")),
+                "got {err:?}"
+            );
+        }
+
+        #[cfg(feature = "persist-sqlite")]
+        #[test]
+        fn persistent_runs_record_params_and_refuse_submit_only() {
+            let (def, wf_dir) = param_workflow();
+            let data_dir = tempfile::tempdir().unwrap();
+            let mut options = RunOptions {
+                policy: Some(Policy::allow_all()),
+                workflow_dir: wf_dir.path().to_string_lossy().to_string(),
+                params: ParamValues::from([("limit".into(), serde_json::json!(7))]),
+                ..RunOptions::default()
+            };
+            let result = WorkflowRunner::run_persistent(&def, &options, data_dir.path()).unwrap();
+            assert_eq!(result.status, WorkflowStatus::Completed);
+            let store = open_store(data_dir.path()).unwrap();
+            let record = store.get_run(&result.run_id).unwrap().unwrap();
+            let metadata: PersistedRunMetadata =
+                serde_json::from_str(&record.metadata_json).unwrap();
+            assert_eq!(metadata.params["limit"], 7);
+            assert_eq!(metadata.inputs_hash, params::params_hash(&metadata.params));
+
+            options.submit_only = true;
+            assert!(matches!(
+                WorkflowRunner::run_persistent(&def, &options, data_dir.path()),
+                Err(WorkflowRunError::Validation(_))
+            ));
+        }
     }
 
     // ── 0.3-S15: external_trigger step (async step execution) ──
@@ -9161,6 +10378,10 @@ mod tests {
                 retry: None,
                 budget: None,
                 required_capability_versions: Default::default(),
+                input_schema: Default::default(),
+                output_schema: None,
+                classification: None,
+                isolation: Default::default(),
             };
             after.inputs.insert("event".into(), "webhook.result".into());
             let def = WorkflowDef {
@@ -9183,6 +10404,10 @@ mod tests {
                             retry: None,
                             budget: None,
                             required_capability_versions: Default::default(),
+                            input_schema: Default::default(),
+                            output_schema: None,
+                            classification: None,
+                            isolation: Default::default(),
                         },
                     ),
                     (
@@ -9199,11 +10424,17 @@ mod tests {
                             retry: None,
                             budget: None,
                             required_capability_versions: Default::default(),
+                            input_schema: Default::default(),
+                            output_schema: None,
+                            classification: None,
+                            isolation: Default::default(),
                         },
                     ),
                     ("after".into(), after),
                 ]),
                 edges: vec![],
+                budget: None,
+                params: Default::default(),
             };
             let json = serde_json::to_string_pretty(&def).unwrap();
             std::fs::write(dir.path().join("workflow.json"), &json).unwrap();
@@ -9218,6 +10449,9 @@ mod tests {
                 live: false,
                 concurrency: 1,
                 submit_only: false,
+                params: Default::default(),
+                profile: None,
+                container: None,
             };
             let r = WorkflowRunner::run_persistent(def, &options, data_dir).unwrap();
             assert_eq!(r.status, WorkflowStatus::Paused);
@@ -9652,6 +10886,9 @@ mod tests {
                 live: false,
                 concurrency: 1,
                 submit_only: false,
+                params: Default::default(),
+                profile: None,
+                container: None,
             };
             let err = WorkflowRunner::run(&def, &options).expect_err("ephemeral path must error");
             assert!(matches!(err, WorkflowRunError::Validation(_)));
@@ -9729,6 +10966,10 @@ mod tests {
                             retry: None,
                             budget: None,
                             required_capability_versions: Default::default(),
+                            input_schema: Default::default(),
+                            output_schema: None,
+                            classification: None,
+                            isolation: Default::default(),
                         },
                     ),
                     (
@@ -9745,6 +10986,10 @@ mod tests {
                             retry: None,
                             budget: None,
                             required_capability_versions: Default::default(),
+                            input_schema: Default::default(),
+                            output_schema: None,
+                            classification: None,
+                            isolation: Default::default(),
                         },
                     ),
                     (
@@ -9761,6 +11006,10 @@ mod tests {
                             retry: None,
                             budget: None,
                             required_capability_versions: Default::default(),
+                            input_schema: Default::default(),
+                            output_schema: None,
+                            classification: None,
+                            isolation: Default::default(),
                         },
                     ),
                     (
@@ -9780,10 +11029,16 @@ mod tests {
                             retry: None,
                             budget: None,
                             required_capability_versions: Default::default(),
+                            input_schema: Default::default(),
+                            output_schema: None,
+                            classification: None,
+                            isolation: Default::default(),
                         },
                     ),
                 ]),
                 edges: vec![],
+                budget: None,
+                params: Default::default(),
             };
             let json = serde_json::to_string_pretty(&def).unwrap();
             std::fs::write(dir.path().join("workflow.json"), &json).unwrap();
@@ -9821,6 +11076,9 @@ mod tests {
                 live: false,
                 concurrency: 2,
                 submit_only: false,
+                params: Default::default(),
+                profile: None,
+                container: None,
             };
             let r = WorkflowRunner::run_persistent(&def, &options, data_dir.path()).unwrap();
             assert_eq!(r.status, WorkflowStatus::Paused);
@@ -9868,6 +11126,9 @@ mod tests {
                 live: false,
                 concurrency: 2,
                 submit_only: false,
+                params: Default::default(),
+                profile: None,
+                container: None,
             };
             let r = WorkflowRunner::run_persistent(&def, &options, data_dir.path()).unwrap();
 
@@ -9920,6 +11181,9 @@ mod tests {
                 live: false,
                 concurrency: 2,
                 submit_only: false,
+                params: Default::default(),
+                profile: None,
+                container: None,
             };
             let r = WorkflowRunner::run_persistent(&def, &options, data_dir.path()).unwrap();
 
@@ -9990,6 +11254,9 @@ mod tests {
                     live: false,
                     concurrency: 2,
                     submit_only: false,
+                    params: Default::default(),
+                    profile: None,
+                    container: None,
                 },
                 data_dir.path(),
             )
@@ -10087,6 +11354,10 @@ mod tests {
                             retry: None,
                             budget: None,
                             required_capability_versions: Default::default(),
+                            input_schema: Default::default(),
+                            output_schema: None,
+                            classification: None,
+                            isolation: Default::default(),
                         },
                     ),
                     (
@@ -10104,6 +11375,10 @@ mod tests {
                             retry: None,
                             budget: None,
                             required_capability_versions: Default::default(),
+                            input_schema: Default::default(),
+                            output_schema: None,
+                            classification: None,
+                            isolation: Default::default(),
                         },
                     ),
                     (
@@ -10118,10 +11393,16 @@ mod tests {
                             retry: None,
                             budget: None,
                             required_capability_versions: Default::default(),
+                            input_schema: Default::default(),
+                            output_schema: None,
+                            classification: None,
+                            isolation: Default::default(),
                         },
                     ),
                 ]),
                 edges: vec![],
+                budget: None,
+                params: Default::default(),
             };
             let json = serde_json::to_string_pretty(&def).unwrap();
             std::fs::write(dir.path().join("workflow.json"), &json).unwrap();
@@ -10133,6 +11414,9 @@ mod tests {
                 live: false,
                 concurrency: 2,
                 submit_only: false,
+                params: Default::default(),
+                profile: None,
+                container: None,
             };
             let r = WorkflowRunner::run_persistent(&def, &options, data_dir.path()).unwrap();
             assert_eq!(r.status, WorkflowStatus::Paused);
@@ -10174,6 +11458,9 @@ mod tests {
                 live: false,
                 concurrency: 1,
                 submit_only: false,
+                params: Default::default(),
+                profile: None,
+                container: None,
             };
             let r = WorkflowRunner::run_persistent(&def, &options, data_dir.path()).unwrap();
             assert_eq!(r.status, WorkflowStatus::Paused);
@@ -10223,6 +11510,9 @@ mod tests {
                 live: false,
                 concurrency: 1,
                 submit_only: false,
+                params: Default::default(),
+                profile: None,
+                container: None,
             };
             let r = WorkflowRunner::run_persistent(&def, &options, data_dir.path()).unwrap();
 
@@ -10266,6 +11556,9 @@ mod tests {
                 live: false,
                 concurrency: 1,
                 submit_only: false,
+                params: Default::default(),
+                profile: None,
+                container: None,
             };
             let r = WorkflowRunner::run_persistent(&def, &options, data_dir.path()).unwrap();
             let store = open_store(data_dir.path()).unwrap();
@@ -10329,6 +11622,10 @@ mod tests {
                             retry: None,
                             budget: None,
                             required_capability_versions: Default::default(),
+                            input_schema: Default::default(),
+                            output_schema: None,
+                            classification: None,
+                            isolation: Default::default(),
                         },
                     ),
                     (
@@ -10346,6 +11643,10 @@ mod tests {
                             retry: None,
                             budget: None,
                             required_capability_versions: Default::default(),
+                            input_schema: Default::default(),
+                            output_schema: None,
+                            classification: None,
+                            isolation: Default::default(),
                         },
                     ),
                     (
@@ -10363,10 +11664,16 @@ mod tests {
                             retry: None,
                             budget: None,
                             required_capability_versions: Default::default(),
+                            input_schema: Default::default(),
+                            output_schema: None,
+                            classification: None,
+                            isolation: Default::default(),
                         },
                     ),
                 ]),
                 edges: vec![],
+                budget: None,
+                params: Default::default(),
             };
             let json = serde_json::to_string_pretty(&def).unwrap();
             std::fs::write(dir.path().join("workflow.json"), &json).unwrap();
@@ -10380,6 +11687,9 @@ mod tests {
                     live: false,
                     concurrency: 2,
                     submit_only: false,
+                    params: Default::default(),
+                    profile: None,
+                    container: None,
                 },
                 data_dir.path(),
             )
@@ -10473,6 +11783,9 @@ mod tests {
                     live: false,
                     concurrency: 1,
                     submit_only: false,
+                    params: Default::default(),
+                    profile: None,
+                    container: None,
                 },
                 data_dir.path(),
             )
@@ -10532,6 +11845,9 @@ mod tests {
                 live: false,
                 concurrency: 1,
                 submit_only: false,
+                params: Default::default(),
+                profile: None,
+                container: None,
             };
             let r = WorkflowRunner::run_persistent(&def, &options, data_dir.path()).unwrap();
             record_approval_decision(
@@ -10613,6 +11929,9 @@ mod tests {
                     live: false,
                     concurrency: 1,
                     submit_only: false,
+                    params: Default::default(),
+                    profile: None,
+                    container: None,
                 },
                 data_dir.path(),
             )
@@ -10684,6 +12003,9 @@ mod tests {
                     live: false,
                     concurrency: 1,
                     submit_only: false,
+                    params: Default::default(),
+                    profile: None,
+                    container: None,
                 },
                 data_dir.path(),
             )
@@ -10755,6 +12077,9 @@ mod tests {
                     live: false,
                     concurrency: 1,
                     submit_only: false,
+                    params: Default::default(),
+                    profile: None,
+                    container: None,
                 },
                 data_dir.path(),
             )
@@ -10808,6 +12133,9 @@ mod tests {
                     live: false,
                     concurrency: 1,
                     submit_only: false,
+                    params: Default::default(),
+                    profile: None,
+                    container: None,
                 },
                 data_dir.path(),
             )
@@ -10858,6 +12186,9 @@ mod tests {
                     live: false,
                     concurrency: 1,
                     submit_only: false,
+                    params: Default::default(),
+                    profile: None,
+                    container: None,
                 },
                 data_dir.path(),
             )
@@ -10945,6 +12276,10 @@ mod tests {
                             retry: None,
                             budget: None,
                             required_capability_versions: Default::default(),
+                            input_schema: Default::default(),
+                            output_schema: None,
+                            classification: None,
+                            isolation: Default::default(),
                         },
                     ),
                     (
@@ -10961,10 +12296,16 @@ mod tests {
                             retry: None,
                             budget: None,
                             required_capability_versions: Default::default(),
+                            input_schema: Default::default(),
+                            output_schema: None,
+                            classification: None,
+                            isolation: Default::default(),
                         },
                     ),
                 ]),
                 edges: vec![],
+                budget: None,
+                params: Default::default(),
             };
             let json = serde_json::to_string_pretty(&def).unwrap();
             std::fs::write(dir.path().join("workflow.json"), &json).unwrap();
@@ -10978,6 +12319,9 @@ mod tests {
                     live: false,
                     concurrency: 1,
                     submit_only: false,
+                    params: Default::default(),
+                    profile: None,
+                    container: None,
                 },
                 data_dir.path(),
             )
@@ -11041,9 +12385,15 @@ mod tests {
                         retry: None,
                         budget: None,
                         required_capability_versions: Default::default(),
+                        input_schema: Default::default(),
+                        output_schema: None,
+                        classification: None,
+                        isolation: Default::default(),
                     },
                 )]),
                 edges: vec![],
+                budget: None,
+                params: Default::default(),
             };
             let json = serde_json::to_string_pretty(&def).unwrap();
             std::fs::write(dir.path().join("workflow.json"), &json).unwrap();
@@ -11057,6 +12407,9 @@ mod tests {
                     live: false,
                     concurrency: 1,
                     submit_only: false,
+                    params: Default::default(),
+                    profile: None,
+                    container: None,
                 },
                 data_dir.path(),
             )
@@ -11083,6 +12436,90 @@ mod tests {
             log.verify().expect("chain must verify");
         }
 
+        #[test]
+        fn create_bundle_redacts_classified_outputs() {
+            use boruna_vm::capability_gateway::{Classification, DataFlowPolicy};
+            let dir = tempfile::tempdir().unwrap();
+            let steps_dir = dir.path().join("steps");
+            std::fs::create_dir_all(&steps_dir).unwrap();
+            std::fs::write(
+                steps_dir.join("creds.ax"),
+                "fn main() -> String { \"hunter2\" }",
+            )
+            .unwrap();
+            std::fs::write(steps_dir.join("count.ax"), "fn main() -> Int { 3 }").unwrap();
+            let step = |source: &str, classification| StepDef {
+                kind: StepKind::Source {
+                    source: source.into(),
+                },
+                capabilities: vec![],
+                inputs: BTreeMap::new(),
+                outputs: BTreeMap::new(),
+                depends_on: vec![],
+                timeout_ms: None,
+                retry: None,
+                budget: None,
+                required_capability_versions: Default::default(),
+                input_schema: Default::default(),
+                output_schema: None,
+                classification,
+                isolation: Default::default(),
+            };
+            let def = WorkflowDef {
+                schema_version: 1,
+                name: "redaction".into(),
+                version: "1.0.0".into(),
+                description: String::new(),
+                steps: BTreeMap::from([
+                    (
+                        "creds".into(),
+                        step("steps/creds.ax", Some(Classification::Secret)),
+                    ),
+                    ("count".into(), step("steps/count.ax", None)),
+                ]),
+                edges: vec![],
+                budget: None,
+                params: Default::default(),
+            };
+            let json = serde_json::to_string_pretty(&def).unwrap();
+            std::fs::write(dir.path().join("workflow.json"), &json).unwrap();
+            let mut policy = Policy::allow_all();
+            policy.data_flow = Some(DataFlowPolicy {
+                max_classification: BTreeMap::new(),
+                redact_in_evidence: Some(Classification::Secret),
+            });
+            let data_dir = tempfile::tempdir().unwrap();
+            let r = WorkflowRunner::run_persistent(
+                &def,
+                &RunOptions {
+                    policy: Some(policy),
+                    workflow_dir: dir.path().to_string_lossy().to_string(),
+                    ..RunOptions::default()
+                },
+                data_dir.path(),
+            )
+            .unwrap();
+            assert_eq!(r.status, WorkflowStatus::Completed);
+
+            let output_dir = tempfile::tempdir().unwrap();
+            create_bundle(data_dir.path(), &r.run_id, output_dir.path()).unwrap();
+            let bundle_path = output_dir.path().join(&r.run_id);
+            let creds =
+                std::fs::read_to_string(bundle_path.join("outputs/creds/result.json")).unwrap();
+            assert!(!creds.contains("hunter2"), "secret output leaked: {creds}");
+            let placeholder: serde_json::Value = serde_json::from_str(&creds).unwrap();
+            assert_eq!(placeholder["redacted"], "secret");
+            assert_eq!(
+                placeholder["output_hash"].as_str(),
+                r.step_results["creds"].output_hash.as_deref()
+            );
+            let count =
+                std::fs::read_to_string(bundle_path.join("outputs/count/result.json")).unwrap();
+            assert!(count.contains('3'));
+            let result = verify_bundle(&bundle_path);
+            assert!(result.valid, "{:?}", result.errors);
+        }
+
         #[test]
         fn create_bundle_lifecycle_chain_for_run_without_decisions() {
             // 0.4-S11: a run with no decisions still produces a chain
@@ -11112,9 +12549,15 @@ mod tests {
                         retry: None,
                         budget: None,
                         required_capability_versions: Default::default(),
+                        input_schema: Default::default(),
+                        output_schema: None,
+                        classification: None,
+                        isolation: Default::default(),
                     },
                 )]),
                 edges: vec![],
+                budget: None,
+                params: Default::default(),
             };
             let json = serde_json::to_string_pretty(&def).unwrap();
             std::fs::write(dir.path().join("workflow.json"), &json).unwrap();
@@ -11128,6 +12571,9 @@ mod tests {
                     live: false,
                     concurrency: 1,
                     submit_only: false,
+                    params: Default::default(),
+                    profile: None,
+                    container: None,
                 },
                 data_dir.path(),
             )
@@ -11187,6 +12633,9 @@ mod tests {
                 live: false,
                 concurrency: 1,
                 submit_only: false,
+                params: Default::default(),
+                profile: None,
+                container: None,
             };
             WorkflowRunner::run_persistent(&def, &opts, data_dir.path()).unwrap();
 
@@ -11267,7 +12716,7 @@ mod tests {
 
             // Write the blob manually and record its hash.
             let payload = "c".repeat(crate::persistence::BLOB_THRESHOLD + 1);
-            let hash = sha256_hex(&payload);
+            let hash = boruna_hash::sha256_hex(&payload);
             store
                 .blob_store()
                 .unwrap()
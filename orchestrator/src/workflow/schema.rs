@@ -0,0 +1,305 @@
+//! Declared value shapes for step inputs and outputs.
+//!
+//! A step may declare `input_schema` (per input name) and `output_schema`
+//! (for its `result`) in `workflow.json`:
+//!
+//! ```json
+//! "output_schema": {"type": "map", "properties": {"score": {"type": "int"}}},
+//! "input_schema": {"items": {"type": "list", "items": {"type": "string"}}}
+//! ```
+//!
+//! [`WorkflowValidator`](crate::workflow::WorkflowValidator) compares the
+//! declared shapes on each side of an input edge. The runner checks actual
+//! values against them before and after a source step runs, failing the
+//! step with a [`SchemaViolation`] that names the offending field.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use boruna_bytecode::Value;
+use serde::{Deserialize, Serialize};
+
+/// Shape of a step value. Tag names follow the Boruna value types.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ValueSchema {
+    Any,
+    Unit,
+    Bool,
+    Int,
+    Float,
+    Decimal,
+    #[serde(rename = "bigint")]
+    BigInt,
+    String,
+    /// `items` constrains every element; absent means any element.
+    List {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        items: Option<Box<ValueSchema>>,
+    },
+    /// Every key in `properties` must be present with that shape. `values`
+    /// constrains the remaining entries; absent means any.
+    Map {
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        properties: BTreeMap<String, ValueSchema>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        values: Option<Box<ValueSchema>>,
+    },
+    /// `None`, or `Some` of `some` (any when absent).
+    Option {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        some: Option<Box<ValueSchema>>,
+    },
+}
+
+impl fmt::Display for ValueSchema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValueSchema::Any => write!(f, "Any"),
+            ValueSchema::Unit => write!(f, "Unit"),
+            ValueSchema::Bool => write!(f, "Bool"),
+            ValueSchema::Int => write!(f, "Int"),
+            ValueSchema::Float => write!(f, "Float"),
+            ValueSchema::Decimal => write!(f, "Decimal"),
+            ValueSchema::BigInt => write!(f, "BigInt"),
+            ValueSchema::String => write!(f, "String"),
+            ValueSchema::List { items: None } => write!(f, "List"),
+            ValueSchema::List { items: Some(s) } => write!(f, "List<{s}>"),
+            ValueSchema::Map { properties, values } => {
+                write!(f, "Map")?;
+                if let Some(v) = values {
+                    write!(f, "<{v}>")?;
+                }
+                if !properties.is_empty() {
+                    let fields: Vec<String> = properties
+                        .iter()
+                        .map(|(k, s)| format!("{k}: {s}"))
+                        .collect();
+                    write!(f, " {{{}}}", fields.join(", "))?;
+                }
+                Ok(())
+            }
+            ValueSchema::Option { some: None } => write!(f, "Option"),
+            ValueSchema::Option { some: Some(s) } => write!(f, "Option<{s}>"),
+        }
+    }
+}
+
+/// A step value that does not match its declared schema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaViolation {
+    pub step_id: String,
+    /// Path to the offending value, e.g. `input 'docs'[2]` or
+    /// `output.score`.
+    pub field: String,
+    pub expected: String,
+    pub actual: String,
+}
+
+impl fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "step '{}' {}: expected {}, got {}",
+            self.step_id, self.field, self.expected, self.actual
+        )
+    }
+}
+
+impl std::error::Error for SchemaViolation {}
+
+impl ValueSchema {
+    /// Check `value` against this schema. On mismatch, returns the path
+    /// below `field` and the expected/actual type names.
+    pub fn check(&self, value: &Value, field: &str) -> Result<(), (String, String, String)> {
+        let mismatch = || {
+            Err((
+                field.to_string(),
+                self.to_string(),
+                value.type_name().to_string(),
+            ))
+        };
+        match (self, value) {
+            (ValueSchema::Any, _)
+            | (ValueSchema::Unit, Value::Unit)
+            | (ValueSchema::Bool, Value::Bool(_))
+            | (ValueSchema::Int, Value::Int(_))
+            | (ValueSchema::Float, Value::Float(_))
+            | (ValueSchema::Decimal, Value::Decimal(_))
+            | (ValueSchema::BigInt, Value::BigInt(_))
+            | (ValueSchema::String, Value::String(_))
+            | (ValueSchema::Option { .. }, Value::None) => Ok(()),
+            (ValueSchema::List { items }, Value::List(elems)) => match items {
+                Some(items) => elems
+                    .iter()
+                    .enumerate()
+                    .try_for_each(|(i, v)| items.check(v, &format!("{field}[{i}]"))),
+                None => Ok(()),
+            },
+            (ValueSchema::Map { properties, values }, Value::Map(entries)) => {
+                for (key, schema) in properties {
+                    let path = format!("{field}.{key}");
+                    match entries.get(key) {
+                        Some(v) => schema.check(v, &path)?,
+                        None => return Err((path, schema.to_string(), "nothing".to_string())),
+                    }
+                }
+                if let Some(values) = values {
+                    for (key, v) in entries {
+                        if !properties.contains_key(key) {
+                            values.check(v, &format!("{field}.{key}"))?;
+                        }
+                    }
+                }
+                Ok(())
+            }
+            (ValueSchema::Option { some }, Value::Some(inner)) => match some {
+                Some(s) => s.check(inner, field),
+                None => Ok(()),
+            },
+            _ => mismatch(),
+        }
+    }
+
+    /// Whether a value declared as `produced` may be given where this
+    /// schema is expected. Lenient where `produced` says nothing: an
+    /// unconstrained producer is compatible with any consumer, and the
+    /// runtime check decides.
+    pub fn accepts(&self, produced: &ValueSchema) -> bool {
+        match (self, produced) {
+            (ValueSchema::Any, _) | (_, ValueSchema::Any) => true,
+            (ValueSchema::List { items: want }, ValueSchema::List { items: got }) => {
+                match (want, got) {
+                    (Some(want), Some(got)) => want.accepts(got),
+                    _ => true,
+                }
+            }
+            (
+                ValueSchema::Map {
+                    properties: want_props,
+                    values: want_values,
+                },
+                ValueSchema::Map {
+                    properties: got_props,
+                    values: got_values,
+                },
+            ) => {
+                let props_ok = want_props.iter().all(|(key, want)| {
+                    match got_props.get(key).or(got_values.as_deref()) {
+                        Some(got) => want.accepts(got),
+                        None => true,
+                    }
+                });
+                let values_ok = match want_values {
+                    Some(want) => {
+                        got_props
+                            .iter()
+                            .filter(|(key, _)| !want_props.contains_key(*key))
+                            .all(|(_, got)| want.accepts(got))
+                            && got_values.as_deref().is_none_or(|got| want.accepts(got))
+                    }
+                    None => true,
+                };
+                props_ok && values_ok
+            }
+            (ValueSchema::Option { some: want }, ValueSchema::Option { some: got }) => {
+                match (want, got) {
+                    (Some(want), Some(got)) => want.accepts(got),
+                    _ => true,
+                }
+            }
+            (want, got) => std::mem::discriminant(want) == std::mem::discriminant(got),
+        }
+    }
+}
+
+/// Check a step's resolved inputs against its `input_schema`.
+pub fn check_inputs(
+    step_id: &str,
+    schemas: &BTreeMap<String, ValueSchema>,
+    inputs: &BTreeMap<String, Value>,
+) -> Result<(), SchemaViolation> {
+    for (name, schema) in schemas {
+        if let Some(value) = inputs.get(name) {
+            schema
+                .check(value, &format!("input '{name}'"))
+                .map_err(|v| violation(step_id, v))?;
+        }
+    }
+    Ok(())
+}
+
+/// Check a step's result against its `output_schema`.
+pub fn check_output(
+    step_id: &str,
+    schema: Option<&ValueSchema>,
+    value: &Value,
+) -> Result<(), SchemaViolation> {
+    match schema {
+        Some(schema) => schema
+            .check(value, "output")
+            .map_err(|v| violation(step_id, v)),
+        None => Ok(()),
+    }
+}
+
+fn violation(
+    step_id: &str,
+    (field, expected, actual): (String, String, String),
+) -> SchemaViolation {
+    SchemaViolation {
+        step_id: step_id.to_string(),
+        field,
+        expected,
+        actual,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(json: &str) -> ValueSchema {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn parses_nested_schemas() {
+        let schema = parse(
+            r#"{"type": "map", "properties": {"tags": {"type": "list", "items": {"type": "string"}}}}"#,
+        );
+        assert_eq!(schema.to_string(), "Map {tags: List<String>}");
+    }
+
+    #[test]
+    fn check_names_the_offending_path() {
+        let schema = parse(r#"{"type": "list", "items": {"type": "int"}}"#);
+        let value = Value::List(vec![Value::Int(1), Value::String("two".into())]);
+        let err = check_output("s", Some(&schema), &value).unwrap_err();
+        assert_eq!(err.field, "output[1]");
+        assert_eq!(
+            err.to_string(),
+            "step 's' output[1]: expected Int, got String"
+        );
+    }
+
+    #[test]
+    fn check_requires_map_properties() {
+        let schema = parse(r#"{"type": "map", "properties": {"score": {"type": "int"}}}"#);
+        let inputs = BTreeMap::from([("doc".to_string(), Value::Map(BTreeMap::new()))]);
+        let schemas = BTreeMap::from([("doc".to_string(), schema)]);
+        let err = check_inputs("s", &schemas, &inputs).unwrap_err();
+        assert_eq!(err.field, "input 'doc'.score");
+        assert_eq!(err.actual, "nothing");
+    }
+
+    #[test]
+    fn accepts_is_lenient_only_where_unconstrained() {
+        let ints = parse(r#"{"type": "list", "items": {"type": "int"}}"#);
+        let strings = parse(r#"{"type": "list", "items": {"type": "string"}}"#);
+        assert!(ints.accepts(&parse(r#"{"type": "list"}"#)));
+        assert!(ints.accepts(&ValueSchema::Any));
+        assert!(!ints.accepts(&strings));
+        assert!(!ValueSchema::Int.accepts(&ValueSchema::String));
+    }
+}
@@ -1,6 +1,7 @@
 use std::collections::{BTreeMap, BTreeSet, VecDeque};
 
-use crate::workflow::definition::{StepKind, WorkflowDef};
+use crate::workflow::definition::{StepIsolation, StepKind, WorkflowDef};
+use crate::workflow::params::{self, PARAMS_PREFIX};
 
 /// Validates a workflow definition for structural and semantic correctness.
 pub struct WorkflowValidator;
@@ -21,6 +22,10 @@ pub enum ValidationErrorKind {
     UnknownInput,
     InvalidCapability,
     DuplicateEdge,
+    SchemaMismatch,
+    InvalidParam,
+    InvalidCondition,
+    InvalidIsolation,
 }
 
 impl std::fmt::Display for ValidationError {
@@ -85,10 +90,43 @@ impl WorkflowValidator {
             }
         }
 
-        // Validate input references (format: "step_id.output_name")
+        // Validate declared params. Once a workflow declares any,
+        // `params.<name>` inputs read them, so no step may be named
+        // `params`.
+        if !def.params.is_empty() && def.steps.contains_key("params") {
+            errors.push(ValidationError {
+                kind: ValidationErrorKind::InvalidParam,
+                message: "step id 'params' is reserved in workflows that declare params".into(),
+            });
+        }
+        for (name, param) in &def.params {
+            if let Some(default) = &param.default {
+                if let Err(e) = params::check(name, &param.schema, default) {
+                    errors.push(ValidationError {
+                        kind: ValidationErrorKind::InvalidParam,
+                        message: format!("default for {e}"),
+                    });
+                }
+            }
+        }
+
+        // Validate input references (format: "step_id.output_name", or
+        // "params.name" for a declared param)
         for (id, step) in &def.steps {
             for (input_name, input_ref) in &step.inputs {
-                if let Some((ref_step, _ref_output)) = input_ref.split_once('.') {
+                let param = input_ref
+                    .strip_prefix(PARAMS_PREFIX)
+                    .filter(|_| !def.params.is_empty());
+                if let Some(name) = param {
+                    if !def.params.contains_key(name) {
+                        errors.push(ValidationError {
+                            kind: ValidationErrorKind::UnknownInput,
+                            message: format!(
+                                "step '{id}' input '{input_name}' references undeclared param '{name}'"
+                            ),
+                        });
+                    }
+                } else if let Some((ref_step, _ref_output)) = input_ref.split_once('.') {
                     if !step_ids.contains(ref_step) {
                         errors.push(ValidationError {
                             kind: ValidationErrorKind::UnknownInput,
@@ -109,6 +147,43 @@ impl WorkflowValidator {
             }
         }
 
+        // Validate declared input shapes against the upstream step's
+        // declared output shape.
+        for (id, step) in &def.steps {
+            for (input_name, want) in &step.input_schema {
+                let Some(input_ref) = step.inputs.get(input_name) else {
+                    errors.push(ValidationError {
+                        kind: ValidationErrorKind::UnknownInput,
+                        message: format!(
+                            "step '{id}' input_schema names undeclared input '{input_name}'"
+                        ),
+                    });
+                    continue;
+                };
+                let declared = match input_ref
+                    .strip_prefix(PARAMS_PREFIX)
+                    .and_then(|name| def.params.get(name))
+                {
+                    Some(param) => Some(&param.schema),
+                    None => input_ref
+                        .split_once('.')
+                        .and_then(|(ref_step, _)| def.steps.get(ref_step))
+                        .and_then(|upstream| upstream.output_schema.as_ref()),
+                };
+                if let Some(got) = declared {
+                    if !want.accepts(got) {
+                        errors.push(ValidationError {
+                            kind: ValidationErrorKind::SchemaMismatch,
+                            message: format!(
+                                "step '{id}' input '{input_name}' expects {want}, \
+                                 but '{input_ref}' is declared as {got}"
+                            ),
+                        });
+                    }
+                }
+            }
+        }
+
         // Validate source steps have a source file
         for (id, step) in &def.steps {
             if let StepKind::Source { source } = &step.kind {
@@ -121,6 +196,34 @@ impl WorkflowValidator {
             }
         }
 
+        // Only source steps run a VM, so only they can be isolated
+        for (id, step) in &def.steps {
+            if step.isolation != StepIsolation::Process
+                && !matches!(step.kind, StepKind::Source { .. })
+            {
+                errors.push(ValidationError {
+                    kind: ValidationErrorKind::InvalidIsolation,
+                    message: format!("step '{id}' sets isolation but is not a source step"),
+                });
+            }
+        }
+
+        // Approval-gate conditions must parse
+        for (id, step) in &def.steps {
+            if let StepKind::ApprovalGate {
+                condition: Some(condition),
+                ..
+            } = &step.kind
+            {
+                if let Err(e) = boruna_framework::Query::parse(condition) {
+                    errors.push(ValidationError {
+                        kind: ValidationErrorKind::InvalidCondition,
+                        message: format!("step '{id}' condition: {e}"),
+                    });
+                }
+            }
+        }
+
         // Check for duplicate edges
         let mut seen_edges = BTreeSet::new();
         for edge in &def.edges {
@@ -297,6 +400,7 @@ impl WorkflowValidator {
 mod tests {
     use super::*;
     use crate::workflow::definition::*;
+    use crate::workflow::schema::ValueSchema;
 
     fn simple_source_step(source: &str) -> StepDef {
         StepDef {
@@ -311,6 +415,10 @@ mod tests {
             retry: None,
             budget: None,
             required_capability_versions: Default::default(),
+            input_schema: Default::default(),
+            output_schema: None,
+            classification: None,
+            isolation: Default::default(),
         }
     }
 
@@ -330,6 +438,8 @@ mod tests {
                 ("fetch".into(), "transform".into()),
                 ("transform".into(), "store".into()),
             ],
+            budget: None,
+            params: Default::default(),
         };
         assert!(WorkflowValidator::validate(&def).is_ok());
     }
@@ -351,6 +461,8 @@ mod tests {
                 ("b".into(), "c".into()),
                 ("c".into(), "a".into()),
             ],
+            budget: None,
+            params: Default::default(),
         };
         let errors = WorkflowValidator::validate(&def).unwrap_err();
         assert!(errors
@@ -367,6 +479,8 @@ mod tests {
             description: String::new(),
             steps: BTreeMap::from([("a".into(), simple_source_step("a.ax"))]),
             edges: vec![("a".into(), "nonexistent".into())],
+            budget: None,
+            params: Default::default(),
         };
         let errors = WorkflowValidator::validate(&def).unwrap_err();
         assert!(errors
@@ -383,6 +497,8 @@ mod tests {
             description: String::new(),
             steps: BTreeMap::new(),
             edges: vec![],
+            budget: None,
+            params: Default::default(),
         };
         let errors = WorkflowValidator::validate(&def).unwrap_err();
         assert!(errors
@@ -401,6 +517,8 @@ mod tests {
             description: String::new(),
             steps: BTreeMap::from([("a".into(), step)]),
             edges: vec![],
+            budget: None,
+            params: Default::default(),
         };
         let errors = WorkflowValidator::validate(&def).unwrap_err();
         assert!(errors
@@ -408,6 +526,78 @@ mod tests {
             .any(|e| e.kind == ValidationErrorKind::UnknownInput));
     }
 
+    #[test]
+    fn test_validate_schema_mismatch_on_edge() {
+        let mut producer = simple_source_step("steps/a.ax");
+        producer.output_schema = Some(ValueSchema::Int);
+        let mut consumer = simple_source_step("steps/b.ax");
+        consumer.inputs.insert("n".into(), "a.result".into());
+        consumer
+            .input_schema
+            .insert("n".into(), ValueSchema::String);
+        consumer
+            .input_schema
+            .insert("ghost".into(), ValueSchema::Any);
+        let def = WorkflowDef {
+            schema_version: 1,
+            name: "test".into(),
+            version: "1.0.0".into(),
+            description: String::new(),
+            steps: BTreeMap::from([("a".into(), producer), ("b".into(), consumer)]),
+            edges: vec![("a".into(), "b".into())],
+            budget: None,
+            params: Default::default(),
+        };
+        let errors = WorkflowValidator::validate(&def).unwrap_err();
+        assert_eq!(errors.len(), 2, "{errors:?}");
+        assert!(errors
+            .iter()
+            .any(|e| e.kind == ValidationErrorKind::SchemaMismatch
+                && e.message
+                    == "step 'b' input 'n' expects String, but 'a.result' is declared as Int"));
+        assert!(errors
+            .iter()
+            .any(|e| e.kind == ValidationErrorKind::UnknownInput && e.message.contains("ghost")));
+    }
+
+    #[test]
+    fn test_validate_params() {
+        let mut consumer = simple_source_step("steps/b.ax");
+        consumer.inputs.insert("n".into(), "params.limit".into());
+        consumer.inputs.insert("m".into(), "params.ghost".into());
+        consumer
+            .input_schema
+            .insert("n".into(), ValueSchema::String);
+        let def = WorkflowDef {
+            schema_version: 1,
+            name: "test".into(),
+            version: "1.0.0".into(),
+            description: String::new(),
+            steps: BTreeMap::from([
+                ("params".into(), simple_source_step("steps/a.ax")),
+                ("b".into(), consumer),
+            ]),
+            edges: vec![],
+            budget: None,
+            params: serde_json::from_str(
+                r#"{"limit": {"type": "int"}, "ratio": {"type": "float", "default": "high"}}"#,
+            )
+            .unwrap(),
+        };
+        let errors = WorkflowValidator::validate(&def).unwrap_err();
+        let messages: Vec<_> = errors.iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            messages,
+            [
+                "InvalidParam: step id 'params' is reserved in workflows that declare params",
+                "InvalidParam: default for param 'ratio': expected Float, got String",
+                "UnknownInput: step 'b' input 'm' references undeclared param 'ghost'",
+                "SchemaMismatch: step 'b' input 'n' expects String, \
+                 but 'params.limit' is declared as Int",
+            ]
+        );
+    }
+
     #[test]
     fn test_topological_order_linear() {
         let def = WorkflowDef {
@@ -421,6 +611,8 @@ mod tests {
                 ("c".into(), simple_source_step("c.ax")),
             ]),
             edges: vec![("a".into(), "b".into()), ("b".into(), "c".into())],
+            budget: None,
+            params: Default::default(),
         };
         let order = WorkflowValidator::topological_order(&def).unwrap();
         let a_pos = order.iter().position(|x| x == "a").unwrap();
@@ -449,6 +641,8 @@ mod tests {
                 ("b".into(), "d".into()),
                 ("c".into(), "d".into()),
             ],
+            budget: None,
+            params: Default::default(),
         };
         let order = WorkflowValidator::topological_order(&def).unwrap();
         let a_pos = order.iter().position(|x| x == "a").unwrap();
@@ -470,6 +664,8 @@ mod tests {
                 ("b".into(), step_b),
             ]),
             edges: vec![],
+            budget: None,
+            params: Default::default(),
         };
         assert!(WorkflowValidator::validate(&def).is_ok());
         let order = WorkflowValidator::topological_order(&def).unwrap();
@@ -489,6 +685,8 @@ mod tests {
                 ("c".into(), simple_source_step("c.ax")),
             ]),
             edges: vec![("a".into(), "b".into()), ("b".into(), "c".into())],
+            budget: None,
+            params: Default::default(),
         };
         let levels = WorkflowValidator::topological_levels(&def).unwrap();
         assert_eq!(levels, vec![vec!["a"], vec!["b"], vec!["c"]]);
@@ -519,6 +717,8 @@ mod tests {
                 ("merge".into(), merge),
             ]),
             edges: vec![],
+            budget: None,
+            params: Default::default(),
         };
         let levels = WorkflowValidator::topological_levels(&def).unwrap();
         assert_eq!(levels.len(), 3);
@@ -548,6 +748,8 @@ mod tests {
                 ("d".into(), d),
             ]),
             edges: vec![],
+            budget: None,
+            params: Default::default(),
         };
         let levels = WorkflowValidator::topological_levels(&def).unwrap();
         assert_eq!(levels, vec![vec!["a"], vec!["b", "c"], vec!["d"]]);
@@ -565,6 +767,8 @@ mod tests {
                 ("b".into(), simple_source_step("b.ax")),
             ]),
             edges: vec![("a".into(), "b".into()), ("b".into(), "a".into())],
+            budget: None,
+            params: Default::default(),
         };
         assert!(WorkflowValidator::topological_levels(&def).is_err());
     }
@@ -593,11 +797,17 @@ mod tests {
                         retry: None,
                         budget: None,
                         required_capability_versions: Default::default(),
+                        input_schema: Default::default(),
+                        output_schema: None,
+                        classification: None,
+                        isolation: Default::default(),
                     },
                 ),
                 ("store".into(), simple_source_step("store.ax")),
             ]),
             edges: vec![("approve".into(), "store".into())],
+            budget: None,
+            params: Default::default(),
         };
         assert!(WorkflowValidator::validate(&def).is_ok());
     }
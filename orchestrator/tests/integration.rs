@@ -16,6 +16,7 @@ fn test_dag_linear_chain() {
             node("B", &["A"], Role::Reviewer),
             node("C", &["B"], Role::Implementer),
         ],
+        strategy: Default::default(),
     };
     let mut sched = Scheduler::new(graph, 4);
     sched.validate().unwrap();
@@ -50,6 +51,7 @@ fn test_dag_diamond_dependency() {
             node("C", &["A"], Role::Implementer),
             node("D", &["B", "C"], Role::Reviewer),
         ],
+        strategy: Default::default(),
     };
     let mut sched = Scheduler::new(graph, 4);
     sched.validate().unwrap();
@@ -82,6 +84,7 @@ fn test_dag_cycle_rejected() {
             node("B", &["A"], Role::Implementer),
             node("C", &["B"], Role::Implementer),
         ],
+        strategy: Default::default(),
     };
     let sched = Scheduler::new(graph, 4);
     assert!(sched.validate().is_err());
@@ -98,6 +101,7 @@ fn test_dag_concurrency_enforcement() {
             node("B", &[], Role::Implementer),
             node("C", &[], Role::Implementer),
         ],
+        strategy: Default::default(),
     };
     let mut sched = Scheduler::new(graph, 2); // max 2 parallel
 
@@ -129,6 +133,7 @@ fn test_dag_failed_blocks_dependents() {
             node("A", &[], Role::Implementer),
             node("B", &["A"], Role::Implementer),
         ],
+        strategy: Default::default(),
     };
     let mut sched = Scheduler::new(graph, 4);
 
@@ -199,6 +204,9 @@ fn test_bundle_apply_rollback_roundtrip() {
             test: false,
             replay: false,
             diagnostics_count: None,
+            trace_stability: None,
+            difftest_corpus: vec![],
+            deterministic_build: false,
         },
         reviewer_checklist: vec![],
     };
@@ -246,6 +254,9 @@ fn test_bundle_apply_fails_on_mismatch() {
             test: false,
             replay: false,
             diagnostics_count: None,
+            trace_stability: None,
+            difftest_corpus: vec![],
+            deterministic_build: false,
         },
         reviewer_checklist: vec![],
     };
@@ -367,6 +378,7 @@ fn test_full_workflow_with_storage() {
             node("W1", &[], Role::Implementer),
             node("W2", &["W1"], Role::Reviewer),
         ],
+        strategy: Default::default(),
     };
 
     store.save_graph(&graph).unwrap();
@@ -451,6 +463,53 @@ fn test_conflict_plan_locks() {
 
 // === Helpers ===
 
+#[test]
+fn test_review_rejects_the_actor_who_applied_the_bundle() {
+    let workspace = tempfile::tempdir().unwrap();
+    let bundle = sample_bundle();
+    let bundle_path = workspace.path().join("bundle.patchbundle.json");
+    bundle.save(&bundle_path).unwrap();
+    let store = Store::new(&workspace.path().join("orchestrator/storage")).unwrap();
+    store
+        .record_activity(&ActivityEvent {
+            timestamp: "2026-02-20T00:00:00Z".into(),
+            actor: "carol".into(),
+            action: "apply".into(),
+            graph_id: None,
+            node_id: None,
+            detail: serde_json::json!({ "bundle_id": bundle.metadata.id }),
+        })
+        .unwrap();
+
+    // The bundle names someone else as author; carol still applied it.
+    assert_ne!(bundle.metadata.author, "carol");
+    let err = boruna_orchestrator::cli::cmd_review(workspace.path(), &bundle_path, None, "carol")
+        .unwrap_err();
+    assert!(err.contains("applied this bundle"), "got: {err}");
+}
+
+#[test]
+fn test_apply_checks_lock_conflicts_under_the_store_lock() {
+    let workspace = tempfile::tempdir().unwrap();
+    let bundle_path = workspace.path().join("bundle.patchbundle.json");
+    sample_bundle().save(&bundle_path).unwrap();
+    let store = Store::new(&workspace.path().join("orchestrator/storage")).unwrap();
+
+    // Another apply holds the store: this one must not get as far as the
+    // lock-conflict check, let alone the gates.
+    let _held = store.lock().unwrap();
+    let err = boruna_orchestrator::cli::cmd_apply(
+        workspace.path(),
+        &bundle_path,
+        None,
+        false,
+        "alice",
+        std::time::Duration::from_millis(50),
+    )
+    .unwrap_err();
+    assert!(err.contains("store is locked"), "got: {err}");
+}
+
 fn node(id: &str, deps: &[&str], role: Role) -> WorkNode {
     WorkNode {
         id: id.to_string(),
@@ -464,6 +523,8 @@ fn node(id: &str, deps: &[&str], role: Role) -> WorkNode {
         assigned_to: None,
         patch_bundle: None,
         review_result: None,
+        artifacts: vec![],
+        priority: Default::default(),
     }
 }
 
@@ -491,6 +552,9 @@ fn sample_bundle() -> PatchBundle {
             test: true,
             replay: false,
             diagnostics_count: None,
+            trace_stability: None,
+            difftest_corpus: vec![],
+            deterministic_build: false,
         },
         reviewer_checklist: vec!["Check backward compatibility".into()],
     }
@@ -47,6 +47,10 @@ fn retry_actually_sleeps_between_attempts() {
         }),
         budget: None,
         required_capability_versions: Default::default(),
+        input_schema: Default::default(),
+        output_schema: None,
+        classification: None,
+        isolation: Default::default(),
     };
     let def = WorkflowDef {
         schema_version: 1,
@@ -55,6 +59,8 @@ fn retry_actually_sleeps_between_attempts() {
         description: String::new(),
         steps: BTreeMap::from([("bad".into(), bad)]),
         edges: vec![],
+        budget: None,
+        params: Default::default(),
     };
     let options = RunOptions {
         policy: Some(Policy::allow_all()),
@@ -63,6 +69,9 @@ fn retry_actually_sleeps_between_attempts() {
         live: false,
         concurrency: 1,
         submit_only: false,
+        params: Default::default(),
+        profile: None,
+        container: None,
     };
 
     let start = Instant::now();
@@ -90,19 +90,19 @@ fn hex_encode(bytes: &[u8]) -> String {
 }
 
 fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
-    use sha2::{Digest, Sha256};
+    use boruna_hash::Hasher;
     const BLOCK: usize = 64;
     let mut k = vec![0u8; BLOCK];
     if key.len() > BLOCK {
-        let hash = Sha256::digest(key);
+        let hash = boruna_hash::sha256(key);
         k[..32].copy_from_slice(&hash);
     } else {
         k[..key.len()].copy_from_slice(key);
     }
     let ipad: Vec<u8> = k.iter().map(|b| b ^ 0x36).collect();
     let opad: Vec<u8> = k.iter().map(|b| b ^ 0x5c).collect();
-    let inner = Sha256::digest([ipad.as_slice(), data].concat());
-    hex_encode(&Sha256::digest(
+    let inner = boruna_hash::sha256([ipad.as_slice(), data].concat());
+    hex_encode(&boruna_hash::sha256(
         [opad.as_slice(), inner.as_slice()].concat(),
     ))
     .as_bytes()
@@ -114,12 +114,12 @@ fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
 /// Create the test bucket in MinIO using a SigV4-signed PUT request.
 /// MinIO requires AWS4-HMAC-SHA256 auth; basic auth is no longer accepted.
 fn create_bucket(host: &str, port: u16, bucket: &str) -> Result<(), Box<dyn std::error::Error>> {
-    use sha2::{Digest, Sha256};
+    use boruna_hash::Hasher;
     let now = chrono::Utc::now();
     let date_str = now.format("%Y%m%d").to_string();
     let datetime_str = now.format("%Y%m%dT%H%M%SZ").to_string();
     let endpoint_host = format!("{host}:{port}");
-    let empty_hash = hex_encode(&Sha256::digest(b""));
+    let empty_hash = hex_encode(&boruna_hash::sha256(b""));
     let canonical_headers = format!(
         "host:{endpoint_host}\nx-amz-content-sha256:{empty_hash}\nx-amz-date:{datetime_str}\n"
     );
@@ -129,7 +129,7 @@ fn create_bucket(host: &str, port: u16, bucket: &str) -> Result<(), Box<dyn std:
     let credential_scope = format!("{date_str}/us-east-1/s3/aws4_request");
     let string_to_sign = format!(
         "AWS4-HMAC-SHA256\n{datetime_str}\n{credential_scope}\n{}",
-        hex_encode(&Sha256::digest(canonical_req.as_bytes()))
+        hex_encode(&boruna_hash::sha256(canonical_req))
     );
     let k_date = hmac_sha256(
         format!("AWS4{MINIO_PASSWORD}").as_bytes(),
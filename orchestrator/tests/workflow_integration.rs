@@ -51,6 +51,9 @@ fn test_run_llm_code_review_completes() {
         workflow_dir: "../examples/workflows/llm_code_review".into(),
         concurrency: 1,
         submit_only: false,
+        params: Default::default(),
+        profile: None,
+        container: None,
     };
 
     let result = WorkflowRunner::run(&def, &options).unwrap();
@@ -71,6 +74,9 @@ fn test_run_document_processing_completes() {
         workflow_dir: "../examples/workflows/document_processing".into(),
         concurrency: 1,
         submit_only: false,
+        params: Default::default(),
+        profile: None,
+        container: None,
     };
 
     let result = WorkflowRunner::run(&def, &options).unwrap();
@@ -92,6 +98,9 @@ fn test_run_customer_support_triage_pauses_at_approval() {
         workflow_dir: "../examples/workflows/customer_support_triage".into(),
         concurrency: 1,
         submit_only: false,
+        params: Default::default(),
+        profile: None,
+        container: None,
     };
 
     let result = WorkflowRunner::run(&def, &options).unwrap();
@@ -120,6 +129,9 @@ fn test_workflow_determinism_same_result() {
         workflow_dir: "../examples/workflows/llm_code_review".into(),
         concurrency: 1,
         submit_only: false,
+        params: Default::default(),
+        profile: None,
+        container: None,
     };
 
     let result1 = WorkflowRunner::run(&def, &options).unwrap();
@@ -234,6 +246,84 @@ fn test_evidence_bundle_tamper_detected() {
     assert!(result.errors.iter().any(|e| e.contains("checksum")));
 }
 
+/// Run the llm_code_review example and record it into a bundle under
+/// `dir`, letting `events_for` swap a step's recorded event log.
+fn record_code_review(
+    dir: &std::path::Path,
+    events_for: impl Fn(&str, &boruna_vm::replay::EventLog) -> boruna_vm::replay::EventLog,
+) -> (WorkflowDef, std::path::PathBuf) {
+    let json =
+        std::fs::read_to_string("../examples/workflows/llm_code_review/workflow.json").unwrap();
+    let def: WorkflowDef = serde_json::from_str(&json).unwrap();
+    let policy = boruna_vm::capability_gateway::Policy::allow_all();
+    let options = RunOptions {
+        policy: Some(policy.clone()),
+        workflow_dir: "../examples/workflows/llm_code_review".into(),
+        ..RunOptions::default()
+    };
+    let result = WorkflowRunner::run(&def, &options).unwrap();
+
+    let mut builder = evidence::EvidenceBundleBuilder::new(dir, &result.run_id, &def.name).unwrap();
+    builder.add_workflow_def(&json).unwrap();
+    builder
+        .add_policy(&serde_json::to_string(&policy).unwrap())
+        .unwrap();
+    let mut audit = AuditLog::new();
+    for (id, sr) in &result.step_results {
+        let log = events_for(id, sr.event_log.as_ref().unwrap());
+        builder.add_event_log(id, &log.to_json().unwrap()).unwrap();
+        audit.append(log::AuditEvent::StepCompleted {
+            step_id: id.clone(),
+            output_hash: sr.output_hash.clone().unwrap(),
+            duration_ms: sr.duration_ms,
+        });
+    }
+    builder.finalize(&audit).unwrap();
+    (def, dir.join(&result.run_id))
+}
+
+#[test]
+fn test_verify_replays_recorded_run() {
+    let dir = tempfile::tempdir().unwrap();
+    let (def, bundle_dir) = record_code_review(dir.path(), |_, log| log.clone());
+    assert!(bundle_dir.join("events/analyze.json").exists());
+
+    let report =
+        WorkflowRunner::verify(&def, "../examples/workflows/llm_code_review", &bundle_dir).unwrap();
+    assert!(report.passed(), "{report:?}");
+    assert_eq!(report.steps.len(), 3);
+    assert!(report
+        .steps
+        .iter()
+        .all(|s| s.status == StepVerifyStatus::Matched));
+}
+
+#[test]
+fn test_verify_flags_changed_capability_calls() {
+    let dir = tempfile::tempdir().unwrap();
+    let (def, bundle_dir) = record_code_review(dir.path(), |id, log| {
+        let mut log = log.clone();
+        if id == "analyze" {
+            let cap = boruna_bytecode::Capability::TimeNow;
+            log.log_cap_call(&cap, &[]);
+            log.log_cap_result(&cap, &boruna_bytecode::Value::Int(0));
+        }
+        log
+    });
+
+    let report =
+        WorkflowRunner::verify(&def, "../examples/workflows/llm_code_review", &bundle_dir).unwrap();
+    assert!(!report.passed());
+    let analyze = report
+        .steps
+        .iter()
+        .find(|s| s.step_id == "analyze")
+        .unwrap();
+    // Same output, different calls: a divergence, not a hash mismatch.
+    assert_eq!(analyze.status, StepVerifyStatus::Diverged);
+    assert_eq!(analyze.expected_hash, analyze.actual_hash);
+}
+
 // === Schema Compatibility Tests (sprint W4) ===
 
 #[test]
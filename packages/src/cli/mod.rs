@@ -1,15 +1,16 @@
 use std::collections::BTreeMap;
 use std::path::Path;
 
+use crate::error::PkgError;
 use crate::resolver;
 use crate::spec::{CapabilityPolicy, PackageManifest};
 use crate::storage::Registry;
 
 /// Initialize a new package manifest in the given directory.
-pub fn cmd_init(dir: &Path) -> Result<(), String> {
+pub fn cmd_init(dir: &Path) -> Result<(), PkgError> {
     let manifest_path = dir.join("package.ax.json");
     if manifest_path.exists() {
-        return Err("package.ax.json already exists".into());
+        return Err(PkgError::Manifest("package.ax.json already exists".into()));
     }
 
     let name = dir
@@ -29,48 +30,54 @@ pub fn cmd_init(dir: &Path) -> Result<(), String> {
         integrity: None,
     };
 
-    manifest.save(&manifest_path)?;
+    manifest.save(&manifest_path).map_err(PkgError::Manifest)?;
 
     // Create src directory
     let src_dir = dir.join("src");
-    std::fs::create_dir_all(&src_dir).map_err(|e| format!("create src dir: {e}"))?;
+    std::fs::create_dir_all(&src_dir)
+        .map_err(|e| PkgError::Manifest(format!("create src dir: {e}")))?;
 
     println!("initialized package: {}", manifest.name);
     Ok(())
 }
 
 /// Add a dependency to the manifest.
-pub fn cmd_add(dir: &Path, name: &str, version: &str) -> Result<(), String> {
+pub fn cmd_add(dir: &Path, name: &str, version: &str) -> Result<(), PkgError> {
     let manifest_path = dir.join("package.ax.json");
-    let mut manifest = PackageManifest::load(&manifest_path)?;
+    let mut manifest = PackageManifest::load(&manifest_path).map_err(PkgError::Manifest)?;
     manifest.dependencies.insert(name.into(), version.into());
-    manifest.save(&manifest_path)?;
+    manifest.save(&manifest_path).map_err(PkgError::Manifest)?;
     println!("added dependency: {name}@{version}");
     Ok(())
 }
 
 /// Remove a dependency from the manifest.
-pub fn cmd_remove(dir: &Path, name: &str) -> Result<(), String> {
+pub fn cmd_remove(dir: &Path, name: &str) -> Result<(), PkgError> {
     let manifest_path = dir.join("package.ax.json");
-    let mut manifest = PackageManifest::load(&manifest_path)?;
+    let mut manifest = PackageManifest::load(&manifest_path).map_err(PkgError::Manifest)?;
     if manifest.dependencies.remove(name).is_none() {
-        return Err(format!("dependency '{name}' not found"));
+        return Err(PkgError::DependencyNotFound(name.into()));
     }
-    manifest.save(&manifest_path)?;
+    manifest.save(&manifest_path).map_err(PkgError::Manifest)?;
     println!("removed dependency: {name}");
     Ok(())
 }
 
 /// Resolve dependencies and generate lockfile.
-pub fn cmd_resolve(dir: &Path, registry_path: &Path) -> Result<(), String> {
-    let manifest = PackageManifest::load(&dir.join("package.ax.json"))?;
-    manifest.validate().map_err(|errs| errs.join("; "))?;
+pub fn cmd_resolve(dir: &Path, registry_path: &Path) -> Result<(), PkgError> {
+    let manifest =
+        PackageManifest::load(&dir.join("package.ax.json")).map_err(PkgError::Manifest)?;
+    manifest
+        .validate()
+        .map_err(|errs| PkgError::Manifest(errs.join("; ")))?;
 
-    let registry = Registry::new(registry_path)?;
-    let result = resolver::resolve(&manifest, &registry)?;
-    let lockfile = resolver::generate_lockfile(&result, &registry)?;
+    let registry = Registry::new(registry_path).map_err(PkgError::Registry)?;
+    let result = resolver::resolve(&manifest, &registry).map_err(PkgError::Resolution)?;
+    let lockfile = resolver::generate_lockfile(&result, &registry).map_err(PkgError::Resolution)?;
 
-    lockfile.save(&dir.join("llm.lock.json"))?;
+    lockfile
+        .save(&dir.join("llm.lock.json"))
+        .map_err(PkgError::Manifest)?;
 
     println!("resolved {} packages:", result.packages.len());
     for id in &result.install_order {
@@ -80,41 +87,45 @@ pub fn cmd_resolve(dir: &Path, registry_path: &Path) -> Result<(), String> {
 }
 
 /// Resolve and verify all packages exist.
-pub fn cmd_install(dir: &Path, registry_path: &Path) -> Result<(), String> {
-    let manifest = PackageManifest::load(&dir.join("package.ax.json"))?;
-    manifest.validate().map_err(|errs| errs.join("; "))?;
+pub fn cmd_install(dir: &Path, registry_path: &Path) -> Result<(), PkgError> {
+    let manifest =
+        PackageManifest::load(&dir.join("package.ax.json")).map_err(PkgError::Manifest)?;
+    manifest
+        .validate()
+        .map_err(|errs| PkgError::Manifest(errs.join("; ")))?;
 
-    let registry = Registry::new(registry_path)?;
-    let result = resolver::resolve(&manifest, &registry)?;
+    let registry = Registry::new(registry_path).map_err(PkgError::Registry)?;
+    let result = resolver::resolve(&manifest, &registry).map_err(PkgError::Resolution)?;
 
     // Verify all packages
     for (id, pkg) in &result.packages {
         let pkg_dir = registry.package_dir(&pkg.name, &pkg.version);
         if !pkg_dir.exists() {
-            return Err(format!("package {id} not found in registry"));
+            return Err(PkgError::Registry(format!(
+                "package {id} not found in registry"
+            )));
         }
     }
 
     // Generate lockfile
-    let lockfile = resolver::generate_lockfile(&result, &registry)?;
-    lockfile.save(&dir.join("llm.lock.json"))?;
+    let lockfile = resolver::generate_lockfile(&result, &registry).map_err(PkgError::Resolution)?;
+    lockfile
+        .save(&dir.join("llm.lock.json"))
+        .map_err(PkgError::Manifest)?;
 
     // Check capability policy
     let policy_path = dir.join("policy.ax.json");
     if policy_path.exists() {
-        let data =
-            std::fs::read_to_string(&policy_path).map_err(|e| format!("read policy: {e}"))?;
-        let policy: CapabilityPolicy =
-            serde_json::from_str(&data).map_err(|e| format!("parse policy: {e}"))?;
-        policy.validate()?;
+        let data = std::fs::read_to_string(&policy_path)
+            .map_err(|e| PkgError::Manifest(format!("read policy: {e}")))?;
+        let policy: CapabilityPolicy = serde_json::from_str(&data)
+            .map_err(|e| PkgError::Manifest(format!("parse policy: {e}")))?;
+        policy.validate().map_err(PkgError::Manifest)?;
 
         let caps = resolver::aggregate_capabilities(&result);
-        if let Err(violations) = policy.check_capabilities(&caps) {
-            return Err(format!(
-                "capability policy violation: dependencies require forbidden capabilities: {}",
-                violations.join(", ")
-            ));
-        }
+        policy
+            .check_capabilities(&caps)
+            .map_err(PkgError::CapabilityPolicy)?;
     }
 
     println!("installed {} packages", result.packages.len());
@@ -122,19 +133,20 @@ pub fn cmd_install(dir: &Path, registry_path: &Path) -> Result<(), String> {
 }
 
 /// Publish package to local registry.
-pub fn cmd_publish(dir: &Path, registry_path: &Path) -> Result<(), String> {
-    let registry = Registry::new(registry_path)?;
-    let hash = registry.publish(dir)?;
+pub fn cmd_publish(dir: &Path, registry_path: &Path) -> Result<(), PkgError> {
+    let registry = Registry::new(registry_path).map_err(PkgError::Registry)?;
+    let hash = registry.publish(dir).map_err(PkgError::Registry)?;
 
-    let manifest = PackageManifest::load(&dir.join("package.ax.json"))?;
+    let manifest =
+        PackageManifest::load(&dir.join("package.ax.json")).map_err(PkgError::Manifest)?;
     println!("published {}@{}", manifest.name, manifest.version);
     println!("  integrity: {hash}");
     Ok(())
 }
 
 /// Verify all packages in registry match their hashes.
-pub fn cmd_verify(registry_path: &Path) -> Result<(), String> {
-    let registry = Registry::new(registry_path)?;
+pub fn cmd_verify(registry_path: &Path) -> Result<(), PkgError> {
+    let registry = Registry::new(registry_path).map_err(PkgError::Registry)?;
     match registry.verify_all() {
         Ok(verified) => {
             println!("verified {} packages:", verified.len());
@@ -147,15 +159,16 @@ pub fn cmd_verify(registry_path: &Path) -> Result<(), String> {
             for f in &failures {
                 eprintln!("  FAIL: {f}");
             }
-            Err(format!("{} packages failed verification", failures.len()))
+            Err(PkgError::Integrity(failures))
         }
     }
 }
 
 /// Print dependency tree.
-pub fn cmd_tree(dir: &Path, registry_path: &Path) -> Result<(), String> {
-    let manifest = PackageManifest::load(&dir.join("package.ax.json"))?;
-    let registry = Registry::new(registry_path)?;
+pub fn cmd_tree(dir: &Path, registry_path: &Path) -> Result<(), PkgError> {
+    let manifest =
+        PackageManifest::load(&dir.join("package.ax.json")).map_err(PkgError::Manifest)?;
+    let registry = Registry::new(registry_path).map_err(PkgError::Registry)?;
 
     println!("{}@{}", manifest.name, manifest.version);
     print_tree_deps(&manifest, &registry, "", true)?;
@@ -167,7 +180,7 @@ fn print_tree_deps(
     registry: &Registry,
     prefix: &str,
     _is_root: bool,
-) -> Result<(), String> {
+) -> Result<(), PkgError> {
     let deps: Vec<_> = manifest.dependencies.iter().collect();
     for (i, (name, version)) in deps.iter().enumerate() {
         let is_last = i == deps.len() - 1;
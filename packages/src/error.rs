@@ -0,0 +1,62 @@
+use std::fmt;
+
+/// Errors from the `boruna-pkg` commands, classified for the JSON
+/// envelope. The library layers below (`spec`, `storage`, `resolver`)
+/// report plain strings; `cli` wraps them by the step that failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PkgError {
+    /// `package.ax.json`, `policy.ax.json` or `llm.lock.json` could not
+    /// be read, written or validated.
+    Manifest(String),
+    /// `remove` named a dependency the manifest does not have.
+    DependencyNotFound(String),
+    /// Missing package, version conflict or dependency cycle.
+    Resolution(String),
+    Registry(String),
+    /// One message per package that failed verification.
+    Integrity(Vec<String>),
+    /// The capabilities dependencies require that the policy forbids.
+    CapabilityPolicy(Vec<String>),
+}
+
+impl fmt::Display for PkgError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Manifest(msg) | Self::Resolution(msg) | Self::Registry(msg) => f.write_str(msg),
+            Self::DependencyNotFound(name) => write!(f, "dependency '{name}' not found"),
+            Self::Integrity(failures) => {
+                write!(f, "{} packages failed verification", failures.len())
+            }
+            Self::CapabilityPolicy(violations) => write!(
+                f,
+                "capability policy violation: dependencies require forbidden capabilities: {}",
+                violations.join(", ")
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PkgError {}
+
+impl boruna_errors::BorunaError for PkgError {
+    fn error_code(&self) -> &'static boruna_errors::ErrorCode {
+        use boruna_errors as codes;
+        match self {
+            Self::Manifest(_) => &codes::PKG_MANIFEST,
+            Self::DependencyNotFound(_) => &codes::PKG_DEPENDENCY_NOT_FOUND,
+            Self::Resolution(_) => &codes::PKG_RESOLUTION,
+            Self::Registry(_) => &codes::PKG_REGISTRY,
+            Self::Integrity(_) => &codes::PKG_INTEGRITY,
+            Self::CapabilityPolicy(_) => &codes::PKG_CAPABILITY_POLICY,
+        }
+    }
+
+    fn error_data(&self) -> serde_json::Value {
+        match self {
+            Self::DependencyNotFound(name) => serde_json::json!({ "name": name }),
+            Self::Integrity(failures) => serde_json::json!({ "failures": failures }),
+            Self::CapabilityPolicy(violations) => serde_json::json!({ "forbidden": violations }),
+            _ => serde_json::Value::Null,
+        }
+    }
+}
@@ -1,4 +1,7 @@
 pub mod cli;
+pub mod error;
 pub mod resolver;
 pub mod spec;
 pub mod storage;
+
+pub use error::PkgError;
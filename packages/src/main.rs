@@ -1,14 +1,20 @@
 use std::path::PathBuf;
 
-use clap::{Parser, Subcommand};
+use boruna_errors::BorunaError;
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
 
 #[derive(Parser)]
 #[command(name = "boruna-pkg", about = "Boruna Package Manager")]
 struct Cli {
-    /// Registry directory (default: ./packages/registry)
+    /// Registry directory (default: ./packages/registry, or `registry`
+    /// in `boruna.toml`)
     #[arg(long, default_value = "packages/registry")]
     registry: PathBuf,
 
+    /// Report a failure as a JSON error envelope on stderr
+    #[arg(long, global = true)]
+    json: bool,
+
     #[command(subcommand)]
     command: Command,
 }
@@ -68,7 +74,16 @@ enum Command {
 }
 
 fn main() {
-    let cli = Cli::parse();
+    let config = boruna_config::Config::load().unwrap_or_else(|e| {
+        if boruna_errors::json_requested() {
+            eprintln!("{}", e.envelope().to_json());
+        } else {
+            eprintln!("error: {e}");
+        }
+        std::process::exit(1);
+    });
+    let matches = config.apply(Cli::command()).get_matches();
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
     let result = match cli.command {
         Command::Init { dir } => boruna_pkg::cli::cmd_init(&dir),
         Command::Add { name, version, dir } => boruna_pkg::cli::cmd_add(&dir, &name, &version),
@@ -81,7 +96,11 @@ fn main() {
     };
 
     if let Err(e) = result {
-        eprintln!("error: {e}");
+        if cli.json {
+            eprintln!("{}", e.envelope().to_json());
+        } else {
+            eprintln!("error: {e}");
+        }
         std::process::exit(1);
     }
 }
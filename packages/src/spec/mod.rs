@@ -1,5 +1,5 @@
+use boruna_hash::{Digest, HashAlgorithm, Hasher};
 use serde::{Deserialize, Serialize};
-use sha2::{Digest, Sha256};
 use std::collections::BTreeMap;
 
 // ── Package Manifest ──
@@ -217,7 +217,17 @@ pub fn compute_content_hash(
     pkg_dir: &std::path::Path,
     dep_hashes: &BTreeMap<String, String>,
 ) -> Result<String, String> {
-    let mut hasher = Sha256::new();
+    compute_content_hash_with(pkg_dir, dep_hashes, HashAlgorithm::Sha256)
+}
+
+/// [`compute_content_hash`] with a chosen algorithm; the result is tagged
+/// with it (`sha256:…`, `blake3:…`).
+pub fn compute_content_hash_with(
+    pkg_dir: &std::path::Path,
+    dep_hashes: &BTreeMap<String, String>,
+    algorithm: HashAlgorithm,
+) -> Result<String, String> {
+    let mut hasher = Hasher::new(algorithm);
 
     // 1. Source files sorted by path
     let src_dir = pkg_dir.join("src");
@@ -254,8 +264,7 @@ pub fn compute_content_hash(
         hasher.update(hash.as_bytes());
     }
 
-    let result = hasher.finalize();
-    Ok(format!("sha256:{:x}", result))
+    Ok(hasher.finalize().to_string())
 }
 
 /// Verify a package's hash matches its HASH file.
@@ -268,8 +277,9 @@ pub fn verify_hash(
         return Err("HASH file not found".into());
     }
     let expected = std::fs::read_to_string(&hash_file).map_err(|e| format!("read HASH: {e}"))?;
-    let actual = compute_content_hash(pkg_dir, dep_hashes)?;
-    Ok(expected.trim() == actual)
+    let expected = Digest::parse(expected.trim()).map_err(|e| format!("HASH: {e}"))?;
+    let actual = compute_content_hash_with(pkg_dir, dep_hashes, expected.algorithm())?;
+    Ok(expected.to_string() == actual)
 }
 
 // ── Helpers ──
@@ -513,6 +523,27 @@ mod tests {
         assert_ne!(h1, h2);
     }
 
+    #[test]
+    fn test_verify_hash_follows_the_recorded_algorithm() {
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("src");
+        std::fs::create_dir_all(&src).unwrap();
+        std::fs::write(src.join("core.ax"), "fn main() -> Int { 42 }").unwrap();
+        sample_manifest()
+            .save(&dir.path().join("package.ax.json"))
+            .unwrap();
+
+        let deps = BTreeMap::new();
+        for algorithm in HashAlgorithm::ALL {
+            let hash = compute_content_hash_with(dir.path(), &deps, algorithm).unwrap();
+            assert!(hash.starts_with(&format!("{algorithm}:")));
+            std::fs::write(dir.path().join("HASH"), &hash).unwrap();
+            assert!(verify_hash(dir.path(), &deps).unwrap());
+        }
+        std::fs::write(src.join("core.ax"), "fn main() -> Int { 99 }").unwrap();
+        assert!(!verify_hash(dir.path(), &deps).unwrap());
+    }
+
     fn sample_manifest() -> PackageManifest {
         PackageManifest {
             name: "test.pkg".into(),
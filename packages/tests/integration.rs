@@ -374,5 +374,15 @@ fn test_full_workflow_with_policy_violation() {
 
     // Install should fail due to policy violation
     let err = boruna_pkg::cli::cmd_install(app_dir.path(), reg_dir.path()).unwrap_err();
-    assert!(err.contains("capability policy violation"));
+    assert!(err.to_string().contains("capability policy violation"));
+    assert_eq!(
+        err,
+        boruna_pkg::PkgError::CapabilityPolicy(vec!["fs.write".into()])
+    );
+    let env = boruna_errors::BorunaError::envelope(&err);
+    assert_eq!(
+        (env.code.as_str(), env.kind.as_str()),
+        ("PKG006", "pkg.capability_policy")
+    );
+    assert_eq!(env.data["forbidden"][0], "fs.write");
 }
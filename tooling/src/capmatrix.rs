@@ -0,0 +1,769 @@
+//! Per-message capability reachability for `boruna lang caps`.
+//!
+//! A framework app's `update(state, msg)` is abstractly interpreted once per
+//! message tag to find which capability call sites that tag can reach. A
+//! site is either a direct `CapCall` or an `Effect` record whose `kind` is
+//! known (mapped to a capability via [`EffectKind::capability_name`]).
+//!
+//! The interpretation is a forward dataflow over each function's bytecode.
+//! Abstract values are constants, records of abstract fields, the message
+//! tag itself, or unknown. Branches on a constant condition follow one edge
+//! only, which is what separates the tags; everything else forks and joins
+//! at merge points. Calls are analysed in context (arguments included), up
+//! to a depth limit. Recursion, indirect calls with an unknown target,
+//! actor spawns and a blown step budget fall back to the conservative
+//! transitive scan and mark the row inexact.
+//!
+//! Candidate tags are the string constants `update` compares `msg.tag`
+//! against. One more row, [`OTHER_TAG`], covers every other tag.
+
+use std::collections::BTreeSet;
+
+use boruna_bytecode::{Capability, Module, Op, Value};
+use boruna_framework::effect::EffectKind;
+use boruna_vm::capability_gateway::Policy;
+use serde::Serialize;
+
+/// Abstract interpretation steps allowed per row before falling back.
+pub const MAX_STEPS: u64 = 200_000;
+
+/// Row tag standing for every tag not listed explicitly.
+pub const OTHER_TAG: &str = "*";
+
+/// Calls nested deeper than this are scanned conservatively.
+const MAX_CALL_DEPTH: usize = 32;
+
+/// Records nested deeper than this are widened to unknown.
+const MAX_RECORD_DEPTH: usize = 4;
+
+/// How a capability is reached from a call site.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SiteKind {
+    /// A `CapCall` instruction.
+    CapCall,
+    /// An `Effect` record returned for the runtime to execute.
+    Effect,
+}
+
+/// One reachable capability call site.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct CallSite {
+    pub function: String,
+    pub ip: u32,
+    pub capability: String,
+    pub kind: SiteKind,
+}
+
+/// The capabilities one message tag can reach.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MessageRow {
+    /// Message tag, or [`OTHER_TAG`].
+    pub tag: String,
+    /// Capability names, sorted by capability id.
+    pub capabilities: Vec<String>,
+    pub sites: Vec<CallSite>,
+    /// False when part of the row came from the conservative fallback, so
+    /// it may list capabilities the tag cannot actually reach.
+    pub exact: bool,
+}
+
+/// Per-message capability matrix of a framework app.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CapabilityMatrix {
+    pub messages: Vec<MessageRow>,
+}
+
+impl CapabilityMatrix {
+    /// Every capability some message can reach, sorted by id.
+    pub fn capabilities(&self) -> Vec<Capability> {
+        let ids: BTreeSet<u32> = self
+            .messages
+            .iter()
+            .flat_map(|row| &row.capabilities)
+            .filter_map(|name| Capability::from_name(name))
+            .map(|cap| cap.id())
+            .collect();
+        ids.into_iter().filter_map(Capability::from_id).collect()
+    }
+
+    /// Tags of the messages that can reach `cap`.
+    pub fn messages_reaching(&self, cap: &Capability) -> Vec<&str> {
+        self.messages
+            .iter()
+            .filter(|row| row.capabilities.iter().any(|c| c == cap.name()))
+            .map(|row| row.tag.as_str())
+            .collect()
+    }
+
+    /// The tightest policy that lets every message run: deny by default,
+    /// allow exactly the reachable capabilities, unbudgeted.
+    pub fn to_policy(&self) -> Policy {
+        let mut policy = Policy::deny_all();
+        for cap in self.capabilities() {
+            policy.allow(&cap, 0);
+        }
+        policy
+    }
+}
+
+/// Build the capability matrix of a framework app. Returns `None` when the
+/// module has no `update` function.
+pub fn analyze(module: &Module) -> Option<CapabilityMatrix> {
+    let update = module.functions.iter().position(|f| f.name == "update")? as u32;
+
+    let mut discover = Analyzer::new(module, Mode::Discover, BTreeSet::new());
+    discover.run(update);
+    let candidates = discover.candidates;
+
+    let mut messages: Vec<MessageRow> = candidates
+        .iter()
+        .map(|tag| {
+            let mut a = Analyzer::new(module, Mode::Fixed(tag.clone()), candidates.clone());
+            a.run(update);
+            a.into_row(tag.clone())
+        })
+        .collect();
+    let mut other = Analyzer::new(module, Mode::Other, candidates);
+    other.run(update);
+    messages.push(other.into_row(OTHER_TAG.to_string()));
+
+    Some(CapabilityMatrix { messages })
+}
+
+/// What the analysis knows about a value.
+#[derive(Debug, Clone, PartialEq)]
+enum Abs {
+    Known(Value),
+    Record(u32, Vec<Abs>),
+    /// The message tag of the row being analysed (discovery and
+    /// [`OTHER_TAG`] rows; fixed rows use `Known`).
+    Tag,
+    Unknown,
+}
+
+impl Abs {
+    fn join(&self, other: &Abs) -> Abs {
+        match (self, other) {
+            (a, b) if a == b => a.clone(),
+            (Abs::Record(ta, fa), Abs::Record(tb, fb)) if ta == tb && fa.len() == fb.len() => {
+                Abs::Record(*ta, fa.iter().zip(fb).map(|(a, b)| a.join(b)).collect())
+            }
+            _ => Abs::Unknown,
+        }
+    }
+
+    fn depth(&self) -> usize {
+        match self {
+            Abs::Record(_, fields) => 1 + fields.iter().map(Abs::depth).max().unwrap_or(0),
+            _ => 0,
+        }
+    }
+
+    fn truth(&self) -> Option<bool> {
+        match self {
+            Abs::Known(v) => Some(v.is_truthy()),
+            _ => None,
+        }
+    }
+
+    fn known_str(&self) -> Option<&str> {
+        match self {
+            Abs::Known(Value::String(s)) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Mode {
+    /// Collect the strings `msg.tag` is compared against.
+    Discover,
+    /// Analyse one known tag.
+    Fixed(String),
+    /// Analyse a tag equal to none of the candidates.
+    Other,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Frame {
+    stack: Vec<Abs>,
+    locals: Vec<Abs>,
+}
+
+impl Frame {
+    fn pop(&mut self) -> Abs {
+        self.stack.pop().unwrap_or(Abs::Unknown)
+    }
+
+    fn pop_n(&mut self, n: usize) -> Vec<Abs> {
+        let mut items: Vec<Abs> = (0..n).map(|_| self.pop()).collect();
+        items.reverse();
+        items
+    }
+
+    /// Join `other` into `self`; true if anything changed.
+    fn join(&mut self, other: &Frame) -> bool {
+        let before = self.clone();
+        for (a, b) in self.stack.iter_mut().zip(&other.stack) {
+            *a = a.join(b);
+        }
+        for (a, b) in self.locals.iter_mut().zip(&other.locals) {
+            *a = a.join(b);
+        }
+        *self != before
+    }
+}
+
+struct Analyzer<'m> {
+    module: &'m Module,
+    mode: Mode,
+    candidates: BTreeSet<String>,
+    effect_type: Option<u32>,
+    sites: BTreeSet<CallSite>,
+    call_chain: Vec<u32>,
+    steps: u64,
+    exact: bool,
+    exhausted: bool,
+}
+
+impl<'m> Analyzer<'m> {
+    fn new(module: &'m Module, mode: Mode, candidates: BTreeSet<String>) -> Self {
+        let effect_type = module
+            .types
+            .iter()
+            .position(|t| t.name == "Effect")
+            .map(|i| i as u32);
+        Analyzer {
+            module,
+            mode,
+            candidates,
+            effect_type,
+            sites: BTreeSet::new(),
+            call_chain: Vec::new(),
+            steps: 0,
+            exact: true,
+            exhausted: false,
+        }
+    }
+
+    fn run(&mut self, update: u32) {
+        let tag = match &self.mode {
+            Mode::Fixed(tag) => Abs::Known(Value::String(tag.clone())),
+            Mode::Discover | Mode::Other => Abs::Tag,
+        };
+        // The runtime passes the message as `Record { type_id: 0, [tag, payload] }`.
+        let msg = Abs::Record(0, vec![tag, Abs::Unknown]);
+        self.exec(update, vec![Abs::Unknown, msg]);
+        if self.exhausted {
+            self.sites.clear();
+            self.conservative(update);
+        }
+    }
+
+    fn into_row(self, tag: String) -> MessageRow {
+        let ids: BTreeSet<u32> = self
+            .sites
+            .iter()
+            .filter_map(|s| Capability::from_name(&s.capability))
+            .map(|c| c.id())
+            .collect();
+        MessageRow {
+            tag,
+            capabilities: ids
+                .into_iter()
+                .filter_map(Capability::from_id)
+                .map(|c| c.name().to_string())
+                .collect(),
+            sites: self.sites.into_iter().collect(),
+            exact: self.exact,
+        }
+    }
+
+    fn site(&mut self, func: u32, ip: usize, cap: &str, kind: SiteKind) {
+        self.sites.insert(CallSite {
+            function: self.module.functions[func as usize].name.clone(),
+            ip: ip as u32,
+            capability: cap.to_string(),
+            kind,
+        });
+    }
+
+    /// Every capability name an `Effect` record with an unknown kind could
+    /// stand for: the effect kinds spelled anywhere in the constant pool.
+    fn possible_effect_caps(&self) -> Vec<&'static str> {
+        let caps: BTreeSet<&'static str> = self
+            .module
+            .constants
+            .iter()
+            .filter_map(|c| match c {
+                Value::String(s) => EffectKind::parse_str(s),
+                _ => None,
+            })
+            .map(|k| k.capability_name())
+            .collect();
+        caps.into_iter().collect()
+    }
+
+    /// Record every site in `func` and its transitive callees, regardless
+    /// of reachability, and mark the row inexact.
+    fn conservative(&mut self, func: u32) {
+        self.exact = false;
+        let effect_caps = self.possible_effect_caps();
+        let mut visited = BTreeSet::new();
+        let mut pending = vec![func];
+        while let Some(idx) = pending.pop() {
+            if !visited.insert(idx) {
+                continue;
+            }
+            let Some(f) = self.module.functions.get(idx as usize) else {
+                continue;
+            };
+            for (ip, op) in f.code.iter().enumerate() {
+                match op {
+                    Op::CapCall(cap_id, _) | Op::CapBatch(cap_id) | Op::CapSpawn(cap_id, _) => {
+                        if let Some(cap) = Capability::from_id(*cap_id) {
+                            self.site(idx, ip, cap.name(), SiteKind::CapCall);
+                        }
+                    }
+                    Op::MakeRecord(ty, _) if Some(*ty) == self.effect_type => {
+                        for cap in &effect_caps {
+                            self.site(idx, ip, cap, SiteKind::Effect);
+                        }
+                    }
+                    Op::Call(callee, _) | Op::SpawnActor(callee) => pending.push(*callee),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    fn compare(&mut self, a: &Abs, b: &Abs) -> Abs {
+        match (a, b) {
+            (Abs::Known(x), Abs::Known(y)) => Abs::Known(Value::Bool(x == y)),
+            (Abs::Tag, other) | (other, Abs::Tag) => match other.known_str() {
+                Some(s) if self.mode == Mode::Discover => {
+                    self.candidates.insert(s.to_string());
+                    Abs::Unknown
+                }
+                Some(s) if self.mode == Mode::Other && self.candidates.contains(s) => {
+                    Abs::Known(Value::Bool(false))
+                }
+                _ => Abs::Unknown,
+            },
+            _ => Abs::Unknown,
+        }
+    }
+
+    /// Abstractly run `func` on `args`; returns the join of its results.
+    fn exec(&mut self, func: u32, args: Vec<Abs>) -> Abs {
+        let module = self.module;
+        let Some(f) = module.functions.get(func as usize) else {
+            self.exact = false;
+            return Abs::Unknown;
+        };
+        let mut locals = vec![Abs::Known(Value::Unit); f.locals as usize];
+        for (slot, arg) in locals.iter_mut().zip(args) {
+            *slot = arg;
+        }
+
+        self.call_chain.push(func);
+        let mut frames: Vec<Option<Frame>> = vec![None; f.code.len()];
+        let mut worklist = BTreeSet::new();
+        if !f.code.is_empty() {
+            frames[0] = Some(Frame {
+                stack: Vec::new(),
+                locals,
+            });
+            worklist.insert(0usize);
+        }
+        let mut result: Option<Abs> = None;
+
+        while let Some(ip) = worklist.pop_first() {
+            self.steps += 1;
+            if self.steps > MAX_STEPS {
+                self.exhausted = true;
+            }
+            if self.exhausted {
+                break;
+            }
+            let mut fr = frames[ip].clone().expect("queued ip has a frame");
+            let mut next = vec![ip + 1];
+            match &f.code[ip] {
+                Op::PushConst(idx) => {
+                    let v = module
+                        .constants
+                        .get(*idx as usize)
+                        .map_or(Abs::Unknown, |c| Abs::Known(c.clone()));
+                    fr.stack.push(v);
+                }
+                Op::LoadLocal(idx) => {
+                    let v = fr.locals.get(*idx as usize).cloned();
+                    fr.stack.push(v.unwrap_or(Abs::Unknown));
+                }
+                Op::StoreLocal(idx) => {
+                    let v = fr.pop();
+                    let idx = *idx as usize;
+                    if idx >= fr.locals.len() {
+                        fr.locals.resize(idx + 1, Abs::Known(Value::Unit));
+                    }
+                    fr.locals[idx] = v;
+                }
+                Op::LoadGlobal(_) | Op::ReceiveMsg => fr.stack.push(Abs::Unknown),
+                Op::StoreGlobal(_) | Op::Pop | Op::EmitUi => {
+                    fr.pop();
+                }
+                Op::Call(callee, n) => {
+                    let args = fr.pop_n(*n as usize);
+                    let v = self.call(*callee, args);
+                    fr.stack.push(v);
+                }
+                Op::CallIndirect(n) => {
+                    let callee = fr.pop();
+                    let args = fr.pop_n(*n as usize);
+                    let v = match callee {
+                        Abs::Known(Value::FnRef(target)) => self.call(target, args),
+                        _ => {
+                            for idx in 0..module.functions.len() as u32 {
+                                self.conservative(idx);
+                            }
+                            Abs::Unknown
+                        }
+                    };
+                    fr.stack.push(v);
+                }
+                Op::Ret | Op::Halt => {
+                    let v = fr.pop();
+                    result = Some(match result {
+                        Some(r) => r.join(&v),
+                        None => v,
+                    });
+                    next.clear();
+                }
+                Op::Jmp(target) => next = vec![*target as usize],
+                Op::JmpIf(target) | Op::JmpIfNot(target) => {
+                    let jump_when = matches!(f.code[ip], Op::JmpIf(_));
+                    next = match fr.pop().truth() {
+                        Some(t) if t == jump_when => vec![*target as usize],
+                        Some(_) => vec![ip + 1],
+                        None => vec![ip + 1, *target as usize],
+                    };
+                }
+                Op::Match(table) => {
+                    let val = fr.pop();
+                    let arms = f.match_tables.get(*table as usize).cloned();
+                    let arms = arms.unwrap_or_default();
+                    next.clear();
+                    let mut forks = Vec::new();
+                    match &val {
+                        Abs::Known(v) => {
+                            let tag = match v {
+                                Value::Enum { variant, .. } => *variant as i32,
+                                Value::Bool(true) => 1,
+                                Value::Bool(false) => 0,
+                                Value::None => -2,
+                                Value::Some(_) => -3,
+                                Value::Ok(_) => -4,
+                                Value::Err(_) => -5,
+                                _ => -1,
+                            };
+                            if let Some(arm) = arms.iter().find(|a| a.tag == tag || a.tag == -1) {
+                                let payload = match v.clone() {
+                                    Value::Enum { payload, .. } => *payload,
+                                    Value::Some(inner) | Value::Ok(inner) | Value::Err(inner) => {
+                                        *inner
+                                    }
+                                    other => other,
+                                };
+                                forks.push((arm.target as usize, Abs::Known(payload)));
+                            }
+                        }
+                        // Strings and records only match the wildcard arm.
+                        Abs::Tag | Abs::Record(..) => {
+                            if let Some(arm) = arms.iter().find(|a| a.tag == -1) {
+                                forks.push((arm.target as usize, val.clone()));
+                            }
+                        }
+                        Abs::Unknown => {
+                            forks.extend(arms.iter().map(|a| (a.target as usize, Abs::Unknown)));
+                        }
+                    }
+                    for (target, payload) in forks {
+                        let mut arm_fr = fr.clone();
+                        arm_fr.stack.push(payload);
+                        self.flow(&mut frames, &mut worklist, target, arm_fr);
+                    }
+                }
+                Op::MakeRecord(ty, n) => {
+                    let fields = fr.pop_n(*n as usize);
+                    if Some(*ty) == self.effect_type {
+                        match fields.first().and_then(Abs::known_str) {
+                            Some(kind) => {
+                                if let Some(kind) = EffectKind::parse_str(kind) {
+                                    self.site(func, ip, kind.capability_name(), SiteKind::Effect);
+                                }
+                            }
+                            None => {
+                                self.exact = false;
+                                for cap in self.possible_effect_caps() {
+                                    self.site(func, ip, cap, SiteKind::Effect);
+                                }
+                            }
+                        }
+                    }
+                    let record = Abs::Record(*ty, fields);
+                    fr.stack.push(if record.depth() > MAX_RECORD_DEPTH {
+                        Abs::Unknown
+                    } else {
+                        record
+                    });
+                }
+                Op::MakeEnum(ty, variant) => {
+                    let v = match fr.pop() {
+                        Abs::Known(payload) => Abs::Known(Value::Enum {
+                            type_id: *ty,
+                            variant: *variant,
+                            payload: Box::new(payload),
+                        }),
+                        _ => Abs::Unknown,
+                    };
+                    fr.stack.push(v);
+                }
+                Op::GetField(idx) => {
+                    let v = match fr.pop() {
+                        Abs::Record(_, fields) => fields.get(*idx as usize).cloned(),
+                        Abs::Known(Value::Record { fields, .. }) => {
+                            fields.get(*idx as usize).cloned().map(Abs::Known)
+                        }
+                        _ => None,
+                    };
+                    fr.stack.push(v.unwrap_or(Abs::Unknown));
+                }
+                Op::SpawnActor(callee) => {
+                    self.conservative(*callee);
+                    fr.stack.push(Abs::Unknown);
+                }
+                Op::SendMsg => {
+                    fr.pop_n(2);
+                }
+                Op::Assert { .. } => {
+                    if fr.pop().truth() == Some(false) {
+                        next.clear();
+                    }
+                }
+                Op::GuardSeal => {
+                    fr.pop();
+                    if fr.pop().truth() == Some(false) {
+                        next.clear();
+                    }
+                }
+                Op::AssertMsg => {
+                    fr.pop();
+                    if fr.pop().truth() == Some(false) {
+                        next.clear();
+                    } else {
+                        fr.stack.push(Abs::Known(Value::Unit));
+                    }
+                }
+                Op::Fail => {
+                    fr.pop();
+                    next.clear();
+                }
+                Op::CapCall(cap_id, n) | Op::CapSpawn(cap_id, n) => {
+                    fr.pop_n(*n as usize);
+                    if let Some(cap) = Capability::from_id(*cap_id) {
+                        self.site(func, ip, cap.name(), SiteKind::CapCall);
+                    }
+                    fr.stack.push(Abs::Unknown);
+                }
+                Op::CapBatch(cap_id) => {
+                    fr.pop();
+                    if let Some(cap) = Capability::from_id(*cap_id) {
+                        self.site(func, ip, cap.name(), SiteKind::CapCall);
+                    }
+                    fr.stack.push(Abs::Unknown);
+                }
+                Op::CapAwait => {
+                    fr.pop();
+                    fr.stack.push(Abs::Unknown);
+                }
+                Op::Eq | Op::Neq => {
+                    let b = fr.pop();
+                    let a = fr.pop();
+                    let mut v = self.compare(&a, &b);
+                    if matches!(f.code[ip], Op::Neq) {
+                        if let Some(t) = v.truth() {
+                            v = Abs::Known(Value::Bool(!t));
+                        }
+                    }
+                    fr.stack.push(v);
+                }
+                Op::And | Op::Or => {
+                    let b = fr.pop().truth();
+                    let a = fr.pop().truth();
+                    let v = if matches!(f.code[ip], Op::And) {
+                        match (a, b) {
+                            (Some(false), _) | (_, Some(false)) => Some(false),
+                            (Some(true), Some(true)) => Some(true),
+                            _ => None,
+                        }
+                    } else {
+                        match (a, b) {
+                            (Some(true), _) | (_, Some(true)) => Some(true),
+                            (Some(false), Some(false)) => Some(false),
+                            _ => None,
+                        }
+                    };
+                    fr.stack
+                        .push(v.map_or(Abs::Unknown, |t| Abs::Known(Value::Bool(t))));
+                }
+                Op::Not => {
+                    let v = fr.pop().truth();
+                    fr.stack
+                        .push(v.map_or(Abs::Unknown, |t| Abs::Known(Value::Bool(!t))));
+                }
+                Op::Dup => {
+                    let v = fr.stack.last().cloned().unwrap_or(Abs::Unknown);
+                    fr.stack.push(v);
+                }
+                Op::Debug | Op::Nop => {}
+                Op::DebugMsg => {
+                    let v = fr.pop();
+                    fr.pop();
+                    fr.stack.push(v);
+                }
+                Op::MakeList(n) => {
+                    fr.pop_n(*n as usize);
+                    fr.stack.push(Abs::Unknown);
+                }
+                Op::Neg
+                | Op::ListLen
+                | Op::ParseInt
+                | Op::TryParseInt
+                | Op::IntToString
+                | Op::FloatToString
+                | Op::StringLen
+                | Op::StringChars
+                | Op::StringToUpper
+                | Op::StringToLower
+                | Op::StringTrim
+                | Op::ListLenBuiltin
+                | Op::ListIsEmpty
+                | Op::ListHead
+                | Op::ListTail
+                | Op::ListReverse
+                | Op::IntParse
+                | Op::FloatParse
+                | Op::BoolToString
+                | Op::MapKeys
+                | Op::MapValues
+                | Op::MapLen
+                | Op::DecimalParse
+                | Op::DecimalToString
+                | Op::IntToDecimal
+                | Op::BigIntParse
+                | Op::BigIntToString
+                | Op::IntToBigInt
+                | Op::BigIntToInt
+                | Op::JsonParse
+                | Op::JsonStringify
+                | Op::TableColumnNames
+                | Op::TableLen
+                | Op::TableHash => {
+                    fr.pop();
+                    fr.stack.push(Abs::Unknown);
+                }
+                Op::Add
+                | Op::Sub
+                | Op::Mul
+                | Op::Div
+                | Op::Mod
+                | Op::Lt
+                | Op::Lte
+                | Op::Gt
+                | Op::Gte
+                | Op::Concat
+                | Op::ListGet
+                | Op::ListPush
+                | Op::StrContains
+                | Op::StrStartsWith
+                | Op::StringContains
+                | Op::StringStartsWith
+                | Op::StringEndsWith
+                | Op::StringJoin
+                | Op::ListAppend
+                | Op::ListConcat
+                | Op::StringSplit
+                | Op::MapGet
+                | Op::MapRemove
+                | Op::MapContainsKey
+                | Op::StrMatch
+                | Op::StrFind
+                | Op::TableSelect
+                | Op::TableFromColumns
+                | Op::TableColumn
+                | Op::TableRow => {
+                    fr.pop_n(2);
+                    fr.stack.push(Abs::Unknown);
+                }
+                Op::StringReplace
+                | Op::StringSlice
+                | Op::MapSet
+                | Op::BigIntPowMod
+                | Op::TableSort => {
+                    fr.pop_n(3);
+                    fr.stack.push(Abs::Unknown);
+                }
+                Op::TableFilter | Op::TableAggregate => {
+                    fr.pop_n(4);
+                    fr.stack.push(Abs::Unknown);
+                }
+            }
+            for target in next {
+                self.flow(&mut frames, &mut worklist, target, fr.clone());
+            }
+        }
+
+        self.call_chain.pop();
+        result.unwrap_or(Abs::Unknown)
+    }
+
+    /// Analyse a call in context, or scan it conservatively when it is
+    /// recursive or nested too deep.
+    fn call(&mut self, callee: u32, args: Vec<Abs>) -> Abs {
+        if self.call_chain.contains(&callee) || self.call_chain.len() >= MAX_CALL_DEPTH {
+            self.conservative(callee);
+            return Abs::Unknown;
+        }
+        self.exec(callee, args)
+    }
+
+    /// Propagate `fr` to `target`, queueing it if its state changed.
+    fn flow(
+        &mut self,
+        frames: &mut [Option<Frame>],
+        worklist: &mut BTreeSet<usize>,
+        target: usize,
+        fr: Frame,
+    ) {
+        let Some(slot) = frames.get_mut(target) else {
+            // Falling off the end of the code traps at run time.
+            return;
+        };
+        match slot {
+            None => {
+                *slot = Some(fr);
+                worklist.insert(target);
+            }
+            Some(existing) => {
+                if existing.stack.len() != fr.stack.len() {
+                    self.exact = false;
+                    return;
+                }
+                if existing.join(&fr) {
+                    worklist.insert(target);
+                }
+            }
+        }
+    }
+}
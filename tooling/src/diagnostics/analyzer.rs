@@ -2,6 +2,7 @@ use std::collections::{HashMap, HashSet};
 
 use boruna_compiler::ast::*;
 
+use super::project::{walk_block, walk_block_with, walk_program, ENTRY_POINTS};
 use super::suggest;
 use super::*;
 
@@ -49,6 +50,11 @@ impl<'a> Analyzer<'a> {
         self.check_record_fields(&mut diags);
         self.check_capability_purity(&mut diags);
         self.check_type_consistency(&mut diags);
+        self.check_unused_locals(&mut diags);
+        self.check_unreachable_arms(&mut diags);
+        self.check_dead_functions(&mut diags);
+        self.check_unhandled_callbacks(&mut diags);
+        self.check_unused_capabilities(&mut diags);
         diags
     }
 
@@ -298,8 +304,10 @@ impl<'a> Analyzer<'a> {
                     let known_fields: HashSet<&str> =
                         type_fields.iter().map(|(n, _)| n.as_str()).collect();
 
-                    for (field_name, _) in fields {
-                        if !known_fields.contains(field_name.as_str()) {
+                    // A deep update's path (`user.name`) is checked by its
+                    // first field; the compiler checks the rest.
+                    for field_name in fields.iter().filter_map(|(n, _)| n.split('.').next()) {
+                        if !known_fields.contains(field_name) {
                             let line = find_field_line(self.source, field_name);
                             let mut diag = Diagnostic::error(
                                 E006_UNKNOWN_FIELD,
@@ -453,6 +461,390 @@ impl<'a> Analyzer<'a> {
         }
     }
 
+    /// W001: `let` bindings never read or reassigned in their function.
+    /// Usage is name-based per function, so shadowed bindings count as used.
+    fn check_unused_locals(&self, diags: &mut Vec<Diagnostic>) {
+        for item in &self.program.items {
+            let Item::Function(f) = item else { continue };
+            let mut bound: Vec<String> = Vec::new();
+            let mut assigned: HashSet<String> = HashSet::new();
+            let mut used: HashSet<String> = HashSet::new();
+            walk_block_with(
+                &f.body,
+                &mut |stmt| match stmt {
+                    Stmt::Let { name, .. } if !bound.contains(name) => bound.push(name.clone()),
+                    Stmt::Assign { target, .. } => {
+                        assigned.insert(target.clone());
+                    }
+                    _ => {}
+                },
+                &mut |expr| {
+                    if let Expr::Ident(name) = expr {
+                        used.insert(name.clone());
+                    }
+                },
+            );
+            used.extend(assigned);
+
+            let fn_line = find_fn_def_line(self.source, &f.name);
+            for name in &bound {
+                if name.starts_with('_') || used.contains(name) {
+                    continue;
+                }
+                let mut diag = Diagnostic::warning(
+                    W001_UNUSED_LOCAL,
+                    format!(
+                        "unused variable: '{name}' is bound in '{}' but never used — remove it \
+                         or prefix it with '_'",
+                        f.name
+                    ),
+                );
+                let line = fn_line.and_then(|l| find_let_line_from(self.source, name, l));
+                if let Some(l) = line {
+                    diag = diag.at(self.file, l, None);
+                    if let Some(p) = suggest::suggest_prefix_unused(self.file, self.source, l, name)
+                    {
+                        diag = diag.with_suggestion(p);
+                    }
+                }
+                diags.push(diag);
+            }
+        }
+    }
+
+    /// W002: match arms that can never run because an earlier arm's pattern
+    /// already covers theirs (a catch-all, or the same/a broader pattern).
+    fn check_unreachable_arms(&self, diags: &mut Vec<Diagnostic>) {
+        for item in &self.program.items {
+            let Item::Function(f) = item else { continue };
+            let Some(fn_line) = find_fn_def_line(self.source, &f.name) else {
+                continue;
+            };
+            let mut match_cursor = fn_line;
+            walk_block(&f.body, &mut |expr| {
+                let Expr::Match { arms, .. } = expr else {
+                    return;
+                };
+                // Matches are visited in source order, so each search resumes
+                // after the previous `match` line.
+                let match_line = find_match_keyword_line(self.source, match_cursor);
+                if let Some(l) = match_line {
+                    match_cursor = l + 1;
+                }
+                let arm_lines = match_line
+                    .map(|l| locate_arm_lines(self.source, l, arms))
+                    .unwrap_or_else(|| vec![None; arms.len()]);
+
+                for (i, arm) in arms.iter().enumerate() {
+                    let earlier = &arms[..i];
+                    let covered_by = if let Some(e) = earlier
+                        .iter()
+                        .find(|e| pattern_subsumes(&e.pattern, &arm.pattern))
+                    {
+                        format!("the earlier arm '{}'", render_pattern(&e.pattern))
+                    } else if self.arms_are_exhaustive(earlier) {
+                        "the earlier arms, which are already exhaustive".to_string()
+                    } else {
+                        continue;
+                    };
+                    let pattern = render_pattern(&arm.pattern);
+                    let mut diag = Diagnostic::warning(
+                        W002_UNREACHABLE_ARM,
+                        format!(
+                            "unreachable match arm: '{pattern}' is already covered by {covered_by}"
+                        ),
+                    );
+                    if let Some(l) = arm_lines[i] {
+                        diag = diag.at(self.file, l, None);
+                        if is_single_line_arm(self.source, l) {
+                            let patch = suggest::suggest_delete_lines(
+                                format!("{W002_UNREACHABLE_ARM}-remove-arm-{l}"),
+                                format!("remove unreachable arm '{pattern}'"),
+                                "the arm can never be selected, so removing it does not change behavior",
+                                self.file,
+                                self.source,
+                                l,
+                                l,
+                            );
+                            if let Some(p) = patch {
+                                diag = diag.with_suggestion(p);
+                            }
+                        }
+                    }
+                    diags.push(diag);
+                }
+            });
+        }
+    }
+
+    /// Whether `arms` together match every value: a catch-all, both `Option`
+    /// or `Result` shapes, both booleans, or every variant of a declared enum.
+    fn arms_are_exhaustive(&self, arms: &[MatchArm]) -> bool {
+        let catch_all = |p: &Pattern| matches!(p, Pattern::Wildcard | Pattern::Ident(_));
+        let mut some = false;
+        let mut none = false;
+        let mut ok = false;
+        let mut err = false;
+        let mut bools = [false; 2];
+        let mut variants: HashSet<&str> = HashSet::new();
+        for arm in arms {
+            match &arm.pattern {
+                p if catch_all(p) => return true,
+                Pattern::SomePat(p) if catch_all(p) => some = true,
+                Pattern::NonePat => none = true,
+                Pattern::OkPat(p) if catch_all(p) => ok = true,
+                Pattern::ErrPat(p) if catch_all(p) => err = true,
+                Pattern::BoolLit(b) => bools[usize::from(*b)] = true,
+                Pattern::EnumVariant(name, payload) if payload.as_deref().is_none_or(catch_all) => {
+                    variants.insert(name);
+                }
+                _ => {}
+            }
+        }
+        if (some && none) || (ok && err) || bools == [true, true] {
+            return true;
+        }
+        !variants.is_empty()
+            && self.types.values().any(|kind| match kind {
+                TypeDefKind::Enum(all) => {
+                    all.iter().all(|(v, _)| variants.contains(v.as_str()))
+                        && variants.iter().all(|v| all.iter().any(|(n, _)| n == v))
+                }
+                TypeDefKind::Record(_) => false,
+            })
+    }
+
+    /// W003: private functions that no other function calls or references.
+    /// Only checked for modules with a public surface (an `export` or the
+    /// framework `update`), since a script's helpers may be driven externally.
+    fn check_dead_functions(&self, diags: &mut Vec<Diagnostic>) {
+        let fns: Vec<&FnDef> = self
+            .program
+            .items
+            .iter()
+            .filter_map(|i| match i {
+                Item::Function(f) => Some(f),
+                _ => None,
+            })
+            .collect();
+        if !fns.iter().any(|f| f.exported) && !self.functions.contains_key("update") {
+            return;
+        }
+
+        // Names referenced from a function other than their own definition,
+        // so plain self-recursion does not keep a function alive.
+        let mut referenced: HashSet<String> = HashSet::new();
+        for f in &fns {
+            let mut visit = |expr: &Expr| {
+                if let Expr::Ident(name) = expr {
+                    if name != &f.name {
+                        referenced.insert(name.clone());
+                    }
+                }
+            };
+            for e in f.requires.iter().chain(&f.ensures) {
+                super::project::walk_expr(e, &mut visit);
+            }
+            walk_block(&f.body, &mut visit);
+        }
+
+        for f in fns {
+            if f.exported || ENTRY_POINTS.contains(&f.name.as_str()) || referenced.contains(&f.name)
+            {
+                continue;
+            }
+            let mut diag = Diagnostic::warning(
+                W003_DEAD_FUNCTION,
+                format!(
+                    "dead function: '{}' is private and never called — remove it or export it",
+                    f.name
+                ),
+            );
+            if let Some(l) = find_fn_def_line(self.source, &f.name) {
+                diag = diag.at(self.file, l, None);
+                if let Some((start, end)) = fn_item_span(self.source, l) {
+                    let patch = suggest::suggest_delete_lines(
+                        format!("{W003_DEAD_FUNCTION}-remove-{}", f.name),
+                        format!("remove unused function '{}'", f.name),
+                        "nothing in this module calls or references the function",
+                        self.file,
+                        self.source,
+                        start,
+                        end,
+                    );
+                    if let Some(p) = patch {
+                        diag = diag.with_suggestion(p);
+                    }
+                }
+            }
+            diags.push(diag);
+        }
+    }
+
+    /// W005: declared capabilities that are never used. A function-level
+    /// declaration is unused when the capability has a built-in call form
+    /// and the function never calls it; capabilities without one (reached
+    /// through effects or the host) cannot be checked and are skipped. A
+    /// module-level `uses` entry is unused when no function declares it.
+    fn check_unused_capabilities(&self, diags: &mut Vec<Diagnostic>) {
+        use boruna_bytecode::Capability;
+        use boruna_compiler::codegen::{builtin_capability, CAPABILITY_BUILTINS};
+
+        let callable = |cap: Capability| {
+            CAPABILITY_BUILTINS
+                .iter()
+                .any(|b| builtin_capability(b).is_some_and(|(c, _)| c == cap))
+        };
+        let mut declared_anywhere: HashSet<Capability> = HashSet::new();
+        for item in &self.program.items {
+            let Item::Function(f) = item else { continue };
+            let called: Vec<Capability> = boruna_compiler::typeck::capability_calls(f)
+                .into_iter()
+                .map(|(_, cap)| cap)
+                .collect();
+            for name in &f.capabilities {
+                let Some(cap) = Capability::from_name(name) else {
+                    continue;
+                };
+                declared_anywhere.insert(cap);
+                if !callable(cap) || called.contains(&cap) {
+                    continue;
+                }
+                let mut diag = Diagnostic::warning(
+                    W005_UNUSED_CAPABILITY,
+                    format!(
+                        "unused capability: function '{}' declares {name} but never calls it — remove it from the declaration",
+                        f.name
+                    ),
+                );
+                if let Some(l) = find_fn_def_line(self.source, &f.name) {
+                    diag = diag.at(self.file, l, None);
+                }
+                diags.push(diag);
+            }
+        }
+        for name in &self.program.uses {
+            let Some(cap) = Capability::from_name(name) else {
+                continue;
+            };
+            if declared_anywhere.contains(&cap) {
+                continue;
+            }
+            let mut diag = Diagnostic::warning(
+                W005_UNUSED_CAPABILITY,
+                format!(
+                    "unused capability: the module uses {name} but no function declares it — remove it from `uses`"
+                ),
+            );
+            let line = self
+                .source
+                .lines()
+                .position(|l| l.trim_start().starts_with("uses "));
+            if let Some(l) = line {
+                diag = diag.at(self.file, l + 1, None);
+            }
+            diags.push(diag);
+        }
+    }
+
+    /// W004: effects whose `callback_tag` never appears as a message tag that
+    /// the app handles. Handled tags are string literals compared against a
+    /// `.tag` field or matched on in `match <expr>.tag`.
+    fn check_unhandled_callbacks(&self, diags: &mut Vec<Diagnostic>) {
+        let Some(update) = self.functions.get("update") else {
+            return;
+        };
+
+        let mut emitted: Vec<String> = Vec::new();
+        let mut handled: HashSet<String> = HashSet::new();
+        walk_program(self.program, &mut |expr| match expr {
+            Expr::Record { fields, .. } => {
+                for (field, value) in fields {
+                    if let (true, Expr::StringLit(tag)) = (field == "callback_tag", value) {
+                        if !tag.is_empty() && !emitted.contains(tag) {
+                            emitted.push(tag.clone());
+                        }
+                    }
+                }
+            }
+            Expr::Binary {
+                op: BinOp::Eq,
+                left,
+                right,
+            } => {
+                for (a, b) in [(left, right), (right, left)] {
+                    if let (true, Expr::StringLit(tag)) = (is_tag_access(a), b.as_ref()) {
+                        handled.insert(tag.clone());
+                    }
+                }
+            }
+            Expr::Match { value, arms } if is_tag_access(value) => {
+                for arm in arms {
+                    if let Pattern::StringLit(tag) = &arm.pattern {
+                        handled.insert(tag.clone());
+                    }
+                }
+            }
+            _ => {}
+        });
+
+        let update_line = find_fn_def_line(self.source, "update");
+        let stub = self.update_stub_result(update);
+        for tag in &emitted {
+            if handled.contains(tag) {
+                continue;
+            }
+            let mut diag = Diagnostic::warning(
+                W004_UNHANDLED_CALLBACK,
+                format!(
+                    "unhandled callback: an effect is emitted with callback_tag \"{tag}\" but \
+                     update() never handles a message with that tag"
+                ),
+            );
+            if let Some(l) = find_line_containing(self.source, &format!("\"{tag}\"")) {
+                diag = diag.at(self.file, l, None);
+            }
+            if let (Some(l), Some(msg), Some(body)) =
+                (update_line, update.params.get(1), stub.as_deref())
+            {
+                let patch = suggest::suggest_callback_handler(
+                    self.file,
+                    self.source,
+                    l,
+                    &msg.name,
+                    tag,
+                    body,
+                );
+                if let Some(p) = patch {
+                    diag = diag.with_suggestion(p);
+                }
+            }
+            diags.push(diag);
+        }
+    }
+
+    /// The "no-op" value `update()` can return: its state parameter, wrapped
+    /// in the return record when that record is exactly `{ state, effects }`.
+    fn update_stub_result(&self, update: &FnDef) -> Option<String> {
+        let first = update.params.first()?;
+        let state = &first.name;
+        let TypeExpr::Named(ret) = update.return_type.as_ref()? else {
+            return None;
+        };
+        if matches!(&first.ty, TypeExpr::Named(n) if n == ret) {
+            return Some(state.clone());
+        }
+        match self.types.get(ret) {
+            Some(TypeDefKind::Record(fields)) => {
+                let mut names: Vec<&str> = fields.iter().map(|(n, _)| n.as_str()).collect();
+                names.sort_unstable();
+                (names == ["effects", "state"])
+                    .then(|| format!("{ret} {{ state: {state}, effects: [] }}"))
+            }
+            _ => None,
+        }
+    }
+
     /// Warn-only type-consistency pass (first step toward strict static typing).
     /// Uses a conservative, inference-free local type environment: it only
     /// reasons about types it can name with confidence (literals, annotated
@@ -667,6 +1059,8 @@ impl<'a> Analyzer<'a> {
         match expr {
             Expr::IntLit(_) => Some("Int".to_string()),
             Expr::FloatLit(_) => Some("Float".to_string()),
+            Expr::DecimalLit(..) => Some("Decimal".to_string()),
+            Expr::BigIntLit(_) => Some("BigInt".to_string()),
             Expr::StringLit(_) => Some("String".to_string()),
             Expr::BoolLit(_) => Some("Bool".to_string()),
             Expr::Ident(name) => env.get(name).cloned(),
@@ -720,7 +1114,7 @@ fn find_line_containing(source: &str, needle: &str) -> Option<usize> {
 }
 
 /// Find the line number where `match <name>` occurs.
-fn find_match_line(source: &str, var_name: &str) -> Option<usize> {
+pub(super) fn find_match_line(source: &str, var_name: &str) -> Option<usize> {
     for (i, line) in source.lines().enumerate() {
         let trimmed = line.trim();
         if trimmed.starts_with("match ") && trimmed.contains(var_name) {
@@ -731,7 +1125,7 @@ fn find_match_line(source: &str, var_name: &str) -> Option<usize> {
 }
 
 /// Find the closing brace of a match expression starting at `start_line` (1-indexed).
-fn find_match_end_line(source: &str, start_line: usize) -> Option<usize> {
+pub(super) fn find_match_end_line(source: &str, start_line: usize) -> Option<usize> {
     let lines: Vec<&str> = source.lines().collect();
     if start_line == 0 || start_line > lines.len() {
         return None;
@@ -755,10 +1149,14 @@ fn find_match_end_line(source: &str, start_line: usize) -> Option<usize> {
 }
 
 /// Find the line number where a function is defined.
-fn find_fn_def_line(source: &str, fn_name: &str) -> Option<usize> {
+pub(super) fn find_fn_def_line(source: &str, fn_name: &str) -> Option<usize> {
     let pattern = format!("fn {fn_name}");
     for (i, line) in source.lines().enumerate() {
-        if line.contains(&pattern) {
+        // Word-boundary aware, so `fn add` does not match `fn add_one`.
+        let found = line.match_indices(&pattern).any(|(pos, _)| {
+            !line[pos + pattern.len()..].starts_with(|c: char| c.is_alphanumeric() || c == '_')
+        });
+        if found {
             return Some(i + 1);
         }
     }
@@ -766,13 +1164,14 @@ fn find_fn_def_line(source: &str, fn_name: &str) -> Option<usize> {
 }
 
 /// Find the line number where a field name appears in a record literal.
-fn find_field_line(source: &str, field_name: &str) -> Option<usize> {
+pub(super) fn find_field_line(source: &str, field_name: &str) -> Option<usize> {
     let pattern = format!("{field_name}:");
     for (i, line) in source.lines().enumerate() {
         let trimmed = line.trim();
         if trimmed.starts_with(&pattern)
             || trimmed.contains(&format!(" {pattern}"))
             || trimmed.contains(&format!(",{pattern}"))
+            || trimmed.contains(&format!(".{pattern}"))
         {
             return Some(i + 1);
         }
@@ -780,6 +1179,142 @@ fn find_field_line(source: &str, field_name: &str) -> Option<usize> {
     None
 }
 
+/// Line (1-indexed) of the first `let <name>` binding at or after `from_line`.
+fn find_let_line_from(source: &str, name: &str, from_line: usize) -> Option<usize> {
+    source
+        .lines()
+        .enumerate()
+        .skip(from_line.saturating_sub(1))
+        .find(|(_, line)| {
+            let trimmed = line.trim_start();
+            let rest = trimmed
+                .strip_prefix("let mut ")
+                .or_else(|| trimmed.strip_prefix("let "));
+            rest.and_then(|r| r.strip_prefix(name))
+                .is_some_and(|r| !r.starts_with(|c: char| c.is_alphanumeric() || c == '_'))
+        })
+        .map(|(i, _)| i + 1)
+}
+
+/// Line (1-indexed) of the next `match` keyword at or after `from_line`.
+fn find_match_keyword_line(source: &str, from_line: usize) -> Option<usize> {
+    source
+        .lines()
+        .enumerate()
+        .skip(from_line.saturating_sub(1))
+        .find(|(_, line)| {
+            let trimmed = line.trim_start();
+            !trimmed.starts_with("//")
+                && (trimmed.starts_with("match ") || trimmed.contains(" match "))
+        })
+        .map(|(i, _)| i + 1)
+}
+
+/// Best-effort line of each arm of the match starting at `match_line`. Arms
+/// are located in order; a multi-line arm body is skipped so nested matches
+/// don't shadow later arms of the outer one.
+fn locate_arm_lines(source: &str, match_line: usize, arms: &[MatchArm]) -> Vec<Option<usize>> {
+    let lines: Vec<&str> = source.lines().collect();
+    let mut cursor = match_line;
+    let mut located = Vec::with_capacity(arms.len());
+    for arm in arms {
+        let prefix = render_pattern(&arm.pattern);
+        let found = (cursor..=lines.len()).find(|&l| {
+            lines[l - 1]
+                .trim_start()
+                .strip_prefix(prefix.as_str())
+                .is_some_and(|rest| rest.trim_start().starts_with("=>"))
+        });
+        if let Some(l) = found {
+            cursor = find_match_end_line(source, l).unwrap_or(l).max(l) + 1;
+        }
+        located.push(found);
+    }
+    located
+}
+
+/// Whether an arm on `line` is self-contained: braces balanced, so deleting
+/// the line cannot unbalance the surrounding match.
+fn is_single_line_arm(source: &str, line: usize) -> bool {
+    let Some(text) = source.lines().nth(line.saturating_sub(1)) else {
+        return false;
+    };
+    let mut depth = 0i32;
+    for ch in text.chars() {
+        match ch {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            _ => {}
+        }
+        if depth < 0 {
+            return false;
+        }
+    }
+    depth == 0
+}
+
+/// Source text of a pattern, as the parser accepts it.
+fn render_pattern(pattern: &Pattern) -> String {
+    match pattern {
+        Pattern::Wildcard => "_".to_string(),
+        Pattern::Ident(name) => name.clone(),
+        Pattern::IntLit(n) => n.to_string(),
+        Pattern::StringLit(s) => format!("\"{s}\""),
+        Pattern::BoolLit(b) => b.to_string(),
+        Pattern::NonePat => "None".to_string(),
+        Pattern::SomePat(p) => format!("Some({})", render_pattern(p)),
+        Pattern::OkPat(p) => format!("Ok({})", render_pattern(p)),
+        Pattern::ErrPat(p) => format!("Err({})", render_pattern(p)),
+        Pattern::EnumVariant(name, None) => name.clone(),
+        Pattern::EnumVariant(name, Some(p)) => format!("{name}({})", render_pattern(p)),
+    }
+}
+
+/// Whether every value matched by `later` is already matched by `earlier`.
+fn pattern_subsumes(earlier: &Pattern, later: &Pattern) -> bool {
+    match (earlier, later) {
+        (Pattern::Wildcard | Pattern::Ident(_), _) => true,
+        (Pattern::IntLit(a), Pattern::IntLit(b)) => a == b,
+        (Pattern::StringLit(a), Pattern::StringLit(b)) => a == b,
+        (Pattern::BoolLit(a), Pattern::BoolLit(b)) => a == b,
+        (Pattern::NonePat, Pattern::NonePat) => true,
+        (Pattern::SomePat(a), Pattern::SomePat(b))
+        | (Pattern::OkPat(a), Pattern::OkPat(b))
+        | (Pattern::ErrPat(a), Pattern::ErrPat(b)) => pattern_subsumes(a, b),
+        (Pattern::EnumVariant(a, pa), Pattern::EnumVariant(b, pb)) if a == b => match (pa, pb) {
+            (None, None) => true,
+            (Some(pa), Some(pb)) => pattern_subsumes(pa, pb),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Lines `(start, end)` (1-indexed) of a whole function item whose header is
+/// on `def_line`, including `//` comment lines directly above it. `None` when
+/// the item shares lines with other code.
+fn fn_item_span(source: &str, def_line: usize) -> Option<(usize, usize)> {
+    let lines: Vec<&str> = source.lines().collect();
+    let header = lines.get(def_line.checked_sub(1)?)?;
+    if !header.trim_start().starts_with("fn ") || !header.contains('{') {
+        return None;
+    }
+    let end = find_match_end_line(source, def_line)?;
+    if end > def_line && lines[end - 1].trim() != "}" {
+        return None;
+    }
+    let mut start = def_line;
+    while start > 1 && lines[start - 2].trim_start().starts_with("//") {
+        start -= 1;
+    }
+    Some((start, end))
+}
+
+/// Whether `expr` reads a `.tag` field (e.g. `msg.tag`).
+fn is_tag_access(expr: &Expr) -> bool {
+    matches!(expr, Expr::FieldAccess { field, .. } if field == "tag")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -935,4 +1470,176 @@ fn view(state: State) -> String { \"ok\" }
         );
         assert!(cap_diag.unwrap().message.contains("update"));
     }
+
+    fn lint(source: &str, code: &str) -> Vec<Diagnostic> {
+        let tokens = boruna_compiler::lexer::lex(source).unwrap();
+        let program = boruna_compiler::parser::parse(tokens).unwrap();
+        Analyzer::new("test.ax", source, &program)
+            .analyze()
+            .into_iter()
+            .filter(|d| d.id == code)
+            .collect()
+    }
+
+    /// Apply the first patch of every `code` diagnostic and return the result.
+    fn apply_fixes(source: &str, code: &str) -> String {
+        let mut ds = DiagnosticSet::new("test.ax");
+        for d in lint(source, code) {
+            ds.push(d);
+        }
+        let (repaired, result) = crate::repair::RepairTool::repair(
+            "test.ax",
+            source,
+            &ds,
+            crate::repair::RepairStrategy::Best,
+            None,
+        );
+        assert!(result.skipped.is_empty(), "{:?}", result.skipped);
+        repaired
+    }
+
+    #[test]
+    fn test_unused_local_warns_and_prefixes() {
+        let source = "fn main() -> Int {\n    let a = 1\n    let mut b = 2\n    b = 3\n    let c = 4\n    c\n}\n";
+        let diags = lint(source, W001_UNUSED_LOCAL);
+        assert_eq!(diags.len(), 1, "{diags:?}");
+        assert!(diags[0].message.contains("'a'"));
+        assert_eq!(diags[0].severity, Severity::Warning);
+
+        let repaired = apply_fixes(source, W001_UNUSED_LOCAL);
+        assert!(repaired.contains("    let _a = 1\n"));
+        assert!(lint(&repaired, W001_UNUSED_LOCAL).is_empty());
+        boruna_compiler::compile("test", &repaired).unwrap();
+    }
+
+    #[test]
+    fn test_unreachable_arms_after_catch_all_and_duplicates() {
+        let source = "\
+fn main() -> Int {
+    let x = Some(3)
+    match x {
+        Some(1) => 1
+        Some(n) => n
+        Some(2) => 2
+        None => 0
+        _ => 9
+    }
+}
+";
+        let diags = lint(source, W002_UNREACHABLE_ARM);
+        let lines: Vec<usize> = diags
+            .iter()
+            .map(|d| d.location.as_ref().unwrap().line)
+            .collect();
+        assert_eq!(lines, vec![6, 8], "{diags:?}");
+        assert!(diags[0].message.contains("'Some(n)'"));
+
+        let repaired = apply_fixes(source, W002_UNREACHABLE_ARM);
+        assert!(!repaired.contains("Some(2)") && !repaired.contains("_ => 9"));
+        assert!(lint(&repaired, W002_UNREACHABLE_ARM).is_empty());
+        boruna_compiler::compile("test", &repaired).unwrap();
+    }
+
+    #[test]
+    fn test_unreachable_arm_in_nested_match_is_located() {
+        let source = "\
+fn main() -> Int {
+    let x = 1
+    match x {
+        1 => match x {
+            _ => 1
+        }
+        2 => 2
+        _ => 0
+        _ => 3
+    }
+}
+";
+        let diags = lint(source, W002_UNREACHABLE_ARM);
+        assert_eq!(diags.len(), 1, "{diags:?}");
+        assert_eq!(diags[0].location.as_ref().unwrap().line, 9);
+    }
+
+    #[test]
+    fn test_dead_private_function_is_removed() {
+        let source = "\
+export fn api() -> Int {
+    used(1)
+}
+
+fn used(x: Int) -> Int {
+    x
+}
+
+// No longer called.
+fn stale(n: Int) -> Int {
+    if n == 0 { 0 } else { stale(n - 1) }
+}
+";
+        let diags = lint(source, W003_DEAD_FUNCTION);
+        assert_eq!(diags.len(), 1, "{diags:?}");
+        assert!(diags[0].message.contains("'stale'"));
+        assert_eq!(diags[0].location.as_ref().unwrap().line, 10);
+
+        let repaired = apply_fixes(source, W003_DEAD_FUNCTION);
+        assert!(!repaired.contains("stale") && !repaired.contains("No longer"));
+        boruna_compiler::compile("test", &repaired).unwrap();
+    }
+
+    #[test]
+    fn test_dead_function_skipped_for_scripts() {
+        let source = "fn helper() -> Int { 1 }\nfn main() -> Int { 0 }\n";
+        assert!(lint(source, W003_DEAD_FUNCTION).is_empty());
+    }
+
+    #[test]
+    fn test_find_fn_def_line_is_word_bounded() {
+        let source =
+            "fn add_one(x: Int) -> Int { x + 1 }\nfn add(a: Int, b: Int) -> Int { a + b }\n";
+        assert_eq!(find_fn_def_line(source, "add"), Some(2));
+    }
+
+    const CALLBACK_APP: &str = "\
+type State { count: Int }
+type Msg { tag: String, payload: String }
+type Effect { kind: String, payload: String, callback_tag: String }
+type UpdateResult { state: State, effects: List<Effect> }
+
+fn init() -> State { State { count: 0 } }
+
+fn update(state: State, msg: Msg) -> UpdateResult {
+    if msg.tag == \"load\" {
+        UpdateResult {
+            state: state,
+            effects: [
+                Effect { kind: \"http_request\", payload: \"a\", callback_tag: \"loaded\" },
+                Effect { kind: \"timer\", payload: \"b\", callback_tag: \"ticked\" },
+            ],
+        }
+    } else {
+        match msg.tag {
+            \"loaded\" => UpdateResult { state: State { count: 1 }, effects: [] },
+            _ => UpdateResult { state: state, effects: [] },
+        }
+    }
+}
+
+fn view(state: State) -> String { \"ok\" }
+";
+
+    #[test]
+    fn test_unhandled_callback_tag_warns_with_stub() {
+        let diags = lint(CALLBACK_APP, W004_UNHANDLED_CALLBACK);
+        assert_eq!(diags.len(), 1, "{diags:?}");
+        assert!(diags[0].message.contains("\"ticked\""));
+        assert_eq!(diags[0].location.as_ref().unwrap().line, 14);
+        assert_eq!(diags[0].suggested_patches[0].confidence, Confidence::Low);
+
+        let repaired = apply_fixes(CALLBACK_APP, W004_UNHANDLED_CALLBACK);
+        assert!(repaired.contains(
+            "    if msg.tag == \"ticked\" { return UpdateResult { state: state, effects: [] } }\n"
+        ));
+        assert!(lint(&repaired, W004_UNHANDLED_CALLBACK).is_empty());
+        boruna_compiler::compile("test", &repaired).unwrap();
+    }
 }
@@ -1,22 +1,68 @@
+use boruna_compiler::ast::{Item, Program};
 use boruna_compiler::CompileError;
 
-use super::analyzer::Analyzer;
+use super::analyzer::{find_field_line, find_fn_def_line, find_match_line, Analyzer};
+use super::config::DiagnosticsConfig;
 use super::suggest;
+use super::suppress::Suppressions;
 use super::*;
+use crate::repair::calibration::Calibration;
 
 /// Collects diagnostics by running the compiler and additional analysis passes.
 pub struct DiagnosticCollector<'a> {
     file: &'a str,
     source: &'a str,
+    config: Option<&'a DiagnosticsConfig>,
+    calibration: Option<&'a Calibration>,
 }
 
 impl<'a> DiagnosticCollector<'a> {
     pub fn new(file: &'a str, source: &'a str) -> Self {
-        DiagnosticCollector { file, source }
+        DiagnosticCollector {
+            file,
+            source,
+            config: None,
+            calibration: None,
+        }
+    }
+
+    /// Apply `config` (severity promotion, path ignores) to the result.
+    /// `file` is resolved against the working directory for path matching.
+    pub fn with_config(mut self, config: &'a DiagnosticsConfig) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Re-rank suggested patches with recorded repair outcomes; see
+    /// [`suggest::rank_patches`].
+    pub fn with_calibration(mut self, calibration: &'a Calibration) -> Self {
+        self.calibration = Some(calibration);
+        self
     }
 
     /// Run all diagnostic passes and return a complete DiagnosticSet.
+    /// `// boruna:allow(...)` pragmas in the source are honored.
     pub fn collect(&self) -> DiagnosticSet {
+        self.collect_with_externals(&[])
+    }
+
+    /// Like [`collect`](Self::collect), but `externals` (declarations from
+    /// other files of a project) are in scope for type checking and
+    /// analysis. Externals whose name the file itself defines are ignored.
+    pub fn collect_with_externals(&self, externals: &[Item]) -> DiagnosticSet {
+        let mut ds = self.collect_unfiltered(externals);
+        if let Some(config) = self.config {
+            config.apply(&mut ds, std::path::Path::new(""));
+        }
+        if let Some(calibration) = self.calibration {
+            for diag in &mut ds.diagnostics {
+                suggest::rank_patches(diag, calibration);
+            }
+        }
+        ds
+    }
+
+    fn collect_unfiltered(&self, externals: &[Item]) -> DiagnosticSet {
         let mut ds = DiagnosticSet::new(self.file);
 
         // Phase 1: Try lex
@@ -29,76 +75,138 @@ impl<'a> DiagnosticCollector<'a> {
         };
 
         // Phase 2: Try parse
-        let program = match boruna_compiler::parser::parse(tokens) {
+        let suppressions = Suppressions::from_tokens(&tokens);
+        let own = match boruna_compiler::parser::parse(tokens) {
             Ok(program) => program,
             Err(e) => {
                 ds.push(self.compile_error_to_diagnostic(&e));
                 return ds;
             }
         };
+        let suppressions = suppressions.with_items(self.source, &own);
+        let program = with_externals(own, externals);
 
         // Phase 3: Try type check
-        if let Err(e) = boruna_compiler::typeck::check(&program) {
-            let mut diag = self.compile_error_to_diagnostic(&e);
+        let typed = boruna_compiler::typeck::check(&program);
+        let type_diag = typed.as_ref().err().map(|e| {
+            let mut diag = self.compile_error_to_diagnostic(e);
             // Try to enhance with suggestions
             suggest::enhance_compiler_diagnostic(&mut diag, self.file, self.source, &program);
-            ds.push(diag);
-            // Don't return — still run analyzers for additional findings
-        }
+            diag
+        });
+        // Don't return on a type error — still run analyzers for
+        // additional findings
 
         // Phase 4: Run additional analyzers on the AST
         let analyzer = Analyzer::new(self.file, self.source, &program);
         let findings = analyzer.analyze();
+        // The analyzer's E005 and E006 locate the match or field and
+        // suggest a fix, so they replace the compiler's report of the same
+        // problem.
+        if let Some(diag) = type_diag {
+            if ![E005_NON_EXHAUSTIVE_MATCH, E006_UNKNOWN_FIELD].contains(&diag.id.as_str())
+                || !findings.iter().any(|d| d.id == diag.id)
+            {
+                ds.push(diag);
+            }
+        }
         for diag in findings {
             ds.push(diag);
         }
 
+        // Phase 5: Size and complexity budget, when the config sets one
+        if let (Ok(()), Some(budget)) = (&typed, self.config.and_then(|c| c.budget.as_ref())) {
+            if let Ok(module) = boruna_compiler::codegen::emit(self.file, &program) {
+                for v in boruna_compiler::budget::check(&program, &module, budget) {
+                    let mut diag = Diagnostic::error(E015_BUDGET_EXCEEDED, v.to_string());
+                    let line = v
+                        .function
+                        .as_deref()
+                        .and_then(|name| find_fn_def_line(self.source, name));
+                    if let Some(l) = line {
+                        diag = diag.at(self.file, l, None);
+                    }
+                    ds.push(diag);
+                }
+            }
+        }
+
+        suppressions.apply(&mut ds, self.file);
         ds
     }
 
     /// Convert a CompileError into a Diagnostic.
     fn compile_error_to_diagnostic(&self, err: &CompileError) -> Diagnostic {
         match err {
-            CompileError::Lexer { line, col, msg } => {
+            CompileError::Lexer { span, msg } => {
                 // Include a pointer hint in the note
-                let enhanced_msg =
-                    if let Some(source_line) = self.source.lines().nth(line.saturating_sub(1)) {
-                        let pointer = " ".repeat(col.saturating_sub(1)) + "^";
-                        format!("{msg}\n  {source_line}\n  {pointer}")
+                let enhanced_msg = if let Some(source_line) =
+                    self.source.lines().nth(span.line.saturating_sub(1))
+                {
+                    let width = if span.end_line == span.line {
+                        span.end_col.saturating_sub(span.col).max(1)
                     } else {
-                        msg.clone()
+                        1
                     };
-                Diagnostic::error(E001_LEXER, enhanced_msg).at(self.file, *line, Some(*col))
+                    let pointer = " ".repeat(span.col.saturating_sub(1)) + &"^".repeat(width);
+                    format!("{msg}\n  {source_line}\n  {pointer}")
+                } else {
+                    msg.clone()
+                };
+                Diagnostic::error(E001_LEXER, enhanced_msg).at_span(self.file, *span)
             }
-            CompileError::Parse { line, msg } => {
+            CompileError::Parse { span, msg } => {
                 // Add a hint about common causes when message is terse
                 let enhanced_msg = if !msg.contains("check") && !msg.contains("hint") {
                     format!("{msg} — check for mismatched braces or a missing expression")
                 } else {
                     msg.clone()
                 };
-                Diagnostic::error(E002_PARSE, enhanced_msg).at(self.file, *line, None)
+                Diagnostic::error(E002_PARSE, enhanced_msg).at_span(self.file, *span)
             }
-            CompileError::Type(msg) => {
+            CompileError::Type { msg, span } => {
                 let (code, line) = classify_type_error(msg, self.source);
-                let mut diag = Diagnostic::error(code, msg.clone());
-                if let Some(l) = line {
-                    diag = diag.at(self.file, l, None);
+                let diag = Diagnostic::error(code, msg.clone());
+                match (span, line) {
+                    (Some(span), _) => diag.at_span(self.file, *span),
+                    (None, Some(l)) => diag.at(self.file, l, None),
+                    (None, None) => diag,
                 }
-                diag
             }
-            CompileError::Codegen(msg) => {
+            CompileError::Budget(msg) => Diagnostic::error(E015_BUDGET_EXCEEDED, msg.clone()),
+            CompileError::Codegen { msg, span } => {
                 let line = find_codegen_error_line(msg, self.source);
-                let mut diag = Diagnostic::error(E008_CODEGEN, msg.clone());
-                if let Some(l) = line {
-                    diag = diag.at(self.file, l, None);
+                let diag = Diagnostic::error(E008_CODEGEN, msg.clone());
+                match (span, line) {
+                    (Some(span), _) => diag.at_span(self.file, *span),
+                    (None, Some(l)) => diag.at(self.file, l, None),
+                    (None, None) => diag,
                 }
-                diag
             }
         }
     }
 }
 
+fn with_externals(mut program: Program, externals: &[Item]) -> Program {
+    let defined: std::collections::HashSet<String> =
+        program.items.iter().filter_map(item_name).collect();
+    program.items.extend(
+        externals
+            .iter()
+            .filter(|item| item_name(item).is_some_and(|n| !defined.contains(&n)))
+            .cloned(),
+    );
+    program
+}
+
+fn item_name(item: &Item) -> Option<String> {
+    match item {
+        Item::Function(f) => Some(f.name.clone()),
+        Item::TypeDef(t) => Some(t.name.clone()),
+        _ => None,
+    }
+}
+
 /// Classify a type error string into a specific error code and try to find the line.
 ///
 /// Strip any " did you mean: '...'?" suffix that the compiler may
@@ -119,6 +227,25 @@ fn classify_type_error(msg: &str, source: &str) -> (&'static str, Option<usize>)
             .unwrap_or("");
         let line = find_identifier_line(source, name);
         (E004_UNDEFINED_FN, line)
+    } else if let Some(rest) = first_line.strip_prefix("non-exhaustive match on ") {
+        // "on 'x' of type 'E'" names the scrutinee; "on enum 'E'" does not.
+        let line = rest
+            .strip_prefix('\'')
+            .and_then(|r| r.split('\'').next())
+            .and_then(|name| find_match_line(source, name));
+        (E005_NON_EXHAUSTIVE_MATCH, line)
+    } else if let Some(rest) = first_line.strip_prefix("unknown field '") {
+        let field = rest.split('\'').next().unwrap_or("");
+        (E006_UNKNOWN_FIELD, find_field_line(source, field))
+    } else if let Some(rest) = first_line.strip_prefix("capability violation: function '") {
+        // "... 'f' calls kv_get, which needs ..." points at the call;
+        // "... 'f' declares net.fetch, which the module's ..." at the fn.
+        let (func, rest) = rest.split_once('\'').unwrap_or((rest, ""));
+        let line = match rest.strip_prefix(" calls ") {
+            Some(call) => find_identifier_line(source, call.split(',').next().unwrap_or("")),
+            None => find_fn_def_line(source, func),
+        };
+        (E007_CAPABILITY_VIOLATION, line)
     } else {
         (E009_TYPE_ERROR, None)
     }
@@ -238,4 +365,49 @@ mod tests {
         let ds = DiagnosticCollector::new("test.ax", source).collect();
         assert!(!ds.has_errors());
     }
+
+    #[test]
+    fn test_collector_reports_budget_from_config() {
+        let source = "fn main() -> Int {\n    42\n}\n\nfn helper() -> Int {\n    if true { 1 } else { 2 }\n}\n";
+        let config: DiagnosticsConfig =
+            serde_json::from_str(r#"{"budget": {"max_nesting_depth": 0}}"#).unwrap();
+        let ds = DiagnosticCollector::new("test.ax", source)
+            .with_config(&config)
+            .collect();
+        let budget: Vec<_> = ds
+            .diagnostics
+            .iter()
+            .filter(|d| d.id == E015_BUDGET_EXCEEDED)
+            .collect();
+        assert_eq!(budget.len(), 1);
+        assert!(budget[0].message.contains("function 'helper'"));
+        assert_eq!(budget[0].location.as_ref().map(|l| l.line), Some(5));
+
+        let ds = DiagnosticCollector::new("test.ax", source).collect();
+        assert!(!ds.has_errors());
+    }
+
+    #[test]
+    fn test_collector_capability_declarations() {
+        let source = "fn main() -> Int {\n    kv_set(\"k\", 1)\n    0\n}\n";
+        let ds = DiagnosticCollector::new("test.ax", source).collect();
+        let err = ds
+            .diagnostics
+            .iter()
+            .find(|d| d.id == E007_CAPABILITY_VIOLATION)
+            .expect("E007");
+        assert_eq!(err.location.as_ref().map(|l| l.line), Some(2));
+
+        let source = "uses kv.get, kv.set\n\nfn main() -> Int uses kv.get, kv.set {\n    kv_set(\"k\", 1)\n    0\n}\n";
+        let ds = DiagnosticCollector::new("test.ax", source).collect();
+        assert!(!ds.has_errors());
+        let unused: Vec<&str> = ds
+            .diagnostics
+            .iter()
+            .filter(|d| d.id == W005_UNUSED_CAPABILITY)
+            .map(|d| d.message.as_str())
+            .collect();
+        assert_eq!(unused.len(), 1, "got: {unused:?}");
+        assert!(unused[0].contains("'main' declares kv.get"));
+    }
 }
@@ -0,0 +1,228 @@
+//! Per-project diagnostic policy, read from `boruna.diagnostics.json`.
+//!
+//! The config lets CI be stricter on hand-written code than on generated
+//! code:
+//!
+//! ```json
+//! {
+//!   "promote": ["W001", "W003"],
+//!   "ignore": [{ "paths": ["generated/**"], "codes": ["W001", "W003"] }]
+//! }
+//! ```
+//!
+//! `promote` reports the listed codes as errors wherever they appear.
+//! `ignore` drops the listed codes (`"*"` for all) in files matching any of
+//! the path globs. Globs are relative to the config file's directory: `*`
+//! and `?` match within one path segment, `**` matches any number of
+//! segments. An optional `budget` object (see [`boruna_compiler::Budget`])
+//! reports a module that outgrows it as `E015`.
+
+use std::path::{Path, PathBuf};
+
+use boruna_compiler::Budget;
+use serde::{Deserialize, Serialize};
+
+use super::*;
+
+/// File name searched for by [`DiagnosticsConfig::discover`].
+pub const CONFIG_FILE_NAME: &str = "boruna.diagnostics.json";
+
+/// Severity promotion and path-scoped ignores for diagnostics.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DiagnosticsConfig {
+    /// Codes reported as errors even when emitted as warnings.
+    #[serde(default)]
+    pub promote: Vec<String>,
+    /// Codes dropped for matching paths.
+    #[serde(default)]
+    pub ignore: Vec<IgnoreRule>,
+    /// Size and complexity limits reported as `E015` by single-file
+    /// checks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget: Option<Budget>,
+    /// Directory the `ignore` globs are relative to. Set by
+    /// [`load`](Self::load); `None` matches paths exactly as reported.
+    #[serde(skip)]
+    pub root: Option<PathBuf>,
+}
+
+/// Codes to drop in files matching any of `paths`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct IgnoreRule {
+    pub paths: Vec<String>,
+    pub codes: Vec<String>,
+}
+
+impl DiagnosticsConfig {
+    /// Read a config file. Globs resolve against its directory.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let json =
+            std::fs::read_to_string(path).map_err(|e| format!("read {}: {e}", path.display()))?;
+        let mut config: DiagnosticsConfig =
+            serde_json::from_str(&json).map_err(|e| format!("parse {}: {e}", path.display()))?;
+        let dir = path.parent().unwrap_or(Path::new("."));
+        let dir = if dir.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            dir
+        };
+        config.root = Some(dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf()));
+        Ok(config)
+    }
+
+    /// Load the nearest [`CONFIG_FILE_NAME`] at or above `start` (a file or
+    /// directory). `Ok(None)` when there is none.
+    pub fn discover(start: &Path) -> Result<Option<Self>, String> {
+        let start = start.canonicalize().unwrap_or_else(|_| start.to_path_buf());
+        let dir = if start.is_dir() {
+            start.as_path()
+        } else {
+            start.parent().unwrap_or(Path::new("."))
+        };
+        for ancestor in dir.ancestors() {
+            let candidate = ancestor.join(CONFIG_FILE_NAME);
+            if candidate.is_file() {
+                return Self::load(&candidate).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Whether `code` is ignored for `file` (a path relative to `root`).
+    pub fn is_ignored(&self, file: &str, code: &str) -> bool {
+        self.ignore.iter().any(|rule| {
+            rule.codes.iter().any(|c| c == "*" || c == code)
+                && rule.paths.iter().any(|p| glob_match(p, file))
+        })
+    }
+
+    /// Apply the policy to `ds`. Diagnostic files are resolved against
+    /// `base` (the project root, or empty for paths relative to the
+    /// working directory) before matching the `ignore` globs.
+    pub fn apply(&self, ds: &mut DiagnosticSet, base: &Path) {
+        let set_file = ds.file.clone();
+        ds.diagnostics.retain(|d| {
+            let file = d.location.as_ref().map_or(set_file.as_str(), |l| &l.file);
+            !self.is_ignored(&self.relative(&base.join(file)), &d.id)
+        });
+        for d in &mut ds.diagnostics {
+            if d.severity == Severity::Warning && self.promote.iter().any(|c| c == &d.id) {
+                d.severity = Severity::Error;
+            }
+        }
+    }
+
+    /// `path` relative to `root`, `/`-separated; unchanged when it lies
+    /// outside `root` or there is no root.
+    fn relative(&self, path: &Path) -> String {
+        let rel = self.root.as_ref().and_then(|root| {
+            let path = path.canonicalize().ok()?;
+            path.strip_prefix(root).ok().map(Path::to_path_buf)
+        });
+        rel.as_deref()
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/")
+            .trim_start_matches("./")
+            .to_string()
+    }
+}
+
+/// Match a `/`-separated path against a glob (`*`, `?`, `**`).
+fn glob_match(pattern: &str, path: &str) -> bool {
+    let pattern: Vec<&str> = pattern.split('/').filter(|s| !s.is_empty()).collect();
+    let path: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    match_segments(&pattern, &path)
+}
+
+fn match_segments(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|i| match_segments(rest, &path[i..])),
+        Some((seg, rest)) => {
+            !path.is_empty()
+                && match_segment(seg.as_bytes(), path[0].as_bytes())
+                && match_segments(rest, &path[1..])
+        }
+    }
+}
+
+fn match_segment(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => text.is_empty(),
+        Some((b'*', rest)) => (0..=text.len()).any(|i| match_segment(rest, &text[i..])),
+        Some((b'?', rest)) => !text.is_empty() && match_segment(rest, &text[1..]),
+        Some((c, rest)) => text.first() == Some(c) && match_segment(rest, &text[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostics::collector::DiagnosticCollector;
+
+    const UNUSED: &str = "fn main() -> Int {\n    let a = 1\n    0\n}\n";
+
+    #[test]
+    fn glob_matching() {
+        assert!(glob_match("generated/**", "generated/a/b.ax"));
+        assert!(glob_match("**/*.gen.ax", "src/x.gen.ax"));
+        assert!(glob_match("**/*.gen.ax", "x.gen.ax"));
+        assert!(glob_match("src/?.ax", "src/a.ax"));
+        assert!(!glob_match("src/*.ax", "src/sub/a.ax"));
+        assert!(!glob_match("generated/**", "src/generated.ax"));
+    }
+
+    #[test]
+    fn promote_turns_warning_into_error() {
+        let config: DiagnosticsConfig = serde_json::from_str(r#"{"promote": ["W001"]}"#).unwrap();
+        let ds = DiagnosticCollector::new("app.ax", UNUSED)
+            .with_config(&config)
+            .collect();
+        assert_eq!(ds.diagnostics[0].id, W001_UNUSED_LOCAL);
+        assert!(ds.has_errors());
+    }
+
+    #[test]
+    fn ignore_applies_only_to_matching_paths() {
+        let config: DiagnosticsConfig = serde_json::from_str(
+            r#"{"ignore": [{"paths": ["gen/**"], "codes": ["*"]}], "promote": ["W001"]}"#,
+        )
+        .unwrap();
+        let generated = DiagnosticCollector::new("gen/app.ax", UNUSED)
+            .with_config(&config)
+            .collect();
+        assert!(generated.diagnostics.is_empty());
+        let hand_written = DiagnosticCollector::new("src/app.ax", UNUSED)
+            .with_config(&config)
+            .collect();
+        assert!(hand_written.has_errors());
+    }
+
+    #[test]
+    fn discover_finds_config_in_ancestor_and_relativizes() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("gen/deep")).unwrap();
+        std::fs::write(
+            dir.path().join(CONFIG_FILE_NAME),
+            r#"{"ignore": [{"paths": ["gen/**"], "codes": ["W001"]}]}"#,
+        )
+        .unwrap();
+        let file = dir.path().join("gen/deep/app.ax");
+        std::fs::write(&file, UNUSED).unwrap();
+
+        let config = DiagnosticsConfig::discover(&file).unwrap().unwrap();
+        let file_str = file.display().to_string();
+        let ds = DiagnosticCollector::new(&file_str, UNUSED)
+            .with_config(&config)
+            .collect();
+        assert!(ds.diagnostics.is_empty(), "{}", ds.to_human());
+    }
+
+    #[test]
+    fn unknown_fields_are_rejected() {
+        assert!(serde_json::from_str::<DiagnosticsConfig>(r#"{"promot": []}"#).is_err());
+    }
+}
@@ -0,0 +1,516 @@
+//! Long-form explanations for diagnostic codes and `boruna` exit codes.
+//!
+//! `boruna explain E005` and `boruna explain exit:4` print these. Every
+//! code in [`super::registry::REGISTRY`] has exactly one entry, keyed by the
+//! same code string; exit codes are keyed `exit:N`. The short summary and
+//! category stay in the registry; this adds what a reader needs to fix the
+//! problem: a description, common causes and suggested fixes.
+
+use serde::Serialize;
+
+use super::registry::{self, DiagnosticCodeInfo};
+
+/// The long form of one diagnostic or exit code.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Explanation {
+    /// `"E005"`, `"W001"` or `"exit:4"`.
+    pub id: &'static str,
+    /// Short title: the registry name for diagnostics, the class for exit
+    /// codes.
+    pub title: &'static str,
+    pub description: &'static str,
+    pub causes: &'static [&'static str],
+    pub fixes: &'static [&'static str],
+}
+
+impl Explanation {
+    /// The registry entry, for diagnostic codes.
+    pub fn diagnostic(&self) -> Option<&'static DiagnosticCodeInfo> {
+        registry::registry().iter().find(|c| c.code == self.id)
+    }
+
+    /// Plain-text rendering for the terminal.
+    pub fn render(&self) -> String {
+        let mut out = format!("{}: {}\n\n{}\n", self.id, self.title, self.description);
+        for (heading, items) in [("Common causes", self.causes), ("How to fix", self.fixes)] {
+            if items.is_empty() {
+                continue;
+            }
+            out.push_str(&format!("\n{heading}:\n"));
+            for item in items {
+                out.push_str(&format!("  - {item}\n"));
+            }
+        }
+        out
+    }
+}
+
+/// Look up `id`: a diagnostic code (`E005`, case-insensitive) or an exit
+/// code (`exit:4`).
+pub fn explain(id: &str) -> Option<&'static Explanation> {
+    let id = id.trim();
+    EXPLANATIONS.iter().find(|e| e.id.eq_ignore_ascii_case(id))
+}
+
+/// Every explanation: diagnostic codes in registry order, then exit codes.
+pub fn all() -> &'static [Explanation] {
+    EXPLANATIONS
+}
+
+const EXPLANATIONS: &[Explanation] = &[
+    Explanation {
+        id: super::E001_LEXER,
+        title: "lexer-error",
+        description: "The lexer met a character or token it does not recognize, so the \
+            source could not be split into tokens. Nothing after the error is checked.",
+        causes: &[
+            "A stray character such as `@`, `#` or a non-ASCII quote pasted from a document.",
+            "An unterminated string literal: a `\"` with no closing quote on the line.",
+        ],
+        fixes: &[
+            "Go to the reported line and column and delete or replace the character.",
+            "Close the string literal, escaping inner quotes as `\\\"`.",
+        ],
+    },
+    Explanation {
+        id: super::E002_PARSE,
+        title: "parse-error",
+        description: "The tokens are valid on their own but do not form a valid program: \
+            the parser expected one construct and found another.",
+        causes: &[
+            "A missing closing `}` or `)`, often reported at the end of the file.",
+            "An incomplete expression such as `1 +` or a `let` without a value.",
+            "A type annotation missing where `.ax` requires one, e.g. on a `let` or a parameter.",
+        ],
+        fixes: &[
+            "Read the `expected ..., found ...` part of the message and fix the construct on that line.",
+            "Check the lines just before the reported one: an unclosed delimiter is often reported later.",
+        ],
+    },
+    Explanation {
+        id: super::E003_UNDEFINED_VAR,
+        title: "undefined-variable",
+        description: "An expression names a variable that is not in scope at that point.",
+        causes: &[
+            "A typo in the variable name.",
+            "Using a `let` binding outside the block that declared it.",
+            "Using a variable before its `let`.",
+        ],
+        fixes: &[
+            "Apply the suggested patch when the message offers a similar name.",
+            "Move the `let` earlier, or to an enclosing block.",
+        ],
+    },
+    Explanation {
+        id: super::E004_UNDEFINED_FN,
+        title: "undefined-function",
+        description: "A call names a function the module does not define. In a project, \
+            the function may exist in another file without being exported.",
+        causes: &[
+            "A typo in the function name.",
+            "The function lives in another file and is not marked `export`.",
+            "A built-in that needs a capability is called under a different name.",
+        ],
+        fixes: &[
+            "Apply the suggested patch when the message offers a similar name.",
+            "Add `export` to the function's definition in the other file.",
+        ],
+    },
+    Explanation {
+        id: super::E005_NON_EXHAUSTIVE_MATCH,
+        title: "non-exhaustive-match",
+        description: "A `match` does not cover every value its scrutinee can take, so some \
+            input would reach no arm. At run time that input would fail with \
+            `no match found for value`.",
+        causes: &[
+            "An enum variant added after the `match` was written.",
+            "Matching an `Option` or `Result` on only one of its cases.",
+            "Matching strings or integers without a `_` arm.",
+        ],
+        fixes: &[
+            "Add an arm for each missing variant the message lists.",
+            "Add a final `_ => ...` arm when the remaining cases share one behavior.",
+        ],
+    },
+    Explanation {
+        id: super::E006_UNKNOWN_FIELD,
+        title: "unknown-field",
+        description: "A record literal or field access names a field the record type does \
+            not declare.",
+        causes: &[
+            "A typo in the field name.",
+            "The field was renamed or removed from the `type` definition.",
+            "A record literal built for a different type.",
+        ],
+        fixes: &[
+            "Apply the suggested patch when the message offers a similar field.",
+            "Add the field to the `type` definition if it is meant to exist.",
+        ],
+    },
+    Explanation {
+        id: super::E007_CAPABILITY_VIOLATION,
+        title: "capability-violation",
+        description: "A function performs an effect without declaring it. Boruna checks \
+            capabilities statically: every built-in that reaches the outside world needs \
+            its capability in the function's `!{...}` set, and in the module's `uses` \
+            list when there is one.",
+        causes: &[
+            "Calling `kv_get`, `queue_push`, `notify_send` or another capability built-in from a function without `!{...}`.",
+            "A function declares a capability the module's `uses` list does not include.",
+        ],
+        fixes: &[
+            "Add the capability the message names, e.g. `fn load() -> Int !{kv.get} { ... }`.",
+            "Add it to the module's `uses` list as well when the module has one.",
+            "Move the effect into `update` and return it as an effect for the host to run, keeping the function pure.",
+        ],
+    },
+    Explanation {
+        id: super::E008_CODEGEN,
+        title: "codegen-error",
+        description: "The program type-checked but could not be lowered to bytecode.",
+        causes: &[
+            "A construct the code generator does not support yet.",
+            "More constants, locals or functions than the bytecode format can address.",
+        ],
+        fixes: &[
+            "Rewrite the construct the message names in a simpler form.",
+            "Split very large functions or modules.",
+            "If the program looks valid, report it: a codegen error on checked code is usually a compiler bug.",
+        ],
+    },
+    Explanation {
+        id: super::E009_TYPE_ERROR,
+        title: "type-error",
+        description: "An expression has a different type from the one its context \
+            requires: an argument, a return value, an operand or an annotated binding.",
+        causes: &[
+            "Returning a `String` from a function declared `-> Int`, or similar.",
+            "Passing arguments in the wrong order or with the wrong types.",
+            "Mixing `Int` and `Float` in arithmetic without a conversion.",
+        ],
+        fixes: &[
+            "Compare the expected and found types in the message and convert or change one side.",
+            "Fix the annotation if the value is right and the declared type is wrong.",
+        ],
+    },
+    Explanation {
+        id: super::E010_COMPILER_PANIC,
+        title: "compiler-panic",
+        description: "The lexer or parser panicked instead of reporting an error. This is \
+            always a compiler bug; the source may or may not be valid.",
+        causes: &["Unusual input that reaches an unhandled case in the compiler."],
+        fixes: &[
+            "Minimize the source to the smallest file that still panics and report it.",
+            "Work around it by rewriting the construct near the reported location.",
+        ],
+    },
+    Explanation {
+        id: super::E011_DUPLICATE_TYPE,
+        title: "duplicate-type",
+        description: "Two files of a project define a type with the same name. Projects \
+            share one type namespace, so the second definition is ambiguous.",
+        causes: &[
+            "A type copied into a second file instead of being imported.",
+            "Two unrelated types that happen to share a name.",
+        ],
+        fixes: &[
+            "Keep one definition, export it, and import it where needed.",
+            "Rename one of the types.",
+        ],
+    },
+    Explanation {
+        id: super::E012_UNUSED_EXPORT,
+        title: "unused-export",
+        description: "A function is marked `export` but no other file of the project \
+            references it, and its file is not an exposed module.",
+        causes: &[
+            "The last caller in another file was removed.",
+            "`export` added in anticipation of a caller that never came.",
+        ],
+        fixes: &[
+            "Remove `export` if the function is only used in its own file.",
+            "Delete the function if nothing uses it.",
+            "List the file in `exposed_modules` if the function is public API.",
+        ],
+    },
+    Explanation {
+        id: super::E013_PRECONDITION_VIOLATION,
+        title: "precondition-violation",
+        description: "A `requires` clause was false when the function was called. The VM \
+            raises it at run time; the message quotes the clause and the arguments that \
+            broke it, which form a replayable counterexample.",
+        causes: &[
+            "A caller passes a value outside the documented range.",
+            "The clause is stricter than the function actually needs.",
+        ],
+        fixes: &[
+            "Fix the caller so it only passes values the clause allows.",
+            "Relax the `requires` clause if the function handles the value correctly.",
+            "Build with `boruna compile --release` to strip contract checks once they are trusted.",
+        ],
+    },
+    Explanation {
+        id: super::E014_POSTCONDITION_VIOLATION,
+        title: "postcondition-violation",
+        description: "An `ensures` clause was false when the function returned: the \
+            function broke its own promise about its result. The message quotes the \
+            clause and the arguments that led to it.",
+        causes: &[
+            "A bug in the function body for the quoted arguments.",
+            "The clause promises more than the function guarantees.",
+        ],
+        fixes: &[
+            "Rerun the function with the quoted arguments and fix the body.",
+            "Weaken the `ensures` clause if it is wrong.",
+        ],
+    },
+    Explanation {
+        id: super::E015_BUDGET_EXCEEDED,
+        title: "budget-exceeded",
+        description: "The compiled module exceeds a limit in the budget file passed to \
+            `boruna compile --budget`. Every exceeded limit is listed.",
+        causes: &[
+            "Too many functions or constants for `max_functions` / `max_constants`.",
+            "A function with more ops than `max_ops_per_function`.",
+            "Blocks nested deeper than `max_nesting_depth`.",
+        ],
+        fixes: &[
+            "Split large functions and flatten deep nesting.",
+            "Raise the limit in the budget file if the growth is intended.",
+        ],
+    },
+    Explanation {
+        id: super::E016_TEMPLATE_MERGE_CONFLICT,
+        title: "template-merge-conflict",
+        description: "`boruna template upgrade` could not merge a hunk because your edit \
+            and the new template version both change the same lines. The file is left \
+            with conflict markers.",
+        causes: &["Local changes to lines the template also changed."],
+        fixes: &[
+            "Resolve the conflict markers by hand, keeping the parts of both versions you need.",
+            "Rerun `boruna template upgrade` after resolving.",
+        ],
+    },
+    Explanation {
+        id: super::W001_UNUSED_LOCAL,
+        title: "unused-variable",
+        description: "A `let` binding is never read or reassigned in its function.",
+        causes: &[
+            "A leftover from a refactor.",
+            "A typo: a later line uses a similar name instead.",
+        ],
+        fixes: &[
+            "Delete the binding, keeping the expression if it has effects.",
+            "Silence it with `// boruna:allow(W001)` above the statement.",
+        ],
+    },
+    Explanation {
+        id: super::W002_UNREACHABLE_ARM,
+        title: "unreachable-match-arm",
+        description: "A `match` arm can never run because an earlier arm is a catch-all or \
+            matches the same pattern.",
+        causes: &[
+            "A `_` arm placed before more specific arms.",
+            "The same variant or literal matched twice.",
+        ],
+        fixes: &[
+            "Move the catch-all arm last.",
+            "Delete the duplicate arm, or merge its body into the first one.",
+        ],
+    },
+    Explanation {
+        id: super::W003_DEAD_FUNCTION,
+        title: "dead-function",
+        description: "A function that is not exported and not an entry point is never called \
+            or referenced.",
+        causes: &["The last call was removed.", "A helper written but never wired in."],
+        fixes: &[
+            "Delete the function.",
+            "Mark it `export` if another file is meant to call it.",
+        ],
+    },
+    Explanation {
+        id: super::W004_UNHANDLED_CALLBACK,
+        title: "unhandled-callback",
+        description: "`update` emits an effect whose `callback_tag` no branch of `update` \
+            handles, so the host's reply message would fall through.",
+        causes: &[
+            "A typo in the callback tag or in the handled tag.",
+            "The handler branch was removed while the effect remained.",
+        ],
+        fixes: &[
+            "Add a branch for the tag in `update`; the diagnostic offers a stub.",
+            "Fix the tag so the effect and the branch agree.",
+        ],
+    },
+    Explanation {
+        id: super::W005_UNUSED_CAPABILITY,
+        title: "unused-capability",
+        description: "A capability is declared but never used: a function's `!{...}` names \
+            it without calling any built-in that needs it, or the module `uses` it without \
+            any function declaring it. Over-declared capabilities widen what a policy must \
+            grant.",
+        causes: &[
+            "A capability call was removed and the declaration stayed.",
+            "Declaring capabilities up front for planned code.",
+        ],
+        fixes: &[
+            "Remove the capability from the declaration; `boruna lang caps` lists every one.",
+        ],
+    },
+    Explanation {
+        id: "exit:0",
+        title: "success",
+        description: "The command did what was asked, or the check it ran passed.",
+        causes: &[],
+        fixes: &[],
+    },
+    Explanation {
+        id: "exit:1",
+        title: "failure",
+        description: "A failure without a more specific class.",
+        causes: &[
+            "A file that does not exist or cannot be read or written.",
+            "An unknown name, such as `boruna skills get` with a missing skill.",
+            "An error with no stable code (`unclassified` in the `--json` envelope).",
+        ],
+        fixes: &["Read the `error:` line on stderr; it names the file or value involved."],
+    },
+    Explanation {
+        id: "exit:2",
+        title: "invalid input",
+        description: "The input was rejected before anything ran: the source, a policy, a \
+            workflow definition or the command line itself is invalid.",
+        causes: &[
+            "A compile error (`E001`-`E016`), including under `boruna run`.",
+            "A policy file that fails `boruna policy validate`.",
+            "A workflow definition that fails validation or whose graph has a cycle.",
+            "An invalid `boruna.toml` or user config (`CFG001`); see `boruna config show`.",
+            "An unknown flag or missing argument.",
+        ],
+        fixes: &[
+            "Run `boruna lang check <file>` for structured diagnostics and suggested patches.",
+            "Run `boruna explain <code>` on the code in the message.",
+            "Run the command with `--help` for usage errors.",
+        ],
+    },
+    Explanation {
+        id: "exit:3",
+        title: "policy denial",
+        description: "The program ran but the capability policy refused something it tried \
+            to do.",
+        causes: &[
+            "A capability call the policy does not allow (`vm.capability_denied`).",
+            "An unsigned or untrusted module under `require_signed_modules`.",
+            "A module compiled against different capability contract versions.",
+            "Data classified above the policy's `data_flow.max_classification`.",
+        ],
+        fixes: &[
+            "Grant the capability in the policy if the program should have it.",
+            "Run `boruna policy infer --from-log` on a recorded run to derive a minimal policy.",
+            "Sign the module with a trusted key, or recompile it with this version of Boruna.",
+        ],
+    },
+    Explanation {
+        id: "exit:4",
+        title: "limit exceeded",
+        description: "The run stopped because it hit a limit. Limits make every run \
+            terminate; hitting one usually means unbounded recursion or a loop, or a \
+            limit set too low for the workload.",
+        causes: &[
+            "More steps than `--max-steps`, or a wall-clock limit.",
+            "More calls to a capability than its policy budget.",
+            "Recursion deeper than the call stack allows.",
+            "A framework app past its cycle limit, or a session manager at its session limit.",
+        ],
+        fixes: &[
+            "Check recursion and loops for a missing base case.",
+            "Raise the limit (`--max-steps`, the policy budget) if the workload needs it.",
+        ],
+    },
+    Explanation {
+        id: "exit:5",
+        title: "replay divergence",
+        description: "Replaying a recorded run, or repeating a run, produced a different \
+            result. Boruna runs are deterministic, so a divergence means the program, its \
+            inputs or a step's source changed since the recording.",
+        causes: &[
+            "A step or module source edited after the run was recorded.",
+            "`boruna replay` against an event log from a different program.",
+            "A flaky test under `trace2tests run --repeat`.",
+            "`boruna compile --verify-deterministic` built the same source to different bytes.",
+        ],
+        fixes: &[
+            "Diff the source against the version that produced the recording.",
+            "Record a fresh run if the change is intended.",
+        ],
+    },
+    Explanation {
+        id: "exit:6",
+        title: "check failed",
+        description: "A check ran to completion and did not pass. Use it to gate CI.",
+        causes: &[
+            "`boruna fmt --check` on an unformatted file.",
+            "Failing `trace2tests` tests, `lang difftest` divergences or `lang fuzz` crashes.",
+            "Over-declared capabilities under `boruna lang caps`.",
+            "An invalid evidence bundle, attestation or anchor.",
+            "A `boruna doctor` check with `error` status.",
+        ],
+        fixes: &["Read the command's report on stdout; it lists each failing item."],
+    },
+    Explanation {
+        id: "exit:7",
+        title: "runtime error",
+        description: "The program failed while running, for a reason other than a policy \
+            denial or a limit.",
+        causes: &[
+            "Division by zero, integer overflow or an out-of-bounds list index.",
+            "A failed assertion or contract (`E013`, `E014`).",
+            "A `match` with no arm for the value.",
+            "A workflow step that failed.",
+        ],
+        fixes: &[
+            "Rerun with `--json` for the error's stable kind and data.",
+            "Record the run with `--record` and inspect it with `boruna trace`.",
+        ],
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_diagnostic_code_is_explained() {
+        for code in registry::registry() {
+            let e =
+                explain(code.code).unwrap_or_else(|| panic!("{} has no explanation", code.code));
+            assert_eq!(e.title, code.name, "{}", code.code);
+            assert!(!e.causes.is_empty() && !e.fixes.is_empty(), "{}", code.code);
+        }
+        let diagnostics = EXPLANATIONS
+            .iter()
+            .filter(|e| e.diagnostic().is_some())
+            .count();
+        assert_eq!(diagnostics, registry::registry().len());
+    }
+
+    #[test]
+    fn ids_are_unique_and_lookup_ignores_case() {
+        let mut seen = std::collections::BTreeSet::new();
+        for e in EXPLANATIONS {
+            assert!(seen.insert(e.id), "duplicate {}", e.id);
+        }
+        assert_eq!(explain("e005").unwrap().id, "E005");
+        assert_eq!(explain("exit:4").unwrap().title, "limit exceeded");
+        assert!(explain("E999").is_none());
+    }
+
+    #[test]
+    fn render_lists_causes_and_fixes() {
+        let text = explain("E007").unwrap().render();
+        assert!(text.starts_with("E007: capability-violation\n"));
+        assert!(text.contains("Common causes:\n  - "));
+        assert!(text.contains("How to fix:\n  - "));
+    }
+}
@@ -1,7 +1,13 @@
 pub mod analyzer;
 pub mod collector;
+pub mod config;
+pub mod explain;
+pub mod project;
 pub mod registry;
 pub mod suggest;
+pub mod suppress;
+
+use std::collections::BTreeMap;
 
 use serde::{Deserialize, Serialize};
 
@@ -15,6 +21,21 @@ pub const E006_UNKNOWN_FIELD: &str = "E006";
 pub const E007_CAPABILITY_VIOLATION: &str = "E007";
 pub const E008_CODEGEN: &str = "E008";
 pub const E009_TYPE_ERROR: &str = "E009";
+pub const E010_COMPILER_PANIC: &str = "E010";
+pub const E011_DUPLICATE_TYPE: &str = "E011";
+pub const E012_UNUSED_EXPORT: &str = "E012";
+/// Runtime contract violations, raised by the VM rather than the compiler.
+pub const E013_PRECONDITION_VIOLATION: &str = "E013";
+pub const E014_POSTCONDITION_VIOLATION: &str = "E014";
+pub const E015_BUDGET_EXCEEDED: &str = "E015";
+pub const E016_TEMPLATE_MERGE_CONFLICT: &str = "E016";
+
+/// Stable warning codes. Never block compilation.
+pub const W001_UNUSED_LOCAL: &str = "W001";
+pub const W002_UNREACHABLE_ARM: &str = "W002";
+pub const W003_DEAD_FUNCTION: &str = "W003";
+pub const W004_UNHANDLED_CALLBACK: &str = "W004";
+pub const W005_UNUSED_CAPABILITY: &str = "W005";
 
 /// A structured, machine-readable diagnostic.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -102,6 +123,17 @@ impl DiagnosticSet {
         self.diagnostics.push(diag);
     }
 
+    /// Diagnostics grouped by the file they point at, in file order.
+    /// Diagnostics without a location are grouped under the set's `file`.
+    pub fn by_file(&self) -> BTreeMap<&str, Vec<&Diagnostic>> {
+        let mut groups: BTreeMap<&str, Vec<&Diagnostic>> = BTreeMap::new();
+        for d in &self.diagnostics {
+            let file = d.location.as_ref().map_or(self.file.as_str(), |l| &l.file);
+            groups.entry(file).or_default().push(d);
+        }
+        groups
+    }
+
     pub fn has_errors(&self) -> bool {
         self.diagnostics
             .iter()
@@ -178,6 +210,18 @@ impl Diagnostic {
         self
     }
 
+    /// Locate the diagnostic at a compiler span, end included.
+    pub fn at_span(mut self, file: &str, span: boruna_compiler::Span) -> Self {
+        self.location = Some(SourceLocation {
+            file: file.to_string(),
+            line: span.line,
+            col: Some(span.col),
+            end_line: Some(span.end_line),
+            end_col: Some(span.end_col),
+        });
+        self
+    }
+
     pub fn with_suggestion(mut self, patch: SuggestedPatch) -> Self {
         self.suggested_patches.push(patch);
         self
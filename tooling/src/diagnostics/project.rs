@@ -0,0 +1,654 @@
+//! Project-wide diagnostics across every `.ax` file under a root.
+//!
+//! Each file is still checked by [`DiagnosticCollector`], but with the
+//! exported functions and types of the other files in scope, so a call
+//! into a sibling file is not reported as undefined. On top of that the
+//! project pass reports what no single file can see: calls to functions
+//! that exist elsewhere but are not exported (E004), type names defined in
+//! more than one file (E011), and exported functions no other file uses
+//! (E012).
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use boruna_compiler::ast::*;
+use serde::Deserialize;
+
+use super::analyzer::find_fn_def_line;
+use super::collector::DiagnosticCollector;
+use super::config::DiagnosticsConfig;
+use super::suppress::Suppressions;
+use super::*;
+
+/// Framework and CLI entry points; exporting them is never "unused".
+pub(super) const ENTRY_POINTS: &[&str] = &["main", "init", "update", "view", "policies"];
+
+/// One parsed project file.
+struct ProjectFile {
+    /// Path relative to the project root, `/`-separated.
+    name: String,
+    source: String,
+    /// `None` when the file does not lex or parse; its collector run
+    /// reports why.
+    program: Option<Program>,
+}
+
+/// The fields of `package.ax.json` the project pass needs.
+#[derive(Deserialize)]
+struct ManifestModules {
+    exposed_modules: Vec<String>,
+}
+
+/// Collects diagnostics for a whole project.
+pub struct ProjectCollector {
+    root: PathBuf,
+    files: Vec<ProjectFile>,
+    /// Files whose exports are the package's public API (from a manifest's
+    /// `exposed_modules`); their exports are never reported as unused.
+    exposed: BTreeSet<String>,
+    config: Option<DiagnosticsConfig>,
+}
+
+impl ProjectCollector {
+    /// Load every `.ax` file under `root`, recursively.
+    pub fn from_dir(root: &Path) -> std::io::Result<Self> {
+        let mut paths = Vec::new();
+        collect_ax_files(root, &mut paths)?;
+        paths.sort();
+        let mut files = Vec::new();
+        for path in paths {
+            let source = std::fs::read_to_string(&path)?;
+            let name = path
+                .strip_prefix(root)
+                .unwrap_or(&path)
+                .to_string_lossy()
+                .replace('\\', "/");
+            files.push(ProjectFile {
+                program: parse(&source),
+                name,
+                source,
+            });
+        }
+        Ok(ProjectCollector {
+            root: root.to_path_buf(),
+            files,
+            exposed: BTreeSet::new(),
+            config: None,
+        })
+    }
+
+    /// Load a package from its `package.ax.json`: the sources under the
+    /// package's `src/`, with `exposed_modules` as its public API.
+    pub fn from_manifest(manifest: &Path) -> Result<Self, String> {
+        let json = std::fs::read_to_string(manifest)
+            .map_err(|e| format!("read manifest {}: {e}", manifest.display()))?;
+        let modules: ManifestModules =
+            serde_json::from_str(&json).map_err(|e| format!("parse manifest: {e}"))?;
+        let src = manifest.parent().unwrap_or(Path::new(".")).join("src");
+        let mut collector =
+            Self::from_dir(&src).map_err(|e| format!("read sources in {}: {e}", src.display()))?;
+        collector.exposed = modules
+            .exposed_modules
+            .iter()
+            .map(|m| format!("{m}.ax"))
+            .collect();
+        Ok(collector)
+    }
+
+    /// Build a project from in-memory `(file, source)` pairs.
+    pub fn from_sources(root: &str, sources: &[(&str, &str)]) -> Self {
+        let mut files: Vec<ProjectFile> = sources
+            .iter()
+            .map(|(name, source)| ProjectFile {
+                name: name.to_string(),
+                source: source.to_string(),
+                program: parse(source),
+            })
+            .collect();
+        files.sort_by(|a, b| a.name.cmp(&b.name));
+        ProjectCollector {
+            root: PathBuf::from(root),
+            files,
+            exposed: BTreeSet::new(),
+            config: None,
+        }
+    }
+
+    /// Apply `config` to the merged result. File paths are resolved
+    /// against the project root for its `ignore` globs.
+    pub fn with_config(mut self, config: DiagnosticsConfig) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Run the per-file and project passes. The set's `file` is the
+    /// project root; diagnostics are grouped by file, in path order (see
+    /// [`DiagnosticSet::by_file`]).
+    pub fn collect(&self) -> DiagnosticSet {
+        let mut ds = DiagnosticSet::new(&self.root.display().to_string());
+
+        for file in &self.files {
+            let externals = self.externals_for(&file.name);
+            let mut file_ds = DiagnosticCollector::new(&file.name, &file.source)
+                .collect_with_externals(&externals);
+            if let Some(program) = &file.program {
+                for diag in &mut file_ds.diagnostics {
+                    self.explain_undefined_call(diag, program);
+                }
+            }
+            ds.diagnostics.extend(file_ds.diagnostics);
+            self.check_duplicate_types(file, &mut ds);
+            self.check_unused_exports(file, &mut ds);
+            // Project-level findings point at definitions, so item pragmas
+            // are the ones that can allow them.
+            if let Some(program) = &file.program {
+                Suppressions::default()
+                    .with_items(&file.source, program)
+                    .apply(&mut ds, &file.name);
+            }
+        }
+
+        if let Some(config) = &self.config {
+            config.apply(&mut ds, &self.root);
+        }
+        ds
+    }
+
+    /// Exported items of every other file, with function bodies dropped:
+    /// only names, signatures, and capabilities matter to the checks.
+    fn externals_for(&self, file: &str) -> Vec<Item> {
+        let mut items = Vec::new();
+        for other in self.files.iter().filter(|f| f.name != file) {
+            let Some(program) = &other.program else {
+                continue;
+            };
+            for item in &program.items {
+                match item {
+                    Item::Function(f) if f.exported => items.push(Item::Function(FnDef {
+                        requires: Vec::new(),
+                        ensures: Vec::new(),
+                        body: Block { stmts: Vec::new() },
+                        ..f.clone()
+                    })),
+                    Item::TypeDef(t) if t.exported => items.push(item.clone()),
+                    _ => {}
+                }
+            }
+        }
+        items
+    }
+
+    /// Turn an undefined-name error on a called function into E004, and
+    /// point at the file that defines it without exporting it.
+    fn explain_undefined_call(&self, diag: &mut Diagnostic, program: &Program) {
+        if diag.id != E003_UNDEFINED_VAR && diag.id != E004_UNDEFINED_FN {
+            return;
+        }
+        let first_line = diag.message.lines().next().unwrap_or_default();
+        let Some(name) = first_line
+            .strip_prefix("undefined variable: ")
+            .or_else(|| first_line.strip_prefix("undefined function: "))
+            .map(str::to_string)
+        else {
+            return;
+        };
+        if !called_names(program).contains(&name) {
+            return;
+        }
+        diag.id = E004_UNDEFINED_FN.to_string();
+        diag.message = diag
+            .message
+            .replacen("undefined variable: ", "undefined function: ", 1);
+        for other in &self.files {
+            let Some(other_program) = &other.program else {
+                continue;
+            };
+            let private_def = other_program
+                .items
+                .iter()
+                .any(|i| matches!(i, Item::Function(f) if f.name == name && !f.exported));
+            if private_def {
+                diag.related.push(RelatedInfo {
+                    message: format!(
+                        "`{name}` is defined in {} but not exported; add `export`",
+                        other.name
+                    ),
+                    location: find_fn_def_line(&other.source, &name)
+                        .map(|line| location(&other.name, line)),
+                });
+            }
+        }
+    }
+
+    /// E011 for each type `file` defines that an earlier file already
+    /// defined.
+    fn check_duplicate_types(&self, file: &ProjectFile, ds: &mut DiagnosticSet) {
+        let Some(program) = &file.program else {
+            return;
+        };
+        for name in type_names(program) {
+            let first = self
+                .files
+                .iter()
+                .take_while(|f| f.name != file.name)
+                .find(|f| {
+                    f.program
+                        .as_ref()
+                        .is_some_and(|p| type_names(p).contains(&name))
+                });
+            let Some(first) = first else {
+                continue;
+            };
+            let mut diag = Diagnostic::error(
+                E011_DUPLICATE_TYPE,
+                format!("type `{name}` is also defined in {}", first.name),
+            )
+            .with_related(RelatedInfo {
+                message: format!("first definition of `{name}`"),
+                location: find_type_def_line(&first.source, &name)
+                    .map(|line| location(&first.name, line)),
+            });
+            if let Some(line) = find_type_def_line(&file.source, &name) {
+                diag = diag.at(&file.name, line, None);
+            }
+            ds.push(diag);
+        }
+    }
+
+    /// E012 (warning) for each exported function of `file` that no other
+    /// file references.
+    fn check_unused_exports(&self, file: &ProjectFile, ds: &mut DiagnosticSet) {
+        let Some(program) = &file.program else {
+            return;
+        };
+        if self.exposed.contains(&file.name) {
+            return;
+        }
+        let used_elsewhere: BTreeSet<String> = self
+            .files
+            .iter()
+            .filter(|f| f.name != file.name)
+            .filter_map(|f| f.program.as_ref())
+            .flat_map(referenced_names)
+            .collect();
+        for item in &program.items {
+            let Item::Function(f) = item else {
+                continue;
+            };
+            if !f.exported
+                || ENTRY_POINTS.contains(&f.name.as_str())
+                || used_elsewhere.contains(&f.name)
+            {
+                continue;
+            }
+            let mut diag = Diagnostic::warning(
+                E012_UNUSED_EXPORT,
+                format!(
+                    "exported function `{}` is not used by any other file",
+                    f.name
+                ),
+            );
+            if let Some(line) = find_fn_def_line(&file.source, &f.name) {
+                diag = diag.at(&file.name, line, None);
+            }
+            ds.push(diag);
+        }
+    }
+}
+
+fn parse(source: &str) -> Option<Program> {
+    let tokens = boruna_compiler::lexer::lex(source).ok()?;
+    boruna_compiler::parser::parse(tokens).ok()
+}
+
+fn collect_ax_files(dir: &Path, out: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_ax_files(&path, out)?;
+        } else if path.extension().is_some_and(|e| e == "ax") {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn location(file: &str, line: usize) -> SourceLocation {
+    SourceLocation {
+        file: file.to_string(),
+        line,
+        col: None,
+        end_line: None,
+        end_col: None,
+    }
+}
+
+fn type_names(program: &Program) -> Vec<String> {
+    program
+        .items
+        .iter()
+        .filter_map(|i| match i {
+            Item::TypeDef(t) => Some(t.name.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+pub(super) fn find_type_def_line(source: &str, name: &str) -> Option<usize> {
+    source
+        .lines()
+        .position(|line| {
+            let trimmed = line.trim_start().trim_start_matches("export ");
+            ["type ", "enum "].iter().any(|kw| {
+                trimmed
+                    .strip_prefix(kw)
+                    .and_then(|rest| rest.strip_prefix(name))
+                    .is_some_and(|rest| {
+                        !rest.starts_with(|c: char| c.is_alphanumeric() || c == '_')
+                    })
+            })
+        })
+        .map(|i| i + 1)
+}
+
+/// Names called directly (`name(...)`) anywhere in `program`.
+fn called_names(program: &Program) -> BTreeSet<String> {
+    let mut names = BTreeSet::new();
+    walk_program(program, &mut |expr| {
+        if let Expr::Call { func, .. } = expr {
+            if let Expr::Ident(name) = func.as_ref() {
+                names.insert(name.clone());
+            }
+        }
+    });
+    names
+}
+
+/// Every identifier `program` mentions, called or passed as a value.
+fn referenced_names(program: &Program) -> BTreeSet<String> {
+    let mut names = BTreeSet::new();
+    walk_program(program, &mut |expr| {
+        if let Expr::Ident(name) = expr {
+            names.insert(name.clone());
+        }
+    });
+    names
+}
+
+pub(super) fn walk_program(program: &Program, visit: &mut dyn FnMut(&Expr)) {
+    for item in &program.items {
+        if let Item::Function(f) = item {
+            for e in f.requires.iter().chain(&f.ensures) {
+                walk_expr(e, visit);
+            }
+            walk_block(&f.body, visit);
+        }
+    }
+}
+
+pub(super) fn walk_block(block: &Block, visit: &mut dyn FnMut(&Expr)) {
+    walk_block_with(block, &mut |_| {}, visit);
+}
+
+pub(super) fn walk_expr(expr: &Expr, visit: &mut dyn FnMut(&Expr)) {
+    walk_expr_with(expr, &mut |_| {}, visit);
+}
+
+/// Like [`walk_block`], but also visits every statement, including those in
+/// nested `if`/`while`/`for`/block bodies.
+pub(super) fn walk_block_with(
+    block: &Block,
+    on_stmt: &mut dyn FnMut(&Stmt),
+    visit: &mut dyn FnMut(&Expr),
+) {
+    for stmt in &block.stmts {
+        on_stmt(stmt);
+        match stmt {
+            Stmt::Let { value, .. } | Stmt::Assign { value, .. } | Stmt::Expr(value) => {
+                walk_expr_with(value, on_stmt, visit)
+            }
+            Stmt::Return(value) => {
+                if let Some(v) = value {
+                    walk_expr_with(v, on_stmt, visit);
+                }
+            }
+            Stmt::While { condition, body } => {
+                walk_expr_with(condition, on_stmt, visit);
+                walk_block_with(body, on_stmt, visit);
+            }
+            Stmt::For { iter, body, .. } => {
+                walk_expr_with(iter, on_stmt, visit);
+                walk_block_with(body, on_stmt, visit);
+            }
+        }
+    }
+}
+
+fn walk_expr_with(expr: &Expr, on_stmt: &mut dyn FnMut(&Stmt), visit: &mut dyn FnMut(&Expr)) {
+    visit(expr);
+    match expr {
+        Expr::Binary { left, right, .. } => {
+            walk_expr_with(left, on_stmt, visit);
+            walk_expr_with(right, on_stmt, visit);
+        }
+        Expr::Unary { expr, .. }
+        | Expr::SomeExpr(expr)
+        | Expr::OkExpr(expr)
+        | Expr::ErrExpr(expr)
+        | Expr::Spawn(expr)
+        | Expr::Emit(expr) => walk_expr_with(expr, on_stmt, visit),
+        Expr::Call { func, args } => {
+            walk_expr_with(func, on_stmt, visit);
+            for a in args {
+                walk_expr_with(a, on_stmt, visit);
+            }
+        }
+        Expr::FieldAccess { object, .. } => walk_expr_with(object, on_stmt, visit),
+        Expr::If {
+            condition,
+            then_block,
+            else_block,
+        } => {
+            walk_expr_with(condition, on_stmt, visit);
+            walk_block_with(then_block, on_stmt, visit);
+            if let Some(b) = else_block {
+                walk_block_with(b, on_stmt, visit);
+            }
+        }
+        Expr::Match { value, arms } => {
+            walk_expr_with(value, on_stmt, visit);
+            for arm in arms {
+                walk_expr_with(&arm.body, on_stmt, visit);
+            }
+        }
+        Expr::Record { fields, spread, .. } => {
+            for (_, e) in fields {
+                walk_expr_with(e, on_stmt, visit);
+            }
+            if let Some(s) = spread {
+                walk_expr_with(s, on_stmt, visit);
+            }
+        }
+        Expr::EnumVariant { payload, .. } => {
+            if let Some(p) = payload {
+                walk_expr_with(p, on_stmt, visit);
+            }
+        }
+        Expr::List(items) => {
+            for e in items {
+                walk_expr_with(e, on_stmt, visit);
+            }
+        }
+        Expr::Send { target, message } => {
+            walk_expr_with(target, on_stmt, visit);
+            walk_expr_with(message, on_stmt, visit);
+        }
+        Expr::Block(b) => walk_block_with(b, on_stmt, visit),
+        Expr::IntLit(_)
+        | Expr::FloatLit(_)
+        | Expr::DecimalLit(..)
+        | Expr::BigIntLit(_)
+        | Expr::StringLit(_)
+        | Expr::BoolLit(_)
+        | Expr::NoneLit
+        | Expr::Ident(_)
+        | Expr::Receive => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostics::config::CONFIG_FILE_NAME;
+
+    const MATH: &str = "export fn double(x: Int) -> Int {\n    x * 2\n}\n\nexport fn triple(x: Int) -> Int {\n    helper(x) * 3\n}\n\nfn helper(x: Int) -> Int {\n    x\n}\n";
+
+    fn ids(ds: &DiagnosticSet) -> Vec<(String, String)> {
+        ds.diagnostics
+            .iter()
+            .map(|d| {
+                let file = d
+                    .location
+                    .as_ref()
+                    .map(|l| l.file.clone())
+                    .unwrap_or_default();
+                (file, d.id.clone())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn cross_file_exported_call_resolves() {
+        let main = "fn main() -> Int {\n    double(2) + triple(1)\n}\n";
+        let ds = ProjectCollector::from_sources("proj", &[("main.ax", main), ("math.ax", MATH)])
+            .collect();
+        assert!(!ds.has_errors(), "{}", ds.to_human());
+        // Single-file checking alone would reject the call.
+        assert!(DiagnosticCollector::new("main.ax", main)
+            .collect()
+            .has_errors());
+    }
+
+    #[test]
+    fn call_to_unexported_function_is_e004_with_related_definition() {
+        let main = "fn main() -> Int {\n    helper(2)\n}\n";
+        let ds = ProjectCollector::from_sources("proj", &[("main.ax", main), ("math.ax", MATH)])
+            .collect();
+        let diag = ds
+            .diagnostics
+            .iter()
+            .find(|d| d.id == E004_UNDEFINED_FN)
+            .expect("E004");
+        assert!(diag.message.starts_with("undefined function: helper"));
+        assert_eq!(diag.location.as_ref().unwrap().file, "main.ax");
+        let related = &diag.related[0];
+        assert!(related.message.contains("math.ax but not exported"));
+        assert_eq!(related.location.as_ref().unwrap().line, 9);
+    }
+
+    #[test]
+    fn duplicate_type_names_are_reported_on_the_later_file() {
+        let a = "type Point { x: Int }\nfn main() -> Int { 0 }\n";
+        let b = "\nexport type Point { x: Int, y: Int }\n";
+        let ds = ProjectCollector::from_sources("proj", &[("b.ax", b), ("a.ax", a)]).collect();
+        let dup: Vec<&Diagnostic> = ds
+            .diagnostics
+            .iter()
+            .filter(|d| d.id == E011_DUPLICATE_TYPE)
+            .collect();
+        assert_eq!(dup.len(), 1);
+        let loc = dup[0].location.as_ref().unwrap();
+        assert_eq!((loc.file.as_str(), loc.line), ("b.ax", 2));
+        assert_eq!(dup[0].related[0].location.as_ref().unwrap().file, "a.ax");
+    }
+
+    #[test]
+    fn unused_exports_are_warned_and_grouped_by_file() {
+        let main = "fn main() -> Int {\n    double(2)\n}\n";
+        let ds = ProjectCollector::from_sources("proj", &[("main.ax", main), ("math.ax", MATH)])
+            .collect();
+        assert_eq!(
+            ids(&ds),
+            vec![("math.ax".into(), E012_UNUSED_EXPORT.into())]
+        );
+        assert_eq!(ds.diagnostics[0].severity, Severity::Warning);
+        assert!(ds.diagnostics[0].message.contains("`triple`"));
+        assert_eq!(ds.diagnostics[0].location.as_ref().unwrap().line, 5);
+
+        let groups = ds.by_file();
+        assert_eq!(groups.keys().copied().collect::<Vec<_>>(), vec!["math.ax"]);
+    }
+
+    #[test]
+    fn manifest_exposed_modules_are_public_api() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("src/util")).unwrap();
+        std::fs::write(
+            dir.path().join("package.ax.json"),
+            r#"{"name":"p","version":"1.0.0","description":"","exposed_modules":["core"]}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("src/core.ax"),
+            "export fn api() -> Int {\n    inner()\n}\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("src/util/inner.ax"),
+            "export fn inner() -> Int {\n    1\n}\n\nexport fn stale() -> Int {\n    2\n}\n",
+        )
+        .unwrap();
+
+        let ds = ProjectCollector::from_manifest(&dir.path().join("package.ax.json"))
+            .unwrap()
+            .collect();
+        assert_eq!(
+            ids(&ds),
+            vec![("util/inner.ax".into(), E012_UNUSED_EXPORT.into())]
+        );
+        assert!(ds.diagnostics[0].message.contains("`stale`"));
+    }
+
+    #[test]
+    fn parse_errors_stay_per_file() {
+        let ds = ProjectCollector::from_sources("proj", &[("bad.ax", "fn { }"), ("math.ax", MATH)])
+            .collect();
+        let groups = ds.by_file();
+        assert_eq!(groups["bad.ax"][0].id, E002_PARSE);
+        // The broken file can't use anything, so both exports are unused.
+        assert_eq!(groups["math.ax"].len(), 2);
+    }
+
+    #[test]
+    fn item_pragma_allows_unused_export() {
+        let math = "// boruna:allow(E012)\nexport fn triple(x: Int) -> Int {\n    x * 3\n}\n";
+        let main = "fn main() -> Int {\n    1\n}\n";
+        let ds = ProjectCollector::from_sources("proj", &[("main.ax", main), ("math.ax", math)])
+            .collect();
+        assert!(ds.diagnostics.is_empty(), "{}", ds.to_human());
+    }
+
+    #[test]
+    fn config_ignores_by_path_relative_to_project_root() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("gen")).unwrap();
+        std::fs::write(dir.path().join("gen/math.ax"), MATH).unwrap();
+        std::fs::write(
+            dir.path().join("main.ax"),
+            "fn main() -> Int {\n    double(1)\n}\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join(CONFIG_FILE_NAME),
+            r#"{"ignore": [{"paths": ["gen/**"], "codes": ["E012"]}]}"#,
+        )
+        .unwrap();
+
+        let config = DiagnosticsConfig::discover(dir.path()).unwrap().unwrap();
+        let ds = ProjectCollector::from_dir(dir.path())
+            .unwrap()
+            .with_config(config)
+            .collect();
+        assert!(ds.diagnostics.is_empty(), "{}", ds.to_human());
+    }
+}
@@ -1,10 +1,10 @@
 //! Machine-readable registry of stable diagnostic codes.
 //!
-//! Every `E0NN` code emitted by the toolchain has exactly one entry here. The
+//! Every `E0NN` and `W0NN` code emitted by the toolchain has exactly one entry here. The
 //! registry is the agent-facing source of truth: `boruna lang codes` serves it
 //! so an agent can resolve a code seen in `lang check --json` output without
 //! reading compiler source. A drift test asserts the registry stays 1:1 with
-//! the `E0NN` constants in `super`.
+//! the `E0NN`/`W0NN` constants in `super`.
 
 use serde::Serialize;
 
@@ -77,6 +77,78 @@ pub const REGISTRY: &[DiagnosticCodeInfo] = &[
         summary: "An expression's type does not match the type required by its context.",
         category: "type",
     },
+    DiagnosticCodeInfo {
+        code: super::E010_COMPILER_PANIC,
+        name: "compiler-panic",
+        summary: "The lexer or parser panicked instead of returning an error (a compiler bug).",
+        category: "internal",
+    },
+    DiagnosticCodeInfo {
+        code: super::E011_DUPLICATE_TYPE,
+        name: "duplicate-type",
+        summary: "A type name is defined in more than one file of a project.",
+        category: "name-resolution",
+    },
+    DiagnosticCodeInfo {
+        code: super::E012_UNUSED_EXPORT,
+        name: "unused-export",
+        summary: "An exported function is not referenced by any other file of a project.",
+        category: "project",
+    },
+    DiagnosticCodeInfo {
+        code: super::E013_PRECONDITION_VIOLATION,
+        name: "precondition-violation",
+        summary: "A `requires` clause was false on entry; the message quotes the clause and the arguments.",
+        category: "contract",
+    },
+    DiagnosticCodeInfo {
+        code: super::E014_POSTCONDITION_VIOLATION,
+        name: "postcondition-violation",
+        summary: "An `ensures` clause was false on return; the message quotes the clause and the arguments.",
+        category: "contract",
+    },
+    DiagnosticCodeInfo {
+        code: super::E015_BUDGET_EXCEEDED,
+        name: "budget-exceeded",
+        summary: "The compiled module exceeds a size or complexity budget (functions, ops per function, constants, nesting depth).",
+        category: "budget",
+    },
+    DiagnosticCodeInfo {
+        code: super::E016_TEMPLATE_MERGE_CONFLICT,
+        name: "template-merge-conflict",
+        summary: "`boruna template upgrade` could not merge a hunk: your edit and the new template version both change the same lines.",
+        category: "template",
+    },
+    DiagnosticCodeInfo {
+        code: super::W001_UNUSED_LOCAL,
+        name: "unused-variable",
+        summary: "A `let` binding is never read or reassigned in its function.",
+        category: "lint",
+    },
+    DiagnosticCodeInfo {
+        code: super::W002_UNREACHABLE_ARM,
+        name: "unreachable-match-arm",
+        summary: "A match arm follows a catch-all or repeats an earlier pattern, so it never runs.",
+        category: "lint",
+    },
+    DiagnosticCodeInfo {
+        code: super::W003_DEAD_FUNCTION,
+        name: "dead-function",
+        summary: "A private function is never called or referenced by any other function.",
+        category: "lint",
+    },
+    DiagnosticCodeInfo {
+        code: super::W004_UNHANDLED_CALLBACK,
+        name: "unhandled-callback",
+        summary: "An effect is emitted with a callback tag that update() never handles.",
+        category: "framework",
+    },
+    DiagnosticCodeInfo {
+        code: super::W005_UNUSED_CAPABILITY,
+        name: "unused-capability",
+        summary: "A declared capability is never called: a function declares it but calls no built-in needing it, or the module `uses` it but no function declares it.",
+        category: "capability",
+    },
 ];
 
 /// Returns the full diagnostic-code registry.
@@ -88,7 +160,7 @@ pub fn registry() -> &'static [DiagnosticCodeInfo] {
 mod tests {
     use super::*;
 
-    /// Extract every `E0NN` code string from a `pub const ... = "E0NN";` line
+    /// Extract every `E0NN`/`W0NN` code string from a `pub const ... = "E0NN";` line
     /// in `diagnostics/mod.rs`. Parsing the source — rather than a hand-kept
     /// list — means a constant added to `mod.rs` but not to the registry can
     /// never slip past `registry_matches_source_constants`.
@@ -97,7 +169,7 @@ mod tests {
         let mut codes = Vec::new();
         for line in src.lines() {
             let line = line.trim();
-            if !line.starts_with("pub const E") {
+            if !line.starts_with("pub const E") && !line.starts_with("pub const W") {
                 continue;
             }
             // ... = "E001";  -> grab the quoted literal.
@@ -115,7 +187,7 @@ mod tests {
         let declared = constants_declared_in_source();
         assert!(
             !declared.is_empty(),
-            "found no `pub const E..` declarations in mod.rs — parser broke"
+            "found no `pub const E..`/`W..` declarations in mod.rs — parser broke"
         );
         let registry_codes: Vec<&str> = REGISTRY.iter().map(|c| c.code).collect();
         for code in &declared {
@@ -134,6 +206,19 @@ mod tests {
         assert_eq!(REGISTRY.len(), declared.len());
     }
 
+    #[test]
+    fn contract_codes_match_the_vm() {
+        use boruna_bytecode::opcode::ContractKind;
+        assert_eq!(
+            ContractKind::Requires.diagnostic_code(),
+            super::super::E013_PRECONDITION_VIOLATION
+        );
+        assert_eq!(
+            ContractKind::Ensures.diagnostic_code(),
+            super::super::E014_POSTCONDITION_VIOLATION
+        );
+    }
+
     #[test]
     fn registry_codes_are_unique() {
         let mut seen = std::collections::BTreeSet::new();
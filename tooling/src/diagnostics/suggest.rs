@@ -1,6 +1,9 @@
+use std::cmp::Reverse;
+
 use boruna_compiler::ast::*;
 
 use super::*;
+use crate::repair::calibration::Calibration;
 
 /// Enhance a compiler diagnostic with suggestions based on AST analysis.
 pub fn enhance_compiler_diagnostic(
@@ -379,6 +382,143 @@ pub fn suggest_remove_capabilities(
     })
 }
 
+/// Suggest silencing an unused `let` binding by prefixing it with `_`.
+pub fn suggest_prefix_unused(
+    file: &str,
+    source: &str,
+    let_line: usize,
+    name: &str,
+) -> Option<SuggestedPatch> {
+    let old_line = *source
+        .lines()
+        .collect::<Vec<_>>()
+        .get(let_line.checked_sub(1)?)?;
+    let indent = &old_line[..old_line.len() - old_line.trim_start().len()];
+    let trimmed = old_line.trim_start();
+    let keyword = ["let mut ", "let "]
+        .into_iter()
+        .find(|kw| trimmed.starts_with(kw))?;
+    let rest = trimmed[keyword.len()..].strip_prefix(name)?;
+    if rest.starts_with(|c: char| c.is_alphanumeric() || c == '_') {
+        return None;
+    }
+
+    Some(SuggestedPatch {
+        id: format!("{W001_UNUSED_LOCAL}-prefix-{name}"),
+        description: format!("rename '{name}' to '_{name}'"),
+        confidence: Confidence::High,
+        rationale: "a leading underscore marks the binding as intentionally unused".to_string(),
+        edits: vec![TextEdit {
+            file: file.to_string(),
+            start_line: let_line,
+            old_text: old_line.to_string(),
+            new_text: format!("{indent}{keyword}_{name}{rest}"),
+        }],
+    })
+}
+
+/// Suggest deleting lines `start_line..=end_line` (1-indexed).
+pub fn suggest_delete_lines(
+    id: String,
+    description: String,
+    rationale: &str,
+    file: &str,
+    source: &str,
+    start_line: usize,
+    end_line: usize,
+) -> Option<SuggestedPatch> {
+    let lines: Vec<&str> = source.lines().collect();
+    if start_line == 0 || end_line < start_line || end_line > lines.len() {
+        return None;
+    }
+
+    Some(SuggestedPatch {
+        id,
+        description,
+        confidence: Confidence::High,
+        rationale: rationale.to_string(),
+        edits: vec![TextEdit {
+            file: file.to_string(),
+            start_line,
+            old_text: lines[start_line - 1..end_line].join("\n"),
+            new_text: String::new(),
+        }],
+    })
+}
+
+/// Suggest a stub branch at the top of `update()` that handles `tag`.
+///
+/// `stub_body` is the expression returned from the branch (typically the
+/// unchanged state with no effects). The stub is a placeholder, so the patch
+/// is low-confidence: the agent is expected to fill in the real handling.
+pub fn suggest_callback_handler(
+    file: &str,
+    source: &str,
+    update_line: usize,
+    msg_param: &str,
+    tag: &str,
+    stub_body: &str,
+) -> Option<SuggestedPatch> {
+    let lines: Vec<&str> = source.lines().collect();
+    let old_line = *lines.get(update_line.checked_sub(1)?)?;
+    // Only insert after a header line that opens the body and nothing else.
+    if !old_line.trim_end().ends_with('{') {
+        return None;
+    }
+    let body_indent = lines
+        .get(update_line)
+        .map(|next| &next[..next.len() - next.trim_start().len()])
+        .filter(|indent| !indent.is_empty())
+        .unwrap_or("    ");
+
+    Some(SuggestedPatch {
+        id: format!("{W004_UNHANDLED_CALLBACK}-handle-{tag}"),
+        description: format!("add a stub handler for callback tag \"{tag}\" to update()"),
+        confidence: Confidence::Low,
+        rationale: "every emitted callback tag is delivered back to update() as a message"
+            .to_string(),
+        edits: vec![TextEdit {
+            file: file.to_string(),
+            start_line: update_line,
+            old_text: old_line.to_string(),
+            new_text: format!(
+                "{old_line}\n{body_indent}if {msg_param}.tag == \"{tag}\" {{ return {stub_body} }}"
+            ),
+        }],
+    })
+}
+
+/// Re-rank `diag`'s patches with observed repair outcomes. A patch whose
+/// kind has enough samples takes the confidence its success rate implies,
+/// and its rationale cites the evidence. Patches are then ordered by
+/// confidence and success rate; ties keep their original order.
+pub fn rank_patches(diag: &mut Diagnostic, calibration: &Calibration) {
+    for patch in &mut diag.suggested_patches {
+        let Some(stats) = calibration.stats(&patch.id) else {
+            continue;
+        };
+        if let Some(confidence) = stats.confidence() {
+            patch.confidence = confidence;
+            patch.rationale = format!(
+                "{} (calibrated: {} of {} applications succeeded)",
+                patch.rationale, stats.succeeded, stats.applied
+            );
+        }
+    }
+    diag.suggested_patches.sort_by_key(|p| {
+        let rank = match p.confidence {
+            Confidence::High => 0,
+            Confidence::Medium => 1,
+            Confidence::Low => 2,
+        };
+        let rate = calibration
+            .stats(&p.id)
+            .and_then(|s| s.success_rate())
+            .unwrap_or(0.0);
+        (rank, Reverse((rate * 1000.0) as u32))
+    });
+}
+
 /// Remove `!{...}` from a function definition line.
 fn remove_capability_annotation(line: &str) -> String {
     // Find !{ and matching }
@@ -527,6 +667,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_rank_patches_uses_calibration() {
+        use crate::repair::calibration::KindStats;
+
+        let source = "    let countt = 1\n";
+        let rename = suggest_rename_identifier("test.ax", source, "countt", "count", Some(1))
+            .expect("rename patch");
+        let prefix = suggest_prefix_unused("test.ax", source, 1, "countt").expect("prefix patch");
+        let mut diag = Diagnostic::warning(W001_UNUSED_LOCAL, "unused".into())
+            .with_suggestion(prefix)
+            .with_suggestion(rename);
+
+        let mut calibration = Calibration::default();
+        calibration.kinds.insert(
+            "E003-rename".into(),
+            KindStats {
+                applied: 10,
+                succeeded: 9,
+                ..KindStats::default()
+            },
+        );
+        rank_patches(&mut diag, &calibration);
+
+        let ids: Vec<&str> = diag
+            .suggested_patches
+            .iter()
+            .map(|p| p.id.as_str())
+            .collect();
+        // Promoted from Medium to High, and ahead of the unmeasured patch.
+        assert_eq!(ids, ["E003-rename-countt", "W001-prefix-countt"]);
+        assert_eq!(diag.suggested_patches[0].confidence, Confidence::High);
+        assert!(diag.suggested_patches[0]
+            .rationale
+            .ends_with("(calibrated: 9 of 10 applications succeeded)"));
+    }
+
     #[test]
     fn test_suggest_missing_match_arms() {
         let source = "\
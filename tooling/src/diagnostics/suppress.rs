@@ -0,0 +1,167 @@
+//! In-source suppression pragmas.
+//!
+//! A `// boruna:allow(E005, W001)` comment on the line(s) directly above
+//! an item or a statement silences those codes for it:
+//!
+//! - above a `fn`/`type`/`enum` (the parser attaches it to the AST as
+//!   `FnDef::allow`/`TypeDef::allow`): the whole definition;
+//! - above a statement inside a body: that statement, including any
+//!   nested block or multi-line expression it opens.
+//!
+//! Only diagnostics with a location can be suppressed.
+
+use boruna_compiler::ast::{Item, Program};
+use boruna_compiler::lexer::{allow_pragma_codes, Token, TokenKind};
+
+use super::analyzer::{find_fn_def_line, find_match_end_line};
+use super::project::find_type_def_line;
+use super::*;
+
+/// Line ranges (1-indexed, inclusive) with the codes allowed in each.
+#[derive(Debug, Clone, Default)]
+pub struct Suppressions {
+    scopes: Vec<(usize, usize, Vec<String>)>,
+}
+
+impl Suppressions {
+    /// Statement-level pragmas, read from the leading trivia of tokens
+    /// inside a body. Item-level pragmas come from the AST instead; see
+    /// [`with_items`](Self::with_items).
+    pub fn from_tokens(tokens: &[Token]) -> Self {
+        let depths = token_depths(tokens);
+        let mut scopes = Vec::new();
+        for (i, tok) in tokens.iter().enumerate() {
+            if depths[i] == 0 {
+                continue;
+            }
+            let codes = allow_pragma_codes(&tok.leading_trivia);
+            if codes.is_empty() {
+                continue;
+            }
+            // The statement runs until the next token, on a later line, that
+            // is back at the same depth and does not continue it (`}` or
+            // `else` closing a nested block).
+            let end = tokens[i + 1..]
+                .iter()
+                .zip(&depths[i + 1..])
+                .find(|(t, &d)| {
+                    t.line > tok.line
+                        && d <= depths[i]
+                        && !matches!(
+                            t.kind,
+                            TokenKind::Newline | TokenKind::RBrace | TokenKind::Else
+                        )
+                })
+                .map_or(usize::MAX, |(t, _)| t.line - 1);
+            scopes.push((tok.line, end, codes));
+        }
+        Suppressions { scopes }
+    }
+
+    /// Add the item-level pragmas the parser attached to `program`'s
+    /// functions and types, scoped to each definition's lines in `source`.
+    pub fn with_items(mut self, source: &str, program: &Program) -> Self {
+        for item in &program.items {
+            let (line, allow) = match item {
+                Item::Function(f) => (find_fn_def_line(source, &f.name), &f.allow),
+                Item::TypeDef(t) => (find_type_def_line(source, &t.name), &t.allow),
+                _ => continue,
+            };
+            if let (Some(start), false) = (line, allow.is_empty()) {
+                let end = find_match_end_line(source, start).unwrap_or(start);
+                self.scopes.push((start, end, allow.clone()));
+            }
+        }
+        self
+    }
+
+    /// Whether `code` is allowed on `line`.
+    pub fn is_suppressed(&self, code: &str, line: usize) -> bool {
+        self.scopes.iter().any(|(start, end, codes)| {
+            (*start..=*end).contains(&line) && codes.iter().any(|c| c == code)
+        })
+    }
+
+    /// Drop diagnostics in `file` that a pragma allows.
+    pub fn apply(&self, ds: &mut DiagnosticSet, file: &str) {
+        ds.diagnostics.retain(|d| match &d.location {
+            Some(loc) if loc.file == file => !self.is_suppressed(&d.id, loc.line),
+            _ => true,
+        });
+    }
+}
+
+/// Brace depth at each token: `{` counts from the token after it, `}` from
+/// itself, so a block's braces sit at the depth of the code around it.
+fn token_depths(tokens: &[Token]) -> Vec<usize> {
+    let mut depth = 0usize;
+    tokens
+        .iter()
+        .map(|t| {
+            match t.kind {
+                TokenKind::RBrace => depth = depth.saturating_sub(1),
+                TokenKind::LBrace => {
+                    depth += 1;
+                    return depth - 1;
+                }
+                _ => {}
+            }
+            depth
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostics::collector::DiagnosticCollector;
+
+    fn ids(source: &str) -> Vec<(String, usize)> {
+        DiagnosticCollector::new("test.ax", source)
+            .collect()
+            .diagnostics
+            .into_iter()
+            .map(|d| (d.id, d.location.map_or(0, |l| l.line)))
+            .collect()
+    }
+
+    #[test]
+    fn statement_pragma_covers_only_that_statement() {
+        let source = "\
+fn main() -> Int {
+    // boruna:allow(W001)
+    let a = if true {
+        1
+    } else {
+        2
+    }
+    let b = 3
+    0
+}
+";
+        assert_eq!(ids(source), vec![(W001_UNUSED_LOCAL.to_string(), 8)]);
+    }
+
+    #[test]
+    fn item_pragma_covers_the_whole_function() {
+        let source = "\
+export fn api() -> Int {
+    1
+}
+
+// Kept for the next release.
+// boruna:allow(W003, W001)
+fn stale() -> Int {
+    let unused = 2
+    0
+}
+";
+        assert!(ids(source).is_empty(), "{:?}", ids(source));
+    }
+
+    #[test]
+    fn pragma_for_another_code_does_not_suppress() {
+        let source = "fn main() -> Int {\n    // boruna:allow(E005)\n    let a = 1\n    0\n}\n";
+        assert_eq!(ids(source), vec![(W001_UNUSED_LOCAL.to_string(), 3)]);
+    }
+}
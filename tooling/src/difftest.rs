@@ -0,0 +1,213 @@
+//! Differential testing of bytecode pipelines for `boruna lang difftest`.
+//!
+//! Every `.ax` file in a corpus is compiled once, then handed to each
+//! [`Pipeline`] to produce the module actually executed. All variants run
+//! under the same policy (`Policy::allow_all`, mock capability handler) and
+//! step limit, and must agree on the return value (or error) and on the
+//! full `EventLog` (`ReplayEngine::verify_full`). Today the pipelines are
+//! serialization round-trips (`Module::to_bytes`/`from_bytes` and JSON);
+//! an optimizing pipeline slots in as another variant.
+
+use std::fs;
+use std::path::Path;
+
+use boruna_bytecode::Module;
+use boruna_vm::capability_gateway::{CapabilityGateway, Policy};
+use boruna_vm::replay::{EventLog, ReplayEngine, ReplayResult};
+use boruna_vm::vm::Vm;
+use serde::Serialize;
+
+/// Default step limit per run.
+pub const DEFAULT_MAX_STEPS: u64 = 1_000_000;
+
+/// A way of turning the compiled module into the module that runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pipeline {
+    /// The module as emitted by the compiler (the reference).
+    Direct,
+    /// `Module::to_bytes` → `Module::from_bytes` (the `.axbc` path).
+    BinaryRoundTrip,
+    /// `Module::to_json` → `Module::from_json`.
+    JsonRoundTrip,
+}
+
+impl Pipeline {
+    pub const ALL: [Pipeline; 3] = [
+        Pipeline::Direct,
+        Pipeline::BinaryRoundTrip,
+        Pipeline::JsonRoundTrip,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Pipeline::Direct => "direct",
+            Pipeline::BinaryRoundTrip => "binary_roundtrip",
+            Pipeline::JsonRoundTrip => "json_roundtrip",
+        }
+    }
+
+    fn prepare(self, module: &Module) -> Result<Module, String> {
+        match self {
+            Pipeline::Direct => Ok(module.clone()),
+            Pipeline::BinaryRoundTrip => {
+                let bytes = module.to_bytes().map_err(|e| e.to_string())?;
+                Module::from_bytes(&bytes).map_err(|e| e.to_string())
+            }
+            Pipeline::JsonRoundTrip => {
+                let json = module.to_json().map_err(|e| e.to_string())?;
+                Module::from_json(&json).map_err(|e| e.to_string())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaseStatus {
+    /// All pipelines agreed.
+    Identical,
+    /// At least one pipeline disagreed with `direct`.
+    Diverged,
+    /// The source did not compile; nothing to compare.
+    Skipped,
+}
+
+/// Outcome of one pipeline on one case.
+#[derive(Debug, Clone, Serialize)]
+pub struct PipelineRun {
+    pub pipeline: &'static str,
+    /// `Ok` return value or `Err` message, rendered for comparison.
+    pub outcome: String,
+    pub event_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CaseResult {
+    pub file: String,
+    pub status: CaseStatus,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub runs: Vec<PipelineRun>,
+    /// First divergence, or the compile error for skipped cases.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DiffReport {
+    pub identical: usize,
+    pub diverged: usize,
+    pub skipped: usize,
+    pub cases: Vec<CaseResult>,
+}
+
+impl DiffReport {
+    pub fn passed(&self) -> bool {
+        self.diverged == 0
+    }
+}
+
+fn execute(module: Module, max_steps: u64) -> (String, EventLog) {
+    let mut vm = Vm::new(module, CapabilityGateway::new(Policy::allow_all()));
+    vm.set_max_steps(max_steps);
+    let outcome = match vm.run() {
+        Ok(v) => format!("ok: {v:?}"),
+        Err(e) => format!("error: {e}"),
+    };
+    (outcome, vm.event_log().clone())
+}
+
+/// Compile `source` and compare every pipeline against `direct`.
+pub fn run_case(file: &str, source: &str, max_steps: u64) -> CaseResult {
+    let module = match boruna_compiler::compile(file, source) {
+        Ok(m) => m,
+        Err(e) => {
+            return CaseResult {
+                file: file.to_string(),
+                status: CaseStatus::Skipped,
+                runs: Vec::new(),
+                reason: Some(format!("compile error: {e}")),
+            }
+        }
+    };
+
+    let mut runs = Vec::new();
+    let mut reason = None;
+    let mut reference: Option<(String, EventLog)> = None;
+    for pipeline in Pipeline::ALL {
+        let (outcome, log) = match pipeline.prepare(&module) {
+            Ok(m) => execute(m, max_steps),
+            Err(e) => (format!("pipeline error: {e}"), EventLog::new()),
+        };
+        runs.push(PipelineRun {
+            pipeline: pipeline.name(),
+            outcome: outcome.clone(),
+            event_count: log.events().len(),
+        });
+        match &reference {
+            None => reference = Some((outcome, log)),
+            Some((ref_outcome, ref_log)) if reason.is_none() => {
+                if *ref_outcome != outcome {
+                    reason = Some(format!(
+                        "{}: outcome differs: {ref_outcome} vs {outcome}",
+                        pipeline.name()
+                    ));
+                } else if let ReplayResult::Diverged { reason: r } =
+                    ReplayEngine::verify_full(ref_log, &log)
+                {
+                    reason = Some(format!("{}: {r}", pipeline.name()));
+                }
+            }
+            Some(_) => {}
+        }
+    }
+
+    CaseResult {
+        file: file.to_string(),
+        status: if reason.is_some() {
+            CaseStatus::Diverged
+        } else {
+            CaseStatus::Identical
+        },
+        runs,
+        reason,
+    }
+}
+
+/// Run every `.ax` file under `dir` (recursively, in path order).
+pub fn run_corpus(dir: &Path, max_steps: u64) -> Result<DiffReport, String> {
+    let mut files = Vec::new();
+    collect_ax(dir, &mut files).map_err(|e| format!("cannot read {}: {e}", dir.display()))?;
+    files.sort();
+
+    let mut report = DiffReport {
+        identical: 0,
+        diverged: 0,
+        skipped: 0,
+        cases: Vec::new(),
+    };
+    for path in files {
+        let source = fs::read_to_string(&path)
+            .map_err(|e| format!("cannot read {}: {e}", path.display()))?;
+        let rel = path.strip_prefix(dir).unwrap_or(&path);
+        let case = run_case(&rel.to_string_lossy(), &source, max_steps);
+        match case.status {
+            CaseStatus::Identical => report.identical += 1,
+            CaseStatus::Diverged => report.diverged += 1,
+            CaseStatus::Skipped => report.skipped += 1,
+        }
+        report.cases.push(case);
+    }
+    Ok(report)
+}
+
+fn collect_ax(dir: &Path, out: &mut Vec<std::path::PathBuf>) -> std::io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_ax(&path, out)?;
+        } else if path.extension().is_some_and(|e| e == "ax") {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
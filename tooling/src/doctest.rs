@@ -0,0 +1,145 @@
+//! `boruna lang test`: run the inline `//! test: <expr> == <expected>`
+//! annotations of a `.ax` file.
+//!
+//! Each annotation is compiled against the file by
+//! [`boruna_compiler::doctest::compile_test`] and both sides run on a fresh
+//! VM. Results come back as a trace2tests [`SuiteReport`], one case per
+//! annotation, so they render as console, JSON or JUnit exactly like
+//! `trace2tests run-suite`.
+
+use std::time::Instant;
+
+use boruna_bytecode::Value;
+use boruna_compiler::doctest::{compile_test, function_index, DocTest, EXPECTED_FN};
+use boruna_vm::capability_gateway::{CapabilityGateway, Policy};
+use boruna_vm::Vm;
+
+use crate::trace2tests::suite::{CaseOutcome, CaseResult, SuiteReport};
+use crate::trace2tests::AssertionResult;
+
+/// Run `tests`, extracted from the file `label`, against `source` (the
+/// file with its imports resolved). Case paths are `<label>:<line>`.
+pub fn run_doctests(label: &str, tests: &[DocTest], source: &str) -> SuiteReport {
+    let started = Instant::now();
+    let cases = tests
+        .iter()
+        .map(|test| run_doctest(label, test, source))
+        .collect();
+    SuiteReport::new(cases, started.elapsed())
+}
+
+fn run_doctest(label: &str, test: &DocTest, source: &str) -> CaseResult {
+    let started = Instant::now();
+    let mut case = CaseResult {
+        path: format!("{label}:{}", test.line),
+        name: test.name(),
+        outcome: CaseOutcome::Error,
+        duration_ms: 0,
+        message: None,
+        assertions: Vec::new(),
+    };
+    match evaluate(test, source) {
+        Err(e) => case.message = Some(e),
+        Ok((actual, expected)) => {
+            let passed = actual == expected;
+            case.outcome = if passed {
+                CaseOutcome::Passed
+            } else {
+                CaseOutcome::Failed
+            };
+            case.assertions.push(AssertionResult {
+                kind: "doctest".to_string(),
+                passed,
+                expected: expected.to_string(),
+                actual: actual.to_string(),
+            });
+        }
+    }
+    case.duration_ms = started.elapsed().as_millis() as u64;
+    case
+}
+
+/// Both sides of `test`, each from its own run of the test module.
+fn evaluate(test: &DocTest, source: &str) -> Result<(Value, Value), String> {
+    let module = compile_test("doctest", source, test).map_err(|e| e.to_string())?;
+    let actual = run(module.clone()).map_err(|e| format!("`{}`: {e}", test.expr))?;
+    let mut expected_module = module;
+    expected_module.entry = function_index(&expected_module, EXPECTED_FN)
+        .ok_or_else(|| format!("{EXPECTED_FN} missing after compile"))?;
+    let expected = run(expected_module).map_err(|e| format!("`{}`: {e}", test.expected))?;
+    Ok((actual, expected))
+}
+
+fn run(module: boruna_bytecode::Module) -> Result<Value, String> {
+    let gateway = CapabilityGateway::new(Policy::default());
+    Vm::new(module, gateway).run().map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use boruna_compiler::doctest::extract;
+
+    const SOURCE: &str = r#"
+//! test: double(21) == 42
+//! test: greet("Ada") == "hi Ada"
+//! test: double(2) == 5
+//! test: missing(1) == 1
+fn double(n: Int) -> Int { n + n }
+
+fn greet(name: String) -> String { "hi " ++ name }
+
+fn main() -> Int { double(1) }
+"#;
+
+    #[test]
+    fn doctests_report_pass_fail_and_error_per_annotation() {
+        let tests = extract(SOURCE).unwrap();
+        assert_eq!(tests.len(), 4);
+        let report = run_doctests("lib.ax", &tests, SOURCE);
+        let outcomes: Vec<_> = report.cases.iter().map(|c| c.outcome).collect();
+        assert_eq!(
+            outcomes,
+            [
+                CaseOutcome::Passed,
+                CaseOutcome::Passed,
+                CaseOutcome::Failed,
+                CaseOutcome::Error
+            ]
+        );
+        let failed = &report.cases[2];
+        assert_eq!(failed.path, "lib.ax:4");
+        assert_eq!(failed.name, "double(2) == 5");
+        assert_eq!(
+            (
+                failed.assertions[0].expected.as_str(),
+                failed.assertions[0].actual.as_str()
+            ),
+            ("5", "4")
+        );
+        assert_eq!(
+            (report.total, report.passed, report.failed, report.errors),
+            (4, 2, 1, 1)
+        );
+    }
+
+    #[test]
+    fn std_library_doctests_pass() {
+        let libs = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../libs");
+        let mut total = 0;
+        for lib in crate::stdlib::LIBRARIES {
+            let source = crate::stdlib::load_library_source(&libs, lib).unwrap();
+            let tests = extract(&source).unwrap();
+            assert!(!tests.is_empty(), "{lib} has no doctests");
+            let report = run_doctests(lib, &tests, &source);
+            let failing: Vec<_> = report
+                .cases
+                .iter()
+                .filter(|c| c.outcome != CaseOutcome::Passed)
+                .collect();
+            assert!(failing.is_empty(), "{lib}: {failing:#?}");
+            total += report.total;
+        }
+        assert!(total > 0);
+    }
+}
@@ -64,16 +64,13 @@ impl std::error::Error for FormatError {}
 impl From<CompileError> for FormatError {
     fn from(err: CompileError) -> Self {
         match err {
-            CompileError::Lexer { line, col, msg } => FormatError::ParseFailed {
-                line,
-                col: Some(col),
-                message: msg,
-            },
-            CompileError::Parse { line, msg } => FormatError::ParseFailed {
-                line,
-                col: None,
-                message: msg,
-            },
+            CompileError::Lexer { span, msg } | CompileError::Parse { span, msg } => {
+                FormatError::ParseFailed {
+                    line: span.line,
+                    col: Some(span.col),
+                    message: msg,
+                }
+            }
             // typeck/codegen errors don't appear here (we don't run them),
             // but if they did we'd surface them as parse-stage failures.
             other => FormatError::ParseFailed {
@@ -271,6 +268,8 @@ fn token_kind_text(kind: &lexer::TokenKind) -> String {
         Ident(s) => s.clone(),
         IntLit(n) => n.to_string(),
         FloatLit(f) => format!("{f}"),
+        DecimalLit((_, text)) => format!("{text}d"),
+        BigIntLit(n) => format!("{n}n"),
         StringLit(s) => format!("\"{s}\""),
         // Everything else (operators, delimiters, newlines) is not useful as
         // an anchor because it appears too frequently or mid-line.
@@ -382,6 +381,14 @@ impl Printer {
             self.write("module ");
             self.write(name);
             self.newline();
+            if !p.items.is_empty() || !p.uses.is_empty() {
+                self.newline();
+            }
+        }
+        if !p.uses.is_empty() {
+            self.write("uses ");
+            self.write(&p.uses.join(", "));
+            self.newline();
             if !p.items.is_empty() {
                 self.newline();
             }
@@ -634,6 +641,8 @@ impl Printer {
                     self.write(&s);
                 }
             }
+            Expr::DecimalLit(_, text) => self.write(&format!("{text}d")),
+            Expr::BigIntLit(n) => self.write(&format!("{n}n")),
             Expr::StringLit(s) => {
                 self.write("\"");
                 self.write(&escape_string(s));
@@ -0,0 +1,376 @@
+//! In-process fuzzing of the compiler front-end (`lexer::lex`,
+//! `parser::parse`) for `boruna lang fuzz`.
+//!
+//! The corpus is seeded from the repo's templates, std libs, and examples;
+//! inputs are mutated with a seeded xorshift PRNG so a run is reproducible
+//! from `(corpus, seed, iterations)`. Any panic is caught, deduplicated by
+//! panic location, and reported as an `E010` [`DiagnosticSet`] alongside
+//! the crashing input. The coverage-guided cargo-fuzz targets live in the
+//! standalone `fuzz/` crate and reuse the same corpus.
+//!
+//! A stack overflow aborts the process and cannot be caught; inputs are
+//! capped at [`MAX_INPUT_LEN`] and the loop runs on a large-stack thread to
+//! keep deep-nesting inputs from reaching that point.
+
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Once;
+use std::time::{Duration, Instant};
+
+use boruna_hash::sha256_hex;
+use serde::Serialize;
+
+use crate::diagnostics::{Diagnostic, DiagnosticSet, RelatedInfo, E010_COMPILER_PANIC};
+
+/// Inputs longer than this are truncated after mutation.
+pub const MAX_INPUT_LEN: usize = 4096;
+
+/// Tokens spliced into inputs; chosen to exercise nesting and literals.
+const DICTIONARY: &[&str] = &[
+    "fn",
+    "type",
+    "enum",
+    "let",
+    "match",
+    "if",
+    "else",
+    "while",
+    "return",
+    "import",
+    "{",
+    "}",
+    "(",
+    ")",
+    "[",
+    "]",
+    "<",
+    ">",
+    "=>",
+    "->",
+    "..",
+    "::",
+    ":",
+    ",",
+    ";",
+    "|",
+    "\"",
+    "'",
+    "0x",
+    "-",
+    "9999999999999999999999",
+    "1.5e308",
+    "_",
+    "!",
+    "?",
+    "@",
+    "#",
+    "List<",
+    "Map<",
+    "Option<",
+];
+
+/// Fallback seed when no corpus files are found.
+const FALLBACK_SEED: &str = "type State { n: Int }\nfn main() -> Int { let x = 1\n x + 2 }\n";
+
+#[derive(Debug, Clone)]
+pub struct FuzzConfig {
+    pub duration: Duration,
+    /// Stop after this many inputs even if time remains.
+    pub max_iterations: Option<u64>,
+    pub seed: u64,
+}
+
+/// A deduplicated front-end panic.
+#[derive(Debug, Clone, Serialize)]
+pub struct FuzzCrash {
+    /// `"lex"` or `"parse"`.
+    pub target: &'static str,
+    pub message: String,
+    /// `file:line:col` of the panic inside the compiler.
+    pub location: String,
+    pub input: String,
+    pub input_sha256: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FuzzReport {
+    pub seed: u64,
+    pub iterations: u64,
+    pub corpus_size: usize,
+    pub crashes: Vec<FuzzCrash>,
+}
+
+/// The workspace `start` is in: the nearest ancestor whose `Cargo.toml`
+/// declares `[workspace]`, else `start` itself.
+pub fn workspace_root(start: &Path) -> PathBuf {
+    let start = start.canonicalize().unwrap_or_else(|_| start.to_path_buf());
+    start
+        .ancestors()
+        .find(|dir| {
+            fs::read_to_string(dir.join("Cargo.toml"))
+                .is_ok_and(|manifest| manifest.lines().any(|l| l.trim() == "[workspace]"))
+        })
+        .map_or_else(|| start.clone(), Path::to_path_buf)
+}
+
+/// Collect seed programs under `root`: `templates/*/*.ax.template`,
+/// `libs/*/src/*.ax`, and `examples/**/*.ax`, keyed by relative path.
+pub fn seed_corpus(root: &Path) -> BTreeMap<String, String> {
+    let mut corpus = BTreeMap::new();
+    for dir in ["templates", "libs", "examples"] {
+        collect(root, &root.join(dir), &mut corpus);
+    }
+    corpus
+}
+
+fn collect(root: &Path, dir: &Path, corpus: &mut BTreeMap<String, String>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect(root, &path, corpus);
+            continue;
+        }
+        let name = path.to_string_lossy();
+        if !(name.ends_with(".ax") || name.ends_with(".ax.template")) {
+            continue;
+        }
+        if let Ok(src) = fs::read_to_string(&path) {
+            let rel = path.strip_prefix(root).unwrap_or(&path);
+            corpus.insert(rel.to_string_lossy().replace('\\', "/"), src);
+        }
+    }
+}
+
+/// Write `corpus` as one file per seed (named by content hash) into `dir`,
+/// the layout cargo-fuzz expects under `fuzz/corpus/<target>/`.
+pub fn write_corpus(corpus: &BTreeMap<String, String>, dir: &Path) -> std::io::Result<usize> {
+    fs::create_dir_all(dir)?;
+    for src in corpus.values() {
+        fs::write(dir.join(sha256_hex(src)), src)?;
+    }
+    Ok(corpus.len())
+}
+
+thread_local! {
+    static CAPTURING: Cell<bool> = const { Cell::new(false) };
+    static LAST_PANIC: RefCell<Option<(String, String)>> = const { RefCell::new(None) };
+}
+
+/// Install (once) a panic hook that records the message and location while
+/// this thread is fuzzing and defers to the previous hook otherwise.
+fn install_hook() {
+    static HOOK: Once = Once::new();
+    HOOK.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            if CAPTURING.with(Cell::get) {
+                let message = info
+                    .payload()
+                    .downcast_ref::<&str>()
+                    .map(|s| s.to_string())
+                    .or_else(|| info.payload().downcast_ref::<String>().cloned())
+                    .unwrap_or_else(|| "<non-string panic payload>".into());
+                let location = info
+                    .location()
+                    .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()))
+                    .unwrap_or_default();
+                LAST_PANIC.with(|p| *p.borrow_mut() = Some((message, location)));
+            } else {
+                previous(info);
+            }
+        }));
+    });
+}
+
+/// Run `source` through the lexer and (if it lexes) the parser, returning
+/// the crash if either panics. Errors are the expected outcome for garbage
+/// input and are not crashes.
+pub fn check_input(source: &str) -> Option<FuzzCrash> {
+    install_hook();
+    CAPTURING.with(|c| c.set(true));
+    let lexed = std::panic::catch_unwind(|| boruna_compiler::lexer::lex(source));
+    let result = match lexed {
+        Err(_) => Some("lex"),
+        Ok(Err(_)) => None,
+        Ok(Ok(tokens)) => std::panic::catch_unwind(move || boruna_compiler::parser::parse(tokens))
+            .is_err()
+            .then_some("parse"),
+    };
+    CAPTURING.with(|c| c.set(false));
+    let target = result?;
+    let (message, location) = LAST_PANIC
+        .with(|p| p.borrow_mut().take())
+        .unwrap_or_default();
+    Some(FuzzCrash {
+        target,
+        message,
+        location,
+        input: source.to_string(),
+        input_sha256: sha256_hex(source),
+    })
+}
+
+/// Mutate and check inputs until the time or iteration budget runs out.
+pub fn run(corpus: &BTreeMap<String, String>, config: &FuzzConfig) -> FuzzReport {
+    let seeds: Vec<&str> = if corpus.is_empty() {
+        vec![FALLBACK_SEED]
+    } else {
+        corpus.values().map(String::as_str).collect()
+    };
+    let config = config.clone();
+    let seeds: Vec<String> = seeds.into_iter().map(str::to_string).collect();
+    let corpus_size = seeds.len();
+    std::thread::Builder::new()
+        .name("boruna-fuzz".into())
+        .stack_size(256 * 1024 * 1024)
+        .spawn(move || fuzz_loop(&seeds, &config, corpus_size))
+        .expect("spawn fuzz thread")
+        .join()
+        .expect("fuzz thread panicked outside catch_unwind")
+}
+
+fn fuzz_loop(seeds: &[String], config: &FuzzConfig, corpus_size: usize) -> FuzzReport {
+    let mut rng = XorShift::new(config.seed);
+    let start = Instant::now();
+    let mut crashes: BTreeMap<(String, &'static str), FuzzCrash> = BTreeMap::new();
+    let mut iterations = 0u64;
+
+    // Seeds themselves go first: a panic on a shipped file is the most
+    // important crash to report.
+    let mut pending: Vec<String> = seeds.to_vec();
+    pending.reverse();
+
+    loop {
+        if config.max_iterations.is_some_and(|n| iterations >= n) {
+            break;
+        }
+        if config.max_iterations.is_none() && start.elapsed() >= config.duration {
+            break;
+        }
+        let input = pending.pop().unwrap_or_else(|| mutate(&mut rng, seeds));
+        iterations += 1;
+        if let Some(crash) = check_input(&input) {
+            let key = (crash.location.clone(), crash.target);
+            match crashes.get(&key) {
+                // Keep the smallest reproducer per location.
+                Some(existing) if existing.input.len() <= crash.input.len() => {}
+                _ => {
+                    crashes.insert(key, crash);
+                }
+            }
+        }
+    }
+
+    FuzzReport {
+        seed: config.seed,
+        iterations,
+        corpus_size,
+        crashes: crashes.into_values().collect(),
+    }
+}
+
+fn mutate(rng: &mut XorShift, seeds: &[String]) -> String {
+    let mut bytes = seeds[rng.below(seeds.len())].as_bytes().to_vec();
+    for _ in 0..1 + rng.below(4) {
+        let len = bytes.len();
+        let at = if len == 0 { 0 } else { rng.below(len + 1) };
+        match rng.below(6) {
+            0 if len > 0 => {
+                let i = rng.below(len);
+                bytes[i] ^= 1 << rng.below(8);
+            }
+            1 => {
+                let tok = DICTIONARY[rng.below(DICTIONARY.len())];
+                bytes.splice(at..at, tok.bytes());
+            }
+            2 if len > 0 => {
+                let end = (at + 1 + rng.below(16)).min(len);
+                bytes.drain(at.min(len)..end.max(at.min(len)));
+            }
+            3 if len > 0 => {
+                let start = rng.below(len);
+                let end = (start + 1 + rng.below(64)).min(len);
+                let chunk = bytes[start..end].to_vec();
+                bytes.splice(at..at, chunk);
+            }
+            4 => {
+                let other = seeds[rng.below(seeds.len())].as_bytes();
+                let cut = if other.is_empty() {
+                    0
+                } else {
+                    rng.below(other.len())
+                };
+                bytes.truncate(at);
+                bytes.extend_from_slice(&other[cut..]);
+            }
+            _ => bytes.truncate(at),
+        }
+    }
+    bytes.truncate(MAX_INPUT_LEN);
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+impl FuzzCrash {
+    /// The crash as a one-diagnostic set, named after the input hash.
+    pub fn to_diagnostic_set(&self) -> DiagnosticSet {
+        let file = format!("crash-{}.ax", &self.input_sha256[..12]);
+        let mut set = DiagnosticSet::new(&file);
+        set.push(
+            Diagnostic::error(
+                E010_COMPILER_PANIC,
+                format!("{} panicked: {}", self.target, self.message),
+            )
+            .at(&file, 1, None)
+            .with_related(RelatedInfo {
+                message: format!("panic location: {}", self.location),
+                location: None,
+            }),
+        );
+        set
+    }
+}
+
+/// Write `crash-<hash>.ax` (the input) and `crash-<hash>.json` (its
+/// DiagnosticSet) per crash into `dir`; returns the JSON paths. `dir` is
+/// created only when there is a crash to write.
+pub fn write_artifacts(report: &FuzzReport, dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    if !report.crashes.is_empty() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut written = Vec::new();
+    for crash in &report.crashes {
+        let stem = format!("crash-{}", &crash.input_sha256[..12]);
+        fs::write(dir.join(format!("{stem}.ax")), &crash.input)?;
+        let json = dir.join(format!("{stem}.json"));
+        fs::write(&json, crash.to_diagnostic_set().to_json())?;
+        written.push(json);
+    }
+    Ok(written)
+}
+
+/// xorshift64* — tiny, deterministic, and good enough for mutation choice.
+struct XorShift(u64);
+
+impl XorShift {
+    fn new(seed: u64) -> Self {
+        XorShift(seed ^ 0x9E37_79B9_7F4A_7C15 | 1)
+    }
+
+    fn next(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.0 = x;
+        x.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n.max(1) as u64) as usize
+    }
+}
@@ -0,0 +1,527 @@
+//! The edit journal behind `boruna lang undo` and `boruna lang redo`.
+//!
+//! `lang repair`, `template apply` and `template upgrade` write over files
+//! in place. Each such write is recorded in the workspace's
+//! [`DEFAULT_HISTORY_DIR`] as a [`PatchBundle`]: one [`FilePatch`] per
+//! changed file, whose hunk replaces the changed lines and keeps enough of
+//! the old text to reverse it. [`JOURNAL_FILE_NAME`] lists the entries in
+//! order and how many of them are applied; undo reverts the last applied
+//! entry, redo re-applies the first undone one, and recording a new edit
+//! drops whatever was undone.
+//!
+//! Undo and redo check that the text each hunk replaces is still in
+//! place, and change nothing when an edit since has touched it.
+//! Hunk texts keep their line endings, so a reversal restores files
+//! byte for byte.
+
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use boruna_orchestrator::patch::{
+    ExpectedChecks, FilePatch, Hunk, PatchBundle, PatchMetadata, RiskLevel,
+};
+use serde::{Deserialize, Serialize};
+
+/// Journal directory, relative to the workspace root.
+pub const DEFAULT_HISTORY_DIR: &str = ".boruna/history";
+/// The entry list inside the journal directory.
+pub const JOURNAL_FILE_NAME: &str = "journal.json";
+
+/// One file write to record: its contents before (`None` when the write
+/// created it) and after.
+#[derive(Debug, Clone)]
+pub struct FileEdit {
+    pub path: PathBuf,
+    pub before: Option<String>,
+    pub after: String,
+}
+
+/// A recorded edit. Its bundle is `<id>.json` in the journal directory.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub id: String,
+    pub intent: String,
+    pub timestamp: String,
+    /// Changed files, relative to the workspace root.
+    pub files: Vec<String>,
+    /// Files the edit created; undo deletes them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub created: Vec<String>,
+}
+
+/// The contents of [`JOURNAL_FILE_NAME`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Journal {
+    pub version: u32,
+    /// Sequence number of the next entry's id.
+    pub next: u64,
+    pub entries: Vec<JournalEntry>,
+    /// How many of `entries`, from the start, are applied. The rest were
+    /// undone and can be redone.
+    pub applied: usize,
+}
+
+/// The journal of the workspace rooted at `root`.
+#[derive(Debug, Clone)]
+pub struct EditJournal {
+    root: PathBuf,
+    dir: PathBuf,
+}
+
+impl EditJournal {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        let root = root.into();
+        let dir = root.join(DEFAULT_HISTORY_DIR);
+        EditJournal { root, dir }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The journal; empty when nothing was recorded yet.
+    pub fn load(&self) -> Result<Journal, String> {
+        let path = self.dir.join(JOURNAL_FILE_NAME);
+        match fs::read_to_string(&path) {
+            Ok(json) => {
+                serde_json::from_str(&json).map_err(|e| format!("parse {}: {e}", path.display()))
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Journal {
+                version: 1,
+                next: 1,
+                ..Journal::default()
+            }),
+            Err(e) => Err(format!("read {}: {e}", path.display())),
+        }
+    }
+
+    /// The bundle recorded for entry `id`.
+    pub fn bundle(&self, id: &str) -> Result<PatchBundle, String> {
+        PatchBundle::load(&self.dir.join(format!("{id}.json")))
+    }
+
+    /// Record `edits`, already written, as one entry. Edits that left a
+    /// file unchanged are dropped; returns `None` when none remain.
+    /// Entries undone before this one are discarded.
+    pub fn record(
+        &self,
+        intent: &str,
+        author: &str,
+        edits: &[FileEdit],
+    ) -> Result<Option<JournalEntry>, String> {
+        let mut patches = Vec::new();
+        let mut created = Vec::new();
+        for edit in edits {
+            let before = edit.before.as_deref().unwrap_or_default();
+            let file = self.relative(&edit.path)?;
+            let Some(patch) = file_patch(&file, before, &edit.after) else {
+                continue;
+            };
+            if edit.before.is_none() {
+                created.push(file);
+            }
+            patches.push(patch);
+        }
+        if patches.is_empty() {
+            return Ok(None);
+        }
+
+        let mut journal = self.load()?;
+        for undone in journal.entries.drain(journal.applied..) {
+            // A stale bundle is harmless; it is overwritten when its id is
+            // reused.
+            let _ = fs::remove_file(self.dir.join(format!("{}.json", undone.id)));
+        }
+        let entry = JournalEntry {
+            id: format!("edit-{:04}", journal.next),
+            intent: intent.to_string(),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            files: patches.iter().map(|p| p.file.clone()).collect(),
+            created,
+        };
+        let bundle = PatchBundle {
+            version: 1,
+            metadata: PatchMetadata {
+                id: entry.id.clone(),
+                intent: entry.intent.clone(),
+                author: author.to_string(),
+                timestamp: entry.timestamp.clone(),
+                touched_modules: entry.files.clone(),
+                risk_level: RiskLevel::Low,
+            },
+            patches,
+            expected_checks: ExpectedChecks {
+                compile: true,
+                test: false,
+                replay: false,
+                diagnostics_count: None,
+                trace_stability: None,
+                difftest_corpus: vec![],
+                deterministic_build: false,
+            },
+            reviewer_checklist: vec![],
+        };
+        bundle.validate().map_err(|errors| errors.join("; "))?;
+
+        fs::create_dir_all(&self.dir).map_err(|e| format!("create {}: {e}", self.dir.display()))?;
+        bundle.save(&self.dir.join(format!("{}.json", entry.id)))?;
+        journal.next += 1;
+        journal.entries.push(entry.clone());
+        journal.applied = journal.entries.len();
+        self.save(&journal)?;
+        Ok(Some(entry))
+    }
+
+    /// Revert the last applied entry; `None` when there is none.
+    pub fn undo(&self) -> Result<Option<JournalEntry>, String> {
+        let mut journal = self.load()?;
+        let Some(entry) = journal
+            .applied
+            .checked_sub(1)
+            .map(|i| journal.entries[i].clone())
+        else {
+            return Ok(None);
+        };
+        let bundle = invert(&self.bundle(&entry.id)?);
+        self.apply(&bundle, &entry, true)?;
+        journal.applied -= 1;
+        self.save(&journal)?;
+        Ok(Some(entry))
+    }
+
+    /// Re-apply the first undone entry; `None` when there is none.
+    pub fn redo(&self) -> Result<Option<JournalEntry>, String> {
+        let mut journal = self.load()?;
+        let Some(entry) = journal.entries.get(journal.applied).cloned() else {
+            return Ok(None);
+        };
+        let bundle = self.bundle(&entry.id)?;
+        self.apply(&bundle, &entry, false)?;
+        journal.applied += 1;
+        self.save(&journal)?;
+        Ok(Some(entry))
+    }
+
+    /// Apply `bundle`, the entry's bundle or its inverse, checking every
+    /// file before writing any. Files `entry` created are absent before it:
+    /// read as empty on redo, deleted on undo.
+    fn apply(&self, bundle: &PatchBundle, entry: &JournalEntry, undo: bool) -> Result<(), String> {
+        let mut writes = Vec::new();
+        for patch in &bundle.patches {
+            let path = self.root.join(&patch.file);
+            let created = entry.created.contains(&patch.file);
+            let current = match fs::read_to_string(&path) {
+                Ok(_) if created && !undo => {
+                    return Err(format!(
+                        "{} exists again; not redoing {}",
+                        patch.file, entry.id
+                    ))
+                }
+                Ok(text) => text,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound && created && !undo => {
+                    String::new()
+                }
+                Err(e) => return Err(format!("read {}: {e}", path.display())),
+            };
+            let text = apply_hunks(&current, &patch.hunks).map_err(|e| {
+                let verb = if undo { "undoing" } else { "redoing" };
+                format!(
+                    "{} changed since {}; not {verb} it ({e})",
+                    patch.file, entry.id
+                )
+            })?;
+            writes.push((path, (!(created && undo)).then_some(text)));
+        }
+        for (path, text) in writes {
+            match text {
+                Some(text) => fs::write(&path, text),
+                None => fs::remove_file(&path),
+            }
+            .map_err(|e| format!("write {}: {e}", path.display()))?;
+        }
+        Ok(())
+    }
+
+    fn save(&self, journal: &Journal) -> Result<(), String> {
+        let path = self.dir.join(JOURNAL_FILE_NAME);
+        let json = serde_json::to_string_pretty(journal).map_err(|e| e.to_string())?;
+        fs::write(&path, json).map_err(|e| format!("write {}: {e}", path.display()))
+    }
+
+    /// `path` relative to the workspace root. Relative paths are taken as
+    /// already relative to it.
+    fn relative(&self, path: &Path) -> Result<String, String> {
+        let relative = if path.is_absolute() {
+            let root = self
+                .root
+                .canonicalize()
+                .map_err(|e| format!("resolve {}: {e}", self.root.display()))?;
+            let parent = path.parent().unwrap_or(path);
+            let parent = parent
+                .canonicalize()
+                .map_err(|e| format!("resolve {}: {e}", parent.display()))?;
+            let inside = parent
+                .strip_prefix(&root)
+                .map_err(|_| format!("{} is outside the workspace", path.display()))?;
+            inside.join(path.file_name().unwrap_or_default())
+        } else {
+            path.components()
+                .filter(|c| *c != Component::CurDir)
+                .collect()
+        };
+        Ok(relative.to_string_lossy().replace('\\', "/"))
+    }
+}
+
+/// A patch turning `before` into `after`: one hunk spanning the first to
+/// the last changed line. `None` when they are equal.
+pub fn file_patch(file: &str, before: &str, after: &str) -> Option<FilePatch> {
+    if before == after {
+        return None;
+    }
+    let old: Vec<&str> = before.split_inclusive('\n').collect();
+    let new: Vec<&str> = after.split_inclusive('\n').collect();
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    Some(FilePatch {
+        file: file.to_string(),
+        hunks: vec![Hunk {
+            start_line: prefix + 1,
+            old_text: old[prefix..old.len() - suffix].concat(),
+            new_text: new[prefix..new.len() - suffix].concat(),
+        }],
+    })
+}
+
+/// Apply `hunks` to `text`. Each hunk's `start_line` counts lines of the
+/// original `text`, and its `old_text` must be there verbatim.
+pub fn apply_hunks(text: &str, hunks: &[Hunk]) -> Result<String, String> {
+    let mut lines: Vec<String> = text.split_inclusive('\n').map(str::to_string).collect();
+    let mut sorted: Vec<&Hunk> = hunks.iter().collect();
+    sorted.sort_by_key(|h| std::cmp::Reverse(h.start_line));
+    for hunk in sorted {
+        let start = hunk.start_line.saturating_sub(1);
+        let count = hunk.old_text.split_inclusive('\n').count();
+        if start + count > lines.len() || lines[start..start + count].concat() != hunk.old_text {
+            return Err(format!("hunk at line {} does not match", hunk.start_line));
+        }
+        let replacement = hunk.new_text.split_inclusive('\n').map(str::to_string);
+        lines.splice(start..start + count, replacement);
+    }
+    Ok(lines.concat())
+}
+
+/// The bundle undoing `bundle`: old and new text swapped, with each hunk's
+/// `start_line` moved to where the hunk sits once `bundle` is applied.
+pub fn invert(bundle: &PatchBundle) -> PatchBundle {
+    let patches = bundle
+        .patches
+        .iter()
+        .map(|patch| {
+            let mut hunks = patch.hunks.clone();
+            hunks.sort_by_key(|h| h.start_line);
+            let mut shift = 0isize;
+            let hunks = hunks
+                .into_iter()
+                .map(|h| {
+                    let old = h.old_text.split_inclusive('\n').count() as isize;
+                    let new = h.new_text.split_inclusive('\n').count() as isize;
+                    let start_line = (h.start_line as isize + shift) as usize;
+                    shift += new - old;
+                    Hunk {
+                        start_line,
+                        old_text: h.new_text,
+                        new_text: h.old_text,
+                    }
+                })
+                .collect();
+            FilePatch {
+                file: patch.file.clone(),
+                hunks,
+            }
+        })
+        .collect();
+    PatchBundle {
+        metadata: PatchMetadata {
+            id: format!("{}-undo", bundle.metadata.id),
+            intent: format!("Undo: {}", bundle.metadata.intent),
+            ..bundle.metadata.clone()
+        },
+        patches,
+        ..bundle.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edit(path: &Path, before: Option<&str>, after: &str) -> FileEdit {
+        fs::write(path, after).unwrap();
+        FileEdit {
+            path: path.to_path_buf(),
+            before: before.map(str::to_string),
+            after: after.to_string(),
+        }
+    }
+
+    #[test]
+    fn file_patch_spans_the_changed_lines() {
+        let patch = file_patch("a.ax", "a\nb\nc\nd\n", "a\nB\nc\nD\n").unwrap();
+        assert_eq!(patch.hunks.len(), 1);
+        let hunk = &patch.hunks[0];
+        assert_eq!(
+            (
+                hunk.start_line,
+                hunk.old_text.as_str(),
+                hunk.new_text.as_str()
+            ),
+            (2, "b\nc\nd\n", "B\nc\nD\n")
+        );
+        assert!(file_patch("a.ax", "x", "x").is_none());
+    }
+
+    #[test]
+    fn inverse_restores_the_original_bytes() {
+        let before = "fn a() {}\nfn b() {}\nfn c() {}";
+        let after = "fn a() {}\n\nfn c() { 1 }";
+        let patch = file_patch("a.ax", before, after).unwrap();
+        assert_eq!(apply_hunks(before, &patch.hunks).unwrap(), after);
+
+        let hunks = vec![
+            Hunk {
+                start_line: 1,
+                old_text: "a\n".into(),
+                new_text: "a\na2\n".into(),
+            },
+            Hunk {
+                start_line: 3,
+                old_text: "c\n".into(),
+                new_text: "C\n".into(),
+            },
+        ];
+        let bundle = PatchBundle {
+            patches: vec![FilePatch {
+                file: "x".into(),
+                hunks,
+            }],
+            ..empty_bundle()
+        };
+        let forward = apply_hunks("a\nb\nc\n", &bundle.patches[0].hunks).unwrap();
+        assert_eq!(forward, "a\na2\nb\nC\n");
+        let back = apply_hunks(&forward, &invert(&bundle).patches[0].hunks).unwrap();
+        assert_eq!(back, "a\nb\nc\n");
+    }
+
+    fn empty_bundle() -> PatchBundle {
+        PatchBundle {
+            version: 1,
+            metadata: PatchMetadata {
+                id: "PB-1".into(),
+                intent: "test".into(),
+                author: "test".into(),
+                timestamp: "2026-01-01T00:00:00Z".into(),
+                touched_modules: vec![],
+                risk_level: RiskLevel::Low,
+            },
+            patches: vec![],
+            expected_checks: ExpectedChecks {
+                compile: true,
+                test: false,
+                replay: false,
+                diagnostics_count: None,
+                trace_stability: None,
+                difftest_corpus: vec![],
+                deterministic_build: false,
+            },
+            reviewer_checklist: vec![],
+        }
+    }
+
+    #[test]
+    fn undo_and_redo_walk_the_journal() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = EditJournal::new(dir.path());
+        let app = dir.path().join("app.ax");
+        let spec = dir.path().join("app.spec.json");
+
+        let v1 = "fn main() -> Int { 1 }\n";
+        let v2 = "fn main() -> Int { 2 }\n";
+        fs::write(&app, v1).unwrap();
+        let first = journal
+            .record("repair app.ax", "lang repair", &[edit(&app, Some(v1), v2)])
+            .unwrap()
+            .unwrap();
+        assert_eq!(first.id, "edit-0001");
+        assert_eq!(first.files, ["app.ax"]);
+        let second = journal
+            .record(
+                "template apply",
+                "template apply",
+                &[edit(&app, Some(v2), "x\n"), edit(&spec, None, "{}\n")],
+            )
+            .unwrap()
+            .unwrap();
+        assert_eq!(second.created, ["app.spec.json"]);
+        assert!(journal.bundle("edit-0002").unwrap().validate().is_ok());
+
+        assert_eq!(journal.undo().unwrap().unwrap().id, "edit-0002");
+        assert_eq!(fs::read_to_string(&app).unwrap(), v2);
+        assert!(!spec.exists());
+        assert_eq!(journal.undo().unwrap().unwrap().id, "edit-0001");
+        assert_eq!(fs::read_to_string(&app).unwrap(), v1);
+        assert!(journal.undo().unwrap().is_none());
+
+        assert_eq!(journal.redo().unwrap().unwrap().id, "edit-0001");
+        assert_eq!(fs::read_to_string(&app).unwrap(), v2);
+
+        // A new edit discards what was undone.
+        let third = journal
+            .record("repair app.ax", "lang repair", &[edit(&app, Some(v2), v1)])
+            .unwrap()
+            .unwrap();
+        assert_eq!(third.id, "edit-0003");
+        assert!(journal.redo().unwrap().is_none());
+        let ids: Vec<_> = journal
+            .load()
+            .unwrap()
+            .entries
+            .into_iter()
+            .map(|e| e.id)
+            .collect();
+        assert_eq!(ids, ["edit-0001", "edit-0003"]);
+    }
+
+    #[test]
+    fn undo_refuses_a_file_changed_since() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = EditJournal::new(dir.path());
+        let app = dir.path().join("app.ax");
+        journal
+            .record("repair", "lang repair", &[edit(&app, Some("a\n"), "b\n")])
+            .unwrap();
+        fs::write(&app, "c\n").unwrap();
+
+        let err = journal.undo().unwrap_err();
+        assert!(err.contains("app.ax changed since edit-0001"), "{err}");
+        assert_eq!(fs::read_to_string(&app).unwrap(), "c\n");
+        assert_eq!(journal.load().unwrap().applied, 1);
+    }
+
+    #[test]
+    fn unchanged_files_are_not_recorded() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = EditJournal::new(dir.path());
+        let app = dir.path().join("app.ax");
+        let recorded = journal
+            .record("repair", "lang repair", &[edit(&app, Some("a\n"), "a\n")])
+            .unwrap();
+        assert!(recorded.is_none());
+        assert!(!journal.dir().exists());
+    }
+}
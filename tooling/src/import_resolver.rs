@@ -74,7 +74,7 @@ pub fn resolve_imports(source: &str, libs_dir: &Path) -> Result<String, ImportEr
 
 /// Parse a line of the form `import "name"` or `import name`.
 /// Returns the library name, or `None` if it is not an import statement.
-fn parse_import_line(line: &str) -> Option<String> {
+pub(crate) fn parse_import_line(line: &str) -> Option<String> {
     let rest = line.strip_prefix("import")?;
     let rest = rest.trim();
     if rest.is_empty() {
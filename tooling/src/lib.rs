@@ -1,5 +1,10 @@
+pub mod capmatrix;
 pub mod diagnostics;
+pub mod difftest;
+pub mod doctest;
 pub mod format;
+pub mod fuzz;
+pub mod history;
 pub mod import_resolver;
 pub mod literate;
 pub mod migrations;
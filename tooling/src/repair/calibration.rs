@@ -0,0 +1,319 @@
+//! Opt-in repair telemetry and the patch-confidence calibration built from
+//! it.
+//!
+//! Suggested patches ship with hard-coded [`Confidence`] levels. When a
+//! [`TelemetryStore`] is enabled, every repair appends one event per applied
+//! patch (did its diagnostic go away on re-check?) and `lang repair
+//! --feedback accept|reject` appends whether the change was kept. The store
+//! folds the events into `calibration.json`, which
+//! [`suggest::rank_patches`](crate::diagnostics::suggest::rank_patches)
+//! consults to re-rank future patches of the same kind.
+//!
+//! Nothing leaves the machine: the store is a local directory named by
+//! `--telemetry` or [`TELEMETRY_ENV`].
+
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::AppliedPatch;
+use crate::diagnostics::Confidence;
+
+/// Environment variable naming the telemetry directory.
+pub const TELEMETRY_ENV: &str = "BORUNA_REPAIR_TELEMETRY";
+/// Aggregated calibration, rebuilt from the event log on every write.
+pub const CALIBRATION_FILE_NAME: &str = "calibration.json";
+/// Append-only event log, one JSON object per line.
+pub const EVENTS_FILE_NAME: &str = "events.jsonl";
+
+/// Observations needed before a kind's confidence is overridden.
+pub const MIN_SAMPLES: u64 = 5;
+
+/// The kind of a patch: its diagnostic code and verb, without the
+/// identifier it targets (`E003-rename-cout` → `E003-rename`).
+pub fn patch_kind(patch_id: &str) -> &str {
+    match patch_id.match_indices('-').nth(1) {
+        Some((i, _)) => &patch_id[..i],
+        None => patch_id,
+    }
+}
+
+/// One telemetry record.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum TelemetryEvent {
+    /// A patch was applied to `file` by `lang repair`.
+    Applied {
+        file: String,
+        patch_id: String,
+        kind: String,
+        /// The diagnostic the patch targeted was gone on re-check.
+        resolved: bool,
+    },
+    /// The user kept (`accepted`) or reverted the patches of the latest
+    /// repair of `file`.
+    Feedback { file: String, accepted: bool },
+}
+
+/// Outcome counts for one patch kind.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KindStats {
+    pub applied: u64,
+    pub resolved: u64,
+    pub accepted: u64,
+    pub rejected: u64,
+    /// Applications that resolved their diagnostic and were not rejected.
+    pub succeeded: u64,
+}
+
+impl KindStats {
+    /// Fraction of applications that succeeded, once there are at least
+    /// [`MIN_SAMPLES`] of them.
+    pub fn success_rate(&self) -> Option<f64> {
+        (self.applied >= MIN_SAMPLES).then(|| self.succeeded as f64 / self.applied as f64)
+    }
+
+    /// Confidence implied by the success rate: High from 80%, Medium from
+    /// 50%, Low below.
+    pub fn confidence(&self) -> Option<Confidence> {
+        self.success_rate().map(|rate| {
+            if rate >= 0.8 {
+                Confidence::High
+            } else if rate >= 0.5 {
+                Confidence::Medium
+            } else {
+                Confidence::Low
+            }
+        })
+    }
+}
+
+/// Per-kind outcome statistics (the contents of `calibration.json`).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Calibration {
+    pub version: u32,
+    pub kinds: BTreeMap<String, KindStats>,
+}
+
+impl Calibration {
+    /// Aggregate an event log. Feedback covers the `Applied` events for
+    /// its file since that file's previous feedback.
+    pub fn from_events(events: &[TelemetryEvent]) -> Self {
+        let mut kinds: BTreeMap<String, KindStats> = BTreeMap::new();
+        // Per file: (kind, resolved) awaiting feedback.
+        let mut pending: BTreeMap<&str, Vec<(&str, bool)>> = BTreeMap::new();
+        let mut settle = |kind: &str, resolved: bool, verdict: Option<bool>| {
+            let stats = kinds.entry(kind.to_string()).or_default();
+            stats.applied += 1;
+            stats.resolved += u64::from(resolved);
+            match verdict {
+                Some(true) => stats.accepted += 1,
+                Some(false) => stats.rejected += 1,
+                None => {}
+            }
+            stats.succeeded += u64::from(resolved && verdict != Some(false));
+        };
+        for event in events {
+            match event {
+                TelemetryEvent::Applied {
+                    file,
+                    kind,
+                    resolved,
+                    ..
+                } => pending.entry(file).or_default().push((kind, *resolved)),
+                TelemetryEvent::Feedback { file, accepted } => {
+                    for (kind, resolved) in pending.remove(file.as_str()).unwrap_or_default() {
+                        settle(kind, resolved, Some(*accepted));
+                    }
+                }
+            }
+        }
+        for (kind, resolved) in pending.into_values().flatten() {
+            settle(kind, resolved, None);
+        }
+        Calibration { version: 1, kinds }
+    }
+
+    /// Statistics for the kind of `patch_id`, if any were recorded.
+    pub fn stats(&self, patch_id: &str) -> Option<&KindStats> {
+        self.kinds.get(patch_kind(patch_id))
+    }
+}
+
+/// A local telemetry directory holding [`EVENTS_FILE_NAME`] and
+/// [`CALIBRATION_FILE_NAME`].
+#[derive(Debug, Clone)]
+pub struct TelemetryStore {
+    dir: PathBuf,
+}
+
+impl TelemetryStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        TelemetryStore { dir: dir.into() }
+    }
+
+    /// The store named by [`TELEMETRY_ENV`], if set and non-empty.
+    pub fn from_env() -> Option<Self> {
+        std::env::var(TELEMETRY_ENV)
+            .ok()
+            .filter(|s| !s.is_empty())
+            .map(Self::new)
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Record the patches one repair applied to `file`.
+    pub fn record_repair(&self, file: &str, applied: &[AppliedPatch]) -> Result<(), String> {
+        let events: Vec<_> = applied
+            .iter()
+            .map(|a| TelemetryEvent::Applied {
+                file: file.to_string(),
+                patch_id: a.patch_id.clone(),
+                kind: patch_kind(&a.patch_id).to_string(),
+                resolved: a.resolved,
+            })
+            .collect();
+        self.append(&events)
+    }
+
+    /// Record whether the latest repair of `file` was kept. Returns how
+    /// many patches the verdict covers; errors when no repair of `file` is
+    /// awaiting feedback.
+    pub fn record_feedback(&self, file: &str, accepted: bool) -> Result<usize, String> {
+        let events = self.events()?;
+        let since_feedback = events
+            .iter()
+            .rev()
+            .take_while(|e| !matches!(e, TelemetryEvent::Feedback { file: f, .. } if f == file))
+            .filter(|e| matches!(e, TelemetryEvent::Applied { file: f, .. } if f == file))
+            .count();
+        if since_feedback == 0 {
+            return Err(format!("no repair of {file} is awaiting feedback"));
+        }
+        self.append(&[TelemetryEvent::Feedback {
+            file: file.to_string(),
+            accepted,
+        }])?;
+        Ok(since_feedback)
+    }
+
+    /// Every recorded event, oldest first.
+    pub fn events(&self) -> Result<Vec<TelemetryEvent>, String> {
+        let path = self.dir.join(EVENTS_FILE_NAME);
+        let text = match std::fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(format!("read {}: {e}", path.display())),
+        };
+        text.lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(|l| serde_json::from_str(l).map_err(|e| format!("parse {}: {e}", path.display())))
+            .collect()
+    }
+
+    /// The aggregated calibration; empty when nothing was recorded yet.
+    pub fn calibration(&self) -> Result<Calibration, String> {
+        let path = self.dir.join(CALIBRATION_FILE_NAME);
+        match std::fs::read_to_string(&path) {
+            Ok(json) => {
+                serde_json::from_str(&json).map_err(|e| format!("parse {}: {e}", path.display()))
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Calibration::default()),
+            Err(e) => Err(format!("read {}: {e}", path.display())),
+        }
+    }
+
+    /// Append `events` to the log and rebuild the calibration file.
+    fn append(&self, events: &[TelemetryEvent]) -> Result<(), String> {
+        if events.is_empty() {
+            return Ok(());
+        }
+        std::fs::create_dir_all(&self.dir)
+            .map_err(|e| format!("create {}: {e}", self.dir.display()))?;
+        let path = self.dir.join(EVENTS_FILE_NAME);
+        let mut log = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| format!("open {}: {e}", path.display()))?;
+        for event in events {
+            let line = serde_json::to_string(event).map_err(|e| e.to_string())?;
+            writeln!(log, "{line}").map_err(|e| format!("write {}: {e}", path.display()))?;
+        }
+
+        let calibration = Calibration::from_events(&self.events()?);
+        let path = self.dir.join(CALIBRATION_FILE_NAME);
+        let json = serde_json::to_string_pretty(&calibration).map_err(|e| e.to_string())?;
+        std::fs::write(&path, json).map_err(|e| format!("write {}: {e}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn applied(patch_id: &str, resolved: bool) -> AppliedPatch {
+        AppliedPatch {
+            diagnostic_id: patch_id[..4].to_string(),
+            patch_id: patch_id.to_string(),
+            description: String::new(),
+            resolved,
+        }
+    }
+
+    #[test]
+    fn patch_kind_drops_the_target() {
+        assert_eq!(patch_kind("E003-rename-cout"), "E003-rename");
+        assert_eq!(patch_kind("W003-remove-old-helper"), "W003-remove");
+        assert_eq!(patch_kind("E005-add-arms"), "E005-add");
+        assert_eq!(patch_kind("E009"), "E009");
+    }
+
+    #[test]
+    fn feedback_settles_only_the_latest_repair_of_that_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = TelemetryStore::new(dir.path());
+        store
+            .record_repair("a.ax", &[applied("W001-prefix-x", true)])
+            .unwrap();
+        store
+            .record_repair("b.ax", &[applied("W001-prefix-y", true)])
+            .unwrap();
+        store
+            .record_repair("a.ax", &[applied("W001-prefix-z", false)])
+            .unwrap();
+        assert_eq!(store.record_feedback("a.ax", false).unwrap(), 2);
+        assert!(store.record_feedback("a.ax", true).is_err());
+
+        let stats = &store.calibration().unwrap().kinds["W001-prefix"];
+        assert_eq!(
+            *stats,
+            KindStats {
+                applied: 3,
+                resolved: 2,
+                accepted: 0,
+                rejected: 2,
+                succeeded: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn confidence_needs_enough_samples() {
+        let mut stats = KindStats {
+            applied: MIN_SAMPLES - 1,
+            succeeded: MIN_SAMPLES - 1,
+            ..KindStats::default()
+        };
+        assert_eq!(stats.confidence(), None);
+        stats.applied = 10;
+        stats.succeeded = 8;
+        assert_eq!(stats.confidence(), Some(Confidence::High));
+        stats.succeeded = 4;
+        assert_eq!(stats.confidence(), Some(Confidence::Low));
+    }
+}
@@ -1,7 +1,10 @@
+pub mod calibration;
+
+use std::collections::{BTreeMap, HashSet};
 use std::path::Path;
 
 use crate::diagnostics::collector::DiagnosticCollector;
-use crate::diagnostics::{Confidence, DiagnosticSet, SuggestedPatch, TextEdit};
+use crate::diagnostics::{Confidence, Diagnostic, DiagnosticSet, SuggestedPatch, TextEdit};
 
 /// Strategy for selecting which patches to apply.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -32,6 +35,8 @@ pub struct AppliedPatch {
     pub diagnostic_id: String,
     pub patch_id: String,
     pub description: String,
+    /// The diagnostic this patch targeted was gone on re-check.
+    pub resolved: bool,
 }
 
 #[derive(Debug)]
@@ -40,6 +45,63 @@ pub struct SkippedPatch {
     pub reason: String,
 }
 
+/// Why an iterative repair stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    /// No remaining diagnostic has a patch that applies.
+    Fixpoint,
+    /// `max_rounds` rounds ran and patches were still available.
+    RoundLimit,
+    /// A round produced a source already seen in an earlier round, so
+    /// further rounds would oscillate.
+    Cycle,
+}
+
+impl StopReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StopReason::Fixpoint => "fixpoint",
+            StopReason::RoundLimit => "round limit",
+            StopReason::Cycle => "cycle",
+        }
+    }
+}
+
+/// One application round of an iterative repair.
+#[derive(Debug)]
+pub struct RepairRound {
+    /// 1-based round number.
+    pub round: usize,
+    pub applied: Vec<AppliedPatch>,
+    pub skipped: Vec<SkippedPatch>,
+    pub diagnostics_before: usize,
+    pub diagnostics_after: usize,
+    /// Codes of diagnostics present before the round but not after (one
+    /// entry per diagnostic), sorted.
+    pub resolved: Vec<String>,
+    /// Codes of diagnostics present after the round but not before, sorted.
+    /// A fix often
+    /// unlocks detection of the next problem, which shows up here.
+    pub introduced: Vec<String>,
+}
+
+/// Result of [`RepairTool::repair_iterative`].
+#[derive(Debug)]
+pub struct IterativeRepairResult {
+    pub rounds: Vec<RepairRound>,
+    pub stop: StopReason,
+    pub verify_passed: bool,
+    pub diagnostics_before: usize,
+    pub diagnostics_after: usize,
+}
+
+impl IterativeRepairResult {
+    /// Every patch applied, across all rounds.
+    pub fn applied(&self) -> impl Iterator<Item = &AppliedPatch> {
+        self.rounds.iter().flat_map(|r| &r.applied)
+    }
+}
+
 /// Repair tool: apply patches from diagnostics to fix source files.
 pub struct RepairTool;
 
@@ -53,6 +115,78 @@ impl RepairTool {
         strategy: RepairStrategy,
         specific_id: Option<&str>,
     ) -> (String, RepairResult) {
+        let collect = |src: &str| DiagnosticCollector::new(file, src).collect();
+        let (repaired, result, _) =
+            Self::repair_round(source, diagnostics, strategy, specific_id, &collect);
+        (repaired, result)
+    }
+
+    /// Repair in rounds: apply patches, re-collect diagnostics with
+    /// `collect`, and repeat with the fresh diagnostics until no patch
+    /// applies, `max_rounds` is reached, or the source starts repeating.
+    /// `initial` are the diagnostics for `source` (round 1's input).
+    pub fn repair_iterative(
+        source: &str,
+        initial: &DiagnosticSet,
+        strategy: RepairStrategy,
+        specific_id: Option<&str>,
+        max_rounds: usize,
+        collect: &dyn Fn(&str) -> DiagnosticSet,
+    ) -> (String, IterativeRepairResult) {
+        let mut current = source.to_string();
+        let mut diagnostics = initial.clone();
+        let mut seen = HashSet::from([current.clone()]);
+        let mut rounds: Vec<RepairRound> = Vec::new();
+
+        let stop = loop {
+            if rounds.len() >= max_rounds {
+                break StopReason::RoundLimit;
+            }
+            let (repaired, result, verify) =
+                Self::repair_round(&current, &diagnostics, strategy, specific_id, collect);
+            let Some(verify) = verify.filter(|_| !result.applied.is_empty()) else {
+                break StopReason::Fixpoint;
+            };
+            let (resolved, introduced) = code_delta(&diagnostics, &verify);
+            rounds.push(RepairRound {
+                round: rounds.len() + 1,
+                applied: result.applied,
+                skipped: result.skipped,
+                diagnostics_before: result.diagnostics_before,
+                diagnostics_after: result.diagnostics_after,
+                resolved,
+                introduced,
+            });
+            diagnostics = verify;
+            if repaired == current {
+                break StopReason::Fixpoint;
+            }
+            current = repaired;
+            if !seen.insert(current.clone()) {
+                break StopReason::Cycle;
+            }
+        };
+
+        let result = IterativeRepairResult {
+            rounds,
+            stop,
+            verify_passed: !diagnostics.has_errors(),
+            diagnostics_before: initial.diagnostics.len(),
+            diagnostics_after: diagnostics.diagnostics.len(),
+        };
+        (current, result)
+    }
+
+    /// One application round. The third element is the re-collected
+    /// diagnostics for the repaired source, or `None` when no patch was
+    /// selected (nothing changed, nothing re-checked).
+    fn repair_round(
+        source: &str,
+        diagnostics: &DiagnosticSet,
+        strategy: RepairStrategy,
+        specific_id: Option<&str>,
+        collect: &dyn Fn(&str) -> DiagnosticSet,
+    ) -> (String, RepairResult, Option<DiagnosticSet>) {
         let mut result = RepairResult {
             applied: Vec::new(),
             skipped: Vec::new(),
@@ -62,19 +196,19 @@ impl RepairTool {
         };
 
         // Collect all applicable patches
-        let patches: Vec<(&str, &SuggestedPatch)> = diagnostics
+        let patches: Vec<(&Diagnostic, &SuggestedPatch)> = diagnostics
             .diagnostics
             .iter()
-            .flat_map(|d| d.suggested_patches.iter().map(move |p| (d.id.as_str(), p)))
+            .flat_map(|d| d.suggested_patches.iter().map(move |p| (d, p)))
             .collect();
 
         if patches.is_empty() {
             result.diagnostics_after = result.diagnostics_before;
-            return (source.to_string(), result);
+            return (source.to_string(), result, None);
         }
 
         // Select patches based on strategy
-        let selected = match strategy {
+        let mut selected = match strategy {
             RepairStrategy::Best => select_best(diagnostics),
             RepairStrategy::ById => {
                 if let Some(id) = specific_id {
@@ -93,24 +227,30 @@ impl RepairTool {
                 reason: "no applicable patches found".into(),
             });
             result.diagnostics_after = result.diagnostics_before;
-            return (source.to_string(), result);
+            return (source.to_string(), result, None);
         }
 
         // Apply patches (sorted by line number descending to avoid offset issues)
+        selected.sort_by_key(|(_, p)| {
+            std::cmp::Reverse(p.edits.iter().map(|e| e.start_line).max().unwrap_or(0))
+        });
         let mut repaired = source.to_string();
-        for (diag_id, patch) in &selected {
+        let mut targets = Vec::new();
+        for (diag, patch) in &selected {
             match apply_patch(&repaired, &patch.edits) {
                 Ok(new_source) => {
                     repaired = new_source;
+                    targets.push(*diag);
                     result.applied.push(AppliedPatch {
-                        diagnostic_id: diag_id.to_string(),
+                        diagnostic_id: diag.id.clone(),
                         patch_id: patch.id.clone(),
                         description: patch.description.clone(),
+                        resolved: false,
                     });
                 }
                 Err(reason) => {
                     result.skipped.push(SkippedPatch {
-                        diagnostic_id: diag_id.to_string(),
+                        diagnostic_id: diag.id.clone(),
                         reason,
                     });
                 }
@@ -118,11 +258,17 @@ impl RepairTool {
         }
 
         // Verify: re-run diagnostics on the repaired source
-        let verify = DiagnosticCollector::new(file, &repaired).collect();
+        let verify = collect(&repaired);
         result.diagnostics_after = verify.diagnostics.len();
         result.verify_passed = !verify.has_errors();
+        for (applied, target) in result.applied.iter_mut().zip(targets) {
+            applied.resolved = !verify
+                .diagnostics
+                .iter()
+                .any(|d| d.id == target.id && d.message == target.message);
+        }
 
-        (repaired, result)
+        (repaired, result, Some(verify))
     }
 
     /// Repair a file on disk. Reads the file, applies patches, writes back.
@@ -147,20 +293,51 @@ impl RepairTool {
     }
 }
 
-/// Select the best (highest confidence) patch for each diagnostic.
-fn select_best(diagnostics: &DiagnosticSet) -> Vec<(String, SuggestedPatch)> {
+/// Codes of the diagnostics resolved and introduced between two sets. A
+/// diagnostic is identified by code and message rather than line, since
+/// edits shift lines.
+fn code_delta(before: &DiagnosticSet, after: &DiagnosticSet) -> (Vec<String>, Vec<String>) {
+    let mut counts: BTreeMap<(&str, &str), isize> = BTreeMap::new();
+    for d in &before.diagnostics {
+        *counts.entry((&d.id, &d.message)).or_default() += 1;
+    }
+    for d in &after.diagnostics {
+        *counts.entry((&d.id, &d.message)).or_default() -= 1;
+    }
+    let mut resolved = Vec::new();
+    let mut introduced = Vec::new();
+    for ((code, _), n) in counts {
+        if n == 0 {
+            continue;
+        }
+        let list = if n > 0 {
+            &mut resolved
+        } else {
+            &mut introduced
+        };
+        list.extend(std::iter::repeat_n(code.to_string(), n.unsigned_abs()));
+    }
+    resolved.sort();
+    introduced.sort();
+    (resolved, introduced)
+}
+
+/// Select the best (highest confidence) patch for each diagnostic. Ties go
+/// to the earlier patch, so a calibrated order from
+/// [`rank_patches`](crate::diagnostics::suggest::rank_patches) is kept.
+fn select_best(diagnostics: &DiagnosticSet) -> Vec<(&Diagnostic, SuggestedPatch)> {
     let mut selected = Vec::new();
     for d in &diagnostics.diagnostics {
         if let Some(best) = d
             .suggested_patches
             .iter()
-            .max_by_key(|p| match p.confidence {
-                Confidence::High => 3,
-                Confidence::Medium => 2,
-                Confidence::Low => 1,
+            .min_by_key(|p| match p.confidence {
+                Confidence::High => 0,
+                Confidence::Medium => 1,
+                Confidence::Low => 2,
             })
         {
-            selected.push((d.id.clone(), best.clone()));
+            selected.push((d, best.clone()));
         }
     }
     selected
@@ -168,18 +345,18 @@ fn select_best(diagnostics: &DiagnosticSet) -> Vec<(String, SuggestedPatch)> {
 
 /// Select a specific patch by its ID.
 fn select_by_id<'a>(
-    patches: &[(&'a str, &'a SuggestedPatch)],
+    patches: &[(&'a Diagnostic, &'a SuggestedPatch)],
     target_id: &str,
-) -> Vec<(String, SuggestedPatch)> {
+) -> Vec<(&'a Diagnostic, SuggestedPatch)> {
     patches
         .iter()
         .filter(|(_, p)| p.id == target_id)
-        .map(|(diag_id, p)| (diag_id.to_string(), (*p).clone()))
+        .map(|(diag, p)| (*diag, (*p).clone()))
         .collect()
 }
 
 /// Select all patches (in confidence order).
-fn select_all(diagnostics: &DiagnosticSet) -> Vec<(String, SuggestedPatch)> {
+fn select_all(diagnostics: &DiagnosticSet) -> Vec<(&Diagnostic, SuggestedPatch)> {
     let mut selected = Vec::new();
     for d in &diagnostics.diagnostics {
         let mut patches: Vec<_> = d.suggested_patches.clone();
@@ -189,14 +366,14 @@ fn select_all(diagnostics: &DiagnosticSet) -> Vec<(String, SuggestedPatch)> {
             Confidence::Low => 2,
         });
         if let Some(best) = patches.into_iter().next() {
-            selected.push((d.id.clone(), best));
+            selected.push((d, best));
         }
     }
     selected
 }
 
 /// Select only High-confidence patches (one per diagnostic).
-fn select_conservative(diagnostics: &DiagnosticSet) -> Vec<(String, SuggestedPatch)> {
+fn select_conservative(diagnostics: &DiagnosticSet) -> Vec<(&Diagnostic, SuggestedPatch)> {
     let mut selected = Vec::new();
     for d in &diagnostics.diagnostics {
         if let Some(best) = d
@@ -209,7 +386,7 @@ fn select_conservative(diagnostics: &DiagnosticSet) -> Vec<(String, SuggestedPat
                 Confidence::Low => 1,
             })
         {
-            selected.push((d.id.clone(), best.clone()));
+            selected.push((d, best.clone()));
         }
     }
     selected
@@ -527,4 +704,87 @@ fn main() -> Int {
             "Best should rename countt to count"
         );
     }
+
+    /// `outer` is the only caller of `inner`: removing dead `outer` in round
+    /// 1 is what makes `inner` dead for round 2.
+    const DEAD_CHAIN: &str = "\
+export fn api() -> Int {
+    1
+}
+
+fn outer() -> Int {
+    inner()
+}
+
+fn inner() -> Int {
+    2
+}
+";
+
+    fn collect(src: &str) -> DiagnosticSet {
+        DiagnosticCollector::new("test.ax", src).collect()
+    }
+
+    #[test]
+    fn test_iterative_repair_reaches_fixpoint() {
+        let initial = collect(DEAD_CHAIN);
+        let (repaired, result) = RepairTool::repair_iterative(
+            DEAD_CHAIN,
+            &initial,
+            RepairStrategy::Best,
+            None,
+            5,
+            &collect,
+        );
+        assert_eq!(result.stop, StopReason::Fixpoint);
+        assert_eq!(result.rounds.len(), 2);
+        assert_eq!(result.rounds[0].resolved, vec!["W003"]);
+        assert_eq!(result.rounds[0].introduced, vec!["W003"]);
+        assert_eq!(result.rounds[1].resolved, vec!["W003"]);
+        assert!(result.rounds[1].introduced.is_empty());
+        assert_eq!(
+            (result.diagnostics_before, result.diagnostics_after),
+            (1, 0)
+        );
+        assert!(result.verify_passed);
+        assert_eq!(result.applied().count(), 2);
+        assert!(!repaired.contains("outer") && !repaired.contains("inner"));
+    }
+
+    #[test]
+    fn test_iterative_repair_stops_at_round_limit() {
+        let initial = collect(DEAD_CHAIN);
+        let (repaired, result) = RepairTool::repair_iterative(
+            DEAD_CHAIN,
+            &initial,
+            RepairStrategy::Best,
+            None,
+            1,
+            &collect,
+        );
+        assert_eq!(result.stop, StopReason::RoundLimit);
+        assert_eq!(result.rounds.len(), 1);
+        assert!(repaired.contains("fn inner"));
+        // Same outcome as a single-round `repair`.
+        let (single, _) =
+            RepairTool::repair("test.ax", DEAD_CHAIN, &initial, RepairStrategy::Best, None);
+        assert_eq!(repaired, single);
+    }
+
+    #[test]
+    fn test_iterative_repair_clean_source_runs_no_rounds() {
+        let source = "fn main() -> Int {\n    0\n}\n";
+        let (repaired, result) = RepairTool::repair_iterative(
+            source,
+            &collect(source),
+            RepairStrategy::Best,
+            None,
+            3,
+            &collect,
+        );
+        assert_eq!(repaired, source);
+        assert!(result.rounds.is_empty());
+        assert_eq!(result.stop, StopReason::Fixpoint);
+        assert!(result.verify_passed);
+    }
 }
@@ -2,6 +2,24 @@
 
 use std::path::Path;
 
+/// Every library under `libs/`.
+pub const LIBRARIES: &[&str] = &[
+    "std-authz",
+    "std-db",
+    "std-forms",
+    "std-guard",
+    "std-http",
+    "std-json",
+    "std-llm",
+    "std-notifications",
+    "std-routing",
+    "std-storage",
+    "std-sync",
+    "std-testing",
+    "std-ui",
+    "std-validation",
+];
+
 /// Compile and run a .llm library source, returning the main() result.
 pub fn run_library(source: &str) -> Result<i64, String> {
     let module = boruna_compiler::compile("stdlib_test", source)
@@ -2,11 +2,27 @@
 //!
 //! Templates are `.ax.template` files with `{{variable}}` placeholders.
 //! Each template has a `template.json` manifest describing args.
+//!
+//! Generated source starts with a stamp comment recording the template,
+//! version and args, which [`upgrade`] uses to re-apply a newer version.
+//!
+//! A template may also ship `tests/*.testspec.json.template` files. They
+//! are substituted with the same args and parsed as trace2tests
+//! [`TestSpec`]s pinned to the generated source, so a scaffolded app
+//! starts with a regression baseline ([`run_template_tests`]).
+//!
+//! Templates live in a local directory or in the cache of remote packs
+//! managed by [`remote`]; [`list_templates_with_cache`] merges both.
+
+pub mod remote;
+pub mod upgrade;
 
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use std::path::Path;
 
+use crate::trace2tests::{self, TestResult, TestSpec};
+
 /// Template manifest (template.json).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TemplateManifest {
@@ -37,6 +53,18 @@ pub struct TemplateResult {
     pub source: String,
     pub dependencies: Vec<String>,
     pub capabilities: Vec<String>,
+    /// Test specs generated from the template's `tests/` directory.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tests: Vec<TemplateTest>,
+}
+
+/// A test spec generated alongside a template's source.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateTest {
+    /// Output path relative to the generated source's directory, e.g.
+    /// `tests/submit.testspec.json`.
+    pub file: String,
+    pub spec: TestSpec,
 }
 
 /// List available templates in a directory.
@@ -65,6 +93,58 @@ pub fn list_templates(templates_dir: &Path) -> Result<Vec<TemplateManifest>, Str
     Ok(templates)
 }
 
+/// Where a listed template comes from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Provenance {
+    /// The local templates directory.
+    Local,
+    /// A pack fetched with `boruna template add`.
+    Remote { source: String, sha256: String },
+}
+
+/// A template manifest together with its provenance.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListedTemplate {
+    #[serde(flatten)]
+    pub manifest: TemplateManifest,
+    pub provenance: Provenance,
+}
+
+/// List the templates in `templates_dir` and in the remote-pack cache at
+/// `cache_dir`, sorted by name. A local template shadows a cached one of
+/// the same name.
+pub fn list_templates_with_cache(
+    templates_dir: &Path,
+    cache_dir: &Path,
+) -> Result<Vec<ListedTemplate>, String> {
+    let mut listed: Vec<ListedTemplate> = list_templates(templates_dir)?
+        .into_iter()
+        .map(|manifest| ListedTemplate {
+            manifest,
+            provenance: Provenance::Local,
+        })
+        .collect();
+    let index = remote::load_cache_index(cache_dir)?;
+    for manifest in list_templates(cache_dir)? {
+        let Some(entry) = index.templates.get(&manifest.name) else {
+            continue;
+        };
+        if listed.iter().any(|t| t.manifest.name == manifest.name) {
+            continue;
+        }
+        listed.push(ListedTemplate {
+            provenance: Provenance::Remote {
+                source: entry.source.clone(),
+                sha256: entry.sha256.clone(),
+            },
+            manifest,
+        });
+    }
+    listed.sort_by(|a, b| a.manifest.name.cmp(&b.manifest.name));
+    Ok(listed)
+}
+
 /// Reject template names that could escape `templates_dir`. A name is a single
 /// directory segment; anything with a path separator, `..`, or a NUL is a
 /// traversal attempt (e.g. via the `boruna_template_apply` MCP tool, whose
@@ -117,17 +197,89 @@ pub fn apply_template(
         std::fs::read_to_string(&template_path).map_err(|e| format!("read template: {e}"))?;
 
     // Substitute variables
-    let source = substitute(&template, args);
+    let body = substitute(&template, args);
+    let stamp = upgrade::TemplateStamp {
+        template: name.to_string(),
+        version: manifest.version.clone(),
+        args: args.clone(),
+        base: upgrade::base_hash(&body),
+    };
+    let source = format!("{}\n{body}", stamp.to_line());
+    let output_file = format!("{name}_app.ax");
+    let tests = apply_template_tests(
+        &templates_dir.join(name).join("tests"),
+        args,
+        &output_file,
+        &source,
+    )?;
 
     Ok(TemplateResult {
         template_name: manifest.name.clone(),
-        output_file: format!("{name}_app.ax"),
+        output_file,
         source,
         dependencies: manifest.dependencies.clone(),
         capabilities: manifest.capabilities.clone(),
+        tests,
     })
 }
 
+/// Substitute and parse every `*.testspec.json.template` in `tests_dir`.
+/// `source_file` and `source_hash` are filled in from the generated
+/// source; `version` defaults to 1 and `name` to the file stem.
+fn apply_template_tests(
+    tests_dir: &Path,
+    args: &BTreeMap<String, String>,
+    output_file: &str,
+    source: &str,
+) -> Result<Vec<TemplateTest>, String> {
+    const SUFFIX: &str = ".testspec.json.template";
+    if !tests_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut stems = Vec::new();
+    for entry in std::fs::read_dir(tests_dir).map_err(|e| format!("read template tests: {e}"))? {
+        let file_name = entry
+            .map_err(|e| format!("read entry: {e}"))?
+            .file_name()
+            .to_string_lossy()
+            .into_owned();
+        if let Some(stem) = file_name.strip_suffix(SUFFIX) {
+            stems.push(stem.to_string());
+        }
+    }
+    stems.sort();
+
+    let mut tests = Vec::new();
+    for stem in stems {
+        let file = format!("tests/{stem}.testspec.json");
+        let raw = std::fs::read_to_string(tests_dir.join(format!("{stem}{SUFFIX}")))
+            .map_err(|e| format!("read template test {file}: {e}"))?;
+        let mut json: serde_json::Value = serde_json::from_str(&substitute(&raw, args))
+            .map_err(|e| format!("template test {file}: {e}"))?;
+        let obj = json
+            .as_object_mut()
+            .ok_or_else(|| format!("template test {file}: expected a JSON object"))?;
+        obj.entry("version").or_insert(1.into());
+        obj.entry("name").or_insert(stem.clone().into());
+        obj.insert("source_file".into(), output_file.into());
+        obj.insert("source_hash".into(), trace2tests::sha256_hex(source).into());
+        let spec: TestSpec =
+            serde_json::from_value(json).map_err(|e| format!("template test {file}: {e}"))?;
+        tests.push(TemplateTest { file, spec });
+    }
+    Ok(tests)
+}
+
+/// Run the test specs generated with `result` against its source, in
+/// order.
+pub fn run_template_tests(result: &TemplateResult) -> Vec<(&TemplateTest, TestResult)> {
+    result
+        .tests
+        .iter()
+        .map(|t| (t, trace2tests::run_test(&t.spec, &result.source)))
+        .collect()
+}
+
 /// Apply a template from a template string (no filesystem).
 pub fn apply_template_string(template: &str, args: &BTreeMap<String, String>) -> String {
     substitute(template, args)
@@ -212,6 +364,69 @@ mod tests {
         assert_eq!(templates[0].name, "test-template");
     }
 
+    #[test]
+    fn test_list_templates_with_cache_shows_provenance() {
+        let write = |dir: &Path, name: &str, version: &str| {
+            let manifest = TemplateManifest {
+                name: name.into(),
+                version: version.into(),
+                description: "A test".into(),
+                dependencies: vec![],
+                capabilities: vec![],
+                args: BTreeMap::new(),
+            };
+            std::fs::create_dir_all(dir.join(name)).unwrap();
+            std::fs::write(
+                dir.join(name).join("template.json"),
+                serde_json::to_string_pretty(&manifest).unwrap(),
+            )
+            .unwrap();
+        };
+        let local = tempfile::tempdir().unwrap();
+        write(local.path(), "shared", "0.1.0");
+        let remote = tempfile::tempdir().unwrap();
+        write(remote.path(), "shared", "2.0.0");
+        write(remote.path(), "billing", "1.0.0");
+
+        let index = remote::PackIndex {
+            templates: ["billing", "shared"]
+                .iter()
+                .map(|name| {
+                    let mut files = BTreeMap::new();
+                    files.insert(
+                        "template.json".to_string(),
+                        std::fs::read(remote.path().join(name).join("template.json")).unwrap(),
+                    );
+                    remote::IndexEntry {
+                        name: name.to_string(),
+                        sha256: remote::pack_hash(&files),
+                        files: vec!["template.json".into()],
+                    }
+                })
+                .collect(),
+        };
+        let index_path = remote.path().join("index.json");
+        std::fs::write(&index_path, serde_json::to_string(&index).unwrap()).unwrap();
+        let cache = tempfile::tempdir().unwrap();
+        let pack = remote::fetch(&index_path.to_string_lossy()).unwrap();
+        remote::install(cache.path(), &pack).unwrap();
+
+        let sha256 = index.templates[0].sha256.clone();
+        let listed = list_templates_with_cache(local.path(), cache.path()).unwrap();
+        assert_eq!(listed.len(), 2);
+        assert_eq!(listed[0].manifest.name, "billing");
+        assert_eq!(
+            listed[0].provenance,
+            Provenance::Remote {
+                source: index_path.to_string_lossy().into_owned(),
+                sha256,
+            }
+        );
+        assert_eq!(listed[1].manifest.name, "shared");
+        assert_eq!(listed[1].manifest.version, "0.1.0");
+        assert_eq!(listed[1].provenance, Provenance::Local);
+    }
+
     #[test]
     fn test_apply_template_fs() {
         let dir = tempfile::tempdir().unwrap();
@@ -258,6 +473,72 @@ mod tests {
         assert_eq!(result.capabilities, vec!["db.query"]);
     }
 
+    #[test]
+    fn test_apply_template_generates_pinned_tests() {
+        let dir = tempfile::tempdir().unwrap();
+        let t_dir = dir.path().join("counter");
+        std::fs::create_dir_all(t_dir.join("tests")).unwrap();
+        let manifest = TemplateManifest {
+            name: "counter".into(),
+            version: "0.1.0".into(),
+            description: "Counter".into(),
+            dependencies: vec![],
+            capabilities: vec![],
+            args: BTreeMap::new(),
+        };
+        std::fs::write(
+            t_dir.join("template.json"),
+            serde_json::to_string_pretty(&manifest).unwrap(),
+        )
+        .unwrap();
+        std::fs::write(
+            t_dir.join("app.ax.template"),
+            "type State { n: Int }\n\
+             type Msg { tag: String, payload: String }\n\
+             type Effect { kind: String, payload: String, callback_tag: String }\n\
+             type UpdateResult { state: State, effects: List<Effect> }\n\
+             type UINode { tag: String, text: String }\n\
+             fn init() -> State { State { n: {{start}} } }\n\
+             fn update(state: State, msg: Msg) -> UpdateResult {\n\
+             UpdateResult { state: State { n: state.n + 1 }, effects: [] } }\n\
+             fn view(state: State) -> UINode { UINode { tag: \"n\", text: \"\" } }\n\
+             fn main() -> Int { 0 }\n",
+        )
+        .unwrap();
+        std::fs::write(
+            t_dir.join("tests/bump.testspec.json.template"),
+            r#"{ "messages": [{ "tag": "bump", "payload": "" }],
+                 "assertions": [{ "kind": "state_field_equals", "path": "n",
+                                  "expected": "{{expected}}", "description": "bumped" }] }"#,
+        )
+        .unwrap();
+
+        let mut args = BTreeMap::new();
+        args.insert("start".into(), "4".into());
+        args.insert("expected".into(), "5".into());
+        let result = apply_template(dir.path(), "counter", &args).unwrap();
+        assert_eq!(result.tests.len(), 1);
+        let test = &result.tests[0];
+        assert_eq!(test.file, "tests/bump.testspec.json");
+        assert_eq!(test.spec.name, "bump");
+        assert_eq!(test.spec.source_file, "counter_app.ax");
+        assert_eq!(
+            test.spec.source_hash,
+            trace2tests::sha256_hex(&result.source)
+        );
+        for (_, r) in run_template_tests(&result) {
+            assert!(r.passed, "{r:?}");
+        }
+
+        args.insert("expected".into(), "6".into());
+        let result = apply_template(dir.path(), "counter", &args).unwrap();
+        assert!(!run_template_tests(&result)[0].1.passed);
+
+        std::fs::write(t_dir.join("tests/bad.testspec.json.template"), "[]").unwrap();
+        let err = apply_template(dir.path(), "counter", &args).unwrap_err();
+        assert!(err.contains("tests/bad.testspec.json"), "got: {err}");
+    }
+
     #[test]
     fn test_apply_template_missing_arg() {
         let dir = tempfile::tempdir().unwrap();
@@ -0,0 +1,578 @@
+//! Remote template packs and the local template cache.
+//!
+//! `boruna template add <source>` fetches a pack of templates into a cache
+//! directory (by default [`DEFAULT_CACHE_DIR`]) so teams can share
+//! templates without passing directories around by hand. A source is
+//! either:
+//!
+//! - a **git repository** (anything not ending in `.json`), cloned with
+//!   `git clone --depth 1`. Templates are the `<name>/template.json`
+//!   directories under `templates/` if the repository has one, else under
+//!   its root.
+//! - a **pack index** (a URL or path ending in `.json`) listing templates
+//!   and their files, fetched relative to the index:
+//!
+//! ```json
+//! { "templates": [
+//!     { "name": "crud-admin", "sha256": "sha256:…",
+//!       "files": ["template.json", "app.ax.template"] } ] }
+//! ```
+//!
+//! `http://` and `https://` indexes need the `http` feature; `file://` URLs
+//! and plain paths always work.
+//!
+//! **Integrity.** Every template is hashed over its files ([`pack_hash`]).
+//! An index entry's `sha256` must match what was fetched. The hash is
+//! recorded in the cache's `cache.json` with the source, and cached
+//! templates are re-hashed before they are applied, so an edited cache
+//! entry is refused rather than used.
+//!
+//! Fetching and installing are separate steps so the caller can show the
+//! fetched pack and ask the user to trust it in between.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use boruna_hash::Hasher;
+use serde::{Deserialize, Serialize};
+
+use super::{validate_template_name, TemplateManifest};
+
+/// Cache directory used when none is given, relative to the working
+/// directory.
+pub const DEFAULT_CACHE_DIR: &str = ".boruna/template-cache";
+
+/// Largest single file accepted from a pack.
+const MAX_FILE_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Where a pack comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateSource {
+    Git(String),
+    Index(String),
+}
+
+impl TemplateSource {
+    /// Classify `source`: anything ending in `.json` is a pack index,
+    /// everything else a git repository.
+    pub fn parse(source: &str) -> Self {
+        if source.ends_with(".json") {
+            TemplateSource::Index(source.to_string())
+        } else {
+            TemplateSource::Git(source.to_string())
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            TemplateSource::Git(s) | TemplateSource::Index(s) => s,
+        }
+    }
+}
+
+/// One template fetched from a pack, not yet installed.
+#[derive(Debug, Clone)]
+pub struct FetchedTemplate {
+    pub manifest: TemplateManifest,
+    /// File contents keyed by `/`-separated path within the template.
+    pub files: BTreeMap<String, Vec<u8>>,
+    /// [`pack_hash`] of `files`.
+    pub sha256: String,
+}
+
+/// Templates fetched from one source.
+#[derive(Debug, Clone)]
+pub struct FetchedPack {
+    pub source: String,
+    pub templates: Vec<FetchedTemplate>,
+}
+
+/// A pack index, as served at an index URL.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackIndex {
+    pub templates: Vec<IndexEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexEntry {
+    pub name: String,
+    /// Expected [`pack_hash`] of the template's files.
+    pub sha256: String,
+    /// Files of the template, relative to `<index dir>/<name>/`.
+    pub files: Vec<String>,
+}
+
+/// The cache's `cache.json`: provenance of every cached template.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CacheIndex {
+    #[serde(default)]
+    pub templates: BTreeMap<String, CachedTemplate>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CachedTemplate {
+    pub version: String,
+    pub source: String,
+    pub sha256: String,
+    /// RFC 3339 time the template was installed.
+    pub fetched_at: String,
+}
+
+/// Hash a template's files: `sha256:<hex>` over each path and its
+/// contents in path order, both length-prefixed.
+pub fn pack_hash(files: &BTreeMap<String, Vec<u8>>) -> String {
+    let mut hasher = Hasher::sha256();
+    for (path, data) in files {
+        hasher.update((path.len() as u64).to_be_bytes());
+        hasher.update(path.as_bytes());
+        hasher.update((data.len() as u64).to_be_bytes());
+        hasher.update(data);
+    }
+    hasher.finalize().to_string()
+}
+
+/// Fetch every template from `source` without touching the cache.
+pub fn fetch(source: &str) -> Result<FetchedPack, String> {
+    let templates = match TemplateSource::parse(source) {
+        TemplateSource::Git(url) => fetch_git(&url)?,
+        TemplateSource::Index(url) => fetch_index(&url)?,
+    };
+    if templates.is_empty() {
+        return Err(format!("no templates found in {source}"));
+    }
+    Ok(FetchedPack {
+        source: source.to_string(),
+        templates,
+    })
+}
+
+fn fetch_git(url: &str) -> Result<Vec<FetchedTemplate>, String> {
+    let checkout = std::env::temp_dir().join(format!(
+        "boruna-template-{}-{}",
+        std::process::id(),
+        chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+    ));
+    let status = std::process::Command::new("git")
+        .args(["clone", "--depth", "1", "--quiet", "--", url])
+        .arg(&checkout)
+        .stdin(std::process::Stdio::null())
+        .status()
+        .map_err(|e| format!("run git: {e}"))?;
+    let result = if status.success() {
+        let root = checkout.join("templates");
+        let is_dir = fs::symlink_metadata(&root).is_ok_and(|m| m.is_dir());
+        read_pack_dir(if is_dir { &root } else { &checkout })
+    } else {
+        Err(format!("git clone {url} failed ({status})"))
+    };
+    let _ = fs::remove_dir_all(&checkout);
+    result
+}
+
+/// Read every `<name>/template.json` directory under `root`. Symlinks are
+/// rejected rather than followed: a pack must not reach outside itself.
+fn read_pack_dir(root: &Path) -> Result<Vec<FetchedTemplate>, String> {
+    let mut templates = Vec::new();
+    for entry in fs::read_dir(root).map_err(|e| format!("read {}: {e}", root.display()))? {
+        let entry = entry.map_err(|e| format!("read entry: {e}"))?;
+        let dir = entry.path();
+        let kind = entry
+            .file_type()
+            .map_err(|e| format!("read {}: {e}", dir.display()))?;
+        if kind.is_symlink() {
+            return Err(symlink_error(&dir));
+        }
+        if !kind.is_dir() || !dir.join("template.json").is_file() {
+            continue;
+        }
+        let mut files = BTreeMap::new();
+        read_files(&dir, "", &mut files)?;
+        templates.push(checked_template(files, None)?);
+    }
+    templates.sort_by(|a, b| a.manifest.name.cmp(&b.manifest.name));
+    Ok(templates)
+}
+
+fn read_files(dir: &Path, prefix: &str, out: &mut BTreeMap<String, Vec<u8>>) -> Result<(), String> {
+    for entry in fs::read_dir(dir).map_err(|e| format!("read {}: {e}", dir.display()))? {
+        let entry = entry.map_err(|e| format!("read entry: {e}"))?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if name.starts_with('.') {
+            continue;
+        }
+        let path = entry.path();
+        let rel = format!("{prefix}{name}");
+        let kind = entry
+            .file_type()
+            .map_err(|e| format!("read {}: {e}", path.display()))?;
+        if kind.is_symlink() {
+            return Err(symlink_error(&path));
+        } else if kind.is_dir() {
+            read_files(&path, &format!("{rel}/"), out)?;
+        } else if kind.is_file() {
+            out.insert(rel, read_local(&path)?);
+        }
+    }
+    Ok(())
+}
+
+fn symlink_error(path: &Path) -> String {
+    format!(
+        "{}: symlinks are not allowed in template packs",
+        path.display()
+    )
+}
+
+fn fetch_index(url: &str) -> Result<Vec<FetchedTemplate>, String> {
+    let data = get(url)?;
+    let index: PackIndex =
+        serde_json::from_slice(&data).map_err(|e| format!("parse index {url}: {e}"))?;
+    let base = url.rsplit_once('/').map_or("", |(base, _)| base);
+    let mut templates = Vec::new();
+    for entry in &index.templates {
+        validate_template_name(&entry.name)?;
+        let mut files = BTreeMap::new();
+        for file in &entry.files {
+            validate_file_path(file)?;
+            let file_url = if base.is_empty() {
+                format!("{}/{file}", entry.name)
+            } else {
+                format!("{base}/{}/{file}", entry.name)
+            };
+            files.insert(file.clone(), get(&file_url)?);
+        }
+        templates.push(checked_template(files, Some(entry))?);
+    }
+    templates.sort_by(|a, b| a.manifest.name.cmp(&b.manifest.name));
+    Ok(templates)
+}
+
+/// Parse the manifest out of `files` and hash them, checking the hash
+/// and name against the index `entry` if there is one.
+fn checked_template(
+    files: BTreeMap<String, Vec<u8>>,
+    entry: Option<&IndexEntry>,
+) -> Result<FetchedTemplate, String> {
+    let manifest: TemplateManifest = files
+        .get("template.json")
+        .ok_or_else(|| "template has no template.json".to_string())
+        .and_then(|data| {
+            serde_json::from_slice(data).map_err(|e| format!("parse template.json: {e}"))
+        })?;
+    validate_template_name(&manifest.name)?;
+    let sha256 = pack_hash(&files);
+    if let Some(entry) = entry {
+        if manifest.name != entry.name {
+            return Err(format!(
+                "index entry '{}' holds template '{}'",
+                entry.name, manifest.name
+            ));
+        }
+        if sha256 != entry.sha256 {
+            return Err(format!(
+                "template '{}' fails its integrity check: index says {}, fetched {sha256}",
+                entry.name, entry.sha256
+            ));
+        }
+    }
+    Ok(FetchedTemplate {
+        manifest,
+        files,
+        sha256,
+    })
+}
+
+/// Reject file paths that could escape the template directory.
+fn validate_file_path(path: &str) -> Result<(), String> {
+    let bad = path.is_empty()
+        || path.starts_with('/')
+        || path.contains('\\')
+        || path.contains('\0')
+        || path.split('/').any(|seg| seg.is_empty() || seg == "..");
+    if bad {
+        return Err(format!("invalid template file path '{path}'"));
+    }
+    Ok(())
+}
+
+fn read_local(path: &Path) -> Result<Vec<u8>, String> {
+    let meta = fs::metadata(path).map_err(|e| format!("read {}: {e}", path.display()))?;
+    if meta.len() > MAX_FILE_BYTES {
+        return Err(format!(
+            "{} is {} bytes, over the {MAX_FILE_BYTES}-byte limit",
+            path.display(),
+            meta.len()
+        ));
+    }
+    fs::read(path).map_err(|e| format!("read {}: {e}", path.display()))
+}
+
+/// Fetch `url`: over HTTP(S) with the `http` feature, else as a local
+/// path (with or without `file://`).
+fn get(url: &str) -> Result<Vec<u8>, String> {
+    if url.starts_with("http://") || url.starts_with("https://") {
+        return get_http(url);
+    }
+    read_local(Path::new(url.strip_prefix("file://").unwrap_or(url)))
+}
+
+#[cfg(feature = "http")]
+fn get_http(url: &str) -> Result<Vec<u8>, String> {
+    use std::io::Read;
+
+    let response = ureq::get(url)
+        .call()
+        .map_err(|e| format!("fetch {url}: {e}"))?;
+    let mut data = Vec::new();
+    response
+        .into_reader()
+        .take(MAX_FILE_BYTES + 1)
+        .read_to_end(&mut data)
+        .map_err(|e| format!("fetch {url}: {e}"))?;
+    if data.len() as u64 > MAX_FILE_BYTES {
+        return Err(format!("{url} is over the {MAX_FILE_BYTES}-byte limit"));
+    }
+    Ok(data)
+}
+
+#[cfg(not(feature = "http"))]
+fn get_http(url: &str) -> Result<Vec<u8>, String> {
+    Err(format!("fetching {url} requires the `http` feature"))
+}
+
+/// Read `cache.json` from `cache_dir`; a missing cache is empty.
+pub fn load_cache_index(cache_dir: &Path) -> Result<CacheIndex, String> {
+    let path = cache_dir.join("cache.json");
+    if !path.exists() {
+        return Ok(CacheIndex::default());
+    }
+    let data = fs::read_to_string(&path).map_err(|e| format!("read {}: {e}", path.display()))?;
+    serde_json::from_str(&data).map_err(|e| format!("parse {}: {e}", path.display()))
+}
+
+/// Write `pack` into `cache_dir`, replacing any cached templates of the
+/// same names, and record its provenance. Returns the installed names.
+pub fn install(cache_dir: &Path, pack: &FetchedPack) -> Result<Vec<String>, String> {
+    let mut index = load_cache_index(cache_dir)?;
+    let fetched_at = chrono::Utc::now().to_rfc3339();
+    let mut installed = Vec::new();
+    for t in &pack.templates {
+        let name = &t.manifest.name;
+        let dir = cache_dir.join(name);
+        if dir.exists() {
+            fs::remove_dir_all(&dir).map_err(|e| format!("remove {}: {e}", dir.display()))?;
+        }
+        for (rel, data) in &t.files {
+            validate_file_path(rel)?;
+            let path = dir.join(rel);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|e| format!("create {}: {e}", parent.display()))?;
+            }
+            fs::write(&path, data).map_err(|e| format!("write {}: {e}", path.display()))?;
+        }
+        index.templates.insert(
+            name.clone(),
+            CachedTemplate {
+                version: t.manifest.version.clone(),
+                source: pack.source.clone(),
+                sha256: t.sha256.clone(),
+                fetched_at: fetched_at.clone(),
+            },
+        );
+        installed.push(name.clone());
+    }
+    let json = serde_json::to_string_pretty(&index).map_err(|e| e.to_string())?;
+    fs::write(cache_dir.join("cache.json"), json + "\n")
+        .map_err(|e| format!("write cache index: {e}"))?;
+    Ok(installed)
+}
+
+/// Check that the cached template `name` still hashes to the recorded
+/// value.
+pub fn verify_cached(cache_dir: &Path, name: &str) -> Result<(), String> {
+    validate_template_name(name)?;
+    let index = load_cache_index(cache_dir)?;
+    let entry = index
+        .templates
+        .get(name)
+        .ok_or_else(|| format!("template '{name}' is not in the cache"))?;
+    let mut files = BTreeMap::new();
+    read_files(&cache_dir.join(name), "", &mut files)?;
+    let actual = pack_hash(&files);
+    if actual != entry.sha256 {
+        return Err(format!(
+            "cached template '{name}' fails its integrity check: recorded {}, found {actual}; \
+             re-run `boruna template add {}`",
+            entry.sha256, entry.source
+        ));
+    }
+    Ok(())
+}
+
+/// Directory to load template `name` from: `templates_dir` if it has
+/// the template, else the cache after an integrity check.
+pub fn resolve_template_dir(
+    templates_dir: &Path,
+    cache_dir: &Path,
+    name: &str,
+) -> Result<PathBuf, String> {
+    validate_template_name(name)?;
+    if templates_dir.join(name).join("template.json").exists() {
+        return Ok(templates_dir.to_path_buf());
+    }
+    if load_cache_index(cache_dir)?.templates.contains_key(name) {
+        verify_cached(cache_dir, name)?;
+        return Ok(cache_dir.to_path_buf());
+    }
+    Err(format!("template '{name}' not found"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_template(dir: &Path, name: &str, caps: &[&str]) {
+        let t_dir = dir.join(name);
+        fs::create_dir_all(&t_dir).unwrap();
+        let manifest = TemplateManifest {
+            name: name.into(),
+            version: "1.0.0".into(),
+            description: format!("{name} template"),
+            dependencies: vec![],
+            capabilities: caps.iter().map(|c| c.to_string()).collect(),
+            args: BTreeMap::new(),
+        };
+        fs::write(
+            t_dir.join("template.json"),
+            serde_json::to_string_pretty(&manifest).unwrap(),
+        )
+        .unwrap();
+        fs::write(t_dir.join("app.ax.template"), "fn main() -> Int { 0 }\n").unwrap();
+    }
+
+    fn index_for(dir: &Path, names: &[&str]) -> PackIndex {
+        let templates = names
+            .iter()
+            .map(|name| {
+                let mut files = BTreeMap::new();
+                read_files(&dir.join(name), "", &mut files).unwrap();
+                IndexEntry {
+                    name: name.to_string(),
+                    sha256: pack_hash(&files),
+                    files: files.keys().cloned().collect(),
+                }
+            })
+            .collect();
+        PackIndex { templates }
+    }
+
+    #[test]
+    fn index_pack_installs_with_provenance_and_verifies() {
+        let remote = tempfile::tempdir().unwrap();
+        write_template(remote.path(), "billing", &["db.query"]);
+        let index = index_for(remote.path(), &["billing"]);
+        let index_path = remote.path().join("index.json");
+        fs::write(&index_path, serde_json::to_string(&index).unwrap()).unwrap();
+
+        let source = format!("file://{}", index_path.display());
+        let pack = fetch(&source).unwrap();
+        assert_eq!(pack.templates.len(), 1);
+        assert_eq!(pack.templates[0].manifest.capabilities, vec!["db.query"]);
+
+        let cache = tempfile::tempdir().unwrap();
+        assert_eq!(install(cache.path(), &pack).unwrap(), vec!["billing"]);
+        let cached = load_cache_index(cache.path()).unwrap();
+        assert_eq!(cached.templates["billing"].source, source);
+        assert_eq!(
+            cached.templates["billing"].sha256,
+            index.templates[0].sha256
+        );
+
+        let local = tempfile::tempdir().unwrap();
+        assert_eq!(
+            resolve_template_dir(local.path(), cache.path(), "billing").unwrap(),
+            cache.path()
+        );
+
+        fs::write(cache.path().join("billing/app.ax.template"), "tampered").unwrap();
+        let err = resolve_template_dir(local.path(), cache.path(), "billing").unwrap_err();
+        assert!(err.contains("integrity check"), "got: {err}");
+    }
+
+    #[test]
+    fn index_hash_mismatch_is_rejected() {
+        let remote = tempfile::tempdir().unwrap();
+        write_template(remote.path(), "billing", &[]);
+        let mut index = index_for(remote.path(), &["billing"]);
+        index.templates[0].sha256 = pack_hash(&BTreeMap::new());
+        let index_path = remote.path().join("index.json");
+        fs::write(&index_path, serde_json::to_string(&index).unwrap()).unwrap();
+
+        let err = fetch(&index_path.to_string_lossy()).unwrap_err();
+        assert!(err.contains("fails its integrity check"), "got: {err}");
+    }
+
+    #[test]
+    fn index_file_paths_cannot_escape_the_template() {
+        let remote = tempfile::tempdir().unwrap();
+        let index = PackIndex {
+            templates: vec![IndexEntry {
+                name: "evil".into(),
+                sha256: pack_hash(&BTreeMap::new()),
+                files: vec!["../../secret".into()],
+            }],
+        };
+        let index_path = remote.path().join("index.json");
+        fs::write(&index_path, serde_json::to_string(&index).unwrap()).unwrap();
+
+        let err = fetch(&index_path.to_string_lossy()).unwrap_err();
+        assert!(err.contains("invalid template file path"), "got: {err}");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn pack_symlinks_are_rejected() {
+        let outside = tempfile::tempdir().unwrap();
+        let secret = outside.path().join("credentials");
+        fs::write(&secret, "aws_secret_access_key = hunter2").unwrap();
+
+        let pack = tempfile::tempdir().unwrap();
+        write_template(pack.path(), "billing", &[]);
+        std::os::unix::fs::symlink(&secret, pack.path().join("billing/secret.template")).unwrap();
+        let err = read_pack_dir(pack.path()).unwrap_err();
+        assert!(err.contains("symlinks are not allowed"), "got: {err}");
+
+        // A directory pointing back up would otherwise recurse forever.
+        fs::remove_file(pack.path().join("billing/secret.template")).unwrap();
+        std::os::unix::fs::symlink("..", pack.path().join("billing/loop")).unwrap();
+        let err = read_pack_dir(pack.path()).unwrap_err();
+        assert!(err.contains("symlinks are not allowed"), "got: {err}");
+    }
+
+    #[test]
+    fn local_templates_shadow_cached_ones() {
+        let local = tempfile::tempdir().unwrap();
+        write_template(local.path(), "billing", &[]);
+        let cache = tempfile::tempdir().unwrap();
+        assert_eq!(
+            resolve_template_dir(local.path(), cache.path(), "billing").unwrap(),
+            local.path()
+        );
+        let err = resolve_template_dir(local.path(), cache.path(), "missing").unwrap_err();
+        assert!(err.contains("not found"), "got: {err}");
+    }
+
+    #[test]
+    fn source_kind_follows_the_suffix() {
+        assert_eq!(
+            TemplateSource::parse("https://example.com/packs/index.json"),
+            TemplateSource::Index("https://example.com/packs/index.json".into())
+        );
+        assert_eq!(
+            TemplateSource::parse("https://example.com/team/templates.git"),
+            TemplateSource::Git("https://example.com/team/templates.git".into())
+        );
+    }
+}
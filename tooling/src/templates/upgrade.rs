@@ -0,0 +1,418 @@
+//! Re-applying a newer template version to a generated file.
+//!
+//! [`apply_template`](super::apply_template) stamps its output with a
+//! first-line comment recording the template, version and args that
+//! produced it, plus the hash of the pristine output (the *base*):
+//!
+//! ```text
+//! // boruna-template: {"template":"form-basic","version":"0.1.0","args":{...},"base":"sha256:…"}
+//! ```
+//!
+//! The base itself is kept in a content-addressed store (by default
+//! [`DEFAULT_BASE_DIR`]) when the file is written. [`upgrade`] regenerates
+//! the file from the new template version with the stamped args and
+//! three-way merges it with the user's copy against that base: a hunk
+//! changed on one side only takes that side, identical changes merge, and
+//! hunks changed differently on both sides become conflicts, written with
+//! `<<<<<<<` / `=======` / `>>>>>>>` markers and reported as
+//! [`E016`](crate::diagnostics::E016_TEMPLATE_MERGE_CONFLICT) diagnostics.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use boruna_hash::HashAlgorithm;
+use serde::{Deserialize, Serialize};
+
+use crate::diagnostics::{Diagnostic, DiagnosticSet, SourceLocation, E016_TEMPLATE_MERGE_CONFLICT};
+
+/// Base store used when none is given, relative to the working directory.
+pub const DEFAULT_BASE_DIR: &str = ".boruna/template-base";
+
+/// Prefix of the stamp comment on the first line of generated output.
+pub const STAMP_PREFIX: &str = "// boruna-template: ";
+
+/// Which template, version and args produced a file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TemplateStamp {
+    pub template: String,
+    pub version: String,
+    pub args: BTreeMap<String, String>,
+    /// Hash of the generated output without the stamp line.
+    pub base: String,
+}
+
+impl TemplateStamp {
+    /// The stamp comment line, without a trailing newline.
+    pub fn to_line(&self) -> String {
+        let json = serde_json::to_string(self).expect("stamp serializes");
+        format!("{STAMP_PREFIX}{json}")
+    }
+}
+
+/// Hash `body` as recorded in a stamp's `base`.
+pub fn base_hash(body: &str) -> String {
+    HashAlgorithm::Sha256.digest(body).to_string()
+}
+
+/// Split `source` into its stamp and the body after the stamp line.
+/// Returns `None` if the first line is not a stamp.
+pub fn split_stamp(source: &str) -> Option<(TemplateStamp, &str)> {
+    let (first, body) = source.split_once('\n').unwrap_or((source, ""));
+    let json = first.strip_prefix(STAMP_PREFIX)?;
+    let stamp = serde_json::from_str(json.trim_end_matches('\r')).ok()?;
+    Some((stamp, body))
+}
+
+/// Save the body of stamped `source` into `base_dir` so a later
+/// [`upgrade`] can merge against it.
+pub fn store_base(base_dir: &Path, source: &str) -> Result<(), String> {
+    let (stamp, body) =
+        split_stamp(source).ok_or_else(|| "source has no template stamp".to_string())?;
+    let path = base_path(base_dir, &stamp.base)?;
+    if path.exists() {
+        return Ok(());
+    }
+    fs::create_dir_all(base_dir).map_err(|e| format!("create {}: {e}", base_dir.display()))?;
+    fs::write(&path, body).map_err(|e| format!("write {}: {e}", path.display()))
+}
+
+fn base_path(base_dir: &Path, hash: &str) -> Result<PathBuf, String> {
+    let digest = boruna_hash::Digest::parse(hash).map_err(|e| format!("bad base hash: {e}"))?;
+    Ok(base_dir.join(format!("{}.ax", digest.hex())))
+}
+
+fn load_base(base_dir: &Path, hash: &str) -> Result<String, String> {
+    let path = base_path(base_dir, hash)?;
+    let body = fs::read_to_string(&path).map_err(|e| {
+        format!(
+            "base output {hash} not found in {} ({e}); it is saved when the file is generated",
+            base_dir.display()
+        )
+    })?;
+    if base_hash(&body) != hash {
+        return Err(format!("{} does not hash to {hash}", path.display()));
+    }
+    Ok(body)
+}
+
+/// Result of [`upgrade`].
+#[derive(Debug, Clone)]
+pub struct UpgradeOutcome {
+    /// The merged file, stamped with the new version. Contains conflict
+    /// markers if `conflicts` has errors.
+    pub source: String,
+    /// The new version's pristine output, stamped; store it with
+    /// [`store_base`] once `source` is written.
+    pub new_base: String,
+    /// One E016 diagnostic per conflicting hunk.
+    pub conflicts: DiagnosticSet,
+}
+
+/// Upgrade `file`, whose current contents are `source`, to
+/// `template@version`, regenerated with the stamped args and merged
+/// against the stamped base.
+pub fn upgrade(
+    file: &str,
+    source: &str,
+    template: &str,
+    version: &str,
+    dirs: &UpgradeDirs,
+) -> Result<UpgradeOutcome, String> {
+    let (stamp, ours) = split_stamp(source).ok_or_else(|| {
+        format!("{file} has no template stamp; it was not generated by `boruna template apply`")
+    })?;
+    if stamp.template != template {
+        return Err(format!(
+            "{file} was generated from template '{}', not '{template}'",
+            stamp.template
+        ));
+    }
+    let dir = super::remote::resolve_template_dir(dirs.templates, dirs.cache, template)?;
+    let manifest = super::load_template(&dir, template)?;
+    if manifest.version != version {
+        return Err(format!(
+            "template '{template}' is at version {}, not {version}",
+            manifest.version
+        ));
+    }
+    let base = load_base(dirs.base, &stamp.base)?;
+    let new_base = super::apply_template(&dir, template, &stamp.args)?.source;
+    let (new_stamp, theirs) = split_stamp(&new_base).expect("apply_template stamps its output");
+
+    let theirs_label = format!("{template}@{version}");
+    let merged = merge3(&base, ours, theirs);
+    let mut body = String::new();
+    let mut conflicts = DiagnosticSet::new(file);
+    // Line 1 is the stamp.
+    let mut line = 2;
+    for hunk in merged {
+        match hunk {
+            Hunk::Clean(lines) => {
+                line += lines.len();
+                push_lines(&mut body, &lines);
+            }
+            Hunk::Conflict { ours, theirs } => {
+                let start = line;
+                body.push_str("<<<<<<< yours\n");
+                push_lines(&mut body, &ours);
+                body.push_str("=======\n");
+                push_lines(&mut body, &theirs);
+                body.push_str(&format!(">>>>>>> {theirs_label}\n"));
+                line += ours.len() + theirs.len() + 3;
+                let mut diag = Diagnostic::error(
+                    E016_TEMPLATE_MERGE_CONFLICT,
+                    format!(
+                        "template upgrade conflict: your edit and {theirs_label} both change these lines"
+                    ),
+                );
+                diag.location = Some(SourceLocation {
+                    file: file.to_string(),
+                    line: start,
+                    col: None,
+                    end_line: Some(line - 1),
+                    end_col: None,
+                });
+                conflicts.push(diag);
+            }
+        }
+    }
+    Ok(UpgradeOutcome {
+        source: format!("{}\n{body}", new_stamp.to_line()),
+        new_base,
+        conflicts,
+    })
+}
+
+/// Directories [`upgrade`] reads from.
+#[derive(Debug, Clone, Copy)]
+pub struct UpgradeDirs<'a> {
+    pub templates: &'a Path,
+    pub cache: &'a Path,
+    pub base: &'a Path,
+}
+
+fn push_lines(out: &mut String, lines: &[&str]) {
+    for l in lines {
+        out.push_str(l);
+        out.push('\n');
+    }
+}
+
+/// A stretch of three-way merge output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Hunk<'a> {
+    Clean(Vec<&'a str>),
+    Conflict {
+        ours: Vec<&'a str>,
+        theirs: Vec<&'a str>,
+    },
+}
+
+/// Line-based three-way merge of `ours` and `theirs` against `base`.
+pub fn merge3<'a>(base: &'a str, ours: &'a str, theirs: &'a str) -> Vec<Hunk<'a>> {
+    let base: Vec<&str> = base.lines().collect();
+    let ours: Vec<&str> = ours.lines().collect();
+    let theirs: Vec<&str> = theirs.lines().collect();
+    let to_ours = lcs_matches(&base, &ours);
+    let to_theirs = lcs_matches(&base, &theirs);
+
+    let mut hunks = Vec::new();
+    let mut clean: Vec<&str> = Vec::new();
+    let (mut b, mut o, mut t) = (0, 0, 0);
+    loop {
+        // The next base line kept by both sides, or the end of all three.
+        let stable = (b..base.len()).find_map(|k| Some((k, to_ours[k]?, to_theirs[k]?)));
+        let (kb, ko, kt) = stable.unwrap_or((base.len(), ours.len(), theirs.len()));
+        let (bc, oc, tc) = (&base[b..kb], &ours[o..ko], &theirs[t..kt]);
+        if oc == bc {
+            clean.extend_from_slice(tc);
+        } else if tc == bc || oc == tc {
+            clean.extend_from_slice(oc);
+        } else {
+            hunks.push(Hunk::Clean(std::mem::take(&mut clean)));
+            hunks.push(Hunk::Conflict {
+                ours: oc.to_vec(),
+                theirs: tc.to_vec(),
+            });
+        }
+        if stable.is_none() {
+            break;
+        }
+        clean.push(base[kb]);
+        (b, o, t) = (kb + 1, ko + 1, kt + 1);
+    }
+    hunks.push(Hunk::Clean(clean));
+    hunks.retain(|h| !matches!(h, Hunk::Clean(lines) if lines.is_empty()));
+    hunks
+}
+
+/// For each line of `a`, the index of the line of `b` it is matched to in
+/// a longest common subsequence.
+fn lcs_matches(a: &[&str], b: &[&str]) -> Vec<Option<usize>> {
+    let (n, m) = (a.len(), b.len());
+    // len[i][j] = LCS length of a[i..] and b[j..].
+    let mut len = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            len[i][j] = if a[i] == b[j] {
+                len[i + 1][j + 1] + 1
+            } else {
+                len[i + 1][j].max(len[i][j + 1])
+            };
+        }
+    }
+    let mut matches = vec![None; n];
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if a[i] == b[j] {
+            matches[i] = Some(j);
+            i += 1;
+            j += 1;
+        } else if len[i + 1][j] >= len[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn one_sided_changes_merge_cleanly() {
+        let base = "a\nb\nc\nd\n";
+        let ours = "a\nB (mine)\nc\nd\n";
+        let theirs = "a\nb\nc\nd\ne (new)\n";
+        assert_eq!(
+            merge3(base, ours, theirs),
+            vec![Hunk::Clean(vec!["a", "B (mine)", "c", "d", "e (new)"])]
+        );
+    }
+
+    #[test]
+    fn overlapping_changes_conflict() {
+        let base = "a\nb\nc\n";
+        let ours = "a\nmine\nc\n";
+        let theirs = "a\ntheirs\nc\n";
+        assert_eq!(
+            merge3(base, ours, theirs),
+            vec![
+                Hunk::Clean(vec!["a"]),
+                Hunk::Conflict {
+                    ours: vec!["mine"],
+                    theirs: vec!["theirs"],
+                },
+                Hunk::Clean(vec!["c"]),
+            ]
+        );
+        // The same change on both sides is not a conflict.
+        assert_eq!(
+            merge3(base, ours, ours),
+            vec![Hunk::Clean(vec!["a", "mine", "c"])]
+        );
+    }
+
+    #[test]
+    fn stamp_round_trips() {
+        let stamp = TemplateStamp {
+            template: "demo".into(),
+            version: "1.0.0".into(),
+            args: BTreeMap::from([("entity".into(), "users".into())]),
+            base: base_hash("fn main() -> Int { 0 }\n"),
+        };
+        let source = format!("{}\nfn main() -> Int {{ 0 }}\n", stamp.to_line());
+        let (parsed, body) = split_stamp(&source).unwrap();
+        assert_eq!(parsed, stamp);
+        assert_eq!(body, "fn main() -> Int { 0 }\n");
+        assert!(split_stamp("fn main() -> Int { 0 }\n").is_none());
+    }
+
+    fn write_template(dir: &Path, version: &str, body: &str) {
+        let t_dir = dir.join("demo");
+        fs::create_dir_all(&t_dir).unwrap();
+        let manifest = super::super::TemplateManifest {
+            name: "demo".into(),
+            version: version.into(),
+            description: "Demo".into(),
+            dependencies: vec![],
+            capabilities: vec![],
+            args: BTreeMap::new(),
+        };
+        fs::write(
+            t_dir.join("template.json"),
+            serde_json::to_string(&manifest).unwrap(),
+        )
+        .unwrap();
+        fs::write(t_dir.join("app.ax.template"), body).unwrap();
+    }
+
+    #[test]
+    fn upgrade_keeps_user_edits_and_reports_conflicts() {
+        let templates = tempfile::tempdir().unwrap();
+        let base_dir = tempfile::tempdir().unwrap();
+        let dirs = UpgradeDirs {
+            templates: templates.path(),
+            cache: &templates.path().join("no-cache"),
+            base: base_dir.path(),
+        };
+        write_template(
+            templates.path(),
+            "1.0.0",
+            "// {{entity}}\nfn limit() -> Int { 10 }\nfn main() -> Int { limit() }\n",
+        );
+        let args = BTreeMap::from([("entity".to_string(), "users".to_string())]);
+        let generated = super::super::apply_template(templates.path(), "demo", &args)
+            .unwrap()
+            .source;
+        store_base(base_dir.path(), &generated).unwrap();
+
+        // The user adds a helper; v2 changes the header comment.
+        let edited = generated.replace("fn main()", "fn helper() -> Int { 1 }\nfn main()");
+        write_template(
+            templates.path(),
+            "2.0.0",
+            "// {{entity}} (v2)\nfn limit() -> Int { 10 }\nfn main() -> Int { limit() }\n",
+        );
+        let out = upgrade("app.ax", &edited, "demo", "2.0.0", &dirs).unwrap();
+        assert!(out.conflicts.diagnostics.is_empty());
+        let (stamp, body) = split_stamp(&out.source).unwrap();
+        assert_eq!(stamp.version, "2.0.0");
+        assert_eq!(stamp.args, args);
+        assert_eq!(
+            body,
+            "// users (v2)\nfn limit() -> Int { 10 }\nfn helper() -> Int { 1 }\nfn main() -> Int { limit() }\n"
+        );
+
+        // Both sides change `limit`.
+        let edited = generated.replace("{ 10 }", "{ 20 }");
+        write_template(
+            templates.path(),
+            "3.0.0",
+            "// {{entity}}\nfn limit() -> Int { 50 }\nfn main() -> Int { limit() }\n",
+        );
+        let out = upgrade("app.ax", &edited, "demo", "3.0.0", &dirs).unwrap();
+        assert_eq!(out.conflicts.diagnostics.len(), 1);
+        let diag = &out.conflicts.diagnostics[0];
+        assert_eq!(diag.id, E016_TEMPLATE_MERGE_CONFLICT);
+        let loc = diag.location.as_ref().unwrap();
+        assert_eq!((loc.line, loc.end_line), (3, Some(7)));
+        let lines: Vec<&str> = out.source.lines().collect();
+        assert_eq!(
+            &lines[2..7],
+            &[
+                "<<<<<<< yours",
+                "fn limit() -> Int { 20 }",
+                "=======",
+                "fn limit() -> Int { 50 }",
+                ">>>>>>> demo@3.0.0",
+            ]
+        );
+
+        let err = upgrade("app.ax", &edited, "demo", "9.0.0", &dirs).unwrap_err();
+        assert!(err.contains("is at version 3.0.0"), "got: {err}");
+    }
+}
@@ -29,6 +29,9 @@ fn view(state: State) -> String { \"ok\" }
         .iter()
         .find(|d| d.id == E005_NON_EXHAUSTIVE_MATCH);
     assert!(match_diag.is_some(), "expected E005 diagnostic");
+    // The compiler rejects the match too; its report is folded into the
+    // analyzer's, which carries the fix.
+    assert_eq!(ds.diagnostics.len(), 1, "{:#?}", ds.diagnostics);
 
     let d = match_diag.unwrap();
     assert!(d.message.contains("Clear"), "should mention Clear");
@@ -44,6 +47,44 @@ fn view(state: State) -> String { \"ok\" }
     assert!(patch.description.contains("add missing match arms"));
 }
 
+/// Compiler errors reach the diagnostic with their full range.
+#[test]
+fn test_compile_error_diagnostic_has_end_position() {
+    let source = "fn main() -> Int {\n    let total: Int = 1\n    totl + 1\n}\n";
+    let ds = DiagnosticCollector::new("test.ax", source).collect();
+    let d = ds
+        .diagnostics
+        .iter()
+        .find(|d| d.id == E003_UNDEFINED_VAR)
+        .expect("E003");
+    let loc = d.location.as_ref().unwrap();
+    assert_eq!(
+        (loc.line, loc.col, loc.end_line, loc.end_col),
+        (3, Some(5), Some(3), Some(9))
+    );
+}
+
+/// A typo inside a deep update path is reported as E006 at its line.
+#[test]
+fn test_deep_update_unknown_field_is_e006() {
+    let source = "\
+type User { name: String }
+type State { user: User, count: Int }
+
+fn bump(s: State) -> State {
+    State { ..s, user.nmae: \"b\" }
+}
+";
+    let ds = DiagnosticCollector::new("test.ax", source).collect();
+    assert_eq!(ds.diagnostics.len(), 1, "{:#?}", ds.diagnostics);
+    let d = &ds.diagnostics[0];
+    assert_eq!(d.id, E006_UNKNOWN_FIELD);
+    assert!(d
+        .message
+        .contains("unknown field 'nmae' in record type 'User'"));
+    assert_eq!(d.location.as_ref().map(|l| l.line), Some(5));
+}
+
 /// Integration test: wrong record field -> suggest rename -> repair.
 #[test]
 fn test_e2e_wrong_field_name_repair() {
@@ -59,6 +100,8 @@ fn init() -> State {
 
     let field_diag = ds.diagnostics.iter().find(|d| d.id == E006_UNKNOWN_FIELD);
     assert!(field_diag.is_some(), "expected E006 diagnostic");
+    // The compiler's own unknown-field error is folded into the analyzer's
+    assert_eq!(ds.diagnostics.len(), 1, "{:#?}", ds.diagnostics);
 
     let d = field_diag.unwrap();
     assert!(d.message.contains("countt"));
@@ -502,6 +545,11 @@ fn test_all_templates_compile() {
     args.insert("fields".into(), "name,email".into());
     let r = templates::apply_template(&templates_dir, "form-basic", &args).unwrap();
     templates::validate_template_output(&r.source).unwrap();
+    assert_eq!(r.tests.len(), 1);
+    assert_eq!(r.tests[0].spec.name, "contact_submit");
+    for (test, result) in templates::run_template_tests(&r) {
+        assert!(result.passed, "{}: {result:?}", test.file);
+    }
 
     // auth-app
     let mut args = std::collections::BTreeMap::new();
@@ -537,3 +585,237 @@ fn test_template_determinism() {
         "template output must be deterministic"
     );
 }
+
+// ─── Front-end Fuzzing Tests ────────────────────────────────
+
+/// The seed corpus picks up templates, std libs, and examples.
+#[test]
+fn test_fuzz_seed_corpus_covers_repo_sources() {
+    use crate::fuzz;
+    let root = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("..");
+    let corpus = fuzz::seed_corpus(&root);
+    assert!(corpus.contains_key("templates/crud-admin/app.ax.template"));
+    // Found from anywhere inside the workspace.
+    let nested = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src");
+    assert_eq!(
+        fuzz::seed_corpus(&fuzz::workspace_root(&nested)).len(),
+        corpus.len()
+    );
+    assert!(corpus.keys().any(|k| k.starts_with("libs/std-json/")));
+    assert!(corpus.keys().any(|k| k.starts_with("examples/")));
+}
+
+/// A fixed seed and iteration count replays the same run.
+#[test]
+fn test_fuzz_run_is_reproducible() {
+    use crate::fuzz::{self, FuzzConfig};
+    let corpus = std::collections::BTreeMap::from([(
+        "a.ax".to_string(),
+        "fn main() -> Int { let x = [1, 2]\n x }\n".to_string(),
+    )]);
+    let config = FuzzConfig {
+        duration: std::time::Duration::from_secs(60),
+        max_iterations: Some(300),
+        seed: 7,
+    };
+    let a = fuzz::run(&corpus, &config);
+    let b = fuzz::run(&corpus, &config);
+    assert_eq!(a.iterations, 300);
+    let hashes = |r: &fuzz::FuzzReport| {
+        r.crashes
+            .iter()
+            .map(|c| c.input_sha256.clone())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(hashes(&a), hashes(&b));
+}
+
+/// Garbage that only produces compile errors is not a crash, and a crash
+/// renders as an E010 DiagnosticSet.
+#[test]
+fn test_fuzz_crash_reporting() {
+    use crate::fuzz::{self, FuzzCrash};
+    assert!(fuzz::check_input("fn {{{ ) ] \"").is_none());
+
+    let crash = FuzzCrash {
+        target: "parse",
+        message: "index out of bounds".into(),
+        location: "crates/llmc/src/parser.rs:10:5".into(),
+        input: "fn".into(),
+        input_sha256: "ab".repeat(32),
+    };
+    let set = crash.to_diagnostic_set();
+    assert_eq!(set.file, "crash-abababababab.ax");
+    assert_eq!(set.diagnostics[0].id, E010_COMPILER_PANIC);
+    assert!(set.diagnostics[0].message.contains("parse panicked"));
+
+    // The crash directory only appears once there is a crash to write.
+    let tmp = tempfile::tempdir().unwrap();
+    let out = tmp.path().join("fuzz-crashes");
+    let mut report = fuzz::FuzzReport {
+        seed: 0,
+        iterations: 1,
+        corpus_size: 1,
+        crashes: vec![],
+    };
+    assert!(fuzz::write_artifacts(&report, &out).unwrap().is_empty());
+    assert!(!out.exists());
+    report.crashes.push(crash);
+    assert_eq!(fuzz::write_artifacts(&report, &out).unwrap().len(), 1);
+    assert!(out.join("crash-abababababab.ax").is_file());
+}
+
+// ─── Differential Testing ───────────────────────────────────
+
+/// Every example agrees across the direct and round-trip pipelines.
+#[test]
+fn test_difftest_examples_identical() {
+    use crate::difftest;
+    let examples = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../examples");
+    let report = difftest::run_corpus(&examples, difftest::DEFAULT_MAX_STEPS).unwrap();
+    assert!(report.identical > 0);
+    let diverged: Vec<_> = report
+        .cases
+        .iter()
+        .filter(|c| c.status == difftest::CaseStatus::Diverged)
+        .collect();
+    assert!(report.passed(), "diverged: {diverged:?}");
+}
+
+/// Runtime errors are compared like values; compile errors are skipped.
+#[test]
+fn test_difftest_case_outcomes() {
+    use crate::difftest::{self, CaseStatus};
+    let ok = difftest::run_case("ok.ax", "fn main() -> Int { 40 + 2 }", 1000);
+    assert_eq!(ok.status, CaseStatus::Identical);
+    assert_eq!(ok.runs.len(), 3);
+    assert!(ok.runs.iter().all(|r| r.outcome == "ok: Int(42)"));
+
+    let looping = difftest::run_case(
+        "loop.ax",
+        "fn main() -> Int { let i = 0\n while true { i = i + 1 }\n i }",
+        100,
+    );
+    assert_eq!(looping.status, CaseStatus::Identical);
+    assert!(looping.runs[0].outcome.starts_with("error:"));
+
+    let bad = difftest::run_case("bad.ax", "fn main( {", 1000);
+    assert_eq!(bad.status, CaseStatus::Skipped);
+}
+
+// ─── Capability Matrix ──────────────────────────────────────
+
+const CAPMATRIX_APP: &str = r#"type State { data: String, n: Int }
+type Msg { tag: String, payload: String }
+type Effect { kind: String, payload: String, callback_tag: String }
+type UpdateResult { state: State, effects: List<Effect> }
+type UINode { tag: String, text: String }
+
+fn init() -> State { State { data: "", n: 0 } }
+
+fn fetch_effect(url: String) -> Effect {
+    Effect { kind: "http_request", payload: url, callback_tag: "fetched" }
+}
+
+fn update(state: State, msg: Msg) -> UpdateResult {
+    if msg.tag == "fetch" && state.n < 3 {
+        UpdateResult { state: state, effects: [fetch_effect("https://example.com")] }
+    } else {
+        match msg.tag {
+            "save" => UpdateResult { state: state, effects: [Effect { kind: "fs_write", payload: "x", callback_tag: "saved" }] },
+            "tick" => UpdateResult { state: state, effects: [Effect { kind: "timer", payload: "", callback_tag: "ticked" }] },
+            _ => UpdateResult { state: State { data: msg.payload, n: state.n + 1 }, effects: [] },
+        }
+    }
+}
+
+fn view(state: State) -> UINode { UINode { tag: "text", text: state.data } }
+"#;
+
+fn capmatrix_row<'a>(
+    matrix: &'a crate::capmatrix::CapabilityMatrix,
+    tag: &str,
+) -> &'a crate::capmatrix::MessageRow {
+    matrix
+        .messages
+        .iter()
+        .find(|r| r.tag == tag)
+        .unwrap_or_else(|| panic!("no row for {tag}: {matrix:?}"))
+}
+
+/// Each tag reaches only the effects of its own branch.
+#[test]
+fn test_capmatrix_separates_message_tags() {
+    use crate::capmatrix::{self, SiteKind, OTHER_TAG};
+    let module = boruna_compiler::compile("app", CAPMATRIX_APP).unwrap();
+    let matrix = capmatrix::analyze(&module).unwrap();
+
+    let tags: Vec<&str> = matrix.messages.iter().map(|r| r.tag.as_str()).collect();
+    assert_eq!(tags, vec!["fetch", "save", "tick", OTHER_TAG]);
+
+    // "fetch" also falls through to the match when `state.n >= 3`.
+    let fetch = capmatrix_row(&matrix, "fetch");
+    assert_eq!(fetch.capabilities, vec!["net.fetch"]);
+    assert!(fetch.exact);
+    assert_eq!(fetch.sites[0].function, "fetch_effect");
+    assert_eq!(fetch.sites[0].kind, SiteKind::Effect);
+
+    assert_eq!(
+        capmatrix_row(&matrix, "save").capabilities,
+        vec!["fs.write"]
+    );
+    assert_eq!(
+        capmatrix_row(&matrix, "tick").capabilities,
+        vec!["time.now"]
+    );
+    assert!(capmatrix_row(&matrix, OTHER_TAG).capabilities.is_empty());
+
+    assert_eq!(
+        matrix.messages_reaching(&boruna_bytecode::Capability::NetFetch),
+        vec!["fetch"]
+    );
+}
+
+/// The generated policy allows exactly the reachable capabilities.
+#[test]
+fn test_capmatrix_policy_is_tightest() {
+    use crate::capmatrix;
+    let module = boruna_compiler::compile("app", CAPMATRIX_APP).unwrap();
+    let policy = capmatrix::analyze(&module).unwrap().to_policy();
+    assert!(!policy.default_allow);
+    let allowed: Vec<&str> = policy.rules.keys().map(String::as_str).collect();
+    assert_eq!(allowed, vec!["fs.write", "net.fetch", "time.now"]);
+}
+
+/// Direct capability calls count, and recursion falls back to the
+/// conservative scan.
+#[test]
+fn test_capmatrix_cap_calls_and_recursion() {
+    use crate::capmatrix::{self, SiteKind};
+    let src = r#"type Msg { tag: String, payload: String }
+fn init() -> Int { 0 }
+fn ping(n: Int) -> String !{step.input} {
+    if n > 0 { ping(n - 1) } else { step_input("seed") }
+}
+fn update(state: Int, msg: Msg) -> String !{step.input} {
+    if msg.tag == "ping" { ping(state) } else { "" }
+}
+fn view(state: Int) -> Int { state }
+"#;
+    let module = boruna_compiler::compile("app", src).unwrap();
+    let matrix = capmatrix::analyze(&module).unwrap();
+    let ping = capmatrix_row(&matrix, "ping");
+    assert_eq!(ping.capabilities, vec!["step.input"]);
+    assert_eq!(ping.sites[0].kind, SiteKind::CapCall);
+    assert!(!ping.exact, "recursive call should be conservative");
+    assert!(capmatrix_row(&matrix, capmatrix::OTHER_TAG)
+        .capabilities
+        .is_empty());
+}
+
+/// Modules without `update` have no matrix.
+#[test]
+fn test_capmatrix_requires_update() {
+    let module = boruna_compiler::compile("m", "fn main() -> Int { 1 }").unwrap();
+    assert!(crate::capmatrix::analyze(&module).is_none());
+}
@@ -199,6 +199,10 @@ impl From<&Value> for ItfValue {
             // formatting Boruna uses for Display. Round-trip-identifiable
             // because no other Boruna value emits this exact pattern.
             Value::Float(f) => ItfValue::Str(f.to_string()),
+            Value::Decimal(d) => ItfValue::Str(d.to_string()),
+            Value::BigInt(n) => ItfValue::BigInt {
+                value: n.to_string(),
+            },
             Value::String(s) => ItfValue::Str(s.clone()),
             Value::None => record_with_tag("None", None),
             Value::Some(inner) => record_with_tag("Some", Some(inner.as_ref())),
@@ -238,6 +242,23 @@ impl From<&Value> for ItfValue {
             Value::FnRef(idx) => ItfValue::Unserializable {
                 descriptor: format!("FnRef:{idx}"),
             },
+            Value::CapHandle(id) => ItfValue::Unserializable {
+                descriptor: format!("CapHandle:{id}"),
+            },
+            // A table is its columns by name, each a tuple of cells.
+            Value::Table(t) => ItfValue::Record(
+                t.columns()
+                    .iter()
+                    .map(|c| {
+                        (
+                            c.name.clone(),
+                            ItfValue::Tup {
+                                items: c.values.iter().map(ItfValue::from).collect(),
+                            },
+                        )
+                    })
+                    .collect(),
+            ),
         }
     }
 }
@@ -0,0 +1,368 @@
+//! Delta encoding of `state_after` in serialized traces.
+//!
+//! A trace stores the state after every cycle, so a large state repeated
+//! over thousands of cycles dominates the file. On disk, a cycle carries
+//! either the full `state_after` (a *keyframe*) or a `state_delta`: the
+//! JSON Patch (RFC 6902 `add`/`remove`/`replace`) that turns the previous
+//! cycle's state into this one. The first cycle and every
+//! [`KEYFRAME_INTERVAL`]th cycle are keyframes, as is any cycle whose
+//! delta would be no smaller than its state, so a reader never applies
+//! more than `KEYFRAME_INTERVAL - 1` patches in a row.
+//!
+//! Encoding happens in `TraceFile`'s `Serialize` and decoding in its
+//! `Deserialize`: in memory every [`TraceCycle`] holds its full state,
+//! hashes are computed over the full state, and traces written before
+//! delta encoding (all keyframes) load unchanged.
+
+use boruna_bytecode::Value;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as Json;
+
+use super::{TraceCycle, TraceEffect, TraceFile, TraceMessage, TRACE_VERSION};
+
+/// Cycles between full `state_after` keyframes.
+pub const KEYFRAME_INTERVAL: usize = 32;
+
+/// One JSON Patch operation. `path` is a JSON Pointer (RFC 6901).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum PatchOp {
+    Add { path: String, value: Json },
+    Remove { path: String },
+    Replace { path: String, value: Json },
+}
+
+/// The operations that turn `from` into `to`. Objects are diffed by key
+/// and lists by index, with elements appended or removed at the end;
+/// anything else that differs is replaced whole.
+pub fn diff(from: &Json, to: &Json) -> Vec<PatchOp> {
+    let mut ops = Vec::new();
+    diff_into(from, to, &mut String::new(), &mut ops);
+    ops
+}
+
+fn diff_into(from: &Json, to: &Json, path: &mut String, ops: &mut Vec<PatchOp>) {
+    if from == to {
+        return;
+    }
+    match (from, to) {
+        (Json::Object(a), Json::Object(b)) => {
+            for key in a.keys().filter(|k| !b.contains_key(*k)) {
+                ops.push(PatchOp::Remove {
+                    path: child(path, key),
+                });
+            }
+            for (key, value) in b {
+                match a.get(key) {
+                    Some(old) => {
+                        let len = path.len();
+                        path.push('/');
+                        path.push_str(&escape(key));
+                        diff_into(old, value, path, ops);
+                        path.truncate(len);
+                    }
+                    None => ops.push(PatchOp::Add {
+                        path: child(path, key),
+                        value: value.clone(),
+                    }),
+                }
+            }
+        }
+        (Json::Array(a), Json::Array(b)) => {
+            for (i, (old, new)) in a.iter().zip(b).enumerate() {
+                let len = path.len();
+                path.push_str(&format!("/{i}"));
+                diff_into(old, new, path, ops);
+                path.truncate(len);
+            }
+            for (i, value) in b.iter().enumerate().skip(a.len()) {
+                ops.push(PatchOp::Add {
+                    path: format!("{path}/{i}"),
+                    value: value.clone(),
+                });
+            }
+            for i in (b.len()..a.len()).rev() {
+                ops.push(PatchOp::Remove {
+                    path: format!("{path}/{i}"),
+                });
+            }
+        }
+        _ => ops.push(PatchOp::Replace {
+            path: path.clone(),
+            value: to.clone(),
+        }),
+    }
+}
+
+/// Apply `ops` to `target` in order.
+pub fn apply(target: &mut Json, ops: &[PatchOp]) -> Result<(), String> {
+    for op in ops {
+        match op {
+            PatchOp::Replace { path, value } => *locate(target, path)? = value.clone(),
+            PatchOp::Add { path, value } => {
+                let (parent, key) = split(path)?;
+                match locate(target, parent)? {
+                    Json::Object(map) => {
+                        map.insert(key, value.clone());
+                    }
+                    Json::Array(list) => {
+                        let i = if key == "-" {
+                            list.len()
+                        } else {
+                            index(&key, path)?
+                        };
+                        if i > list.len() {
+                            return Err(format!("patch path `{path}`: index out of range"));
+                        }
+                        list.insert(i, value.clone());
+                    }
+                    _ => return Err(format!("patch path `{path}`: parent is not a container")),
+                }
+            }
+            PatchOp::Remove { path } => {
+                let (parent, key) = split(path)?;
+                let removed = match locate(target, parent)? {
+                    Json::Object(map) => map.remove(&key).is_some(),
+                    Json::Array(list) => {
+                        let i = index(&key, path)?;
+                        i < list.len() && {
+                            list.remove(i);
+                            true
+                        }
+                    }
+                    _ => false,
+                };
+                if !removed {
+                    return Err(format!("patch path `{path}`: nothing to remove"));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn child(path: &str, key: &str) -> String {
+    format!("{path}/{}", escape(key))
+}
+
+fn escape(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+fn unescape(token: &str) -> String {
+    token.replace("~1", "/").replace("~0", "~")
+}
+
+fn index(token: &str, path: &str) -> Result<usize, String> {
+    token
+        .parse()
+        .map_err(|_| format!("patch path `{path}`: `{token}` is not a list index"))
+}
+
+/// Split a pointer into its parent pointer and unescaped last token.
+fn split(path: &str) -> Result<(&str, String), String> {
+    let at = path
+        .rfind('/')
+        .ok_or_else(|| format!("patch path `{path}` has no parent"))?;
+    Ok((&path[..at], unescape(&path[at + 1..])))
+}
+
+fn locate<'a>(target: &'a mut Json, path: &str) -> Result<&'a mut Json, String> {
+    if path.is_empty() {
+        return Ok(target);
+    }
+    let rest = path
+        .strip_prefix('/')
+        .ok_or_else(|| format!("patch path `{path}` must start with `/`"))?;
+    let mut node = target;
+    for token in rest.split('/') {
+        let token = unescape(token);
+        node = match node {
+            Json::Object(map) => map.get_mut(&token),
+            Json::Array(list) => list.get_mut(index(&token, path)?),
+            _ => None,
+        }
+        .ok_or_else(|| format!("patch path `{path}` does not exist"))?;
+    }
+    Ok(node)
+}
+
+// ─── Wire format ──────────────────────────────────────────────
+
+/// [`TraceFile`] as written to disk.
+#[derive(Serialize, Deserialize)]
+pub(super) struct WireTraceFile {
+    version: u32,
+    source_file: String,
+    source_hash: String,
+    cycles: Vec<WireCycle>,
+    final_state_hash: String,
+    trace_hash: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct WireCycle {
+    cycle: u64,
+    message: TraceMessage,
+    state_before_hash: String,
+    state_after_hash: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    state_after: Option<Json>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    state_delta: Option<Vec<PatchOp>>,
+    effects: Vec<TraceEffect>,
+    ui_tree_hash: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ui_tree: Option<Value>,
+}
+
+impl From<TraceFile> for WireTraceFile {
+    fn from(trace: TraceFile) -> Self {
+        let mut previous: Option<Json> = None;
+        let cycles = trace
+            .cycles
+            .into_iter()
+            .enumerate()
+            .map(|(i, c)| {
+                let delta = previous
+                    .as_ref()
+                    .filter(|_| i % KEYFRAME_INTERVAL != 0)
+                    .map(|prev| diff(prev, &c.state_after))
+                    .filter(|ops| serialized_len(ops) < serialized_len(&c.state_after));
+                previous = Some(c.state_after.clone());
+                let (state_after, state_delta) = match delta {
+                    Some(ops) => (None, Some(ops)),
+                    None => (Some(c.state_after), None),
+                };
+                WireCycle {
+                    cycle: c.cycle,
+                    message: c.message,
+                    state_before_hash: c.state_before_hash,
+                    state_after_hash: c.state_after_hash,
+                    state_after,
+                    state_delta,
+                    effects: c.effects,
+                    ui_tree_hash: c.ui_tree_hash,
+                    ui_tree: c.ui_tree,
+                }
+            })
+            .collect();
+        WireTraceFile {
+            version: trace.version,
+            source_file: trace.source_file,
+            source_hash: trace.source_hash,
+            cycles,
+            final_state_hash: trace.final_state_hash,
+            trace_hash: trace.trace_hash,
+        }
+    }
+}
+
+impl TryFrom<WireTraceFile> for TraceFile {
+    type Error = String;
+
+    fn try_from(wire: WireTraceFile) -> Result<Self, String> {
+        if wire.version != TRACE_VERSION {
+            return Err(format!(
+                "trace version {} is not {TRACE_VERSION}; read it with `trace2tests::load_trace`",
+                wire.version
+            ));
+        }
+        let mut previous: Option<Json> = None;
+        let mut cycles = Vec::with_capacity(wire.cycles.len());
+        for c in wire.cycles {
+            let state_after = match (c.state_after, c.state_delta) {
+                (Some(state), None) => state,
+                (None, Some(ops)) => {
+                    let mut state = previous.take().ok_or_else(|| {
+                        format!("cycle {}: state_delta without a previous state", c.cycle)
+                    })?;
+                    apply(&mut state, &ops).map_err(|e| format!("cycle {}: {e}", c.cycle))?;
+                    state
+                }
+                // `null` deserializes as `None`.
+                (None, None) => Json::Null,
+                (Some(_), Some(_)) => {
+                    return Err(format!(
+                        "cycle {}: both state_after and state_delta",
+                        c.cycle
+                    ))
+                }
+            };
+            previous = Some(state_after.clone());
+            cycles.push(TraceCycle {
+                cycle: c.cycle,
+                message: c.message,
+                state_before_hash: c.state_before_hash,
+                state_after_hash: c.state_after_hash,
+                state_after,
+                effects: c.effects,
+                ui_tree_hash: c.ui_tree_hash,
+                ui_tree: c.ui_tree,
+            });
+        }
+        Ok(TraceFile {
+            version: wire.version,
+            source_file: wire.source_file,
+            source_hash: wire.source_hash,
+            cycles,
+            final_state_hash: wire.final_state_hash,
+            trace_hash: wire.trace_hash,
+        })
+    }
+}
+
+fn serialized_len<T: Serialize + ?Sized>(value: &T) -> usize {
+    serde_json::to_string(value).map_or(usize::MAX, |s| s.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn roundtrip(from: Json, to: Json) {
+        let ops = diff(&from, &to);
+        let mut patched = from.clone();
+        apply(&mut patched, &ops).unwrap();
+        assert_eq!(patched, to, "ops: {ops:?}");
+    }
+
+    #[test]
+    fn diff_then_apply_reproduces_the_target() {
+        roundtrip(
+            json!({"a": 1, "b": [1, 2]}),
+            json!({"a": 2, "b": [1, 2, 3]}),
+        );
+        roundtrip(
+            json!({"a": 1, "b": [1, 2, 3]}),
+            json!({"b": [1], "c": null}),
+        );
+        roundtrip(json!({"a/b": {"~x": 1}}), json!({"a/b": {"~x": 2}}));
+        roundtrip(json!([1, {"x": 1}]), json!("replaced"));
+        roundtrip(json!({"Int": 1}), json!({"Int": 1}));
+    }
+
+    #[test]
+    fn small_change_in_large_list_is_a_single_op() {
+        let from: Json = (0..1000).collect::<Vec<_>>().into();
+        let mut to = from.clone();
+        to[500] = json!(-1);
+        assert_eq!(
+            diff(&from, &to),
+            [PatchOp::Replace {
+                path: "/500".into(),
+                value: json!(-1)
+            }]
+        );
+    }
+
+    #[test]
+    fn bad_patch_is_reported() {
+        let mut target = json!({"a": [1]});
+        let ops = [PatchOp::Remove {
+            path: "/a/3".into(),
+        }];
+        assert!(apply(&mut target, &ops).is_err());
+    }
+}
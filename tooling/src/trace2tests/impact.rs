@@ -0,0 +1,473 @@
+//! `trace2tests affected`: the test specs a patch bundle could change.
+//!
+//! Each spec replays one app source. The source and the libraries it
+//! imports (`import "name"` → `<libs>/<name>/src/core.ax`, followed
+//! transitively) form its module graph. A bundle affects a spec when it
+//! touches, somewhere in that graph, a function reachable from the App
+//! protocol entry points (`init`, `update`, `view`, `policies`), or a line
+//! outside any function (types, imports, new functions). Hunks that only
+//! change comments or blank lines affect nothing.
+//!
+//! The analysis errs toward running a spec:
+//! - a spec whose `source_hash` no longer matches its source, or whose
+//!   source cannot be read or lexed, is affected;
+//! - a patched spec file affects that spec;
+//! - a patched file that is neither `.ax` source nor documentation (`.md`,
+//!   `.txt`) may change the compiler or runtime, so it affects every spec.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use boruna_compiler::lexer::{lex_full, TokenKind};
+use boruna_orchestrator::patch::{FilePatch, PatchBundle};
+use serde::Serialize;
+
+use super::suite::{discover_specs, spec_source_path};
+use super::{sha256_hex, TestSpec};
+use crate::import_resolver::parse_import_line;
+
+/// Functions the framework runtime calls.
+const ENTRY_POINTS: &[&str] = &["init", "update", "view", "policies"];
+
+/// Where bundle paths and imports are resolved.
+#[derive(Debug, Clone)]
+pub struct ImpactOptions {
+    /// Directory the bundle's file paths are relative to.
+    pub root: PathBuf,
+    /// Directory holding imported libraries.
+    pub libs_dir: PathBuf,
+}
+
+/// The specs a bundle could affect.
+#[derive(Debug, Clone, Serialize)]
+pub struct ImpactReport {
+    pub bundle_id: String,
+    /// Affected specs, by path, with why.
+    pub affected: Vec<AffectedSpec>,
+    /// Specs the bundle cannot change.
+    pub unaffected: Vec<String>,
+}
+
+/// A spec the bundle could change.
+#[derive(Debug, Clone, Serialize)]
+pub struct AffectedSpec {
+    pub path: String,
+    pub reasons: Vec<String>,
+}
+
+/// A function's lines and the names it refers to.
+struct FnSpan {
+    name: String,
+    start: usize,
+    end: usize,
+    refs: BTreeSet<String>,
+}
+
+/// What a bundle touches in one `.ax` file.
+enum Touched {
+    /// Only these functions.
+    Functions(BTreeSet<String>),
+    /// Something outside a function, at this line.
+    TopLevel(usize),
+}
+
+/// Find the specs under `specs_dir` that `bundle` could affect.
+pub fn affected_specs(
+    bundle: &PatchBundle,
+    specs_dir: &Path,
+    options: &ImpactOptions,
+) -> Result<ImpactReport, String> {
+    let specs = discover_specs(specs_dir)
+        .map_err(|e| format!("cannot read {}: {e}", specs_dir.display()))?;
+
+    let mut global = Vec::new();
+    let mut sources: BTreeMap<PathBuf, &FilePatch> = BTreeMap::new();
+    let mut others: BTreeMap<PathBuf, &str> = BTreeMap::new();
+    for patch in &bundle.patches {
+        let path = normalize(&options.root.join(&patch.file));
+        match Path::new(&patch.file).extension().and_then(|e| e.to_str()) {
+            Some("ax") => {
+                sources.insert(path, patch);
+            }
+            Some("md" | "txt") => {}
+            _ if specs.iter().any(|s| normalize(s) == path) => {
+                others.insert(path, &patch.file);
+            }
+            _ => global.push(format!(
+                "{} is not .ax source and may change the toolchain",
+                patch.file
+            )),
+        }
+    }
+
+    let mut report = ImpactReport {
+        bundle_id: bundle.metadata.id.clone(),
+        affected: Vec::new(),
+        unaffected: Vec::new(),
+    };
+    for spec_path in &specs {
+        let rel = spec_path
+            .strip_prefix(specs_dir)
+            .unwrap_or(spec_path)
+            .to_string_lossy()
+            .replace('\\', "/");
+        let mut reasons = global.clone();
+        if let Some(file) = others.get(&normalize(spec_path)) {
+            reasons.push(format!("{file} is the spec itself"));
+        }
+        reasons.extend(spec_reasons(spec_path, &sources, options));
+        if reasons.is_empty() {
+            report.unaffected.push(rel);
+        } else {
+            report.affected.push(AffectedSpec { path: rel, reasons });
+        }
+    }
+    Ok(report)
+}
+
+/// Why the patched `.ax` files affect the spec at `spec_path`.
+fn spec_reasons(
+    spec_path: &Path,
+    sources: &BTreeMap<PathBuf, &FilePatch>,
+    options: &ImpactOptions,
+) -> Vec<String> {
+    let spec: TestSpec = match std::fs::read_to_string(spec_path)
+        .map_err(|e| e.to_string())
+        .and_then(|json| serde_json::from_str(&json).map_err(|e| e.to_string()))
+    {
+        Ok(spec) => spec,
+        Err(e) => return vec![format!("spec cannot be read: {e}")],
+    };
+    let source_path = spec_source_path(spec_path, &spec);
+    let source = match std::fs::read_to_string(&source_path) {
+        Ok(source) => source,
+        Err(e) => return vec![format!("cannot read {}: {e}", source_path.display())],
+    };
+    if sha256_hex(&source) != spec.source_hash {
+        return vec![format!(
+            "{} changed since the spec was recorded",
+            source_path.display()
+        )];
+    }
+
+    // The module graph: every file reachable through imports.
+    let mut files: BTreeMap<PathBuf, String> = BTreeMap::new();
+    let mut pending = vec![(normalize(&source_path), source)];
+    while let Some((path, text)) = pending.pop() {
+        for name in text.lines().filter_map(|l| parse_import_line(l.trim())) {
+            let lib = normalize(&options.libs_dir.join(&name).join("src/core.ax"));
+            if !files.contains_key(&lib) && !pending.iter().any(|(p, _)| *p == lib) {
+                if let Ok(lib_text) = std::fs::read_to_string(&lib) {
+                    pending.push((lib, lib_text));
+                }
+            }
+        }
+        files.insert(path, text);
+    }
+
+    let mut spans: Vec<FnSpan> = Vec::new();
+    let mut touched: Vec<(String, BTreeSet<String>)> = Vec::new();
+    let mut reasons = Vec::new();
+    for (path, text) in &files {
+        let file_spans = match function_spans(text) {
+            Ok(s) => s,
+            Err(e) => {
+                reasons.push(format!("{} cannot be analysed: {e}", path.display()));
+                continue;
+            }
+        };
+        if let Some(patch) = sources.get(path) {
+            match touched_in(patch, &file_spans) {
+                Touched::Functions(names) if names.is_empty() => {}
+                Touched::Functions(names) => touched.push((patch.file.clone(), names)),
+                Touched::TopLevel(line) => reasons.push(format!(
+                    "{} changes line {line} outside a function",
+                    patch.file
+                )),
+            }
+        }
+        spans.extend(file_spans);
+    }
+
+    let reachable = reachable_from_entry_points(&spans);
+    for (file, names) in touched {
+        for name in names.intersection(&reachable) {
+            reasons.push(format!("{file} changes `{name}`"));
+        }
+    }
+    reasons
+}
+
+/// The functions in `source` with their line ranges and references.
+fn function_spans(source: &str) -> Result<Vec<FnSpan>, String> {
+    let tokens = lex_full(source).map_err(|e| e.to_string())?.tokens;
+    let mut spans: Vec<FnSpan> = Vec::new();
+    let mut depth = 0usize;
+    let mut open = false;
+    for (i, token) in tokens.iter().enumerate() {
+        match &token.kind {
+            TokenKind::Fn if depth == 0 => {
+                if let Some(TokenKind::Ident(name)) = tokens.get(i + 1).map(|t| &t.kind) {
+                    spans.push(FnSpan {
+                        name: name.clone(),
+                        start: token.line,
+                        end: token.line,
+                        refs: BTreeSet::new(),
+                    });
+                    open = true;
+                }
+            }
+            TokenKind::LBrace => depth += 1,
+            TokenKind::RBrace => {
+                depth = depth.saturating_sub(1);
+                if depth == 0 && open {
+                    if let Some(span) = spans.last_mut() {
+                        span.end = token.line;
+                    }
+                    open = false;
+                }
+            }
+            TokenKind::Ident(name) if open => {
+                if let Some(span) = spans.last_mut() {
+                    span.refs.insert(name.clone());
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(spans)
+}
+
+/// The functions `patch` changes, judged against the unpatched file.
+fn touched_in(patch: &FilePatch, spans: &[FnSpan]) -> Touched {
+    let mut names = BTreeSet::new();
+    for hunk in &patch.hunks {
+        if only_trivia(&hunk.old_text) && only_trivia(&hunk.new_text) {
+            continue;
+        }
+        let first = hunk.start_line.max(1);
+        let last = first + hunk.old_text.lines().count().max(1) - 1;
+        for line in first..=last {
+            match spans.iter().find(|s| s.start <= line && line <= s.end) {
+                Some(span) => {
+                    names.insert(span.name.clone());
+                }
+                None if hunk.old_text.lines().count() == 0 => return Touched::TopLevel(first),
+                None => {
+                    let text = hunk.old_text.lines().nth(line - first).unwrap_or("");
+                    if !only_trivia(text) {
+                        return Touched::TopLevel(line);
+                    }
+                }
+            }
+        }
+        if names.is_empty() {
+            return Touched::TopLevel(first);
+        }
+    }
+    Touched::Functions(names)
+}
+
+fn only_trivia(text: &str) -> bool {
+    text.lines().all(|l| {
+        let t = l.trim();
+        t.is_empty() || t.starts_with("//")
+    })
+}
+
+fn reachable_from_entry_points(spans: &[FnSpan]) -> BTreeSet<String> {
+    let by_name: BTreeMap<&str, Vec<&FnSpan>> =
+        spans.iter().fold(BTreeMap::new(), |mut map, span| {
+            map.entry(span.name.as_str()).or_default().push(span);
+            map
+        });
+    let mut reachable = BTreeSet::new();
+    let mut pending: Vec<&str> = ENTRY_POINTS.to_vec();
+    while let Some(name) = pending.pop() {
+        let Some(defs) = by_name.get(name) else {
+            continue;
+        };
+        if !reachable.insert(name.to_string()) {
+            continue;
+        }
+        for span in defs {
+            pending.extend(span.refs.iter().map(String::as_str));
+        }
+    }
+    reachable
+}
+
+/// `path` made absolute, without following symlinks that may not exist.
+fn normalize(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| {
+        std::env::current_dir()
+            .map(|cwd| cwd.join(path))
+            .unwrap_or_else(|_| path.to_path_buf())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{generate_test, record_trace};
+    use super::*;
+    use boruna_bytecode::Value;
+    use boruna_framework::runtime::AppMessage;
+    use boruna_orchestrator::patch::{ExpectedChecks, Hunk, PatchMetadata, RiskLevel};
+
+    const APP: &str = r#"import "mathx"
+
+type State { count: Int }
+type Msg { tag: String, payload: Int }
+type Effect { kind: String, payload: String, callback_tag: String }
+type UpdateResult { state: State, effects: List<Effect> }
+type UINode { tag: String, text: String }
+
+fn init() -> State {
+    State { count: 0 }
+}
+
+// Bumps the counter.
+fn update(state: State, msg: Msg) -> UpdateResult {
+    UpdateResult { state: State { count: step(state.count) }, effects: [] }
+}
+
+fn view(state: State) -> UINode {
+    UINode { tag: "text", text: "count" }
+}
+
+fn unused() -> Int {
+    1
+}
+
+fn main() -> Int {
+    0
+}
+"#;
+
+    const LIB: &str =
+        "fn step(n: Int) -> Int {\n    n + 1\n}\n\nfn spare(n: Int) -> Int {\n    n\n}\n";
+
+    fn bundle(patches: Vec<FilePatch>) -> PatchBundle {
+        PatchBundle {
+            version: 1,
+            metadata: PatchMetadata {
+                id: "PB-1".into(),
+                intent: "test".into(),
+                author: "test".into(),
+                timestamp: "2026-01-01T00:00:00Z".into(),
+                touched_modules: vec![],
+                risk_level: RiskLevel::Low,
+            },
+            patches,
+            expected_checks: ExpectedChecks {
+                compile: true,
+                test: true,
+                replay: false,
+                diagnostics_count: None,
+                trace_stability: None,
+                difftest_corpus: vec![],
+                deterministic_build: false,
+            },
+            reviewer_checklist: vec![],
+        }
+    }
+
+    fn patch(file: &str, start_line: usize, old: &str, new: &str) -> FilePatch {
+        FilePatch {
+            file: file.into(),
+            hunks: vec![Hunk {
+                start_line,
+                old_text: old.into(),
+                new_text: new.into(),
+            }],
+        }
+    }
+
+    /// A workspace with the app, its library and one spec; returns the
+    /// affected spec paths for `patches`.
+    fn affected(patches: Vec<FilePatch>) -> Vec<String> {
+        let dir = tempfile::TempDir::new().unwrap();
+        let root = dir.path();
+        std::fs::write(root.join("app.ax"), APP).unwrap();
+        std::fs::create_dir_all(root.join("libs/mathx/src")).unwrap();
+        std::fs::write(root.join("libs/mathx/src/core.ax"), LIB).unwrap();
+        std::fs::create_dir_all(root.join("specs")).unwrap();
+        let source = APP.replace("import \"mathx\"\n", "").replace(
+            "fn unused",
+            "fn step(n: Int) -> Int {\n    n + 1\n}\n\nfn unused",
+        );
+        let trace = record_trace(
+            &source,
+            "app.ax",
+            vec![AppMessage::new("inc", Value::Int(0))],
+        )
+        .unwrap();
+        let mut spec = generate_test(&trace, "counter");
+        spec.source_file = root.join("app.ax").display().to_string();
+        spec.source_hash = sha256_hex(APP);
+        std::fs::write(
+            root.join("specs/counter.json"),
+            serde_json::to_string(&spec).unwrap(),
+        )
+        .unwrap();
+
+        let options = ImpactOptions {
+            root: root.to_path_buf(),
+            libs_dir: root.join("libs"),
+        };
+        let report = affected_specs(&bundle(patches), &root.join("specs"), &options).unwrap();
+        report.affected.into_iter().map(|a| a.path).collect()
+    }
+
+    #[test]
+    fn reachable_function_change_affects_spec() {
+        let hits = affected(vec![patch(
+            "app.ax",
+            15,
+            "    UpdateResult { state: State { count: step(state.count) }, effects: [] }",
+            "    UpdateResult { state: State { count: state.count }, effects: [] }",
+        )]);
+        assert_eq!(hits, ["counter.json"]);
+    }
+
+    #[test]
+    fn imported_library_change_affects_spec_only_when_reachable() {
+        let lib = "libs/mathx/src/core.ax";
+        assert_eq!(
+            affected(vec![patch(lib, 2, "    n + 1", "    n + 2")]),
+            ["counter.json"]
+        );
+        assert!(affected(vec![patch(lib, 6, "    n", "    n * 2")]).is_empty());
+    }
+
+    #[test]
+    fn unreachable_function_and_comment_changes_affect_nothing() {
+        assert!(affected(vec![patch("app.ax", 23, "    1", "    2")]).is_empty());
+        assert!(affected(vec![patch(
+            "app.ax",
+            13,
+            "// Bumps the counter.",
+            "// Adds one."
+        )])
+        .is_empty());
+        assert!(affected(vec![patch("other.ax", 1, "fn x() -> Int { 1 }", "")]).is_empty());
+        assert!(affected(vec![patch("README.md", 1, "a", "b")]).is_empty());
+    }
+
+    #[test]
+    fn top_level_and_toolchain_changes_affect_spec() {
+        assert_eq!(
+            affected(vec![patch(
+                "app.ax",
+                3,
+                "type State { count: Int }",
+                "type State { count: Int, x: Int }"
+            )]),
+            ["counter.json"]
+        );
+        assert_eq!(
+            affected(vec![patch("crates/llmvm/src/vm.rs", 1, "a", "b")]),
+            ["counter.json"]
+        );
+    }
+}
@@ -1,19 +1,35 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use serde::{Deserialize, Serialize};
-use sha2::{Digest, Sha256};
 
+use boruna_bytecode::module::{TypeDef, TypeKind};
 use boruna_bytecode::Value;
+use boruna_framework::effect::Effect;
+use boruna_framework::error::FrameworkError;
+use boruna_framework::query::{value_to_json, Query};
 use boruna_framework::runtime::{AppMessage, CycleRecord};
 use boruna_framework::testing::TestHarness;
+use boruna_vm::VmError;
+
+pub mod delta;
+pub mod impact;
+pub mod suite;
+pub mod v1;
 
 // ─── Trace Schema ──────────────────────────────────────────────
 
-/// Version of the trace file format.
-pub const TRACE_VERSION: u32 = 1;
+/// Version of the trace file format. Version 2 types message and effect
+/// payloads as [`Value`]s, records the capability behind each effect and
+/// can carry UI snapshots; version 1 traces are read through
+/// [`load_trace`], which upgrades them with [`v1::upgrade_trace`].
+pub const TRACE_VERSION: u32 = 2;
 
 /// A complete execution trace of a framework app.
+///
+/// Serialized with `state_after` delta-encoded between keyframes; see
+/// [`delta`].
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(into = "delta::WireTraceFile", try_from = "delta::WireTraceFile")]
 pub struct TraceFile {
     pub version: u32,
     pub source_file: String,
@@ -33,30 +49,58 @@ pub struct TraceCycle {
     pub state_after: serde_json::Value,
     pub effects: Vec<TraceEffect>,
     pub ui_tree_hash: Option<String>,
+    /// The view after the cycle, when recorded with
+    /// [`RecordOptions::ui_snapshots`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ui_tree: Option<Value>,
 }
 
 /// A message in the trace.
+///
+/// `payload` is written as a tagged [`Value`] (`{"Int": 1}`); plain JSON
+/// (`1`), as in version 1 specs written by hand, is read with
+/// [`value_from_json`].
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TraceMessage {
     pub tag: String,
-    pub payload: serde_json::Value,
+    #[serde(deserialize_with = "payload_from_json")]
+    pub payload: Value,
+}
+
+fn payload_from_json<'de, D: serde::Deserializer<'de>>(d: D) -> Result<Value, D::Error> {
+    serde_json::Value::deserialize(d).map(|json| value_from_json(&json))
 }
 
-/// An effect in the trace.
+/// An effect in the trace: a capability call the app asked the host for.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TraceEffect {
     pub kind: String,
+    /// Capability the host calls for it, e.g. `net.fetch`.
+    pub capability: String,
+    /// `None` in traces upgraded from version 1, which kept only the hash.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payload: Option<Value>,
     pub payload_hash: String,
     pub callback_tag: String,
 }
 
+impl TraceEffect {
+    fn from_effect(effect: &Effect) -> Self {
+        TraceEffect {
+            kind: effect.kind.as_str().to_string(),
+            capability: effect.kind.capability_name().to_string(),
+            payload: Some(effect.payload.clone()),
+            payload_hash: hash_value(&effect.payload),
+            callback_tag: effect.callback_tag.clone(),
+        }
+    }
+}
+
 // ─── Hashing ──────────────────────────────────────────────────
 
 /// Compute SHA-256 of a string, return hex-encoded.
 pub fn sha256_hex(data: &str) -> String {
-    let mut hasher = Sha256::new();
-    hasher.update(data.as_bytes());
-    format!("{:x}", hasher.finalize())
+    boruna_hash::sha256_hex(data)
 }
 
 /// Hash a Value by serializing to canonical JSON.
@@ -78,7 +122,7 @@ pub fn trace_fingerprint(cycles: &[TraceCycle]) -> String {
             "c{}:msg={}:{},before={},after={},effects=[{}],ui={}",
             c.cycle,
             c.message.tag,
-            c.message.payload,
+            serde_json::to_value(&c.message.payload).unwrap_or(serde_json::Value::Null),
             c.state_before_hash,
             c.state_after_hash,
             effects_str.join(","),
@@ -90,11 +134,28 @@ pub fn trace_fingerprint(cycles: &[TraceCycle]) -> String {
 
 // ─── Recording ────────────────────────────────────────────────
 
+/// What a trace records beyond the version 2 minimum.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RecordOptions {
+    /// Store each cycle's view in [`TraceCycle::ui_tree`].
+    pub ui_snapshots: bool,
+}
+
 /// Record an execution trace by running an app with a sequence of messages.
 pub fn record_trace(
     source: &str,
     source_file: &str,
     messages: Vec<AppMessage>,
+) -> Result<TraceFile, String> {
+    record_trace_with(source, source_file, messages, RecordOptions::default())
+}
+
+/// [`record_trace`] with [`RecordOptions`].
+pub fn record_trace_with(
+    source: &str,
+    source_file: &str,
+    messages: Vec<AppMessage>,
+    options: RecordOptions,
 ) -> Result<TraceFile, String> {
     let mut harness =
         TestHarness::from_source(source).map_err(|e| format!("failed to create harness: {e}"))?;
@@ -105,7 +166,30 @@ pub fn record_trace(
             .map_err(|e| format!("cycle {} failed: {e}", harness.cycle()))?;
     }
 
-    trace_from_cycle_log(source, source_file, harness.cycle_log(), harness.state())
+    let mut trace =
+        trace_from_cycle_log(source, source_file, harness.cycle_log(), harness.state())?;
+    if options.ui_snapshots {
+        for (cycle, cr) in trace.cycles.iter_mut().zip(harness.cycle_log()) {
+            cycle.ui_tree = cr.ui_tree.clone();
+        }
+    }
+    Ok(trace)
+}
+
+fn trace_cycle(cr: &CycleRecord) -> TraceCycle {
+    TraceCycle {
+        cycle: cr.cycle,
+        message: TraceMessage {
+            tag: cr.message.tag.clone(),
+            payload: cr.message.payload.clone(),
+        },
+        state_before_hash: hash_value(&cr.state_before),
+        state_after_hash: hash_value(&cr.state_after),
+        state_after: serde_json::to_value(&cr.state_after).unwrap_or(serde_json::Value::Null),
+        effects: cr.effects.iter().map(TraceEffect::from_effect).collect(),
+        ui_tree_hash: cr.ui_tree.as_ref().map(hash_value),
+        ui_tree: None,
+    }
 }
 
 /// Build a TraceFile from an existing cycle log and final state.
@@ -117,30 +201,7 @@ pub fn trace_from_cycle_log(
 ) -> Result<TraceFile, String> {
     let source_hash = sha256_hex(source);
 
-    let cycles: Vec<TraceCycle> = cycle_log
-        .iter()
-        .map(|cr| TraceCycle {
-            cycle: cr.cycle,
-            message: TraceMessage {
-                tag: cr.message.tag.clone(),
-                payload: serde_json::to_value(&cr.message.payload)
-                    .unwrap_or(serde_json::Value::Null),
-            },
-            state_before_hash: hash_value(&cr.state_before),
-            state_after_hash: hash_value(&cr.state_after),
-            state_after: serde_json::to_value(&cr.state_after).unwrap_or(serde_json::Value::Null),
-            effects: cr
-                .effects
-                .iter()
-                .map(|e| TraceEffect {
-                    kind: e.kind.as_str().to_string(),
-                    payload_hash: hash_value(&e.payload),
-                    callback_tag: e.callback_tag.clone(),
-                })
-                .collect(),
-            ui_tree_hash: cr.ui_tree.as_ref().map(hash_value),
-        })
-        .collect();
+    let cycles: Vec<TraceCycle> = cycle_log.iter().map(trace_cycle).collect();
 
     let final_state_hash = hash_value(final_state);
     let fingerprint = trace_fingerprint(&cycles);
@@ -156,6 +217,25 @@ pub fn trace_from_cycle_log(
     })
 }
 
+/// Parse a trace file of any supported version, upgrading version 1.
+pub fn load_trace(json: &str) -> Result<TraceFile, String> {
+    #[derive(Deserialize)]
+    struct Version {
+        version: u32,
+    }
+    let Version { version } =
+        serde_json::from_str(json).map_err(|e| format!("invalid trace: {e}"))?;
+    match version {
+        1 => serde_json::from_str(json)
+            .map(v1::upgrade_trace)
+            .map_err(|e| format!("invalid version 1 trace: {e}")),
+        TRACE_VERSION => serde_json::from_str(json).map_err(|e| format!("invalid trace: {e}")),
+        other => Err(format!(
+            "unsupported trace version {other} (this build reads 1 to {TRACE_VERSION})"
+        )),
+    }
+}
+
 // ─── Test Generation ──────────────────────────────────────────
 
 /// A generated test specification.
@@ -170,11 +250,46 @@ pub struct TestSpec {
 }
 
 /// An assertion in a test spec.
+///
+/// Whole-run kinds: `final_state_hash`, `trace_hash`, `cycle_count`.
+/// Granular kinds (see [`generate_granular_test`]):
+/// - `effect_sequence` — `expected` is the cycle's effects as
+///   `kind:callback_tag` joined by `,` (empty for none).
+/// - `state_field_equals` — the field at `path` (dot-separated record field
+///   names, map keys, or list indices) renders as `expected`; checks the
+///   state after `cycle`, or the final state when `cycle` is absent.
+/// - `ui_contains_text` — some text in the view output of `cycle` (or the
+///   last cycle) contains `expected`.
+/// - `query` — [`query`](Self::query) evaluates to `expected` (compact
+///   JSON) over `state` and `effects` after `cycle`, or the final state when
+///   `cycle` is absent; see [`boruna_framework::query`].
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TestAssertion {
     pub kind: String,
     pub expected: String,
     pub description: String,
+    /// 1-based cycle number for per-cycle kinds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cycle: Option<u64>,
+    /// Field path for `state_field_equals`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Query for `query`, e.g. `state.users[?role=="admin"] | count`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+}
+
+impl TestAssertion {
+    fn whole_run(kind: &str, expected: String, description: String) -> Self {
+        TestAssertion {
+            kind: kind.to_string(),
+            expected,
+            description,
+            cycle: None,
+            path: None,
+            query: None,
+        }
+    }
 }
 
 /// Generate a test spec from a trace file.
@@ -182,21 +297,21 @@ pub fn generate_test(trace: &TraceFile, name: &str) -> TestSpec {
     let messages: Vec<TraceMessage> = trace.cycles.iter().map(|c| c.message.clone()).collect();
 
     let assertions = vec![
-        TestAssertion {
-            kind: "final_state_hash".to_string(),
-            expected: trace.final_state_hash.clone(),
-            description: "final state matches recorded execution".to_string(),
-        },
-        TestAssertion {
-            kind: "trace_hash".to_string(),
-            expected: trace.trace_hash.clone(),
-            description: "complete trace matches recorded execution".to_string(),
-        },
-        TestAssertion {
-            kind: "cycle_count".to_string(),
-            expected: trace.cycles.len().to_string(),
-            description: format!("exactly {} cycles executed", trace.cycles.len()),
-        },
+        TestAssertion::whole_run(
+            "final_state_hash",
+            trace.final_state_hash.clone(),
+            "final state matches recorded execution".to_string(),
+        ),
+        TestAssertion::whole_run(
+            "trace_hash",
+            trace.trace_hash.clone(),
+            "complete trace matches recorded execution".to_string(),
+        ),
+        TestAssertion::whole_run(
+            "cycle_count",
+            trace.cycles.len().to_string(),
+            format!("exactly {} cycles executed", trace.cycles.len()),
+        ),
     ];
 
     TestSpec {
@@ -209,6 +324,210 @@ pub fn generate_test(trace: &TraceFile, name: &str) -> TestSpec {
     }
 }
 
+/// Most `ui_contains_text` assertions generated for the final cycle.
+const MAX_GENERATED_UI_TEXTS: usize = 5;
+
+/// Generate a spec with granular assertions in addition to the whole-run
+/// ones: per cycle, the effect sequence and every top-level state field
+/// that changed; for the final cycle, up to [`MAX_GENERATED_UI_TEXTS`]
+/// texts from the view. `source` must be the traced source (checked
+/// against `source_hash`); it is replayed to recover field names and the
+/// UI tree, which the trace stores only as hashes.
+pub fn generate_granular_test(
+    trace: &TraceFile,
+    name: &str,
+    source: &str,
+) -> Result<TestSpec, String> {
+    if sha256_hex(source) != trace.source_hash {
+        return Err(format!(
+            "source does not match trace (expected source hash {})",
+            trace.source_hash
+        ));
+    }
+    let mut spec = generate_test(trace, name);
+    let mut harness =
+        TestHarness::from_source(source).map_err(|e| format!("failed to create harness: {e}"))?;
+    for msg in &spec.messages {
+        harness
+            .send(AppMessage::new(&msg.tag, msg.payload.clone()))
+            .map_err(|e| format!("cycle {} failed: {e}", harness.cycle()))?;
+    }
+    let types = &harness.runtime().module().types;
+
+    for cr in harness.cycle_log() {
+        spec.assertions.push(TestAssertion {
+            kind: "effect_sequence".to_string(),
+            expected: effect_sequence(&cr.effects),
+            description: format!("cycle {} emits the recorded effects", cr.cycle),
+            cycle: Some(cr.cycle),
+            path: None,
+            query: None,
+        });
+        if let (
+            Value::Record {
+                type_id,
+                fields: after,
+            },
+            Value::Record { fields: before, .. },
+        ) = (&cr.state_after, &cr.state_before)
+        {
+            for (i, value) in after.iter().enumerate() {
+                if before.get(i) == Some(value) {
+                    continue;
+                }
+                let path = record_field_name(types, *type_id, i).unwrap_or_else(|| i.to_string());
+                spec.assertions.push(TestAssertion {
+                    kind: "state_field_equals".to_string(),
+                    expected: value.to_string(),
+                    description: format!("after cycle {}, state.{path} is {value}", cr.cycle),
+                    cycle: Some(cr.cycle),
+                    path: Some(path),
+                    query: None,
+                });
+            }
+        }
+    }
+
+    if let Some(last) = harness.cycle_log().last() {
+        let mut texts = Vec::new();
+        if let Some(ui) = &last.ui_tree {
+            collect_ui_texts(ui, &mut texts);
+        }
+        let mut seen = BTreeSet::new();
+        texts.retain(|t| seen.insert(t.clone()));
+        for text in texts.into_iter().take(MAX_GENERATED_UI_TEXTS) {
+            spec.assertions.push(TestAssertion {
+                kind: "ui_contains_text".to_string(),
+                description: format!("after cycle {}, the UI shows {text:?}", last.cycle),
+                expected: text,
+                cycle: Some(last.cycle),
+                path: None,
+                query: None,
+            });
+        }
+    }
+    Ok(spec)
+}
+
+/// `kind:callback_tag` per effect, joined by `,`.
+fn effect_sequence(effects: &[Effect]) -> String {
+    effects
+        .iter()
+        .map(|e| format!("{}:{}", e.kind.as_str(), e.callback_tag))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn record_field_name(types: &[TypeDef], type_id: u32, index: usize) -> Option<String> {
+    match &types.get(type_id as usize)?.kind {
+        TypeKind::Record { fields } => fields.get(index).map(|(name, _)| name.clone()),
+        TypeKind::Enum { .. } => None,
+    }
+}
+
+/// Resolve a dot-separated path: record field names (or indices), map
+/// keys, and list indices.
+fn resolve_path<'a>(value: &'a Value, path: &str, types: &[TypeDef]) -> Option<&'a Value> {
+    let mut current = value;
+    for segment in path.split('.').filter(|s| !s.is_empty()) {
+        current = match current {
+            Value::Record { type_id, fields } => {
+                let index = match &types.get(*type_id as usize).map(|t| &t.kind) {
+                    Some(TypeKind::Record { fields: names }) => {
+                        names.iter().position(|(n, _)| n == segment)
+                    }
+                    _ => None,
+                }
+                .or_else(|| segment.parse().ok())?;
+                fields.get(index)?
+            }
+            Value::Map(entries) => entries.get(segment)?,
+            Value::List(items) => items.get(segment.parse::<usize>().ok()?)?,
+            _ => return None,
+        };
+    }
+    Some(current)
+}
+
+/// Text leaves of a view tree, in order. A record's first field is its
+/// tag (see `boruna_framework::ui::value_to_ui_tree`) and is skipped.
+fn collect_ui_texts(value: &Value, out: &mut Vec<String>) {
+    match value {
+        Value::String(s) if !s.is_empty() => out.push(s.clone()),
+        Value::Record { fields, .. } => {
+            for field in fields.iter().skip(1) {
+                collect_ui_texts(field, out);
+            }
+        }
+        Value::List(items) => items.iter().for_each(|v| collect_ui_texts(v, out)),
+        Value::Map(entries) => entries.values().for_each(|v| collect_ui_texts(v, out)),
+        Value::Some(v) => collect_ui_texts(v, out),
+        _ => {}
+    }
+}
+
+/// Actual value for a granular assertion, rendered like `expected`.
+fn check_granular(
+    assertion: &TestAssertion,
+    cycle_log: &[CycleRecord],
+    final_state: &Value,
+    types: &[TypeDef],
+) -> String {
+    let record = match assertion.cycle {
+        Some(n) => match cycle_log.iter().find(|c| c.cycle == n) {
+            Some(cr) => Some(cr),
+            None => return format!("<no cycle {n}>"),
+        },
+        None => cycle_log.last(),
+    };
+    match assertion.kind.as_str() {
+        "effect_sequence" => record.map_or_else(String::new, |cr| effect_sequence(&cr.effects)),
+        "state_field_equals" => {
+            let state = record.map_or(final_state, |cr| &cr.state_after);
+            let path = assertion.path.as_deref().unwrap_or("");
+            resolve_path(state, path, types)
+                .map_or_else(|| format!("<no field {path}>"), |v| v.to_string())
+        }
+        "query" => {
+            let text = assertion.query.as_deref().unwrap_or("");
+            let query = match Query::parse(text) {
+                Ok(q) => q,
+                Err(e) => return format!("<invalid query: {e}>"),
+            };
+            let state = record.map_or(final_state, |cr| &cr.state_after);
+            let effects: Vec<serde_json::Value> = record
+                .map(|cr| cr.effects.as_slice())
+                .unwrap_or_default()
+                .iter()
+                .map(|e| {
+                    serde_json::json!({
+                        "kind": e.kind.as_str(),
+                        "payload": value_to_json(&e.payload, types),
+                        "callback_tag": e.callback_tag,
+                    })
+                })
+                .collect();
+            let scope = serde_json::json!({
+                "state": value_to_json(state, types),
+                "effects": effects,
+            });
+            query.eval(&scope).to_string()
+        }
+        "ui_contains_text" => {
+            let mut texts = Vec::new();
+            if let Some(ui) = record.and_then(|cr| cr.ui_tree.as_ref()) {
+                collect_ui_texts(ui, &mut texts);
+            }
+            if texts.iter().any(|t| t.contains(&assertion.expected)) {
+                assertion.expected.clone()
+            } else {
+                format!("<not in UI: {}>", texts.join(" | "))
+            }
+        }
+        other => format!("unknown assertion kind: {other}"),
+    }
+}
+
 // ─── Test Execution ───────────────────────────────────────────
 
 /// Result of running a test spec.
@@ -217,10 +536,13 @@ pub struct TestResult {
     pub passed: bool,
     pub assertion_results: Vec<AssertionResult>,
     pub error: Option<String>,
+    /// Hash of the trace this run produced; `None` when the run did not
+    /// complete.
+    pub trace_hash: Option<String>,
 }
 
 /// Result of a single assertion check.
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize)]
 pub struct AssertionResult {
     pub kind: String,
     pub passed: bool,
@@ -228,8 +550,21 @@ pub struct AssertionResult {
     pub actual: String,
 }
 
-/// Run a test spec against source code.
+/// Run a test spec against source code. Specs of every trace version up to
+/// [`TRACE_VERSION`] run: version 1 payloads are read as plain JSON where
+/// they are not tagged values.
 pub fn run_test(spec: &TestSpec, source: &str) -> TestResult {
+    if !(1..=TRACE_VERSION).contains(&spec.version) {
+        return TestResult {
+            passed: false,
+            assertion_results: Vec::new(),
+            error: Some(format!(
+                "unsupported test spec version {} (this build runs 1 to {TRACE_VERSION})",
+                spec.version
+            )),
+            trace_hash: None,
+        };
+    }
     let mut harness = match TestHarness::from_source(source) {
         Ok(h) => h,
         Err(e) => {
@@ -237,48 +572,26 @@ pub fn run_test(spec: &TestSpec, source: &str) -> TestResult {
                 passed: false,
                 assertion_results: Vec::new(),
                 error: Some(format!("failed to create harness: {e}")),
+                trace_hash: None,
             }
         }
     };
 
     for msg in &spec.messages {
-        let payload = value_from_json(&msg.payload);
-        let app_msg = AppMessage::new(&msg.tag, payload);
+        let app_msg = AppMessage::new(&msg.tag, msg.payload.clone());
         if let Err(e) = harness.send(app_msg) {
             return TestResult {
                 passed: false,
                 assertion_results: Vec::new(),
                 error: Some(format!("message send failed: {e}")),
+                trace_hash: None,
             };
         }
     }
 
     // Build actual trace data
     let cycle_log = harness.cycle_log();
-    let actual_cycles: Vec<TraceCycle> = cycle_log
-        .iter()
-        .map(|cr| TraceCycle {
-            cycle: cr.cycle,
-            message: TraceMessage {
-                tag: cr.message.tag.clone(),
-                payload: serde_json::to_value(&cr.message.payload)
-                    .unwrap_or(serde_json::Value::Null),
-            },
-            state_before_hash: hash_value(&cr.state_before),
-            state_after_hash: hash_value(&cr.state_after),
-            state_after: serde_json::to_value(&cr.state_after).unwrap_or(serde_json::Value::Null),
-            effects: cr
-                .effects
-                .iter()
-                .map(|e| TraceEffect {
-                    kind: e.kind.as_str().to_string(),
-                    payload_hash: hash_value(&e.payload),
-                    callback_tag: e.callback_tag.clone(),
-                })
-                .collect(),
-            ui_tree_hash: cr.ui_tree.as_ref().map(hash_value),
-        })
-        .collect();
+    let actual_cycles: Vec<TraceCycle> = cycle_log.iter().map(trace_cycle).collect();
 
     let actual_final_hash = hash_value(harness.state());
     let actual_fingerprint = trace_fingerprint(&actual_cycles);
@@ -291,7 +604,12 @@ pub fn run_test(spec: &TestSpec, source: &str) -> TestResult {
             "final_state_hash" => actual_final_hash.clone(),
             "trace_hash" => actual_trace_hash.clone(),
             "cycle_count" => actual_cycles.len().to_string(),
-            other => format!("unknown assertion kind: {other}"),
+            _ => check_granular(
+                assertion,
+                cycle_log,
+                harness.state(),
+                &harness.runtime().module().types,
+            ),
         };
         results.push(AssertionResult {
             kind: assertion.kind.clone(),
@@ -307,6 +625,45 @@ pub fn run_test(spec: &TestSpec, source: &str) -> TestResult {
         passed: all_passed,
         assertion_results: results,
         error: None,
+        trace_hash: Some(actual_trace_hash),
+    }
+}
+
+/// Result of running one spec several times (see [`run_test_repeated`]).
+#[derive(Debug)]
+pub struct RepeatResult {
+    /// The first run; assertions are reported from it.
+    pub first: TestResult,
+    /// Trace hash of every run, in order.
+    pub trace_hashes: Vec<Option<String>>,
+}
+
+impl RepeatResult {
+    /// True when the runs did not all produce the same trace (or some
+    /// completed and others did not).
+    pub fn is_flaky(&self) -> bool {
+        self.trace_hashes.windows(2).any(|w| w[0] != w[1])
+    }
+
+    /// Number of different outcomes seen across the runs.
+    pub fn distinct_traces(&self) -> usize {
+        self.trace_hashes.iter().collect::<BTreeSet<_>>().len()
+    }
+}
+
+/// Run `spec` `runs` times (at least once) and record each run's trace
+/// hash, as a direct check of the determinism guarantee. The framework has
+/// no scheduling or RNG seed to vary yet, so every run gets identical
+/// input; any divergence is nondeterminism in the runtime itself.
+pub fn run_test_repeated(spec: &TestSpec, source: &str, runs: usize) -> RepeatResult {
+    let first = run_test(spec, source);
+    let mut trace_hashes = vec![first.trace_hash.clone()];
+    for _ in 1..runs {
+        trace_hashes.push(run_test(spec, source).trace_hash);
+    }
+    RepeatResult {
+        first,
+        trace_hashes,
     }
 }
 
@@ -348,7 +705,7 @@ pub fn value_from_json(json: &serde_json::Value) -> Value {
 pub fn messages_to_app(messages: &[TraceMessage]) -> Vec<AppMessage> {
     messages
         .iter()
-        .map(|m| AppMessage::new(&m.tag, value_from_json(&m.payload)))
+        .map(|m| AppMessage::new(&m.tag, m.payload.clone()))
         .collect()
 }
 
@@ -373,6 +730,7 @@ pub enum PredicateOutcome {
 /// `predicate` returns `PredicateOutcome::Fail` if the failure reproduces.
 /// Returns the minimal sequence that still triggers the failure.
 /// The result is 1-minimal: removing any single message stops the failure.
+/// The surviving payloads are then shrunk (see [`shrink_payloads`]).
 pub fn minimize_trace(
     source: &str,
     messages: &[TraceMessage],
@@ -449,9 +807,218 @@ pub fn minimize_trace(
         }
     }
 
+    // Phase 3: shrink what each remaining message carries
+    shrink_payloads(source, &mut current, predicate);
+
     current
 }
 
+/// Most predicate runs spent bisecting one number or string length.
+const MAX_BISECT_STEPS: u32 = 64;
+
+/// Shrink each message's payload while `predicate` keeps failing. The
+/// declared type of `Msg.payload` in `source` decides what is tried: `Int`
+/// and `Float` bisect toward 0, `String` truncates, `List<T>` drops
+/// elements then shrinks the rest as `T`, and records shrink field by
+/// field. Candidates always have the declared type, so the app never sees
+/// a payload it could not have received. Nothing is shrunk when the
+/// source has no `Msg` record with a `payload` field.
+pub fn shrink_payloads(source: &str, messages: &mut [TraceMessage], predicate: &TracePredicate) {
+    let types = match TestHarness::from_source(source) {
+        Ok(h) => h.runtime().module().types.clone(),
+        Err(_) => return,
+    };
+    let Some(payload_ty) = types.iter().find_map(|t| match &t.kind {
+        TypeKind::Record { fields } if t.name == "Msg" => fields
+            .iter()
+            .find(|(name, _)| name == "payload")
+            .map(|(_, ty)| ty.clone()),
+        _ => None,
+    }) else {
+        return;
+    };
+
+    for i in 0..messages.len() {
+        let original = messages[i].payload.clone();
+        let mut fails = |candidate: &Value| {
+            let mut trial = messages.to_vec();
+            trial[i].payload = candidate.clone();
+            predicate(source, &trial) == PredicateOutcome::Fail
+        };
+        messages[i].payload = shrink_value(&original, &payload_ty, &types, &mut fails);
+    }
+}
+
+/// Smallest value found for which `fails` holds, starting from `value`
+/// (which is known to fail).
+fn shrink_value(
+    value: &Value,
+    ty: &str,
+    types: &[TypeDef],
+    fails: &mut dyn FnMut(&Value) -> bool,
+) -> Value {
+    match (value, ty) {
+        (Value::Int(n), "Int") => {
+            let n = *n;
+            let shrunk = bisect_toward_zero(n.unsigned_abs(), &mut |m| {
+                fails(&Value::Int(if n < 0 {
+                    (m as i64).wrapping_neg()
+                } else {
+                    m as i64
+                }))
+            });
+            Value::Int(if n < 0 {
+                (shrunk as i64).wrapping_neg()
+            } else {
+                shrunk as i64
+            })
+        }
+        (Value::Float(f), "Float") => {
+            let f = *f;
+            let signed = |m: u64| {
+                if m == 0 {
+                    0.0
+                } else {
+                    (m as f64).copysign(f)
+                }
+            };
+            let whole = f.trunc();
+            if whole.abs() < u64::MAX as f64 && (whole == f || fails(&Value::Float(whole))) {
+                let m = bisect_toward_zero(whole.abs() as u64, &mut |m| {
+                    fails(&Value::Float(signed(m)))
+                });
+                return Value::Float(signed(m));
+            }
+            if fails(&Value::Float(0.0)) {
+                return Value::Float(0.0);
+            }
+            Value::Float(f)
+        }
+        (Value::String(s), "String") => {
+            let chars: Vec<char> = s.chars().collect();
+            let keep = bisect_toward_zero(chars.len() as u64, &mut |m| {
+                fails(&Value::String(chars[..m as usize].iter().collect()))
+            });
+            Value::String(chars[..keep as usize].iter().collect())
+        }
+        (Value::List(items), _) if ty.starts_with("List<") && ty.ends_with('>') => {
+            let elem_ty = &ty["List<".len()..ty.len() - 1];
+            let mut items = items.clone();
+            if !items.is_empty() && fails(&Value::List(Vec::new())) {
+                return Value::List(Vec::new());
+            }
+            let mut i = 0;
+            while i < items.len() {
+                let mut without = items.clone();
+                without.remove(i);
+                if fails(&Value::List(without.clone())) {
+                    items = without;
+                } else {
+                    i += 1;
+                }
+            }
+            for i in 0..items.len() {
+                let shrunk = {
+                    let current = items.clone();
+                    shrink_value(&items[i], elem_ty, types, &mut |v| {
+                        let mut trial = current.clone();
+                        trial[i] = v.clone();
+                        fails(&Value::List(trial))
+                    })
+                };
+                items[i] = shrunk;
+            }
+            Value::List(items)
+        }
+        (Value::Record { type_id, fields }, _) => {
+            let Some(TypeKind::Record { fields: field_tys }) = types
+                .get(*type_id as usize)
+                .filter(|t| t.name == ty)
+                .map(|t| &t.kind)
+            else {
+                return value.clone();
+            };
+            let mut fields = fields.clone();
+            for (i, (_, field_ty)) in field_tys.iter().enumerate().take(fields.len()) {
+                let shrunk = {
+                    let current = fields.clone();
+                    shrink_value(&fields[i], field_ty, types, &mut |v| {
+                        let mut trial = current.clone();
+                        trial[i] = v.clone();
+                        fails(&Value::Record {
+                            type_id: *type_id,
+                            fields: trial,
+                        })
+                    })
+                };
+                fields[i] = shrunk;
+            }
+            Value::Record {
+                type_id: *type_id,
+                fields,
+            }
+        }
+        _ => value.clone(),
+    }
+}
+
+/// Smallest `m` in `0..=n` for which `fails(m)` holds, assuming `fails(n)`
+/// and that failures are monotone in `m`. Tries 0 first, then bisects.
+fn bisect_toward_zero(n: u64, fails: &mut dyn FnMut(u64) -> bool) -> u64 {
+    if n == 0 || fails(0) {
+        return 0;
+    }
+    // Invariant: fails(hi), !fails(lo).
+    let (mut lo, mut hi) = (0u64, n);
+    let mut steps = 0;
+    while hi - lo > 1 && steps < MAX_BISECT_STEPS {
+        let mid = lo + (hi - lo) / 2;
+        if fails(mid) {
+            hi = mid;
+        } else {
+            lo = mid;
+        }
+        steps += 1;
+    }
+    hi
+}
+
+/// Version of the external predicate protocol. The input file handed to
+/// the command carries `protocol`, `source_file`, `module_file` (the
+/// compiled module, so the predicate need not recompile) and `messages`.
+pub const PREDICATE_PROTOCOL_VERSION: u32 = 2;
+
+/// Structured verdict an external predicate prints on stdout:
+/// `{"outcome": "fail" | "pass" | "unresolved", "reason": "..."}`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PredicateVerdict {
+    pub outcome: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// Interpret an external predicate's result. If stdout holds a JSON
+/// verdict it decides, and an unknown `outcome` is `Unresolved`.
+/// Otherwise the exit status decides, as in protocol v1: success is
+/// `Pass`, anything else `Fail`. Returns the verdict's reason, if any.
+pub fn parse_predicate_verdict(
+    stdout: &str,
+    exited_ok: bool,
+) -> (PredicateOutcome, Option<String>) {
+    match serde_json::from_str::<PredicateVerdict>(stdout.trim()) {
+        Ok(verdict) => {
+            let outcome = match verdict.outcome.as_str() {
+                "fail" => PredicateOutcome::Fail,
+                "pass" => PredicateOutcome::Pass,
+                _ => PredicateOutcome::Unresolved,
+            };
+            (outcome, verdict.reason)
+        }
+        Err(_) if exited_ok => (PredicateOutcome::Pass, None),
+        Err(_) => (PredicateOutcome::Fail, None),
+    }
+}
+
 /// Built-in predicate: failure = runtime error during message processing.
 pub fn predicate_runtime_error(source: &str, messages: &[TraceMessage]) -> PredicateOutcome {
     let mut harness = match TestHarness::from_source(source) {
@@ -460,8 +1027,7 @@ pub fn predicate_runtime_error(source: &str, messages: &[TraceMessage]) -> Predi
     };
 
     for msg in messages {
-        let payload = value_from_json(&msg.payload);
-        let app_msg = AppMessage::new(&msg.tag, payload);
+        let app_msg = AppMessage::new(&msg.tag, msg.payload.clone());
         match harness.send(app_msg) {
             Ok(_) => {}
             Err(_) => return PredicateOutcome::Fail,
@@ -471,6 +1037,29 @@ pub fn predicate_runtime_error(source: &str, messages: &[TraceMessage]) -> Predi
     PredicateOutcome::Pass
 }
 
+/// Built-in predicate: failure = an `assert(cond, msg)` or `fail(msg)` in
+/// the app trapping during message processing. Any other error is
+/// unrelated to the failure being minimized.
+pub fn predicate_assertion(source: &str, messages: &[TraceMessage]) -> PredicateOutcome {
+    let mut harness = match TestHarness::from_source(source) {
+        Ok(h) => h,
+        Err(_) => return PredicateOutcome::Unresolved,
+    };
+
+    for msg in messages {
+        let app_msg = AppMessage::new(&msg.tag, msg.payload.clone());
+        match harness.send(app_msg) {
+            Ok(_) => {}
+            Err(FrameworkError::Runtime(
+                VmError::UserAssertion { .. } | VmError::UserFailure { .. },
+            )) => return PredicateOutcome::Fail,
+            Err(_) => return PredicateOutcome::Unresolved,
+        }
+    }
+
+    PredicateOutcome::Pass
+}
+
 /// Create a predicate that checks for state mismatch.
 /// Returns Fail if the final state hash differs from `expected_hash`.
 pub fn make_state_mismatch_predicate(expected_hash: String) -> Box<TracePredicate> {
@@ -481,8 +1070,7 @@ pub fn make_state_mismatch_predicate(expected_hash: String) -> Box<TracePredicat
         };
 
         for msg in messages {
-            let payload = value_from_json(&msg.payload);
-            let app_msg = AppMessage::new(&msg.tag, payload);
+            let app_msg = AppMessage::new(&msg.tag, msg.payload.clone());
             if harness.send(app_msg).is_err() {
                 return PredicateOutcome::Unresolved;
             }
@@ -553,7 +1141,7 @@ fn main() -> Int {
         tags.iter()
             .map(|t| TraceMessage {
                 tag: t.to_string(),
-                payload: serde_json::to_value(Value::Int(0)).unwrap(),
+                payload: Value::Int(0),
             })
             .collect()
     }
@@ -579,7 +1167,7 @@ fn main() -> Int {
         let msgs = make_messages(&["increment", "increment", "decrement"]);
         let trace = record_trace(COUNTER_APP, "test.ax", msgs).unwrap();
 
-        assert_eq!(trace.version, 1);
+        assert_eq!(trace.version, TRACE_VERSION);
         assert_eq!(trace.source_file, "test.ax");
         assert_eq!(trace.cycles.len(), 3);
         assert!(!trace.source_hash.is_empty());
@@ -627,13 +1215,138 @@ fn main() -> Int {
         assert_eq!(trace.cycles.len(), restored.cycles.len());
     }
 
+    #[test]
+    fn test_trace_json_delta_encodes_state_between_keyframes() {
+        let tags = vec!["increment"; delta::KEYFRAME_INTERVAL + 8];
+        let trace = record_trace(COUNTER_APP, "test.ax", make_messages(&tags)).unwrap();
+
+        let json: serde_json::Value = serde_json::to_value(&trace).unwrap();
+        let cycles = json["cycles"].as_array().unwrap();
+        let keyframes: Vec<usize> = (0..cycles.len())
+            .filter(|&i| cycles[i].get("state_after").is_some())
+            .collect();
+        assert_eq!(keyframes, [0, delta::KEYFRAME_INTERVAL]);
+        assert!(cycles[1]["state_delta"].is_array());
+
+        let restored: TraceFile = serde_json::from_value(json).unwrap();
+        for (a, b) in trace.cycles.iter().zip(&restored.cycles) {
+            assert_eq!(a.state_after, b.state_after);
+            assert_eq!(a.state_after_hash, b.state_after_hash);
+        }
+    }
+
+    /// `trace` as version 1 wrote it.
+    fn downgrade(trace: &TraceFile) -> v1::TraceFileV1 {
+        v1::TraceFileV1 {
+            version: 1,
+            source_file: trace.source_file.clone(),
+            source_hash: trace.source_hash.clone(),
+            cycles: trace
+                .cycles
+                .iter()
+                .map(|c| v1::TraceCycleV1 {
+                    cycle: c.cycle,
+                    message: v1::TraceMessageV1 {
+                        tag: c.message.tag.clone(),
+                        payload: serde_json::to_value(&c.message.payload).unwrap(),
+                    },
+                    state_before_hash: c.state_before_hash.clone(),
+                    state_after_hash: c.state_after_hash.clone(),
+                    state_after: c.state_after.clone(),
+                    effects: c
+                        .effects
+                        .iter()
+                        .map(|e| v1::TraceEffectV1 {
+                            kind: e.kind.clone(),
+                            payload_hash: e.payload_hash.clone(),
+                            callback_tag: e.callback_tag.clone(),
+                        })
+                        .collect(),
+                    ui_tree_hash: c.ui_tree_hash.clone(),
+                })
+                .collect(),
+            final_state_hash: trace.final_state_hash.clone(),
+            trace_hash: trace.trace_hash.clone(),
+        }
+    }
+
+    #[test]
+    fn test_load_trace_upgrades_version_1() {
+        let msgs = make_messages(&["increment", "increment", "decrement"]);
+        let trace = record_trace(COUNTER_APP, "test.ax", msgs).unwrap();
+        let json = serde_json::to_string(&downgrade(&trace)).unwrap();
+
+        assert!(serde_json::from_str::<TraceFile>(&json).is_err());
+        let upgraded = load_trace(&json).unwrap();
+        assert_eq!(upgraded.version, TRACE_VERSION);
+        assert_eq!(upgraded.trace_hash, trace.trace_hash);
+        assert_eq!(
+            trace_fingerprint(&upgraded.cycles),
+            trace_fingerprint(&trace.cycles)
+        );
+
+        // A spec generated from the upgraded trace, written as version 1,
+        // still runs.
+        let mut spec = generate_test(&upgraded, "banked");
+        spec.version = 1;
+        assert!(run_test(&spec, COUNTER_APP).passed);
+    }
+
+    #[test]
+    fn test_load_trace_rejects_unknown_version() {
+        let err = load_trace(r#"{"version": 99}"#).unwrap_err();
+        assert!(err.contains("unsupported trace version 99"), "{err}");
+    }
+
+    #[test]
+    fn test_version_1_spec_with_plain_json_payloads_runs() {
+        let msgs = make_messages(&["increment", "increment"]);
+        let trace = record_trace(COUNTER_APP, "test.ax", msgs).unwrap();
+        let mut spec = serde_json::to_value(generate_test(&trace, "by_hand")).unwrap();
+        spec["version"] = 1.into();
+        for m in spec["messages"].as_array_mut().unwrap() {
+            m["payload"] = 0.into();
+        }
+        let spec: TestSpec = serde_json::from_value(spec).unwrap();
+        assert_eq!(spec.messages[0].payload, Value::Int(0));
+        assert!(run_test(&spec, COUNTER_APP).passed);
+    }
+
+    #[test]
+    fn test_effects_record_capability_calls_and_ui_snapshots() {
+        let app = COUNTER_APP.replace(
+            "state: State { count: state.count + 1, label: state.label },\n            effects: [],",
+            "state: State { count: state.count + 1, label: state.label },\n            effects: [Effect { kind: \"http_request\", payload: \"https://x\", callback_tag: \"fetched\" }],",
+        );
+        let trace = record_trace_with(
+            &app,
+            "test.ax",
+            make_messages(&["increment"]),
+            RecordOptions { ui_snapshots: true },
+        )
+        .unwrap();
+        let effect = &trace.cycles[0].effects[0];
+        assert_eq!(effect.capability, "net.fetch");
+        assert_eq!(effect.payload, Some(Value::String("https://x".into())));
+        assert!(trace.cycles[0].ui_tree.is_some());
+
+        let restored: TraceFile =
+            serde_json::from_str(&serde_json::to_string(&trace).unwrap()).unwrap();
+        assert_eq!(restored.cycles[0].ui_tree, trace.cycles[0].ui_tree);
+        assert!(record_trace(&app, "test.ax", make_messages(&["increment"]))
+            .unwrap()
+            .cycles[0]
+            .ui_tree
+            .is_none());
+    }
+
     #[test]
     fn test_generate_test() {
         let msgs = make_messages(&["increment", "increment", "decrement"]);
         let trace = record_trace(COUNTER_APP, "test.ax", msgs).unwrap();
         let spec = generate_test(&trace, "counter_regression");
 
-        assert_eq!(spec.version, 1);
+        assert_eq!(spec.version, TRACE_VERSION);
         assert_eq!(spec.name, "counter_regression");
         assert_eq!(spec.messages.len(), 3);
         assert_eq!(spec.assertions.len(), 3);
@@ -677,6 +1390,35 @@ fn main() -> Int {
         assert!(!result.passed, "test should fail with modified source");
     }
 
+    #[test]
+    fn test_run_test_repeated_is_stable() {
+        let msgs = make_messages(&["increment", "decrement", "increment"]);
+        let trace = record_trace(COUNTER_APP, "test.ax", msgs).unwrap();
+        let spec = generate_test(&trace, "test");
+        let result = run_test_repeated(&spec, COUNTER_APP, 5);
+
+        assert!(result.first.passed);
+        assert_eq!(result.trace_hashes.len(), 5);
+        assert_eq!(
+            result.trace_hashes[0].as_deref(),
+            Some(trace.trace_hash.as_str())
+        );
+        assert!(!result.is_flaky());
+        assert_eq!(result.distinct_traces(), 1);
+    }
+
+    #[test]
+    fn test_repeat_result_flags_divergent_runs() {
+        let spec = generate_test(
+            &record_trace(COUNTER_APP, "test.ax", make_messages(&["increment"])).unwrap(),
+            "test",
+        );
+        let mut result = run_test_repeated(&spec, COUNTER_APP, 3);
+        result.trace_hashes[2] = None;
+        assert!(result.is_flaky());
+        assert_eq!(result.distinct_traces(), 2);
+    }
+
     #[test]
     fn test_value_from_json_serde_roundtrip() {
         let values = vec![
@@ -773,6 +1515,164 @@ fn main() -> Int {
         assert_eq!(minimal[1].tag, "b");
     }
 
+    #[test]
+    fn test_parse_predicate_verdict() {
+        assert_eq!(
+            parse_predicate_verdict(r#"{"outcome":"fail","reason":"div by zero"}"#, true),
+            (PredicateOutcome::Fail, Some("div by zero".to_string()))
+        );
+        assert_eq!(
+            parse_predicate_verdict("  {\"outcome\": \"pass\"}\n", false),
+            (PredicateOutcome::Pass, None)
+        );
+        assert_eq!(
+            parse_predicate_verdict(r#"{"outcome":"maybe"}"#, false).0,
+            PredicateOutcome::Unresolved
+        );
+        // No verdict on stdout: fall back to the exit status (v1).
+        assert_eq!(
+            parse_predicate_verdict("checking...\n", true).0,
+            PredicateOutcome::Pass
+        );
+        assert_eq!(parse_predicate_verdict("", false).0, PredicateOutcome::Fail);
+    }
+
+    fn payload_message(tag: &str, payload: Value) -> TraceMessage {
+        TraceMessage {
+            tag: tag.to_string(),
+            payload,
+        }
+    }
+
+    #[test]
+    fn test_minimize_shrinks_int_payload_to_threshold() {
+        let msgs = vec![
+            payload_message("increment", Value::Int(3)),
+            payload_message("bad", Value::Int(-987_654)),
+            payload_message("increment", Value::Int(7)),
+        ];
+        // Fails once any payload is at or below -250.
+        let pred = |_src: &str, msgs: &[TraceMessage]| -> PredicateOutcome {
+            let hit = msgs
+                .iter()
+                .any(|m| matches!(m.payload.clone(), Value::Int(n) if n <= -250));
+            if hit {
+                PredicateOutcome::Fail
+            } else {
+                PredicateOutcome::Pass
+            }
+        };
+
+        let minimal = minimize_trace(COUNTER_APP, &msgs, &pred);
+        assert_eq!(minimal.len(), 1);
+        assert_eq!(minimal[0].payload.clone(), Value::Int(-250));
+    }
+
+    #[test]
+    fn test_minimize_shrinks_real_crash_payload() {
+        let app = COUNTER_APP.replace(
+            "state: State { count: state.count + 1, label: state.label },",
+            "state: State { count: 100 / (40 - msg.payload / 1000), label: state.label },",
+        );
+        // Only payloads in 40000..=40999 divide by zero.
+        let msgs = vec![
+            payload_message("increment", Value::Int(1)),
+            payload_message("increment", Value::Int(40_777)),
+        ];
+        assert_eq!(predicate_runtime_error(&app, &msgs), PredicateOutcome::Fail);
+
+        let minimal = minimize_trace(&app, &msgs, &predicate_runtime_error);
+        assert_eq!(minimal.len(), 1);
+        let Value::Int(n) = minimal[0].payload.clone() else {
+            panic!("payload changed type");
+        };
+        assert!((40_000..=40_777).contains(&n), "shrunk to {n}");
+        assert_eq!(
+            predicate_runtime_error(&app, &minimal),
+            PredicateOutcome::Fail
+        );
+    }
+
+    #[test]
+    fn test_predicate_assertion_ignores_other_runtime_errors() {
+        let app = COUNTER_APP.replace(
+            "state: State { count: state.count + 1, label: state.label },",
+            "state: State { count: 100 / (40 - msg.payload), label: state.label },",
+        );
+        let app = app.replace(
+            "if msg.tag == \"increment\" {",
+            "assert(msg.payload < 50, \"payload too large\")\n    if msg.tag == \"increment\" {",
+        );
+        let divide_by_zero = vec![payload_message("increment", Value::Int(40))];
+        let too_large = vec![
+            payload_message("increment", Value::Int(1)),
+            payload_message("decrement", Value::Int(99)),
+        ];
+        assert_eq!(
+            predicate_runtime_error(&app, &divide_by_zero),
+            PredicateOutcome::Fail
+        );
+        assert_eq!(
+            predicate_assertion(&app, &divide_by_zero),
+            PredicateOutcome::Unresolved
+        );
+        assert_eq!(
+            predicate_assertion(&app, &too_large),
+            PredicateOutcome::Fail
+        );
+
+        let minimal = minimize_trace(&app, &too_large, &predicate_assertion);
+        assert_eq!(minimal.len(), 1);
+        assert_eq!(minimal[0].tag, "decrement");
+    }
+
+    #[test]
+    fn test_shrink_string_and_list_payloads() {
+        let list_app = COUNTER_APP.replace("payload: Int", "payload: List<String>");
+        let mut msgs = vec![payload_message(
+            "bad",
+            Value::List(vec![
+                Value::String("keep".into()),
+                Value::String("xxxxxxxxxx".into()),
+                Value::String("zz".into()),
+            ]),
+        )];
+        // Fails while some element is at least 3 characters long.
+        let pred = |_src: &str, msgs: &[TraceMessage]| -> PredicateOutcome {
+            let hit = msgs.iter().any(|m| match m.payload.clone() {
+                Value::List(items) => items
+                    .iter()
+                    .any(|v| matches!(v, Value::String(s) if s.len() >= 3)),
+                _ => false,
+            });
+            if hit {
+                PredicateOutcome::Fail
+            } else {
+                PredicateOutcome::Pass
+            }
+        };
+
+        shrink_payloads(&list_app, &mut msgs, &pred);
+        assert_eq!(
+            msgs[0].payload.clone(),
+            Value::List(vec![Value::String("xxx".into())])
+        );
+    }
+
+    #[test]
+    fn test_shrink_payloads_respects_declared_type() {
+        // The schema says String, so an Int payload is left alone rather
+        // than shrunk into a value the app could not receive.
+        let string_app = COUNTER_APP.replace("payload: Int", "payload: String");
+        let mut msgs = vec![payload_message("bad", Value::Int(500))];
+        let always_fail = |_src: &str, _msgs: &[TraceMessage]| PredicateOutcome::Fail;
+        shrink_payloads(&string_app, &mut msgs, &always_fail);
+        assert_eq!(msgs[0].payload.clone(), Value::Int(500));
+
+        shrink_payloads(COUNTER_APP, &mut msgs, &always_fail);
+        assert_eq!(msgs[0].payload.clone(), Value::Int(0));
+    }
+
     #[test]
     fn test_minimize_deterministic() {
         let tags: Vec<&str> = vec![
@@ -891,6 +1791,139 @@ fn main() -> Int {
         assert!(!trace.trace_hash.is_empty());
     }
 
+    #[test]
+    fn test_generate_granular_test() {
+        let msgs = make_messages(&["increment", "noop", "decrement"]);
+        let trace = record_trace(COUNTER_APP, "test.ax", msgs).unwrap();
+        let spec = generate_granular_test(&trace, "granular", COUNTER_APP).unwrap();
+
+        let of_kind = |k: &str| -> Vec<&TestAssertion> {
+            spec.assertions.iter().filter(|a| a.kind == k).collect()
+        };
+        assert_eq!(of_kind("effect_sequence").len(), 3);
+        assert!(of_kind("effect_sequence")
+            .iter()
+            .all(|a| a.expected.is_empty()));
+        // Only changed fields: `count` on cycles 1 and 3, nothing on the no-op.
+        let fields = of_kind("state_field_equals");
+        assert_eq!(fields.len(), 2);
+        assert_eq!(fields[0].cycle, Some(1));
+        assert_eq!(fields[0].path.as_deref(), Some("count"));
+        assert_eq!(fields[0].expected, "1");
+        assert_eq!(fields[1].cycle, Some(3));
+        assert_eq!(fields[1].expected, "0");
+        let ui = of_kind("ui_contains_text");
+        assert_eq!(ui.len(), 1);
+        assert_eq!(ui[0].expected, "counter");
+
+        let result = run_test(&spec, COUNTER_APP);
+        assert!(result.passed, "{result:?}");
+
+        // Source mismatch is refused rather than generating wrong names.
+        assert!(generate_granular_test(&trace, "granular", "fn main() -> Int { 0 }").is_err());
+    }
+
+    #[test]
+    fn test_granular_assertions_pinpoint_failure() {
+        let msgs = make_messages(&["increment", "decrement"]);
+        let trace = record_trace(COUNTER_APP, "test.ax", msgs).unwrap();
+        let spec = generate_granular_test(&trace, "granular", COUNTER_APP).unwrap();
+
+        let modified = COUNTER_APP.replace("count: state.count - 1", "count: state.count - 2");
+        let result = run_test(&spec, &modified);
+        assert!(!result.passed);
+        let failed: Vec<&str> = result
+            .assertion_results
+            .iter()
+            .filter(|r| !r.passed)
+            .map(|r| r.kind.as_str())
+            .collect();
+        // Whole-run hashes fail, and exactly one field assertion (cycle 2).
+        assert_eq!(
+            failed,
+            vec!["final_state_hash", "trace_hash", "state_field_equals"]
+        );
+        let field = result
+            .assertion_results
+            .iter()
+            .find(|r| r.kind == "state_field_equals" && !r.passed)
+            .unwrap();
+        assert_eq!(field.actual, "-1");
+    }
+
+    #[test]
+    fn test_granular_assertion_kinds_by_hand() {
+        let spec = TestSpec {
+            version: TRACE_VERSION,
+            name: "hand".into(),
+            source_file: "test.ax".into(),
+            source_hash: sha256_hex(COUNTER_APP),
+            messages: make_trace_messages(&["increment"]),
+            assertions: vec![
+                TestAssertion {
+                    kind: "state_field_equals".into(),
+                    expected: "\"counter\"".into(),
+                    description: String::new(),
+                    cycle: None,
+                    path: Some("label".into()),
+                    query: None,
+                },
+                TestAssertion {
+                    kind: "ui_contains_text".into(),
+                    expected: "count".into(),
+                    description: String::new(),
+                    cycle: Some(1),
+                    path: None,
+                    query: None,
+                },
+                TestAssertion {
+                    kind: "effect_sequence".into(),
+                    expected: String::new(),
+                    description: String::new(),
+                    cycle: Some(7),
+                    path: None,
+                    query: None,
+                },
+                TestAssertion {
+                    kind: "query".into(),
+                    expected: "true".into(),
+                    description: String::new(),
+                    cycle: Some(1),
+                    path: None,
+                    query: Some("state.count == 1 && (effects | count) == 0".into()),
+                },
+                TestAssertion {
+                    kind: "query".into(),
+                    expected: "1".into(),
+                    description: String::new(),
+                    cycle: None,
+                    path: None,
+                    query: Some("state.count |".into()),
+                },
+            ],
+        };
+        let result = run_test(&spec, COUNTER_APP);
+        let outcomes: Vec<(bool, &str)> = result
+            .assertion_results
+            .iter()
+            .map(|r| (r.passed, r.actual.as_str()))
+            .collect();
+        assert_eq!(
+            outcomes,
+            vec![
+                (true, "\"counter\""),
+                (true, "count"),
+                (false, "<no cycle 7>"),
+                (true, "true"),
+                (
+                    false,
+                    "<invalid query: query error at offset 13: expected a function \
+                     name after `|`, found end of query>"
+                ),
+            ]
+        );
+    }
+
     #[test]
     fn test_test_spec_json_roundtrip() {
         let msgs = make_messages(&["increment"]);
@@ -0,0 +1,496 @@
+//! `trace2tests run-suite`: discover every test spec under a directory and
+//! run them on a worker pool with a per-spec wall-clock timeout.
+//!
+//! Each spec runs on its own thread so a timeout can be reported without
+//! waiting for it; the abandoned thread still stops once the VM's step
+//! limit is reached. Results are sorted by spec path, so the JSON and JUnit
+//! reports differ between runs only in their timings.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use super::{run_test_repeated, AssertionResult, TestSpec};
+
+/// How a suite is run.
+#[derive(Debug, Clone)]
+pub struct SuiteOptions {
+    /// Number of specs run concurrently (at least 1).
+    pub jobs: usize,
+    /// Wall-clock limit for a single run of a spec.
+    pub timeout: Duration,
+    /// Runs per spec (at least 1). Above 1, a spec whose trace hash
+    /// differs between runs is reported as [`CaseOutcome::Flaky`].
+    pub repeat: usize,
+}
+
+/// Outcome of one spec in a suite.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaseOutcome {
+    Passed,
+    /// At least one assertion did not hold.
+    Failed,
+    /// The spec could not be loaded or the app could not be run.
+    Error,
+    TimedOut,
+    /// Repeated runs produced different traces.
+    Flaky,
+}
+
+/// Result of one spec in a suite.
+#[derive(Debug, Clone, Serialize)]
+pub struct CaseResult {
+    /// Spec file, relative to the suite directory.
+    pub path: String,
+    /// Spec name, or the file stem when the spec did not load.
+    pub name: String,
+    pub outcome: CaseOutcome,
+    pub duration_ms: u64,
+    /// Load/run error or timeout description.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    pub assertions: Vec<AssertionResult>,
+}
+
+/// Aggregated results of a suite run.
+#[derive(Debug, Clone, Serialize)]
+pub struct SuiteReport {
+    pub total: usize,
+    pub passed: usize,
+    pub failed: usize,
+    pub errors: usize,
+    pub timed_out: usize,
+    pub flaky: usize,
+    pub duration_ms: u64,
+    pub cases: Vec<CaseResult>,
+}
+
+impl SuiteReport {
+    pub(crate) fn new(cases: Vec<CaseResult>, duration: Duration) -> Self {
+        let count = |o: CaseOutcome| cases.iter().filter(|c| c.outcome == o).count();
+        SuiteReport {
+            total: cases.len(),
+            passed: count(CaseOutcome::Passed),
+            failed: count(CaseOutcome::Failed),
+            errors: count(CaseOutcome::Error),
+            timed_out: count(CaseOutcome::TimedOut),
+            flaky: count(CaseOutcome::Flaky),
+            duration_ms: duration.as_millis() as u64,
+            cases,
+        }
+    }
+
+    pub fn all_passed(&self) -> bool {
+        self.passed == self.total
+    }
+
+    /// Render as JUnit XML. Failed assertions and flaky specs become
+    /// `<failure>`; load errors and timeouts become `<error>`.
+    pub fn to_junit(&self) -> String {
+        let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        let counts = format!(
+            "tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{}\"",
+            self.total,
+            self.failed + self.flaky,
+            self.errors + self.timed_out,
+            seconds(self.duration_ms)
+        );
+        out.push_str(&format!("<testsuites name=\"trace2tests\" {counts}>\n"));
+        out.push_str(&format!("  <testsuite name=\"trace2tests\" {counts}>\n"));
+        for case in &self.cases {
+            out.push_str(&format!(
+                "    <testcase name=\"{}\" classname=\"{}\" time=\"{}\"",
+                xml_escape(&case.name),
+                xml_escape(&case.path),
+                seconds(case.duration_ms)
+            ));
+            match case.outcome {
+                CaseOutcome::Passed => out.push_str("/>\n"),
+                CaseOutcome::Failed => {
+                    let failing: Vec<&AssertionResult> =
+                        case.assertions.iter().filter(|a| !a.passed).collect();
+                    let body: Vec<String> = failing
+                        .iter()
+                        .map(|a| {
+                            format!("{}: expected={}, actual={}", a.kind, a.expected, a.actual)
+                        })
+                        .collect();
+                    out.push_str(&format!(
+                        ">\n      <failure message=\"{} of {} assertions failed\">{}</failure>\n    </testcase>\n",
+                        failing.len(),
+                        case.assertions.len(),
+                        xml_escape(&body.join("\n"))
+                    ));
+                }
+                CaseOutcome::Flaky => {
+                    out.push_str(&format!(
+                        ">\n      <failure message=\"{}\"/>\n    </testcase>\n",
+                        xml_escape(case.message.as_deref().unwrap_or(""))
+                    ));
+                }
+                CaseOutcome::Error | CaseOutcome::TimedOut => {
+                    out.push_str(&format!(
+                        ">\n      <error message=\"{}\"/>\n    </testcase>\n",
+                        xml_escape(case.message.as_deref().unwrap_or(""))
+                    ));
+                }
+            }
+        }
+        out.push_str("  </testsuite>\n</testsuites>\n");
+        out
+    }
+}
+
+fn seconds(ms: u64) -> String {
+    format!("{:.3}", ms as f64 / 1000.0)
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Find every test spec under `dir`, recursively, sorted by path. A spec
+/// is a `.json` file whose top-level object has `messages` and
+/// `assertions`; other JSON (such as recorded traces) is skipped. Files
+/// that are not valid JSON are included so the run reports them as errors.
+/// Symlinked directories are not followed, so a link cycle cannot loop.
+pub fn discover_specs(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut specs = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        for entry in std::fs::read_dir(&current)? {
+            let entry = entry?;
+            let file_type = entry.file_type()?;
+            let path = entry.path();
+            if file_type.is_dir() {
+                pending.push(path);
+            } else if file_type.is_symlink() && path.is_dir() {
+                continue;
+            } else if path.extension().is_some_and(|e| e == "json") && looks_like_spec(&path)? {
+                specs.push(path);
+            }
+        }
+    }
+    specs.sort();
+    Ok(specs)
+}
+
+fn looks_like_spec(path: &Path) -> std::io::Result<bool> {
+    let text = std::fs::read_to_string(path)?;
+    Ok(match serde_json::from_str::<serde_json::Value>(&text) {
+        Ok(doc) => doc.get("messages").is_some() && doc.get("assertions").is_some(),
+        Err(_) => true,
+    })
+}
+
+/// Discover and run every spec under `dir`. A spec's relative
+/// `source_file` is resolved against the spec's directory first, then the
+/// working directory (as `trace2tests run` does).
+pub fn run_suite(dir: &Path, opts: &SuiteOptions) -> std::io::Result<SuiteReport> {
+    let specs = discover_specs(dir)?;
+    let started = Instant::now();
+    let next = AtomicUsize::new(0);
+
+    let mut cases: Vec<CaseResult> = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..opts.jobs.max(1).min(specs.len().max(1)))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut done = Vec::new();
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(path) = specs.get(i) else {
+                            break;
+                        };
+                        done.push(run_case(dir, path, opts));
+                    }
+                    done
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|w| w.join().unwrap_or_default())
+            .collect()
+    });
+    cases.sort_by(|a, b| a.path.cmp(&b.path));
+
+    Ok(SuiteReport::new(cases, started.elapsed()))
+}
+
+fn run_case(dir: &Path, path: &Path, opts: &SuiteOptions) -> CaseResult {
+    let runs = opts.repeat.max(1);
+    // Saturates: a huge timeout or repeat count waits without a limit
+    // rather than overflowing.
+    let timeout = opts
+        .timeout
+        .saturating_mul(u32::try_from(runs).unwrap_or(u32::MAX));
+    let started = Instant::now();
+    let rel = path.strip_prefix(dir).unwrap_or(path);
+    let mut case = CaseResult {
+        path: rel.to_string_lossy().replace('\\', "/"),
+        name: path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        outcome: CaseOutcome::Error,
+        duration_ms: 0,
+        message: None,
+        assertions: Vec::new(),
+    };
+
+    match load_case(path) {
+        Err(e) => case.message = Some(e),
+        Ok((spec, source)) => {
+            case.name = spec.name.clone();
+            let (tx, rx) = mpsc::channel();
+            let spawned = std::thread::Builder::new()
+                .name(format!("trace2tests:{}", case.name))
+                .spawn(move || {
+                    // The receiver is gone once the case timed out.
+                    let _ = tx.send(run_test_repeated(&spec, &source, runs));
+                });
+            match spawned {
+                Err(e) => case.message = Some(format!("cannot spawn test thread: {e}")),
+                Ok(_) => match rx.recv_timeout(timeout) {
+                    Ok(repeated) if repeated.is_flaky() => {
+                        case.outcome = CaseOutcome::Flaky;
+                        case.message = Some(format!(
+                            "nondeterministic: {} distinct traces over {runs} runs",
+                            repeated.distinct_traces()
+                        ));
+                        case.assertions = repeated.first.assertion_results;
+                    }
+                    Ok(repeated) => {
+                        let result = repeated.first;
+                        case.outcome = if result.error.is_some() {
+                            CaseOutcome::Error
+                        } else if result.passed {
+                            CaseOutcome::Passed
+                        } else {
+                            CaseOutcome::Failed
+                        };
+                        case.message = result.error;
+                        case.assertions = result.assertion_results;
+                    }
+                    Err(mpsc::RecvTimeoutError::Timeout) => {
+                        case.outcome = CaseOutcome::TimedOut;
+                        case.message = Some(format!("timed out after {}s", timeout.as_secs_f64()));
+                    }
+                    Err(mpsc::RecvTimeoutError::Disconnected) => {
+                        case.message = Some("test thread panicked".to_string());
+                    }
+                },
+            }
+        }
+    }
+
+    case.duration_ms = started.elapsed().as_millis() as u64;
+    case
+}
+
+fn load_case(path: &Path) -> Result<(TestSpec, String), String> {
+    let json = std::fs::read_to_string(path).map_err(|e| format!("cannot read spec: {e}"))?;
+    let spec: TestSpec =
+        serde_json::from_str(&json).map_err(|e| format!("invalid test spec: {e}"))?;
+    let source_path = spec_source_path(path, &spec);
+    let source = std::fs::read_to_string(&source_path)
+        .map_err(|e| format!("cannot read source {}: {e}", source_path.display()))?;
+    Ok((spec, source))
+}
+
+/// The source of the spec at `path`: its relative `source_file` beside the
+/// spec when that file exists, otherwise as given.
+pub(super) fn spec_source_path(path: &Path, spec: &TestSpec) -> PathBuf {
+    let declared = PathBuf::from(&spec.source_file);
+    let beside_spec = path.parent().map(|p| p.join(&declared));
+    match beside_spec {
+        Some(p) if declared.is_relative() && p.is_file() => p,
+        _ => declared,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{generate_test, record_trace};
+    use super::*;
+    use boruna_bytecode::Value;
+    use boruna_framework::runtime::AppMessage;
+
+    const APP: &str = r#"
+type State { count: Int }
+type Msg { tag: String, payload: Int }
+type Effect { kind: String, payload: String, callback_tag: String }
+type UpdateResult { state: State, effects: List<Effect> }
+type UINode { tag: String, text: String }
+
+fn init() -> State {
+    State { count: 0 }
+}
+
+fn update(state: State, msg: Msg) -> UpdateResult {
+    UpdateResult { state: State { count: state.count + 1 }, effects: [] }
+}
+
+fn view(state: State) -> UINode {
+    UINode { tag: "text", text: "count" }
+}
+
+fn main() -> Int {
+    0
+}
+"#;
+
+    fn write_spec(dir: &Path, file: &str, name: &str, tags: &[&str]) -> TestSpec {
+        let msgs = tags
+            .iter()
+            .map(|t| AppMessage::new(*t, Value::Int(0)))
+            .collect();
+        let trace = record_trace(APP, "app.ax", msgs).unwrap();
+        let spec = generate_test(&trace, name);
+        let path = dir.join(file);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, serde_json::to_string(&spec).unwrap()).unwrap();
+        spec
+    }
+
+    fn opts() -> SuiteOptions {
+        SuiteOptions {
+            jobs: 4,
+            timeout: Duration::from_secs(30),
+            repeat: 1,
+        }
+    }
+
+    #[test]
+    fn test_run_suite_aggregates_outcomes() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("app.ax"), APP).unwrap();
+        write_spec(dir.path(), "a_pass.json", "passes", &["inc", "inc"]);
+        let mut broken = write_spec(dir.path(), "b_fail.json", "fails", &["inc"]);
+        broken.assertions[2].expected = "7".to_string();
+        std::fs::write(
+            dir.path().join("b_fail.json"),
+            serde_json::to_string(&broken).unwrap(),
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("c_bad.json"), "{ not json").unwrap();
+        // A nested spec resolves `app.ax` against its own directory.
+        write_spec(dir.path(), "nested/d.json", "nested", &["inc"]);
+        std::fs::write(dir.path().join("nested/app.ax"), APP).unwrap();
+        // Not a spec: skipped.
+        std::fs::write(dir.path().join("trace.json"), r#"{"cycles": []}"#).unwrap();
+
+        let report = run_suite(dir.path(), &opts()).unwrap();
+        let summary: Vec<(&str, CaseOutcome)> = report
+            .cases
+            .iter()
+            .map(|c| (c.path.as_str(), c.outcome))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("a_pass.json", CaseOutcome::Passed),
+                ("b_fail.json", CaseOutcome::Failed),
+                ("c_bad.json", CaseOutcome::Error),
+                ("nested/d.json", CaseOutcome::Passed),
+            ]
+        );
+        assert_eq!(
+            (report.total, report.passed, report.failed, report.errors),
+            (4, 2, 1, 1)
+        );
+        assert!(!report.all_passed());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_discover_specs_does_not_follow_directory_symlinks() {
+        let dir = tempfile::tempdir().unwrap();
+        write_spec(dir.path(), "nested/a.json", "a", &["inc"]);
+        std::os::unix::fs::symlink(dir.path(), dir.path().join("nested/loop")).unwrap();
+        std::os::unix::fs::symlink(
+            dir.path().join("nested/a.json"),
+            dir.path().join("linked.json"),
+        )
+        .unwrap();
+        let specs = discover_specs(dir.path()).unwrap();
+        assert_eq!(
+            specs,
+            vec![
+                dir.path().join("linked.json"),
+                dir.path().join("nested/a.json")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_huge_timeout_does_not_overflow() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("app.ax"), APP).unwrap();
+        write_spec(dir.path(), "a.json", "a", &["inc"]);
+        let opts = SuiteOptions {
+            timeout: Duration::MAX,
+            repeat: 2,
+            ..opts()
+        };
+        assert!(run_suite(dir.path(), &opts).unwrap().all_passed());
+    }
+
+    #[test]
+    fn test_run_suite_is_order_independent_of_jobs() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("app.ax"), APP).unwrap();
+        for i in 0..6 {
+            write_spec(
+                dir.path(),
+                &format!("s{i}.json"),
+                &format!("s{i}"),
+                &["inc"],
+            );
+        }
+        let serial = run_suite(dir.path(), &SuiteOptions { jobs: 1, ..opts() }).unwrap();
+        let parallel = run_suite(dir.path(), &opts()).unwrap();
+        let names = |r: &SuiteReport| r.cases.iter().map(|c| c.name.clone()).collect::<Vec<_>>();
+        assert_eq!(names(&serial), names(&parallel));
+        assert!(parallel.all_passed());
+    }
+
+    #[test]
+    fn test_run_suite_repeat_reports_stable_specs_as_passed() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("app.ax"), APP).unwrap();
+        write_spec(dir.path(), "s.json", "s", &["inc", "inc", "inc"]);
+        let report = run_suite(
+            dir.path(),
+            &SuiteOptions {
+                repeat: 10,
+                ..opts()
+            },
+        )
+        .unwrap();
+        assert_eq!(report.cases[0].outcome, CaseOutcome::Passed);
+        assert_eq!(report.flaky, 0);
+    }
+
+    #[test]
+    fn test_junit_output() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("app.ax"), APP).unwrap();
+        write_spec(dir.path(), "ok.json", "a<b", &["inc"]);
+        std::fs::write(dir.path().join("zz.json"), "[").unwrap();
+
+        let xml = run_suite(dir.path(), &opts()).unwrap().to_junit();
+        assert!(xml.starts_with("<?xml"));
+        assert!(xml
+            .contains("<testsuites name=\"trace2tests\" tests=\"2\" failures=\"0\" errors=\"1\""));
+        assert!(xml.contains("<testcase name=\"a&lt;b\" classname=\"ok.json\""));
+        assert!(xml.contains("<error message=\"invalid test spec:"));
+    }
+}
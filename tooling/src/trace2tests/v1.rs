@@ -0,0 +1,93 @@
+//! Version 1 of the trace format, kept so banked traces stay readable.
+//!
+//! Version 1 stored message payloads as untyped JSON and effects as a kind,
+//! a payload hash and a callback tag. [`upgrade_trace`] converts such a
+//! trace to the current format: payloads are read with
+//! [`value_from_json`], each effect's capability is derived from its kind,
+//! and the effect payloads, which version 1 never stored, stay `None`.
+//! Hashes are carried over unchanged, so `final_state_hash` and
+//! `trace_hash` assertions generated from a version 1 trace still hold.
+
+use boruna_framework::effect::EffectKind;
+use serde::{Deserialize, Serialize};
+
+use super::{value_from_json, TraceCycle, TraceEffect, TraceFile, TraceMessage, TRACE_VERSION};
+
+/// A version 1 trace file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraceFileV1 {
+    pub version: u32,
+    pub source_file: String,
+    pub source_hash: String,
+    pub cycles: Vec<TraceCycleV1>,
+    pub final_state_hash: String,
+    pub trace_hash: String,
+}
+
+/// One cycle of a version 1 trace.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraceCycleV1 {
+    pub cycle: u64,
+    pub message: TraceMessageV1,
+    pub state_before_hash: String,
+    pub state_after_hash: String,
+    pub state_after: serde_json::Value,
+    pub effects: Vec<TraceEffectV1>,
+    pub ui_tree_hash: Option<String>,
+}
+
+/// A version 1 message: the payload is untyped JSON.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraceMessageV1 {
+    pub tag: String,
+    pub payload: serde_json::Value,
+}
+
+/// A version 1 effect.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraceEffectV1 {
+    pub kind: String,
+    pub payload_hash: String,
+    pub callback_tag: String,
+}
+
+/// Convert a version 1 trace to the current format.
+pub fn upgrade_trace(v1: TraceFileV1) -> TraceFile {
+    let cycles = v1
+        .cycles
+        .into_iter()
+        .map(|c| TraceCycle {
+            cycle: c.cycle,
+            message: TraceMessage {
+                tag: c.message.tag,
+                payload: value_from_json(&c.message.payload),
+            },
+            state_before_hash: c.state_before_hash,
+            state_after_hash: c.state_after_hash,
+            state_after: c.state_after,
+            effects: c
+                .effects
+                .into_iter()
+                .map(|e| TraceEffect {
+                    capability: EffectKind::parse_str(&e.kind)
+                        .map_or("", |k| k.capability_name())
+                        .to_string(),
+                    kind: e.kind,
+                    payload: None,
+                    payload_hash: e.payload_hash,
+                    callback_tag: e.callback_tag,
+                })
+                .collect(),
+            ui_tree_hash: c.ui_tree_hash,
+            ui_tree: None,
+        })
+        .collect();
+    TraceFile {
+        version: TRACE_VERSION,
+        source_file: v1.source_file,
+        source_hash: v1.source_hash,
+        cycles,
+        final_state_hash: v1.final_state_hash,
+        trace_hash: v1.trace_hash,
+    }
+}
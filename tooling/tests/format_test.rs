@@ -48,6 +48,11 @@ fn golden_capabilities() {
     golden("capabilities");
 }
 
+#[test]
+fn golden_uses() {
+    golden("uses");
+}
+
 #[test]
 fn idempotency_roundtrip_on_examples() {
     // Walk a representative set of comment-free programs synthesized from
@@ -89,3 +94,10 @@ fn check_distinguishes_canonical_from_unformatted() {
     let canonical = format_source(unformatted).unwrap();
     assert!(check_source(&canonical).unwrap());
 }
+
+#[test]
+fn decimal_literals_keep_their_source_text() {
+    // Trailing zeros are the literal's declared scale.
+    let src = "fn main() -> Decimal {\n    12.50d + 3.000d + 7d\n}\n";
+    assert_eq!(format_source(src).unwrap(), src);
+}
@@ -2,7 +2,7 @@
 //!
 //! Invariant: every stable diagnostic code is *classified* — it is either
 //! auto-fixable (the toolchain emits a `SuggestedPatch` carrying at least one
-//! `TextEdit`, so `boruna lang repair` can apply it mechanically) or it is on an
+//! `TextEdit` that changes the source, so `boruna lang repair` can apply it mechanically) or it is on an
 //! explicit ALLOWLIST of codes that are intentionally not auto-fixed, each with a
 //! documented reason.
 //!
@@ -10,7 +10,7 @@
 //!
 //! * The registry (`diagnostics::registry`) is the enumerable source of truth for
 //!   every code the system can emit; a separate drift test in that module keeps it
-//!   1:1 with the `E0NN` constants. So enumerating codes here is complete.
+//!   1:1 with the `E0NN`/`W0NN` constants. So enumerating codes here is complete.
 //! * `FIXABLE` pairs each auto-fixable code with a triggering `.ax` snippet. The
 //!   test drives that snippet through the *real* repair entry point
 //!   (`DiagnosticCollector` → `RepairTool`) and asserts a patch with a real edit
@@ -28,7 +28,8 @@ use boruna_tooling::diagnostics::registry::registry;
 use boruna_tooling::repair::{RepairStrategy, RepairTool};
 
 /// Codes that ship an auto-applicable quickfix, each paired with source that
-/// triggers the code with a mechanical (non-empty-edit) patch.
+/// triggers the code with a mechanical patch. An edit counts when it changes
+/// the text; an empty `new_text` is a deletion, which the repair loop applies.
 ///
 /// Each snippet is run through `DiagnosticCollector::collect()` — the exact path
 /// `boruna lang check`/`repair` uses — so this proves the fix reaches the repair
@@ -73,6 +74,44 @@ const FIXABLE: &[(&str, &str)] = &[
          fn update(state: State, msg: Msg) -> State !{fs.read} { state }\n\
          fn view(state: State) -> String { \"ok\" }\n",
     ),
+    // W001 unused-variable → prefix the binding with `_`.
+    (
+        "W001",
+        "fn main() -> Int {\n    let unused = 1\n    0\n}\n",
+    ),
+    // W002 unreachable-match-arm → delete the dead arm.
+    (
+        "W002",
+        "fn main() -> Int {\n\
+         \x20   let x = 3\n\
+         \x20   match x {\n\
+         \x20       _ => 0\n\
+         \x20       1 => 1\n\
+         \x20   }\n\
+         }\n",
+    ),
+    // W003 dead-function → delete the private function nobody calls.
+    (
+        "W003",
+        "export fn api() -> Int {\n    1\n}\n\nfn stale() -> Int {\n    2\n}\n",
+    ),
+    // W004 unhandled-callback → insert a stub handler at the top of update().
+    (
+        "W004",
+        "type State { count: Int }\n\
+         type Msg { tag: String, payload: String }\n\
+         type Effect { kind: String, payload: String, callback_tag: String }\n\
+         type UpdateResult { state: State, effects: List<Effect> }\n\
+         \n\
+         fn init() -> State { State { count: 0 } }\n\
+         fn update(state: State, msg: Msg) -> UpdateResult {\n\
+         \x20   UpdateResult {\n\
+         \x20       state: state,\n\
+         \x20       effects: [Effect { kind: \"http_request\", payload: \"\", callback_tag: \"loaded\" }],\n\
+         \x20   }\n\
+         }\n\
+         fn view(state: State) -> String { \"ok\" }\n",
+    ),
 ];
 
 /// Codes intentionally NOT auto-fixed by the repair loop. Each entry documents
@@ -111,6 +150,53 @@ const ALLOWLIST: &[(&str, &str)] = &[
         // Confidence::Low); picking the right coercion needs human intent.
         "type mismatches yield only a textual hint (no edit); the correct coercion needs human intent",
     ),
+    (
+        "E010",
+        // Compiler panics are reported by `lang fuzz`, never by `lang check`;
+        // the fix belongs in the compiler, not the source.
+        "compiler panics are compiler bugs surfaced by `lang fuzz`; there is no source-level fix",
+    ),
+    (
+        "E011",
+        // Project-wide: which of the duplicate definitions keeps the name is
+        // a design choice, and renaming one touches every file that uses it.
+        "choosing which duplicate type keeps its name needs human intent",
+    ),
+    (
+        "E012",
+        // Project-wide: an unused export may still be API consumed outside
+        // the checked tree, so dropping `export` is not mechanically safe.
+        "an unused export may be consumed outside the project; dropping `export` needs human intent",
+    ),
+    (
+        "E013",
+        // Raised by the VM at runtime, not by `lang check`: the caller passed
+        // an argument the contract forbids, and only its author knows why.
+        "precondition violations are runtime failures; the fix is in the caller's data or logic",
+    ),
+    (
+        "E014",
+        // Raised by the VM at runtime: either the body or the clause is wrong.
+        "postcondition violations are runtime failures; deciding whether the body or the clause is wrong needs human intent",
+    ),
+    (
+        "E015",
+        // Shrinking a module means restructuring it: splitting functions,
+        // flattening nesting or moving data out of the constant pool.
+        "budget overruns need a design decision about how to split or restructure the module",
+    ),
+    (
+        "E016",
+        // Both sides changed the same lines; only the file's owner knows
+        // which edit, or which blend of them, is right.
+        "merge conflicts need a human to reconcile their edit with the new template version",
+    ),
+    (
+        "W005",
+        // The declaration is what reviewers approve; narrowing it is their
+        // call, not the repair loop's.
+        "dropping a declared capability changes what reviewers approved; it may anticipate a call being added",
+    ),
 ];
 
 fn registry_codes() -> BTreeSet<String> {
@@ -188,11 +274,11 @@ fn fixable_codes_produce_applicable_quickfix() {
         let has_applicable_edit = diag
             .suggested_patches
             .iter()
-            .any(|p| p.edits.iter().any(|e| !e.new_text.is_empty()));
+            .any(|p| p.edits.iter().any(|e| e.new_text != e.old_text));
         assert!(
             has_applicable_edit,
             "{code} is listed FIXABLE but its diagnostic carries no patch with a \
-             non-empty TextEdit — the repair loop cannot act on it"
+             changing TextEdit — the repair loop cannot act on it"
         );
 
         // And the repair tool must actually apply at least one patch.